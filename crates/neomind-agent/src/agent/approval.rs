@@ -0,0 +1,409 @@
+//! Human approval for sensitive tool calls.
+//!
+//! Tool calls are grouped into action categories `<tool>.<action>` (e.g.
+//! `device.control`, `rule.delete`). Categories listed in the
+//! `agent.approval_required` config are not executed right away: the chat
+//! stream records a [`PendingAction`], emits `AgentEvent::AwaitingApproval`
+//! and waits until an operator approves or rejects it through the API. Actions
+//! nobody decides on expire after `agent.approval_ttl_secs`. Rejected and
+//! expired calls are reported back to the LLM as failed tool calls.
+//! Non-streaming turns (`Agent::process`) hold calls the same way, without
+//! the event.
+//!
+//! Patterns in the config match a category exactly, or all actions of a tool
+//! with `<tool>.*`; `*` holds every tool call.
+//!
+//! A `plan` call is held as a whole if any of its steps would be held on its
+//! own; the step categories are recorded on the [`PendingAction`] so the
+//! operator sees what the plan does.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Notify;
+
+use crate::toolkit::plan::{ExecutionPlan, PLAN_TOOL_NAME};
+use crate::tools::mapper::{map_tool_parameters, resolve_domain_name};
+
+/// Decided actions are kept this long for the approvals list.
+const DECIDED_RETENTION_SECS: i64 = 3600;
+
+static PENDING_ACTIONS: OnceLock<Arc<PendingActionStore>> = OnceLock::new();
+
+/// The process-wide store shared by chat streams and the API.
+pub fn pending_actions() -> Arc<PendingActionStore> {
+    PENDING_ACTIONS
+        .get_or_init(|| Arc::new(PendingActionStore::new()))
+        .clone()
+}
+
+/// State of a held tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+/// A tool call waiting for (or decided by) an operator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAction {
+    pub id: String,
+    pub session_id: String,
+    pub tool: String,
+    /// Action category, `<tool>.<action>`
+    pub category: String,
+    /// Categories of the steps of a `plan` call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub step_categories: Vec<String>,
+    pub arguments: Value,
+    pub status: ApprovalStatus,
+    pub created_at: i64,
+    pub expires_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decided_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<i64>,
+    /// Operator's reason for the decision
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl PendingAction {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.status == ApprovalStatus::Pending && now >= self.expires_at
+    }
+
+    /// The call's own category followed by those of its plan steps.
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.category.as_str())
+            .chain(self.step_categories.iter().map(String::as_str))
+    }
+}
+
+/// Approval decision failure.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ApprovalError {
+    #[error("pending action '{0}' not found")]
+    NotFound(String),
+    #[error("pending action '{id}' is already {}", status.as_str())]
+    AlreadyDecided { id: String, status: ApprovalStatus },
+}
+
+/// In-memory store of held tool calls.
+///
+/// Held calls belong to a running chat stream, so they don't survive a
+/// restart; the stream that waited on them is gone as well.
+pub struct PendingActionStore {
+    actions: RwLock<HashMap<String, PendingAction>>,
+    decided: Notify,
+}
+
+impl Default for PendingActionStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PendingActionStore {
+    pub fn new() -> Self {
+        Self {
+            actions: RwLock::new(HashMap::new()),
+            decided: Notify::new(),
+        }
+    }
+
+    /// Hold a tool call for `ttl_secs`.
+    pub fn create(
+        &self,
+        session_id: &str,
+        tool: &str,
+        category: &str,
+        step_categories: Vec<String>,
+        arguments: Value,
+        ttl_secs: u64,
+    ) -> PendingAction {
+        let now = chrono::Utc::now().timestamp();
+        let action = PendingAction {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            tool: tool.to_string(),
+            category: category.to_string(),
+            step_categories,
+            arguments,
+            status: ApprovalStatus::Pending,
+            created_at: now,
+            expires_at: now + ttl_secs as i64,
+            decided_by: None,
+            decided_at: None,
+            reason: None,
+        };
+        let mut actions = self.actions.write();
+        actions.retain(|_, a| {
+            a.status == ApprovalStatus::Pending
+                || a.decided_at.unwrap_or(a.expires_at) + DECIDED_RETENTION_SECS > now
+        });
+        actions.insert(action.id.clone(), action.clone());
+        action
+    }
+
+    /// Current state of an action.
+    pub fn get(&self, id: &str) -> Option<PendingAction> {
+        self.expire_stale();
+        self.actions.read().get(id).cloned()
+    }
+
+    /// All actions, newest first.
+    pub fn list(&self) -> Vec<PendingAction> {
+        self.expire_stale();
+        let mut actions: Vec<_> = self.actions.read().values().cloned().collect();
+        actions.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+        actions
+    }
+
+    /// Let the held call run.
+    pub fn approve(
+        &self,
+        id: &str,
+        decided_by: Option<String>,
+        reason: Option<String>,
+    ) -> Result<PendingAction, ApprovalError> {
+        self.decide(id, ApprovalStatus::Approved, decided_by, reason)
+    }
+
+    /// Refuse the held call.
+    pub fn reject(
+        &self,
+        id: &str,
+        decided_by: Option<String>,
+        reason: Option<String>,
+    ) -> Result<PendingAction, ApprovalError> {
+        self.decide(id, ApprovalStatus::Rejected, decided_by, reason)
+    }
+
+    /// Wait until the action is decided or expires.
+    pub async fn wait(&self, id: &str) -> Result<PendingAction, ApprovalError> {
+        loop {
+            // Register before checking so a decision in between isn't missed
+            let notified = self.decided.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let action = self
+                .get(id)
+                .ok_or_else(|| ApprovalError::NotFound(id.to_string()))?;
+            if action.status != ApprovalStatus::Pending {
+                return Ok(action);
+            }
+            let remaining = (action.expires_at - chrono::Utc::now().timestamp()).max(0) as u64;
+            let _ = tokio::time::timeout(Duration::from_secs(remaining), notified).await;
+        }
+    }
+
+    fn decide(
+        &self,
+        id: &str,
+        status: ApprovalStatus,
+        decided_by: Option<String>,
+        reason: Option<String>,
+    ) -> Result<PendingAction, ApprovalError> {
+        self.expire_stale();
+        let decided = {
+            let mut actions = self.actions.write();
+            let action = actions
+                .get_mut(id)
+                .ok_or_else(|| ApprovalError::NotFound(id.to_string()))?;
+            if action.status != ApprovalStatus::Pending {
+                return Err(ApprovalError::AlreadyDecided {
+                    id: id.to_string(),
+                    status: action.status,
+                });
+            }
+            action.status = status;
+            action.decided_by = decided_by;
+            action.decided_at = Some(chrono::Utc::now().timestamp());
+            action.reason = reason;
+            action.clone()
+        };
+        self.decided.notify_waiters();
+        Ok(decided)
+    }
+
+    fn expire_stale(&self) {
+        let now = chrono::Utc::now().timestamp();
+        let mut expired = false;
+        for action in self.actions.write().values_mut() {
+            if action.is_expired(now) {
+                action.status = ApprovalStatus::Expired;
+                expired = true;
+            }
+        }
+        if expired {
+            self.decided.notify_waiters();
+        }
+    }
+}
+
+/// Action category of a tool call: `<tool>.<action>`, or just the tool name
+/// for tools without actions. Aliases resolve to their domain, so
+/// `control_device` is `device.control`.
+pub fn action_category(tool: &str, arguments: &Value) -> String {
+    let domain = resolve_domain_name(tool);
+    let mapped = map_tool_parameters(tool, arguments);
+    match mapped.get("action").and_then(|v| v.as_str()) {
+        Some(action) => format!("{}.{}", domain, action),
+        None => domain,
+    }
+}
+
+/// Categories of the steps of a `plan` call; empty for other tools and for
+/// plans that don't parse (those fail without running anything).
+///
+/// A step whose action references another step's output is only known at
+/// run time, so its category is `<tool>.*`.
+pub fn plan_step_categories(tool: &str, arguments: &Value) -> Vec<String> {
+    if tool != PLAN_TOOL_NAME {
+        return Vec::new();
+    }
+    let Ok(plan) = ExecutionPlan::from_args(arguments) else {
+        return Vec::new();
+    };
+    plan.steps
+        .iter()
+        .map(|step| {
+            let category = action_category(&step.tool, &step.args);
+            match category.split_once('.') {
+                Some((domain, action)) if action.contains("{{") => format!("{}.*", domain),
+                _ => category,
+            }
+        })
+        .collect()
+}
+
+/// Whether `category` matches one of the configured approval patterns.
+///
+/// `<tool>.*` stands for an action not known yet and matches every pattern
+/// that names one of the tool's actions.
+pub fn requires_approval(category: &str, patterns: &[String]) -> bool {
+    if let Some(tool) = category.strip_suffix(".*") {
+        return patterns.iter().any(|pattern| {
+            pattern == "*" || pattern == tool || pattern.starts_with(&format!("{}.", tool))
+        });
+    }
+    patterns.iter().any(|pattern| {
+        if pattern == "*" || pattern == category {
+            return true;
+        }
+        match pattern.strip_suffix(".*") {
+            Some(tool) => category == tool || category.starts_with(&format!("{}.", tool)),
+            None => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_requires_approval_patterns() {
+        let patterns = vec!["device.control".to_string(), "rule.*".to_string()];
+        assert!(requires_approval("device.control", &patterns));
+        assert!(!requires_approval("device.list", &patterns));
+        assert!(requires_approval("rule.delete", &patterns));
+        assert!(requires_approval("rule", &patterns));
+        assert!(!requires_approval("rules.delete", &patterns));
+        assert!(requires_approval("shell", &["*".to_string()]));
+        assert!(!requires_approval("device.control", &[]));
+    }
+
+    #[test]
+    fn test_plan_steps_are_categorized() {
+        let args = json!({"steps": [
+            {"id": "list", "tool": "device", "args": {"action": "list"}},
+            {"id": "on", "tool": "device",
+             "args": {"action": "control", "device_id": "{{list.devices.0.id}}", "command": "on"}},
+            {"id": "any", "tool": "rule", "args": {"action": "{{list.next}}"}}
+        ]});
+        let steps = plan_step_categories(PLAN_TOOL_NAME, &args);
+        assert_eq!(steps, ["device.list", "device.control", "rule.*"]);
+        assert!(plan_step_categories("device", &args).is_empty());
+
+        let patterns = vec!["device.control".to_string(), "rule.delete".to_string()];
+        assert!(!requires_approval(PLAN_TOOL_NAME, &patterns));
+        assert!(steps.iter().any(|c| requires_approval(c, &patterns)));
+        // An action resolved at run time could be any of the tool's actions
+        assert!(requires_approval("rule.*", &patterns));
+        assert!(!requires_approval("device.*", &["rule.delete".to_string()]));
+    }
+
+    #[test]
+    fn test_action_category() {
+        let args = json!({"action": "control", "device_id": "lamp-1", "command": "on"});
+        assert_eq!(action_category("device", &args), "device.control");
+        assert_eq!(action_category("vision", &json!({})), "vision");
+    }
+
+    #[tokio::test]
+    async fn test_approve_wakes_waiter() {
+        let store = Arc::new(PendingActionStore::new());
+        let action = store.create("s1", "device", "device.control", Vec::new(), json!({}), 60);
+        assert_eq!(store.list().len(), 1);
+
+        let waiter = {
+            let store = store.clone();
+            let id = action.id.clone();
+            tokio::spawn(async move { store.wait(&id).await })
+        };
+        tokio::task::yield_now().await;
+        store
+            .approve(&action.id, Some("admin".to_string()), None)
+            .unwrap();
+
+        let decided = waiter.await.unwrap().unwrap();
+        assert_eq!(decided.status, ApprovalStatus::Approved);
+        assert_eq!(decided.decided_by.as_deref(), Some("admin"));
+        assert_eq!(
+            store.reject(&action.id, None, None).unwrap_err(),
+            ApprovalError::AlreadyDecided {
+                id: action.id.clone(),
+                status: ApprovalStatus::Approved,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_undecided_action_expires() {
+        let store = PendingActionStore::new();
+        let action = store.create("s1", "rule", "rule.delete", Vec::new(), json!({}), 0);
+
+        let decided = store.wait(&action.id).await.unwrap();
+        assert_eq!(decided.status, ApprovalStatus::Expired);
+        assert!(matches!(
+            store.approve(&action.id, None, None),
+            Err(ApprovalError::AlreadyDecided { .. })
+        ));
+        assert!(matches!(
+            store.approve("missing", None, None),
+            Err(ApprovalError::NotFound(_))
+        ));
+    }
+}
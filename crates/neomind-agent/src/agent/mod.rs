@@ -19,8 +19,10 @@
 //! └─────────────────────────────────────────────────────┘
 //! ```
 
+pub mod approval;
 pub mod conversation_context;
 pub mod fallback;
+pub mod prompt_injection;
 pub mod semantic_mapper;
 pub mod smart_followup;
 pub mod staged;
@@ -29,6 +31,7 @@ pub mod tokenizer;
 pub mod tool_parser;
 pub mod types;
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -57,8 +60,12 @@ pub type SharedSemanticMapper = Arc<semantic_mapper::SemanticToolMapper>;
 pub type EventStream = Pin<Box<dyn Stream<Item = AgentEvent> + Send>>;
 pub type MessageStream = Pin<Box<dyn Stream<Item = (String, bool)> + Send>>;
 
+pub use approval::{
+    pending_actions, ApprovalError, ApprovalStatus, PendingAction, PendingActionStore,
+};
 pub use conversation_context::ConversationContext;
 pub use fallback::{default_fallback_rules, process_fallback, FallbackRule};
+pub use prompt_injection::{ContentSource, InjectionMode, PromptInjectionDetectionHook};
 pub use smart_followup::SmartFollowUpManager;
 pub use streaming::{
     events_to_string_stream, process_multimodal_stream_events_with_safeguards,
@@ -66,7 +73,7 @@ pub use streaming::{
 };
 pub use types::{
     AgentConfig, AgentEvent, AgentInternalState, AgentMessage, AgentMessageImage, AgentResponse,
-    LlmBackend, SessionState, ToolCall,
+    ContextStats, LlmBackend, SessionState, ToolCall,
 };
 
 /// === ANTHROPIC-STYLE IMPROVEMENT: Tool Result Clearing ===
@@ -758,6 +765,13 @@ impl Agent {
     ) -> Self {
         let session_id_clone = session_id.clone();
 
+        // Sessions with a tool allowlist get their own view of the registry,
+        // so disallowed tools are neither offered to the LLM nor executed
+        let tools = match &config.allowed_tools {
+            Some(allowlist) => Arc::new(tools.restricted_to(allowlist.iter().cloned())),
+            None => tools,
+        };
+
         // Create LLM interface
         let llm_config = ChatConfig {
             model: config.model.clone(),
@@ -1190,7 +1204,7 @@ impl Agent {
         // definitions_for_llm() already filters out disabled tools (master-off
         // extension or per-command disable). Use it directly instead of
         // iterating list() + get() so the chat path can't leak disabled tools.
-        let core_defs: Vec<CoreToolDefinition> = self
+        let mut core_defs: Vec<CoreToolDefinition> = self
             .tools
             .definitions_for_llm()
             .into_iter()
@@ -1200,6 +1214,9 @@ impl Agent {
                 parameters: def.parameters,
             })
             .collect();
+        if !core_defs.is_empty() {
+            core_defs.push(crate::toolkit::plan::tool_definition());
+        }
 
         let tool_count = core_defs.len();
         self.llm_interface.set_tool_definitions(core_defs).await;
@@ -1256,11 +1273,18 @@ impl Agent {
         // definitions_for_llm() filters out disabled tools so the text prompt
         // stays in sync with the function-calling schema.
         let defs = self.tools.definitions_for_llm();
-        let extension_defs: Vec<_> = defs.iter().filter(|d| d.name.contains(':')).collect();
+        use crate::toolkit::extension_tools::is_extension_tool_name;
+        let extension_defs: Vec<_> = defs
+            .iter()
+            .filter(|d| is_extension_tool_name(&d.name))
+            .collect();
 
-        for def in defs.iter().filter(|d| !d.name.contains(':')) {
+        for def in defs.iter().filter(|d| !is_extension_tool_name(&d.name)) {
             prompt.push_str(&format!("**{}**: {}\n", def.name, def.description));
         }
+        if !defs.is_empty() {
+            prompt.push_str(&crate::toolkit::plan::prompt_hint());
+        }
 
         if !extension_defs.is_empty() {
             prompt.push_str("\n### Extension Tools\n");
@@ -1292,6 +1316,11 @@ impl Agent {
         prompt
     }
 
+    /// Tool allowlist of this session, if it is restricted.
+    pub fn allowed_tools(&self) -> Option<&[String]> {
+        self.config.allowed_tools.as_deref()
+    }
+
     /// Get the session ID.
     pub fn session_id(&self) -> &str {
         &self.session_id
@@ -1328,6 +1357,15 @@ impl Agent {
         self.internal_state.write().await.clear_memory();
     }
 
+    /// Screen the user's message for prompt injections before it is stored
+    /// or sent to the LLM.
+    async fn screen_user_input<'a>(&self, user_message: &'a str) -> Cow<'a, str> {
+        PromptInjectionDetectionHook::from_config()
+            .with_llm(self.llm_interface.clone())
+            .screen_with_llm(ContentSource::UserInput, user_message)
+            .await
+    }
+
     /// === FAST PATH: Check for simple responses BEFORE acquiring lock ===
     /// This improves latency for common queries like greetings and confirmations.
     fn try_fast_path(&self, user_message: &str) -> Option<AgentResponse> {
@@ -1408,6 +1446,9 @@ impl Agent {
     pub async fn process(&self, user_message: &str) -> Result<AgentResponse> {
         tracing::debug!(message = %user_message, "Agent::process starting");
 
+        let user_message = self.screen_user_input(user_message).await;
+        let user_message = user_message.as_ref();
+
         // === FAST PATH: Try simple responses WITHOUT acquiring lock ===
         if let Some(response) = self.try_fast_path(user_message) {
             // Save to history for context continuity
@@ -1778,6 +1819,9 @@ impl Agent {
             "Agent::process_multimodal_stream_events starting"
         );
 
+        let user_message = self.screen_user_input(user_message).await;
+        let user_message = user_message.as_ref();
+
         let _lock = self.process_lock.lock().await;
 
         // Check if LLM is configured (required for multimodal)
@@ -2121,6 +2165,14 @@ impl Agent {
                     let sem = semaphore.clone();
 
                     async move {
+                        // Held calls wait for an operator before taking a
+                        // concurrency slot, like in the streaming loop
+                        if let Err(refusal) =
+                            streaming::approve_call(&self.session_id, &name, &arguments).await
+                        {
+                            let output = format!("Tool {} execution failed: {}", name, refusal);
+                            return (name, id, arguments, Ok(output));
+                        }
                         let _permit = match sem.acquire().await {
                             Ok(p) => p,
                             Err(e) => {
@@ -2692,6 +2744,17 @@ END"#
         const MAX_RETRIES: u32 = 2;
         let start = std::time::Instant::now();
 
+        // Plans run their steps through the streaming executor; steps may
+        // write, so the combined result is never cached
+        if name == crate::toolkit::plan::PLAN_TOOL_NAME {
+            let output = streaming::execute_plan_call(&self.tools, arguments, MAX_RETRIES).await;
+            let sanitized = self.sanitize_tool_output_for_llm(name, &output.data);
+            return Ok(match output.error {
+                Some(error) => format!("Tool {} execution failed: {}\n{}", name, error, sanitized),
+                None => sanitized,
+            });
+        }
+
         // === CACHE: Check if result is cached ===
         let args_key = arguments.to_string();
         if let Some(cached_result) = self.tool_result_cache.read().await.get(name, &args_key) {
@@ -2722,6 +2785,10 @@ END"#
             .await
             .unwrap_or(mapped_arguments);
 
+        if let Err(violation) = crate::toolkit::guardrails::check(name, &semantically_mapped) {
+            return Ok(format!("Tool {} execution failed: {}", name, violation));
+        }
+
         // Sanitize arguments for logging (limit size to avoid log spam)
         let args_preview = if semantically_mapped.to_string().len() > 200 {
             format!(
@@ -2903,6 +2970,9 @@ END"#
         summary_up_to_index: Option<u64>,
         safeguards: StreamSafeguards,
     ) -> Result<Pin<Box<dyn Stream<Item = AgentEvent> + Send>>> {
+        let user_message = self.screen_user_input(user_message).await;
+        let user_message = user_message.as_ref();
+
         // Add user message to history
         let user_msg = AgentMessage::user(user_message);
         self.internal_state.write().await.push_message(user_msg);
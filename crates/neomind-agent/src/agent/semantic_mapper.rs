@@ -456,7 +456,7 @@ impl SemanticToolMapper {
                 }
             }
 
-            // The "rule" tool handles: list, get, create, update, delete, history
+            // The "rule" tool handles: list, get, create, update, delete, history, explain, simulate, conflicts
             "rule" => {
                 // Rule name → ID resolution removed: rule_cache was never populated
                 // (register_rule/register_rules had zero callers). Rule IDs from LLM
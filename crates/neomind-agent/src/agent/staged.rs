@@ -2,9 +2,20 @@
 //!
 //! Provides keyword-based intent classification used by the streaming agent
 //! and planner modules to route user messages to appropriate handlers.
+//!
+//! The built-in keywords can be extended or replaced per category at runtime
+//! with an [`IntentProfile`] of extra keywords and regular expressions, so
+//! deployments can teach the classifier their own domain phrasing.
+
+use std::collections::HashMap;
+use std::sync::Arc;
 
+use parking_lot::RwLock;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+static INTENT_PROFILE: RwLock<Option<Arc<CompiledProfile>>> = RwLock::new(None);
+
 /// Intent category for user queries.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -26,6 +37,26 @@ pub enum IntentCategory {
 }
 
 impl IntentCategory {
+    /// Stable snake_case name, as used in profiles and stored examples.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IntentCategory::Device => "device",
+            IntentCategory::Rule => "rule",
+            IntentCategory::Data => "data",
+            IntentCategory::Alert => "alert",
+            IntentCategory::System => "system",
+            IntentCategory::Help => "help",
+            IntentCategory::General => "general",
+        }
+    }
+
+    /// Parse a category name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all_variants()
+            .into_iter()
+            .find(|c| c.as_str().eq_ignore_ascii_case(name.trim()))
+    }
+
     /// Get display name for this intent.
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -234,6 +265,86 @@ pub struct IntentResult {
     pub keywords: Vec<String>,
 }
 
+/// Extra keywords and regular expressions for one intent category.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntentRules {
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Regular expressions matched against the lowercased message
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+/// A custom intent profile, loaded at runtime.
+///
+/// ```json
+/// {
+///   "replace_builtin": false,
+///   "categories": {
+///     "data": { "keywords": ["能耗", "kwh"], "patterns": ["how (much|many) .* used"] }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntentProfile {
+    /// Use only the profile's rules for the categories it lists, instead of
+    /// adding them to the built-in keywords
+    #[serde(default)]
+    pub replace_builtin: bool,
+    #[serde(default)]
+    pub categories: HashMap<IntentCategory, IntentRules>,
+}
+
+struct CompiledRules {
+    keywords: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+struct CompiledProfile {
+    profile: IntentProfile,
+    rules: HashMap<IntentCategory, CompiledRules>,
+}
+
+impl IntentProfile {
+    fn compile(self) -> Result<CompiledProfile, String> {
+        let mut rules = HashMap::new();
+        for (category, r) in &self.categories {
+            let patterns = r
+                .patterns
+                .iter()
+                .map(|p| {
+                    Regex::new(p)
+                        .map_err(|e| format!("Invalid pattern for {}: {}", category.as_str(), e))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let keywords = r
+                .keywords
+                .iter()
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect();
+            rules.insert(category.clone(), CompiledRules { keywords, patterns });
+        }
+        Ok(CompiledProfile {
+            profile: self,
+            rules,
+        })
+    }
+}
+
+/// Install the intent profile used by every classifier, or clear it with
+/// `None`. Fails without changing anything if a pattern doesn't compile.
+pub fn set_intent_profile(profile: Option<IntentProfile>) -> Result<(), String> {
+    let compiled = profile.map(IntentProfile::compile).transpose()?;
+    *INTENT_PROFILE.write() = compiled.map(Arc::new);
+    Ok(())
+}
+
+/// The installed intent profile, if any.
+pub fn intent_profile() -> Option<IntentProfile> {
+    INTENT_PROFILE.read().as_ref().map(|p| p.profile.clone())
+}
+
 /// Intent classifier using keyword matching.
 #[derive(Clone)]
 pub struct IntentClassifier {
@@ -250,20 +361,45 @@ impl Default for IntentClassifier {
 }
 
 impl IntentClassifier {
-    /// Classify user intent from message.
+    /// Classify user intent from message, using the installed profile.
     pub fn classify(&self, message: &str) -> IntentResult {
+        let profile = INTENT_PROFILE.read().clone();
+        self.classify_with(message, profile.as_deref())
+    }
+
+    fn classify_with(&self, message: &str, profile: Option<&CompiledProfile>) -> IntentResult {
         let message_lower = message.to_lowercase();
 
         // Count keyword matches for each category
         let mut scores: Vec<(IntentCategory, f32, Vec<String>)> = IntentCategory::all_variants()
             .iter()
             .map(|category| {
-                let keywords = category.keywords();
+                let rules = profile.and_then(|p| p.rules.get(category));
+                let use_builtin =
+                    rules.is_none() || profile.is_some_and(|p| !p.profile.replace_builtin);
                 let mut matched_keywords = Vec::new();
 
-                for &keyword in keywords {
-                    if message_lower.contains(keyword) {
-                        matched_keywords.push(keyword.to_string());
+                if use_builtin {
+                    for &keyword in category.keywords() {
+                        if message_lower.contains(keyword) {
+                            matched_keywords.push(keyword.to_string());
+                        }
+                    }
+                }
+                if let Some(rules) = rules {
+                    for keyword in &rules.keywords {
+                        if message_lower.contains(keyword.as_str())
+                            && !matched_keywords.contains(keyword)
+                        {
+                            matched_keywords.push(keyword.clone());
+                        }
+                    }
+                    // A pattern match counts like a keyword of the matched text
+                    for pattern in &rules.patterns {
+                        let found = pattern.find(&message_lower);
+                        if let Some(m) = found.filter(|m| !m.as_str().is_empty()) {
+                            matched_keywords.push(m.as_str().to_string());
+                        }
                     }
                 }
 
@@ -357,6 +493,46 @@ mod tests {
         assert_eq!(result.category, IntentCategory::General);
     }
 
+    #[test]
+    fn test_intent_profile_extends_and_replaces() {
+        let classifier = IntentClassifier::default();
+        let message = "kWh used by Floor 3 yesterday";
+        assert_eq!(classifier.classify_with(message, None).category, IntentCategory::General);
+
+        let mut profile = IntentProfile::default();
+        profile.categories.insert(
+            IntentCategory::Data,
+            IntentRules {
+                keywords: vec!["KWh".to_string()],
+                patterns: vec![r"used by floor \d+".to_string()],
+            },
+        );
+        let compiled = profile.clone().compile().unwrap();
+        let result = classifier.classify_with(message, Some(&compiled));
+        assert_eq!(result.category, IntentCategory::Data);
+        assert!(result.keywords.contains(&"kwh".to_string()));
+        assert!(result.keywords.contains(&"used by floor 3".to_string()));
+
+        // Built-in keywords still apply unless the profile replaces them
+        let builtin = "查询温度数据";
+        let result = classifier.classify_with(builtin, Some(&compiled));
+        assert_eq!(result.category, IntentCategory::Data);
+        profile.replace_builtin = true;
+        let compiled = profile.compile().unwrap();
+        let result = classifier.classify_with(builtin, Some(&compiled));
+        assert_ne!(result.category, IntentCategory::Data);
+    }
+
+    #[test]
+    fn test_invalid_intent_profile_is_rejected() {
+        let profile: IntentProfile =
+            serde_json::from_str(r#"{"categories": {"alert": {"patterns": ["(unclosed"]}}}"#)
+                .unwrap();
+        assert!(set_intent_profile(Some(profile)).is_err());
+        assert_eq!(IntentCategory::from_name(" Alert "), Some(IntentCategory::Alert));
+        assert_eq!(IntentCategory::from_name("weather"), None);
+    }
+
     #[test]
     fn test_intent_keywords() {
         assert!(IntentCategory::Device.keywords().contains(&"设备"));
@@ -0,0 +1,210 @@
+//! Holding tool calls for operator approval (see `agent::approval`).
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::context::ToolExecutionResult;
+use crate::agent::approval::{self, pending_actions, ApprovalError, ApprovalStatus, PendingAction};
+use crate::toolkit::ToolOutput;
+use neomind_core::config::agent_env_vars;
+
+/// Heartbeat interval while a stream waits for decisions, so the client
+/// connection isn't dropped as idle.
+pub(crate) const APPROVAL_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// Split tool calls into those that run right away and those held for
+/// approval. Plans are held if any of their steps needs approval.
+pub(crate) fn hold_for_approval(
+    session_id: &str,
+    inputs: Vec<(String, Value)>,
+) -> (Vec<(String, Value)>, Vec<PendingAction>) {
+    let patterns = agent_env_vars::approval_required();
+    if patterns.is_empty() {
+        return (inputs, Vec::new());
+    }
+    let ttl_secs = agent_env_vars::approval_ttl_secs();
+    let store = pending_actions();
+
+    let mut run_now = Vec::new();
+    let mut held = Vec::new();
+    for (name, arguments) in inputs {
+        let category = approval::action_category(&name, &arguments);
+        let step_categories = approval::plan_step_categories(&name, &arguments);
+        let held_for = std::iter::once(&category)
+            .chain(&step_categories)
+            .any(|c| approval::requires_approval(c, &patterns));
+        if held_for {
+            let action = store.create(
+                session_id,
+                &name,
+                &category,
+                step_categories,
+                arguments,
+                ttl_secs,
+            );
+            tracing::info!(
+                tool = %name,
+                category = %category,
+                action_id = %action.id,
+                "Tool call held for approval"
+            );
+            held.push(action);
+        } else {
+            run_now.push((name, arguments));
+        }
+    }
+    (run_now, held)
+}
+
+/// Wait until every held call is decided.
+///
+/// Returns the approved calls, to be executed, and a failed result for each
+/// rejected or expired one so the LLM learns why nothing happened.
+pub(crate) async fn await_decisions(
+    held: Vec<PendingAction>,
+) -> (Vec<(String, Value)>, Vec<(String, ToolExecutionResult)>) {
+    let store = pending_actions();
+    let decisions = futures::future::join_all(held.iter().map(|a| store.wait(&a.id))).await;
+
+    let mut approved = Vec::new();
+    let mut refused = Vec::new();
+    for (action, decision) in held.into_iter().zip(decisions) {
+        let status = decision
+            .as_ref()
+            .map_or(ApprovalStatus::Expired, |d| d.status);
+        if status == ApprovalStatus::Approved {
+            approved.push((action.tool, action.arguments));
+            continue;
+        }
+
+        let message = refusal_message(&action, decision);
+        refused.push((
+            action.tool.clone(),
+            ToolExecutionResult {
+                _name: action.tool,
+                arguments: action.arguments,
+                result: Ok(ToolOutput::error(message)),
+            },
+        ));
+    }
+    (approved, refused)
+}
+
+/// Hold a single call if it needs approval and wait for the decision.
+///
+/// For the non-streaming tool loop, which has no client to announce the hold
+/// to; the call still shows up in the approvals API. Fails with the message
+/// to report to the LLM if the call may not run.
+pub(crate) async fn approve_call(
+    session_id: &str,
+    name: &str,
+    arguments: &Value,
+) -> Result<(), String> {
+    let (_, held) = hold_for_approval(session_id, vec![(name.to_string(), arguments.clone())]);
+    let Some(action) = held.into_iter().next() else {
+        return Ok(());
+    };
+    let decision = pending_actions().wait(&action.id).await;
+    if matches!(&decision, Ok(d) if d.status == ApprovalStatus::Approved) {
+        return Ok(());
+    }
+    Err(refusal_message(&action, decision))
+}
+
+/// Why a held call was not executed, for the LLM.
+fn refusal_message(
+    action: &PendingAction,
+    decision: Result<PendingAction, ApprovalError>,
+) -> String {
+    let status = decision
+        .as_ref()
+        .map_or(ApprovalStatus::Expired, |d| d.status);
+    tracing::info!(
+        tool = %action.tool,
+        action_id = %action.id,
+        status = status.as_str(),
+        "Held tool call not executed"
+    );
+    match decision {
+        Ok(PendingAction {
+            status: ApprovalStatus::Rejected,
+            reason,
+            ..
+        }) => match reason {
+            Some(reason) => format!(
+                "Action {} was rejected by an operator: {}",
+                action.category, reason
+            ),
+            None => format!("Action {} was rejected by an operator", action.category),
+        },
+        _ => format!(
+            "Action {} was not approved within the approval time limit",
+            action.category
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_core::config::service::{global, keys};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn test_approve_call_waits_for_decision() {
+        // A tool no other test calls, so the global setting holds nothing else
+        let changes = BTreeMap::from([(
+            keys::AGENT_APPROVAL_REQUIRED.to_string(),
+            json!("approval_gate_test"),
+        )]);
+        global().update(changes, "test").unwrap();
+        let store = pending_actions();
+        let session = uuid::Uuid::new_v4().to_string();
+
+        assert_eq!(approve_call(&session, "device", &json!({})).await, Ok(()));
+
+        let decide = |approve: bool| {
+            let store = store.clone();
+            let session = session.clone();
+            tokio::spawn(async move {
+                loop {
+                    let held = store
+                        .list()
+                        .into_iter()
+                        .find(|a| a.session_id == session && a.status == ApprovalStatus::Pending);
+                    if let Some(action) = held {
+                        let reason = Some("not now".to_string());
+                        let decided = if approve {
+                            store.approve(&action.id, None, None)
+                        } else {
+                            store.reject(&action.id, None, reason)
+                        };
+                        decided.unwrap();
+                        return;
+                    }
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+
+        let decider = decide(true);
+        assert_eq!(
+            approve_call(&session, "approval_gate_test", &json!({})).await,
+            Ok(())
+        );
+        decider.await.unwrap();
+
+        let decider = decide(false);
+        let refusal = approve_call(&session, "approval_gate_test", &json!({}))
+            .await
+            .unwrap_err();
+        decider.await.unwrap();
+        assert!(
+            refusal.contains("rejected by an operator: not now"),
+            "{}",
+            refusal
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
 use serde_json::Value;
@@ -9,6 +10,10 @@ pub struct ToolResultCache {
     entries: HashMap<String, (crate::toolkit::ToolOutput, Instant)>,
     ttl: Duration,
     max_entries: usize,
+    /// Lookups of cacheable calls answered from the cache
+    hits: AtomicU64,
+    /// Lookups of cacheable calls that had to execute the tool
+    misses: AtomicU64,
 }
 
 impl ToolResultCache {
@@ -17,9 +22,25 @@ impl ToolResultCache {
             entries: HashMap::new(),
             ttl,
             max_entries: 1000, // Prevent unbounded memory growth
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
         }
     }
 
+    /// Count a lookup of a cacheable call.
+    pub(crate) fn record_lookup(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Hits and misses counted since the cache was created.
+    pub fn hit_stats(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
     pub(crate) fn get(&self, key: &str) -> Option<crate::toolkit::ToolOutput> {
         self.entries.get(key).and_then(|(result, timestamp)| {
             if timestamp.elapsed() < self.ttl {
@@ -118,6 +139,11 @@ pub(crate) fn is_tool_cacheable(name: &str, arguments: &Value) -> bool {
         return false;
     }
 
+    // Plan steps may write, so a plan result is never cached.
+    if name == crate::toolkit::plan::PLAN_TOOL_NAME {
+        return false;
+    }
+
     // Other tools (skill, web_fetch, vision, ...) are read-only by default.
     true
 }
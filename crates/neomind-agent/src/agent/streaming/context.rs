@@ -1,4 +1,4 @@
-use super::super::types::AgentMessage;
+use super::super::types::{AgentMessage, ContextStats};
 use crate::agent::streaming::{CompactionConfig, MessagePriority};
 
 /// Result of a single tool execution with metadata
@@ -11,12 +11,14 @@ pub(crate) struct ToolExecutionResult {
 /// Build context window with optional conversation summary injection.
 ///
 /// When a summary is provided, messages up to `summary_up_to_index` are removed
-/// and a system message with the summary is prepended to the context.
+/// and a system message with the summary is prepended to the context. What was
+/// summarized, dropped or shortened is recorded in `stats`.
 pub(crate) fn build_context_window_with_summary(
     messages: &[AgentMessage],
     max_tokens: usize,
     summary: Option<&str>,
     summary_up_to_index: Option<u64>,
+    stats: &mut ContextStats,
 ) -> Vec<AgentMessage> {
     // Adapt compaction to model capacity — larger contexts get gentler treatment
     let config = CompactionConfig::for_context_size(max_tokens);
@@ -34,8 +36,11 @@ pub(crate) fn build_context_window_with_summary(
             messages.to_vec()
         };
 
+    stats.history_messages = messages.len();
+    stats.messages_summarized = messages.len() - filtered.len();
+
     // Build context window from filtered messages
-    let mut result = build_context_window_with_config(&filtered, max_tokens, &config);
+    let mut result = select_context_window(&filtered, max_tokens, &config, stats);
 
     // Inject summary as a system message at the beginning (after any existing system messages)
     if let Some(summary_text) = summary {
@@ -46,9 +51,12 @@ pub(crate) fn build_context_window_with_summary(
             ));
             // Find insertion point: after system messages, before other messages
             let insert_pos = result.iter().take_while(|m| m.role == "system").count();
+            stats.history_tokens += estimate_message_tokens(&summary_msg);
+            stats.summary_injected = true;
             result.insert(insert_pos, summary_msg);
         }
     }
+    stats.messages_in_context = result.len();
 
     result
 }
@@ -66,12 +74,25 @@ pub fn build_context_window_with_config(
     messages: &[AgentMessage],
     max_tokens: usize,
     config: &CompactionConfig,
+) -> Vec<AgentMessage> {
+    select_context_window(messages, max_tokens, config, &mut ContextStats::default())
+}
+
+/// [`build_context_window_with_config`], recording what was compacted,
+/// dropped and truncated in `stats`.
+fn select_context_window(
+    messages: &[AgentMessage],
+    max_tokens: usize,
+    config: &CompactionConfig,
+    stats: &mut ContextStats,
 ) -> Vec<AgentMessage> {
     // Step 1: Calculate total tokens without any compaction
     let total_tokens: usize = messages.iter().map(estimate_message_tokens).sum();
 
     // Step 2: Only compact tool results if we're actually over budget
     let working = if config.compact_tool_results && total_tokens > max_tokens {
+        let tool_results = messages.iter().filter(|m| m.role == "tool").count();
+        stats.tool_results_compacted = tool_results.saturating_sub(config.keep_recent_tool_results);
         compact_tool_results_stream_with_config(messages, config)
     } else {
         messages.to_vec()
@@ -100,6 +121,7 @@ pub fn build_context_window_with_config(
 
         // Truncate long messages only if we're near budget
         let final_msg = if total_tokens > max_tokens && msg_tokens > config.max_message_length {
+            stats.messages_truncated += 1;
             truncate_agent_message(msg, config.max_message_length)
         } else {
             msg.clone()
@@ -109,6 +131,8 @@ pub fn build_context_window_with_config(
         selected_messages.push(final_msg);
     }
 
+    stats.messages_dropped = working.len() - selected_messages.len();
+    stats.history_tokens = current_tokens;
     selected_messages.reverse();
     selected_messages
 }
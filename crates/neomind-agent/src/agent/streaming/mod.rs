@@ -13,8 +13,11 @@
 //! - `context`: context window management
 //! - `resolve`: cached argument resolution
 //! - `tool_exec`: tool execution with retry
+//! - `approval_gate`: holding tool calls for operator approval
+//! - `span`: tracing spans for a chat turn
 
 // Sub-modules
+mod approval_gate;
 mod cache;
 mod context;
 mod dedup;
@@ -22,6 +25,7 @@ mod intent;
 mod resolve;
 mod result_format;
 mod sanitize;
+mod span;
 mod stream_core;
 mod stream_multimodal;
 mod thinking;
@@ -41,8 +45,10 @@ pub use stream_multimodal::process_multimodal_stream_events_with_safeguards;
 pub use thinking::cleanup_thinking_content;
 
 // Re-exports for internal crate use and test access
+pub(crate) use approval_gate::approve_call;
 pub(crate) use resolve::resolve_cached_arguments;
 pub(crate) use sanitize::{sanitize_tool_result_for_prompt, truncate_result_utf8};
+pub(crate) use tool_exec::execute_plan_call;
 
 #[cfg(test)]
 use sanitize::{humanize_bytes, is_large_base64_string};
@@ -369,6 +375,48 @@ mod tests {
         println!("Cache key generation test passed");
     }
 
+    /// Context stats account for every history message
+    #[test]
+    fn test_context_window_stats() {
+        use crate::agent::types::{AgentMessage, ContextStats};
+
+        let long_reply = "details ".repeat(400);
+        let history: Vec<AgentMessage> = (0..12)
+            .map(|i| {
+                if i % 2 == 0 {
+                    AgentMessage::user(format!("question {}", i))
+                } else {
+                    AgentMessage::assistant(long_reply.clone())
+                }
+            })
+            .collect();
+
+        // Everything fits: only the summarized messages are left out
+        let mut stats = ContextStats::default();
+        let window = context::build_context_window_with_summary(
+            &history,
+            1_000_000,
+            Some("earlier questions"),
+            Some(1),
+            &mut stats,
+        );
+        assert_eq!(stats.history_messages, 12);
+        assert_eq!(stats.messages_summarized, 2);
+        assert_eq!(stats.messages_dropped, 0);
+        assert!(stats.summary_injected);
+        assert_eq!(stats.messages_in_context, window.len());
+        assert_eq!(window.len(), 11);
+
+        // Over budget: old assistant replies are dropped, questions kept
+        let mut stats = ContextStats::default();
+        let window =
+            context::build_context_window_with_summary(&history, 500, None, None, &mut stats);
+        assert!(stats.messages_dropped > 0);
+        assert!(!stats.summary_injected);
+        assert_eq!(window.len(), 12 - stats.messages_dropped);
+        assert_eq!(window.iter().filter(|m| m.role == "user").count(), 6);
+    }
+
     /// Test that malformed tool call JSON is not detected as tool calls
     /// This prevents false positives from JSON like [{"name":"[...]"}]
     #[test]
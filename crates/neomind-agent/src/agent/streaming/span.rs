@@ -0,0 +1,49 @@
+//! Tracing spans for the streaming pipeline.
+//!
+//! A chat turn is an `agent.turn` span with `agent.intent`, `agent.context`,
+//! `agent.llm_call` and `agent.tool` children. The turn keeps running inside
+//! the returned event stream, so the stream enters the turn span on every
+//! poll; spans opened while it is polled (LLM rounds, tool calls) nest under
+//! the turn even though they start after the pipeline function returned.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::{Stream, StreamExt};
+use tracing::Span;
+
+/// Stream that is polled inside `span`.
+pub(crate) struct SpannedStream<S> {
+    inner: S,
+    span: Span,
+}
+
+impl<S> SpannedStream<S> {
+    pub(crate) fn new(inner: S, span: Span) -> Self {
+        Self { inner, span }
+    }
+}
+
+impl<S: Stream + Unpin> Stream for SpannedStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let _entered = this.span.enter();
+        this.inner.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_items_pass_through() {
+        let stream = SpannedStream::new(
+            futures::stream::iter(vec![1, 2, 3]),
+            tracing::info_span!("test"),
+        );
+        assert_eq!(stream.collect::<Vec<_>>().await, vec![1, 2, 3]);
+    }
+}
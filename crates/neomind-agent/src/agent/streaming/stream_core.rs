@@ -9,7 +9,9 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use futures::{Stream, StreamExt};
+use tracing::Instrument;
 
+use super::approval_gate::{await_decisions, hold_for_approval, APPROVAL_HEARTBEAT};
 use super::context::{
     build_context_window_with_config, build_context_window_with_summary, ToolExecutionResult,
 };
@@ -18,16 +20,19 @@ use super::intent::build_list_only_dead_end_prompt;
 use super::resolve::resolve_cached_arguments;
 use super::result_format::format_tool_results;
 use super::sanitize::sanitize_tool_result_for_prompt;
+use super::span::SpannedStream;
 use super::thinking::cleanup_thinking_content;
 use super::tool_detect::detect_json_tool_calls;
 use super::tool_exec::execute_tool_with_retry;
+use crate::agent::prompt_injection::screen_tool_result;
 use crate::agent::staged::{IntentCategory, IntentClassifier};
 use crate::agent::tool_parser::{
     is_degenerate_fence_only_output, parse_tool_calls, remove_tool_calls_from_response,
 };
-use crate::agent::types::{AgentEvent, AgentInternalState, AgentMessage, ToolCall};
+use crate::agent::types::{AgentEvent, AgentInternalState, AgentMessage, ContextStats, ToolCall};
 use crate::error::{NeoMindError, Result};
 use crate::llm::LlmInterface;
+use crate::memory::MemorySnapshot;
 use neomind_core::llm::compaction::CompactionConfig;
 
 /// Configuration for stream processing safeguards
@@ -113,6 +118,11 @@ impl StreamSafeguards {
     }
 }
 
+#[tracing::instrument(
+    name = "agent.turn",
+    skip_all,
+    fields(message_len = user_message.len(), multimodal = false)
+)]
 pub async fn process_stream_events_with_safeguards(
     llm_interface: Arc<LlmInterface>,
     internal_state: Arc<tokio::sync::RwLock<AgentInternalState>>,
@@ -127,7 +137,8 @@ pub async fn process_stream_events_with_safeguards(
     // === INTENT RECOGNITION: Understand user intent before LLM call ===
     // This helps reduce cognitive load and provides better visualization
     let classifier = IntentClassifier::default();
-    let intent_result = classifier.classify(&user_message);
+    let intent_result =
+        tracing::info_span!("agent.intent").in_scope(|| classifier.classify(&user_message));
 
     tracing::info!(
         "Intent recognized: category={:?}, confidence={:.2}, keywords={:?}",
@@ -180,7 +191,9 @@ pub async fn process_stream_events_with_safeguards(
     // Pure async - no block_in_place
     let state_guard = internal_state.read().await;
     let history_messages = state_guard.memory.clone();
+    let tool_result_cache = state_guard.tool_result_cache.clone();
     drop(state_guard); // Release lock before calling LLM
+    let cache_baseline = tool_result_cache.read().await.hit_stats();
 
     // === DYNAMIC CONTEXT WINDOW: Get model's actual capacity ===
     let max_context = llm_interface.max_context_length().await;
@@ -205,15 +218,28 @@ pub async fn process_stream_events_with_safeguards(
         max_context, prompt_overhead, RESERVE_FOR_RESPONSE, effective_max
     );
 
-    let history_for_llm: Vec<neomind_core::Message> = build_context_window_with_summary(
-        &history_messages,
-        effective_max,
-        conversation_summary.as_deref(),
-        summary_up_to_index,
-    )
-    .iter()
-    .map(|msg| msg.to_core())
-    .collect::<Vec<_>>();
+    let mut context_stats = ContextStats {
+        context_window: max_context,
+        prompt_overhead,
+        history_budget: effective_max,
+        ..Default::default()
+    };
+    let history_for_llm: Vec<neomind_core::Message> =
+        tracing::info_span!("agent.context", max_tokens = effective_max).in_scope(|| {
+            build_context_window_with_summary(
+                &history_messages,
+                effective_max,
+                conversation_summary.as_deref(),
+                summary_up_to_index,
+                &mut context_stats,
+            )
+            .iter()
+            .map(|msg| msg.to_core())
+            .collect::<Vec<_>>()
+        });
+    let memory_injected = MemorySnapshot::is_injected(&llm_interface.get_system_prompt().await);
+    context_stats.memory_injections =
+        usize::from(memory_injected) + usize::from(context_stats.summary_injected);
 
     tracing::debug!(
         "Passing {} messages from history to LLM",
@@ -237,11 +263,12 @@ pub async fn process_stream_events_with_safeguards(
     // Get the stream from llm_interface - thinking is controlled by instance/user settings
     let stream_result = llm_interface
         .chat_stream_with_history(&user_message, &history_for_llm)
+        .instrument(tracing::info_span!("agent.llm_call", round = 1))
         .await;
 
     let stream = stream_result.map_err(|e| NeoMindError::Llm(e.to_string()))?;
 
-    Ok(Box::pin(async_stream::stream! {
+    let events = async_stream::stream! {
         let mut stream = stream;
         let mut buffer = String::new();
         let mut yielded_up_to: usize = 0; // Track how much of buffer has been yielded to prevent duplication
@@ -396,7 +423,9 @@ pub async fn process_stream_events_with_safeguards(
                     &context_msg,
                     &history_for_llm,
                     thinking_override
-                ).await;
+                )
+                .instrument(tracing::info_span!("agent.llm_call", round = tool_iteration_count + 1))
+                .await;
 
                 let round_stream = match round_stream_result {
                     Ok(s) => s,
@@ -430,7 +459,9 @@ pub async fn process_stream_events_with_safeguards(
                             let summary_result = llm_interface.chat_stream_summary(
                                 fallback_prompt,
                                 &summary_history,
-                            ).await;
+                            )
+                            .instrument(tracing::info_span!("agent.llm_call", kind = "summary"))
+                            .await;
 
                             match summary_result {
                                 Ok(s) => {
@@ -863,20 +894,78 @@ pub async fn process_stream_events_with_safeguards(
                     })
                     .collect();
 
+                // Calls in approval-required categories wait for an operator
+                let session_id = internal_state.read().await.session_id.clone();
+                let (mut tool_inputs, held) = hold_for_approval(&session_id, tool_inputs);
+                let mut refused_results = Vec::new();
+                if !held.is_empty() {
+                    for action in &held {
+                        yield AgentEvent::awaiting_approval(action);
+                    }
+                    let decisions = await_decisions(held);
+                    tokio::pin!(decisions);
+                    let (approved, refused) = loop {
+                        tokio::select! {
+                            decided = &mut decisions => break decided,
+                            _ = tokio::time::sleep(APPROVAL_HEARTBEAT) => {}
+                        }
+                        yield AgentEvent::heartbeat();
+                    };
+                    tool_inputs.extend(approved);
+                    refused_results = refused;
+                }
+
+                // Streaming tools push partial output here while they run; it is
+                // forwarded as ToolProgress events before the batch completes.
+                let (progress_tx, mut progress_rx) =
+                    tokio::sync::mpsc::unbounded_channel::<(String, crate::toolkit::ToolOutput)>();
+
                 let tool_futures = futures::stream::iter(tool_inputs.into_iter().map(|(name, arguments)| {
                     let tools_clone = tools.clone();
                     let cache_clone = cache.clone();
+                    let progress_tx = progress_tx.clone();
 
                     async move {
+                        let (chunk_tx, mut chunk_rx) = tokio::sync::mpsc::unbounded_channel();
+                        let exec = async {
+                            let result = execute_tool_with_retry(&tools_clone, &cache_clone, &name, arguments.clone(), Some(&chunk_tx)).await;
+                            drop(chunk_tx);
+                            result
+                        };
+                        let forward = async {
+                            while let Some(chunk) = chunk_rx.recv().await {
+                                let _ = progress_tx.send((name.clone(), chunk));
+                            }
+                        };
+                        let (result, ()) = futures::join!(exec, forward);
+
                         (name.clone(), ToolExecutionResult {
                             _name: name.clone(),
                             arguments: arguments.clone(),
-                            result: execute_tool_with_retry(&tools_clone, &cache_clone, &name, arguments.clone()).await,
+                            result,
                         })
                     }
                 })).buffer_unordered(MAX_TOOL_CONCURRENCY);
 
-                let tool_results_executed: Vec<_> = tool_futures.collect().await;
+                let collect_results = tool_futures.collect::<Vec<_>>();
+                tokio::pin!(collect_results);
+                let mut tool_results_executed: Vec<_> = loop {
+                    let next = tokio::select! {
+                        Some(progress) = progress_rx.recv() => futures::future::Either::Left(progress),
+                        results = &mut collect_results => futures::future::Either::Right(results),
+                    };
+                    match next {
+                        futures::future::Either::Left((tool, chunk)) => {
+                            yield AgentEvent::tool_progress_round(tool, chunk.data, tool_iteration_count + 1);
+                        }
+                        futures::future::Either::Right(results) => break results,
+                    }
+                };
+                // Chunks sent just before the last tool finished
+                while let Ok((tool, chunk)) = progress_rx.try_recv() {
+                    yield AgentEvent::tool_progress_round(tool, chunk.data, tool_iteration_count + 1);
+                }
+                tool_results_executed.extend(refused_results);
 
                 // Process results
                 let mut tool_calls_with_results: Vec<ToolCall> = Vec::new();
@@ -942,6 +1031,7 @@ pub async fn process_stream_events_with_safeguards(
 
                             yield AgentEvent::tool_call_end_round(&name, &display_str, output.success, tool_iteration_count + 1);
 
+                            let slimmed_str = screen_tool_result(slimmed_str);
                             tool_call_results.push((name.clone(), slimmed_str));
                         }
                         Err(e) => {
@@ -1135,7 +1225,9 @@ pub async fn process_stream_events_with_safeguards(
                     let summary_result = llm_interface.chat_stream_summary(
                         summary_prompt,
                         &summary_history,
-                    ).await;
+                    )
+                    .instrument(tracing::info_span!("agent.llm_call", kind = "summary"))
+                    .await;
 
                     let mut final_content = String::new();
                     match summary_result {
@@ -1242,7 +1334,9 @@ pub async fn process_stream_events_with_safeguards(
                     let summary_result = llm_interface.chat_stream_summary(
                         summary_prompt,
                         &summary_history,
-                    ).await;
+                    )
+                    .instrument(tracing::info_span!("agent.llm_call", kind = "summary"))
+                    .await;
 
                     match summary_result {
                         Ok(stream) => {
@@ -1320,7 +1414,9 @@ pub async fn process_stream_events_with_safeguards(
                         &retry_prompt,
                         &retry_history,
                         Some(false), // Force disable thinking
-                    ).await;
+                    )
+                    .instrument(tracing::info_span!("agent.llm_call", kind = "retry"))
+                    .await;
 
                     match retry_result {
                         Ok(retry_stream) => {
@@ -1410,13 +1506,24 @@ pub async fn process_stream_events_with_safeguards(
             break 'multi_round_loop;
         }
 
+        // Context diagnostics, with the tool cache lookups of this turn
+        let (hits, misses) = tool_result_cache.read().await.hit_stats();
+        context_stats.cache_hits = hits.saturating_sub(cache_baseline.0);
+        context_stats.cache_misses = misses.saturating_sub(cache_baseline.1);
+        yield AgentEvent::context_stats(context_stats);
+
         // Read token usage from LLM interface (captured from Ollama backend stream)
         let prompt_tokens = llm_interface.take_last_prompt_tokens().await;
         match prompt_tokens {
             Some(pt) => yield AgentEvent::end_with_tokens(pt),
             None => yield AgentEvent::end(),
         }
-    }))
+    };
+
+    Ok(Box::pin(SpannedStream::new(
+        Box::pin(events),
+        tracing::Span::current(),
+    )))
 }
 
 /// Convert AgentEvent stream to String stream for backward compatibility.
@@ -8,27 +8,41 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use futures::{Stream, StreamExt};
+use tracing::Instrument;
 
+use super::approval_gate::{await_decisions, hold_for_approval, APPROVAL_HEARTBEAT};
 use super::context::{build_context_window_with_summary, ToolExecutionResult};
 use super::dedup::deduplicate_tool_results;
 use super::intent::build_list_only_dead_end_prompt;
 use super::resolve::resolve_cached_arguments;
 use super::result_format::format_tool_results;
 use super::sanitize::sanitize_tool_result_for_prompt;
+use super::span::SpannedStream;
 use super::stream_core::StreamSafeguards;
 use super::tool_detect::detect_json_tool_calls;
 use super::tool_exec::execute_tool_with_retry;
+use crate::agent::prompt_injection::screen_tool_result;
 use crate::agent::tool_parser::{
     is_degenerate_fence_only_output, parse_tool_calls, remove_tool_calls_from_response,
 };
 use crate::agent::types::{
-    AgentEvent, AgentInternalState, AgentMessage, AgentMessageImage, ToolCall,
+    AgentEvent, AgentInternalState, AgentMessage, AgentMessageImage, ContextStats, ToolCall,
 };
 use crate::error::{NeoMindError, Result};
 use crate::llm::LlmInterface;
+use crate::memory::MemorySnapshot;
 
 /// Process multimodal message with configurable safeguards.
 #[allow(clippy::too_many_arguments)]
+#[tracing::instrument(
+    name = "agent.turn",
+    skip_all,
+    fields(
+        message_len = user_message.len(),
+        multimodal = true,
+        images = images.len()
+    )
+)]
 pub async fn process_multimodal_stream_events_with_safeguards(
     llm_interface: Arc<LlmInterface>,
     internal_state: Arc<tokio::sync::RwLock<AgentInternalState>>,
@@ -56,7 +70,9 @@ pub async fn process_multimodal_stream_events_with_safeguards(
     // Get conversation history
     let state_guard = internal_state.read().await;
     let history_messages = state_guard.memory.clone();
+    let tool_result_cache = state_guard.tool_result_cache.clone();
     drop(state_guard);
+    let cache_baseline = tool_result_cache.read().await.hit_stats();
 
     // Build context window — measure actual prompt overhead instead of guessing
     let max_context = llm_interface.max_context_length().await;
@@ -66,15 +82,28 @@ pub async fn process_multimodal_stream_events_with_safeguards(
         .saturating_sub(1024)
         .max((max_context * 20) / 100);
 
-    let history_for_llm: Vec<neomind_core::Message> = build_context_window_with_summary(
-        &history_messages,
-        effective_max,
-        conversation_summary.as_deref(),
-        summary_up_to_index,
-    )
-    .iter()
-    .map(|msg| msg.to_core())
-    .collect::<Vec<_>>();
+    let mut context_stats = ContextStats {
+        context_window: max_context,
+        prompt_overhead,
+        history_budget: effective_max,
+        ..Default::default()
+    };
+    let history_for_llm: Vec<neomind_core::Message> =
+        tracing::info_span!("agent.context", max_tokens = effective_max).in_scope(|| {
+            build_context_window_with_summary(
+                &history_messages,
+                effective_max,
+                conversation_summary.as_deref(),
+                summary_up_to_index,
+                &mut context_stats,
+            )
+            .iter()
+            .map(|msg| msg.to_core())
+            .collect::<Vec<_>>()
+        });
+    let memory_injected = MemorySnapshot::is_injected(&llm_interface.get_system_prompt().await);
+    context_stats.memory_injections =
+        usize::from(memory_injected) + usize::from(context_stats.summary_injected);
 
     tracing::debug!(
         "Passing {} messages from history to LLM (multimodal)",
@@ -91,6 +120,7 @@ pub async fn process_multimodal_stream_events_with_safeguards(
     // Thinking helps the model analyze images more thoroughly
     let stream_result = llm_interface
         .chat_stream_multimodal_with_history(multimodal_user_msg, &history_for_llm)
+        .instrument(tracing::info_span!("agent.llm_call", round = 1))
         .await;
 
     let stream = stream_result.map_err(|e| NeoMindError::Llm(e.to_string()))?;
@@ -138,7 +168,7 @@ pub async fn process_multimodal_stream_events_with_safeguards(
         }
     }
 
-    Ok(Box::pin(async_stream::stream! {
+    let events = async_stream::stream! {
         let mut stream = stream;
         let mut buffer = String::new();
         let mut tool_calls_detected = false;
@@ -273,6 +303,27 @@ pub async fn process_multimodal_stream_events_with_safeguards(
                 })
                 .collect();
 
+            // Calls in approval-required categories wait for an operator
+            let session_id = internal_state.read().await.session_id.clone();
+            let (mut tool_inputs, held) = hold_for_approval(&session_id, tool_inputs);
+            let mut refused_results = Vec::new();
+            if !held.is_empty() {
+                for action in &held {
+                    yield AgentEvent::awaiting_approval(action);
+                }
+                let decisions = await_decisions(held);
+                tokio::pin!(decisions);
+                let (approved, refused) = loop {
+                    tokio::select! {
+                        decided = &mut decisions => break decided,
+                        _ = tokio::time::sleep(APPROVAL_HEARTBEAT) => {}
+                    }
+                    yield AgentEvent::heartbeat();
+                };
+                tool_inputs.extend(approved);
+                refused_results = refused;
+            }
+
             let tool_futures = futures::stream::iter(tool_inputs.into_iter().map(|(name, arguments)| {
                 let tools_clone = tools.clone();
                 let cache_clone = cache.clone();
@@ -281,12 +332,13 @@ pub async fn process_multimodal_stream_events_with_safeguards(
                     (name.clone(), ToolExecutionResult {
                         _name: name.clone(),
                         arguments: arguments.clone(),
-                        result: execute_tool_with_retry(&tools_clone, &cache_clone, &name, arguments.clone()).await,
+                        result: execute_tool_with_retry(&tools_clone, &cache_clone, &name, arguments.clone(), None).await,
                     })
                 }
             })).buffer_unordered(6);
 
-            let tool_results_executed: Vec<_> = tool_futures.collect().await;
+            let mut tool_results_executed: Vec<_> = tool_futures.collect().await;
+            tool_results_executed.extend(refused_results);
 
             // Process results
             let mut tool_calls_with_results: Vec<ToolCall> = Vec::new();
@@ -347,6 +399,7 @@ pub async fn process_multimodal_stream_events_with_safeguards(
 
                         yield AgentEvent::tool_call_end(&name, &display_str, output.success);
 
+                        let slimmed_str = screen_tool_result(slimmed_str);
                         tool_call_results.push((name.clone(), slimmed_str));
                     }
                     Err(e) => {
@@ -425,6 +478,7 @@ pub async fn process_multimodal_stream_events_with_safeguards(
 
                 let cont_stream_result = llm_interface
                     .chat_stream_with_history_thinking(&dead_end_prompt, &cont_history, None)
+                    .instrument(tracing::info_span!("agent.llm_call", kind = "continuation"))
                     .await;
 
                 if let Ok(cont_stream) = cont_stream_result {
@@ -458,6 +512,27 @@ pub async fn process_multimodal_stream_events_with_safeguards(
                                 )
                             })
                             .collect();
+                        // Calls in approval-required categories wait for an operator
+                        let session_id = internal_state.read().await.session_id.clone();
+                        let (mut cont_inputs, held) = hold_for_approval(&session_id, cont_inputs);
+                        let mut cont_refused = Vec::new();
+                        if !held.is_empty() {
+                            for action in &held {
+                                yield AgentEvent::awaiting_approval(action);
+                            }
+                            let decisions = await_decisions(held);
+                            tokio::pin!(decisions);
+                            let (approved, refused) = loop {
+                                tokio::select! {
+                                    decided = &mut decisions => break decided,
+                                    _ = tokio::time::sleep(APPROVAL_HEARTBEAT) => {}
+                                }
+                                yield AgentEvent::heartbeat();
+                            };
+                            cont_inputs.extend(approved);
+                            cont_refused = refused;
+                        }
+
                         let cont_futures = futures::stream::iter(cont_inputs.into_iter().map(|(name, arguments)| {
                             let tools_clone = tools.clone();
                             let cache_clone = cache_cont.clone();
@@ -465,11 +540,12 @@ pub async fn process_multimodal_stream_events_with_safeguards(
                                 (name.clone(), ToolExecutionResult {
                                     _name: name.clone(),
                                     arguments: arguments.clone(),
-                                    result: execute_tool_with_retry(&tools_clone, &cache_clone, &name, arguments.clone()).await,
+                                    result: execute_tool_with_retry(&tools_clone, &cache_clone, &name, arguments.clone(), None).await,
                                 })
                             }
                         })).buffer_unordered(6);
-                        let cont_results: Vec<_> = cont_futures.collect().await;
+                        let mut cont_results: Vec<_> = cont_futures.collect().await;
+                        cont_results.extend(cont_refused);
 
                         // Save continuation assistant message + tool results to history
                         let cont_msg = AgentMessage::assistant_with_tools(
@@ -506,6 +582,7 @@ pub async fn process_multimodal_stream_events_with_safeguards(
                                     };
                                     let display_str = sanitize_tool_result_for_prompt(&slimmed_str);
                                     yield AgentEvent::tool_call_end(&name, &display_str, output.success);
+                                    let slimmed_str = screen_tool_result(slimmed_str);
                                     let mut state = internal_state.write().await;
                                     state.push_message(AgentMessage::tool_result(&name, &slimmed_str));
                                     tool_call_results.push((name.clone(), slimmed_str));
@@ -537,7 +614,9 @@ pub async fn process_multimodal_stream_events_with_safeguards(
             let summary_result = llm_interface.chat_stream_summary(
                 summary_prompt,
                 &summary_history,
-            ).await;
+            )
+            .instrument(tracing::info_span!("agent.llm_call", kind = "summary"))
+            .await;
 
             match summary_result {
                 Ok(stream) => {
@@ -623,10 +702,20 @@ pub async fn process_multimodal_stream_events_with_safeguards(
             }
         }
 
+        let (hits, misses) = tool_result_cache.read().await.hit_stats();
+        context_stats.cache_hits = hits.saturating_sub(cache_baseline.0);
+        context_stats.cache_misses = misses.saturating_sub(cache_baseline.1);
+        yield AgentEvent::context_stats(context_stats);
+
         let pt = llm_interface.take_last_prompt_tokens().await;
         match pt {
             Some(t) => yield AgentEvent::end_with_tokens(t),
             None => yield AgentEvent::end(),
         }
-    }))
+    };
+
+    Ok(Box::pin(SpannedStream::new(
+        Box::pin(events),
+        tracing::Span::current(),
+    )))
 }
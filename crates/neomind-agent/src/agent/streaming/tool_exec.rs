@@ -4,20 +4,28 @@ use tokio::sync::RwLock;
 
 use super::cache::{is_tool_cacheable, ToolResultCache};
 use super::resolve::resolve_tool_name;
+use crate::toolkit::plan::{ExecutionPlan, PLAN_TOOL_NAME};
 
 /// Execute a tool with retry logic for transient errors and caching.
+///
+/// When `progress` is set, intermediate chunks from streaming tools are sent
+/// to it while the tool runs (see `ToolRegistry::execute_streaming`).
+#[tracing::instrument(name = "agent.tool", skip_all, fields(tool = %name))]
 pub(crate) async fn execute_tool_with_retry(
     tools: &crate::toolkit::ToolRegistry,
     cache: &Arc<RwLock<ToolResultCache>>,
     name: &str,
     arguments: serde_json::Value,
+    progress: Option<&crate::toolkit::ToolProgressSender>,
 ) -> std::result::Result<crate::toolkit::ToolOutput, crate::toolkit::ToolError> {
     // Check cache for read-only tools
     if is_tool_cacheable(name, &arguments) {
         let cache_key = ToolResultCache::make_key(name, &arguments);
         {
             let cache_read = cache.read().await;
-            if let Some(cached) = cache_read.get(&cache_key) {
+            let cached = cache_read.get(&cache_key);
+            cache_read.record_lookup(cached.is_some());
+            if let Some(cached) = cached {
                 tracing::debug!(tool = %name, "Tool result cache HIT");
                 return Ok(cached);
             }
@@ -26,7 +34,8 @@ pub(crate) async fn execute_tool_with_retry(
     }
 
     let max_retries = 2u32;
-    let result = execute_with_retry_impl(tools, name, arguments.clone(), max_retries).await;
+    let result =
+        execute_with_retry_impl(tools, name, arguments.clone(), max_retries, progress).await;
 
     // Cache successful results for cacheable tools
     if is_tool_cacheable(name, &arguments) {
@@ -50,7 +59,62 @@ pub(crate) async fn execute_with_retry_impl(
     name: &str,
     arguments: serde_json::Value,
     max_retries: u32,
+    progress: Option<&crate::toolkit::ToolProgressSender>,
 ) -> std::result::Result<crate::toolkit::ToolOutput, crate::toolkit::ToolError> {
+    if name == PLAN_TOOL_NAME {
+        return Ok(execute_plan_call(tools, &arguments, max_retries).await);
+    }
+    execute_step_with_retry(tools, name, arguments, max_retries, progress).await
+}
+
+/// Run a `plan` tool call. Each step goes through the same guardrails,
+/// name mapping and retries as a direct call; plan errors are returned to
+/// the LLM as a failed tool output.
+pub(crate) async fn execute_plan_call(
+    tools: &crate::toolkit::ToolRegistry,
+    arguments: &serde_json::Value,
+    max_retries: u32,
+) -> crate::toolkit::ToolOutput {
+    let plan = match ExecutionPlan::from_args(arguments) {
+        Ok(plan) => plan,
+        Err(e) => return crate::toolkit::ToolOutput::error(e.to_string()),
+    };
+    let outcome = plan
+        .run(|calls| async move {
+            let runs = calls.iter().map(|call| {
+                execute_step_with_retry(tools, &call.name, call.args.clone(), max_retries, None)
+            });
+            let results = futures::future::join_all(runs).await;
+            calls
+                .into_iter()
+                .zip(results)
+                .map(|(call, result)| crate::toolkit::ToolResult {
+                    name: call.name,
+                    result,
+                })
+                .collect()
+        })
+        .await;
+    match outcome {
+        Ok(outcome) => outcome.into_output(),
+        Err(e) => crate::toolkit::ToolOutput::error(e.to_string()),
+    }
+}
+
+/// Retry logic for a single (non-plan) tool call.
+async fn execute_step_with_retry(
+    tools: &crate::toolkit::ToolRegistry,
+    name: &str,
+    arguments: serde_json::Value,
+    max_retries: u32,
+    progress: Option<&crate::toolkit::ToolProgressSender>,
+) -> std::result::Result<crate::toolkit::ToolOutput, crate::toolkit::ToolError> {
+    // Guardrails are keyed by the name the LLM used; CLI domains reach the
+    // registry as `shell` commands
+    if let Err(violation) = crate::toolkit::guardrails::check(name, &arguments) {
+        return Ok(violation.into_output());
+    }
+
     // Map simplified tool name to real tool name
     let real_tool_name = resolve_tool_name(name);
 
@@ -77,15 +141,22 @@ pub(crate) async fn execute_with_retry_impl(
     };
 
     for attempt in 0..=max_retries {
-        let result = tokio::time::timeout(
-            tokio::time::Duration::from_secs(timeout_secs),
-            tools.execute(&real_tool_name, exec_args.clone()),
-        )
-        .await
-        .unwrap_or(Err(crate::toolkit::ToolError::Execution(format!(
-            "Tool '{}' timed out after {}s",
-            name, timeout_secs
-        ))));
+        let call = async {
+            match progress {
+                Some(tx) => {
+                    tools
+                        .execute_streaming(&real_tool_name, exec_args.clone(), tx.clone())
+                        .await
+                }
+                None => tools.execute(&real_tool_name, exec_args.clone()).await,
+            }
+        };
+        let result = tokio::time::timeout(tokio::time::Duration::from_secs(timeout_secs), call)
+            .await
+            .unwrap_or(Err(crate::toolkit::ToolError::Execution(format!(
+                "Tool '{}' timed out after {}s",
+                name, timeout_secs
+            ))));
 
         match &result {
             Ok(output) if output.success => return result,
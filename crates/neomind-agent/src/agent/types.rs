@@ -44,6 +44,29 @@ pub enum AgentEvent {
         #[serde(skip_serializing_if = "Option::is_none")]
         round: Option<usize>,
     },
+    /// Incremental output from a streaming tool (see `Tool::execute_streaming`)
+    ToolProgress {
+        /// Tool name
+        tool: String,
+        /// Partial output chunk
+        data: Value,
+        /// Round number (1-based) for multi-round tool calling
+        #[serde(skip_serializing_if = "Option::is_none")]
+        round: Option<usize>,
+    },
+    /// Tool call held until an operator approves it (see `agent::approval`)
+    AwaitingApproval {
+        /// Pending action id, used to approve or reject it
+        action_id: String,
+        /// Tool name
+        tool: String,
+        /// Action category (`<tool>.<action>`)
+        category: String,
+        /// Tool arguments
+        arguments: Value,
+        /// Unix timestamp after which the action expires
+        expires_at: i64,
+    },
     /// Error occurred
     Error {
         /// Error message
@@ -76,6 +99,11 @@ pub enum AgentEvent {
         #[serde(skip_serializing_if = "Option::is_none")]
         keywords: Option<Vec<String>>,
     },
+    /// How the turn's context was assembled, sent before the stream ends
+    ContextStats {
+        /// Token budget, compaction and cache diagnostics
+        stats: ContextStats,
+    },
     /// Execution plan step
     Plan {
         /// Step description
@@ -163,6 +191,26 @@ impl AgentEvent {
         }
     }
 
+    /// Create a tool progress event with round number.
+    pub fn tool_progress_round(tool: impl Into<String>, data: Value, round: usize) -> Self {
+        Self::ToolProgress {
+            tool: tool.into(),
+            data,
+            round: Some(round),
+        }
+    }
+
+    /// Create an awaiting-approval event for a held tool call.
+    pub fn awaiting_approval(action: &super::approval::PendingAction) -> Self {
+        Self::AwaitingApproval {
+            action_id: action.id.clone(),
+            tool: action.tool.clone(),
+            category: action.category.clone(),
+            arguments: action.arguments.clone(),
+            expires_at: action.expires_at,
+        }
+    }
+
     /// Create an error event.
     pub fn error(message: impl Into<String>) -> Self {
         Self::Error {
@@ -206,6 +254,11 @@ impl AgentEvent {
         }
     }
 
+    /// Create a context stats event.
+    pub fn context_stats(stats: ContextStats) -> Self {
+        Self::ContextStats { stats }
+    }
+
     /// Create a plan event.
     pub fn plan(step: impl Into<String>, stage: impl Into<String>) -> Self {
         Self::Plan {
@@ -246,6 +299,44 @@ impl AgentEvent {
     }
 }
 
+/// Context diagnostics for one chat turn.
+///
+/// Explains what the model actually saw: how much of the history fit into
+/// the budget, what was summarized, dropped or shortened, which memory was
+/// injected and how often tool results came from the cache.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextStats {
+    /// Model context window in tokens
+    pub context_window: usize,
+    /// Tokens taken by the system prompt and tool definitions
+    pub prompt_overhead: usize,
+    /// Tokens available for conversation history
+    pub history_budget: usize,
+    /// Estimated tokens of the history sent to the model
+    pub history_tokens: usize,
+    /// Messages in the session history
+    pub history_messages: usize,
+    /// Messages sent to the model, including an injected summary
+    pub messages_in_context: usize,
+    /// Messages replaced by the conversation summary
+    pub messages_summarized: usize,
+    /// Messages left out to stay within the budget
+    pub messages_dropped: usize,
+    /// Messages shortened to stay within the budget
+    pub messages_truncated: usize,
+    /// Older tool results replaced by a one-line recap
+    pub tool_results_compacted: usize,
+    /// Whether a conversation summary was injected
+    pub summary_injected: bool,
+    /// Memory blocks injected into the prompt (memory snapshot, summary)
+    pub memory_injections: usize,
+    /// Tool calls answered from the tool result cache
+    pub cache_hits: u64,
+    /// Cacheable tool calls that had to be executed
+    pub cache_misses: u64,
+}
+
 /// Agent configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentConfig {
@@ -273,6 +364,9 @@ pub struct AgentConfig {
     /// Number of recent tool results to keep intact (default: 2)
     #[serde(default = "default_keep_tool_results")]
     pub keep_recent_tool_results: usize,
+    /// Tools this agent may see and call; `None` allows every registered tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 /// Default value for max tool calls per request.
@@ -312,6 +406,7 @@ impl Default for AgentConfig {
             api_key: std::env::var("OPENAI_API_KEY").ok(),
             max_tool_calls: default_max_tool_calls(),
             keep_recent_tool_results: default_keep_tool_results(),
+            allowed_tools: None,
         }
     }
 }
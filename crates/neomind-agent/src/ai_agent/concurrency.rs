@@ -0,0 +1,340 @@
+//! Concurrency limits for agent executions.
+//!
+//! Every execution path — schedule, event, manual and rule-triggered — goes
+//! through [`AgentExecutor::execute_agent`](super::AgentExecutor::execute_agent),
+//! which first takes an [`ExecutionPermit`] from the shared
+//! [`ExecutionLimiter`]. The limiter enforces each agent's
+//! [`ConcurrencyPolicy`] plus a global cap across all agents; when either is
+//! reached the agent's [`OverflowPolicy`] decides whether the execution waits,
+//! is dropped, or replaces the one already waiting.
+//!
+//! Freed slots go to the oldest waiting execution whose agent has room, so a
+//! busy agent can't starve the others at the global cap.
+
+use neomind_storage::{ConcurrencyPolicy, OverflowPolicy};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::oneshot;
+
+use crate::error::NeoMindError;
+
+/// Prefix of the error returned for executions the limiter turned away.
+const REJECTED_PREFIX: &str = "Execution not started";
+
+/// Why an execution was not started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum LimitRejection {
+    #[error("concurrency limit reached")]
+    Dropped,
+    #[error("execution queue is full")]
+    QueueFull,
+    #[error("replaced by a newer execution")]
+    Replaced,
+}
+
+impl LimitRejection {
+    pub fn into_error(self, agent_id: &str) -> NeoMindError {
+        NeoMindError::Other(format!("{} for agent {}: {}", REJECTED_PREFIX, agent_id, self))
+    }
+}
+
+/// Whether `err` is a rejection by the limiter. Rejections are not failures:
+/// they shouldn't be retried or counted against the agent.
+pub fn is_rejection(err: &NeoMindError) -> bool {
+    matches!(err, NeoMindError::Other(msg) if msg.starts_with(REJECTED_PREFIX))
+}
+
+type Waiter = oneshot::Sender<Result<ExecutionPermit, LimitRejection>>;
+
+#[derive(Default)]
+struct AgentSlots {
+    running: usize,
+    limit: Option<usize>,
+    /// Waiting executions with their arrival sequence number
+    waiting: VecDeque<(u64, Waiter)>,
+    rejected: u64,
+}
+
+impl AgentSlots {
+    fn has_room(&self) -> bool {
+        self.limit.is_none_or(|max| self.running < max)
+    }
+}
+
+#[derive(Default)]
+struct LimiterState {
+    running: usize,
+    next_seq: u64,
+    agents: HashMap<String, AgentSlots>,
+}
+
+/// Concurrency figures for one agent.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AgentConcurrencyStats {
+    pub running: usize,
+    pub queued: usize,
+    /// Executions dropped, turned away by a full queue or replaced
+    pub rejected: u64,
+}
+
+/// Concurrency figures across all agents.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConcurrencyStats {
+    pub running: usize,
+    pub global_limit: usize,
+    pub queued: usize,
+    pub agents: HashMap<String, AgentConcurrencyStats>,
+}
+
+/// Hands out execution slots per agent and globally. Cheap to clone; clones
+/// share the same slots.
+#[derive(Clone)]
+pub struct ExecutionLimiter {
+    global_limit: usize,
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl ExecutionLimiter {
+    /// Create a limiter allowing `global_limit` executions at once (at least 1).
+    pub fn new(global_limit: usize) -> Self {
+        Self {
+            global_limit: global_limit.max(1),
+            state: Arc::new(Mutex::new(LimiterState::default())),
+        }
+    }
+
+    pub fn global_limit(&self) -> usize {
+        self.global_limit
+    }
+
+    /// Take a slot for an execution of `agent_id`, waiting or failing as
+    /// `policy` says when the agent or the system is full. The slot is held
+    /// until the permit is dropped.
+    pub async fn acquire(
+        &self,
+        agent_id: &str,
+        policy: &ConcurrencyPolicy,
+    ) -> Result<ExecutionPermit, LimitRejection> {
+        let rx = {
+            let mut guard = self.state.lock();
+            let state = &mut *guard;
+            let global_room = state.running < self.global_limit;
+            let slots = state.agents.entry(agent_id.to_string()).or_default();
+            slots.limit = policy.max_concurrent.map(|max| max.max(1));
+            slots.waiting.retain(|(_, waiter)| !waiter.is_closed());
+
+            if global_room && slots.has_room() && slots.waiting.is_empty() {
+                slots.running += 1;
+                state.running += 1;
+                return Ok(self.permit(agent_id));
+            }
+
+            match policy.on_limit {
+                OverflowPolicy::Drop => {
+                    slots.rejected += 1;
+                    return Err(LimitRejection::Dropped);
+                }
+                OverflowPolicy::Queue if slots.waiting.len() >= policy.max_queue => {
+                    slots.rejected += 1;
+                    return Err(LimitRejection::QueueFull);
+                }
+                OverflowPolicy::Queue => {}
+                OverflowPolicy::ReplaceLatest => {
+                    for (_, waiter) in slots.waiting.drain(..) {
+                        slots.rejected += 1;
+                        let _ = waiter.send(Err(LimitRejection::Replaced));
+                    }
+                }
+            }
+
+            let (tx, rx) = oneshot::channel();
+            slots.waiting.push_back((state.next_seq, tx));
+            state.next_seq += 1;
+            tracing::debug!(
+                agent_id = %agent_id,
+                queued = slots.waiting.len(),
+                "Agent execution queued at concurrency limit"
+            );
+            rx
+        };
+
+        // A permit sent after this future is dropped is dropped with the
+        // channel, which releases the slot again
+        rx.await.unwrap_or(Err(LimitRejection::Dropped))
+    }
+
+    /// Concurrency figures for one agent.
+    pub fn agent_stats(&self, agent_id: &str) -> AgentConcurrencyStats {
+        self.state
+            .lock()
+            .agents
+            .get(agent_id)
+            .map(Self::slot_stats)
+            .unwrap_or_default()
+    }
+
+    /// Concurrency figures for all agents that have run since startup.
+    pub fn stats(&self) -> ConcurrencyStats {
+        let state = self.state.lock();
+        let agents: HashMap<String, AgentConcurrencyStats> = state
+            .agents
+            .iter()
+            .map(|(id, slots)| (id.clone(), Self::slot_stats(slots)))
+            .collect();
+        ConcurrencyStats {
+            running: state.running,
+            global_limit: self.global_limit,
+            queued: agents.values().map(|a| a.queued).sum(),
+            agents,
+        }
+    }
+
+    fn slot_stats(slots: &AgentSlots) -> AgentConcurrencyStats {
+        AgentConcurrencyStats {
+            running: slots.running,
+            queued: slots
+                .waiting
+                .iter()
+                .filter(|(_, waiter)| !waiter.is_closed())
+                .count(),
+            rejected: slots.rejected,
+        }
+    }
+
+    fn permit(&self, agent_id: &str) -> ExecutionPermit {
+        ExecutionPermit {
+            limiter: self.clone(),
+            agent_id: agent_id.to_string(),
+        }
+    }
+
+    fn release(&self, agent_id: &str) {
+        let undelivered = {
+            let mut guard = self.state.lock();
+            let state = &mut *guard;
+            state.running = state.running.saturating_sub(1);
+            if let Some(slots) = state.agents.get_mut(agent_id) {
+                slots.running = slots.running.saturating_sub(1);
+            }
+            self.dispatch(state)
+        };
+        // Dropping these re-enters `release`, so only after the lock is gone
+        drop(undelivered);
+    }
+
+    /// Start waiting executions while there is room, oldest first. Returns
+    /// the permits whose waiter went away in the meantime.
+    fn dispatch(&self, state: &mut LimiterState) -> Vec<ExecutionPermit> {
+        let mut undelivered = Vec::new();
+        while state.running < self.global_limit {
+            let next = state
+                .agents
+                .iter_mut()
+                .filter(|(_, slots)| slots.has_room())
+                .filter_map(|(id, slots)| {
+                    let seq = slots.waiting.front()?.0;
+                    Some((seq, id, slots))
+                })
+                .min_by_key(|(seq, _, _)| *seq);
+            let Some((_, agent_id, slots)) = next else {
+                break;
+            };
+            let Some((_, waiter)) = slots.waiting.pop_front() else {
+                break;
+            };
+            slots.running += 1;
+            state.running += 1;
+            if let Err(Ok(permit)) = waiter.send(Ok(ExecutionPermit {
+                limiter: self.clone(),
+                agent_id: agent_id.clone(),
+            })) {
+                undelivered.push(permit);
+            }
+        }
+        undelivered
+    }
+}
+
+/// A running execution's slot; released on drop.
+pub struct ExecutionPermit {
+    limiter: ExecutionLimiter,
+    agent_id: String,
+}
+
+impl Drop for ExecutionPermit {
+    fn drop(&mut self) {
+        self.limiter.release(&self.agent_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn policy(max: usize, on_limit: OverflowPolicy) -> ConcurrencyPolicy {
+        ConcurrencyPolicy {
+            max_concurrent: Some(max),
+            on_limit,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queue_and_drop() {
+        let limiter = ExecutionLimiter::new(10);
+        let queue = policy(1, OverflowPolicy::Queue);
+        let first = limiter.acquire("a", &queue).await.unwrap();
+
+        let waiting = {
+            let limiter = limiter.clone();
+            let queue = queue.clone();
+            tokio::spawn(async move { limiter.acquire("a", &queue).await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(limiter.agent_stats("a").queued, 1);
+
+        let drop_policy = policy(1, OverflowPolicy::Drop);
+        let rejected = limiter.acquire("a", &drop_policy).await.err();
+        assert_eq!(rejected, Some(LimitRejection::Dropped));
+        assert!(is_rejection(&LimitRejection::Dropped.into_error("a")));
+
+        drop(first);
+        assert!(waiting.await.unwrap().is_ok());
+        let stats = limiter.agent_stats("a");
+        assert_eq!((stats.running, stats.queued, stats.rejected), (0, 0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_replace_latest_and_global_cap() {
+        let limiter = ExecutionLimiter::new(2);
+        let replace = policy(1, OverflowPolicy::ReplaceLatest);
+        let running = limiter.acquire("a", &replace).await.unwrap();
+
+        let spawn_waiter = |agent: &'static str, policy: ConcurrencyPolicy| {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire(agent, &policy).await.map(|_| ()) })
+        };
+        let older = spawn_waiter("a", replace.clone());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let newer = spawn_waiter("a", replace.clone());
+        assert_eq!(older.await.unwrap(), Err(LimitRejection::Replaced));
+
+        // Agent "b" has no limit of its own but shares the global cap
+        let unlimited = ConcurrencyPolicy::default();
+        let b = limiter.acquire("b", &unlimited).await.unwrap();
+        let waiting_b = spawn_waiter("b", unlimited);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let stats = limiter.stats();
+        assert_eq!((stats.running, stats.queued), (2, 2));
+
+        drop(running);
+        assert!(newer.await.unwrap().is_ok());
+        drop(b);
+        assert!(waiting_b.await.unwrap().is_ok());
+        assert_eq!(limiter.stats().running, 0);
+    }
+}
@@ -20,6 +20,7 @@ impl AgentExecutor {
     pub(crate) async fn execute_single_command(
         &self,
         agent: &AiAgent,
+        execution_id: &str,
         resource: &AgentResource,
         decision: &Decision,
     ) -> Option<neomind_storage::ActionExecuted> {
@@ -54,13 +55,23 @@ impl AgentExecutor {
             "Executing command from LLM decision"
         );
 
-        // Execute the command via DeviceService
+        // Execute the command via DeviceService. The key is scoped to this
+        // execution, so a command that several decisions ask for reaches
+        // the device only once.
+        let idempotency_key = format!(
+            "agent:{}:{}:{}",
+            agent.id, execution_id, resource.resource_id
+        );
+        let owner = format!("agent:{}", agent.id);
         let execution_result = device_service
-            .send_command(device_id, command_name, params_map)
-            .await;
+            .send_command_idempotent(device_id, command_name, params_map, &owner, &idempotency_key)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|submission| submission.outcome.map(|_| submission.replayed));
 
         let (success, result) = match execution_result {
-            Ok(_) => (true, Some("Command sent successfully".to_string())),
+            Ok(false) => (true, Some("Command sent successfully".to_string())),
+            Ok(true) => (true, Some("Command already sent in this execution".to_string())),
             Err(e) => {
                 tracing::warn!(
                     agent_id = %agent.id,
@@ -316,6 +327,7 @@ impl AgentExecutor {
     async fn handle_command_decision(
         &self,
         agent: &AiAgent,
+        execution_id: &str,
         decision: &Decision,
         actions: &mut Vec<neomind_storage::ActionExecuted>,
     ) {
@@ -350,8 +362,9 @@ impl AgentExecutor {
                     if let Some(resource) = agent.resources.iter().find(|r| {
                         r.resource_type == ResourceType::Command && r.resource_id == resource_id
                     }) {
-                        if let Some(action_executed) =
-                            self.execute_single_command(agent, resource, decision).await
+                        if let Some(action_executed) = self
+                            .execute_single_command(agent, execution_id, resource, decision)
+                            .await
                         {
                             actions.push(action_executed);
                         }
@@ -384,8 +397,9 @@ impl AgentExecutor {
                     match resource.resource_type {
                         ResourceType::Command => {
                             if parts.len() == 2 {
-                                if let Some(action_executed) =
-                                    self.execute_single_command(agent, resource, decision).await
+                                if let Some(action_executed) = self
+                                    .execute_single_command(agent, execution_id, resource, decision)
+                                    .await
                                 {
                                     actions.push(action_executed);
                                 }
@@ -438,6 +452,7 @@ impl AgentExecutor {
     async fn handle_condition_met_decision(
         &self,
         agent: &AiAgent,
+        execution_id: &str,
         decision: &Decision,
         actions: &mut Vec<neomind_storage::ActionExecuted>,
         notifications: &mut Vec<neomind_storage::NotificationSent>,
@@ -445,8 +460,9 @@ impl AgentExecutor {
         // Execute all device commands
         for resource in &agent.resources {
             if resource.resource_type == ResourceType::Command {
-                if let Some(action_executed) =
-                    self.execute_single_command(agent, resource, decision).await
+                if let Some(action_executed) = self
+                    .execute_single_command(agent, execution_id, resource, decision)
+                    .await
                 {
                     actions.push(action_executed);
                 }
@@ -485,6 +501,7 @@ impl AgentExecutor {
     async fn handle_execute_action_decision(
         &self,
         agent: &AiAgent,
+        execution_id: &str,
         decision: &Decision,
         actions: &mut Vec<neomind_storage::ActionExecuted>,
     ) {
@@ -523,8 +540,9 @@ impl AgentExecutor {
         }
 
         for resource in commands_to_execute {
-            if let Some(action_executed) =
-                self.execute_single_command(agent, resource, decision).await
+            if let Some(action_executed) = self
+                .execute_single_command(agent, execution_id, resource, decision)
+                .await
             {
                 actions.push(action_executed);
             }
@@ -534,6 +552,7 @@ impl AgentExecutor {
     pub(crate) async fn execute_decisions(
         &self,
         agent: &AiAgent,
+        execution_id: &str,
         decisions: &[Decision],
     ) -> AgentResult<(
         Vec<neomind_storage::ActionExecuted>,
@@ -616,7 +635,7 @@ impl AgentExecutor {
 
             // Handle LLM-driven command decisions (decision_type == "command")
             if decision.decision_type == "command" {
-                self.handle_command_decision(agent, decision, &mut actions_executed)
+                self.handle_command_decision(agent, execution_id, decision, &mut actions_executed)
                     .await;
                 continue;
             }
@@ -631,6 +650,7 @@ impl AgentExecutor {
             if decision.decision_type == "condition_met" {
                 self.handle_condition_met_decision(
                     agent,
+                    execution_id,
                     decision,
                     &mut actions_executed,
                     &mut notifications_sent,
@@ -641,8 +661,13 @@ impl AgentExecutor {
 
             // Handle execute_action decisions
             if Self::is_execute_action(&decision.action) {
-                self.handle_execute_action_decision(agent, decision, &mut actions_executed)
-                    .await;
+                self.handle_execute_action_decision(
+                    agent,
+                    execution_id,
+                    decision,
+                    &mut actions_executed,
+                )
+                .await;
             }
         }
 
@@ -4,6 +4,8 @@
 //! background executions for matching event-type agents.
 
 use super::*;
+use crate::ai_agent::concurrency::is_rejection;
+use crate::ai_agent::trigger_filter::TriggerFilter;
 
 impl AgentExecutor {
     /// Prune stale entries from the event dedup map.
@@ -83,159 +85,8 @@ impl AgentExecutor {
         metric: &str,
         value: &MetricValue,
     ) -> AgentResult<()> {
-        // Refresh event-triggered agents cache
-        self.refresh_event_agents().await;
-
-        let event_agents = self.event_agents.read().await;
-
-        tracing::debug!(
-            device_id = %device_id,
-            metric = %metric,
-            event_agent_count = event_agents.len(),
-            "[EVENT] Checking device event against {} event-triggered agents",
-            event_agents.len()
-        );
-
-        // Clone device_id for use in spawned tasks
-        let device_id_for_spawn = device_id.clone();
-
-        // Clean up old entries from recent_executions (older than cooldown window)
-        self.cleanup_stale_dedup_entries().await;
-        let now = chrono::Utc::now().timestamp();
-
-        for (_agent_id, agent) in event_agents.iter() {
-            // Check if this agent has event-based schedule
-            if matches!(
-                agent.schedule.schedule_type,
-                neomind_storage::ScheduleType::Event
-            ) {
-                // Check if agent's event filter matches this event
-                if self
-                    .matches_data_source_filter(agent, "device", &device_id, metric)
-                    .await
-                {
-                    // Cooldown: one execution per (agent, source) per 60s window
-                    const COOLDOWN_SECS: i64 = 60;
-                    let dedup_key = format!("{}:device:{}", agent.id, device_id);
-                    let recent = self.recent_executions.read().await;
-                    let is_duplicate = recent
-                        .get(&dedup_key)
-                        .map(|&timestamp| now - timestamp < COOLDOWN_SECS)
-                        .unwrap_or(false);
-                    drop(recent);
-
-                    if is_duplicate {
-                        tracing::info!(
-                            agent_name = %agent.name,
-                            device_id = %device_id,
-                            metric = %metric,
-                            "Skipping event-triggered execution (cooldown: {}s)",
-                            COOLDOWN_SECS
-                        );
-                        continue;
-                    }
-
-                    // Clone dedup_key before it's moved into the recent_executions map;
-                    // the spawned task needs a copy to clear the cooldown on failure.
-                    let dedup_key_clone = dedup_key.clone();
-
-                    // Mark this execution as recent
-                    {
-                        let mut recent = self.recent_executions.write().await;
-                        recent.insert(dedup_key, now);
-                    }
-
-                    tracing::debug!(
-                        agent_name = %agent.name,
-                        device_id = %device_id,
-                        metric = %metric,
-                        "Event-triggered agent execution"
-                    );
-
-                    // Clone the agent and event data for execution
-                    let agent_clone = agent.clone();
-                    let metric_clone = metric.to_string();
-                    let value_clone = value.clone();
-                    let device_id_for_task = device_id_for_spawn.clone();
-                    let timestamp = chrono::Utc::now().timestamp();
-
-                    // Build executor config for spawned task
-                    let executor_config = self.build_spawn_config(agent);
-                    let agent_id_for_log = agent.id.clone();
-                    let recent_executions_clone = self.recent_executions.clone();
-
-                    tokio::spawn(async move {
-                        // Acquire per-backend semaphore (WAIT, not fail)
-                        Self::acquire_backend_permit(
-                            &executor_config.backend_semaphores,
-                            &agent_id_for_log,
-                            &agent_clone
-                                .llm_backend_id
-                                .clone()
-                                .unwrap_or_else(|| "default".to_string()),
-                        )
-                        .await;
-
-                        // Create event trigger data
-                        let event_trigger_data = EventTriggerData {
-                            source: DataSourceRef {
-                                source_type: "device".to_string(),
-                                source_id: device_id_for_task,
-                                field: metric_clone,
-                            },
-                            value: value_clone,
-                            timestamp,
-                        };
-
-                        match AgentExecutor::new(executor_config).await {
-                            Ok(executor) => {
-                                tracing::debug!(
-                                    agent_id = %agent_id_for_log,
-                                    trigger_device = %event_trigger_data.source.source_id,
-                                    trigger_metric = %event_trigger_data.source.field,
-                                    "Executing event-triggered agent with event data"
-                                );
-
-                                // Execute the agent with event data (includes the triggering metric value directly).
-                                // On failure, perform one inline retry with a short backoff; if the retry
-                                // also fails, clear the cooldown marker so transient errors (API hiccups,
-                                // network blips) don't lock out subsequent events for 60 seconds.
-                                let result = Self::execute_with_retry(
-                                    &executor,
-                                    agent_clone,
-                                    event_trigger_data,
-                                    1,
-                                    &agent_id_for_log,
-                                )
-                                .await;
-
-                                if result.is_err() {
-                                    let mut recent = recent_executions_clone.write().await;
-                                    recent.remove(&dedup_key_clone);
-                                    tracing::info!(
-                                        agent_id = %agent_id_for_log,
-                                        dedup_key = %dedup_key_clone,
-                                        "Cleared event cooldown after failed execution"
-                                    );
-                                }
-                            }
-                            Err(e) => {
-                                tracing::error!(
-                                    agent_id = %agent_id_for_log,
-                                    error = %e,
-                                    "Failed to create executor for event-triggered agent"
-                                );
-                                // Executor creation failed — clear cooldown so the next event can retry.
-                                let mut recent = recent_executions_clone.write().await;
-                                recent.remove(&dedup_key_clone);
-                            }
-                        }
-                    });
-                }
-            }
-        }
-
-        Ok(())
+        self.check_and_trigger_data_event("device", device_id, metric.to_string(), value)
+            .await
     }
 
     /// Unified entry point for triggering agents on any data source update.
@@ -261,8 +112,6 @@ impl AgentExecutor {
             event_agents.len()
         );
 
-        let source_id_for_spawn = source_id.clone();
-
         // Clean up old entries from recent_executions (older than cooldown window)
         self.cleanup_stale_dedup_entries().await;
         let now = chrono::Utc::now().timestamp();
@@ -284,128 +133,206 @@ impl AgentExecutor {
                 continue;
             }
 
-            // Cooldown: one execution per (agent, source) per 60s window
-            const COOLDOWN_SECS: i64 = 60;
-            let dedup_key = format!("{}:{}:{}", agent.id, source_type, source_id);
-            let recent = self.recent_executions.read().await;
-            let is_duplicate = recent
-                .get(&dedup_key)
-                .map(|&timestamp| now - timestamp < COOLDOWN_SECS)
-                .unwrap_or(false);
-            drop(recent);
+            let filter = match agent
+                .schedule
+                .event_filter
+                .as_deref()
+                .map(TriggerFilter::parse)
+                .transpose()
+            {
+                Ok(filter) => filter.unwrap_or_default(),
+                Err(e) => {
+                    tracing::warn!(
+                        agent_name = %agent.name,
+                        error = %e,
+                        "Skipping data event: agent has an invalid payload filter"
+                    );
+                    continue;
+                }
+            };
 
-            if is_duplicate {
-                tracing::info!(
+            // Events are deduplicated and debounced per (agent, source)
+            let dedup_key = format!("{}:{}:{}", agent.id, source_type, source_id);
+            if !self.passes_trigger_filter(&filter, &dedup_key, &field, value) {
+                tracing::debug!(
                     agent_name = %agent.name,
-                    source_type = %source_type,
                     source_id = %source_id,
                     field = %field,
-                    "Skipping data event-triggered execution (cooldown: {}s)",
-                    COOLDOWN_SECS
+                    "[DATA_EVENT] Payload filter did not match"
                 );
                 continue;
             }
 
-            // Clone dedup_key before it's moved into the recent_executions map;
-            // the spawned task needs a copy to clear the cooldown on failure.
-            let dedup_key_clone = dedup_key.clone();
-
-            // Mark this execution as recent
-            {
-                let mut recent = self.recent_executions.write().await;
-                recent.insert(dedup_key, now);
-            }
-
-            tracing::debug!(
-                agent_name = %agent.name,
-                source_type = %source_type,
-                source_id = %source_id,
-                field = %field,
-                "Data event-triggered agent execution"
-            );
-
-            // Clone the agent and event data for execution
-            let agent_clone = agent.clone();
-            let field_clone = field.clone();
-            let value_clone = value.clone();
-            let source_id_for_task = source_id_for_spawn.clone();
-            let source_type_for_task = source_type.to_string();
-            let timestamp = chrono::Utc::now().timestamp();
-
-            // Build executor config for spawned task
+            let event_trigger_data = EventTriggerData {
+                source: DataSourceRef {
+                    source_type: source_type.to_string(),
+                    source_id: source_id.clone(),
+                    field: field.clone(),
+                },
+                value: value.clone(),
+                timestamp: now,
+            };
             let executor_config = self.build_spawn_config(agent);
-            let agent_id_for_log = agent.id.clone();
-            let recent_executions_clone = self.recent_executions.clone();
+            let recent_executions = self.recent_executions.clone();
+
+            let Some(debounce_secs) = filter.debounce_secs else {
+                Self::start_event_execution(
+                    executor_config,
+                    recent_executions,
+                    agent.clone(),
+                    dedup_key,
+                    event_trigger_data,
+                )
+                .await;
+                continue;
+            };
 
+            // Trailing debounce: each update restarts the window, and only the
+            // last one of a burst goes on to execute
+            let ticket = self.trigger_state.lock().debounce(&dedup_key);
+            let trigger_state = self.trigger_state.clone();
+            let agent_clone = agent.clone();
             tokio::spawn(async move {
-                // Acquire per-backend semaphore (WAIT, not fail)
-                Self::acquire_backend_permit(
-                    &executor_config.backend_semaphores,
-                    &agent_id_for_log,
-                    &agent_clone
-                        .llm_backend_id
-                        .clone()
-                        .unwrap_or_else(|| "default".to_string()),
+                tokio::time::sleep(std::time::Duration::from_secs(debounce_secs)).await;
+                if !trigger_state.lock().settle(&dedup_key, ticket) {
+                    return;
+                }
+                Self::start_event_execution(
+                    executor_config,
+                    recent_executions,
+                    agent_clone,
+                    dedup_key,
+                    event_trigger_data,
                 )
                 .await;
+            });
+        }
 
-                // Create event trigger data with unified DataSourceRef
-                let event_trigger_data = EventTriggerData {
-                    source: DataSourceRef {
-                        source_type: source_type_for_task,
-                        source_id: source_id_for_task,
-                        field: field_clone,
-                    },
-                    value: value_clone,
-                    timestamp,
-                };
-
-                match AgentExecutor::new(executor_config).await {
-                    Ok(executor) => {
-                        tracing::debug!(
-                            agent_id = %agent_id_for_log,
-                            trigger_source_type = %event_trigger_data.source.source_type,
-                            trigger_source_id = %event_trigger_data.source.source_id,
-                            trigger_field = %event_trigger_data.source.field,
-                            "Executing data event-triggered agent with event data"
-                        );
+        Ok(())
+    }
 
-                        // Execute with one inline retry; clear cooldown on persistent failure
-                        // so transient errors (API hiccups, network blips) don't lock out
-                        // subsequent events for the full 60s cooldown window.
-                        let result = Self::execute_with_retry(
-                            &executor,
-                            agent_clone,
-                            event_trigger_data,
-                            1,
-                            &agent_id_for_log,
-                        )
-                        .await;
-
-                        if result.is_err() {
-                            let mut recent = recent_executions_clone.write().await;
-                            recent.remove(&dedup_key_clone);
-                            tracing::info!(
-                                agent_id = %agent_id_for_log,
-                                dedup_key = %dedup_key_clone,
-                                "Cleared event cooldown after failed execution"
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!(
+    /// Apply an agent's payload conditions to an event value. Updates the
+    /// last seen value when `changes_only` is set.
+    fn passes_trigger_filter(
+        &self,
+        filter: &TriggerFilter,
+        dedup_key: &str,
+        field: &str,
+        value: &MetricValue,
+    ) -> bool {
+        if filter.path.is_none() && filter.matches.is_none() && !filter.changes_only {
+            return true;
+        }
+        let payload = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+        let Some(selected) = filter.select(&payload) else {
+            return false;
+        };
+        if filter.changes_only {
+            let change_key = format!("{}:{}", dedup_key, field);
+            if !self.trigger_state.lock().observe_change(&change_key, selected) {
+                return false;
+            }
+        }
+        filter.is_match(selected)
+    }
+
+    /// Start an event-triggered execution in the background, unless the
+    /// agent already ran for this source within the cooldown window.
+    async fn start_event_execution(
+        executor_config: AgentExecutorConfig,
+        recent_executions: Arc<RwLock<HashMap<String, i64>>>,
+        agent: AiAgent,
+        dedup_key: String,
+        event_trigger_data: EventTriggerData,
+    ) {
+        // Cooldown: one execution per (agent, source) per 60s window
+        const COOLDOWN_SECS: i64 = 60;
+        let now = chrono::Utc::now().timestamp();
+        {
+            let mut recent = recent_executions.write().await;
+            let is_duplicate = recent
+                .get(&dedup_key)
+                .is_some_and(|&timestamp| now - timestamp < COOLDOWN_SECS);
+            if is_duplicate {
+                tracing::info!(
+                    agent_name = %agent.name,
+                    source_type = %event_trigger_data.source.source_type,
+                    source_id = %event_trigger_data.source.source_id,
+                    field = %event_trigger_data.source.field,
+                    "Skipping data event-triggered execution (cooldown: {}s)",
+                    COOLDOWN_SECS
+                );
+                return;
+            }
+            // Mark this execution as recent
+            recent.insert(dedup_key.clone(), now);
+        }
+
+        tracing::debug!(
+            agent_name = %agent.name,
+            source_type = %event_trigger_data.source.source_type,
+            source_id = %event_trigger_data.source.source_id,
+            field = %event_trigger_data.source.field,
+            "Data event-triggered agent execution"
+        );
+
+        let agent_id_for_log = agent.id.clone();
+        tokio::spawn(async move {
+            // Acquire per-backend semaphore (WAIT, not fail)
+            Self::acquire_backend_permit(
+                &executor_config.backend_semaphores,
+                &agent_id_for_log,
+                &agent
+                    .llm_backend_id
+                    .clone()
+                    .unwrap_or_else(|| "default".to_string()),
+            )
+            .await;
+
+            match AgentExecutor::new(executor_config).await {
+                Ok(executor) => {
+                    tracing::debug!(
+                        agent_id = %agent_id_for_log,
+                        trigger_source_type = %event_trigger_data.source.source_type,
+                        trigger_source_id = %event_trigger_data.source.source_id,
+                        trigger_field = %event_trigger_data.source.field,
+                        "Executing data event-triggered agent with event data"
+                    );
+
+                    // Execute with one inline retry; clear cooldown on persistent failure
+                    // so transient errors (API hiccups, network blips) don't lock out
+                    // subsequent events for the full 60s cooldown window.
+                    let result = Self::execute_with_retry(
+                        &executor,
+                        agent,
+                        event_trigger_data,
+                        1,
+                        &agent_id_for_log,
+                    )
+                    .await;
+
+                    if result.is_err() {
+                        let mut recent = recent_executions.write().await;
+                        recent.remove(&dedup_key);
+                        tracing::info!(
                             agent_id = %agent_id_for_log,
-                            error = %e,
-                            "Failed to create executor for data event-triggered agent"
+                            dedup_key = %dedup_key,
+                            "Cleared event cooldown after failed execution"
                         );
-                        let mut recent = recent_executions_clone.write().await;
-                        recent.remove(&dedup_key_clone);
                     }
                 }
-            });
-        }
-
-        Ok(())
+                Err(e) => {
+                    tracing::error!(
+                        agent_id = %agent_id_for_log,
+                        error = %e,
+                        "Failed to create executor for data event-triggered agent"
+                    );
+                    // Executor creation failed — clear cooldown so the next event can retry.
+                    let mut recent = recent_executions.write().await;
+                    recent.remove(&dedup_key);
+                }
+            }
+        });
     }
 
     /// Build an `AgentExecutorConfig` by cloning all necessary fields from `self`.
@@ -425,6 +352,7 @@ impl AgentExecutor {
             tool_registry: self.tool_registry.read().clone(),
             memory_store: self.memory_store.clone(),
             backend_semaphores: self.backend_semaphores.clone(),
+            execution_limiter: Some(self.execution_limiter.clone()),
             skill_registry: self._config.skill_registry.clone(),
         }
     }
@@ -612,6 +540,8 @@ impl AgentExecutor {
                     );
                     return Ok(());
                 }
+                // Turned away by the concurrency policy; already logged
+                Err(e) if is_rejection(&e) => return Err(e),
                 Err(e) => {
                     if attempt <= retries {
                         tracing::warn!(
@@ -835,6 +765,7 @@ impl AgentExecutor {
     /// from the cache, preventing it from being triggered by events before the
     /// next scheduled refresh.
     pub async fn remove_event_agent(&self, agent_id: &str) {
+        self.trigger_state.lock().forget_agent(agent_id);
         let mut cache = self.event_agents.write().await;
         if cache.remove(agent_id).is_some() {
             tracing::debug!(
@@ -29,6 +29,7 @@ use tokio::sync::{RwLock, Semaphore};
 use neomind_core::datasource::DataSourceId;
 
 use crate::agent::types::LlmBackend;
+use crate::ai_agent::trigger_filter::TriggerState;
 use crate::error::{NeoMindError, Result as AgentResult};
 
 /// Internal representation of image content for multimodal LLM messages.
@@ -229,6 +230,8 @@ pub struct AgentExecutorConfig {
     pub memory_store: Option<Arc<MarkdownMemoryStore>>,
     /// Per-LLM-backend semaphores concurrency limiting (shared with scheduler)
     pub backend_semaphores: Option<crate::ai_agent::scheduler::BackendSemaphores>,
+    /// Per-agent and global execution limits (a private limiter if None)
+    pub execution_limiter: Option<crate::ai_agent::concurrency::ExecutionLimiter>,
     /// Skill registry for querying operation guides
     pub skill_registry: Option<crate::skills::SharedSkillRegistry>,
 }
@@ -284,6 +287,8 @@ pub struct AgentExecutor {
     /// Track recent executions to prevent duplicates (agent_id, device_id -> timestamp)
     /// Deduplicates by device only, not by individual metrics
     pub(crate) recent_executions: Arc<RwLock<HashMap<String, i64>>>,
+    /// Previous payload values and pending debounce windows of event triggers
+    pub(crate) trigger_state: Arc<parking_lot::Mutex<TriggerState>>,
     /// LLM runtime cache: backend_id -> runtime
     /// Key format: "{backend_type}:{endpoint}:{model}" for cache invalidation
     pub(crate) llm_runtime_cache:
@@ -297,6 +302,8 @@ pub struct AgentExecutor {
     pub(crate) memory_store: Option<Arc<MarkdownMemoryStore>>,
     /// Per-LLM-backend semaphores for concurrency limiting (shared with scheduler)
     pub(crate) backend_semaphores: Option<crate::ai_agent::scheduler::BackendSemaphores>,
+    /// Per-agent and global execution limits, enforced by `execute_agent`
+    pub(crate) execution_limiter: crate::ai_agent::concurrency::ExecutionLimiter,
     /// Semaphore limiting concurrent tool executions (default: 6)
     pub(crate) tool_concurrency: Arc<Semaphore>,
 }
@@ -330,11 +337,17 @@ impl AgentExecutor {
             llm_backend_store,
             event_agents: Arc::new(RwLock::new(HashMap::new())),
             recent_executions: Arc::new(RwLock::new(HashMap::new())),
+            trigger_state: Arc::default(),
             llm_runtime_cache: Arc::new(RwLock::new(HashMap::new())),
             extension_registry,
             tool_registry: parking_lot::RwLock::new(config.tool_registry.clone()),
             memory_store: config.memory_store.clone(),
             backend_semaphores: config.backend_semaphores.clone(),
+            execution_limiter: config.execution_limiter.clone().unwrap_or_else(|| {
+                crate::ai_agent::concurrency::ExecutionLimiter::new(
+                    crate::ai_agent::SchedulerConfig::default().max_concurrent,
+                )
+            }),
             tool_concurrency: Arc::new(Semaphore::new(6)),
         })
     }
@@ -390,6 +403,7 @@ impl AgentExecutor {
         Vec<neomind_core::llm::backend::ToolDefinition>,
         std::collections::HashMap<String, String>,
     ) {
+        use crate::toolkit::extension_tools::parse_extension_tool_name;
         use neomind_core::llm::backend::sanitize_tool_name;
 
         let defs = registry.definitions_for_llm();
@@ -420,7 +434,14 @@ impl AgentExecutor {
                     config.allowed_tools.iter().map(|s| s.as_str()).collect();
 
                 defs.iter()
-                    .filter(|d| allowed.contains(d.name.as_str()))
+                    .filter(|d| {
+                        // Agents saved before extension tools were namespaced
+                        // list them as `{extension_id}:{command}`
+                        allowed.contains(d.name.as_str())
+                            || parse_extension_tool_name(&d.name).is_some_and(|(ext, cmd)| {
+                                allowed.contains(format!("{}:{}", ext, cmd).as_str())
+                            })
+                    })
                     .map(|d| to_tool_def(d, &mut name_map))
                     .collect()
             }
@@ -604,6 +625,11 @@ impl AgentExecutor {
         self.store.clone()
     }
 
+    /// Get the execution limiter (for queue depth and running counts).
+    pub fn execution_limiter(&self) -> &crate::ai_agent::concurrency::ExecutionLimiter {
+        &self.execution_limiter
+    }
+
     /// Update the tool registry (e.g. after extensions are loaded).
     pub fn set_tool_registry(&self, registry: Arc<crate::toolkit::ToolRegistry>) {
         *self.tool_registry.write() = Some(registry);
@@ -722,12 +748,29 @@ impl AgentExecutor {
     }
 
     /// Execute an agent and record the full decision process.
+    ///
+    /// The execution first takes a slot under the agent's concurrency policy;
+    /// if it is dropped or replaced instead, the error satisfies
+    /// [`is_rejection`](crate::ai_agent::concurrency::is_rejection).
     pub async fn execute_agent(
         &self,
         agent: AiAgent,
         event_data: Option<EventTriggerData>,
         invocation_input: Option<super::AgentInput>,
     ) -> AgentResult<AgentExecutionRecord> {
+        let _permit = self
+            .execution_limiter
+            .acquire(&agent.id, &agent.concurrency)
+            .await
+            .map_err(|rejection| {
+                tracing::info!(
+                    agent_id = %agent.id,
+                    reason = %rejection,
+                    "Agent execution not started"
+                );
+                rejection.into_error(&agent.id)
+            })?;
+
         let agent_id = agent.id.clone();
         let agent_name = agent.name.clone();
         let execution_id = uuid::Uuid::new_v4().to_string();
@@ -1410,7 +1453,7 @@ impl AgentExecutor {
 
                 // Step 3: Execute decisions
                 let (actions_executed, notifications_sent) =
-                    self.execute_decisions(&agent, &decisions).await?;
+                    self.execute_decisions(&agent, &execution_id, &decisions).await?;
 
                 // Send thinking events for each action executed
                 for action in &actions_executed {
@@ -10,16 +10,20 @@
 //! - Full decision process recording for verification
 //! - Error recovery for long-running stability
 
+pub mod concurrency;
 pub mod executor;
 pub mod scheduler;
+pub mod trigger_filter;
 
 use neomind_storage::{AgentExecutionRecord, AgentSchedule, AgentStatus, AiAgent, ExecutionStatus};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+pub use concurrency::{AgentConcurrencyStats, ConcurrencyStats, ExecutionLimiter};
 pub use executor::{AgentExecutor, AgentExecutorConfig};
 pub use scheduler::{AgentScheduler, BackendSemaphores, SchedulerConfig};
+pub use trigger_filter::{JsonPath, MatchOp, PayloadMatch, TriggerFilter};
 
 /// AI Agent manager - the main entry point for user-defined agents.
 ///
@@ -124,11 +128,14 @@ impl AiAgentManager {
     pub async fn new(config: AgentExecutorConfig) -> Result<Arc<Self>, crate::error::NeoMindError> {
         // Create scheduler first so we can share its backend semaphores with the executor
         let scheduler_config = SchedulerConfig::default().with_env_concurrency();
+        let global_limit = scheduler_config.max_concurrent;
         let scheduler = Arc::new(AgentScheduler::new(scheduler_config).await?);
 
         // Share backend semaphores between scheduler and executor
         let mut executor_config = config;
         executor_config.backend_semaphores = Some(scheduler.backend_semaphores().clone());
+        // The global execution cap follows the scheduler's limit
+        executor_config.execution_limiter = Some(ExecutionLimiter::new(global_limit));
 
         let executor = Arc::new(AgentExecutor::new(executor_config).await?);
 
@@ -171,6 +178,7 @@ impl AiAgentManager {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
             conversation_history: Default::default(),
             user_messages: Default::default(),
             conversation_summary: Default::default(),
@@ -268,13 +276,19 @@ impl AiAgentManager {
             },
         };
 
-        // Update agent status based on result
-        let new_status = if result.is_ok() {
+        // Update agent status based on result. An execution turned away by
+        // the concurrency limit leaves the agent healthy.
+        let rejected = result.as_ref().is_err_and(concurrency::is_rejection);
+        let new_status = if result.is_ok() || rejected {
             AgentStatus::Active
         } else {
             AgentStatus::Error
         };
-        let error_msg = result.as_ref().err().map(|e| e.to_string());
+        let error_msg = result
+            .as_ref()
+            .err()
+            .filter(|_| !rejected)
+            .map(|e| e.to_string());
         self.executor
             .store()
             .update_agent_status(agent_id, new_status, error_msg)
@@ -1,6 +1,7 @@
 //! Agent scheduler for periodic and event-triggered execution.
 //! Uses standard cron library for accurate cron expression parsing.
 
+use crate::ai_agent::concurrency::is_rejection;
 use crate::ai_agent::executor::AgentExecutor;
 use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
@@ -176,6 +177,7 @@ impl SchedulerConfig {
     /// devices when many agents fire at once.
     /// - `NEOMIND_MAX_CONCURRENT` (global execution limit, default 10)
     /// - `NEOMIND_MAX_CONCURRENT_PER_BACKEND` (per-LLM-backend, default 2)
+    ///
     /// Unset or invalid values keep the defaults.
     pub fn with_env_concurrency(mut self) -> Self {
         if let Some(n) = parse_concurrency_env("NEOMIND_MAX_CONCURRENT") {
@@ -576,6 +578,15 @@ impl AgentScheduler {
                                             }
                                         }
                                     }
+                                    // Turned away by the agent's concurrency
+                                    // policy: not a failure, nothing to retry
+                                    Err(e) if is_rejection(&e) => {
+                                        tracing::info!(
+                                            agent_id = %agent_id,
+                                            error = %e,
+                                            "Scheduled agent execution skipped"
+                                        );
+                                    }
                                     Err(e) => {
                                         let new_consecutive = consecutive + 1;
 
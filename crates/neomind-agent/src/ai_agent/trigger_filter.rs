@@ -0,0 +1,363 @@
+//! Payload filters for event-triggered agents.
+//!
+//! Besides the `sources` an event agent listens to, its `event_filter` can
+//! narrow down which updates actually start an execution:
+//!
+//! ```json
+//! {
+//!   "sources": [{ "type": "device", "id": "sensor-01", "field": "values" }],
+//!   "path": "$.values.temperature",
+//!   "match": { "op": "gt", "value": 30 },
+//!   "changes_only": true,
+//!   "debounce_secs": 10
+//! }
+//! ```
+//!
+//! - `path` selects a value from the event payload; updates where it doesn't
+//!   resolve are ignored. Without a path the whole value is used.
+//! - `match` compares the selected value against a constant.
+//! - `changes_only` ignores updates whose selected value equals the previous
+//!   one for the same source and field. The first update only records a
+//!   baseline.
+//! - `debounce_secs` waits until a source has been quiet for the window and
+//!   then runs once with the latest update.
+//!
+//! The usual per-source cooldown still applies after all of these.
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Comparison applied by a [`PayloadMatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchOp {
+    #[serde(alias = "==")]
+    Eq,
+    #[serde(alias = "!=")]
+    Ne,
+    #[serde(alias = ">")]
+    Gt,
+    #[serde(alias = ">=")]
+    Gte,
+    #[serde(alias = "<")]
+    Lt,
+    #[serde(alias = "<=")]
+    Lte,
+    /// Substring of a string, or element of an array
+    Contains,
+    /// The path resolves to a non-null value
+    Exists,
+}
+
+/// Condition on the selected payload value.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PayloadMatch {
+    pub op: MatchOp,
+    #[serde(default)]
+    pub value: Value,
+}
+
+impl PayloadMatch {
+    pub fn is_match(&self, actual: &Value) -> bool {
+        let ordering = || {
+            let (a, b) = (as_number(actual)?, as_number(&self.value)?);
+            a.partial_cmp(&b)
+        };
+        match self.op {
+            MatchOp::Eq => values_equal(actual, &self.value),
+            MatchOp::Ne => !values_equal(actual, &self.value),
+            MatchOp::Gt => ordering().is_some_and(|o| o.is_gt()),
+            MatchOp::Gte => ordering().is_some_and(|o| o.is_ge()),
+            MatchOp::Lt => ordering().is_some_and(|o| o.is_lt()),
+            MatchOp::Lte => ordering().is_some_and(|o| o.is_le()),
+            MatchOp::Contains => match (actual, &self.value) {
+                (Value::String(s), Value::String(needle)) => s.contains(needle.as_str()),
+                (Value::Array(items), needle) => items.iter().any(|v| values_equal(v, needle)),
+                _ => false,
+            },
+            MatchOp::Exists => !actual.is_null(),
+        }
+    }
+}
+
+/// Numbers and numeric strings as `f64`, so `"21.5"` from a text payload
+/// compares like `21.5`.
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        // 1 and 1.0 are the same reading
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        _ => a == b,
+    }
+}
+
+/// One step of a [`JsonPath`].
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    /// Array index; negative counts from the end
+    Index(i64),
+}
+
+/// A JSONPath selecting a single value: `$`, `.key`, `['key']` and `[n]`
+/// steps, e.g. `$.values.temperature` or `$.sensors[-1]['value']`. The
+/// leading `$` is optional.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let invalid = |why: &str| Err(format!("Invalid path '{}': {}", path, why));
+        let trimmed = path.trim();
+        let mut rest = trimmed.strip_prefix('$').unwrap_or(trimmed);
+        let mut segments = Vec::new();
+        let mut first = trimmed.len() == rest.len();
+
+        while !rest.is_empty() {
+            if let Some(bracketed) = rest.strip_prefix('[') {
+                let Some(end) = bracketed.find(']') else {
+                    return invalid("unclosed '['");
+                };
+                let inner = bracketed[..end].trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                segments.push(match quoted {
+                    Some(key) => PathSegment::Key(key.to_string()),
+                    None => match inner.parse() {
+                        Ok(index) => PathSegment::Index(index),
+                        Err(_) => return invalid("expected an index or a quoted key"),
+                    },
+                });
+                rest = &bracketed[end + 1..];
+            } else {
+                let body = match rest.strip_prefix('.') {
+                    Some(body) => body,
+                    None if first => rest,
+                    None => return invalid("expected '.' or '['"),
+                };
+                let end = body.find(['.', '[']).unwrap_or(body.len());
+                if end == 0 {
+                    return invalid("empty key");
+                }
+                segments.push(PathSegment::Key(body[..end].to_string()));
+                rest = &body[end..];
+            }
+            first = false;
+        }
+        Ok(Self { segments })
+    }
+
+    /// The value at this path, if there is one.
+    pub fn select<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                PathSegment::Key(key) => current.get(key),
+                PathSegment::Index(index) => {
+                    let items = current.as_array()?;
+                    let index = if *index < 0 {
+                        items.len().checked_sub(index.unsigned_abs() as usize)?
+                    } else {
+                        *index as usize
+                    };
+                    items.get(index)
+                }
+            })
+    }
+}
+
+#[derive(Deserialize)]
+struct RawTriggerFilter {
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default, rename = "match")]
+    matches: Option<PayloadMatch>,
+    #[serde(default)]
+    changes_only: bool,
+    #[serde(default)]
+    debounce_secs: Option<u64>,
+}
+
+/// Longest accepted debounce window.
+pub const MAX_DEBOUNCE_SECS: u64 = 3600;
+
+/// Payload conditions from an agent's `event_filter`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TriggerFilter {
+    pub path: Option<JsonPath>,
+    pub matches: Option<PayloadMatch>,
+    pub changes_only: bool,
+    pub debounce_secs: Option<u64>,
+}
+
+impl TriggerFilter {
+    /// Read the payload conditions from an `event_filter` JSON string. Other
+    /// keys (`sources`, legacy `event_type`, ...) are left alone, and a
+    /// string that isn't a JSON object has no conditions.
+    pub fn parse(event_filter: &str) -> Result<Self, String> {
+        let json = match serde_json::from_str::<Value>(event_filter) {
+            Ok(json) if json.is_object() => json,
+            _ => return Ok(Self::default()),
+        };
+        let raw: RawTriggerFilter = serde_json::from_value(json)
+            .map_err(|e| format!("Invalid event_filter: {}", e))?;
+        if raw.debounce_secs.is_some_and(|secs| secs > MAX_DEBOUNCE_SECS) {
+            return Err(format!(
+                "event_filter.debounce_secs must be at most {}",
+                MAX_DEBOUNCE_SECS
+            ));
+        }
+        Ok(Self {
+            path: raw.path.as_deref().map(JsonPath::parse).transpose()?,
+            matches: raw.matches,
+            changes_only: raw.changes_only,
+            debounce_secs: raw.debounce_secs.filter(|secs| *secs > 0),
+        })
+    }
+
+    /// The value the conditions apply to, or `None` if the path doesn't
+    /// resolve.
+    pub fn select<'a>(&self, payload: &'a Value) -> Option<&'a Value> {
+        match self.path {
+            Some(ref path) => path.select(payload),
+            None => Some(payload),
+        }
+    }
+
+    /// Whether `selected` satisfies the `match` condition, if any.
+    pub fn is_match(&self, selected: &Value) -> bool {
+        self.matches.as_ref().is_none_or(|m| m.is_match(selected))
+    }
+}
+
+/// Previous values and pending debounce windows of event triggers.
+#[derive(Default)]
+pub(crate) struct TriggerState {
+    last_values: HashMap<String, Value>,
+    pending: HashMap<String, u64>,
+    next_ticket: u64,
+}
+
+impl TriggerState {
+    /// Record the value seen for `key`; true if it differs from the
+    /// previous one. The first value is a baseline, not a change.
+    pub(crate) fn observe_change(&mut self, key: &str, value: &Value) -> bool {
+        match self.last_values.insert(key.to_string(), value.clone()) {
+            Some(previous) => !values_equal(&previous, value),
+            None => false,
+        }
+    }
+
+    /// Open a debounce window for `key`, superseding any pending one.
+    pub(crate) fn debounce(&mut self, key: &str) -> u64 {
+        self.next_ticket += 1;
+        self.pending.insert(key.to_string(), self.next_ticket);
+        self.next_ticket
+    }
+
+    /// Close the window for `key` if `ticket` is still the latest one;
+    /// false if a newer update took over.
+    pub(crate) fn settle(&mut self, key: &str, ticket: u64) -> bool {
+        if self.pending.get(key) == Some(&ticket) {
+            self.pending.remove(key);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Drop everything kept for an agent.
+    pub(crate) fn forget_agent(&mut self, agent_id: &str) {
+        let prefix = format!("{}:", agent_id);
+        self.last_values.retain(|key, _| !key.starts_with(&prefix));
+        self.pending.retain(|key, _| !key.starts_with(&prefix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_path() {
+        let payload = json!({
+            "values": { "temperature": 31.5, "door state": "open" },
+            "sensors": [{ "value": 1 }, { "value": 2 }]
+        });
+        let select = |path: &str| JsonPath::parse(path).unwrap().select(&payload).cloned();
+
+        assert_eq!(select("$.values.temperature"), Some(json!(31.5)));
+        assert_eq!(select("values['door state']"), Some(json!("open")));
+        assert_eq!(select("$.sensors[1].value"), Some(json!(2)));
+        assert_eq!(select("$.sensors[-2][\"value\"]"), Some(json!(1)));
+        assert_eq!(select("$"), Some(payload.clone()));
+        assert_eq!(select("$.sensors[5]"), None);
+        assert_eq!(select("$.missing.key"), None);
+
+        assert!(JsonPath::parse("$.values[").is_err());
+        assert!(JsonPath::parse("$..values").is_err());
+        assert!(JsonPath::parse("$[abc]").is_err());
+    }
+
+    #[test]
+    fn test_trigger_filter() {
+        let filter = TriggerFilter::parse(
+            r#"{"sources": [{"type": "device", "id": "all"}],
+                "path": "$.temperature", "match": {"op": ">", "value": 30},
+                "changes_only": true, "debounce_secs": 5}"#,
+        )
+        .unwrap();
+        assert!(filter.changes_only);
+        assert_eq!(filter.debounce_secs, Some(5));
+
+        let hot = json!({ "temperature": "31" });
+        let selected = filter.select(&hot).unwrap();
+        assert!(filter.is_match(selected));
+        assert!(!filter.is_match(&json!(29.9)));
+        assert!(filter.select(&json!({ "humidity": 40 })).is_none());
+
+        // Only payload keys are read; plain source filters parse as empty
+        let plain = TriggerFilter::parse(r#"{"event_type": "device.metric"}"#).unwrap();
+        assert_eq!(plain, TriggerFilter::default());
+        assert_eq!(TriggerFilter::parse("not json"), Ok(TriggerFilter::default()));
+        assert!(TriggerFilter::parse(r#"{"match": {"op": "between"}}"#).is_err());
+        assert!(TriggerFilter::parse(r#"{"debounce_secs": 86400}"#).is_err());
+
+        let contains = PayloadMatch {
+            op: MatchOp::Contains,
+            value: json!("alarm"),
+        };
+        assert!(contains.is_match(&json!(["ok", "alarm"])));
+        assert!(contains.is_match(&json!("smoke alarm")));
+    }
+
+    #[test]
+    fn test_trigger_state() {
+        let mut state = TriggerState::default();
+        assert!(!state.observe_change("a:device:s1:t", &json!(20)));
+        assert!(!state.observe_change("a:device:s1:t", &json!(20.0)));
+        assert!(state.observe_change("a:device:s1:t", &json!(21)));
+
+        let first = state.debounce("a:device:s1");
+        let second = state.debounce("a:device:s1");
+        assert!(!state.settle("a:device:s1", first));
+        assert!(state.settle("a:device:s1", second));
+        assert!(!state.settle("a:device:s1", second));
+
+        state.forget_agent("a");
+        assert!(!state.observe_change("a:device:s1:t", &json!(22)));
+    }
+}
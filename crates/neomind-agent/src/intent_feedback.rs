@@ -0,0 +1,343 @@
+//! Intent feedback loop.
+//!
+//! Each streamed chat turn records the classifier's prediction for the user
+//! message as an [`IntentExample`] in the session store. A prediction gets
+//! labeled when the user marks the answer as wrong (optionally naming the
+//! right intent), or when the next message in the session reads as a
+//! correction ("no, I meant ...") and classifies differently; the earlier
+//! example is then labeled with the intent of the correcting message.
+//!
+//! Labeled examples are exported as JSONL, either as chat fine-tuning
+//! records or as plain text/label pairs for a classifier. Unlabeled
+//! predictions are kept for [`UNLABELED_RETENTION_SECS`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use neomind_storage::{IntentExample, IntentFeedback, IntentFeedbackSource, SessionStore};
+use parking_lot::RwLock;
+use serde::Deserialize;
+
+use crate::agent::staged::IntentCategory;
+use crate::error::{NeoMindError, Result};
+
+/// How long predictions without feedback are kept.
+pub const UNLABELED_RETENTION_SECS: i64 = 30 * 86_400;
+
+/// Openings that reject the previous answer outright.
+const CONTRADICTION_PREFIXES: &[&str] = &[
+    "no,", "no ", "no.", "nope", "wrong", "not that", "不是", "不对", "错了",
+];
+
+/// Phrases that correct the previous answer anywhere in the message.
+const CONTRADICTION_PHRASES: &[&str] = &[
+    "i meant",
+    "i mean ",
+    "that's not what",
+    "that is not what",
+    "not what i",
+    "我是说",
+    "我的意思是",
+    "搞错了",
+    "理解错",
+];
+
+/// Whether a follow-up message contradicts the previous answer.
+pub fn is_contradiction(message: &str) -> bool {
+    let message = message.trim().to_lowercase();
+    CONTRADICTION_PREFIXES.iter().any(|p| message.starts_with(p))
+        || CONTRADICTION_PHRASES.iter().any(|p| message.contains(p))
+}
+
+/// JSONL layout of an export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// `{"messages": [system, user, assistant]}` chat fine-tuning records
+    #[default]
+    Chat,
+    /// `{"text", "label", "predicted", "source"}` records
+    Classification,
+}
+
+/// Instruction placed in the system message of chat export records.
+fn classification_prompt() -> String {
+    let names: Vec<&str> = IntentCategory::all_variants()
+        .iter()
+        .map(|c| c.as_str())
+        .collect();
+    format!(
+        "Classify the intent of the user's message as one of: {}. Answer with the category only.",
+        names.join(", ")
+    )
+}
+
+/// Render labeled examples as JSONL; unlabeled ones are skipped.
+pub fn export_jsonl(examples: &[IntentExample], format: ExportFormat) -> String {
+    let prompt = classification_prompt();
+    let mut out = String::new();
+    for example in examples {
+        let Some(label) = example.label() else {
+            continue;
+        };
+        let record = match format {
+            ExportFormat::Chat => serde_json::json!({
+                "messages": [
+                    { "role": "system", "content": prompt },
+                    { "role": "user", "content": example.message },
+                    { "role": "assistant", "content": label },
+                ]
+            }),
+            ExportFormat::Classification => serde_json::json!({
+                "text": example.message,
+                "label": label,
+                "predicted": example.predicted,
+                "source": example.feedback.as_ref().map(|f| f.source),
+            }),
+        };
+        out.push_str(&record.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+/// Records intent predictions and the feedback on them.
+pub struct IntentFeedbackLog {
+    store: Arc<SessionStore>,
+    /// Latest example per session, checked when the next message arrives
+    latest: RwLock<HashMap<String, String>>,
+}
+
+impl IntentFeedbackLog {
+    pub fn new(store: Arc<SessionStore>) -> Self {
+        Self {
+            store,
+            latest: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record the prediction for a user message.
+    ///
+    /// If the message contradicts the session's previous answer and
+    /// classifies differently, the previous example is labeled with this
+    /// message's intent first.
+    pub fn record(
+        &self,
+        session_id: &str,
+        message: &str,
+        category: &IntentCategory,
+        confidence: f32,
+        keywords: Vec<String>,
+    ) -> Result<IntentExample> {
+        let now = chrono::Utc::now().timestamp();
+        let previous = self.latest.read().get(session_id).cloned();
+        if let Some(previous_id) = previous.filter(|_| is_contradiction(message)) {
+            let previous = self.store.get_intent_example(&previous_id).ok().flatten();
+            if let Some(mut previous) = previous {
+                let differs = *category != IntentCategory::General
+                    && previous.predicted != category.as_str();
+                if previous.feedback.is_none() && differs {
+                    previous.feedback = Some(IntentFeedback {
+                        source: IntentFeedbackSource::FollowUp,
+                        correct: Some(category.as_str().to_string()),
+                        at: now,
+                    });
+                    self.store
+                        .save_intent_example(&previous)
+                        .map_err(storage_error)?;
+                }
+            }
+        }
+
+        let example = IntentExample {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            message: message.to_string(),
+            predicted: category.as_str().to_string(),
+            confidence,
+            keywords,
+            created_at: now,
+            feedback: None,
+        };
+        self.store
+            .save_intent_example(&example)
+            .map_err(storage_error)?;
+        self.latest
+            .write()
+            .insert(session_id.to_string(), example.id.clone());
+        Ok(example)
+    }
+
+    /// Attach user feedback to an example of the session, by default the
+    /// latest one. `correct` names the right intent, if the user gave one.
+    pub fn submit(
+        &self,
+        session_id: &str,
+        example_id: Option<&str>,
+        source: IntentFeedbackSource,
+        correct: Option<&IntentCategory>,
+    ) -> Result<IntentExample> {
+        let example_id = match example_id {
+            Some(id) => id.to_string(),
+            None => self
+                .latest_example_id(session_id)?
+                .ok_or_else(|| NeoMindError::NotFound("Intent prediction".to_string()))?,
+        };
+        let mut example = self
+            .store
+            .get_intent_example(&example_id)
+            .map_err(storage_error)?
+            .filter(|e| e.session_id == session_id)
+            .ok_or_else(|| NeoMindError::NotFound(format!("Intent example {}", example_id)))?;
+
+        example.feedback = Some(IntentFeedback {
+            source,
+            correct: correct.map(|c| c.as_str().to_string()),
+            at: chrono::Utc::now().timestamp(),
+        });
+        self.store
+            .save_intent_example(&example)
+            .map_err(storage_error)?;
+        Ok(example)
+    }
+
+    fn latest_example_id(&self, session_id: &str) -> Result<Option<String>> {
+        if let Some(id) = self.latest.read().get(session_id) {
+            return Ok(Some(id.clone()));
+        }
+        // Not recorded since the last restart
+        Ok(self
+            .store
+            .list_intent_examples(Some(session_id))
+            .map_err(storage_error)?
+            .pop()
+            .map(|e| e.id))
+    }
+
+    /// Examples, oldest first, optionally for one session and only labeled.
+    pub fn examples(
+        &self,
+        session_id: Option<&str>,
+        labeled_only: bool,
+    ) -> Result<Vec<IntentExample>> {
+        let mut examples = self
+            .store
+            .list_intent_examples(session_id)
+            .map_err(storage_error)?;
+        if labeled_only {
+            examples.retain(|e| e.label().is_some());
+        }
+        Ok(examples)
+    }
+
+    /// Delete a session's examples. Returns the number removed.
+    pub fn forget_session(&self, session_id: &str) -> Result<usize> {
+        self.latest.write().remove(session_id);
+        self.store
+            .delete_intent_examples(session_id)
+            .map_err(storage_error)
+    }
+
+    /// Drop unlabeled predictions older than the retention window.
+    pub fn prune(&self) -> Result<usize> {
+        let cutoff = chrono::Utc::now().timestamp() - UNLABELED_RETENTION_SECS;
+        self.store
+            .prune_intent_examples(cutoff)
+            .map_err(storage_error)
+    }
+}
+
+fn storage_error(e: neomind_storage::Error) -> NeoMindError {
+    NeoMindError::Storage(format!("Intent feedback storage error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> IntentFeedbackLog {
+        let path = std::env::temp_dir()
+            .join(format!("intent_feedback_{}.redb", uuid::Uuid::new_v4()));
+        IntentFeedbackLog::new(SessionStore::open(path).unwrap())
+    }
+
+    #[test]
+    fn test_contradiction_detection() {
+        assert!(is_contradiction("No, I meant the power usage"));
+        assert!(is_contradiction("不对，我是说能耗数据"));
+        assert!(is_contradiction("that's not what I asked"));
+        assert!(!is_contradiction("Now turn on the fan"));
+        assert!(!is_contradiction("show the notes"));
+    }
+
+    #[test]
+    fn test_follow_up_labels_previous_prediction() {
+        let log = log();
+        let first = log
+            .record("s1", "floor 3 kwh", &IntentCategory::Device, 0.4, vec![])
+            .unwrap();
+        // Not a contradiction: nothing is labeled
+        log.record("s1", "thanks", &IntentCategory::General, 0.5, vec![])
+            .unwrap();
+        assert!(log.examples(None, true).unwrap().is_empty());
+
+        let second = log
+            .record("s1", "rule list", &IntentCategory::Rule, 0.5, vec![])
+            .unwrap();
+        log.record(
+            "s1",
+            "no, I meant energy data",
+            &IntentCategory::Data,
+            0.5,
+            vec![],
+        )
+        .unwrap();
+        let labeled = log.examples(Some("s1"), true).unwrap();
+        assert_eq!(labeled.len(), 1);
+        assert_eq!(labeled[0].id, second.id);
+        assert_eq!(labeled[0].label(), Some("data"));
+        assert_ne!(labeled[0].id, first.id);
+    }
+
+    #[test]
+    fn test_submit_and_export() {
+        let log = log();
+        let example = log
+            .record("s1", "floor 3 kwh", &IntentCategory::Device, 0.4, vec![])
+            .unwrap();
+        log.submit("s1", None, IntentFeedbackSource::ThumbsDown, None)
+            .unwrap();
+        // A thumbs-down without the right intent is not training data yet
+        let all = log.examples(None, false).unwrap();
+        assert_eq!(export_jsonl(&all, ExportFormat::Chat), "");
+        let foreign = log.submit(
+            "other",
+            Some(&example.id),
+            IntentFeedbackSource::Correction,
+            None,
+        );
+        assert!(foreign.is_err());
+
+        log.submit(
+            "s1",
+            Some(&example.id),
+            IntentFeedbackSource::Correction,
+            Some(&IntentCategory::Data),
+        )
+        .unwrap();
+        let examples = log.examples(None, true).unwrap();
+
+        let chat = export_jsonl(&examples, ExportFormat::Chat);
+        let record: serde_json::Value = serde_json::from_str(chat.trim_end()).unwrap();
+        assert_eq!(record["messages"][1]["content"], "floor 3 kwh");
+        assert_eq!(record["messages"][2]["content"], "data");
+
+        let plain = export_jsonl(&examples, ExportFormat::Classification);
+        let record: serde_json::Value = serde_json::from_str(plain.trim_end()).unwrap();
+        assert_eq!(record["label"], "data");
+        assert_eq!(record["predicted"], "device");
+        assert_eq!(record["source"], "correction");
+
+        assert_eq!(log.forget_session("s1").unwrap(), 1);
+    }
+}
@@ -39,6 +39,7 @@ pub mod ai_agent;
 pub mod context;
 pub mod error;
 pub mod image_utils;
+pub mod intent_feedback;
 pub mod llm;
 pub mod llm_backends; // Merged from neomind-llm crate
 pub mod memory;
@@ -46,16 +47,20 @@ pub mod prompts;
 pub mod session;
 pub mod skills;
 pub mod smart_conversation;
+pub mod structured_output;
 pub mod testing_helpers;
 pub mod toolkit;
 pub mod tools;
+pub mod usage;
 
 // Re-export commonly used types
 pub use agent::{Agent, AgentConfig, AgentEvent, AgentMessage, LlmBackend};
 // Re-export commonly used types
 pub use ai_agent::AgentInput;
 pub use error::{NeoMindError, Result};
-pub use session::{CreateSessionOptions, SessionManager};
+pub use session::{
+    ContextHealth, CreateSessionOptions, PurgeReport, SessionExport, SessionManager,
+};
 
 // Re-export llm_backends types for backward compatibility (merged from neomind-llm crate)
 pub use llm_backends::get_instance_manager;
@@ -30,6 +30,10 @@ use crate::error::Result as AgentResult;
 pub use crate::llm_backends::{
     get_instance_manager, BackendTypeDefinition, LlmBackendInstanceManager,
 };
+use crate::llm_backends::{LlmResponseCache, ResponseCacheConfig};
+use crate::structured_output::{self, DEFAULT_STRUCTURED_RETRIES};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 
 /// Default concurrent LLM request limit.
 /// Note: This constant is kept for backward compatibility but the actual default
@@ -277,6 +281,8 @@ pub struct LlmInterface {
     /// Pinned skill IDs selected by the user for this session.
     /// These skills are injected as full guides (not just hints) into the system prompt.
     pinned_skills: Arc<RwLock<Vec<String>>>,
+    /// Cache for non-streaming completions (enabled via `AGENT_LLM_CACHE_TTL_SECS`).
+    response_cache: Option<Arc<LlmResponseCache>>,
 }
 
 impl LlmInterface {
@@ -304,6 +310,8 @@ impl LlmInterface {
             skill_registry: Arc::new(RwLock::new(None)),
             skill_context: Arc::new(RwLock::new(TransientSkillContext::default())),
             pinned_skills: Arc::new(RwLock::new(Vec::new())),
+            response_cache: ResponseCacheConfig::from_env()
+                .map(|config| Arc::new(LlmResponseCache::from_config(config))),
         }
     }
 
@@ -334,6 +342,8 @@ impl LlmInterface {
             skill_registry: Arc::new(RwLock::new(None)),
             skill_context: Arc::new(RwLock::new(TransientSkillContext::default())),
             pinned_skills: Arc::new(RwLock::new(Vec::new())),
+            response_cache: ResponseCacheConfig::from_env()
+                .map(|config| Arc::new(LlmResponseCache::from_config(config))),
         }
     }
 
@@ -678,6 +688,17 @@ impl LlmInterface {
         }
     }
 
+    /// Use `cache` for non-streaming completions (builder pattern).
+    pub fn with_response_cache(mut self, cache: Arc<LlmResponseCache>) -> Self {
+        self.response_cache = Some(cache);
+        self
+    }
+
+    /// The completion cache, if enabled.
+    pub fn response_cache(&self) -> Option<&Arc<LlmResponseCache>> {
+        self.response_cache.as_ref()
+    }
+
     /// Set the system prompt (builder pattern).
     pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
         // Store the prompt in Arc<RwLock<String>>
@@ -982,6 +1003,88 @@ impl LlmInterface {
             .await
     }
 
+    /// Ask the model for a value of type `T`, returned as typed data.
+    ///
+    /// The JSON schema of `T` is injected as the system prompt (the regular
+    /// system prompt, tools and skills are not used). Malformed output is
+    /// repaired where possible; otherwise the parse error is sent back to the
+    /// model, up to [`DEFAULT_STRUCTURED_RETRIES`] more times.
+    pub async fn generate_structured<T>(&self, prompt: impl Into<String>) -> AgentResult<T>
+    where
+        T: JsonSchema + DeserializeOwned,
+    {
+        self.generate_structured_with_retries(prompt, DEFAULT_STRUCTURED_RETRIES)
+            .await
+    }
+
+    /// [`generate_structured`](Self::generate_structured) with an explicit retry bound.
+    pub async fn generate_structured_with_retries<T>(
+        &self,
+        prompt: impl Into<String>,
+        max_retries: usize,
+    ) -> AgentResult<T>
+    where
+        T: JsonSchema + DeserializeOwned,
+    {
+        let _permit = self.limiter.acquire().await;
+        let llm = self.get_runtime().await?;
+        let model = match self.model.read().await.clone() {
+            Some(m) => m,
+            None => llm.model_name().to_string(),
+        };
+        let (eff_temp, eff_top_p, eff_top_k, _) = self.get_effective_params().await;
+
+        let schema = structured_output::schema_json::<T>();
+        let mut messages = vec![
+            Message::system(structured_output::schema_system_prompt(&schema)),
+            Message::user(prompt.into()),
+        ];
+        let mut last_error = String::new();
+
+        for attempt in 0..=max_retries {
+            let input = LlmInput {
+                messages: messages.clone(),
+                params: neomind_core::llm::backend::GenerationParams {
+                    // Low temperature keeps the output close to the schema.
+                    temperature: Some(eff_temp.min(0.2)),
+                    top_p: Some(eff_top_p),
+                    top_k: Some(eff_top_k as u32),
+                    max_tokens: None,
+                    stop: None,
+                    frequency_penalty: None,
+                    presence_penalty: None,
+                    thinking_enabled: Some(false),
+                    max_context: None,
+                },
+                model: Some(model.clone()),
+                stream: false,
+                tools: None,
+            };
+            let output = llm
+                .generate(input)
+                .await
+                .map_err(|e| NeoMindError::Llm(e.to_string()))?;
+
+            match structured_output::parse_structured::<T>(&output.text) {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    tracing::debug!(attempt, error = %e, "Structured output did not parse");
+                    messages.push(Message::assistant(&output.text));
+                    messages.push(Message::user(structured_output::repair_prompt(&e)));
+                    last_error = e;
+                }
+            }
+        }
+
+        Err(NeoMindError::Parse {
+            location: "structured LLM output".to_string(),
+            message: format!(
+                "no valid JSON after {} attempts: {last_error}",
+                max_retries + 1
+            ),
+        })
+    }
+
     /// Internal chat implementation.
     async fn chat_internal(
         &self,
@@ -1195,9 +1298,23 @@ impl LlmInterface {
             tools: tools_input,
         };
 
+        if let Some(cache) = &self.response_cache {
+            if let Some(cached) = cache.get(&input).await {
+                tracing::debug!(model = ?input.model, "LLM response cache hit");
+                return Ok(ChatResponse {
+                    text: cached.text,
+                    tokens_used: 0,
+                    duration: start.elapsed(),
+                    finish_reason: cached.finish_reason,
+                    thinking: cached.thinking,
+                });
+            }
+        }
+
         // Get runtime using instance manager if enabled
         let llm = self.get_runtime().await?;
 
+        let cache_input = self.response_cache.as_ref().map(|_| input.clone());
         let output = llm
             .generate(input)
             .await
@@ -1209,11 +1326,34 @@ impl LlmInterface {
             .map(|u| u.completion_tokens as usize)
             .unwrap_or_else(|| output.text.split_whitespace().count());
 
+        let finish_reason = format!("{:?}", output.finish_reason);
+
+        // Only complete answers are reused; truncated or failed ones are retried.
+        if let (Some(cache), Some(cache_input)) = (&self.response_cache, cache_input) {
+            if matches!(
+                output.finish_reason,
+                neomind_core::llm::backend::FinishReason::Stop
+            ) && output.tool_calls.is_none()
+            {
+                cache
+                    .put(
+                        &cache_input,
+                        crate::llm_backends::response_cache::CachedResponse {
+                            text: output.text.clone(),
+                            thinking: output.thinking.clone(),
+                            finish_reason: finish_reason.clone(),
+                            completion_tokens: tokens_used,
+                        },
+                    )
+                    .await;
+            }
+        }
+
         Ok(ChatResponse {
             text: output.text,
             tokens_used,
             duration,
-            finish_reason: format!("{:?}", output.finish_reason),
+            finish_reason,
             thinking: output.thinking,
         })
     }
@@ -1687,6 +1827,7 @@ impl LlmInterface {
                         let (clean, is_thinking, tokens) = extract_token_marker(&content, is_thinking);
                         if let Some(t) = tokens {
                             *token_tracker.lock().await = Some(t);
+                            record_prompt_tokens(t);
                         }
                         if !clean.is_empty() {
                             yield Ok((clean, is_thinking));
@@ -2021,6 +2162,7 @@ impl LlmInterface {
                         let (clean, is_thinking, tokens) = extract_token_marker(&content, is_thinking);
                         if let Some(t) = tokens {
                             *token_tracker.lock().await = Some(t);
+                            record_prompt_tokens(t);
                         }
                         if !clean.is_empty() {
                             yield Ok((clean, is_thinking));
@@ -2123,6 +2265,17 @@ pub(crate) fn extract_skill_id_from_result(result_str: &str) -> String {
     format!("hash:{:x}", h.finish())
 }
 
+/// Count prompt tokens reported by a streaming backend.
+fn record_prompt_tokens(tokens: u32) {
+    neomind_core::metrics::global()
+        .counter(
+            "neomind_llm_prompt_tokens_total",
+            "Prompt tokens reported by LLM backends",
+            &[],
+        )
+        .inc_by(tokens as u64);
+}
+
 /// Extract in-band token usage marker from a stream chunk.
 /// Returns (clean_content, is_thinking, extracted_prompt_tokens).
 /// The marker format is `\n__NEOMIND_TOKEN_PROMPT:NN__`.
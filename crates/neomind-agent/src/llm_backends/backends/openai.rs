@@ -298,7 +298,15 @@ struct CloudCapabilities {
 
 impl CloudRuntime {
     /// Create a new cloud runtime.
-    pub fn new(config: CloudConfig) -> Result<Self, LlmError> {
+    ///
+    /// `api_key` may be a `secret://` handle, resolved from the secrets store.
+    pub fn new(mut config: CloudConfig) -> Result<Self, LlmError> {
+        if neomind_core::secrets::parse_handle(&config.api_key).is_some() {
+            config.api_key = neomind_core::secrets::resolve(&config.api_key)
+                .map_err(|e| LlmError::InvalidInput(e.to_string()))?
+                .into_owned();
+        }
+
         // Note: Don't set a global timeout — it kills long-running streaming responses
         // from thinking models that can take many minutes.
         // Instead, we use per-request timeouts only for non-streaming requests.
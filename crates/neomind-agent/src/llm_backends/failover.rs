@@ -0,0 +1,467 @@
+//! Automatic failover across an ordered chain of LLM backends.
+//!
+//! [`FailoverRuntime`] is an [`LlmRuntime`] that sends each request to the
+//! first healthy backend in its chain. Transient failures (timeouts, network
+//! errors, 429 and 5xx responses, unavailable backends) put the backend into
+//! a cooldown and the request moves on to the next one. Errors caused by the
+//! request itself (invalid input, context overflow, other 4xx) are returned
+//! as-is, since another backend would reject them too.
+//!
+//! Once a cooldown expires the backend is probed with `is_available()` and,
+//! if healthy, is tried first again, so traffic returns to the primary as
+//! soon as it recovers. Every switch publishes
+//! `NeoMindEvent::LlmBackendFailover` and increments
+//! `neomind_llm_failovers_total`.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::Stream;
+use neomind_core::event::NeoMindEvent;
+use neomind_core::llm::backend::{
+    BackendId, LlmError, LlmInput, LlmOutput, LlmRuntime, StreamChunk,
+};
+use neomind_core::EventBus;
+use parking_lot::Mutex;
+
+use super::instance_manager::LlmBackendInstanceManager;
+
+/// Default per-attempt timeout (non-streaming: whole response; streaming:
+/// until the stream is established).
+pub const DEFAULT_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Default time a failed backend is skipped before it is probed again.
+pub const DEFAULT_RECOVERY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Failover behaviour.
+#[derive(Debug, Clone)]
+pub struct FailoverConfig {
+    pub attempt_timeout: Duration,
+    pub recovery_interval: Duration,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            attempt_timeout: DEFAULT_ATTEMPT_TIMEOUT,
+            recovery_interval: DEFAULT_RECOVERY_INTERVAL,
+        }
+    }
+}
+
+struct ChainEntry {
+    id: String,
+    runtime: Arc<dyn LlmRuntime>,
+}
+
+#[derive(Default)]
+struct FailoverState {
+    /// Index of the backend that served the last successful request.
+    current: usize,
+    /// Backends in cooldown, by chain index.
+    cooling_until: HashMap<usize, Instant>,
+}
+
+/// LLM runtime that fails over along an ordered chain of backends.
+pub struct FailoverRuntime {
+    chain: Vec<ChainEntry>,
+    config: FailoverConfig,
+    state: Mutex<FailoverState>,
+    event_bus: Option<EventBus>,
+}
+
+impl FailoverRuntime {
+    /// Create a failover runtime over `(backend id, runtime)` pairs; the first
+    /// entry is the primary.
+    pub fn new(chain: Vec<(String, Arc<dyn LlmRuntime>)>) -> Result<Self, LlmError> {
+        if chain.is_empty() {
+            return Err(LlmError::InvalidInput(
+                "Failover chain needs at least one backend".to_string(),
+            ));
+        }
+        Ok(Self {
+            chain: chain
+                .into_iter()
+                .map(|(id, runtime)| ChainEntry { id, runtime })
+                .collect(),
+            config: FailoverConfig::default(),
+            state: Mutex::new(FailoverState::default()),
+            event_bus: None,
+        })
+    }
+
+    /// Build a chain from configured backend instances, in the given order.
+    pub async fn from_manager(
+        manager: &LlmBackendInstanceManager,
+        backend_ids: &[String],
+    ) -> Result<Self, LlmError> {
+        let mut chain = Vec::with_capacity(backend_ids.len());
+        for id in backend_ids {
+            chain.push((id.clone(), manager.get_runtime(id).await?));
+        }
+        Self::new(chain)
+    }
+
+    pub fn with_config(mut self, config: FailoverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Publish switchover events on `bus`.
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.event_bus = Some(bus);
+        self
+    }
+
+    /// Id of the backend that served the last successful request.
+    pub fn current_backend(&self) -> &str {
+        &self.chain[self.state.lock().current].id
+    }
+
+    /// Chain indices in the order they should be tried: backends out of
+    /// cooldown first (in chain order), then cooling ones as a last resort.
+    async fn attempt_order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let expired: Vec<usize> = {
+            let state = self.state.lock();
+            state
+                .cooling_until
+                .iter()
+                .filter(|(_, until)| **until <= now)
+                .map(|(idx, _)| *idx)
+                .collect()
+        };
+
+        // Health probe before letting a recovered backend take traffic again.
+        for idx in expired {
+            let healthy = self.chain[idx].runtime.is_available().await;
+            let mut state = self.state.lock();
+            if healthy {
+                state.cooling_until.remove(&idx);
+            } else {
+                state
+                    .cooling_until
+                    .insert(idx, now + self.config.recovery_interval);
+            }
+        }
+
+        let state = self.state.lock();
+        let (ready, cooling): (Vec<usize>, Vec<usize>) =
+            (0..self.chain.len()).partition(|idx| !state.cooling_until.contains_key(idx));
+        ready.into_iter().chain(cooling).collect()
+    }
+
+    fn mark_failed(&self, idx: usize, error: &LlmError) {
+        tracing::warn!(
+            backend = %self.chain[idx].id,
+            error = %error,
+            cooldown_secs = self.config.recovery_interval.as_secs(),
+            "LLM backend failed, trying next in failover chain"
+        );
+        self.state
+            .lock()
+            .cooling_until
+            .insert(idx, Instant::now() + self.config.recovery_interval);
+    }
+
+    async fn mark_served(&self, idx: usize, last_error: Option<&LlmError>) {
+        let previous = {
+            let mut state = self.state.lock();
+            state.cooling_until.remove(&idx);
+            std::mem::replace(&mut state.current, idx)
+        };
+        if previous == idx {
+            return;
+        }
+
+        let reason = match last_error {
+            Some(e) => e.to_string(),
+            None if idx == 0 => "primary recovered".to_string(),
+            None => "backend in cooldown".to_string(),
+        };
+        let (from, to) = (&self.chain[previous].id, &self.chain[idx].id);
+        tracing::info!(from = %from, to = %to, reason = %reason, "LLM backend switchover");
+        neomind_core::metrics::global()
+            .counter(
+                "neomind_llm_failovers_total",
+                "LLM backend switchovers in failover chains",
+                &[],
+            )
+            .inc();
+        if let Some(bus) = &self.event_bus {
+            bus.publish(NeoMindEvent::LlmBackendFailover {
+                from_backend: from.clone(),
+                to_backend: to.clone(),
+                reason,
+                timestamp: chrono::Utc::now().timestamp(),
+            })
+            .await;
+        }
+    }
+}
+
+/// Whether another backend could succeed where this one failed.
+fn is_failover_error(error: &LlmError) -> bool {
+    match error {
+        LlmError::BackendUnavailable(_)
+        | LlmError::Network(_)
+        | LlmError::Timeout(_)
+        | LlmError::ModelNotFound(_)
+        | LlmError::Io(_) => true,
+        LlmError::Api { status, .. } => *status == 429 || *status >= 500,
+        LlmError::InvalidInput(_)
+        | LlmError::ContextOverflow { .. }
+        | LlmError::Serialization(_) => false,
+        LlmError::Generation(_) | LlmError::Unknown(_) => true,
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmRuntime for FailoverRuntime {
+    fn backend_id(&self) -> BackendId {
+        self.chain[self.state.lock().current].runtime.backend_id()
+    }
+
+    fn model_name(&self) -> &str {
+        // The borrow must outlive the lock, so report the primary's model.
+        self.chain[0].runtime.model_name()
+    }
+
+    async fn is_available(&self) -> bool {
+        for entry in &self.chain {
+            if entry.runtime.is_available().await {
+                return true;
+            }
+        }
+        false
+    }
+
+    async fn generate(&self, input: LlmInput) -> Result<LlmOutput, LlmError> {
+        let mut last_error = None;
+        for idx in self.attempt_order().await {
+            let attempt = tokio::time::timeout(
+                self.config.attempt_timeout,
+                self.chain[idx].runtime.generate(input.clone()),
+            )
+            .await
+            .unwrap_or(Err(LlmError::Timeout(
+                self.config.attempt_timeout.as_secs(),
+            )));
+
+            match attempt {
+                Ok(output) => {
+                    self.mark_served(idx, last_error.as_ref()).await;
+                    return Ok(output);
+                }
+                Err(e) if is_failover_error(&e) => {
+                    self.mark_failed(idx, &e);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            LlmError::BackendUnavailable("all backends in failover chain".to_string())
+        }))
+    }
+
+    async fn generate_stream(
+        &self,
+        input: LlmInput,
+    ) -> Result<Pin<Box<dyn Stream<Item = StreamChunk> + Send>>, LlmError> {
+        // Failover only covers establishing the stream; once tokens are
+        // flowing, switching backends would splice two different answers.
+        let mut last_error = None;
+        for idx in self.attempt_order().await {
+            let attempt = tokio::time::timeout(
+                self.config.attempt_timeout,
+                self.chain[idx].runtime.generate_stream(input.clone()),
+            )
+            .await
+            .unwrap_or(Err(LlmError::Timeout(
+                self.config.attempt_timeout.as_secs(),
+            )));
+
+            match attempt {
+                Ok(stream) => {
+                    self.mark_served(idx, last_error.as_ref()).await;
+                    return Ok(stream);
+                }
+                Err(e) if is_failover_error(&e) => {
+                    self.mark_failed(idx, &e);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            LlmError::BackendUnavailable("all backends in failover chain".to_string())
+        }))
+    }
+
+    /// The smallest window in the chain, so prompts fit whichever backend serves them.
+    fn max_context_length(&self) -> usize {
+        self.chain
+            .iter()
+            .map(|e| e.runtime.max_context_length())
+            .min()
+            .unwrap_or_default()
+    }
+
+    fn supports_multimodal(&self) -> bool {
+        self.chain.iter().all(|e| e.runtime.supports_multimodal())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_core::llm::backend::FinishReason;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    struct FakeBackend {
+        name: &'static str,
+        failing: AtomicBool,
+        calls: AtomicUsize,
+        error_status: u16,
+    }
+
+    impl FakeBackend {
+        fn new(name: &'static str, error_status: u16) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                failing: AtomicBool::new(false),
+                calls: AtomicUsize::new(0),
+                error_status,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LlmRuntime for FakeBackend {
+        fn backend_id(&self) -> BackendId {
+            BackendId::new(BackendId::MOCK)
+        }
+
+        fn model_name(&self) -> &str {
+            self.name
+        }
+
+        async fn is_available(&self) -> bool {
+            !self.failing.load(Ordering::SeqCst)
+        }
+
+        async fn generate(&self, _input: LlmInput) -> Result<LlmOutput, LlmError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(LlmError::Api {
+                    status: self.error_status,
+                    body: String::new(),
+                });
+            }
+            Ok(LlmOutput {
+                text: self.name.to_string(),
+                finish_reason: FinishReason::Stop,
+                usage: None,
+                thinking: None,
+                tool_calls: None,
+            })
+        }
+
+        async fn generate_stream(
+            &self,
+            _input: LlmInput,
+        ) -> Result<Pin<Box<dyn Stream<Item = StreamChunk> + Send>>, LlmError> {
+            Err(LlmError::Generation("not used".to_string()))
+        }
+
+        fn max_context_length(&self) -> usize {
+            4096
+        }
+    }
+
+    fn input() -> LlmInput {
+        LlmInput {
+            messages: vec![neomind_core::Message::user("hi")],
+            params: Default::default(),
+            model: None,
+            stream: false,
+            tools: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_and_recovers_to_primary() {
+        let primary = FakeBackend::new("primary", 503);
+        let backup = FakeBackend::new("backup", 503);
+        let bus = EventBus::new();
+        let mut rx = bus.subscribe();
+        let runtime = FailoverRuntime::new(vec![
+            (
+                "primary".to_string(),
+                primary.clone() as Arc<dyn LlmRuntime>,
+            ),
+            ("backup".to_string(), backup.clone() as Arc<dyn LlmRuntime>),
+        ])
+        .unwrap()
+        .with_config(FailoverConfig {
+            attempt_timeout: Duration::from_secs(5),
+            recovery_interval: Duration::from_millis(50),
+        })
+        .with_event_bus(bus);
+
+        primary.failing.store(true, Ordering::SeqCst);
+        assert_eq!(runtime.generate(input()).await.unwrap().text, "backup");
+        assert_eq!(runtime.current_backend(), "backup");
+        let (event, _) = rx.recv().await.unwrap();
+        assert!(matches!(
+            event,
+            NeoMindEvent::LlmBackendFailover { ref to_backend, .. } if to_backend == "backup"
+        ));
+
+        // Primary is in cooldown and not retried.
+        runtime.generate(input()).await.unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+
+        primary.failing.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        assert_eq!(runtime.generate(input()).await.unwrap().text, "primary");
+        assert_eq!(runtime.current_backend(), "primary");
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_do_not_fail_over() {
+        let primary = FakeBackend::new("primary", 400);
+        let backup = FakeBackend::new("backup", 503);
+        let runtime = FailoverRuntime::new(vec![
+            (
+                "primary".to_string(),
+                primary.clone() as Arc<dyn LlmRuntime>,
+            ),
+            ("backup".to_string(), backup.clone() as Arc<dyn LlmRuntime>),
+        ])
+        .unwrap();
+
+        primary.failing.store(true, Ordering::SeqCst);
+        assert!(matches!(
+            runtime.generate(input()).await,
+            Err(LlmError::Api { status: 400, .. })
+        ));
+        assert_eq!(backup.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert!(is_failover_error(&LlmError::Timeout(30)));
+        assert!(is_failover_error(&LlmError::Api {
+            status: 429,
+            body: String::new()
+        }));
+        assert!(!is_failover_error(&LlmError::ContextOverflow {
+            prompt_tokens: 10,
+            max_context: 5
+        }));
+    }
+}
@@ -10,12 +10,14 @@ use std::time::Instant;
 use dashmap::DashMap;
 use neomind_core::llm::backend::{LlmError, LlmInput, LlmRuntime};
 use neomind_core::llm::detect_vision_capability;
+use neomind_core::EventBus;
 use neomind_storage::{ConnectionTestResult, LlmBackendInstance, LlmBackendStore, LlmBackendType};
 
 use super::backends::create_backend;
 #[cfg(feature = "llamacpp")]
 use super::backends::llamacpp::{LlamaCppConfig, LlamaCppRuntime};
 use super::backends::ollama::{detect_model_context, OllamaConfig, OllamaRuntime};
+use super::failover::FailoverRuntime;
 
 /// Ensure an instance has correct capabilities.
 ///
@@ -111,6 +113,9 @@ pub(crate) fn ensure_instance_capabilities(mut instance: LlmBackendInstance) ->
     instance
 }
 
+/// A failover runtime and the backend chain it was built from.
+type CachedFailover = (Vec<String>, Arc<dyn LlmRuntime>);
+
 /// LLM backend instance manager
 ///
 /// Manages multiple LLM backend instances with runtime caching,
@@ -130,6 +135,16 @@ pub struct LlmBackendInstanceManager {
 
     /// Health check results cache
     health_cache: Arc<DashMap<String, (bool, Instant)>>,
+
+    /// Backend IDs tried in order when the active backend fails
+    failover_chain: Arc<RwLock<Vec<String>>>,
+
+    /// Failover runtime for the active backend, with the chain it was built
+    /// from. Kept across requests so cooldowns and recovery carry over.
+    failover_runtime: Arc<RwLock<Option<CachedFailover>>>,
+
+    /// Event bus for failover switchover events
+    event_bus: OnceLock<EventBus>,
 }
 
 impl LlmBackendInstanceManager {
@@ -143,6 +158,7 @@ impl LlmBackendInstanceManager {
         // localhost:11434/ministral-3:3b, which looked configured but never worked
         // on devices without that exact Ollama model installed.
         let active_id = storage.get_active_backend_id().unwrap_or_default();
+        let failover_chain = storage.get_failover_chain().unwrap_or_default();
 
         // Load instances from storage. No default is created above, so on a fresh
         // install this is empty until the user adds a backend via the UI/API.
@@ -163,6 +179,16 @@ impl LlmBackendInstanceManager {
             active_id: Arc::new(RwLock::new(active_id)),
             runtime_cache: Arc::new(DashMap::new()),
             health_cache: Arc::new(DashMap::new()),
+            failover_chain: Arc::new(RwLock::new(failover_chain)),
+            failover_runtime: Arc::new(RwLock::new(None)),
+            event_bus: OnceLock::new(),
+        }
+    }
+
+    /// Publish failover switchover events on `bus`.
+    pub fn set_event_bus(&self, bus: EventBus) {
+        if self.event_bus.set(bus).is_ok() {
+            self.clear_failover_runtime();
         }
     }
 
@@ -177,6 +203,9 @@ impl LlmBackendInstanceManager {
     }
 
     /// Get the active runtime (with caching)
+    ///
+    /// With a failover chain configured, this is a [`FailoverRuntime`] over
+    /// the active backend followed by the chain.
     pub async fn get_active_runtime(&self) -> Result<Arc<dyn LlmRuntime>, LlmError> {
         let active_id = {
             let active_id = self.active_id.read().map_err(|_| {
@@ -189,7 +218,99 @@ impl LlmBackendInstanceManager {
             LlmError::InvalidInput("No active LLM backend configured".to_string())
         })?;
 
-        self.get_runtime(&id).await
+        let chain = self.runtime_chain(&id);
+        if chain.len() == 1 {
+            return self.get_runtime(&id).await;
+        }
+
+        if let Ok(cached) = self.failover_runtime.read() {
+            if let Some((ids, runtime)) = cached.as_ref() {
+                if *ids == chain {
+                    return Ok(runtime.clone());
+                }
+            }
+        }
+
+        match FailoverRuntime::from_manager(self, &chain).await {
+            Ok(failover) => {
+                let failover = match self.event_bus.get() {
+                    Some(bus) => failover.with_event_bus(bus.clone()),
+                    None => failover,
+                };
+                let runtime: Arc<dyn LlmRuntime> = Arc::new(failover);
+                if let Ok(mut cached) = self.failover_runtime.write() {
+                    *cached = Some((chain, runtime.clone()));
+                }
+                Ok(runtime)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    chain = ?chain,
+                    "Failed to build LLM failover chain, using the active backend alone"
+                );
+                self.get_runtime(&id).await
+            }
+        }
+    }
+
+    /// The active backend followed by the configured failover chain,
+    /// skipping duplicates and instances that no longer exist.
+    fn runtime_chain(&self, active_id: &str) -> Vec<String> {
+        let mut chain = vec![active_id.to_string()];
+        if let Ok(failover_chain) = self.failover_chain.read() {
+            for id in failover_chain.iter() {
+                if !chain.contains(id) && self.instances.contains_key(id) {
+                    chain.push(id.clone());
+                }
+            }
+        }
+        chain
+    }
+
+    /// Backend IDs tried in order when the active backend fails.
+    pub fn failover_chain(&self) -> Vec<String> {
+        self.failover_chain
+            .read()
+            .map(|chain| chain.clone())
+            .unwrap_or_default()
+    }
+
+    /// Set the failover chain. An empty chain turns failover off.
+    pub fn set_failover_chain(&self, ids: Vec<String>) -> Result<(), LlmError> {
+        for (i, id) in ids.iter().enumerate() {
+            if !self.instances.contains_key(id) {
+                return Err(LlmError::BackendUnavailable(format!(
+                    "Backend instance {}",
+                    id
+                )));
+            }
+            if ids[..i].contains(id) {
+                return Err(LlmError::InvalidInput(format!(
+                    "Backend {} appears twice in the failover chain",
+                    id
+                )));
+            }
+        }
+
+        self.storage
+            .set_failover_chain(&ids)
+            .map_err(|e| LlmError::InvalidInput(e.to_string()))?;
+
+        let mut chain = self.failover_chain.write().map_err(|_| {
+            LlmError::InvalidInput("Failed to acquire failover_chain write lock".to_string())
+        })?;
+        *chain = ids;
+        drop(chain);
+
+        self.clear_failover_runtime();
+        Ok(())
+    }
+
+    fn clear_failover_runtime(&self) {
+        if let Ok(mut cached) = self.failover_runtime.write() {
+            *cached = None;
+        }
     }
 
     /// Get runtime for a specific backend instance
@@ -527,6 +648,7 @@ impl LlmBackendInstanceManager {
 
         // Clear runtime cache when switching
         self.runtime_cache.clear();
+        self.clear_failover_runtime();
 
         // Update storage
         self.storage
@@ -559,6 +681,7 @@ impl LlmBackendInstanceManager {
 
         // Clear runtime cache for this instance
         self.runtime_cache.remove(&id);
+        self.clear_failover_runtime();
 
         Ok(())
     }
@@ -591,6 +714,14 @@ impl LlmBackendInstanceManager {
         // Clear health cache
         self.health_cache.remove(id);
 
+        // Drop it from the failover chain
+        let chain = self.failover_chain();
+        if chain.iter().any(|c| c == id) {
+            self.set_failover_chain(chain.into_iter().filter(|c| c != id).collect())?;
+        } else {
+            self.clear_failover_runtime();
+        }
+
         Ok(())
     }
 
@@ -666,6 +797,7 @@ impl LlmBackendInstanceManager {
             .collect();
 
         let active_id = self.storage.get_active_backend_id().unwrap_or_default();
+        let failover_chain = self.storage.get_failover_chain().unwrap_or_default();
 
         // Update in-memory state - DashMap clear and insert is lock-free
         self.instances.clear();
@@ -678,6 +810,12 @@ impl LlmBackendInstanceManager {
             LlmError::InvalidInput("Failed to acquire active_id write lock".to_string())
         })?;
         *self_active_id = active_id;
+        drop(self_active_id);
+
+        if let Ok(mut chain) = self.failover_chain.write() {
+            *chain = failover_chain;
+        }
+        self.clear_failover_runtime();
 
         Ok(())
     }
@@ -977,6 +1115,7 @@ impl LlmBackendInstanceManager {
     /// Clear the runtime cache (e.g., after configuration change)
     pub fn clear_cache(&self) {
         self.runtime_cache.clear();
+        self.clear_failover_runtime();
     }
 
     /// Get health check status (cached)
@@ -1125,6 +1264,7 @@ impl LlmBackendInstanceManager {
             // a cached OllamaRuntime would keep its old `capabilities_override`
             // and the running session would see stale vision capability.
             self.runtime_cache.clear();
+            self.clear_failover_runtime();
             // Also clear health cache to force re-evaluation.
             self.health_cache.clear();
         }
@@ -1209,6 +1349,9 @@ pub fn get_instance_manager() -> Result<Arc<LlmBackendInstanceManager>, LlmError
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::Stream;
+    use neomind_core::llm::backend::{BackendId, FinishReason, LlmOutput, StreamChunk};
+    use std::pin::Pin;
 
     #[test]
     fn test_backend_type_definition() {
@@ -1326,4 +1469,116 @@ mod tests {
             Some("user_override"),
         );
     }
+
+    struct StubRuntime {
+        name: &'static str,
+        failing: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmRuntime for StubRuntime {
+        fn backend_id(&self) -> BackendId {
+            BackendId::new(BackendId::MOCK)
+        }
+
+        fn model_name(&self) -> &str {
+            self.name
+        }
+
+        async fn generate(&self, _input: LlmInput) -> Result<LlmOutput, LlmError> {
+            if self.failing {
+                return Err(LlmError::Network("connection refused".to_string()));
+            }
+            Ok(LlmOutput {
+                text: self.name.to_string(),
+                finish_reason: FinishReason::Stop,
+                usage: None,
+                thinking: None,
+                tool_calls: None,
+            })
+        }
+
+        async fn generate_stream(
+            &self,
+            _input: LlmInput,
+        ) -> Result<Pin<Box<dyn Stream<Item = StreamChunk> + Send>>, LlmError> {
+            Err(LlmError::Generation("not used".to_string()))
+        }
+
+        fn max_context_length(&self) -> usize {
+            4096
+        }
+    }
+
+    #[tokio::test]
+    async fn test_active_runtime_fails_over_along_configured_chain() {
+        use neomind_core::event::NeoMindEvent;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = LlmBackendStore::open(dir.path().join("llm_backends.redb")).unwrap();
+        let manager = LlmBackendInstanceManager::new(store.clone());
+        for id in ["primary", "backup"] {
+            manager
+                .upsert_instance(LlmBackendInstance::new(
+                    id.to_string(),
+                    id.to_string(),
+                    LlmBackendType::Ollama,
+                ))
+                .await
+                .unwrap();
+        }
+        manager.set_active("primary").await.unwrap();
+        let bus = EventBus::new();
+        let mut rx = bus.subscribe();
+        manager.set_event_bus(bus);
+
+        let primary: Arc<dyn LlmRuntime> = Arc::new(StubRuntime {
+            name: "primary",
+            failing: true,
+        });
+        let backup: Arc<dyn LlmRuntime> = Arc::new(StubRuntime {
+            name: "backup",
+            failing: false,
+        });
+        manager
+            .runtime_cache
+            .insert("primary".to_string(), primary.clone());
+        manager
+            .runtime_cache
+            .insert("backup".to_string(), backup.clone());
+
+        // Without a chain the active backend is used as-is
+        let runtime = manager.get_active_runtime().await.unwrap();
+        assert!(Arc::ptr_eq(&runtime, &primary));
+
+        assert!(manager
+            .set_failover_chain(vec!["missing".to_string()])
+            .is_err());
+        manager
+            .set_failover_chain(vec!["backup".to_string()])
+            .unwrap();
+        assert_eq!(store.get_failover_chain().unwrap(), vec!["backup"]);
+
+        let runtime = manager.get_active_runtime().await.unwrap();
+        let output = runtime.generate(LlmInput::new("hi")).await.unwrap();
+        assert_eq!(output.text, "backup");
+        let (event, _) = rx.recv().await.unwrap();
+        assert!(matches!(
+            event,
+            NeoMindEvent::LlmBackendFailover { ref to_backend, .. } if to_backend == "backup"
+        ));
+
+        // The same failover runtime serves later requests
+        assert!(Arc::ptr_eq(
+            &runtime,
+            &manager.get_active_runtime().await.unwrap()
+        ));
+
+        // Removing the backup drops it from the chain
+        manager.remove_instance("backup").await.unwrap();
+        assert!(manager.failover_chain().is_empty());
+        assert!(store.get_failover_chain().unwrap().is_empty());
+        let runtime = manager.get_active_runtime().await.unwrap();
+        assert!(Arc::ptr_eq(&runtime, &primary));
+    }
 }
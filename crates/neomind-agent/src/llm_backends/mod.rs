@@ -11,8 +11,11 @@
 
 pub mod backend_plugin;
 pub mod backends;
+pub mod failover;
 pub mod instance_manager;
 pub mod rate_limited_client;
+pub mod response_cache;
+pub mod whisper;
 
 // Re-export backend types - available unconditionally for backward compatibility
 // (actual instantiation requires appropriate feature)
@@ -26,5 +29,16 @@ pub use instance_manager::{
     get_instance_manager, BackendTypeDefinition, LlmBackendInstanceManager,
 };
 
+// Failover chain
+pub use failover::{FailoverConfig, FailoverRuntime};
+
+// Response cache
+pub use response_cache::{
+    set_query_embedder, LlmResponseCache, QueryEmbedder, ResponseCacheConfig, SharedQueryEmbedder,
+};
+
+// Speech-to-text
+pub use whisper::{WhisperConfig, WhisperTranscriber};
+
 // Backend creation utilities
 pub use backends::create_backend;
@@ -0,0 +1,539 @@
+//! Response cache for LLM completions.
+//!
+//! Two layers, both bounded by a TTL:
+//! - **Exact**: keyed on a hash of (model, messages, generation params, tools).
+//!   A repeated request with an identical prompt is answered without calling
+//!   the backend.
+//! - **Semantic** (optional): single-turn prompts (system + one user message)
+//!   are also indexed by an embedding of the user text. A later single-turn
+//!   prompt with the same model, system prompt, params and tools whose
+//!   embedding is close enough reuses the answer, so "list my devices" and
+//!   "show me my devices" share one completion. Turned on by
+//!   `AGENT_LLM_CACHE_SIMILARITY`; queries are embedded by the embedder the
+//!   server installs with [`set_query_embedder`] (the knowledge base's).
+//!
+//! Hits and misses are counted in `neomind_llm_cache_requests_total{result}`;
+//! completion tokens served from the cache are counted in
+//! `neomind_llm_cache_tokens_saved_total`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use neomind_core::config::agent_env_vars;
+use neomind_core::llm::backend::LlmInput;
+use neomind_core::{Message, MessageRole};
+use parking_lot::Mutex;
+
+/// Default time-to-live of a cached response.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Default maximum number of cached responses per layer.
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 512;
+
+/// Default minimum cosine similarity for a semantic hit.
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.95;
+
+/// Produces embeddings for semantic cache lookups.
+#[async_trait]
+pub trait QueryEmbedder: Send + Sync {
+    /// Embed `text`, or `None` if the embedding could not be computed.
+    async fn embed(&self, text: &str) -> Option<Vec<f32>>;
+
+    /// Identifies the vector space; embeddings from different models are
+    /// never compared.
+    fn model_id(&self) -> String {
+        String::new()
+    }
+}
+
+static QUERY_EMBEDDER: RwLock<Option<Arc<dyn QueryEmbedder>>> = RwLock::new(None);
+
+/// Set the embedder behind [`SharedQueryEmbedder`], or `None` to turn
+/// semantic lookups off.
+pub fn set_query_embedder(embedder: Option<Arc<dyn QueryEmbedder>>) {
+    if let Ok(mut current) = QUERY_EMBEDDER.write() {
+        *current = embedder;
+    }
+}
+
+/// Embeds with whichever embedder [`set_query_embedder`] installed last, so
+/// caches created before it is set or changed follow along.
+pub struct SharedQueryEmbedder;
+
+impl SharedQueryEmbedder {
+    fn current() -> Option<Arc<dyn QueryEmbedder>> {
+        QUERY_EMBEDDER.read().ok()?.clone()
+    }
+}
+
+#[async_trait]
+impl QueryEmbedder for SharedQueryEmbedder {
+    async fn embed(&self, text: &str) -> Option<Vec<f32>> {
+        Self::current()?.embed(text).await
+    }
+
+    fn model_id(&self) -> String {
+        Self::current()
+            .map(|embedder| embedder.model_id())
+            .unwrap_or_default()
+    }
+}
+
+/// Response cache configuration.
+#[derive(Debug, Clone)]
+pub struct ResponseCacheConfig {
+    pub ttl: Duration,
+    pub max_entries: usize,
+    pub similarity_threshold: f32,
+    /// Whether the semantic layer was asked for
+    pub semantic: bool,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: DEFAULT_CACHE_TTL,
+            max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            similarity_threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            semantic: false,
+        }
+    }
+}
+
+impl ResponseCacheConfig {
+    /// Read the configuration from `AGENT_LLM_CACHE_TTL_SECS` and
+    /// `AGENT_LLM_CACHE_SIMILARITY`. Returns `None` (cache disabled) unless a
+    /// non-zero TTL is set; the semantic layer is on when a similarity is.
+    pub fn from_env() -> Option<Self> {
+        let ttl = agent_env_vars::llm_cache_ttl_secs()?;
+        let mut config = Self {
+            ttl: Duration::from_secs(ttl),
+            ..Self::default()
+        };
+        if let Some(threshold) = agent_env_vars::llm_cache_similarity() {
+            config.similarity_threshold = threshold.clamp(0.0, 1.0);
+            config.semantic = true;
+        }
+        Some(config)
+    }
+}
+
+/// A cached completion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedResponse {
+    pub text: String,
+    pub thinking: Option<String>,
+    pub finish_reason: String,
+    /// Completion tokens the original request used.
+    pub completion_tokens: usize,
+}
+
+struct ExactEntry {
+    response: CachedResponse,
+    inserted: Instant,
+}
+
+struct SemanticEntry {
+    /// Hash of everything except the user text (model, system prompt, params, tools).
+    scope: u64,
+    /// Embedder the entry was embedded with
+    model: String,
+    embedding: Vec<f32>,
+    response: CachedResponse,
+    inserted: Instant,
+}
+
+/// Exact + semantic cache for LLM completions.
+pub struct LlmResponseCache {
+    config: ResponseCacheConfig,
+    exact: Mutex<HashMap<u64, ExactEntry>>,
+    semantic: Mutex<VecDeque<SemanticEntry>>,
+    embedder: Option<Arc<dyn QueryEmbedder>>,
+}
+
+impl LlmResponseCache {
+    pub fn new(config: ResponseCacheConfig) -> Self {
+        Self {
+            config,
+            exact: Mutex::new(HashMap::new()),
+            semantic: Mutex::new(VecDeque::new()),
+            embedder: None,
+        }
+    }
+
+    /// Build the cache described by `config`, with the shared embedder when
+    /// the semantic layer is on.
+    pub fn from_config(config: ResponseCacheConfig) -> Self {
+        let semantic = config.semantic;
+        let cache = Self::new(config);
+        if semantic {
+            cache.with_embedder(Arc::new(SharedQueryEmbedder))
+        } else {
+            cache
+        }
+    }
+
+    /// Enable the semantic layer.
+    pub fn with_embedder(mut self, embedder: Arc<dyn QueryEmbedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    pub fn config(&self) -> &ResponseCacheConfig {
+        &self.config
+    }
+
+    /// Look up a completion for `input`, trying the exact layer first.
+    pub async fn get(&self, input: &LlmInput) -> Option<CachedResponse> {
+        let key = exact_key(input);
+        let hit = {
+            let mut exact = self.exact.lock();
+            match exact.get(&key) {
+                Some(entry) if entry.inserted.elapsed() < self.config.ttl => {
+                    Some(entry.response.clone())
+                }
+                Some(_) => {
+                    exact.remove(&key);
+                    None
+                }
+                None => None,
+            }
+        };
+        if let Some(response) = hit {
+            record_lookup("exact_hit", Some(&response));
+            return Some(response);
+        }
+
+        if let Some(response) = self.get_semantic(input).await {
+            record_lookup("semantic_hit", Some(&response));
+            return Some(response);
+        }
+
+        record_lookup("miss", None);
+        None
+    }
+
+    async fn get_semantic(&self, input: &LlmInput) -> Option<CachedResponse> {
+        let embedder = self.embedder.as_ref()?;
+        let (scope, query) = semantic_scope(input)?;
+        let model = embedder.model_id();
+        let embedding = embedder.embed(&query).await?;
+
+        let mut semantic = self.semantic.lock();
+        semantic.retain(|e| e.inserted.elapsed() < self.config.ttl);
+        semantic
+            .iter()
+            .filter(|e| e.scope == scope && e.model == model)
+            .map(|e| (cosine_similarity(&embedding, &e.embedding), e))
+            .filter(|(similarity, _)| *similarity >= self.config.similarity_threshold)
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, e)| e.response.clone())
+    }
+
+    /// Store the completion for `input`.
+    pub async fn put(&self, input: &LlmInput, response: CachedResponse) {
+        let key = exact_key(input);
+        {
+            let mut exact = self.exact.lock();
+            if exact.len() >= self.config.max_entries && !exact.contains_key(&key) {
+                exact.retain(|_, e| e.inserted.elapsed() < self.config.ttl);
+                if exact.len() >= self.config.max_entries {
+                    if let Some(oldest) = exact
+                        .iter()
+                        .min_by_key(|(_, e)| e.inserted)
+                        .map(|(k, _)| *k)
+                    {
+                        exact.remove(&oldest);
+                    }
+                }
+            }
+            exact.insert(
+                key,
+                ExactEntry {
+                    response: response.clone(),
+                    inserted: Instant::now(),
+                },
+            );
+        }
+
+        let Some(embedder) = &self.embedder else {
+            return;
+        };
+        let Some((scope, query)) = semantic_scope(input) else {
+            return;
+        };
+        let model = embedder.model_id();
+        let Some(embedding) = embedder.embed(&query).await else {
+            return;
+        };
+        let mut semantic = self.semantic.lock();
+        if semantic.len() >= self.config.max_entries {
+            semantic.pop_front();
+        }
+        semantic.push_back(SemanticEntry {
+            scope,
+            model,
+            embedding,
+            response,
+            inserted: Instant::now(),
+        });
+    }
+
+    /// Number of entries in the exact layer.
+    pub fn len(&self) -> usize {
+        self.exact.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all cached responses.
+    pub fn clear(&self) {
+        self.exact.lock().clear();
+        self.semantic.lock().clear();
+    }
+}
+
+fn record_lookup(result: &str, response: Option<&CachedResponse>) {
+    let registry = neomind_core::metrics::global();
+    registry
+        .counter(
+            "neomind_llm_cache_requests_total",
+            "LLM response cache lookups by result",
+            &[("result", result)],
+        )
+        .inc();
+    if let Some(response) = response {
+        registry
+            .counter(
+                "neomind_llm_cache_tokens_saved_total",
+                "Completion tokens served from the LLM response cache",
+                &[],
+            )
+            .inc_by(response.completion_tokens as u64);
+    }
+}
+
+/// Hash the request parameters shared by both layers.
+fn hash_request_params(input: &LlmInput, hasher: &mut DefaultHasher) {
+    input.model.hash(hasher);
+    // GenerationParams has no Hash impl; its Debug output is stable and
+    // covers every field.
+    format!("{:?}", input.params).hash(hasher);
+    serde_json::to_string(&input.tools)
+        .unwrap_or_default()
+        .hash(hasher);
+}
+
+fn exact_key(input: &LlmInput) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_request_params(input, &mut hasher);
+    // Leave out message timestamps: they differ between identical requests
+    for message in &input.messages {
+        serde_json::to_string(&(&message.role, &message.content, &message.tool_name))
+            .unwrap_or_default()
+            .hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// For single-turn prompts, return the scope hash and the user text to embed.
+fn semantic_scope(input: &LlmInput) -> Option<(u64, String)> {
+    let (system, user): (Vec<&Message>, Vec<&Message>) = input
+        .messages
+        .iter()
+        .partition(|m| m.role == MessageRole::System);
+    let [user] = user.as_slice() else {
+        return None;
+    };
+    if user.role != MessageRole::User {
+        return None;
+    }
+    let query = user.content.as_text();
+    if query.trim().is_empty() {
+        return None;
+    }
+
+    let mut hasher = DefaultHasher::new();
+    hash_request_params(input, &mut hasher);
+    for message in system {
+        message.content.as_text().hash(&mut hasher);
+    }
+    Some((hasher.finish(), query))
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_core::llm::backend::GenerationParams;
+
+    /// Embeds by keyword so paraphrases of the same request map together.
+    struct KeywordEmbedder;
+
+    #[async_trait]
+    impl QueryEmbedder for KeywordEmbedder {
+        async fn embed(&self, text: &str) -> Option<Vec<f32>> {
+            let text = text.to_lowercase();
+            Some(vec![
+                text.contains("device") as u8 as f32,
+                text.contains("rule") as u8 as f32,
+            ])
+        }
+    }
+
+    fn input(messages: Vec<Message>) -> LlmInput {
+        LlmInput {
+            messages,
+            params: GenerationParams::default(),
+            model: Some("test-model".to_string()),
+            stream: false,
+            tools: None,
+        }
+    }
+
+    fn response(text: &str) -> CachedResponse {
+        CachedResponse {
+            text: text.to_string(),
+            thinking: None,
+            finish_reason: "Stop".to_string(),
+            completion_tokens: 12,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exact_hit_and_ttl() {
+        let cache = LlmResponseCache::new(ResponseCacheConfig {
+            ttl: Duration::from_millis(50),
+            ..Default::default()
+        });
+        let request = input(vec![Message::system("sys"), Message::user("list devices")]);
+
+        assert!(cache.get(&request).await.is_none());
+        cache.put(&request, response("3 devices")).await;
+        assert_eq!(cache.get(&request).await, Some(response("3 devices")));
+
+        let mut other_model = request.clone();
+        other_model.model = Some("other".to_string());
+        assert!(cache.get(&other_model).await.is_none());
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        assert!(cache.get(&request).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_semantic_hit_for_single_turn_only() {
+        let cache = LlmResponseCache::new(ResponseCacheConfig::default())
+            .with_embedder(Arc::new(KeywordEmbedder));
+        cache
+            .put(
+                &input(vec![
+                    Message::system("sys"),
+                    Message::user("list my devices"),
+                ]),
+                response("3 devices"),
+            )
+            .await;
+
+        let paraphrase = input(vec![
+            Message::system("sys"),
+            Message::user("show me all devices"),
+        ]);
+        assert_eq!(cache.get(&paraphrase).await, Some(response("3 devices")));
+
+        let different = input(vec![Message::system("sys"), Message::user("list rules")]);
+        assert!(cache.get(&different).await.is_none());
+
+        let other_system = input(vec![
+            Message::system("other"),
+            Message::user("show me all devices"),
+        ]);
+        assert!(cache.get(&other_system).await.is_none());
+
+        let multi_turn = input(vec![
+            Message::system("sys"),
+            Message::user("hi"),
+            Message::assistant("hello"),
+            Message::user("show me all devices"),
+        ]);
+        assert!(cache.get(&multi_turn).await.is_none());
+    }
+
+    /// [`KeywordEmbedder`] under another model id.
+    struct RenamedEmbedder;
+
+    #[async_trait]
+    impl QueryEmbedder for RenamedEmbedder {
+        async fn embed(&self, text: &str) -> Option<Vec<f32>> {
+            KeywordEmbedder.embed(text).await
+        }
+
+        fn model_id(&self) -> String {
+            "renamed".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_layer_uses_installed_embedder() {
+        let request = input(vec![
+            Message::system("sys"),
+            Message::user("list my devices"),
+        ]);
+        let paraphrase = input(vec![
+            Message::system("sys"),
+            Message::user("show me all devices"),
+        ]);
+
+        set_query_embedder(Some(Arc::new(KeywordEmbedder)));
+        let exact_only = LlmResponseCache::from_config(ResponseCacheConfig::default());
+        exact_only.put(&request, response("3 devices")).await;
+        assert!(exact_only.get(&paraphrase).await.is_none());
+
+        let cache = LlmResponseCache::from_config(ResponseCacheConfig {
+            semantic: true,
+            ..Default::default()
+        });
+        cache.put(&request, response("3 devices")).await;
+        assert_eq!(cache.get(&paraphrase).await, Some(response("3 devices")));
+
+        // Entries from another embedder are not compared
+        set_query_embedder(Some(Arc::new(RenamedEmbedder)));
+        assert!(cache.get(&paraphrase).await.is_none());
+
+        set_query_embedder(None);
+        assert!(cache.get(&paraphrase).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_max_entries_evicts_oldest() {
+        let cache = LlmResponseCache::new(ResponseCacheConfig {
+            max_entries: 2,
+            ..Default::default()
+        });
+        for q in ["a", "b", "c"] {
+            cache.put(&input(vec![Message::user(q)]), response(q)).await;
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&input(vec![Message::user("a")])).await.is_none());
+        assert!(cache.get(&input(vec![Message::user("c")])).await.is_some());
+    }
+}
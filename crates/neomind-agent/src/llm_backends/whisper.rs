@@ -0,0 +1,205 @@
+//! Whisper speech-to-text.
+//!
+//! Talks to any server exposing the OpenAI transcription API shape: a
+//! multipart POST with a `file` part that answers `{"text": ...}`. That
+//! covers the OpenAI API (`/v1/audio/transcriptions`) as well as a local
+//! whisper.cpp server (`whisper-server`, `/inference`), so edge devices can
+//! transcribe without leaving the LAN.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use neomind_core::llm::backend::LlmError;
+use neomind_core::llm::modality::{AudioContent, AudioTranscriber, Transcription};
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+
+/// Transcription endpoint URL. Transcription is disabled when unset.
+pub const WHISPER_URL_ENV: &str = "NEOMIND_WHISPER_URL";
+/// Bearer token for the endpoint (required by the OpenAI API).
+pub const WHISPER_API_KEY_ENV: &str = "NEOMIND_WHISPER_API_KEY";
+/// Model name sent with each request.
+pub const WHISPER_MODEL_ENV: &str = "NEOMIND_WHISPER_MODEL";
+
+const OPENAI_TRANSCRIPTION_URL: &str = "https://api.openai.com/v1/audio/transcriptions";
+const DEFAULT_LOCAL_URL: &str = "http://127.0.0.1:8080/inference";
+const DEFAULT_MODEL: &str = "whisper-1";
+
+/// Whisper endpoint configuration.
+#[derive(Debug, Clone)]
+pub struct WhisperConfig {
+    /// Full URL of the transcription endpoint.
+    pub url: String,
+    /// Bearer token, if the endpoint needs one.
+    pub api_key: Option<String>,
+    /// Model name (ignored by whisper.cpp, which serves the model it was started with).
+    pub model: String,
+    /// Request timeout.
+    pub timeout: Duration,
+}
+
+impl WhisperConfig {
+    /// Local whisper.cpp server on the default port.
+    pub fn local() -> Self {
+        Self {
+            url: DEFAULT_LOCAL_URL.to_string(),
+            api_key: None,
+            model: DEFAULT_MODEL.to_string(),
+            timeout: Duration::from_secs(120),
+        }
+    }
+
+    /// OpenAI transcription API.
+    pub fn openai(api_key: impl Into<String>) -> Self {
+        Self {
+            url: OPENAI_TRANSCRIPTION_URL.to_string(),
+            api_key: Some(api_key.into()),
+            model: DEFAULT_MODEL.to_string(),
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Read `NEOMIND_WHISPER_URL` / `_API_KEY` / `_MODEL`.
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var(WHISPER_URL_ENV)
+            .ok()
+            .filter(|u| !u.is_empty())?;
+        Some(Self {
+            url,
+            api_key: std::env::var(WHISPER_API_KEY_ENV)
+                .ok()
+                .filter(|k| !k.is_empty()),
+            model: std::env::var(WHISPER_MODEL_ENV).unwrap_or_else(|_| DEFAULT_MODEL.to_string()),
+            timeout: Duration::from_secs(120),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    duration: Option<f32>,
+}
+
+/// [`AudioTranscriber`] backed by a Whisper HTTP endpoint.
+pub struct WhisperTranscriber {
+    config: WhisperConfig,
+    client: reqwest::Client,
+}
+
+impl WhisperTranscriber {
+    pub fn new(config: WhisperConfig) -> Result<Self, LlmError> {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+        Ok(Self { config, client })
+    }
+
+    /// Build from environment variables; `None` when no endpoint is configured.
+    pub fn from_env() -> Option<Self> {
+        let config = WhisperConfig::from_env()?;
+        match Self::new(config) {
+            Ok(transcriber) => Some(transcriber),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to create Whisper transcriber");
+                None
+            }
+        }
+    }
+
+    pub fn config(&self) -> &WhisperConfig {
+        &self.config
+    }
+}
+
+#[async_trait]
+impl AudioTranscriber for WhisperTranscriber {
+    fn name(&self) -> &str {
+        "whisper"
+    }
+
+    async fn transcribe(
+        &self,
+        audio: &AudioContent,
+        language: Option<&str>,
+    ) -> Result<Transcription, LlmError> {
+        if audio.data.is_empty() {
+            return Err(LlmError::InvalidInput("empty audio".to_string()));
+        }
+
+        let file = Part::bytes(audio.data.clone())
+            .file_name(format!("audio.{}", audio.format.extension()))
+            .mime_str(audio.format.mime_type())
+            .map_err(|e| LlmError::InvalidInput(e.to_string()))?;
+        let mut form = Form::new()
+            .part("file", file)
+            .text("model", self.config.model.clone())
+            .text("response_format", "json");
+        if let Some(lang) = language {
+            form = form.text("language", lang.to_string());
+        }
+
+        let mut request = self.client.post(&self.config.url).multipart(form);
+        if let Some(key) = &self.config.api_key {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| LlmError::Network(e.to_string()))?;
+        if !status.is_success() {
+            return Err(LlmError::Api {
+                status: status.as_u16(),
+                body,
+            });
+        }
+
+        let parsed: TranscriptionResponse = serde_json::from_str(&body)?;
+        Ok(Transcription {
+            text: parsed.text.trim().to_string(),
+            language: parsed.language.or_else(|| language.map(str::to_string)),
+            duration_secs: parsed.duration,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_core::llm::modality::AudioFormat;
+
+    #[test]
+    fn test_response_parsing() {
+        let openai: TranscriptionResponse =
+            serde_json::from_str(r#"{"text": " turn on the lights "}"#).unwrap();
+        assert_eq!(openai.text, " turn on the lights ");
+        assert!(openai.language.is_none());
+
+        let whisper_cpp: TranscriptionResponse =
+            serde_json::from_str(r#"{"text": "打开灯", "language": "zh", "duration": 1.5}"#)
+                .unwrap();
+        assert_eq!(whisper_cpp.language.as_deref(), Some("zh"));
+        assert_eq!(whisper_cpp.duration, Some(1.5));
+    }
+
+    #[tokio::test]
+    async fn test_empty_audio_rejected() {
+        let transcriber = WhisperTranscriber::new(WhisperConfig::local()).unwrap();
+        let audio = AudioContent {
+            data: Vec::new(),
+            format: AudioFormat::Wav,
+        };
+        let err = transcriber.transcribe(&audio, None).await.unwrap_err();
+        assert!(matches!(err, LlmError::InvalidInput(_)));
+    }
+}
@@ -6,8 +6,12 @@
 pub mod compressor;
 pub mod dedup;
 pub mod scheduler;
+pub mod session_summary;
 pub mod snapshot;
+pub mod summarizer;
 
 // Re-exports consumed via shortcut path (crate::memory::TypeName)
 pub use scheduler::MemoryScheduler;
+pub use session_summary::SessionDigest;
 pub use snapshot::MemorySnapshot;
+pub use summarizer::{ExtractiveSummarizer, LlmSummarizer, Summarizer};
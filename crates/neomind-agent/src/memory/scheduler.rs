@@ -2,6 +2,8 @@
 //!
 //! Runs periodic tasks for:
 //! - Temp file cleanup (session directories)
+//! - Chat retention: purging sessions idle for `chat_retention_days`, with
+//!   their derived memories
 
 use std::fs;
 use std::sync::Arc;
@@ -11,10 +13,13 @@ use tracing::{error, info, warn};
 
 use neomind_storage::{MarkdownMemoryStore, MemoryConfig};
 
+use crate::SessionManager;
+
 /// Memory scheduler for background tasks
 pub struct MemoryScheduler {
     store: Arc<RwLock<MarkdownMemoryStore>>,
     config: MemoryConfig,
+    sessions: Option<Arc<SessionManager>>,
     job_handle: Option<tokio::task::JoinHandle<()>>,
 }
 
@@ -24,33 +29,53 @@ impl MemoryScheduler {
         Self {
             store,
             config,
+            sessions: None,
             job_handle: None,
         }
     }
 
+    /// Enforce `chat_retention_days` on these sessions.
+    pub fn with_sessions(mut self, sessions: Arc<SessionManager>) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+
+    fn retention_enabled(&self) -> bool {
+        self.sessions.is_some() && self.config.chat_retention_days > 0
+    }
+
     /// Start background jobs
     pub fn start(&mut self) {
-        if !self.config.enabled {
+        if !self.config.enabled && !self.retention_enabled() {
             info!("Memory system disabled, not starting scheduler");
             return;
         }
 
         let store = self.store.clone();
         let config = self.config.clone();
+        let sessions = self.sessions.clone();
 
         self.job_handle = Some(tokio::spawn(async move {
             // Run cleanup every 24 hours
             let mut cleanup_timer = interval(Duration::from_secs(86400));
 
-            info!("Memory scheduler started (temp file cleanup every 24h)");
+            info!(
+                retention_days = config.chat_retention_days,
+                "Memory scheduler started (temp file cleanup every 24h)"
+            );
 
             // Trigger first cleanup immediately
             cleanup_timer.tick().await;
 
             loop {
                 cleanup_timer.tick().await;
-                if let Err(e) = Self::run_temp_cleanup_job(&store, &config).await {
-                    error!(error = %e, "Temp cleanup job failed");
+                if config.enabled {
+                    if let Err(e) = Self::run_temp_cleanup_job(&store, &config).await {
+                        error!(error = %e, "Temp cleanup job failed");
+                    }
+                }
+                if let Some(sessions) = &sessions {
+                    sessions.purge_expired_sessions(config.chat_retention_days).await;
                 }
             }
         }));
@@ -168,8 +193,10 @@ mod tests {
     #[test]
     fn test_scheduler_creation() {
         let temp = TempDir::new().unwrap();
-        let mut config = MemoryConfig::default();
-        config.storage_path = temp.path().to_string_lossy().to_string();
+        let config = MemoryConfig {
+            storage_path: temp.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
 
         let store = Arc::new(RwLock::new(MarkdownMemoryStore::new(temp.path())));
         let scheduler = MemoryScheduler::new(store, config);
@@ -180,8 +207,10 @@ mod tests {
     #[tokio::test]
     async fn test_scheduler_start_stop() {
         let temp = TempDir::new().unwrap();
-        let mut config = MemoryConfig::default();
-        config.storage_path = temp.path().to_string_lossy().to_string();
+        let config = MemoryConfig {
+            storage_path: temp.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
 
         let store = Arc::new(RwLock::new(MarkdownMemoryStore::new(temp.path())));
         let mut scheduler = MemoryScheduler::new(store, config);
@@ -196,9 +225,11 @@ mod tests {
     #[test]
     fn test_disabled_scheduler() {
         let temp = TempDir::new().unwrap();
-        let mut config = MemoryConfig::default();
-        config.storage_path = temp.path().to_string_lossy().to_string();
-        config.enabled = false;
+        let config = MemoryConfig {
+            storage_path: temp.path().to_string_lossy().to_string(),
+            enabled: false,
+            ..Default::default()
+        };
 
         let store = Arc::new(RwLock::new(MarkdownMemoryStore::new(temp.path())));
         let mut scheduler = MemoryScheduler::new(store, config);
@@ -206,4 +237,23 @@ mod tests {
         scheduler.start();
         assert!(!scheduler.is_running()); // Should not start when disabled
     }
+
+    #[tokio::test]
+    async fn test_retention_starts_scheduler_without_memory() {
+        let temp = TempDir::new().unwrap();
+        let config = MemoryConfig {
+            storage_path: temp.path().to_string_lossy().to_string(),
+            enabled: false,
+            chat_retention_days: 30,
+            ..Default::default()
+        };
+
+        let store = Arc::new(RwLock::new(MarkdownMemoryStore::new(temp.path())));
+        let mut scheduler =
+            MemoryScheduler::new(store, config).with_sessions(Arc::new(SessionManager::memory()));
+
+        scheduler.start();
+        assert!(scheduler.is_running());
+        scheduler.stop();
+    }
 }
@@ -0,0 +1,274 @@
+//! Session summaries written when a session is archived or goes idle.
+//!
+//! The configured [`Summarizer`](super::summarizer::Summarizer) condenses the
+//! conversation into a short summary plus the key entities it touched
+//! (devices, rules, locations). The summary is stored in
+//! `SessionMetadata` and prepended to the `custom:session-history` memory
+//! file, a rolling log the memory tool can read back later ("what did we
+//! change on the HVAC last week?"). The oldest entries fall off the bottom
+//! once the file reaches its char limit.
+
+use neomind_storage::MarkdownMemoryStore;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::compressor::evict_to_limit;
+use crate::agent::AgentMessage;
+use crate::error::Result;
+use crate::llm::LlmInterface;
+
+/// Custom memory file holding archived session summaries.
+pub const SESSION_HISTORY_FILE: &str = "session-history";
+
+/// Transcript budget sent to the LLM; older messages are dropped first.
+const TRANSCRIPT_CHAR_BUDGET: usize = 12_000;
+
+/// Per-message cap so one long tool dump can't crowd out the conversation.
+const MESSAGE_CHAR_CAP: usize = 1_000;
+
+/// Digest of a session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SessionDigest {
+    /// Two to four sentences: what was asked, what was changed, outcome.
+    pub summary: String,
+    /// Devices, rules, locations and other named things the session touched.
+    #[serde(default)]
+    pub entities: Vec<String>,
+}
+
+/// Summarize a conversation. Returns `None` when there is nothing worth
+/// summarizing (no user message).
+pub async fn summarize_history(
+    llm: &LlmInterface,
+    history: &[AgentMessage],
+) -> Result<Option<SessionDigest>> {
+    let transcript = build_transcript(history);
+    if transcript.is_empty() {
+        return Ok(None);
+    }
+
+    let prompt = format!(
+        "Summarize this conversation between a user and the NeoMind IoT assistant \
+         for later recall. Focus on what the user asked for and what was actually \
+         done (device commands, rule changes, findings). Write the summary in the \
+         same language as the conversation; if it mixes languages, use the \
+         language of the user's messages and keep names as written. List the \
+         key entities by name.\n\n\
+         Conversation:\n{transcript}"
+    );
+    let mut digest: SessionDigest = llm.generate_structured(prompt).await?;
+    digest.summary = digest.summary.trim().to_string();
+    digest.entities.retain(|e| !e.trim().is_empty());
+    digest.entities.dedup();
+    Ok(Some(digest).filter(|d| !d.summary.is_empty()))
+}
+
+/// Render user/assistant turns as `role: text` lines, newest kept when over
+/// budget. Empty when the conversation has no user message.
+fn build_transcript(history: &[AgentMessage]) -> String {
+    if !history.iter().any(|m| m.role == "user") {
+        return String::new();
+    }
+
+    let mut lines = Vec::new();
+    let mut used = 0usize;
+    for msg in history.iter().rev() {
+        if msg.role != "user" && msg.role != "assistant" {
+            continue;
+        }
+        let text = msg.content.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let text: String = text.chars().take(MESSAGE_CHAR_CAP).collect();
+        let line = format!("{}: {}", msg.role, text);
+        used += line.chars().count();
+        if used > TRANSCRIPT_CHAR_BUDGET && !lines.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    lines.reverse();
+    lines.join("\n")
+}
+
+/// Prepend a digest to the session history memory file, evicting the oldest
+/// entries beyond `max_chars`.
+pub fn index_digest(
+    store: &MarkdownMemoryStore,
+    session_id: &str,
+    title: Option<&str>,
+    digest: &SessionDigest,
+    timestamp: i64,
+    max_chars: usize,
+) -> neomind_storage::Result<()> {
+    let existing = store.read_custom_file(SESSION_HISTORY_FILE)?;
+    // A re-summarized session replaces its previous entry.
+    let marker = session_marker(session_id);
+    let existing = remove_entry(&existing, &marker);
+
+    let entry = format_entry(session_id, title, digest, timestamp);
+    let combined = if existing.trim().is_empty() {
+        entry
+    } else {
+        format!("{}\n{}", entry, existing.trim_start())
+    };
+    let evicted = evict_to_limit(&combined, max_chars);
+    store.write_custom_file(SESSION_HISTORY_FILE, &evicted.content)
+}
+
+/// The session's entry in the session-history file, if it has one.
+pub fn session_entry(
+    store: &MarkdownMemoryStore,
+    session_id: &str,
+) -> neomind_storage::Result<Option<String>> {
+    let content = store.read_custom_file(SESSION_HISTORY_FILE)?;
+    let marker = session_marker(session_id);
+    Ok(split_sections(&content).into_iter().find(|s| s.contains(&marker)))
+}
+
+/// Remove the session's entry from the session-history file. Returns `false`
+/// if it had none.
+pub fn forget_session(
+    store: &MarkdownMemoryStore,
+    session_id: &str,
+) -> neomind_storage::Result<bool> {
+    let existing = store.read_custom_file(SESSION_HISTORY_FILE)?;
+    let marker = session_marker(session_id);
+    if !existing.contains(&marker) {
+        return Ok(false);
+    }
+    store.write_custom_file(SESSION_HISTORY_FILE, &remove_entry(&existing, &marker))?;
+    Ok(true)
+}
+
+fn session_marker(session_id: &str) -> String {
+    format!("<!-- session:{} -->", session_id)
+}
+
+fn format_entry(
+    session_id: &str,
+    title: Option<&str>,
+    digest: &SessionDigest,
+    timestamp: i64,
+) -> String {
+    let date = chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    let mut entry = format!(
+        "## {} — {}\n{}\n{}\n",
+        date,
+        title.filter(|t| !t.is_empty()).unwrap_or("Chat session"),
+        session_marker(session_id),
+        digest.summary
+    );
+    if !digest.entities.is_empty() {
+        entry.push_str(&format!("Entities: {}\n", digest.entities.join(", ")));
+    }
+    entry
+}
+
+/// Drop the `## ...` section containing `marker`.
+fn remove_entry(content: &str, marker: &str) -> String {
+    if !content.contains(marker) {
+        return content.to_string();
+    }
+    split_sections(content)
+        .into_iter()
+        .filter(|s| !s.contains(marker))
+        .collect()
+}
+
+/// Split into `## ...` sections, each with its trailing newline.
+fn split_sections(content: &str) -> Vec<String> {
+    let mut sections: Vec<String> = Vec::new();
+    for line in content.lines() {
+        if line.starts_with("## ") || sections.is_empty() {
+            sections.push(String::new());
+        }
+        let current = sections.last_mut().expect("pushed above");
+        current.push_str(line);
+        current.push('\n');
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(summary: &str) -> SessionDigest {
+        SessionDigest {
+            summary: summary.to_string(),
+            entities: vec!["HVAC-1".to_string(), "night-mode rule".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_build_transcript() {
+        assert!(build_transcript(&[AgentMessage::assistant("hello")]).is_empty());
+
+        let history = vec![
+            AgentMessage::user("Lower the HVAC setpoint to 22"),
+            AgentMessage::assistant("Setpoint changed to 22°C"),
+        ];
+        assert_eq!(
+            build_transcript(&history),
+            "user: Lower the HVAC setpoint to 22\nassistant: Setpoint changed to 22°C"
+        );
+    }
+
+    #[test]
+    fn test_index_digest_newest_first_and_replaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MarkdownMemoryStore::new(dir.path());
+
+        index_digest(
+            &store,
+            "s1",
+            Some("HVAC"),
+            &digest("Lowered setpoint"),
+            0,
+            4000,
+        )
+        .unwrap();
+        index_digest(&store, "s2", None, &digest("Checked sensors"), 60, 4000).unwrap();
+        let content = store.read_custom_file(SESSION_HISTORY_FILE).unwrap();
+        assert!(
+            content.find("Checked sensors").unwrap() < content.find("Lowered setpoint").unwrap()
+        );
+        assert!(content.contains("Entities: HVAC-1, night-mode rule"));
+
+        index_digest(
+            &store,
+            "s1",
+            Some("HVAC"),
+            &digest("Raised setpoint"),
+            120,
+            4000,
+        )
+        .unwrap();
+        let content = store.read_custom_file(SESSION_HISTORY_FILE).unwrap();
+        assert!(!content.contains("Lowered setpoint"));
+        assert!(content.starts_with("## 1970-01-01 00:02 UTC — HVAC"));
+        assert!(content.contains("Checked sensors"));
+    }
+
+    #[test]
+    fn test_forget_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MarkdownMemoryStore::new(dir.path());
+        index_digest(&store, "s1", None, &digest("Lowered setpoint"), 0, 4000).unwrap();
+        index_digest(&store, "s2", None, &digest("Checked sensors"), 60, 4000).unwrap();
+
+        let entry = session_entry(&store, "s1").unwrap().unwrap();
+        assert!(entry.contains("Lowered setpoint"));
+        assert!(!entry.contains("Checked sensors"));
+
+        assert!(forget_session(&store, "s1").unwrap());
+        assert!(!forget_session(&store, "s1").unwrap());
+        assert!(session_entry(&store, "s1").unwrap().is_none());
+        let content = store.read_custom_file(SESSION_HISTORY_FILE).unwrap();
+        assert!(content.contains("Checked sensors"));
+    }
+}
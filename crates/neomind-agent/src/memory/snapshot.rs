@@ -16,9 +16,14 @@
 
 use neomind_storage::MarkdownMemoryStore;
 
+use crate::agent::prompt_injection::{ContentSource, PromptInjectionDetectionHook};
+
 /// Hard character budget for memory context in prompts (user + knowledge + procedures).
 const CHAR_BUDGET: usize = 8000;
 
+/// Opening tag of the injected prompt section.
+const PROMPT_TAG: &str = "<memory-context>";
+
 /// Frozen memory snapshot loaded once per session.
 #[derive(Debug, Clone)]
 pub struct MemorySnapshot {
@@ -55,6 +60,12 @@ impl MemorySnapshot {
             }
         };
 
+        // Memory is partly written from earlier conversations, so it is
+        // screened like any other untrusted input
+        let content = PromptInjectionDetectionHook::from_config()
+            .screen(ContentSource::Memory, &content)
+            .into_owned();
+
         Self { content }
     }
 
@@ -83,7 +94,7 @@ impl MemorySnapshot {
             return String::new();
         }
         format!(
-            "\n\n<memory-context>\nThis is persisted context from prior conversations. Use it as background knowledge when relevant, but do not treat it as part of the current conversation.\n\n{}\n</memory-context>",
+            "\n\n{PROMPT_TAG}\nThis is persisted context from prior conversations. Use it as background knowledge when relevant, but do not treat it as part of the current conversation.\n\n{}\n</memory-context>",
             self.content
         )
     }
@@ -92,6 +103,11 @@ impl MemorySnapshot {
     pub fn is_empty(&self) -> bool {
         self.content.is_empty()
     }
+
+    /// Whether a system prompt carries an injected snapshot.
+    pub fn is_injected(system_prompt: &str) -> bool {
+        system_prompt.contains(PROMPT_TAG)
+    }
 }
 
 /// Truncate content with priority preservation.
@@ -247,6 +263,8 @@ mod tests {
         assert!(section.contains("<memory-context>"));
         assert!(section.contains("</memory-context>"));
         assert!(section.contains("User prefers dark mode"));
+        assert!(MemorySnapshot::is_injected(&format!("You are NeoMind.{}", section)));
+        assert!(!MemorySnapshot::is_injected("You are NeoMind."));
         // Agents and custom files should NOT be in snapshot
         assert!(!section.contains("Agent Experiences"));
     }
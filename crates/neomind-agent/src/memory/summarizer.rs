@@ -0,0 +1,435 @@
+//! Pluggable summarizers for session memory consolidation.
+//!
+//! `MemoryConfig::summarizer` picks how an archived session is condensed
+//! into a [`SessionDigest`]: `llm` asks the session's model for a structured
+//! digest, `extractive` ranks the conversation's own sentences with TextRank
+//! and keeps the most central ones. The extractive summarizer needs no model
+//! call, so it is also the fallback when the LLM fails.
+//!
+//! Conversations often mix Chinese and English ("把 HVAC-1 的 setpoint 调到
+//! 22"). Sentences are split on both `。！？；` and `.!?`, compared on CJK
+//! character bigrams plus lowercased English words, and re-joined with the
+//! spacing and terminal punctuation of their own language.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use neomind_storage::{MemoryConfig, SummarizerKind};
+
+use super::session_summary::{summarize_history, SessionDigest};
+use crate::agent::AgentMessage;
+use crate::error::Result;
+use crate::llm::LlmInterface;
+
+/// Sentences ranked per conversation; the oldest are dropped first.
+const MAX_SENTENCES: usize = 200;
+
+/// Per-sentence cap so one pasted log can't become the summary.
+const SENTENCE_CHAR_CAP: usize = 300;
+
+/// Entities listed in an extractive digest.
+const MAX_ENTITIES: usize = 8;
+
+/// Longest quoted span (「...」, “...”) taken as an entity name.
+const QUOTED_ENTITY_CHARS: usize = 20;
+
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 50;
+const CONVERGENCE: f64 = 1e-6;
+
+/// Punctuation that ends a sentence regardless of what follows.
+const SENTENCE_ENDS: &[char] = &['。', '！', '？', '；', '!', '?', '\n'];
+
+/// Full-width endings, joined to the next sentence without a space.
+const CJK_ENDS: &[char] = &['。', '！', '？', '；'];
+
+const QUOTE_PAIRS: &[(char, char)] = &[('「', '」'), ('“', '”'), ('《', '》'), ('【', '】')];
+
+/// English words that carry no topic.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "can", "could", "did", "do", "does",
+    "for", "from", "had", "has", "have", "here", "how", "if", "in", "is", "it", "its", "just",
+    "me", "my", "no", "not", "of", "ok", "okay", "on", "or", "please", "so", "than", "that",
+    "the", "then", "there", "this", "to", "was", "we", "were", "what", "will", "with", "would",
+    "yes", "you", "your",
+];
+
+/// Condenses a conversation into a [`SessionDigest`].
+#[async_trait]
+pub trait Summarizer: Send + Sync {
+    /// Name used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Summarize a conversation. Returns `None` when there is nothing worth
+    /// summarizing (no user message).
+    async fn summarize(&self, history: &[AgentMessage]) -> Result<Option<SessionDigest>>;
+}
+
+/// The summarizer selected in the memory config.
+pub fn from_config(config: &MemoryConfig, llm: Arc<LlmInterface>) -> Box<dyn Summarizer> {
+    match config.summarizer {
+        SummarizerKind::Llm => Box::new(LlmSummarizer::new(llm)),
+        SummarizerKind::Extractive => {
+            Box::new(ExtractiveSummarizer::new(config.extractive_sentences))
+        }
+    }
+}
+
+/// Structured digest written by the session's LLM.
+pub struct LlmSummarizer {
+    llm: Arc<LlmInterface>,
+}
+
+impl LlmSummarizer {
+    pub fn new(llm: Arc<LlmInterface>) -> Self {
+        Self { llm }
+    }
+}
+
+#[async_trait]
+impl Summarizer for LlmSummarizer {
+    fn name(&self) -> &'static str {
+        "llm"
+    }
+
+    async fn summarize(&self, history: &[AgentMessage]) -> Result<Option<SessionDigest>> {
+        summarize_history(&self.llm, history).await
+    }
+}
+
+/// TextRank over the sentences of the user and assistant turns.
+pub struct ExtractiveSummarizer {
+    max_sentences: usize,
+}
+
+impl ExtractiveSummarizer {
+    pub fn new(max_sentences: usize) -> Self {
+        Self {
+            max_sentences: max_sentences.max(1),
+        }
+    }
+
+    /// The most central sentences in conversation order, plus the
+    /// identifier-like names that come up most often.
+    pub fn digest(&self, history: &[AgentMessage]) -> Option<SessionDigest> {
+        if !history.iter().any(|m| m.role == "user") {
+            return None;
+        }
+        let texts: Vec<&str> = history
+            .iter()
+            .filter(|m| m.role == "user" || m.role == "assistant")
+            .map(|m| m.content.as_ref())
+            .collect();
+
+        let mut sentences: Vec<Sentence> = texts
+            .iter()
+            .flat_map(|text| split_sentences(text))
+            .map(|text| Sentence {
+                terms: terms(&text),
+                text,
+            })
+            .filter(|s| !s.terms.is_empty())
+            .collect();
+        if sentences.len() > MAX_SENTENCES {
+            sentences.drain(..sentences.len() - MAX_SENTENCES);
+        }
+        if sentences.is_empty() {
+            return None;
+        }
+
+        let scores = textrank(&sentences);
+        let mut picked: Vec<usize> = (0..sentences.len()).collect();
+        picked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+        picked.truncate(self.max_sentences);
+        picked.sort_unstable();
+
+        Some(SessionDigest {
+            summary: join_sentences(picked.iter().map(|&i| sentences[i].text.as_str())),
+            entities: extract_entities(&texts),
+        })
+    }
+}
+
+#[async_trait]
+impl Summarizer for ExtractiveSummarizer {
+    fn name(&self) -> &'static str {
+        "extractive"
+    }
+
+    async fn summarize(&self, history: &[AgentMessage]) -> Result<Option<SessionDigest>> {
+        Ok(self.digest(history))
+    }
+}
+
+struct Sentence {
+    text: String,
+    terms: HashSet<String>,
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}' // kana
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}' // hangul
+            | '\u{F900}'..='\u{FAFF}'
+    )
+}
+
+/// Whether a sentence reads as CJK; a CJK character weighs about as much as
+/// a short English word.
+fn is_mostly_cjk(text: &str) -> bool {
+    let cjk = text.chars().filter(|&c| is_cjk(c)).count();
+    let latin = text.chars().filter(|c| c.is_ascii_alphabetic()).count();
+    cjk > 0 && cjk * 3 >= latin
+}
+
+/// Split a message into sentences. A `.` only ends a sentence when followed
+/// by whitespace, so `22.5` and `v1.2` stay whole.
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        let ends = SENTENCE_ENDS.contains(&c)
+            || (c == '.' && chars.peek().is_none_or(|next| next.is_whitespace()));
+        if ends {
+            push_sentence(&mut sentences, &current);
+            current.clear();
+        }
+    }
+    push_sentence(&mut sentences, &current);
+    sentences
+}
+
+fn push_sentence(sentences: &mut Vec<String>, raw: &str) {
+    // Markdown list and heading markers aren't part of the sentence
+    let text = raw
+        .trim()
+        .trim_start_matches(['-', '*', '#', '>', '•'])
+        .trim();
+    if !text.is_empty() {
+        sentences.push(text.chars().take(SENTENCE_CHAR_CAP).collect());
+    }
+}
+
+/// CJK character bigrams and lowercased English words, without stopwords.
+fn terms(text: &str) -> HashSet<String> {
+    let mut terms = HashSet::new();
+    let mut word = String::new();
+    let mut prev_cjk: Option<char> = None;
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut terms);
+            if let Some(prev) = prev_cjk {
+                terms.insert(format!("{}{}", prev, c));
+            }
+            prev_cjk = Some(c);
+            continue;
+        }
+        prev_cjk = None;
+        if c.is_alphanumeric() || c == '-' || c == '_' {
+            word.extend(c.to_lowercase());
+        } else {
+            flush_word(&mut word, &mut terms);
+        }
+    }
+    flush_word(&mut word, &mut terms);
+    terms
+}
+
+fn flush_word(word: &mut String, terms: &mut HashSet<String>) {
+    let w = word.trim_matches(['-', '_']);
+    let keep = !w.is_empty()
+        && !STOPWORDS.contains(&w)
+        && (w.chars().count() > 1 || w.chars().all(|c| c.is_ascii_digit()));
+    if keep {
+        terms.insert(w.to_string());
+    }
+    word.clear();
+}
+
+/// Term overlap normalized by sentence length, as in the TextRank paper.
+fn similarity(a: &Sentence, b: &Sentence) -> f64 {
+    let overlap = a.terms.intersection(&b.terms).count();
+    if overlap == 0 {
+        return 0.0;
+    }
+    let norm = ((a.terms.len() + 1) as f64).ln() + ((b.terms.len() + 1) as f64).ln();
+    overlap as f64 / norm
+}
+
+/// PageRank over the sentence similarity graph.
+fn textrank(sentences: &[Sentence]) -> Vec<f64> {
+    let weights: Vec<Vec<f64>> = sentences
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            sentences
+                .iter()
+                .enumerate()
+                .map(|(j, b)| if i == j { 0.0 } else { similarity(a, b) })
+                .collect()
+        })
+        .collect();
+    let out_sums: Vec<f64> = weights.iter().map(|row| row.iter().sum()).collect();
+
+    let mut scores = vec![1.0; sentences.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let next: Vec<f64> = (0..sentences.len())
+            .map(|i| {
+                let rank: f64 = weights
+                    .iter()
+                    .zip(&out_sums)
+                    .zip(&scores)
+                    .filter(|((_, sum), _)| **sum > 0.0)
+                    .map(|((row, sum), score)| row[i] / sum * score)
+                    .sum();
+                (1.0 - DAMPING) + DAMPING * rank
+            })
+            .collect();
+        let delta = next
+            .iter()
+            .zip(&scores)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        scores = next;
+        if delta < CONVERGENCE {
+            break;
+        }
+    }
+    scores
+}
+
+/// Join sentences, ending each with punctuation of its own language and
+/// spacing only after Latin punctuation.
+fn join_sentences<'a>(sentences: impl Iterator<Item = &'a str>) -> String {
+    let mut out = String::new();
+    for sentence in sentences {
+        if !out.is_empty() && !out.ends_with(CJK_ENDS) {
+            out.push(' ');
+        }
+        out.push_str(sentence);
+        if !sentence.ends_with(SENTENCE_ENDS) && !sentence.ends_with('.') {
+            out.push(if is_mostly_cjk(sentence) { '。' } else { '.' });
+        }
+    }
+    out
+}
+
+/// Identifier-like tokens (`HVAC-1`, `temp_sensor_02`, `AirPurifier`) and
+/// short quoted names, most frequent first.
+fn extract_entities(texts: &[&str]) -> Vec<String> {
+    // First-seen order breaks ties
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    let mut bump = |name: &str| match counts.iter_mut().find(|(n, _)| n == name) {
+        Some((_, count)) => *count += 1,
+        None => counts.push((name.to_string(), 1)),
+    };
+
+    for text in texts {
+        for token in text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_')) {
+            let token = token.trim_matches(['-', '_']);
+            if is_identifier(token) {
+                bump(token);
+            }
+        }
+        for &(open, close) in QUOTE_PAIRS {
+            let mut rest = *text;
+            while let Some(start) = rest.find(open) {
+                let after = &rest[start + open.len_utf8()..];
+                let Some(end) = after.find(close) else {
+                    break;
+                };
+                let name = after[..end].trim();
+                if !name.is_empty() && name.chars().count() <= QUOTED_ENTITY_CHARS {
+                    bump(name);
+                }
+                rest = &after[end + close.len_utf8()..];
+            }
+        }
+    }
+
+    counts.sort_by_key(|c| std::cmp::Reverse(c.1));
+    counts
+        .into_iter()
+        .take(MAX_ENTITIES)
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Names rather than words: letters mixed with digits or joined by `-`/`_`,
+/// acronyms of three or more letters, or camelCase.
+fn is_identifier(token: &str) -> bool {
+    let letters = token.chars().filter(|c| c.is_ascii_alphabetic()).count();
+    if letters == 0 || token.len() < 2 {
+        return false;
+    }
+    let has_digit = token.chars().any(|c| c.is_ascii_digit());
+    let joined = token.contains(['-', '_']);
+    let acronym = letters >= 3 && !token.chars().any(|c| c.is_ascii_lowercase());
+    let camel = token.chars().skip(1).any(|c| c.is_ascii_uppercase())
+        && token.chars().any(|c| c.is_ascii_lowercase());
+    has_digit || joined || acronym || camel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_mixed_language() {
+        assert_eq!(
+            split_sentences("把温度调到22.5度。Then turn on HVAC-1! 好的\n- 已完成"),
+            vec!["把温度调到22.5度。", "Then turn on HVAC-1!", "好的", "已完成"]
+        );
+        assert_eq!(
+            split_sentences("Setpoint is 22.5. Fan v1.2 is idle"),
+            vec!["Setpoint is 22.5.", "Fan v1.2 is idle"]
+        );
+
+        let t = terms("把 HVAC-1 的温度调高, please");
+        assert!(t.contains("hvac-1"));
+        assert!(t.contains("温度"));
+        assert!(t.contains("调高"));
+        assert!(!t.contains("please"));
+    }
+
+    #[test]
+    fn test_extractive_digest() {
+        let summarizer = ExtractiveSummarizer::new(2);
+        assert!(summarizer.digest(&[AgentMessage::assistant("hello")]).is_none());
+
+        let history = vec![
+            AgentMessage::user("HVAC-1 的温度太高了，帮我调低温度"),
+            AgentMessage::assistant(
+                "已将 HVAC-1 的温度设定调到 22 度。Anything else? The weather is sunny",
+            ),
+            AgentMessage::user("Also enable the night-mode rule for HVAC-1"),
+            AgentMessage::assistant("Enabled the night-mode rule for HVAC-1"),
+        ];
+        let digest = summarizer.digest(&history).unwrap();
+        // Off-topic sentences share no terms and rank lowest
+        assert!(!digest.summary.contains("sunny"));
+        assert!(!digest.summary.contains("Anything else"));
+        assert_eq!(digest.entities[0], "HVAC-1");
+        assert!(digest.entities.contains(&"night-mode".to_string()));
+
+        let summary = ExtractiveSummarizer::new(10).digest(&history).unwrap().summary;
+        // Conversation order, CJK sentences terminated with 。 and unspaced
+        assert!(summary.starts_with("HVAC-1 的温度太高了，帮我调低温度。已将"));
+        assert!(summary.ends_with("Enabled the night-mode rule for HVAC-1."));
+    }
+
+    #[test]
+    fn test_entities() {
+        let text = "把「客厅灯」和 temp_sensor_02 关掉, OK? I checked the API";
+        let entities = extract_entities(&[text]);
+        assert_eq!(entities, vec!["temp_sensor_02", "API", "客厅灯"]);
+        assert!(!is_identifier("setpoint"));
+        assert!(is_identifier("AirPurifier"));
+    }
+}
@@ -44,7 +44,7 @@ impl CapabilityIndex {
     }
 
     /// CLI command tree skeleton, auto-generated from clap. Filters to the
-    /// 14 domains that have subcommands; leaf commands (Serve/Prompt/Chat/
+    /// 15 domains that have subcommands; leaf commands (Serve/Prompt/Chat/
     /// Health/Logs/ListModels/CheckUpdate/Login/Logout/Whoami) are
     /// interactive/local or self-evident and are excluded.
     pub fn build_cli_tree() -> String {
@@ -156,7 +156,7 @@ mod tests {
     }
 
     #[test]
-    fn cli_tree_has_14_domains_and_device_history() {
+    fn cli_tree_has_15_domains_and_device_history() {
         let tree = CapabilityIndex::build_cli_tree();
         let domains = [
             "device",
@@ -170,6 +170,7 @@ mod tests {
             "widget",
             "push",
             "connector",
+            "knowledge",
             "settings",
             "system",
             "api-key",
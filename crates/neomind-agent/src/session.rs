@@ -1,6 +1,6 @@
 //! Session manager for multiple agent sessions with persistence.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -10,10 +10,20 @@ use uuid::Uuid;
 
 use crate::llm::LlmInterface;
 
+use neomind_core::tenant::TenantId;
 use neomind_storage::SessionStore;
 
-use super::agent::{Agent, AgentConfig, AgentEvent, AgentMessage, LlmBackend};
+use super::agent::{
+    Agent, AgentConfig, AgentEvent, AgentMessage, ContextStats, LlmBackend, ToolCall,
+};
+use crate::agent::staged::IntentCategory;
 use super::error::{NeoMindError, Result};
+use crate::agent::tokenizer::{estimate_message_tokens, estimate_tokens};
+use crate::memory::session_summary::{self, index_digest};
+use crate::memory::summarizer::{self, ExtractiveSummarizer};
+use crate::memory::SessionDigest;
+use crate::intent_feedback::IntentFeedbackLog;
+use crate::usage::{PriceTable, SessionBudget, SessionUsage, UsageTracker};
 
 // Re-export instance manager for convenience
 pub use crate::llm_backends::{
@@ -42,6 +52,9 @@ pub struct CreateSessionOptions {
     pub model: Option<String>,
     /// Enable or disable tool calling.
     pub enable_tools: Option<bool>,
+    /// Restrict the session to these tools (see
+    /// `SessionManager::create_session_with_tools`).
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 /// Convert an LlmBackendInstance to LlmBackend enum for agent configuration.
@@ -179,6 +192,98 @@ pub struct SessionInfo {
     /// Whether memory injection is enabled
     #[serde(rename = "memoryEnabled", default)]
     pub memory_enabled: bool,
+    /// Session this one was forked from
+    #[serde(
+        rename = "parentSessionId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub parent_session_id: Option<String>,
+    /// Tenant that owns the session
+    #[serde(
+        rename = "tenantId",
+        default,
+        skip_serializing_if = "TenantId::is_default"
+    )]
+    pub tenant_id: TenantId,
+    /// Tools the session is restricted to, if any
+    #[serde(rename = "allowedTools", skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+}
+
+/// Session temp files the memory tool writes (`sessions/{id}/{target}.md`).
+const SESSION_FILE_TARGETS: [&str; 3] = ["scratch", "notes", "todo"];
+
+/// Everything stored for one session, as returned by the export API.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExport {
+    pub session_id: String,
+    /// Creation time (Unix seconds)
+    pub created_at: Option<i64>,
+    pub metadata: neomind_storage::SessionMetadata,
+    pub messages: Vec<AgentMessage>,
+    /// Tool calls made in the session, in order, with their results
+    pub tool_calls: Vec<ToolCall>,
+    pub memories: SessionMemories,
+    pub usage: Option<SessionUsage>,
+    pub exported_at: i64,
+}
+
+/// Memories derived from a session.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMemories {
+    /// The session's entry in the `session-history` memory file
+    pub summary_entry: Option<String>,
+    /// Session temp files by target (scratch, notes, todo)
+    pub temp_files: BTreeMap<String, String>,
+}
+
+/// Context diagnostics of a session, as returned by the context API.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextHealth {
+    pub session_id: String,
+    /// Messages in the session history
+    pub history_messages: usize,
+    /// Estimated tokens of the whole history
+    pub history_tokens: usize,
+    /// Last message index covered by the conversation summary
+    pub summary_up_to_index: Option<u64>,
+    pub memory_enabled: bool,
+    /// Whether the memory snapshot is in the session's system prompt
+    pub memory_snapshot_loaded: bool,
+    /// Turns streamed since the server started
+    pub turns: u64,
+    /// Tool result cache hits and misses over those turns
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// How the latest turn's context was assembled
+    pub last_turn: Option<ContextStats>,
+}
+
+/// Context stats collected from a session's streamed turns.
+#[derive(Debug, Clone, Default)]
+struct ContextRecord {
+    turns: u64,
+    cache_hits: u64,
+    cache_misses: u64,
+    last: Option<ContextStats>,
+}
+
+type ContextRecordMap = Arc<RwLock<HashMap<String, ContextRecord>>>;
+
+/// What a hard delete removed.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReport {
+    pub session_id: String,
+    pub messages: usize,
+    pub summary_entry_removed: bool,
+    pub temp_files_removed: bool,
+    /// Intent predictions recorded for the session
+    pub intent_examples: usize,
 }
 
 /// Type alias for the cancel-sender map shared between manager and stream wrappers.
@@ -262,6 +367,15 @@ pub struct SessionManager {
     /// extension. A slow subscriber that fills its channel just drops events
     /// (deliberate: voice workloads should never accumulate backlog).
     event_subscribers: Arc<RwLock<HashMap<String, Vec<tokio::sync::mpsc::Sender<AgentEvent>>>>>,
+    /// Token usage and cost per session
+    usage: Arc<UsageTracker>,
+    /// Intent predictions and the feedback on them
+    intents: Arc<IntentFeedbackLog>,
+    /// Context stats of the latest turns per session
+    context_records: ContextRecordMap,
+    /// Backend selected per session via `configure_agent_by_backend_id`;
+    /// sessions without an entry use the active backend.
+    session_backends: Arc<RwLock<HashMap<String, String>>>,
 }
 
 impl SessionManager {
@@ -285,6 +399,8 @@ impl SessionManager {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             session_messages: Arc::new(RwLock::new(HashMap::new())),
+            intents: Arc::new(IntentFeedbackLog::new(store.clone())),
+            context_records: Arc::new(RwLock::new(HashMap::new())),
             store,
             default_config: AgentConfig::default(),
             default_llm_backend: Arc::new(RwLock::new(None)),
@@ -292,6 +408,8 @@ impl SessionManager {
             skill_registry: crate::skills::create_shared_registry(None),
             cancel_senders: Arc::new(RwLock::new(HashMap::new())),
             event_subscribers: Arc::new(RwLock::new(HashMap::new())),
+            usage: Arc::new(UsageTracker::new(PriceTable::from_env())),
+            session_backends: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -305,6 +423,8 @@ impl SessionManager {
         let manager = Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             session_messages: Arc::new(RwLock::new(HashMap::new())),
+            intents: Arc::new(IntentFeedbackLog::new(store.clone())),
+            context_records: Arc::new(RwLock::new(HashMap::new())),
             store,
             default_config: AgentConfig::default(),
             default_llm_backend: Arc::new(RwLock::new(None)),
@@ -312,6 +432,8 @@ impl SessionManager {
             skill_registry: crate::skills::create_shared_registry(Some(data_dir)),
             cancel_senders: Arc::new(RwLock::new(HashMap::new())),
             event_subscribers: Arc::new(RwLock::new(HashMap::new())),
+            usage: Arc::new(UsageTracker::new(PriceTable::from_env())),
+            session_backends: Arc::new(RwLock::new(HashMap::new())),
         };
 
         // Restore sessions from database on startup
@@ -328,6 +450,12 @@ impl SessionManager {
             );
         }
 
+        match manager.intents.prune() {
+            Ok(0) => {}
+            Ok(pruned) => tracing::info!(pruned, "Pruned unlabeled intent predictions"),
+            Err(e) => tracing::warn!(error = %e, "Failed to prune intent predictions"),
+        }
+
         tracing::info!(message = "SessionManager initialized with persistent storage");
 
         Ok(manager)
@@ -590,7 +718,111 @@ impl SessionManager {
         );
 
         let agent = self.get_session(session_id).await?;
-        agent.configure_llm(backend).await
+        agent.configure_llm(backend).await?;
+        self.session_backends
+            .write()
+            .await
+            .insert(session_id.to_string(), backend_id.to_string());
+        Ok(())
+    }
+
+    /// Token usage tracker shared by all sessions.
+    pub fn usage_tracker(&self) -> Arc<UsageTracker> {
+        self.usage.clone()
+    }
+
+    /// Intent predictions recorded for streamed turns and their feedback.
+    pub fn intent_feedback(&self) -> Arc<IntentFeedbackLog> {
+        self.intents.clone()
+    }
+
+    /// Context diagnostics of a session: history size, summary and memory
+    /// state, and how the latest streamed turn's context was assembled.
+    pub async fn context_health(&self, session_id: &str) -> Result<ContextHealth> {
+        let agent = self.get_session(session_id).await?;
+        let history = agent.history().await;
+        let metadata = self
+            .store
+            .get_session_metadata(session_id)
+            .unwrap_or_default();
+        let record = self
+            .context_records
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(ContextHealth {
+            session_id: session_id.to_string(),
+            history_messages: history.len(),
+            history_tokens: history.iter().map(estimate_message_tokens).sum(),
+            summary_up_to_index: metadata
+                .summary_up_to_index
+                .filter(|_| metadata.conversation_summary.is_some()),
+            memory_enabled: metadata.memory_enabled,
+            memory_snapshot_loaded: agent.has_memory_snapshot(),
+            turns: record.turns,
+            cache_hits: record.cache_hits,
+            cache_misses: record.cache_misses,
+            last_turn: record.last,
+        })
+    }
+
+    /// Token usage and estimated cost of a session, if it has any.
+    pub fn get_session_usage(&self, session_id: &str) -> Option<SessionUsage> {
+        self.usage.get(session_id)
+    }
+
+    /// Set the hard budget applied to every session.
+    pub fn set_session_budget(&self, budget: SessionBudget) {
+        self.usage.set_budget(budget);
+    }
+
+    /// Backend id and model a session's next turn will be billed to.
+    async fn session_backend(&self, session_id: &str) -> (String, String) {
+        let manager = get_instance_manager().ok();
+        let backend_id = match self.session_backends.read().await.get(session_id) {
+            Some(id) => Some(id.clone()),
+            None => manager
+                .as_ref()
+                .and_then(|m| m.get_active_instance())
+                .map(|i| i.id),
+        };
+        let Some(backend_id) = backend_id else {
+            return ("default".to_string(), "unknown".to_string());
+        };
+        let model = manager
+            .and_then(|m| m.get_instance(&backend_id))
+            .map(|i| i.model)
+            .unwrap_or_else(|| "unknown".to_string());
+        (backend_id, model)
+    }
+
+    /// Move an over-budget session to the fallback backend, or reject the
+    /// turn when no fallback is configured.
+    async fn enforce_budget(&self, session_id: &str) -> Result<()> {
+        if !self.usage.is_over_budget(session_id) {
+            return Ok(());
+        }
+        let Some(fallback) = self.usage.budget().fallback_backend else {
+            return Err(NeoMindError::Validation(format!(
+                "Session {} has exceeded its token budget",
+                session_id
+            )));
+        };
+        let current = self.session_backends.read().await.get(session_id).cloned();
+        if current.as_deref() != Some(fallback.as_str()) {
+            tracing::info!(
+                session_id = %session_id,
+                fallback_backend = %fallback,
+                "Session exceeded its token budget, switching to fallback backend"
+            );
+            self.configure_agent_by_backend_id(session_id, &fallback)
+                .await?;
+            self.usage.mark_downgraded(session_id);
+        }
+        Ok(())
     }
 
     /// Set the tool registry for all new sessions.
@@ -722,6 +954,7 @@ impl SessionManager {
     /// with `default_config` happens here.
     pub async fn create_session_with_config(&self, config: AgentConfig) -> Result<String> {
         let session_id = Uuid::new_v4().to_string();
+        let allowed_tools = config.allowed_tools.clone();
 
         // Use tool registry if set, otherwise create default mock tools
         let tool_registry = self.tool_registry.read().await.clone();
@@ -758,9 +991,43 @@ impl SessionManager {
         // Save session ID to database
         self.save_session_id(&session_id)?;
 
+        // The allowlist must survive a restart, or a restored session would
+        // come back with every tool
+        if allowed_tools.is_some() {
+            let metadata = neomind_storage::SessionMetadata {
+                allowed_tools,
+                ..Default::default()
+            };
+            self.store
+                .save_session_metadata(&session_id, &metadata)
+                .map_err(|e| {
+                    NeoMindError::Storage(format!("Failed to save session metadata: {}", e))
+                })?;
+        }
+
         Ok(session_id)
     }
 
+    /// Create a new session that may only see and call the tools in
+    /// `allowlist` (e.g. a public kiosk session that can query data but never
+    /// control devices). Everything else comes from `default_config`.
+    ///
+    /// Disallowed tools are left out of the tool definitions and prompt sent
+    /// to the LLM, and the session's registry refuses to execute them. The
+    /// allowlist is persisted with the session and inherited by forks. Note
+    /// that allowing `shell` grants the whole `neomind` CLI.
+    pub async fn create_session_with_tools<I, S>(&self, allowlist: I) -> Result<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.create_session_with_options(CreateSessionOptions {
+            allowed_tools: Some(allowlist.into_iter().map(Into::into).collect()),
+            ..Default::default()
+        })
+        .await
+    }
+
     /// Create a new session with the supplied options merged on top of the
     /// manager's `default_config`. Fields left `None` in `opts` fall back to
     /// the default. Use this when you need per-session overrides (e.g. a
@@ -787,6 +1054,9 @@ impl SessionManager {
         if let Some(et) = opts.enable_tools {
             cfg.enable_tools = et;
         }
+        if let Some(tools) = opts.allowed_tools {
+            cfg.allowed_tools = Some(tools);
+        }
         cfg
     }
 
@@ -815,13 +1085,20 @@ impl SessionManager {
     async fn restore_session_from_db(&self, session_id: &str) -> Result<Arc<Agent>> {
         tracing::info!(session_id = %session_id, message = "Restoring session from database");
 
+        let mut config = self.default_config.clone();
+        config.allowed_tools = self
+            .store
+            .get_session_metadata(session_id)
+            .ok()
+            .and_then(|m| m.allowed_tools);
+
         // Use tool registry if set, otherwise create default agent
         let tool_registry = self.tool_registry.read().await.clone();
 
         let agent = if let Some(tools) = tool_registry {
-            Agent::with_tools(self.default_config.clone(), session_id.to_string(), tools)
+            Agent::with_tools(config, session_id.to_string(), tools)
         } else {
-            Agent::new(self.default_config.clone(), session_id.to_string())
+            Agent::new(config, session_id.to_string())
         };
 
         // Configure LLM if a default backend is set
@@ -967,6 +1244,9 @@ impl SessionManager {
         // Remove from memory (if present)
         self.sessions.write().await.remove(session_id);
         self.session_messages.write().await.remove(session_id);
+        self.session_backends.write().await.remove(session_id);
+        self.context_records.write().await.remove(session_id);
+        self.usage.remove(session_id);
 
         // Remove from database
         tracing::debug!(" Deleting from database...");
@@ -976,6 +1256,332 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Fork a session at `at_message_index`.
+    ///
+    /// Creates a new session whose history is a copy of the source session's
+    /// messages `0..=at_message_index`, so a conversation can continue down a
+    /// different path without touching the original. The new session records
+    /// its parent in `SessionMetadata` and inherits the parent's LLM backend,
+    /// memory setting and tool allowlist.
+    pub async fn fork_session(&self, session_id: &str, at_message_index: usize) -> Result<String> {
+        let source = self.get_session(session_id).await?;
+        let history = source.history().await;
+        if at_message_index >= history.len() {
+            return Err(NeoMindError::Validation(format!(
+                "Message index {} out of range (session has {} messages)",
+                at_message_index,
+                history.len()
+            )));
+        }
+        let forked_history = history[..=at_message_index].to_vec();
+
+        let fork_id = self
+            .create_session_with_options(CreateSessionOptions {
+                allowed_tools: source.allowed_tools().map(<[String]>::to_vec),
+                ..Default::default()
+            })
+            .await?;
+        let fork = self.get_session(&fork_id).await?;
+        fork.restore_history(forked_history.clone()).await;
+        self.save_history(&fork_id, &forked_history)?;
+        self.session_messages
+            .write()
+            .await
+            .insert(fork_id.clone(), forked_history);
+
+        let backend_id = self.session_backends.read().await.get(session_id).cloned();
+        if let Some(backend_id) = backend_id {
+            if let Err(e) = self
+                .configure_agent_by_backend_id(&fork_id, &backend_id)
+                .await
+            {
+                tracing::warn!(
+                    session_id = %fork_id,
+                    backend_id = %backend_id,
+                    error = %e,
+                    "Failed to carry LLM backend over to forked session"
+                );
+            }
+        }
+
+        let parent = self
+            .store
+            .get_session_metadata(session_id)
+            .unwrap_or_default();
+        // The parent's compression summary only applies if it covers nothing
+        // past the fork point.
+        let summary_applies = parent
+            .summary_up_to_index
+            .is_some_and(|idx| idx <= at_message_index as u64);
+        let metadata = neomind_storage::SessionMetadata {
+            title: parent.title.map(|t| format!("{} (fork)", t)),
+            memory_enabled: parent.memory_enabled,
+            conversation_summary: parent.conversation_summary.filter(|_| summary_applies),
+            summary_up_to_index: parent.summary_up_to_index.filter(|_| summary_applies),
+            preview: parent.preview,
+            parent_session_id: Some(session_id.to_string()),
+            forked_at_index: Some(at_message_index as u64),
+            archive_summary: None,
+            key_entities: Vec::new(),
+            summarized_at: None,
+            tenant_id: parent.tenant_id,
+            allowed_tools: parent.allowed_tools,
+        };
+        self.store
+            .save_session_metadata(&fork_id, &metadata)
+            .map_err(|e| {
+                NeoMindError::Storage(format!("Failed to save session metadata: {}", e))
+            })?;
+
+        tracing::info!(
+            parent = %session_id,
+            session_id = %fork_id,
+            at_message_index,
+            "Forked session"
+        );
+        Ok(fork_id)
+    }
+
+    /// Summarize a session for later recall.
+    ///
+    /// The summary and key entities are stored in `SessionMetadata` and, when
+    /// memory is enabled for the session, prepended to the `session-history`
+    /// memory file. Returns `None` when the session has nothing to summarize or
+    /// was already summarized after its last message.
+    pub async fn summarize_session(&self, session_id: &str) -> Result<Option<SessionDigest>> {
+        let agent = self.get_session(session_id).await?;
+        let history = agent.history().await;
+        let last_message_at = history.last().map(|m| m.timestamp).unwrap_or(0);
+
+        let mut metadata = self
+            .store
+            .get_session_metadata(session_id)
+            .unwrap_or_default();
+        if metadata
+            .summarized_at
+            .is_some_and(|at| at >= last_message_at)
+        {
+            return Ok(None);
+        }
+
+        let config = neomind_storage::MemoryConfig::load();
+        let summarizer = summarizer::from_config(&config, agent.llm_interface());
+        let digest = match summarizer.summarize(&history).await {
+            Ok(digest) => digest,
+            Err(e) => {
+                tracing::warn!(
+                    session_id = %session_id,
+                    summarizer = summarizer.name(),
+                    error = %e,
+                    "Session summary failed, falling back to extractive"
+                );
+                ExtractiveSummarizer::new(config.extractive_sentences).digest(&history)
+            }
+        };
+        let Some(digest) = digest else {
+            return Ok(None);
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        metadata.archive_summary = Some(digest.summary.clone());
+        metadata.key_entities = digest.entities.clone();
+        metadata.summarized_at = Some(now);
+        self.store
+            .save_session_metadata(session_id, &metadata)
+            .map_err(|e| {
+                NeoMindError::Storage(format!("Failed to save session metadata: {}", e))
+            })?;
+
+        if metadata.memory_enabled && config.enabled {
+            let max_chars = config.agent_char_limit;
+            let store = neomind_storage::MarkdownMemoryStore::with_config(
+                config.storage_path.clone(),
+                config,
+            );
+            if let Err(e) = index_digest(
+                &store,
+                session_id,
+                metadata.title.as_deref(),
+                &digest,
+                now,
+                max_chars,
+            ) {
+                tracing::warn!(session_id = %session_id, error = %e, "Failed to index session summary into memory");
+            }
+        }
+
+        tracing::info!(
+            session_id = %session_id,
+            entities = digest.entities.len(),
+            "Summarized session"
+        );
+        Ok(Some(digest))
+    }
+
+    /// Archive a session: summarize it, persist its history and unload it
+    /// from memory. The session stays in the database and is restored on the
+    /// next access.
+    pub async fn archive_session(&self, session_id: &str) -> Result<Option<SessionDigest>> {
+        let digest = match self.summarize_session(session_id).await {
+            Ok(digest) => digest,
+            Err(NeoMindError::NotFound(msg)) => return Err(NeoMindError::NotFound(msg)),
+            Err(e) => {
+                tracing::warn!(session_id = %session_id, error = %e, "Session summary failed, archiving without it");
+                None
+            }
+        };
+
+        self.persist_history(session_id).await?;
+        self.sessions.write().await.remove(session_id);
+        self.session_messages.write().await.remove(session_id);
+        Ok(digest)
+    }
+
+    /// Sessions forked directly from `session_id`.
+    pub fn list_forks(&self, session_id: &str) -> Result<Vec<String>> {
+        self.store
+            .list_child_sessions(session_id)
+            .map_err(|e| NeoMindError::Storage(format!("Failed to list forks: {}", e)))
+    }
+
+    /// Export everything stored for a session: metadata, messages, tool
+    /// calls, usage and the memories derived from it.
+    pub async fn export_session(&self, session_id: &str) -> Result<SessionExport> {
+        let in_memory = self.sessions.read().await.contains_key(session_id);
+        let in_db = self
+            .store
+            .session_exists(session_id)
+            .map_err(|e| NeoMindError::Storage(format!("Failed to check session: {}", e)))?;
+        if !in_memory && !in_db {
+            return Err(NeoMindError::NotFound(format!("Session: {}", session_id)));
+        }
+
+        let messages = self.get_history(session_id).await?;
+        let tool_calls = messages
+            .iter()
+            .filter_map(|m| m.tool_calls.as_ref())
+            .flatten()
+            .cloned()
+            .collect();
+
+        let memory = memory_store();
+        let summary_entry = session_summary::session_entry(&memory, session_id)
+            .map_err(|e| NeoMindError::Storage(format!("Failed to read memory: {}", e)))?;
+        let mut temp_files = BTreeMap::new();
+        for target in SESSION_FILE_TARGETS {
+            let content = memory
+                .read_session_file(session_id, target)
+                .await
+                .map_err(|e| NeoMindError::Storage(format!("Failed to read memory: {}", e)))?;
+            if !content.is_empty() {
+                temp_files.insert(target.to_string(), content);
+            }
+        }
+
+        Ok(SessionExport {
+            session_id: session_id.to_string(),
+            created_at: self.store.get_session_timestamp(session_id).ok().flatten(),
+            metadata: self
+                .store
+                .get_session_metadata(session_id)
+                .unwrap_or_default(),
+            messages,
+            tool_calls,
+            memories: SessionMemories {
+                summary_entry,
+                temp_files,
+            },
+            usage: self.usage.get(session_id),
+            exported_at: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// Hard-delete a session and everything derived from it: history,
+    /// metadata, pending stream state, usage, its `session-history` memory
+    /// entry and temp files. Checks afterwards that nothing is left and fails
+    /// if anything is.
+    pub async fn purge_session(&self, session_id: &str) -> Result<PurgeReport> {
+        let messages = self.store.message_count(session_id).unwrap_or(0);
+        self.remove_session(session_id).await?;
+        self.store
+            .delete_pending_stream(session_id)
+            .map_err(|e| NeoMindError::Storage(format!("Failed to delete stream state: {}", e)))?;
+        let intent_examples = self.intents.forget_session(session_id)?;
+
+        let memory = memory_store();
+        let summary_entry_removed = session_summary::forget_session(&memory, session_id)
+            .map_err(|e| NeoMindError::Storage(format!("Failed to update memory: {}", e)))?;
+        let temp_files_removed = memory
+            .delete_session_files(session_id)
+            .map_err(|e| NeoMindError::Storage(format!("Failed to delete memory: {}", e)))?;
+
+        let leftover = self.store.session_exists(session_id).unwrap_or(true)
+            || self.store.message_count(session_id).unwrap_or(1) > 0
+            || !matches!(self.store.get_pending_stream(session_id), Ok(None))
+            || !matches!(session_summary::session_entry(&memory, session_id), Ok(None));
+        if leftover {
+            return Err(NeoMindError::Storage(format!(
+                "Session {} still has stored data after purge",
+                session_id
+            )));
+        }
+
+        tracing::info!(
+            target: "neomind::audit",
+            session_id = %session_id,
+            messages,
+            summary_entry_removed,
+            temp_files_removed,
+            intent_examples,
+            "Session purged"
+        );
+        Ok(PurgeReport {
+            session_id: session_id.to_string(),
+            messages,
+            summary_entry_removed,
+            temp_files_removed,
+            intent_examples,
+        })
+    }
+
+    /// Purge sessions with no activity for `retention_days` (0 keeps
+    /// everything). Returns the number of sessions purged.
+    pub async fn purge_expired_sessions(&self, retention_days: u64) -> usize {
+        if retention_days == 0 {
+            return 0;
+        }
+        let cutoff = chrono::Utc::now().timestamp() - retention_days as i64 * 86400;
+
+        let mut purged = 0;
+        for session_id in self.list_sessions().await {
+            let loaded = self.sessions.read().await.get(&session_id).cloned();
+            let last_message_at = match loaded {
+                Some(agent) => agent.history().await.last().map(|m| m.timestamp),
+                None => self
+                    .load_history(&session_id)
+                    .ok()
+                    .and_then(|h| h.last().map(|m| m.timestamp)),
+            };
+            let last_activity = last_message_at
+                .or_else(|| self.store.get_session_timestamp(&session_id).ok().flatten());
+            if last_activity.is_none_or(|at| at >= cutoff) {
+                continue;
+            }
+            match self.purge_session(&session_id).await {
+                Ok(_) => purged += 1,
+                Err(e) => tracing::warn!(
+                    session_id = %session_id,
+                    error = %e,
+                    "Failed to purge expired session"
+                ),
+            }
+        }
+        if purged > 0 {
+            tracing::info!(purged, retention_days, "Purged expired chat sessions");
+        }
+        purged
+    }
+
     /// Update session title.
     pub async fn update_session_title(
         &self,
@@ -1009,6 +1615,30 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Assign the session to a tenant. Called right after creation; the
+    /// tenant is inherited by forks.
+    pub async fn set_session_tenant(&self, session_id: &str, tenant_id: TenantId) -> Result<()> {
+        let mut metadata = self
+            .store
+            .get_session_metadata(session_id)
+            .unwrap_or_default();
+        metadata.tenant_id = tenant_id;
+
+        self.store
+            .save_session_metadata(session_id, &metadata)
+            .map_err(|e| {
+                NeoMindError::Storage(format!("Failed to save session metadata: {}", e))
+            })
+    }
+
+    /// Tenant that owns the session (the default tenant if unknown).
+    pub fn session_tenant(&self, session_id: &str) -> TenantId {
+        self.store
+            .get_session_metadata(session_id)
+            .map(|m| m.tenant_id)
+            .unwrap_or_default()
+    }
+
     /// Toggle memory enabled state for a session.
     pub async fn toggle_memory(&self, session_id: &str, enabled: bool) -> Result<bool> {
         // Check if session exists
@@ -1102,6 +1732,9 @@ impl SessionManager {
                 title,
                 preview,
                 memory_enabled: metadata.memory_enabled,
+                parent_session_id: metadata.parent_session_id,
+                tenant_id: metadata.tenant_id,
+                allowed_tools: metadata.allowed_tools,
             });
         }
 
@@ -1155,6 +1788,9 @@ impl SessionManager {
                 title,
                 preview,
                 memory_enabled: metadata.memory_enabled,
+                parent_session_id: metadata.parent_session_id,
+                tenant_id: metadata.tenant_id,
+                allowed_tools: metadata.allowed_tools,
             });
         }
 
@@ -1254,6 +1890,7 @@ impl SessionManager {
         session_id: &str,
         message: &str,
     ) -> Result<Pin<Box<dyn Stream<Item = AgentEvent> + Send>>> {
+        self.enforce_budget(session_id).await?;
         let agent = self.get_session(session_id).await?;
 
         // Load memory snapshot if enabled and not yet loaded
@@ -1284,6 +1921,11 @@ impl SessionManager {
 
         let session_id_owned = session_id.to_string();
         let cancel_senders = self.cancel_senders.clone();
+        let usage = self.usage.clone();
+        let intents = self.intents.clone();
+        let context_records = self.context_records.clone();
+        let user_message = message.to_string();
+        let (backend_id, model) = self.session_backend(session_id).await;
 
         let stream = agent
             .process_stream_events_with_safeguards(
@@ -1301,7 +1943,15 @@ impl SessionManager {
         let cleanup_stream = Box::pin(async_stream::stream! {
             let mut guard = CancelSenderGuard::new(cancel_senders.clone(), session_id_owned.clone());
             let mut stream = stream;
+            let mut completion_tokens = 0u64;
             while let Some(event) = stream.next().await {
+                if let Some(prompt_tokens) = observe_usage(&event, &mut completion_tokens) {
+                    usage.record(&session_id_owned, &backend_id, &model, prompt_tokens, completion_tokens);
+                }
+                observe_intent(&intents, &session_id_owned, &user_message, &event);
+                if let AgentEvent::ContextStats { stats } = &event {
+                    record_context_stats(&context_records, &session_id_owned, stats).await;
+                }
                 yield event;
             }
             // Stream ended naturally — remove the cancel sender inline.
@@ -1459,6 +2109,8 @@ impl SessionManager {
         message: &str,
         images: Vec<String>,
     ) -> Result<Pin<Box<dyn Stream<Item = super::agent::AgentEvent> + Send>>> {
+        self.enforce_budget(session_id).await?;
+
         // Check if images are provided and model supports vision
         if !images.is_empty() {
             let agent = self.get_session(session_id).await?;
@@ -1479,6 +2131,11 @@ impl SessionManager {
 
         let session_id_owned = session_id.to_string();
         let cancel_senders = self.cancel_senders.clone();
+        let usage = self.usage.clone();
+        let intents = self.intents.clone();
+        let context_records = self.context_records.clone();
+        let user_message = message.to_string();
+        let (backend_id, model) = self.session_backend(session_id).await;
 
         let stream = agent
             .process_multimodal_stream_events_with_safeguards(message, images, safeguards)
@@ -1489,7 +2146,15 @@ impl SessionManager {
         let cleanup_stream = Box::pin(async_stream::stream! {
             let mut guard = CancelSenderGuard::new(cancel_senders.clone(), session_id_owned.clone());
             let mut stream = stream;
+            let mut completion_tokens = 0u64;
             while let Some(event) = stream.next().await {
+                if let Some(prompt_tokens) = observe_usage(&event, &mut completion_tokens) {
+                    usage.record(&session_id_owned, &backend_id, &model, prompt_tokens, completion_tokens);
+                }
+                observe_intent(&intents, &session_id_owned, &user_message, &event);
+                if let AgentEvent::ContextStats { stats } = &event {
+                    record_context_stats(&context_records, &session_id_owned, stats).await;
+                }
                 yield event;
             }
             cancel_senders.write().await.remove(&session_id_owned);
@@ -1650,16 +2315,23 @@ impl SessionManager {
     /// Clean up inactive sessions (older than specified seconds).
     pub async fn cleanup_inactive(&self, max_age_seconds: i64) -> usize {
         let now = chrono::Utc::now().timestamp();
-        let mut sessions = self.sessions.write().await;
         let mut to_remove = Vec::new();
 
-        for (id, agent) in sessions.iter() {
+        for (id, agent) in self.sessions.read().await.iter() {
             let state = agent.state().await;
             if now - state.last_activity > max_age_seconds {
                 to_remove.push(id.clone());
             }
         }
 
+        // Summarize before the session is dropped so it stays recallable.
+        for id in &to_remove {
+            if let Err(e) = self.summarize_session(id).await {
+                tracing::warn!(session_id = %id, error = %e, "Failed to summarize idle session");
+            }
+        }
+
+        let mut sessions = self.sessions.write().await;
         for id in &to_remove {
             sessions.remove(id);
             self.session_messages.write().await.remove(id);
@@ -1670,25 +2342,85 @@ impl SessionManager {
     }
 }
 
+/// The markdown memory store session summaries and temp files live in.
+fn memory_store() -> neomind_storage::MarkdownMemoryStore {
+    let config = neomind_storage::MemoryConfig::load();
+    neomind_storage::MarkdownMemoryStore::with_config(config.storage_path.clone(), config)
+}
+
+/// Accumulate completion tokens from a streamed event; returns the turn's
+/// prompt tokens once it ends.
+fn observe_usage(event: &AgentEvent, completion_tokens: &mut u64) -> Option<u64> {
+    match event {
+        AgentEvent::Content { content } => {
+            *completion_tokens += estimate_tokens(content) as u64;
+            None
+        }
+        AgentEvent::End { prompt_tokens } => Some(prompt_tokens.unwrap_or(0) as u64),
+        _ => None,
+    }
+}
+
+/// Keep a turn's context stats for the context API.
+async fn record_context_stats(records: &ContextRecordMap, session_id: &str, stats: &ContextStats) {
+    let mut records = records.write().await;
+    let record = records.entry(session_id.to_string()).or_default();
+    record.turns += 1;
+    record.cache_hits += stats.cache_hits;
+    record.cache_misses += stats.cache_misses;
+    record.last = Some(stats.clone());
+}
+
+/// Record the intent prediction announced for a turn.
+fn observe_intent(
+    intents: &IntentFeedbackLog,
+    session_id: &str,
+    message: &str,
+    event: &AgentEvent,
+) {
+    let AgentEvent::Intent {
+        category,
+        confidence,
+        keywords,
+        ..
+    } = event
+    else {
+        return;
+    };
+    let Some(category) = IntentCategory::from_name(category) else {
+        return;
+    };
+    let confidence = confidence.unwrap_or(0.0);
+    let keywords = keywords.clone().unwrap_or_default();
+    if let Err(e) = intents.record(session_id, message, &category, confidence, keywords) {
+        tracing::warn!(session_id = %session_id, error = %e, "Failed to record intent prediction");
+    }
+}
+
 impl Default for SessionManager {
     fn default() -> Self {
         Self::new().unwrap_or_else(|e| {
             tracing::error!(error = %e, "Failed to create SessionManager, using in-memory only");
+            let store = SessionStore::open(":memory:").unwrap_or_else(|_| {
+                // Fallback to temp file if :memory: fails
+                let temp_path = std::env::temp_dir()
+                    .join(format!("sessions_fallback_{}.redb", uuid::Uuid::new_v4()));
+                SessionStore::open(&temp_path).expect("Failed to create fallback session store")
+            });
             Self {
                 sessions: Arc::new(RwLock::new(HashMap::new())),
                 session_messages: Arc::new(RwLock::new(HashMap::new())),
-                store: SessionStore::open(":memory:").unwrap_or_else(|_| {
-                    // Fallback to temp file if :memory: fails
-                    let temp_path = std::env::temp_dir()
-                        .join(format!("sessions_fallback_{}.redb", uuid::Uuid::new_v4()));
-                    SessionStore::open(&temp_path).expect("Failed to create fallback session store")
-                }),
+                intents: Arc::new(IntentFeedbackLog::new(store.clone())),
+                context_records: Arc::new(RwLock::new(HashMap::new())),
+                store,
                 default_config: AgentConfig::default(),
                 default_llm_backend: Arc::new(RwLock::new(None)),
                 tool_registry: Arc::new(RwLock::new(None)),
                 skill_registry: crate::skills::create_shared_registry(None),
                 cancel_senders: Arc::new(RwLock::new(HashMap::new())),
                 event_subscribers: Arc::new(RwLock::new(HashMap::new())),
+                usage: Arc::new(UsageTracker::new(PriceTable::from_env())),
+                session_backends: Arc::new(RwLock::new(HashMap::new())),
             }
         })
     }
@@ -1744,8 +2476,9 @@ mod tests {
         let opts = CreateSessionOptions {
             system_prompt: Some("voice-assistant mode: short replies".to_string()),
             temperature: Some(0.1),
-            model: None,        // inherit default
-            enable_tools: None, // inherit default
+            model: None,         // inherit default
+            enable_tools: None,  // inherit default
+            allowed_tools: None, // inherit default
         };
 
         let session_id = manager.create_session_with_options(opts).await.unwrap();
@@ -1809,6 +2542,98 @@ mod tests {
         assert_eq!(sp, "original prompt");
     }
 
+    #[tokio::test]
+    async fn test_fork_session() {
+        let manager = create_temp_manager();
+        let parent = manager.create_session().await.unwrap();
+        let agent = manager.get_session(&parent).await.unwrap();
+        agent
+            .restore_history(vec![
+                AgentMessage::user("turn on the fan"),
+                AgentMessage::assistant("Fan is on"),
+                AgentMessage::user("set it to high"),
+                AgentMessage::assistant("Fan set to high"),
+            ])
+            .await;
+
+        let fork = manager.fork_session(&parent, 1).await.unwrap();
+        let fork_history = manager.get_history(&fork).await.unwrap();
+        assert_eq!(fork_history.len(), 2);
+        assert_eq!(&*fork_history[1].content, "Fan is on");
+        // The original conversation is untouched.
+        assert_eq!(manager.get_history(&parent).await.unwrap().len(), 4);
+
+        let metadata = manager.session_store().get_session_metadata(&fork).unwrap();
+        assert_eq!(metadata.parent_session_id.as_deref(), Some(parent.as_str()));
+        assert_eq!(metadata.forked_at_index, Some(1));
+        assert_eq!(manager.list_forks(&parent).unwrap(), vec![fork]);
+
+        assert!(manager.fork_session(&parent, 4).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_session_with_tools_restricts_and_persists() {
+        async fn offered_tools(agent: &Agent) -> Vec<String> {
+            agent.update_tool_definitions().await;
+            let defs = agent.llm_interface().get_tool_definitions().await;
+            defs.into_iter().map(|d| d.name).collect()
+        }
+
+        let manager = create_temp_manager();
+        let open = manager.create_session().await.unwrap();
+        let open_tools = offered_tools(&manager.get_session(&open).await.unwrap()).await;
+        assert!(open_tools.iter().any(|t| t == "confirm_action"));
+
+        let kiosk = manager
+            .create_session_with_tools(["ask_user"])
+            .await
+            .unwrap();
+        let agent = manager.get_session(&kiosk).await.unwrap();
+        assert_eq!(agent.allowed_tools(), Some(&["ask_user".to_string()][..]));
+        let kiosk_tools = offered_tools(&agent).await;
+        assert!(kiosk_tools.iter().any(|t| t == "ask_user"));
+        assert!(!kiosk_tools.iter().any(|t| t == "confirm_action"));
+
+        // Restored from the database and forked sessions stay restricted
+        manager.sessions.write().await.remove(&kiosk);
+        let restored = manager.get_session(&kiosk).await.unwrap();
+        assert!(!offered_tools(&restored)
+            .await
+            .iter()
+            .any(|t| t == "confirm_action"));
+        restored
+            .restore_history(vec![AgentMessage::user("show the lobby temperature")])
+            .await;
+        let fork = manager.fork_session(&kiosk, 0).await.unwrap();
+        let fork_agent = manager.get_session(&fork).await.unwrap();
+        assert_eq!(fork_agent.allowed_tools(), agent.allowed_tools());
+        let metadata = manager.session_store().get_session_metadata(&fork).unwrap();
+        assert_eq!(metadata.allowed_tools, Some(vec!["ask_user".to_string()]));
+
+        let listed = manager.list_sessions_with_info_light().await;
+        let open_info = listed.iter().find(|i| i.session_id == open).unwrap();
+        assert!(open_info.allowed_tools.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_archive_session_unloads_and_restores() {
+        let manager = create_temp_manager();
+        let session_id = manager.create_session().await.unwrap();
+        let agent = manager.get_session(&session_id).await.unwrap();
+        agent
+            .restore_history(vec![AgentMessage::assistant("Hello")])
+            .await;
+
+        // Nothing from the user, so no LLM call and no summary.
+        let digest = manager.archive_session(&session_id).await.unwrap();
+        assert!(digest.is_none());
+        assert_eq!(manager.session_count().await, 0);
+
+        let history = manager.get_history(&session_id).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(manager.archive_session("missing").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_remove_session() {
         let manager = create_temp_manager();
@@ -1819,6 +2644,50 @@ mod tests {
         assert!(manager.get_session(&session_id).await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_export_and_purge_session() {
+        let manager = create_temp_manager();
+        let session_id = manager.create_session().await.unwrap();
+        let agent = manager.get_session(&session_id).await.unwrap();
+        let mut reply = AgentMessage::assistant("Fan is on");
+        reply.tool_calls = Some(vec![ToolCall {
+            name: "device".to_string(),
+            id: "call-1".to_string(),
+            arguments: serde_json::json!({"action": "control", "device_id": "fan"}),
+            result: Some(serde_json::json!({"success": true})),
+            round: Some(1),
+        }]);
+        agent
+            .restore_history(vec![AgentMessage::user("turn on the fan"), reply])
+            .await;
+        manager.persist_history(&session_id).await.unwrap();
+
+        let export = manager.export_session(&session_id).await.unwrap();
+        assert_eq!(export.messages.len(), 2);
+        assert_eq!(export.tool_calls.len(), 1);
+        assert_eq!(export.tool_calls[0].name, "device");
+
+        let report = manager.purge_session(&session_id).await.unwrap();
+        assert_eq!(report.messages, 2);
+        assert!(manager.export_session(&session_id).await.is_err());
+        assert!(manager.purge_session(&session_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_sessions() {
+        let manager = create_temp_manager();
+        let session_id = manager.create_session().await.unwrap();
+        let agent = manager.get_session(&session_id).await.unwrap();
+        let mut old = AgentMessage::user("hello");
+        old.timestamp -= 10 * 86400;
+        agent.restore_history(vec![old]).await;
+
+        assert_eq!(manager.purge_expired_sessions(0).await, 0);
+        assert_eq!(manager.purge_expired_sessions(30).await, 0);
+        assert_eq!(manager.purge_expired_sessions(7).await, 1);
+        assert!(manager.list_sessions().await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_list_sessions() {
         let manager = create_temp_manager();
@@ -0,0 +1,199 @@
+//! Structured (JSON schema) output for LLM calls.
+//!
+//! [`LlmInterface::generate_structured`](crate::llm::LlmInterface::generate_structured)
+//! asks the model for a single JSON value matching the JSON schema of a Rust
+//! type. The helpers here build the schema instruction, pull the JSON out of
+//! whatever the model wrapped it in (thinking tags, markdown fences, prose),
+//! patch the common syntax slips small models make, and deserialize it.
+//! When that still fails the serde error is fed back to the model for a
+//! bounded number of retries.
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+/// Retries after the first attempt when the output doesn't parse.
+pub const DEFAULT_STRUCTURED_RETRIES: usize = 2;
+
+/// Pretty-printed JSON schema of `T`.
+pub fn schema_json<T: JsonSchema>() -> String {
+    let schema = schemars::schema_for!(T);
+    serde_json::to_string_pretty(&schema).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// System prompt instructing the model to answer with JSON only.
+pub fn schema_system_prompt(schema: &str) -> String {
+    format!(
+        "You are a JSON generator. Reply with exactly one JSON value that conforms to \
+         the following JSON Schema. Do not add explanations, comments or markdown \
+         fences, and do not include fields that are not in the schema.\n\n\
+         JSON Schema:\n{schema}"
+    )
+}
+
+/// Follow-up message asking the model to fix its previous reply.
+pub fn repair_prompt(error: &str) -> String {
+    format!(
+        "Your previous reply could not be parsed against the schema: {error}. \
+         Reply again with only the corrected JSON value."
+    )
+}
+
+/// Parse model output into `T`, extracting and repairing the JSON first.
+pub fn parse_structured<T: DeserializeOwned>(text: &str) -> Result<T, String> {
+    let json = extract_json(text).ok_or_else(|| "no JSON value found in the reply".to_string())?;
+    match serde_json::from_str::<T>(json) {
+        Ok(value) => Ok(value),
+        Err(first) => {
+            let repaired = repair_json(json);
+            serde_json::from_str::<T>(&repaired).map_err(|_| first.to_string())
+        }
+    }
+}
+
+/// Locate the JSON object or array in a model reply.
+///
+/// Skips `<think>` blocks and prefers the contents of a markdown code fence.
+/// The returned slice starts at the first `{` or `[` and ends at its matching
+/// bracket, or at the end of the text when the reply was cut off.
+pub fn extract_json(text: &str) -> Option<&str> {
+    let mut text = text;
+    if let Some(end) = text.find("</think>") {
+        text = &text[end + "</think>".len()..];
+    }
+    if let Some(fenced) = fenced_block(text) {
+        text = fenced;
+    }
+
+    let start = text.find(['{', '['])?;
+    let body = &text[start..];
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&body[..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(body.trim_end())
+}
+
+fn fenced_block(text: &str) -> Option<&str> {
+    let open = text.find("```")?;
+    let after = &text[open + 3..];
+    // Skip the language tag (```json)
+    let content_start = after.find('\n').map(|i| i + 1).unwrap_or(0);
+    let content = &after[content_start..];
+    let close = content.find("```").unwrap_or(content.len());
+    Some(&content[..close])
+}
+
+/// Fix trailing commas and close brackets left open by a truncated reply.
+pub fn repair_json(json: &str) -> String {
+    let mut out = String::with_capacity(json.len() + 4);
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for c in json.chars() {
+        if in_string {
+            out.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => stack.push('}'),
+            '[' => stack.push(']'),
+            '}' | ']' => {
+                strip_trailing_comma(&mut out);
+                stack.pop();
+            }
+            _ => {}
+        }
+        out.push(c);
+    }
+
+    if in_string {
+        out.push('"');
+    }
+    while let Some(close) = stack.pop() {
+        strip_trailing_comma(&mut out);
+        out.push(close);
+    }
+    out
+}
+
+fn strip_trailing_comma(out: &mut String) {
+    let trimmed = out.trim_end().len();
+    if out[..trimmed].ends_with(',') {
+        out.truncate(trimmed - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, JsonSchema, PartialEq)]
+    struct Threshold {
+        metric: String,
+        value: f64,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_extract_json_from_wrapped_reply() {
+        let reply =
+            "<think>{\"draft\": 1}</think>Sure, here it is:\n```json\n{\"a\": \"}\"}\n```\nDone.";
+        assert_eq!(extract_json(reply), Some("{\"a\": \"}\"}"));
+        assert_eq!(extract_json("[1, 2] trailing"), Some("[1, 2]"));
+        assert_eq!(extract_json("no json here"), None);
+    }
+
+    #[test]
+    fn test_parse_structured_repairs_common_mistakes() {
+        let expected = Threshold {
+            metric: "temperature".into(),
+            value: 30.5,
+            tags: vec!["a".into()],
+        };
+        let trailing = r#"{"metric": "temperature", "value": 30.5, "tags": ["a",],}"#;
+        assert_eq!(parse_structured::<Threshold>(trailing).unwrap(), expected);
+
+        let truncated = r#"{"metric": "temperature", "value": 30.5, "tags": ["a""#;
+        assert_eq!(parse_structured::<Threshold>(truncated).unwrap(), expected);
+
+        let err = parse_structured::<Threshold>(r#"{"metric": "temperature"}"#).unwrap_err();
+        assert!(err.contains("value"));
+    }
+
+    #[test]
+    fn test_schema_prompt_contains_fields() {
+        let prompt = schema_system_prompt(&schema_json::<Threshold>());
+        assert!(prompt.contains("\"metric\""));
+        assert!(prompt.contains("\"tags\""));
+    }
+}
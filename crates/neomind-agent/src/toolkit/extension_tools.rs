@@ -20,6 +20,36 @@ use neomind_core::tools::ToolCategory;
 /// Maximum length for a string value before truncation in extension output.
 const MAX_STRING_VALUE_LEN: usize = 200;
 
+/// Namespace prefix of extension tool names: `ext.<extension_id>.<command>`.
+pub const EXTENSION_TOOL_PREFIX: &str = "ext.";
+
+/// Tool name for an extension command.
+pub fn extension_tool_name(extension_id: &str, command: &str) -> String {
+    format!("{}{}.{}", EXTENSION_TOOL_PREFIX, extension_id, command)
+}
+
+/// Whether `name` is an extension tool name, either as registered or in the
+/// sanitized form sent to OpenAI-compatible APIs (`ext_<id>_<command>`).
+pub fn is_extension_tool_name(name: &str) -> bool {
+    name.starts_with(EXTENSION_TOOL_PREFIX) || name.starts_with("ext_")
+}
+
+/// Split an extension tool name into `(extension_id, command)`.
+///
+/// Extension ids may contain dots, command names don't, so the command is
+/// everything after the last dot. The older `<extension_id>:<command>` form
+/// is still accepted.
+pub fn parse_extension_tool_name(name: &str) -> Option<(&str, &str)> {
+    let (extension_id, command) = match name.strip_prefix(EXTENSION_TOOL_PREFIX) {
+        Some(rest) => rest.rsplit_once('.')?,
+        None => name.split_once(':')?,
+    };
+    if extension_id.is_empty() || command.is_empty() {
+        return None;
+    }
+    Some((extension_id, command))
+}
+
 /// Recursively sanitize extension output to truncate large base64/binary strings.
 /// LLM is a text consumer — it doesn't need raw binary payloads, just metadata.
 fn sanitize_extension_output(value: &Value) -> Value {
@@ -61,7 +91,7 @@ pub struct ExtensionTool {
     extension_id: String,
     /// Extension name for display (reserved for future use)
     _extension_name: String,
-    /// Full tool name in format "{extension_id}:{command_name}"
+    /// Full tool name in format "ext.{extension_id}.{command_name}"
     /// This is computed once and stored for efficient name() calls
     full_name: String,
 }
@@ -74,7 +104,7 @@ impl ExtensionTool {
         extension_id: String,
         extension_name: String,
     ) -> Self {
-        let full_name = format!("{}:{}", extension_id, command.name);
+        let full_name = extension_tool_name(&extension_id, &command.name);
         Self {
             extension,
             command,
@@ -274,7 +304,7 @@ impl ExtensionTool {
 
 #[async_trait]
 impl Tool for ExtensionTool {
-    /// Tool name in format: "{extension_id}:{command_name}"
+    /// Tool name in format: "ext.{extension_id}.{command_name}"
     ///
     /// IMPORTANT: This must match the name in to_tool_definition() for proper tool resolution.
     /// The tool registry uses this name for lookups when LLMs invoke tools.
@@ -366,16 +396,20 @@ impl ExtensionToolExecutor {
         let mut all_tools = Vec::new();
 
         for info in extensions {
-            // Get the extension instance by ID
-            if let Some(extension) = self.registry.get(&info.metadata.id).await {
-                let tools = ExtensionTool::from_extension(extension).await;
-                all_tools.extend(tools);
-            }
+            all_tools.extend(self.tools_for(&info.metadata.id).await);
         }
 
         all_tools
     }
 
+    /// Generate the tools of one extension; empty if it isn't registered.
+    pub async fn tools_for(&self, extension_id: &str) -> Vec<ExtensionTool> {
+        match self.registry.get(extension_id).await {
+            Some(extension) => ExtensionTool::from_extension(extension).await,
+            None => Vec::new(),
+        }
+    }
+
     /// Generate tool definitions for LLM consumption.
     pub async fn generate_tool_definitions(&self) -> Vec<ToolDefinition> {
         let tools = self.generate_tools().await;
@@ -405,18 +439,14 @@ impl ExtensionToolExecutor {
 
     /// Execute an extension command by tool name.
     ///
-    /// Tool names should be in format "{extension_id}:{command_id}".
+    /// Tool names should be in format "ext.{extension_id}.{command_id}".
     pub async fn execute_by_tool_name(&self, tool_name: &str, args: &Value) -> Result<Value> {
-        let parts: Vec<&str> = tool_name.split(':').collect();
-        if parts.len() != 2 {
+        let Some((extension_id, command_id)) = parse_extension_tool_name(tool_name) else {
             return Err(ExtensionError::InvalidArguments(format!(
-                "Invalid tool name format: '{}'. Expected '{{extension_id}}:{{command_id}}'",
+                "Invalid tool name format: '{}'. Expected 'ext.{{extension_id}}.{{command}}'",
                 tool_name
             )));
-        }
-
-        let extension_id = parts[0];
-        let command_id = parts[1];
+        };
 
         self.registry
             .execute_command(extension_id, command_id, args)
@@ -532,11 +562,29 @@ mod tests {
         let tool = &tools[0];
         let def = tool.to_tool_definition();
 
-        assert_eq!(def.name, "test.extension:test_command");
+        assert_eq!(def.name, "ext.test.extension.test_command");
         assert_eq!(def.namespace, Some("test.extension".to_string()));
         assert_eq!(def.version, "2.0.0");
     }
 
+    #[test]
+    fn test_extension_tool_names() {
+        let name = extension_tool_name("test.extension", "test_command");
+        assert!(is_extension_tool_name(&name));
+        assert!(is_extension_tool_name("ext_test_extension_test_command"));
+        assert!(!is_extension_tool_name("device"));
+        assert_eq!(
+            parse_extension_tool_name(&name),
+            Some(("test.extension", "test_command"))
+        );
+        assert_eq!(
+            parse_extension_tool_name("test.extension:test_command"),
+            Some(("test.extension", "test_command"))
+        );
+        assert_eq!(parse_extension_tool_name("ext.noid"), None);
+        assert_eq!(parse_extension_tool_name("device"), None);
+    }
+
     #[test]
     fn test_normalize_image_args_strips_data_uri() {
         // Data URI should be stripped to raw base64
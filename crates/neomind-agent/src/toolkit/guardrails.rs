@@ -0,0 +1,402 @@
+//! Declarative guardrails for tool arguments.
+//!
+//! Rules come from the `[guardrails]` section of config.toml, keyed by tool
+//! (`device`) or by action category (`device.control`):
+//!
+//! ```toml
+//! [guardrails."device.control"]
+//! allowed_devices = ["lamp-*", "thermostat-1"]
+//! allowed_hours = { start = "07:00", end = "22:00" }
+//! ranges = { value = { min = 16, max = 28 } }
+//!
+//! [guardrails.device]
+//! max_batch = 10
+//! ```
+//!
+//! Rules are checked before a tool runs, with aliases and simplified
+//! parameter names resolved. A violation is not an execution error: the
+//! call returns a failed [`ToolOutput`] carrying a [`FriendlyError`] the LLM
+//! can relay to the user. This keeps a prompt-injected instruction from
+//! driving a device outside the limits the operator configured.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::NaiveTime;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::tool::ToolOutput;
+use crate::tools::mapper::{map_tool_parameters, resolve_domain_name};
+
+/// Arguments that name the device(s) a call targets.
+const DEVICE_ARGS: &[&str] = &["device_id", "device", "device_ids", "devices"];
+
+static GUARDRAILS: RwLock<Option<Arc<Guardrails>>> = RwLock::new(None);
+
+/// Install the guardrails enforced by [`check`].
+pub fn set_global(guardrails: Guardrails) {
+    *GUARDRAILS.write() = Some(Arc::new(guardrails));
+}
+
+/// Check a tool call against the installed guardrails.
+pub fn check(tool: &str, arguments: &Value) -> Result<(), FriendlyError> {
+    let Some(guardrails) = GUARDRAILS.read().clone() else {
+        return Ok(());
+    };
+    let result = guardrails.check(tool, arguments, chrono::Local::now().time());
+    if let Err(violation) = &result {
+        tracing::warn!(
+            tool = %tool,
+            rule = %violation.rule,
+            code = violation.code,
+            "Tool call blocked by guardrail"
+        );
+    }
+    result
+}
+
+/// Guardrail violation, phrased so the LLM can pass it on to the user.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FriendlyError {
+    /// Machine-readable reason, e.g. `value_out_of_range`
+    pub code: &'static str,
+    /// Rule that refused the call (`device` or `device.control`)
+    pub rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argument: Option<String>,
+    pub message: String,
+    /// What the user could ask for instead
+    pub hint: String,
+}
+
+impl FriendlyError {
+    fn new(code: &'static str, rule: &str, message: String, hint: String) -> Self {
+        Self {
+            code,
+            rule: rule.to_string(),
+            argument: None,
+            message,
+            hint,
+        }
+    }
+
+    fn with_argument(mut self, argument: &str) -> Self {
+        self.argument = Some(argument.to_string());
+        self
+    }
+
+    /// Failed tool output carrying this error as metadata.
+    pub fn into_output(self) -> ToolOutput {
+        let message = self.to_string();
+        ToolOutput::error_with_metadata(message, serde_json::json!({ "guardrail": self }))
+    }
+}
+
+impl fmt::Display for FriendlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Blocked by guardrail: {}. {}", self.message, self.hint)
+    }
+}
+
+impl std::error::Error for FriendlyError {}
+
+/// Inclusive numeric bounds for an argument.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValueRange {
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+}
+
+impl ValueRange {
+    fn contains(&self, value: f64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+
+    fn describe(&self) -> String {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => format!("between {} and {}", min, max),
+            (Some(min), None) => format!("at least {}", min),
+            (None, Some(max)) => format!("at most {}", max),
+            (None, None) => "any value".to_string(),
+        }
+    }
+}
+
+/// Local time of day during which a tool may run. A window whose end is
+/// before its start spans midnight.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Constraints for one tool or action category.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ToolRule {
+    /// Bounds for numeric arguments, matched by name at any depth
+    pub ranges: HashMap<String, ValueRange>,
+    /// Device ids (glob patterns) the call may target; empty allows any
+    pub allowed_devices: Vec<String>,
+    /// Time of day the call may run
+    pub allowed_hours: Option<TimeWindow>,
+    /// Maximum length of list arguments
+    pub max_batch: Option<usize>,
+}
+
+impl ToolRule {
+    fn check(&self, rule: &str, args: &Value, now: NaiveTime) -> Result<(), FriendlyError> {
+        if let Some(window) = &self.allowed_hours {
+            if !window.contains(now) {
+                return Err(FriendlyError::new(
+                    "outside_allowed_hours",
+                    rule,
+                    format!(
+                        "{} is only allowed between {} and {}",
+                        rule,
+                        window.start.format("%H:%M"),
+                        window.end.format("%H:%M")
+                    ),
+                    "Ask the user to try again during the allowed hours".to_string(),
+                ));
+            }
+        }
+
+        if let Some(max_batch) = self.max_batch {
+            let oversized = args.as_object().and_then(|obj| {
+                obj.iter().find_map(|(key, value)| {
+                    let len = value.as_array()?.len();
+                    (len > max_batch).then_some((key, len))
+                })
+            });
+            if let Some((key, len)) = oversized {
+                return Err(FriendlyError::new(
+                    "batch_too_large",
+                    rule,
+                    format!("{} items requested but {} allows at most {}", len, rule, max_batch),
+                    format!("Split the request into batches of at most {}", max_batch),
+                )
+                .with_argument(key));
+            }
+        }
+
+        if !self.allowed_devices.is_empty() {
+            for device_id in device_ids(args) {
+                let allowed = self.allowed_devices.iter().any(|pattern| {
+                    glob::Pattern::new(pattern).is_ok_and(|p| p.matches(device_id))
+                });
+                if !allowed {
+                    return Err(FriendlyError::new(
+                        "device_not_allowed",
+                        rule,
+                        format!("device '{}' may not be used with {}", device_id, rule),
+                        "Only the devices configured for this action can be targeted".to_string(),
+                    )
+                    .with_argument("device_id"));
+                }
+            }
+        }
+
+        for (name, range) in &self.ranges {
+            let mut values = Vec::new();
+            collect_numbers(args, name, &mut values);
+            if let Some(value) = values.into_iter().find(|v| !range.contains(*v)) {
+                return Err(FriendlyError::new(
+                    "value_out_of_range",
+                    rule,
+                    format!("{} = {} is outside the allowed range for {}", name, value, rule),
+                    format!("Use a value {}", range.describe()),
+                )
+                .with_argument(name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Guardrail rules keyed by tool name or action category.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Guardrails {
+    rules: HashMap<String, ToolRule>,
+}
+
+impl Guardrails {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Reject device patterns that can't be compiled.
+    pub fn validate(&self) -> Result<(), String> {
+        for (key, rule) in &self.rules {
+            for pattern in &rule.allowed_devices {
+                glob::Pattern::new(pattern)
+                    .map_err(|e| format!("{}: invalid device pattern '{}': {}", key, pattern, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a call at local time `now`. Both the tool rule and the rule of
+    /// its action category apply, to the arguments as the LLM sent them and
+    /// as they are after parameter mapping (which renames some of them).
+    pub fn check(
+        &self,
+        tool: &str,
+        arguments: &Value,
+        now: NaiveTime,
+    ) -> Result<(), FriendlyError> {
+        if self.rules.is_empty() {
+            return Ok(());
+        }
+        let domain = resolve_domain_name(tool);
+        let args = map_tool_parameters(tool, arguments);
+        let category = args
+            .get("action")
+            .and_then(Value::as_str)
+            .map(|action| format!("{}.{}", domain, action));
+
+        for key in std::iter::once(domain.as_str()).chain(category.as_deref()) {
+            if let Some(rule) = self.rules.get(key) {
+                rule.check(key, arguments, now)?;
+                rule.check(key, &args, now)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn device_ids(args: &Value) -> Vec<&str> {
+    let mut ids = Vec::new();
+    for key in DEVICE_ARGS {
+        match args.get(key) {
+            Some(Value::String(id)) => ids.push(id.as_str()),
+            Some(Value::Array(items)) => ids.extend(items.iter().filter_map(Value::as_str)),
+            _ => {}
+        }
+    }
+    ids
+}
+
+/// Numeric values (or numeric strings) stored under `name` anywhere in `value`.
+fn collect_numbers(value: &Value, name: &str, out: &mut Vec<f64>) {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map {
+                if key == name {
+                    let number = match item {
+                        Value::Number(n) => n.as_f64(),
+                        Value::String(s) => s.trim().parse().ok(),
+                        _ => None,
+                    };
+                    out.extend(number);
+                }
+                collect_numbers(item, name, out);
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_numbers(item, name, out)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn guardrails() -> Guardrails {
+        let config: toml::Value = toml::from_str(
+            r#"
+            [device]
+            max_batch = 2
+
+            ["device.control"]
+            allowed_devices = ["lamp-*", "thermostat-1"]
+            allowed_hours = { start = "07:00", end = "22:00" }
+            ranges = { value = { min = 16, max = 28 } }
+            "#,
+        )
+        .unwrap();
+        let guardrails: Guardrails = config.try_into().unwrap();
+        guardrails.validate().unwrap();
+        guardrails
+    }
+
+    fn at(hour: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_allowed_call_passes() {
+        let args = json!({
+            "action": "control",
+            "device_id": "thermostat-1",
+            "command": "set",
+            "params": {"value": 21}
+        });
+        assert!(guardrails().check("device", &args, at(12)).is_ok());
+        // Rules of other tools don't apply
+        assert!(guardrails().check("rule", &json!({"action": "delete"}), at(3)).is_ok());
+    }
+
+    #[test]
+    fn test_violations() {
+        let g = guardrails();
+        let control = |device: &str, value: Value| {
+            json!({"action": "control", "device_id": device, "command": "set", "value": value})
+        };
+
+        let err = g.check("device", &control("lamp-2", json!(35)), at(12)).unwrap_err();
+        assert_eq!(err.code, "value_out_of_range");
+        assert_eq!(err.argument.as_deref(), Some("value"));
+        assert!(err.to_string().contains("between 16 and 28"));
+        assert_eq!(
+            g.check("device", &control("lamp-2", json!("12")), at(12)).unwrap_err().code,
+            "value_out_of_range"
+        );
+
+        let err = g.check("device", &control("door-lock", json!(20)), at(12)).unwrap_err();
+        assert_eq!(err.code, "device_not_allowed");
+        assert_eq!(err.rule, "device.control");
+
+        let err = g.check("device", &control("lamp-2", json!(20)), at(23)).unwrap_err();
+        assert_eq!(err.code, "outside_allowed_hours");
+
+        let batch = json!({"action": "list", "device_ids": ["a", "b", "c"]});
+        let err = g.check("device", &batch, at(12)).unwrap_err();
+        assert_eq!(err.code, "batch_too_large");
+        assert_eq!(err.rule, "device");
+
+        let output = err.into_output();
+        assert!(!output.success);
+        assert_eq!(output.metadata.unwrap()["guardrail"]["code"], "batch_too_large");
+    }
+
+    #[test]
+    fn test_time_window_spanning_midnight() {
+        let night = TimeWindow {
+            start: at(22),
+            end: at(6),
+        };
+        assert!(night.contains(at(23)));
+        assert!(night.contains(at(2)));
+        assert!(!night.contains(at(12)));
+    }
+}
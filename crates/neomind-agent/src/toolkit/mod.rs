@@ -6,9 +6,12 @@ pub mod error;
 pub mod extension_tools;
 pub mod file_edit;
 pub mod file_write;
+pub mod guardrails;
 pub mod image_edit;
 pub mod memory_tool;
 pub mod path_validator;
+pub mod plan;
+pub mod policy;
 pub mod registry;
 pub mod shell;
 pub mod skill_tool;
@@ -20,15 +23,18 @@ pub mod web_fetch;
 
 // Re-exports consumed via shortcut path (toolkit::TypeName)
 pub use error::{Result, ToolError};
-pub use registry::{ToolRegistry, ToolRegistryBuilder, ToolResult};
-pub use tool::{Tool, ToolDefinition, ToolExample, ToolOutput};
+pub use guardrails::{FriendlyError, Guardrails};
+pub use plan::{ExecutionPlan, PlanError, PlanOutcome, PlanStep};
+pub use policy::ToolExecutionPolicy;
+pub use registry::{ToolProgressSender, ToolRegistry, ToolRegistryBuilder, ToolResult};
+pub use tool::{Tool, ToolDefinition, ToolExample, ToolOutput, ToolOutputStream};
 
 // Re-exports from core (backward compatibility)
 pub use neomind_core::tools::{
     object_schema, string_property, ToolCategory, ToolRelationships, UsageScenario,
 };
 
-pub use shell::ShellConfig;
+pub use shell::{ShellConfig, TenantKeyIssuer};
 
 pub use memory_tool::MemoryTool;
 
@@ -0,0 +1,488 @@
+//! Execution plans: several dependent tool calls in one round-trip.
+//!
+//! A plan is a small DAG of tool calls. A step's arguments can reference the
+//! output of an earlier step as `{{<step id>}}` or `{{<step id>.<path>}}`,
+//! where the path is a dot-separated list of object keys and array indices
+//! into the step's output data. A string that consists of a single reference
+//! is replaced by the referenced JSON value; references inside a longer
+//! string are interpolated as text.
+//!
+//! Steps run in dependency order, independent steps in parallel, so "list the
+//! sensors, then read the first one's telemetry and its rules" takes one LLM
+//! turn instead of three. A step whose dependency failed is skipped. The LLM
+//! submits plans through the `plan` tool ([`tool_definition`]).
+
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::registry::{ToolCall, ToolResult};
+use super::tool::ToolOutput;
+
+/// Name of the tool the LLM uses to submit a plan.
+pub const PLAN_TOOL_NAME: &str = "plan";
+
+/// Upper bound on steps per plan.
+pub const MAX_PLAN_STEPS: usize = 16;
+
+/// Description shown to the LLM.
+const PLAN_DESCRIPTION: &str = "Run several tool calls in one step when later calls need \
+    earlier results. Each step has an `id`, a `tool` and `args`; args can reference another \
+    step's output as \"{{id}}\" or \"{{id.path.0.field}}\". Independent steps run in parallel.";
+
+/// One tool call in a plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// Letters, digits, `_` and `-`
+    pub id: String,
+    pub tool: String,
+    #[serde(default, alias = "arguments")]
+    pub args: Value,
+    /// Steps that must finish first, in addition to the referenced ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+/// A DAG of tool calls.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionPlan {
+    pub steps: Vec<PlanStep>,
+}
+
+/// Why a plan can't run.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PlanError {
+    #[error("plan has no steps")]
+    Empty,
+    #[error("plan has {0} steps, at most {max} are allowed", max = MAX_PLAN_STEPS)]
+    TooManySteps(usize),
+    #[error("invalid step id '{0}': use letters, digits, '_' and '-'")]
+    InvalidStepId(String),
+    #[error("duplicate step id '{0}'")]
+    DuplicateStep(String),
+    #[error("step '{step}' depends on unknown step '{dependency}'")]
+    UnknownDependency { step: String, dependency: String },
+    #[error("steps {0} depend on each other")]
+    Cycle(String),
+    #[error("step '{0}' cannot run a nested plan")]
+    NestedPlan(String),
+    #[error("invalid plan: {0}")]
+    Invalid(String),
+}
+
+/// How a step ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Succeeded,
+    Failed,
+    /// Not run because a dependency failed
+    Skipped,
+}
+
+/// Result of one step.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepOutcome {
+    pub id: String,
+    pub tool: String,
+    pub status: StepStatus,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub output: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Results of all steps, in plan order.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanOutcome {
+    pub steps: Vec<StepOutcome>,
+}
+
+impl PlanOutcome {
+    pub fn step(&self, id: &str) -> Option<&StepOutcome> {
+        self.steps.iter().find(|s| s.id == id)
+    }
+
+    /// Combined tool output; fails if any step did not succeed.
+    pub fn into_output(self) -> ToolOutput {
+        let failed = self
+            .steps
+            .iter()
+            .filter(|s| s.status != StepStatus::Succeeded)
+            .count();
+        let total = self.steps.len();
+        let data = json!({ "steps": self.steps });
+        if failed == 0 {
+            ToolOutput::success(data)
+        } else {
+            ToolOutput {
+                success: false,
+                data,
+                error: Some(format!("{} of {} plan steps did not succeed", failed, total)),
+                metadata: None,
+            }
+        }
+    }
+}
+
+fn reference_regex() -> &'static Regex {
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    REFERENCE.get_or_init(|| {
+        Regex::new(r"\{\{\s*([A-Za-z0-9_-]+)((?:\.[^.{}\s]+)*)\s*\}\}")
+            .expect("valid plan reference pattern")
+    })
+}
+
+fn valid_step_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Step ids referenced anywhere in `value`.
+fn collect_references(value: &Value, refs: &mut BTreeSet<String>) {
+    match value {
+        Value::String(s) => {
+            for captures in reference_regex().captures_iter(s) {
+                refs.insert(captures[1].to_string());
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_references(v, refs)),
+        Value::Object(map) => map.values().for_each(|v| collect_references(v, refs)),
+        _ => {}
+    }
+}
+
+/// Follow a `.key.0.field` path into a step's output.
+fn lookup<'a>(output: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(output, |value, segment| match value {
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            Value::Object(map) => map.get(segment),
+            _ => None,
+        })
+}
+
+/// Replace references in `value` with step outputs.
+fn resolve(value: &Value, outputs: &HashMap<String, Value>) -> Result<Value, String> {
+    match value {
+        Value::String(s) => {
+            let regex = reference_regex();
+            let find = |captures: &regex::Captures| -> Result<Value, String> {
+                let output = outputs
+                    .get(&captures[1])
+                    .ok_or_else(|| format!("step '{}' has no output", &captures[1]))?;
+                lookup(output, &captures[2])
+                    .cloned()
+                    .ok_or_else(|| format!("{} not found in the output", &captures[0]))
+            };
+            // A lone reference keeps the referenced value's type
+            if let Some(captures) = regex.captures(s) {
+                if captures[0].len() == s.trim().len() {
+                    return find(&captures);
+                }
+            }
+            let mut resolved = String::with_capacity(s.len());
+            let mut last = 0;
+            for captures in regex.captures_iter(s) {
+                let whole = captures.get(0).expect("match");
+                resolved.push_str(&s[last..whole.start()]);
+                match find(&captures)? {
+                    Value::String(text) => resolved.push_str(&text),
+                    other => resolved.push_str(&other.to_string()),
+                }
+                last = whole.end();
+            }
+            resolved.push_str(&s[last..]);
+            Ok(Value::String(resolved))
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|v| resolve(v, outputs))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| resolve(v, outputs).map(|v| (k.clone(), v)))
+            .collect::<Result<_, _>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+impl ExecutionPlan {
+    /// Parse the `plan` tool's arguments: `{"steps": [...]}` or a bare array.
+    pub fn from_args(args: &Value) -> Result<Self, PlanError> {
+        let steps = match args {
+            Value::Array(_) => args,
+            _ => args
+                .get("steps")
+                .ok_or_else(|| PlanError::Invalid("missing 'steps'".to_string()))?,
+        };
+        let steps: Vec<PlanStep> = serde_json::from_value(steps.clone())
+            .map_err(|e| PlanError::Invalid(e.to_string()))?;
+        Ok(Self { steps })
+    }
+
+    /// Steps a step waits for: its `depends_on` plus every referenced step.
+    pub fn dependencies(step: &PlanStep) -> BTreeSet<String> {
+        let mut deps: BTreeSet<String> = step.depends_on.iter().cloned().collect();
+        collect_references(&step.args, &mut deps);
+        deps
+    }
+
+    /// Validate the plan and group step indices into layers; every step's
+    /// dependencies are in earlier layers.
+    pub fn layers(&self) -> Result<Vec<Vec<usize>>, PlanError> {
+        if self.steps.is_empty() {
+            return Err(PlanError::Empty);
+        }
+        if self.steps.len() > MAX_PLAN_STEPS {
+            return Err(PlanError::TooManySteps(self.steps.len()));
+        }
+
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            if !valid_step_id(&step.id) {
+                return Err(PlanError::InvalidStepId(step.id.clone()));
+            }
+            if step.tool == PLAN_TOOL_NAME {
+                return Err(PlanError::NestedPlan(step.id.clone()));
+            }
+            if index.insert(&step.id, i).is_some() {
+                return Err(PlanError::DuplicateStep(step.id.clone()));
+            }
+        }
+
+        let mut deps: Vec<BTreeSet<usize>> = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            let mut step_deps = BTreeSet::new();
+            for dependency in Self::dependencies(step) {
+                let &i = index.get(dependency.as_str()).ok_or_else(|| {
+                    PlanError::UnknownDependency {
+                        step: step.id.clone(),
+                        dependency: dependency.clone(),
+                    }
+                })?;
+                step_deps.insert(i);
+            }
+            deps.push(step_deps);
+        }
+
+        let mut layer_of: Vec<Option<usize>> = vec![None; self.steps.len()];
+        let mut layers: Vec<Vec<usize>> = Vec::new();
+        while layer_of.iter().any(Option::is_none) {
+            let ready: Vec<usize> = (0..self.steps.len())
+                .filter(|&i| layer_of[i].is_none())
+                .filter(|&i| deps[i].iter().all(|&d| layer_of[d].is_some()))
+                .collect();
+            if ready.is_empty() {
+                let stuck: Vec<&str> = (0..self.steps.len())
+                    .filter(|&i| layer_of[i].is_none())
+                    .map(|i| self.steps[i].id.as_str())
+                    .collect();
+                return Err(PlanError::Cycle(stuck.join(", ")));
+            }
+            for &i in &ready {
+                layer_of[i] = Some(layers.len());
+            }
+            layers.push(ready);
+        }
+        Ok(layers)
+    }
+
+    /// Run the plan. `execute` runs one layer of independent calls and
+    /// returns their results in call order.
+    pub async fn run<F, Fut>(&self, mut execute: F) -> Result<PlanOutcome, PlanError>
+    where
+        F: FnMut(Vec<ToolCall>) -> Fut,
+        Fut: Future<Output = Vec<ToolResult>>,
+    {
+        let layers = self.layers()?;
+        let mut outputs: HashMap<String, Value> = HashMap::new();
+        let mut outcomes: Vec<Option<StepOutcome>> = vec![None; self.steps.len()];
+
+        for layer in layers {
+            let mut calls = Vec::new();
+            let mut called = Vec::new();
+            for i in layer {
+                let step = &self.steps[i];
+                let failed_dependency = Self::dependencies(step)
+                    .into_iter()
+                    .find(|d| !outputs.contains_key(d));
+                let outcome = match failed_dependency {
+                    Some(dependency) => Err((
+                        StepStatus::Skipped,
+                        format!("skipped because step '{}' failed", dependency),
+                    )),
+                    None => resolve(&step.args, &outputs).map_err(|e| (StepStatus::Failed, e)),
+                };
+                match outcome {
+                    Ok(args) => {
+                        calls.push(ToolCall::new(step.tool.clone(), args).with_id(step.id.clone()));
+                        called.push(i);
+                    }
+                    Err((status, error)) => {
+                        outcomes[i] = Some(StepOutcome {
+                            id: step.id.clone(),
+                            tool: step.tool.clone(),
+                            status,
+                            output: Value::Null,
+                            error: Some(error),
+                        });
+                    }
+                }
+            }
+            if calls.is_empty() {
+                continue;
+            }
+
+            let results = execute(calls).await;
+            for (i, result) in called.into_iter().zip(results) {
+                let step = &self.steps[i];
+                let (status, output, error) = match result.result {
+                    Ok(output) if output.success => {
+                        outputs.insert(step.id.clone(), output.data.clone());
+                        (StepStatus::Succeeded, output.data, None)
+                    }
+                    Ok(output) => (
+                        StepStatus::Failed,
+                        output.data,
+                        Some(output.error.unwrap_or_else(|| "Unknown error".to_string())),
+                    ),
+                    Err(e) => (StepStatus::Failed, Value::Null, Some(e.to_string())),
+                };
+                outcomes[i] = Some(StepOutcome {
+                    id: step.id.clone(),
+                    tool: step.tool.clone(),
+                    status,
+                    output,
+                    error,
+                });
+            }
+        }
+
+        let steps = outcomes
+            .into_iter()
+            .zip(&self.steps)
+            .map(|(outcome, step)| {
+                // A layer executor that returned too few results
+                outcome.unwrap_or_else(|| StepOutcome {
+                    id: step.id.clone(),
+                    tool: step.tool.clone(),
+                    status: StepStatus::Failed,
+                    output: Value::Null,
+                    error: Some("no result".to_string()),
+                })
+            })
+            .collect();
+        Ok(PlanOutcome { steps })
+    }
+}
+
+/// The `plan` tool as offered to the LLM.
+pub fn tool_definition() -> neomind_core::llm::backend::ToolDefinition {
+    neomind_core::llm::backend::ToolDefinition {
+        name: PLAN_TOOL_NAME.to_string(),
+        description: PLAN_DESCRIPTION.to_string(),
+        parameters: json!({
+            "type": "object",
+            "properties": {
+                "steps": {
+                    "type": "array",
+                    "maxItems": MAX_PLAN_STEPS,
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": "string", "description": "Step id, e.g. \"sensors\""},
+                            "tool": {"type": "string", "description": "Tool to call"},
+                            "args": {
+                                "type": "object",
+                                "description": "Tool arguments; may contain {{id.path}} references"
+                            },
+                            "depends_on": {
+                                "type": "array",
+                                "items": {"type": "string"},
+                                "description": "Steps to wait for besides referenced ones"
+                            }
+                        },
+                        "required": ["id", "tool"]
+                    }
+                }
+            },
+            "required": ["steps"]
+        }),
+    }
+}
+
+/// Prompt line describing the `plan` tool.
+pub fn prompt_hint() -> String {
+    format!("**{}**: {}\n", PLAN_TOOL_NAME, PLAN_DESCRIPTION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(steps: Value) -> ExecutionPlan {
+        ExecutionPlan::from_args(&json!({ "steps": steps })).unwrap()
+    }
+
+    #[test]
+    fn test_layers_follow_references() {
+        let plan = plan(json!([
+            {"id": "telemetry", "tool": "device", "args": {"device_id": "{{sensors.0.id}}"}},
+            {"id": "sensors", "tool": "device", "args": {"action": "list"}},
+            {"id": "rules", "tool": "rule", "args": {"device": "{{sensors.0.id}}"}},
+            {"id": "report", "tool": "message", "depends_on": ["telemetry", "rules"]}
+        ]));
+        assert_eq!(plan.layers().unwrap(), vec![vec![1], vec![0, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_invalid_plans() {
+        assert_eq!(plan(json!([])).layers(), Err(PlanError::Empty));
+        assert!(matches!(
+            plan(json!([{"id": "a", "tool": "x", "args": {"v": "{{b}}"}}])).layers(),
+            Err(PlanError::UnknownDependency { .. })
+        ));
+        assert_eq!(
+            plan(json!([
+                {"id": "a", "tool": "x", "depends_on": ["b"]},
+                {"id": "b", "tool": "x", "args": {"v": "{{a.value}}"}}
+            ]))
+            .layers(),
+            Err(PlanError::Cycle("a, b".to_string()))
+        );
+        assert!(matches!(
+            plan(json!([{"id": "a", "tool": "plan"}])).layers(),
+            Err(PlanError::NestedPlan(_))
+        ));
+        assert!(ExecutionPlan::from_args(&json!({"steps": "nope"})).is_err());
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        let outputs = HashMap::from([(
+            "sensors".to_string(),
+            json!([{"id": "temp-1", "value": 21.5}]),
+        )]);
+        let args = json!({
+            "device_id": "{{sensors.0.id}}",
+            "value": "{{ sensors.0.value }}",
+            "note": "reading {{sensors.0.id}} = {{sensors.0.value}}"
+        });
+        assert_eq!(
+            resolve(&args, &outputs).unwrap(),
+            json!({
+                "device_id": "temp-1",
+                "value": 21.5,
+                "note": "reading temp-1 = 21.5"
+            })
+        );
+        assert!(resolve(&json!("{{sensors.3.id}}"), &outputs).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Execution limits applied by `ToolRegistry` around every tool call.
+//!
+//! `timeouts` holds the per-tool defaults that tools apply to their own I/O.
+//! This policy is the outer guard: it bounds the whole `Tool::execute` future
+//! (so a tool that forgets its own timeout still can't hang a chat turn),
+//! caps how many tool calls run at once, and truncates oversized outputs
+//! before they are fed back into the LLM context.
+//!
+//! The default policy imposes no limits, so registries that never configure
+//! one behave exactly as before.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::timeouts;
+use super::tool::ToolOutput;
+
+/// Limits enforced by `ToolRegistry::execute` / `execute_parallel`.
+#[derive(Debug, Clone, Default)]
+pub struct ToolExecutionPolicy {
+    /// Timeout applied to tools without an explicit override. `None` = unbounded.
+    pub default_timeout: Option<Duration>,
+    /// Per-tool timeout overrides keyed by tool name.
+    pub tool_timeouts: HashMap<String, Duration>,
+    /// Maximum number of tool executions in flight across the registry.
+    pub max_concurrent: Option<usize>,
+    /// Maximum serialized size (bytes) of `ToolOutput::data`. Larger outputs
+    /// are truncated and flagged in metadata rather than failing the call.
+    pub max_output_bytes: Option<usize>,
+}
+
+impl ToolExecutionPolicy {
+    /// Create a policy with no limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the timeout used for tools without an override.
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    /// Override the timeout for a single tool.
+    pub fn with_tool_timeout(mut self, tool: impl Into<String>, timeout: Duration) -> Self {
+        self.tool_timeouts.insert(tool.into(), timeout);
+        self
+    }
+
+    /// Cap the number of concurrent tool executions.
+    pub fn with_max_concurrent(mut self, max: usize) -> Self {
+        self.max_concurrent = Some(max.max(1));
+        self
+    }
+
+    /// Cap the serialized output size.
+    pub fn with_max_output_bytes(mut self, max: usize) -> Self {
+        self.max_output_bytes = Some(max);
+        self
+    }
+
+    /// Effective timeout for a tool, clamped to `timeouts::HARD_MAX`.
+    pub fn timeout_for(&self, tool: &str) -> Option<Duration> {
+        self.tool_timeouts
+            .get(tool)
+            .copied()
+            .or(self.default_timeout)
+            .map(|t| t.min(timeouts::HARD_MAX))
+    }
+
+    /// Truncate `output.data` if it exceeds `max_output_bytes`.
+    ///
+    /// The truncated payload is a string prefix (cut on a char boundary) and
+    /// `metadata.truncated` / `metadata.original_bytes` are set so the agent
+    /// can tell the user the result was shortened.
+    pub fn enforce_output_limit(&self, mut output: ToolOutput) -> ToolOutput {
+        let Some(max) = self.max_output_bytes else {
+            return output;
+        };
+
+        let serialized = match &output.data {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        if serialized.len() <= max {
+            return output;
+        }
+
+        let mut cut = max;
+        while cut > 0 && !serialized.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        output.data = Value::String(format!(
+            "{}... [truncated {} of {} bytes]",
+            &serialized[..cut],
+            serialized.len() - cut,
+            serialized.len()
+        ));
+
+        let mut metadata = match output.metadata.take() {
+            Some(Value::Object(map)) => map,
+            Some(other) => {
+                let mut map = serde_json::Map::new();
+                map.insert("original".to_string(), other);
+                map
+            }
+            None => serde_json::Map::new(),
+        };
+        metadata.insert("truncated".to_string(), Value::Bool(true));
+        metadata.insert(
+            "original_bytes".to_string(),
+            Value::from(serialized.len() as u64),
+        );
+        output.metadata = Some(Value::Object(metadata));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_is_unbounded() {
+        let policy = ToolExecutionPolicy::default();
+        assert!(policy.timeout_for("anything").is_none());
+        let out = ToolOutput::success("x".repeat(10_000));
+        assert_eq!(
+            policy.enforce_output_limit(out.clone()).data,
+            out.data,
+            "no limit means no truncation"
+        );
+    }
+
+    #[test]
+    fn test_timeout_override_and_hard_max() {
+        let policy = ToolExecutionPolicy::new()
+            .with_default_timeout(Duration::from_secs(5))
+            .with_tool_timeout("report", Duration::from_secs(3600));
+
+        assert_eq!(policy.timeout_for("shell"), Some(Duration::from_secs(5)));
+        assert_eq!(policy.timeout_for("report"), Some(timeouts::HARD_MAX));
+    }
+
+    #[test]
+    fn test_output_truncation_respects_char_boundary() {
+        let policy = ToolExecutionPolicy::new().with_max_output_bytes(4);
+        // "温度" is 6 bytes; cutting at 4 must back off to 3
+        let out = policy.enforce_output_limit(ToolOutput::success("温度"));
+        let data = out.data.as_str().unwrap();
+        assert!(data.starts_with("温..."));
+
+        let meta = out.metadata.unwrap();
+        assert_eq!(meta["truncated"], true);
+        assert_eq!(meta["original_bytes"], 6);
+    }
+}
@@ -2,14 +2,24 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use futures::StreamExt;
+use neomind_core::tenant::{with_tenant, TenantId};
 use parking_lot::RwLock;
 use serde_json::Value;
+use tokio::sync::{mpsc, Semaphore};
 use tokio_util::sync::CancellationToken;
 
 use super::error::{Result, ToolError};
+use super::guardrails;
+use super::plan::{ExecutionPlan, PlanError, PlanOutcome};
+use super::policy::ToolExecutionPolicy;
 use super::tool::{DynTool, MemoryToolHandles, ToolDefinition, ToolOutput};
 
+/// Receives intermediate chunks from `ToolRegistry::execute_streaming`.
+pub type ToolProgressSender = mpsc::UnboundedSender<ToolOutput>;
+
 /// Tool registry for managing available tools.
 ///
 /// Caches serialized tool definitions to avoid redundant JSON serialization
@@ -32,6 +42,14 @@ pub struct ToolRegistry {
     /// affects `definitions_for_llm()` (filter) and `is_disabled()` but NOT
     /// `definitions()` (catalog sees all + uses `is_disabled` to mark).
     disabled: Arc<RwLock<HashSet<String>>>,
+    /// Timeout / concurrency / output-size limits applied around every call.
+    /// Default policy has no limits.
+    policy: Arc<ToolExecutionPolicy>,
+    /// Permits for `policy.max_concurrent`; `None` when unbounded.
+    concurrency: Option<Arc<Semaphore>>,
+    /// Tool allowlist of a session-scoped view (see `restricted_to`).
+    /// `None` for the shared registry, which allows every registered tool.
+    allowed: Option<Arc<HashSet<String>>>,
 }
 
 impl ToolRegistry {
@@ -42,9 +60,78 @@ impl ToolRegistry {
             cached_definitions: RwLock::new(None),
             cancellation_token: Arc::new(RwLock::new(None)),
             disabled: Arc::new(RwLock::new(HashSet::new())),
+            policy: Arc::new(ToolExecutionPolicy::default()),
+            concurrency: None,
+            allowed: None,
         }
     }
 
+    /// A view of this registry limited to the tools named in `allowlist`.
+    ///
+    /// Used for sessions that must not see the full tool set (e.g. a kiosk
+    /// session that may query data but never control devices). Names match
+    /// either the registered or the LLM-sanitized tool name. The view
+    /// snapshots the current tools and shares the disabled set, cancellation
+    /// token and execution policy (including its concurrency permits) with
+    /// this registry. Restricting an already restricted view can only
+    /// narrow it.
+    pub fn restricted_to<I, S>(&self, allowlist: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let allowed: HashSet<String> = allowlist.into_iter().map(Into::into).collect();
+        let tools = self
+            .tools
+            .iter()
+            .filter(|(name, _)| {
+                allowed.contains(name.as_str())
+                    || allowed.contains(&neomind_core::llm::backend::sanitize_tool_name(name))
+            })
+            .map(|(name, tool)| (name.clone(), tool.clone()))
+            .collect();
+        Self {
+            tools,
+            cached_definitions: RwLock::new(None),
+            cancellation_token: self.cancellation_token.clone(),
+            disabled: self.disabled.clone(),
+            policy: self.policy.clone(),
+            concurrency: self.concurrency.clone(),
+            allowed: Some(Arc::new(allowed)),
+        }
+    }
+
+    /// The allowlist of a restricted view, or `None` if every tool is allowed.
+    pub fn allowed_tools(&self) -> Option<&HashSet<String>> {
+        self.allowed.as_deref()
+    }
+
+    /// Check whether a call to `name` may execute. A restricted view only
+    /// holds its allowed tools, so any name it can't resolve is refused.
+    pub fn is_allowed(&self, name: &str) -> bool {
+        self.allowed.is_none() || self.get(name).is_some()
+    }
+
+    /// Error for a call the allowlist refuses.
+    fn not_allowed(name: &str) -> ToolError {
+        ToolError::PermissionDenied(format!("Tool {} is not allowed in this session", name))
+    }
+
+    /// Replace the execution policy.
+    ///
+    /// Calls already in flight keep the permits of the previous policy.
+    pub fn set_execution_policy(&mut self, policy: ToolExecutionPolicy) {
+        self.concurrency = policy
+            .max_concurrent
+            .map(|max| Arc::new(Semaphore::new(max)));
+        self.policy = Arc::new(policy);
+    }
+
+    /// Current execution policy.
+    pub fn execution_policy(&self) -> &ToolExecutionPolicy {
+        &self.policy
+    }
+
     /// Invalidate cached definitions (call after any mutation).
     fn invalidate_cache(&self) {
         *self.cached_definitions.write() = None;
@@ -75,6 +162,36 @@ impl ToolRegistry {
         removed
     }
 
+    /// Names of the registered tools provided by an extension.
+    pub fn extension_tool_names(&self, extension_id: &str) -> Vec<String> {
+        self.tools
+            .iter()
+            .filter(|(_, tool)| tool.namespace() == Some(extension_id))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Replace an extension's tools with `tools`, dropping commands the
+    /// extension no longer provides. Returns the number registered.
+    pub fn sync_extension_tools(&mut self, extension_id: &str, tools: Vec<DynTool>) -> usize {
+        self.remove_extension_tools(extension_id);
+        let count = tools.len();
+        self.register_all(tools);
+        count
+    }
+
+    /// Unregister all tools of an extension. Returns the number removed.
+    pub fn remove_extension_tools(&mut self, extension_id: &str) -> usize {
+        let names = self.extension_tool_names(extension_id);
+        for name in &names {
+            self.tools.remove(name);
+        }
+        if !names.is_empty() {
+            self.invalidate_cache();
+        }
+        names.len()
+    }
+
     /// Get a tool by name.
     ///
     /// Falls back to desanitized lookup for extension tools whose names were
@@ -172,23 +289,67 @@ impl ToolRegistry {
         if self.is_disabled(name) {
             return Err(ToolError::Disabled(name.to_string()));
         }
+        if !self.is_allowed(name) {
+            return Err(Self::not_allowed(name));
+        }
+        // Calls outside the configured argument limits never reach the tool
+        if let Err(violation) = guardrails::check(name, &args) {
+            return Ok(violation.into_output());
+        }
 
         let tool = self
             .get(name)
             .ok_or_else(|| ToolError::NotFound(name.to_string()))?;
 
         let token_opt = self.cancellation_token.read().clone();
-        match token_opt {
-            None => tool.execute(args).await,
-            Some(token) => {
-                let fut = tool.execute(args);
-                tokio::select! {
-                    biased;
-                    _ = token.cancelled() => Err(ToolError::Canceled),
-                    res = fut => res,
-                }
-            }
+        run_guarded(
+            tool.clone(),
+            args,
+            token_opt,
+            self.policy.clone(),
+            self.concurrency.clone(),
+            None,
+        )
+        .await
+    }
+
+    /// Execute a tool by name, forwarding intermediate output to `progress`.
+    ///
+    /// For tools that `supports_streaming()`, every chunk except the last is
+    /// sent to `progress` as it arrives and the last chunk is returned as the
+    /// result. Other tools behave exactly like `execute`. The same
+    /// cancellation and execution policy apply; the timeout bounds the whole
+    /// stream, not each chunk.
+    pub async fn execute_streaming(
+        &self,
+        name: &str,
+        args: Value,
+        progress: ToolProgressSender,
+    ) -> Result<ToolOutput> {
+        if self.is_disabled(name) {
+            return Err(ToolError::Disabled(name.to_string()));
+        }
+        if !self.is_allowed(name) {
+            return Err(Self::not_allowed(name));
+        }
+        if let Err(violation) = guardrails::check(name, &args) {
+            return Ok(violation.into_output());
         }
+
+        let tool = self
+            .get(name)
+            .ok_or_else(|| ToolError::NotFound(name.to_string()))?;
+
+        let token_opt = self.cancellation_token.read().clone();
+        run_guarded(
+            tool.clone(),
+            args,
+            token_opt,
+            self.policy.clone(),
+            self.concurrency.clone(),
+            Some(progress),
+        )
+        .await
     }
 
     /// Execute multiple tools in parallel using `JoinSet` for lower overhead
@@ -217,6 +378,8 @@ impl ToolRegistry {
         // tool execution against `token.cancelled()`. `None` skips the select!
         // entirely (identical to pre-cancellation behavior).
         let cancel_token_snapshot = self.cancellation_token.read().clone();
+        // Spawned tasks don't inherit the caller's tenant scope
+        let tenant = TenantId::current();
 
         let mut join_set: JoinSet<(usize, ToolResult)> = JoinSet::new();
 
@@ -229,24 +392,31 @@ impl ToolRegistry {
                 join_set.spawn(async move { (idx, ToolResult { name, result }) });
                 continue;
             }
+            if !self.is_allowed(&call.name) {
+                let name = call.name;
+                let result = Err(Self::not_allowed(&name));
+                join_set.spawn(async move { (idx, ToolResult { name, result }) });
+                continue;
+            }
+            if let Err(violation) = guardrails::check(&call.name, &call.args) {
+                let name = call.name;
+                let result = Ok(violation.into_output());
+                join_set.spawn(async move { (idx, ToolResult { name, result }) });
+                continue;
+            }
             if let Some(tool) = self.get(&call.name) {
                 let tool_clone = tool.clone();
                 let args = call.args;
                 let name = call.name;
                 let cancel_for_task = cancel_token_snapshot.clone();
+                let policy = self.policy.clone();
+                let concurrency = self.concurrency.clone();
+                let tenant = tenant.clone();
 
                 join_set.spawn(async move {
-                    let result = match cancel_for_task {
-                        None => tool_clone.execute(args).await,
-                        Some(token) => {
-                            let fut = tool_clone.execute(args);
-                            tokio::select! {
-                                biased;
-                                _ = token.cancelled() => Err(ToolError::Canceled),
-                                res = fut => res,
-                            }
-                        }
-                    };
+                    let guarded =
+                        run_guarded(tool_clone, args, cancel_for_task, policy, concurrency, None);
+                    let result = with_tenant(tenant, guarded).await;
                     (idx, ToolResult { name, result })
                 });
             } else {
@@ -296,6 +466,15 @@ impl ToolRegistry {
             .collect()
     }
 
+    /// Execute a plan of dependent tool calls, one layer of independent
+    /// steps at a time via `execute_parallel`.
+    pub async fn execute_plan(
+        &self,
+        plan: &ExecutionPlan,
+    ) -> std::result::Result<PlanOutcome, PlanError> {
+        plan.run(|calls| self.execute_parallel(calls)).await
+    }
+
     /// Get the number of registered tools.
     pub fn len(&self) -> usize {
         self.tools.len()
@@ -341,6 +520,118 @@ impl Default for ToolRegistry {
     }
 }
 
+/// Run a single tool call under the registry's cancellation token and
+/// execution policy.
+///
+/// Order matters: the concurrency permit is acquired first (waiting for a
+/// permit is not counted against the tool's timeout), then the call races
+/// cancellation and the timeout, and finally the output is size-capped.
+async fn run_guarded(
+    tool: DynTool,
+    args: Value,
+    token: Option<CancellationToken>,
+    policy: Arc<ToolExecutionPolicy>,
+    concurrency: Option<Arc<Semaphore>>,
+    progress: Option<ToolProgressSender>,
+) -> Result<ToolOutput> {
+    let _permit = match concurrency {
+        Some(sem) => match token.as_ref() {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => return Err(ToolError::Canceled),
+                permit = sem.acquire_owned() => permit.ok(),
+            },
+            None => sem.acquire_owned().await.ok(),
+        },
+        None => None,
+    };
+
+    // Key on the registered name, not the (possibly sanitized) requested one
+    let timeout = policy.timeout_for(tool.name());
+    let started = Instant::now();
+    let fut = async {
+        match timeout {
+            Some(limit) => {
+                match tokio::time::timeout(limit, invoke(&tool, args, &policy, progress)).await {
+                    Ok(res) => res,
+                    Err(_) => {
+                        tracing::warn!(
+                            tool = %tool.name(),
+                            timeout_ms = limit.as_millis() as u64,
+                            "Tool execution timed out"
+                        );
+                        Err(ToolError::Timeout)
+                    }
+                }
+            }
+            None => invoke(&tool, args, &policy, progress).await,
+        }
+    };
+
+    let result = match token {
+        None => fut.await,
+        Some(token) => {
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(ToolError::Canceled),
+                res = fut => res,
+            }
+        }
+    };
+
+    record_tool_execution(tool.name(), &result, started.elapsed());
+    result.map(|output| policy.enforce_output_limit(output))
+}
+
+/// Feed tool latency and outcome into the shared metrics registry.
+fn record_tool_execution(name: &str, result: &Result<ToolOutput>, elapsed: Duration) {
+    let status = match result {
+        Ok(output) if output.success => "success",
+        Ok(_) => "failure",
+        Err(ToolError::Timeout) => "timeout",
+        Err(ToolError::Canceled) => "canceled",
+        Err(_) => "error",
+    };
+    let registry = neomind_core::metrics::global();
+    registry
+        .histogram(
+            "neomind_tool_execution_seconds",
+            "Tool execution latency",
+            &[("tool", name)],
+        )
+        .observe_duration(elapsed);
+    registry
+        .counter(
+            "neomind_tool_executions_total",
+            "Tool executions by outcome",
+            &[("tool", name), ("status", status)],
+        )
+        .inc();
+}
+
+/// Call the tool, draining `execute_streaming` when a progress sink is given.
+async fn invoke(
+    tool: &DynTool,
+    args: Value,
+    policy: &ToolExecutionPolicy,
+    progress: Option<ToolProgressSender>,
+) -> Result<ToolOutput> {
+    let progress = match progress {
+        Some(tx) if tool.supports_streaming() => tx,
+        _ => return tool.execute(args).await,
+    };
+
+    let mut stream = tool.execute_streaming(args);
+    let mut last = None;
+    while let Some(chunk) = stream.next().await {
+        if let Some(prev) = last.replace(chunk?) {
+            // Receiver gone just means nobody is watching; keep draining
+            let _ = progress.send(policy.enforce_output_limit(prev));
+        }
+    }
+    last.ok_or_else(|| ToolError::Execution(format!("Tool '{}' produced no output", tool.name())))
+}
+
 /// A tool call request.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ToolCall {
@@ -392,6 +683,10 @@ pub struct ToolResult {
 pub struct ToolRegistryBuilder {
     registry: ToolRegistry,
     extension_registry: Option<Arc<neomind_core::extension::registry::ExtensionRegistry>>,
+    /// Extensions whose tools are not registered (tool master switch off)
+    excluded_extensions: HashSet<String>,
+    policy: Option<ToolExecutionPolicy>,
+    tenant_keys: Option<super::shell::TenantKeyIssuer>,
 }
 
 impl Default for ToolRegistryBuilder {
@@ -406,9 +701,25 @@ impl ToolRegistryBuilder {
         Self {
             registry: ToolRegistry::new(),
             extension_registry: None,
+            excluded_extensions: HashSet::new(),
+            policy: None,
+            tenant_keys: None,
         }
     }
 
+    /// Set the execution policy (timeouts, concurrency, output size).
+    pub fn with_execution_policy(mut self, policy: ToolExecutionPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Issue the tenant-bound API keys the shell tool runs `neomind`
+    /// commands with. Call before `with_shell_tool`.
+    pub fn with_tenant_keys(mut self, issuer: super::shell::TenantKeyIssuer) -> Self {
+        self.tenant_keys = Some(issuer);
+        self
+    }
+
     /// Set the extension registry for scanning extension tools.
     pub fn with_extension_registry(
         mut self,
@@ -418,19 +729,33 @@ impl ToolRegistryBuilder {
         self
     }
 
+    /// Skip these extensions when scanning; their tools are not registered.
+    pub fn without_extensions(mut self, extension_ids: impl IntoIterator<Item = String>) -> Self {
+        self.excluded_extensions.extend(extension_ids);
+        self
+    }
+
     /// Scan extensions and add their tools to the registry.
     ///
-    /// Returns the builder on success (with extension tools added), or the original builder on error.
+    /// Each command becomes a tool named `ext.<extension_id>.<command>`.
     /// Call `.build()` after this method to get the final registry.
     pub async fn with_extensions_scanned(mut self) -> Self {
         if let Some(ext_registry) = &self.extension_registry {
             use super::extension_tools::ExtensionToolExecutor;
 
             let executor = ExtensionToolExecutor::new(ext_registry.clone());
-            let tools = executor.generate_tools().await;
-
-            for tool in tools {
-                self.registry.register(Arc::new(tool));
+            for info in ext_registry.list().await {
+                let extension_id = info.metadata.id;
+                if self.excluded_extensions.contains(&extension_id) {
+                    continue;
+                }
+                let tools: Vec<DynTool> = executor
+                    .tools_for(&extension_id)
+                    .await
+                    .into_iter()
+                    .map(|tool| Arc::new(tool) as DynTool)
+                    .collect();
+                self.registry.sync_extension_tools(&extension_id, tools);
             }
         }
         self
@@ -446,15 +771,21 @@ impl ToolRegistryBuilder {
     pub fn with_shell_tool(mut self, config: Option<super::shell::ShellConfig>) -> Self {
         if let Some(shell_config) = config {
             if shell_config.enabled {
-                self.registry
-                    .register(Arc::new(super::shell::ShellTool::new(shell_config)));
+                let mut tool = super::shell::ShellTool::new(shell_config);
+                if let Some(issuer) = self.tenant_keys.clone() {
+                    tool = tool.with_tenant_keys(issuer);
+                }
+                self.registry.register(Arc::new(tool));
             }
         }
         self
     }
 
     /// Build the registry.
-    pub fn build(self) -> ToolRegistry {
+    pub fn build(mut self) -> ToolRegistry {
+        if let Some(policy) = self.policy.take() {
+            self.registry.set_execution_policy(policy);
+        }
         self.registry
     }
 }
@@ -462,7 +793,7 @@ impl ToolRegistryBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::toolkit::{Tool, ToolOutput};
+    use crate::toolkit::{Tool, ToolExecutionPolicy, ToolOutput};
     use async_trait::async_trait;
     use neomind_core::tools::ToolCategory;
     use serde_json::Value;
@@ -557,6 +888,119 @@ mod tests {
         assert!(matches!(result.unwrap_err(), ToolError::NotFound(_)));
     }
 
+    #[tokio::test]
+    async fn test_restricted_view_filters_and_refuses() {
+        let mut registry = ToolRegistry::new();
+        for name in ["query_data", "control_device", "ext.a:cmd"] {
+            registry.register(Arc::new(TestTool {
+                name: name.to_string(),
+            }));
+        }
+        let view = registry.restricted_to(["query_data", "ext_a_cmd"]);
+
+        let mut names: Vec<_> = view
+            .definitions_for_llm()
+            .into_iter()
+            .map(|d| d.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, ["ext.a:cmd", "query_data"]);
+        assert!(view.allowed_tools().is_some());
+        assert!(registry.allowed_tools().is_none());
+
+        assert!(view.execute("query_data", Value::Null).await.is_ok());
+        let err = view
+            .execute("control_device", Value::Null)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::PermissionDenied(_)));
+
+        let results = view
+            .execute_parallel(vec![
+                ToolCall::new("ext_a_cmd", Value::Null),
+                ToolCall::new("control_device", Value::Null),
+            ])
+            .await;
+        assert!(results[0].result.is_ok());
+        assert!(matches!(
+            results[1].result,
+            Err(ToolError::PermissionDenied(_))
+        ));
+
+        // The shared registry is untouched and still governs disabling
+        assert!(registry.execute("control_device", Value::Null).await.is_ok());
+        registry.disable("query_data");
+        assert!(matches!(
+            view.execute("query_data", Value::Null).await,
+            Err(ToolError::Disabled(_))
+        ));
+    }
+
+    // Test tool provided by an extension
+    struct ExtTool {
+        name: String,
+        extension: String,
+    }
+
+    #[async_trait]
+    impl Tool for ExtTool {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn description(&self) -> &str {
+            "An extension tool"
+        }
+
+        fn parameters(&self) -> Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        fn namespace(&self) -> Option<&str> {
+            Some(&self.extension)
+        }
+
+        async fn execute(&self, _args: Value) -> super::Result<ToolOutput> {
+            Ok(ToolOutput::success(serde_json::json!({})))
+        }
+    }
+
+    fn ext_tool(extension: &str, command: &str) -> DynTool {
+        Arc::new(ExtTool {
+            name: crate::toolkit::extension_tools::extension_tool_name(extension, command),
+            extension: extension.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_sync_and_remove_extension_tools() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(TestTool {
+            name: "shell".to_string(),
+        }));
+        registry.sync_extension_tools("acme.weather", vec![ext_tool("acme.weather", "forecast")]);
+        registry.sync_extension_tools("acme", vec![ext_tool("acme", "ping")]);
+
+        // Reload: the new command set replaces the old one
+        let synced = registry.sync_extension_tools(
+            "acme.weather",
+            vec![
+                ext_tool("acme.weather", "current"),
+                ext_tool("acme.weather", "alerts"),
+            ],
+        );
+        assert_eq!(synced, 2);
+        assert!(!registry.has("ext.acme.weather.forecast"));
+        assert!(registry.has("ext.acme.weather.current"));
+        assert_eq!(registry.definitions().len(), 4);
+
+        // Disable: only this extension's tools go away
+        assert_eq!(registry.remove_extension_tools("acme.weather"), 2);
+        assert!(registry.extension_tool_names("acme.weather").is_empty());
+        assert!(registry.has("ext.acme.ping"));
+        assert!(registry.has("shell"));
+    }
+
     #[tokio::test]
     async fn test_registry_execute_parallel() {
         let mut registry = ToolRegistry::new();
@@ -578,6 +1022,31 @@ mod tests {
         assert!(results[1].result.as_ref().unwrap().success);
     }
 
+    #[tokio::test]
+    async fn test_registry_execute_plan() {
+        use crate::toolkit::plan::StepStatus;
+
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(TestTool {
+            name: "tool1".to_string(),
+        }));
+
+        let plan = ExecutionPlan::from_args(&serde_json::json!({"steps": [
+            {"id": "first", "tool": "tool1"},
+            {"id": "second", "tool": "tool1", "args": {"input": "{{first.result}}"}},
+            {"id": "missing", "tool": "no_such_tool"},
+            {"id": "after", "tool": "tool1", "args": {"input": "{{missing}}"}}
+        ]}))
+        .unwrap();
+        let outcome = registry.execute_plan(&plan).await.unwrap();
+
+        assert_eq!(outcome.step("first").unwrap().status, StepStatus::Succeeded);
+        assert_eq!(outcome.step("second").unwrap().status, StepStatus::Succeeded);
+        assert_eq!(outcome.step("missing").unwrap().status, StepStatus::Failed);
+        assert_eq!(outcome.step("after").unwrap().status, StepStatus::Skipped);
+        assert!(!outcome.into_output().success);
+    }
+
     #[test]
     fn test_tool_call() {
         let call =
@@ -698,6 +1167,171 @@ mod tests {
         }
     }
 
+    // ====================================================================
+    // Execution policy tests
+    // ====================================================================
+
+    struct SleepTool {
+        name: &'static str,
+        millis: u64,
+        in_flight: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl SleepTool {
+        fn new(name: &'static str, millis: u64) -> Self {
+            Self {
+                name,
+                millis,
+                in_flight: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+                peak: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Tool for SleepTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+        fn description(&self) -> &str {
+            "sleeps"
+        }
+        fn parameters(&self) -> Value {
+            serde_json::json!({"type":"object"})
+        }
+        async fn execute(&self, _args: Value) -> super::Result<ToolOutput> {
+            use std::sync::atomic::Ordering;
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(self.millis)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(ToolOutput::success("x".repeat(64)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_policy_timeout_returns_timeout_error() {
+        use std::time::Duration;
+
+        let mut registry = ToolRegistryBuilder::new()
+            .with_execution_policy(
+                ToolExecutionPolicy::new()
+                    .with_default_timeout(Duration::from_secs(10))
+                    .with_tool_timeout("slow", Duration::from_millis(20)),
+            )
+            .build();
+        registry.register(Arc::new(SleepTool::new("slow", 500)));
+        registry.register(Arc::new(SleepTool::new("fast", 1)));
+
+        let result = registry.execute("slow", serde_json::json!({})).await;
+        assert!(matches!(result, Err(ToolError::Timeout)));
+
+        let results = registry
+            .execute_parallel(vec![
+                ToolCall::new("slow", serde_json::json!({})),
+                ToolCall::new("fast", serde_json::json!({})),
+            ])
+            .await;
+        assert!(matches!(results[0].result, Err(ToolError::Timeout)));
+        assert!(results[1].result.as_ref().unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn test_policy_limits_concurrency() {
+        use std::sync::atomic::Ordering;
+
+        let tool = SleepTool::new("sleep", 20);
+        let peak = tool.peak.clone();
+        let mut registry = ToolRegistryBuilder::new()
+            .with_execution_policy(ToolExecutionPolicy::new().with_max_concurrent(2))
+            .build();
+        registry.register(Arc::new(tool));
+
+        let calls = (0..6)
+            .map(|_| ToolCall::new("sleep", serde_json::json!({})))
+            .collect();
+        let results = registry.execute_parallel(calls).await;
+
+        assert!(results.iter().all(|r| r.result.is_ok()));
+        assert!(peak.load(Ordering::SeqCst) <= 2, "at most 2 in flight");
+    }
+
+    #[tokio::test]
+    async fn test_policy_truncates_large_output() {
+        let mut registry = ToolRegistryBuilder::new()
+            .with_execution_policy(ToolExecutionPolicy::new().with_max_output_bytes(16))
+            .build();
+        registry.register(Arc::new(SleepTool::new("big", 1)));
+
+        let output = registry
+            .execute("big", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(output.success);
+        assert_eq!(output.metadata.unwrap()["truncated"], true);
+    }
+
+    struct CountdownTool;
+
+    #[async_trait]
+    impl Tool for CountdownTool {
+        fn name(&self) -> &str {
+            "countdown"
+        }
+        fn description(&self) -> &str {
+            "streams 3, 2, 1 then done"
+        }
+        fn parameters(&self) -> Value {
+            serde_json::json!({"type":"object"})
+        }
+        async fn execute(&self, _args: Value) -> super::Result<ToolOutput> {
+            Ok(ToolOutput::success("done"))
+        }
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+        fn execute_streaming(&self, _args: Value) -> crate::toolkit::ToolOutputStream<'_> {
+            Box::pin(futures::stream::iter(vec![
+                Ok(ToolOutput::success(3)),
+                Ok(ToolOutput::success(2)),
+                Ok(ToolOutput::success(1)),
+                Ok(ToolOutput::success("done")),
+            ]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_forwards_progress() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(CountdownTool));
+        registry.register(Arc::new(TestTool {
+            name: "plain".to_string(),
+        }));
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let output = registry
+            .execute_streaming("countdown", serde_json::json!({}), tx)
+            .await
+            .unwrap();
+        assert_eq!(output.data, "done");
+
+        let mut chunks = Vec::new();
+        while let Ok(chunk) = rx.try_recv() {
+            chunks.push(chunk.data);
+        }
+        assert_eq!(chunks, vec![3, 2, 1]);
+
+        // Non-streaming tools send nothing and return the normal result
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let output = registry
+            .execute_streaming("plain", serde_json::json!({}), tx)
+            .await
+            .unwrap();
+        assert!(output.success);
+        assert!(rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_clear_cancellation_token_resumes_normal_execution() {
         use tokio_util::sync::CancellationToken;
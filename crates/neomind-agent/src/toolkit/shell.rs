@@ -8,9 +8,11 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::process::Stdio;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::AsyncReadExt;
 
+use neomind_core::tenant::{TenantId, TENANT_ENV};
 use neomind_core::tools::ToolCategory;
 
 use super::error::{Result, ToolError};
@@ -59,21 +61,55 @@ struct CommandOutput {
     timed_out: bool,
 }
 
+/// Issues the API key `neomind` commands use for a tenant's sessions.
+///
+/// The server binds the key to that tenant, so a command authenticated with
+/// it cannot reach another tenant's records whatever tenant it names.
+pub type TenantKeyIssuer = Arc<dyn Fn(&TenantId) -> Option<String> + Send + Sync>;
+
 /// Shell tool — executes system commands.
 pub struct ShellTool {
     config: ShellConfig,
+    tenant_keys: Option<TenantKeyIssuer>,
 }
 
 impl ShellTool {
     pub fn new(config: ShellConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            tenant_keys: None,
+        }
+    }
+
+    /// Authenticate the commands of tenant sessions with keys from `issuer`.
+    pub fn with_tenant_keys(mut self, issuer: TenantKeyIssuer) -> Self {
+        self.tenant_keys = Some(issuer);
+        self
+    }
+
+    /// API key for `neomind` commands run by the current task.
+    ///
+    /// Tool calls of a chat session run in its tenant's scope and only ever
+    /// get that tenant's key; without an issuer they get none. Unscoped
+    /// callers fall back to the server's own key.
+    fn api_key(&self) -> Result<Option<String>> {
+        let Some(tenant) = TenantId::current() else {
+            return Ok(Self::resolve_api_key());
+        };
+        self.tenant_keys
+            .as_ref()
+            .and_then(|issue| issue(&tenant))
+            .map(Some)
+            .ok_or_else(|| {
+                ToolError::PermissionDenied(format!("No API key available for tenant '{}'", tenant))
+            })
     }
 
     /// Build a platform-appropriate shell command.
     /// Unix: login shell (`$SHELL -l -c`) with isolated process group;
     ///       falls back to `/bin/sh -c` without `-l` if $SHELL is not set.
     /// Windows: `cmd /C`
-    fn build_command(command: &str) -> std::process::Command {
+    fn build_command(command: &str, api_key: Option<&str>) -> std::process::Command {
         let (shell, is_login) = shell_path();
         let mut cmd = std::process::Command::new(shell);
         shell_arg(&mut cmd, command, is_login);
@@ -81,8 +117,11 @@ impl ShellTool {
         set_process_group(&mut cmd);
 
         // Inject NEOMIND_API_KEY so spawned neomind CLI can authenticate
-        // without depending on CWD-relative data/api_keys.redb lookup.
-        if let Some(key) = Self::resolve_api_key() {
+        // without depending on CWD-relative data/api_keys.redb lookup. The
+        // server's own key must not reach the command through the inherited
+        // environment.
+        cmd.env_remove("NEOMIND_API_KEY");
+        if let Some(key) = api_key {
             cmd.env("NEOMIND_API_KEY", key);
         }
 
@@ -91,6 +130,11 @@ impl ShellTool {
         // which strips most useful information from the output.
         cmd.env("NEOMIND_JSON", "1");
 
+        // Act for the tenant of the session running the command
+        if let Some(tenant) = TenantId::current() {
+            cmd.env(TENANT_ENV, tenant.as_str());
+        }
+
         // Prepend the current binary's directory to PATH so subprocess `neomind`
         // invocations resolve to the same binary that's running the server.
         // Without this, `/bin/sh -c "neomind ..."` walks PATH and may find a
@@ -120,7 +164,7 @@ impl ShellTool {
         cmd
     }
 
-    /// Resolve API key for neomind CLI commands.
+    /// Resolve the server's API key for commands run outside a tenant scope.
     ///
     /// Checks env var first, then reads directly from the server's redb.
     /// Deliberately skips the credential file layer (`read_default_api_key`)
@@ -149,6 +193,7 @@ impl ShellTool {
         &self,
         command: &str,
         timeout: Duration,
+        api_key: Option<String>,
     ) -> Option<CommandOutput> {
         let trimmed = command.trim();
         // Only intercept commands that start with `neomind ` (or are exactly
@@ -180,17 +225,10 @@ impl ShellTool {
             return None;
         }
 
-        // Make auth + JSON-output env visible to the in-process handler.
-        // `dispatch`'s `ApiClient` reads `NEOMIND_API_KEY` (via auto_auth)
-        // and the handlers read `NEOMIND_JSON` to pick the output format.
-        // This mirrors the env injection done for the subprocess in
-        // `build_command`.
-        if let Some(key) = Self::resolve_api_key() {
-            // NOTE: env mutation is process-global. The agent runtime is
-            // single-tenancy and is the only concurrent writer of this var,
-            // so this is equivalent to the existing subprocess env injection.
-            std::env::set_var("NEOMIND_API_KEY", key);
-        }
+        // The handlers read `NEOMIND_JSON` to pick the output format; every
+        // caller wants JSON, so setting it process-wide is harmless. The key
+        // and tenant are per task: `ApiClient` picks up the key scoped by
+        // `with_api_key` below and the task's `TenantId::current()`.
         std::env::set_var("NEOMIND_JSON", "1");
 
         tracing::debug!(
@@ -204,7 +242,14 @@ impl ShellTool {
         // ApiClient has its own 30s HTTP timeout, but a handler may issue
         // multiple requests; this guarantees the in-process path cannot hang
         // longer than the subprocess equivalent would.
-        match tokio::time::timeout(timeout, neomind_cli_ops::dispatch::dispatch(&argv)).await {
+        let dispatch = neomind_cli_ops::dispatch::dispatch(&argv);
+        let dispatch = async {
+            match api_key {
+                Some(key) => neomind_cli_ops::api_client::with_api_key(key, dispatch).await,
+                None => dispatch.await,
+            }
+        };
+        match tokio::time::timeout(timeout, dispatch).await {
             Ok(Ok(resp)) => {
                 let exit_code = if resp.success { 0 } else { 1 };
                 let stdout = serde_json::to_string_pretty(&resp).unwrap_or_else(|e| {
@@ -264,11 +309,24 @@ impl ShellTool {
         // PATH (eliminates version drift between the running server and the
         // CLI binary). Side-effecting/interactive/local-only commands return
         // `NotInProcess` and fall through to the subprocess path below.
-        if let Some(output) = self.try_in_process_dispatch(command, timeout).await {
+        let api_key = self.api_key()?;
+        if let Some(output) = self
+            .try_in_process_dispatch(command, timeout, api_key.clone())
+            .await
+        {
             return Ok(output);
         }
 
-        let mut cmd = Self::build_command(command);
+        // A host command could read the server's own credentials from the
+        // data directory, so other tenants' sessions stop here.
+        if let Some(tenant) = TenantId::current().filter(|tenant| !tenant.is_default()) {
+            return Err(ToolError::PermissionDenied(format!(
+                "Sessions of tenant '{}' may only run neomind commands handled in-process",
+                tenant
+            )));
+        }
+
+        let mut cmd = Self::build_command(command, api_key.as_deref());
 
         if let Some(dir) = working_dir {
             let path = std::path::Path::new(dir);
@@ -709,7 +767,7 @@ impl ShellTool {
                 {
                     Some("Don't guess metric names. Run 'neomind device list' to see all metric_fields per type, or 'neomind device get <ID>' for a specific device's actual field names.".to_string())
                 } else {
-                    Some("Available actions: list, get, create, update, delete, latest, history, control, write-metric, webhook-url, types, drafts. ID is positional: neomind device <action> <ID> [flags].".to_string())
+                    Some("Available actions: list, get, create, update, delete, latest, history, query, anomalies, health-summary, state-at, energy, control, groups, control-group, members, write-metric, webhook-url, types, drafts. ID is positional: neomind device <action> <ID> [flags].".to_string())
                 }
             }
             "dashboard" => {
@@ -733,7 +791,7 @@ impl ShellTool {
                 } else if action == "enable" || action == "disable" {
                     Some("Run 'neomind rule list' to find the rule ID, then 'neomind rule <enable|disable> <ID>'.".to_string())
                 } else {
-                    Some("Available actions: list, get, create, update, delete, enable, disable, test, history".to_string())
+                    Some("Available actions: list, get, create, update, delete, enable, disable, test, history, explain, simulate, conflicts".to_string())
                 }
             }
             "agent" => {
@@ -821,7 +879,6 @@ impl ShellTool {
         // Find the first non-env-assignment token. Skips `KEY=value` prefixes
         // like `DISPLAY=:0` so the bare-command check lands on the real binary.
         let first = command
-            .trim()
             .split_whitespace()
             .find(|t| !t.contains('='))?
             .trim_matches('"');
@@ -865,7 +922,7 @@ Use this tool to run any system command. For NeoMind platform operations, use th
 - On command failure, check the `suggestion` field in the JSON output for recovery hints.
 
 ## NeoMind CLI Domain Syntax
-The `neomind` CLI has 14 domains: `device`, `dashboard`, `rule`, `agent`, `extension`, `widget`, `transform`, `llm`, `message`, `connector`, `push`, `settings`, `system`, `api-key`.
+The `neomind` CLI has 15 domains: `device`, `dashboard`, `rule`, `agent`, `extension`, `widget`, `transform`, `llm`, `message`, `connector`, `push`, `knowledge`, `settings`, `system`, `api-key`.
 
 **Domain-specific command syntax, JSON formats, and copy-paste templates live in skill docs** — use the `skill` tool (`skill(action="search", query="<domain>")`) to load the matching guide, or run `neomind <domain> <action> --help` for flags and examples. All commands return JSON by default in this environment (controlled by the `NEOMIND_JSON` env var) — do NOT pass any `--json` flag.
 
@@ -873,10 +930,20 @@ The `neomind` CLI has 14 domains: `device`, `dashboard`, `rule`, `agent`, `exten
 When the user asks for a domain-specific action, try the matching `neomind <domain> <subcommand>` FIRST — do NOT fall back to raw shell tools (`ping`, `nc`, `ls`, `curl`) until the CLI subcommand has been tried and returned an error.
 - **`neomind connector test <id>`** — test reachability of an MQTT broker. Use this, NOT `ping`/`nc`/`/dev/tcp`.
 - **`neomind connector subscriptions`** — list active MQTT subscriptions across all brokers (takes no id).
+- **`neomind device groups` / `device control-group <group> <command>`** — one call for a set of devices ("turn off all lights on floor 2"). Prefer this over looping `device control` per device.
+- **`neomind device anomalies [<ID>] [--metric <m>]`** — values the server flagged against the metric's learned baseline, with expected value and z-score. Use this for "anything unusual?" / "why did X spike?" before reading raw history.
+- **`neomind device health-summary`** — fleet availability: online/offline/never-seen counts overall and per device type, plus which devices are offline and for how long. Use this for "which devices are down?" instead of calling `device get` per device.
+- **`neomind device state-at <ID> --at <time>`** — the device's metric values and online status at a past moment (`--at` takes Unix seconds, RFC 3339 or an age like `2h`). Use this for incident questions like "what was the valve position when the alarm fired?" instead of scanning `device history`.
+- **`neomind device create --adapter-type virtual` / `device members <ID>`** — a virtual device computes metrics (mean/min/max/sum/count/median) from member devices matched by ID list or tag/location/type selector, e.g. the average office temperature. Query it and reference it in rules like any device; `device control` on it fans out to the members.
+- **`neomind device energy [--group <g>] [--time-range 7d] [--bucket day]`** — energy consumption and cost report (per device, over time, per tariff period). Use this for "how much electricity/money did X use" instead of summing `device history` yourself.
+- **`neomind device query "SELECT avg(temperature) FROM device:* WHERE time > now() - 1h GROUP BY 5m"`** — SQL-like aggregation and filtering over telemetry across devices in one call. Use this for "average/max X per device" or "when was X above Y" instead of pulling `device history` for each device.
 - **`neomind device drafts list` / `drafts approve <id>` / `drafts reject <id>`** — manage auto-discovery drafts. Drafts are NOT deleted via `device delete`; use `device drafts reject <id>` to dismiss a draft.
 - **`neomind extension status <id>` / `extension logs <id>` / `extension reload <id>` / `extension config <id>`** — runtime introspection beyond `list`/`get`. If `extension list` shows an extension but you need health/logs, use these.
 - **`neomind agent clear-memory <id>` / `agent executions <id>`** — memory reset and execution history (distinct from `agent get`).
-- **`neomind transform test-code`** — dry-run transform JavaScript against sample input before saving. For rules, use `neomind rule test <id> --input '<JSON>'` (what-if evaluation against existing rule).
+- **`neomind system maintenance-create --name <n> [--devices|--groups|--rules <ids>] --duration <min>`** — maintenance window: covered rules skip their actions and alerts are recorded without notifying. Add `--at HH:MM [--weekdays mon,fri]` for a recurring window. Use this for "silence alerts while we service line 2" instead of disabling rules; list with `neomind system maintenance [--active]`.
+- **`neomind rule history <id>` / `rule explain <execution_id>`** — every rule execution records the trigger, the value each condition saw, which branch matched and each action's result. Use `rule explain` for "why did the AC turn on at 3am?" and answer from its output instead of guessing from current state.
+- **`neomind knowledge search "<question>"`** — passages from uploaded device manuals and procedures, with the document name. Use this for "how do I reset/replace/calibrate X" before answering from general knowledge, and cite the document.
+- **`neomind transform test-code`** — dry-run transform JavaScript against sample input before saving. For rules, use `neomind rule simulate --body '<JSON>'` (replay recorded data through a candidate rule before creating it), `neomind rule conflicts --body '<JSON>'` (check it against existing rules for contradicting actions, shadowing and trigger loops) or `neomind rule test <id> --input '<JSON>'` (what-if evaluation against existing rule).
 
 ## Native System Commands
 Runs on host via `/bin/sh -c` (Unix) or `cmd /C` (Windows). Common tools available: ping, traceroute, curl, arp, nmap, ps, df, free, top, uptime, systemctl status, ls, cat, head, tail, grep, find, wc, arp-scan, avahi-browse, bluetoothctl, docker.
@@ -1036,6 +1103,39 @@ mod tests {
         assert_eq!(data["timed_out"], false);
     }
 
+    #[tokio::test]
+    async fn test_tenant_session_cannot_switch_tenant() {
+        let issued = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let issuer: TenantKeyIssuer = {
+            let issued = issued.clone();
+            Arc::new(move |tenant: &TenantId| {
+                issued.lock().unwrap().push(tenant.clone());
+                Some(format!("nmk_{}", tenant))
+            })
+        };
+        let tool = ShellTool::new(test_config()).with_tenant_keys(issuer);
+        let tenant_a = TenantId::new("tenant-a").unwrap();
+
+        let result = tenant_a
+            .clone()
+            .scope(tool.execute(serde_json::json!({
+                "command": "NEOMIND_TENANT=tenant-b neomind device list"
+            })))
+            .await;
+        assert!(matches!(result, Err(ToolError::PermissionDenied(_))));
+        // Only tenant A's key was ever handed out
+        assert_eq!(*issued.lock().unwrap(), vec![tenant_a]);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_session_without_issuer_is_refused() {
+        let tool = ShellTool::new(test_config());
+        let result = TenantId::default()
+            .scope(tool.execute(serde_json::json!({ "command": "echo hello" })))
+            .await;
+        assert!(matches!(result, Err(ToolError::PermissionDenied(_))));
+    }
+
     #[tokio::test]
     async fn test_stderr_capture() {
         let tool = ShellTool::new(test_config());
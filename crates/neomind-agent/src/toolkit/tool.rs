@@ -3,6 +3,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -209,6 +210,22 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with the given arguments.
     async fn execute(&self, args: Value) -> Result<ToolOutput>;
 
+    /// Whether `execute_streaming` yields incremental output.
+    ///
+    /// Callers only pay for the streaming path when this returns `true`.
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    /// Execute the tool, yielding incremental output chunks.
+    ///
+    /// Every item except the last is a progress chunk; the last item is the
+    /// final result (what `execute` would have returned). The default wraps
+    /// `execute` as a single-item stream.
+    fn execute_streaming(&self, args: Value) -> ToolOutputStream<'_> {
+        Box::pin(futures::stream::once(self.execute(args)))
+    }
+
     /// Get the tool category.
     fn category(&self) -> ToolCategory {
         ToolCategory::System
@@ -307,6 +324,9 @@ pub trait Tool: Send + Sync {
 /// Dynamic tool wrapper for trait objects.
 pub type DynTool = Arc<dyn Tool>;
 
+/// Incremental output from `Tool::execute_streaming`.
+pub type ToolOutputStream<'a> = BoxStream<'a, Result<ToolOutput>>;
+
 /// Helper function to create a JSON object schema for parameters.
 /// Includes strict mode (additionalProperties: false) to prevent invalid parameters.
 pub fn object_schema(properties: Value, required: Vec<String>) -> Value {
@@ -394,6 +414,21 @@ mod tests {
         assert_eq!(result.data, "Processed: Hello");
     }
 
+    #[tokio::test]
+    async fn test_default_execute_streaming_yields_final_result() {
+        use futures::StreamExt;
+
+        let tool = DummyTool;
+        assert!(!tool.supports_streaming());
+
+        let chunks: Vec<_> = tool
+            .execute_streaming(serde_json::json!({"message": "Hi"}))
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].as_ref().unwrap().data, "Processed: Hi");
+    }
+
     #[tokio::test]
     async fn test_tool_validation_missing_required() {
         let tool = DummyTool;
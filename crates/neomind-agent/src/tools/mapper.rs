@@ -124,6 +124,12 @@ impl ToolNameMapper {
         self.register_alias("query_data", "device");
         self.register_alias("control_device", "device");
 
+        // Single-action device tools, executed as `neomind device <action>`
+        self.register_cli_action("device.health_summary", "device");
+        self.register_cli_action("device_health_summary", "device");
+        self.register_cli_action("device.state_at", "device");
+        self.register_cli_action("device_state_at", "device");
+
         self.register_alias("list_rules", "rule");
         self.register_alias("create_rule", "rule");
         self.register_alias("delete_rule", "rule");
@@ -172,6 +178,14 @@ impl ToolNameMapper {
             .insert(simplified.to_string(), real.to_string());
     }
 
+    /// 注册单一动作的 CLI 工具名称
+    ///
+    /// 执行路由到 shell，参数映射仍按所属 domain 处理
+    fn register_cli_action(&mut self, name: &str, domain: &str) {
+        self.register_simplified(name, "shell");
+        self.register_alias(name, domain);
+    }
+
     /// 注册别名映射
     fn register_alias(&mut self, alias: &str, real: &str) {
         self.alias_to_real
@@ -210,9 +224,10 @@ impl ToolNameMapper {
     ///
     /// 对于部分匹配的别名，尝试找到最相似的工具
     fn fuzzy_match(&self, input: &str) -> Option<String> {
-        // Extension tool names (format: "{ext_id}:{cmd}") must not be fuzzy-matched
-        // to avoid routing e.g. "uink-rms-bridge:list_devices" -> "device"
-        if input.contains(':') {
+        // Extension tool names (format: "ext.{ext_id}.{cmd}", or the legacy
+        // "{ext_id}:{cmd}") must not be fuzzy-matched to avoid routing e.g.
+        // "ext.uink-rms-bridge.list_devices" -> "device"
+        if input.contains(':') || crate::toolkit::extension_tools::is_extension_tool_name(input) {
             return None;
         }
 
@@ -285,6 +300,7 @@ pub const CLI_DOMAINS: &[&str] = &[
     "extension",
     "connector",
     "push",
+    "knowledge",
 ];
 
 /// Convert a CLI domain tool call into a `neomind` CLI command for shell execution.
@@ -293,7 +309,9 @@ pub const CLI_DOMAINS: &[&str] = &[
 /// Returns `Some({"command": "neomind <domain> <action> --flag value ..."})` suitable
 /// for passing to `ShellTool::execute`, or `None` if the name is not a CLI domain.
 pub fn build_cli_command(original_tool_name: &str, arguments: &Value) -> Option<Value> {
-    if !CLI_DOMAINS.contains(&original_tool_name) {
+    // Aliases such as `device.health_summary` resolve to their domain
+    let domain = resolve_domain_name(original_tool_name);
+    if !CLI_DOMAINS.contains(&domain.as_str()) {
         return None;
     }
 
@@ -302,10 +320,10 @@ pub fn build_cli_command(original_tool_name: &str, arguments: &Value) -> Option<
     let obj = mapped.as_object()?;
 
     let action = obj.get("action").and_then(|v| v.as_str()).unwrap_or("list");
-    let mut cmd = format!("neomind {} {}", original_tool_name, action);
+    let mut cmd = format!("neomind {} {}", domain, action);
 
     // Special case: device control takes device_id and command as positional args
-    if original_tool_name == "device" && action == "control" {
+    if domain == "device" && action == "control" {
         if let Some(id) = obj.get("device_id").and_then(|v| v.as_str()) {
             cmd.push_str(&format!(" {}", id));
             if let Some(command) = obj.get("command").and_then(|v| v.as_str()) {
@@ -323,6 +341,20 @@ pub fn build_cli_command(original_tool_name: &str, arguments: &Value) -> Option<
         }
     }
 
+    // Special case: device state_at takes device_id as a positional arg
+    if domain == "device" && action == "state_at" {
+        if let Some(id) = obj.get("device_id").and_then(|v| v.as_str()) {
+            cmd.push_str(&format!(" {}", id));
+            for (k, v) in obj {
+                if k == "action" || k == "device_id" {
+                    continue;
+                }
+                append_flag(&mut cmd, k, v);
+            }
+            return Some(serde_json::json!({"command": cmd}));
+        }
+    }
+
     // Generic flag assembly for remaining params (skip action — it's already positional)
     for (k, v) in obj {
         if k == "action" {
@@ -369,6 +401,8 @@ pub fn map_tool_parameters(tool_name: &str, arguments: &Value) -> Value {
                 }
                 "device_analyze" => Some("latest"),
                 "device_control" | "control_device" => Some("control"),
+                "device.health_summary" | "device_health_summary" => Some("health_summary"),
+                "device.state_at" | "device_state_at" => Some("state_at"),
                 "query_data" => Some("history"),
                 // Rule aliases
                 "list_rules" | "get_rule" => Some("list"),
@@ -462,6 +496,13 @@ pub fn map_tool_parameters(tool_name: &str, arguments: &Value) -> Value {
                         Some("list")
                     }
                 }
+                "knowledge" => {
+                    if obj.contains_key("query") {
+                        Some("search")
+                    } else {
+                        Some("list")
+                    }
+                }
 
                 _ => None,
             };
@@ -642,6 +683,29 @@ mod tests {
         assert_eq!(mapper.resolve("通知列表"), "message");
     }
 
+    #[test]
+    fn test_health_summary_tool_routing() {
+        let mapper = ToolNameMapper::new();
+        assert_eq!(mapper.resolve("device.health_summary"), "shell");
+        assert_eq!(resolve_domain_name("device.health_summary"), "device");
+
+        let args = serde_json::json!({});
+        for name in ["device.health_summary", "device_health_summary"] {
+            let cmd = build_cli_command(name, &args).unwrap();
+            assert_eq!(cmd["command"], "neomind device health_summary");
+        }
+    }
+
+    #[test]
+    fn test_state_at_tool_routing() {
+        let mapper = ToolNameMapper::new();
+        assert_eq!(mapper.resolve("device.state_at"), "shell");
+
+        let args = serde_json::json!({"device": "valve-3", "at": 1772373900});
+        let cmd = build_cli_command("device.state_at", &args).unwrap();
+        assert_eq!(cmd["command"], "neomind device state_at valve-3 --at 1772373900");
+    }
+
     #[test]
     fn test_real_name_passthrough() {
         let mapper = ToolNameMapper::new();
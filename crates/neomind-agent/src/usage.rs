@@ -0,0 +1,295 @@
+//! Per-session token accounting and cost estimation.
+//!
+//! [`UsageTracker`] aggregates prompt and completion tokens for every chat
+//! turn, per session and per backend, and prices them with a [`PriceTable`].
+//! A [`SessionBudget`] caps what a single session may spend; once a session
+//! is over budget `SessionManager` moves it to the configured fallback
+//! backend (a cheaper model) or, without one, rejects further messages.
+//!
+//! Usage is kept in memory and starts from zero after a restart.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+/// Environment variable pointing at a JSON price table file.
+pub const PRICE_TABLE_ENV: &str = "NEOMIND_LLM_PRICE_TABLE";
+
+/// Price of one model or backend, in currency units per 1000 tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub prompt_per_1k: f64,
+    pub completion_per_1k: f64,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+        (prompt_tokens as f64 * self.prompt_per_1k
+            + completion_tokens as f64 * self.completion_per_1k)
+            / 1000.0
+    }
+}
+
+/// Prices keyed by backend id or model name.
+///
+/// Lookup tries the backend id first, then the model name, then the `"*"`
+/// entry; anything else is free (local models).
+///
+/// ```json
+/// { "gpt-4o-mini": { "prompt_per_1k": 0.00015, "completion_per_1k": 0.0006 } }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PriceTable {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl PriceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_price(mut self, key: impl Into<String>, price: ModelPrice) -> Self {
+        self.prices.insert(key.into(), price);
+        self
+    }
+
+    /// Load a price table from a JSON file.
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Load the table named by `NEOMIND_LLM_PRICE_TABLE`, or an empty one.
+    pub fn from_env() -> Self {
+        let Ok(path) = std::env::var(PRICE_TABLE_ENV) else {
+            return Self::default();
+        };
+        Self::from_file(&path).unwrap_or_else(|e| {
+            tracing::warn!(path = %path, error = %e, "Failed to load LLM price table");
+            Self::default()
+        })
+    }
+
+    pub fn price_for(&self, backend_id: &str, model: &str) -> ModelPrice {
+        self.prices
+            .get(backend_id)
+            .or_else(|| self.prices.get(model))
+            .or_else(|| self.prices.get("*"))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Hard per-session limits. `None` means unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionBudget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost: Option<f64>,
+    /// Backend a session is moved to once it exceeds the budget. Without
+    /// one, over-budget sessions are rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_backend: Option<String>,
+}
+
+/// Accumulated token counts and cost.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost: f64,
+}
+
+impl UsageTotals {
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    fn add(&mut self, prompt_tokens: u64, completion_tokens: u64, cost: f64) {
+        self.requests += 1;
+        self.prompt_tokens += prompt_tokens;
+        self.completion_tokens += completion_tokens;
+        self.cost += cost;
+    }
+}
+
+/// Usage of one session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionUsage {
+    pub session_id: String,
+    pub totals: UsageTotals,
+    /// Per backend id.
+    pub by_backend: HashMap<String, UsageTotals>,
+    /// Set once the session has been moved to the fallback backend.
+    #[serde(default)]
+    pub downgraded: bool,
+}
+
+/// Aggregates token usage across sessions.
+#[derive(Default)]
+pub struct UsageTracker {
+    sessions: RwLock<HashMap<String, SessionUsage>>,
+    prices: RwLock<PriceTable>,
+    budget: RwLock<SessionBudget>,
+}
+
+impl UsageTracker {
+    pub fn new(prices: PriceTable) -> Self {
+        Self {
+            prices: RwLock::new(prices),
+            ..Self::default()
+        }
+    }
+
+    pub fn set_price_table(&self, prices: PriceTable) {
+        *self.prices.write() = prices;
+    }
+
+    pub fn price_table(&self) -> PriceTable {
+        self.prices.read().clone()
+    }
+
+    pub fn set_budget(&self, budget: SessionBudget) {
+        *self.budget.write() = budget;
+    }
+
+    pub fn budget(&self) -> SessionBudget {
+        self.budget.read().clone()
+    }
+
+    /// Record one completed turn.
+    pub fn record(
+        &self,
+        session_id: &str,
+        backend_id: &str,
+        model: &str,
+        prompt_tokens: u64,
+        completion_tokens: u64,
+    ) {
+        let cost = self
+            .prices
+            .read()
+            .price_for(backend_id, model)
+            .cost(prompt_tokens, completion_tokens);
+
+        let mut sessions = self.sessions.write();
+        let usage = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| SessionUsage {
+                session_id: session_id.to_string(),
+                ..Default::default()
+            });
+        usage.totals.add(prompt_tokens, completion_tokens, cost);
+        usage
+            .by_backend
+            .entry(backend_id.to_string())
+            .or_default()
+            .add(prompt_tokens, completion_tokens, cost);
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<SessionUsage> {
+        self.sessions.read().get(session_id).cloned()
+    }
+
+    pub fn all(&self) -> Vec<SessionUsage> {
+        self.sessions.read().values().cloned().collect()
+    }
+
+    pub fn remove(&self, session_id: &str) {
+        self.sessions.write().remove(session_id);
+    }
+
+    /// Whether the session has used up its budget.
+    pub fn is_over_budget(&self, session_id: &str) -> bool {
+        let budget = self.budget.read();
+        let sessions = self.sessions.read();
+        let Some(usage) = sessions.get(session_id) else {
+            return false;
+        };
+        budget
+            .max_tokens
+            .is_some_and(|max| usage.totals.total_tokens() >= max)
+            || budget.max_cost.is_some_and(|max| usage.totals.cost >= max)
+    }
+
+    pub(crate) fn mark_downgraded(&self, session_id: &str) {
+        if let Some(usage) = self.sessions.write().get_mut(session_id) {
+            usage.downgraded = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices() -> PriceTable {
+        PriceTable::new()
+            .with_price(
+                "gpt-4o",
+                ModelPrice {
+                    prompt_per_1k: 0.005,
+                    completion_per_1k: 0.015,
+                },
+            )
+            .with_price(
+                "cloud-backend",
+                ModelPrice {
+                    prompt_per_1k: 0.001,
+                    completion_per_1k: 0.002,
+                },
+            )
+    }
+
+    #[test]
+    fn test_price_lookup_order() {
+        let table = prices();
+        assert_eq!(
+            table.price_for("cloud-backend", "gpt-4o").prompt_per_1k,
+            0.001
+        );
+        assert_eq!(table.price_for("other", "gpt-4o").prompt_per_1k, 0.005);
+        assert_eq!(table.price_for("ollama", "qwen3"), ModelPrice::default());
+
+        let parsed: PriceTable =
+            serde_json::from_str(r#"{"*": {"prompt_per_1k": 0.1, "completion_per_1k": 0.2}}"#)
+                .unwrap();
+        assert_eq!(parsed.price_for("any", "any").completion_per_1k, 0.2);
+    }
+
+    #[test]
+    fn test_record_aggregates_per_backend() {
+        let tracker = UsageTracker::new(prices());
+        tracker.record("s1", "openai", "gpt-4o", 1000, 1000);
+        tracker.record("s1", "ollama", "qwen3", 500, 100);
+        tracker.record("s2", "openai", "gpt-4o", 10, 10);
+
+        let usage = tracker.get("s1").unwrap();
+        assert_eq!(usage.totals.requests, 2);
+        assert_eq!(usage.totals.total_tokens(), 2600);
+        assert!((usage.totals.cost - 0.02).abs() < 1e-9);
+        assert_eq!(usage.by_backend["ollama"].prompt_tokens, 500);
+        assert_eq!(usage.by_backend["ollama"].cost, 0.0);
+        assert_eq!(tracker.all().len(), 2);
+    }
+
+    #[test]
+    fn test_budget() {
+        let tracker = UsageTracker::new(prices());
+        tracker.set_budget(SessionBudget {
+            max_tokens: Some(1000),
+            ..Default::default()
+        });
+        tracker.record("s1", "openai", "gpt-4o", 400, 100);
+        assert!(!tracker.is_over_budget("s1"));
+        tracker.record("s1", "openai", "gpt-4o", 400, 100);
+        assert!(tracker.is_over_budget("s1"));
+        assert!(!tracker.is_over_budget("unknown"));
+    }
+}
@@ -35,6 +35,7 @@ impl TestContext {
             tool_registry: None,
             memory_store: None,
             backend_semaphores: None,
+            execution_limiter: None,
             skill_registry: None,
         };
 
@@ -87,6 +88,7 @@ impl TestContext {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
             priority: 128,
             conversation_history: vec![],
             user_messages: vec![],
@@ -72,6 +72,7 @@ impl FullTestContext {
             tool_registry: None,
             memory_store: None,
             backend_semaphores: None,
+            execution_limiter: None,
             skill_registry: None,
         };
 
@@ -199,6 +200,7 @@ impl FullTestContext {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
             priority: 128,
             conversation_history: vec![],
             user_messages: vec![],
@@ -280,6 +282,7 @@ impl FullTestContext {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
             priority: 128,
             conversation_history: vec![],
             user_messages: vec![],
@@ -0,0 +1,377 @@
+//! Golden-transcript regression tests for the chat agent.
+//!
+//! Each file in `tests/golden/` records a conversation: the user turns, the
+//! completions the LLM returned, the outputs of the tools it called, and the
+//! transcript the agent produced (tool calls per round and the final answer).
+//! The test replays the completions through a scripted `LlmRuntime` and tool
+//! registry and diffs the new transcript against the recorded one, so prompt,
+//! parser or tool-loop changes can't silently change behaviour.
+//!
+//! After an intended change, re-record with
+//! `NEOMIND_UPDATE_GOLDEN=1 cargo test -p neomind-agent --test golden_transcripts`
+//! and review the diff of `tests/golden/`. A new transcript only needs its
+//! turns, completions and tool outputs; recording fills in `expected`.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use neomind_agent::toolkit::{Result as ToolResult, Tool, ToolOutput, ToolRegistry};
+use neomind_agent::{Agent, AgentConfig, AgentEvent};
+use neomind_core::llm::backend::{
+    BackendId, FinishReason, LlmError, LlmInput, LlmOutput, LlmRuntime, StreamChunk,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const TURN_TIMEOUT: Duration = Duration::from_secs(60);
+
+// ============================================================================
+// Golden file format
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GoldenFile {
+    #[serde(default)]
+    description: String,
+    /// Tool outputs, looked up by tool name and exact arguments.
+    #[serde(default)]
+    tool_outputs: Vec<RecordedTool>,
+    turns: Vec<GoldenTurn>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedTool {
+    tool: String,
+    arguments: Value,
+    #[serde(default)]
+    output: Value,
+    /// Set to make the tool fail with this message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GoldenTurn {
+    user: String,
+    /// LLM completions for this turn, one per LLM call, in order.
+    completions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expected: Option<Transcript>,
+}
+
+/// What the agent did in one turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Transcript {
+    /// Tool calls per round; calls within a round are sorted, since they run
+    /// concurrently.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rounds: Vec<Vec<ObservedCall>>,
+    answer: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ObservedCall {
+    tool: String,
+    arguments: Value,
+    success: bool,
+}
+
+// ============================================================================
+// Scripted LLM and tools
+// ============================================================================
+
+/// Returns the recorded completions in order, one per call.
+#[derive(Default)]
+struct ScriptedLlm {
+    completions: Mutex<VecDeque<String>>,
+}
+
+impl ScriptedLlm {
+    fn next_completion(&self) -> Result<String, LlmError> {
+        self.completions.lock().pop_front().ok_or_else(|| {
+            LlmError::Generation("golden transcript has no completion left".to_string())
+        })
+    }
+}
+
+#[async_trait]
+impl LlmRuntime for ScriptedLlm {
+    fn backend_id(&self) -> BackendId {
+        BackendId::new(BackendId::MOCK)
+    }
+
+    fn model_name(&self) -> &str {
+        "golden"
+    }
+
+    async fn generate(&self, _input: LlmInput) -> Result<LlmOutput, LlmError> {
+        Ok(LlmOutput {
+            text: self.next_completion()?,
+            finish_reason: FinishReason::Stop,
+            usage: None,
+            thinking: None,
+            tool_calls: None,
+        })
+    }
+
+    async fn generate_stream(
+        &self,
+        _input: LlmInput,
+    ) -> Result<Pin<Box<dyn Stream<Item = StreamChunk> + Send>>, LlmError> {
+        let chunks: Vec<StreamChunk> = vec![Ok((self.next_completion()?, false))];
+        Ok(Box::pin(futures::stream::iter(chunks)))
+    }
+
+    fn max_context_length(&self) -> usize {
+        32_768
+    }
+}
+
+/// Answers calls from the recorded outputs for one tool name.
+struct ScriptedTool {
+    name: String,
+    outputs: Vec<RecordedTool>,
+}
+
+#[async_trait]
+impl Tool for ScriptedTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        "replays recorded outputs"
+    }
+
+    fn parameters(&self) -> Value {
+        serde_json::json!({ "type": "object" })
+    }
+
+    async fn execute(&self, args: Value) -> ToolResult<ToolOutput> {
+        let recorded = self.outputs.iter().find(|r| r.arguments == args);
+        Ok(match recorded {
+            Some(RecordedTool { error: Some(error), .. }) => ToolOutput::error(error.clone()),
+            Some(recorded) => ToolOutput::success(recorded.output.clone()),
+            None => ToolOutput::error(format!("no recorded output for {} {}", self.name, args)),
+        })
+    }
+}
+
+// ============================================================================
+// Replay
+// ============================================================================
+
+async fn replay(golden: &GoldenFile) -> Vec<Transcript> {
+    let mut by_tool: BTreeMap<&str, Vec<RecordedTool>> = BTreeMap::new();
+    for recorded in &golden.tool_outputs {
+        by_tool
+            .entry(recorded.tool.as_str())
+            .or_default()
+            .push(recorded.clone());
+    }
+    let mut registry = ToolRegistry::new();
+    for (name, outputs) in by_tool {
+        registry.register(Arc::new(ScriptedTool {
+            name: name.to_string(),
+            outputs,
+        }));
+    }
+
+    let llm = Arc::new(ScriptedLlm::default());
+    let agent = Agent::with_tools(
+        AgentConfig::default(),
+        "golden".to_string(),
+        Arc::new(registry),
+    );
+    agent.set_custom_llm(llm.clone()).await;
+
+    let mut transcripts = Vec::new();
+    for turn in &golden.turns {
+        *llm.completions.lock() = turn.completions.iter().cloned().collect();
+        let mut transcript = tokio::time::timeout(TURN_TIMEOUT, run_turn(&agent, &turn.user))
+            .await
+            .unwrap_or_else(|_| Transcript {
+                rounds: Vec::new(),
+                answer: String::new(),
+                errors: vec!["turn timed out".to_string()],
+            });
+        let unused = llm.completions.lock().len();
+        if unused > 0 {
+            transcript
+                .errors
+                .push(format!("{} recorded completion(s) not used", unused));
+        }
+        transcripts.push(transcript);
+    }
+    transcripts
+}
+
+async fn run_turn(agent: &Agent, user: &str) -> Transcript {
+    let mut rounds: BTreeMap<usize, Vec<ObservedCall>> = BTreeMap::new();
+    let mut answer = String::new();
+    let mut errors = Vec::new();
+
+    let mut events = match agent.process_stream_events(user, None, None).await {
+        Ok(events) => events,
+        Err(e) => {
+            errors.push(e.to_string());
+            return Transcript {
+                rounds: Vec::new(),
+                answer,
+                errors,
+            };
+        }
+    };
+
+    // Every ToolCallStart is followed by its ToolCallEnd
+    let mut started: Option<(String, Value, usize)> = None;
+    while let Some(event) = events.next().await {
+        match event {
+            AgentEvent::Content { content } => answer.push_str(&content),
+            AgentEvent::ToolCallStart {
+                tool,
+                arguments,
+                round,
+            } => started = Some((tool, arguments, round.unwrap_or(1))),
+            AgentEvent::ToolCallEnd { success, .. } => {
+                if let Some((tool, arguments, round)) = started.take() {
+                    rounds.entry(round).or_default().push(ObservedCall {
+                        tool,
+                        arguments,
+                        success,
+                    });
+                }
+            }
+            AgentEvent::Error { message } => errors.push(message),
+            AgentEvent::End { .. } => break,
+            _ => {}
+        }
+    }
+
+    Transcript {
+        rounds: rounds
+            .into_values()
+            .map(|mut calls| {
+                calls.sort_by_key(|c| (c.tool.clone(), c.arguments.to_string()));
+                calls
+            })
+            .collect(),
+        answer: answer.trim().to_string(),
+        errors,
+    }
+}
+
+// ============================================================================
+// Diffing
+// ============================================================================
+
+/// Line diff of two texts (LCS), `-` for expected-only and `+` for actual-only lines.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let a: Vec<&str> = expected.lines().collect();
+    let b: Vec<&str> = actual.lines().collect();
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            out.push_str(&format!("  {}\n", a[i]));
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push_str(&format!("+ {}\n", b[j]));
+            j += 1;
+        } else {
+            out.push_str(&format!("- {}\n", a[i]));
+            i += 1;
+        }
+    }
+    out
+}
+
+fn golden_files() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("reading {}: {}", dir.display(), e))
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[tokio::test]
+async fn golden_transcripts() {
+    let update = std::env::var_os("NEOMIND_UPDATE_GOLDEN").is_some();
+    let paths = golden_files();
+    assert!(!paths.is_empty(), "no golden transcripts found");
+
+    let mut failures = Vec::new();
+    for path in paths {
+        let text = fs::read_to_string(&path).unwrap();
+        let mut golden: GoldenFile = serde_json::from_str(&text)
+            .unwrap_or_else(|e| panic!("parsing {}: {}", path.display(), e));
+        let transcripts = replay(&golden).await;
+
+        let mut changed = false;
+        for (i, (turn, actual)) in golden.turns.iter_mut().zip(transcripts).enumerate() {
+            if turn.expected.as_ref() == Some(&actual) {
+                continue;
+            }
+            if update {
+                turn.expected = Some(actual);
+                changed = true;
+                continue;
+            }
+            let expected = turn
+                .expected
+                .as_ref()
+                .map(|t| serde_json::to_string_pretty(t).unwrap())
+                .unwrap_or_default();
+            let actual = serde_json::to_string_pretty(&actual).unwrap();
+            failures.push(format!(
+                "{} turn {} ({:?}):\n{}",
+                path.display(),
+                i + 1,
+                turn.user,
+                line_diff(&expected, &actual)
+            ));
+        }
+
+        if changed {
+            let json = serde_json::to_string_pretty(&golden).unwrap();
+            fs::write(&path, json + "\n").unwrap();
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "agent transcripts differ from the golden files \
+         (re-record with NEOMIND_UPDATE_GOLDEN=1 if the change is intended):\n\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_line_diff() {
+    let diff = line_diff("a\nb\nc", "a\nx\nc");
+    assert_eq!(diff, "  a\n+ x\n- b\n  c\n");
+}
@@ -50,6 +50,7 @@ impl LlmTestContext {
             tool_registry: None,
             memory_store: None,
             backend_semaphores: None,
+            execution_limiter: None,
             skill_registry: None,
         };
 
@@ -108,6 +109,7 @@ impl LlmTestContext {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
             enable_tool_chaining: false,
             max_chain_depth: 3,
         };
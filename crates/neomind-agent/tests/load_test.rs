@@ -177,6 +177,7 @@ impl LoadTestContext {
             tool_registry: None,
             memory_store: None,
             backend_semaphores: None,
+            execution_limiter: None,
             skill_registry: None,
         };
 
@@ -231,6 +232,7 @@ impl LoadTestContext {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
             priority: 128,
             conversation_history: vec![],
             user_messages: vec![],
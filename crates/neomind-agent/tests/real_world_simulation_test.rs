@@ -80,6 +80,7 @@ impl SimulationContext {
             tool_registry: None,
             memory_store: None,
             backend_semaphores: None,
+            execution_limiter: None,
             skill_registry: None,
         };
 
@@ -197,6 +198,7 @@ impl SimulationContext {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
             priority: 128,
             conversation_history: vec![],
             user_messages: vec![],
@@ -52,6 +52,7 @@ impl RealPerfTestContext {
             tool_registry: None,
             memory_store: None,
             backend_semaphores: None,
+            execution_limiter: None,
             skill_registry: None,
         };
         let executor = AgentExecutor::new(executor_config).await?;
@@ -153,6 +154,7 @@ impl RealPerfTestContext {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
             priority: 128,
             conversation_history: vec![],
             user_messages: vec![],
@@ -330,6 +332,7 @@ async fn test_llm_vs_mock_comparison() -> anyhow::Result<()> {
         tool_registry: None,
         memory_store: None,
         backend_semaphores: None,
+        execution_limiter: None,
         skill_registry: None,
     };
 
@@ -377,6 +380,7 @@ async fn test_llm_vs_mock_comparison() -> anyhow::Result<()> {
         system_prompt: None,
         max_retries: 0,
         consecutive_failures: 0,
+        concurrency: Default::default(),
         priority: 128,
         conversation_history: vec![],
         user_messages: vec![],
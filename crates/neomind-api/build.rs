@@ -0,0 +1,15 @@
+fn main() {
+    // Only the `grpc` feature needs generated code; keep default builds free
+    // of protoc and tonic-build.
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/neomind.proto");
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::configure()
+            .build_client(true)
+            .build_server(true)
+            .compile_protos(&["proto/neomind.proto"], &["proto"])
+            .expect("Failed to compile proto/neomind.proto");
+    }
+}
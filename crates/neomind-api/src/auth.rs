@@ -13,18 +13,20 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use dashmap::DashMap;
+use neomind_core::tenant::{TenantId, TenantScope, TENANT_HEADER};
 use redb::{Database, ReadableTable, TableDefinition};
 use tracing::{error, info, warn};
 
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode},
+    extract::{FromRequestParts, State},
+    http::{request::Parts, HeaderMap, Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Json, Response},
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::auth_users::Permission;
 use crate::crypto::CryptoService;
 use crate::server::ServerState;
 
@@ -34,6 +36,9 @@ const API_KEYS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("api_k
 // Table definition for API key hashes (for validation)
 const API_KEY_HASHES_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("api_key_hashes");
 
+// Table definition for per-key daily LLM token usage: hash -> TokenUsage (bincode)
+const API_KEY_USAGE_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("api_key_usage");
+
 /// API Key information.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeyInfo {
@@ -47,6 +52,102 @@ pub struct ApiKeyInfo {
     pub permissions: Vec<String>,
     /// Whether this key is active
     pub active: bool,
+    /// Requests per minute allowed for this key. `None` uses the global limit.
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
+    /// LLM tokens this key may consume per UTC day. `None` means unlimited.
+    #[serde(default)]
+    pub daily_token_quota: Option<u64>,
+    /// Tenant this key is bound to. `None` gives access to every tenant.
+    #[serde(default)]
+    pub tenant_id: Option<TenantId>,
+}
+
+impl ApiKeyInfo {
+    fn new(name: impl Into<String>, permissions: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.into(),
+            created_at: chrono::Utc::now().timestamp(),
+            permissions,
+            active: true,
+            rate_limit_per_minute: None,
+            daily_token_quota: None,
+            tenant_id: None,
+        }
+    }
+}
+
+/// `ApiKeyInfo` as stored before per-key limits existed. bincode is not
+/// self-describing, so old records must be decoded with the old layout.
+#[derive(Deserialize)]
+struct LegacyApiKeyInfo {
+    id: String,
+    name: String,
+    created_at: i64,
+    permissions: Vec<String>,
+    active: bool,
+}
+
+/// `ApiKeyInfo` as stored before keys could be bound to a tenant.
+#[derive(Deserialize)]
+struct UnscopedApiKeyInfo {
+    id: String,
+    name: String,
+    created_at: i64,
+    permissions: Vec<String>,
+    active: bool,
+    rate_limit_per_minute: Option<u32>,
+    daily_token_quota: Option<u64>,
+}
+
+fn decode_key_info(bytes: &[u8]) -> Result<ApiKeyInfo, bincode::Error> {
+    bincode::deserialize(bytes).or_else(|e| {
+        if let Ok(old) = bincode::deserialize::<UnscopedApiKeyInfo>(bytes) {
+            return Ok(ApiKeyInfo {
+                id: old.id,
+                name: old.name,
+                created_at: old.created_at,
+                permissions: old.permissions,
+                active: old.active,
+                rate_limit_per_minute: old.rate_limit_per_minute,
+                daily_token_quota: old.daily_token_quota,
+                tenant_id: None,
+            });
+        }
+        let legacy: LegacyApiKeyInfo = bincode::deserialize(bytes).map_err(|_| e)?;
+        Ok(ApiKeyInfo {
+            id: legacy.id,
+            name: legacy.name,
+            created_at: legacy.created_at,
+            permissions: legacy.permissions,
+            active: legacy.active,
+            rate_limit_per_minute: None,
+            daily_token_quota: None,
+            tenant_id: None,
+        })
+    })
+}
+
+/// LLM tokens consumed by one API key on one UTC day.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct TokenUsage {
+    /// Days since the Unix epoch (UTC)
+    day: i64,
+    tokens: u64,
+}
+
+fn current_day() -> i64 {
+    chrono::Utc::now().timestamp().div_euclid(86_400)
+}
+
+/// Keys agent tools act with, one per tenant (see `AuthState::agent_key`).
+#[derive(Default)]
+struct AgentKeys {
+    /// Tenant -> plaintext key
+    by_tenant: DashMap<TenantId, String>,
+    /// Key hash -> info
+    by_hash: DashMap<String, ApiKeyInfo>,
 }
 
 /// Authentication state with persistent storage.
@@ -59,6 +160,10 @@ pub struct AuthState {
     db_path: String,
     /// Cryptographic service for key encryption
     crypto: Arc<CryptoService>,
+    /// Daily LLM token usage per key hash
+    token_usage: Arc<DashMap<String, TokenUsage>>,
+    /// Tenant-bound keys for agent tools, kept in memory only
+    agent_keys: Arc<AgentKeys>,
 }
 
 impl AuthState {
@@ -94,6 +199,8 @@ impl AuthState {
             api_keys: Arc::new(DashMap::from_iter(keys)),
             db_path: db_path.to_string(),
             crypto,
+            token_usage: Arc::new(Self::load_usage_from_db(db_path)),
+            agent_keys: Arc::default(),
         };
 
         // Persist keys to database (ensures newly generated keys are saved)
@@ -116,6 +223,8 @@ impl AuthState {
             api_keys: Arc::new(DashMap::new()),
             db_path: ":memory:".to_string(),
             crypto,
+            token_usage: Arc::new(DashMap::new()),
+            agent_keys: Arc::default(),
         }
     }
 
@@ -143,10 +252,13 @@ impl AuthState {
             keys
         };
 
+        let token_usage = Arc::new(Self::load_usage_from_db(&db_path));
         let state = Self {
             api_keys: Arc::new(DashMap::from_iter(keys)),
             db_path,
             crypto,
+            token_usage,
+            agent_keys: Arc::default(),
         };
 
         // Persist keys to database (ensures newly generated keys are saved)
@@ -184,25 +296,13 @@ impl AuthState {
                 // Load the metadata from the hashes table
                 let info = if let Ok(hash_table) = read_txn.open_table(API_KEY_HASHES_TABLE) {
                     if let Ok(Some(value)) = hash_table.get(hash_str) {
-                        bincode::deserialize(value.value())?
+                        decode_key_info(value.value())?
                     } else {
                         // Fallback for old format
-                        ApiKeyInfo {
-                            id: Uuid::new_v4().to_string(),
-                            name: "Migrated Key".to_string(),
-                            created_at: chrono::Utc::now().timestamp(),
-                            permissions: vec!["*".to_string()],
-                            active: true,
-                        }
+                        ApiKeyInfo::new("Migrated Key", vec!["*".to_string()])
                     }
                 } else {
-                    ApiKeyInfo {
-                        id: Uuid::new_v4().to_string(),
-                        name: "Migrated Key".to_string(),
-                        created_at: chrono::Utc::now().timestamp(),
-                        permissions: vec!["*".to_string()],
-                        active: true,
-                    }
+                    ApiKeyInfo::new("Migrated Key", vec!["*".to_string()])
                 };
 
                 keys.insert(hash_str.to_string(), (encrypted, info));
@@ -221,6 +321,53 @@ impl AuthState {
         Ok(keys)
     }
 
+    /// Load per-key token usage. A missing database or table means no usage yet.
+    fn load_usage_from_db(path: &str) -> DashMap<String, TokenUsage> {
+        let usage = DashMap::new();
+        let path_ref = std::path::Path::new(path);
+        if !path_ref.exists() {
+            return usage;
+        }
+        let result: Result<(), Box<dyn std::error::Error>> = (|| {
+            let db = Database::open(path_ref)?;
+            let read_txn = db.begin_read()?;
+            if let Ok(table) = read_txn.open_table(API_KEY_USAGE_TABLE) {
+                for item in table.iter()? {
+                    let (hash, value) = item?;
+                    let entry: TokenUsage = bincode::deserialize(value.value())?;
+                    usage.insert(hash.value().to_string(), entry);
+                }
+            }
+            Ok(())
+        })();
+        if let Err(e) = result {
+            warn!(category = "auth", error = %e, "Failed to load API key token usage");
+        }
+        usage
+    }
+
+    /// Persist the token usage of one key.
+    fn save_usage_to_db(
+        &self,
+        hash: &str,
+        usage: &TokenUsage,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path_ref = std::path::Path::new(&self.db_path);
+        let db = if path_ref.exists() {
+            Database::open(path_ref)?
+        } else {
+            Database::create(path_ref)?
+        };
+        let write_txn = db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(API_KEY_USAGE_TABLE)?;
+            let bytes = bincode::serialize(usage)?;
+            table.insert(hash, &*bytes)?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
     /// Save API keys to database with encryption.
     fn save_to_db(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
         let path_ref = std::path::Path::new(path);
@@ -247,6 +394,11 @@ impl AuthState {
                 table.remove(&**key)?;
                 hash_table.remove(&**key)?;
             }
+            // Drop usage of deleted keys
+            let mut usage_table = write_txn.open_table(API_KEY_USAGE_TABLE)?;
+            for key in to_delete.iter().filter(|k| !self.api_keys.contains_key(*k)) {
+                usage_table.remove(&**key)?;
+            }
 
             // Insert all current keys (already encrypted) - DashMap iter is lock-free
             for ref_item in self.api_keys.iter() {
@@ -264,13 +416,7 @@ impl AuthState {
     /// Generate a default API key for first-time setup.
     fn generate_default_key(crypto: &CryptoService) -> HashMap<String, (String, ApiKeyInfo)> {
         let key = format!("nmk_{}", Uuid::new_v4().to_string().replace("-", ""));
-        let info = ApiKeyInfo {
-            id: Uuid::new_v4().to_string(),
-            name: "Default API Key".to_string(),
-            created_at: chrono::Utc::now().timestamp(),
-            permissions: vec!["*".to_string()],
-            active: true,
-        };
+        let info = ApiKeyInfo::new("Default API Key", vec!["*".to_string()]);
 
         let hash = crypto.hash_api_key(&key);
         let encrypted = crypto.encrypt_str(&key).unwrap_or_else(|_| key.clone());
@@ -289,13 +435,7 @@ impl AuthState {
         crypto: &CryptoService,
     ) -> HashMap<String, (String, ApiKeyInfo)> {
         let key = format!("nmk_{}", Uuid::new_v4().to_string().replace("-", ""));
-        let info = ApiKeyInfo {
-            id: Uuid::new_v4().to_string(),
-            name: "Default API Key".to_string(),
-            created_at: chrono::Utc::now().timestamp(),
-            permissions: vec!["*".to_string()],
-            active: true,
-        };
+        let info = ApiKeyInfo::new("Default API Key", vec!["*".to_string()]);
 
         let hash = crypto.hash_api_key(&key);
         let encrypted = crypto.encrypt_str(&key).unwrap_or_else(|_| key.clone());
@@ -312,13 +452,7 @@ impl AuthState {
 
         // Load from NEOMIND_API_KEY environment variable
         if let Ok(default_key) = std::env::var("NEOMIND_API_KEY") {
-            let info = ApiKeyInfo {
-                id: Uuid::new_v4().to_string(),
-                name: "Default API Key (from env)".to_string(),
-                created_at: chrono::Utc::now().timestamp(),
-                permissions: vec!["*".to_string()],
-                active: true,
-            };
+            let info = ApiKeyInfo::new("Default API Key (from env)", vec!["*".to_string()]);
             let hash = crypto.hash_api_key(&default_key);
             let encrypted = crypto
                 .encrypt_str(&default_key)
@@ -335,11 +469,7 @@ impl AuthState {
 
     /// Validate an API key.
     pub fn validate_key(&self, key: &str) -> bool {
-        let hash = self.crypto.hash_api_key(key);
-        self.api_keys
-            .get(&hash)
-            .map(|item| item.value().1.active)
-            .unwrap_or(false)
+        self.validate_key_info(key).is_some()
     }
 
     /// Validate an API key and return its info.
@@ -349,9 +479,31 @@ impl AuthState {
         self.api_keys
             .get(&hash)
             .map(|item| item.value().1.clone())
+            .or_else(|| self.agent_keys.by_hash.get(&hash).map(|info| info.clone()))
             .filter(|info| info.active)
     }
 
+    /// API key agent tools use for the sessions of `tenant`.
+    ///
+    /// The key is bound to `tenant`, so the `neomind` commands a session runs
+    /// cannot reach other tenants' records whatever tenant header they send.
+    /// It is issued on first use, kept in memory only and never listed.
+    pub fn agent_key(&self, tenant: &TenantId) -> String {
+        self.agent_keys
+            .by_tenant
+            .entry(tenant.clone())
+            .or_insert_with(|| {
+                let key = format!("nmk_{}", Uuid::new_v4().to_string().replace("-", ""));
+                let mut info = ApiKeyInfo::new(format!("Agent ({})", tenant), vec!["*".into()]);
+                info.tenant_id = Some(tenant.clone());
+                self.agent_keys
+                    .by_hash
+                    .insert(self.crypto.hash_api_key(&key), info);
+                key
+            })
+            .clone()
+    }
+
     /// List all API keys (for admin endpoints).
     /// Returns the masked keys (first 8 chars only) with info.
     pub async fn list_keys(&self) -> Vec<(String, ApiKeyInfo)> {
@@ -363,14 +515,25 @@ impl AuthState {
 
     /// Create a new API key and persist to database.
     pub async fn create_key(&self, name: String, permissions: Vec<String>) -> (String, ApiKeyInfo) {
+        self.create_key_with_limits(name, permissions, None, None, None)
+            .await
+    }
+
+    /// Create a new API key with its own request rate limit, daily LLM
+    /// token quota and tenant binding, and persist to database.
+    pub async fn create_key_with_limits(
+        &self,
+        name: String,
+        permissions: Vec<String>,
+        rate_limit_per_minute: Option<u32>,
+        daily_token_quota: Option<u64>,
+        tenant_id: Option<TenantId>,
+    ) -> (String, ApiKeyInfo) {
         let key = format!("nmk_{}", Uuid::new_v4().to_string().replace("-", ""));
-        let info = ApiKeyInfo {
-            id: Uuid::new_v4().to_string(),
-            name,
-            created_at: chrono::Utc::now().timestamp(),
-            permissions,
-            active: true,
-        };
+        let mut info = ApiKeyInfo::new(name, permissions);
+        info.rate_limit_per_minute = rate_limit_per_minute;
+        info.daily_token_quota = daily_token_quota;
+        info.tenant_id = tenant_id;
 
         let hash = self.crypto.hash_api_key(&key);
         let encrypted = self
@@ -441,14 +604,8 @@ impl AuthState {
 
     /// Check if a key has a specific permission.
     pub fn check_permission(&self, key: &str, permission: &str) -> bool {
-        let hash = self.crypto.hash_api_key(key);
-        self.api_keys
-            .get(&hash)
-            .map(|item| {
-                let info = &item.value().1;
-                if !info.active {
-                    return false;
-                }
+        self.validate_key_info(key)
+            .map(|info| {
                 // Wildcard permission grants all
                 if info.permissions.contains(&"*".to_string()) {
                     return true;
@@ -458,6 +615,70 @@ impl AuthState {
             })
             .unwrap_or(false)
     }
+
+    /// Update the rate limit and token quota of the key with the given ID.
+    /// Returns the updated info, or `None` if no key has that ID.
+    pub async fn set_key_limits(
+        &self,
+        id: &str,
+        rate_limit_per_minute: Option<u32>,
+        daily_token_quota: Option<u64>,
+    ) -> Option<ApiKeyInfo> {
+        let info = self
+            .api_keys
+            .iter_mut()
+            .find(|item| item.value().1.id == id)
+            .map(|mut item| {
+                let info = &mut item.value_mut().1;
+                info.rate_limit_per_minute = rate_limit_per_minute;
+                info.daily_token_quota = daily_token_quota;
+                info.clone()
+            })?;
+
+        if let Err(e) = self.save_to_db(&self.db_path) {
+            warn!(category = "auth", error = %e, "Failed to save API keys to database");
+        }
+
+        Some(info)
+    }
+
+    /// LLM tokens `key` may still use today, or `None` if it has no quota.
+    pub fn tokens_remaining(&self, key: &str) -> Option<u64> {
+        let hash = self.crypto.hash_api_key(key);
+        let quota = self.api_keys.get(&hash)?.value().1.daily_token_quota?;
+        let used = self
+            .token_usage
+            .get(&hash)
+            .filter(|usage| usage.day == current_day())
+            .map(|usage| usage.tokens)
+            .unwrap_or(0);
+        Some(quota.saturating_sub(used))
+    }
+
+    /// Add `tokens` to today's LLM usage of `key` and persist it.
+    pub fn record_token_usage(&self, key: &str, tokens: u64) {
+        let hash = self.crypto.hash_api_key(key);
+        if tokens == 0 || !self.api_keys.contains_key(&hash) {
+            return;
+        }
+
+        let today = current_day();
+        let usage = {
+            let mut entry = self.token_usage.entry(hash.clone()).or_default();
+            if entry.day != today {
+                *entry = TokenUsage {
+                    day: today,
+                    tokens: 0,
+                };
+            }
+            entry.tokens = entry.tokens.saturating_add(tokens);
+            *entry
+        };
+
+        if let Err(e) = self.save_usage_to_db(&hash, &usage) {
+            warn!(category = "auth", error = %e, "Failed to save API key token usage");
+        }
+    }
 }
 
 impl Default for AuthState {
@@ -471,14 +692,19 @@ impl Default for AuthState {
 pub struct AuthError {
     pub status: StatusCode,
     pub message: String,
+    /// Permission the caller lacked (403 responses from `require_permission`).
+    pub missing_permission: Option<&'static str>,
 }
 
 impl IntoResponse for AuthError {
     fn into_response(self) -> Response {
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "error": self.message,
             "status": self.status.as_u16(),
         });
+        if let Some(permission) = self.missing_permission {
+            body["missing_permission"] = serde_json::json!(permission);
+        }
         (self.status, Json(body)).into_response()
     }
 }
@@ -488,6 +714,7 @@ impl AuthError {
         Self {
             status: StatusCode::UNAUTHORIZED,
             message: message.to_string(),
+            missing_permission: None,
         }
     }
 
@@ -495,10 +722,101 @@ impl AuthError {
         Self {
             status: StatusCode::FORBIDDEN,
             message: message.to_string(),
+            missing_permission: None,
+        }
+    }
+
+    pub fn missing_permission(permission: Permission) -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            message: format!("Missing permission: {}", permission.as_str()),
+            missing_permission: Some(permission.as_str()),
         }
     }
 }
 
+/// Role-based permission middleware.
+///
+/// Must run inside `hybrid_auth_middleware` (or `jwt_auth_middleware`), which
+/// put the caller's `SessionInfo` into the request extensions. Routes opt in
+/// through the `require_permission!` helper in `server::router`.
+pub async fn require_permission(
+    permission: Permission,
+    req: axum::extract::Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    let session = req
+        .extensions()
+        .get::<crate::auth_users::SessionInfo>()
+        .ok_or_else(|| AuthError::unauthorized("Authentication required"))?;
+
+    if !session.role.has_permission(permission) {
+        warn!(
+            category = "auth",
+            user = %session.username,
+            role = session.role.as_str(),
+            permission = permission.as_str(),
+            "Permission denied"
+        );
+        return Err(AuthError::missing_permission(permission));
+    }
+
+    Ok(next.run(req).await)
+}
+
+/// POST endpoints that only evaluate or read, so read-only roles may call them.
+const READ_ONLY_POSTS: &[&str] = &[
+    "/api/data/query",
+    "/api/skills/match",
+    "/api/knowledge/search",
+    "/api/rules/validate",
+    "/api/rules/simulate",
+    "/api/rules/conflicts",
+    "/api/settings/retention/preview",
+    "/api/agents/validate-cron",
+    "/api/config/validate",
+];
+
+/// Whether a request may be served to a read-only role.
+fn is_read_only_request(method: &Method, path: &str) -> bool {
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => true,
+        Method::POST => READ_ONLY_POSTS.contains(&path),
+        _ => false,
+    }
+}
+
+/// Default write guard for the protected routes.
+///
+/// `Viewer` is read-only: every request other than GET/HEAD/OPTIONS (and the
+/// evaluation endpoints in `READ_ONLY_POSTS`) is rejected with 403, whether
+/// or not the route also carries a `require_permission!` layer. Must run
+/// inside `hybrid_auth_middleware`.
+pub async fn reject_read_only_writes(
+    req: axum::extract::Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    let session = req
+        .extensions()
+        .get::<crate::auth_users::SessionInfo>()
+        .ok_or_else(|| AuthError::unauthorized("Authentication required"))?;
+
+    if session.role == crate::auth_users::UserRole::Viewer
+        && !is_read_only_request(req.method(), req.uri().path())
+    {
+        warn!(
+            category = "auth",
+            user = %session.username,
+            method = %req.method(),
+            path = req.uri().path(),
+            "Write denied for read-only role"
+        );
+        return Err(AuthError::forbidden("Read-only role cannot modify resources"));
+    }
+
+    Ok(next.run(req).await)
+}
+
 /// API Key authentication middleware.
 ///
 /// Checks for X-API-Key header and validates against stored keys.
@@ -542,6 +860,61 @@ pub async fn api_key_middleware(
 #[derive(Debug, Clone)]
 pub struct ValidatedApiKey(pub String);
 
+/// Tenant scope of the request, set by [`hybrid_auth_middleware`].
+///
+/// Routes outside that middleware see every tenant.
+#[derive(Debug, Clone, Default)]
+pub struct RequestTenant(pub TenantScope);
+
+#[axum::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestTenant {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(
+            parts
+                .extensions
+                .get::<TenantScope>()
+                .cloned()
+                .unwrap_or_default(),
+        ))
+    }
+}
+
+/// Resolve the tenant scope of a request from the tenant its user or API key
+/// is bound to and the optional [`TENANT_HEADER`].
+///
+/// Bound credentials are always limited to their tenant and may not name
+/// another one; everyone else sees all tenants unless the header narrows the
+/// scope.
+pub(crate) fn resolve_tenant_scope(
+    bound: Option<TenantId>,
+    headers: &HeaderMap,
+) -> Result<TenantScope, AuthError> {
+    let requested = match headers.get(TENANT_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|v| v.parse::<TenantId>().ok())
+                .ok_or_else(|| AuthError {
+                    status: StatusCode::BAD_REQUEST,
+                    message: format!("Invalid {} header", TENANT_HEADER),
+                    missing_permission: None,
+                })?,
+        ),
+        None => None,
+    };
+
+    match (bound, requested) {
+        (Some(bound), Some(requested)) if bound != requested => Err(AuthError::forbidden(
+            "Credentials are not allowed to access this tenant",
+        )),
+        (Some(tenant), _) | (None, Some(tenant)) => Ok(TenantScope::Tenant(tenant)),
+        (None, None) => Ok(TenantScope::All),
+    }
+}
+
 /// Optional authentication middleware.
 ///
 /// Allows requests without authentication but validates the key if provided.
@@ -616,24 +989,39 @@ pub async fn hybrid_auth_middleware(
         let proxy_session = crate::auth_users::SessionInfo {
             user_id: "share-proxy".to_string(),
             username: "share-proxy".to_string(),
-            role: crate::auth_users::UserRole::User,
+            role: crate::auth_users::UserRole::Operator,
             created_at: 0,
             expires_at: i64::MAX,
+            tenant_id: None,
         };
         req.extensions_mut().insert(proxy_session);
+        req.extensions_mut().insert(resolve_tenant_scope(None, &headers)?);
         return Ok(next.run(req).await);
     }
 
     // First, try to extract and validate JWT token from Authorization header
     if let Some(auth_header) = headers.get("authorization").and_then(|v| v.to_str().ok()) {
         if let Some(token) = auth_header.strip_prefix("Bearer ") {
-            // Try JWT authentication first
-            match state.auth.user_state.validate_token(token) {
+            // Try JWT authentication first. Setup-only sessions of admins
+            // without a second factor are limited to MFA enrollment.
+            let session = if allows_setup_session(req.uri().path()) {
+                state.auth.user_state.validate_setup_token(token)
+            } else {
+                state.auth.user_state.validate_token(token)
+            };
+            match session {
                 Ok(session_info) => {
                     // JWT token is valid, store session info and proceed
+                    let scope = resolve_tenant_scope(session_info.tenant_id.clone(), &headers)?;
                     req.extensions_mut().insert(session_info);
+                    req.extensions_mut().insert(scope);
                     return Ok(next.run(req).await);
                 }
+                Err(crate::auth_users::AuthError::MfaSetupRequired) => {
+                    return Err(AuthError::forbidden(
+                        "Enroll a second factor before using this account",
+                    ));
+                }
                 Err(_) => {
                     // JWT token is invalid or expired, fall through to API key check
                     // (but don't fail yet - maybe they're using API key)
@@ -655,21 +1043,11 @@ pub async fn hybrid_auth_middleware(
 
     if let Some(key) = api_key {
         if let Some(info) = state.auth.api_key_state.validate_key_info(key) {
+            let scope = resolve_tenant_scope(info.tenant_id.clone(), &headers)?;
             req.extensions_mut()
                 .insert(ValidatedApiKey(key.to_string()));
-            // Construct service account SessionInfo from API key info
-            let service_account = crate::auth_users::SessionInfo {
-                user_id: format!("apikey:{}", info.id),
-                username: info.name,
-                role: if info.permissions.contains(&"*".to_string()) {
-                    crate::auth_users::UserRole::Admin
-                } else {
-                    crate::auth_users::UserRole::User
-                },
-                created_at: info.created_at,
-                expires_at: i64::MAX,
-            };
-            req.extensions_mut().insert(service_account);
+            req.extensions_mut().insert(scope);
+            req.extensions_mut().insert(api_key_session(info));
             return Ok(next.run(req).await);
         }
     }
@@ -680,6 +1058,29 @@ pub async fn hybrid_auth_middleware(
     ))
 }
 
+/// Service account `SessionInfo` standing for an API key: keys with the `*`
+/// wildcard act as admins, all others as operators.
+pub(crate) fn api_key_session(info: ApiKeyInfo) -> crate::auth_users::SessionInfo {
+    crate::auth_users::SessionInfo {
+        user_id: format!("apikey:{}", info.id),
+        username: info.name,
+        role: if info.permissions.contains(&"*".to_string()) {
+            crate::auth_users::UserRole::Admin
+        } else {
+            crate::auth_users::UserRole::Operator
+        },
+        created_at: info.created_at,
+        expires_at: i64::MAX,
+        tenant_id: info.tenant_id,
+    }
+}
+
+/// Routes open to setup-only sessions: the MFA enrollment endpoints plus
+/// reading the current user and logging out.
+fn allows_setup_session(path: &str) -> bool {
+    path == "/api/auth/me" || path == "/api/auth/logout" || path.starts_with("/api/auth/mfa")
+}
+
 /// Constant-time string comparison. Prevents timing side-channels when
 /// comparing secrets. Returns false immediately if lengths differ (this
 /// leaks length info, which is acceptable for random secrets where length
@@ -727,4 +1128,116 @@ mod tests {
         assert!(auth.delete_key(&key).await);
         assert!(!auth.validate_key(&key));
     }
+
+    #[test]
+    fn test_legacy_key_info_decodes() {
+        let legacy = (
+            "id-1".to_string(),
+            "Old Key".to_string(),
+            42i64,
+            vec!["*".to_string()],
+            true,
+        );
+        let bytes = bincode::serialize(&legacy).unwrap();
+        let info = decode_key_info(&bytes).unwrap();
+        assert_eq!(info.name, "Old Key");
+        assert!(info.active);
+        assert_eq!(info.rate_limit_per_minute, None);
+        assert_eq!(info.daily_token_quota, None);
+        assert_eq!(info.tenant_id, None);
+
+        let unscoped = (
+            "id-2".to_string(),
+            "Limited Key".to_string(),
+            42i64,
+            vec!["*".to_string()],
+            true,
+            Some(10u32),
+            Some(100u64),
+        );
+        let bytes = bincode::serialize(&unscoped).unwrap();
+        let info = decode_key_info(&bytes).unwrap();
+        assert_eq!(info.rate_limit_per_minute, Some(10));
+        assert_eq!(info.tenant_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_bound_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().to_str().unwrap();
+        let auth = AuthState::new_with_data_dir(data_dir);
+        let acme = TenantId::new("acme").unwrap();
+
+        let (key, _) = auth
+            .create_key_with_limits("Acme".to_string(), vec![], None, None, Some(acme.clone()))
+            .await;
+        let reopened = AuthState::new_with_data_dir(data_dir);
+        let info = reopened.validate_key_info(&key).unwrap();
+        assert_eq!(info.tenant_id, Some(acme.clone()));
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            resolve_tenant_scope(info.tenant_id.clone(), &headers).unwrap(),
+            TenantScope::Tenant(acme.clone())
+        );
+        assert_eq!(resolve_tenant_scope(None, &headers).unwrap(), TenantScope::All);
+
+        headers.insert(TENANT_HEADER, "other".parse().unwrap());
+        let err = resolve_tenant_scope(Some(acme), &headers).unwrap_err();
+        assert_eq!(err.status, StatusCode::FORBIDDEN);
+        assert_eq!(
+            resolve_tenant_scope(None, &headers).unwrap(),
+            TenantScope::Tenant(TenantId::new("other").unwrap())
+        );
+
+        headers.insert(TENANT_HEADER, "Not Valid".parse().unwrap());
+        let err = resolve_tenant_scope(None, &headers).unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_agent_key_is_bound_and_not_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().to_str().unwrap();
+        let auth = AuthState::new_with_data_dir(data_dir);
+        let acme = TenantId::new("acme").unwrap();
+
+        let key = auth.agent_key(&acme);
+        assert_eq!(auth.agent_key(&acme), key);
+        assert_ne!(auth.agent_key(&TenantId::default()), key);
+        let info = auth.validate_key_info(&key).unwrap();
+        assert_eq!(info.tenant_id, Some(acme.clone()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(TENANT_HEADER, "other".parse().unwrap());
+        let err = resolve_tenant_scope(info.tenant_id, &headers).unwrap_err();
+        assert_eq!(err.status, StatusCode::FORBIDDEN);
+
+        auth.init_storage().await;
+        assert!(auth.list_keys().await.iter().all(|(_, info)| info.tenant_id.is_none()));
+        assert!(!AuthState::new_with_data_dir(data_dir).validate_key(&key));
+    }
+
+    #[tokio::test]
+    async fn test_token_quota_accounting() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().to_str().unwrap();
+        let auth = AuthState::new_with_data_dir(data_dir);
+
+        let (key, info) = auth
+            .create_key_with_limits("Dashboard".to_string(), vec![], Some(10), Some(100), None)
+            .await;
+        assert_eq!(info.rate_limit_per_minute, Some(10));
+        assert_eq!(auth.tokens_remaining(&key), Some(100));
+
+        auth.record_token_usage(&key, 30);
+        assert_eq!(auth.tokens_remaining(&key), Some(70));
+
+        // Usage survives a restart.
+        let reopened = AuthState::new_with_data_dir(data_dir);
+        assert_eq!(reopened.tokens_remaining(&key), Some(70));
+
+        reopened.set_key_limits(&info.id, None, None).await.unwrap();
+        assert_eq!(reopened.tokens_remaining(&key), None);
+    }
 }
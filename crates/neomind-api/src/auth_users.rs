@@ -12,6 +12,10 @@
 //! └──────────────┘     └──────────────┘     └──────────────┘
 //! ```
 //!
+//! Users may add a second factor (authenticator app or passkey, see
+//! [`crate::mfa`]); their password login then has to be finished through
+//! [`AuthUserState::complete_mfa_login`].
+//!
 //! # Usage
 //!
 //! ```rust,ignore
@@ -48,6 +52,13 @@ use axum::{
     response::{IntoResponse, Json, Response},
 };
 
+use neomind_core::tenant::TenantId;
+
+use crate::mfa::{
+    self, webauthn, MfaChallenges, MfaLoginChallenge, MfaProof, MfaRecord, MfaStatus, PasskeyInfo,
+    PendingLogin,
+};
+
 type HmacSha256 = Hmac<Sha256>;
 
 /// Helper function to safely create HMAC instance
@@ -58,6 +69,8 @@ fn create_hmac(key: &[u8]) -> Result<HmacSha256, AuthError> {
 
 // Table definitions
 const USERS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("users");
+// Second factors: key = user_id, value = MfaRecord (JSON)
+const MFA_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("user_mfa");
 
 /// User roles for RBAC
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -65,8 +78,10 @@ const USERS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("users");
 pub enum UserRole {
     /// Admin user - full access
     Admin,
-    /// Regular user - can use chat, manage own sessions
-    User,
+    /// Operator - can chat, control devices and manage rules.
+    /// Stored as "user" before roles were split out.
+    #[serde(alias = "user")]
+    Operator,
     /// Read-only user - can view but not modify
     Viewer,
 }
@@ -75,7 +90,7 @@ impl UserRole {
     pub fn as_str(&self) -> &'static str {
         match self {
             UserRole::Admin => "admin",
-            UserRole::User => "user",
+            UserRole::Operator => "operator",
             UserRole::Viewer => "viewer",
         }
     }
@@ -83,11 +98,75 @@ impl UserRole {
     pub fn parse(s: &str) -> Option<Self> {
         match s {
             "admin" => Some(UserRole::Admin),
-            "user" => Some(UserRole::User),
+            "operator" | "user" => Some(UserRole::Operator),
             "viewer" => Some(UserRole::Viewer),
             _ => None,
         }
     }
+
+    /// Whether this role grants `permission`.
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        match self {
+            UserRole::Admin => true,
+            UserRole::Operator => matches!(
+                permission,
+                Permission::DeviceControl
+                    | Permission::RuleWrite
+                    | Permission::RuleDelete
+                    | Permission::ApproveActions
+            ),
+            UserRole::Viewer => false,
+        }
+    }
+}
+
+/// Permissions guarding destructive operations.
+///
+/// Routes are annotated with the permission they need in `server::router`
+/// (see `auth::require_permission`); handlers that are not routed through
+/// that layer check `SessionInfo::ensure_permission` themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Send commands to devices
+    DeviceControl,
+    /// Create, update or import rules
+    RuleWrite,
+    /// Delete rules
+    RuleDelete,
+    /// Import a configuration backup
+    BackupRestore,
+    /// Create, list and delete user accounts
+    ManageUsers,
+    /// Change the per-session LLM token budget
+    ManageBudget,
+    /// Change runtime configuration
+    ManageConfig,
+    /// Create, rotate and delete integration secrets
+    ManageSecrets,
+    /// Approve or reject tool calls held by the agent
+    ApproveActions,
+    /// Install or upload extensions and frontend components
+    ManageExtensions,
+    /// Create, change and delete API keys
+    ManageApiKeys,
+}
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::DeviceControl => "device:control",
+            Permission::RuleWrite => "rule:write",
+            Permission::RuleDelete => "rule:delete",
+            Permission::BackupRestore => "backup:restore",
+            Permission::ManageUsers => "users:manage",
+            Permission::ManageBudget => "usage:budget",
+            Permission::ManageConfig => "config:write",
+            Permission::ManageSecrets => "secrets:manage",
+            Permission::ApproveActions => "agent:approve",
+            Permission::ManageExtensions => "extensions:manage",
+            Permission::ManageApiKeys => "apikeys:manage",
+        }
+    }
 }
 
 /// User account information.
@@ -107,6 +186,38 @@ pub struct User {
     pub last_login: Option<i64>,
     /// Whether user is active
     pub active: bool,
+    /// Tenant the user is bound to. `None` gives access to every tenant.
+    #[serde(default)]
+    pub tenant_id: Option<TenantId>,
+}
+
+/// `User` as stored before users could be bound to a tenant. bincode is not
+/// self-describing, so old records must be decoded with the old layout.
+#[derive(Deserialize)]
+struct UnscopedUser {
+    id: String,
+    username: String,
+    password_hash: String,
+    role: UserRole,
+    created_at: i64,
+    last_login: Option<i64>,
+    active: bool,
+}
+
+fn decode_user(bytes: &[u8]) -> Result<User, bincode::Error> {
+    bincode::deserialize(bytes).or_else(|e| {
+        let old: UnscopedUser = bincode::deserialize(bytes).map_err(|_| e)?;
+        Ok(User {
+            id: old.id,
+            username: old.username,
+            password_hash: old.password_hash,
+            role: old.role,
+            created_at: old.created_at,
+            last_login: old.last_login,
+            active: old.active,
+            tenant_id: None,
+        })
+    })
 }
 
 /// Session token information.
@@ -122,6 +233,26 @@ pub struct SessionInfo {
     pub created_at: i64,
     /// Session expiration time
     pub expires_at: i64,
+    /// Tenant the user is bound to (from the `tenant` token claim)
+    #[serde(default)]
+    pub tenant_id: Option<TenantId>,
+}
+
+impl SessionInfo {
+    /// Fail with `AuthError::Forbidden` unless the session's role grants `permission`.
+    pub fn ensure_permission(&self, permission: Permission) -> Result<(), AuthError> {
+        if self.role.has_permission(permission) {
+            Ok(())
+        } else {
+            Err(AuthError::Forbidden(permission))
+        }
+    }
+
+    /// Whether this session stands for an API key or the share proxy rather
+    /// than a person.
+    pub fn is_service_account(&self) -> bool {
+        self.user_id.starts_with("apikey:") || self.user_id == "share-proxy"
+    }
 }
 
 /// Login request.
@@ -136,6 +267,9 @@ pub struct LoginRequest {
 pub struct LoginResponse {
     pub token: String,
     pub user: UserInfo,
+    /// Set for administrators who have not enrolled a second factor yet
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub mfa_setup_required: bool,
 }
 
 /// User information (without password).
@@ -145,6 +279,8 @@ pub struct UserInfo {
     pub username: String,
     pub role: UserRole,
     pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<TenantId>,
 }
 
 /// Register request.
@@ -154,6 +290,15 @@ pub struct RegisterRequest {
     pub password: String,
     #[serde(default)]
     pub role: Option<UserRole>,
+    /// Tenant to bind the new user to (admin-created users only)
+    #[serde(default)]
+    pub tenant_id: Option<TenantId>,
+}
+
+/// Role change request (admin only).
+#[derive(Debug, Deserialize)]
+pub struct UpdateRoleRequest {
+    pub role: UserRole,
 }
 
 /// Change password request.
@@ -163,6 +308,33 @@ pub struct ChangePasswordRequest {
     pub new_password: String,
 }
 
+/// Second step of a login for users with a second factor.
+#[derive(Debug, Deserialize)]
+pub struct MfaLoginRequest {
+    pub mfa_token: String,
+    #[serde(flatten)]
+    pub proof: MfaProof,
+}
+
+/// Confirm authenticator app enrollment with its first code.
+#[derive(Debug, Deserialize)]
+pub struct TotpConfirmRequest {
+    pub code: String,
+    /// An existing factor, needed when one is already enrolled
+    #[serde(default)]
+    pub proof: MfaProof,
+}
+
+/// Finish passkey registration.
+#[derive(Debug, Deserialize)]
+pub struct PasskeyRegistrationRequest {
+    #[serde(flatten)]
+    pub credential: webauthn::RegistrationResponse,
+    /// An existing factor, needed when one is already enrolled
+    #[serde(default)]
+    pub proof: MfaProof,
+}
+
 /// Authentication state with user management.
 #[derive(Clone)]
 pub struct AuthUserState {
@@ -173,12 +345,20 @@ pub struct AuthUserState {
     /// caller (some in sync closures) to .await. Locks are never held across
     /// an await, so no deadlock risk.
     sessions: Arc<std::sync::RwLock<HashMap<String, SessionInfo>>>,
+    /// Sessions of admins who have not enrolled a second factor yet. They only
+    /// pass [`Self::validate_setup_token`], which guards MFA enrollment.
+    setup_sessions: Arc<std::sync::RwLock<HashMap<String, SessionInfo>>>,
     /// Database path
     db_path: &'static str,
     /// JWT secret key
     jwt_secret: String,
     /// Session duration (seconds)
     session_duration: i64,
+    /// Enrolled second factors by user ID (write-through to MFA_TABLE)
+    mfa: Arc<RwLock<HashMap<String, MfaRecord>>>,
+    /// Pending MFA logins and WebAuthn challenges. std::sync::Mutex for the
+    /// same reason as `sessions`: never held across an await.
+    challenges: Arc<std::sync::Mutex<MfaChallenges>>,
 }
 
 impl AuthUserState {
@@ -206,6 +386,7 @@ impl AuthUserState {
         // Load users from database
         // If no users exist, the setup wizard will handle creating the first admin
         let users = Self::load_users_from_db(db_path).unwrap_or_default();
+        let mfa = Self::load_mfa_from_db(db_path).unwrap_or_default();
 
         if users.is_empty() {
             info!(
@@ -217,15 +398,19 @@ impl AuthUserState {
         Self {
             users: Arc::new(RwLock::new(users)),
             sessions: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            setup_sessions: Arc::new(std::sync::RwLock::new(HashMap::new())),
             db_path,
             jwt_secret,
             session_duration: 7 * 24 * 60 * 60, // 7 days
+            mfa: Arc::new(RwLock::new(mfa)),
+            challenges: Arc::new(std::sync::Mutex::new(MfaChallenges::default())),
         }
     }
 
     /// Create a new auth state with custom configuration (for testing).
     pub fn with_config(db_path: String, jwt_secret: String) -> Self {
         let users = Self::load_users_from_db(&db_path).unwrap_or_default();
+        let mfa = Self::load_mfa_from_db(&db_path).unwrap_or_default();
         // Leak the strings to get &'static str for db_path
         let db_path_static: &'static str = Box::leak(db_path.into_boxed_str());
         let jwt_secret_owned = jwt_secret;
@@ -233,9 +418,12 @@ impl AuthUserState {
         Self {
             users: Arc::new(RwLock::new(users)),
             sessions: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            setup_sessions: Arc::new(std::sync::RwLock::new(HashMap::new())),
             db_path: db_path_static,
             jwt_secret: jwt_secret_owned,
             session_duration: 7 * 24 * 60 * 60,
+            mfa: Arc::new(RwLock::new(mfa)),
+            challenges: Arc::new(std::sync::Mutex::new(MfaChallenges::default())),
         }
     }
 
@@ -249,9 +437,12 @@ impl AuthUserState {
         Self {
             users: Arc::new(RwLock::new(HashMap::new())),
             sessions: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            setup_sessions: Arc::new(std::sync::RwLock::new(HashMap::new())),
             db_path: ":memory:", // Placeholder, won't be used
             jwt_secret,
             session_duration: 7 * 24 * 60 * 60,
+            mfa: Arc::new(RwLock::new(HashMap::new())),
+            challenges: Arc::new(std::sync::Mutex::new(MfaChallenges::default())),
         }
     }
 
@@ -271,7 +462,7 @@ impl AuthUserState {
         if let Ok(table) = read_txn.open_table(USERS_TABLE) {
             for item in table.iter()? {
                 let (username, value) = item?;
-                let user = bincode::deserialize::<User>(value.value())?;
+                let user = decode_user(value.value())?;
                 users.insert(username.value().to_string(), user);
             }
         }
@@ -299,6 +490,17 @@ impl AuthUserState {
         let username = user.username.clone();
         let user_bytes = bincode::serialize(user)?;
 
+        let db = Self::open_or_create_db(path)?;
+        let write_txn = db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(USERS_TABLE)?;
+            table.insert(username.as_str(), user_bytes.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    fn open_or_create_db(path: &str) -> Result<Database, Box<dyn std::error::Error>> {
         // Ensure parent directory exists
         if let Some(parent) = std::path::Path::new(path).parent() {
             std::fs::create_dir_all(parent)?;
@@ -310,11 +512,47 @@ impl AuthUserState {
         } else {
             Database::create(path)?
         };
+        Ok(db)
+    }
+
+    /// Load second factors from database, keyed by user ID.
+    fn load_mfa_from_db(
+        path: &str,
+    ) -> Result<HashMap<String, MfaRecord>, Box<dyn std::error::Error>> {
+        if !std::path::Path::new(path).exists() {
+            return Ok(HashMap::new());
+        }
+
+        let db = Database::open(path)?;
+        let read_txn = db.begin_read()?;
 
+        let mut records = HashMap::new();
+        if let Ok(table) = read_txn.open_table(MFA_TABLE) {
+            for item in table.iter()? {
+                let (user_id, value) = item?;
+                let record = serde_json::from_slice::<MfaRecord>(value.value())?;
+                records.insert(user_id.value().to_string(), record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Save a user's second factors to database synchronously.
+    fn save_mfa_to_db(
+        path: &str,
+        user_id: &str,
+        record: &MfaRecord,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if path == ":memory:" {
+            return Ok(());
+        }
+
+        let bytes = serde_json::to_vec(record)?;
+        let db = Self::open_or_create_db(path)?;
         let write_txn = db.begin_write()?;
         {
-            let mut table = write_txn.open_table(USERS_TABLE)?;
-            table.insert(username.as_str(), user_bytes.as_slice())?;
+            let mut table = write_txn.open_table(MFA_TABLE)?;
+            table.insert(user_id, bytes.as_slice())?;
         }
         write_txn.commit()?;
         Ok(())
@@ -379,16 +617,20 @@ impl AuthUserState {
 
         let header =
             BASE64_URL_SAFE_NO_PAD.encode(json!({"alg": "HS256", "typ": "JWT"}).to_string());
-        let payload = BASE64_URL_SAFE_NO_PAD.encode(
-            json!({
-                "sub": user.id,
-                "username": user.username,
-                "role": user.role.as_str(),
-                "iat": now,
-                "exp": expires_at,
-            })
-            .to_string(),
-        );
+        let mut claims = json!({
+            "sub": user.id,
+            "username": user.username,
+            "role": user.role.as_str(),
+            "iat": now,
+            "exp": expires_at,
+            // Two logins within the same second must not share a token,
+            // or a setup-only session could reuse a full one
+            "jti": uuid::Uuid::new_v4().simple().to_string(),
+        });
+        if let Some(tenant) = &user.tenant_id {
+            claims["tenant"] = json!(tenant);
+        }
+        let payload = BASE64_URL_SAFE_NO_PAD.encode(claims.to_string());
         let signature = {
             let data = format!("{}.{}", header, payload);
             let mut mac = create_hmac(self.jwt_secret.as_bytes())?;
@@ -440,25 +682,63 @@ impl AuthUserState {
         // map. login/register insert, logout removes — without this check
         // logout is a no-op (a JWT's signature/exp alone cannot be revoked).
         if !self.sessions.read().unwrap().contains_key(token) {
+            if self.setup_sessions.read().unwrap().contains_key(token) {
+                return Err(AuthError::MfaSetupRequired);
+            }
             return Err(AuthError::SessionRevoked);
         }
 
+        let tenant_id = match payload["tenant"].as_str() {
+            Some(tenant) => Some(
+                TenantId::new(tenant)
+                    .map_err(|_| AuthError::InvalidToken("Invalid tenant claim".into()))?,
+            ),
+            None => None,
+        };
+
         Ok(SessionInfo {
             user_id: payload["sub"].as_str().unwrap_or("").to_string(),
             username: payload["username"].as_str().unwrap_or("").to_string(),
             role: UserRole::parse(payload["role"].as_str().unwrap_or("user"))
-                .unwrap_or(UserRole::User),
+                .unwrap_or(UserRole::Operator),
             created_at: payload["iat"].as_i64().unwrap_or(0),
             expires_at: exp,
+            tenant_id,
         })
     }
 
-    /// Register a new user.
+    /// Like [`Self::validate_token`], but also accepts the setup-only tokens of
+    /// admins who still have to enroll a second factor.
+    pub fn validate_setup_token(&self, token: &str) -> Result<SessionInfo, AuthError> {
+        match self.validate_token(token) {
+            Err(AuthError::MfaSetupRequired) => self
+                .setup_sessions
+                .read()
+                .unwrap()
+                .get(token)
+                .cloned()
+                .ok_or(AuthError::SessionRevoked),
+            result => result,
+        }
+    }
+
+    /// Register a new user with access to every tenant.
     pub async fn register(
         &self,
         username: &str,
         password: &str,
         role: UserRole,
+    ) -> Result<(UserInfo, String), AuthError> {
+        self.register_in_tenant(username, password, role, None).await
+    }
+
+    /// Register a new user bound to `tenant` (`None`: every tenant).
+    pub async fn register_in_tenant(
+        &self,
+        username: &str,
+        password: &str,
+        role: UserRole,
+        tenant: Option<TenantId>,
     ) -> Result<(UserInfo, String), AuthError> {
         // Validate username
         if username.len() < 3 {
@@ -489,6 +769,7 @@ impl AuthUserState {
             created_at: chrono::Utc::now().timestamp(),
             last_login: None,
             active: true,
+            tenant_id: tenant,
         };
 
         // Save to database synchronously (ensures persistence before returning)
@@ -511,6 +792,8 @@ impl AuthUserState {
         // Register issues a usable token — insert into the active sessions
         // map so validate_token accepts it (same as login). Without this the
         // stateful-revocation check added to validate_token would reject it.
+        // A new admin has no second factor yet, so like at login the token is
+        // limited to MFA setup.
         {
             let session_info = SessionInfo {
                 user_id: user.id.clone(),
@@ -518,8 +801,14 @@ impl AuthUserState {
                 role: user.role.clone(),
                 created_at: chrono::Utc::now().timestamp(),
                 expires_at: chrono::Utc::now().timestamp() + self.session_duration,
+                tenant_id: user.tenant_id.clone(),
             };
-            self.sessions.write().unwrap().insert(token.clone(), session_info);
+            let sessions = if role == UserRole::Admin {
+                &self.setup_sessions
+            } else {
+                &self.sessions
+            };
+            sessions.write().unwrap().insert(token.clone(), session_info);
         }
 
         info!(
@@ -535,15 +824,18 @@ impl AuthUserState {
                 username: user.username.clone(),
                 role: user.role,
                 created_at: user.created_at,
+                tenant_id: user.tenant_id,
             },
             token,
         ))
     }
 
     /// Login user and return token.
+    ///
+    /// Users with a second factor get [`AuthError::MfaRequired`] instead and
+    /// finish with [`Self::complete_mfa_login`].
     pub async fn login(&self, username: &str, password: &str) -> Result<LoginResponse, AuthError> {
-        // Clone user data before releasing lock
-        let (user_id, user_role, user_created_at) = {
+        let user_id = {
             let users = self.users.read().await;
             let user = users.get(username).ok_or(AuthError::InvalidCredentials)?;
 
@@ -555,52 +847,162 @@ impl AuthUserState {
                 return Err(AuthError::InvalidCredentials);
             }
 
-            (user.id.clone(), user.role.clone(), user.created_at)
+            user.id.clone()
         };
 
-        // Update last login
-        let mut users = self.users.write().await;
-        if let Some(u) = users.get_mut(username) {
-            u.last_login = Some(chrono::Utc::now().timestamp());
+        if let Some(challenge) = self.begin_mfa_login(&user_id, username).await {
+            info!(
+                category = "auth",
+                username = username,
+                "Password accepted, waiting for second factor"
+            );
+            return Err(AuthError::MfaRequired(Box::new(challenge)));
         }
-        drop(users);
 
-        // Generate token
-        let token = {
-            let users = self.users.read().await;
-            let user = users.get(username).ok_or(AuthError::UserNotFound)?;
-            self.generate_token(user)?
+        self.start_session(username).await
+    }
+
+    /// Finish a login started by [`Self::login`] with a second factor.
+    ///
+    /// An MFA token allows at most [`mfa::MAX_LOGIN_ATTEMPTS`] attempts, and
+    /// the user is locked out after [`mfa::MAX_FACTOR_FAILURES`] failures in
+    /// a row, however many tokens they were spread over.
+    pub async fn complete_mfa_login(
+        &self,
+        mfa_token: &str,
+        proof: &MfaProof,
+    ) -> Result<LoginResponse, AuthError> {
+        let now = chrono::Utc::now().timestamp();
+        // The attempt is counted before the proof is checked, so concurrent
+        // guesses cannot get past the limit while verification is running
+        let pending = {
+            let mut challenges = self.challenges.lock().unwrap();
+            challenges.prune(now);
+            let login = challenges.logins.get_mut(mfa_token).ok_or_else(|| {
+                AuthError::InvalidToken("MFA login expired, sign in again".into())
+            })?;
+            login.attempts += 1;
+            let pending = login.clone();
+            if pending.attempts >= mfa::MAX_LOGIN_ATTEMPTS {
+                challenges.logins.remove(mfa_token);
+            }
+            pending
         };
 
+        let method = self
+            .update_mfa(&pending.user_id, |record| {
+                self.check_factor(&pending.user_id, now, || {
+                    record.verify(proof, pending.challenge.as_deref(), now)
+                })
+            })
+            .await?;
+        self.challenges.lock().unwrap().logins.remove(mfa_token);
+
+        info!(
+            category = "auth",
+            username = %pending.username,
+            method = method,
+            "Second factor accepted"
+        );
+        self.start_session(&pending.username).await
+    }
+
+    /// Hand out an MFA token if the user has a second factor.
+    async fn begin_mfa_login(&self, user_id: &str, username: &str) -> Option<MfaLoginChallenge> {
+        let record = self
+            .mfa
+            .read()
+            .await
+            .get(user_id)
+            .filter(|r| r.is_enabled())
+            .cloned()?;
+        let now = chrono::Utc::now().timestamp();
+        let challenge = (!record.passkeys.is_empty()).then(webauthn::new_challenge);
+        let login = MfaLoginChallenge {
+            mfa_token: uuid::Uuid::new_v4().simple().to_string(),
+            methods: record.methods(),
+            webauthn: challenge
+                .as_deref()
+                .map(|c| webauthn::request_options(c, &record.passkeys)),
+            expires_at: now + mfa::CHALLENGE_TTL_SECS,
+        };
+
+        let mut challenges = self.challenges.lock().unwrap();
+        challenges.prune(now);
+        challenges.logins.insert(
+            login.mfa_token.clone(),
+            PendingLogin {
+                user_id: user_id.to_string(),
+                username: username.to_string(),
+                challenge,
+                expires_at: login.expires_at,
+                attempts: 0,
+            },
+        );
+        Some(login)
+    }
+
+    /// Issue a session token to a user whose credentials were checked.
+    ///
+    /// Admins without a second factor get a setup-only token that is limited
+    /// to MFA enrollment; it becomes a full session once a factor is enrolled.
+    async fn start_session(&self, username: &str) -> Result<LoginResponse, AuthError> {
+        // Update last login
+        let user = {
+            let mut users = self.users.write().await;
+            let user = users.get_mut(username).ok_or(AuthError::UserNotFound)?;
+            user.last_login = Some(chrono::Utc::now().timestamp());
+            user.clone()
+        };
+
+        // Generate token
+        let token = self.generate_token(&user)?;
+        let mfa_setup_required = user.role == UserRole::Admin && !self.mfa_enabled(&user.id).await;
+
         // Store session
         let session_info = SessionInfo {
-            user_id: user_id.clone(),
+            user_id: user.id.clone(),
             username: username.to_string(),
-            role: user_role.clone(),
+            role: user.role.clone(),
             created_at: chrono::Utc::now().timestamp(),
             expires_at: chrono::Utc::now().timestamp() + self.session_duration,
+            tenant_id: user.tenant_id.clone(),
         };
-        let mut sessions = self.sessions.write().unwrap();
-        sessions.insert(token.clone(), session_info);
-        drop(sessions);
-
-        info!(category = "auth", username = username, "User logged in");
+        if mfa_setup_required {
+            self.setup_sessions
+                .write()
+                .unwrap()
+                .insert(token.clone(), session_info);
+            info!(
+                category = "auth",
+                username = username,
+                "Admin logged in without a second factor, session limited to MFA setup"
+            );
+        } else {
+            self.sessions
+                .write()
+                .unwrap()
+                .insert(token.clone(), session_info);
+            info!(category = "auth", username = username, "User logged in");
+        }
 
         Ok(LoginResponse {
             token,
             user: UserInfo {
-                id: user_id,
+                id: user.id,
                 username: username.to_string(),
-                role: user_role,
-                created_at: user_created_at,
+                role: user.role,
+                created_at: user.created_at,
+                tenant_id: user.tenant_id,
             },
+            mfa_setup_required,
         })
     }
 
     /// Logout user (invalidate session).
     pub async fn logout(&self, token: &str) -> Result<(), AuthError> {
-        let mut sessions = self.sessions.write().unwrap();
-        sessions.remove(token);
+        self.sessions.write().unwrap().remove(token);
+        self.setup_sessions.write().unwrap().remove(token);
         Ok(())
     }
 
@@ -614,6 +1016,7 @@ impl AuthUserState {
                 username: u.username.clone(),
                 role: u.role.clone(),
                 created_at: u.created_at,
+                tenant_id: u.tenant_id.clone(),
             })
             .collect()
     }
@@ -625,6 +1028,52 @@ impl AuthUserState {
         Ok(())
     }
 
+    /// Change a user's role and revoke their open sessions, whose tokens
+    /// still carry the old role.
+    pub async fn set_user_role(
+        &self,
+        username: &str,
+        role: UserRole,
+    ) -> Result<UserInfo, AuthError> {
+        let mut users = self.users.write().await;
+        let user = users.get_mut(username).ok_or(AuthError::UserNotFound)?;
+        let previous = std::mem::replace(&mut user.role, role);
+
+        if let Err(e) = Self::save_user_to_db(self.db_path, user) {
+            error!(category = "auth", username = username, error = %e, "Failed to save user to database");
+            user.role = previous;
+            return Err(AuthError::DatabaseError(format!(
+                "Failed to save user: {}",
+                e
+            )));
+        }
+
+        let user_id = user.id.clone();
+        self.sessions
+            .write()
+            .unwrap()
+            .retain(|_, session| session.user_id != user_id);
+        self.setup_sessions
+            .write()
+            .unwrap()
+            .retain(|_, session| session.user_id != user_id);
+
+        info!(
+            category = "auth",
+            username = username,
+            role = user.role.as_str(),
+            "User role changed"
+        );
+
+        Ok(UserInfo {
+            id: user.id.clone(),
+            username: user.username.clone(),
+            role: user.role.clone(),
+            created_at: user.created_at,
+            tenant_id: user.tenant_id.clone(),
+        })
+    }
+
     /// Change password.
     pub async fn change_password(
         &self,
@@ -651,6 +1100,351 @@ impl AuthUserState {
 
         Ok(())
     }
+
+    /// A user's second factors.
+    pub async fn mfa_status(&self, username: &str) -> Result<MfaStatus, AuthError> {
+        let user = self.find_user(username).await?;
+        let record = self
+            .mfa
+            .read()
+            .await
+            .get(&user.id)
+            .cloned()
+            .unwrap_or_default();
+        Ok(record.status(user.role == UserRole::Admin))
+    }
+
+    /// Start authenticator app enrollment. Returns the secret and the
+    /// `otpauth://` URI to show as a QR code.
+    pub async fn begin_totp_enrollment(
+        &self,
+        username: &str,
+    ) -> Result<(String, String), AuthError> {
+        let user = self.find_user(username).await?;
+        let secret = mfa::totp::generate_secret();
+        self.update_mfa(&user.id, |record| {
+            if record.totp_secret.is_some() {
+                return Err(AuthError::InvalidInput(
+                    "An authenticator app is already enrolled; remove it first".into(),
+                ));
+            }
+            record.pending_totp_secret = Some(secret.clone());
+            Ok(())
+        })
+        .await?;
+        let uri = mfa::totp::otpauth_uri(username, &secret);
+        Ok((secret, uri))
+    }
+
+    /// Finish authenticator app enrollment with its first code. Returns new
+    /// recovery codes when this is the user's first factor.
+    pub async fn confirm_totp_enrollment(
+        &self,
+        username: &str,
+        code: &str,
+        proof: &MfaProof,
+    ) -> Result<Option<Vec<String>>, AuthError> {
+        let user = self.find_user(username).await?;
+        let now = chrono::Utc::now().timestamp();
+        let codes = self
+            .with_step_up(&user, proof, true, |record| {
+                let secret = record.pending_totp_secret.take().ok_or_else(|| {
+                    AuthError::InvalidInput("No authenticator app enrollment in progress".into())
+                })?;
+                let step = mfa::totp::verify(&secret, code, now as u64, None)
+                    .ok_or_else(|| AuthError::MfaFailed("Invalid authentication code".into()))?;
+                let first_factor = !record.is_enabled();
+                record.totp_secret = Some(secret);
+                record.totp_last_step = Some(step);
+                Ok(first_factor.then(|| record.reset_recovery_codes()))
+            })
+            .await?;
+        self.promote_setup_sessions(&user.id);
+        info!(category = "auth", username = username, "Authenticator app enrolled");
+        Ok(codes)
+    }
+
+    /// Turn the user's setup-only sessions into full ones once a second
+    /// factor is enrolled, so the UI can carry on without a new login.
+    fn promote_setup_sessions(&self, user_id: &str) {
+        let mut setup_sessions = self.setup_sessions.write().unwrap();
+        let tokens: Vec<String> = setup_sessions
+            .iter()
+            .filter(|(_, session)| session.user_id == user_id)
+            .map(|(token, _)| token.clone())
+            .collect();
+        let mut sessions = self.sessions.write().unwrap();
+        for token in tokens {
+            if let Some(session) = setup_sessions.remove(&token) {
+                sessions.insert(token, session);
+            }
+        }
+    }
+
+    /// Remove the authenticator app.
+    pub async fn disable_totp(&self, username: &str, proof: &MfaProof) -> Result<(), AuthError> {
+        let user = self.find_user(username).await?;
+        self.with_step_up(&user, proof, false, |record| {
+            if record.totp_secret.take().is_none() {
+                return Err(AuthError::InvalidInput(
+                    "No authenticator app is enrolled".into(),
+                ));
+            }
+            Self::keep_required_factor(&user, record)
+        })
+        .await?;
+        info!(category = "auth", username = username, "Authenticator app removed");
+        Ok(())
+    }
+
+    /// Replace the user's recovery codes.
+    pub async fn regenerate_recovery_codes(
+        &self,
+        username: &str,
+        proof: &MfaProof,
+    ) -> Result<Vec<String>, AuthError> {
+        let user = self.find_user(username).await?;
+        self.with_step_up(&user, proof, false, |record| {
+            Ok(record.reset_recovery_codes())
+        })
+        .await
+    }
+
+    /// Start passkey registration for a page served from `origin`. Returns
+    /// the options for `navigator.credentials.create()`.
+    pub async fn begin_passkey_registration(
+        &self,
+        username: &str,
+        origin: &str,
+    ) -> Result<serde_json::Value, AuthError> {
+        let user = self.find_user(username).await?;
+        let now = chrono::Utc::now().timestamp();
+        let challenge =
+            webauthn::registration_challenge(origin, now).map_err(AuthError::InvalidInput)?;
+        let existing = self
+            .mfa
+            .read()
+            .await
+            .get(&user.id)
+            .map(|r| r.passkeys.clone())
+            .unwrap_or_default();
+        let options = webauthn::creation_options(&challenge, &user.id, username, &existing);
+
+        let mut challenges = self.challenges.lock().unwrap();
+        challenges.prune(now);
+        challenges.registrations.insert(user.id, challenge);
+        Ok(options)
+    }
+
+    /// Finish passkey registration. Returns new recovery codes when this is
+    /// the user's first factor.
+    pub async fn finish_passkey_registration(
+        &self,
+        username: &str,
+        credential: &webauthn::RegistrationResponse,
+        proof: &MfaProof,
+    ) -> Result<(PasskeyInfo, Option<Vec<String>>), AuthError> {
+        let user = self.find_user(username).await?;
+        let now = chrono::Utc::now().timestamp();
+        let challenge = self
+            .challenges
+            .lock()
+            .unwrap()
+            .registrations
+            .remove(&user.id)
+            .filter(|c| c.expires_at >= now)
+            .ok_or_else(|| AuthError::InvalidInput("No passkey registration in progress".into()))?;
+        let passkey = webauthn::verify_registration(credential, &challenge, now)
+            .map_err(AuthError::MfaFailed)?;
+        let info = PasskeyInfo::from(&passkey);
+
+        let codes = self
+            .with_step_up(&user, proof, true, |record| {
+                if record.passkeys.iter().any(|p| p.id == passkey.id) {
+                    return Err(AuthError::InvalidInput(
+                        "This passkey is already registered".into(),
+                    ));
+                }
+                let first_factor = !record.is_enabled();
+                record.passkeys.push(passkey);
+                Ok(first_factor.then(|| record.reset_recovery_codes()))
+            })
+            .await?;
+        self.promote_setup_sessions(&user.id);
+        info!(category = "auth", username = username, passkey = %info.name, "Passkey registered");
+        Ok((info, codes))
+    }
+
+    /// Remove a passkey.
+    pub async fn remove_passkey(
+        &self,
+        username: &str,
+        passkey_id: &str,
+        proof: &MfaProof,
+    ) -> Result<(), AuthError> {
+        let user = self.find_user(username).await?;
+        self.with_step_up(&user, proof, false, |record| {
+            let before = record.passkeys.len();
+            record.passkeys.retain(|p| p.id != passkey_id);
+            if record.passkeys.len() == before {
+                return Err(AuthError::InvalidInput(format!(
+                    "Passkey not found: {}",
+                    passkey_id
+                )));
+            }
+            Self::keep_required_factor(&user, record)
+        })
+        .await?;
+        info!(category = "auth", username = username, "Passkey removed");
+        Ok(())
+    }
+
+    /// Options for confirming a sensitive action with a passkey, or `None`
+    /// when the user has no passkey and must use a code instead.
+    pub async fn begin_step_up(
+        &self,
+        username: &str,
+    ) -> Result<Option<serde_json::Value>, AuthError> {
+        let user = self.find_user(username).await?;
+        let passkeys = self
+            .mfa
+            .read()
+            .await
+            .get(&user.id)
+            .map(|r| r.passkeys.clone())
+            .unwrap_or_default();
+        if passkeys.is_empty() {
+            return Ok(None);
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let challenge = webauthn::new_challenge();
+        let options = webauthn::request_options(&challenge, &passkeys);
+        let mut challenges = self.challenges.lock().unwrap();
+        challenges.prune(now);
+        challenges
+            .step_ups
+            .insert(user.id, (challenge, now + mfa::CHALLENGE_TTL_SECS));
+        Ok(Some(options))
+    }
+
+    /// Check a second factor presented to confirm a sensitive action.
+    pub async fn verify_step_up(&self, username: &str, proof: &MfaProof) -> Result<(), AuthError> {
+        let user = self.find_user(username).await?;
+        self.with_step_up(&user, proof, false, |_| Ok(())).await
+    }
+
+    /// Remove all of a user's second factors, e.g. after a lost phone.
+    pub async fn reset_mfa(&self, username: &str) -> Result<(), AuthError> {
+        let user = self.find_user(username).await?;
+        self.update_mfa(&user.id, |record| {
+            *record = MfaRecord::default();
+            Ok(())
+        })
+        .await?;
+        info!(category = "auth", username = username, "Second factors reset");
+        Ok(())
+    }
+
+    async fn find_user(&self, username: &str) -> Result<User, AuthError> {
+        self.users
+            .read()
+            .await
+            .get(username)
+            .cloned()
+            .ok_or(AuthError::UserNotFound)
+    }
+
+    async fn mfa_enabled(&self, user_id: &str) -> bool {
+        self.mfa
+            .read()
+            .await
+            .get(user_id)
+            .is_some_and(MfaRecord::is_enabled)
+    }
+
+    /// Administrators must not remove their last second factor.
+    fn keep_required_factor(user: &User, record: &MfaRecord) -> Result<(), AuthError> {
+        if user.role == UserRole::Admin && !record.is_enabled() {
+            return Err(AuthError::InvalidInput(
+                "Administrators must keep at least one second factor".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Apply `f` to a user's MFA record and persist it.
+    async fn update_mfa<T>(
+        &self,
+        user_id: &str,
+        f: impl FnOnce(&mut MfaRecord) -> Result<T, AuthError>,
+    ) -> Result<T, AuthError> {
+        let mut records = self.mfa.write().await;
+        let mut record = records.get(user_id).cloned().unwrap_or_default();
+        let result = f(&mut record)?;
+        record.clear_if_unused();
+
+        if let Err(e) = Self::save_mfa_to_db(self.db_path, user_id, &record) {
+            error!(category = "auth", user_id = user_id, error = %e, "Failed to save second factors");
+            return Err(AuthError::DatabaseError(format!(
+                "Failed to save second factors: {}",
+                e
+            )));
+        }
+        records.insert(user_id.to_string(), record);
+        Ok(result)
+    }
+
+    /// Run a second-factor check for `user_id` under the per-user failure
+    /// limit shared by login and step-up.
+    fn check_factor<T>(
+        &self,
+        user_id: &str,
+        now: i64,
+        verify: impl FnOnce() -> Result<T, String>,
+    ) -> Result<T, AuthError> {
+        self.challenges
+            .lock()
+            .unwrap()
+            .begin_factor_attempt(user_id, now)
+            .map_err(AuthError::MfaLocked)?;
+        let result = verify().map_err(AuthError::MfaFailed)?;
+        self.challenges.lock().unwrap().factor_accepted(user_id);
+        Ok(result)
+    }
+
+    /// Check `proof` against the user's factors, then apply `f`. With
+    /// `only_if_enrolled`, users without any factor skip the check (first
+    /// enrollment).
+    async fn with_step_up<T>(
+        &self,
+        user: &User,
+        proof: &MfaProof,
+        only_if_enrolled: bool,
+        f: impl FnOnce(&mut MfaRecord) -> Result<T, AuthError>,
+    ) -> Result<T, AuthError> {
+        let now = chrono::Utc::now().timestamp();
+        let challenge = {
+            let mut challenges = self.challenges.lock().unwrap();
+            challenges.prune(now);
+            challenges.step_ups.get(&user.id).map(|(c, _)| c.clone())
+        };
+
+        let result = self
+            .update_mfa(&user.id, |record| {
+                if record.is_enabled() || !only_if_enrolled {
+                    self.check_factor(&user.id, now, || {
+                        record.verify(proof, challenge.as_deref(), now)
+                    })?;
+                }
+                f(record)
+            })
+            .await?;
+        if proof.webauthn.is_some() {
+            self.challenges.lock().unwrap().step_ups.remove(&user.id);
+        }
+        Ok(result)
+    }
 }
 
 impl Default for AuthUserState {
@@ -672,6 +1466,16 @@ pub enum AuthError {
     SessionRevoked,
     InvalidInput(String),
     DatabaseError(String),
+    /// The caller's role lacks the given permission.
+    Forbidden(Permission),
+    /// Password accepted; the login must be finished with a second factor.
+    MfaRequired(Box<MfaLoginChallenge>),
+    /// A second factor was missing or did not check out.
+    MfaFailed(String),
+    /// Too many failed second factors; seconds until the next try.
+    MfaLocked(i64),
+    /// Setup-only session of an admin who has not enrolled a second factor.
+    MfaSetupRequired,
 }
 
 impl std::fmt::Display for AuthError {
@@ -686,6 +1490,17 @@ impl std::fmt::Display for AuthError {
             AuthError::SessionRevoked => write!(f, "Session has been revoked"),
             AuthError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
             AuthError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            AuthError::Forbidden(permission) => {
+                write!(f, "Missing permission: {}", permission.as_str())
+            }
+            AuthError::MfaRequired(_) => write!(f, "Second factor required"),
+            AuthError::MfaFailed(msg) => write!(f, "Second factor rejected: {}", msg),
+            AuthError::MfaLocked(secs) => write!(
+                f,
+                "Too many failed second factors, try again in {} seconds",
+                secs
+            ),
+            AuthError::MfaSetupRequired => write!(f, "Second factor enrollment required"),
         }
     }
 }
@@ -711,6 +1526,47 @@ impl IntoResponse for AuthError {
             }
             AuthError::InvalidInput(msg) => (HttpStatusCode::BAD_REQUEST, msg),
             AuthError::DatabaseError(msg) => (HttpStatusCode::INTERNAL_SERVER_ERROR, msg),
+            AuthError::Forbidden(permission) => {
+                let body = serde_json::json!({
+                    "error": format!("Missing permission: {}", permission.as_str()),
+                    "status": HttpStatusCode::FORBIDDEN.as_u16(),
+                    "missing_permission": permission.as_str(),
+                });
+                return (HttpStatusCode::FORBIDDEN, Json(body)).into_response();
+            }
+            AuthError::MfaRequired(challenge) => {
+                let body = serde_json::json!({
+                    "error": "Second factor required",
+                    "status": HttpStatusCode::UNAUTHORIZED.as_u16(),
+                    "mfa_required": true,
+                    "mfa": challenge,
+                });
+                return (HttpStatusCode::UNAUTHORIZED, Json(body)).into_response();
+            }
+            // 403 rather than 401: a rejected step-up code must not look
+            // like an expired session to the web UI
+            AuthError::MfaFailed(msg) => (HttpStatusCode::FORBIDDEN, msg),
+            AuthError::MfaLocked(secs) => {
+                let body = serde_json::json!({
+                    "error": AuthError::MfaLocked(secs).to_string(),
+                    "status": HttpStatusCode::TOO_MANY_REQUESTS.as_u16(),
+                    "retry_after": secs,
+                });
+                return (
+                    HttpStatusCode::TOO_MANY_REQUESTS,
+                    [(axum::http::header::RETRY_AFTER, secs.to_string())],
+                    Json(body),
+                )
+                    .into_response();
+            }
+            AuthError::MfaSetupRequired => {
+                let body = serde_json::json!({
+                    "error": "Enroll a second factor before using this account",
+                    "status": HttpStatusCode::FORBIDDEN.as_u16(),
+                    "mfa_setup_required": true,
+                });
+                return (HttpStatusCode::FORBIDDEN, Json(body)).into_response();
+            }
         };
 
         let body = serde_json::json!({
@@ -811,7 +1667,7 @@ mod tests {
     async fn test_user_registration() {
         let (auth, db_path) = make_test_auth("registration");
         let (user, token) = auth
-            .register("testuser", "password123", UserRole::User)
+            .register("testuser", "password123", UserRole::Operator)
             .await
             .unwrap();
         assert_eq!(user.username, "testuser");
@@ -822,7 +1678,7 @@ mod tests {
     #[tokio::test]
     async fn test_user_login() {
         let (auth, _) = make_test_auth("login");
-        auth.register("testuser", "password123", UserRole::User)
+        auth.register("testuser", "password123", UserRole::Operator)
             .await
             .unwrap();
 
@@ -835,7 +1691,7 @@ mod tests {
     async fn test_token_validation() {
         let (auth, db_path) = make_test_auth("token_validation");
         let (_, token) = auth
-            .register("testuser", "password123", UserRole::User)
+            .register("testuser", "password123", UserRole::Operator)
             .await
             .unwrap();
 
@@ -844,13 +1700,51 @@ mod tests {
         cleanup_test_db(&db_path);
     }
 
+    #[tokio::test]
+    async fn test_tenant_bound_user() {
+        let (auth, db_path) = make_test_auth("tenant_bound");
+        let acme = TenantId::new("acme").unwrap();
+        let (user, token) = auth
+            .register_in_tenant("testuser", "password123", UserRole::Operator, Some(acme.clone()))
+            .await
+            .unwrap();
+        assert_eq!(user.tenant_id, Some(acme.clone()));
+        assert_eq!(auth.validate_token(&token).unwrap().tenant_id, Some(acme.clone()));
+
+        let reopened =
+            AuthUserState::with_config(db_path.display().to_string(), "other_secret".to_string());
+        let response = reopened.login("testuser", "password123").await.unwrap();
+        assert_eq!(response.user.tenant_id, Some(acme.clone()));
+        let session = reopened.validate_token(&response.token).unwrap();
+        assert_eq!(session.tenant_id, Some(acme));
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_decode_user_without_tenant() {
+        // Field order of `User` before the tenant was added
+        let old = (
+            "id-1",
+            "alice",
+            "hash",
+            UserRole::Viewer,
+            10i64,
+            None::<i64>,
+            true,
+        );
+        let user = decode_user(&bincode::serialize(&old).unwrap()).unwrap();
+        assert_eq!(user.username, "alice");
+        assert_eq!(user.role, UserRole::Viewer);
+        assert_eq!(user.tenant_id, None);
+    }
+
     #[tokio::test]
     async fn test_logout_revokes_token() {
         // Regression: logout must actually invalidate the JWT, not just remove
         // a dead sessions-map entry. validate_token consults the map now, so a
         // logged-out token is rejected with SessionRevoked.
         let (auth, db_path) = make_test_auth("logout_revokes");
-        auth.register("testuser", "password123", UserRole::User)
+        auth.register("testuser", "password123", UserRole::Operator)
             .await
             .unwrap();
         let resp = auth.login("testuser", "password123").await.unwrap();
@@ -867,4 +1761,267 @@ mod tests {
         ));
         cleanup_test_db(&db_path);
     }
+
+    /// Current and following TOTP codes for a base32 secret.
+    fn totp_codes(secret: &str) -> (String, String) {
+        let key = mfa::totp::base32_decode(secret).unwrap();
+        let step = chrono::Utc::now().timestamp() as u64 / mfa::totp::STEP_SECS;
+        let code = |step| format!("{:06}", mfa::totp::code_at(&key, step, mfa::totp::DIGITS));
+        (code(step), code(step + 1))
+    }
+
+    #[tokio::test]
+    async fn test_login_with_second_factor() {
+        let (auth, db_path) = make_test_auth("login_mfa");
+        auth.register("testuser", "password123", UserRole::Operator)
+            .await
+            .unwrap();
+        let (secret, uri) = auth.begin_totp_enrollment("testuser").await.unwrap();
+        assert!(uri.contains(&secret));
+        let (current, _) = totp_codes(&secret);
+        let recovery = auth
+            .confirm_totp_enrollment("testuser", &current, &MfaProof::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recovery.len(), mfa::RECOVERY_CODE_COUNT);
+
+        let challenge = match auth.login("testuser", "password123").await {
+            Err(AuthError::MfaRequired(challenge)) => challenge,
+            _ => panic!("expected a second-factor challenge"),
+        };
+        assert_eq!(challenge.methods, ["totp", "recovery_code"]);
+
+        // The enrollment code cannot be replayed
+        let replay = MfaProof {
+            code: Some(current),
+            ..Default::default()
+        };
+        assert!(matches!(
+            auth.complete_mfa_login(&challenge.mfa_token, &replay).await,
+            Err(AuthError::MfaFailed(_))
+        ));
+
+        let proof = MfaProof {
+            recovery_code: Some(recovery[0].clone()),
+            ..Default::default()
+        };
+        let response = auth
+            .complete_mfa_login(&challenge.mfa_token, &proof)
+            .await
+            .unwrap();
+        assert!(auth.validate_token(&response.token).is_ok());
+        // MFA tokens are single-use
+        assert!(auth
+            .complete_mfa_login(&challenge.mfa_token, &proof)
+            .await
+            .is_err());
+
+        // Factors and used recovery codes survive a restart
+        let reloaded = AuthUserState::with_config(
+            db_path.display().to_string(),
+            "test_secret_key_12345678".to_string(),
+        );
+        let status = reloaded.mfa_status("testuser").await.unwrap();
+        assert!(status.totp);
+        assert_eq!(status.recovery_codes_remaining, mfa::RECOVERY_CODE_COUNT - 1);
+        cleanup_test_db(&db_path);
+    }
+
+    #[tokio::test]
+    async fn test_mfa_login_attempts_are_limited() {
+        let (auth, db_path) = make_test_auth("mfa_attempts");
+        auth.register("testuser", "password123", UserRole::Operator)
+            .await
+            .unwrap();
+        let (secret, _) = auth.begin_totp_enrollment("testuser").await.unwrap();
+        let (current, next) = totp_codes(&secret);
+        auth.confirm_totp_enrollment("testuser", &current, &MfaProof::default())
+            .await
+            .unwrap();
+
+        let challenge = match auth.login("testuser", "password123").await {
+            Err(AuthError::MfaRequired(challenge)) => challenge,
+            _ => panic!("expected a second-factor challenge"),
+        };
+        let wrong = MfaProof {
+            recovery_code: Some("not-a-recovery-code".into()),
+            ..Default::default()
+        };
+        for _ in 0..mfa::MAX_LOGIN_ATTEMPTS {
+            assert!(matches!(
+                auth.complete_mfa_login(&challenge.mfa_token, &wrong).await,
+                Err(AuthError::MfaFailed(_))
+            ));
+        }
+
+        // Out of attempts: even the right code no longer works
+        let proof = MfaProof {
+            code: Some(next),
+            ..Default::default()
+        };
+        assert!(matches!(
+            auth.complete_mfa_login(&challenge.mfa_token, &proof).await,
+            Err(AuthError::InvalidToken(_))
+        ));
+        cleanup_test_db(&db_path);
+    }
+
+    #[tokio::test]
+    async fn test_mfa_failures_lock_out_across_logins() {
+        let (auth, db_path) = make_test_auth("mfa_lockout");
+        auth.register("testuser", "password123", UserRole::Operator)
+            .await
+            .unwrap();
+        let (secret, _) = auth.begin_totp_enrollment("testuser").await.unwrap();
+        let (current, _) = totp_codes(&secret);
+        let recovery = auth
+            .confirm_totp_enrollment("testuser", &current, &MfaProof::default())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let login = || async {
+            match auth.login("testuser", "password123").await {
+                Err(AuthError::MfaRequired(challenge)) => challenge.mfa_token,
+                _ => panic!("expected a second-factor challenge"),
+            }
+        };
+        let wrong = MfaProof {
+            recovery_code: Some("not-a-recovery-code".into()),
+            ..Default::default()
+        };
+        // A fresh MFA token per guess does not reset the count
+        for _ in 0..mfa::MAX_FACTOR_FAILURES {
+            let token = login().await;
+            assert!(matches!(
+                auth.complete_mfa_login(&token, &wrong).await,
+                Err(AuthError::MfaFailed(_))
+            ));
+        }
+
+        let right = MfaProof {
+            recovery_code: Some(recovery[0].clone()),
+            ..Default::default()
+        };
+        let token = login().await;
+        assert!(matches!(
+            auth.complete_mfa_login(&token, &right).await,
+            Err(AuthError::MfaLocked(secs)) if secs > 0
+        ));
+        // Step-up shares the lockout
+        assert!(matches!(
+            auth.verify_step_up("testuser", &right).await,
+            Err(AuthError::MfaLocked(_))
+        ));
+        // The locked-out attempt did not consume the recovery code
+        let status = auth.mfa_status("testuser").await.unwrap();
+        assert_eq!(status.recovery_codes_remaining, mfa::RECOVERY_CODE_COUNT);
+        cleanup_test_db(&db_path);
+    }
+
+    #[tokio::test]
+    async fn test_registered_admin_gets_setup_only_session() {
+        let (auth, db_path) = make_test_auth("admin_register_mfa");
+        let (_, admin_token) = auth
+            .register("admin", "password123", UserRole::Admin)
+            .await
+            .unwrap();
+        assert!(matches!(
+            auth.validate_token(&admin_token),
+            Err(AuthError::MfaSetupRequired)
+        ));
+        assert!(auth.validate_setup_token(&admin_token).is_ok());
+
+        let (_, operator_token) = auth
+            .register("operator", "password123", UserRole::Operator)
+            .await
+            .unwrap();
+        assert!(auth.validate_token(&operator_token).is_ok());
+        cleanup_test_db(&db_path);
+    }
+
+    #[tokio::test]
+    async fn test_admin_keeps_a_second_factor() {
+        let (auth, db_path) = make_test_auth("admin_mfa");
+        auth.register("admin", "password123", UserRole::Admin)
+            .await
+            .unwrap();
+        let response = auth.login("admin", "password123").await.unwrap();
+        assert!(response.mfa_setup_required);
+        // Until a factor is enrolled the token only works for MFA setup
+        assert!(matches!(
+            auth.validate_token(&response.token),
+            Err(AuthError::MfaSetupRequired)
+        ));
+        assert!(auth.validate_setup_token(&response.token).is_ok());
+
+        let (secret, _) = auth.begin_totp_enrollment("admin").await.unwrap();
+        let (current, next) = totp_codes(&secret);
+        auth.confirm_totp_enrollment("admin", &current, &MfaProof::default())
+            .await
+            .unwrap();
+        assert!(auth.mfa_status("admin").await.unwrap().required);
+        // Enrolling lifts the setup-only restriction of the current session
+        assert!(auth.validate_token(&response.token).is_ok());
+        assert!(matches!(
+            auth.login("admin", "password123").await,
+            Err(AuthError::MfaRequired(_))
+        ));
+
+        let proof = MfaProof {
+            code: Some(next),
+            ..Default::default()
+        };
+        assert!(matches!(
+            auth.disable_totp("admin", &proof).await,
+            Err(AuthError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            auth.verify_step_up("admin", &MfaProof::default()).await,
+            Err(AuthError::MfaFailed(_))
+        ));
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_role_permissions() {
+        assert!(UserRole::Admin.has_permission(Permission::BackupRestore));
+        assert!(UserRole::Operator.has_permission(Permission::DeviceControl));
+        assert!(UserRole::Operator.has_permission(Permission::RuleDelete));
+        assert!(!UserRole::Operator.has_permission(Permission::BackupRestore));
+        assert!(!UserRole::Operator.has_permission(Permission::ManageUsers));
+        assert!(!UserRole::Operator.has_permission(Permission::ManageConfig));
+        assert!(!UserRole::Operator.has_permission(Permission::ManageSecrets));
+        assert!(UserRole::Operator.has_permission(Permission::ApproveActions));
+        assert!(!UserRole::Operator.has_permission(Permission::ManageExtensions));
+        assert!(!UserRole::Operator.has_permission(Permission::ManageApiKeys));
+        assert!(!UserRole::Viewer.has_permission(Permission::DeviceControl));
+        assert!(!UserRole::Viewer.has_permission(Permission::ApproveActions));
+    }
+
+    #[test]
+    fn test_legacy_user_role_maps_to_operator() {
+        let role: UserRole = serde_json::from_str("\"user\"").unwrap();
+        assert_eq!(role, UserRole::Operator);
+        assert_eq!(UserRole::parse("user"), Some(UserRole::Operator));
+        assert_eq!(serde_json::to_string(&role).unwrap(), "\"operator\"");
+    }
+
+    #[test]
+    fn test_ensure_permission_reports_missing_permission() {
+        let session = SessionInfo {
+            user_id: "v".to_string(),
+            username: "viewer".to_string(),
+            role: UserRole::Viewer,
+            created_at: 0,
+            expires_at: i64::MAX,
+            tenant_id: None,
+        };
+        let err = session
+            .ensure_permission(Permission::RuleDelete)
+            .unwrap_err();
+        assert!(matches!(err, AuthError::Forbidden(Permission::RuleDelete)));
+        assert_eq!(err.into_response().status(), HttpStatusCode::FORBIDDEN);
+    }
 }
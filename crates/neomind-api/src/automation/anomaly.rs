@@ -0,0 +1,461 @@
+//! Anomaly detection on device telemetry.
+//!
+//! [`AnomalyDetector`] keeps an exponentially weighted baseline (mean and
+//! variance) for every numeric device metric, plus one per hour of day so
+//! daily patterns (HVAC schedules, daylight, shift work) don't read as
+//! anomalies. Baselines are seeded from `TimeSeriesStorage` the first time a
+//! metric is seen and then updated from `DeviceMetric` events. A value more
+//! than `z_threshold` standard deviations away from the expected value is
+//! published as `NeoMindEvent::Anomaly` and kept in a ring buffer for the
+//! agent's `device anomalies` command.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use chrono::{TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use neomind_core::{EventBus, NeoMindEvent};
+use neomind_devices::TimeSeriesStorage;
+
+/// Tuning for [`AnomalyDetector`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyConfig {
+    /// EWMA smoothing factor (0-1); higher adapts faster
+    pub alpha: f64,
+    /// Deviation, in standard deviations, that counts as an anomaly
+    pub z_threshold: f64,
+    /// Deviation at or above which an anomaly is "critical"
+    pub critical_z: f64,
+    /// Samples a baseline needs before it is used for detection
+    pub min_samples: u64,
+    /// History loaded to seed a new baseline (hours)
+    pub warmup_hours: i64,
+    /// Compare against the hour-of-day baseline when it has enough samples
+    pub seasonal: bool,
+    /// Recent anomalies kept in memory
+    pub max_recent: usize,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.05,
+            z_threshold: 3.5,
+            critical_z: 6.0,
+            min_samples: 30,
+            warmup_hours: 72,
+            seasonal: true,
+            max_recent: 500,
+        }
+    }
+}
+
+/// Exponentially weighted mean and variance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ewma {
+    pub mean: f64,
+    pub variance: f64,
+    pub count: u64,
+}
+
+impl Ewma {
+    pub fn update(&mut self, value: f64, alpha: f64) {
+        if self.count == 0 {
+            self.mean = value;
+            self.variance = 0.0;
+        } else {
+            let diff = value - self.mean;
+            let incr = alpha * diff;
+            self.mean += incr;
+            self.variance = (1.0 - alpha) * (self.variance + diff * incr);
+        }
+        self.count += 1;
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+}
+
+/// Learned baseline for one device metric.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricBaseline {
+    /// All samples
+    pub overall: Ewma,
+    /// Samples per hour of day (UTC)
+    pub hourly: Vec<Ewma>,
+    /// Timestamp of the newest sample
+    pub last_timestamp: i64,
+}
+
+impl MetricBaseline {
+    fn new() -> Self {
+        Self {
+            hourly: vec![Ewma::default(); 24],
+            ..Default::default()
+        }
+    }
+
+    fn hour_of(timestamp: i64) -> usize {
+        chrono::Utc
+            .timestamp_opt(timestamp, 0)
+            .single()
+            .map(|t| t.hour() as usize)
+            .unwrap_or(0)
+    }
+
+    /// The baseline to compare a sample at `timestamp` against.
+    fn reference(&self, timestamp: i64, config: &AnomalyConfig) -> Option<Ewma> {
+        let hourly = self.hourly.get(Self::hour_of(timestamp)).copied();
+        let candidate = match hourly {
+            Some(h) if config.seasonal && h.count >= config.min_samples => h,
+            _ => self.overall,
+        };
+        (candidate.count >= config.min_samples).then_some(candidate)
+    }
+
+    fn update(&mut self, value: f64, timestamp: i64, alpha: f64) {
+        self.overall.update(value, alpha);
+        if let Some(h) = self.hourly.get_mut(Self::hour_of(timestamp)) {
+            h.update(value, alpha);
+        }
+        self.last_timestamp = self.last_timestamp.max(timestamp);
+    }
+}
+
+/// A detected outlier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub device_id: String,
+    pub metric: String,
+    pub value: f64,
+    pub expected: f64,
+    pub std_dev: f64,
+    pub z_score: f64,
+    /// "warning" or "critical"
+    pub severity: String,
+    pub timestamp: i64,
+}
+
+impl Anomaly {
+    fn to_event(&self) -> NeoMindEvent {
+        NeoMindEvent::Anomaly {
+            device_id: self.device_id.clone(),
+            metric: self.metric.clone(),
+            value: self.value,
+            expected: self.expected,
+            z_score: self.z_score,
+            severity: self.severity.clone(),
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// Learns per-metric baselines and flags outliers.
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    baselines: RwLock<HashMap<(String, String), MetricBaseline>>,
+    recent: RwLock<VecDeque<Anomaly>>,
+    running: std::sync::atomic::AtomicBool,
+}
+
+impl AnomalyDetector {
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            baselines: RwLock::new(HashMap::new()),
+            recent: RwLock::new(VecDeque::new()),
+            running: std::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    pub fn config(&self) -> &AnomalyConfig {
+        &self.config
+    }
+
+    /// Score `value` against the baseline, then fold it into the baseline.
+    /// Returns the anomaly if the value is an outlier.
+    pub async fn observe(
+        &self,
+        device_id: &str,
+        metric: &str,
+        value: f64,
+        timestamp: i64,
+    ) -> Option<Anomaly> {
+        if !value.is_finite() {
+            return None;
+        }
+        let anomaly = {
+            let mut baselines = self.baselines.write().await;
+            let baseline = baselines
+                .entry((device_id.to_string(), metric.to_string()))
+                .or_insert_with(MetricBaseline::new);
+            if timestamp < baseline.last_timestamp {
+                // Late or replayed sample: already reflected in the baseline
+                return None;
+            }
+            let anomaly = baseline
+                .reference(timestamp, &self.config)
+                .and_then(|reference| self.score(device_id, metric, value, timestamp, reference));
+            baseline.update(value, timestamp, self.config.alpha);
+            anomaly
+        };
+
+        if let Some(anomaly) = &anomaly {
+            let mut recent = self.recent.write().await;
+            recent.push_back(anomaly.clone());
+            while recent.len() > self.config.max_recent {
+                recent.pop_front();
+            }
+        }
+        anomaly
+    }
+
+    fn score(
+        &self,
+        device_id: &str,
+        metric: &str,
+        value: f64,
+        timestamp: i64,
+        reference: Ewma,
+    ) -> Option<Anomaly> {
+        // Floor the spread so a perfectly flat metric doesn't flag rounding noise
+        let std_dev = reference
+            .std_dev()
+            .max(reference.mean.abs() * 1e-3)
+            .max(1e-6);
+        let z_score = (value - reference.mean) / std_dev;
+        if z_score.abs() < self.config.z_threshold {
+            return None;
+        }
+        let severity = if z_score.abs() >= self.config.critical_z {
+            "critical"
+        } else {
+            "warning"
+        };
+        Some(Anomaly {
+            device_id: device_id.to_string(),
+            metric: metric.to_string(),
+            value,
+            expected: reference.mean,
+            std_dev,
+            z_score,
+            severity: severity.to_string(),
+            timestamp,
+        })
+    }
+
+    /// Whether a baseline exists for the metric.
+    pub async fn has_baseline(&self, device_id: &str, metric: &str) -> bool {
+        self.baselines
+            .read()
+            .await
+            .contains_key(&(device_id.to_string(), metric.to_string()))
+    }
+
+    pub async fn baseline(&self, device_id: &str, metric: &str) -> Option<MetricBaseline> {
+        self.baselines
+            .read()
+            .await
+            .get(&(device_id.to_string(), metric.to_string()))
+            .cloned()
+    }
+
+    /// Seed a baseline from stored history without flagging anything.
+    pub async fn seed_from_history(
+        &self,
+        telemetry: &TimeSeriesStorage,
+        device_id: &str,
+        metric: &str,
+        now: i64,
+    ) {
+        let start = now - self.config.warmup_hours * 3600;
+        let points = match telemetry.query(device_id, metric, start, now).await {
+            Ok(points) => points,
+            Err(e) => {
+                tracing::debug!(device_id, metric, "Anomaly baseline seeding failed: {}", e);
+                return;
+            }
+        };
+        let mut baselines = self.baselines.write().await;
+        let baseline = baselines
+            .entry((device_id.to_string(), metric.to_string()))
+            .or_insert_with(MetricBaseline::new);
+        for point in points {
+            if let Some(value) = point.value.as_f64().filter(|v| v.is_finite()) {
+                baseline.update(value, point.timestamp, self.config.alpha);
+            }
+        }
+    }
+
+    /// Recent anomalies, newest first.
+    pub async fn recent(
+        &self,
+        device_id: Option<&str>,
+        metric: Option<&str>,
+        since: Option<i64>,
+        limit: usize,
+    ) -> Vec<Anomaly> {
+        self.recent_where(
+            |d| device_id.is_none_or(|id| id == d),
+            metric,
+            since,
+            limit,
+        )
+        .await
+    }
+
+    /// Like [`Self::recent`], for the devices `device` accepts.
+    pub async fn recent_where(
+        &self,
+        device: impl Fn(&str) -> bool,
+        metric: Option<&str>,
+        since: Option<i64>,
+        limit: usize,
+    ) -> Vec<Anomaly> {
+        self.recent
+            .read()
+            .await
+            .iter()
+            .rev()
+            .filter(|a| device(&a.device_id))
+            .filter(|a| metric.is_none_or(|m| a.metric == m))
+            .filter(|a| since.is_none_or(|s| a.timestamp >= s))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Subscribe to device metrics and publish anomalies on the event bus.
+    /// Calling this more than once has no effect.
+    pub fn start(self: &Arc<Self>, event_bus: Arc<EventBus>, telemetry: Arc<TimeSeriesStorage>) {
+        if self.running.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            return;
+        }
+        let detector = self.clone();
+        tokio::spawn(async move {
+            let mut rx = event_bus.filter().device_events();
+            tracing::info!("Anomaly detector started - learning device metric baselines");
+            while let Some((event, _metadata)) = rx.recv().await {
+                if event.is_virtual_metric() {
+                    continue;
+                }
+                let NeoMindEvent::DeviceMetric {
+                    device_id,
+                    metric,
+                    value,
+                    timestamp,
+                    ..
+                } = event
+                else {
+                    continue;
+                };
+                if !value.is_numeric() {
+                    continue;
+                }
+                let Some(value) = value.as_f64() else {
+                    continue;
+                };
+                if !detector.has_baseline(&device_id, &metric).await {
+                    // History up to (not including) this sample
+                    detector
+                        .seed_from_history(&telemetry, &device_id, &metric, timestamp - 1)
+                        .await;
+                }
+                if let Some(anomaly) = detector
+                    .observe(&device_id, &metric, value, timestamp)
+                    .await
+                {
+                    tracing::info!(
+                        device_id = %anomaly.device_id,
+                        metric = %anomaly.metric,
+                        value = anomaly.value,
+                        expected = anomaly.expected,
+                        z_score = anomaly.z_score,
+                        "Anomaly detected"
+                    );
+                    let _ = event_bus.publish(anomaly.to_event()).await;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> AnomalyDetector {
+        AnomalyDetector::new(AnomalyConfig {
+            min_samples: 10,
+            seasonal: false,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_flags_outlier_after_warmup() {
+        let detector = detector();
+        for i in 0..50 {
+            let value = 20.0 + if i % 2 == 0 { 0.5 } else { -0.5 };
+            assert!(detector.observe("d1", "temp", value, i).await.is_none());
+        }
+        let anomaly = detector.observe("d1", "temp", 35.0, 50).await.unwrap();
+        assert!(anomaly.z_score > 3.5);
+        assert_eq!(anomaly.severity, "critical");
+        assert!((anomaly.expected - 20.0).abs() < 0.5);
+
+        let recent = detector.recent(Some("d1"), None, None, 10).await;
+        assert_eq!(recent, vec![anomaly]);
+        assert!(detector.recent(Some("d2"), None, None, 10).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_no_detection_before_min_samples() {
+        let detector = detector();
+        for i in 0..5 {
+            detector.observe("d1", "temp", 20.0, i).await;
+        }
+        assert!(detector.observe("d1", "temp", 1000.0, 5).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_seasonal_baseline() {
+        let detector = AnomalyDetector::new(AnomalyConfig {
+            min_samples: 5,
+            ..Default::default()
+        });
+        // Hour 0 runs around 10, hour 12 around 30, for ten days
+        for day in 0..10 {
+            let base = day * 86_400;
+            detector
+                .observe("d1", "load", 10.0 + (day % 2) as f64, base)
+                .await;
+            detector
+                .observe("d1", "load", 30.0 + (day % 2) as f64, base + 12 * 3600)
+                .await;
+        }
+        let day = 10 * 86_400;
+        // Normal for midday, abnormal at midnight
+        assert!(detector
+            .observe("d1", "load", 30.5, day + 12 * 3600)
+            .await
+            .is_none());
+        assert!(detector
+            .observe("d1", "load", 30.5, day + 86_400)
+            .await
+            .is_some());
+    }
+
+    #[test]
+    fn test_ewma() {
+        let mut ewma = Ewma::default();
+        ewma.update(10.0, 0.5);
+        assert_eq!(ewma.mean, 10.0);
+        ewma.update(20.0, 0.5);
+        assert_eq!(ewma.mean, 15.0);
+        assert_eq!(ewma.variance, 25.0);
+    }
+}
@@ -0,0 +1,338 @@
+//! Minimal mDNS / DNS-SD browser.
+//!
+//! Sends one-shot PTR queries for a list of service types to the mDNS
+//! multicast group and collects the answers (PTR + SRV + TXT + A) that arrive
+//! within a listen window. Queries are sent from an ephemeral port, so
+//! responders reply by unicast (RFC 6762 §6.7) and no membership in the
+//! multicast group is needed.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+
+/// mDNS multicast group and port.
+pub const MDNS_ADDR: SocketAddr =
+    SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::new(224, 0, 0, 251)), 5353);
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+
+/// Guards against compression-pointer loops in malformed packets.
+const MAX_NAME_JUMPS: usize = 16;
+
+/// A service instance announced over mDNS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdnsService {
+    /// Instance name without the service suffix (e.g. "Kitchen Lamp")
+    pub instance: String,
+    /// Service type (e.g. "_http._tcp.local")
+    pub service_type: String,
+    /// Target host from the SRV record
+    pub host: Option<String>,
+    pub address: Ipv4Addr,
+    pub port: Option<u16>,
+    /// TXT key/value pairs
+    pub txt: HashMap<String, String>,
+}
+
+/// Resource record data we care about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordData {
+    Ptr(String),
+    Srv { port: u16, target: String },
+    Txt(HashMap<String, String>),
+    A(Ipv4Addr),
+    Other,
+}
+
+/// A parsed resource record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub name: String,
+    pub data: RecordData,
+}
+
+/// Build a query packet with one PTR question per service type.
+pub fn build_query(service_types: &[String]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(12 + service_types.len() * 32);
+    // Header: id, flags, qdcount, ancount, nscount, arcount
+    buf.extend_from_slice(&[0, 0, 0, 0]);
+    buf.extend_from_slice(&(service_types.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+    for service_type in service_types {
+        encode_name(service_type, &mut buf);
+        buf.extend_from_slice(&TYPE_PTR.to_be_bytes());
+        buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+    }
+    buf
+}
+
+fn encode_name(name: &str, buf: &mut Vec<u8>) {
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        let bytes = &label.as_bytes()[..label.len().min(63)];
+        buf.push(bytes.len() as u8);
+        buf.extend_from_slice(bytes);
+    }
+    buf.push(0);
+}
+
+fn read_u16(packet: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([
+        *packet.get(pos)?,
+        *packet.get(pos + 1)?,
+    ]))
+}
+
+/// Read a possibly-compressed name at `pos`. Returns the name and the
+/// position just past it in the original (uncompressed) stream.
+fn read_name(packet: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut end = None;
+    let mut jumps = 0;
+    loop {
+        let len = *packet.get(pos)? as usize;
+        if len == 0 {
+            end.get_or_insert(pos + 1);
+            break;
+        }
+        if len & 0xC0 == 0xC0 {
+            jumps += 1;
+            if jumps > MAX_NAME_JUMPS {
+                return None;
+            }
+            let offset = ((len & 0x3F) << 8) | *packet.get(pos + 1)? as usize;
+            end.get_or_insert(pos + 2);
+            pos = offset;
+            continue;
+        }
+        let label = packet.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + len;
+    }
+    Some((labels.join("."), end?))
+}
+
+fn parse_txt(data: &[u8]) -> HashMap<String, String> {
+    let mut txt = HashMap::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let len = data[pos] as usize;
+        let Some(entry) = data.get(pos + 1..pos + 1 + len) else {
+            break;
+        };
+        let entry = String::from_utf8_lossy(entry).into_owned();
+        if !entry.is_empty() {
+            let (key, value) = entry.split_once('=').unwrap_or((entry.as_str(), ""));
+            txt.insert(key.to_lowercase(), value.to_string());
+        }
+        pos += 1 + len;
+    }
+    txt
+}
+
+/// Parse every answer, authority and additional record of a response.
+/// Returns `None` for packets that are not responses or are truncated.
+pub fn parse_response(packet: &[u8]) -> Option<Vec<Record>> {
+    let flags = read_u16(packet, 2)?;
+    if flags & 0x8000 == 0 {
+        return None;
+    }
+    let qdcount = read_u16(packet, 4)? as usize;
+    let rrcount = read_u16(packet, 6)? as usize
+        + read_u16(packet, 8)? as usize
+        + read_u16(packet, 10)? as usize;
+
+    let mut pos = 12;
+    for _ in 0..qdcount {
+        let (_, next) = read_name(packet, pos)?;
+        pos = next + 4;
+    }
+
+    let mut records = Vec::with_capacity(rrcount);
+    for _ in 0..rrcount {
+        let (name, next) = read_name(packet, pos)?;
+        let rtype = read_u16(packet, next)?;
+        let rdlen = read_u16(packet, next + 8)? as usize;
+        let rdata_start = next + 10;
+        let rdata = packet.get(rdata_start..rdata_start + rdlen)?;
+        let data = match rtype {
+            TYPE_PTR => RecordData::Ptr(read_name(packet, rdata_start)?.0),
+            TYPE_SRV => RecordData::Srv {
+                port: read_u16(packet, rdata_start + 4)?,
+                target: read_name(packet, rdata_start + 6)?.0,
+            },
+            TYPE_TXT => RecordData::Txt(parse_txt(rdata)),
+            TYPE_A if rdlen == 4 => {
+                RecordData::A(Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]))
+            }
+            _ => RecordData::Other,
+        };
+        records.push(Record { name, data });
+        pos = rdata_start + rdlen;
+    }
+    Some(records)
+}
+
+fn records_named<'a>(records: &'a [Record], name: &'a str) -> impl Iterator<Item = &'a Record> {
+    records
+        .iter()
+        .filter(move |r| r.name.eq_ignore_ascii_case(name))
+}
+
+/// Assemble service instances from the records of one response. `source`
+/// is used when the response carries no A record for the SRV target.
+pub fn collect_services(
+    records: &[Record],
+    service_types: &[String],
+    source: Ipv4Addr,
+) -> Vec<MdnsService> {
+    records
+        .iter()
+        .filter_map(|r| match &r.data {
+            RecordData::Ptr(instance_name)
+                if service_types
+                    .iter()
+                    .any(|t| t.trim_end_matches('.').eq_ignore_ascii_case(&r.name)) =>
+            {
+                Some((r.name.clone(), instance_name.clone()))
+            }
+            _ => None,
+        })
+        .map(|(service_type, instance_name)| {
+            let mut service = MdnsService {
+                instance: instance_name
+                    .strip_suffix(&format!(".{}", service_type))
+                    .unwrap_or(&instance_name)
+                    .to_string(),
+                service_type,
+                host: None,
+                address: source,
+                port: None,
+                txt: HashMap::new(),
+            };
+            for record in records_named(records, &instance_name) {
+                match &record.data {
+                    RecordData::Srv { port, target } => {
+                        service.port = Some(*port);
+                        service.host = Some(target.clone());
+                    }
+                    RecordData::Txt(txt) => service.txt.extend(txt.clone()),
+                    _ => {}
+                }
+            }
+            if let Some(host) = &service.host {
+                if let Some(ip) = records_named(records, host).find_map(|r| match r.data {
+                    RecordData::A(ip) => Some(ip),
+                    _ => None,
+                }) {
+                    service.address = ip;
+                }
+            }
+            service
+        })
+        .collect()
+}
+
+/// Query `service_types` and collect the services that answer within `window`.
+pub async fn browse(
+    service_types: &[String],
+    window: Duration,
+) -> std::io::Result<Vec<MdnsService>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.set_multicast_ttl_v4(255)?;
+    socket
+        .send_to(&build_query(service_types), MDNS_ADDR)
+        .await?;
+
+    let deadline = tokio::time::Instant::now() + window;
+    let mut services: Vec<MdnsService> = Vec::new();
+    let mut buf = vec![0u8; 9000];
+    while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let (len, from) = received?;
+        let std::net::IpAddr::V4(source) = from.ip() else {
+            continue;
+        };
+        let Some(records) = parse_response(&buf[..len]) else {
+            continue;
+        };
+        for service in collect_services(&records, service_types, source) {
+            if !services
+                .iter()
+                .any(|s| s.instance == service.instance && s.service_type == service.service_type)
+            {
+                services.push(service);
+            }
+        }
+    }
+    Ok(services)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_record(buf: &mut Vec<u8>, name: &str, rtype: u16, rdata: &[u8]) {
+        encode_name(name, buf);
+        buf.extend_from_slice(&rtype.to_be_bytes());
+        buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+        buf.extend_from_slice(&120u32.to_be_bytes());
+        buf.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        buf.extend_from_slice(rdata);
+    }
+
+    #[test]
+    fn test_query_layout() {
+        let query = build_query(&["_http._tcp.local".to_string()]);
+        assert_eq!(&query[4..6], &[0, 1]);
+        assert_eq!(&query[12..18], b"\x05_http");
+        assert_eq!(&query[query.len() - 4..], &[0, 12, 0, 1]);
+    }
+
+    #[test]
+    fn test_parse_response_into_service() {
+        let mut packet = vec![0, 0, 0x84, 0, 0, 0, 0, 4, 0, 0, 0, 0];
+
+        let mut ptr = Vec::new();
+        encode_name("Lamp._http._tcp.local", &mut ptr);
+        push_record(&mut packet, "_http._tcp.local", TYPE_PTR, &ptr);
+
+        let mut srv = vec![0, 0, 0, 0, 0x1F, 0x90];
+        encode_name("lamp.local", &mut srv);
+        push_record(&mut packet, "Lamp._http._tcp.local", TYPE_SRV, &srv);
+
+        push_record(
+            &mut packet,
+            "Lamp._http._tcp.local",
+            TYPE_TXT,
+            b"\x0bmodel=LX-10\x06vendor",
+        );
+        push_record(&mut packet, "lamp.local", TYPE_A, &[192, 168, 1, 20]);
+
+        let records = parse_response(&packet).unwrap();
+        assert_eq!(records.len(), 4);
+
+        let services = collect_services(
+            &records,
+            &["_http._tcp.local".to_string()],
+            Ipv4Addr::new(10, 0, 0, 1),
+        );
+        assert_eq!(services.len(), 1);
+        let lamp = &services[0];
+        assert_eq!(lamp.instance, "Lamp");
+        assert_eq!(lamp.port, Some(8080));
+        assert_eq!(lamp.address, Ipv4Addr::new(192, 168, 1, 20));
+        assert_eq!(lamp.txt.get("model").map(String::as_str), Some("LX-10"));
+        assert!(lamp.txt.contains_key("vendor"));
+    }
+
+    #[test]
+    fn test_compression_loop_is_rejected() {
+        let packet = [0xC0, 0x00];
+        assert!(read_name(&packet, 0).is_none());
+    }
+}
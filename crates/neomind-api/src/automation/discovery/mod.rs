@@ -6,6 +6,7 @@
 //! - Auto-generating virtual metrics
 //! - Generating device type definitions
 //! - Zero-config auto-onboarding of unknown devices
+//! - Continuous mDNS/SSDP network scanning with an approval queue
 //!
 //! ## Analysis Pipeline
 //!
@@ -18,8 +19,11 @@
 
 pub mod auto_onboard;
 pub mod hex_analyzer;
+pub mod mdns;
+pub mod network;
 pub mod path_extractor;
 pub mod semantic_inference;
+pub mod ssdp;
 pub mod statistics_analyzer;
 pub mod structure_analyzer;
 pub mod types;
@@ -30,6 +34,9 @@ pub use hex_analyzer::{
     compute_stats as hex_compute_stats, hex_to_bytes, is_hex_string, HexAnalyzer, HexInfo,
     HexProbability, MetricInterpretation, SuggestedType,
 };
+pub use network::{
+    DiscoveredEndpoint, DiscoveryStatus, EndpointApproval, NetworkDiscovery, NetworkDiscoveryConfig,
+};
 pub use path_extractor::DataPathExtractor;
 pub use semantic_inference::{MetricEnhancement, SemanticInference};
 pub use statistics_analyzer::{
@@ -0,0 +1,535 @@
+//! Continuous network discovery (mDNS + SSDP) with an approval queue.
+//!
+//! [`NetworkDiscovery`] periodically browses the local network with mDNS and
+//! SSDP. Each endpoint found is correlated with the known
+//! [`DeviceTypeTemplate`]s and, the first time it is seen, persisted in the
+//! device registry store as [`DiscoveryStatus::Pending`]. Operators then
+//! approve it (registering a device, optionally mapping it to a different
+//! type) or ignore it. Approved and ignored endpoints are remembered, so later
+//! scans only refresh their address and `last_seen`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use neomind_devices::{ConnectionConfig, DeviceConfig, DeviceService, DeviceTypeTemplate};
+pub use neomind_storage::device_registry::{DiscoveredEndpoint, DiscoveryStatus};
+
+use super::types::{DiscoveryError, Result};
+use super::{mdns, ssdp};
+
+/// Scan interval in seconds; `0` disables continuous network discovery.
+pub const NETWORK_DISCOVERY_INTERVAL_ENV: &str = "NEOMIND_NETWORK_DISCOVERY_INTERVAL_SECS";
+
+/// mDNS service types browsed by default.
+pub const DEFAULT_MDNS_SERVICE_TYPES: &[&str] = &[
+    "_http._tcp.local",
+    "_mqtt._tcp.local",
+    "_coap._udp.local",
+    "_hap._tcp.local",
+    "_esphomelib._tcp.local",
+    "_modbus._tcp.local",
+];
+
+/// Minimum correlation score for a template to be suggested.
+const MIN_MATCH_SCORE: usize = 2;
+
+/// Network discovery settings.
+#[derive(Debug, Clone)]
+pub struct NetworkDiscoveryConfig {
+    /// Time between scans (seconds).
+    pub scan_interval_secs: u64,
+    /// How long each scan listens for responses (seconds).
+    pub listen_window_secs: u64,
+    /// mDNS service types to browse.
+    pub mdns_service_types: Vec<String>,
+    /// Also run SSDP searches.
+    pub ssdp: bool,
+}
+
+impl Default for NetworkDiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            scan_interval_secs: 300,
+            listen_window_secs: 3,
+            mdns_service_types: DEFAULT_MDNS_SERVICE_TYPES
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            ssdp: true,
+        }
+    }
+}
+
+impl NetworkDiscoveryConfig {
+    /// Defaults with the interval taken from `NEOMIND_NETWORK_DISCOVERY_INTERVAL_SECS`.
+    /// Returns `None` when the interval is set to `0`.
+    pub fn from_env() -> Option<Self> {
+        let mut config = Self::default();
+        if let Some(secs) = std::env::var(NETWORK_DISCOVERY_INTERVAL_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+        {
+            config.scan_interval_secs = secs;
+        }
+        (config.scan_interval_secs > 0).then_some(config)
+    }
+}
+
+/// Operator decision to register a discovered endpoint as a device.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EndpointApproval {
+    /// Device type to map the endpoint to (defaults to the suggested type)
+    #[serde(default)]
+    pub device_type: Option<String>,
+    /// Device name (defaults to the discovered name)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Device ID (generated when omitted)
+    #[serde(default)]
+    pub device_id: Option<String>,
+    /// Adapter type (default "mqtt")
+    #[serde(default)]
+    pub adapter_type: Option<String>,
+    /// Connection config; host and port default to the discovered address
+    #[serde(default)]
+    pub connection_config: Option<serde_json::Value>,
+}
+
+/// Background mDNS/SSDP scanner and approval queue.
+pub struct NetworkDiscovery {
+    service: Arc<DeviceService>,
+    config: NetworkDiscoveryConfig,
+    endpoints: RwLock<HashMap<String, DiscoveredEndpoint>>,
+    http: reqwest::Client,
+    started: AtomicBool,
+}
+
+impl NetworkDiscovery {
+    /// Create the scanner, loading previously discovered endpoints from storage.
+    pub fn new(service: Arc<DeviceService>, config: NetworkDiscoveryConfig) -> Self {
+        let endpoints = match service.registry().storage() {
+            Some(store) => match store.list_discovered_endpoints() {
+                Ok(list) => list.into_iter().map(|e| (e.id.clone(), e)).collect(),
+                Err(e) => {
+                    tracing::warn!("Failed to load discovered endpoints: {}", e);
+                    HashMap::new()
+                }
+            },
+            None => HashMap::new(),
+        };
+        Self {
+            service,
+            config,
+            endpoints: RwLock::new(endpoints),
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(3))
+                .build()
+                .unwrap_or_default(),
+            started: AtomicBool::new(false),
+        }
+    }
+
+    pub fn config(&self) -> &NetworkDiscoveryConfig {
+        &self.config
+    }
+
+    /// Start continuous scanning. Calling this more than once has no effect.
+    pub fn start(self: &Arc<Self>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let discovery = self.clone();
+        tokio::spawn(async move {
+            let mut ticker =
+                tokio::time::interval(Duration::from_secs(discovery.config.scan_interval_secs));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let queued = discovery.scan().await;
+                if queued > 0 {
+                    tracing::info!(
+                        queued,
+                        "Network discovery queued new endpoints for approval"
+                    );
+                }
+            }
+        });
+        tracing::info!(
+            interval_secs = self.config.scan_interval_secs,
+            "Network discovery (mDNS + SSDP) started"
+        );
+    }
+
+    /// Run one mDNS + SSDP scan. Returns how many endpoints were newly queued.
+    pub async fn scan(&self) -> usize {
+        let window = Duration::from_secs(self.config.listen_window_secs.max(1));
+        let (mdns_result, ssdp_result) = tokio::join!(
+            mdns::browse(&self.config.mdns_service_types, window),
+            async {
+                if self.config.ssdp {
+                    ssdp::search(ssdp::SEARCH_ALL, window).await
+                } else {
+                    Ok(Vec::new())
+                }
+            }
+        );
+
+        let now = chrono::Utc::now().timestamp();
+        let mut found = Vec::new();
+        match mdns_result {
+            Ok(services) => found.extend(services.iter().map(|s| endpoint_from_mdns(s, now))),
+            Err(e) => tracing::debug!("mDNS scan failed: {}", e),
+        }
+        match ssdp_result {
+            Ok(responses) => {
+                // ssdp:all yields one response per service; keep one per device
+                let mut seen = HashSet::new();
+                for response in responses {
+                    let uuid = device_uuid(&response.usn).to_string();
+                    if !seen.insert(uuid) {
+                        continue;
+                    }
+                    let description = ssdp::fetch_description(&self.http, &response).await;
+                    found.push(endpoint_from_ssdp(&response, description, now));
+                }
+            }
+            Err(e) => tracing::debug!("SSDP scan failed: {}", e),
+        }
+
+        self.ingest(found).await
+    }
+
+    /// Merge scan results into the queue. New endpoints are correlated and
+    /// queued as pending; known ones only have their address refreshed.
+    /// Returns how many endpoints were newly queued.
+    pub async fn ingest(&self, found: Vec<DiscoveredEndpoint>) -> usize {
+        if found.is_empty() {
+            return 0;
+        }
+        let templates = self.service.list_templates();
+        let mut endpoints = self.endpoints.write().await;
+        let mut queued = 0;
+        for mut endpoint in found {
+            let record = match endpoints.get_mut(&endpoint.id) {
+                Some(existing) => {
+                    existing.address = endpoint.address;
+                    existing.port = endpoint.port.or(existing.port);
+                    existing.last_seen = endpoint.last_seen;
+                    existing.properties = endpoint.properties;
+                    if existing.status == DiscoveryStatus::Pending
+                        && existing.suggested_type.is_none()
+                    {
+                        existing.suggested_type = correlate(existing, &templates);
+                    }
+                    existing.clone()
+                }
+                None => {
+                    endpoint.suggested_type = correlate(&endpoint, &templates);
+                    endpoint.status = DiscoveryStatus::Pending;
+                    queued += 1;
+                    endpoints.insert(endpoint.id.clone(), endpoint.clone());
+                    endpoint
+                }
+            };
+            self.persist(&record);
+        }
+        queued
+    }
+
+    /// Discovered endpoints, most recently seen first.
+    pub async fn list(&self, status: Option<DiscoveryStatus>) -> Vec<DiscoveredEndpoint> {
+        let endpoints = self.endpoints.read().await;
+        let mut list: Vec<DiscoveredEndpoint> = endpoints
+            .values()
+            .filter(|e| status.is_none_or(|s| e.status == s))
+            .cloned()
+            .collect();
+        list.sort_by_key(|d| std::cmp::Reverse(d.last_seen));
+        list
+    }
+
+    pub async fn get(&self, id: &str) -> Option<DiscoveredEndpoint> {
+        self.endpoints.read().await.get(id).cloned()
+    }
+
+    /// Register a pending endpoint as a device.
+    pub async fn approve(&self, id: &str, approval: EndpointApproval) -> Result<DeviceConfig> {
+        let endpoint = self
+            .get(id)
+            .await
+            .ok_or_else(|| DiscoveryError::NotFound(format!("Discovered endpoint '{}'", id)))?;
+        if let Some(device_id) = &endpoint.device_id {
+            return Err(DiscoveryError::Validation(format!(
+                "Endpoint already registered as device '{}'",
+                device_id
+            )));
+        }
+
+        let device_type = approval
+            .device_type
+            .or_else(|| endpoint.suggested_type.clone())
+            .ok_or_else(|| {
+                DiscoveryError::Validation(
+                    "No matching device type was found; provide device_type to map this endpoint"
+                        .to_string(),
+                )
+            })?;
+        let device_id = approval.device_id.unwrap_or_else(|| {
+            let suffix: String = uuid::Uuid::new_v4()
+                .simple()
+                .to_string()
+                .chars()
+                .take(8)
+                .collect();
+            format!("{}_{}", device_type, suffix)
+        });
+
+        let mut connection_config: ConnectionConfig = match approval.connection_config {
+            Some(value) => serde_json::from_value(value).map_err(|e| {
+                DiscoveryError::Validation(format!("Invalid connection_config: {}", e))
+            })?,
+            None => ConnectionConfig::new(),
+        };
+        connection_config
+            .host
+            .get_or_insert_with(|| endpoint.address.clone());
+        if connection_config.port.is_none() {
+            connection_config.port = endpoint.port;
+        }
+        connection_config
+            .extra
+            .insert("discovery_id".to_string(), serde_json::json!(endpoint.id));
+
+        let config = DeviceConfig {
+            device_id: device_id.clone(),
+            name: approval.name.unwrap_or_else(|| endpoint.name.clone()),
+            device_type,
+            adapter_type: approval.adapter_type.unwrap_or_else(|| "mqtt".to_string()),
+            connection_config,
+            adapter_id: None,
+            last_seen: 0,
+            offline_timeout_secs: None,
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
+        };
+        self.service
+            .register_device(config.clone())
+            .await
+            .map_err(|e| DiscoveryError::Validation(format!("Failed to register device: {}", e)))?;
+
+        self.set_status(id, DiscoveryStatus::Approved, Some(device_id))
+            .await?;
+        Ok(config)
+    }
+
+    /// Dismiss an endpoint; it stays known so later scans don't queue it again.
+    pub async fn ignore(&self, id: &str) -> Result<DiscoveredEndpoint> {
+        self.set_status(id, DiscoveryStatus::Ignored, None).await
+    }
+
+    async fn set_status(
+        &self,
+        id: &str,
+        status: DiscoveryStatus,
+        device_id: Option<String>,
+    ) -> Result<DiscoveredEndpoint> {
+        let mut endpoints = self.endpoints.write().await;
+        let endpoint = endpoints
+            .get_mut(id)
+            .ok_or_else(|| DiscoveryError::NotFound(format!("Discovered endpoint '{}'", id)))?;
+        endpoint.status = status;
+        if device_id.is_some() {
+            endpoint.device_id = device_id;
+        }
+        let endpoint = endpoint.clone();
+        drop(endpoints);
+        self.persist(&endpoint);
+        Ok(endpoint)
+    }
+
+    fn persist(&self, endpoint: &DiscoveredEndpoint) {
+        if let Some(store) = self.service.registry().storage() {
+            if let Err(e) = store.save_discovered_endpoint(endpoint) {
+                tracing::warn!(id = %endpoint.id, "Failed to persist discovered endpoint: {}", e);
+            }
+        }
+    }
+}
+
+/// The `uuid:...` part of a USN, shared by all services of one device.
+fn device_uuid(usn: &str) -> &str {
+    usn.split("::").next().unwrap_or(usn)
+}
+
+fn first_property(properties: &HashMap<String, String>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|k| properties.get(*k))
+        .filter(|v| !v.is_empty())
+        .cloned()
+}
+
+fn endpoint_from_mdns(service: &mdns::MdnsService, now: i64) -> DiscoveredEndpoint {
+    DiscoveredEndpoint {
+        id: format!("mdns:{}:{}", service.service_type, service.instance),
+        protocol: "mdns".to_string(),
+        address: service.address.to_string(),
+        port: service.port,
+        name: service.instance.clone(),
+        service_type: service.service_type.clone(),
+        manufacturer: first_property(&service.txt, &["manufacturer", "vendor", "mf"]),
+        model: first_property(&service.txt, &["model", "md", "mdl"]),
+        properties: service.txt.clone(),
+        first_seen: now,
+        last_seen: now,
+        ..Default::default()
+    }
+}
+
+fn endpoint_from_ssdp(
+    response: &ssdp::SsdpResponse,
+    description: Option<ssdp::DeviceDescription>,
+    now: i64,
+) -> DiscoveredEndpoint {
+    let description = description.unwrap_or_default();
+    let port = response
+        .location
+        .as_deref()
+        .and_then(|l| reqwest::Url::parse(l).ok())
+        .and_then(|u| u.port_or_known_default());
+    DiscoveredEndpoint {
+        id: format!("ssdp:{}", device_uuid(&response.usn)),
+        protocol: "ssdp".to_string(),
+        address: response.address.to_string(),
+        port,
+        name: description
+            .friendly_name
+            .or_else(|| response.server.clone())
+            .unwrap_or_else(|| response.address.to_string()),
+        service_type: description
+            .device_type
+            .unwrap_or_else(|| response.search_target.clone()),
+        manufacturer: description.manufacturer,
+        model: description.model_name,
+        properties: response.headers.clone(),
+        first_seen: now,
+        last_seen: now,
+        ..Default::default()
+    }
+}
+
+fn tokens(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|t| t.len() >= 3)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Suggest the template that best matches an endpoint's name, model,
+/// manufacturer and service properties.
+///
+/// Shared words count once; shared model-number-like words (containing a
+/// digit, e.g. "ne301") count twice. A template ID appearing verbatim counts
+/// three times. Templates scoring below two are not suggested.
+pub fn correlate(
+    endpoint: &DiscoveredEndpoint,
+    templates: &[DeviceTypeTemplate],
+) -> Option<String> {
+    let mut text = format!("{} {}", endpoint.name, endpoint.service_type);
+    for value in [&endpoint.manufacturer, &endpoint.model]
+        .into_iter()
+        .flatten()
+    {
+        text.push(' ');
+        text.push_str(value);
+    }
+    for value in endpoint.properties.values() {
+        text.push(' ');
+        text.push_str(value);
+    }
+    let text = text.to_lowercase();
+    let endpoint_tokens = tokens(&text);
+
+    templates
+        .iter()
+        .filter_map(|template| {
+            let id = template.device_type.to_lowercase();
+            let mut score = if id.len() >= 3 && text.contains(&id) {
+                3
+            } else {
+                0
+            };
+            let template_tokens = tokens(&format!(
+                "{} {} {}",
+                template.device_type,
+                template.name,
+                template.categories.join(" ")
+            ));
+            score += template_tokens
+                .intersection(&endpoint_tokens)
+                .map(|t| {
+                    if t.chars().any(|c| c.is_ascii_digit()) {
+                        2
+                    } else {
+                        1
+                    }
+                })
+                .sum::<usize>();
+            (score >= MIN_MATCH_SCORE).then_some((score, template))
+        })
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, template)| template.device_type.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(name: &str, model: Option<&str>) -> DiscoveredEndpoint {
+        DiscoveredEndpoint {
+            id: format!("mdns:_http._tcp.local:{}", name),
+            protocol: "mdns".to_string(),
+            address: "192.168.1.20".to_string(),
+            name: name.to_string(),
+            service_type: "_http._tcp.local".to_string(),
+            model: model.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_correlate_prefers_model_numbers() {
+        let mut camera = DeviceTypeTemplate::new("ne301_camera", "NE301 Camera");
+        camera.categories = vec!["camera".to_string()];
+        let sensor = DeviceTypeTemplate::new("dht22_sensor", "DHT22 Sensor");
+        let templates = vec![camera, sensor];
+
+        assert_eq!(
+            correlate(&endpoint("Lobby Cam", Some("NE301")), &templates).as_deref(),
+            Some("ne301_camera")
+        );
+        assert_eq!(
+            correlate(&endpoint("greenhouse", Some("dht22")), &templates).as_deref(),
+            Some("dht22_sensor")
+        );
+        assert_eq!(
+            correlate(&endpoint("printer", Some("LaserJet")), &templates),
+            None
+        );
+    }
+
+    #[test]
+    fn test_device_uuid() {
+        assert_eq!(
+            device_uuid("uuid:2f402f80-da50::urn:schemas-upnp-org:service:Basic:1"),
+            "uuid:2f402f80-da50"
+        );
+        assert_eq!(device_uuid("uuid:abc"), "uuid:abc");
+    }
+}
@@ -0,0 +1,172 @@
+//! Minimal SSDP (UPnP) search.
+//!
+//! Sends an `M-SEARCH` to the SSDP multicast group and collects the unicast
+//! responses that arrive within a listen window. The UPnP device description
+//! behind a response's `LOCATION` header can be fetched for the friendly
+//! name, manufacturer and model.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+
+/// SSDP multicast group and port.
+pub const SSDP_ADDR: SocketAddr =
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+
+/// Search target matching every UPnP device and service.
+pub const SEARCH_ALL: &str = "ssdp:all";
+
+/// A response to an `M-SEARCH`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SsdpResponse {
+    pub address: Ipv4Addr,
+    /// Search target (`ST`)
+    pub search_target: String,
+    /// Unique service name (`USN`)
+    pub usn: String,
+    /// URL of the device description (`LOCATION`)
+    pub location: Option<String>,
+    /// `SERVER` header (OS and UPnP stack)
+    pub server: Option<String>,
+    /// All headers, keys lower-cased
+    pub headers: HashMap<String, String>,
+}
+
+/// Fields read from a UPnP device description.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceDescription {
+    pub friendly_name: Option<String>,
+    pub manufacturer: Option<String>,
+    pub model_name: Option<String>,
+    pub device_type: Option<String>,
+}
+
+/// Build an `M-SEARCH` request. `mx` is the maximum response delay in seconds.
+pub fn build_search(search_target: &str, mx: u8) -> String {
+    format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: {}\r\nST: {}\r\n\r\n",
+        SSDP_ADDR, mx, search_target
+    )
+}
+
+/// Parse a search response. Returns `None` for anything other than a
+/// `200 OK` carrying a `USN`.
+pub fn parse_response(data: &str, address: Ipv4Addr) -> Option<SsdpResponse> {
+    let mut lines = data.split("\r\n");
+    let status = lines.next()?;
+    if !status.starts_with("HTTP/1.1 200") {
+        return None;
+    }
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect();
+    Some(SsdpResponse {
+        address,
+        search_target: headers.get("st").cloned().unwrap_or_default(),
+        usn: headers.get("usn").cloned()?,
+        location: headers.get("location").cloned(),
+        server: headers.get("server").cloned(),
+        headers,
+    })
+}
+
+fn xml_tag(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    let value = xml[start..end].trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Read the root device fields from a UPnP description document.
+pub fn parse_description(xml: &str) -> DeviceDescription {
+    DeviceDescription {
+        friendly_name: xml_tag(xml, "friendlyName"),
+        manufacturer: xml_tag(xml, "manufacturer"),
+        model_name: xml_tag(xml, "modelName"),
+        device_type: xml_tag(xml, "deviceType"),
+    }
+}
+
+/// Fetch and parse the device description behind `response.location`.
+///
+/// Only descriptions served by the responding host itself are fetched, so a
+/// spoofed response can't point the server at arbitrary URLs.
+pub async fn fetch_description(
+    client: &reqwest::Client,
+    response: &SsdpResponse,
+) -> Option<DeviceDescription> {
+    let location = reqwest::Url::parse(response.location.as_deref()?).ok()?;
+    if location.host_str()? != response.address.to_string() {
+        return None;
+    }
+    let body = client.get(location).send().await.ok()?.text().await.ok()?;
+    Some(parse_description(&body))
+}
+
+/// Search for `search_target` and collect responses that arrive within
+/// `window`, one per USN.
+pub async fn search(search_target: &str, window: Duration) -> std::io::Result<Vec<SsdpResponse>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.set_multicast_ttl_v4(2)?;
+    let mx = window.as_secs().clamp(1, 5) as u8;
+    socket
+        .send_to(build_search(search_target, mx).as_bytes(), SSDP_ADDR)
+        .await?;
+
+    let deadline = tokio::time::Instant::now() + window;
+    let mut responses: Vec<SsdpResponse> = Vec::new();
+    let mut buf = vec![0u8; 4096];
+    while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let (len, from) = received?;
+        let IpAddr::V4(address) = from.ip() else {
+            continue;
+        };
+        let Some(response) = parse_response(&String::from_utf8_lossy(&buf[..len]), address) else {
+            continue;
+        };
+        if !responses.iter().any(|r| r.usn == response.usn) {
+            responses.push(response);
+        }
+    }
+    Ok(responses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let raw = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\nLOCATION: http://192.168.1.30:49152/desc.xml\r\nSERVER: Linux/5.4 UPnP/1.0 Hue/1.0\r\nST: upnp:rootdevice\r\nUSN: uuid:2f402f80-da50::upnp:rootdevice\r\n\r\n";
+        let response = parse_response(raw, Ipv4Addr::new(192, 168, 1, 30)).unwrap();
+        assert_eq!(response.search_target, "upnp:rootdevice");
+        assert_eq!(response.usn, "uuid:2f402f80-da50::upnp:rootdevice");
+        assert_eq!(
+            response.location.as_deref(),
+            Some("http://192.168.1.30:49152/desc.xml")
+        );
+        assert_eq!(
+            response.headers.get("cache-control").unwrap(),
+            "max-age=1800"
+        );
+
+        assert!(
+            parse_response("NOTIFY * HTTP/1.1\r\nUSN: x\r\n\r\n", Ipv4Addr::LOCALHOST).is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_description() {
+        let xml = "<root><device><deviceType>urn:schemas-upnp-org:device:Basic:1</deviceType>\
+                   <friendlyName>Hue Bridge</friendlyName><manufacturer>Signify</manufacturer>\
+                   <modelName>BSB002</modelName></device></root>";
+        let description = parse_description(xml);
+        assert_eq!(description.friendly_name.as_deref(), Some("Hue Bridge"));
+        assert_eq!(description.manufacturer.as_deref(), Some("Signify"));
+        assert_eq!(description.model_name.as_deref(), Some("BSB002"));
+    }
+}
@@ -33,6 +33,9 @@ pub enum DiscoveryError {
     #[error("Configuration error: {0}")]
     Configuration(String),
 
+    #[error("Not found: {0}")]
+    NotFound(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -0,0 +1,382 @@
+//! Energy consumption and cost reporting.
+//!
+//! Metrics registered as [`EnergyMeter`]s are turned into consumption
+//! intervals: power readings (W / kW) are integrated with the trapezoidal
+//! rule, cumulative counters (Wh / kWh) contribute their increase. Intervals
+//! are priced with the configured [`EnergyTariff`] at their local time and
+//! summed per device, per time bucket and per tariff period.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{Datelike, TimeZone, Timelike};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use neomind_devices::{DeviceError, DeviceService, TimeSeriesStorage};
+use neomind_storage::{EnergyConfig, EnergyMeter, EnergyMeterKind, EnergyTariff};
+
+/// Power samples further apart than this are treated as a gap (device
+/// offline) and not integrated.
+pub const MAX_POWER_GAP_SECS: i64 = 15 * 60;
+
+/// Name used for consumption outside every tariff period.
+const DEFAULT_PERIOD: &str = "default";
+
+/// Time bucket for report series.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnergyBucket {
+    Hour,
+    #[default]
+    Day,
+    Month,
+}
+
+impl EnergyBucket {
+    fn label<T: TimeZone>(self, time: &chrono::DateTime<T>) -> String
+    where
+        T::Offset: std::fmt::Display,
+    {
+        match self {
+            Self::Hour => time.format("%Y-%m-%d %H:00").to_string(),
+            Self::Day => time.format("%Y-%m-%d").to_string(),
+            Self::Month => time.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// Energy consumed between two timestamps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnergyInterval {
+    pub start: i64,
+    pub end: i64,
+    pub kwh: f64,
+}
+
+impl EnergyInterval {
+    fn midpoint(&self) -> i64 {
+        self.start + (self.end - self.start) / 2
+    }
+}
+
+/// Integrate power samples `(timestamp, value)` into energy. `scale`
+/// converts the metric's unit to kW.
+pub fn power_intervals(points: &[(i64, f64)], scale: f64) -> Vec<EnergyInterval> {
+    points
+        .windows(2)
+        .filter(|w| w[1].0 > w[0].0 && w[1].0 - w[0].0 <= MAX_POWER_GAP_SECS)
+        .map(|w| {
+            let hours = (w[1].0 - w[0].0) as f64 / 3600.0;
+            let avg_kw = (w[0].1 + w[1].1).max(0.0) / 2.0 * scale;
+            EnergyInterval {
+                start: w[0].0,
+                end: w[1].0,
+                kwh: avg_kw * hours,
+            }
+        })
+        .collect()
+}
+
+/// Turn cumulative counter samples into consumption. A decrease is a counter
+/// reset: the new reading is counted as consumed since the reset. `scale`
+/// converts the metric's unit to kWh.
+pub fn counter_intervals(points: &[(i64, f64)], scale: f64) -> Vec<EnergyInterval> {
+    points
+        .windows(2)
+        .filter(|w| w[1].0 > w[0].0)
+        .map(|w| {
+            let delta = if w[1].1 >= w[0].1 {
+                w[1].1 - w[0].1
+            } else {
+                w[1].1.max(0.0)
+            };
+            EnergyInterval {
+                start: w[0].0,
+                end: w[1].0,
+                kwh: delta * scale,
+            }
+        })
+        .collect()
+}
+
+/// Consumption of one meter.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceEnergy {
+    pub device_id: String,
+    pub name: String,
+    pub metric: String,
+    pub kwh: f64,
+    pub cost: f64,
+}
+
+/// Consumption within one time bucket.
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketEnergy {
+    /// Local time label, e.g. "2026-10-16" for a day bucket
+    pub label: String,
+    pub kwh: f64,
+    pub cost: f64,
+}
+
+/// Consumption within one tariff period.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodEnergy {
+    pub name: String,
+    pub rate: f64,
+    pub kwh: f64,
+    pub cost: f64,
+}
+
+/// Energy cost report.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnergyReport {
+    pub start: i64,
+    pub end: i64,
+    pub bucket: EnergyBucket,
+    pub timezone: String,
+    pub currency: String,
+    pub total_kwh: f64,
+    pub total_cost: f64,
+    /// Most consuming first
+    pub devices: Vec<DeviceEnergy>,
+    /// Chronological
+    pub series: Vec<BucketEnergy>,
+    pub tariff_periods: Vec<PeriodEnergy>,
+}
+
+/// Consumption intervals of one meter, ready to be priced.
+pub struct MeterIntervals {
+    pub meter: EnergyMeter,
+    pub device_name: String,
+    pub intervals: Vec<EnergyInterval>,
+}
+
+/// Price and aggregate meter intervals. Intervals are attributed to the
+/// report range, bucket and tariff period by their midpoint.
+pub fn build_report(
+    meters: Vec<MeterIntervals>,
+    tariff: &EnergyTariff,
+    tz: Tz,
+    start: i64,
+    end: i64,
+    bucket: EnergyBucket,
+) -> EnergyReport {
+    let mut devices = Vec::with_capacity(meters.len());
+    let mut series: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+    let mut periods: BTreeMap<String, PeriodEnergy> = BTreeMap::new();
+
+    for meter in meters {
+        let mut kwh = 0.0;
+        let mut cost = 0.0;
+        for interval in &meter.intervals {
+            let at = interval.midpoint();
+            if at < start || at >= end {
+                continue;
+            }
+            let Some(local) = tz.timestamp_opt(at, 0).single() else {
+                continue;
+            };
+            let (period, rate) = tariff.rate_at(
+                local.weekday().num_days_from_monday() as u8,
+                local.hour() as u8,
+            );
+            let interval_cost = interval.kwh * rate;
+            kwh += interval.kwh;
+            cost += interval_cost;
+
+            let slot = series.entry(bucket.label(&local)).or_default();
+            slot.0 += interval.kwh;
+            slot.1 += interval_cost;
+
+            let name = period.map_or(DEFAULT_PERIOD, |p| p.name.as_str());
+            let entry = periods
+                .entry(name.to_string())
+                .or_insert_with(|| PeriodEnergy {
+                    name: name.to_string(),
+                    rate,
+                    kwh: 0.0,
+                    cost: 0.0,
+                });
+            entry.kwh += interval.kwh;
+            entry.cost += interval_cost;
+        }
+        devices.push(DeviceEnergy {
+            device_id: meter.meter.device_id,
+            name: meter.device_name,
+            metric: meter.meter.metric,
+            kwh,
+            cost,
+        });
+    }
+
+    devices.sort_by(|a, b| b.kwh.total_cmp(&a.kwh));
+    EnergyReport {
+        start,
+        end,
+        bucket,
+        timezone: tz.name().to_string(),
+        currency: tariff.currency.clone(),
+        total_kwh: devices.iter().map(|d| d.kwh).sum(),
+        total_cost: devices.iter().map(|d| d.cost).sum(),
+        devices,
+        series: series
+            .into_iter()
+            .map(|(label, (kwh, cost))| BucketEnergy { label, kwh, cost })
+            .collect(),
+        tariff_periods: periods.into_values().collect(),
+    }
+}
+
+/// Build a report from stored telemetry. `device_ids` restricts the report
+/// to those devices (e.g. the members of a group).
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_report(
+    service: &DeviceService,
+    telemetry: &TimeSeriesStorage,
+    config: &EnergyConfig,
+    tz: Tz,
+    start: i64,
+    end: i64,
+    bucket: EnergyBucket,
+    device_ids: Option<&HashSet<String>>,
+) -> Result<EnergyReport, DeviceError> {
+    let mut meters = Vec::new();
+    for meter in &config.meters {
+        if device_ids.is_some_and(|ids| !ids.contains(&meter.device_id)) {
+            continue;
+        }
+        let Some(scale) = meter.scale() else {
+            tracing::warn!(
+                device_id = %meter.device_id,
+                metric = %meter.metric,
+                unit = %meter.unit,
+                "Energy meter unit does not match its kind, skipping"
+            );
+            continue;
+        };
+        // Look back far enough to pair the first in-range sample
+        let points: Vec<(i64, f64)> = telemetry
+            .query(
+                &meter.device_id,
+                &meter.metric,
+                start - MAX_POWER_GAP_SECS,
+                end,
+            )
+            .await?
+            .into_iter()
+            .filter_map(|p| p.value.as_f64().map(|v| (p.timestamp, v)))
+            .filter(|(_, v)| v.is_finite())
+            .collect();
+        let intervals = match meter.kind {
+            EnergyMeterKind::Power => power_intervals(&points, scale),
+            EnergyMeterKind::Energy => counter_intervals(&points, scale),
+        };
+        let device_name = service
+            .get_device(&meter.device_id)
+            .map(|d| d.name)
+            .unwrap_or_else(|| meter.device_id.clone());
+        meters.push(MeterIntervals {
+            meter: meter.clone(),
+            device_name,
+            intervals,
+        });
+    }
+    Ok(build_report(meters, &config.tariff, tz, start, end, bucket))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_storage::TariffPeriod;
+
+    fn meter(device_id: &str) -> EnergyMeter {
+        EnergyMeter {
+            device_id: device_id.to_string(),
+            metric: "power".to_string(),
+            kind: EnergyMeterKind::Power,
+            unit: "W".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_power_integration_skips_gaps() {
+        // 1 kW for one hour in 10-minute samples, then a two-hour gap
+        let mut points: Vec<(i64, f64)> = (0..=6).map(|i| (i * 600, 1000.0)).collect();
+        points.push((3600 + 7200, 1000.0));
+        let intervals = power_intervals(&points, 0.001);
+        assert_eq!(intervals.len(), 6);
+        let kwh: f64 = intervals.iter().map(|i| i.kwh).sum();
+        assert!((kwh - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_counter_reset() {
+        let points = [(0, 100.0), (3600, 102.5), (7200, 0.5), (10800, 1.5)];
+        let kwh: f64 = counter_intervals(&points, 1.0).iter().map(|i| i.kwh).sum();
+        assert!((kwh - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_report_prices_by_period() {
+        let tariff = EnergyTariff {
+            currency: "EUR".to_string(),
+            default_rate: 0.2,
+            periods: vec![TariffPeriod {
+                name: "night".to_string(),
+                start_hour: 0,
+                end_hour: 6,
+                weekdays: vec![],
+                rate: 0.1,
+            }],
+        };
+        // 2026-10-16 00:00 UTC; one kWh at 01:00 and two at 12:00
+        let day = 1_792_108_800;
+        let meters = vec![
+            MeterIntervals {
+                meter: meter("heater"),
+                device_name: "Heater".to_string(),
+                intervals: vec![
+                    EnergyInterval {
+                        start: day + 3600,
+                        end: day + 3600 + 60,
+                        kwh: 1.0,
+                    },
+                    EnergyInterval {
+                        start: day + 12 * 3600,
+                        end: day + 12 * 3600 + 60,
+                        kwh: 2.0,
+                    },
+                ],
+            },
+            MeterIntervals {
+                meter: meter("lamp"),
+                device_name: "Lamp".to_string(),
+                intervals: vec![EnergyInterval {
+                    start: day - 3600,
+                    end: day - 3500,
+                    kwh: 5.0,
+                }],
+            },
+        ];
+        let report = build_report(
+            meters,
+            &tariff,
+            chrono_tz::UTC,
+            day,
+            day + 86_400,
+            EnergyBucket::Hour,
+        );
+        assert!((report.total_kwh - 3.0).abs() < 1e-9);
+        assert!((report.total_cost - 0.5).abs() < 1e-9);
+        assert_eq!(report.devices[0].device_id, "heater");
+        assert_eq!(report.devices[1].kwh, 0.0);
+        assert_eq!(report.series.len(), 2);
+        assert_eq!(report.series[0].label, "2026-10-16 01:00");
+        let names: Vec<&str> = report
+            .tariff_periods
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["default", "night"]);
+    }
+}
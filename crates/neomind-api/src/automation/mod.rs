@@ -8,10 +8,13 @@
 //! - **Unified Types**: Single `Automation` enum that wraps transforms and rules
 //! - **Shared Resources**: Common templates, devices, and metrics for all types
 
+pub mod anomaly;
 pub mod device_type_generator;
 pub mod discovery;
+pub mod energy;
 pub mod error;
 pub mod output_registry;
+pub mod script_heap;
 pub mod store;
 pub mod transform;
 pub mod types;
@@ -41,6 +44,10 @@ pub use types::{
     TransformOperation, TransformScope, TypeCounts,
 };
 
+// Re-export the allocator wrapper that caps `Script` transform heap use
+#[cfg(feature = "script-heap-limit")]
+pub use script_heap::MeteredAlloc;
+
 // Re-export transform engine
 pub use transform::{TransformEngine, TransformResult, TransformedMetric};
 
@@ -0,0 +1,111 @@
+//! Per-thread heap accounting for `Script` transforms.
+//!
+//! Boa has no heap limit of its own. Binaries built with the
+//! `script-heap-limit` feature wrap their global allocator in
+//! [`MeteredAlloc`]. It counts the bytes a thread holds while a
+//! [`HeapMeter`] is armed on it; the script runner reads the meter each time
+//! the VM yields and aborts the script once its cap is passed. The wrapper
+//! adds a thread-local check to every allocation in the process, which is
+//! why it is opt-in.
+//!
+//! Without `MeteredAlloc` installed the meter always reads zero: scripts then
+//! run with no heap cap, but the loop, recursion, instruction and output
+//! limits and the timeout still apply.
+
+#[cfg(feature = "script-heap-limit")]
+use std::alloc::{GlobalAlloc, Layout};
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+thread_local! {
+    static ARMED: Cell<bool> = const { Cell::new(false) };
+    static HELD: Cell<usize> = const { Cell::new(0) };
+}
+
+#[cfg(feature = "script-heap-limit")]
+fn record_alloc(size: usize) {
+    let _ = ARMED.try_with(|armed| {
+        if armed.get() {
+            HELD.with(|held| held.set(held.get().saturating_add(size)));
+        }
+    });
+}
+
+#[cfg(feature = "script-heap-limit")]
+fn record_dealloc(size: usize) {
+    let _ = ARMED.try_with(|armed| {
+        if armed.get() {
+            // Memory from before arming may be freed too; never go below zero
+            HELD.with(|held| held.set(held.get().saturating_sub(size)));
+        }
+    });
+}
+
+/// Global allocator wrapper that feeds [`HeapMeter`].
+///
+/// ```ignore
+/// #[global_allocator]
+/// static GLOBAL: MeteredAlloc<std::alloc::System> = MeteredAlloc(std::alloc::System);
+/// ```
+#[cfg(feature = "script-heap-limit")]
+pub struct MeteredAlloc<A>(pub A);
+
+#[cfg(feature = "script-heap-limit")]
+unsafe impl<A: GlobalAlloc> GlobalAlloc for MeteredAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.0.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Bytes held by the current thread since the meter was armed.
+///
+/// Disarms on drop. Not `Send`: the count belongs to the arming thread.
+pub(crate) struct HeapMeter {
+    _thread_bound: PhantomData<*const ()>,
+}
+
+impl HeapMeter {
+    pub(crate) fn arm() -> Self {
+        HELD.with(|held| held.set(0));
+        ARMED.with(|armed| armed.set(true));
+        Self {
+            _thread_bound: PhantomData,
+        }
+    }
+
+    pub(crate) fn held(&self) -> usize {
+        HELD.with(Cell::get)
+    }
+}
+
+impl Drop for HeapMeter {
+    fn drop(&mut self) {
+        ARMED.with(|armed| armed.set(false));
+    }
+}
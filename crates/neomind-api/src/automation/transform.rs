@@ -30,20 +30,40 @@
 
 use super::error::{AutomationError, Result};
 use super::output_registry::TransformOutputRegistry;
+use super::script_heap::HeapMeter;
 use super::store::SharedAutomationStore;
 use super::types::{
-    AggregationFunc, AutomationType, ExecutionRecord, ExecutionStatus, TimeWindow,
+    AggregationFunc, AutomationType, ExecutionRecord, ExecutionStatus, ScriptLimits, TimeWindow,
     TransformAutomation, TransformOperation,
 };
 use chrono::Utc;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 // Import ExtensionRegistry for extension invoke support
 use neomind_core::event::MetricValue;
 use neomind_core::extension::registry::ExtensionRegistry;
 
+/// VM cost units a `Script` runs between limit checks.
+const SCRIPT_SLICE: u32 = 4096;
+
+/// Extra time a `Script` gets past its own timeout before the caller stops
+/// waiting for its thread.
+const SCRIPT_TIMEOUT_GRACE: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// `Script` transforms running at once. Bounds the blocking threads held by
+/// scripts stuck in a native call past their timeout.
+static SCRIPT_SLOTS: Semaphore = Semaphore::const_new(8);
+
+fn script_error(message: String) -> AutomationError {
+    AutomationError::TransformError {
+        operation: "Script".to_string(),
+        message,
+    }
+}
+
 /// JavaScript-based transform executor using Boa engine
 ///
 /// Executes user-written JavaScript code in a sandboxed environment.
@@ -585,6 +605,132 @@ impl JsTransformExecutor {
         Ok(metrics)
     }
 
+    /// Execute a `Script` transform operation
+    ///
+    /// Unlike [`execute`](Self::execute), the script only sees `input` (no
+    /// extension calls or image helpers) and runs on a blocking thread under
+    /// `limits`, clamped to [`ScriptLimits::MAX`]: runaway loops, recursion,
+    /// execution budget, wall-clock time, heap use and an oversized result
+    /// all abort the script.
+    pub async fn execute_script(
+        &self,
+        code: &str,
+        input: &Value,
+        output: &str,
+        device_id: &str,
+        timestamp: i64,
+        limits: &ScriptLimits,
+    ) -> Result<Vec<TransformedMetric>> {
+        let limits = limits.clamped();
+        let timeout = std::time::Duration::from_millis(limits.timeout_ms);
+        let code = code.to_string();
+        let input = input.clone();
+
+        let run = async move {
+            let permit = SCRIPT_SLOTS
+                .acquire()
+                .await
+                .expect("script slot semaphore is never closed");
+            tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                JsTransformExecutor::new().run_script(&code, &input, &limits)
+            })
+            .await
+        };
+        // The VM checks the deadline itself whenever it yields; this outer
+        // bound also covers a native call (a huge `repeat`, a callback-heavy
+        // builtin) that never yields. Such a thread finishes in the
+        // background while still holding its slot.
+        let result_value = tokio::time::timeout(timeout + SCRIPT_TIMEOUT_GRACE, run)
+            .await
+            .map_err(|_| {
+                script_error(format!(
+                    "Script exceeded the {} ms timeout",
+                    limits.timeout_ms
+                ))
+            })?
+            .map_err(|e| script_error(format!("Script task failed: {}", e)))??;
+
+        self.json_to_metrics(&result_value, output, device_id, timestamp)
+    }
+
+    /// Run a `Script` body to completion on the current thread.
+    ///
+    /// The VM yields every [`SCRIPT_SLICE`] cost units; at each yield the
+    /// execution budget, deadline and heap meter are checked.
+    fn run_script(&self, code: &str, input: &Value, limits: &ScriptLimits) -> Result<Value> {
+        use boa_engine::{context::Context, Script, Source};
+        use std::future::Future;
+        use std::task::{Context as TaskContext, Poll, Waker};
+
+        let deadline =
+            std::time::Instant::now() + std::time::Duration::from_millis(limits.timeout_ms);
+        let meter = HeapMeter::arm();
+
+        let mut context = Context::default();
+        context
+            .runtime_limits_mut()
+            .set_loop_iteration_limit(limits.max_loop_iterations);
+        context
+            .runtime_limits_mut()
+            .set_recursion_limit(limits.max_recursion_depth);
+
+        let input_json = serde_json::to_string(input)
+            .map_err(|e| script_error(format!("Failed to serialize input: {}", e)))?;
+        let wrapped_code = format!(
+            "var input = {};\n(function() {{\n{}\n}})()",
+            input_json, code
+        );
+        let script = Script::parse(
+            Source::from_bytes(wrapped_code.as_bytes()),
+            None,
+            &mut context,
+        )
+        .map_err(|e| script_error(format!("JavaScript parse error: {}", e)))?;
+
+        let result = {
+            let mut task = TaskContext::from_waker(Waker::noop());
+            let mut evaluation =
+                std::pin::pin!(script.evaluate_async_with_budget(&mut context, SCRIPT_SLICE));
+            let mut spent: u64 = 0;
+            loop {
+                if let Poll::Ready(result) = evaluation.as_mut().poll(&mut task) {
+                    break result;
+                }
+                spent += u64::from(SCRIPT_SLICE);
+                if spent > limits.max_instructions {
+                    return Err(script_error(format!(
+                        "Script exceeded the {} instruction budget",
+                        limits.max_instructions
+                    )));
+                }
+                if std::time::Instant::now() >= deadline {
+                    return Err(script_error(format!(
+                        "Script exceeded the {} ms timeout",
+                        limits.timeout_ms
+                    )));
+                }
+                if meter.held() > limits.max_heap_bytes {
+                    return Err(script_error(format!(
+                        "Script exceeded the {} byte heap limit",
+                        limits.max_heap_bytes
+                    )));
+                }
+            }
+        }
+        .map_err(|e| script_error(format!("JavaScript execution error: {}", e)))?;
+        let result_value = self.js_value_to_json(result, &mut context)?;
+
+        let size = serde_json::to_vec(&result_value).map(|v| v.len()).unwrap_or(0);
+        if size > limits.max_output_bytes {
+            return Err(script_error(format!(
+                "Script result is {} bytes, limit is {}",
+                size, limits.max_output_bytes
+            )));
+        }
+        Ok(result_value)
+    }
+
     /// Convert Boa value to JSON Value
     fn js_value_to_json(
         &self,
@@ -1635,6 +1781,21 @@ impl TransformEngine {
                 self.execute_encode(from, *format, output, device_id, timestamp, raw_data)
                     .await
             }
+
+            TransformOperation::Script {
+                code,
+                output,
+                from,
+                limits,
+            } => {
+                let data = match from {
+                    Some(path) => self.extract_value_by_path(raw_data, path)?,
+                    None => raw_data.clone(),
+                };
+                self.js_executor
+                    .execute_script(code, &data, output, device_id, timestamp, limits)
+                    .await
+            }
         }
     }
 
@@ -3333,6 +3494,120 @@ mod tests {
         assert_eq!(result.timestamp, 1234567890);
     }
 
+    #[test]
+    fn test_execute_script() {
+        let engine = TransformEngine::new();
+        let data = json!({"raw": {"t": 2515, "h": 650}});
+        let op = TransformOperation::Script {
+            code: "return { temp: input.t / 100, humidity: input.h / 10 };".to_string(),
+            output: "env".to_string(),
+            from: Some("$.raw".to_string()),
+            limits: ScriptLimits::default(),
+        };
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt
+            .block_on(engine.execute_operation(&op, "sensor1", 1234567890, &data))
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result
+            .iter()
+            .any(|m| m.metric == "env.temp" && m.value == MetricValue::Float(25.15)));
+
+        // Runaway loops hit the iteration limit instead of hanging
+        let op = TransformOperation::Script {
+            code: "while (true) {}".to_string(),
+            output: "spin".to_string(),
+            from: None,
+            limits: ScriptLimits {
+                max_loop_iterations: 1000,
+                ..ScriptLimits::default()
+            },
+        };
+        assert!(rt
+            .block_on(engine.execute_operation(&op, "sensor1", 1234567890, &data))
+            .is_err());
+    }
+
+    #[test]
+    fn test_script_budget_spans_nested_loops() {
+        let engine = TransformEngine::new();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        // Each loop stays under the per-loop limit; together they don't
+        let op = TransformOperation::Script {
+            code: r#"
+                function inner() {
+                    var n = 0;
+                    for (var j = 0; j < 1000; j++) { n++; }
+                    return n;
+                }
+                var total = 0;
+                for (var i = 0; i < 1000000; i++) { total += inner(); }
+                return { total: total };
+            "#
+            .to_string(),
+            output: "nested".to_string(),
+            from: None,
+            limits: ScriptLimits {
+                max_loop_iterations: 1_000_000,
+                ..ScriptLimits::default()
+            },
+        };
+        let err = rt
+            .block_on(engine.execute_operation(&op, "sensor1", 0, &json!({})))
+            .unwrap_err();
+        assert!(err.to_string().contains("instruction budget"), "{}", err);
+
+        // User limits above the server maximum are lowered
+        let limits = ScriptLimits {
+            max_instructions: u64::MAX,
+            timeout_ms: u64::MAX,
+            ..ScriptLimits::default()
+        }
+        .clamped();
+        assert_eq!(limits.max_instructions, ScriptLimits::MAX.max_instructions);
+        assert_eq!(limits.timeout_ms, ScriptLimits::MAX.timeout_ms);
+    }
+
+    #[cfg(feature = "script-heap-limit")]
+    #[test]
+    fn test_script_heap_limit() {
+        let engine = TransformEngine::new();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let op = TransformOperation::Script {
+            code: r#"
+                var chunks = [];
+                for (var i = 0; i < 1000000; i++) { chunks.push("x".repeat(4096) + i); }
+                return chunks.length;
+            "#
+            .to_string(),
+            output: "hog".to_string(),
+            from: None,
+            limits: ScriptLimits {
+                max_loop_iterations: 1_000_000,
+                max_heap_bytes: 4 * 1024 * 1024,
+                ..ScriptLimits::default()
+            },
+        };
+        let err = rt
+            .block_on(engine.execute_operation(&op, "sensor1", 0, &json!({})))
+            .unwrap_err();
+        assert!(err.to_string().contains("heap limit"), "{}", err);
+
+        // Small scripts stay well under the default cap
+        let op = TransformOperation::Script {
+            code: "return [1, 2, 3].map(function (x) { return x * 2; });".to_string(),
+            output: "doubled".to_string(),
+            from: None,
+            limits: ScriptLimits::default(),
+        };
+        assert!(rt
+            .block_on(engine.execute_operation(&op, "sensor1", 0, &json!({})))
+            .is_ok());
+    }
+
     #[test]
     fn test_execute_array_aggregation() {
         let engine = TransformEngine::new();
@@ -3436,6 +3711,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::len_zero)]
     fn test_resolve_image_data_base64_fallback() {
         // Test that base64 data still works when no URL is present
         let large_base64 = "a".repeat(300); // Simulates base64-like string
@@ -432,6 +432,82 @@ pub enum TransformOperation {
         /// Output metric name
         output: String,
     },
+
+    /// Script - run JavaScript against the payload in a sandbox
+    /// The code sees `input` and returns the result; objects become one
+    /// metric per key (`output.key`), scalars a single `output` metric
+    Script {
+        /// JavaScript function body, e.g. `return input.a * input.b;`
+        code: String,
+        /// Output metric name (prefix for object results)
+        output: String,
+        /// Optional: input data path (default: root `$`)
+        #[serde(default)]
+        from: Option<String>,
+        /// Execution limits
+        #[serde(default)]
+        limits: ScriptLimits,
+    },
+}
+
+/// Limits applied to a `Script` transform operation
+///
+/// User-supplied values are lowered to [`ScriptLimits::MAX`] before the
+/// script runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptLimits {
+    /// Maximum iterations of any single loop
+    pub max_loop_iterations: u64,
+    /// Execution budget of the whole script in VM cost units (roughly one
+    /// per instruction); bounds nested loops and loops across calls
+    pub max_instructions: u64,
+    /// Maximum function call depth
+    pub max_recursion_depth: usize,
+    /// Maximum size of the serialized result in bytes
+    pub max_output_bytes: usize,
+    /// Wall-clock time the script may run, in milliseconds
+    pub timeout_ms: u64,
+    /// Maximum heap the script may hold, in bytes. Only enforced in
+    /// binaries built with the `script-heap-limit` feature
+    pub max_heap_bytes: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_loop_iterations: 100_000,
+            max_instructions: 10_000_000,
+            max_recursion_depth: 64,
+            max_output_bytes: 64 * 1024,
+            timeout_ms: 1_000,
+            max_heap_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl ScriptLimits {
+    /// Server-side ceiling for every limit
+    pub const MAX: Self = Self {
+        max_loop_iterations: 1_000_000,
+        max_instructions: 100_000_000,
+        max_recursion_depth: 256,
+        max_output_bytes: 1024 * 1024,
+        timeout_ms: 5_000,
+        max_heap_bytes: 64 * 1024 * 1024,
+    };
+
+    /// These limits with each value lowered to [`ScriptLimits::MAX`]
+    pub fn clamped(&self) -> Self {
+        Self {
+            max_loop_iterations: self.max_loop_iterations.min(Self::MAX.max_loop_iterations),
+            max_instructions: self.max_instructions.min(Self::MAX.max_instructions),
+            max_recursion_depth: self.max_recursion_depth.min(Self::MAX.max_recursion_depth),
+            max_output_bytes: self.max_output_bytes.min(Self::MAX.max_output_bytes),
+            timeout_ms: self.timeout_ms.min(Self::MAX.timeout_ms),
+            max_heap_bytes: self.max_heap_bytes.min(Self::MAX.max_heap_bytes),
+        }
+    }
 }
 
 /// Data decode/encode format
@@ -500,6 +576,7 @@ impl TransformOperation {
             }
             TransformOperation::Decode { output, .. } => vec![output.clone()],
             TransformOperation::Encode { output, .. } => vec![output.clone()],
+            TransformOperation::Script { output, .. } => vec![output.clone()],
         }
     }
 
@@ -553,6 +630,7 @@ impl TransformOperation {
             TransformOperation::GroupBy { .. } => 3,
             TransformOperation::Decode { .. } => 2,
             TransformOperation::Encode { .. } => 2,
+            TransformOperation::Script { .. } => 3,
         }
     }
 
@@ -627,6 +705,9 @@ impl TransformOperation {
             } => {
                 format!("Encode {} as '{:?}' to '{}'", from, format, output)
             }
+            TransformOperation::Script { code, output, .. } => {
+                format!("Script ({} lines) to '{}'", code.lines().count(), output)
+            }
             _ => format!("{:?}", self),
         }
     }
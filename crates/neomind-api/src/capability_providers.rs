@@ -229,6 +229,26 @@ impl DeviceCapabilityProvider {
             std::collections::HashMap::new()
         };
 
+        // Extensions retrying a timed-out call pass the same key to avoid
+        // actuating the device twice. Each extension has its own keys.
+        if let Some(key) = params.get("idempotency_key").and_then(|v| v.as_str()) {
+            let extension_id = params.get("_extension_id").and_then(|v| v.as_str());
+            let owner = format!("extension:{}", extension_id.unwrap_or_default());
+            let submission = device_service
+                .send_command_idempotent(device_id, command, params_map, &owner, key)
+                .await
+                .map_err(|e| CapabilityError::ProviderError(e.to_string()))?;
+            let result = submission.outcome.map_err(CapabilityError::ProviderError)?;
+            return Ok(json!({
+                "success": true,
+                "device_id": device_id,
+                "command": command,
+                "result": result,
+                "command_id": submission.command_id,
+                "replayed": submission.replayed,
+            }));
+        }
+
         device_service
             .send_command(device_id, command, params_map)
             .await
@@ -299,7 +319,9 @@ impl DeviceCapabilityProvider {
             uplink_samples: vec![],
             commands,
             default_offline_timeout_secs: None,
+            expected_interval_secs: None,
             store_raw: None,
+            binary_codec: None,
         };
 
         device_service
@@ -358,6 +380,9 @@ impl DeviceCapabilityProvider {
             adapter_id,
             last_seen: now_ms,
             offline_timeout_secs: None,
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
         };
 
         device_service
@@ -1714,6 +1739,14 @@ impl ChatSessionCapabilityProvider {
         if let Some(et) = params.get("enable_tools").and_then(|v| v.as_bool()) {
             opts.enable_tools = Some(et);
         }
+        if let Some(tools) = params.get("allowed_tools").and_then(|v| v.as_array()) {
+            opts.allowed_tools = Some(
+                tools
+                    .iter()
+                    .filter_map(|t| t.as_str().map(str::to_string))
+                    .collect(),
+            );
+        }
 
         let session_id = mgr
             .get_or_create_session_with_options(existing.map(|s| s.to_string()), opts)
@@ -1899,6 +1932,27 @@ fn agent_event_to_json(event: &AgentEvent) -> Value {
             }
             v
         }
+        AgentEvent::ToolProgress { tool, data, round } => {
+            let mut v = json!({ "type": "ToolProgress", "tool": tool, "data": data });
+            if let Some(r) = round {
+                v["round"] = json!(r);
+            }
+            v
+        }
+        AgentEvent::AwaitingApproval {
+            action_id,
+            tool,
+            category,
+            arguments,
+            expires_at,
+        } => json!({
+            "type": "AwaitingApproval",
+            "actionId": action_id,
+            "tool": tool,
+            "category": category,
+            "arguments": arguments,
+            "expiresAt": expires_at,
+        }),
         AgentEvent::Error { message } => json!({ "type": "Error", "message": message }),
         AgentEvent::Warning { message } => json!({ "type": "Warning", "message": message }),
         AgentEvent::Intent {
@@ -1913,6 +1967,7 @@ fn agent_event_to_json(event: &AgentEvent) -> Value {
             "confidence": confidence,
             "keywords": keywords,
         }),
+        AgentEvent::ContextStats { stats } => json!({ "type": "ContextStats", "stats": stats }),
         AgentEvent::Plan { step, stage } => json!({ "type": "Plan", "step": step, "stage": stage }),
         AgentEvent::IntermediateEnd => json!({ "type": "intermediate_end" }),
         AgentEvent::End { prompt_tokens } => {
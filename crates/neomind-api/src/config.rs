@@ -323,6 +323,12 @@ struct TomlConfig {
     mqtt: Option<TomlMqttConfig>,
     #[serde(default)]
     server: Option<TomlServerConfig>,
+    /// `[chirpstack]` section, deserialized as `ChirpStackAdapterConfig`
+    #[serde(default)]
+    chirpstack: Option<toml::Value>,
+    /// `[guardrails]` section, deserialized as `Guardrails`
+    #[serde(default)]
+    guardrails: Option<toml::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -437,9 +443,112 @@ pub fn load_embedded_broker_config() -> Option<EmbeddedBrokerConfig> {
         tls_cert_path: None,
         tls_key_path: None,
         tls_ca_path: None,
+        ..EmbeddedBrokerConfig::default()
     })
 }
 
+/// Load the ChirpStack integration configuration from config.toml.
+///
+/// Returns the HTTP-integration-only default when config.toml has no
+/// `[chirpstack]` section.
+pub fn load_chirpstack_config(name: &str) -> neomind_devices::adapters::ChirpStackAdapterConfig {
+    use neomind_devices::adapters::ChirpStackAdapterConfig;
+
+    let section = std::fs::read_to_string("config.toml")
+        .ok()
+        .and_then(|content| toml::from_str::<TomlConfig>(&content).ok())
+        .and_then(|config| config.chirpstack);
+    let Some(mut section) = section else {
+        return ChirpStackAdapterConfig::new(name);
+    };
+
+    if let Some(table) = section.as_table_mut() {
+        table.insert("name".to_string(), toml::Value::String(name.to_string()));
+    }
+    match section.try_into::<ChirpStackAdapterConfig>() {
+        Ok(config) => {
+            info!(
+                category = "chirpstack",
+                broker = ?config.broker,
+                "Loading ChirpStack config from config.toml"
+            );
+            config
+        }
+        Err(e) => {
+            warn!(
+                category = "chirpstack",
+                error = %e,
+                "Invalid [chirpstack] section in config.toml, using defaults"
+            );
+            ChirpStackAdapterConfig::new(name)
+        }
+    }
+}
+
+/// Load tool argument guardrails from config.toml and install them.
+///
+/// An invalid `[guardrails]` section is reported and leaves tool calls
+/// unrestricted, like a missing one.
+pub fn init_guardrails() {
+    use neomind_agent::toolkit::{guardrails, Guardrails};
+
+    let section = std::fs::read_to_string("config.toml")
+        .ok()
+        .and_then(|content| toml::from_str::<TomlConfig>(&content).ok())
+        .and_then(|config| config.guardrails);
+    let Some(section) = section else {
+        return;
+    };
+
+    let parsed = section
+        .try_into::<Guardrails>()
+        .map_err(|e| e.to_string())
+        .and_then(|g| g.validate().map(|()| g));
+    match parsed {
+        Ok(g) if g.is_empty() => {}
+        Ok(g) => {
+            info!(category = "ai", "Loaded tool guardrails from config.toml");
+            guardrails::set_global(g);
+        }
+        Err(e) => {
+            warn!(
+                category = "ai",
+                error = %e,
+                "Invalid [guardrails] section in config.toml, tool calls are not restricted"
+            );
+        }
+    }
+}
+
+/// Where the intent profile set through the API is kept.
+pub const INTENT_PROFILE_PATH: &str = "data/intent_profile.json";
+
+/// Load the custom intent profile saved through the API and install it.
+///
+/// An unreadable or invalid profile is reported and the built-in intent
+/// keywords are used alone.
+pub fn init_intent_profile() {
+    use neomind_agent::agent::staged::{set_intent_profile, IntentProfile};
+
+    let Ok(content) = std::fs::read_to_string(INTENT_PROFILE_PATH) else {
+        return;
+    };
+    let installed = serde_json::from_str::<IntentProfile>(&content)
+        .map_err(|e| e.to_string())
+        .and_then(|profile| set_intent_profile(Some(profile)));
+    match installed {
+        Ok(()) => info!(category = "ai", "Loaded custom intent profile"),
+        Err(e) => {
+            warn!(
+                category = "ai",
+                error = %e,
+                "Invalid intent profile in {}, using built-in intent keywords",
+                INTENT_PROFILE_PATH
+            );
+        }
+    }
+}
+
 /// Get embedded broker configuration (redb > config.toml > default).
 ///
 /// On first call, if no config exists in redb, the resolved config (from
@@ -537,6 +646,58 @@ pub fn get_server_config() -> (String, u16) {
     (host, port)
 }
 
+/// Get the gRPC listener port from `NEOMIND_GRPC_PORT` (default 9376).
+///
+/// The gRPC server binds to the same host as the HTTP server.
+#[cfg(feature = "grpc")]
+pub fn get_grpc_port() -> u16 {
+    std::env::var("NEOMIND_GRPC_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(crate::grpc::DEFAULT_GRPC_PORT)
+}
+
+/// OpenTelemetry trace export settings.
+///
+/// Export is enabled by setting `OTEL_EXPORTER_OTLP_ENDPOINT` (or
+/// `NEOMIND_OTLP_ENDPOINT`) to an OTLP/gRPC collector, e.g.
+/// `http://localhost:4317`. Spans are only exported when the server is built
+/// with the `otel` feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtelConfig {
+    pub endpoint: String,
+    /// `service.name` resource attribute (`OTEL_SERVICE_NAME`, default "neomind").
+    pub service_name: String,
+    /// Fraction of traces to sample, 0.0-1.0 (`OTEL_TRACES_SAMPLER_ARG`, default 1.0).
+    pub sample_ratio: f64,
+}
+
+impl OtelConfig {
+    /// Read the export settings from the environment; `None` when no endpoint is set.
+    pub fn from_env() -> Option<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let endpoint = lookup("OTEL_EXPORTER_OTLP_ENDPOINT")
+            .or_else(|| lookup("NEOMIND_OTLP_ENDPOINT"))
+            .filter(|e| !e.trim().is_empty())?;
+        let service_name = lookup("OTEL_SERVICE_NAME")
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| "neomind".to_string());
+        let sample_ratio = lookup("OTEL_TRACES_SAMPLER_ARG")
+            .and_then(|r| r.parse::<f64>().ok())
+            .map(|r| r.clamp(0.0, 1.0))
+            .unwrap_or(1.0);
+
+        Some(Self {
+            endpoint: endpoint.trim().to_string(),
+            service_name,
+            sample_ratio,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -566,4 +727,38 @@ endpoint = "http://localhost:11434"
         assert_eq!(settings.backend_name(), "ollama");
         assert_eq!(settings.model, "ministral-3:3b");
     }
+
+    #[test]
+    fn test_otel_config_from_env() {
+        let vars = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        assert_eq!(OtelConfig::from_lookup(vars(&[])), None);
+
+        let config = OtelConfig::from_lookup(vars(&[
+            ("NEOMIND_OTLP_ENDPOINT", "http://collector:4317"),
+            ("OTEL_TRACES_SAMPLER_ARG", "2.5"),
+        ]))
+        .unwrap();
+        assert_eq!(config.endpoint, "http://collector:4317");
+        assert_eq!(config.service_name, "neomind");
+        assert_eq!(config.sample_ratio, 1.0);
+
+        let config = OtelConfig::from_lookup(vars(&[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://otel:4317"),
+            ("NEOMIND_OTLP_ENDPOINT", "http://ignored:4317"),
+            ("OTEL_SERVICE_NAME", "edge-01"),
+            ("OTEL_TRACES_SAMPLER_ARG", "0.25"),
+        ]))
+        .unwrap();
+        assert_eq!(config.endpoint, "http://otel:4317");
+        assert_eq!(config.service_name, "edge-01");
+        assert_eq!(config.sample_ratio, 0.25);
+    }
 }
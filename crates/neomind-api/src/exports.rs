@@ -0,0 +1,589 @@
+//! Asynchronous telemetry export jobs.
+//!
+//! An export job pages through the time series store in fixed-size chunks and
+//! streams rows to a file under `data/exports/`, so multi-million-row ranges
+//! never sit in memory. Jobs run in the background; callers poll
+//! [`ExportManager::get`] and download the file once the job has completed.
+//! At most [`MAX_RUNNING_JOBS`] jobs run at a time.
+//!
+//! Each job record is saved next to its file as `export-{id}.json`.
+//! [`ExportManager::open`] loads the records on startup, marks jobs that were
+//! cut off by the restart as failed and removes partial and orphaned files.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use neomind_core::tenant::TenantId;
+use neomind_devices::TimeSeriesStorage;
+use neomind_storage::DataPoint;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+
+/// Points fetched from storage per query.
+const PAGE_SIZE: usize = 10_000;
+
+/// Export jobs allowed to run at the same time.
+pub const MAX_RUNNING_JOBS: usize = 4;
+
+/// Output file format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    /// One JSON object per line
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Jsonl => "application/x-ndjson",
+        }
+    }
+}
+
+/// What to export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRequest {
+    pub device_ids: Vec<String>,
+    /// Metrics to export; empty exports every stored metric of each device
+    #[serde(default)]
+    pub metrics: Vec<String>,
+    /// Range start (unix seconds)
+    pub start: i64,
+    /// Range end (unix seconds, inclusive)
+    pub end: i64,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+impl ExportRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.device_ids.is_empty() {
+            return Err("device_ids must not be empty".to_string());
+        }
+        if self.end < self.start {
+            return Err("end must not be before start".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// State of one export job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJob {
+    pub id: String,
+    pub status: ExportStatus,
+    pub request: ExportRequest,
+    pub rows: u64,
+    pub size_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<i64>,
+    /// Tenant that started the job.
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
+}
+
+impl ExportJob {
+    pub fn file_name(&self) -> String {
+        format!("export-{}.{}", self.id, self.request.format.extension())
+    }
+}
+
+/// Runs and tracks export jobs.
+pub struct ExportManager {
+    dir: PathBuf,
+    telemetry: Arc<TimeSeriesStorage>,
+    jobs: RwLock<HashMap<String, ExportJob>>,
+}
+
+impl ExportManager {
+    pub fn new(dir: PathBuf, telemetry: Arc<TimeSeriesStorage>) -> Self {
+        Self {
+            dir,
+            telemetry,
+            jobs: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Create a manager for `dir`, loading the jobs saved there.
+    ///
+    /// Jobs still running when the server stopped are marked failed, and
+    /// completed jobs whose file is gone are dropped. Partial files and
+    /// export files without a job record are deleted.
+    pub fn open(dir: PathBuf, telemetry: Arc<TimeSeriesStorage>) -> Self {
+        let mut manager = Self::new(dir, telemetry);
+        let Ok(entries) = std::fs::read_dir(&manager.dir) else {
+            return manager;
+        };
+        let paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+
+        let mut jobs = HashMap::new();
+        for path in paths.iter().filter(|p| is_record(p)) {
+            let job = std::fs::read(path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<ExportJob>(&bytes).ok());
+            let Some(mut job) = job else {
+                tracing::warn!("Removing unreadable export record {}", path.display());
+                let _ = std::fs::remove_file(path);
+                continue;
+            };
+            match job.status {
+                ExportStatus::Running => {
+                    job.status = ExportStatus::Failed;
+                    job.error = Some("Interrupted by a server restart".to_string());
+                    job.finished_at = Some(chrono::Utc::now().timestamp());
+                    if let Err(e) = manager.write_record(&job) {
+                        tracing::warn!("Failed to save export job {}: {}", job.id, e);
+                    }
+                }
+                ExportStatus::Completed if !manager.file_path(&job).exists() => {
+                    let _ = std::fs::remove_file(path);
+                    continue;
+                }
+                _ => {}
+            }
+            jobs.insert(job.id.clone(), job);
+        }
+
+        // Anything else named like an export belongs to no job.
+        let kept: Vec<PathBuf> = jobs.values().map(|j| manager.file_path(j)).collect();
+        for path in paths {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if name.starts_with("export-") && !is_record(&path) && !kept.contains(&path) {
+                tracing::info!("Removing orphaned export file {}", path.display());
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+
+        tracing::info!(
+            "Loaded {} export job(s) from {}",
+            jobs.len(),
+            manager.dir.display()
+        );
+        *manager.jobs.get_mut() = jobs;
+        manager
+    }
+
+    /// Start a job owned by `tenant_id` in the background and return its
+    /// initial state. Fails if [`MAX_RUNNING_JOBS`] jobs are already running.
+    pub async fn start(
+        self: &Arc<Self>,
+        request: ExportRequest,
+        tenant_id: TenantId,
+    ) -> Result<ExportJob, String> {
+        let mut jobs = self.jobs.write().await;
+        let running = jobs
+            .values()
+            .filter(|j| j.status == ExportStatus::Running)
+            .count();
+        if running >= MAX_RUNNING_JOBS {
+            return Err(format!(
+                "{} export jobs are already running; try again when one finishes",
+                running
+            ));
+        }
+        let job = ExportJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            status: ExportStatus::Running,
+            request,
+            rows: 0,
+            size_bytes: 0,
+            error: None,
+            created_at: chrono::Utc::now().timestamp(),
+            finished_at: None,
+            tenant_id,
+        };
+        jobs.insert(job.id.clone(), job.clone());
+        drop(jobs);
+        self.save(&job).await;
+
+        let manager = self.clone();
+        let id = job.id.clone();
+        tokio::spawn(async move {
+            let result = manager.run(&id).await;
+            let finished = {
+                let mut jobs = manager.jobs.write().await;
+                let Some(job) = jobs.get_mut(&id) else {
+                    return;
+                };
+                job.finished_at = Some(chrono::Utc::now().timestamp());
+                match result {
+                    Ok(size) => {
+                        job.status = ExportStatus::Completed;
+                        job.size_bytes = size;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Export job {} failed: {}", id, e);
+                        job.status = ExportStatus::Failed;
+                        job.error = Some(e);
+                    }
+                }
+                job.clone()
+            };
+            manager.save(&finished).await;
+        });
+        Ok(job)
+    }
+
+    pub async fn get(&self, id: &str) -> Option<ExportJob> {
+        self.jobs.read().await.get(id).cloned()
+    }
+
+    /// All jobs, newest first.
+    pub async fn list(&self) -> Vec<ExportJob> {
+        let mut jobs: Vec<_> = self.jobs.read().await.values().cloned().collect();
+        jobs.sort_by_key(|j| std::cmp::Reverse(j.created_at));
+        jobs
+    }
+
+    pub fn file_path(&self, job: &ExportJob) -> PathBuf {
+        self.dir.join(job.file_name())
+    }
+
+    fn record_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("export-{}.json", id))
+    }
+
+    fn write_record(&self, job: &ExportJob) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.record_path(&job.id), serde_json::to_vec(job)?)
+    }
+
+    /// Save a job record; the job itself is unaffected if this fails.
+    async fn save(&self, job: &ExportJob) {
+        let result = tokio::fs::create_dir_all(&self.dir).await;
+        let result = match (result, serde_json::to_vec(job)) {
+            (Ok(()), Ok(bytes)) => tokio::fs::write(self.record_path(&job.id), bytes).await,
+            (Err(e), _) => Err(e),
+            (_, Err(e)) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to save export job {}: {}", job.id, e);
+        }
+    }
+
+    /// Remove a finished job and its file.
+    pub async fn delete(&self, id: &str) -> Result<(), String> {
+        let mut jobs = self.jobs.write().await;
+        match jobs.get(id) {
+            None => return Err(format!("Export job not found: {}", id)),
+            Some(job) if job.status == ExportStatus::Running => {
+                return Err(format!("Export job {} is still running", id));
+            }
+            Some(job) => {
+                let _ = tokio::fs::remove_file(self.file_path(job)).await;
+                let _ = tokio::fs::remove_file(self.record_path(id)).await;
+            }
+        }
+        jobs.remove(id);
+        Ok(())
+    }
+
+    async fn set_rows(&self, id: &str, rows: u64) {
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            job.rows = rows;
+        }
+    }
+
+    /// Write the export to a `.part` file and rename it on success.
+    async fn run(&self, id: &str) -> Result<u64, String> {
+        let job = self
+            .get(id)
+            .await
+            .ok_or_else(|| format!("Export job not found: {}", id))?;
+        let request = &job.request;
+        let path = self.file_path(&job);
+        let part = path.with_extension("part");
+
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+        let file = tokio::fs::File::create(&part)
+            .await
+            .map_err(|e| format!("Failed to create export file: {}", e))?;
+        let mut writer = tokio::io::BufWriter::new(file);
+        let io_err = |e: std::io::Error| format!("Failed to write export: {}", e);
+
+        if request.format == ExportFormat::Csv {
+            writer
+                .write_all(b"timestamp,device_id,metric,value,quality\n")
+                .await
+                .map_err(io_err)?;
+        }
+
+        // Make buffered writes visible to range queries.
+        let _ = self.telemetry.flush();
+        let store = self.telemetry.inner_store();
+        let mut rows = 0u64;
+        for device_id in &request.device_ids {
+            let source_id = format!("device:{}", device_id);
+            let metrics = if request.metrics.is_empty() {
+                self.telemetry
+                    .list_metrics(&source_id)
+                    .await
+                    .map_err(|e| format!("Failed to list metrics of {}: {}", device_id, e))?
+            } else {
+                request.metrics.clone()
+            };
+
+            for metric in &metrics {
+                let mut cursor = request.start;
+                loop {
+                    let page = store
+                        .query_range(&source_id, metric, cursor, request.end, Some(PAGE_SIZE))
+                        .await
+                        .map_err(|e| format!("Failed to query {}/{}: {}", device_id, metric, e))?;
+                    let mut chunk = String::new();
+                    for point in &page.points {
+                        chunk.push_str(&format_row(request.format, device_id, metric, point));
+                    }
+                    writer.write_all(chunk.as_bytes()).await.map_err(io_err)?;
+                    rows += page.points.len() as u64;
+                    self.set_rows(id, rows).await;
+
+                    match page.points.last() {
+                        Some(last)
+                            if page.points.len() == PAGE_SIZE && last.timestamp < request.end =>
+                        {
+                            cursor = last.timestamp + 1;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+
+        writer.flush().await.map_err(io_err)?;
+        drop(writer);
+        tokio::fs::rename(&part, &path)
+            .await
+            .map_err(|e| format!("Failed to finalize export file: {}", e))?;
+        let size = tokio::fs::metadata(&path)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+        Ok(size)
+    }
+}
+
+/// Whether `path` is a saved job record (`export-{id}.json`).
+fn is_record(path: &std::path::Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    name.starts_with("export-") && name.ends_with(".json")
+}
+
+fn format_row(format: ExportFormat, device_id: &str, metric: &str, point: &DataPoint) -> String {
+    match format {
+        ExportFormat::Csv => {
+            let value = match &point.value {
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::String(s) => csv_field(s),
+                serde_json::Value::Number(_) | serde_json::Value::Bool(_) => {
+                    point.value.to_string()
+                }
+                other => csv_field(&other.to_string()),
+            };
+            let quality = point.quality.map(|q| q.to_string()).unwrap_or_default();
+            format!(
+                "{},{},{},{},{}\n",
+                point.timestamp,
+                csv_field(device_id),
+                csv_field(metric),
+                value,
+                quality
+            )
+        }
+        ExportFormat::Jsonl => {
+            let row = serde_json::json!({
+                "timestamp": point.timestamp,
+                "device_id": device_id,
+                "metric": metric,
+                "value": point.value,
+                "quality": point.quality,
+            });
+            format!("{}\n", row)
+        }
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or newline.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_devices::telemetry::DataPoint as DevicePoint;
+    use neomind_devices::MetricValue;
+
+    #[test]
+    fn test_csv_escaping() {
+        let point = DataPoint {
+            timestamp: 10,
+            value: serde_json::json!("a,\"b\""),
+            quality: None,
+            metadata: None,
+        };
+        assert_eq!(
+            format_row(ExportFormat::Csv, "d1", "status", &point),
+            "10,d1,status,\"a,\"\"b\"\"\",\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_pages_through_range() {
+        let telemetry = Arc::new(TimeSeriesStorage::memory().unwrap());
+        for ts in 0..(PAGE_SIZE as i64 + 5) {
+            telemetry
+                .write(
+                    "device:pump-1",
+                    "pressure",
+                    DevicePoint::new(ts, MetricValue::Float(ts as f64)),
+                )
+                .await
+                .unwrap();
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let manager = Arc::new(ExportManager::new(dir.path().to_path_buf(), telemetry));
+        let job = manager
+            .start(
+                ExportRequest {
+                    device_ids: vec!["pump-1".to_string()],
+                    metrics: vec![],
+                    start: 0,
+                    end: i64::MAX,
+                    format: ExportFormat::Csv,
+                },
+                TenantId::default(),
+            )
+            .await
+            .unwrap();
+
+        let job = loop {
+            let job = manager.get(&job.id).await.unwrap();
+            if job.status != ExportStatus::Running {
+                break job;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        assert_eq!(job.status, ExportStatus::Completed, "{:?}", job.error);
+        assert_eq!(job.rows, PAGE_SIZE as u64 + 5);
+
+        let content = std::fs::read_to_string(manager.file_path(&job)).unwrap();
+        assert_eq!(content.lines().count(), PAGE_SIZE + 6);
+        assert!(
+            content.starts_with("timestamp,device_id,metric,value,quality\n0,pump-1,pressure,0")
+        );
+    }
+
+    fn saved_job(id: &str, status: ExportStatus) -> ExportJob {
+        ExportJob {
+            id: id.to_string(),
+            status,
+            request: ExportRequest {
+                device_ids: vec!["pump-1".to_string()],
+                metrics: vec![],
+                start: 0,
+                end: 10,
+                format: ExportFormat::Csv,
+            },
+            rows: 0,
+            size_bytes: 0,
+            error: None,
+            created_at: 0,
+            finished_at: None,
+            tenant_id: TenantId::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_restores_jobs_and_removes_leftovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let telemetry = Arc::new(TimeSeriesStorage::memory().unwrap());
+        let manager = ExportManager::new(dir.path().to_path_buf(), telemetry.clone());
+        let done = saved_job("done", ExportStatus::Completed);
+        let cut = saved_job("cut", ExportStatus::Running);
+        let gone = saved_job("gone", ExportStatus::Completed);
+        for job in [&done, &cut, &gone] {
+            manager.write_record(job).unwrap();
+        }
+        std::fs::write(manager.file_path(&done), "timestamp\n").unwrap();
+        let part = manager.file_path(&cut).with_extension("part");
+        std::fs::write(&part, "timestamp\n").unwrap();
+        let orphan = dir.path().join("export-orphan.csv");
+        std::fs::write(&orphan, "timestamp\n").unwrap();
+
+        let manager = ExportManager::open(dir.path().to_path_buf(), telemetry);
+        assert_eq!(manager.list().await.len(), 2);
+        assert_eq!(
+            manager.get("done").await.unwrap().status,
+            ExportStatus::Completed
+        );
+        let cut = manager.get("cut").await.unwrap();
+        assert_eq!(cut.status, ExportStatus::Failed);
+        assert!(cut.error.is_some());
+        assert!(manager.get("gone").await.is_none());
+        assert!(!manager.record_path("gone").exists());
+        assert!(!part.exists());
+        assert!(!orphan.exists());
+
+        // The failed state was saved, so it survives the next restart too.
+        let bytes = std::fs::read(manager.record_path("cut")).unwrap();
+        let saved: ExportJob = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(saved.status, ExportStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_running_jobs_are_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let telemetry = Arc::new(TimeSeriesStorage::memory().unwrap());
+        let manager = Arc::new(ExportManager::new(dir.path().to_path_buf(), telemetry));
+        for i in 0..MAX_RUNNING_JOBS {
+            let job = saved_job(&format!("running-{}", i), ExportStatus::Running);
+            manager.jobs.write().await.insert(job.id.clone(), job);
+        }
+        let request = saved_job("new", ExportStatus::Running).request;
+        assert!(manager
+            .start(request.clone(), TenantId::default())
+            .await
+            .is_err());
+
+        manager
+            .jobs
+            .write()
+            .await
+            .get_mut("running-0")
+            .unwrap()
+            .status = ExportStatus::Failed;
+        assert!(manager.start(request, TenantId::default()).await.is_ok());
+    }
+}
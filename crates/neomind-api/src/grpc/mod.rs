@@ -0,0 +1,526 @@
+//! gRPC API surface (feature `grpc`).
+//!
+//! Exposes session chat, device queries and rule CRUD over tonic for
+//! machine-to-machine consumers that would rather not parse SSE or the chat
+//! WebSocket protocol. Every unary call delegates to the matching HTTP
+//! handler, so validation, persistence and response shapes are shared with
+//! the REST API; JSON payloads carry the REST response's `data` field.
+//!
+//! Authentication mirrors `hybrid_auth_middleware`: a JWT or API key in the
+//! `authorization: Bearer <token>` metadata entry, or an API key in
+//! `x-api-key`. The tenant scope is resolved the same way, including the
+//! `x-neomind-tenant` metadata entry.
+
+// tonic fixes `Status` as the error type of every call
+#![allow(clippy::result_large_err)]
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+
+use axum::extract::{Json, Path, Query, State};
+use axum::http::StatusCode;
+use futures::Stream;
+use neomind_agent::AgentEvent;
+use neomind_core::tenant::TenantScope;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::auth::{RequestTenant, ValidatedApiKey};
+use crate::auth_users::{Permission, SessionInfo, UserRole};
+use crate::handlers::common::HandlerResult;
+use crate::handlers::devices::models::PaginationQuery;
+use crate::handlers::sessions::ChatQuota;
+use crate::models::error::ErrorResponse;
+use crate::server::state::AuthState;
+use crate::server::ServerState;
+
+/// Generated protobuf types and service stubs for `proto/neomind.proto`.
+pub mod proto {
+    tonic::include_proto!("neomind.v1");
+}
+
+use proto::neo_mind_server::{NeoMind, NeoMindServer};
+
+/// Default port for the gRPC listener (HTTP default + 1).
+pub const DEFAULT_GRPC_PORT: u16 = 9376;
+
+/// Buffered chat events per stream before the agent is back-pressured.
+const CHAT_EVENT_BUFFER: usize = 64;
+
+/// gRPC service backed by the shared server state.
+#[derive(Clone)]
+pub struct NeoMindGrpc {
+    state: ServerState,
+}
+
+impl NeoMindGrpc {
+    pub fn new(state: ServerState) -> Self {
+        Self { state }
+    }
+}
+
+/// Serve the gRPC API on `addr` until the process receives a shutdown signal.
+pub async fn serve(state: ServerState, addr: SocketAddr) -> anyhow::Result<()> {
+    let auth = state.auth.clone();
+    let service = NeoMindServer::with_interceptor(NeoMindGrpc::new(state), move |req| {
+        authenticate(&auth, req)
+    });
+
+    tracing::info!(category = "grpc", addr = %addr, "gRPC API listening");
+    tonic::transport::Server::builder()
+        .add_service(service)
+        .serve_with_shutdown(addr, crate::shutdown::shutdown_signal())
+        .await?;
+    Ok(())
+}
+
+/// Reject calls without a valid JWT or API key, and attach the caller's
+/// session, tenant scope (and API key, for token quotas) to the request.
+fn authenticate(auth: &AuthState, mut req: Request<()>) -> Result<Request<()>, Status> {
+    let metadata = req.metadata();
+    let token = metadata
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let api_key = metadata.get("x-api-key").and_then(|v| v.to_str().ok());
+
+    let mut validated_key = None;
+    let session = match token.map(|t| auth.user_state.validate_token(t)) {
+        Some(Ok(session)) => Some(session),
+        _ => token
+            .into_iter()
+            .chain(api_key)
+            .find_map(|key| Some((key, auth.api_key_state.validate_key_info(key)?)))
+            .map(|(key, info)| {
+                validated_key = Some(ValidatedApiKey(key.to_string()));
+                crate::auth::api_key_session(info)
+            }),
+    };
+    let Some(session) = session else {
+        return Err(Status::unauthenticated(
+            "Authentication required. Provide a valid JWT token or API key.",
+        ));
+    };
+
+    let headers = req.metadata().clone().into_headers();
+    let scope = crate::auth::resolve_tenant_scope(session.tenant_id.clone(), &headers)
+        .map_err(|e| {
+            if e.status == StatusCode::FORBIDDEN {
+                Status::permission_denied(e.message)
+            } else {
+                Status::invalid_argument(e.message)
+            }
+        })?;
+    req.extensions_mut().insert(scope);
+    req.extensions_mut().insert(session);
+    if let Some(key) = validated_key {
+        req.extensions_mut().insert(key);
+    }
+    Ok(req)
+}
+
+/// Session attached by [`authenticate`].
+fn request_session<T>(request: &Request<T>) -> Result<&SessionInfo, Status> {
+    request
+        .extensions()
+        .get::<SessionInfo>()
+        .ok_or_else(|| Status::unauthenticated("Authentication required"))
+}
+
+/// Fail with `PERMISSION_DENIED` unless the caller's role grants
+/// `permission`, like the `require_permission!` layer on the HTTP routes.
+fn authorize<T>(request: &Request<T>, permission: Permission) -> Result<(), Status> {
+    request_session(request)?
+        .ensure_permission(permission)
+        .map_err(|e| Status::permission_denied(e.to_string()))
+}
+
+/// Tenant scope attached by [`authenticate`].
+fn request_tenant<T>(request: &Request<T>) -> RequestTenant {
+    RequestTenant(
+        request
+            .extensions()
+            .get::<TenantScope>()
+            .cloned()
+            .unwrap_or_default(),
+    )
+}
+
+/// Map an HTTP handler error onto the closest gRPC status.
+fn to_status(err: ErrorResponse) -> Status {
+    let message = match err.hint {
+        Some(hint) => format!("{} (hint: {})", err.message, hint),
+        None => err.message,
+    };
+    match err.status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+            Status::invalid_argument(message)
+        }
+        StatusCode::UNAUTHORIZED => Status::unauthenticated(message),
+        StatusCode::FORBIDDEN => Status::permission_denied(message),
+        StatusCode::NOT_FOUND | StatusCode::GONE => Status::not_found(message),
+        StatusCode::CONFLICT => Status::already_exists(message),
+        StatusCode::TOO_MANY_REQUESTS => Status::resource_exhausted(message),
+        StatusCode::SERVICE_UNAVAILABLE => Status::unavailable(message),
+        StatusCode::REQUEST_TIMEOUT => Status::deadline_exceeded(message),
+        _ => Status::internal(message),
+    }
+}
+
+/// Turn an HTTP handler result into a `JsonReply` carrying its `data` field.
+fn to_reply(result: HandlerResult<serde_json::Value>) -> Result<Response<proto::JsonReply>, Status> {
+    let Json(response) = result.map_err(to_status)?;
+    let data = response.data.unwrap_or(serde_json::Value::Null);
+    Ok(Response::new(proto::JsonReply {
+        json: data.to_string(),
+    }))
+}
+
+fn parse_json(json: &str) -> Result<serde_json::Value, Status> {
+    serde_json::from_str(json).map_err(|e| Status::invalid_argument(format!("Invalid JSON: {}", e)))
+}
+
+/// Encode an agent event the way the chat WebSocket does (`{"type": ..., ...}`).
+fn to_chat_event(session_id: &str, event: &AgentEvent) -> proto::ChatEvent {
+    let value = serde_json::to_value(event).unwrap_or_default();
+    proto::ChatEvent {
+        session_id: session_id.to_string(),
+        r#type: value
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        json: value.to_string(),
+    }
+}
+
+/// Run one chat turn and forward its events. Returns `Ok(false)` once the
+/// client has gone away.
+async fn run_chat_turn(
+    state: &ServerState,
+    scope: &TenantScope,
+    quota: &ChatQuota,
+    req: proto::ChatRequest,
+    tx: &mpsc::Sender<Result<proto::ChatEvent, Status>>,
+) -> Result<bool, Status> {
+    if req.message.trim().is_empty() {
+        return Err(Status::invalid_argument("message must not be empty"));
+    }
+    quota.check().map_err(to_status)?;
+
+    let sessions = &state.agents.session_manager;
+    let session_id = if req.session_id.is_empty() {
+        let session_id = sessions
+            .create_session()
+            .await
+            .map_err(|e| Status::internal(format!("Failed to create session: {}", e)))?;
+        if let Some(tenant) = scope.tenant() {
+            sessions
+                .set_session_tenant(&session_id, tenant.clone())
+                .await
+                .map_err(|e| Status::internal(format!("Failed to create session: {}", e)))?;
+        }
+        session_id
+    } else if scope.allows(&sessions.session_tenant(&req.session_id)) {
+        req.session_id
+    } else {
+        return Err(Status::not_found(format!("Session not found: {}", req.session_id)));
+    };
+
+    // Tool calls made during the turn act on behalf of the session's tenant
+    let tenant = sessions.session_tenant(&session_id);
+    tenant
+        .scope(async {
+            let stream = sessions
+                .process_message_events_with_backend_and_skills(
+                    &session_id,
+                    &req.message,
+                    req.backend_id.as_deref(),
+                    &req.selected_skills,
+                )
+                .await
+                .map_err(|e| {
+                    let msg = e.to_string();
+                    if msg.contains("Not found") || msg.contains("Session:") {
+                        Status::not_found(format!("Session not found: {}", session_id))
+                    } else {
+                        Status::internal(msg)
+                    }
+                })?;
+            let mut stream = quota.clone().meter(stream, req.message.clone());
+
+            while let Some(event) = stream.next().await {
+                let is_end = matches!(event, AgentEvent::End { .. });
+                if tx.send(Ok(to_chat_event(&session_id, &event))).await.is_err() {
+                    return Ok(false);
+                }
+                if is_end {
+                    break;
+                }
+            }
+            Ok(true)
+        })
+        .await
+}
+
+type ChatStream = Pin<Box<dyn Stream<Item = Result<proto::ChatEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl NeoMind for NeoMindGrpc {
+    type ChatStream = ChatStream;
+
+    async fn chat(
+        &self,
+        request: Request<Streaming<proto::ChatRequest>>,
+    ) -> Result<Response<Self::ChatStream>, Status> {
+        // Chat can drive tools that change devices and rules, so read-only
+        // roles are refused as on the chat WebSocket.
+        if request_session(&request)?.role == UserRole::Viewer {
+            return Err(Status::permission_denied("Read-only role cannot use chat"));
+        }
+        let RequestTenant(scope) = request_tenant(&request);
+        let api_key = request.extensions().get::<ValidatedApiKey>().cloned();
+        let quota = ChatQuota::new(&self.state, api_key.map(|ValidatedApiKey(key)| key));
+        let mut inbound = request.into_inner();
+        let state = self.state.clone();
+        let (tx, rx) = mpsc::channel(CHAT_EVENT_BUFFER);
+
+        // Turns on one stream run sequentially, like messages in a chat window.
+        tokio::spawn(async move {
+            loop {
+                let req = match inbound.message().await {
+                    Ok(Some(req)) => req,
+                    Ok(None) => break,
+                    Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        break;
+                    }
+                };
+                match run_chat_turn(&state, &scope, &quota, req, &tx).await {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(status) => {
+                        if tx.send(Err(status)).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn list_devices(
+        &self,
+        request: Request<proto::ListDevicesRequest>,
+    ) -> Result<Response<proto::JsonReply>, Status> {
+        let tenant = request_tenant(&request);
+        let req = request.into_inner();
+        let query = PaginationQuery {
+            page: req.page.map(|p| p as usize),
+            limit: req.limit.map(|l| l as usize),
+            device_type: req.device_type,
+            status: req.status,
+        };
+        to_reply(
+            crate::handlers::devices::list_devices_handler(
+                State(self.state.clone()),
+                tenant,
+                Query(query),
+            )
+            .await,
+        )
+    }
+
+    async fn get_device(
+        &self,
+        request: Request<proto::GetDeviceRequest>,
+    ) -> Result<Response<proto::JsonReply>, Status> {
+        let tenant = request_tenant(&request);
+        let device_id = request.into_inner().device_id;
+        to_reply(
+            crate::handlers::devices::get_device_handler(
+                State(self.state.clone()),
+                tenant,
+                Path(device_id),
+            )
+            .await,
+        )
+    }
+
+    async fn list_rules(
+        &self,
+        request: Request<proto::ListRulesRequest>,
+    ) -> Result<Response<proto::JsonReply>, Status> {
+        let tenant = request_tenant(&request);
+        to_reply(
+            crate::handlers::rules::list_rules_handler(State(self.state.clone()), tenant).await,
+        )
+    }
+
+    async fn get_rule(
+        &self,
+        request: Request<proto::RuleIdRequest>,
+    ) -> Result<Response<proto::JsonReply>, Status> {
+        let tenant = request_tenant(&request);
+        let rule_id = request.into_inner().rule_id;
+        to_reply(
+            crate::handlers::rules::get_rule_handler(
+                State(self.state.clone()),
+                tenant,
+                Path(rule_id),
+            )
+            .await,
+        )
+    }
+
+    async fn create_rule(
+        &self,
+        request: Request<proto::RuleBody>,
+    ) -> Result<Response<proto::JsonReply>, Status> {
+        authorize(&request, Permission::RuleWrite)?;
+        let tenant = request_tenant(&request);
+        let body = parse_json(&request.into_inner().json)?;
+        to_reply(
+            crate::handlers::rules::create_rule_handler(
+                State(self.state.clone()),
+                tenant,
+                Json(body),
+            )
+            .await,
+        )
+    }
+
+    async fn update_rule(
+        &self,
+        request: Request<proto::UpdateRuleRequest>,
+    ) -> Result<Response<proto::JsonReply>, Status> {
+        authorize(&request, Permission::RuleWrite)?;
+        let tenant = request_tenant(&request);
+        let req = request.into_inner();
+        let body = parse_json(&req.json)?;
+        to_reply(
+            crate::handlers::rules::update_rule_handler(
+                State(self.state.clone()),
+                tenant,
+                Path(req.rule_id),
+                Json(body),
+            )
+            .await,
+        )
+    }
+
+    async fn delete_rule(
+        &self,
+        request: Request<proto::RuleIdRequest>,
+    ) -> Result<Response<proto::JsonReply>, Status> {
+        authorize(&request, Permission::RuleDelete)?;
+        let tenant = request_tenant(&request);
+        let rule_id = request.into_inner().rule_id;
+        to_reply(
+            crate::handlers::rules::delete_rule_handler(
+                State(self.state.clone()),
+                tenant,
+                Path(rule_id),
+            )
+            .await,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_mapping() {
+        assert_eq!(
+            to_status(ErrorResponse::not_found("Rule")).code(),
+            tonic::Code::NotFound
+        );
+        let status = to_status(ErrorResponse::bad_request("bad").with_hint("fix it"));
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().contains("fix it"));
+        assert_eq!(
+            to_status(ErrorResponse::internal("boom")).code(),
+            tonic::Code::Internal
+        );
+    }
+
+    #[test]
+    fn test_chat_event_carries_variant_name() {
+        let event = to_chat_event(
+            "s1",
+            &AgentEvent::Content {
+                content: "hi".to_string(),
+            },
+        );
+        assert_eq!(event.session_id, "s1");
+        assert_eq!(event.r#type, "Content");
+        let json: serde_json::Value = serde_json::from_str(&event.json).unwrap();
+        assert_eq!(json["content"], "hi");
+    }
+
+    /// Authenticate `token` and carry the attached extensions into a call.
+    fn authenticated<T>(state: &ServerState, token: &str, message: T) -> Request<T> {
+        let mut req = Request::new(());
+        req.metadata_mut().insert(
+            "authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        let (metadata, extensions, ()) = authenticate(&state.auth, req).unwrap().into_parts();
+        Request::from_parts(metadata, extensions, message)
+    }
+
+    #[tokio::test]
+    async fn test_viewer_cannot_modify_rules() {
+        let state = ServerState::new_for_testing().await;
+        let (_, viewer) = state
+            .auth
+            .user_state
+            .register("grpc_viewer", "viewer_password_123", UserRole::Viewer)
+            .await
+            .unwrap();
+        let (_, operator) = state
+            .auth
+            .user_state
+            .register("grpc_operator", "operator_password_123", UserRole::Operator)
+            .await
+            .unwrap();
+        let service = NeoMindGrpc::new(state.clone());
+
+        let body = || proto::RuleBody {
+            json: "{}".to_string(),
+        };
+        let status = service
+            .create_rule(authenticated(&state, &viewer, body()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        let rule = || proto::RuleIdRequest {
+            rule_id: "rule-1".to_string(),
+        };
+        let status = service
+            .delete_rule(authenticated(&state, &viewer, rule()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        // Operators get past the role check and fail on the rule itself
+        let status = service
+            .create_rule(authenticated(&state, &operator, body()))
+            .await
+            .unwrap_err();
+        assert_ne!(status.code(), tonic::Code::PermissionDenied);
+
+        // Reads stay open to viewers
+        assert!(service
+            .list_rules(authenticated(&state, &viewer, proto::ListRulesRequest {}))
+            .await
+            .is_ok());
+    }
+}
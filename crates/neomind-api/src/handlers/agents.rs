@@ -11,7 +11,8 @@ use std::sync::Arc;
 use neomind_agent::llm_backends::get_instance_manager;
 use neomind_storage::{
     AgentExecutionRecord, AgentFilter, AgentMemory, AgentSchedule, AgentStats, AgentStatus,
-    AiAgent, ExecutionMode, ExecutionStatus, ResourceType, ScheduleType, UserMessage,
+    AiAgent, ConcurrencyPolicy, ExecutionMode, ExecutionStatus, ResourceType, ScheduleType,
+    UserMessage,
 };
 
 use super::{
@@ -200,6 +201,8 @@ struct AgentDetailDto {
     /// Custom system prompt override
     #[serde(skip_serializing_if = "Option::is_none")]
     system_prompt: Option<String>,
+    /// Concurrency limits
+    concurrency: ConcurrencyPolicy,
 }
 
 /// Agent resource for API responses.
@@ -422,6 +425,9 @@ pub struct CreateAgentRequest {
     /// Custom system prompt override (replaces default IoT role prompt)
     #[serde(default)]
     pub system_prompt: Option<String>,
+    /// Concurrency limits (default: global cap only, queue when full)
+    #[serde(default)]
+    pub concurrency: Option<ConcurrencyPolicy>,
 }
 
 /// Resource request in the new unified format.
@@ -509,6 +515,9 @@ pub struct UpdateAgentRequest {
     /// Custom system prompt override (replaces default IoT role prompt)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub system_prompt: Option<String>,
+    /// Concurrency limits
+    #[serde(default)]
+    pub concurrency: Option<ConcurrencyPolicy>,
 }
 
 /// Resource in update request (new format).
@@ -643,6 +652,7 @@ impl From<&AiAgent> for AgentDetailDto {
             context_window_size: Some(agent.context_window_size),
             execution_mode: execution_mode_to_string(&agent.execution_mode).to_string(),
             system_prompt: agent.system_prompt.clone(),
+            concurrency: agent.concurrency.clone(),
         }
     }
 }
@@ -753,6 +763,29 @@ impl From<AgentExecutionRecord> for AgentExecutionDetailDto {
     }
 }
 
+/// Check the bounds of an agent's concurrency policy.
+fn validate_concurrency(policy: &ConcurrencyPolicy) -> Result<(), ErrorResponse> {
+    use crate::validator::validate_usize_range;
+
+    if let Some(max) = policy.max_concurrent {
+        validate_usize_range(max, "concurrency.max_concurrent", 1, 100)?;
+    }
+    validate_usize_range(policy.max_queue, "concurrency.max_queue", 0, 1000)
+}
+
+/// Check the payload conditions (`path`, `match`, `debounce_secs`) in an
+/// event filter.
+fn validate_event_filter(event_filter: Option<&str>) -> Result<(), ErrorResponse> {
+    match event_filter.map(neomind_agent::ai_agent::TriggerFilter::parse) {
+        Some(Err(e)) => Err(ErrorResponse::validation(e).with_hint(
+            "'path' is a JSONPath such as \"$.values.temperature\"; 'match' is \
+             {\"op\": \"eq\" | \"ne\" | \"gt\" | \"gte\" | \"lt\" | \"lte\" | \
+             \"contains\" | \"exists\", \"value\": ...}.",
+        )),
+        _ => Ok(()),
+    }
+}
+
 fn format_datetime(ts: i64) -> String {
     chrono::DateTime::from_timestamp(ts, 0)
         .map(|dt| dt.to_rfc3339())
@@ -857,6 +890,10 @@ pub async fn create_agent(
     if let Some(cw) = request.context_window_size {
         validate_usize_range(cw, "context_window_size", 1, 100)?;
     }
+    if let Some(ref policy) = request.concurrency {
+        validate_concurrency(policy)?;
+    }
+    validate_event_filter(request.schedule.event_filter.as_deref())?;
 
     // Validate schedule type
     if !["interval", "cron", "event"].contains(&request.schedule.schedule_type.as_str()) {
@@ -1069,6 +1106,7 @@ pub async fn create_agent(
         system_prompt: request.system_prompt,
         max_retries: 0,
         consecutive_failures: 0,
+        concurrency: request.concurrency.unwrap_or_default(),
         conversation_history: Default::default(),
         user_messages: Default::default(),
         conversation_summary: Default::default(),
@@ -1332,6 +1370,7 @@ pub async fn update_agent(
 
     // Update schedule if provided
     if let Some(schedule) = request.schedule {
+        validate_event_filter(schedule.event_filter.as_deref())?;
         let schedule_type = match schedule.schedule_type.as_str() {
             "interval" => neomind_storage::ScheduleType::Interval,
             "cron" => neomind_storage::ScheduleType::Cron,
@@ -1498,6 +1537,10 @@ pub async fn update_agent(
         crate::validator::validate_usize_range(context_window, "context_window_size", 1, 100)?;
         agent.context_window_size = context_window;
     }
+    if let Some(policy) = request.concurrency {
+        validate_concurrency(&policy)?;
+        agent.concurrency = policy;
+    }
     if let Some(mode) = request.execution_mode {
         agent.execution_mode = match mode.as_str() {
             "free" | "react" => neomind_storage::agents::ExecutionMode::Free,
@@ -2072,11 +2115,33 @@ pub async fn get_agent_stats(
         .map_err(|e| ErrorResponse::internal(format!("Failed to get agent: {}", e)))?
         .ok_or_else(|| ErrorResponse::not_found(format!("Agent not found: {}", id)))?;
 
+    // Live slot usage; zero before the agent manager is up
+    let (live, global) = match state.get_or_init_agent_manager().await {
+        Ok(manager) => {
+            let limiter = manager.executor().execution_limiter();
+            let stats = limiter.stats();
+            (
+                limiter.agent_stats(&id),
+                json!({
+                    "running": stats.running,
+                    "queued": stats.queued,
+                    "limit": stats.global_limit,
+                }),
+            )
+        }
+        Err(_) => (Default::default(), Value::Null),
+    };
+
     ok(json!({
         "total_executions": agent.stats.total_executions,
         "successful_executions": agent.stats.successful_executions,
         "failed_executions": agent.stats.failed_executions,
         "avg_duration_ms": agent.stats.avg_duration_ms,
+        "running": live.running,
+        "queued": live.queued,
+        "rejected": live.rejected,
+        "concurrency": agent.concurrency,
+        "global": global,
     }))
 }
 
@@ -0,0 +1,177 @@
+//! Agent action approval handlers.
+//!
+//! GET  /api/approvals              - List held tool calls (pending first)
+//! GET  /api/approvals/:id          - Get a held tool call
+//! POST /api/approvals/:id/approve  - Let the tool call run
+//! POST /api/approvals/:id/reject   - Refuse the tool call
+//!
+//! Tool calls in the categories listed in `agent.approval_required` (e.g.
+//! `device.control`) are held by the chat stream, which emits an
+//! `AwaitingApproval` event and waits for a decision here. Calls not decided
+//! within `agent.approval_ttl_secs` expire and are not executed.
+//!
+//! Approving a call in one of the categories listed in
+//! `agent.approval_mfa_required` additionally needs a second factor from the
+//! approving user, sent alongside the reason.
+//!
+//! A held call belongs to the tenant of its chat session; callers only see
+//! and decide calls of their own tenant, others are reported as not found.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use neomind_agent::agent::approval::requires_approval;
+use neomind_agent::agent::{pending_actions, ApprovalStatus, PendingAction};
+use neomind_core::config::agent_env_vars;
+
+use neomind_core::tenant::TenantScope;
+
+use super::common::{ok, HandlerResult};
+use super::sessions::session_in_scope;
+use crate::auth::RequestTenant;
+use crate::auth_users::SessionInfo;
+use crate::mfa::MfaProof;
+use crate::models::error::ErrorResponse;
+use crate::server::ServerState;
+
+/// Optional body of an approve/reject request.
+#[derive(Debug, Default, Deserialize)]
+pub struct DecisionRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Second factor for high-risk approvals
+    #[serde(flatten)]
+    pub proof: MfaProof,
+}
+
+/// `GET /api/approvals` — held tool calls, pending ones first.
+pub async fn list_approvals_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+) -> HandlerResult<serde_json::Value> {
+    let mut actions: Vec<_> = pending_actions()
+        .list()
+        .into_iter()
+        .filter(|a| session_in_scope(&state, &scope, &a.session_id))
+        .collect();
+    actions.sort_by_key(|a| a.status != ApprovalStatus::Pending);
+    let pending = actions
+        .iter()
+        .filter(|a| a.status == ApprovalStatus::Pending)
+        .count();
+    ok(json!({
+        "count": actions.len(),
+        "pending": pending,
+        "actions": actions,
+    }))
+}
+
+/// `GET /api/approvals/:id` — one held tool call.
+pub async fn get_approval_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<PendingAction> {
+    ok(held_action(&state, &scope, &id)?)
+}
+
+/// `POST /api/approvals/:id/approve` — let a held tool call run.
+///
+/// High-risk categories need `code`, `recovery_code` or a `webauthn`
+/// assertion (for a `POST /api/auth/mfa/challenge` challenge) in the body.
+pub async fn approve_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
+    Path(id): Path<String>,
+    body: Option<Json<DecisionRequest>>,
+) -> HandlerResult<PendingAction> {
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+    let held = held_action(&state, &scope, &id)?;
+    // A plan is high-risk if any of its steps is
+    let mfa_patterns = agent_env_vars::approval_mfa_required();
+    let high_risk = held
+        .categories()
+        .any(|category| requires_approval(category, &mfa_patterns));
+    if held.status == ApprovalStatus::Pending && high_risk {
+        confirm_second_factor(&state, user.as_ref(), &req.proof).await?;
+    }
+
+    let action = pending_actions().approve(&id, decided_by(user), req.reason)?;
+    tracing::info!(action_id = %id, category = %action.category, "Held tool call approved");
+    ok(action)
+}
+
+/// `POST /api/approvals/:id/reject` — refuse a held tool call.
+///
+/// The reason, if given, is passed back to the agent.
+pub async fn reject_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
+    Path(id): Path<String>,
+    body: Option<Json<DecisionRequest>>,
+) -> HandlerResult<PendingAction> {
+    held_action(&state, &scope, &id)?;
+    let reason = body.and_then(|Json(req)| req.reason);
+    let action = pending_actions().reject(&id, decided_by(user), reason)?;
+    tracing::info!(action_id = %id, category = %action.category, "Held tool call rejected");
+    ok(action)
+}
+
+/// A held call in the caller's scope; calls of other tenants are not found.
+fn held_action(
+    state: &ServerState,
+    scope: &TenantScope,
+    id: &str,
+) -> Result<PendingAction, ErrorResponse> {
+    pending_actions()
+        .get(id)
+        .filter(|action| session_in_scope(state, scope, &action.session_id))
+        .ok_or_else(|| ErrorResponse::not_found(format!("Pending action '{}'", id)))
+}
+
+/// Check the approving user's second factor.
+async fn confirm_second_factor(
+    state: &ServerState,
+    user: Option<&Extension<SessionInfo>>,
+    proof: &MfaProof,
+) -> Result<(), ErrorResponse> {
+    let user = match user {
+        Some(Extension(user)) if !user.is_service_account() => user,
+        _ => {
+            return Err(ErrorResponse::new(
+                "MFA_REQUIRED",
+                "This action must be approved by a user with a second factor",
+                StatusCode::FORBIDDEN,
+            ))
+        }
+    };
+    if proof.is_empty() {
+        return Err(ErrorResponse::new(
+            "MFA_REQUIRED",
+            "Approving this action needs a second factor",
+            StatusCode::FORBIDDEN,
+        )
+        .with_hint(
+            "Send `code`, `recovery_code` or a `webauthn` assertion for \
+             POST /api/auth/mfa/challenge",
+        ));
+    }
+    state
+        .auth
+        .user_state
+        .verify_step_up(&user.username, proof)
+        .await
+        .map_err(|e| ErrorResponse::new("MFA_FAILED", e.to_string(), StatusCode::FORBIDDEN))
+}
+
+/// Requests authenticated by API key have no user.
+fn decided_by(user: Option<Extension<SessionInfo>>) -> Option<String> {
+    user.map(|Extension(user)| user.username)
+}
@@ -0,0 +1,210 @@
+//! Voice input: transcribe an audio upload and run it as a chat message.
+
+use axum::extract::{Multipart, State};
+use axum::http::StatusCode;
+use axum::Extension;
+use neomind_core::llm::modality::{AudioContent, AudioFormat};
+use serde::Serialize;
+
+use super::common::{ok, HandlerResult};
+use super::sessions::{check_session_scope, ChatQuota};
+use super::ServerState;
+use crate::auth::{RequestTenant, ValidatedApiKey};
+use crate::models::ErrorResponse;
+
+/// Response of `POST /api/chat/audio`.
+#[derive(Debug, Serialize)]
+pub struct AudioChatResponse {
+    /// Session the message was sent to (created when none was given).
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    /// Text recognized from the audio.
+    pub transcript: String,
+    /// Detected language, if the transcriber reported one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// The assistant's response.
+    pub response: String,
+    /// Tools used.
+    #[serde(rename = "toolsUsed")]
+    pub tools_used: Vec<String>,
+    /// Processing time in milliseconds (transcription + agent).
+    #[serde(rename = "processingTimeMs")]
+    pub processing_time_ms: u64,
+}
+
+/// Parsed multipart form.
+#[derive(Debug, Default)]
+struct AudioUpload {
+    audio: Option<AudioContent>,
+    session_id: Option<String>,
+    language: Option<String>,
+}
+
+/// Resolve the audio format from the part's content type, falling back to
+/// the file extension.
+fn detect_format(content_type: Option<&str>, file_name: Option<&str>) -> Option<AudioFormat> {
+    content_type
+        .and_then(AudioFormat::from_mime_type)
+        .or_else(|| {
+            file_name
+                .and_then(|name| name.rsplit_once('.'))
+                .and_then(|(_, ext)| AudioFormat::from_extension(ext))
+        })
+}
+
+async fn read_upload(mut multipart: Multipart) -> Result<AudioUpload, ErrorResponse> {
+    let mut upload = AudioUpload::default();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ErrorResponse::bad_request(format!("Invalid multipart body: {}", e)))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "audio" | "file" => {
+                let format = detect_format(field.content_type(), field.file_name())
+                    .ok_or_else(|| ErrorResponse::bad_request("Unsupported audio format"))?;
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+                upload.audio = Some(AudioContent {
+                    data: data.to_vec(),
+                    format,
+                });
+            }
+            "session_id" | "sessionId" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+                upload.session_id = Some(value).filter(|v| !v.is_empty());
+            }
+            "language" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+                upload.language = Some(value).filter(|v| !v.is_empty());
+            }
+            _ => {}
+        }
+    }
+    Ok(upload)
+}
+
+/// `POST /api/chat/audio` — multipart upload with an `audio` file part and
+/// optional `session_id` and `language` fields.
+///
+/// The audio is transcribed and the text is processed like a typed chat
+/// message. Requires a transcriber (`NEOMIND_WHISPER_URL`).
+pub async fn audio_chat_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    api_key: Option<Extension<ValidatedApiKey>>,
+    multipart: Multipart,
+) -> HandlerResult<AudioChatResponse> {
+    // Voice turns count against the API key's daily LLM token quota like typed ones
+    let quota = ChatQuota::new(&state, api_key.map(|Extension(ValidatedApiKey(key))| key));
+    quota.check()?;
+
+    let upload = read_upload(multipart).await?;
+    // Reject other tenants' sessions before spending a transcription on them
+    if let Some(id) = &upload.session_id {
+        check_session_scope(&state, &scope, id)?;
+    }
+
+    let transcriber = state.agents.transcriber.clone().ok_or_else(|| {
+        ErrorResponse::new(
+            "TRANSCRIPTION_UNAVAILABLE",
+            "Speech-to-text is not configured (set NEOMIND_WHISPER_URL)",
+            StatusCode::SERVICE_UNAVAILABLE,
+        )
+    })?;
+
+    let audio = upload
+        .audio
+        .ok_or_else(|| ErrorResponse::bad_request("Missing 'audio' file part"))?;
+
+    let started = std::time::Instant::now();
+    let transcription = transcriber
+        .transcribe(&audio, upload.language.as_deref())
+        .await
+        .map_err(|e| {
+            tracing::warn!(transcriber = transcriber.name(), error = %e, "Transcription failed");
+            ErrorResponse::new(
+                "TRANSCRIPTION_FAILED",
+                format!("Transcription failed: {}", e),
+                StatusCode::BAD_GATEWAY,
+            )
+        })?;
+    if transcription.text.is_empty() {
+        return Err(ErrorResponse::bad_request("No speech recognized"));
+    }
+
+    let session_manager = &state.agents.session_manager;
+    let session_id = match upload.session_id {
+        Some(id) => id,
+        None => {
+            let id = session_manager
+                .create_session()
+                .await
+                .map_err(|e| ErrorResponse::with_message(e.to_string()))?;
+            if let Some(tenant) = scope.tenant() {
+                session_manager
+                    .set_session_tenant(&id, tenant.clone())
+                    .await
+                    .map_err(|e| ErrorResponse::with_message(e.to_string()))?;
+            }
+            id
+        }
+    };
+
+    tracing::info!(
+        session_id = %session_id,
+        audio_bytes = audio.data.len(),
+        transcript_len = transcription.text.chars().count(),
+        "audio_chat_handler: transcribed voice message"
+    );
+
+    let response = session_manager
+        .process_message(&session_id, &transcription.text)
+        .await
+        .map_err(|e| {
+            let err_msg = e.to_string();
+            if err_msg.contains("Not found") || err_msg.contains("Session:") {
+                ErrorResponse::not_found("Session")
+            } else {
+                ErrorResponse::with_message(err_msg)
+            }
+        })?;
+    quota.record(&transcription.text, None, &response.message.content);
+
+    ok(AudioChatResponse {
+        session_id,
+        transcript: transcription.text,
+        language: transcription.language,
+        response: response.message.content.to_string(),
+        tools_used: response.tools_used,
+        processing_time_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            detect_format(Some("audio/webm;codecs=opus"), Some("rec.wav")),
+            Some(AudioFormat::Webm)
+        );
+        assert_eq!(
+            detect_format(Some("application/octet-stream"), Some("rec.wav")),
+            Some(AudioFormat::Wav)
+        );
+        assert_eq!(detect_format(None, Some("notes.txt")), None);
+    }
+}
@@ -5,6 +5,7 @@ use axum::{
     http::{HeaderMap, StatusCode},
     response::{IntoResponse, Json},
 };
+use neomind_core::tenant::TenantId;
 use serde::{Deserialize, Serialize};
 
 use crate::auth::{ApiKeyInfo, AuthError};
@@ -25,14 +26,85 @@ fn extract_api_key(headers: &HeaderMap) -> Result<String, AuthError> {
         .ok_or_else(|| AuthError::unauthorized("Missing API key"))
 }
 
+/// Validate the caller's API key and return its info.
+fn validate_caller(state: &ServerState, headers: &HeaderMap) -> Result<ApiKeyInfo, AuthError> {
+    let api_key = extract_api_key(headers)?;
+    state
+        .auth
+        .api_key_state
+        .validate_key_info(&api_key)
+        .ok_or_else(|| AuthError::unauthorized("Invalid API key"))
+}
+
+/// Keys bound to a tenant may only manage keys of the same tenant.
+fn can_manage(caller: &ApiKeyInfo, key: &ApiKeyInfo) -> bool {
+    caller.tenant_id.is_none() || key.tenant_id == caller.tenant_id
+}
+
+/// Permissions for a key created by `caller`: never more than the caller's own.
+/// Empty means "same as the caller".
+fn bounded_permissions(
+    caller: &ApiKeyInfo,
+    requested: Vec<String>,
+) -> Result<Vec<String>, AuthError> {
+    if requested.is_empty() {
+        return Ok(caller.permissions.clone());
+    }
+    if caller.permissions.iter().any(|p| p == "*")
+        || requested.iter().all(|p| caller.permissions.contains(p))
+    {
+        Ok(requested)
+    } else {
+        Err(AuthError::forbidden(
+            "API key cannot grant permissions it does not have",
+        ))
+    }
+}
+
+/// A limit for a key managed by `caller`: never looser than the caller's own.
+/// Omitted falls back to the caller's limit.
+fn bounded_limit<T: PartialOrd>(
+    caller: Option<T>,
+    requested: Option<T>,
+) -> Result<Option<T>, AuthError> {
+    match (caller, requested) {
+        (None, requested) => Ok(requested),
+        (Some(own), None) => Ok(Some(own)),
+        (Some(own), Some(requested)) if requested <= own => Ok(Some(requested)),
+        (Some(_), Some(_)) => Err(AuthError::forbidden(
+            "API key cannot grant limits above its own",
+        )),
+    }
+}
+
 /// Request to create a new API key.
 #[derive(Debug, Deserialize)]
 pub struct CreateKeyRequest {
     /// Human-readable name for the key
     pub name: String,
-    /// Permissions (empty means full access)
+    /// Permissions (empty means the same as the calling key)
     #[serde(default)]
     pub permissions: Vec<String>,
+    /// Requests per minute for this key (global limit if omitted)
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
+    /// LLM tokens per UTC day for this key (unlimited if omitted)
+    #[serde(default)]
+    pub daily_token_quota: Option<u64>,
+    /// Tenant to bind the key to (all tenants if omitted)
+    #[serde(default)]
+    pub tenant_id: Option<TenantId>,
+}
+
+/// Request to change the limits of an existing API key.
+/// Omitted fields clear the corresponding limit, unless the calling key has
+/// one, which then applies.
+#[derive(Debug, Deserialize)]
+pub struct UpdateKeyLimitsRequest {
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
+    #[serde(default)]
+    pub daily_token_quota: Option<u64>,
 }
 
 /// Response for creating an API key.
@@ -63,6 +135,12 @@ pub struct KeyListItem {
     pub permissions: Vec<String>,
     /// Active status
     pub active: bool,
+    /// Requests per minute (global limit if unset)
+    pub rate_limit_per_minute: Option<u32>,
+    /// LLM tokens per UTC day (unlimited if unset)
+    pub daily_token_quota: Option<u64>,
+    /// Tenant the key is bound to (all tenants if unset)
+    pub tenant_id: Option<TenantId>,
     /// Masked key preview (first 8 chars only)
     pub preview: String,
 }
@@ -75,6 +153,9 @@ impl From<(String, ApiKeyInfo)> for KeyListItem {
             created_at: info.created_at,
             permissions: info.permissions,
             active: info.active,
+            rate_limit_per_minute: info.rate_limit_per_minute,
+            daily_token_quota: info.daily_token_quota,
+            tenant_id: info.tenant_id,
             preview: format!("{}...", &key[..key.len().min(12)]),
         }
     }
@@ -99,13 +180,15 @@ pub async fn list_keys_handler(
     headers: HeaderMap,
 ) -> Result<Json<KeyListResponse>, AuthError> {
     // Validate API key
-    let api_key = extract_api_key(&headers)?;
-    if !state.auth.api_key_state.validate_key(&api_key) {
-        return Err(AuthError::unauthorized("Invalid API key"));
-    }
+    let caller = validate_caller(&state, &headers)?;
 
+    // Keys bound to a tenant only see that tenant's keys
     let keys = state.auth.api_key_state.list_keys().await;
-    let items: Vec<KeyListItem> = keys.into_iter().map(Into::into).collect();
+    let items: Vec<KeyListItem> = keys
+        .into_iter()
+        .filter(|(_, info)| can_manage(&caller, info))
+        .map(Into::into)
+        .collect();
 
     Ok(Json(KeyListResponse { keys: items }))
 }
@@ -117,26 +200,71 @@ pub async fn create_key_handler(
     Json(req): Json<CreateKeyRequest>,
 ) -> Result<Json<CreateKeyResponse>, AuthError> {
     // Validate API key
-    let api_key = extract_api_key(&headers)?;
-    if !state.auth.api_key_state.validate_key(&api_key) {
-        return Err(AuthError::unauthorized("Invalid API key"));
-    }
+    let caller = validate_caller(&state, &headers)?;
 
-    let permissions = if req.permissions.is_empty() {
-        vec!["*".to_string()]
-    } else {
-        req.permissions
+    // A key bound to a tenant can only create keys for that tenant
+    let tenant_id = match (caller.tenant_id.clone(), req.tenant_id) {
+        (Some(own), Some(requested)) if own != requested => {
+            return Err(AuthError::forbidden("API key is not allowed to access this tenant"));
+        }
+        (Some(own), _) => Some(own),
+        (None, requested) => requested,
     };
 
+    let permissions = bounded_permissions(&caller, req.permissions)?;
+    let rate_limit_per_minute =
+        bounded_limit(caller.rate_limit_per_minute, req.rate_limit_per_minute)?;
+    let daily_token_quota = bounded_limit(caller.daily_token_quota, req.daily_token_quota)?;
+
     let (key, info) = state
         .auth
         .api_key_state
-        .create_key(req.name, permissions)
+        .create_key_with_limits(
+            req.name,
+            permissions,
+            rate_limit_per_minute,
+            daily_token_quota,
+            tenant_id,
+        )
         .await;
 
     Ok(Json(CreateKeyResponse { api_key: key, info }))
 }
 
+/// Update the rate limit and token quota of an API key (requires authentication).
+pub async fn update_key_limits_handler(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateKeyLimitsRequest>,
+) -> Result<Json<ApiKeyInfo>, AuthError> {
+    // Validate API key
+    let caller = validate_caller(&state, &headers)?;
+    let not_found = || AuthError {
+        status: StatusCode::NOT_FOUND,
+        message: format!("API key {} not found", id),
+        missing_permission: None,
+    };
+    let keys = state.auth.api_key_state.list_keys().await;
+    if !keys.iter().any(|(_, info)| info.id == id && can_manage(&caller, info)) {
+        return Err(not_found());
+    }
+    if caller.id == id {
+        return Err(AuthError::forbidden("API key cannot change its own limits"));
+    }
+    let rate_limit_per_minute =
+        bounded_limit(caller.rate_limit_per_minute, req.rate_limit_per_minute)?;
+    let daily_token_quota = bounded_limit(caller.daily_token_quota, req.daily_token_quota)?;
+
+    state
+        .auth
+        .api_key_state
+        .set_key_limits(&id, rate_limit_per_minute, daily_token_quota)
+        .await
+        .map(Json)
+        .ok_or_else(not_found)
+}
+
 /// Delete an API key by ID (requires authentication).
 pub async fn delete_key_handler(
     State(state): State<ServerState>,
@@ -144,15 +272,12 @@ pub async fn delete_key_handler(
     Path(id): Path<String>,
 ) -> Result<ApiResponse, AuthError> {
     // Validate API key
-    let api_key = extract_api_key(&headers)?;
-    if !state.auth.api_key_state.validate_key(&api_key) {
-        return Err(AuthError::unauthorized("Invalid API key"));
-    }
+    let caller = validate_caller(&state, &headers)?;
     // Find the key by ID and delete it
     let keys = state.auth.api_key_state.list_keys().await;
     let key_to_delete = keys
         .iter()
-        .find(|(_, info)| info.id == id)
+        .find(|(_, info)| info.id == id && can_manage(&caller, info))
         .map(|(k, _)| k.clone());
 
     if let Some(key) = key_to_delete {
@@ -213,6 +338,52 @@ mod tests {
         let req: CreateKeyRequest = serde_json::from_str(json).unwrap();
         assert_eq!(req.name, "Test Key");
         assert!(req.permissions.is_empty());
+        assert_eq!(req.rate_limit_per_minute, None);
+        assert_eq!(req.daily_token_quota, None);
+    }
+
+    #[test]
+    fn test_create_key_request_limits() {
+        let json = r#"{"name":"Dashboard","rate_limit_per_minute":60,"daily_token_quota":50000}"#;
+        let req: CreateKeyRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(req.rate_limit_per_minute, Some(60));
+        assert_eq!(req.daily_token_quota, Some(50_000));
+    }
+
+    fn key(permissions: &[&str], rate: Option<u32>, quota: Option<u64>) -> ApiKeyInfo {
+        ApiKeyInfo {
+            id: "caller".into(),
+            name: "caller".into(),
+            created_at: 0,
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            active: true,
+            rate_limit_per_minute: rate,
+            daily_token_quota: quota,
+            tenant_id: None,
+        }
+    }
+
+    #[test]
+    fn test_new_key_permissions_bounded_by_caller() {
+        let limited = key(&["read"], None, None);
+        assert_eq!(bounded_permissions(&limited, vec![]).unwrap(), ["read"]);
+        assert!(bounded_permissions(&limited, vec!["read".into()]).is_ok());
+        assert!(bounded_permissions(&limited, vec!["*".into()]).is_err());
+        assert!(bounded_permissions(&limited, vec!["write".into()]).is_err());
+
+        let full = key(&["*"], None, None);
+        assert_eq!(bounded_permissions(&full, vec![]).unwrap(), ["*"]);
+        assert!(bounded_permissions(&full, vec!["write".into()]).is_ok());
+    }
+
+    #[test]
+    fn test_new_key_limits_bounded_by_caller() {
+        assert_eq!(bounded_limit::<u32>(None, None).unwrap(), None);
+        assert_eq!(bounded_limit(None, Some(10u32)).unwrap(), Some(10));
+        // A limited caller cannot hand out an unlimited or looser key
+        assert_eq!(bounded_limit(Some(60u32), None).unwrap(), Some(60));
+        assert_eq!(bounded_limit(Some(60u32), Some(30)).unwrap(), Some(30));
+        assert!(bounded_limit(Some(60u32), Some(61)).is_err());
     }
 
     #[test]
@@ -2,15 +2,18 @@
 
 use axum::{
     extract::{Extension, Path, State},
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     response::Json,
 };
 
 use crate::auth_users::{
-    AuthError, ChangePasswordRequest, LoginRequest, LoginResponse, RegisterRequest, SessionInfo,
-    UserRole,
+    AuthError, ChangePasswordRequest, LoginRequest, LoginResponse, MfaLoginRequest,
+    PasskeyRegistrationRequest, Permission, RegisterRequest, SessionInfo, TotpConfirmRequest,
+    UpdateRoleRequest, UserRole,
 };
+use crate::mfa::{MfaProof, MfaStatus};
 use crate::server::ServerState;
+use neomind_core::tenant::TenantId;
 
 /// Login handler - authenticate user and return JWT token.
 pub async fn login_handler(
@@ -25,13 +28,32 @@ pub async fn login_handler(
     Ok(Json(response))
 }
 
+/// Second login step for users with a second factor: trade the MFA token
+/// from the 401 login response and a code, recovery code or passkey
+/// assertion for a session token.
+pub async fn login_mfa_handler(
+    State(state): State<ServerState>,
+    Json(req): Json<MfaLoginRequest>,
+) -> Result<Json<LoginResponse>, AuthError> {
+    let response = state
+        .auth
+        .user_state
+        .complete_mfa_login(&req.mfa_token, &req.proof)
+        .await?;
+    Ok(Json(response))
+}
+
 /// Register handler - create a new user account.
 ///
-/// SECURITY: Self-service registration ALWAYS creates a `UserRole::User`
-/// account, regardless of any `role` field supplied in the request body.
-/// Promoting to admin must go through the admin-only `create_user_handler`.
-/// The `role` field on `RegisterRequest` is accepted (for backwards-
-/// compatibility with older clients) but silently ignored.
+/// SECURITY: Self-service registration ALWAYS creates a read-only
+/// `UserRole::Viewer` account, regardless of any `role` field supplied in the
+/// request body. The route is public, so anything more would let anonymous
+/// callers control devices or approve held tool calls. Operators and admins
+/// are created or promoted through the admin-only user endpoints
+/// (`create_user_handler`, `update_user_role_handler`). The `role` field on
+/// `RegisterRequest` is accepted (for backwards-compatibility with older
+/// clients) but silently ignored, as is `tenant_id`: self-registered users
+/// are bound to the default tenant.
 pub async fn register_handler(
     State(state): State<ServerState>,
     Json(req): Json<RegisterRequest>,
@@ -39,7 +61,12 @@ pub async fn register_handler(
     let (user, token) = state
         .auth
         .user_state
-        .register(&req.username, &req.password, UserRole::User)
+        .register_in_tenant(
+            &req.username,
+            &req.password,
+            UserRole::Viewer,
+            Some(TenantId::default()),
+        )
         .await?;
     let response = serde_json::json!({
         "token": token,
@@ -71,6 +98,7 @@ pub async fn get_current_user_handler(
         "username": user.username,
         "role": user.role.as_str(),
         "created_at": user.created_at,
+        "tenant_id": user.tenant_id,
     })))
 }
 
@@ -133,10 +161,7 @@ pub async fn list_users_handler(
     State(state): State<ServerState>,
     Extension(user): Extension<SessionInfo>,
 ) -> Result<Json<serde_json::Value>, AuthError> {
-    // Check admin permission
-    if user.role != UserRole::Admin {
-        return Err(AuthError::InvalidInput("Admin access required".into()));
-    }
+    user.ensure_permission(Permission::ManageUsers)?;
 
     let users = state.auth.user_state.list_users().await;
     Ok(Json(serde_json::json!({"users": users})))
@@ -148,17 +173,14 @@ pub async fn create_user_handler(
     Extension(admin_user): Extension<SessionInfo>,
     Json(req): Json<RegisterRequest>,
 ) -> Result<(StatusCode, Json<serde_json::Value>), AuthError> {
-    // Check admin permission
-    if admin_user.role != UserRole::Admin {
-        return Err(AuthError::InvalidInput("Admin access required".into()));
-    }
+    admin_user.ensure_permission(Permission::ManageUsers)?;
 
-    let role = req.role.unwrap_or(UserRole::User);
+    let role = req.role.unwrap_or(UserRole::Operator);
     let role_str = role.as_str(); // Store role string before moving role
     let (user, _token) = state
         .auth
         .user_state
-        .register(&req.username, &req.password, role)
+        .register_in_tenant(&req.username, &req.password, role, req.tenant_id)
         .await?;
 
     tracing::info!(
@@ -171,16 +193,44 @@ pub async fn create_user_handler(
     Ok((StatusCode::CREATED, Json(serde_json::json!({"user": user}))))
 }
 
+/// Change a user's role (admin only). The user's open sessions are revoked
+/// so the new role applies from their next login.
+pub async fn update_user_role_handler(
+    State(state): State<ServerState>,
+    Extension(admin_user): Extension<SessionInfo>,
+    Path(username): Path<String>,
+    Json(req): Json<UpdateRoleRequest>,
+) -> Result<Json<serde_json::Value>, AuthError> {
+    admin_user.ensure_permission(Permission::ManageUsers)?;
+
+    // Prevent an admin from locking themselves out
+    if username == admin_user.username {
+        return Err(AuthError::InvalidInput("Cannot change your own role".into()));
+    }
+
+    let user = state
+        .auth
+        .user_state
+        .set_user_role(&username, req.role)
+        .await?;
+
+    tracing::info!(
+        admin = %admin_user.username,
+        user = %user.username,
+        role = user.role.as_str(),
+        "Admin changed user role"
+    );
+
+    Ok(Json(serde_json::json!({"user": user})))
+}
+
 /// Delete user handler (admin only).
 pub async fn delete_user_handler(
     State(state): State<ServerState>,
     Extension(admin_user): Extension<SessionInfo>,
     Path(username): Path<String>,
 ) -> Result<Json<serde_json::Value>, AuthError> {
-    // Check admin permission
-    if admin_user.role != UserRole::Admin {
-        return Err(AuthError::InvalidInput("Admin access required".into()));
-    }
+    admin_user.ensure_permission(Permission::ManageUsers)?;
 
     // Prevent self-deletion
     if username == admin_user.username {
@@ -201,3 +251,186 @@ pub async fn delete_user_handler(
         serde_json::json!({"message": format!("User '{}' deleted successfully", username)}),
     ))
 }
+
+/// Reset a user's second factors handler (admin only), for users who lost
+/// their phone and recovery codes.
+pub async fn reset_user_mfa_handler(
+    State(state): State<ServerState>,
+    Extension(admin_user): Extension<SessionInfo>,
+    Path(username): Path<String>,
+) -> Result<Json<serde_json::Value>, AuthError> {
+    admin_user.ensure_permission(Permission::ManageUsers)?;
+
+    state.auth.user_state.reset_mfa(&username).await?;
+
+    tracing::info!(
+        admin = %admin_user.username,
+        user = %username,
+        "Admin reset second factors"
+    );
+
+    Ok(Json(
+        serde_json::json!({"message": format!("Second factors of '{}' removed", username)}),
+    ))
+}
+
+/// Second factors belong to people; API keys and the share proxy have none.
+fn account_name(user: &SessionInfo) -> Result<&str, AuthError> {
+    if user.is_service_account() {
+        return Err(AuthError::InvalidInput(
+            "Second factors are only available to user accounts".into(),
+        ));
+    }
+    Ok(&user.username)
+}
+
+/// Get the current user's second factors.
+pub async fn get_mfa_status_handler(
+    State(state): State<ServerState>,
+    Extension(user): Extension<SessionInfo>,
+) -> Result<Json<MfaStatus>, AuthError> {
+    let status = state
+        .auth
+        .user_state
+        .mfa_status(account_name(&user)?)
+        .await?;
+    Ok(Json(status))
+}
+
+/// Start authenticator app enrollment. The `otpauth_uri` is what the UI
+/// renders as a QR code; the secret is for manual entry.
+pub async fn totp_setup_handler(
+    State(state): State<ServerState>,
+    Extension(user): Extension<SessionInfo>,
+) -> Result<Json<serde_json::Value>, AuthError> {
+    let (secret, uri) = state
+        .auth
+        .user_state
+        .begin_totp_enrollment(account_name(&user)?)
+        .await?;
+    Ok(Json(serde_json::json!({
+        "secret": secret,
+        "otpauth_uri": uri,
+    })))
+}
+
+/// Confirm authenticator app enrollment with the first code. Recovery
+/// codes are returned once, when this is the user's first factor.
+pub async fn totp_confirm_handler(
+    State(state): State<ServerState>,
+    Extension(user): Extension<SessionInfo>,
+    Json(req): Json<TotpConfirmRequest>,
+) -> Result<Json<serde_json::Value>, AuthError> {
+    let recovery_codes = state
+        .auth
+        .user_state
+        .confirm_totp_enrollment(account_name(&user)?, &req.code, &req.proof)
+        .await?;
+    Ok(Json(serde_json::json!({
+        "enabled": true,
+        "recovery_codes": recovery_codes,
+    })))
+}
+
+/// Remove the authenticator app; needs a current second factor.
+pub async fn totp_disable_handler(
+    State(state): State<ServerState>,
+    Extension(user): Extension<SessionInfo>,
+    Json(proof): Json<MfaProof>,
+) -> Result<Json<serde_json::Value>, AuthError> {
+    state
+        .auth
+        .user_state
+        .disable_totp(account_name(&user)?, &proof)
+        .await?;
+    Ok(Json(
+        serde_json::json!({"message": "Authenticator app removed"}),
+    ))
+}
+
+/// Replace the recovery codes; needs a current second factor.
+pub async fn recovery_codes_handler(
+    State(state): State<ServerState>,
+    Extension(user): Extension<SessionInfo>,
+    Json(proof): Json<MfaProof>,
+) -> Result<Json<serde_json::Value>, AuthError> {
+    let recovery_codes = state
+        .auth
+        .user_state
+        .regenerate_recovery_codes(account_name(&user)?, &proof)
+        .await?;
+    Ok(Json(serde_json::json!({ "recovery_codes": recovery_codes })))
+}
+
+/// Start passkey registration. The relying party is taken from the
+/// request's `Origin`, so this must be called from the browser.
+pub async fn passkey_register_begin_handler(
+    State(state): State<ServerState>,
+    Extension(user): Extension<SessionInfo>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, AuthError> {
+    let origin = headers
+        .get(header::ORIGIN)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            AuthError::InvalidInput("Passkeys must be registered from a browser".into())
+        })?;
+    let options = state
+        .auth
+        .user_state
+        .begin_passkey_registration(account_name(&user)?, origin)
+        .await?;
+    Ok(Json(serde_json::json!({ "publicKey": options })))
+}
+
+/// Finish passkey registration with the browser's credential.
+pub async fn passkey_register_finish_handler(
+    State(state): State<ServerState>,
+    Extension(user): Extension<SessionInfo>,
+    Json(req): Json<PasskeyRegistrationRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), AuthError> {
+    let (passkey, recovery_codes) = state
+        .auth
+        .user_state
+        .finish_passkey_registration(account_name(&user)?, &req.credential, &req.proof)
+        .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "passkey": passkey,
+            "recovery_codes": recovery_codes,
+        })),
+    ))
+}
+
+/// Remove a passkey; needs a current second factor.
+pub async fn passkey_remove_handler(
+    State(state): State<ServerState>,
+    Extension(user): Extension<SessionInfo>,
+    Path(id): Path<String>,
+    Json(proof): Json<MfaProof>,
+) -> Result<Json<serde_json::Value>, AuthError> {
+    state
+        .auth
+        .user_state
+        .remove_passkey(account_name(&user)?, &id, &proof)
+        .await?;
+    Ok(Json(serde_json::json!({"message": "Passkey removed"})))
+}
+
+/// Passkey options for confirming a sensitive action (removing a factor,
+/// approving a high-risk agent action). `webauthn` is null for users
+/// without passkeys, who send a code instead.
+pub async fn step_up_challenge_handler(
+    State(state): State<ServerState>,
+    Extension(user): Extension<SessionInfo>,
+) -> Result<Json<serde_json::Value>, AuthError> {
+    let options = state
+        .auth
+        .user_state
+        .begin_step_up(account_name(&user)?)
+        .await?;
+    Ok(Json(serde_json::json!({
+        "webauthn": options.map(|o| serde_json::json!({ "publicKey": o })),
+    })))
+}
@@ -1,12 +1,75 @@
-//! Configuration import/export handlers.
-
-use axum::{extract::State, response::Json};
+//! Configuration handlers.
+//!
+//! `/api/config` reads and changes runtime configuration through
+//! `neomind_core::config::service`; changes are validated against the schema,
+//! persisted with their history and applied without a restart. The
+//! `/api/config/{export,import,validate}` endpoints move whole configuration
+//! bundles between instances.
+
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use neomind_core::config::service::{self, ConfigEntry};
+use neomind_storage::{ConfigChangeEntry, SettingsStore};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use super::common::{ok, HandlerResult};
 use super::ServerState;
 use crate::models::{common::ApiResponse, ErrorResponse};
 
+const SETTINGS_DB_PATH: &str = "data/settings.redb";
+
+/// Query for configuration change history.
+#[derive(Debug, Deserialize)]
+pub struct ConfigHistoryQuery {
+    /// Only changes to this key
+    pub key: Option<String>,
+    #[serde(default = "default_history_limit")]
+    pub limit: usize,
+}
+
+fn default_history_limit() -> usize {
+    50
+}
+
+/// `GET /api/config` — runtime configuration with schema, current value and
+/// where the value comes from (env, stored or default).
+pub async fn get_runtime_config_handler() -> HandlerResult<Vec<ConfigEntry>> {
+    ok(service::global().entries())
+}
+
+/// `PUT /api/config` — change runtime configuration.
+///
+/// The body maps config keys to new values; `null` resets a key to its
+/// default. Either every change is applied or none is.
+pub async fn update_runtime_config_handler(
+    Json(changes): Json<BTreeMap<String, serde_json::Value>>,
+) -> HandlerResult<Vec<ConfigEntry>> {
+    if changes.is_empty() {
+        return Err(ErrorResponse::bad_request("No config changes given"));
+    }
+    ok(service::global().update(changes, "api")?)
+}
+
+/// `GET /api/config/history` — configuration changes, newest first.
+pub async fn get_config_history_handler(
+    Query(query): Query<ConfigHistoryQuery>,
+) -> HandlerResult<Vec<ConfigChangeEntry>> {
+    let store = SettingsStore::open(SETTINGS_DB_PATH)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to open settings store: {}", e)))?;
+    let limit = query.limit.clamp(1, 1000);
+    let history = match &query.key {
+        Some(key) => store.get_config_history(key, limit),
+        None => store.get_all_config_history(limit),
+    }
+    .map_err(|e| ErrorResponse::internal(format!("Failed to load config history: {}", e)))?;
+    ok(history)
+}
+
 /// Exported configuration bundle.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigExport {
@@ -38,7 +101,9 @@ pub struct LlmSettingsExport {
     pub temperature: f32,
     pub top_p: f32,
     pub max_tokens: usize,
-    // API key is excluded for security reasons
+    /// Only exported when it is a `secret://` handle; plain keys are left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
 }
 
 /// Device configurations for export.
@@ -171,6 +236,9 @@ pub async fn export_config_handler(
             temperature: settings.temperature,
             top_p: settings.top_p,
             max_tokens: settings.max_tokens,
+            api_key: settings
+                .api_key
+                .filter(|key| neomind_core::secrets::parse_handle(key).is_some()),
         });
     }
 
@@ -365,7 +433,8 @@ async fn import_llm_settings(
                 .clone()
                 .unwrap_or_else(|| "https://api.openai.com/v1".to_string());
             LlmBackend::OpenAi {
-                api_key: String::new(), // API key needs to be set separately for security
+                // Plain API keys are never exported; handles resolve on this instance
+                api_key: settings.api_key.clone().unwrap_or_default(),
                 endpoint,
                 model: settings.model.clone(),
                 capabilities: None,
@@ -420,6 +489,7 @@ mod tests {
                 temperature: 0.7,
                 top_p: 0.9,
                 max_tokens: 2048,
+                api_key: None,
             }),
             devices: None,
             rules: None,
@@ -461,4 +531,26 @@ mod tests {
         assert!(import.config.llm_settings.is_some());
         assert!(import.options.unwrap().sections.llm_settings);
     }
+
+    #[tokio::test]
+    async fn test_update_runtime_config_rejects_bad_changes() {
+        let changes = |key: &str, value: serde_json::Value| {
+            Json(BTreeMap::from([(key.to_string(), value)]))
+        };
+
+        let err = update_runtime_config_handler(changes("agent.nope", json!(1)))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::BAD_REQUEST);
+
+        let err = update_runtime_config_handler(changes("agent.top_p", json!(3.0)))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::BAD_REQUEST);
+
+        let err = update_runtime_config_handler(Json(BTreeMap::new()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::BAD_REQUEST);
+    }
 }
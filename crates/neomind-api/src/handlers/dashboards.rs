@@ -7,7 +7,7 @@ use axum::{
     extract::{Path, Query, State},
     http::{Method, Request, StatusCode},
     response::{IntoResponse, Response},
-    Json,
+    Extension, Json,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -16,9 +16,12 @@ use super::{
     common::{ok, HandlerResult},
     ServerState,
 };
+use crate::auth::RequestTenant;
+use crate::auth_users::{SessionInfo, UserRole};
 use crate::automation::types::{AutomationMetadata, TransformAutomation};
 use crate::models::ErrorResponse;
 use neomind_core::event::NeoMindEvent;
+use neomind_core::tenant::TenantScope;
 use neomind_storage::dashboards::{
     default_templates, Dashboard as StoredDashboard, DashboardComponent as StoredComponent,
     DashboardLayout as StoredLayout, DashboardTemplate as StoredTemplate,
@@ -169,6 +172,12 @@ pub struct Dashboard {
         rename = "sort_order"
     )]
     pub sort_order: Option<i32>,
+    /// Owning user ID; absent for dashboards shared by everyone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Dashboard-wide refresh interval in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_interval: Option<u32>,
 }
 
 /// Request to create a dashboard
@@ -179,6 +188,9 @@ pub struct CreateDashboardRequest {
     pub layout: DashboardLayout,
     #[serde(default)]
     pub components: Vec<CreateDashboardComponent>,
+    /// Dashboard-wide refresh interval in seconds; 0 or absent disables it
+    #[serde(default)]
+    pub refresh_interval: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -230,6 +242,9 @@ pub struct UpdateDashboardRequest {
     pub layout: Option<DashboardLayout>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Vec<JsonValue>>,
+    /// New refresh interval in seconds; 0 clears it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_interval: Option<u32>,
 }
 
 /// Dashboard template
@@ -277,6 +292,9 @@ pub struct PaginationParams {
     pub limit: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offset: Option<usize>,
+    /// Admins only: include dashboards owned by other users
+    #[serde(default)]
+    pub all: bool,
 }
 
 // ============================================================================
@@ -294,6 +312,8 @@ fn stored_to_api(dashboard: &StoredDashboard) -> Dashboard {
         updated_at: dashboard.updated_at,
         is_default: dashboard.is_default,
         sort_order: dashboard.sort_order,
+        owner: dashboard.owner.clone(),
+        refresh_interval: dashboard.refresh_interval,
     }
 }
 
@@ -399,55 +419,102 @@ fn stored_template_to_api(template: &StoredTemplate) -> DashboardTemplate {
     }
 }
 
+// ============================================================================
+// Ownership
+// ============================================================================
+
+/// The user a dashboard request acts for. API keys and the share proxy act
+/// for the installation rather than a person, so ownership does not apply
+/// to them and they see every dashboard.
+fn acting_user(user: Option<Extension<SessionInfo>>) -> Option<SessionInfo> {
+    user.map(|Extension(user)| user)
+        .filter(|user| !user.is_service_account())
+}
+
+/// Owners and admins may access a dashboard; unowned dashboards are open to
+/// everyone.
+fn can_access(dashboard: &StoredDashboard, user: Option<&SessionInfo>) -> bool {
+    user.is_none_or(|user| {
+        user.role == UserRole::Admin || dashboard.is_visible_to(&user.user_id)
+    })
+}
+
+/// Load a dashboard the caller may access. Other users' and other tenants'
+/// dashboards are reported as missing so their IDs are not revealed.
+fn load_dashboard(
+    state: &ServerState,
+    scope: &TenantScope,
+    id: &str,
+    user: Option<&SessionInfo>,
+) -> Result<StoredDashboard, ErrorResponse> {
+    state
+        .dashboard_store
+        .load(id)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load dashboard: {}", e)))?
+        .filter(|dashboard| scope.allows(&dashboard.tenant_id) && can_access(dashboard, user))
+        .ok_or_else(|| ErrorResponse::not_found(format!("Dashboard '{}' not found", id)))
+}
+
 // ============================================================================
 // Handlers
 // ============================================================================
 
-/// List all dashboards
+/// List the caller's dashboards and those shared by everyone in their tenant
 ///
-/// Performance optimization: Supports pagination via limit/offset query parameters.
+/// Supports pagination via limit/offset query parameters; admins can add
+/// `all=true` to include other users' dashboards.
 /// Example: GET /api/dashboards?limit=10&offset=20
 pub async fn list_dashboards_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Query(params): Query<PaginationParams>,
 ) -> HandlerResult<DashboardsResponse> {
     // Enforce reasonable limits to prevent performance issues
     let limit = params.limit.unwrap_or(100).min(1000); // Default 100, max 1000
     let offset = params.offset.unwrap_or(0);
 
-    // Get total count for pagination metadata (only when paginating)
-    let total = if limit < usize::MAX || offset > 0 {
-        state.dashboard_store.count().ok()
-    } else {
-        None
-    };
-
-    let dashboards = state
+    let mut dashboards = state
         .dashboard_store
-        .list_paginated(Some(limit), Some(offset))
+        .list_all()
         .map_err(|e| ErrorResponse::internal(format!("Failed to list dashboards: {}", e)))?;
 
+    // Tenant and ownership filtering have to happen before paging
+    dashboards.retain(|d| scope.allows(&d.tenant_id));
+    let user = acting_user(user);
+    let show_all = params.all && user.as_ref().is_none_or(|u| u.role == UserRole::Admin);
+    if let (Some(user), false) = (&user, show_all) {
+        dashboards.retain(|d| d.is_visible_to(&user.user_id));
+    }
+
     // Sort by `sort_order` so the sidebar reflects manual reordering. Legacy
     // rows without `sort_order` fall to the bottom (i32::MAX); stable sort
     // keeps their relative order intact.
-    let mut dashboards = dashboards;
     dashboards.sort_by_key(|d| d.sort_order.unwrap_or(i32::MAX));
+    let total = dashboards.len();
 
-    let api_dashboards: Vec<Dashboard> = dashboards.iter().map(stored_to_api).collect();
+    let api_dashboards: Vec<Dashboard> = dashboards
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .map(stored_to_api)
+        .collect();
     let count = api_dashboards.len();
 
     ok(DashboardsResponse {
         dashboards: api_dashboards,
         count,
-        total,
-        limit: if total.is_some() { Some(limit) } else { None },
-        offset: if total.is_some() { Some(offset) } else { None },
+        total: Some(total),
+        limit: Some(limit),
+        offset: Some(offset),
     })
 }
 
 /// Get a dashboard by ID
 pub async fn get_dashboard_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path(id): Path<String>,
 ) -> HandlerResult<Dashboard> {
     // Special handling for "overview" and "blank" template IDs
@@ -468,21 +535,20 @@ pub async fn get_dashboard_handler(
             updated_at: now,
             is_default: Some(id == "overview"),
             sort_order: None,
+            owner: None,
+            refresh_interval: None,
         });
     }
 
-    let dashboard = state
-        .dashboard_store
-        .load(&id)
-        .map_err(|e| ErrorResponse::internal(format!("Failed to load dashboard: {}", e)))?
-        .ok_or_else(|| ErrorResponse::not_found(format!("Dashboard '{}' not found", id)))?;
-
+    let dashboard = load_dashboard(&state, &scope, &id, acting_user(user).as_ref())?;
     ok(stored_to_api(&dashboard))
 }
 
-/// Create a new dashboard
+/// Create a new dashboard, owned by the calling user
 pub async fn create_dashboard_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Json(req): Json<CreateDashboardRequest>,
 ) -> HandlerResult<Dashboard> {
     let id = format!("dashboard_{}", uuid::Uuid::new_v4());
@@ -514,6 +580,9 @@ pub async fn create_dashboard_handler(
         updated_at: now,
         is_default: None,
         sort_order: Some(next_order),
+        owner: acting_user(user).map(|u| u.user_id),
+        refresh_interval: req.refresh_interval.filter(|secs| *secs > 0),
+        tenant_id: scope.owner(),
     };
 
     state
@@ -529,14 +598,12 @@ pub async fn create_dashboard_handler(
 /// Update a dashboard
 pub async fn update_dashboard_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path(id): Path<String>,
     Json(req): Json<UpdateDashboardRequest>,
 ) -> HandlerResult<Dashboard> {
-    let mut dashboard = state
-        .dashboard_store
-        .load(&id)
-        .map_err(|e| ErrorResponse::internal(format!("Failed to load dashboard: {}", e)))?
-        .ok_or_else(|| ErrorResponse::not_found(format!("Dashboard '{}' not found", id)))?;
+    let mut dashboard = load_dashboard(&state, &scope, &id, acting_user(user).as_ref())?;
 
     // Update fields if provided
     if let Some(name) = req.name {
@@ -545,6 +612,9 @@ pub async fn update_dashboard_handler(
     if let Some(layout) = req.layout {
         dashboard.layout = api_to_stored_layout(&layout);
     }
+    if let Some(secs) = req.refresh_interval {
+        dashboard.refresh_interval = Some(secs).filter(|secs| *secs > 0);
+    }
     if let Some(components) = req.components {
         // Parse components from JSON — fail if any component is invalid
         let parsed: Result<Vec<StoredComponent>, String> = components
@@ -582,14 +652,12 @@ pub async fn update_dashboard_handler(
 /// Add components to a dashboard (append mode)
 pub async fn add_components_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path(id): Path<String>,
     Json(req): Json<AddComponentsRequest>,
 ) -> HandlerResult<Dashboard> {
-    let mut dashboard = state
-        .dashboard_store
-        .load(&id)
-        .map_err(|e| ErrorResponse::internal(format!("Failed to load dashboard: {}", e)))?
-        .ok_or_else(|| ErrorResponse::not_found(format!("Dashboard '{}' not found", id)))?;
+    let mut dashboard = load_dashboard(&state, &scope, &id, acting_user(user).as_ref())?;
 
     // Parse and append new components
     let new_components: Vec<StoredComponent> = req
@@ -627,14 +695,12 @@ pub async fn add_components_handler(
 /// Remove components from a dashboard by ID
 pub async fn remove_components_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path(id): Path<String>,
     Json(req): Json<RemoveComponentsRequest>,
 ) -> HandlerResult<serde_json::Value> {
-    let mut dashboard = state
-        .dashboard_store
-        .load(&id)
-        .map_err(|e| ErrorResponse::internal(format!("Failed to load dashboard: {}", e)))?
-        .ok_or_else(|| ErrorResponse::not_found(format!("Dashboard '{}' not found", id)))?;
+    let mut dashboard = load_dashboard(&state, &scope, &id, acting_user(user).as_ref())?;
 
     let before = dashboard.components.len();
     dashboard.components.retain(|c| !req.ids.contains(&c.id));
@@ -670,19 +736,11 @@ pub struct RemoveComponentsRequest {
 /// Delete a dashboard
 pub async fn delete_dashboard_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
-    // Check if dashboard exists
-    if !state
-        .dashboard_store
-        .exists(&id)
-        .map_err(|e| ErrorResponse::internal(format!("Failed to check dashboard: {}", e)))?
-    {
-        return Err(ErrorResponse::not_found(format!(
-            "Dashboard '{}' not found",
-            id
-        )));
-    }
+    load_dashboard(&state, &scope, &id, acting_user(user).as_ref())?;
 
     state
         .dashboard_store
@@ -700,19 +758,11 @@ pub async fn delete_dashboard_handler(
 /// Set default dashboard
 pub async fn set_default_dashboard_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
-    // Check if dashboard exists
-    if !state
-        .dashboard_store
-        .exists(&id)
-        .map_err(|e| ErrorResponse::internal(format!("Failed to check dashboard: {}", e)))?
-    {
-        return Err(ErrorResponse::not_found(format!(
-            "Dashboard '{}' not found",
-            id
-        )));
-    }
+    load_dashboard(&state, &scope, &id, acting_user(user).as_ref())?;
 
     state
         .dashboard_store
@@ -747,6 +797,8 @@ pub struct ReorderDashboardsResponse {
 /// so other clients refetch.
 pub async fn reorder_dashboards_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Json(req): Json<ReorderDashboardsRequest>,
 ) -> HandlerResult<ReorderDashboardsResponse> {
     if req.dashboard_ids.is_empty() {
@@ -754,6 +806,10 @@ pub async fn reorder_dashboards_handler(
             "dashboard_ids must not be empty",
         ));
     }
+    let user = acting_user(user);
+    for id in &req.dashboard_ids {
+        load_dashboard(&state, &scope, id, user.as_ref())?;
+    }
 
     let items: Vec<(String, i32)> = req
         .dashboard_ids
@@ -843,15 +899,12 @@ pub struct SharedDashboardResponse {
 /// Create a share link for a dashboard
 pub async fn create_share_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path(id): Path<String>,
     Json(req): Json<CreateShareRequest>,
 ) -> HandlerResult<ShareTokenResponse> {
-    // Verify dashboard exists
-    state
-        .dashboard_store
-        .load(&id)
-        .map_err(|e| ErrorResponse::internal(format!("Failed to load dashboard: {}", e)))?
-        .ok_or_else(|| ErrorResponse::not_found(format!("Dashboard '{}' not found", id)))?;
+    load_dashboard(&state, &scope, &id, acting_user(user).as_ref())?;
 
     // Generate token: ds_ prefix + 22 random hex chars
     let random_bytes: [u8; 16] = rand::random();
@@ -894,8 +947,11 @@ pub async fn create_share_handler(
 /// List all share links for a dashboard
 pub async fn list_shares_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path(id): Path<String>,
 ) -> HandlerResult<Vec<ShareTokenResponse>> {
+    load_dashboard(&state, &scope, &id, acting_user(user).as_ref())?;
     let tokens = state
         .dashboard_store
         .list_share_tokens(&id)
@@ -923,8 +979,12 @@ pub async fn list_shares_handler(
 /// Revoke a share link
 pub async fn revoke_share_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path((id, token)): Path<(String, String)>,
 ) -> HandlerResult<serde_json::Value> {
+    load_dashboard(&state, &scope, &id, acting_user(user).as_ref())?;
+
     // Verify the token belongs to this dashboard
     let share = state
         .dashboard_store
@@ -1183,7 +1243,7 @@ fn new_output_prefix(source_prefix: &str) -> String {
 ///   - dataSource.sourceId             → "transform:{new_id}"
 ///   - dataSource.id                   → new_id
 ///   - dataSource.metricId / .field    → replace old_prefix with new_prefix if the
-///                                       string starts with "{old_prefix}."
+///     string starts with "{old_prefix}."
 fn rewrite_component_transform_refs(
     component: &mut StoredComponent,
     old_id: &str,
@@ -1335,6 +1395,9 @@ fn build_duplicate_dashboard(
         updated_at: now,
         is_default: None,
         sort_order: Some(max_sort_order + 1),
+        owner: source.owner.clone(),
+        refresh_interval: source.refresh_interval,
+        tenant_id: source.tenant_id.clone(),
     };
 
     DuplicateBuild {
@@ -1350,14 +1413,12 @@ fn build_duplicate_dashboard(
 /// shared (they are global resources).
 pub async fn duplicate_dashboard_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path(id): Path<String>,
 ) -> HandlerResult<Dashboard> {
-    // Load source
-    let source = state
-        .dashboard_store
-        .load(&id)
-        .map_err(|e| ErrorResponse::internal(format!("Failed to load source dashboard: {}", e)))?
-        .ok_or_else(|| ErrorResponse::not_found(format!("Dashboard '{}' not found", id)))?;
+    let user = acting_user(user);
+    let source = load_dashboard(&state, &scope, &id, user.as_ref())?;
 
     // Read max sort order
     let max_order = state
@@ -1376,7 +1437,12 @@ pub async fn duplicate_dashboard_handler(
     };
 
     // Pure clone logic
-    let build = build_duplicate_dashboard(&source, available_transforms, max_order);
+    let mut build = build_duplicate_dashboard(&source, available_transforms, max_order);
+    // The copy belongs to whoever made it
+    if let Some(user) = user {
+        build.dashboard.owner = Some(user.user_id);
+    }
+    build.dashboard.tenant_id = scope.owner();
 
     // Persist new transforms first (orphan transforms are harmless if dashboard save fails)
     if let Some(store) = &state.automation.automation_store {
@@ -1697,6 +1763,9 @@ mod tests {
             updated_at: 200,
             is_default: Some(true),
             sort_order: Some(3),
+            owner: None,
+            refresh_interval: Some(15),
+            tenant_id: Default::default(),
         };
 
         let result = build_duplicate_dashboard(&src_dashboard, vec![src_transform], 10);
@@ -1710,6 +1779,7 @@ mod tests {
             "is_default must NOT be inherited"
         );
         assert_eq!(result.dashboard.sort_order, Some(11), "appended to end");
+        assert_eq!(result.dashboard.refresh_interval, Some(15));
         assert!(result.dashboard.created_at > 100, "fresh created_at");
 
         // Transform clones
@@ -1753,6 +1823,9 @@ mod tests {
             updated_at: 0,
             is_default: None,
             sort_order: None,
+            owner: None,
+            refresh_interval: None,
+            tenant_id: Default::default(),
         };
         let result = build_duplicate_dashboard(&src, vec![], 0);
         assert_eq!(result.dashboard.name, "X (copy) (copy)");
@@ -9,7 +9,9 @@
 use axum::extract::{Query, State};
 use serde::{Deserialize, Serialize};
 
+use crate::auth::RequestTenant;
 use crate::handlers::common::{ok, HandlerResult};
+use crate::handlers::devices::crud::source_in_scope;
 use crate::server::types::ServerState;
 use neomind_core::datasource::DataSourceId;
 
@@ -78,6 +80,7 @@ pub struct ListDataSourcesResponse {
 /// Supports server-side filtering, search, and pagination.
 pub async fn list_all_data_sources_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Query(params): Query<ListDataSourcesQuery>,
 ) -> HandlerResult<ListDataSourcesResponse> {
     let mut sources = Vec::new();
@@ -115,6 +118,9 @@ pub async fn list_all_data_sources_handler(
     sources.append(&mut device_sources);
     sources.append(&mut extension_sources);
     sources.append(&mut transform_sources);
+    sources.retain(|s| {
+        source_in_scope(&state, &scope, &format!("{}:{}", s.source_type, s.source_name))
+    });
 
     // Sort by id for consistent ordering
     sources.sort_by(|a, b| a.id.cmp(&b.id));
@@ -509,6 +515,7 @@ pub struct TelemetryQueryParams {
 /// - `GET /api/telemetry?source=transform:converter&metric=output&aggregate=avg`
 pub async fn query_telemetry_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Query(params): Query<TelemetryQueryParams>,
 ) -> HandlerResult<serde_json::Value> {
     let now = chrono::Utc::now().timestamp();
@@ -545,6 +552,9 @@ pub async fn query_telemetry_handler(
 
     let source_part = ds_id.source_part();
     let metric_part = ds_id.metric_part();
+    if !source_in_scope(&state, &scope, &source_part) {
+        return Err(crate::models::error::ErrorResponse::not_found("Source"));
+    }
 
     let telemetry = &state.devices.telemetry;
 
@@ -611,3 +621,48 @@ pub async fn query_telemetry_handler(
         "data": data,
     }))
 }
+
+// ============================================================================
+// SQL-like Query API
+// ============================================================================
+
+/// Request body for the query endpoint.
+#[derive(Debug, Deserialize)]
+pub struct DataQueryRequest {
+    /// Query text, see [`neomind_storage::query`] for the grammar
+    pub query: String,
+}
+
+/// POST /api/data/query
+///
+/// Run a SQL-like query over stored telemetry.
+///
+/// Example body:
+/// `{"query": "SELECT avg(temperature) FROM device:sensor_1 WHERE time > now() - 1h GROUP BY 5m"}`
+pub async fn query_data_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    axum::Json(req): axum::Json<DataQueryRequest>,
+) -> HandlerResult<neomind_storage::QueryResult> {
+    let now = chrono::Utc::now().timestamp();
+    let query = neomind_storage::Query::parse(&req.query, now).map_err(query_error)?;
+
+    // Wildcards only expand to, and named sources only read, the scope's devices
+    let store = state.devices.telemetry.inner_store();
+    let result = neomind_storage::query::execute_where(&store, &query, |source| {
+        source_in_scope(&state, &scope, source)
+    })
+    .await
+    .map_err(query_error)?;
+    ok(result)
+}
+
+/// Syntax and limit errors are the caller's fault; anything else is ours.
+fn query_error(e: neomind_storage::Error) -> crate::models::error::ErrorResponse {
+    match e {
+        neomind_storage::Error::InvalidInput(msg) => {
+            crate::models::error::ErrorResponse::bad_request(msg)
+        }
+        other => crate::models::error::ErrorResponse::internal(other.to_string()),
+    }
+}
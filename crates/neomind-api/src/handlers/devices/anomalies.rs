@@ -0,0 +1,76 @@
+//! Telemetry anomaly handlers.
+//!
+//! Exposes what the [`AnomalyDetector`](crate::automation::anomaly::AnomalyDetector)
+//! has flagged recently, together with the baseline it compared against, so
+//! the agent can explain why a value was unusual.
+
+use axum::extract::{Query, State};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::crud::{check_device_scope, device_in_scope};
+use crate::auth::RequestTenant;
+use crate::handlers::{
+    common::{ok, HandlerResult},
+    ServerState,
+};
+
+/// Query for listing anomalies.
+#[derive(Debug, Deserialize)]
+pub struct AnomaliesQuery {
+    pub device_id: Option<String>,
+    pub metric: Option<String>,
+    /// Only anomalies at or after this Unix timestamp
+    pub since: Option<i64>,
+    /// Maximum number of anomalies (default 50)
+    pub limit: Option<usize>,
+}
+
+/// List recently detected anomalies, newest first.
+pub async fn list_anomalies_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Query(query): Query<AnomaliesQuery>,
+) -> HandlerResult<serde_json::Value> {
+    if let Some(device_id) = &query.device_id {
+        check_device_scope(&state, &scope, device_id)?;
+    }
+    let detector = &state.automation.anomaly_detector;
+    let anomalies = detector
+        .recent_where(
+            |device_id| {
+                query.device_id.as_deref().is_none_or(|id| id == device_id)
+                    && device_in_scope(&state, &scope, device_id)
+            },
+            query.metric.as_deref(),
+            query.since,
+            query.limit.unwrap_or(50).min(500),
+        )
+        .await;
+
+    // Include the current baseline of each metric involved
+    let mut baselines = serde_json::Map::new();
+    for anomaly in &anomalies {
+        let key = format!("{}/{}", anomaly.device_id, anomaly.metric);
+        if baselines.contains_key(&key) {
+            continue;
+        }
+        if let Some(baseline) = detector.baseline(&anomaly.device_id, &anomaly.metric).await {
+            baselines.insert(
+                key,
+                json!({
+                    "mean": baseline.overall.mean,
+                    "std_dev": baseline.overall.std_dev(),
+                    "samples": baseline.overall.count,
+                }),
+            );
+        }
+    }
+
+    ok(json!({
+        "count": anomalies.len(),
+        "anomalies": anomalies,
+        "baselines": baselines,
+        "z_threshold": detector.config().z_threshold,
+    }))
+}
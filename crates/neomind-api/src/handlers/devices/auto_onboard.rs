@@ -255,6 +255,9 @@ pub async fn approve_draft_device(
                 adapter_id: draft.adapter_id.clone().or(Some(default_adapter_id)),
                 last_seen: chrono::Utc::now().timestamp(),
                 offline_timeout_secs: None,
+                tags: Vec::new(),
+                location: None,
+                tenant_id: Default::default(),
             };
 
             // Register the device
@@ -317,7 +320,9 @@ pub async fn approve_draft_device(
                 commands: Vec::new(),       // No commands generated yet
                 uplink_samples: Vec::new(), // Samples not stored in draft
                 default_offline_timeout_secs: None,
+                expected_interval_secs: None,
                 store_raw: None,
+                binary_codec: None,
             };
 
             // Register the device type template
@@ -358,6 +363,9 @@ pub async fn approve_draft_device(
                 adapter_id: draft.adapter_id.clone().or(Some(default_adapter_id)),
                 last_seen: chrono::Utc::now().timestamp(),
                 offline_timeout_secs: None,
+                tags: Vec::new(),
+                location: None,
+                tenant_id: Default::default(),
             };
 
             // Register the device
@@ -10,6 +10,7 @@ use axum::{extract::State, Json};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use crate::auth::RequestTenant;
 use crate::config;
 use crate::handlers::common::{ok, HandlerResult};
 use crate::models::ErrorResponse;
@@ -95,6 +96,7 @@ fn resolve_broker_config(broker_id: &str) -> Result<(String, u16, String, String
 ///     If device already exists, returns its config (idempotent).
 pub async fn ble_provision_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<BleProvisionRequest>,
 ) -> HandlerResult<serde_json::Value> {
     // 1. Validate device_type exists in registry
@@ -115,6 +117,13 @@ pub async fn ble_provision_handler(
 
     // 3. Check for duplicate — if device already exists, update or return config
     if let Some(existing) = state.devices.service.get_device(&device_id) {
+        // Device IDs are global: only the owner's scope may re-provision
+        if !scope.allows(&existing.tenant_id) {
+            return Err(ErrorResponse::conflict(format!(
+                "Device ID '{}' is already in use",
+                device_id
+            )));
+        }
         tracing::info!(
             category = "ble",
             device_id = %device_id,
@@ -157,10 +166,14 @@ pub async fn ble_provision_handler(
                     json_path: existing.connection_config.json_path.clone(),
                     entity_id: existing.connection_config.entity_id.clone(),
                     extra,
+                    ..Default::default()
                 },
                 adapter_id: existing.adapter_id.clone(),
                 last_seen: existing.last_seen,
                 offline_timeout_secs: existing.offline_timeout_secs,
+                tags: existing.tags.clone(),
+                location: existing.location.clone(),
+                tenant_id: existing.tenant_id.clone(),
             };
 
             state
@@ -230,10 +243,14 @@ pub async fn ble_provision_handler(
             json_path: None,
             entity_id: None,
             extra,
+            ..Default::default()
         },
         adapter_id: None,
         last_seen: 0,
         offline_timeout_secs: None,
+        tags: Vec::new(),
+        location: None,
+        tenant_id: scope.owner(),
     };
 
     state
@@ -0,0 +1,77 @@
+//! ChirpStack HTTP integration receiver.
+//!
+//! ChirpStack's HTTP integration POSTs every device event to one URL with the
+//! event type in the `event` query parameter. Configure it with:
+//! - Endpoint: `http://<neomind>/api/chirpstack/events`
+//! - Payload encoding: JSON
+//! - Header `X-API-Key: <chirpstack.api_key>` when an API key is configured
+//!
+//! Events are processed by the `internal-chirpstack` adapter, the same path
+//! as the MQTT integration.
+
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::HeaderMap,
+};
+use std::collections::HashMap;
+
+use crate::handlers::{
+    common::{ok, HandlerResult},
+    ServerState,
+};
+use crate::models::ErrorResponse;
+
+use neomind_devices::adapter::AdapterError;
+use neomind_devices::adapters::ChirpStackAdapter;
+
+use super::webhook::extract_api_key;
+
+/// Handle a ChirpStack HTTP integration event.
+///
+/// Endpoint: `POST /api/chirpstack/events?event=up`
+pub async fn chirpstack_event_handler(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: Bytes,
+) -> HandlerResult<serde_json::Value> {
+    let adapter = state
+        .devices
+        .service
+        .get_adapter("internal-chirpstack")
+        .await
+        .ok_or_else(|| ErrorResponse::service_unavailable("ChirpStack adapter not initialized"))?;
+    let adapter = adapter
+        .as_any()
+        .downcast_ref::<ChirpStackAdapter>()
+        .cloned()
+        .ok_or_else(|| ErrorResponse::internal("Failed to downcast ChirpStack adapter"))?;
+
+    adapter
+        .verify_api_key(extract_api_key(&headers).as_deref())
+        .map_err(|e| ErrorResponse::unauthorized(e.to_string()))?;
+
+    let event = params
+        .get("event")
+        .ok_or_else(|| ErrorResponse::bad_request("Missing 'event' query parameter"))?;
+    let body: serde_json::Value = serde_json::from_slice(&body).map_err(|e| {
+        ErrorResponse::bad_request(format!(
+            "ChirpStack event must be JSON (set the integration payload encoding to JSON): {}",
+            e
+        ))
+    })?;
+
+    adapter.handle_event(event, &body).await.map_err(|e| {
+        tracing::warn!(event = %event, error = %e, "ChirpStack event processing failed");
+        match e {
+            AdapterError::Configuration(msg) => ErrorResponse::bad_request(msg),
+            _ => ErrorResponse::internal("ChirpStack event processing failed"),
+        }
+    })?;
+
+    ok(serde_json::json!({
+        "success": true,
+        "event": event,
+    }))
+}
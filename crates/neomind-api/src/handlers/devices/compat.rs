@@ -36,6 +36,9 @@ pub fn device_instance_to_config(instance: &DeviceInstance) -> DeviceConfig {
         adapter_id: instance.adapter_id.clone(),
         last_seen: 0,
         offline_timeout_secs: None,
+        tags: Vec::new(),
+        location: None,
+        tenant_id: Default::default(),
     }
 }
 
@@ -111,7 +114,9 @@ pub fn device_type_to_template(def: &DeviceTypeDefinition) -> DeviceTypeTemplate
         // Extract commands from downlink
         commands: def.downlink.commands.clone(),
         default_offline_timeout_secs: None,
+        expected_interval_secs: None,
         store_raw: None,
+        binary_codec: None,
     }
 }
 
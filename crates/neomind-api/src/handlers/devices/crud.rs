@@ -11,13 +11,15 @@ use uuid::Uuid;
 use super::compat::{config_to_device_instance, format_status_to_str};
 use super::models::{
     AddDeviceRequest, BatchCurrentValuesRequest, DeviceDto, PaginationMeta, PaginationQuery,
-    UpdateDeviceRequest,
+    StateAtQuery, UpdateDeviceRequest,
 };
+use crate::auth::RequestTenant;
 use crate::handlers::{
     common::{ok, HandlerResult},
     ServerState,
 };
 use crate::models::ErrorResponse;
+use neomind_core::tenant::TenantScope;
 use neomind_devices::{
     adapter::ConnectionStatus as AdapterConnectionStatus,
     mdl::ConnectionStatus as MdlConnectionStatus,
@@ -68,6 +70,48 @@ fn get_plugin_info(adapter_id: &Option<String>) -> (Option<String>, Option<Strin
     }
 }
 
+/// Ensure `device_id` is visible in the request's tenant scope.
+///
+/// Devices of other tenants are reported as not found so their existence
+/// doesn't leak.
+pub(crate) fn check_device_scope(
+    state: &ServerState,
+    scope: &TenantScope,
+    device_id: &str,
+) -> Result<(), ErrorResponse> {
+    if device_in_scope(state, scope, device_id) {
+        Ok(())
+    } else {
+        Err(ErrorResponse::not_found("Device"))
+    }
+}
+
+/// Whether `device_id` is registered in the request's tenant scope.
+///
+/// Unscoped requests may also name devices that are not registered.
+pub(crate) fn device_in_scope(state: &ServerState, scope: &TenantScope, device_id: &str) -> bool {
+    *scope == TenantScope::All
+        || state
+            .devices
+            .service
+            .get_device(device_id)
+            .is_some_and(|config| scope.allows(&config.tenant_id))
+}
+
+/// Whether a telemetry source (`device:<id>`, `extension:<id>`, ...) is
+/// visible in the request's tenant scope.
+///
+/// Only devices are owned by a tenant; extension, transform and other
+/// instance-wide sources are hidden from tenant-bound requests.
+pub(crate) fn source_in_scope(state: &ServerState, scope: &TenantScope, source: &str) -> bool {
+    if *scope == TenantScope::All {
+        return true;
+    }
+    source
+        .strip_prefix("device:")
+        .is_some_and(|device_id| device_in_scope(state, scope, device_id))
+}
+
 /// List devices with pagination and filtering support.
 /// Uses new DeviceService with real device status from event tracking
 ///
@@ -75,6 +119,7 @@ fn get_plugin_info(adapter_id: &Option<String>) -> (Option<String>, Option<Strin
 /// for both filtering and DTO conversion, eliminating duplicate status queries.
 pub async fn list_devices_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Query(pagination): Query<PaginationQuery>,
 ) -> HandlerResult<serde_json::Value> {
     // Parse pagination parameters
@@ -86,8 +131,8 @@ pub async fn list_devices_handler(
     let configs = state.devices.service.list_devices();
     let all_statuses = state.devices.service.get_all_device_statuses().await;
     let all_templates = state.devices.service.list_templates();
-    // Use the globally-configured offline timeout (instead of hardcoded 5 min)
-    let global_offline_timeout = state.devices.service.heartbeat_config().offline_timeout;
+    // Heartbeat config carries the global timeout and the interval grace period
+    let heartbeat = state.devices.service.heartbeat_config();
     // Build device_type → template map for per-device offline-timeout resolution
     let template_map: std::collections::HashMap<&str, &neomind_devices::DeviceTypeTemplate> =
         all_templates
@@ -95,17 +140,10 @@ pub async fn list_devices_handler(
             .map(|t| (t.device_type.as_str(), t))
             .collect();
     // Resolve per-device effective offline timeout.
-    // Priority: device override > template default > global.
+    // Priority: device override > template default > expected interval + grace > global.
     let effective_timeout = |config: &neomind_devices::DeviceConfig| -> u64 {
-        if let Some(secs) = config.offline_timeout_secs {
-            return secs;
-        }
-        if let Some(tpl) = template_map.get(config.device_type.as_str()) {
-            if let Some(secs) = tpl.default_offline_timeout_secs {
-                return secs;
-            }
-        }
-        global_offline_timeout
+        let template = template_map.get(config.device_type.as_str()).copied();
+        heartbeat.offline_timeout_for(config, template)
     };
 
     struct DeviceWithStatus {
@@ -115,6 +153,10 @@ pub async fn list_devices_handler(
 
     let mut devices_with_status = Vec::new();
     for config in configs {
+        if !scope.allows(&config.tenant_id) {
+            continue;
+        }
+
         // Look up status from batch-fetched map (no per-device lock)
         let device_status = all_statuses
             .get(&config.device_id)
@@ -235,6 +277,8 @@ pub async fn list_devices_handler(
             plugin_name,
             adapter_id: config.adapter_id.clone(),
             offline_timeout_secs: config.offline_timeout_secs,
+            tags: config.tags.clone(),
+            location: config.location.clone(),
             effective_offline_timeout_secs: effective_timeout(&config),
             metric_count,
             command_count,
@@ -257,6 +301,7 @@ pub async fn list_devices_handler(
 /// Uses new DeviceService with real device status from event tracking
 pub async fn get_device_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     // Use new DeviceService
@@ -265,7 +310,9 @@ pub async fn get_device_handler(
         .service
         .get_device_with_template(&device_id)
         .await
-        .map_err(|_| ErrorResponse::not_found("Device"))?;
+        .ok()
+        .filter(|(config, _)| scope.allows(&config.tenant_id))
+        .ok_or_else(|| ErrorResponse::not_found("Device"))?;
 
     let metric_count = template.metrics.len();
     let command_count = template.commands.len();
@@ -286,11 +333,12 @@ pub async fn get_device_handler(
     let device_status = state.devices.service.get_device_status(&device_id).await;
 
     // Resolve per-device effective offline timeout
-    // (device override > template default > global)
-    let effective_timeout = config
-        .offline_timeout_secs
-        .or(template.default_offline_timeout_secs)
-        .unwrap_or(state.devices.service.heartbeat_config().offline_timeout);
+    // (device override > template default > expected interval + grace > global)
+    let effective_timeout = state
+        .devices
+        .service
+        .heartbeat_config()
+        .offline_timeout_for(&config, Some(&template));
     let online = device_status.is_connected_within(effective_timeout);
 
     // Determine status string based on actual connectivity
@@ -360,6 +408,7 @@ pub async fn get_device_handler(
 /// This is the recommended endpoint for UI components that need device state.
 pub async fn get_device_current_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     // Get device config and template
@@ -368,14 +417,17 @@ pub async fn get_device_current_handler(
         .service
         .get_device_with_template(&device_id)
         .await
-        .map_err(|_| ErrorResponse::not_found("Device"))?;
+        .ok()
+        .filter(|(config, _)| scope.allows(&config.tenant_id))
+        .ok_or_else(|| ErrorResponse::not_found("Device"))?;
 
     // Get device status
     let device_status = state.devices.service.get_device_status(&device_id).await;
-    let effective_timeout = config
-        .offline_timeout_secs
-        .or(template.default_offline_timeout_secs)
-        .unwrap_or(state.devices.service.heartbeat_config().offline_timeout);
+    let effective_timeout = state
+        .devices
+        .service
+        .heartbeat_config()
+        .offline_timeout_for(&config, Some(&template));
     let online = device_status.is_connected_within(effective_timeout);
     let status = if online {
         MdlConnectionStatus::Connected
@@ -571,11 +623,16 @@ pub async fn get_device_current_handler(
 /// This is optimized for dashboard components that need data from multiple devices.
 pub async fn get_devices_current_batch_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<BatchCurrentValuesRequest>,
 ) -> HandlerResult<serde_json::Value> {
     let mut devices = std::collections::HashMap::new();
 
     for device_id in req.device_ids {
+        if check_device_scope(&state, &scope, &device_id).is_err() {
+            continue;
+        }
+
         // Unified source_id for telemetry storage queries
         let device_source_id = format!("device:{}", device_id);
 
@@ -653,8 +710,11 @@ pub async fn get_devices_current_batch_handler(
 /// Uses new DeviceService
 pub async fn delete_device_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
     state
         .devices
         .service
@@ -671,6 +731,7 @@ pub async fn delete_device_handler(
 /// Uses new DeviceService
 pub async fn add_device_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<AddDeviceRequest>,
 ) -> HandlerResult<serde_json::Value> {
     // Generate device ID if not provided: {device_type}_{random_8_chars}
@@ -687,6 +748,16 @@ pub async fn add_device_handler(
         format!("{}_{}", req.device_type, random_str)
     };
 
+    // Device IDs are global: re-registering is only allowed within the owner's scope
+    if let Some(existing) = state.devices.service.get_device(&device_id) {
+        if !scope.allows(&existing.tenant_id) {
+            return Err(ErrorResponse::conflict(format!(
+                "Device ID '{}' is already in use",
+                device_id
+            )));
+        }
+    }
+
     // Parse connection_config JSON into ConnectionConfig
     let connection_config: neomind_devices::ConnectionConfig =
         serde_json::from_value(req.connection_config)
@@ -702,6 +773,9 @@ pub async fn add_device_handler(
         adapter_id: None, // Will be set by adapter when registered
         last_seen: 0,
         offline_timeout_secs: None,
+        tags: req.tags,
+        location: req.location,
+        tenant_id: scope.owner(),
     };
 
     // Register device using new DeviceService
@@ -722,6 +796,7 @@ pub async fn add_device_handler(
 /// Only updates the fields provided in the request.
 pub async fn update_device_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
     Json(req): Json<UpdateDeviceRequest>,
 ) -> HandlerResult<serde_json::Value> {
@@ -730,6 +805,7 @@ pub async fn update_device_handler(
         .devices
         .service
         .get_device(&device_id)
+        .filter(|config| scope.allows(&config.tenant_id))
         .ok_or_else(|| ErrorResponse::not_found("Device"))?;
 
     // Validate offline_timeout_secs if provided
@@ -764,6 +840,9 @@ pub async fn update_device_handler(
         // Direct assignment: frontend always sends this field explicitly.
         // null/None = clear override (fall back to template/global), Some(n) = set.
         offline_timeout_secs: req.offline_timeout_secs,
+        tags: req.tags.unwrap_or(existing.tags),
+        location: req.location.or(existing.location),
+        tenant_id: existing.tenant_id,
     };
 
     // Update device using new DeviceService
@@ -786,12 +865,14 @@ pub async fn update_device_handler(
 /// GET /api/devices/:id/state
 pub async fn get_device_state_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     // Check device exists
     if state.devices.service.get_device(&device_id).is_none() {
         return Err(ErrorResponse::not_found("Device"));
     }
+    check_device_scope(&state, &scope, &device_id)?;
 
     // Get device status
     let device_status = state.devices.service.get_device_status(&device_id).await;
@@ -816,12 +897,14 @@ pub async fn get_device_state_handler(
 /// GET /api/devices/:id/health
 pub async fn get_device_health_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     // Check device exists
     if state.devices.service.get_device(&device_id).is_none() {
         return Err(ErrorResponse::not_found("Device"));
     }
+    check_device_scope(&state, &scope, &device_id)?;
 
     let device_status = state.devices.service.get_device_status(&device_id).await;
     let effective_timeout = state.devices.service.effective_offline_timeout(&device_id);
@@ -861,10 +944,106 @@ pub async fn get_device_health_handler(
     }))
 }
 
+/// Get fleet availability statistics (online/offline/never-seen counts,
+/// per device type breakdown and the currently offline devices).
+/// GET /api/devices/health/summary
+pub async fn get_fleet_health_summary_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+) -> HandlerResult<serde_json::Value> {
+    let summary = state
+        .devices
+        .service
+        .health_summary_where(|config| scope.allows(&config.tenant_id))
+        .await;
+    ok(json!(summary))
+}
+
+/// Get the telemetry load shedding report: ingestion rates, points shed
+/// per device and recent shedding episodes.
+/// GET /api/devices/ingest/report
+pub async fn get_ingest_report_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+) -> HandlerResult<serde_json::Value> {
+    let Some(mut report) = state.devices.service.ingest_report().await else {
+        return ok(json!({ "enabled": false }));
+    };
+    let visible = |device_id: &str| {
+        state
+            .devices
+            .service
+            .get_device(device_id)
+            .is_some_and(|config| scope.allows(&config.tenant_id))
+    };
+    report.devices.retain(|d| visible(&d.device_id));
+    report.episodes.retain(|e| visible(&e.device_id));
+    ok(json!({
+        "enabled": true,
+        "report": report,
+    }))
+}
+
+/// List the member devices a virtual device is computed from.
+/// GET /api/devices/:id/members
+pub async fn get_virtual_members_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(device_id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
+    let members = state
+        .devices
+        .service
+        .get_virtual_members(&device_id)
+        .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+    let members: Vec<serde_json::Value> = members
+        .iter()
+        .map(|m| {
+            json!({
+                "device_id": m.device_id,
+                "name": m.name,
+                "device_type": m.device_type,
+            })
+        })
+        .collect();
+
+    ok(json!({
+        "device_id": device_id,
+        "count": members.len(),
+        "members": members,
+    }))
+}
+
+/// Reconstruct a device's metric values and online status at a past moment.
+/// GET /api/devices/:id/state?at=<unix_secs>
+pub async fn get_device_state_at_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(device_id): Path<String>,
+    Query(query): Query<StateAtQuery>,
+) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
+    let at = query.at.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let snapshot = state
+        .devices
+        .service
+        .state_at(&device_id, at)
+        .await
+        .map_err(|e| match e {
+            neomind_devices::DeviceError::NotFoundStr(_) => ErrorResponse::not_found("Device"),
+            e => ErrorResponse::internal(e.to_string()),
+        })?;
+    ok(json!(snapshot))
+}
+
 /// Force device refresh (poll for current state).
 /// POST /api/devices/:id/refresh
 pub async fn refresh_device_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     // Check device exists
@@ -872,6 +1051,7 @@ pub async fn refresh_device_handler(
         .devices
         .service
         .get_device(&device_id)
+        .filter(|config| scope.allows(&config.tenant_id))
         .ok_or_else(|| ErrorResponse::not_found("Device"))?;
 
     // Try to get adapter and trigger refresh
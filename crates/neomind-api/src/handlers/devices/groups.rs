@@ -0,0 +1,235 @@
+//! Device group handlers.
+//!
+//! Groups combine static members with a tag/location selector; commands sent
+//! to a group fan out to every member whose device type supports them.
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use neomind_core::tenant::TenantScope;
+use neomind_devices::{CommandGroupResult, DeviceGroup, GroupOrdering, GroupSelector};
+
+use crate::auth::RequestTenant;
+use crate::handlers::{
+    common::{ok, HandlerResult},
+    ServerState,
+};
+use crate::models::ErrorResponse;
+
+/// Request body for creating or updating a device group.
+#[derive(Debug, Deserialize)]
+pub struct DeviceGroupRequest {
+    /// Group ID (generated from the name when omitted on create)
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Static member device IDs
+    #[serde(default)]
+    pub members: Vec<String>,
+    /// Dynamic membership rule
+    #[serde(default)]
+    pub selector: Option<GroupSelector>,
+}
+
+/// Request body for a group command.
+#[derive(Debug, Deserialize)]
+pub struct GroupCommandRequest {
+    /// Command parameters, sent unchanged to every member
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+    /// `parallel` (default) or `sequential`
+    #[serde(default)]
+    pub ordering: Option<GroupOrdering>,
+}
+
+fn group_id_from_name(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        slug
+    }
+}
+
+/// Look up a group visible in the request's tenant scope.
+///
+/// Groups of other tenants are reported as not found.
+fn scoped_group(
+    state: &ServerState,
+    scope: &TenantScope,
+    group_id: &str,
+) -> Result<DeviceGroup, ErrorResponse> {
+    state
+        .devices
+        .service
+        .registry()
+        .get_group(group_id)
+        .filter(|group| scope.allows(&group.tenant_id))
+        .ok_or_else(|| ErrorResponse::not_found(format!("Group '{}'", group_id)))
+}
+
+/// List device groups.
+pub async fn list_device_groups_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+) -> HandlerResult<serde_json::Value> {
+    let groups: Vec<DeviceGroup> = state
+        .devices
+        .service
+        .registry()
+        .list_groups()
+        .into_iter()
+        .filter(|group| scope.allows(&group.tenant_id))
+        .collect();
+    ok(json!({
+        "groups": groups,
+        "count": groups.len(),
+    }))
+}
+
+/// Create a device group.
+pub async fn create_device_group_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Json(req): Json<DeviceGroupRequest>,
+) -> HandlerResult<DeviceGroup> {
+    let registry = state.devices.service.registry();
+    let id = req.id.unwrap_or_else(|| group_id_from_name(&req.name));
+    // Group IDs are global, like device IDs
+    if registry.get_group(&id).is_some() {
+        return Err(ErrorResponse::bad_request(format!(
+            "Group '{}' already exists",
+            id
+        )));
+    }
+    let group = registry.save_group(DeviceGroup {
+        id,
+        name: req.name,
+        description: req.description,
+        members: req.members,
+        selector: req.selector,
+        tenant_id: scope.owner(),
+        ..Default::default()
+    })?;
+    ok(group)
+}
+
+/// Get a device group.
+pub async fn get_device_group_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(group_id): Path<String>,
+) -> HandlerResult<DeviceGroup> {
+    ok(scoped_group(&state, &scope, &group_id)?)
+}
+
+/// Replace a device group's name, description, members and selector.
+pub async fn update_device_group_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(group_id): Path<String>,
+    Json(req): Json<DeviceGroupRequest>,
+) -> HandlerResult<DeviceGroup> {
+    let existing = scoped_group(&state, &scope, &group_id)?;
+    let group = state.devices.service.registry().save_group(DeviceGroup {
+        id: group_id,
+        name: req.name,
+        description: req.description,
+        members: req.members,
+        selector: req.selector,
+        tenant_id: existing.tenant_id,
+        ..Default::default()
+    })?;
+    ok(group)
+}
+
+/// Delete a device group. Member devices are not affected.
+pub async fn delete_device_group_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(group_id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    scoped_group(&state, &scope, &group_id)?;
+    state
+        .devices
+        .service
+        .registry()
+        .delete_group(&group_id)
+        .map_err(|_| ErrorResponse::not_found(format!("Group '{}'", group_id)))?;
+    ok(json!({
+        "group_id": group_id,
+        "deleted": true,
+    }))
+}
+
+/// List the current members of a group with their connection status.
+pub async fn get_device_group_devices_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(group_id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    scoped_group(&state, &scope, &group_id)?;
+    let members = state
+        .devices
+        .service
+        .get_group_status(&group_id)
+        .await
+        .map_err(|_| ErrorResponse::not_found(format!("Group '{}'", group_id)))?;
+    let devices: Vec<serde_json::Value> = members
+        .into_iter()
+        .map(|(device, status)| {
+            json!({
+                "device_id": device.device_id,
+                "name": device.name,
+                "device_type": device.device_type,
+                "tags": device.tags,
+                "location": device.location,
+                "status": status.status,
+                "last_seen": status.last_seen,
+            })
+        })
+        .collect();
+    ok(json!({
+        "group_id": group_id,
+        "count": devices.len(),
+        "devices": devices,
+    }))
+}
+
+/// Send a command to every member of a group that supports it.
+///
+/// Per-device failures are reported in the result; the batch is not rolled
+/// back.
+pub async fn send_device_group_command_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path((group_id, command)): Path<(String, String)>,
+    Json(req): Json<GroupCommandRequest>,
+) -> HandlerResult<CommandGroupResult> {
+    scoped_group(&state, &scope, &group_id)?;
+    let result = state
+        .devices
+        .service
+        .send_group_command(
+            &group_id,
+            &command,
+            req.params,
+            req.ordering.unwrap_or(GroupOrdering::Parallel),
+        )
+        .await
+        .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+    ok(result)
+}
@@ -2,15 +2,19 @@
 
 use axum::{
     extract::{Path, Query, State},
-    Json,
+    Extension, Json,
 };
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use serde::Deserialize;
 use serde_json::json;
 
-use neomind_devices::{DataPoint, MetricValue};
+use neomind_core::tenant::TenantScope;
+use neomind_devices::{CommandGroup, CommandGroupResult, DataPoint, DeviceError, MetricValue};
 
+use super::crud::check_device_scope;
 use super::models::{SendCommandRequest, TimeRangeQuery};
+use crate::auth::RequestTenant;
+use crate::auth_users::SessionInfo;
 use crate::handlers::{
     common::{ok, HandlerResult},
     ServerState,
@@ -21,8 +25,11 @@ use crate::models::ErrorResponse;
 /// Uses new DeviceService
 pub async fn read_metric_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path((device_id, metric)): Path<(String, String)>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
     // Get current metrics for the device (using default 48-hour window)
     let current_values = state
         .devices
@@ -47,9 +54,12 @@ pub async fn read_metric_handler(
 /// Uses new DeviceService for querying telemetry
 pub async fn query_metric_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path((device_id, metric)): Path<(String, String)>,
     Query(query): Query<TimeRangeQuery>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
     let end = query.end.unwrap_or_else(|| chrono::Utc::now().timestamp());
     let start = query.start.unwrap_or(end - 86400); // Default 24 hours
 
@@ -89,9 +99,12 @@ pub async fn query_metric_handler(
 /// Uses time_series_storage directly (DeviceService doesn't have aggregate method yet)
 pub async fn aggregate_metric_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path((device_id, metric)): Path<(String, String)>,
     Query(query): Query<TimeRangeQuery>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
     let end = query.end.unwrap_or_else(|| chrono::Utc::now().timestamp());
     let start = query.start.unwrap_or(end - 86400); // Default 24 hours
 
@@ -121,13 +134,78 @@ pub async fn aggregate_metric_handler(
     }))
 }
 
+/// Owner of a caller's idempotency keys: its tenant, or the caller itself
+/// when it is not bound to one.
+fn idempotency_owner(scope: &TenantScope, user: Option<&SessionInfo>) -> String {
+    match (scope.tenant(), user) {
+        (Some(tenant), _) => format!("tenant:{}", tenant),
+        (None, Some(user)) => format!("user:{}", user.user_id),
+        (None, None) => "anonymous".to_string(),
+    }
+}
+
 /// Send a command to a device.
 /// Uses new DeviceService for command sending
 pub async fn send_command_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    user: Option<Extension<SessionInfo>>,
     Path((device_id, command)): Path<(String, String)>,
     Json(req): Json<SendCommandRequest>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
+    if req.schedule_at.is_some() || req.recurrence.is_some() {
+        if req.idempotency_key.is_some() {
+            return Err(ErrorResponse::bad_request(
+                "idempotency_key cannot be used with a scheduled command",
+            ));
+        }
+        let scheduled = state
+            .devices
+            .service
+            .schedule_command(
+                &device_id,
+                &command,
+                req.params,
+                req.schedule_at.map(|at| at.timestamp()),
+                req.recurrence.as_deref(),
+            )
+            .await
+            .map_err(|e| {
+                ErrorResponse::bad_request(format!("Failed to schedule command: {}", e))
+            })?;
+        return ok(json!({
+            "device_id": device_id,
+            "command": command,
+            "sent": false,
+            "scheduled": scheduled,
+        }));
+    }
+
+    if let Some(key) = req.idempotency_key.as_deref() {
+        let owner = idempotency_owner(&scope, user.as_deref());
+        let submission = state
+            .devices
+            .service
+            .send_command_idempotent(&device_id, &command, req.params, &owner, key)
+            .await
+            .map_err(|e| match e {
+                DeviceError::AlreadyExists(msg) => ErrorResponse::conflict(msg),
+                e => ErrorResponse::bad_request(e.to_string()),
+            })?;
+        submission
+            .outcome
+            .map_err(|e| ErrorResponse::bad_request(format!("Failed to send command: {}", e)))?;
+        return ok(json!({
+            "device_id": device_id,
+            "command": command,
+            "sent": true,
+            "command_id": submission.command_id,
+            "replayed": submission.replayed,
+        }));
+    }
+
     // Use DeviceService.send_command which accepts HashMap<String, serde_json::Value>
     state
         .devices
@@ -143,6 +221,72 @@ pub async fn send_command_handler(
     }))
 }
 
+/// List the commands scheduled for a device, soonest first.
+pub async fn list_scheduled_commands_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(device_id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
+    let scheduled = state
+        .devices
+        .service
+        .list_scheduled_commands(&device_id)
+        .await;
+    ok(json!({
+        "device_id": device_id,
+        "scheduled": scheduled,
+    }))
+}
+
+/// Cancel a scheduled command.
+pub async fn cancel_scheduled_command_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path((device_id, schedule_id)): Path<(String, String)>,
+) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
+    if !state
+        .devices
+        .service
+        .cancel_scheduled_command(&device_id, &schedule_id)
+        .await
+    {
+        return Err(ErrorResponse::not_found(format!(
+            "Scheduled command '{}'",
+            schedule_id
+        )));
+    }
+    ok(json!({
+        "device_id": device_id,
+        "id": schedule_id,
+        "cancelled": true,
+    }))
+}
+
+/// Send a batch of commands as one group.
+///
+/// The body is a [`CommandGroup`]: `commands` (each with an optional
+/// `rollback` inverse command), `ordering` (`parallel` | `sequential`) and
+/// `atomic`. Groups over `MAX_GROUP_COMMANDS` commands are rejected.
+/// Per-command failures are reported in the result, not as an HTTP error;
+/// check the group `status`.
+pub async fn send_command_group_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Json(group): Json<CommandGroup>,
+) -> HandlerResult<CommandGroupResult> {
+    group
+        .validate()
+        .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+    for command in &group.commands {
+        check_device_scope(&state, &scope, &command.device_id)?;
+    }
+    ok(group.execute(state.devices.service.as_ref()).await)
+}
+
 /// Convert MetricValue to JSON value.
 pub fn value_to_json(value: &MetricValue) -> serde_json::Value {
     match value {
@@ -187,8 +331,11 @@ pub struct WriteMetricRequest {
 pub async fn write_metric_handler(
     Path(device_id): Path<String>,
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<WriteMetricRequest>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
     let metric_value = json_to_metric_value(&req.value);
     let timestamp = req
         .timestamp
@@ -2,24 +2,32 @@
 //!
 //! Provides REST API for device management with MDL support.
 
+pub mod anomalies;
 pub mod auto_onboard;
 pub mod ble_provision;
+pub mod chirpstack;
 pub mod compat;
 pub mod crud;
+pub mod groups;
 pub mod mdl;
 pub mod metrics;
 pub mod models;
+pub mod network_discovery;
 pub mod telemetry;
 pub mod telemetry_stats;
 pub mod types;
 pub mod webhook;
 
 // Re-export all handlers for use in routing
+pub use anomalies::*;
 pub use auto_onboard::*;
 pub use ble_provision::*;
+pub use chirpstack::*;
 pub use crud::*;
+pub use groups::*;
 pub use mdl::*;
 pub use metrics::*;
+pub use network_discovery::*;
 pub use telemetry::*;
 pub use telemetry_stats::*;
 pub use types::*;
@@ -43,6 +43,12 @@ pub struct DeviceDto {
     /// (device override → template default → global). Read-only — included
     /// so the frontend can display "Default: Ns" without a separate API call.
     pub effective_offline_timeout_secs: u64,
+    /// Tags used by dynamic device groups
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Location path used by dynamic device groups
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
     /// Metric and command counts (from template)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metric_count: Option<usize>,
@@ -117,6 +123,13 @@ pub struct TimeRangeQuery {
     pub limit: Option<usize>,
 }
 
+/// Query parameters for reconstructing past device state.
+#[derive(Debug, Deserialize)]
+pub struct StateAtQuery {
+    /// Unix timestamp (seconds); defaults to now
+    pub at: Option<i64>,
+}
+
 /// Request to add a new device.
 #[derive(Debug, Deserialize)]
 pub struct AddDeviceRequest {
@@ -131,6 +144,12 @@ pub struct AddDeviceRequest {
     pub adapter_type: String,
     /// Connection configuration (protocol-specific)
     pub connection_config: serde_json::Value,
+    /// Tags used by dynamic device groups
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Location path (e.g. "hq/floor-2") used by dynamic device groups
+    #[serde(default)]
+    pub location: Option<String>,
 }
 
 /// Request to update an existing device.
@@ -154,6 +173,12 @@ pub struct UpdateDeviceRequest {
     /// `HeartbeatConfig::offline_timeout`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offline_timeout_secs: Option<u64>,
+    /// Replaces the device's tags when provided
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Location path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
 }
 
 /// Pagination query parameters
@@ -207,6 +232,17 @@ pub struct SendCommandRequest {
     /// Command parameters
     #[serde(default)]
     pub params: HashMap<String, serde_json::Value>,
+    /// Client-chosen key identifying this submission. Retries carrying the
+    /// same key get the first submission's outcome instead of re-sending.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Run the command at this time instead of now (RFC 3339)
+    #[serde(default)]
+    pub schedule_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Run the command on this cron schedule (six fields with seconds,
+    /// UTC), starting at `schedule_at` if given
+    #[serde(default)]
+    pub recurrence: Option<String>,
 }
 
 /// Request body for MDL generation from sample data.
@@ -0,0 +1,111 @@
+//! Network discovery queue handlers.
+//!
+//! Endpoints found by the background mDNS/SSDP scan wait here until an
+//! operator approves them (registering a device) or ignores them.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::automation::discovery::{
+    types::DiscoveryError, DiscoveredEndpoint, DiscoveryStatus, EndpointApproval,
+};
+use crate::handlers::{
+    common::{ok, HandlerResult},
+    ServerState,
+};
+use crate::models::ErrorResponse;
+
+/// Query for listing discovered endpoints.
+#[derive(Debug, Deserialize)]
+pub struct DiscoveredEndpointsQuery {
+    /// Filter by status: pending, approved or ignored
+    pub status: Option<DiscoveryStatus>,
+}
+
+fn discovery_error(e: DiscoveryError) -> ErrorResponse {
+    match e {
+        DiscoveryError::NotFound(msg) => ErrorResponse::not_found(msg),
+        DiscoveryError::Validation(msg) => ErrorResponse::bad_request(msg),
+        other => ErrorResponse::internal(other.to_string()),
+    }
+}
+
+/// List network-discovered endpoints (all statuses unless filtered).
+pub async fn list_discovered_endpoints_handler(
+    State(state): State<ServerState>,
+    Query(query): Query<DiscoveredEndpointsQuery>,
+) -> HandlerResult<serde_json::Value> {
+    let endpoints = state.devices.network_discovery.list(query.status).await;
+    ok(json!({
+        "count": endpoints.len(),
+        "endpoints": endpoints,
+    }))
+}
+
+/// Get one discovered endpoint.
+pub async fn get_discovered_endpoint_handler(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> HandlerResult<DiscoveredEndpoint> {
+    let endpoint = state
+        .devices
+        .network_discovery
+        .get(&id)
+        .await
+        .ok_or_else(|| ErrorResponse::not_found(format!("Discovered endpoint '{}'", id)))?;
+    ok(endpoint)
+}
+
+/// Run an mDNS + SSDP scan now instead of waiting for the next interval.
+pub async fn scan_network_handler(
+    State(state): State<ServerState>,
+) -> HandlerResult<serde_json::Value> {
+    let discovery = &state.devices.network_discovery;
+    let queued = discovery.scan().await;
+    let pending = discovery.list(Some(DiscoveryStatus::Pending)).await.len();
+    ok(json!({
+        "queued": queued,
+        "pending": pending,
+    }))
+}
+
+/// Approve an endpoint and register it as a device.
+///
+/// The body may map the endpoint to a device type other than the suggested
+/// one and set the name, ID, adapter and connection config.
+pub async fn approve_discovered_endpoint_handler(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Json(approval): Json<Option<EndpointApproval>>,
+) -> HandlerResult<serde_json::Value> {
+    let device = state
+        .devices
+        .network_discovery
+        .approve(&id, approval.unwrap_or_default())
+        .await
+        .map_err(discovery_error)?;
+    ok(json!({
+        "endpoint_id": id,
+        "device_id": device.device_id,
+        "device_type": device.device_type,
+        "approved": true,
+    }))
+}
+
+/// Ignore an endpoint; later scans won't queue it again.
+pub async fn ignore_discovered_endpoint_handler(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> HandlerResult<DiscoveredEndpoint> {
+    let endpoint = state
+        .devices
+        .network_discovery
+        .ignore(&id)
+        .await
+        .map_err(discovery_error)?;
+    ok(endpoint)
+}
@@ -6,6 +6,8 @@ use serde_json::json;
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
+use super::crud::check_device_scope;
+use crate::auth::RequestTenant;
 use crate::handlers::{
     common::{ok, HandlerResult},
     ServerState,
@@ -42,11 +44,14 @@ fn get_transform_namespaces() -> &'static [&'static str; 5] {
 /// - aggregate: optional aggregation type (avg, min, max, sum, last)
 pub async fn get_device_telemetry_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
     Query(params): Query<HashMap<String, String>>,
 ) -> HandlerResult<serde_json::Value> {
     use crate::validator::{validate_numeric_range, validate_string_length};
 
+    check_device_scope(&state, &scope, &device_id)?;
+
     // Validate device_id if provided
     validate_string_length(&device_id, "device_id", 1, 100)?;
 
@@ -587,9 +592,12 @@ pub async fn get_device_telemetry_handler(
 /// Returns summary statistics for all device metrics over a time range.
 pub async fn get_device_telemetry_summary_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
     Query(params): Query<HashMap<String, String>>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
     // Default to last 24 hours (timestamps in seconds)
     let end = chrono::Utc::now().timestamp();
     let start = params
@@ -888,9 +896,12 @@ fn metric_value_to_json(value: &neomind_devices::MetricValue) -> serde_json::Val
 /// - limit: maximum number of commands to return (default: 50)
 pub async fn get_device_command_history_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
     Query(params): Query<HashMap<String, String>>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
     let limit = params
         .get("limit")
         .and_then(|s| s.parse::<usize>().ok())
@@ -917,6 +928,7 @@ pub async fn get_device_command_history_handler(
                     neomind_devices::CommandStatus::Success => "success",
                     neomind_devices::CommandStatus::Failed => "failed",
                     neomind_devices::CommandStatus::Timeout => "timeout",
+                    neomind_devices::CommandStatus::Expired => "expired",
                 },
                 "result": cmd.result,
                 "error": cmd.error,
@@ -941,8 +953,11 @@ pub async fn get_device_command_history_handler(
 /// exist for a device, bypassing the device service and template logic.
 pub async fn list_device_metrics_debug_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
     // Unified source_id for storage queries
     let device_source_id = format!("device:{}", device_id);
 
@@ -998,9 +1013,12 @@ pub async fn list_device_metrics_debug_handler(
 /// the actual timestamps stored in the database, helping identify data gaps.
 pub async fn analyze_metric_timestamps_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(device_id): Path<String>,
     Query(params): Query<HashMap<String, String>>,
 ) -> HandlerResult<serde_json::Value> {
+    check_device_scope(&state, &scope, &device_id)?;
+
     // Unified source_id for storage queries
     let device_source_id = format!("device:{}", device_id);
 
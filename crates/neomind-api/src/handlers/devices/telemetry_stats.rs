@@ -16,12 +16,16 @@ use crate::handlers::{
 /// - Average read/write latency
 /// - Cache hit rates
 /// - Query performance by metric type
+/// - Write buffer flushes and the write-ahead journal
+/// - Hot metric cache hits and size (`null` when disabled)
 pub async fn get_telemetry_stats_handler(
     State(state): State<ServerState>,
 ) -> HandlerResult<serde_json::Value> {
     // Get telemetry storage stats
     let telemetry = state.devices.telemetry.clone();
     let telemetry_stats = telemetry.get_stats().await;
+    let flush_stats = telemetry.flush_stats();
+    let hot_cache = telemetry.hot_cache_stats();
 
     let avg_read_ms = telemetry_stats.avg_read_us() / 1000.0;
     let avg_write_ms = telemetry_stats.avg_write_us() / 1000.0;
@@ -47,6 +51,11 @@ pub async fn get_telemetry_stats_handler(
             "read_count": telemetry_stats.read_count,
             "write_count": telemetry_stats.write_count,
         },
+        "flush": {
+            "avg_flush_ms": flush_stats.avg_flush_ms(),
+            "stats": flush_stats,
+        },
+        "hot_cache": hot_cache,
         "health": {
             "status": if avg_read_ms < 200.0 { "healthy" } else { "degraded" },
             "recommendations": get_performance_recommendations(avg_read_ms, cache_hit_rate, telemetry_stats.read_count)
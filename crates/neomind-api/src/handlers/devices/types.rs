@@ -117,7 +117,7 @@ pub async fn get_device_type_handler(
     };
 
     // Return in new simplified format (direct metrics/commands arrays).
-    // Include store_raw + default_offline_timeout_secs so exports round-trip
+    // Include store_raw, offline/interval settings and binary_codec so exports round-trip
     // (GET is the path the UI export uses; omitting them drops these settings).
     ok(json!({
         "device_type": template.device_type,
@@ -129,7 +129,9 @@ pub async fn get_device_type_handler(
         "uplink_samples": template.uplink_samples,
         "commands": template.commands,
         "default_offline_timeout_secs": template.default_offline_timeout_secs,
+        "expected_interval_secs": template.expected_interval_secs,
         "store_raw": template.store_raw,
+        "binary_codec": template.binary_codec,
         "metric_count": template.metrics.len(),
         "command_count": template.commands.len(),
     }))
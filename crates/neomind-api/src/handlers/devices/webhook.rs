@@ -71,7 +71,7 @@ fn extract_token(
 /// Distinct from `extract_token` — that reads the per-device `Authorization: Bearer`
 /// secret. The adapter-level key is a global pre-shared secret for the whole
 /// adapter, useful when the platform is exposed without per-device provisioning.
-fn extract_api_key(headers: &HeaderMap) -> Option<String> {
+pub(crate) fn extract_api_key(headers: &HeaderMap) -> Option<String> {
     headers
         .get("x-api-key")
         .and_then(|v| v.to_str().ok())
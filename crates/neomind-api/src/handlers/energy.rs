@@ -0,0 +1,157 @@
+//! Energy monitoring handlers.
+//!
+//! `GET /api/energy` reports consumption and cost for the configured meters;
+//! `/api/energy/config` reads and replaces the meters and tariff.
+
+use std::collections::HashSet;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use neomind_storage::{EnergyConfig, SettingsStore};
+use serde::Deserialize;
+
+use super::common::{ok, HandlerResult};
+use super::devices::crud::{check_device_scope, device_in_scope};
+use crate::auth::RequestTenant;
+use crate::automation::energy::{generate_report, EnergyBucket, EnergyReport};
+use crate::models::error::ErrorResponse;
+use crate::server::ServerState;
+
+const SETTINGS_DB_PATH: &str = "data/settings.redb";
+
+fn settings_store() -> Result<std::sync::Arc<SettingsStore>, ErrorResponse> {
+    SettingsStore::open(SETTINGS_DB_PATH)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to open settings store: {}", e)))
+}
+
+/// Query for an energy report.
+#[derive(Debug, Deserialize)]
+pub struct EnergyReportQuery {
+    /// Unix timestamp, defaults to 24 hours before `end`
+    pub start: Option<i64>,
+    /// Unix timestamp, defaults to now
+    pub end: Option<i64>,
+    #[serde(default)]
+    pub bucket: EnergyBucket,
+    /// Only this device
+    pub device_id: Option<String>,
+    /// Only members of this device group
+    pub group_id: Option<String>,
+}
+
+/// `GET /api/energy` — consumption and cost per device, time bucket and
+/// tariff period.
+///
+/// Only devices in the request's tenant scope are reported.
+pub async fn get_energy_report_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Query(query): Query<EnergyReportQuery>,
+) -> HandlerResult<EnergyReport> {
+    let end = query.end.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let start = query.start.unwrap_or(end - 86_400);
+    if start >= end {
+        return Err(ErrorResponse::bad_request("start must be before end"));
+    }
+
+    let mut device_ids: Option<HashSet<String>> = None;
+    if let Some(group_id) = &query.group_id {
+        let visible = state
+            .devices
+            .service
+            .registry()
+            .get_group(group_id)
+            .is_some_and(|group| scope.allows(&group.tenant_id));
+        if !visible {
+            return Err(ErrorResponse::not_found(format!("Group '{}'", group_id)));
+        }
+        let members = state
+            .devices
+            .service
+            .get_group_devices(group_id)
+            .map_err(|e| ErrorResponse::not_found(e.to_string()))?;
+        device_ids = Some(members.into_iter().map(|d| d.device_id).collect());
+    }
+    if let Some(device_id) = &query.device_id {
+        check_device_scope(&state, &scope, device_id)?;
+        device_ids = Some(match device_ids {
+            Some(ids) => ids.into_iter().filter(|id| id == device_id).collect(),
+            None => HashSet::from([device_id.clone()]),
+        });
+    }
+
+    let store = settings_store()?;
+    let config = store.get_energy_config();
+    // Without a device or group, a scoped request sees the meters of its
+    // own tenant's devices
+    let device_ids: HashSet<String> = match device_ids {
+        Some(ids) => ids,
+        None => config.meters.iter().map(|m| m.device_id.clone()).collect(),
+    }
+    .into_iter()
+    .filter(|id| device_in_scope(&state, &scope, id))
+    .collect();
+
+    let tz = store
+        .get_global_timezone()
+        .parse::<chrono_tz::Tz>()
+        .unwrap_or(chrono_tz::UTC);
+    let report = generate_report(
+        &state.devices.service,
+        &state.devices.telemetry,
+        &config,
+        tz,
+        start,
+        end,
+        query.bucket,
+        Some(&device_ids),
+    )
+    .await
+    .map_err(|e| ErrorResponse::internal(format!("Failed to read telemetry: {}", e)))?;
+    ok(report)
+}
+
+/// `GET /api/energy/config` — registered meters and tariff.
+pub async fn get_energy_config_handler(
+    State(_state): State<ServerState>,
+) -> HandlerResult<EnergyConfig> {
+    ok(settings_store()?.get_energy_config())
+}
+
+/// `PUT /api/energy/config` — replace meters and tariff.
+pub async fn update_energy_config_handler(
+    State(_state): State<ServerState>,
+    Json(config): Json<EnergyConfig>,
+) -> HandlerResult<EnergyConfig> {
+    if let Some(meter) = config.meters.iter().find(|m| m.scale().is_none()) {
+        return Err(ErrorResponse::bad_request(format!(
+            "Meter {}/{}: unit '{}' does not match kind (power: W or kW, energy: Wh or kWh)",
+            meter.device_id, meter.metric, meter.unit
+        )));
+    }
+    if config.tariff.default_rate < 0.0 || config.tariff.periods.iter().any(|p| p.rate < 0.0) {
+        return Err(ErrorResponse::bad_request(
+            "Tariff rates must not be negative",
+        ));
+    }
+    if config
+        .tariff
+        .periods
+        .iter()
+        .any(|p| p.start_hour > 23 || p.end_hour > 24 || p.weekdays.iter().any(|d| *d > 6))
+    {
+        return Err(ErrorResponse::bad_request(
+            "Tariff hours must be 0-24 and weekdays 0 (Monday) to 6 (Sunday)",
+        ));
+    }
+
+    settings_store()?
+        .save_energy_config(&config)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to save energy config: {}", e)))?;
+    tracing::info!(
+        meters = config.meters.len(),
+        periods = config.tariff.periods.len(),
+        "Energy configuration updated"
+    );
+    ok(config)
+}
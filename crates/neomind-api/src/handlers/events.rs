@@ -7,7 +7,7 @@
 
 use axum::{
     extract::{Query, State, WebSocketUpgrade},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{sse::Event, Sse},
     Json,
 };
@@ -17,11 +17,14 @@ use serde::Deserialize;
 use serde_json::Value;
 use std::time::Duration;
 
+use crate::auth::resolve_tenant_scope;
 use crate::handlers::common::{ok, HandlerResult};
+use crate::handlers::devices::crud::device_in_scope;
 use crate::handlers::ServerState;
 use crate::models::error::ErrorResponse;
 use neomind_core::event::EventMetadata;
 use neomind_core::eventbus::{EventBus, EventBusReceiver, FilteredReceiver};
+use neomind_core::tenant::{TenantId, TenantScope};
 use neomind_core::NeoMindEvent;
 
 /// Batch configuration for WebSocket event streaming.
@@ -309,12 +312,18 @@ fn extract_event_data(event: &NeoMindEvent) -> Value {
 /// Event stream query parameters.
 #[derive(Debug, Deserialize)]
 pub struct EventStreamParams {
-    /// Filter by event type (can be specified multiple times)
+    /// Filter by event type (comma-separated, e.g. `DeviceMetric,AlertCreated`)
     #[serde(default)]
-    pub event_type: Vec<String>,
+    pub event_type: Option<String>,
     /// Filter by category: device, rule, llm, alert, tool
     #[serde(default)]
     pub category: Option<String>,
+    /// Only device events for these devices (comma-separated)
+    #[serde(default)]
+    pub device_id: Option<String>,
+    /// Only alert/message events with these severities (comma-separated)
+    #[serde(default)]
+    pub severity: Option<String>,
     /// Last event ID to resume from
     #[serde(default)]
     pub last_event_id: Option<String>,
@@ -326,6 +335,75 @@ pub struct EventStreamParams {
     pub api_key: Option<String>,
 }
 
+/// Per-connection event filter built from [`EventStreamParams`].
+///
+/// Each dimension is an OR over its values and the dimensions are ANDed.
+/// Filtering by `device_id` or `severity` drops events that carry no such
+/// field.
+#[derive(Debug, Default)]
+struct EventStreamFilter {
+    event_types: Vec<String>,
+    device_ids: Vec<String>,
+    severities: Vec<String>,
+}
+
+fn split_list(value: &Option<String>) -> Vec<String> {
+    value
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+impl EventStreamFilter {
+    fn from_params(params: &EventStreamParams) -> Self {
+        Self {
+            event_types: split_list(&params.event_type),
+            device_ids: split_list(&params.device_id),
+            severities: split_list(&params.severity)
+                .into_iter()
+                .map(|s| s.to_lowercase())
+                .collect(),
+        }
+    }
+
+    fn matches(&self, event: &NeoMindEvent) -> bool {
+        if !self.event_types.is_empty() && !self.event_types.iter().any(|t| t == event.type_name())
+        {
+            return false;
+        }
+        if !self.device_ids.is_empty()
+            && !event
+                .device_id()
+                .is_some_and(|id| self.device_ids.iter().any(|d| d == id))
+        {
+            return false;
+        }
+        if !self.severities.is_empty()
+            && !event
+                .severity()
+                .is_some_and(|sev| self.severities.contains(&sev.to_lowercase()))
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Whether `event` may be streamed to a client in `scope`.
+///
+/// Tenant-bound clients only see events about their own devices; events that
+/// name no device (rules, agents, LLM, extensions) are instance-wide.
+fn event_in_scope(state: &ServerState, scope: &TenantScope, event: &NeoMindEvent) -> bool {
+    *scope == TenantScope::All
+        || event
+            .device_id()
+            .is_some_and(|device_id| device_in_scope(state, scope, device_id))
+}
+
 /// POST /api/events
 ///
 /// Publish a custom event to the event bus.
@@ -395,31 +473,51 @@ pub async fn publish_event_handler(
 
 /// SSE endpoint for streaming events.
 ///
+/// GET /api/events/stream
+///
 /// Streams real-time events from the event bus using Server-Sent Events.
-/// Clients can filter by event type or category.
-/// Supports JWT token (`?token=xxx`) or API key (`?api_key=xxx`) authentication.
+/// Clients can filter by category, event type, device id and severity.
+/// Supports JWT token (`?token=xxx`) or API key (`?api_key=xxx`) for
+/// browsers' `EventSource`, and `Authorization: Bearer` / `X-API-Key`
+/// headers for other clients.
 pub async fn event_stream_handler(
     State(state): State<ServerState>,
+    headers: HeaderMap,
     Query(params): Query<EventStreamParams>,
 ) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, StatusCode> {
     // Validate authentication: JWT token or API key
-    let authenticated = if let Some(token) = params.token.as_ref() {
-        state.auth.user_state.validate_token(token).is_ok()
-    } else if let Some(api_key) = params.api_key.as_ref() {
-        state.auth.api_key_state.validate_key(api_key)
-    } else {
-        false
+    let header_token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let header_api_key = headers.get("x-api-key").and_then(|v| v.to_str().ok());
+    // Tenant the credential is bound to, if any
+    let key_tenant = |key: &str| {
+        state
+            .auth
+            .api_key_state
+            .validate_key_info(key)
+            .map(|info| info.tenant_id)
+            .ok_or(StatusCode::UNAUTHORIZED)
     };
-
-    if !authenticated {
+    let bound: Option<TenantId> = if let Some(token) = params.token.as_deref().or(header_token) {
+        match state.auth.user_state.validate_token(token) {
+            Ok(session) => session.tenant_id,
+            Err(_) => key_tenant(token)?,
+        }
+    } else if let Some(api_key) = params.api_key.as_deref().or(header_api_key) {
+        key_tenant(api_key)?
+    } else {
         return Err(StatusCode::UNAUTHORIZED);
-    }
+    };
+    let scope = resolve_tenant_scope(bound, &headers).map_err(|e| e.status)?;
 
     // Get the event bus from the server state
     let event_bus = state.core.event_bus.as_ref().ok_or(StatusCode::NOT_FOUND)?;
 
     // Create a receiver for events
     let rx = create_filtered_receiver(event_bus, &params.category);
+    let filter = EventStreamFilter::from_params(&params);
 
     // Create the SSE stream
     let stream = async_stream::stream! {
@@ -427,12 +525,8 @@ pub async fn event_stream_handler(
         let mut _counter: u64 = 0;  // Event counter (reserved for future metrics)
 
         while let Some((event, metadata)) = rx.recv().await {
-            // Apply event type filter if specified
-            if !params.event_type.is_empty() {
-                let event_type = event.type_name().to_string();
-                if !params.event_type.contains(&event_type) {
-                    continue;
-                }
+            if !filter.matches(&event) || !event_in_scope(&state, &scope, &event) {
+                continue;
             }
 
             _counter += 1;
@@ -520,6 +614,7 @@ pub async fn event_websocket_handler(
         use axum::extract::ws::Message;
 
         let mut rx = create_filtered_receiver(&event_bus, &params.category);
+        let filter = EventStreamFilter::from_params(&params);
         let auth_user_state = state.auth.user_state.clone();
         let auth_api_key_state = state.auth.api_key_state.clone();
 
@@ -527,9 +622,12 @@ pub async fn event_websocket_handler(
         let pre_authenticated = params
             .api_key
             .as_ref()
-            .is_some_and(|key| auth_api_key_state.validate_key(key));
+            .and_then(|key| auth_api_key_state.validate_key_info(key));
+        // Tenant the user or API key is bound to; unbound clients see every tenant
+        let mut bound: Option<TenantId> = None;
 
-        let authenticated = if pre_authenticated {
+        let authenticated = if let Some(key_info) = pre_authenticated {
+            bound = key_info.tenant_id;
             let _ = socket.send(Message::Text(
                 serde_json::json!({"type": "Authenticated", "message": "Authentication successful"}).to_string()
             )).await;
@@ -547,7 +645,8 @@ pub async fn event_websocket_handler(
                                 // Try JWT token
                                 if let Some(token) = data["token"].as_str() {
                                     match auth_user_state.validate_token(token) {
-                                        Ok(_) => {
+                                        Ok(session) => {
+                                            bound = session.tenant_id;
                                             tracing::info!("WebSocket event stream authenticated");
                                             let _ = socket.send(Message::Text(
                                                 serde_json::json!({"type": "Authenticated", "message": "Authentication successful"}).to_string()
@@ -567,8 +666,9 @@ pub async fn event_websocket_handler(
                                 }
                                 // Try API key via Auth message
                                 if let Some(api_key) = data["api_key"].as_str() {
-                                    if auth_api_key_state.validate_key(api_key) {
+                                    if let Some(key_info) = auth_api_key_state.validate_key_info(api_key) {
                                         tracing::info!("WebSocket event stream authenticated via API key");
+                                        bound = key_info.tenant_id;
                                         let _ = socket.send(Message::Text(
                                             serde_json::json!({"type": "Authenticated", "message": "Authentication successful"}).to_string()
                                         )).await;
@@ -612,6 +712,7 @@ pub async fn event_websocket_handler(
             let _ = socket.close().await;
             return;
         }
+        let scope = bound.map(TenantScope::Tenant).unwrap_or_default();
 
         // Send events to the authenticated WebSocket client
         // Performance optimization: Batch events to reduce network overhead
@@ -679,12 +780,8 @@ pub async fn event_websocket_handler(
                 recv_result = rx.recv() => {
                     match recv_result {
                         Some((event, metadata)) => {
-                            // Apply event type filter
-                            if !params.event_type.is_empty() {
-                                let event_type = event.type_name().to_string();
-                                if !params.event_type.contains(&event_type) {
-                                    continue;
-                                }
+                            if !filter.matches(&event) || !event_in_scope(&state, &scope, &event) {
+                                continue;
                             }
 
                             let event_type = event.type_name();
@@ -769,3 +866,73 @@ pub async fn event_websocket_handler(
         let _ = socket.close().await;
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(
+        event_type: Option<&str>,
+        device_id: Option<&str>,
+        severity: Option<&str>,
+    ) -> EventStreamParams {
+        EventStreamParams {
+            event_type: event_type.map(String::from),
+            category: None,
+            device_id: device_id.map(String::from),
+            severity: severity.map(String::from),
+            last_event_id: None,
+            token: None,
+            api_key: None,
+        }
+    }
+
+    fn metric(device_id: &str) -> NeoMindEvent {
+        NeoMindEvent::DeviceMetric {
+            device_id: device_id.to_string(),
+            metric: "temp".to_string(),
+            value: neomind_core::MetricValue::Float(21.0),
+            timestamp: 0,
+            quality: None,
+            is_virtual: None,
+        }
+    }
+
+    fn alert(severity: &str) -> NeoMindEvent {
+        NeoMindEvent::AlertCreated {
+            alert_id: "a1".to_string(),
+            title: "t".to_string(),
+            severity: severity.to_string(),
+            message: "m".to_string(),
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_filter_by_event_type_and_device() {
+        let filter = EventStreamFilter::from_params(&params(
+            Some("DeviceMetric, DeviceOnline"),
+            Some("s1,s2"),
+            None,
+        ));
+        assert!(filter.matches(&metric("s1")));
+        assert!(!filter.matches(&metric("s3")));
+        assert!(!filter.matches(&alert("critical")));
+    }
+
+    #[test]
+    fn test_filter_by_severity_is_case_insensitive() {
+        let filter = EventStreamFilter::from_params(&params(None, None, Some("Critical,warning")));
+        assert!(filter.matches(&alert("critical")));
+        assert!(filter.matches(&alert("WARNING")));
+        assert!(!filter.matches(&alert("info")));
+        assert!(!filter.matches(&metric("s1")));
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = EventStreamFilter::from_params(&params(None, None, None));
+        assert!(filter.matches(&metric("s1")));
+        assert!(filter.matches(&alert("info")));
+    }
+}
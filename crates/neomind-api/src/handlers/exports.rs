@@ -0,0 +1,151 @@
+//! Telemetry export job handlers.
+//!
+//! POST   /api/exports              - Start an export job
+//! GET    /api/exports              - List export jobs
+//! GET    /api/exports/:id          - Job status and progress
+//! GET    /api/exports/:id/download - Download a completed export
+//! DELETE /api/exports/:id          - Delete a job and its file
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use tokio::io::AsyncReadExt;
+
+use neomind_core::tenant::TenantScope;
+
+use super::common::{ok, HandlerResult};
+use super::devices::crud::check_device_scope;
+use crate::auth::RequestTenant;
+use crate::exports::{ExportJob, ExportRequest, ExportStatus};
+use crate::models::error::ErrorResponse;
+use crate::server::ServerState;
+
+/// Bytes read from the export file per response chunk.
+const DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Look up an export job visible in the request's tenant scope.
+async fn scoped_job(
+    state: &ServerState,
+    scope: &TenantScope,
+    id: &str,
+) -> Result<ExportJob, ErrorResponse> {
+    state
+        .exports
+        .get(id)
+        .await
+        .filter(|job| scope.allows(&job.tenant_id))
+        .ok_or_else(|| ErrorResponse::not_found(format!("Export job not found: {}", id)))
+}
+
+/// `POST /api/exports` — start an export job; poll `GET /api/exports/:id`.
+/// Answers 429 while the maximum number of jobs is already running.
+pub async fn create_export_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Json(request): Json<ExportRequest>,
+) -> HandlerResult<ExportJob> {
+    request.validate().map_err(ErrorResponse::bad_request)?;
+    for device_id in &request.device_ids {
+        check_device_scope(&state, &scope, device_id)?;
+    }
+    let job = state
+        .exports
+        .start(request, scope.owner())
+        .await
+        .map_err(|e| ErrorResponse::new("TOO_MANY_EXPORTS", e, StatusCode::TOO_MANY_REQUESTS))?;
+    ok(job)
+}
+
+/// `GET /api/exports` — all export jobs, newest first.
+pub async fn list_exports_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+) -> HandlerResult<serde_json::Value> {
+    let jobs: Vec<ExportJob> = state
+        .exports
+        .list()
+        .await
+        .into_iter()
+        .filter(|job| scope.allows(&job.tenant_id))
+        .collect();
+    ok(json!({
+        "count": jobs.len(),
+        "jobs": jobs,
+    }))
+}
+
+/// `GET /api/exports/:id` — job status and rows written so far.
+pub async fn get_export_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<ExportJob> {
+    ok(scoped_job(&state, &scope, &id).await?)
+}
+
+/// `GET /api/exports/:id/download` — stream the export file.
+pub async fn download_export_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> Result<Response, ErrorResponse> {
+    let job = scoped_job(&state, &scope, &id).await?;
+    if job.status != ExportStatus::Completed {
+        return Err(ErrorResponse::bad_request(format!(
+            "Export job {} is not completed",
+            id
+        )));
+    }
+
+    let file = tokio::fs::File::open(state.exports.file_path(&job))
+        .await
+        .map_err(|e| ErrorResponse::not_found(format!("Export file not found: {}", e)))?;
+    let stream = futures::stream::try_unfold(file, |mut file| async move {
+        let mut buf = vec![0u8; DOWNLOAD_CHUNK_SIZE];
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            return Ok::<_, std::io::Error>(None);
+        }
+        buf.truncate(n);
+        Ok(Some((buf, file)))
+    });
+
+    Ok((
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                job.request.format.content_type().to_string(),
+            ),
+            (header::CONTENT_LENGTH, job.size_bytes.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", job.file_name()),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// `DELETE /api/exports/:id` — delete a finished job and its file.
+pub async fn delete_export_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    scoped_job(&state, &scope, &id).await?;
+    state.exports.delete(&id).await.map_err(|e| {
+        if e.contains("not found") {
+            ErrorResponse::not_found(e)
+        } else {
+            ErrorResponse::bad_request(e)
+        }
+    })?;
+    ok(json!({ "deleted": id }))
+}
@@ -26,8 +26,11 @@ use crate::server::types::MAX_EXTENSION_DOWNLOAD_SIZE;
 use crate::server::ServerState;
 use futures::StreamExt;
 use neomind_core::datasource::DataSourceId;
-use neomind_core::extension::{MetricDataType, ParameterDefinition};
-use neomind_storage::{ExtensionRecord, ExtensionStore};
+use neomind_core::extension::signing;
+use neomind_core::extension::{
+    MetricDataType, ParameterDefinition, PermissionGrant, SignaturePolicy, SignatureStatus,
+};
+use neomind_storage::{ExtensionRecord, ExtensionStore, SettingsStore};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 /// Validate an extension ID to prevent path traversal in filesystem operations.
@@ -48,6 +51,21 @@ fn validate_extension_id(id: &str) -> Result<(), ErrorResponse> {
     Ok(())
 }
 
+/// Trusted publishers and whether unsigned packages are accepted.
+fn signature_policy() -> Result<SignaturePolicy, ErrorResponse> {
+    SettingsStore::open("data/settings.redb")
+        .map(|store| store.get_signature_policy())
+        .map_err(|e| ErrorResponse::internal(format!("Failed to open settings store: {}", e)))
+}
+
+/// Pin the key a just-installed package was signed with, so upgrades of
+/// the extension must be signed with it too.
+fn pin_signer(target_dir: &std::path::Path, extension_id: &str, signature: &SignatureStatus) {
+    if let Err(e) = signing::record_signer(&target_dir.join(extension_id), signature) {
+        tracing::error!(extension_id, error = %e, "Failed to pin extension signer");
+    }
+}
+
 /// Extension DTO for API responses.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtensionDto {
@@ -1139,10 +1157,12 @@ pub struct SetToolEnabledRequest {
 }
 
 /// Rebuild the ToolRegistry disabled set from the on-disk state of all
-/// extensions and push it live. Called from both PATCH handlers below so the
-/// LLM picks up the change on the next tool-calling round without a server
-/// restart. Cheap: O(n_extensions × n_commands) string formatting.
+/// extensions and push it live. Called from the per-command PATCH handler so
+/// the LLM picks up the change on the next tool-calling round without a
+/// server restart. Cheap: O(n_extensions × n_commands) string formatting.
 async fn refresh_tool_registry_disabled(state: &ServerState) {
+    use neomind_agent::toolkit::extension_tools::extension_tool_name;
+
     let Some(registry) = state.agents.session_manager.get_tool_registry().await else {
         return;
     };
@@ -1150,23 +1170,11 @@ async fn refresh_tool_registry_disabled(state: &ServerState) {
     let mut disabled: std::collections::HashSet<String> = std::collections::HashSet::new();
     if let Ok(store) = ExtensionStore::open("data/extensions.redb") {
         if let Ok(records) = store.load_all() {
-            for r in records {
-                if !r.enabled {
-                    // Master off: every command of this extension is hidden.
-                    // We don't know all command names here cheaply, so we mark
-                    // by prefix-lookup at the registry level using the disabled
-                    // set as exact names; the registry's `definitions_for_llm`
-                    // also needs a prefix mode. Simpler: walk the loaded
-                    // extension runtime to enumerate command names.
-                    if let Some(info) = state.extensions.runtime.get(&r.id).await {
-                        for cmd in &info.commands {
-                            disabled.insert(format!("{}:{}", r.id, cmd.name));
-                        }
-                    }
-                    continue;
-                }
+            // Master-off extensions have no tools registered; see
+            // `set_extension_enabled_handler`
+            for r in records.into_iter().filter(|r| r.enabled) {
                 for cmd_name in &r.disabled_commands {
-                    disabled.insert(format!("{}:{}", r.id, cmd_name));
+                    disabled.insert(extension_tool_name(&r.id, cmd_name));
                 }
             }
         }
@@ -1176,10 +1184,10 @@ async fn refresh_tool_registry_disabled(state: &ServerState) {
 
 /// PATCH /api/extensions/:id/enabled
 ///
-/// Master tool-toggle for an extension. `enabled=false` removes ALL of this
-/// extension's tools from the LLM-facing list; `enabled=true` restores them
-/// (subject to per-command disables). Storage is the source of truth; the
-/// live ToolRegistry is refreshed from storage after the write.
+/// Master tool-toggle for an extension. `enabled=false` unregisters ALL of
+/// this extension's tools; `enabled=true` registers them again (subject to
+/// per-command disables). Storage is the source of truth; the live
+/// ToolRegistry is rebuilt from storage after the write.
 pub async fn set_extension_enabled_handler(
     State(state): State<ServerState>,
     Path(id): Path<String>,
@@ -1200,7 +1208,7 @@ pub async fn set_extension_enabled_handler(
         .save(&record)
         .map_err(|e| ErrorResponse::internal(format!("Save extension: {e}")))?;
 
-    refresh_tool_registry_disabled(&state).await;
+    state.refresh_extension_tools().await;
 
     tracing::info!(
         extension = %id,
@@ -1256,6 +1264,113 @@ pub async fn set_extension_command_enabled_handler(
     ok(json!({ "id": id, "command": cmd, "enabled": req.enabled }))
 }
 
+/// Permissions an extension declares, the ones approved, and those pending.
+#[derive(Debug, Serialize)]
+pub struct ExtensionPermissionsDto {
+    pub extension_id: String,
+    pub requested: Vec<String>,
+    pub granted: Vec<String>,
+    pub pending: Vec<String>,
+    /// False for extensions declaring no permissions; those are unrestricted.
+    pub enforced: bool,
+}
+
+impl ExtensionPermissionsDto {
+    fn new(extension_id: String, grant: PermissionGrant) -> Self {
+        Self {
+            extension_id,
+            pending: grant.pending(),
+            enforced: grant.is_enforced(),
+            requested: grant.requested.into_iter().collect(),
+            granted: grant.granted.into_iter().collect(),
+        }
+    }
+}
+
+/// Request to replace an extension's approved permissions.
+#[derive(Debug, Deserialize)]
+pub struct GrantPermissionsRequest {
+    pub granted: Vec<String>,
+}
+
+/// GET /api/extensions/:id/permissions
+///
+/// Declared permissions come from the loaded manifest; approvals from the
+/// extension store.
+pub async fn get_extension_permissions_handler(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> HandlerResult<ExtensionPermissionsDto> {
+    validate_extension_id(&id)?;
+
+    let grant = match state.extensions.runtime.permissions().get(&id) {
+        Some(grant) => grant,
+        None => {
+            let record = state
+                .extensions
+                .store
+                .load(&id)
+                .map_err(|e| ErrorResponse::internal(format!("Load extension: {e}")))?
+                .ok_or_else(|| ErrorResponse::not_found(format!("Extension {id}")))?;
+            PermissionGrant {
+                requested: Default::default(),
+                granted: record.granted_permissions.into_iter().collect(),
+            }
+        }
+    };
+    ok(ExtensionPermissionsDto::new(id, grant))
+}
+
+/// PUT /api/extensions/:id/permissions
+///
+/// Replace the approved permissions. Only permissions the manifest declares
+/// can be granted. Takes effect on the extension's next capability request.
+pub async fn update_extension_permissions_handler(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Json(req): Json<GrantPermissionsRequest>,
+) -> HandlerResult<ExtensionPermissionsDto> {
+    validate_extension_id(&id)?;
+
+    let permissions = state.extensions.runtime.permissions();
+    if let Some(grant) = permissions.get(&id) {
+        let undeclared: Vec<&String> = req
+            .granted
+            .iter()
+            .filter(|name| !grant.requested.contains(*name))
+            .collect();
+        if !undeclared.is_empty() {
+            return Err(ErrorResponse::bad_request(format!(
+                "Permissions not declared by extension {}: {:?}",
+                id, undeclared
+            )));
+        }
+    }
+
+    let store = &state.extensions.store;
+    let mut record = store
+        .load(&id)
+        .map_err(|e| ErrorResponse::internal(format!("Load extension: {e}")))?
+        .ok_or_else(|| ErrorResponse::not_found(format!("Extension {id}")))?;
+    record.granted_permissions = req.granted.clone();
+    record.granted_permissions.sort();
+    record.granted_permissions.dedup();
+    record.touch();
+    store
+        .save(&record)
+        .map_err(|e| ErrorResponse::internal(format!("Save extension: {e}")))?;
+
+    permissions.approve(&id, req.granted.into_iter().collect());
+
+    tracing::info!(
+        extension = %id,
+        granted = ?record.granted_permissions,
+        "extension permissions updated"
+    );
+    let grant = permissions.get(&id).unwrap_or_default();
+    ok(ExtensionPermissionsDto::new(id, grant))
+}
+
 /// GET /api/extensions/:id/event-subscriptions
 ///
 /// Get event subscriptions for an extension.
@@ -2517,6 +2632,30 @@ pub async fn install_marketplace_extension_handler(
         let data_dir = std::env::var("NEOMIND_DATA_DIR").unwrap_or_else(|_| "data".to_string());
         let target_dir = PathBuf::from(data_dir).join("extensions");
 
+        // The SHA256 comes from the same index as the download, so it can't
+        // vouch for the publisher; the signature can.
+        let policy = signature_policy()?;
+        let verify_path = tmp_path.clone();
+        let verify_dir = target_dir.clone();
+        let signature = tokio::task::spawn_blocking(move || {
+            signing::verify_install_file(&verify_path, &policy, &verify_dir)
+        })
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Task join error: {}", e)))?;
+        let signature = match signature {
+            Ok(signature) => signature,
+            Err(e) => {
+                return ok(MarketplaceInstallResponse {
+                    success: false,
+                    extension_id: req.id.clone(),
+                    downloaded: true,
+                    installed: false,
+                    path: None,
+                    error: Some(format!("{} — refusing to install", e)),
+                });
+            }
+        };
+
         // Install from the temp file — streams the ZIP from disk, not memory.
         // (install_from_file reads manifest + validates internally, so the old
         // from_bytes() pre-check is redundant and dropped.)
@@ -2533,6 +2672,7 @@ pub async fn install_marketplace_extension_handler(
             Ok(Ok(result)) => {
                 let ext_id = result.extension_id.clone();
                 let version = result.version.clone();
+                pin_signer(&target_dir, &ext_id, &signature);
 
                 tracing::info!(
                     extension_id = %ext_id,
@@ -2567,7 +2707,11 @@ pub async fn install_marketplace_extension_handler(
                             .binary_path
                             .extension()
                             .and_then(|e| e.to_str())
-                            .map(|e| if e == "wasm" { "wasm" } else { "native" })
+                            .map(|e| match e {
+                                "wasm" => "wasm",
+                                "py" => "python",
+                                _ => "native",
+                            })
                             .unwrap_or("native")
                             .to_string();
 
@@ -3853,6 +3997,19 @@ pub async fn upload_extension_package_handler(
         .await
         .map_err(|e| ErrorResponse::bad_request(format!("Invalid package: {}", e)))?;
 
+    let data_dir = std::env::var("NEOMIND_DATA_DIR").unwrap_or_else(|_| "data".to_string());
+    let target_dir = PathBuf::from(data_dir).join("extensions");
+
+    let policy = signature_policy()?;
+    let verify_path = file_path.clone();
+    let verify_dir = target_dir.clone();
+    let signature = tokio::task::spawn_blocking(move || {
+        signing::verify_install_file(&verify_path, &policy, &verify_dir)
+    })
+    .await
+    .map_err(|e| ErrorResponse::internal(format!("Task join error: {}", e)))?
+    .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+
     let ext_id = package.manifest.id.clone();
     let version = package.manifest.version.clone();
     let name = package.manifest.name.clone();
@@ -3879,13 +4036,11 @@ pub async fn upload_extension_package_handler(
     }
 
     // Install the package
-    let data_dir = std::env::var("NEOMIND_DATA_DIR").unwrap_or_else(|_| "data".to_string());
-    let target_dir = PathBuf::from(data_dir).join("extensions");
-
     let install_result = package
         .install(&target_dir)
         .await
         .map_err(|e| ErrorResponse::internal(format!("Installation failed: {}", e)))?;
+    pin_signer(&target_dir, &install_result.extension_id, &signature);
 
     tracing::info!(
         extension_id = %install_result.extension_id,
@@ -3950,6 +4105,7 @@ pub async fn upload_extension_package_handler(
             "description": c.description,
             "category": c.category
         })).collect::<Vec<_>>(),
+        "signature": signature,
         "replaced": is_registered
     }))
 }
@@ -3974,6 +4130,20 @@ pub async fn validate_extension_package_handler(
         .await
         .map_err(|e| ErrorResponse::bad_request(format!("Invalid package: {}", e)))?;
 
+    let data_dir = std::env::var("NEOMIND_DATA_DIR").unwrap_or_else(|_| "data".to_string());
+    let extensions_dir = PathBuf::from(data_dir).join("extensions");
+    let policy = signature_policy()?;
+    let verify_path = file_path.clone();
+    let signature = tokio::task::spawn_blocking(move || {
+        signing::verify_install_file(&verify_path, &policy, &extensions_dir)
+    })
+    .await
+    .map_err(|e| ErrorResponse::internal(format!("Task join error: {}", e)))?;
+    let signature = match signature {
+        Ok(status) => json!(status),
+        Err(e) => json!({ "status": "rejected", "error": e.to_string() }),
+    };
+
     let platform = detect_platform();
     let has_binary = package.get_binary_path().is_some();
     let has_frontend = package.manifest.frontend.is_some();
@@ -4000,11 +4170,36 @@ pub async fn validate_extension_package_handler(
         "components_count": components_count,
         "capabilities": package.manifest.capabilities,
         "permissions": package.manifest.permissions,
+        "signature": signature,
         "checksum": package.checksum,
         "size": package.size
     }))
 }
 
+/// GET /api/extensions/signature-policy
+/// Trusted publisher keys and whether unsigned packages may be installed.
+pub async fn get_signature_policy_handler() -> HandlerResult<SignaturePolicy> {
+    ok(signature_policy()?)
+}
+
+/// PUT /api/extensions/signature-policy
+/// Replace the trusted publishers. Applies to future installs only; installed
+/// extensions are not re-checked.
+pub async fn update_signature_policy_handler(
+    Json(policy): Json<SignaturePolicy>,
+) -> HandlerResult<SignaturePolicy> {
+    policy.validate().map_err(ErrorResponse::bad_request)?;
+    SettingsStore::open("data/settings.redb")
+        .and_then(|store| store.save_signature_policy(&policy))
+        .map_err(|e| ErrorResponse::internal(format!("Failed to save signature policy: {}", e)))?;
+    tracing::info!(
+        publishers = policy.publishers.len(),
+        require_signature = policy.require_signature,
+        "Extension signature policy updated"
+    );
+    ok(policy)
+}
+
 /// Upload package request
 #[derive(Debug, Deserialize)]
 pub struct UploadPackageRequest {
@@ -4079,13 +4274,18 @@ pub async fn uninstall_extension_handler(
 /// POST /api/extensions/upload/file
 /// Upload an extension package file directly (.nep format).
 ///
-/// This endpoint accepts a JSON body with base64-encoded file data.
+/// This endpoint accepts a JSON body with base64-encoded file data, or a
+/// `url` to download the package from. The package's signature is checked
+/// against the signature policy before anything is written. With
+/// `upgrade: true` the extension must already be installed and the package
+/// version must be newer.
 ///
 /// Request body:
 /// ```json
 /// {
 ///   "data": "<base64-encoded .nep file>",
-///   "filename": "extension.nep"
+///   "filename": "extension.nep",
+///   "upgrade": false
 /// }
 /// ```
 ///
@@ -4100,9 +4300,82 @@ pub async fn uninstall_extension_handler(
 #[derive(Debug, serde::Deserialize)]
 pub struct UploadExtensionFileRequest {
     /// Base64-encoded .nep file data
+    #[serde(default)]
     pub data: String,
     /// Optional filename
     pub filename: Option<String>,
+    /// Download the package from this URL instead of `data`
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Only replace an installed extension with a newer version
+    #[serde(default)]
+    pub upgrade: bool,
+}
+
+/// Download a package for `upload_extension_file_handler`.
+async fn download_package(url: &str) -> Result<Vec<u8>, ErrorResponse> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(ErrorResponse::bad_request(
+            "Package URL must be http:// or https://",
+        ));
+    }
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(600))
+        .build()
+        .map_err(|e| ErrorResponse::internal(e.to_string()))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| ErrorResponse::bad_request(format!("Failed to download package: {}", e)))?;
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_EXTENSION_DOWNLOAD_SIZE)
+    {
+        return Err(ErrorResponse::bad_request("Package is too large"));
+    }
+
+    let mut data = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk
+            .map_err(|e| ErrorResponse::bad_request(format!("Failed to download package: {}", e)))?;
+        data.extend_from_slice(&chunk);
+        if data.len() as u64 > MAX_EXTENSION_DOWNLOAD_SIZE {
+            return Err(ErrorResponse::bad_request("Package is too large"));
+        }
+    }
+    Ok(data)
+}
+
+/// Reject an upgrade to a version that isn't newer than the installed one.
+fn check_upgrade(ext_id: &str, version: &str) -> Result<String, ErrorResponse> {
+    let installed = ExtensionStore::open("data/extensions.redb")
+        .and_then(|store| {
+            if store.is_uninstalled(ext_id)? {
+                return Ok(None);
+            }
+            store.load(ext_id)
+        })
+        .map_err(|e| ErrorResponse::internal(format!("Failed to read extension store: {}", e)))?
+        .ok_or_else(|| {
+            ErrorResponse::not_found(format!("Installed extension {}", ext_id))
+                .with_hint("Use install for extensions that aren't installed yet")
+        })?;
+
+    let parse = |v: &str| semver::Version::parse(v.trim_start_matches('v'));
+    let newer = match (parse(version), parse(&installed.version)) {
+        (Ok(new), Ok(old)) => new > old,
+        _ => version != installed.version,
+    };
+    if !newer {
+        return Err(ErrorResponse::conflict(format!(
+            "Extension {} {} is installed; package version {} is not newer",
+            ext_id, installed.version, version
+        )));
+    }
+    Ok(installed.version)
 }
 
 #[axum::debug_handler]
@@ -4110,19 +4383,24 @@ pub async fn upload_extension_file_handler(
     State(state): State<ServerState>,
     Json(req): Json<UploadExtensionFileRequest>,
 ) -> HandlerResult<serde_json::Value> {
-    // Log upload request details
-    let data_len = req.data.len();
-    let filename = req.filename.as_deref().unwrap_or("unknown");
-    tracing::info!(
-        "Extension upload request received: filename={}, base64_size={}MB",
-        filename,
-        data_len / 1_000_000
-    );
+    let body_bytes = if let Some(url) = req.url.as_deref().filter(|_| req.data.is_empty()) {
+        tracing::info!("Extension install from URL: {}", url);
+        download_package(url).await?
+    } else {
+        // Log upload request details
+        let data_len = req.data.len();
+        let filename = req.filename.as_deref().unwrap_or("unknown");
+        tracing::info!(
+            "Extension upload request received: filename={}, base64_size={}MB",
+            filename,
+            data_len / 1_000_000
+        );
 
-    // Decode base64 data
-    let body_bytes = STANDARD
-        .decode(&req.data)
-        .map_err(|e| ErrorResponse::bad_request(format!("Invalid base64 data: {}", e)))?;
+        // Decode base64 data
+        STANDARD
+            .decode(&req.data)
+            .map_err(|e| ErrorResponse::bad_request(format!("Invalid base64 data: {}", e)))?
+    };
 
     tracing::info!(
         "Base64 decoded successfully: binary_size={}MB",
@@ -4176,6 +4454,23 @@ pub async fn upload_extension_file_handler(
         "Package validated successfully"
     );
 
+    let previous_version = if req.upgrade {
+        Some(check_upgrade(&ext_id, &version)?)
+    } else {
+        None
+    };
+
+    let policy = signature_policy()?;
+    let body_bytes = std::sync::Arc::new(body_bytes);
+    let bytes_for_verify = body_bytes.clone();
+    let verify_dir = target_dir.clone();
+    let signature = tokio::task::spawn_blocking(move || {
+        signing::verify_install_bytes(&bytes_for_verify, &policy, &verify_dir)
+    })
+    .await
+    .map_err(|e| ErrorResponse::internal(format!("Task join error: {}", e)))?
+    .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+
     // Step 2: Check if already registered and unload FIRST (before overwriting files)
     // This is critical on macOS where overwriting a dylib that's in use can cause issues
     let runtime = state.extensions.runtime.clone();
@@ -4209,6 +4504,7 @@ pub async fn upload_extension_file_handler(
         tracing::error!("install_sync failed for {}: {} (kind={:?})", ext_id, e, e);
         ErrorResponse::internal(format!("Installation failed: {}", e))
     })?;
+    pin_signer(&target_dir, &install_result.extension_id, &signature);
 
     tracing::info!(
         extension_id = %install_result.extension_id,
@@ -4229,7 +4525,11 @@ pub async fn upload_extension_file_handler(
         .binary_path
         .extension()
         .and_then(|e| e.to_str())
-        .map(|e| if e == "wasm" { "wasm" } else { "native" })
+        .map(|e| match e {
+            "wasm" => "wasm",
+            "py" => "python",
+            _ => "native",
+        })
         .unwrap_or("native")
         .to_string();
 
@@ -4281,6 +4581,8 @@ pub async fn upload_extension_file_handler(
             "description": c.description,
             "category": c.category
         })).collect::<Vec<_>>(),
+        "signature": signature,
+        "previous_version": previous_version,
         "replaced": is_registered
     }))
 }
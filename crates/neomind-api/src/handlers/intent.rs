@@ -0,0 +1,194 @@
+//! Intent feedback and intent profile handlers.
+//!
+//! POST   /api/sessions/:id/intent-feedback - Mark a turn's intent as wrong
+//! GET    /api/intent/examples              - Recorded predictions
+//! GET    /api/intent/examples/export       - Labeled examples as JSONL
+//! GET    /api/intent/profile               - Custom keywords and patterns
+//! PUT    /api/intent/profile               - Replace the profile
+//! DELETE /api/intent/profile               - Back to built-in keywords
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use neomind_agent::agent::staged::{
+    intent_profile, set_intent_profile, IntentCategory, IntentProfile,
+};
+use neomind_agent::intent_feedback::{export_jsonl, ExportFormat};
+use neomind_agent::NeoMindError;
+use neomind_core::tenant::TenantScope;
+use neomind_storage::{IntentExample, IntentFeedbackSource};
+use serde::Deserialize;
+
+use super::common::{ok, HandlerResult};
+use super::sessions::{check_session_scope, session_in_scope};
+use crate::auth::RequestTenant;
+use crate::config::INTENT_PROFILE_PATH;
+use crate::models::error::ErrorResponse;
+use crate::server::ServerState;
+
+/// Feedback on the intent predicted for a turn.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentFeedbackRequest {
+    /// Example to label; the session's latest prediction by default
+    #[serde(default)]
+    pub example_id: Option<String>,
+    /// The intent the message actually had
+    #[serde(default)]
+    pub correct_intent: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExamplesQuery {
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Only examples with a known correct intent
+    #[serde(default)]
+    pub labeled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+fn feedback_error(e: NeoMindError) -> ErrorResponse {
+    match e {
+        NeoMindError::NotFound(what) => ErrorResponse::not_found(what),
+        e => ErrorResponse::internal(e.to_string()),
+    }
+}
+
+/// Examples of the sessions visible to the caller.
+fn visible_examples(
+    state: &ServerState,
+    scope: &TenantScope,
+    session_id: Option<&str>,
+    labeled_only: bool,
+) -> Result<Vec<IntentExample>, ErrorResponse> {
+    if let Some(id) = session_id {
+        check_session_scope(state, scope, id)?;
+    }
+    let mut examples = state
+        .agents
+        .session_manager
+        .intent_feedback()
+        .examples(session_id, labeled_only)
+        .map_err(feedback_error)?;
+    examples.retain(|e| session_in_scope(state, scope, &e.session_id));
+    Ok(examples)
+}
+
+/// `POST /api/sessions/:id/intent-feedback` — thumbs-down on the intent of
+/// a turn, optionally with the intent it should have been.
+pub async fn submit_intent_feedback_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+    Json(req): Json<IntentFeedbackRequest>,
+) -> HandlerResult<IntentExample> {
+    check_session_scope(&state, &scope, &id)?;
+    let correct = req
+        .correct_intent
+        .as_deref()
+        .map(|name| {
+            IntentCategory::from_name(name)
+                .ok_or_else(|| ErrorResponse::bad_request(format!("Unknown intent: {}", name)))
+        })
+        .transpose()?;
+    let source = if correct.is_some() {
+        IntentFeedbackSource::Correction
+    } else {
+        IntentFeedbackSource::ThumbsDown
+    };
+
+    let example = state
+        .agents
+        .session_manager
+        .intent_feedback()
+        .submit(&id, req.example_id.as_deref(), source, correct.as_ref())
+        .map_err(feedback_error)?;
+    ok(example)
+}
+
+/// `GET /api/intent/examples` — recorded predictions, oldest first.
+pub async fn list_intent_examples_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Query(query): Query<ExamplesQuery>,
+) -> HandlerResult<Vec<IntentExample>> {
+    ok(visible_examples(
+        &state,
+        &scope,
+        query.session_id.as_deref(),
+        query.labeled,
+    )?)
+}
+
+/// `GET /api/intent/examples/export?format=chat|classification` — labeled
+/// examples as a fine-tuning JSONL file.
+pub async fn export_intent_examples_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ErrorResponse> {
+    let examples = visible_examples(&state, &scope, None, true)?;
+    let body = export_jsonl(&examples, query.format);
+    let name = match query.format {
+        ExportFormat::Chat => "intent_finetune_chat.jsonl",
+        ExportFormat::Classification => "intent_finetune_classification.jsonl",
+    };
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", name),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// `GET /api/intent/profile` — the installed profile; empty when only the
+/// built-in keywords are used.
+pub async fn get_intent_profile_handler() -> HandlerResult<IntentProfile> {
+    ok(intent_profile().unwrap_or_default())
+}
+
+/// `PUT /api/intent/profile` — install a profile and keep it across restarts.
+pub async fn update_intent_profile_handler(
+    Json(profile): Json<IntentProfile>,
+) -> HandlerResult<IntentProfile> {
+    let content = serde_json::to_string_pretty(&profile)
+        .map_err(|e| ErrorResponse::internal(e.to_string()))?;
+    // Compile first so an invalid pattern leaves the saved profile alone
+    set_intent_profile(Some(profile.clone())).map_err(ErrorResponse::bad_request)?;
+    tokio::fs::write(INTENT_PROFILE_PATH, content)
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to save intent profile: {}", e)))?;
+    ok(profile)
+}
+
+/// `DELETE /api/intent/profile` — drop the profile.
+pub async fn delete_intent_profile_handler() -> HandlerResult<serde_json::Value> {
+    set_intent_profile(None).map_err(ErrorResponse::internal)?;
+    match tokio::fs::remove_file(INTENT_PROFILE_PATH).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            return Err(ErrorResponse::internal(format!(
+                "Failed to delete intent profile: {}",
+                e
+            )));
+        }
+    }
+    ok(serde_json::json!({ "deleted": true }))
+}
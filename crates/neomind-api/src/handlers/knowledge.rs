@@ -0,0 +1,273 @@
+//! Knowledge base handlers.
+//!
+//! POST   /api/knowledge/documents     - Upload a document (multipart `file`)
+//! GET    /api/knowledge/documents     - List documents
+//! DELETE /api/knowledge/documents/:id - Delete a document
+//! POST   /api/knowledge/search        - Retrieve relevant chunks
+//! GET    /api/knowledge/config        - Embedding and chunking settings
+//! PUT    /api/knowledge/config        - Update settings
+//! POST   /api/knowledge/reindex       - Start re-embedding with the current embedder
+//! GET    /api/knowledge/reindex       - Reindex progress
+
+use axum::{
+    extract::{Multipart, Path, State},
+    Json,
+};
+use neomind_agent::llm_backends::{set_query_embedder, QueryEmbedder};
+use neomind_storage::{KnowledgeConfig, LlmBackendStore, SettingsStore};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+use super::common::{ok, HandlerResult};
+use crate::knowledge::{
+    DocumentFormat, Embedder, KnowledgeDocument, KnowledgeHit, ReindexJob, ReindexStatus,
+};
+use crate::models::error::ErrorResponse;
+use crate::server::ServerState;
+
+const SETTINGS_DB_PATH: &str = "data/settings.redb";
+const LLM_BACKENDS_DB_PATH: &str = "data/llm_backends.redb";
+/// Largest accepted upload.
+pub const MAX_DOCUMENT_SIZE: usize = 50 * 1024 * 1024;
+const DEFAULT_TOP_K: usize = 5;
+const MAX_TOP_K: usize = 20;
+
+fn settings_store() -> Result<std::sync::Arc<SettingsStore>, ErrorResponse> {
+    SettingsStore::open(SETTINGS_DB_PATH)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to open settings store: {}", e)))
+}
+
+/// The configured embedder, resolving its LLM backend.
+fn embedder(config: &KnowledgeConfig) -> Result<Embedder, ErrorResponse> {
+    let backend = match &config.embedding_backend_id {
+        Some(id) => LlmBackendStore::open(LLM_BACKENDS_DB_PATH)
+            .and_then(|store| store.load_instance(id))
+            .map_err(|e| ErrorResponse::internal(format!("Failed to load LLM backend: {}", e)))?,
+        None => None,
+    };
+    Embedder::from_config(config, backend).map_err(ErrorResponse::bad_request)
+}
+
+/// Use the configured embedder for the LLM response cache's semantic layer.
+/// Called at startup; config updates install the new embedder directly.
+pub fn install_query_embedder() {
+    let embedder = settings_store()
+        .ok()
+        .and_then(|store| embedder(&store.get_knowledge_config()).ok());
+    set_query_embedder(embedder.map(|e| Arc::new(e) as Arc<dyn QueryEmbedder>));
+}
+
+/// `POST /api/knowledge/documents` — multipart upload with a `file` part
+/// (PDF, Markdown, HTML or text) and optional `name` and `session_id` fields.
+/// A document attached in a session is deleted with it.
+pub async fn upload_document_handler(
+    State(state): State<ServerState>,
+    mut multipart: Multipart,
+) -> HandlerResult<KnowledgeDocument> {
+    let mut file: Option<(String, DocumentFormat, Vec<u8>)> = None;
+    let mut name: Option<String> = None;
+    let mut session_id: Option<String> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ErrorResponse::bad_request(format!("Invalid multipart body: {}", e)))?
+    {
+        match field.name().unwrap_or_default() {
+            "file" => {
+                let file_name = field.file_name().unwrap_or("document").to_string();
+                let format =
+                    DocumentFormat::detect(field.content_type(), &file_name).ok_or_else(|| {
+                        ErrorResponse::bad_request(
+                            "Unsupported document type; upload PDF, Markdown, HTML or text",
+                        )
+                    })?;
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+                file = Some((file_name, format, data.to_vec()));
+            }
+            "name" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+                name = Some(value).filter(|v| !v.trim().is_empty());
+            }
+            "session_id" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+                session_id = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+            }
+            _ => {}
+        }
+    }
+    let (file_name, format, data) =
+        file.ok_or_else(|| ErrorResponse::bad_request("Missing 'file' part"))?;
+    if let Some(session_id) = &session_id {
+        let exists = state
+            .agents
+            .session_manager
+            .session_store()
+            .session_exists(session_id)
+            .map_err(|e| ErrorResponse::internal(format!("Failed to check session: {}", e)))?;
+        if !exists {
+            return Err(ErrorResponse::not_found("Session"));
+        }
+    }
+
+    let config = settings_store()?.get_knowledge_config();
+    let embedder = embedder(&config)?;
+    let document = state
+        .knowledge
+        .ingest(
+            name.as_deref().unwrap_or(&file_name),
+            format,
+            &data,
+            session_id.as_deref(),
+            &config,
+            &embedder,
+        )
+        .await
+        .map_err(ErrorResponse::bad_request)?;
+    tracing::info!(
+        document = %document.name,
+        chunks = document.chunks,
+        "Knowledge document ingested"
+    );
+    ok(document)
+}
+
+/// `GET /api/knowledge/documents` — all documents, newest first.
+pub async fn list_documents_handler(
+    State(state): State<ServerState>,
+) -> HandlerResult<serde_json::Value> {
+    let documents = state
+        .knowledge
+        .list()
+        .await
+        .map_err(ErrorResponse::internal)?;
+    ok(json!({ "documents": documents, "count": documents.len() }))
+}
+
+/// `DELETE /api/knowledge/documents/:id`
+pub async fn delete_document_handler(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    if !state
+        .knowledge
+        .delete(&id)
+        .await
+        .map_err(ErrorResponse::internal)?
+    {
+        return Err(ErrorResponse::not_found(format!("Document {}", id)));
+    }
+    ok(json!({ "deleted": id }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KnowledgeSearchRequest {
+    pub query: String,
+    /// Chunks to return (default 5, max 20)
+    pub top_k: Option<usize>,
+}
+
+/// `POST /api/knowledge/search` — chunks most relevant to `query`.
+pub async fn search_knowledge_handler(
+    State(state): State<ServerState>,
+    Json(request): Json<KnowledgeSearchRequest>,
+) -> HandlerResult<Vec<KnowledgeHit>> {
+    if request.query.trim().is_empty() {
+        return Err(ErrorResponse::bad_request("query must not be empty"));
+    }
+    let top_k = request.top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K);
+    let embedder = embedder(&settings_store()?.get_knowledge_config())?;
+    let hits = state
+        .knowledge
+        .search(&request.query, top_k, &embedder)
+        .await
+        .map_err(ErrorResponse::internal)?;
+    ok(hits)
+}
+
+/// `GET /api/knowledge/config`
+pub async fn get_knowledge_config_handler() -> HandlerResult<KnowledgeConfig> {
+    ok(settings_store()?.get_knowledge_config())
+}
+
+/// `PUT /api/knowledge/config` — changing the embedder starts a background
+/// reindex, returned as `reindex`; existing documents stay searchable with
+/// the previous embedder until it completes.
+pub async fn update_knowledge_config_handler(
+    State(state): State<ServerState>,
+    Json(config): Json<KnowledgeConfig>,
+) -> HandlerResult<serde_json::Value> {
+    config.validate().map_err(ErrorResponse::bad_request)?;
+    // Fail early on an unknown or unusable backend.
+    let target = embedder(&config)?;
+    let store = settings_store()?;
+    // The old backend may be gone; then unmigrated chunks are unsearchable
+    // until the reindex finishes.
+    let previous = embedder(&store.get_knowledge_config()).ok();
+
+    let model_changed = previous.as_ref().map(Embedder::model_id) != Some(target.model_id());
+    if model_changed && reindex_running(&state).await {
+        return Err(ErrorResponse::conflict(
+            "A reindex is running; wait for it to finish before changing the embedder",
+        ));
+    }
+    store
+        .save_knowledge_config(&config)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to save knowledge config: {}", e)))?;
+    set_query_embedder(Some(Arc::new(target.clone())));
+
+    let reindex = if model_changed {
+        Some(
+            state
+                .knowledge
+                .start_reindex(target, previous)
+                .await
+                .map_err(ErrorResponse::internal)?,
+        )
+    } else {
+        None
+    };
+    ok(json!({ "config": config, "reindex": reindex }))
+}
+
+async fn reindex_running(state: &ServerState) -> bool {
+    state
+        .knowledge
+        .reindex_job()
+        .await
+        .is_some_and(|job| job.status == ReindexStatus::Running)
+}
+
+/// `POST /api/knowledge/reindex` — re-embed chunks not embedded by the
+/// current embedder, e.g. to finish a reindex interrupted by a restart.
+pub async fn reindex_knowledge_handler(
+    State(state): State<ServerState>,
+) -> HandlerResult<ReindexJob> {
+    if reindex_running(&state).await {
+        return Err(ErrorResponse::conflict("A reindex is already running"));
+    }
+    let embedder = embedder(&settings_store()?.get_knowledge_config())?;
+    let job = state
+        .knowledge
+        .start_reindex(embedder, None)
+        .await
+        .map_err(ErrorResponse::conflict)?;
+    ok(job)
+}
+
+/// `GET /api/knowledge/reindex` — the running or most recent reindex.
+pub async fn get_reindex_handler(State(state): State<ServerState>) -> HandlerResult<ReindexJob> {
+    match state.knowledge.reindex_job().await {
+        Some(job) => ok(job),
+        None => Err(ErrorResponse::not_found("Reindex job")),
+    }
+}
@@ -1033,6 +1033,47 @@ pub async fn get_backend_stats_handler(
     ok(stats)
 }
 
+/// Request body for setting the failover chain.
+#[derive(Debug, Deserialize)]
+pub struct UpdateFailoverChainRequest {
+    /// Backend IDs tried in order when the active backend fails; empty
+    /// turns failover off
+    pub backend_ids: Vec<String>,
+}
+
+/// Get the LLM failover chain
+///
+/// GET /api/llm-backends/failover
+pub async fn get_failover_chain_handler(
+    State(_state): State<ServerState>,
+) -> HandlerResult<serde_json::Value> {
+    let manager = get_manager()?;
+
+    ok(json!({
+        "active_id": manager.get_active_instance().map(|i| i.id),
+        "backend_ids": manager.failover_chain(),
+    }))
+}
+
+/// Set the LLM failover chain
+///
+/// PUT /api/llm-backends/failover
+pub async fn update_failover_chain_handler(
+    State(_state): State<ServerState>,
+    Json(req): Json<UpdateFailoverChainRequest>,
+) -> HandlerResult<serde_json::Value> {
+    let manager = get_manager()?;
+
+    manager
+        .set_failover_chain(req.backend_ids)
+        .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+
+    ok(json!({
+        "backend_ids": manager.failover_chain(),
+        "message": "Failover chain updated",
+    }))
+}
+
 /// Fetch available models from an Ollama server
 ///
 /// GET /api/llm-backends/ollama/models?endpoint=http://localhost:11434
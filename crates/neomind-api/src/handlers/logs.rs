@@ -273,13 +273,13 @@ mod tests {
     #[test]
     fn max_files_and_size_guards_are_sane() {
         // Smoke: constants shouldn't drift to absurd values accidentally.
-        assert!(MAX_FILES >= 10 && MAX_FILES <= 200);
-        assert!(MAX_BYTES_PER_FILE >= 8 * 1024 * 1024);
-        assert!(MAX_BYTES_PER_FILE <= 256 * 1024 * 1024);
+        const { assert!(MAX_FILES >= 10 && MAX_FILES <= 200) };
+        const { assert!(MAX_BYTES_PER_FILE >= 8 * 1024 * 1024) };
+        const { assert!(MAX_BYTES_PER_FILE <= 256 * 1024 * 1024) };
         // Total cap must be ≥ per-file cap (otherwise a single file would
         // always trip the total cap) and ≤ a reasonable RAM ceiling.
-        assert!(MAX_TOTAL_BYTES_READ >= MAX_BYTES_PER_FILE);
-        assert!(MAX_TOTAL_BYTES_READ <= 2 * 1024 * 1024 * 1024); // ≤ 2 GiB
+        const { assert!(MAX_TOTAL_BYTES_READ >= MAX_BYTES_PER_FILE) };
+        const { assert!(MAX_TOTAL_BYTES_READ <= 2 * 1024 * 1024 * 1024) }; // ≤ 2 GiB
     }
 
     #[test]
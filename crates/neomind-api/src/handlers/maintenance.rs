@@ -0,0 +1,154 @@
+//! Maintenance window handlers.
+//!
+//! GET    /api/maintenance         - List maintenance windows
+//! POST   /api/maintenance         - Create a maintenance window
+//! GET    /api/maintenance/active  - Windows in effect now
+//! PUT    /api/maintenance/:id     - Replace a maintenance window
+//! DELETE /api/maintenance/:id     - Delete a maintenance window
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use neomind_storage::{MaintenanceWindow, SettingsStore};
+use serde_json::json;
+
+use super::common::{ok, HandlerResult};
+use crate::models::error::ErrorResponse;
+use crate::server::ServerState;
+
+const SETTINGS_DB_PATH: &str = "data/settings.redb";
+
+fn settings_store() -> Result<std::sync::Arc<SettingsStore>, ErrorResponse> {
+    SettingsStore::open(SETTINGS_DB_PATH)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to open settings store: {}", e)))
+}
+
+fn load_windows(store: &SettingsStore) -> Result<Vec<MaintenanceWindow>, ErrorResponse> {
+    store
+        .load_maintenance_windows()
+        .map_err(|e| ErrorResponse::internal(format!("Failed to load maintenance windows: {}", e)))
+}
+
+fn save_windows(
+    state: &ServerState,
+    store: &SettingsStore,
+    windows: &[MaintenanceWindow],
+) -> Result<(), ErrorResponse> {
+    store.save_maintenance_windows(windows).map_err(|e| {
+        ErrorResponse::internal(format!("Failed to save maintenance windows: {}", e))
+    })?;
+    state.refresh_maintenance_windows();
+    Ok(())
+}
+
+fn validate(state: &ServerState, window: &MaintenanceWindow) -> Result<(), ErrorResponse> {
+    window.validate().map_err(ErrorResponse::bad_request)?;
+    if let Some(device_id) = window
+        .devices
+        .iter()
+        .find(|d| state.devices.service.get_device(d).is_none())
+    {
+        return Err(ErrorResponse::bad_request(format!(
+            "Device not found: {}",
+            device_id
+        )));
+    }
+    if let Some(group_id) = window
+        .groups
+        .iter()
+        .find(|g| state.devices.service.get_group_devices(g).is_err())
+    {
+        return Err(ErrorResponse::bad_request(format!(
+            "Device group not found: {}",
+            group_id
+        )));
+    }
+    Ok(())
+}
+
+/// `GET /api/maintenance` — all maintenance windows.
+pub async fn list_maintenance_windows_handler(
+    State(_state): State<ServerState>,
+) -> HandlerResult<serde_json::Value> {
+    let store = settings_store()?;
+    let windows = load_windows(&store)?;
+    ok(json!({
+        "count": windows.len(),
+        "windows": windows,
+    }))
+}
+
+/// `GET /api/maintenance/active` — windows in effect now.
+pub async fn list_active_maintenance_windows_handler(
+    State(state): State<ServerState>,
+) -> HandlerResult<serde_json::Value> {
+    let now = chrono::Utc::now().timestamp();
+    let windows = state.core.message_manager.maintenance().active(now);
+    ok(json!({
+        "timestamp": now,
+        "count": windows.len(),
+        "windows": windows,
+    }))
+}
+
+/// `POST /api/maintenance` — create a window. An empty `id` is generated.
+pub async fn create_maintenance_window_handler(
+    State(state): State<ServerState>,
+    Json(mut window): Json<MaintenanceWindow>,
+) -> HandlerResult<MaintenanceWindow> {
+    validate(&state, &window)?;
+    let store = settings_store()?;
+    let mut windows = load_windows(&store)?;
+    if window.id.is_empty() {
+        window.id = uuid::Uuid::new_v4().to_string();
+    } else if windows.iter().any(|w| w.id == window.id) {
+        return Err(ErrorResponse::bad_request(format!(
+            "Maintenance window already exists: {}",
+            window.id
+        )));
+    }
+    window.created_at = chrono::Utc::now().timestamp();
+    windows.push(window.clone());
+    save_windows(&state, &store, &windows)?;
+    ok(window)
+}
+
+/// `PUT /api/maintenance/:id` — replace a window.
+pub async fn update_maintenance_window_handler(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Json(mut window): Json<MaintenanceWindow>,
+) -> HandlerResult<MaintenanceWindow> {
+    validate(&state, &window)?;
+    let store = settings_store()?;
+    let mut windows = load_windows(&store)?;
+    let existing = windows
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| ErrorResponse::not_found(format!("Maintenance window not found: {}", id)))?;
+    window.id = id;
+    window.created_at = existing.created_at;
+    *existing = window.clone();
+    save_windows(&state, &store, &windows)?;
+    ok(window)
+}
+
+/// `DELETE /api/maintenance/:id` — delete a window.
+pub async fn delete_maintenance_window_handler(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    let store = settings_store()?;
+    let mut windows = load_windows(&store)?;
+    let before = windows.len();
+    windows.retain(|w| w.id != id);
+    if windows.len() == before {
+        return Err(ErrorResponse::not_found(format!(
+            "Maintenance window not found: {}",
+            id
+        )));
+    }
+    save_windows(&state, &store, &windows)?;
+    ok(json!({ "deleted": id }))
+}
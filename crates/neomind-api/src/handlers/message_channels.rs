@@ -42,6 +42,9 @@ use neomind_messages::SlackChannelFactory;
 #[cfg(feature = "feishu")]
 use neomind_messages::FeishuChannelFactory;
 
+#[cfg(feature = "mqtt")]
+use neomind_messages::MqttChannelFactory;
+
 use super::{
     common::{ok, HandlerResult},
     ServerState,
@@ -137,14 +140,36 @@ pub async fn get_channel_type_schema_handler(
     }))
 }
 
+/// Point an MQTT channel that targets the embedded broker at the port the
+/// broker actually listens on, unless the config sets one explicitly.
+#[cfg(feature = "mqtt")]
+fn with_embedded_broker_port(mut config: serde_json::Value) -> serde_json::Value {
+    let embedded = config
+        .get("embedded_broker")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if let Some(obj) = config.as_object_mut() {
+        if embedded && !obj.contains_key("port") {
+            let port = crate::config::get_embedded_broker_config().port;
+            obj.insert("port".to_string(), json!(port));
+        }
+    }
+    config
+}
+
 /// Create a new channel.
 /// POST /api/messages/channels
 pub async fn create_channel_handler(
     State(state): State<ServerState>,
-    Json(req): Json<CreateChannelRequest>,
+    Json(mut req): Json<CreateChannelRequest>,
 ) -> HandlerResult<serde_json::Value> {
     let registry = state.core.message_manager.channels().await;
 
+    #[cfg(feature = "mqtt")]
+    if req.channel_type == "mqtt" {
+        req.config = with_embedded_broker_port(req.config);
+    }
+
     // Check if channel already exists
     {
         let registry_guard = registry.read().await;
@@ -207,9 +232,16 @@ pub async fn create_channel_handler(
                 .create(&req.config)
                 .map_err(|e| ErrorResponse::bad_request(format!("Invalid config: {}", e)))?
         }
+        #[cfg(feature = "mqtt")]
+        "mqtt" => {
+            let factory = MqttChannelFactory;
+            factory
+                .create(&req.config)
+                .map_err(|e| ErrorResponse::bad_request(format!("Invalid config: {}", e)))?
+        }
         _ => {
             return Err(ErrorResponse::bad_request(format!(
-                "Unknown channel type: {}. Supported types: webhook, email, telegram, wecom, dingtalk, slack, feishu",
+                "Unknown channel type: {}. Supported types: webhook, email, telegram, wecom, dingtalk, slack, feishu, mqtt",
                 req.channel_type
             )));
         }
@@ -312,6 +344,10 @@ pub async fn update_channel_handler(
             obj.insert("recipients".to_string(), serde_json::json!(recipients));
         }
     }
+    #[cfg(feature = "mqtt")]
+    if channel_type == "mqtt" {
+        config = with_embedded_broker_port(config);
+    }
 
     // Create new channel with updated config
     let channel: Arc<dyn MessageChannel> = match channel_type.as_str() {
@@ -364,6 +400,13 @@ pub async fn update_channel_handler(
                 .create(&config)
                 .map_err(|e| ErrorResponse::bad_request(format!("Invalid config: {}", e)))?
         }
+        #[cfg(feature = "mqtt")]
+        "mqtt" => {
+            let factory = MqttChannelFactory;
+            factory
+                .create(&config)
+                .map_err(|e| ErrorResponse::bad_request(format!("Invalid config: {}", e)))?
+        }
         _ => {
             return Err(ErrorResponse::bad_request(format!(
                 "Unknown channel type: {}",
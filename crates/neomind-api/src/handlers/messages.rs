@@ -7,6 +7,12 @@
 //! POST   /api/messages/:id/acknowledge - Acknowledge message
 //! POST   /api/messages/:id/resolve  - Resolve message
 //! GET    /api/messages/stats        - Message statistics
+//! GET    /api/messages/groups       - Alert deduplication groups
+//! GET    /api/messages/grouping     - Alert grouping configuration
+//! PUT    /api/messages/grouping     - Update alert grouping configuration
+//! GET    /api/messages/escalation   - Escalation policies and on-call schedules
+//! PUT    /api/messages/escalation   - Update escalation policies and schedules
+//! GET    /api/messages/escalation/on-call - Who is on call now
 
 use axum::{
     extract::{Path, Query, State},
@@ -14,7 +20,10 @@ use axum::{
 };
 use serde::Deserialize;
 
-use neomind_messages::{Message, MessageId, MessageSeverity};
+use neomind_core::tenant::TenantScope;
+use neomind_messages::{
+    AlertGroupingConfig, EscalationConfig, Message, MessageId, MessageSeverity,
+};
 
 use super::{
     common::{ok, HandlerResult},
@@ -22,9 +31,34 @@ use super::{
 };
 
 // Import json macro for handler responses
+use crate::auth::RequestTenant;
 use crate::models::ErrorResponse;
 use serde_json::json;
 
+/// Whether the message is visible in `scope`.
+async fn message_in_scope(state: &ServerState, scope: &TenantScope, msg_id: &MessageId) -> bool {
+    *scope == TenantScope::All
+        || state
+            .core
+            .message_manager
+            .get_message(msg_id)
+            .await
+            .is_some_and(|message| scope.allows(&message.tenant_id))
+}
+
+/// Messages of other tenants are reported as not found.
+async fn check_message_scope(
+    state: &ServerState,
+    scope: &TenantScope,
+    msg_id: &MessageId,
+) -> Result<(), ErrorResponse> {
+    if message_in_scope(state, scope, msg_id).await {
+        Ok(())
+    } else {
+        Err(ErrorResponse::not_found("Message not found"))
+    }
+}
+
 /// Query parameters for listing messages.
 #[derive(Debug, Deserialize)]
 pub struct ListMessagesQuery {
@@ -39,6 +73,7 @@ pub struct ListMessagesQuery {
 /// GET /api/messages?limit=10&offset=0&severity=warning&status=active
 pub async fn list_messages_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Query(params): Query<ListMessagesQuery>,
 ) -> HandlerResult<serde_json::Value> {
     let limit = params.limit.unwrap_or(50).min(200);
@@ -97,6 +132,9 @@ pub async fn list_messages_handler(
     let filtered: Vec<&Message> = messages
         .iter()
         .filter(|m| {
+            if !scope.allows(&m.tenant_id) {
+                return false;
+            }
             if let Some(ref sev) = params.severity {
                 let msg_sev = format!("{:?}", m.severity).to_lowercase();
                 if msg_sev != sev.to_lowercase().as_str() {
@@ -152,6 +190,7 @@ pub struct CreateMessageRequest {
 /// POST /api/messages
 pub async fn create_message_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<CreateMessageRequest>,
 ) -> HandlerResult<serde_json::Value> {
     let severity = match req.severity.as_str() {
@@ -167,6 +206,7 @@ pub async fn create_message_handler(
     tracing::info!("Creating message: {} - {}", req.title, req.severity);
 
     let mut msg = Message::new(req.category, severity, req.title, req.message, source);
+    msg.tenant_id = scope.owner();
 
     if let Some(source_type) = req.source_type {
         msg.source_type = source_type;
@@ -198,6 +238,7 @@ pub async fn create_message_handler(
 /// GET /api/messages/:id
 pub async fn get_message_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     let msg_id = MessageId(
@@ -209,6 +250,7 @@ pub async fn get_message_handler(
         .message_manager
         .get_message(&msg_id)
         .await
+        .filter(|message| scope.allows(&message.tenant_id))
         .ok_or_else(|| ErrorResponse::not_found("Message not found"))?;
 
     ok(json!(message))
@@ -218,11 +260,13 @@ pub async fn get_message_handler(
 /// DELETE /api/messages/:id
 pub async fn delete_message_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     let msg_id = MessageId(
         uuid::Uuid::parse_str(&id).map_err(|_| ErrorResponse::bad_request("Invalid message ID"))?,
     );
+    check_message_scope(&state, &scope, &msg_id).await?;
 
     state
         .core
@@ -241,11 +285,13 @@ pub async fn delete_message_handler(
 /// POST /api/messages/:id/acknowledge
 pub async fn acknowledge_message_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     let msg_id = MessageId(
         uuid::Uuid::parse_str(&id).map_err(|_| ErrorResponse::bad_request("Invalid message ID"))?,
     );
+    check_message_scope(&state, &scope, &msg_id).await?;
 
     state
         .core
@@ -264,11 +310,13 @@ pub async fn acknowledge_message_handler(
 /// POST /api/messages/:id/resolve
 pub async fn resolve_message_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     let msg_id = MessageId(
         uuid::Uuid::parse_str(&id).map_err(|_| ErrorResponse::bad_request("Invalid message ID"))?,
     );
+    check_message_scope(&state, &scope, &msg_id).await?;
 
     state
         .core
@@ -287,11 +335,13 @@ pub async fn resolve_message_handler(
 /// POST /api/messages/:id/archive
 pub async fn archive_message_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     let msg_id = MessageId(
         uuid::Uuid::parse_str(&id).map_err(|_| ErrorResponse::bad_request("Invalid message ID"))?,
     );
+    check_message_scope(&state, &scope, &msg_id).await?;
 
     state
         .core
@@ -306,15 +356,147 @@ pub async fn archive_message_handler(
     }))
 }
 
+/// Tenant-bound callers may not change settings or data shared by every tenant.
+fn check_instance_scope(scope: &TenantScope) -> Result<(), ErrorResponse> {
+    if *scope == TenantScope::All {
+        Ok(())
+    } else {
+        Err(ErrorResponse::new(
+            "FORBIDDEN",
+            "This operation affects every tenant",
+            axum::http::StatusCode::FORBIDDEN,
+        ))
+    }
+}
+
 /// Message statistics.
 /// GET /api/messages/stats
 pub async fn message_stats_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
 ) -> HandlerResult<serde_json::Value> {
-    let stats = state.core.message_manager.get_stats().await;
+    let stats = state
+        .core
+        .message_manager
+        .get_stats_where(|message| scope.allows(&message.tenant_id))
+        .await;
     ok(json!(stats))
 }
 
+/// Alert deduplication groups, most recently seen first.
+/// GET /api/messages/groups
+pub async fn list_alert_groups_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+) -> HandlerResult<serde_json::Value> {
+    let mut groups = Vec::new();
+    for group in state.core.message_manager.list_alert_groups().await {
+        let visible = match MessageId::from_string(&group.message_id) {
+            Ok(id) => message_in_scope(&state, &scope, &id).await,
+            Err(_) => scope == TenantScope::All,
+        };
+        if visible {
+            groups.push(group);
+        }
+    }
+    ok(json!({
+        "count": groups.len(),
+        "groups": groups,
+    }))
+}
+
+/// Alert grouping configuration.
+/// GET /api/messages/grouping
+pub async fn get_grouping_config_handler(
+    State(state): State<ServerState>,
+) -> HandlerResult<AlertGroupingConfig> {
+    ok(state.core.message_manager.grouping_config().await)
+}
+
+/// Update the alert grouping configuration.
+/// PUT /api/messages/grouping
+pub async fn update_grouping_config_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Json(config): Json<AlertGroupingConfig>,
+) -> HandlerResult<AlertGroupingConfig> {
+    check_instance_scope(&scope)?;
+    if config.dedup_window_secs < 0 || config.flap_window_secs < 0 {
+        return Err(ErrorResponse::bad_request("Windows must not be negative"));
+    }
+
+    let settings_store = neomind_storage::SettingsStore::open("data/settings.redb")
+        .map_err(|e| ErrorResponse::internal(format!("Failed to open settings store: {}", e)))?;
+    let value =
+        serde_json::to_string(&config).map_err(|e| ErrorResponse::internal(e.to_string()))?;
+    settings_store
+        .save(
+            neomind_messages::grouping::ALERT_GROUPING_SETTINGS_KEY,
+            &value,
+        )
+        .map_err(|e| ErrorResponse::internal(format!("Failed to save grouping config: {}", e)))?;
+
+    state
+        .core
+        .message_manager
+        .set_grouping_config(config.clone())
+        .await;
+    ok(config)
+}
+
+/// Escalation policies and on-call schedules.
+/// GET /api/messages/escalation
+pub async fn get_escalation_config_handler(
+    State(state): State<ServerState>,
+) -> HandlerResult<EscalationConfig> {
+    ok(state.core.escalation.config().await)
+}
+
+/// Update escalation policies and on-call schedules.
+/// PUT /api/messages/escalation
+pub async fn update_escalation_config_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Json(config): Json<EscalationConfig>,
+) -> HandlerResult<EscalationConfig> {
+    check_instance_scope(&scope)?;
+    config.validate().map_err(ErrorResponse::bad_request)?;
+
+    let settings_store = neomind_storage::SettingsStore::open("data/settings.redb")
+        .map_err(|e| ErrorResponse::internal(format!("Failed to open settings store: {}", e)))?;
+    let value =
+        serde_json::to_string(&config).map_err(|e| ErrorResponse::internal(e.to_string()))?;
+    settings_store
+        .save(
+            neomind_messages::escalation::ESCALATION_SETTINGS_KEY,
+            &value,
+        )
+        .map_err(|e| ErrorResponse::internal(format!("Failed to save escalation config: {}", e)))?;
+
+    state.core.escalation.set_config(config.clone()).await;
+    ok(config)
+}
+
+/// Who is currently on call in each schedule.
+/// GET /api/messages/escalation/on-call
+pub async fn get_on_call_handler(
+    State(state): State<ServerState>,
+) -> HandlerResult<serde_json::Value> {
+    let now = chrono::Utc::now().timestamp();
+    let on_call: Vec<_> = state
+        .core
+        .escalation
+        .on_call(now)
+        .await
+        .into_iter()
+        .map(|(schedule_id, member)| json!({ "schedule_id": schedule_id, "member": member }))
+        .collect();
+    ok(json!({
+        "timestamp": now,
+        "on_call": on_call,
+    }))
+}
+
 /// Bulk acknowledge messages.
 /// POST /api/messages/acknowledge
 #[derive(Debug, Deserialize)]
@@ -324,6 +506,7 @@ pub struct BulkAcknowledgeRequest {
 
 pub async fn bulk_acknowledge_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<BulkAcknowledgeRequest>,
 ) -> HandlerResult<serde_json::Value> {
     let mut ids = Vec::new();
@@ -332,7 +515,9 @@ pub async fn bulk_acknowledge_handler(
             MessageId(uuid::Uuid::parse_str(id_str).map_err(|_| {
                 ErrorResponse::bad_request(format!("Invalid message ID: {}", id_str))
             })?);
-        ids.push(msg_id);
+        if message_in_scope(&state, &scope, &msg_id).await {
+            ids.push(msg_id);
+        }
     }
 
     let count = state
@@ -351,6 +536,7 @@ pub async fn bulk_acknowledge_handler(
 /// POST /api/messages/resolve
 pub async fn bulk_resolve_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<BulkAcknowledgeRequest>,
 ) -> HandlerResult<serde_json::Value> {
     let mut ids = Vec::new();
@@ -359,7 +545,9 @@ pub async fn bulk_resolve_handler(
             MessageId(uuid::Uuid::parse_str(id_str).map_err(|_| {
                 ErrorResponse::bad_request(format!("Invalid message ID: {}", id_str))
             })?);
-        ids.push(msg_id);
+        if message_in_scope(&state, &scope, &msg_id).await {
+            ids.push(msg_id);
+        }
     }
 
     let count = state
@@ -378,6 +566,7 @@ pub async fn bulk_resolve_handler(
 /// POST /api/messages/delete
 pub async fn bulk_delete_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<BulkAcknowledgeRequest>,
 ) -> HandlerResult<serde_json::Value> {
     let mut ids = Vec::new();
@@ -386,7 +575,9 @@ pub async fn bulk_delete_handler(
             MessageId(uuid::Uuid::parse_str(id_str).map_err(|_| {
                 ErrorResponse::bad_request(format!("Invalid message ID: {}", id_str))
             })?);
-        ids.push(msg_id);
+        if message_in_scope(&state, &scope, &msg_id).await {
+            ids.push(msg_id);
+        }
     }
 
     let count = state
@@ -410,8 +601,10 @@ pub struct CleanupRequest {
 
 pub async fn cleanup_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<CleanupRequest>,
 ) -> HandlerResult<serde_json::Value> {
+    check_instance_scope(&scope)?;
     let count = state
         .core
         .message_manager
@@ -436,6 +629,16 @@ pub fn messages_router() -> axum::Router<ServerState> {
             get(list_messages_handler).post(create_message_handler),
         )
         .route("/messages/stats", get(message_stats_handler))
+        .route("/messages/groups", get(list_alert_groups_handler))
+        .route(
+            "/messages/grouping",
+            get(get_grouping_config_handler).put(update_grouping_config_handler),
+        )
+        .route(
+            "/messages/escalation",
+            get(get_escalation_config_handler).put(update_escalation_config_handler),
+        )
+        .route("/messages/escalation/on-call", get(get_on_call_handler))
         .route("/messages/cleanup", post(cleanup_handler))
         .route("/messages/acknowledge", post(bulk_acknowledge_handler))
         .route("/messages/resolve", post(bulk_resolve_handler))
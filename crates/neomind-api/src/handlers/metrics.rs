@@ -0,0 +1,38 @@
+//! Prometheus metrics exporter (feature `prometheus`).
+
+use axum::http::header;
+use axum::response::IntoResponse;
+
+/// Content type of the Prometheus text exposition format.
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// `GET /metrics` — render the shared `neomind_core::metrics` registry.
+pub async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+        neomind_core::metrics::global().render(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metrics_handler_renders_registry() {
+        neomind_core::metrics::global()
+            .counter("neomind_test_handler_total", "Test counter", &[])
+            .inc();
+
+        let response = metrics_handler().await.into_response();
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            PROMETHEUS_CONTENT_TYPE
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("neomind_test_handler_total 1"));
+    }
+}
@@ -1,6 +1,8 @@
 //! API handlers organized by domain.
 
 pub mod agents;
+pub mod approvals;
+pub mod audio;
 pub mod auth;
 pub mod auth_users;
 pub mod automations;
@@ -12,28 +14,40 @@ pub mod dashboards;
 pub mod data;
 pub mod data_push;
 pub mod devices;
+pub mod energy;
 pub mod events;
+pub mod exports;
 pub mod extension_stream;
 pub mod extensions;
 pub mod frontend_components;
 pub mod images;
 pub mod instances;
+pub mod intent;
+pub mod knowledge;
 pub mod llm_backends;
 pub mod logs;
+pub mod maintenance;
 pub mod memory;
 pub mod message_channels;
 pub mod messages;
+#[cfg(feature = "prometheus")]
+pub mod metrics;
 pub mod mqtt;
 pub mod onboarding;
+pub mod provisioning;
+pub mod reports;
 pub mod rules;
+pub mod secrets;
 pub mod sessions;
 pub mod settings;
 pub mod setup;
 pub mod skills;
+pub mod state_machines;
 pub mod stats;
 pub mod suggestions;
 pub mod summarization;
 pub mod tools;
+pub mod usage;
 pub mod ws;
 
 // Re-export ServerState so handlers can use it
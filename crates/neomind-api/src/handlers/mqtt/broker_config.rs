@@ -48,6 +48,14 @@ struct BrokerConfigDto {
     /// TLS CA certificate path (if configured)
     #[serde(skip_serializing_if = "Option::is_none")]
     tls_ca_path: Option<String>,
+    /// Client certificates required (mutual TLS)
+    tls_client_auth: bool,
+    /// MQTT 5 shared subscriptions (`$share/<group>/<filter>`) allowed
+    shared_subscriptions: bool,
+    /// Maximum session expiry interval in seconds
+    session_expiry_secs: u64,
+    /// Maximum MQTT 5 topic aliases per connection
+    max_topic_aliases: u16,
     /// User credentials (excluding internal system credentials)
     credentials: Vec<CredentialDto>,
 }
@@ -85,6 +93,18 @@ pub struct UpdateBrokerConfigRequest {
     /// Enable TLS
     #[serde(default)]
     tls_enabled: Option<bool>,
+    /// Require client certificates (needs TLS)
+    #[serde(default)]
+    tls_client_auth: Option<bool>,
+    /// Allow MQTT 5 shared subscriptions
+    #[serde(default)]
+    shared_subscriptions: Option<bool>,
+    /// Maximum session expiry interval in seconds
+    #[serde(default)]
+    session_expiry_secs: Option<u64>,
+    /// Maximum MQTT 5 topic aliases per connection (0 disables aliases)
+    #[serde(default)]
+    max_topic_aliases: Option<u16>,
 }
 
 /// Request body for adding a new credential.
@@ -143,6 +163,10 @@ pub async fn get_broker_config_handler() -> HandlerResult<serde_json::Value> {
         tls_cert_path: config.tls_cert_path,
         tls_key_path: config.tls_key_path,
         tls_ca_path: config.tls_ca_path,
+        tls_client_auth: config.tls_client_auth,
+        shared_subscriptions: config.shared_subscriptions,
+        session_expiry_secs: config.session_expiry_secs,
+        max_topic_aliases: config.max_topic_aliases,
         credentials,
     };
 
@@ -217,14 +241,29 @@ pub async fn update_broker_config_handler(
         }
         config.tls_enabled = tls_enabled;
     }
+    if let Some(client_auth) = req.tls_client_auth {
+        config.tls_client_auth = client_auth;
+    }
+    if config.tls_client_auth && !config.tls_enabled {
+        return Err(ErrorResponse::bad_request(
+            "Client certificate authentication requires TLS to be enabled".to_string(),
+        ));
+    }
+    if let Some(shared) = req.shared_subscriptions {
+        config.shared_subscriptions = shared;
+    }
+    if let Some(secs) = req.session_expiry_secs {
+        config.session_expiry_secs = secs;
+    }
+    if let Some(max) = req.max_topic_aliases {
+        config.max_topic_aliases = max;
+    }
 
     // All changes require a broker restart.
     // When auth_enabled changes, external_auth is only set when enabled,
     // so the broker must restart to add/remove the auth handler.
     #[cfg(feature = "embedded-broker")]
-    let needs_restart = old_config.listen != config.listen
-        || old_config.port != config.port
-        || old_config.tls_enabled != config.tls_enabled;
+    let needs_restart = old_config.requires_restart(&config);
 
     // Auto-generate system credential if enabling auth for the first time
     if config.auth_enabled {
@@ -268,7 +307,9 @@ pub async fn update_broker_config_handler(
     // — no broker restart needed, just update the flag.
     #[cfg(feature = "embedded-broker")]
     if needs_restart {
-        tracing::info!("Broker restart required (port/TLS changed). Applying restart...");
+        tracing::info!(
+            "Broker restart required (port/TLS/MQTT 5 options changed). Applying restart..."
+        );
         match _state.restart_embedded_broker().await {
             Ok(()) => {
                 return ok(json!({
@@ -188,6 +188,11 @@ pub async fn create_and_connect_broker(
     // If the external broker points to the embedded broker (localhost) and has
     // no credentials, inject the system credential so it can authenticate.
     let (resolved_username, resolved_password) = resolve_broker_credentials(broker);
+    // The password may be a `secret://` handle
+    let resolved_password = resolved_password
+        .map(|p| neomind_core::secrets::resolve(&p).map(|p| p.into_owned()))
+        .transpose()
+        .map_err(|e| e.to_string())?;
 
     // Create MqttAdapter config
     let mqtt_config = MqttAdapterConfig {
@@ -216,6 +221,7 @@ pub async fn create_and_connect_broker(
         discovery_prefix: "neomind".to_string(),
         auto_discovery: false,
         storage_dir: Some("data".to_string()),
+        auto_mapping: Default::default(),
     };
 
     // Create the MQTT adapter
@@ -771,12 +777,18 @@ pub async fn test_broker_handler(Path(id): Path<String>) -> HandlerResult<serde_
         "Testing MQTT connection with CONNECT/CONNACK"
     );
 
+    let password = broker
+        .password
+        .as_deref()
+        .map(neomind_core::secrets::resolve)
+        .transpose()?;
+
     // Perform real MQTT handshake via neomind-devices
     let result = neomind_devices::adapters::mqtt::test_mqtt_connection(
         &broker_host,
         broker_port,
         broker.username.as_deref(),
-        broker.password.as_deref(),
+        password.as_deref(),
         broker.tls,
         broker.ca_cert.as_deref(),
         broker.client_cert.as_deref(),
@@ -1,7 +1,8 @@
 //! Self-signed TLS certificate generation for the embedded MQTT broker.
 //!
 //! Generates a CA certificate and a server certificate signed by that CA,
-//! suitable for encrypting MQTT connections in IoT scenarios.
+//! suitable for encrypting MQTT connections in IoT scenarios. The same CA
+//! issues client certificates for provisioned devices (mutual TLS).
 
 use std::net::IpAddr;
 use std::path::PathBuf;
@@ -19,6 +20,16 @@ pub struct CertPaths {
     pub server_key_path: String,
 }
 
+/// PEM material of a device client certificate.
+pub struct ClientCert {
+    pub cert_pem: String,
+    pub key_pem: String,
+    /// CA certificate the device uses to verify the broker
+    pub ca_pem: String,
+    /// Hex-encoded SHA-256 fingerprint of the DER certificate
+    pub fingerprint: String,
+}
+
 /// Get the TLS directory for storing certificates.
 /// Uses `NEOMIND_DATA_DIR` env var (same as the rest of the project) or falls back to `data`.
 fn get_tls_dir() -> PathBuf {
@@ -75,21 +86,7 @@ pub fn generate_self_signed_certs() -> Result<CertPaths, String> {
     // --- CA key + self-signed cert ---
     let ca_key = KeyPair::generate().map_err(|e| format!("Failed to generate CA key: {}", e))?;
 
-    let mut ca_params = CertificateParams::new(Vec::<String>::new())
-        .map_err(|e| format!("Failed to create CA params: {}", e))?;
-    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
-    ca_params
-        .distinguished_name
-        .push(DnType::CommonName, "NeoMind MQTT CA");
-    ca_params
-        .distinguished_name
-        .push(DnType::OrganizationName, "NeoMind");
-
-    // CA Key Usage: certificate signing and CRL signing
-    ca_params.key_usages.push(KeyUsagePurpose::KeyCertSign);
-    ca_params.key_usages.push(KeyUsagePurpose::CrlSign);
-    ca_params.key_usages.push(KeyUsagePurpose::DigitalSignature);
-
+    let mut ca_params = ca_params()?;
     ca_params.not_before = not_before;
     ca_params.not_after = ca_not_after;
 
@@ -157,7 +154,9 @@ pub fn generate_self_signed_certs() -> Result<CertPaths, String> {
         .map_err(|e| format!("Failed to write CA cert: {}", e))?;
     std::fs::write(&ca_key_path, ca_key.serialize_pem())
         .map_err(|e| format!("Failed to write CA key: {}", e))?;
-    std::fs::write(&server_cert_path, server_cert.pem())
+    // Server cert file holds the full chain so TLS clients (and client
+    // certificate verification) can build the path to the CA.
+    std::fs::write(&server_cert_path, format!("{}{}", server_cert.pem(), ca_cert.pem()))
         .map_err(|e| format!("Failed to write server cert: {}", e))?;
     std::fs::write(&server_key_path, server_key.serialize_pem())
         .map_err(|e| format!("Failed to write server key: {}", e))?;
@@ -187,3 +186,77 @@ pub fn generate_self_signed_certs() -> Result<CertPaths, String> {
         server_key_path: server_key_path.to_string_lossy().to_string(),
     })
 }
+
+/// Certificate parameters of the broker CA.
+///
+/// Also used to rebuild the issuer when signing client certificates; only
+/// the distinguished name and key have to match the stored CA.
+fn ca_params() -> Result<CertificateParams, String> {
+    let mut ca_params = CertificateParams::new(Vec::<String>::new())
+        .map_err(|e| format!("Failed to create CA params: {}", e))?;
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    ca_params
+        .distinguished_name
+        .push(DnType::CommonName, "NeoMind MQTT CA");
+    ca_params
+        .distinguished_name
+        .push(DnType::OrganizationName, "NeoMind");
+
+    // CA Key Usage: certificate signing and CRL signing
+    ca_params.key_usages.push(KeyUsagePurpose::KeyCertSign);
+    ca_params.key_usages.push(KeyUsagePurpose::CrlSign);
+    ca_params.key_usages.push(KeyUsagePurpose::DigitalSignature);
+    Ok(ca_params)
+}
+
+/// Issue a client certificate signed by the broker CA.
+///
+/// The common name is the device's MQTT username. The certificate is valid
+/// for 1 year. Requires the CA created by [`generate_self_signed_certs`].
+pub fn generate_client_cert(common_name: &str) -> Result<ClientCert, String> {
+    let tls_dir = get_tls_dir();
+    let ca_pem = std::fs::read_to_string(tls_dir.join("mqtt-ca.crt"))
+        .map_err(|e| format!("CA certificate not available (generate TLS first): {}", e))?;
+    let ca_key_pem = std::fs::read_to_string(tls_dir.join("mqtt-ca.key"))
+        .map_err(|e| format!("CA key not available (generate TLS first): {}", e))?;
+    let ca_key =
+        KeyPair::from_pem(&ca_key_pem).map_err(|e| format!("Failed to load CA key: {}", e))?;
+    let issuer = ca_params()?
+        .self_signed(&ca_key)
+        .map_err(|e| format!("Failed to load CA: {}", e))?;
+
+    let now = time::OffsetDateTime::now_utc();
+    let client_key =
+        KeyPair::generate().map_err(|e| format!("Failed to generate client key: {}", e))?;
+    let mut params = CertificateParams::new(Vec::<String>::new())
+        .map_err(|e| format!("Failed to create client params: {}", e))?;
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    params.key_usages.push(KeyUsagePurpose::DigitalSignature);
+    // Extended Key Usage: TLS client authentication
+    params
+        .extended_key_usages
+        .push(ExtendedKeyUsagePurpose::ClientAuth);
+    params.not_before = now - time::Duration::hours(1);
+    params.not_after = now + time::Duration::days(365);
+
+    let cert = params
+        .signed_by(&client_key, &issuer, &ca_key)
+        .map_err(|e| format!("Failed to sign client cert: {}", e))?;
+
+    let fingerprint = {
+        use sha2::{Digest, Sha256};
+        Sha256::digest(cert.der())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    };
+
+    Ok(ClientCert {
+        cert_pem: cert.pem(),
+        key_pem: client_key.serialize_pem(),
+        ca_pem,
+        fingerprint,
+    })
+}
@@ -0,0 +1,293 @@
+//! Device provisioning handlers.
+//!
+//! GET    /api/provisioning/claims              - List pending claim codes
+//! POST   /api/provisioning/claims              - Create a claim code for a device
+//! DELETE /api/provisioning/claims/:code        - Cancel a claim code
+//! POST   /api/provisioning/claim               - Redeem a claim code (called by the device)
+//! GET    /api/provisioning/devices             - List device identities
+//! POST   /api/provisioning/devices/:id/revoke  - Revoke a device identity
+//!
+//! A technician creates a short-lived claim code for a device id and enters
+//! it on the device, which redeems it once for its own MQTT credentials (plus
+//! a client certificate when mutual TLS is used). The embedded broker only
+//! lets each identity use topics containing its device id. Revoking an
+//! identity takes effect immediately, without a broker restart.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use neomind_storage::{DeviceAuthMethod, DeviceClaim, DeviceIdentity};
+
+use super::common::{ok, HandlerResult};
+use crate::config;
+use crate::handlers::mqtt::cert_gen;
+use crate::models::error::ErrorResponse;
+use crate::server::ServerState;
+
+/// Default claim code lifetime (24 hours).
+const DEFAULT_CLAIM_TTL_SECS: u64 = 24 * 3600;
+
+/// Maximum claim code lifetime (7 days).
+const MAX_CLAIM_TTL_SECS: u64 = 7 * 24 * 3600;
+
+/// Claim code alphabet without easily confused characters (0/O, 1/I/L).
+const CLAIM_CODE_CHARSET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+/// Request to create a claim code.
+#[derive(Debug, Deserialize)]
+pub struct CreateClaimRequest {
+    pub device_id: String,
+    #[serde(default)]
+    pub auth_method: DeviceAuthMethod,
+    /// Lifetime of the code in seconds (default 24h, max 7 days)
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+/// Request to redeem a claim code.
+#[derive(Debug, Deserialize)]
+pub struct RedeemClaimRequest {
+    pub code: String,
+}
+
+/// Device identity as returned by the API (without the password hash).
+#[derive(Debug, Serialize)]
+pub struct DeviceIdentityDto {
+    pub device_id: String,
+    pub username: String,
+    pub auth_method: DeviceAuthMethod,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cert_fingerprint: Option<String>,
+    pub claimed_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<i64>,
+}
+
+impl From<DeviceIdentity> for DeviceIdentityDto {
+    fn from(identity: DeviceIdentity) -> Self {
+        Self {
+            device_id: identity.device_id,
+            username: identity.username,
+            auth_method: identity.auth_method,
+            cert_fingerprint: identity.cert_fingerprint,
+            claimed_at: identity.claimed_at,
+            revoked_at: identity.revoked_at,
+        }
+    }
+}
+
+fn settings_store() -> Result<std::sync::Arc<neomind_storage::SettingsStore>, ErrorResponse> {
+    config::open_settings_store()
+        .map_err(|e| ErrorResponse::internal(format!("Failed to open settings store: {}", e)))
+}
+
+/// Device ids become MQTT topic levels, so they must not contain separators
+/// or wildcards.
+fn validate_device_id(device_id: &str) -> Result<(), ErrorResponse> {
+    let valid = !device_id.is_empty()
+        && device_id.len() <= 64
+        && !device_id
+            .chars()
+            .any(|c| c == '/' || c == '+' || c == '#' || c.is_whitespace());
+    if valid {
+        Ok(())
+    } else {
+        Err(ErrorResponse::bad_request(
+            "Device id must be 1-64 characters without '/', '+', '#' or whitespace".to_string(),
+        ))
+    }
+}
+
+/// Generate a claim code like `K7QX-2MPD`.
+fn generate_claim_code() -> String {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    let chars: String = (0..8)
+        .map(|_| CLAIM_CODE_CHARSET[rng.gen_range(0..CLAIM_CODE_CHARSET.len())] as char)
+        .collect();
+    format!("{}-{}", &chars[..4], &chars[4..])
+}
+
+/// Generate a random device password.
+fn generate_device_password() -> String {
+    use rand::{distributions::Alphanumeric, Rng};
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+/// Reload the broker's credential cache after identities changed.
+#[cfg(feature = "embedded-broker")]
+fn refresh_credential_cache(state: &ServerState, store: &neomind_storage::SettingsStore) {
+    match crate::server::types::CredentialCache::load_from_store(store) {
+        Ok(cache) => *state.credential_cache.write().unwrap() = cache,
+        Err(e) => tracing::error!("Failed to refresh credential cache: {}", e),
+    }
+}
+
+#[cfg(not(feature = "embedded-broker"))]
+fn refresh_credential_cache(_state: &ServerState, _store: &neomind_storage::SettingsStore) {}
+
+/// `GET /api/provisioning/claims` — pending claim codes.
+pub async fn list_claims_handler() -> HandlerResult<serde_json::Value> {
+    let now = chrono::Utc::now().timestamp();
+    let claims: Vec<DeviceClaim> = settings_store()?
+        .list_device_claims()?
+        .into_iter()
+        .filter(|c| !c.is_expired(now))
+        .collect();
+    ok(json!({
+        "count": claims.len(),
+        "claims": claims,
+    }))
+}
+
+/// `POST /api/provisioning/claims` — create a one-time claim code.
+pub async fn create_claim_handler(
+    Json(req): Json<CreateClaimRequest>,
+) -> HandlerResult<DeviceClaim> {
+    validate_device_id(&req.device_id)?;
+    let ttl = req.ttl_secs.unwrap_or(DEFAULT_CLAIM_TTL_SECS);
+    if ttl == 0 || ttl > MAX_CLAIM_TTL_SECS {
+        return Err(ErrorResponse::bad_request(format!(
+            "ttl_secs must be between 1 and {}",
+            MAX_CLAIM_TTL_SECS
+        )));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let claim = DeviceClaim {
+        code: generate_claim_code(),
+        device_id: req.device_id,
+        auth_method: req.auth_method,
+        created_at: now,
+        expires_at: now + ttl as i64,
+    };
+    settings_store()?.create_device_claim(&claim)?;
+
+    tracing::info!(device_id = %claim.device_id, "Created device claim code");
+    ok(claim)
+}
+
+/// `DELETE /api/provisioning/claims/:code` — cancel a claim code.
+pub async fn delete_claim_handler(Path(code): Path<String>) -> HandlerResult<serde_json::Value> {
+    if !settings_store()?.delete_device_claim(&code)? {
+        return Err(ErrorResponse::not_found(format!("Claim code '{}'", code)));
+    }
+    ok(json!({ "deleted": code }))
+}
+
+/// `POST /api/provisioning/claim` — redeem a claim code.
+///
+/// Called by the device itself, so the route is unauthenticated (but rate
+/// limited); the claim code is the credential. Re-claiming a device replaces
+/// its previous identity. The returned password and key are not stored in
+/// plain text and can't be retrieved again.
+pub async fn redeem_claim_handler(
+    State(state): State<ServerState>,
+    Json(req): Json<RedeemClaimRequest>,
+) -> HandlerResult<serde_json::Value> {
+    let store = settings_store()?;
+    let code = req.code.trim().to_ascii_uppercase();
+    let claim = store
+        .take_device_claim(&code)?
+        .ok_or_else(|| ErrorResponse::not_found("Claim code".to_string()))?;
+    let now = chrono::Utc::now().timestamp();
+    if claim.is_expired(now) {
+        return Err(ErrorResponse::bad_request("Claim code has expired".to_string()));
+    }
+
+    let username = DeviceIdentity::username_for(&claim.device_id);
+    let client_cert = match claim.auth_method {
+        DeviceAuthMethod::Password => None,
+        DeviceAuthMethod::Certificate => match cert_gen::generate_client_cert(&username) {
+            Ok(cert) => Some(cert),
+            Err(e) => {
+                // Keep the code usable once the broker CA is in place
+                store.create_device_claim(&claim)?;
+                return Err(ErrorResponse::internal(format!(
+                    "Failed to issue client certificate: {}",
+                    e
+                )));
+            }
+        },
+    };
+
+    let password = generate_device_password();
+    let password_hash = bcrypt::hash(&password, 12)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to hash password: {}", e)))?;
+    let identity = DeviceIdentity {
+        device_id: claim.device_id.clone(),
+        username: username.clone(),
+        password_hash,
+        auth_method: claim.auth_method,
+        cert_fingerprint: client_cert.as_ref().map(|c| c.fingerprint.clone()),
+        claimed_at: now,
+        revoked_at: None,
+    };
+    store.save_device_identity(&identity)?;
+    refresh_credential_cache(&state, &store);
+    neomind_core::secrets::register_value(&password);
+
+    tracing::info!(
+        device_id = %identity.device_id,
+        auth_method = ?identity.auth_method,
+        "Device claimed broker identity"
+    );
+
+    let broker = config::get_embedded_broker_config();
+    ok(json!({
+        "device_id": identity.device_id,
+        "username": username,
+        "password": password,
+        "broker": {
+            "port": broker.port,
+            "tls": broker.tls_enabled,
+            "client_auth": broker.tls_client_auth,
+        },
+        "topic_filter": format!("device/+/{}/#", identity.device_id),
+        "client_cert": client_cert.map(|c| json!({
+            "cert_pem": c.cert_pem,
+            "key_pem": c.key_pem,
+            "ca_pem": c.ca_pem,
+            "fingerprint": c.fingerprint,
+        })),
+    }))
+}
+
+/// `GET /api/provisioning/devices` — issued device identities.
+pub async fn list_identities_handler() -> HandlerResult<serde_json::Value> {
+    let identities: Vec<DeviceIdentityDto> = settings_store()?
+        .list_device_identities()?
+        .into_iter()
+        .map(DeviceIdentityDto::from)
+        .collect();
+    ok(json!({
+        "count": identities.len(),
+        "devices": identities,
+    }))
+}
+
+/// `POST /api/provisioning/devices/:id/revoke` — revoke a device identity.
+///
+/// The device can no longer connect, and an open connection can no longer
+/// publish or subscribe.
+pub async fn revoke_identity_handler(
+    State(state): State<ServerState>,
+    Path(device_id): Path<String>,
+) -> HandlerResult<DeviceIdentityDto> {
+    let store = settings_store()?;
+    let identity = store
+        .revoke_device_identity(&device_id)?
+        .ok_or_else(|| ErrorResponse::not_found(format!("Device identity '{}'", device_id)))?;
+    refresh_credential_cache(&state, &store);
+
+    tracing::info!(device_id = %device_id, "Revoked device identity");
+    ok(identity.into())
+}
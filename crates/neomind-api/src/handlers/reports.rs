@@ -0,0 +1,281 @@
+//! Report template and generated report handlers.
+//!
+//! GET    /api/reports/templates         - List templates
+//! POST   /api/reports/templates         - Create a template
+//! GET    /api/reports/templates/:id     - Get a template
+//! PUT    /api/reports/templates/:id     - Replace a template
+//! DELETE /api/reports/templates/:id     - Delete a template
+//! POST   /api/reports/templates/:id/run - Generate a report now
+//! GET    /api/reports                   - List generated reports
+//! GET    /api/reports/:id               - Report record and delivery outcome
+//! GET    /api/reports/:id/download      - Download the rendered file
+//! DELETE /api/reports/:id               - Delete a report and its file
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use neomind_core::tenant::TenantScope;
+use neomind_storage::{ReportRecord, ReportTemplate};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::common::{ok, HandlerResult};
+use super::devices::crud::check_device_scope;
+use crate::auth::RequestTenant;
+use crate::models::error::ErrorResponse;
+use crate::server::ServerState;
+
+#[derive(Debug, Deserialize)]
+pub struct ListReportsQuery {
+    pub template_id: Option<String>,
+}
+
+fn storage_error(e: neomind_storage::Error) -> ErrorResponse {
+    ErrorResponse::internal(format!("Report storage error: {}", e))
+}
+
+/// Look up a template visible in the request's tenant scope.
+fn load_template(
+    state: &ServerState,
+    scope: &TenantScope,
+    id: &str,
+) -> Result<ReportTemplate, ErrorResponse> {
+    state
+        .reports
+        .store()
+        .get_template(id)
+        .map_err(storage_error)?
+        .filter(|template| scope.allows(&template.tenant_id))
+        .ok_or_else(|| ErrorResponse::not_found(format!("Report template not found: {}", id)))
+}
+
+/// Look up a generated report visible in the request's tenant scope.
+fn load_report(
+    state: &ServerState,
+    scope: &TenantScope,
+    id: &str,
+) -> Result<ReportRecord, ErrorResponse> {
+    state
+        .reports
+        .store()
+        .get_report(id)
+        .map_err(storage_error)?
+        .filter(|record| scope.allows(&record.tenant_id))
+        .ok_or_else(|| ErrorResponse::not_found(format!("Report not found: {}", id)))
+}
+
+/// Check the template itself, its devices, its cron expression and its
+/// channels.
+async fn validate_template(
+    state: &ServerState,
+    scope: &TenantScope,
+    template: &ReportTemplate,
+) -> Result<(), ErrorResponse> {
+    template.validate().map_err(ErrorResponse::bad_request)?;
+    for section in &template.sections {
+        for device_id in &section.device_ids {
+            check_device_scope(state, scope, device_id)?;
+        }
+    }
+    if let Some(schedule) = &template.schedule {
+        schedule.parse::<cron::Schedule>().map_err(|e| {
+            ErrorResponse::bad_request(format!("Invalid cron expression '{}': {}", schedule, e))
+        })?;
+    }
+    let registry = state.core.message_manager.channels().await;
+    let channels = registry.read().await;
+    for name in &template.channels {
+        if channels.get(name).await.is_none() {
+            return Err(ErrorResponse::bad_request(format!("Message channel not found: {}", name)));
+        }
+    }
+    Ok(())
+}
+
+/// `GET /api/reports/templates` — all templates, sorted by name.
+pub async fn list_templates_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+) -> HandlerResult<serde_json::Value> {
+    let templates: Vec<ReportTemplate> = state
+        .reports
+        .store()
+        .list_templates()
+        .map_err(storage_error)?
+        .into_iter()
+        .filter(|template| scope.allows(&template.tenant_id))
+        .collect();
+    ok(json!({
+        "count": templates.len(),
+        "templates": templates,
+    }))
+}
+
+/// `POST /api/reports/templates` — create a template; an id is generated
+/// when none is given.
+pub async fn create_template_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Json(mut template): Json<ReportTemplate>,
+) -> HandlerResult<ReportTemplate> {
+    validate_template(&state, &scope, &template).await?;
+    if template.id.is_empty() {
+        template.id = uuid::Uuid::new_v4().to_string();
+    }
+    let store = state.reports.store();
+    let existing = store.get_template(&template.id).map_err(storage_error)?;
+    if existing.is_some() {
+        return Err(ErrorResponse::bad_request(format!(
+            "Report template already exists: {}",
+            template.id
+        )));
+    }
+    let now = chrono::Utc::now().timestamp();
+    template.created_at = now;
+    template.updated_at = now;
+    template.last_run_at = None;
+    template.tenant_id = scope.owner();
+    store.save_template(&template).map_err(storage_error)?;
+    ok(template)
+}
+
+/// `GET /api/reports/templates/:id`
+pub async fn get_template_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<ReportTemplate> {
+    ok(load_template(&state, &scope, &id)?)
+}
+
+/// `PUT /api/reports/templates/:id` — replace a template, keeping its
+/// creation time, last run and owner.
+pub async fn update_template_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+    Json(mut template): Json<ReportTemplate>,
+) -> HandlerResult<ReportTemplate> {
+    let existing = load_template(&state, &scope, &id)?;
+    validate_template(&state, &scope, &template).await?;
+    template.id = id;
+    template.created_at = existing.created_at;
+    template.updated_at = chrono::Utc::now().timestamp();
+    template.last_run_at = existing.last_run_at;
+    template.tenant_id = existing.tenant_id;
+    state
+        .reports
+        .store()
+        .save_template(&template)
+        .map_err(storage_error)?;
+    ok(template)
+}
+
+/// `DELETE /api/reports/templates/:id` — generated reports are kept.
+pub async fn delete_template_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    load_template(&state, &scope, &id)?;
+    let deleted = state
+        .reports
+        .store()
+        .delete_template(&id)
+        .map_err(storage_error)?;
+    if !deleted {
+        return Err(ErrorResponse::not_found(format!("Report template not found: {}", id)));
+    }
+    ok(json!({ "deleted": id }))
+}
+
+/// `POST /api/reports/templates/:id/run` — generate and deliver a report
+/// now, regardless of the template's schedule.
+pub async fn run_template_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<ReportRecord> {
+    let template = load_template(&state, &scope, &id)?;
+    let record = state
+        .reports
+        .generate(&template, "manual")
+        .await
+        .map_err(ErrorResponse::internal)?;
+    ok(record)
+}
+
+/// `GET /api/reports?template_id=` — generated reports, newest first.
+pub async fn list_reports_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Query(query): Query<ListReportsQuery>,
+) -> HandlerResult<serde_json::Value> {
+    let reports: Vec<ReportRecord> = state
+        .reports
+        .store()
+        .list_reports(query.template_id.as_deref())
+        .map_err(storage_error)?
+        .into_iter()
+        .filter(|record| scope.allows(&record.tenant_id))
+        .collect();
+    ok(json!({
+        "count": reports.len(),
+        "reports": reports,
+    }))
+}
+
+/// `GET /api/reports/:id`
+pub async fn get_report_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<ReportRecord> {
+    ok(load_report(&state, &scope, &id)?)
+}
+
+/// `GET /api/reports/:id/download` — the rendered HTML or PDF file.
+pub async fn download_report_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> Result<Response, ErrorResponse> {
+    let record = load_report(&state, &scope, &id)?;
+    let bytes = tokio::fs::read(state.reports.file_path(&record))
+        .await
+        .map_err(|e| ErrorResponse::not_found(format!("Report file not found: {}", e)))?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, record.format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", record.file_name()),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// `DELETE /api/reports/:id` — delete a report and its file.
+pub async fn delete_report_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    load_report(&state, &scope, &id)?;
+    let deleted = state
+        .reports
+        .delete_report(&id)
+        .await
+        .map_err(ErrorResponse::internal)?;
+    if !deleted {
+        return Err(ErrorResponse::not_found(format!("Report not found: {}", id)));
+    }
+    ok(json!({ "deleted": id }))
+}
@@ -7,6 +7,7 @@ use axum::{
 use chrono;
 use serde_json::{json, Value};
 
+use neomind_core::tenant::TenantScope;
 use neomind_devices::MetricDataType as DeviceMetricDataType;
 use neomind_rules::{
     ComparisonOperator, CompiledRule, LogicalOperator, NotifySeverity, RuleAction, RuleCondition,
@@ -17,6 +18,7 @@ use super::{
     common::{ok, HandlerResult},
     ServerState,
 };
+use crate::auth::RequestTenant;
 use crate::models::ErrorResponse;
 
 /// Detailed rule info for API responses.
@@ -297,13 +299,46 @@ impl From<&CompiledRule> for RuleDetailDto {
     }
 }
 
+/// Look up a rule, treating rules owned by another tenant as missing.
+async fn scoped_rule(
+    state: &ServerState,
+    scope: &TenantScope,
+    rule_id: &RuleId,
+) -> Result<CompiledRule, ErrorResponse> {
+    state
+        .automation
+        .rule_engine
+        .get_rule(rule_id)
+        .await
+        .filter(|rule| scope.allows(&rule.tenant_id))
+        .ok_or_else(|| ErrorResponse::not_found("Rule"))
+}
+
+/// Whether a rule with this ID exists outside `scope`.
+async fn owned_elsewhere(state: &ServerState, scope: &TenantScope, rule_id: &RuleId) -> bool {
+    state
+        .automation
+        .rule_engine
+        .get_rule(rule_id)
+        .await
+        .is_some_and(|rule| !scope.allows(&rule.tenant_id))
+}
+
+/// Rules visible in `scope`.
+async fn scoped_rules(state: &ServerState, scope: &TenantScope) -> Vec<CompiledRule> {
+    let mut rules = state.automation.rule_engine.list_rules().await;
+    rules.retain(|rule| scope.allows(&rule.tenant_id));
+    rules
+}
+
 /// List rules.
 ///
 /// GET /api/rules
 pub async fn list_rules_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
 ) -> HandlerResult<serde_json::Value> {
-    let rules = state.automation.rule_engine.list_rules().await;
+    let rules = scoped_rules(&state, &scope).await;
     let dtos: Vec<RuleDto> = rules
         .into_iter()
         .map(|r| {
@@ -362,17 +397,13 @@ pub async fn list_rules_handler(
 /// GET /api/rules/:id
 pub async fn get_rule_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     let rule_id = RuleId::from_string(&id)
         .map_err(|_| ErrorResponse::bad_request(format!("Invalid rule ID: {}", id)))?;
 
-    let rule = state
-        .automation
-        .rule_engine
-        .get_rule(&rule_id)
-        .await
-        .ok_or_else(|| ErrorResponse::not_found("Rule"))?;
+    let rule = scoped_rule(&state, &scope, &rule_id).await?;
 
     let dto = RuleDetailDto::from(&rule);
     let history = state
@@ -392,6 +423,7 @@ pub async fn get_rule_handler(
 /// PUT /api/rules/:id
 pub async fn update_rule_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
     Json(req): Json<serde_json::Value>,
 ) -> HandlerResult<serde_json::Value> {
@@ -446,11 +478,16 @@ pub async fn update_rule_handler(
         // Set source from frontend if provided
         rule.source = source;
 
-        // Preserve runtime state, created_at, and enabled from existing rule
+        // Preserve runtime state, created_at, owner and enabled from existing rule
         let existing = state.automation.rule_engine.get_rule(&rule_id).await;
+        if existing.as_ref().is_some_and(|old| !scope.allows(&old.tenant_id)) {
+            return Err(ErrorResponse::not_found("Rule"));
+        }
+        rule.tenant_id = scope.owner();
         if let Some(ref old) = existing {
             rule.state = old.state.clone();
             rule.created_at = old.created_at;
+            rule.tenant_id = old.tenant_id.clone();
         }
         rule.enabled =
             enabled.unwrap_or_else(|| existing.as_ref().map(|r| r.enabled).unwrap_or(true));
@@ -519,12 +556,7 @@ pub async fn update_rule_handler(
     }
 
     // Partial update: get the current rule and update only provided fields
-    let mut rule = state
-        .automation
-        .rule_engine
-        .get_rule(&rule_id)
-        .await
-        .ok_or_else(|| ErrorResponse::not_found("Rule"))?;
+    let mut rule = scoped_rule(&state, &scope, &rule_id).await?;
 
     // Update fields
     if let Some(name) = name {
@@ -577,11 +609,14 @@ pub async fn update_rule_handler(
 /// DELETE /api/rules/:id
 pub async fn delete_rule_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     let rule_id = RuleId::from_string(&id)
         .map_err(|_| ErrorResponse::bad_request(format!("Invalid rule ID: {}", id)))?;
 
+    scoped_rule(&state, &scope, &rule_id).await?;
+
     let removed = state
         .automation
         .rule_engine
@@ -614,11 +649,13 @@ pub async fn delete_rule_handler(
 /// POST /api/rules/:id/enable
 pub async fn set_rule_status_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
     Json(req): Json<SetRuleStatusRequest>,
 ) -> HandlerResult<serde_json::Value> {
     let rule_id = RuleId::from_string(&id)
         .map_err(|_| ErrorResponse::bad_request(format!("Invalid rule ID: {}", id)))?;
+    scoped_rule(&state, &scope, &rule_id).await?;
 
     state
         .automation
@@ -656,6 +693,7 @@ pub async fn set_rule_status_handler(
 /// POST /api/rules/:id/test
 pub async fn test_rule_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
     Query(params): Query<std::collections::HashMap<String, String>>,
     body: Option<axum::Json<serde_json::Value>>,
@@ -678,12 +716,7 @@ pub async fn test_rule_handler(
         .map_err(|_| ErrorResponse::bad_request(format!("Invalid rule ID: {}", id)))?;
 
     // Get the rule
-    let rule = state
-        .automation
-        .rule_engine
-        .get_rule(&rule_id)
-        .await
-        .ok_or_else(|| ErrorResponse::not_found("Rule"))?;
+    let rule = scoped_rule(&state, &scope, &rule_id).await?;
 
     // Debug: Log the source field to understand the data structure
     tracing::debug!(
@@ -985,10 +1018,12 @@ pub async fn test_rule_handler(
 /// if the condition is met. This is the entry point for `RuleTrigger::Manual` rules.
 pub async fn trigger_rule_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     let rule_id = RuleId::from_string(&id)
         .map_err(|_| ErrorResponse::bad_request(format!("Invalid rule ID: {}", id)))?;
+    scoped_rule(&state, &scope, &rule_id).await?;
 
     let result = state.automation.rule_engine.execute_rule(&rule_id).await;
     if !result.success && result.error.as_deref() == Some("Rule not found") {
@@ -1008,6 +1043,7 @@ pub async fn trigger_rule_handler(
 /// POST /api/rules — accepts a JSON body representing a CompiledRule.
 pub async fn create_rule_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(mut req): Json<serde_json::Value>,
 ) -> HandlerResult<serde_json::Value> {
     use crate::validator::{validate_required_string, validate_string_length};
@@ -1074,6 +1110,12 @@ pub async fn create_rule_handler(
     }
     rule.updated_at = now;
 
+    // Rule IDs are global: a new rule may not replace another tenant's rule
+    rule.tenant_id = scope.owner();
+    if owned_elsewhere(&state, &scope, &rule.id).await {
+        return Err(ErrorResponse::conflict(format!("Rule ID '{}' is already in use", rule.id)));
+    }
+
     // Finalize: auto-generate dsl_preview, extract trigger sources
     rule.finalize();
 
@@ -1140,18 +1182,14 @@ pub async fn create_rule_handler(
 /// GET /api/rules/:id/history
 pub async fn get_rule_history_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> HandlerResult<serde_json::Value> {
     let rule_id = RuleId::from_string(&id)
         .map_err(|_| ErrorResponse::bad_request(format!("Invalid rule ID: {}", id)))?;
 
     // Check if rule exists
-    let _rule = state
-        .automation
-        .rule_engine
-        .get_rule(&rule_id)
-        .await
-        .ok_or_else(|| ErrorResponse::not_found("Rule"))?;
+    scoped_rule(&state, &scope, &rule_id).await?;
 
     // Try persistent store first, fall back to in-memory
     let history = if let Some(ref store) = state.automation.rule_store {
@@ -1179,14 +1217,36 @@ pub async fn get_rule_history_handler(
     }))
 }
 
+/// Explain a single rule execution: trigger, condition values and action
+/// results, step by step.
+///
+/// GET /api/rules/executions/:execution_id
+pub async fn explain_rule_execution_handler(
+    State(state): State<ServerState>,
+    Path(execution_id): Path<String>,
+) -> HandlerResult<serde_json::Value> {
+    let execution = state
+        .automation
+        .rule_engine
+        .get_execution(&execution_id)
+        .await
+        .ok_or_else(|| ErrorResponse::not_found("Rule execution"))?;
+
+    ok(json!({
+        "explanation": neomind_rules::explain_execution(&execution),
+        "execution": execution,
+    }))
+}
+
 /// Export all rules as JSON.
 ///
 /// GET /api/rules/export
 pub async fn export_rules_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
 ) -> HandlerResult<serde_json::Value> {
     // Get all rules
-    let rules = state.automation.rule_engine.list_rules().await;
+    let rules = scoped_rules(&state, &scope).await;
 
     // Build export structure
     let export = json!({
@@ -1204,6 +1264,7 @@ pub async fn export_rules_handler(
 /// POST /api/rules/import
 pub async fn import_rules_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Json(req): Json<serde_json::Value>,
 ) -> HandlerResult<serde_json::Value> {
     let rules_data = req
@@ -1221,6 +1282,12 @@ pub async fn import_rules_handler(
             Ok(mut rule) => {
                 // Finalize the rule
                 rule.finalize();
+                rule.tenant_id = scope.owner();
+                if owned_elsewhere(&state, &scope, &rule.id).await {
+                    errors.push(format!("Rule {}: ID is already in use", rule.name));
+                    skipped += 1;
+                    continue;
+                }
 
                 match state.automation.rule_engine.add_rule(rule).await {
                     Ok(_) => imported += 1,
@@ -1418,7 +1485,7 @@ fn convert_metric_data_type(dt: DeviceMetricDataType) -> neomind_rules::MetricDa
 ///
 /// This is shared between `validate_rule_handler` and `create_rule_handler` so that
 /// rule creation rejects rules that reference non-existent resources.
-fn build_validation_context(state: &ServerState) -> neomind_rules::ValidationContext {
+pub(crate) fn build_validation_context(state: &ServerState) -> neomind_rules::ValidationContext {
     use neomind_rules::{DeviceInfo, ValidationContext};
 
     let mut context = ValidationContext::new();
@@ -1584,3 +1651,129 @@ pub async fn validate_rule_handler(
         "warnings": result.warnings,
     }))
 }
+
+/// Request body for rule simulation.
+#[derive(Debug, serde::Deserialize)]
+pub struct SimulateRuleRequest {
+    /// Candidate rule JSON (same shape as `POST /api/rules`).
+    #[serde(default)]
+    pub rule: Option<Value>,
+    /// Simulate an existing rule instead of a candidate.
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    /// Range start in seconds (default: `end - hours`).
+    #[serde(default)]
+    pub start: Option<i64>,
+    /// Range end in seconds (default: now).
+    #[serde(default)]
+    pub end: Option<i64>,
+    /// Look-back window used when `start` is omitted (default: 24).
+    #[serde(default)]
+    pub hours: Option<i64>,
+}
+
+/// Replay stored history through a rule without executing its actions.
+///
+/// POST /api/rules/simulate
+pub async fn simulate_rule_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Json(req): Json<SimulateRuleRequest>,
+) -> HandlerResult<serde_json::Value> {
+    let rule = resolve_candidate_rule(&state, &scope, req.rule, req.rule_id).await?;
+
+    let end = req.end.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let start = req
+        .start
+        .unwrap_or_else(|| end - req.hours.unwrap_or(24).max(1) * 3600);
+
+    let report = neomind_rules::RuleEngine::simulate(&rule, &state.devices.telemetry, start, end)
+        .await
+        .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+
+    ok(json!({
+        "would_fire": !report.firings.is_empty(),
+        "report": report,
+    }))
+}
+
+/// Resolve the rule named by a `rule` (candidate JSON) / `rule_id` request pair.
+async fn resolve_candidate_rule(
+    state: &ServerState,
+    scope: &TenantScope,
+    rule: Option<Value>,
+    rule_id: Option<String>,
+) -> Result<CompiledRule, ErrorResponse> {
+    match (rule, rule_id) {
+        (Some(mut body), _) => {
+            if body.get("trigger").is_none() {
+                if let Some(obj) = body.as_object_mut() {
+                    obj.insert(
+                        "trigger".to_string(),
+                        json!({"trigger_type": "data_change"}),
+                    );
+                }
+            }
+            let mut rule: CompiledRule = serde_json::from_value(body)
+                .map_err(|e| ErrorResponse::bad_request(format!("Invalid rule data: {}", e)))?;
+            rule.finalize();
+            Ok(rule)
+        }
+        (None, Some(id)) => {
+            let rule_id = RuleId::from_string(&id)
+                .map_err(|_| ErrorResponse::bad_request(format!("Invalid rule ID: {}", id)))?;
+            scoped_rule(state, scope, &rule_id).await
+        }
+        (None, None) => Err(ErrorResponse::bad_request(
+            "Provide either 'rule' (candidate rule JSON) or 'rule_id'",
+        )),
+    }
+}
+
+/// Analyze all enabled rules for overlapping conditions with contradicting
+/// actions, shadowed rules and circular trigger chains.
+///
+/// GET /api/rules/conflicts
+pub async fn analyze_rule_conflicts_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+) -> HandlerResult<serde_json::Value> {
+    let rules = scoped_rules(&state, &scope).await;
+    let conflicts = neomind_rules::RuleValidator::analyze_conflicts(&rules);
+
+    ok(json!({
+        "rules_analyzed": rules.iter().filter(|r| r.enabled).count(),
+        "count": conflicts.len(),
+        "conflicts": conflicts,
+    }))
+}
+
+/// Request body for rule impact analysis.
+#[derive(Debug, serde::Deserialize)]
+pub struct RuleImpactRequest {
+    /// Candidate rule JSON (same shape as `POST /api/rules`).
+    #[serde(default)]
+    pub rule: Option<Value>,
+    /// Analyze an existing rule instead of a candidate.
+    #[serde(default)]
+    pub rule_id: Option<String>,
+}
+
+/// Conflicts a candidate (or existing) rule has with the other enabled rules.
+///
+/// POST /api/rules/conflicts
+pub async fn rule_impact_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Json(req): Json<RuleImpactRequest>,
+) -> HandlerResult<serde_json::Value> {
+    let rule = resolve_candidate_rule(&state, &scope, req.rule, req.rule_id).await?;
+    let rules = scoped_rules(&state, &scope).await;
+    let conflicts = neomind_rules::RuleValidator::analyze_rule_impact(&rule, &rules);
+
+    ok(json!({
+        "rule_id": rule.id.to_string(),
+        "count": conflicts.len(),
+        "conflicts": conflicts,
+    }))
+}
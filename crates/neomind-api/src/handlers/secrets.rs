@@ -0,0 +1,80 @@
+//! Secrets handlers.
+//!
+//! GET    /api/secrets              - List secrets (metadata only)
+//! POST   /api/secrets              - Create a secret
+//! POST   /api/secrets/:name/rotate - Replace a secret's value
+//! DELETE /api/secrets/:name        - Delete a secret
+//!
+//! Secret values are write-only: no endpoint returns them. Configuration
+//! references a secret by its handle (`secret://<name>`).
+
+use axum::{extract::Path, Json};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::common::{ok, HandlerResult};
+use crate::models::error::ErrorResponse;
+use crate::secrets::{secret_store, SecretInfo};
+
+/// Request to create a secret.
+#[derive(Debug, Deserialize)]
+pub struct CreateSecretRequest {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Request to rotate a secret.
+#[derive(Debug, Deserialize)]
+pub struct RotateSecretRequest {
+    pub value: String,
+}
+
+fn require_value(value: &str) -> Result<(), ErrorResponse> {
+    if value.is_empty() {
+        return Err(ErrorResponse::bad_request("Secret value must not be empty"));
+    }
+    Ok(())
+}
+
+/// `GET /api/secrets` — all secrets, without their values.
+pub async fn list_secrets_handler() -> HandlerResult<serde_json::Value> {
+    let secrets = secret_store()?.list()?;
+    ok(json!({
+        "count": secrets.len(),
+        "secrets": secrets,
+    }))
+}
+
+/// `POST /api/secrets` — store a new secret.
+pub async fn create_secret_handler(
+    Json(req): Json<CreateSecretRequest>,
+) -> HandlerResult<SecretInfo> {
+    require_value(&req.value)?;
+    let info = secret_store()?.create(&req.name, &req.value, req.description)?;
+    tracing::info!(secret = %info.name, "Secret created");
+    ok(info)
+}
+
+/// `POST /api/secrets/:name/rotate` — replace a secret's value.
+///
+/// Integrations pick up the new value the next time they connect.
+pub async fn rotate_secret_handler(
+    Path(name): Path<String>,
+    Json(req): Json<RotateSecretRequest>,
+) -> HandlerResult<SecretInfo> {
+    require_value(&req.value)?;
+    let info = secret_store()?.rotate(&name, &req.value)?;
+    tracing::info!(secret = %info.name, version = info.version, "Secret rotated");
+    ok(info)
+}
+
+/// `DELETE /api/secrets/:name` — delete a secret.
+pub async fn delete_secret_handler(Path(name): Path<String>) -> HandlerResult<serde_json::Value> {
+    if !secret_store()?.delete(&name)? {
+        return Err(ErrorResponse::not_found(format!("Secret '{}'", name)));
+    }
+    tracing::info!(secret = %name, "Secret deleted");
+    ok(json!({ "deleted": name }))
+}
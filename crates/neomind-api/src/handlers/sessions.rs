@@ -1,11 +1,16 @@
 //! Session management handlers.
 
-use super::ws::create_connection_metadata;
+use super::ws::{
+    create_connection_metadata, negotiate_version, protocol_features, ResumeError, StreamEvent,
+    PROTOCOL_VERSION, RESUMABLE_PROTOCOL_VERSION, RESUME_WINDOW,
+};
 
 use axum::extract::ws::{Message as AxumMessage, WebSocket, WebSocketUpgrade};
 use axum::{
     extract::{Path, Query, State},
+    http::StatusCode,
     response::Json,
+    Extension,
 };
 use futures::stream::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
@@ -17,12 +22,31 @@ use tokio::sync::mpsc;
 use tracing::info;
 
 use neomind_agent::AgentEvent;
+use neomind_core::tenant::TenantScope;
 use neomind_storage::{PendingStreamState, StreamStage};
 
-/// Stream event sent from the LLM processing task to the WebSocket handler.
-#[derive(Debug, Clone)]
-struct StreamEvent {
-    json: String,
+/// Deliver one stream event to the client.
+///
+/// Resumable streams go through the session's stream buffer, which assigns the
+/// event a `seq` and keeps it for replay; delivery there never fails, so the
+/// stream keeps running while the client is disconnected. Returns `false` when
+/// a non-resumable client has gone away.
+async fn emit_stream_event(
+    state: &super::ServerState,
+    session_id: &str,
+    tx: &mpsc::Sender<StreamEvent>,
+    resumable: bool,
+    event: serde_json::Value,
+) -> bool {
+    if resumable {
+        state.agents.stream_buffers.push(session_id, event).await;
+        return true;
+    }
+    tx.send(StreamEvent {
+        json: event.to_string(),
+    })
+    .await
+    .is_ok()
 }
 
 /// Process the LLM stream in a spawned task and send events through a channel.
@@ -35,6 +59,7 @@ async fn process_stream_to_channel(
     user_message: String,
     tx: mpsc::Sender<StreamEvent>,
     state: super::ServerState,
+    resumable: bool,
 ) {
     let mut end_event_sent = false;
     let mut event_count = 0u32;
@@ -150,6 +175,35 @@ async fn process_stream_to_channel(
                         }
                         json
                     }
+                    AgentEvent::ToolProgress { tool, data, round } => {
+                        let mut json = json!({
+                            "type": "ToolProgress",
+                            "tool": tool,
+                            "data": data,
+                            "sessionId": session_id,
+                        });
+                        if let Some(r) = round {
+                            json["round"] = json!(r);
+                        }
+                        json
+                    }
+                    AgentEvent::AwaitingApproval {
+                        action_id,
+                        tool,
+                        category,
+                        arguments,
+                        expires_at,
+                    } => {
+                        json!({
+                            "type": "AwaitingApproval",
+                            "actionId": action_id,
+                            "tool": tool,
+                            "category": category,
+                            "arguments": arguments,
+                            "expiresAt": expires_at,
+                            "sessionId": session_id,
+                        })
+                    }
                     AgentEvent::Error { message } => {
                         json!({
                             "type": "Error",
@@ -172,6 +226,13 @@ async fn process_stream_to_channel(
                             "sessionId": session_id,
                         })
                     }
+                    AgentEvent::ContextStats { stats } => {
+                        json!({
+                            "type": "ContextStats",
+                            "stats": stats,
+                            "sessionId": session_id,
+                        })
+                    }
                     AgentEvent::Plan { step, stage } => {
                         json!({
                             "type": "Plan",
@@ -275,12 +336,8 @@ async fn process_stream_to_channel(
                     }
                 };
 
-                let stream_event = StreamEvent {
-                    json: event_json.to_string(),
-                };
-
                 // Try to send, but don't block if channel is closed
-                if tx.send(stream_event).await.is_err() {
+                if !emit_stream_event(&state, &session_id, &tx, resumable, event_json).await {
                     tracing::warn!("Failed to send stream event through channel");
                     break;
                 }
@@ -300,11 +357,7 @@ async fn process_stream_to_channel(
                         "type": "end",
                         "sessionId": session_id,
                     });
-                    let _ = tx
-                        .send(StreamEvent {
-                            json: end_json.to_string(),
-                        })
-                        .await;
+                    emit_stream_event(&state, &session_id, &tx, resumable, end_json).await;
                 }
                 break;
             }
@@ -321,28 +374,24 @@ async fn process_stream_to_channel(
                     "message": "Stream timeout: response took too long",
                     "sessionId": session_id,
                 });
-                let _ = tx
-                    .send(StreamEvent {
-                        json: timeout_json.to_string(),
-                    })
-                    .await;
+                emit_stream_event(&state, &session_id, &tx, resumable, timeout_json).await;
                 // Send end event after timeout
                 if !end_event_sent {
                     let end_json = json!({
                         "type": "end",
                         "sessionId": session_id,
                     });
-                    let _ = tx
-                        .send(StreamEvent {
-                            json: end_json.to_string(),
-                        })
-                        .await;
+                    emit_stream_event(&state, &session_id, &tx, resumable, end_json).await;
                 }
                 break;
             }
         }
     }
 
+    if resumable {
+        state.agents.stream_buffers.finish(&session_id);
+    }
+
     // Persist history after stream completes
     if let Err(e) = state
         .agents
@@ -353,6 +402,7 @@ async fn process_stream_to_channel(
         tracing::warn!(category = "session", error = %e, "Failed to persist history");
     }
 }
+use crate::auth::{RequestTenant, ValidatedApiKey};
 use crate::models::{
     common::ApiResponse, pagination::Pagination, ChatRequest, ChatResponse, CreateSessionRequest,
     ErrorResponse,
@@ -363,6 +413,123 @@ use super::ServerState;
 /// Heartbeat interval for WebSocket connections (seconds)
 const HEARTBEAT_INTERVAL_SECS: u64 = 30;
 
+/// Whether the session belongs to a tenant visible in `scope`.
+pub(crate) fn session_in_scope(
+    state: &ServerState,
+    scope: &TenantScope,
+    session_id: &str,
+) -> bool {
+    *scope == TenantScope::All
+        || scope.allows(&state.agents.session_manager.session_tenant(session_id))
+}
+
+/// Sessions of other tenants are reported as not found.
+pub(crate) fn check_session_scope(
+    state: &ServerState,
+    scope: &TenantScope,
+    session_id: &str,
+) -> Result<(), ErrorResponse> {
+    if session_in_scope(state, scope, session_id) {
+        Ok(())
+    } else {
+        Err(ErrorResponse::not_found("Session"))
+    }
+}
+
+/// Daily LLM token quota of the API key a chat turn runs under.
+///
+/// Shared by every chat entry point (REST, WebSocket, gRPC and voice): the
+/// quota is checked before a turn starts and the turn's tokens are charged
+/// once it ends. Callers without an API key are not metered.
+#[derive(Clone)]
+pub(crate) struct ChatQuota {
+    keys: Arc<crate::auth::AuthState>,
+    api_key: Option<String>,
+}
+
+impl ChatQuota {
+    pub(crate) fn new(state: &ServerState, api_key: Option<String>) -> Self {
+        Self {
+            keys: state.auth.api_key_state.clone(),
+            api_key,
+        }
+    }
+
+    /// Refuse the turn once the key's quota is used up.
+    pub(crate) fn check(&self) -> Result<(), ErrorResponse> {
+        match &self.api_key {
+            Some(key) if self.keys.tokens_remaining(key) == Some(0) => Err(ErrorResponse::new(
+                "QUOTA_EXCEEDED",
+                "Daily LLM token quota exhausted for this API key",
+                StatusCode::TOO_MANY_REQUESTS,
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Charge a finished turn: the prompt tokens reported by the backend (or an
+    /// estimate from the prompt) plus an estimate of the response.
+    pub(crate) fn record(&self, prompt: &str, prompt_tokens: Option<u32>, response: &str) {
+        let Some(key) = &self.api_key else {
+            return;
+        };
+        let prompt = prompt_tokens
+            .map(|t| t as usize)
+            .unwrap_or_else(|| neomind_core::llm::estimate_tokens(prompt));
+        let completion = neomind_core::llm::estimate_tokens(response);
+        let tokens = (prompt + completion) as u64;
+        self.keys.record_token_usage(key, tokens);
+    }
+
+    /// Wrap an agent event stream so the turn is charged when the stream is
+    /// dropped, whether it ran to the end or the client went away mid-turn.
+    pub(crate) fn meter(
+        self,
+        mut stream: Pin<Box<dyn Stream<Item = AgentEvent> + Send>>,
+        prompt: String,
+    ) -> Pin<Box<dyn Stream<Item = AgentEvent> + Send>> {
+        if self.api_key.is_none() {
+            return stream;
+        }
+        let mut usage = TurnUsage {
+            quota: self,
+            prompt,
+            prompt_tokens: None,
+            response: String::new(),
+        };
+        Box::pin(async_stream::stream! {
+            while let Some(event) = stream.next().await {
+                match &event {
+                    AgentEvent::Content { content } => usage.response.push_str(content),
+                    AgentEvent::End { prompt_tokens } => usage.prompt_tokens = *prompt_tokens,
+                    _ => {}
+                }
+                yield event;
+            }
+        })
+    }
+}
+
+/// Token usage of one metered chat turn, charged on drop.
+struct TurnUsage {
+    quota: ChatQuota,
+    prompt: String,
+    prompt_tokens: Option<u32>,
+    response: String,
+}
+
+impl Drop for TurnUsage {
+    fn drop(&mut self) {
+        let Self {
+            quota,
+            prompt,
+            prompt_tokens,
+            response,
+        } = self;
+        quota.record(prompt, *prompt_tokens, response);
+    }
+}
+
 /// Session list item.
 #[derive(Debug, Clone, Serialize)]
 pub struct SessionListItem {
@@ -380,6 +547,7 @@ pub struct SessionListItem {
 /// to override per-session fields like `system_prompt`.
 pub async fn create_session_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     body: Option<Json<Option<CreateSessionRequest>>>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
     // Unwrap the doubly-optional body: outer Option = "was there a body at
@@ -390,13 +558,14 @@ pub async fn create_session_handler(
     let session_id = match req.and_then(|r| r.config) {
         Some(cfg) => {
             // Legacy `config: AgentConfig` path — full struct. Translate to
-            // options patch (only the four commonly-overridden fields flow
+            // options patch (only the commonly-overridden fields flow
             // through; the rest of AgentConfig stays at platform default).
             let opts = neomind_agent::CreateSessionOptions {
                 system_prompt: Some(cfg.system_prompt),
                 temperature: Some(cfg.temperature),
                 model: Some(cfg.model),
                 enable_tools: Some(cfg.enable_tools),
+                allowed_tools: cfg.allowed_tools,
             };
             state
                 .agents
@@ -413,6 +582,15 @@ pub async fn create_session_handler(
             .map_err(|e| ErrorResponse::with_message(e.to_string()))?,
     };
 
+    if let Some(tenant) = scope.tenant() {
+        state
+            .agents
+            .session_manager
+            .set_session_tenant(&session_id, tenant.clone())
+            .await
+            .map_err(|e| ErrorResponse::with_message(e.to_string()))?;
+    }
+
     Ok(Json(ApiResponse::success(json!({
         "sessionId": session_id,
     }))))
@@ -442,6 +620,7 @@ fn default_page_size() -> u32 {
 /// to avoid N+1 database queries. For detailed session info, use individual session endpoints.
 pub async fn list_sessions_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Query(query): Query<ListSessionsQuery>,
 ) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, ErrorResponse> {
     let pagination = Pagination {
@@ -450,11 +629,12 @@ pub async fn list_sessions_handler(
     };
 
     // Use lightweight version to avoid loading message count/preview for every session
-    let all_sessions = state
+    let mut all_sessions = state
         .agents
         .session_manager
         .list_sessions_with_info_light()
         .await;
+    all_sessions.retain(|s| scope.allows(&s.tenant_id));
     let total_count = all_sessions.len() as u32;
 
     // Calculate pagination
@@ -475,8 +655,11 @@ pub async fn list_sessions_handler(
 /// Get session info.
 pub async fn get_session_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
     let agent = state
         .agents
         .session_manager
@@ -493,8 +676,11 @@ pub async fn get_session_handler(
 /// Get session history.
 pub async fn get_session_history_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
     // First check if session exists (get_history returns empty for NotFound)
     let _agent = state
         .agents
@@ -519,8 +705,11 @@ pub async fn get_session_history_handler(
 /// Delete a session.
 pub async fn delete_session_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
     state
         .agents
         .session_manager
@@ -545,8 +734,11 @@ pub async fn delete_session_handler(
 /// P0.3: Get pending stream state for a session (for recovery after disconnection).
 pub async fn get_pending_stream_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
     let session_store = state.agents.session_manager.session_store();
 
     match session_store.get_pending_stream(&id) {
@@ -574,8 +766,11 @@ pub async fn get_pending_stream_handler(
 /// P0.3: Clear pending stream state for a session (user chose to discard).
 pub async fn clear_pending_stream_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
     let session_store = state.agents.session_manager.session_store();
 
     session_store.delete_pending_stream(&id).map_err(|e| {
@@ -598,9 +793,12 @@ pub struct UpdateSessionRequest {
 /// Update a session (e.g., rename).
 pub async fn update_session_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
     Json(req): Json<UpdateSessionRequest>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
     state
         .agents
         .session_manager
@@ -624,9 +822,12 @@ pub struct ToggleMemoryRequest {
 /// Toggle memory enabled state for a session.
 pub async fn toggle_memory_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
     Json(req): Json<ToggleMemoryRequest>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
     state
         .agents
         .session_manager
@@ -640,6 +841,192 @@ pub async fn toggle_memory_handler(
     }))))
 }
 
+/// Request body for forking a session.
+#[derive(Debug, Deserialize)]
+pub struct ForkSessionRequest {
+    /// Index of the last message to copy into the fork
+    #[serde(rename = "atMessageIndex")]
+    pub at_message_index: usize,
+}
+
+/// Fork a session: start a new session from a copy of its history up to a
+/// given message.
+pub async fn fork_session_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+    Json(req): Json<ForkSessionRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
+    let fork_id = state
+        .agents
+        .session_manager
+        .fork_session(&id, req.at_message_index)
+        .await
+        .map_err(|e| match e {
+            neomind_agent::NeoMindError::NotFound(_) => ErrorResponse::not_found("Session"),
+            neomind_agent::NeoMindError::Validation(msg) => ErrorResponse::bad_request(msg),
+            e => ErrorResponse::with_message(e.to_string()),
+        })?;
+
+    Ok(Json(ApiResponse::success(json!({
+        "sessionId": fork_id,
+        "parentSessionId": id,
+        "atMessageIndex": req.at_message_index,
+    }))))
+}
+
+/// Archive a session: summarize it into memory and unload it.
+pub async fn archive_session_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
+    let digest = state
+        .agents
+        .session_manager
+        .archive_session(&id)
+        .await
+        .map_err(|e| match e {
+            neomind_agent::NeoMindError::NotFound(_) => ErrorResponse::not_found("Session"),
+            e => ErrorResponse::with_message(e.to_string()),
+        })?;
+
+    Ok(Json(ApiResponse::success(json!({
+        "sessionId": id,
+        "archived": true,
+        "summary": digest.as_ref().map(|d| d.summary.clone()),
+        "entities": digest.map(|d| d.entities).unwrap_or_default(),
+    }))))
+}
+
+/// Context diagnostics of a session: history size, summary and memory state,
+/// and how the latest turn's context was assembled (messages dropped or
+/// compacted, memory injections, tool cache hits).
+pub async fn get_context_health_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<neomind_agent::ContextHealth>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
+    let health = state
+        .agents
+        .session_manager
+        .context_health(&id)
+        .await
+        .map_err(|e| match e {
+            neomind_agent::NeoMindError::NotFound(_) => ErrorResponse::not_found("Session"),
+            e => ErrorResponse::with_message(e.to_string()),
+        })?;
+    Ok(Json(ApiResponse::success(health)))
+}
+
+/// Export everything stored for a session: messages, tool calls, usage and
+/// derived memories, plus the knowledge documents attached in it.
+pub async fn export_session_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
+    let export = state
+        .agents
+        .session_manager
+        .export_session(&id)
+        .await
+        .map_err(|e| match e {
+            neomind_agent::NeoMindError::NotFound(_) => ErrorResponse::not_found("Session"),
+            e => ErrorResponse::with_message(e.to_string()),
+        })?;
+    let documents: Vec<_> = state
+        .knowledge
+        .list()
+        .await
+        .map_err(ErrorResponse::internal)?
+        .into_iter()
+        .filter(|d| d.session_id.as_deref() == Some(id.as_str()))
+        .collect();
+
+    let mut body = serde_json::to_value(&export)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to serialize export: {}", e)))?;
+    body["knowledgeDocuments"] = json!(documents);
+    Ok(Json(ApiResponse::success(body)))
+}
+
+/// Hard-delete a session: history, derived memories and the vector entries
+/// of documents attached in it. Fails unless all of it is verifiably gone.
+pub async fn purge_session_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
+    // Vector entries first: a failure here leaves the session in place so
+    // the purge can be retried
+    let vector_entries = state
+        .knowledge
+        .delete_session_documents(&id)
+        .await
+        .map_err(ErrorResponse::internal)?;
+    let remaining = state
+        .knowledge
+        .session_chunk_count(&id)
+        .await
+        .map_err(ErrorResponse::internal)?;
+    if remaining > 0 {
+        return Err(ErrorResponse::internal(format!(
+            "{} vector entries of session {} remain after purge",
+            remaining, id
+        )));
+    }
+    let report = state
+        .agents
+        .session_manager
+        .purge_session(&id)
+        .await
+        .map_err(|e| match e {
+            neomind_agent::NeoMindError::NotFound(_) => ErrorResponse::not_found("Session"),
+            e => ErrorResponse::internal(e.to_string()),
+        })?;
+
+    Ok(Json(ApiResponse::success(json!({
+        "sessionId": id,
+        "purged": true,
+        "messages": report.messages,
+        "summaryEntryRemoved": report.summary_entry_removed,
+        "tempFilesRemoved": report.temp_files_removed,
+        "intentExamplesRemoved": report.intent_examples,
+        "vectorEntriesRemoved": vector_entries,
+        "verified": true,
+    }))))
+}
+
+/// List sessions forked from a session.
+pub async fn list_forks_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ErrorResponse> {
+    check_session_scope(&state, &scope, &id)?;
+
+    let forks = state
+        .agents
+        .session_manager
+        .list_forks(&id)
+        .map_err(|e| ErrorResponse::with_message(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(json!({
+        "sessionId": id,
+        "forks": forks,
+    }))))
+}
+
 /// Clean up invalid sessions (dirty data).
 /// Removes sessions that appear in the list but don't have valid data.
 pub async fn cleanup_sessions_handler(
@@ -660,12 +1047,20 @@ pub async fn cleanup_sessions_handler(
 /// Chat handler (REST).
 pub async fn chat_handler(
     State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
     Path(id): Path<String>,
+    api_key: Option<Extension<ValidatedApiKey>>,
     Json(req): Json<ChatRequest>,
 ) -> Result<Json<ChatResponse>, ErrorResponse> {
     use futures::StreamExt;
     use tokio::time::{timeout, Duration};
 
+    check_session_scope(&state, &scope, &id)?;
+
+    // API keys with a daily LLM token quota are refused once it is used up.
+    let quota = ChatQuota::new(&state, api_key.map(|Extension(ValidatedApiKey(key))| key));
+    quota.check()?;
+
     tracing::info!(
         session_id = %id,
         message_len = req.message.chars().count(),
@@ -735,7 +1130,7 @@ pub async fn chat_handler(
     };
 
     let mut stream = match stream_result {
-        Ok(s) => s,
+        Ok(s) => quota.meter(s, final_message.clone()),
         Err(e) => {
             let err_msg = e.to_string();
             if err_msg.contains("Not found") || err_msg.contains("Session:") {
@@ -760,7 +1155,9 @@ pub async fn chat_handler(
     let mut tools_seen: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut error_msg: Option<String> = None;
 
-    let timed_out = timeout(Duration::from_secs(timeout_secs), async {
+    // Tools run while the stream is polled; scope them to the session's tenant
+    let tenant = state.agents.session_manager.session_tenant(&id);
+    let turn = timeout(Duration::from_secs(timeout_secs), async {
         while let Some(event) = stream.next().await {
             match event {
                 AgentEvent::Content { content } => response.push_str(&content),
@@ -782,11 +1179,12 @@ pub async fn chat_handler(
                 _ => {}
             }
         }
-    })
-    .await
-    .is_err();
+    });
+    let timed_out = tenant.scope(turn).await.is_err();
 
     let processing_time_ms = started.elapsed().as_millis() as u64;
+    // Charge the turn now rather than when the handler returns
+    drop(stream);
 
     // Surface error / timeout inline rather than returning an empty/blank response,
     // so the caller can distinguish a model limitation from a system failure.
@@ -871,12 +1269,42 @@ pub async fn ws_chat_handler(
         None => None,
     };
 
+    // Chat can drive tools that change devices and rules, so read-only roles
+    // are held to the same rule as the HTTP chat endpoints.
+    if session_info
+        .as_ref()
+        .is_some_and(|info| info.role == crate::auth_users::UserRole::Viewer)
+    {
+        tracing::warn!("Read-only role attempted to open a chat WebSocket");
+        return ws.on_upgrade(|mut socket| async move {
+            let _ = socket
+                .send(AxumMessage::Text(
+                    json!({"type": "Error", "message": "Read-only role cannot use chat"})
+                        .to_string(),
+                ))
+                .await;
+            let _ = socket.close().await;
+        });
+    }
+
+    // Users bound to a tenant chat in that tenant only
+    let mut scope = session_info
+        .as_ref()
+        .and_then(|info| info.tenant_id.clone())
+        .map(TenantScope::Tenant)
+        .unwrap_or_default();
+
     // If no valid JWT, try API key authentication
+    let mut validated_key = None;
     if session_info.is_none() {
         if let Some(api_key) = params.get("api_key") {
-            if state.auth.api_key_state.validate_key(api_key) {
+            if let Some(key_info) = state.auth.api_key_state.validate_key_info(api_key) {
                 info!("WebSocket authenticated via API key");
                 // No user session for API key auth — proceed without session_info
+                if let Some(tenant) = key_info.tenant_id {
+                    scope = TenantScope::Tenant(tenant);
+                }
+                validated_key = Some(api_key.clone());
             } else {
                 tracing::warn!("Invalid API key, rejecting WebSocket connection");
                 return ws.on_upgrade(|mut socket| async move {
@@ -912,7 +1340,8 @@ pub async fn ws_chat_handler(
     }
 
     let session_id = params.get("sessionId").cloned();
-    ws.on_upgrade(|socket| handle_ws_socket(socket, state, session_id, session_info))
+    let quota = ChatQuota::new(&state, validated_key);
+    ws.on_upgrade(|socket| handle_ws_socket(socket, state, session_id, session_info, scope, quota))
 }
 
 /// Send session history to the client.
@@ -992,7 +1421,12 @@ async fn handle_ws_socket(
     state: ServerState,
     session_id: Option<String>,
     _session_info: Option<crate::auth_users::SessionInfo>,
+    scope: TenantScope,
+    quota: ChatQuota,
 ) {
+    // Sessions of other tenants are treated as unknown
+    let session_id = session_id.filter(|sid| session_in_scope(&state, &scope, sid));
+
     // Create connection metadata for tracking state and heartbeat
     let conn_meta = create_connection_metadata();
     conn_meta
@@ -1016,6 +1450,9 @@ async fn handle_ws_socket(
     // This keeps the main event loop responsive to WebSocket pings
     let (stream_tx, mut stream_rx) = mpsc::channel::<StreamEvent>(100);
 
+    // Chat protocol version; clients that never send `hello` stay on version 1
+    let mut protocol_version = 1u32;
+
     // Send welcome message
     let welcome = json!({
         "type": "system",
@@ -1048,13 +1485,126 @@ async fn handle_ws_socket(
                                 // Track message received
                                 conn_meta.increment_received();
 
-                                // Check for pong response to our heartbeat ping
-                                if let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) {
-                                    if value.get("type") == Some(&json!("pong")) {
+                                let value = serde_json::from_str::<serde_json::Value>(&text).ok();
+                                let msg_type = value
+                                    .as_ref()
+                                    .and_then(|v| v.get("type"))
+                                    .and_then(|t| t.as_str());
+                                let resumable = protocol_version >= RESUMABLE_PROTOCOL_VERSION;
+
+                                match msg_type {
+                                    // Pong response to our heartbeat ping
+                                    Some("pong") => {
                                         conn_meta.record_pong().await;
                                         tracing::debug!("Received pong from client");
                                         continue;
                                     }
+                                    // Protocol version handshake
+                                    Some("hello") => {
+                                        let requested = value
+                                            .as_ref()
+                                            .and_then(|v| v.get("protocolVersion"))
+                                            .and_then(|v| v.as_u64())
+                                            .map(|v| u32::try_from(v).unwrap_or(u32::MAX))
+                                            .unwrap_or(1);
+                                        let reply = match negotiate_version(requested) {
+                                            Ok(version) => {
+                                                protocol_version = version;
+                                                json!({
+                                                    "type": "hello_ack",
+                                                    "protocolVersion": version,
+                                                    "serverProtocolVersion": PROTOCOL_VERSION,
+                                                    "features": protocol_features(version),
+                                                })
+                                            }
+                                            Err(message) => json!({
+                                                "type": "Error",
+                                                "message": message,
+                                            }),
+                                        };
+                                        if socket.send(AxumMessage::Text(reply.to_string())).await.is_err() {
+                                            break;
+                                        }
+                                        continue;
+                                    }
+                                    // Client confirms stream events up to `seq`; they no longer need to be kept
+                                    Some("ack") if resumable => {
+                                        if let Some(seq) = value.as_ref().and_then(|v| v.get("seq")).and_then(|v| v.as_u64()) {
+                                            let ack_session_id = match value.as_ref().and_then(|v| v.get("sessionId")).and_then(|v| v.as_str()) {
+                                                Some(sid) => sid.to_string(),
+                                                None => current_session_id.read().await.clone().unwrap_or_default(),
+                                            };
+                                            state.agents.stream_buffers.ack(&ack_session_id, seq);
+                                        }
+                                        continue;
+                                    }
+                                    // Reconnected client picks up a response where it left off
+                                    Some("resume") if resumable => {
+                                        let field = |name: &str| {
+                                            value
+                                                .as_ref()
+                                                .and_then(|v| v.get(name))
+                                                .and_then(|v| v.as_str())
+                                                .unwrap_or_default()
+                                                .to_string()
+                                        };
+                                        let resume_session_id = field("sessionId");
+                                        let resume_token = field("resumeToken");
+                                        let last_seq = value
+                                            .as_ref()
+                                            .and_then(|v| v.get("lastSeq"))
+                                            .and_then(|v| v.as_u64())
+                                            .unwrap_or(0);
+
+                                        let resumed = if session_in_scope(&state, &scope, &resume_session_id) {
+                                            state.agents.stream_buffers.resume(&resume_session_id, &resume_token, last_seq, stream_tx.clone())
+                                        } else {
+                                            Err(ResumeError::UnknownStream)
+                                        };
+                                        match resumed {
+                                            Ok(replay) => {
+                                                *current_session_id.write().await = Some(resume_session_id.clone());
+                                                tracing::info!(
+                                                    session_id = %resume_session_id,
+                                                    replayed = replay.events.len(),
+                                                    "Resuming chat stream"
+                                                );
+
+                                                let mut sent = true;
+                                                for event in replay.events {
+                                                    if socket.send(AxumMessage::Text(event)).await.is_err() {
+                                                        sent = false;
+                                                        break;
+                                                    }
+                                                }
+                                                let msg = json!({
+                                                    "type": "resumed",
+                                                    "sessionId": resume_session_id,
+                                                    "lastSeq": replay.last_seq,
+                                                    "finished": replay.finished,
+                                                }).to_string();
+                                                if !sent || socket.send(AxumMessage::Text(msg)).await.is_err() {
+                                                    break;
+                                                }
+                                            }
+                                            Err(e) => {
+                                                let msg = json!({
+                                                    "type": "resume_failed",
+                                                    "sessionId": resume_session_id,
+                                                    "reason": e.as_str(),
+                                                }).to_string();
+                                                if socket.send(AxumMessage::Text(msg)).await.is_err() {
+                                                    break;
+                                                }
+                                                // The missed events are gone; let the client rebuild from history
+                                                if !resume_session_id.is_empty() {
+                                                    let _ = send_session_history(&mut socket, &resume_session_id, &state).await;
+                                                }
+                                            }
+                                        }
+                                        continue;
+                                    }
+                                    _ => {}
                                 }
 
                                 if let Ok(chat_req) = serde_json::from_str::<ChatRequest>(&text) {
@@ -1071,7 +1621,7 @@ async fn handle_ws_socket(
                                         };
 
                                         // Send interrupt signal to the active stream
-                                        if !cancel_session_id.is_empty() {
+                                        if !cancel_session_id.is_empty() && session_in_scope(&state, &scope, &cancel_session_id) {
                                             state.agents.session_manager.cancel_session(&cancel_session_id).await;
                                         }
 
@@ -1100,7 +1650,9 @@ async fn handle_ws_socket(
 
                                         // Check if we need to switch sessions (async ops outside lock)
                                         let needs_switch = if let Some(ref req_id) = requested_session_id {
-                                            req_id != &current_id && state.agents.session_manager.get_session(req_id).await.is_ok()
+                                            req_id != &current_id
+                                                && session_in_scope(&state, &scope, req_id)
+                                                && state.agents.session_manager.get_session(req_id).await.is_ok()
                                         } else {
                                             false
                                         };
@@ -1142,6 +1694,11 @@ async fn handle_ws_socket(
                                                 state.agents.session_manager.create_session().await
                                                     .unwrap_or_else(|_| uuid::Uuid::new_v4().to_string())
                                             };
+                                            if let Some(tenant) = scope.tenant() {
+                                                if let Err(e) = state.agents.session_manager.set_session_tenant(&new_id, tenant.clone()).await {
+                                                    tracing::warn!(session_id = %new_id, error = %e, "Failed to assign session tenant");
+                                                }
+                                            }
 
                                             {
                                                 let mut write_guard = current_session_id.write().await;
@@ -1163,6 +1720,20 @@ async fn handle_ws_socket(
                                         }
                                     };
 
+                                    // Protocol v2: acknowledge that the message was accepted
+                                    if resumable {
+                                        if let Some(message_id) = value.as_ref().and_then(|v| v.get("messageId")).and_then(|v| v.as_str()) {
+                                            let ack = json!({
+                                                "type": "ack",
+                                                "messageId": message_id,
+                                                "sessionId": session_id,
+                                            }).to_string();
+                                            if socket.send(AxumMessage::Text(ack)).await.is_err() {
+                                                break;
+                                            }
+                                        }
+                                    }
+
                                     // Filter out control messages (commands starting with '/')
                                     // These are not user messages and should not be sent to the LLM
                                     let message = chat_req.message.trim();
@@ -1177,6 +1748,18 @@ async fn handle_ws_socket(
                                         continue;
                                     }
 
+                                    // API keys with a daily LLM token quota are refused once it is used up
+                                    if let Err(e) = quota.check() {
+                                        let msg = json!({
+                                            "type": "Error",
+                                            "message": e.message,
+                                        }).to_string();
+                                        if socket.send(AxumMessage::Text(msg)).await.is_err() {
+                                            break;
+                                        }
+                                        continue;
+                                    }
+
                                     // Try event streaming first (rich response with tool calls)
                                     // Spawn a task to process the stream asynchronously, keeping the main loop responsive
                                     let backend_id = chat_req.backend_id.as_deref();
@@ -1227,32 +1810,52 @@ async fn handle_ws_socket(
                                             backend_id_str.as_deref(),
                                         ).await {
                                             Ok(stream) => {
+                                                let stream = quota.clone().meter(stream, final_message.clone());
                                                 // Clone the channel sender and session ID for the spawned task
                                                 let task_tx = stream_tx.clone();
                                                 let task_session_id = session_id.clone();
                                                 let task_state = state.clone();
 
-                                                // Spawn a task to process the LLM stream and send events through the channel
-                                                tokio::spawn(async move {
-                                                    process_stream_to_channel(stream, task_session_id, chat_req.message.clone(), task_tx, task_state).await;
-                                                });
+                                                // Protocol v2: buffer events so the client can resume after a disconnect
+                                                if resumable {
+                                                    let resume_token = state.agents.stream_buffers.begin(&task_session_id, task_tx.clone());
+                                                    let msg = json!({
+                                                        "type": "stream_start",
+                                                        "sessionId": task_session_id,
+                                                        "resumeToken": resume_token,
+                                                    }).to_string();
+                                                    if socket.send(AxumMessage::Text(msg)).await.is_err() {
+                                                        break;
+                                                    }
+                                                }
+
+                                                // Spawn a task to process the LLM stream and send events through the channel.
+                                                // Tools run while the stream is polled, so the task carries the session's tenant.
+                                                let tenant = state.agents.session_manager.session_tenant(&task_session_id);
+                                                tokio::spawn(tenant.scope(async move {
+                                                    process_stream_to_channel(stream, task_session_id, chat_req.message.clone(), task_tx, task_state, resumable).await;
+                                                }));
                                             }
                                             Err(e) => {
                                                 // Fallback to non-streaming on error
                                                 tracing::error!(error = %e, session_id = %task_session_id, backend_id = ?backend_id_str, "Streaming multimodal failed, falling back to non-streaming");
-                                                let response = match task_state.agents.session_manager.process_message_multimodal_with_backend(
+                                                let tenant = task_state.agents.session_manager.session_tenant(&task_session_id);
+                                                let response = match tenant.scope(task_state.agents.session_manager.process_message_multimodal_with_backend(
                                                     &task_session_id,
                                                     &chat_req.message,
                                                     images,
                                                     backend_id_str.as_deref(),
-                                                ).await {
-                                                    Ok(resp) => json!({
-                                                        "type": "response",
-                                                        "content": resp.message.content,
-                                                        "sessionId": task_session_id,
-                                                        "toolsUsed": resp.tools_used,
-                                                        "processingTimeMs": resp.processing_time_ms,
-                                                    }).to_string(),
+                                                )).await {
+                                                    Ok(resp) => {
+                                                        quota.record(&chat_req.message, None, &resp.message.content);
+                                                        json!({
+                                                            "type": "response",
+                                                            "content": resp.message.content,
+                                                            "sessionId": task_session_id,
+                                                            "toolsUsed": resp.tools_used,
+                                                            "processingTimeMs": resp.processing_time_ms,
+                                                        }).to_string()
+                                                    }
                                                     Err(inner_e) => json!({
                                                         "type": "Error",
                                                         "message": inner_e.to_string(),
@@ -1281,28 +1884,48 @@ async fn handle_ws_socket(
                                         // (now handled inside Agent::process to avoid cross-session races)
                                         match state.agents.session_manager.process_message_events_with_backend_and_skills(&session_id, &final_message, backend_id, &selected_skills).await {
                                             Ok(stream) => {
+                                                let stream = quota.clone().meter(stream, final_message.clone());
                                                 // Clone the channel sender and session ID for the spawned task
                                                 let task_tx = stream_tx.clone();
                                                 let task_session_id = session_id.clone();
                                                 let task_state = state.clone();
 
-                                                // Spawn a task to process the LLM stream and send events through the channel
-                                                tokio::spawn(async move {
-                                                    process_stream_to_channel(stream, task_session_id, chat_req.message.clone(), task_tx, task_state).await;
-                                                });
+                                                // Protocol v2: buffer events so the client can resume after a disconnect
+                                                if resumable {
+                                                    let resume_token = state.agents.stream_buffers.begin(&task_session_id, task_tx.clone());
+                                                    let msg = json!({
+                                                        "type": "stream_start",
+                                                        "sessionId": task_session_id,
+                                                        "resumeToken": resume_token,
+                                                    }).to_string();
+                                                    if socket.send(AxumMessage::Text(msg)).await.is_err() {
+                                                        break;
+                                                    }
+                                                }
+
+                                                // Spawn a task to process the LLM stream and send events through the channel.
+                                                // Tools run while the stream is polled, so the task carries the session's tenant.
+                                                let tenant = state.agents.session_manager.session_tenant(&task_session_id);
+                                                tokio::spawn(tenant.scope(async move {
+                                                    process_stream_to_channel(stream, task_session_id, chat_req.message.clone(), task_tx, task_state, resumable).await;
+                                                }));
                                             }
                                             Err(e) => {
                                                 // Fallback to non-streaming on error
                                                 tracing::error!(error = %e, session_id = %session_id, backend_id = ?chat_req.backend_id, "Streaming text failed, falling back to non-streaming");
                                                 let backend_id = chat_req.backend_id.as_deref();
-                                                let response = match state.agents.session_manager.process_message_with_backend(&session_id, &chat_req.message, backend_id).await {
-                                                    Ok(resp) => json!({
-                                                        "type": "response",
-                                                        "content": resp.message.content,
-                                                        "sessionId": session_id,
-                                                        "toolsUsed": resp.tools_used,
-                                                        "processingTimeMs": resp.processing_time_ms,
-                                                    }).to_string(),
+                                                let tenant = state.agents.session_manager.session_tenant(&session_id);
+                                                let response = match tenant.scope(state.agents.session_manager.process_message_with_backend(&session_id, &chat_req.message, backend_id)).await {
+                                                    Ok(resp) => {
+                                                        quota.record(&chat_req.message, None, &resp.message.content);
+                                                        json!({
+                                                            "type": "response",
+                                                            "content": resp.message.content,
+                                                            "sessionId": session_id,
+                                                            "toolsUsed": resp.tools_used,
+                                                            "processingTimeMs": resp.processing_time_ms,
+                                                        }).to_string()
+                                                    }
                                                     Err(inner_e) => json!({
                                                         "type": "Error",
                                                         "message": inner_e.to_string(),
@@ -1408,6 +2031,27 @@ async fn handle_ws_socket(
         conn_meta.connection_duration()
     );
 
+    // Streams of a protocol v2 client keep running so it can reconnect and
+    // resume them; they are cancelled only if nobody resumes within the window.
+    let resumable_sessions = state.agents.stream_buffers.detach_all(&stream_tx);
+    for session_id in &resumable_sessions {
+        let session_id = session_id.clone();
+        let stream_buffers = state.agents.stream_buffers.clone();
+        let session_manager = state.agents.session_manager.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(RESUME_WINDOW).await;
+            if stream_buffers.is_orphaned(&session_id)
+                && session_manager.cancel_session(&session_id).await
+            {
+                tracing::info!(
+                    category = "session",
+                    session_id = %session_id,
+                    "Cancelled chat stream that was not resumed"
+                );
+            }
+        });
+    }
+
     // Cleanup: persist session history AFTER loop ends (when connection closes)
     let session_id_opt = current_session_id.read().await.clone();
     if let Some(session_id) = session_id_opt.as_ref() {
@@ -1415,11 +2059,12 @@ async fn handle_ws_socket(
         // Without this, a client disconnect leaves the stream running in its
         // spawned task (burning tokens) and the cancel_senders entry leaks
         // because the wrapped cleanup_stream never reaches its end-of-loop remove.
-        let cancelled = state
-            .agents
-            .session_manager
-            .cancel_session(session_id)
-            .await;
+        let cancelled = !resumable_sessions.contains(session_id)
+            && state
+                .agents
+                .session_manager
+                .cancel_session(session_id)
+                .await;
         if cancelled {
             tracing::info!(
                 category = "session",
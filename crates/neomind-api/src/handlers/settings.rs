@@ -285,6 +285,8 @@ pub async fn get_retention_config(
         "interval_hours": config.interval_hours,
         "default_retention": config.default_retention,
         "image_retention": config.image_retention,
+        "device_type_overrides": config.device_type_overrides,
+        "metric_overrides": config.metric_overrides,
     }))
 }
 
@@ -307,11 +309,18 @@ pub async fn update_retention_config(
     let settings_store = SettingsStore::open(SETTINGS_DB_PATH)
         .map_err(|e| ErrorResponse::internal(format!("Failed to open settings store: {}", e)))?;
 
+    // Overrides omitted from the request keep their stored values, so older
+    // clients that only send the global fields don't wipe them.
+    let existing = settings_store.get_retention_config();
     let config = neomind_storage::settings::RetentionConfig {
         enabled: req.enabled,
         interval_hours: req.interval_hours,
         default_retention: req.default_retention,
         image_retention: req.image_retention,
+        device_type_overrides: req
+            .device_type_overrides
+            .unwrap_or(existing.device_type_overrides),
+        metric_overrides: req.metric_overrides.unwrap_or(existing.metric_overrides),
     };
 
     settings_store
@@ -332,12 +341,49 @@ pub async fn update_retention_config(
         "interval_hours": config.interval_hours,
         "default_retention": config.default_retention,
         "image_retention": config.image_retention,
+        "device_type_overrides": config.device_type_overrides,
+        "metric_overrides": config.metric_overrides,
     }))
 }
 
+/// Preview what a retention configuration would delete, without deleting.
+///
+/// The body is a full retention config (unspecified fields take their
+/// defaults), so the UI can show the impact of a change before saving it.
+pub async fn preview_retention(
+    State(state): State<ServerState>,
+    Json(config): Json<neomind_storage::settings::RetentionConfig>,
+) -> HandlerResult<serde_json::Value> {
+    use neomind_storage::TimeSeriesStore;
+
+    const TELEMETRY_DB_PATH: &str = "data/telemetry.redb";
+
+    let ts_store = TimeSeriesStore::open(TELEMETRY_DB_PATH)
+        .map_err(|e| ErrorResponse::internal(format!("Failed to open telemetry store: {}", e)))?;
+    ts_store.set_device_types(device_type_map(&state));
+
+    let preview = ts_store
+        .preview_retention(&config.to_retention_policy())
+        .await
+        .map_err(|e| ErrorResponse::internal(format!("Failed to preview retention: {}", e)))?;
+
+    ok(json!(preview))
+}
+
+/// Device id -> device type, for resolving per-device-type retention.
+pub(crate) fn device_type_map(state: &ServerState) -> std::collections::HashMap<String, String> {
+    state
+        .devices
+        .service
+        .list_devices()
+        .into_iter()
+        .map(|d| (d.device_id, d.device_type))
+        .collect()
+}
+
 /// Manually trigger a retention cleanup.
 pub async fn trigger_retention_cleanup(
-    State(_state): State<ServerState>,
+    State(state): State<ServerState>,
 ) -> HandlerResult<serde_json::Value> {
     use neomind_storage::{SettingsStore, TimeSeriesStore};
 
@@ -355,6 +401,7 @@ pub async fn trigger_retention_cleanup(
 
     // Apply the policy synchronously (cheap) so the config is live before
     // we trigger cleanup.
+    ts_store.set_device_types(device_type_map(&state));
     ts_store.set_retention_policy(policy).await;
 
     // Spawn the actual cleanup in the background so the HTTP request
@@ -393,4 +440,8 @@ pub struct RetentionConfigRequest {
     pub interval_hours: u64,
     pub default_retention: Option<u64>,
     pub image_retention: Option<u64>,
+    #[serde(default)]
+    pub device_type_overrides: Option<std::collections::HashMap<String, Option<u64>>>,
+    #[serde(default)]
+    pub metric_overrides: Option<std::collections::HashMap<String, Option<u64>>>,
 }
@@ -46,6 +46,10 @@ pub struct InitializeAdminRequest {
     /// Email (optional)
     #[serde(default)]
     pub email: Option<String>,
+    /// Global timezone (optional). Set here because the new admin's token
+    /// only reaches the MFA endpoints until a second factor is enrolled.
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 /// Initialize admin response.
@@ -57,6 +61,8 @@ pub struct InitializeAdminResponse {
     pub user: AdminUserInfo,
     /// JWT token for immediate login
     pub token: String,
+    /// The token is limited to MFA enrollment until a second factor is added
+    pub mfa_setup_required: bool,
 }
 
 /// Created admin user info.
@@ -191,6 +197,15 @@ pub async fn initialize_admin_handler(
         "Admin account created during setup"
     );
 
+    if let Some(timezone) = req.timezone {
+        let request = crate::handlers::settings::TimezoneRequest { timezone };
+        if let Err(e) =
+            crate::handlers::settings::update_timezone(State(state.clone()), Json(request)).await
+        {
+            tracing::warn!(category = "setup", error = %e.message, "Failed to save timezone");
+        }
+    }
+
     Ok(Json(InitializeAdminResponse {
         message: "Admin account created successfully".to_string(),
         user: AdminUserInfo {
@@ -200,6 +215,7 @@ pub async fn initialize_admin_handler(
             created_at: user_info.created_at,
         },
         token,
+        mfa_setup_required: true,
     }))
 }
 
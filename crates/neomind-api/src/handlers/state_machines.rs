@@ -0,0 +1,306 @@
+//! State machine automation handlers.
+//!
+//! GET    /api/state-machines            - List machines
+//! POST   /api/state-machines            - Create a machine
+//! GET    /api/state-machines/:id        - Definition and current state
+//! GET    /api/state-machines/:id/graph  - Definition as nodes and edges
+//! PUT    /api/state-machines/:id        - Replace the definition
+//! DELETE /api/state-machines/:id        - Delete a machine
+//!
+//! Create and update accept either the plain definition or the graph
+//! returned by `/graph` (recognised by its `nodes` field).
+//! POST   /api/state-machines/:id/events - Post a named event
+//! POST   /api/state-machines/:id/reset  - Back to the initial state
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use neomind_core::tenant::TenantScope;
+use neomind_rules::{
+    MachineGraph, MachineRuntime, RuleAction, RuleValidator, StateMachineAutomation,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::common::{ok, HandlerResult};
+use super::rules::build_validation_context;
+use crate::auth::RequestTenant;
+use crate::models::ErrorResponse;
+use crate::server::ServerState;
+
+/// Request body for posting an event.
+#[derive(Debug, Deserialize)]
+pub struct MachineEventRequest {
+    pub event: String,
+}
+
+/// Look up a machine, treating machines owned by another tenant as missing.
+async fn scoped_machine(
+    state: &ServerState,
+    scope: &TenantScope,
+    id: &str,
+) -> Result<StateMachineAutomation, ErrorResponse> {
+    state
+        .automation
+        .rule_engine
+        .get_machine(id)
+        .await
+        .filter(|machine| scope.allows(&machine.tenant_id))
+        .ok_or_else(|| ErrorResponse::not_found("State machine"))
+}
+
+/// Deserialize a definition or graph and check its structure and the
+/// devices, metrics and agents it references.
+fn parse_machine(
+    state: &ServerState,
+    body: Value,
+) -> Result<StateMachineAutomation, ErrorResponse> {
+    use crate::validator::validate_string_length;
+
+    let parsed = if body.get("nodes").is_some() {
+        let graph: MachineGraph = serde_json::from_value(body).map_err(|e| {
+            ErrorResponse::bad_request(format!("Invalid state machine graph: {}", e)).with_hint(
+                "Required fields: 'name' and 'nodes' ({\"id\", \"kind\": \"initial\" | \
+                 \"state\" | \"any\", \"position\": {\"x\", \"y\"}}). Edges are \
+                 {\"id\", \"source\", \"target\"} plus an 'event', 'condition' and/or 'after'.",
+            )
+        })?;
+        StateMachineAutomation::from_graph(graph)
+    } else {
+        let machine: StateMachineAutomation = serde_json::from_value(body).map_err(|e| {
+            ErrorResponse::bad_request(format!("Invalid state machine: {}", e)).with_hint(
+                "Required fields: 'name', 'initial' and 'states' ({\"name\", \"on_entry\", \
+                 \"on_exit\"} with rule actions). Transitions are {\"from\", \"to\"} plus an \
+                 'event', a rule 'condition' and/or 'after' (ms); 'from' may be \"*\".",
+            )
+        })?;
+        machine.validate().map(|_| machine)
+    };
+    let machine = parsed.map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+    validate_string_length(&machine.name, "name", 1, 100)?;
+
+    let context = build_validation_context(state);
+    let mut problems = Vec::new();
+    for machine_state in &machine.states {
+        let actions: Vec<RuleAction> = machine_state
+            .on_entry
+            .iter()
+            .chain(&machine_state.on_exit)
+            .cloned()
+            .collect();
+        problems.extend(RuleValidator::validate_rule(&None, &actions, &context).errors);
+    }
+    for transition in &machine.transitions {
+        problems.extend(
+            RuleValidator::validate_rule(&transition.condition, &transition.actions, &context)
+                .errors,
+        );
+    }
+    if !problems.is_empty() {
+        let detail = problems
+            .iter()
+            .map(|e| format!("- {}", e.message))
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Err(ErrorResponse::bad_request(format!(
+            "State machine references unavailable resources:\n{}",
+            detail
+        )));
+    }
+    Ok(machine)
+}
+
+/// Install a machine in the engine, persist it and return the finalized copy.
+async fn install_machine(
+    state: &ServerState,
+    machine: StateMachineAutomation,
+) -> Result<StateMachineAutomation, ErrorResponse> {
+    let id = machine.id.clone();
+    let engine = &state.automation.rule_engine;
+    engine
+        .add_machine(machine)
+        .await
+        .map_err(|e| ErrorResponse::bad_request(e.to_string()))?;
+    let machine = engine
+        .get_machine(&id)
+        .await
+        .ok_or_else(|| ErrorResponse::internal("State machine vanished after install"))?;
+
+    if let Some(ref store) = state.automation.rule_store {
+        if let Err(e) = store.save_machine(&machine) {
+            tracing::warn!("Failed to save state machine to store: {}", e);
+        }
+    }
+    Ok(machine)
+}
+
+/// List state machines.
+///
+/// GET /api/state-machines
+pub async fn list_machines_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+) -> HandlerResult<Value> {
+    let mut machines = state.automation.rule_engine.list_machines().await;
+    machines.retain(|m| scope.allows(&m.tenant_id));
+    machines.sort_by(|a, b| a.name.cmp(&b.name));
+    ok(json!({
+        "count": machines.len(),
+        "machines": machines,
+    }))
+}
+
+/// Get a state machine with its current state.
+///
+/// GET /api/state-machines/:id
+pub async fn get_machine_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<Value> {
+    let machine = scoped_machine(&state, &scope, &id).await?;
+    ok(json!({ "machine": machine }))
+}
+
+/// Export a state machine as a graph for the visual editor.
+///
+/// GET /api/state-machines/:id/graph
+pub async fn get_machine_graph_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<MachineGraph> {
+    let machine = scoped_machine(&state, &scope, &id).await?;
+    ok(machine.to_graph())
+}
+
+/// Create a state machine. It starts in its initial state.
+///
+/// POST /api/state-machines
+pub async fn create_machine_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Json(body): Json<Value>,
+) -> HandlerResult<Value> {
+    let mut machine = parse_machine(&state, body)?;
+    if state
+        .automation
+        .rule_engine
+        .get_machine(&machine.id)
+        .await
+        .is_some()
+    {
+        return Err(ErrorResponse::conflict(format!(
+            "State machine ID '{}' is already in use",
+            machine.id
+        )));
+    }
+
+    let now = chrono::Utc::now();
+    machine.tenant_id = scope.owner();
+    machine.runtime = MachineRuntime::default();
+    machine.created_at = now;
+    machine.updated_at = now;
+
+    let machine = install_machine(&state, machine).await?;
+    ok(json!({ "machine": machine }))
+}
+
+/// Replace a state machine's definition. The current state is kept if it
+/// still exists.
+///
+/// PUT /api/state-machines/:id
+pub async fn update_machine_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+    Json(body): Json<Value>,
+) -> HandlerResult<Value> {
+    let existing = scoped_machine(&state, &scope, &id).await?;
+    let mut machine = parse_machine(&state, body)?;
+    machine.id = existing.id;
+    machine.tenant_id = existing.tenant_id;
+    machine.runtime = existing.runtime;
+    machine.created_at = existing.created_at;
+    if machine.layout.is_empty() {
+        // Editing the plain JSON shouldn't lose the editor layout
+        machine.layout = existing.layout;
+    }
+
+    let machine = install_machine(&state, machine).await?;
+    ok(json!({
+        "machine": machine,
+        "updated": true,
+    }))
+}
+
+/// Delete a state machine.
+///
+/// DELETE /api/state-machines/:id
+pub async fn delete_machine_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<Value> {
+    scoped_machine(&state, &scope, &id).await?;
+    state.automation.rule_engine.remove_machine(&id).await;
+
+    if let Some(ref store) = state.automation.rule_store {
+        if let Err(e) = store.delete_machine(&id) {
+            tracing::warn!("Failed to delete state machine from store: {}", e);
+        }
+    }
+    ok(json!({
+        "machine_id": id,
+        "deleted": true,
+    }))
+}
+
+/// Post a named event; takes the first matching transition out of the
+/// current state, if any.
+///
+/// POST /api/state-machines/:id/events
+pub async fn post_machine_event_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+    Json(req): Json<MachineEventRequest>,
+) -> HandlerResult<Value> {
+    scoped_machine(&state, &scope, &id).await?;
+    let engine = &state.automation.rule_engine;
+    let transition = engine
+        .fire_machine_event(&id, &req.event)
+        .await
+        .map_err(|e| ErrorResponse::not_found(e.to_string()))?;
+    let current = engine
+        .get_machine(&id)
+        .await
+        .map(|m| m.runtime.current)
+        .unwrap_or_default();
+
+    ok(json!({
+        "event": req.event,
+        "transitioned": transition.is_some(),
+        "transition": transition,
+        "state": current,
+    }))
+}
+
+/// Put a machine back in its initial state without running actions.
+///
+/// POST /api/state-machines/:id/reset
+pub async fn reset_machine_handler(
+    State(state): State<ServerState>,
+    RequestTenant(scope): RequestTenant,
+    Path(id): Path<String>,
+) -> HandlerResult<Value> {
+    scoped_machine(&state, &scope, &id).await?;
+    let machine = state
+        .automation
+        .rule_engine
+        .reset_machine(&id)
+        .await
+        .map_err(|e| ErrorResponse::not_found(e.to_string()))?;
+    ok(json!({ "machine": machine }))
+}
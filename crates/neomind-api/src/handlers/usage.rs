@@ -0,0 +1,96 @@
+//! LLM token usage and cost handlers.
+
+use axum::extract::{Path, State};
+use axum::Json;
+use neomind_agent::usage::{SessionBudget, SessionUsage, UsageTotals};
+use serde::Serialize;
+
+use super::common::{ok, HandlerResult};
+use crate::models::error::ErrorResponse;
+use crate::server::ServerState;
+
+/// Usage across all sessions.
+#[derive(Debug, Serialize)]
+pub struct UsageSummary {
+    pub totals: UsageTotals,
+    pub budget: SessionBudget,
+    pub sessions: Vec<SessionUsage>,
+}
+
+fn summarize(mut sessions: Vec<SessionUsage>, budget: SessionBudget) -> UsageSummary {
+    sessions.sort_by(|a, b| b.totals.cost.total_cmp(&a.totals.cost));
+    let totals = sessions.iter().fold(UsageTotals::default(), |mut acc, s| {
+        acc.requests += s.totals.requests;
+        acc.prompt_tokens += s.totals.prompt_tokens;
+        acc.completion_tokens += s.totals.completion_tokens;
+        acc.cost += s.totals.cost;
+        acc
+    });
+    UsageSummary {
+        totals,
+        budget,
+        sessions,
+    }
+}
+
+/// `GET /api/usage` — token usage and estimated cost of every session,
+/// most expensive first.
+pub async fn get_usage_handler(State(state): State<ServerState>) -> HandlerResult<UsageSummary> {
+    let tracker = state.agents.session_manager.usage_tracker();
+    ok(summarize(tracker.all(), tracker.budget()))
+}
+
+/// `GET /api/usage/:session_id` — usage of one session.
+pub async fn get_session_usage_handler(
+    State(state): State<ServerState>,
+    Path(session_id): Path<String>,
+) -> HandlerResult<SessionUsage> {
+    let usage = state
+        .agents
+        .session_manager
+        .get_session_usage(&session_id)
+        .ok_or_else(|| ErrorResponse::not_found("Session usage"))?;
+    ok(usage)
+}
+
+/// `PUT /api/usage/budget` — set the per-session budget.
+pub async fn update_budget_handler(
+    State(state): State<ServerState>,
+    Json(budget): Json<SessionBudget>,
+) -> HandlerResult<SessionBudget> {
+    if budget.max_cost.is_some_and(|c| c < 0.0) {
+        return Err(ErrorResponse::bad_request("max_cost must not be negative"));
+    }
+    state
+        .agents
+        .session_manager
+        .set_session_budget(budget.clone());
+    ok(budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_orders_by_cost() {
+        let session = |id: &str, cost: f64| SessionUsage {
+            session_id: id.to_string(),
+            totals: UsageTotals {
+                requests: 1,
+                prompt_tokens: 100,
+                completion_tokens: 50,
+                cost,
+            },
+            ..Default::default()
+        };
+        let summary = summarize(
+            vec![session("cheap", 0.01), session("pricey", 0.5)],
+            SessionBudget::default(),
+        );
+        assert_eq!(summary.sessions[0].session_id, "pricey");
+        assert_eq!(summary.totals.requests, 2);
+        assert_eq!(summary.totals.total_tokens(), 300);
+        assert!((summary.totals.cost - 0.51).abs() < 1e-9);
+    }
+}
@@ -1,10 +1,16 @@
 //! WebSocket 处理相关模块
 //!
-//! 包含连接状态管理、心跳检测等功能
+//! 包含连接状态管理、心跳检测、协议版本协商和断线续传等功能
 
 pub mod connection_state;
+pub mod protocol;
+pub mod stream_buffer;
 
 pub use connection_state::{
     create_connection_metadata, ConnectionMetadata, ConnectionState, ConnectionStateRef,
     HeartbeatState,
 };
+pub use protocol::{
+    negotiate_version, protocol_features, PROTOCOL_VERSION, RESUMABLE_PROTOCOL_VERSION,
+};
+pub use stream_buffer::{ResumeError, ResumeReplay, StreamBuffers, StreamEvent, RESUME_WINDOW};
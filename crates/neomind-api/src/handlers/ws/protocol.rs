@@ -0,0 +1,63 @@
+//! WebSocket 聊天协议版本协商
+//!
+//! 客户端连接后可发送 `{"type": "hello", "protocolVersion": 2}` 进行握手，
+//! 服务端回复 `hello_ack`，其中包含协商后的版本和支持的功能。
+//! 未握手的客户端按版本 1 处理，消息格式与之前完全一致。
+//!
+//! 版本 2 新增：
+//! - 流事件带递增的 `seq`，每次响应开始时下发 `stream_start`（含 `resumeToken`）
+//! - 客户端发送 `{"type": "ack", "sessionId", "seq"}` 确认已收到的事件
+//! - 聊天消息可携带 `messageId`，服务端接受后回复 `{"type": "ack", "messageId"}`
+//! - 断线后发送 `{"type": "resume", "sessionId", "resumeToken", "lastSeq"}`
+//!   补发错过的事件，并继续接收仍在进行中的响应
+
+/// 服务端支持的最高协议版本
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// 服务端仍支持的最低协议版本
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// 第一个支持确认和断线续传的协议版本
+pub const RESUMABLE_PROTOCOL_VERSION: u32 = 2;
+
+/// 指定版本支持的功能列表（在 `hello_ack` 中返回）
+pub fn protocol_features(version: u32) -> Vec<&'static str> {
+    if version >= RESUMABLE_PROTOCOL_VERSION {
+        vec!["ack", "resume"]
+    } else {
+        Vec::new()
+    }
+}
+
+/// 根据客户端请求的版本协商实际使用的版本
+///
+/// 取双方都支持的最高版本；客户端版本低于服务端最低版本时返回错误信息。
+pub fn negotiate_version(client_version: u32) -> Result<u32, String> {
+    if client_version < MIN_PROTOCOL_VERSION {
+        return Err(format!(
+            "Unsupported protocol version {} (supported: {}-{})",
+            client_version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+        ));
+    }
+    Ok(client_version.min(PROTOCOL_VERSION))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_version() {
+        assert_eq!(negotiate_version(1), Ok(1));
+        assert_eq!(negotiate_version(2), Ok(2));
+        // 更新的客户端降级到服务端支持的最高版本
+        assert_eq!(negotiate_version(7), Ok(PROTOCOL_VERSION));
+        assert!(negotiate_version(0).is_err());
+    }
+
+    #[test]
+    fn test_protocol_features() {
+        assert!(protocol_features(1).is_empty());
+        assert_eq!(protocol_features(2), vec!["ack", "resume"]);
+    }
+}
@@ -0,0 +1,306 @@
+//! 流事件缓冲与断线续传
+//!
+//! 协议版本 2 的连接上，每个会话最近一次响应的事件都会带上递增的 `seq`
+//! 并保存在 [`StreamBuffers`] 中。连接断开后响应继续生成，客户端在
+//! [`RESUME_WINDOW`] 内带着 `resumeToken` 和最后收到的 `seq` 重连，
+//! 即可补收错过的事件并继续接收后续事件。
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+
+/// 断线后等待客户端续传的时间，超时后取消仍在进行的响应
+pub const RESUME_WINDOW: Duration = Duration::from_secs(120);
+
+/// 每个会话最多缓冲的事件数，超出后丢弃最早的事件
+pub const MAX_BUFFERED_EVENTS: usize = 2000;
+
+/// 发送给 WebSocket 连接的流事件
+#[derive(Debug, Clone)]
+pub struct StreamEvent {
+    pub json: String,
+}
+
+/// 续传失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResumeError {
+    /// 会话没有可续传的响应（从未开始或已过期）
+    UnknownStream,
+    /// 令牌与当前响应不匹配（已开始新的响应）
+    TokenMismatch,
+    /// 所需事件已被丢弃，无法补齐
+    EventsDropped,
+}
+
+impl ResumeError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResumeError::UnknownStream => "unknown_stream",
+            ResumeError::TokenMismatch => "token_mismatch",
+            ResumeError::EventsDropped => "events_dropped",
+        }
+    }
+}
+
+/// 续传时补发的事件
+#[derive(Debug)]
+pub struct ResumeReplay {
+    /// `lastSeq` 之后的事件（已序列化，含 `seq`）
+    pub events: Vec<String>,
+    /// 当前最后一个事件的序号
+    pub last_seq: u64,
+    /// 响应是否已经结束
+    pub finished: bool,
+}
+
+#[derive(Debug)]
+struct SessionStream {
+    resume_token: String,
+    next_seq: u64,
+    events: VecDeque<(u64, String)>,
+    /// 当前接收事件的连接，断线期间为 `None`
+    subscriber: Option<mpsc::Sender<StreamEvent>>,
+    finished: bool,
+    updated_at: Instant,
+}
+
+impl SessionStream {
+    fn first_seq(&self) -> u64 {
+        self.events.front().map(|(seq, _)| *seq).unwrap_or(self.next_seq)
+    }
+}
+
+/// 按会话保存最近一次响应的事件
+#[derive(Debug)]
+pub struct StreamBuffers {
+    streams: Mutex<HashMap<String, SessionStream>>,
+    max_events: usize,
+    retention: Duration,
+}
+
+impl Default for StreamBuffers {
+    fn default() -> Self {
+        Self::new(MAX_BUFFERED_EVENTS, RESUME_WINDOW)
+    }
+}
+
+impl StreamBuffers {
+    pub fn new(max_events: usize, retention: Duration) -> Self {
+        Self {
+            streams: Mutex::new(HashMap::new()),
+            max_events: max_events.max(1),
+            retention,
+        }
+    }
+
+    /// 开始一次新的响应，替换该会话之前的缓冲，返回续传令牌
+    pub fn begin(&self, session_id: &str, subscriber: mpsc::Sender<StreamEvent>) -> String {
+        let token = uuid::Uuid::new_v4().to_string();
+        let mut streams = self.streams.lock();
+        let retention = self.retention;
+        streams.retain(|_, s| !(s.finished && s.updated_at.elapsed() > retention));
+        streams.insert(
+            session_id.to_string(),
+            SessionStream {
+                resume_token: token.clone(),
+                next_seq: 1,
+                events: VecDeque::new(),
+                subscriber: Some(subscriber),
+                finished: false,
+                updated_at: Instant::now(),
+            },
+        );
+        token
+    }
+
+    /// 为事件分配序号并缓冲，然后转发给当前连接
+    ///
+    /// 没有连接时事件只保留在缓冲区中。返回分配的序号，会话没有进行中的
+    /// 响应时返回 `None`。
+    pub async fn push(&self, session_id: &str, mut event: serde_json::Value) -> Option<u64> {
+        let (seq, json, subscriber) = {
+            let mut streams = self.streams.lock();
+            let stream = streams.get_mut(session_id)?;
+            let seq = stream.next_seq;
+            stream.next_seq += 1;
+            event["seq"] = serde_json::json!(seq);
+            let json = event.to_string();
+            stream.events.push_back((seq, json.clone()));
+            while stream.events.len() > self.max_events {
+                stream.events.pop_front();
+            }
+            stream.updated_at = Instant::now();
+            (seq, json, stream.subscriber.clone())
+        };
+
+        if let Some(subscriber) = subscriber {
+            if subscriber.send(StreamEvent { json }).await.is_err() {
+                self.detach(session_id, &subscriber);
+            }
+        }
+        Some(seq)
+    }
+
+    /// 标记响应结束，缓冲保留到过期以便补发结尾事件
+    pub fn finish(&self, session_id: &str) {
+        if let Some(stream) = self.streams.lock().get_mut(session_id) {
+            stream.finished = true;
+            stream.updated_at = Instant::now();
+        }
+    }
+
+    /// 丢弃客户端已确认（`seq` 及之前）的事件
+    pub fn ack(&self, session_id: &str, seq: u64) {
+        if let Some(stream) = self.streams.lock().get_mut(session_id) {
+            while stream.events.front().is_some_and(|(s, _)| *s <= seq) {
+                stream.events.pop_front();
+            }
+        }
+    }
+
+    /// 续传：返回 `last_seq` 之后的事件，并把后续事件转发给新连接
+    pub fn resume(
+        &self,
+        session_id: &str,
+        resume_token: &str,
+        last_seq: u64,
+        subscriber: mpsc::Sender<StreamEvent>,
+    ) -> Result<ResumeReplay, ResumeError> {
+        let mut streams = self.streams.lock();
+        let stream = streams
+            .get_mut(session_id)
+            .ok_or(ResumeError::UnknownStream)?;
+        if stream.resume_token != resume_token {
+            return Err(ResumeError::TokenMismatch);
+        }
+        if last_seq + 1 < stream.first_seq() {
+            return Err(ResumeError::EventsDropped);
+        }
+
+        let events = stream
+            .events
+            .iter()
+            .filter(|(seq, _)| *seq > last_seq)
+            .map(|(_, json)| json.clone())
+            .collect();
+        stream.subscriber = Some(subscriber);
+        stream.updated_at = Instant::now();
+        Ok(ResumeReplay {
+            events,
+            last_seq: stream.next_seq - 1,
+            finished: stream.finished,
+        })
+    }
+
+    /// 连接断开时解除绑定（仅当仍绑定到该连接）
+    pub fn detach(&self, session_id: &str, subscriber: &mpsc::Sender<StreamEvent>) {
+        if let Some(stream) = self.streams.lock().get_mut(session_id) {
+            if stream
+                .subscriber
+                .as_ref()
+                .is_some_and(|s| s.same_channel(subscriber))
+            {
+                stream.subscriber = None;
+            }
+        }
+    }
+
+    /// 解除该连接绑定的所有会话，返回其中响应仍在进行的会话
+    pub fn detach_all(&self, subscriber: &mpsc::Sender<StreamEvent>) -> Vec<String> {
+        let mut running = Vec::new();
+        for (session_id, stream) in self.streams.lock().iter_mut() {
+            if stream
+                .subscriber
+                .as_ref()
+                .is_some_and(|s| s.same_channel(subscriber))
+            {
+                stream.subscriber = None;
+                if !stream.finished {
+                    running.push(session_id.clone());
+                }
+            }
+        }
+        running
+    }
+
+    /// 响应仍在进行但没有任何连接接收
+    pub fn is_orphaned(&self, session_id: &str) -> bool {
+        self.streams
+            .lock()
+            .get(session_id)
+            .is_some_and(|s| !s.finished && s.subscriber.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn seq_of(json: &str) -> u64 {
+        serde_json::from_str::<serde_json::Value>(json).unwrap()["seq"]
+            .as_u64()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_push_forwards_and_resume_replays() {
+        let buffers = StreamBuffers::default();
+        let (tx, mut rx) = mpsc::channel(10);
+        let token = buffers.begin("s1", tx.clone());
+
+        assert_eq!(buffers.push("s1", json!({"type": "Content"})).await, Some(1));
+        assert_eq!(seq_of(&rx.recv().await.unwrap().json), 1);
+        assert_eq!(buffers.push("unknown", json!({})).await, None);
+
+        // 断线期间事件只进入缓冲
+        assert_eq!(buffers.detach_all(&tx), vec!["s1".to_string()]);
+        assert!(buffers.is_orphaned("s1"));
+        buffers.push("s1", json!({"type": "Content"})).await;
+        buffers.push("s1", json!({"type": "end"})).await;
+        buffers.finish("s1");
+        assert!(rx.try_recv().is_err());
+
+        let (tx2, _rx2) = mpsc::channel(10);
+        assert_eq!(
+            buffers.resume("s1", "bad", 1, tx2.clone()).unwrap_err(),
+            ResumeError::TokenMismatch
+        );
+        let replay = buffers.resume("s1", &token, 1, tx2).unwrap();
+        let seqs: Vec<u64> = replay.events.iter().map(String::as_str).map(seq_of).collect();
+        assert_eq!(seqs, vec![2, 3]);
+        assert_eq!(replay.last_seq, 3);
+        assert!(replay.finished);
+        assert!(!buffers.is_orphaned("s1"));
+    }
+
+    #[tokio::test]
+    async fn test_ack_and_overflow_limit_resume() {
+        let buffers = StreamBuffers::new(3, RESUME_WINDOW);
+        let (tx, _rx) = mpsc::channel(10);
+        let token = buffers.begin("s1", tx.clone());
+        for _ in 0..5 {
+            buffers.push("s1", json!({"type": "Content"})).await;
+        }
+
+        // 只保留最后 3 个事件（3..=5）
+        assert_eq!(
+            buffers.resume("s1", &token, 1, tx.clone()).unwrap_err(),
+            ResumeError::EventsDropped
+        );
+        assert_eq!(buffers.resume("s1", &token, 2, tx.clone()).unwrap().events.len(), 3);
+
+        buffers.ack("s1", 4);
+        let replay = buffers.resume("s1", &token, 4, tx.clone()).unwrap();
+        assert_eq!(replay.events.len(), 1);
+
+        // 新的响应会使旧令牌失效
+        buffers.begin("s1", tx.clone());
+        assert_eq!(
+            buffers.resume("s1", &token, 0, tx).unwrap_err(),
+            ResumeError::TokenMismatch
+        );
+    }
+}
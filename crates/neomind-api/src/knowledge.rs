@@ -0,0 +1,1329 @@
+//! Knowledge base: document ingestion and retrieval for RAG.
+//!
+//! Uploaded documents (PDF, Markdown, HTML, plain text) are converted to
+//! text, split into overlapping chunks and embedded. Chunks are stored in a
+//! [`PersistentVectorStore`] under `data/knowledge.redb` with their text and
+//! source document in the metadata, so search results can be quoted and
+//! attributed without a second lookup.
+//!
+//! Embeddings come from an LLM backend's embedding endpoint (Ollama
+//! `/api/embed` or OpenAI-compatible `/embeddings`) when one is configured in
+//! [`KnowledgeConfig`], otherwise from a local hashing embedder that needs no
+//! model and works offline.
+//!
+//! Vectors from different embedders can't be compared, so changing the
+//! embedder starts a background reindex job that re-embeds the stored chunk
+//! text. While it runs, searches query both the new and the previous
+//! embedder and merge the results, so documents stay searchable throughout.
+//! Job state is kept in memory; chunks record the model that embedded them,
+//! so a reindex interrupted by a restart picks up where it stopped when run
+//! again.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use neomind_agent::llm_backends::QueryEmbedder;
+use neomind_storage::{
+    KnowledgeConfig, LlmBackendInstance, LlmBackendType, PersistentVectorStore, SearchOptions,
+    VectorDocument,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OnceCell, RwLock};
+
+/// Vector store category of knowledge chunks.
+const CHUNK_CATEGORY: &str = "knowledge_chunk";
+/// Tag prefix marking chunks of documents attached in a chat session.
+const SESSION_TAG_PREFIX: &str = "session:";
+/// Dimension of the local hashing embedder.
+const LOCAL_DIMENSION: usize = 512;
+/// Texts sent per embedding request.
+const EMBED_BATCH: usize = 32;
+/// Upper bound on chunks per document.
+const MAX_CHUNKS: usize = 5000;
+
+fn session_tag(session_id: &str) -> String {
+    format!("{}{}", SESSION_TAG_PREFIX, session_id)
+}
+
+/// Supported document formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentFormat {
+    Pdf,
+    Markdown,
+    Html,
+    Text,
+}
+
+impl DocumentFormat {
+    /// Detect from the content type, falling back to the file extension.
+    pub fn detect(content_type: Option<&str>, file_name: &str) -> Option<Self> {
+        let from_mime =
+            content_type.and_then(|ct| match ct.split(';').next().unwrap_or_default().trim() {
+                "application/pdf" => Some(Self::Pdf),
+                "text/markdown" | "text/x-markdown" => Some(Self::Markdown),
+                "text/html" | "application/xhtml+xml" => Some(Self::Html),
+                "text/plain" => Some(Self::Text),
+                _ => None,
+            });
+        from_mime.or_else(|| {
+            let ext = file_name.rsplit_once('.')?.1.to_ascii_lowercase();
+            match ext.as_str() {
+                "pdf" => Some(Self::Pdf),
+                "md" | "markdown" => Some(Self::Markdown),
+                "html" | "htm" => Some(Self::Html),
+                "txt" | "text" => Some(Self::Text),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// An ingested document, reconstructed from its chunks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeDocument {
+    pub id: String,
+    pub name: String,
+    pub format: DocumentFormat,
+    pub chunks: usize,
+    pub chars: usize,
+    /// Embedder the chunks were embedded with
+    pub embedding_model: String,
+    pub uploaded_at: i64,
+    /// Chat session the document was attached in; deleted with the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+/// One retrieved chunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeHit {
+    pub document_id: String,
+    pub document_name: String,
+    pub chunk_index: usize,
+    pub score: f32,
+    pub text: String,
+}
+
+/// Status of a reindex job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReindexStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress of a background reindex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReindexJob {
+    pub id: String,
+    pub status: ReindexStatus,
+    /// Embedder chunks are being moved to
+    pub target_model: String,
+    /// Embedder still serving unmigrated chunks, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_model: Option<String>,
+    pub total_chunks: usize,
+    pub processed_chunks: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub started_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<i64>,
+}
+
+/// The current or last reindex, with the embedder used for dual querying
+/// while it runs.
+struct Migration {
+    job: ReindexJob,
+    previous: Option<Embedder>,
+}
+
+/// Turns text into embedding vectors.
+#[derive(Clone)]
+pub enum Embedder {
+    /// Feature-hashed word and bigram counts; no model required.
+    Local,
+    Ollama {
+        client: reqwest::Client,
+        endpoint: String,
+        model: String,
+    },
+    OpenAiCompatible {
+        client: reqwest::Client,
+        endpoint: String,
+        model: String,
+        api_key: Option<String>,
+    },
+}
+
+impl Embedder {
+    /// Build the embedder described by `config`. `backend` is the LLM backend
+    /// instance named by `embedding_backend_id`.
+    pub fn from_config(
+        config: &KnowledgeConfig,
+        backend: Option<LlmBackendInstance>,
+    ) -> Result<Self, String> {
+        let Some(backend_id) = &config.embedding_backend_id else {
+            return Ok(Self::Local);
+        };
+        let backend = backend.ok_or_else(|| format!("LLM backend not found: {}", backend_id))?;
+        let model = config
+            .embedding_model
+            .clone()
+            .ok_or_else(|| "embedding_model is not configured".to_string())?;
+        let endpoint = backend
+            .endpoint
+            .clone()
+            .ok_or_else(|| format!("LLM backend {} has no endpoint", backend_id))?
+            .trim_end_matches('/')
+            .to_string();
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(match backend.backend_type {
+            LlmBackendType::Ollama => Self::Ollama {
+                client,
+                endpoint,
+                model,
+            },
+            _ => Self::OpenAiCompatible {
+                client,
+                endpoint,
+                model,
+                api_key: backend.api_key,
+            },
+        })
+    }
+
+    /// Identifies the vector space; chunks embedded by a different model
+    /// can't be compared with this one's queries.
+    pub fn model_id(&self) -> String {
+        match self {
+            Self::Local => format!("local-hash-{}", LOCAL_DIMENSION),
+            Self::Ollama { model, .. } => format!("ollama:{}", model),
+            Self::OpenAiCompatible {
+                endpoint, model, ..
+            } => format!("{}:{}", endpoint, model),
+        }
+    }
+
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(EMBED_BATCH) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Ok(vectors)
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let vectors = match self {
+            Self::Local => return Ok(texts.iter().map(|t| local_embedding(t)).collect()),
+            Self::Ollama {
+                client,
+                endpoint,
+                model,
+            } => {
+                let response: serde_json::Value = client
+                    .post(format!("{}/api/embed", endpoint))
+                    .json(&serde_json::json!({ "model": model, "input": texts }))
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| format!("Embedding request failed: {}", e))?
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid embedding response: {}", e))?;
+                response
+                    .get("embeddings")
+                    .and_then(|v| serde_json::from_value::<Vec<Vec<f32>>>(v.clone()).ok())
+                    .ok_or_else(|| "Embedding response has no embeddings".to_string())?
+            }
+            Self::OpenAiCompatible {
+                client,
+                endpoint,
+                model,
+                api_key,
+            } => {
+                let url = if endpoint.ends_with("/v1") {
+                    format!("{}/embeddings", endpoint)
+                } else {
+                    format!("{}/v1/embeddings", endpoint)
+                };
+                let mut request = client
+                    .post(url)
+                    .json(&serde_json::json!({ "model": model, "input": texts }));
+                if let Some(key) = api_key {
+                    request = request.bearer_auth(key);
+                }
+                let response: OpenAiEmbeddings = request
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| format!("Embedding request failed: {}", e))?
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid embedding response: {}", e))?;
+                let mut data = response.data;
+                data.sort_by_key(|d| d.index);
+                data.into_iter().map(|d| d.embedding).collect()
+            }
+        };
+        if vectors.len() != texts.len() {
+            return Err(format!(
+                "Embedding response has {} vectors for {} inputs",
+                vectors.len(),
+                texts.len()
+            ));
+        }
+        Ok(vectors)
+    }
+}
+
+/// Query embeddings for the LLM response cache's semantic layer, from the
+/// same embedder as the knowledge base.
+#[async_trait::async_trait]
+impl QueryEmbedder for Embedder {
+    async fn embed(&self, text: &str) -> Option<Vec<f32>> {
+        match Embedder::embed(self, &[text.to_string()]).await {
+            Ok(mut vectors) => vectors.pop(),
+            Err(e) => {
+                tracing::debug!("Query embedding for the response cache failed: {}", e);
+                None
+            }
+        }
+    }
+
+    fn model_id(&self) -> String {
+        Embedder::model_id(self)
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddings {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbedding {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Chunk store and ingestion pipeline.
+pub struct KnowledgeBase {
+    path: PathBuf,
+    store: OnceCell<Arc<PersistentVectorStore>>,
+    /// Serializes ingest, delete and reindex writes.
+    write_lock: Mutex<()>,
+    migration: RwLock<Option<Migration>>,
+}
+
+impl KnowledgeBase {
+    /// The store at `path` is opened on first use.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            store: OnceCell::new(),
+            write_lock: Mutex::new(()),
+            migration: RwLock::new(None),
+        }
+    }
+
+    async fn store(&self) -> Result<&Arc<PersistentVectorStore>, String> {
+        self.store
+            .get_or_try_init(|| async {
+                let store = PersistentVectorStore::open(&self.path)
+                    .map_err(|e| format!("Failed to open knowledge store: {}", e))?;
+                store
+                    .load_index()
+                    .await
+                    .map_err(|e| format!("Failed to load knowledge index: {}", e))?;
+                Ok(store)
+            })
+            .await
+    }
+
+    /// Extract, chunk, embed and store a document. Documents attached in a
+    /// chat session are tagged with it.
+    pub async fn ingest(
+        &self,
+        name: &str,
+        format: DocumentFormat,
+        data: &[u8],
+        session_id: Option<&str>,
+        config: &KnowledgeConfig,
+        embedder: &Embedder,
+    ) -> Result<KnowledgeDocument, String> {
+        let text = extract_text(format, data)?;
+        let chunks = chunk_text(&text, config.chunk_size, config.chunk_overlap);
+        if chunks.is_empty() {
+            return Err("Document contains no text".to_string());
+        }
+        if chunks.len() > MAX_CHUNKS {
+            return Err(format!(
+                "Document is too large ({} chunks, max {})",
+                chunks.len(),
+                MAX_CHUNKS
+            ));
+        }
+        let vectors = embedder.embed(&chunks).await?;
+
+        let document = KnowledgeDocument {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            format,
+            chunks: chunks.len(),
+            chars: text.chars().count(),
+            embedding_model: embedder.model_id(),
+            uploaded_at: chrono::Utc::now().timestamp(),
+            session_id: session_id.map(str::to_string),
+        };
+
+        let _guard = self.write_lock.lock().await;
+        let store = self.store().await?;
+        for (index, (chunk, vector)) in chunks.into_iter().zip(vectors).enumerate() {
+            let mut doc = VectorDocument::new(format!("{}:{}", document.id, index), vector)
+                .with_category(CHUNK_CATEGORY)
+                .with_tag(document.id.clone());
+            if let Some(session_id) = session_id {
+                doc = doc.with_tag(session_tag(session_id));
+            }
+            let doc = doc.with_metadata(serde_json::json!({
+                "document": document,
+                "embedding_model": document.embedding_model,
+                "chunk_index": index,
+                "text": chunk,
+            }));
+            store
+                .insert(doc)
+                .await
+                .map_err(|e| format!("Failed to store chunk: {}", e))?;
+        }
+        Ok(document)
+    }
+
+    /// All documents, newest first.
+    pub async fn list(&self) -> Result<Vec<KnowledgeDocument>, String> {
+        let mut documents: BTreeMap<String, KnowledgeDocument> = BTreeMap::new();
+        for chunk in self.store().await?.get_by_category(CHUNK_CATEGORY) {
+            if let Some(doc) = chunk
+                .metadata
+                .get("document")
+                .and_then(|d| serde_json::from_value::<KnowledgeDocument>(d.clone()).ok())
+            {
+                documents.entry(doc.id.clone()).or_insert(doc);
+            }
+        }
+        let mut documents: Vec<_> = documents.into_values().collect();
+        documents.sort_by_key(|d| std::cmp::Reverse(d.uploaded_at));
+        Ok(documents)
+    }
+
+    /// Remove a document's chunks. Returns `false` if it doesn't exist.
+    pub async fn delete(&self, id: &str) -> Result<bool, String> {
+        let _guard = self.write_lock.lock().await;
+        let store = self.store().await?;
+        let chunks = store.get_by_tag(id);
+        for chunk in &chunks {
+            store
+                .delete(&chunk.id)
+                .await
+                .map_err(|e| format!("Failed to delete chunk: {}", e))?;
+        }
+        Ok(!chunks.is_empty())
+    }
+
+    /// Remove the chunks of every document attached in a chat session.
+    /// Returns the number of chunks deleted.
+    pub async fn delete_session_documents(&self, session_id: &str) -> Result<usize, String> {
+        let _guard = self.write_lock.lock().await;
+        let store = self.store().await?;
+        let chunks = store.get_by_tag(&session_tag(session_id));
+        for chunk in &chunks {
+            store
+                .delete(&chunk.id)
+                .await
+                .map_err(|e| format!("Failed to delete chunk: {}", e))?;
+        }
+        Ok(chunks.len())
+    }
+
+    /// Chunks still stored for a chat session.
+    pub async fn session_chunk_count(&self, session_id: &str) -> Result<usize, String> {
+        Ok(self.store().await?.get_by_tag(&session_tag(session_id)).len())
+    }
+
+    /// Chunks most similar to `query`. Only chunks embedded by `embedder` are
+    /// compared, except while a reindex is running, when unmigrated chunks
+    /// are searched with the previous embedder as well. Scores from the two
+    /// embedders are merged as-is; both are cosine similarities, close enough
+    /// to rank the few results a migration window needs.
+    pub async fn search(
+        &self,
+        query: &str,
+        top_k: usize,
+        embedder: &Embedder,
+    ) -> Result<Vec<KnowledgeHit>, String> {
+        let store = self.store().await?;
+        if store.count().unwrap_or(0) == 0 {
+            return Ok(Vec::new());
+        }
+        let mut hits = search_model(store, query, top_k, embedder).await?;
+
+        let previous = self
+            .migration
+            .read()
+            .await
+            .as_ref()
+            .filter(|m| m.job.status == ReindexStatus::Running)
+            .and_then(|m| m.previous.clone())
+            .filter(|p| p.model_id() != embedder.model_id());
+        if let Some(previous) = previous {
+            match search_model(store, query, top_k, &previous).await {
+                Ok(more) => {
+                    hits.extend(more);
+                    hits.sort_by(|a, b| {
+                        b.score
+                            .partial_cmp(&a.score)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                    hits.truncate(top_k);
+                }
+                Err(e) => tracing::warn!("Search with previous embedder failed: {}", e),
+            }
+        }
+        Ok(hits)
+    }
+
+    /// The running or most recent reindex job.
+    pub async fn reindex_job(&self) -> Option<ReindexJob> {
+        self.migration.read().await.as_ref().map(|m| m.job.clone())
+    }
+
+    /// Start re-embedding, in the background, every chunk not embedded by
+    /// `target`. `previous` is the embedder being replaced; searches use it
+    /// for chunks that haven't been migrated yet. Fails if a reindex is
+    /// already running.
+    pub async fn start_reindex(
+        self: &Arc<Self>,
+        target: Embedder,
+        previous: Option<Embedder>,
+    ) -> Result<ReindexJob, String> {
+        let store = self.store().await?.clone();
+        let mut migration = self.migration.write().await;
+        if migration
+            .as_ref()
+            .is_some_and(|m| m.job.status == ReindexStatus::Running)
+        {
+            return Err("A reindex is already running".to_string());
+        }
+
+        let model = target.model_id();
+        let stale: Vec<String> = store
+            .get_by_category(CHUNK_CATEGORY)
+            .into_iter()
+            .filter(|c| c.metadata["embedding_model"].as_str() != Some(model.as_str()))
+            .map(|c| c.id)
+            .collect();
+        let now = chrono::Utc::now().timestamp();
+        let mut job = ReindexJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            status: ReindexStatus::Running,
+            target_model: model,
+            previous_model: previous.as_ref().map(Embedder::model_id),
+            total_chunks: stale.len(),
+            processed_chunks: 0,
+            error: None,
+            started_at: now,
+            finished_at: None,
+        };
+        if stale.is_empty() {
+            job.status = ReindexStatus::Completed;
+            job.finished_at = Some(now);
+            *migration = Some(Migration {
+                job: job.clone(),
+                previous: None,
+            });
+            return Ok(job);
+        }
+        *migration = Some(Migration {
+            job: job.clone(),
+            previous,
+        });
+        drop(migration);
+
+        let kb = self.clone();
+        let id = job.id.clone();
+        tokio::spawn(async move {
+            let result = kb.run_reindex(&store, &stale, &target).await;
+            let mut migration = kb.migration.write().await;
+            if let Some(m) = migration.as_mut().filter(|m| m.job.id == id) {
+                m.job.finished_at = Some(chrono::Utc::now().timestamp());
+                match result {
+                    Ok(()) => {
+                        m.job.status = ReindexStatus::Completed;
+                        m.previous = None;
+                        tracing::info!(
+                            chunks = m.job.total_chunks,
+                            model = %m.job.target_model,
+                            "Knowledge reindex completed"
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!("Knowledge reindex {} failed: {}", id, e);
+                        m.job.status = ReindexStatus::Failed;
+                        m.job.error = Some(e);
+                    }
+                }
+            }
+        });
+        Ok(job)
+    }
+
+    /// Re-embed `ids` batch by batch. Embedding happens outside the write
+    /// lock so uploads and deletes aren't blocked for the whole job; chunks
+    /// deleted in the meantime are skipped.
+    async fn run_reindex(
+        &self,
+        store: &PersistentVectorStore,
+        ids: &[String],
+        target: &Embedder,
+    ) -> Result<(), String> {
+        let model = target.model_id();
+        for batch in ids.chunks(EMBED_BATCH) {
+            let mut chunks = Vec::with_capacity(batch.len());
+            for id in batch {
+                if let Some(chunk) = store
+                    .get(id)
+                    .await
+                    .map_err(|e| format!("Failed to read chunk: {}", e))?
+                {
+                    chunks.push(chunk);
+                }
+            }
+            let texts: Vec<String> = chunks
+                .iter()
+                .map(|c| c.metadata["text"].as_str().unwrap_or_default().to_string())
+                .collect();
+            let vectors = target.embed(&texts).await?;
+
+            {
+                let _guard = self.write_lock.lock().await;
+                for (mut chunk, vector) in chunks.into_iter().zip(vectors) {
+                    if store
+                        .get(&chunk.id)
+                        .await
+                        .map_err(|e| format!("Failed to read chunk: {}", e))?
+                        .is_none()
+                    {
+                        continue;
+                    }
+                    chunk.embedding = vector;
+                    chunk.metadata["embedding_model"] = serde_json::json!(model);
+                    chunk.metadata["document"]["embedding_model"] = serde_json::json!(model);
+                    store
+                        .insert(chunk)
+                        .await
+                        .map_err(|e| format!("Failed to store chunk: {}", e))?;
+                }
+            }
+            if let Some(m) = self.migration.write().await.as_mut() {
+                m.job.processed_chunks += batch.len();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Search the chunks embedded by `embedder`.
+async fn search_model(
+    store: &PersistentVectorStore,
+    query: &str,
+    top_k: usize,
+    embedder: &Embedder,
+) -> Result<Vec<KnowledgeHit>, String> {
+    let vector = embedder
+        .embed(&[query.to_string()])
+        .await?
+        .pop()
+        .ok_or_else(|| "Embedder returned no vector".to_string())?;
+    let options = SearchOptions::new(top_k)
+        .with_filter("embedding_model", serde_json::json!(embedder.model_id()));
+    let results = store
+        .search_with_options(&vector, options)
+        .await
+        .map_err(|e| format!("Knowledge search failed: {}", e))?;
+    Ok(results
+        .into_iter()
+        .map(|result| {
+            let document = &result.metadata["document"];
+            KnowledgeHit {
+                document_id: document["id"].as_str().unwrap_or_default().to_string(),
+                document_name: document["name"].as_str().unwrap_or_default().to_string(),
+                chunk_index: result.metadata["chunk_index"].as_u64().unwrap_or(0) as usize,
+                score: result.score,
+                text: result.metadata["text"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            }
+        })
+        .collect())
+}
+
+/// Convert a document to plain text.
+pub fn extract_text(format: DocumentFormat, data: &[u8]) -> Result<String, String> {
+    let text = match format {
+        DocumentFormat::Pdf => pdf_text(data)?,
+        DocumentFormat::Html => html_text(&String::from_utf8_lossy(data)),
+        DocumentFormat::Markdown | DocumentFormat::Text => {
+            String::from_utf8_lossy(data).into_owned()
+        }
+    };
+    if text.trim().is_empty() {
+        return Err(match format {
+            DocumentFormat::Pdf => {
+                "No extractable text in PDF (scanned pages or unsupported font encoding)"
+                    .to_string()
+            }
+            _ => "Document contains no text".to_string(),
+        });
+    }
+    Ok(text)
+}
+
+/// Split text into chunks of about `size` characters on paragraph and word
+/// boundaries, repeating the last `overlap` characters of each chunk at the
+/// start of the next.
+pub fn chunk_text(text: &str, size: usize, overlap: usize) -> Vec<String> {
+    let size = size.max(1);
+    // Paragraphs, with paragraphs longer than a chunk split on whitespace.
+    let mut pieces: Vec<String> = Vec::new();
+    for paragraph in text.split("\n\n") {
+        let paragraph = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+        if paragraph.is_empty() {
+            continue;
+        }
+        let chars: Vec<char> = paragraph.chars().collect();
+        let mut start = 0;
+        while start < chars.len() {
+            let mut end = (start + size).min(chars.len());
+            if end < chars.len() {
+                if let Some(space) = chars[start..end].iter().rposition(|c| *c == ' ') {
+                    if space > 0 {
+                        end = start + space;
+                    }
+                }
+            }
+            pieces.push(
+                chars[start..end]
+                    .iter()
+                    .collect::<String>()
+                    .trim()
+                    .to_string(),
+            );
+            start = end;
+        }
+    }
+
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for piece in pieces.into_iter().filter(|p| !p.is_empty()) {
+        let current_len = current.chars().count();
+        if current_len > 0 && current_len + piece.chars().count() + 2 > size {
+            let tail = overlap_tail(&current, overlap);
+            chunks.push(std::mem::replace(&mut current, tail));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(&piece);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// The last `overlap` characters of `chunk`, starting at a word boundary and
+/// marked with a leading ellipsis.
+fn overlap_tail(chunk: &str, overlap: usize) -> String {
+    if overlap == 0 {
+        return String::new();
+    }
+    let chars: Vec<char> = chunk.chars().collect();
+    let start = chars.len().saturating_sub(overlap);
+    let tail: String = chars[start..].iter().collect();
+    let tail = match tail.find(char::is_whitespace) {
+        Some(i) if start > 0 => tail[i..].trim_start().to_string(),
+        _ => tail,
+    };
+    if tail.is_empty() {
+        tail
+    } else {
+        format!("…{}", tail)
+    }
+}
+
+/// Feature-hashed, L2-normalized bag of lowercase words and word bigrams.
+/// Runs of CJK characters are split into character bigrams.
+fn local_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0f32; LOCAL_DIMENSION];
+    let mut add = |feature: &str| {
+        let hash = fnv1a(feature.as_bytes());
+        let index = (hash % LOCAL_DIMENSION as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[index] += sign;
+    };
+
+    let lower = text.to_lowercase();
+    let mut previous: Option<&str> = None;
+    for word in lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        if word.chars().any(is_cjk) {
+            let chars: Vec<char> = word.chars().collect();
+            for pair in chars.windows(2) {
+                add(&pair.iter().collect::<String>());
+            }
+            if chars.len() == 1 {
+                add(word);
+            }
+            previous = None;
+            continue;
+        }
+        add(word);
+        if let Some(prev) = previous {
+            add(&format!("{} {}", prev, word));
+        }
+        previous = Some(word);
+    }
+
+    // Sublinear term frequency, then unit length for cosine similarity.
+    for v in vector.iter_mut() {
+        *v = v.signum() * (1.0 + v.abs()).ln();
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF)
+}
+
+/// FNV-1a; stable across builds, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Convert HTML to text: drops scripts, styles and tags, breaks lines at
+/// block elements and decodes common entities.
+fn html_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len() / 2);
+    let lower = html.to_ascii_lowercase();
+    let mut i = 0;
+    while i < html.len() {
+        let Some(offset) = html[i..].find('<') else {
+            out.push_str(&html[i..]);
+            break;
+        };
+        out.push_str(&html[i..i + offset]);
+        i += offset;
+
+        if lower[i..].starts_with("<!--") {
+            i = lower[i..]
+                .find("-->")
+                .map(|e| i + e + 3)
+                .unwrap_or(html.len());
+            continue;
+        }
+        // A `<` that doesn't open a tag is text.
+        if !lower[i + 1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!') {
+            out.push('<');
+            i += 1;
+            continue;
+        }
+
+        let (end, inner) = match html[i..].find('>') {
+            Some(e) => (i + e + 1, &lower[i + 1..i + e]),
+            None => (html.len(), &lower[i + 1..]),
+        };
+        let closing = inner.starts_with('/');
+        let tag = inner
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+
+        // Skip the contents of non-text elements entirely.
+        if !closing && matches!(tag, "script" | "style" | "head" | "noscript" | "svg") {
+            let close = format!("</{}", tag);
+            i = match lower[end..].find(&close) {
+                Some(e) => {
+                    let close_start = end + e;
+                    html[close_start..]
+                        .find('>')
+                        .map(|g| close_start + g + 1)
+                        .unwrap_or(html.len())
+                }
+                None => html.len(),
+            };
+            continue;
+        }
+
+        if matches!(
+            tag,
+            "p" | "div"
+                | "br"
+                | "li"
+                | "tr"
+                | "table"
+                | "section"
+                | "article"
+                | "h1"
+                | "h2"
+                | "h3"
+                | "h4"
+                | "h5"
+                | "h6"
+                | "pre"
+                | "blockquote"
+        ) {
+            out.push_str("\n\n");
+        } else if matches!(tag, "td" | "th") {
+            out.push(' ');
+        }
+        i = end;
+    }
+    decode_entities(&out)
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|&e| e <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|h| u32::from_str_radix(h, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Best-effort PDF text extraction: inflates content streams and collects
+/// the strings shown by text operators. Handles the simple-font PDFs most
+/// manuals are exported as; scanned pages and CID-keyed fonts yield no text.
+fn pdf_text(data: &[u8]) -> Result<String, String> {
+    if !data.starts_with(b"%PDF") {
+        return Err("Not a PDF file".to_string());
+    }
+    let mut out = String::new();
+    let mut pos = 0;
+    while let Some(offset) = find(&data[pos..], b"stream") {
+        let keyword = pos + offset;
+        pos = keyword + 6;
+        // `endstream` also contains `stream`.
+        if keyword >= 3 && &data[keyword - 3..keyword] == b"end" {
+            continue;
+        }
+        let mut start = pos;
+        if data.get(start) == Some(&b'\r') {
+            start += 1;
+        }
+        if data.get(start) == Some(&b'\n') {
+            start += 1;
+        }
+        let Some(len) = find(&data[start..], b"endstream") else {
+            break;
+        };
+        let raw = &data[start..start + len];
+        pos = start + len + 9;
+
+        // Stream dictionary: from the object header up to the keyword.
+        let dict_start = rfind(&data[..keyword], b"obj").unwrap_or(0);
+        let dict = String::from_utf8_lossy(&data[dict_start..keyword]);
+        if [
+            "/Image",
+            "/FontFile",
+            "/Length1",
+            "/XRef",
+            "/ObjStm",
+            "/Metadata",
+        ]
+        .iter()
+        .any(|k| dict.contains(k))
+        {
+            continue;
+        }
+        let content = if dict.contains("/FlateDecode") {
+            use std::io::Read;
+            let mut inflated = Vec::new();
+            if flate2::read::ZlibDecoder::new(raw)
+                .read_to_end(&mut inflated)
+                .is_err()
+            {
+                continue;
+            }
+            inflated
+        } else if dict.contains("/Filter") {
+            continue;
+        } else {
+            raw.to_vec()
+        };
+        let text = content_stream_text(&content);
+        if !text.trim().is_empty() {
+            out.push_str(&text);
+            out.push_str("\n\n");
+        }
+    }
+    Ok(out)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
+
+/// Text shown by `Tj`, `TJ`, `'` and `"` inside `BT`/`ET` blocks, with line
+/// breaks at text positioning operators.
+fn content_stream_text(content: &[u8]) -> String {
+    let mut out = String::new();
+    let mut strings: Vec<String> = Vec::new();
+    let mut in_text = false;
+    let mut i = 0;
+    while i < content.len() {
+        match content[i] {
+            b'(' => {
+                let (s, next) = pdf_literal(content, i + 1);
+                strings.push(s);
+                i = next;
+            }
+            b'<' if content.get(i + 1) != Some(&b'<') => {
+                let end = content[i..]
+                    .iter()
+                    .position(|b| *b == b'>')
+                    .map(|e| i + e)
+                    .unwrap_or(content.len());
+                strings.push(pdf_hex(&content[i + 1..end]));
+                i = end + 1;
+            }
+            b'-' | b'0'..=b'9' | b'.' => {
+                // A large negative TJ adjustment is a word gap.
+                let end = content[i..]
+                    .iter()
+                    .position(|b| !matches!(b, b'-' | b'0'..=b'9' | b'.'))
+                    .map(|e| i + e)
+                    .unwrap_or(content.len());
+                if let Ok(n) = std::str::from_utf8(&content[i..end])
+                    .unwrap_or_default()
+                    .parse::<f32>()
+                {
+                    if n < -200.0 && !strings.is_empty() {
+                        strings.push(" ".to_string());
+                    }
+                }
+                i = end.max(i + 1);
+            }
+            c if c.is_ascii_alphabetic() || c == b'\'' || c == b'"' => {
+                let end = content[i..]
+                    .iter()
+                    .position(|b| !(b.is_ascii_alphabetic() || *b == b'*'))
+                    .map(|e| i + e)
+                    .unwrap_or(content.len())
+                    .max(i + 1);
+                match &content[i..end] {
+                    b"BT" => in_text = true,
+                    b"ET" => {
+                        in_text = false;
+                        out.push('\n');
+                    }
+                    b"Tj" | b"TJ" if in_text => out.extend(strings.drain(..)),
+                    b"'" | b"\"" if in_text => {
+                        out.push('\n');
+                        out.extend(strings.drain(..));
+                    }
+                    b"Td" | b"TD" | b"T*" | b"Tm"
+                        if in_text && !out.ends_with('\n') && !out.is_empty() =>
+                    {
+                        out.push('\n');
+                    }
+                    _ => {}
+                }
+                strings.clear();
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    out
+}
+
+/// Decode a literal string starting after `(`; returns it and the index
+/// after the closing `)`.
+fn pdf_literal(content: &[u8], mut i: usize) -> (String, usize) {
+    let mut bytes = Vec::new();
+    let mut depth = 0;
+    while i < content.len() {
+        match content[i] {
+            b'\\' => {
+                i += 1;
+                match content.get(i) {
+                    Some(b'n') => bytes.push(b'\n'),
+                    Some(b'r') => bytes.push(b'\r'),
+                    Some(b't') => bytes.push(b'\t'),
+                    Some(b'b') | Some(b'f') => {}
+                    Some(d @ b'0'..=b'7') => {
+                        let mut value = (d - b'0') as u32;
+                        let mut digits = 1;
+                        while digits < 3 {
+                            match content.get(i + 1) {
+                                Some(d @ b'0'..=b'7') => {
+                                    value = value * 8 + (d - b'0') as u32;
+                                    i += 1;
+                                    digits += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        bytes.push(value as u8);
+                    }
+                    Some(b'\r') | Some(b'\n') => {}
+                    Some(c) => bytes.push(*c),
+                    None => break,
+                }
+            }
+            b'(' => {
+                depth += 1;
+                bytes.push(b'(');
+            }
+            b')' if depth == 0 => return (latin1(&bytes), i + 1),
+            b')' => {
+                depth -= 1;
+                bytes.push(b')');
+            }
+            c => bytes.push(c),
+        }
+        i += 1;
+    }
+    (latin1(&bytes), content.len())
+}
+
+/// Hex strings are kept only when they decode to printable single-byte text;
+/// two-byte glyph IDs from CID fonts are unreadable without the font's CMap.
+fn pdf_hex(hex: &[u8]) -> String {
+    let digits: Vec<u8> = hex
+        .iter()
+        .filter_map(|b| (*b as char).to_digit(16).map(|d| d as u8))
+        .collect();
+    let bytes: Vec<u8> = digits
+        .chunks(2)
+        .map(|p| p[0] << 4 | p.get(1).copied().unwrap_or(0))
+        .collect();
+    if bytes.iter().all(|b| *b >= 0x20 || b.is_ascii_whitespace()) {
+        latin1(&bytes)
+    } else {
+        String::new()
+    }
+}
+
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| *b as char).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text() {
+        let text = format!(
+            "{}\n\n{}\n\nshort",
+            "alpha ".repeat(30).trim(),
+            "beta ".repeat(30).trim()
+        );
+        let chunks = chunk_text(&text, 200, 20);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].starts_with("alpha") && chunks[0].ends_with("alpha"));
+        // Second chunk repeats the end of the first.
+        assert!(chunks[1].starts_with("…alpha"));
+        assert!(chunks[1].ends_with("short"));
+        assert!(chunks.iter().all(|c| c.chars().count() <= 200 + 20));
+
+        let long = "word ".repeat(100);
+        assert!(chunk_text(&long, 120, 0)
+            .iter()
+            .all(|c| c.chars().count() <= 120));
+    }
+
+    #[test]
+    fn test_html_text() {
+        let html = "<html><head><title>x</title><style>p{}</style></head>\
+                    <body><h1>Reset</h1><p>Hold &quot;PWR&quot; &amp; wait&nbsp;5s</p>\
+                    <script>alert(1)</script><!-- note --></body></html>";
+        let text = html_text(html);
+        assert_eq!(
+            text.split_whitespace().collect::<Vec<_>>().join(" "),
+            "Reset Hold \"PWR\" & wait 5s"
+        );
+    }
+
+    #[test]
+    fn test_pdf_text() {
+        use std::io::Write;
+        let content = b"BT /F1 12 Tf 72 720 Td (Pump P-100 manual) Tj 0 -14 Td \
+                        [(Replace the) -300 (seal \\(yearly\\))] TJ ET";
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut pdf = b"%PDF-1.4\n4 0 obj\n<< /Length ".to_vec();
+        pdf.extend(format!("{} /Filter /FlateDecode >>\nstream\n", compressed.len()).bytes());
+        pdf.extend(&compressed);
+        pdf.extend(b"\nendstream\nendobj\n%%EOF");
+
+        let text = extract_text(DocumentFormat::Pdf, &pdf).unwrap();
+        let lines: Vec<&str> = text.lines().filter(|l| !l.is_empty()).collect();
+        assert_eq!(
+            lines,
+            vec!["Pump P-100 manual", "Replace the seal (yearly)"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let kb = KnowledgeBase::new(dir.path().join("knowledge.redb"));
+        let config = KnowledgeConfig::default();
+        let embedder = Embedder::Local;
+
+        let pump = kb
+            .ingest(
+                "pump.md",
+                DocumentFormat::Markdown,
+                b"# Pump\n\nTo replace the mechanical seal, stop the pump and drain the casing.",
+                None,
+                &config,
+                &embedder,
+            )
+            .await
+            .unwrap();
+        kb.ingest(
+            "gateway.md",
+            DocumentFormat::Markdown,
+            b"# Gateway\n\nThe LoRa gateway reboots when the WAN link drops.",
+            Some("s1"),
+            &config,
+            &embedder,
+        )
+        .await
+        .unwrap();
+        assert_eq!(kb.list().await.unwrap().len(), 2);
+
+        let hits = kb
+            .search("how do I replace the pump seal", 1, &embedder)
+            .await
+            .unwrap();
+        assert_eq!(hits[0].document_name, "pump.md");
+
+        assert!(kb.delete(&pump.id).await.unwrap());
+        assert_eq!(kb.list().await.unwrap().len(), 1);
+
+        // Deleting the session removes the document attached in it
+        assert_eq!(kb.session_chunk_count("s1").await.unwrap(), 1);
+        assert_eq!(kb.delete_session_documents("s1").await.unwrap(), 1);
+        assert_eq!(kb.session_chunk_count("s1").await.unwrap(), 0);
+        assert!(kb.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reindex_migrates_stale_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let kb = Arc::new(KnowledgeBase::new(dir.path().join("knowledge.redb")));
+        let embedder = Embedder::Local;
+        kb.ingest(
+            "pump.md",
+            DocumentFormat::Markdown,
+            b"To replace the mechanical seal, stop the pump and drain the casing.",
+            None,
+            &KnowledgeConfig::default(),
+            &embedder,
+        )
+        .await
+        .unwrap();
+
+        // Pretend the chunks came from another model.
+        let store = kb.store().await.unwrap().clone();
+        for mut chunk in store.get_by_category(CHUNK_CATEGORY) {
+            chunk.metadata["embedding_model"] = serde_json::json!("legacy");
+            store.insert(chunk).await.unwrap();
+        }
+        assert!(kb
+            .search("pump seal", 3, &embedder)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let job = kb.start_reindex(embedder.clone(), None).await.unwrap();
+        assert_eq!(job.total_chunks, 1);
+        let job = loop {
+            let job = kb.reindex_job().await.unwrap();
+            if job.status != ReindexStatus::Running {
+                break job;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        assert_eq!(job.status, ReindexStatus::Completed);
+        assert_eq!(job.processed_chunks, 1);
+        assert_eq!(
+            kb.search("pump seal", 3, &embedder).await.unwrap()[0].document_name,
+            "pump.md"
+        );
+
+        // Nothing left to migrate.
+        let job = kb.start_reindex(embedder, None).await.unwrap();
+        assert_eq!(job.status, ReindexStatus::Completed);
+        assert_eq!(job.total_chunks, 0);
+    }
+}
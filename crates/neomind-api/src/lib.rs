@@ -1,6 +1,7 @@
 //! API server for Edge AI Agent.
 //!
-//! This crate provides the HTTP/WebSocket API server for the Edge AI Agent system.
+//! This crate provides the HTTP/WebSocket API server (plus an optional
+//! gRPC surface behind the `grpc` feature) for the Edge AI Agent system.
 
 pub mod auth;
 pub mod auth_users;
@@ -10,14 +11,29 @@ pub mod capability_providers;
 pub mod config;
 pub mod crypto;
 pub mod event_services;
+pub mod exports;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod handlers;
+pub mod knowledge;
+pub mod mfa;
 pub mod models;
 
 pub mod rate_limit;
+pub mod reports;
+pub mod secrets;
 pub mod server;
 pub mod shutdown;
 pub mod startup;
+#[cfg(feature = "otel")]
+pub mod telemetry;
 pub mod validator;
 
+// Unit tests meter script heap use like server binaries built with the feature
+#[cfg(all(test, feature = "script-heap-limit"))]
+#[global_allocator]
+static GLOBAL: automation::MeteredAlloc<std::alloc::System> =
+    automation::MeteredAlloc(std::alloc::System);
+
 // Re-export server entry points for binary crates (neomind-cli, neomind-tauri)
 pub use server::{run, start_server};
@@ -0,0 +1,396 @@
+//! Second-factor authentication.
+//!
+//! Users can enroll an authenticator app ([`totp`]) and any number of
+//! WebAuthn passkeys ([`webauthn`]). Enrolling the first factor issues ten
+//! single-use recovery codes, of which only SHA-256 hashes are kept.
+//!
+//! Once a factor is enrolled, password login only yields a short-lived MFA
+//! token; the session token is issued by `POST /api/auth/login/mfa` after a
+//! code, recovery code or passkey assertion checks out. Administrators who
+//! have not enrolled yet only get a setup-only session that reaches the MFA
+//! endpoints until their first factor is enrolled, and cannot remove their
+//! last factor. Approving held agent actions in the categories listed in
+//! `agent.approval_mfa_required` (all of them by default) asks for a second
+//! factor as well.
+//!
+//! The records live in their own table of `users.redb`, keyed by user ID,
+//! so the bincode user records are left untouched.
+
+pub mod totp;
+pub mod webauthn;
+
+use std::collections::HashMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use webauthn::{AssertionResponse, Passkey, RegistrationChallenge};
+
+/// Lifetime of MFA login tokens and WebAuthn challenges, in seconds.
+pub const CHALLENGE_TTL_SECS: i64 = 300;
+
+/// Second-factor attempts allowed per MFA login token.
+pub const MAX_LOGIN_ATTEMPTS: u32 = 5;
+
+/// Failed second factors in a row, across logins and step-ups, before a
+/// user is locked out.
+pub const MAX_FACTOR_FAILURES: u32 = 5;
+
+/// First lockout, in seconds; every further failure doubles it.
+pub const LOCKOUT_SECS: i64 = 60;
+
+/// Longest lockout, in seconds. Failures older than this are forgotten.
+pub const MAX_LOCKOUT_SECS: i64 = 3600;
+
+/// Recovery codes issued per user.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Unambiguous characters for recovery codes (no 0/o, 1/l/i).
+const RECOVERY_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// A user's enrolled second factors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MfaRecord {
+    /// Confirmed TOTP secret (base32)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub totp_secret: Option<String>,
+    /// Secret handed out for enrollment, waiting for a first valid code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_totp_secret: Option<String>,
+    /// Last accepted TOTP step; codes can't be reused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub totp_last_step: Option<u64>,
+    #[serde(default)]
+    pub passkeys: Vec<Passkey>,
+    /// SHA-256 hashes of the unused recovery codes
+    #[serde(default)]
+    pub recovery_codes: Vec<String>,
+}
+
+impl MfaRecord {
+    pub fn is_enabled(&self) -> bool {
+        self.totp_secret.is_some() || !self.passkeys.is_empty()
+    }
+
+    /// Factors that can complete a login, in the order the UI offers them.
+    pub fn methods(&self) -> Vec<&'static str> {
+        let mut methods = Vec::new();
+        if self.totp_secret.is_some() {
+            methods.push("totp");
+        }
+        if !self.passkeys.is_empty() {
+            methods.push("webauthn");
+        }
+        if !self.recovery_codes.is_empty() {
+            methods.push("recovery_code");
+        }
+        methods
+    }
+
+    /// Check a proof and return the method it used. `challenge` is the
+    /// outstanding WebAuthn challenge, if any.
+    ///
+    /// A TOTP step or recovery code is consumed on success.
+    pub fn verify(
+        &mut self,
+        proof: &MfaProof,
+        challenge: Option<&str>,
+        now: i64,
+    ) -> Result<&'static str, String> {
+        if !self.is_enabled() {
+            return Err("No second factor is enrolled".to_string());
+        }
+        if let Some(code) = proof.code.as_deref() {
+            let secret = self
+                .totp_secret
+                .as_deref()
+                .ok_or("No authenticator app is enrolled")?;
+            let step = totp::verify(secret, code, now as u64, self.totp_last_step)
+                .ok_or("Invalid authentication code")?;
+            self.totp_last_step = Some(step);
+            return Ok("totp");
+        }
+        if let Some(code) = proof.recovery_code.as_deref() {
+            let hash = hash_recovery_code(code);
+            let index = self
+                .recovery_codes
+                .iter()
+                .position(|h| *h == hash)
+                .ok_or("Invalid recovery code")?;
+            self.recovery_codes.remove(index);
+            return Ok("recovery_code");
+        }
+        if let Some(assertion) = &proof.webauthn {
+            let challenge = challenge.ok_or("No passkey challenge is outstanding")?;
+            let passkey = self
+                .passkeys
+                .iter_mut()
+                .find(|p| p.id == assertion.id.trim_end_matches('='))
+                .ok_or("Unknown passkey")?;
+            webauthn::verify_assertion(assertion, challenge, passkey, now)?;
+            return Ok("webauthn");
+        }
+        Err("A second factor is required".to_string())
+    }
+
+    /// Issue a fresh set of recovery codes, replacing any left.
+    pub fn reset_recovery_codes(&mut self) -> Vec<String> {
+        let codes = generate_recovery_codes();
+        self.recovery_codes = codes.iter().map(|c| hash_recovery_code(c)).collect();
+        codes
+    }
+
+    /// Forget recovery codes once the last factor is gone.
+    pub fn clear_if_unused(&mut self) {
+        if !self.is_enabled() {
+            self.recovery_codes.clear();
+            self.totp_last_step = None;
+        }
+    }
+
+    pub fn status(&self, required: bool) -> MfaStatus {
+        MfaStatus {
+            enabled: self.is_enabled(),
+            required,
+            totp: self.totp_secret.is_some(),
+            passkeys: self.passkeys.iter().map(PasskeyInfo::from).collect(),
+            recovery_codes_remaining: self.recovery_codes.len(),
+        }
+    }
+}
+
+/// A second factor presented with a request. Exactly one field is
+/// normally set; they are tried in field order.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MfaProof {
+    /// Code from the authenticator app
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub recovery_code: Option<String>,
+    /// Passkey assertion for the outstanding challenge
+    #[serde(default)]
+    pub webauthn: Option<AssertionResponse>,
+}
+
+impl MfaProof {
+    pub fn is_empty(&self) -> bool {
+        self.code.is_none() && self.recovery_code.is_none() && self.webauthn.is_none()
+    }
+}
+
+/// What the client needs to finish a login with a second factor.
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaLoginChallenge {
+    pub mfa_token: String,
+    pub methods: Vec<&'static str>,
+    /// `PublicKeyCredentialRequestOptions` when passkeys are enrolled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webauthn: Option<serde_json::Value>,
+    pub expires_at: i64,
+}
+
+/// A user's second factors as shown on the account page.
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaStatus {
+    pub enabled: bool,
+    /// Whether the user's role must have a second factor
+    pub required: bool,
+    pub totp: bool,
+    pub passkeys: Vec<PasskeyInfo>,
+    pub recovery_codes_remaining: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PasskeyInfo {
+    pub id: String,
+    pub name: String,
+    pub created_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+}
+
+impl From<&Passkey> for PasskeyInfo {
+    fn from(passkey: &Passkey) -> Self {
+        Self {
+            id: passkey.id.clone(),
+            name: passkey.name.clone(),
+            created_at: passkey.created_at,
+            last_used_at: passkey.last_used_at,
+        }
+    }
+}
+
+/// A password login waiting for its second factor.
+#[derive(Debug, Clone)]
+pub struct PendingLogin {
+    pub user_id: String,
+    pub username: String,
+    pub challenge: Option<String>,
+    pub expires_at: i64,
+    pub attempts: u32,
+}
+
+/// Second-factor failures of one user.
+#[derive(Debug, Clone, Default)]
+pub struct FactorFailures {
+    /// Attempts since the last accepted factor
+    pub count: u32,
+    pub last_attempt: i64,
+    pub locked_until: i64,
+}
+
+/// Outstanding MFA ceremonies; nothing here survives a restart.
+#[derive(Debug, Default)]
+pub struct MfaChallenges {
+    /// By MFA token
+    pub logins: HashMap<String, PendingLogin>,
+    /// Passkey registrations by user ID
+    pub registrations: HashMap<String, RegistrationChallenge>,
+    /// Step-up passkey challenges by user ID, with their expiry
+    pub step_ups: HashMap<String, (String, i64)>,
+    /// Failed second factors by user ID, shared by login and step-up
+    pub failures: HashMap<String, FactorFailures>,
+}
+
+impl MfaChallenges {
+    /// Drop everything that expired before `now`.
+    pub fn prune(&mut self, now: i64) {
+        self.logins.retain(|_, l| l.expires_at >= now);
+        self.registrations.retain(|_, r| r.expires_at >= now);
+        self.step_ups
+            .retain(|_, (_, expires_at)| *expires_at >= now);
+        self.failures
+            .retain(|_, f| f.locked_until >= now || now - f.last_attempt < MAX_LOCKOUT_SECS);
+    }
+
+    /// Count a second-factor attempt by `user_id` before it is checked, so
+    /// concurrent guesses can't get past the limit. Fails with the seconds
+    /// left while the user is locked out.
+    pub fn begin_factor_attempt(&mut self, user_id: &str, now: i64) -> Result<(), i64> {
+        let failures = self.failures.entry(user_id.to_string()).or_default();
+        if failures.locked_until > now {
+            return Err(failures.locked_until - now);
+        }
+        failures.count += 1;
+        failures.last_attempt = now;
+        if failures.count >= MAX_FACTOR_FAILURES {
+            let doublings = (failures.count - MAX_FACTOR_FAILURES).min(16);
+            failures.locked_until = now + (LOCKOUT_SECS << doublings).min(MAX_LOCKOUT_SECS);
+        }
+        Ok(())
+    }
+
+    /// Forget the failures of a user whose second factor checked out.
+    pub fn factor_accepted(&mut self, user_id: &str) {
+        self.failures.remove(user_id);
+    }
+}
+
+/// New recovery codes in `xxxxx-xxxxx` form.
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut code: String = (0..10)
+                .map(|_| RECOVERY_ALPHABET[rng.gen_range(0..RECOVERY_ALPHABET.len())] as char)
+                .collect();
+            code.insert(5, '-');
+            code
+        })
+        .collect()
+}
+
+/// Hash a recovery code for storage, ignoring case, spaces and dashes.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totp_record() -> MfaRecord {
+        let mut record = MfaRecord {
+            totp_secret: Some(totp::base32_encode(b"12345678901234567890")),
+            ..Default::default()
+        };
+        record.reset_recovery_codes();
+        record
+    }
+
+    #[test]
+    fn test_recovery_codes_are_hashed_and_single_use() {
+        let mut record = totp_record();
+        let codes = record.reset_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert!(!record.recovery_codes.contains(&codes[0]));
+
+        let proof = MfaProof {
+            recovery_code: Some(codes[0].to_uppercase().replace('-', " ")),
+            ..Default::default()
+        };
+        assert_eq!(record.verify(&proof, None, 0), Ok("recovery_code"));
+        assert_eq!(record.recovery_codes.len(), RECOVERY_CODE_COUNT - 1);
+        assert!(record.verify(&proof, None, 0).is_err());
+    }
+
+    #[test]
+    fn test_verify_totp_consumes_step() {
+        let mut record = totp_record();
+        let proof = MfaProof {
+            code: Some("287082".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(record.verify(&proof, None, 59), Ok("totp"));
+        assert_eq!(record.totp_last_step, Some(1));
+        assert!(record.verify(&proof, None, 59).is_err());
+        assert!(record.verify(&MfaProof::default(), None, 59).is_err());
+    }
+
+    #[test]
+    fn test_disabled_record() {
+        let mut record = totp_record();
+        assert_eq!(record.methods(), ["totp", "recovery_code"]);
+        record.totp_secret = None;
+        record.clear_if_unused();
+        assert!(!record.is_enabled());
+        assert!(record.recovery_codes.is_empty());
+        let proof = MfaProof {
+            code: Some("287082".to_string()),
+            ..Default::default()
+        };
+        assert!(record.verify(&proof, None, 59).is_err());
+    }
+
+    #[test]
+    fn test_factor_failures_back_off() {
+        let mut challenges = MfaChallenges::default();
+        for _ in 0..MAX_FACTOR_FAILURES {
+            assert!(challenges.begin_factor_attempt("u1", 100).is_ok());
+        }
+        assert_eq!(
+            challenges.begin_factor_attempt("u1", 100),
+            Err(LOCKOUT_SECS)
+        );
+        // Other users are not affected
+        assert!(challenges.begin_factor_attempt("u2", 100).is_ok());
+
+        // The next failure after the lockout doubles it
+        let later = 100 + LOCKOUT_SECS;
+        assert!(challenges.begin_factor_attempt("u1", later).is_ok());
+        assert_eq!(
+            challenges.begin_factor_attempt("u1", later),
+            Err(2 * LOCKOUT_SECS)
+        );
+
+        challenges.factor_accepted("u1");
+        assert!(challenges.begin_factor_attempt("u1", later).is_ok());
+    }
+}
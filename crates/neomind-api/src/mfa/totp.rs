@@ -0,0 +1,170 @@
+//! Time-based one-time passwords (RFC 6238) as used by authenticator apps.
+//!
+//! Secrets are 160 random bits, exchanged base32-encoded inside an
+//! `otpauth://` URI that the UI renders as a QR code. Codes are 6 digits
+//! over 30 second steps (HMAC-SHA1), which is what every common
+//! authenticator app defaults to.
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// Length of one time step in seconds.
+pub const STEP_SECS: u64 = 30;
+
+/// Digits per code.
+pub const DIGITS: u32 = 6;
+
+/// Steps accepted either side of the current one, for clock drift between
+/// the server and the phone.
+pub const DRIFT_STEPS: u64 = 1;
+
+/// Issuer shown in authenticator apps.
+pub const ISSUER: &str = "NeoMind";
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A new random secret, base32-encoded.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    base32_encode(&bytes)
+}
+
+/// The `otpauth://` URI to encode in the enrollment QR code.
+pub fn otpauth_uri(account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={digits}&period={period}",
+        issuer = ISSUER,
+        account = urlencoding::encode(account),
+        secret = secret,
+        digits = DIGITS,
+        period = STEP_SECS,
+    )
+}
+
+/// The code for a time step (RFC 4226 HOTP with dynamic truncation).
+pub fn code_at(secret: &[u8], step: u64, digits: u32) -> u32 {
+    let mut mac = HmacSha1::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    binary % 10u32.pow(digits)
+}
+
+/// Check a code against a base32 secret at `now` (Unix seconds).
+///
+/// Returns the matched time step. Steps at or before `last_step` are
+/// rejected, so a code that was already used cannot be replayed within its
+/// validity window.
+pub fn verify(secret: &str, code: &str, now: u64, last_step: Option<u64>) -> Option<u64> {
+    let code = code.trim().replace(' ', "");
+    if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let key = base32_decode(secret)?;
+    let current = now / STEP_SECS;
+    let first = current.saturating_sub(DRIFT_STEPS);
+    (first..=current + DRIFT_STEPS)
+        .filter(|step| last_step.is_none_or(|last| *step > last))
+        .find(|step| {
+            let expected = format!("{:06}", code_at(&key, *step, DIGITS));
+            crate::auth::constant_time_eq_str(&expected, &code)
+        })
+}
+
+/// RFC 4648 base32 without padding.
+pub fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Decode base32, ignoring case, spaces and padding.
+pub fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in input.bytes().filter(|c| !matches!(c, b' ' | b'=' | b'-')) {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|a| *a == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, SHA1 key
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        for (time, expected) in [
+            (59u64, 94287082),
+            (1111111109, 7081804),
+            (1234567890, 89005924),
+            (2000000000, 69279037),
+        ] {
+            assert_eq!(code_at(RFC_SECRET, time / STEP_SECS, 8), expected);
+        }
+    }
+
+    #[test]
+    fn test_base32_roundtrip() {
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("mzxw6ytboi======").unwrap(), b"foobar");
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        assert_eq!(base32_decode(&secret).unwrap().len(), 20);
+        assert!(base32_decode("not base32!").is_none());
+    }
+
+    #[test]
+    fn test_verify_drift_window_and_replay() {
+        let secret = base32_encode(RFC_SECRET);
+        // 287082 is the 6-digit code of step 1 (t = 30..59)
+        assert_eq!(verify(&secret, "287082", 59, None), Some(1));
+        assert_eq!(verify(&secret, "287 082", 89, None), Some(1));
+        assert_eq!(verify(&secret, "287082", 29, None), Some(1));
+        assert_eq!(verify(&secret, "287082", 95, None), None);
+        // Already used
+        assert_eq!(verify(&secret, "287082", 59, Some(1)), None);
+        assert_eq!(verify(&secret, "28708", 59, None), None);
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let uri = otpauth_uri("ops admin", "JBSWY3DPEHPK3PXP");
+        assert!(uri.starts_with("otpauth://totp/NeoMind:ops%20admin?secret=JBSWY3DPEHPK3PXP"));
+        assert!(uri.contains("issuer=NeoMind"));
+    }
+}
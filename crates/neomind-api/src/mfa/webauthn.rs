@@ -0,0 +1,501 @@
+//! WebAuthn passkeys as a second factor.
+//!
+//! Only the subset a self-hosted gateway needs is implemented: ES256
+//! (P-256) credentials with "none" attestation. The attestation statement
+//! is not verified, so a passkey proves possession of the key registered by
+//! a logged-in user, not the make of the authenticator. Client data type,
+//! challenge and origin, the RP ID hash, the user-presence flag, the
+//! signature and the signature counter are all checked.
+//!
+//! The relying party is derived from the browser's `Origin` at registration
+//! and stored with the passkey, so assertions are only accepted from the
+//! origin the passkey was created on.
+
+use base64::prelude::*;
+use ciborium::value::Value as Cbor;
+use rand::RngCore;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use super::CHALLENGE_TTL_SECS;
+
+/// COSE algorithm identifier of ES256.
+const COSE_ES256: i64 = -7;
+
+/// Authenticator data flags.
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// A registered passkey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Passkey {
+    /// Credential ID (base64url)
+    pub id: String,
+    pub name: String,
+    /// Uncompressed P-256 public key point (base64url)
+    pub public_key: String,
+    pub rp_id: String,
+    pub origin: String,
+    pub sign_count: u32,
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+}
+
+/// An outstanding registration ceremony.
+#[derive(Debug, Clone)]
+pub struct RegistrationChallenge {
+    pub challenge: String,
+    pub rp_id: String,
+    pub origin: String,
+    pub expires_at: i64,
+}
+
+/// The browser's `PublicKeyCredential` from `navigator.credentials.create()`,
+/// binary fields base64url-encoded.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegistrationResponse {
+    pub id: String,
+    pub client_data_json: String,
+    pub attestation_object: String,
+    /// Label shown in the passkey list
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// The browser's `PublicKeyCredential` from `navigator.credentials.get()`,
+/// binary fields base64url-encoded.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssertionResponse {
+    pub id: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+}
+
+struct AuthenticatorData {
+    rp_id_hash: Vec<u8>,
+    flags: u8,
+    sign_count: u32,
+    /// Credential ID and public key, present on registration
+    attested: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+/// A fresh random challenge (base64url).
+pub fn new_challenge() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE64_URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Start a registration ceremony for a page served from `origin`.
+pub fn registration_challenge(origin: &str, now: i64) -> Result<RegistrationChallenge, String> {
+    Ok(RegistrationChallenge {
+        challenge: new_challenge(),
+        rp_id: rp_id_from_origin(origin)?,
+        origin: origin.trim_end_matches('/').to_string(),
+        expires_at: now + CHALLENGE_TTL_SECS,
+    })
+}
+
+/// The RP ID for an origin: its host name without scheme or port.
+pub fn rp_id_from_origin(origin: &str) -> Result<String, String> {
+    let (scheme, rest) = origin
+        .split_once("://")
+        .ok_or_else(|| format!("Invalid origin: {}", origin))?;
+    if scheme != "https" && scheme != "http" {
+        return Err(format!("Unsupported origin scheme: {}", scheme));
+    }
+    let host = rest.split('/').next().unwrap_or_default();
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => host,
+    };
+    if host.is_empty() {
+        return Err(format!("Invalid origin: {}", origin));
+    }
+    Ok(host.to_ascii_lowercase())
+}
+
+/// `PublicKeyCredentialCreationOptions` for the browser.
+pub fn creation_options(
+    challenge: &RegistrationChallenge,
+    user_id: &str,
+    username: &str,
+    existing: &[Passkey],
+) -> serde_json::Value {
+    json!({
+        "challenge": challenge.challenge,
+        "rp": { "id": challenge.rp_id, "name": super::totp::ISSUER },
+        "user": {
+            "id": BASE64_URL_SAFE_NO_PAD.encode(user_id),
+            "name": username,
+            "displayName": username,
+        },
+        "pubKeyCredParams": [{ "type": "public-key", "alg": COSE_ES256 }],
+        "timeout": CHALLENGE_TTL_SECS * 1000,
+        "attestation": "none",
+        "authenticatorSelection": {
+            "residentKey": "preferred",
+            "userVerification": "preferred",
+        },
+        "excludeCredentials": credential_descriptors(existing),
+    })
+}
+
+/// `PublicKeyCredentialRequestOptions` for the browser.
+pub fn request_options(challenge: &str, passkeys: &[Passkey]) -> serde_json::Value {
+    json!({
+        "challenge": challenge,
+        "timeout": CHALLENGE_TTL_SECS * 1000,
+        "userVerification": "preferred",
+        "allowCredentials": credential_descriptors(passkeys),
+    })
+}
+
+fn credential_descriptors(passkeys: &[Passkey]) -> Vec<serde_json::Value> {
+    passkeys
+        .iter()
+        .map(|p| json!({ "type": "public-key", "id": p.id }))
+        .collect()
+}
+
+/// Verify a registration response and return the new passkey.
+pub fn verify_registration(
+    response: &RegistrationResponse,
+    challenge: &RegistrationChallenge,
+    now: i64,
+) -> Result<Passkey, String> {
+    let client_data = decode(&response.client_data_json, "client_data_json")?;
+    check_client_data(
+        &client_data,
+        "webauthn.create",
+        &challenge.challenge,
+        &challenge.origin,
+    )?;
+
+    let attestation = decode(&response.attestation_object, "attestation_object")?;
+    let auth_data = parse_authenticator_data(&attestation_auth_data(&attestation)?)?;
+    check_authenticator_data(&auth_data, &challenge.rp_id)?;
+    let (credential_id, public_key) = auth_data
+        .attested
+        .ok_or("Attestation carries no credential")?;
+
+    let id = BASE64_URL_SAFE_NO_PAD.encode(credential_id);
+    if id != response.id.trim_end_matches('=') {
+        return Err("Credential ID does not match the attested credential".to_string());
+    }
+    let name = response
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or("Passkey");
+    Ok(Passkey {
+        id,
+        name: name.to_string(),
+        public_key: BASE64_URL_SAFE_NO_PAD.encode(public_key),
+        rp_id: challenge.rp_id.clone(),
+        origin: challenge.origin.clone(),
+        sign_count: auth_data.sign_count,
+        created_at: now,
+        last_used_at: None,
+    })
+}
+
+/// Verify an assertion made with `passkey` for `challenge`, advancing the
+/// passkey's signature counter.
+pub fn verify_assertion(
+    response: &AssertionResponse,
+    challenge: &str,
+    passkey: &mut Passkey,
+    now: i64,
+) -> Result<(), String> {
+    let client_data = decode(&response.client_data_json, "client_data_json")?;
+    check_client_data(&client_data, "webauthn.get", challenge, &passkey.origin)?;
+
+    let raw_auth_data = decode(&response.authenticator_data, "authenticator_data")?;
+    let auth_data = parse_authenticator_data(&raw_auth_data)?;
+    check_authenticator_data(&auth_data, &passkey.rp_id)?;
+
+    let mut signed = raw_auth_data;
+    signed.extend_from_slice(&Sha256::digest(client_data.as_slice()));
+    let public_key = decode(&passkey.public_key, "public_key")?;
+    let signature = decode(&response.signature, "signature")?;
+    UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, public_key)
+        .verify(&signed, &signature)
+        .map_err(|_| "Invalid passkey signature".to_string())?;
+
+    // Authenticators that keep a counter must increase it on every use; a
+    // stale counter means the key was copied
+    if (auth_data.sign_count != 0 || passkey.sign_count != 0)
+        && auth_data.sign_count <= passkey.sign_count
+    {
+        return Err("Passkey signature counter went backwards".to_string());
+    }
+    passkey.sign_count = auth_data.sign_count;
+    passkey.last_used_at = Some(now);
+    Ok(())
+}
+
+fn decode(value: &str, field: &str) -> Result<Vec<u8>, String> {
+    BASE64_URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|e| format!("Invalid base64url in {}: {}", field, e))
+}
+
+fn check_client_data(raw: &[u8], kind: &str, challenge: &str, origin: &str) -> Result<(), String> {
+    let client_data: ClientData =
+        serde_json::from_slice(raw).map_err(|e| format!("Invalid client data: {}", e))?;
+    if client_data.kind != kind {
+        return Err(format!("Unexpected client data type: {}", client_data.kind));
+    }
+    if !crate::auth::constant_time_eq_str(client_data.challenge.trim_end_matches('='), challenge) {
+        return Err("Challenge does not match".to_string());
+    }
+    if client_data.origin.trim_end_matches('/') != origin {
+        return Err(format!("Unexpected origin: {}", client_data.origin));
+    }
+    Ok(())
+}
+
+fn check_authenticator_data(auth_data: &AuthenticatorData, rp_id: &str) -> Result<(), String> {
+    if auth_data.rp_id_hash != Sha256::digest(rp_id.as_bytes()).as_slice() {
+        return Err("Passkey belongs to a different relying party".to_string());
+    }
+    if auth_data.flags & FLAG_USER_PRESENT == 0 {
+        return Err("Authenticator did not confirm user presence".to_string());
+    }
+    Ok(())
+}
+
+/// The `authData` bytes of a CBOR attestation object.
+fn attestation_auth_data(attestation: &[u8]) -> Result<Vec<u8>, String> {
+    let value: Cbor = ciborium::de::from_reader(attestation)
+        .map_err(|e| format!("Invalid attestation object: {}", e))?;
+    map_get(&value, |k| k.as_text() == Some("authData"))
+        .and_then(Cbor::as_bytes)
+        .cloned()
+        .ok_or_else(|| "Attestation object has no authData".to_string())
+}
+
+fn parse_authenticator_data(data: &[u8]) -> Result<AuthenticatorData, String> {
+    if data.len() < 37 {
+        return Err("Authenticator data is too short".to_string());
+    }
+    let flags = data[32];
+    let sign_count = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+    let attested = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+        // aaguid (16) | credential id length (2) | credential id | COSE key
+        let rest = &data[37..];
+        if rest.len() < 18 {
+            return Err("Attested credential data is too short".to_string());
+        }
+        let id_len = u16::from_be_bytes([rest[16], rest[17]]) as usize;
+        let id = rest
+            .get(18..18 + id_len)
+            .ok_or("Credential ID is truncated")?
+            .to_vec();
+        let key: Cbor = ciborium::de::from_reader(&rest[18 + id_len..])
+            .map_err(|e| format!("Invalid credential public key: {}", e))?;
+        Some((id, cose_es256_public_key(&key)?))
+    } else {
+        None
+    };
+    Ok(AuthenticatorData {
+        rp_id_hash: data[..32].to_vec(),
+        flags,
+        sign_count,
+        attested,
+    })
+}
+
+/// Convert a COSE EC2 key to an uncompressed SEC1 point.
+fn cose_es256_public_key(key: &Cbor) -> Result<Vec<u8>, String> {
+    let int = |label: i128| {
+        map_get(key, |k| k.as_integer().map(i128::from) == Some(label))
+            .and_then(Cbor::as_integer)
+            .map(i128::from)
+    };
+    let bytes = |label: i128| {
+        map_get(key, |k| k.as_integer().map(i128::from) == Some(label)).and_then(Cbor::as_bytes)
+    };
+    // kty 2 = EC2, crv 1 = P-256
+    if int(1) != Some(2) || int(3) != Some(COSE_ES256 as i128) || int(-1) != Some(1) {
+        return Err("Only ES256 (P-256) passkeys are supported".to_string());
+    }
+    match (bytes(-2), bytes(-3)) {
+        (Some(x), Some(y)) if x.len() == 32 && y.len() == 32 => {
+            let mut point = Vec::with_capacity(65);
+            point.push(0x04);
+            point.extend_from_slice(x);
+            point.extend_from_slice(y);
+            Ok(point)
+        }
+        _ => Err("Malformed P-256 public key".to_string()),
+    }
+}
+
+fn map_get(map: &Cbor, matches: impl Fn(&Cbor) -> bool) -> Option<&Cbor> {
+    map.as_map()?
+        .iter()
+        .find(|(k, _)| matches(k))
+        .map(|(_, v)| v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+
+    const ORIGIN: &str = "https://gateway.local:9375";
+
+    struct Authenticator {
+        key: EcdsaKeyPair,
+        rng: SystemRandom,
+        credential_id: Vec<u8>,
+        counter: u32,
+    }
+
+    impl Authenticator {
+        fn new() -> Self {
+            let rng = SystemRandom::new();
+            let pkcs8 =
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+            let key =
+                EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng)
+                    .unwrap();
+            Self {
+                key,
+                rng,
+                credential_id: vec![7; 16],
+                counter: 0,
+            }
+        }
+
+        fn auth_data(&mut self, rp_id: &str, attest: bool) -> Vec<u8> {
+            self.counter += 1;
+            let mut data = Sha256::digest(rp_id.as_bytes()).to_vec();
+            let flags = FLAG_USER_PRESENT | if attest { FLAG_ATTESTED_CREDENTIAL } else { 0 };
+            data.push(flags);
+            data.extend_from_slice(&self.counter.to_be_bytes());
+            if attest {
+                data.extend_from_slice(&[0; 16]);
+                data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+                data.extend_from_slice(&self.credential_id);
+                let point = self.key.public_key().as_ref();
+                let int = |v: i64| Cbor::Integer(v.into());
+                let cose = Cbor::Map(vec![
+                    (int(1), int(2)),
+                    (int(3), int(COSE_ES256)),
+                    (int(-1), int(1)),
+                    (int(-2), Cbor::Bytes(point[1..33].to_vec())),
+                    (int(-3), Cbor::Bytes(point[33..].to_vec())),
+                ]);
+                ciborium::ser::into_writer(&cose, &mut data).unwrap();
+            }
+            data
+        }
+
+        fn register(&mut self, challenge: &RegistrationChallenge) -> RegistrationResponse {
+            let client_data = client_data("webauthn.create", &challenge.challenge);
+            let attestation = Cbor::Map(vec![
+                (Cbor::Text("fmt".into()), Cbor::Text("none".into())),
+                (Cbor::Text("attStmt".into()), Cbor::Map(vec![])),
+                (
+                    Cbor::Text("authData".into()),
+                    Cbor::Bytes(self.auth_data(&challenge.rp_id, true)),
+                ),
+            ]);
+            let mut attestation_object = Vec::new();
+            ciborium::ser::into_writer(&attestation, &mut attestation_object).unwrap();
+            RegistrationResponse {
+                id: BASE64_URL_SAFE_NO_PAD.encode(&self.credential_id),
+                client_data_json: BASE64_URL_SAFE_NO_PAD.encode(client_data),
+                attestation_object: BASE64_URL_SAFE_NO_PAD.encode(attestation_object),
+                name: Some("YubiKey".to_string()),
+            }
+        }
+
+        fn assert(&mut self, rp_id: &str, challenge: &str) -> AssertionResponse {
+            let client_data = client_data("webauthn.get", challenge);
+            let auth_data = self.auth_data(rp_id, false);
+            let mut signed = auth_data.clone();
+            signed.extend_from_slice(&Sha256::digest(&client_data));
+            let signature = self.key.sign(&self.rng, &signed).unwrap();
+            AssertionResponse {
+                id: BASE64_URL_SAFE_NO_PAD.encode(&self.credential_id),
+                client_data_json: BASE64_URL_SAFE_NO_PAD.encode(client_data),
+                authenticator_data: BASE64_URL_SAFE_NO_PAD.encode(auth_data),
+                signature: BASE64_URL_SAFE_NO_PAD.encode(signature.as_ref()),
+            }
+        }
+    }
+
+    fn client_data(kind: &str, challenge: &str) -> Vec<u8> {
+        json!({ "type": kind, "challenge": challenge, "origin": ORIGIN })
+            .to_string()
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_rp_id_from_origin() {
+        assert_eq!(rp_id_from_origin(ORIGIN).unwrap(), "gateway.local");
+        assert_eq!(rp_id_from_origin("http://localhost").unwrap(), "localhost");
+        assert!(rp_id_from_origin("gateway.local").is_err());
+        assert!(rp_id_from_origin("ftp://gateway.local").is_err());
+    }
+
+    #[test]
+    fn test_register_and_assert() {
+        let mut authenticator = Authenticator::new();
+        let challenge = registration_challenge(ORIGIN, 0).unwrap();
+        let response = authenticator.register(&challenge);
+        let mut passkey = verify_registration(&response, &challenge, 0).unwrap();
+        assert_eq!(passkey.name, "YubiKey");
+        assert_eq!(passkey.rp_id, "gateway.local");
+        assert_eq!(passkey.sign_count, 1);
+
+        let login_challenge = new_challenge();
+        let assertion = authenticator.assert("gateway.local", &login_challenge);
+        verify_assertion(&assertion, &login_challenge, &mut passkey, 10).unwrap();
+        assert_eq!(passkey.sign_count, 2);
+        assert_eq!(passkey.last_used_at, Some(10));
+
+        // Replaying the same assertion fails on the counter
+        let err = verify_assertion(&assertion, &login_challenge, &mut passkey, 20).unwrap_err();
+        assert!(err.contains("counter"));
+    }
+
+    #[test]
+    fn test_assertion_rejections() {
+        let mut authenticator = Authenticator::new();
+        let challenge = registration_challenge(ORIGIN, 0).unwrap();
+        let response = authenticator.register(&challenge);
+        let mut passkey = verify_registration(&response, &challenge, 0).unwrap();
+
+        let assertion = authenticator.assert("gateway.local", "other-challenge");
+        let err = verify_assertion(&assertion, &new_challenge(), &mut passkey, 0).unwrap_err();
+        assert!(err.contains("Challenge"));
+
+        let challenge = new_challenge();
+        let assertion = authenticator.assert("evil.example", &challenge);
+        assert!(verify_assertion(&assertion, &challenge, &mut passkey, 0).is_err());
+
+        let mut assertion = authenticator.assert("gateway.local", &challenge);
+        assertion.signature = BASE64_URL_SAFE_NO_PAD.encode([0u8; 70]);
+        let err = verify_assertion(&assertion, &challenge, &mut passkey, 0).unwrap_err();
+        assert!(err.contains("signature"));
+    }
+}
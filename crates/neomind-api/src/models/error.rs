@@ -194,6 +194,41 @@ impl From<neomind_rules::RuleError> for ErrorResponse {
     }
 }
 
+impl From<neomind_core::config::service::ConfigError> for ErrorResponse {
+    fn from(e: neomind_core::config::service::ConfigError) -> Self {
+        use neomind_core::config::service::ConfigError;
+        match e {
+            ConfigError::UnknownKey(_) | ConfigError::Invalid { .. } => {
+                Self::bad_request(e.to_string())
+            }
+            ConfigError::EnvOverride { .. } => Self::conflict(e.to_string()),
+            ConfigError::Store(_) => Self::internal(e.to_string()),
+        }
+    }
+}
+
+impl From<neomind_core::secrets::SecretError> for ErrorResponse {
+    fn from(e: neomind_core::secrets::SecretError) -> Self {
+        use neomind_core::secrets::SecretError;
+        match e {
+            SecretError::InvalidName(_) => Self::bad_request(e.to_string()),
+            SecretError::NotFound(name) => Self::not_found(format!("Secret '{}'", name)),
+            SecretError::AlreadyExists(_) => Self::conflict(e.to_string()),
+            SecretError::NoResolver(_) | SecretError::Store(_) => Self::internal(e.to_string()),
+        }
+    }
+}
+
+impl From<neomind_agent::agent::ApprovalError> for ErrorResponse {
+    fn from(e: neomind_agent::agent::ApprovalError) -> Self {
+        use neomind_agent::agent::ApprovalError;
+        match e {
+            ApprovalError::NotFound(id) => Self::not_found(format!("Pending action '{}'", id)),
+            ApprovalError::AlreadyDecided { .. } => Self::conflict(e.to_string()),
+        }
+    }
+}
+
 impl From<neomind_storage::Error> for ErrorResponse {
     fn from(e: neomind_storage::Error) -> Self {
         Self::internal(format!("Storage error: {}", e))
@@ -73,6 +73,9 @@ pub struct SessionConfigPatch {
     /// Enable or disable tool calling for this session.
     #[serde(rename = "enableTools")]
     pub enable_tools: Option<bool>,
+    /// Restrict the session to these tools.
+    #[serde(rename = "allowedTools")]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 impl From<SessionConfigPatch> for neomind_agent::CreateSessionOptions {
@@ -82,6 +85,7 @@ impl From<SessionConfigPatch> for neomind_agent::CreateSessionOptions {
             temperature: p.temperature,
             model: p.model,
             enable_tools: p.enable_tools,
+            allowed_tools: p.allowed_tools,
         }
     }
 }
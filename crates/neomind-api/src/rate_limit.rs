@@ -16,6 +16,12 @@ use axum::{
 };
 use dashmap::DashMap;
 
+/// Response header with the requests left in the current window.
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "X-RateLimit-Remaining";
+
+/// Response header with the LLM tokens an API key may still use today.
+pub const QUOTA_TOKENS_REMAINING_HEADER: &str = "X-Quota-Tokens-Remaining";
+
 /// Rate limiter configuration.
 #[derive(Clone)]
 pub struct RateLimitConfig {
@@ -120,6 +126,17 @@ impl RateLimiter {
     /// are lock-free and don't require awaiting. This reduces async overhead
     /// by ~200ns per call.
     pub fn check_rate_limit(&self, client_key: &str) -> Result<(), RateLimitExceeded> {
+        self.check(client_key, None).map(|_| ())
+    }
+
+    /// Check a request against `max_requests` per window (the configured
+    /// limit when `None`) and return how many requests remain.
+    pub fn check(
+        &self,
+        client_key: &str,
+        max_requests: Option<u32>,
+    ) -> Result<u32, RateLimitExceeded> {
+        let max_requests = max_requests.unwrap_or(self.config.max_requests) as usize;
         let now = Instant::now();
         let window_start = now - self.config.per_duration;
 
@@ -138,7 +155,7 @@ impl RateLimiter {
         state.history.retain(|&timestamp| timestamp > window_start);
 
         // Check if the client has exceeded the limit
-        if state.history.len() >= self.config.max_requests as usize {
+        if state.history.len() >= max_requests {
             let oldest = state.history.first().copied();
             if let Some(oldest_timestamp) = oldest {
                 let wait_time = self
@@ -176,7 +193,7 @@ impl RateLimiter {
         // Reset warning when request is allowed
         state.last_warning = None;
 
-        Ok(())
+        Ok(max_requests.saturating_sub(state.history.len()) as u32)
     }
 
     /// Clean up old entries to prevent memory leak.
@@ -322,6 +339,22 @@ mod tests {
         assert!(limiter.check_rate_limit("client1").is_ok());
     }
 
+    #[test]
+    fn test_per_key_limit_and_remaining() {
+        let limiter = RateLimiter::with_config(RateLimitConfig {
+            max_requests: 100,
+            per_duration: Duration::from_secs(60),
+            warn_interval: Duration::from_secs(1),
+        });
+
+        assert_eq!(limiter.check("dashboard", Some(2)).unwrap(), 1);
+        assert_eq!(limiter.check("dashboard", Some(2)).unwrap(), 0);
+        assert!(limiter.check("dashboard", Some(2)).is_err());
+
+        // Other clients keep the global limit.
+        assert_eq!(limiter.check("other", None).unwrap(), 99);
+    }
+
     #[test]
     fn test_adaptive_config() {
         let config = RateLimitConfig::default();
@@ -0,0 +1,433 @@
+//! Scheduled and on-demand reports.
+//!
+//! A [`ReportTemplate`] (stored in `neomind_storage::reports`) names devices,
+//! metrics and a look-back range. Generating it collects summary statistics
+//! and a downsampled series per metric from telemetry, renders an HTML or
+//! PDF document under `data/reports/`, records a [`ReportRecord`] and sends
+//! the file as an attachment through the template's message channels.
+//!
+//! Templates with a cron `schedule` are picked up by a 30s scheduler tick,
+//! the same cadence as schedule-type rules.
+
+mod render;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use neomind_devices::{DeviceService, TimeSeriesStorage};
+use neomind_messages::{Message, MessageAttachment, MessageManager, MessageSeverity};
+use neomind_storage::{ReportDelivery, ReportFormat, ReportRecord, ReportStore, ReportTemplate};
+use serde::Serialize;
+
+pub use render::{render_html, render_pdf};
+
+/// Points per chart series; enough for a readable line at page width.
+const CHART_POINTS: usize = 120;
+
+/// Generated reports kept per template; older ones are deleted.
+const MAX_REPORTS_PER_TEMPLATE: usize = 50;
+
+/// Scheduler tick interval in seconds.
+const SCHEDULER_TICK_SECS: i64 = 30;
+
+/// Statistics of one metric of one device over the report range.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricSummary {
+    pub device_id: String,
+    pub device_name: String,
+    pub metric: String,
+    pub count: u64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub avg: Option<f64>,
+    pub last: Option<f64>,
+    /// Downsampled (timestamp, value) pairs for the chart; empty when the
+    /// section has charts disabled
+    pub series: Vec<(i64, f64)>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionData {
+    pub title: String,
+    pub chart: bool,
+    pub metrics: Vec<MetricSummary>,
+}
+
+/// Everything a renderer needs.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportData {
+    pub title: String,
+    pub description: String,
+    pub range_start: i64,
+    pub range_end: i64,
+    pub generated_at: i64,
+    pub sections: Vec<SectionData>,
+}
+
+/// Whether a cron schedule has a fire time in `(since, now]`, where `since`
+/// is the later of the last run and one tick ago. Using the last run keeps a
+/// slow tick from firing the same slot twice.
+pub fn schedule_due(schedule: &str, last_run_at: Option<i64>, now: i64) -> Result<bool, String> {
+    let parsed: cron::Schedule = schedule
+        .parse()
+        .map_err(|e| format!("Invalid cron expression '{}': {}", schedule, e))?;
+    let since = last_run_at
+        .unwrap_or(i64::MIN)
+        .max(now - SCHEDULER_TICK_SECS);
+    let Some(since) = DateTime::<Utc>::from_timestamp(since, 0) else {
+        return Ok(false);
+    };
+    Ok(parsed
+        .after(&since)
+        .next()
+        .is_some_and(|next| next.timestamp() <= now))
+}
+
+/// Generates, stores and delivers reports.
+pub struct ReportManager {
+    dir: PathBuf,
+    store: Arc<ReportStore>,
+    devices: Arc<DeviceService>,
+    telemetry: Arc<TimeSeriesStorage>,
+    messages: Arc<MessageManager>,
+}
+
+impl ReportManager {
+    pub fn new(
+        dir: PathBuf,
+        store: Arc<ReportStore>,
+        devices: Arc<DeviceService>,
+        telemetry: Arc<TimeSeriesStorage>,
+        messages: Arc<MessageManager>,
+    ) -> Self {
+        Self {
+            dir,
+            store,
+            devices,
+            telemetry,
+            messages,
+        }
+    }
+
+    pub fn store(&self) -> &Arc<ReportStore> {
+        &self.store
+    }
+
+    pub fn file_path(&self, record: &ReportRecord) -> PathBuf {
+        self.dir.join(record.file_name())
+    }
+
+    /// Render a template for the range ending now, store the file and send
+    /// it to the template's channels. Delivery failures are recorded on the
+    /// returned record rather than failing the run.
+    pub async fn generate(
+        &self,
+        template: &ReportTemplate,
+        trigger: &str,
+    ) -> Result<ReportRecord, String> {
+        let now = Utc::now().timestamp();
+        let range_start = now - template.range_secs as i64;
+        let data = self.collect(template, range_start, now).await?;
+        let bytes = match template.format {
+            ReportFormat::Html => render_html(&data).into_bytes(),
+            ReportFormat::Pdf => render_pdf(&data),
+        };
+
+        let mut record = ReportRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            template_id: template.id.clone(),
+            template_name: template.name.clone(),
+            format: template.format,
+            range_start,
+            range_end: now,
+            generated_at: now,
+            size_bytes: bytes.len() as u64,
+            trigger: trigger.to_string(),
+            deliveries: Vec::new(),
+            tenant_id: template.tenant_id.clone(),
+        };
+
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| format!("Failed to create report directory: {}", e))?;
+        tokio::fs::write(self.file_path(&record), &bytes)
+            .await
+            .map_err(|e| format!("Failed to write report: {}", e))?;
+
+        record.deliveries = self.deliver(template, &record, &bytes).await;
+        self.store
+            .save_report(&record)
+            .map_err(|e| format!("Failed to save report record: {}", e))?;
+
+        // Re-read so edits made while rendering are not overwritten
+        if let Ok(Some(mut stored)) = self.store.get_template(&template.id) {
+            stored.last_run_at = Some(now);
+            if let Err(e) = self.store.save_template(&stored) {
+                tracing::warn!("Failed to update report template {}: {}", template.id, e);
+            }
+        }
+        self.prune(&template.id).await;
+        Ok(record)
+    }
+
+    /// Remove a generated report and its file. Returns whether it existed.
+    pub async fn delete_report(&self, id: &str) -> Result<bool, String> {
+        let record = self
+            .store
+            .get_report(id)
+            .map_err(|e| format!("Failed to load report: {}", e))?;
+        let Some(record) = record else {
+            return Ok(false);
+        };
+        let _ = tokio::fs::remove_file(self.file_path(&record)).await;
+        self.store
+            .delete_report(id)
+            .map_err(|e| format!("Failed to delete report: {}", e))
+    }
+
+    /// Start the background task that runs scheduled templates.
+    pub fn start_scheduler(self: &Arc<Self>) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            tracing::info!("Starting report scheduler ({}s tick)", SCHEDULER_TICK_SECS);
+            let period = std::time::Duration::from_secs(SCHEDULER_TICK_SECS as u64);
+            let mut ticker = tokio::time::interval(period);
+            ticker.tick().await; // First tick completes immediately
+
+            loop {
+                ticker.tick().await;
+                manager.run_due(Utc::now().timestamp()).await;
+            }
+        });
+    }
+
+    async fn run_due(&self, now: i64) {
+        let templates = match self.store.list_templates() {
+            Ok(templates) => templates,
+            Err(e) => {
+                tracing::warn!("Failed to list report templates: {}", e);
+                return;
+            }
+        };
+        for template in templates {
+            let Some(schedule) = template.schedule.as_deref().filter(|_| template.enabled) else {
+                continue;
+            };
+            match schedule_due(schedule, template.last_run_at, now) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::warn!("Report template {}: {}", template.id, e);
+                    continue;
+                }
+            }
+            match self.generate(&template, "schedule").await {
+                Ok(record) => tracing::info!(
+                    template_id = %template.id,
+                    report_id = %record.id,
+                    size_bytes = record.size_bytes,
+                    "Scheduled report generated"
+                ),
+                Err(e) => tracing::warn!(
+                    template_id = %template.id,
+                    error = %e,
+                    "Scheduled report failed"
+                ),
+            }
+        }
+    }
+
+    async fn collect(
+        &self,
+        template: &ReportTemplate,
+        start: i64,
+        end: i64,
+    ) -> Result<ReportData, String> {
+        // Make buffered writes visible to range queries.
+        let _ = self.telemetry.flush();
+
+        let mut sections = Vec::with_capacity(template.sections.len());
+        for section in &template.sections {
+            let mut metrics = Vec::new();
+            for device_id in &section.device_ids {
+                let device_name = self
+                    .devices
+                    .get_device(device_id)
+                    .map(|d| d.name)
+                    .unwrap_or_else(|| device_id.clone());
+                let source_id = format!("device:{}", device_id);
+                let names = if section.metrics.is_empty() {
+                    self.telemetry
+                        .list_metrics(&source_id)
+                        .await
+                        .map_err(|e| format!("Failed to list metrics of {}: {}", device_id, e))?
+                        .into_iter()
+                        .filter(|m| !m.is_empty() && m != "_raw")
+                        .collect()
+                } else {
+                    section.metrics.clone()
+                };
+
+                for metric in names {
+                    let stats = self
+                        .telemetry
+                        .aggregate(&source_id, &metric, start, end)
+                        .await
+                        .map_err(|e| format!("Failed to query {}/{}: {}", device_id, metric, e))?;
+                    // Listed metrics without numeric data (strings, blobs)
+                    // have nothing to summarize
+                    if section.metrics.is_empty() && stats.avg.is_none() {
+                        continue;
+                    }
+                    let series = if section.chart {
+                        self.series(&source_id, &metric, start, end).await
+                    } else {
+                        Vec::new()
+                    };
+                    metrics.push(MetricSummary {
+                        device_id: device_id.clone(),
+                        device_name: device_name.clone(),
+                        metric,
+                        count: stats.count,
+                        min: stats.min,
+                        max: stats.max,
+                        avg: stats.avg,
+                        last: stats.last.as_ref().and_then(|v| v.as_f64()),
+                        series,
+                    });
+                }
+            }
+            sections.push(SectionData {
+                title: section.title.clone(),
+                chart: section.chart,
+                metrics,
+            });
+        }
+
+        Ok(ReportData {
+            title: template.name.clone(),
+            description: template.description.clone(),
+            range_start: start,
+            range_end: end,
+            generated_at: end,
+            sections,
+        })
+    }
+
+    async fn series(&self, source_id: &str, metric: &str, start: i64, end: i64) -> Vec<(i64, f64)> {
+        match self
+            .telemetry
+            .query_bucketed(source_id, metric, start, end, CHART_POINTS)
+            .await
+        {
+            Ok((points, _)) => points
+                .iter()
+                .filter_map(|p| p.value.as_f64().map(|v| (p.timestamp, v)))
+                .collect(),
+            Err(e) => {
+                tracing::debug!("No chart series for {}/{}: {}", source_id, metric, e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn deliver(
+        &self,
+        template: &ReportTemplate,
+        record: &ReportRecord,
+        bytes: &[u8],
+    ) -> Vec<ReportDelivery> {
+        if template.channels.is_empty() {
+            return Vec::new();
+        }
+
+        let mut message = Message::new(
+            "report",
+            MessageSeverity::Info,
+            format!("Report: {}", template.name),
+            format!(
+                "{} report covering {} to {} is attached.",
+                template.name,
+                render::format_time(record.range_start),
+                render::format_time(record.range_end)
+            ),
+            template.id.clone(),
+        )
+        .with_metadata(serde_json::json!({
+            "report_id": record.id,
+            "template_id": template.id,
+            "range_start": record.range_start,
+            "range_end": record.range_end,
+        }))
+        .with_attachment(MessageAttachment::new(
+            record.file_name(),
+            record.format.content_type(),
+            bytes,
+        ));
+        message.source_type = "report".to_string();
+
+        let registry = self.messages.channels().await;
+        let channels = registry.read().await;
+        let mut deliveries = Vec::with_capacity(template.channels.len());
+        for name in &template.channels {
+            let result = match channels.get(name).await {
+                None => Err(format!("Channel '{}' not found", name)),
+                Some(channel) => {
+                    if channels.is_enabled_effective(name).await {
+                        channel.send(&message).await.map_err(|e| e.to_string())
+                    } else {
+                        Err(format!("Channel '{}' is disabled", name))
+                    }
+                }
+            };
+            if let Err(e) = &result {
+                tracing::warn!("Failed to deliver report {} to '{}': {}", record.id, name, e);
+            }
+            deliveries.push(ReportDelivery {
+                channel: name.clone(),
+                success: result.is_ok(),
+                error: result.err(),
+            });
+        }
+        deliveries
+    }
+
+    /// Keep the newest [`MAX_REPORTS_PER_TEMPLATE`] reports of a template.
+    async fn prune(&self, template_id: &str) {
+        let reports = match self.store.list_reports(Some(template_id)) {
+            Ok(reports) => reports,
+            Err(e) => {
+                tracing::warn!("Failed to list reports of {}: {}", template_id, e);
+                return;
+            }
+        };
+        for old in reports.iter().skip(MAX_REPORTS_PER_TEMPLATE) {
+            if let Err(e) = self.delete_report(&old.id).await {
+                tracing::warn!("Failed to prune report {}: {}", old.id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_due() {
+        // Every day at 06:00:00 UTC; 2026-03-01 06:00:00 = 1772344800
+        let daily = "0 0 6 * * *";
+        let slot = 1_772_344_800;
+        assert!(schedule_due(daily, None, slot + 10).unwrap());
+        assert!(!schedule_due(daily, None, slot - 10).unwrap());
+        // Missed by more than a tick
+        assert!(!schedule_due(daily, None, slot + 60).unwrap());
+        // Already ran this slot
+        assert!(!schedule_due(daily, Some(slot + 5), slot + 20).unwrap());
+        // Ran yesterday
+        assert!(schedule_due(daily, Some(slot - 86_400), slot + 20).unwrap());
+
+        assert!(schedule_due("not a cron", None, slot).is_err());
+    }
+}
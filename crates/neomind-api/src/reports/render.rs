@@ -0,0 +1,505 @@
+//! HTML and PDF rendering of collected report data.
+//!
+//! HTML reports are self-contained (inline CSS, SVG charts). PDF reports are
+//! written directly as PDF 1.4 with the built-in Helvetica fonts, so no font
+//! files or rendering engine are needed; the trade-off is that text outside
+//! Latin-1 is shown as `?` in PDFs. Use HTML for names in other scripts.
+
+use std::fmt::Write;
+
+use chrono::DateTime;
+
+use super::{MetricSummary, ReportData};
+
+const CHART_WIDTH: f32 = 640.0;
+const CHART_HEIGHT: f32 = 140.0;
+
+pub(super) fn format_time(ts: i64) -> String {
+    DateTime::from_timestamp(ts, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+/// Up to two decimals, without trailing zeros.
+fn format_number(value: Option<f64>) -> String {
+    let Some(value) = value else {
+        return "-".to_string();
+    };
+    let s = format!("{:.2}", value);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Map a series onto a `width` x `height` box, y growing upwards.
+fn scale_series(series: &[(i64, f64)], width: f32, height: f32) -> Vec<(f32, f32)> {
+    let (Some(first), Some(last)) = (series.first(), series.last()) else {
+        return Vec::new();
+    };
+    let span = (last.0 - first.0).max(1) as f32;
+    let min = series.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max = series.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    series
+        .iter()
+        .map(|(ts, v)| {
+            let x = (ts - first.0) as f32 / span * width;
+            let y = if max > min {
+                ((v - min) / (max - min)) as f32 * height
+            } else {
+                height / 2.0
+            };
+            (x, y)
+        })
+        .collect()
+}
+
+fn chart_label(metric: &MetricSummary) -> String {
+    format!("{} - {}", metric.device_name, metric.metric)
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;\
+margin:32px;color:#222}h1{margin-bottom:4px}.meta{color:#666;margin-top:0}\
+table{border-collapse:collapse;margin:12px 0;min-width:60%}\
+th,td{border:1px solid #ddd;padding:4px 10px;text-align:right}\
+th:first-child,td:first-child,th:nth-child(2),td:nth-child(2){text-align:left}\
+th{background:#f4f4f4}figure{margin:16px 0}figcaption{font-size:13px;color:#444}\
+.empty{color:#888}";
+
+/// Render a standalone HTML document.
+pub fn render_html(data: &ReportData) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title>\
+         <style>{}</style></head><body>\n<h1>{}</h1>\n<p class=\"meta\">{} to {} \
+         &middot; generated {}</p>\n",
+        escape_html(&data.title),
+        HTML_STYLE,
+        escape_html(&data.title),
+        format_time(data.range_start),
+        format_time(data.range_end),
+        format_time(data.generated_at)
+    );
+    if !data.description.is_empty() {
+        let _ = writeln!(html, "<p>{}</p>", escape_html(&data.description));
+    }
+
+    for section in &data.sections {
+        let _ = writeln!(html, "<h2>{}</h2>", escape_html(&section.title));
+        if section.metrics.is_empty() {
+            html.push_str("<p class=\"empty\">No data in this range.</p>\n");
+            continue;
+        }
+        html.push_str(
+            "<table><tr><th>Device</th><th>Metric</th><th>Samples</th><th>Min</th>\
+             <th>Avg</th><th>Max</th><th>Last</th></tr>\n",
+        );
+        for m in &section.metrics {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+                 <td>{}</td></tr>",
+                escape_html(&m.device_name),
+                escape_html(&m.metric),
+                m.count,
+                format_number(m.min),
+                format_number(m.avg),
+                format_number(m.max),
+                format_number(m.last)
+            );
+        }
+        html.push_str("</table>\n");
+
+        for m in section.metrics.iter().filter(|m| m.series.len() > 1) {
+            let _ = writeln!(
+                html,
+                "<figure><figcaption>{}</figcaption>{}</figure>",
+                escape_html(&chart_label(m)),
+                svg_chart(m)
+            );
+        }
+    }
+    html.push_str("</body></html>\n");
+    html
+}
+
+fn svg_chart(metric: &MetricSummary) -> String {
+    // Left gutter for the min/max labels
+    let gutter = 56.0;
+    let pad = 8.0;
+    let width = CHART_WIDTH + gutter + pad;
+    let height = CHART_HEIGHT + 2.0 * pad;
+    let points: Vec<String> = scale_series(&metric.series, CHART_WIDTH, CHART_HEIGHT)
+        .into_iter()
+        .map(|(x, y)| format!("{:.1},{:.1}", gutter + x, pad + CHART_HEIGHT - y))
+        .collect();
+    let min = metric.series.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max = metric.series.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-size=\"11\" fill=\"#666\">\
+         <rect x=\"{gutter}\" y=\"{pad}\" width=\"{cw}\" height=\"{ch}\" fill=\"none\" \
+         stroke=\"#ddd\"/>\
+         <text x=\"{label_x}\" y=\"{top}\" text-anchor=\"end\">{max}</text>\
+         <text x=\"{label_x}\" y=\"{bottom}\" text-anchor=\"end\">{min}</text>\
+         <polyline fill=\"none\" stroke=\"#1f6feb\" stroke-width=\"1.5\" points=\"{points}\"/>\
+         </svg>",
+        w = width,
+        h = height,
+        cw = CHART_WIDTH,
+        ch = CHART_HEIGHT,
+        label_x = gutter - 6.0,
+        top = pad + 10.0,
+        bottom = pad + CHART_HEIGHT,
+        max = format_number(Some(max)),
+        min = format_number(Some(min)),
+        points = points.join(" ")
+    )
+}
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+
+/// Table column x positions: device, metric, samples, min, avg, max, last.
+const COLUMNS: [f32; 7] = [50.0, 190.0, 300.0, 350.0, 405.0, 460.0, 515.0];
+
+/// Render a PDF document.
+pub fn render_pdf(data: &ReportData) -> Vec<u8> {
+    let mut pdf = PdfWriter::new();
+    pdf.text(MARGIN, 18.0, true, &data.title);
+    pdf.text(
+        MARGIN,
+        10.0,
+        false,
+        &format!(
+            "{} to {} - generated {}",
+            format_time(data.range_start),
+            format_time(data.range_end),
+            format_time(data.generated_at)
+        ),
+    );
+    if !data.description.is_empty() {
+        pdf.text(MARGIN, 10.0, false, &data.description);
+    }
+
+    for section in &data.sections {
+        pdf.gap(10.0);
+        pdf.text(MARGIN, 14.0, true, &section.title);
+        if section.metrics.is_empty() {
+            pdf.text(MARGIN, 10.0, false, "No data in this range.");
+            continue;
+        }
+        let header = ["Device", "Metric", "Samples", "Min", "Avg", "Max", "Last"];
+        pdf.row(9.0, true, &header.map(String::from));
+        for m in &section.metrics {
+            pdf.row(
+                9.0,
+                false,
+                &[
+                    truncate(&m.device_name, 26),
+                    truncate(&m.metric, 20),
+                    m.count.to_string(),
+                    format_number(m.min),
+                    format_number(m.avg),
+                    format_number(m.max),
+                    format_number(m.last),
+                ],
+            );
+        }
+
+        for m in section.metrics.iter().filter(|m| m.series.len() > 1) {
+            pdf.chart(&chart_label(m), &m.series);
+        }
+    }
+    pdf.finish()
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        s.to_string()
+    } else {
+        let head: String = s.chars().take(max_chars - 3).collect();
+        format!("{}...", head)
+    }
+}
+
+/// Escape a PDF literal string. Latin-1 characters map onto WinAnsiEncoding
+/// as octal escapes; anything else becomes `?`.
+fn escape_pdf(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(out, "\\{:03o}", c as u32);
+            }
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+/// Minimal single-font PDF writer: text lines, table rows and line charts
+/// flowing top to bottom over A4 pages.
+struct PdfWriter {
+    pages: Vec<String>,
+    content: String,
+    /// Baseline of the next line
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            content: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.pages.push(std::mem::take(&mut self.content));
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn text(&mut self, x: f32, size: f32, bold: bool, text: &str) {
+        self.row_at(&[x], size, bold, &[text.to_string()]);
+    }
+
+    fn row(&mut self, size: f32, bold: bool, cells: &[String]) {
+        self.row_at(&COLUMNS, size, bold, cells);
+    }
+
+    fn row_at(&mut self, columns: &[f32], size: f32, bold: bool, cells: &[String]) {
+        let line_height = size * 1.5;
+        self.ensure_space(line_height);
+        self.y -= line_height;
+        let font = if bold { "F2" } else { "F1" };
+        for (x, cell) in columns.iter().zip(cells) {
+            let _ = writeln!(
+                self.content,
+                "BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET",
+                font,
+                size,
+                x,
+                self.y,
+                escape_pdf(cell)
+            );
+        }
+    }
+
+    fn chart(&mut self, label: &str, series: &[(i64, f64)]) {
+        let gutter = 50.0;
+        let width = PAGE_WIDTH - 2.0 * MARGIN - gutter;
+        let height = 90.0;
+        // Caption, box and some air below
+        self.ensure_space(height + 30.0);
+        self.gap(6.0);
+        self.text(MARGIN, 9.0, false, label);
+        self.y -= 4.0;
+
+        let left = MARGIN + gutter;
+        let bottom = self.y - height;
+        let min = series.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let max = series.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+        let _ = writeln!(
+            self.content,
+            "0.85 0.85 0.85 RG 0.5 w {:.1} {:.1} {:.1} {:.1} re S",
+            left, bottom, width, height
+        );
+        for (value, y) in [(max, self.y - 8.0), (min, bottom)] {
+            let _ = writeln!(
+                self.content,
+                "BT /F1 8 Tf {:.1} {:.1} Td ({}) Tj ET",
+                MARGIN,
+                y,
+                escape_pdf(&format_number(Some(value)))
+            );
+        }
+
+        let mut path = String::from("0.12 0.44 0.92 RG 1 w");
+        for (i, (x, y)) in scale_series(series, width, height).into_iter().enumerate() {
+            let op = if i == 0 { "m" } else { "l" };
+            let _ = write!(path, " {:.1} {:.1} {}", left + x, bottom + y, op);
+        }
+        path.push_str(" S");
+        let _ = writeln!(self.content, "{}", path);
+        self.y = bottom - 10.0;
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let mut pages = self.pages;
+        if !self.content.is_empty() || pages.is_empty() {
+            pages.push(self.content);
+        }
+
+        // 1 catalog, 2 page tree, 3-4 fonts, then a page and its content
+        // stream per page
+        let kids: Vec<String> = (0..pages.len())
+            .map(|i| format!("{} 0 R", 5 + 2 * i))
+            .collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                pages.len()
+            ),
+            font_object("Helvetica"),
+            font_object("Helvetica-Bold"),
+        ];
+        for (i, content) in pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                6 + 2 * i
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                content.len(),
+                content
+            ));
+        }
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            let _ = write!(out, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+        }
+        let xref = out.len();
+        let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(out, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        );
+        out.into_bytes()
+    }
+}
+
+fn font_object(name: &str) -> String {
+    format!(
+        "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+        name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SectionData;
+    use super::*;
+
+    fn data(metric_count: usize) -> ReportData {
+        let metrics = (0..metric_count)
+            .map(|i| MetricSummary {
+                device_id: "boiler-1".to_string(),
+                device_name: "Boiler <1> Grüße 锅炉".to_string(),
+                metric: format!("temp_{}", i),
+                count: 3,
+                min: Some(20.0),
+                max: Some(22.5),
+                avg: Some(21.25),
+                last: Some(22.5),
+                series: vec![(0, 20.0), (60, 21.0), (120, 22.5)],
+            })
+            .collect();
+        ReportData {
+            title: "Daily (boilers)".to_string(),
+            description: String::new(),
+            range_start: 1_772_323_200,
+            range_end: 1_772_409_600,
+            generated_at: 1_772_409_600,
+            sections: vec![SectionData {
+                title: "Boilers".to_string(),
+                chart: true,
+                metrics,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_html_report() {
+        let html = render_html(&data(1));
+        assert!(html.contains("<h1>Daily (boilers)</h1>"));
+        assert!(html.contains("Boiler &lt;1&gt; Grüße 锅炉"));
+        assert!(html.contains("<td>21.25</td><td>22.5</td>"));
+        assert!(html.contains("<polyline"));
+        assert!(html.contains("2026-03-01 00:00 UTC to 2026-03-02 00:00 UTC"));
+    }
+
+    #[test]
+    fn test_pdf_structure() {
+        let pdf = String::from_utf8(render_pdf(&data(1))).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(Daily \\(boilers\\)) Tj"));
+        assert!(pdf.contains("(Boiler <1> Gr\\374\\337e ??) Tj"));
+
+        // Every xref entry points at its object header
+        let xref: usize = pdf
+            .lines()
+            .skip_while(|l| *l != "startxref")
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pdf[xref..].starts_with("xref\n"));
+        let entries = pdf[xref..].lines().skip(3);
+        for (i, entry) in entries.take_while(|l| l.ends_with(" n ")).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", i + 1)));
+        }
+    }
+
+    #[test]
+    fn test_pdf_breaks_pages() {
+        let pdf = String::from_utf8(render_pdf(&data(12))).unwrap();
+        let pages = pdf.matches("/Type /Page ").count();
+        assert!(pages > 1);
+        assert!(pdf.contains(&format!("/Count {}", pages)));
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(Some(21.0)), "21");
+        assert_eq!(format_number(Some(21.256)), "21.26");
+        assert_eq!(format_number(Some(-0.001)), "0");
+        assert_eq!(format_number(None), "-");
+    }
+}
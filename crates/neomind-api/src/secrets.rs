@@ -0,0 +1,282 @@
+//! Encrypted secrets store.
+//!
+//! Credentials for integrations are stored encrypted with [`CryptoService`]
+//! in `data/secrets.redb` and referenced from configuration by handle
+//! (`secret://<name>`). The store is registered as the process-wide
+//! [`SecretResolver`], so LLM backends, MQTT brokers and message channels
+//! resolve handles when they connect. Values are never returned by the API;
+//! rotating a secret replaces its value and bumps its version.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use neomind_core::secrets::{self, SecretError, SecretResolver};
+use parking_lot::Mutex;
+use redb::{Database, ReadableTable, TableDefinition};
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::CryptoService;
+
+/// Default location of the secrets database.
+pub const SECRETS_DB_PATH: &str = "data/secrets.redb";
+
+// Secrets table: key = secret name, value = StoredSecret (JSON)
+const SECRETS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("secrets");
+
+static SECRET_STORE: Mutex<Option<Arc<SecretStore>>> = Mutex::new(None);
+
+/// Secret metadata; the value itself is never exposed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretInfo {
+    pub name: String,
+    /// Handle to reference the secret from configuration
+    pub handle: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Incremented on every rotation
+    pub version: u32,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredSecret {
+    #[serde(flatten)]
+    info: SecretInfo,
+    /// Base64 nonce + AES-256-GCM ciphertext
+    ciphertext: String,
+}
+
+fn store_err(e: impl std::fmt::Display) -> SecretError {
+    SecretError::Store(e.to_string())
+}
+
+/// Encrypted key-value store for integration credentials.
+pub struct SecretStore {
+    db: Database,
+    crypto: CryptoService,
+}
+
+impl SecretStore {
+    /// Open or create a secrets database.
+    pub fn open(path: impl AsRef<Path>, crypto: CryptoService) -> Result<Self, SecretError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(store_err)?;
+        }
+        let db = Database::create(path).map_err(store_err)?;
+        let write_txn = db.begin_write().map_err(store_err)?;
+        write_txn.open_table(SECRETS_TABLE).map_err(store_err)?;
+        write_txn.commit().map_err(store_err)?;
+        Ok(Self { db, crypto })
+    }
+
+    /// Metadata of all secrets, sorted by name.
+    pub fn list(&self) -> Result<Vec<SecretInfo>, SecretError> {
+        let read_txn = self.db.begin_read().map_err(store_err)?;
+        let table = read_txn.open_table(SECRETS_TABLE).map_err(store_err)?;
+        let mut secrets = Vec::new();
+        for result in table.iter().map_err(store_err)? {
+            let (_, data) = result.map_err(store_err)?;
+            let stored: StoredSecret = serde_json::from_slice(data.value()).map_err(store_err)?;
+            secrets.push(stored.info);
+        }
+        Ok(secrets)
+    }
+
+    /// Metadata of one secret.
+    pub fn get(&self, name: &str) -> Result<Option<SecretInfo>, SecretError> {
+        Ok(self.load(name)?.map(|stored| stored.info))
+    }
+
+    /// Store a new secret.
+    pub fn create(
+        &self,
+        name: &str,
+        value: &str,
+        description: Option<String>,
+    ) -> Result<SecretInfo, SecretError> {
+        secrets::validate_name(name)?;
+        if self.load(name)?.is_some() {
+            return Err(SecretError::AlreadyExists(name.to_string()));
+        }
+        let now = chrono::Utc::now().timestamp();
+        let info = SecretInfo {
+            name: name.to_string(),
+            handle: secrets::handle(name),
+            description,
+            version: 1,
+            created_at: now,
+            updated_at: now,
+        };
+        self.save(&info, value)?;
+        secrets::register_value(value);
+        Ok(info)
+    }
+
+    /// Replace the value of an existing secret.
+    pub fn rotate(&self, name: &str, value: &str) -> Result<SecretInfo, SecretError> {
+        let stored = self
+            .load(name)?
+            .ok_or_else(|| SecretError::NotFound(name.to_string()))?;
+        let previous = self.decrypt(&stored)?;
+        let info = SecretInfo {
+            version: stored.info.version + 1,
+            updated_at: chrono::Utc::now().timestamp(),
+            ..stored.info
+        };
+        self.save(&info, value)?;
+        // Keep masking the previous value, it may still appear in old logs
+        secrets::register_value(&previous);
+        secrets::register_value(value);
+        Ok(info)
+    }
+
+    /// Delete a secret. Returns whether it existed.
+    pub fn delete(&self, name: &str) -> Result<bool, SecretError> {
+        let write_txn = self.db.begin_write().map_err(store_err)?;
+        let existed = {
+            let mut table = write_txn.open_table(SECRETS_TABLE).map_err(store_err)?;
+            let removed = table.remove(name).map_err(store_err)?;
+            removed.is_some()
+        };
+        write_txn.commit().map_err(store_err)?;
+        Ok(existed)
+    }
+
+    /// Register all stored values for log redaction.
+    pub fn register_values(&self) -> Result<(), SecretError> {
+        for info in self.list()? {
+            if let Some(value) = self.reveal(&info.name)? {
+                secrets::register_value(&value);
+            }
+        }
+        Ok(())
+    }
+
+    fn reveal(&self, name: &str) -> Result<Option<String>, SecretError> {
+        self.load(name)?
+            .map(|stored| self.decrypt(&stored))
+            .transpose()
+    }
+
+    fn decrypt(&self, stored: &StoredSecret) -> Result<String, SecretError> {
+        self.crypto
+            .decrypt_str(&stored.ciphertext)
+            .map_err(|e| store_err(format!("cannot decrypt '{}': {}", stored.info.name, e)))
+    }
+
+    fn load(&self, name: &str) -> Result<Option<StoredSecret>, SecretError> {
+        let read_txn = self.db.begin_read().map_err(store_err)?;
+        let table = read_txn.open_table(SECRETS_TABLE).map_err(store_err)?;
+        let Some(data) = table.get(name).map_err(store_err)? else {
+            return Ok(None);
+        };
+        serde_json::from_slice(data.value())
+            .map(Some)
+            .map_err(store_err)
+    }
+
+    fn save(&self, info: &SecretInfo, value: &str) -> Result<(), SecretError> {
+        let stored = StoredSecret {
+            info: info.clone(),
+            ciphertext: self.crypto.encrypt_str(value).map_err(store_err)?,
+        };
+        let data = serde_json::to_vec(&stored).map_err(store_err)?;
+        let write_txn = self.db.begin_write().map_err(store_err)?;
+        {
+            let mut table = write_txn.open_table(SECRETS_TABLE).map_err(store_err)?;
+            table
+                .insert(info.name.as_str(), data.as_slice())
+                .map_err(store_err)?;
+        }
+        write_txn.commit().map_err(store_err)
+    }
+}
+
+impl SecretResolver for SecretStore {
+    fn resolve_secret(&self, name: &str) -> Result<Option<String>, SecretError> {
+        self.reveal(name)
+    }
+}
+
+/// The server's secrets store, opened on first use.
+pub fn secret_store() -> Result<Arc<SecretStore>, SecretError> {
+    let mut guard = SECRET_STORE.lock();
+    if let Some(store) = guard.as_ref() {
+        return Ok(store.clone());
+    }
+    let store = Arc::new(SecretStore::open(
+        SECRETS_DB_PATH,
+        CryptoService::from_env_or_generate(),
+    )?);
+    *guard = Some(store.clone());
+    Ok(store)
+}
+
+/// Open the secrets store, make it the secret resolver and mask its values
+/// in logs.
+pub fn init_secrets() {
+    match secret_store() {
+        Ok(store) => {
+            if let Err(e) = store.register_values() {
+                tracing::warn!("Failed to load secrets for redaction: {}", e);
+            }
+            secrets::set_resolver(store);
+        }
+        Err(e) => tracing::warn!("Secrets store unavailable: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let crypto = CryptoService::generate_random();
+        let store = SecretStore::open(dir.path().join("secrets.redb"), crypto).unwrap();
+
+        let info = store
+            .create("smtp-password", "first-smtp-value", None)
+            .unwrap();
+        assert_eq!(info.handle, "secret://smtp-password");
+        assert_eq!(info.version, 1);
+        assert!(matches!(
+            store.create("smtp-password", "again", None),
+            Err(SecretError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            store.create("Bad Name", "value", None),
+            Err(SecretError::InvalidName(_))
+        ));
+
+        // Values are encrypted at rest
+        let raw = {
+            let read_txn = store.db.begin_read().unwrap();
+            let table = read_txn.open_table(SECRETS_TABLE).unwrap();
+            let data = table.get("smtp-password").unwrap().unwrap();
+            String::from_utf8(data.value().to_vec()).unwrap()
+        };
+        assert!(!raw.contains("first-smtp-value"));
+
+        let rotated = store.rotate("smtp-password", "second-smtp-value").unwrap();
+        assert_eq!(rotated.version, 2);
+        assert_eq!(rotated.created_at, info.created_at);
+        assert_eq!(
+            store.resolve_secret("smtp-password").unwrap().as_deref(),
+            Some("second-smtp-value")
+        );
+        assert_eq!(
+            secrets::redact_text("old first-smtp-value new second-smtp-value"),
+            "old *** new ***"
+        );
+
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert!(store.delete("smtp-password").unwrap());
+        assert!(!store.delete("smtp-password").unwrap());
+        assert_eq!(store.resolve_secret("smtp-password").unwrap(), None);
+        assert!(matches!(store.rotate("smtp-password", "x"), Err(SecretError::NotFound(_))));
+    }
+}
@@ -7,7 +7,9 @@ use axum::{
 use std::net::SocketAddr;
 
 use super::types::ServerState;
-use crate::rate_limit::extract_client_id;
+use crate::rate_limit::{
+    extract_client_id, QUOTA_TOKENS_REMAINING_HEADER, RATE_LIMIT_REMAINING_HEADER,
+};
 
 /// Rate limiting middleware.
 ///
@@ -15,6 +17,10 @@ use crate::rate_limit::extract_client_id;
 /// Public endpoints have higher limits; protected endpoints have standard limits.
 /// WebSocket and SSE endpoints are excluded from rate limiting.
 ///
+/// API keys with their own `rate_limit_per_minute` use it instead of the
+/// global limit. Responses carry `X-RateLimit-Remaining`, plus
+/// `X-Quota-Tokens-Remaining` for keys with a daily LLM token quota.
+///
 pub async fn rate_limit_middleware(
     State(state): State<ServerState>,
     connect_info: Option<ConnectInfo<SocketAddr>>,
@@ -26,10 +32,36 @@ pub async fn rate_limit_middleware(
         return next.run(request).await;
     }
 
-    let client_id = extract_client_id(request.headers(), connect_info.as_ref());
+    let headers = request.headers();
+    let client_id = extract_client_id(headers, connect_info.as_ref());
 
-    match state.rate_limiter.check_rate_limit(&client_id) {
-        Ok(_) => next.run(request).await,
+    let api_key = headers
+        .get("x-api-key")
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| {
+            headers
+                .get("authorization")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "))
+        })
+        .map(|k| k.to_string());
+    let max_requests = api_key
+        .as_deref()
+        .and_then(|k| state.auth.api_key_state.validate_key_info(k))
+        .and_then(|info| info.rate_limit_per_minute);
+    match state.rate_limiter.check(&client_id, max_requests) {
+        Ok(remaining) => {
+            let mut response = next.run(request).await;
+            let response_headers = response.headers_mut();
+            response_headers.insert(RATE_LIMIT_REMAINING_HEADER, remaining.into());
+            let tokens = api_key
+                .as_deref()
+                .and_then(|k| state.auth.api_key_state.tokens_remaining(k));
+            if let Some(tokens) = tokens {
+                response_headers.insert(QUOTA_TOKENS_REMAINING_HEADER, tokens.into());
+            }
+            response
+        }
         Err(e) => {
             if e.should_log() {
                 tracing::warn!(
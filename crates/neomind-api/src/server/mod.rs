@@ -60,6 +60,20 @@ pub async fn run(bind: SocketAddr) -> anyhow::Result<()> {
     // Initialization phase
     startup.phase_init();
 
+    // Load runtime configuration before anything reads it
+    state.init_config_service();
+    startup.service("Runtime config", ServiceStatus::Started);
+
+    // Open the secrets store before integrations resolve `secret://` handles
+    crate::secrets::init_secrets();
+    startup.service("Secrets store", ServiceStatus::Started);
+
+    // Tool argument guardrails from config.toml
+    crate::config::init_guardrails();
+
+    // Custom intent keywords/patterns saved through the API
+    crate::config::init_intent_profile();
+
     // Initialize device type storage (must be before init_device_adapters)
     state.init_device_storage().await;
     startup.service("Device storage", ServiceStatus::Started);
@@ -71,6 +85,14 @@ pub async fn run(bind: SocketAddr) -> anyhow::Result<()> {
     state.init_transform_event_service().await;
     startup.service("Transform event service", ServiceStatus::Started);
 
+    // Initialize anomaly detection on device telemetry
+    state.init_anomaly_detection();
+    startup.service("Anomaly detection", ServiceStatus::Started);
+
+    // Load maintenance windows
+    state.init_maintenance_windows();
+    startup.service("Maintenance windows", ServiceStatus::Started);
+
     // Initialize tools
     state.init_tools().await;
     startup.service("AI tools", ServiceStatus::Started);
@@ -166,6 +188,7 @@ pub async fn run(bind: SocketAddr) -> anyhow::Result<()> {
 
     // Start telemetry retention cleanup background task
     {
+        let device_service = state.devices.service.clone();
         tokio::spawn(async move {
             use neomind_storage::{SettingsStore, TimeSeriesStore};
 
@@ -186,6 +209,15 @@ pub async fn run(bind: SocketAddr) -> anyhow::Result<()> {
                 if config.enabled {
                     let policy = config.to_retention_policy();
                     if let Ok(ts_store) = TimeSeriesStore::open(TELEMETRY_DB_PATH) {
+                        // Refresh each cycle so newly added devices pick up
+                        // their type's retention override
+                        ts_store.set_device_types(
+                            device_service
+                                .list_devices()
+                                .into_iter()
+                                .map(|d| (d.device_id, d.device_type))
+                                .collect(),
+                        );
                         ts_store.set_retention_policy(policy).await;
                         match ts_store.apply_retention().await {
                             Ok(result) => {
@@ -411,12 +443,19 @@ pub async fn run(bind: SocketAddr) -> anyhow::Result<()> {
             // Initialize AI Agent event listener
             bg_state.init_agent_events().await;
 
-            // Detect llama.cpp backend capabilities from /props endpoint
+            // Embed queries for the LLM response cache's semantic layer
+            crate::handlers::knowledge::install_query_embedder();
+
+            // Publish LLM failover events on the event bus and detect
+            // llama.cpp backend capabilities from /props endpoint
             {
                 let mut retry_interval = tokio::time::interval(Duration::from_secs(5));
                 for _ in 0..12 {
                     retry_interval.tick().await;
                     if let Ok(instance_manager) = neomind_agent::get_instance_manager() {
+                        if let Some(bus) = &bg_state.core.event_bus {
+                            instance_manager.set_event_bus((**bus).clone());
+                        }
                         instance_manager.detect_llamacpp_capabilities().await;
                         break;
                     }
@@ -520,6 +559,19 @@ pub async fn run(bind: SocketAddr) -> anyhow::Result<()> {
         });
     }
 
+    // gRPC API alongside HTTP, on the same host
+    #[cfg(feature = "grpc")]
+    {
+        let grpc_addr = SocketAddr::new(bind.ip(), crate::config::get_grpc_port());
+        let grpc_state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = crate::grpc::serve(grpc_state, grpc_addr).await {
+                tracing::error!(category = "grpc", error = %e, "gRPC server failed");
+            }
+        });
+        startup.service("gRPC API", ServiceStatus::Started);
+    }
+
     // Services phase
     startup.phase_services();
 
@@ -13,8 +13,23 @@ use super::middleware::rate_limit_middleware;
 use super::types::ServerState;
 use super::types::MAX_EXTENSION_UPLOAD_SIZE;
 use super::types::MAX_REQUEST_BODY_SIZE;
-use crate::auth::hybrid_auth_middleware;
-use crate::auth_users::jwt_auth_middleware;
+use crate::auth::{hybrid_auth_middleware, reject_read_only_writes};
+use crate::auth_users::{jwt_auth_middleware, Permission};
+
+/// Per-route permission annotation:
+/// `post(handler).route_layer(require_permission!(Permission::DeviceControl))`.
+///
+/// The route-level layer runs inside the router-wide auth middleware, so the
+/// caller's `SessionInfo` is already attached when the role is checked.
+macro_rules! require_permission {
+    ($permission:expr) => {
+        axum::middleware::from_fn(
+            |req: axum::extract::Request, next: axum::middleware::Next| {
+                crate::auth::require_permission($permission, req, next)
+            },
+        )
+    };
+}
 
 /// Create the application router.
 pub async fn create_router() -> Router {
@@ -24,11 +39,12 @@ pub async fn create_router() -> Router {
 /// Create the application router with a specific state.
 pub fn create_router_with_state(state: ServerState) -> Router {
     use crate::handlers::{
-        agents, auth as auth_handlers, auth_users, automations, basic, capabilities, config,
-        dashboards, data, data_push, devices, events, extension_stream, extensions,
-        frontend_components, images, instances, llm_backends, logs, memory, message_channels,
-        messages, mqtt, onboarding, rules, sessions, settings, setup, skills, stats, suggestions,
-        tools,
+        agents, approvals, audio, auth as auth_handlers, auth_users, automations, basic,
+        capabilities, config, dashboards, data, data_push, devices, energy, events, exports,
+        extension_stream, extensions, frontend_components, images, instances, intent, knowledge,
+        llm_backends, logs, maintenance, memory, message_channels, messages, mqtt, onboarding,
+        provisioning, reports, rules, secrets, sessions, settings, setup, skills, state_machines,
+        stats, suggestions, tools, usage,
     };
 
     // Public routes (no authentication required)
@@ -46,9 +62,8 @@ pub fn create_router_with_state(state: ServerState) -> Router {
         .route("/api/auth/status", get(auth_handlers::auth_status_handler))
         // Auth verification (public route - validates credentials internally)
         .route("/api/auth/verify", get(auth_users::get_auth_status_handler))
-        // User authentication (public - login and register)
-        .route("/api/auth/login", post(auth_users::login_handler))
-        .route("/api/auth/register", post(auth_users::register_handler))
+        // User authentication (login and register) is public but rate
+        // limited, see `auth_routes` below
         // Setup endpoints (public - only available when no users exist)
         .route("/api/setup/status", get(setup::setup_status_handler))
         .route(
@@ -211,6 +226,18 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             post(onboarding::reset_onboarding_handler),
         );
 
+    // Public credential endpoints. They take passwords and second factors
+    // from anyone, so they sit behind `rate_limit_middleware` to slow down
+    // guessing; second factors are also limited per user in `AuthUserState`.
+    let auth_routes = Router::new()
+        .route("/api/auth/login", post(auth_users::login_handler))
+        .route("/api/auth/login/mfa", post(auth_users::login_mfa_handler))
+        .route("/api/auth/register", post(auth_users::register_handler))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_middleware,
+        ));
+
     // JWT protected routes (require JWT token authentication)
     let jwt_routes = Router::new()
         // User info and session management
@@ -220,6 +247,37 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/auth/change-password",
             post(auth_users::change_password_handler),
         )
+        // Second factors of the current user
+        .route("/api/auth/mfa", get(auth_users::get_mfa_status_handler))
+        .route("/api/auth/mfa/totp", post(auth_users::totp_setup_handler))
+        .route(
+            "/api/auth/mfa/totp/confirm",
+            post(auth_users::totp_confirm_handler),
+        )
+        .route(
+            "/api/auth/mfa/totp/disable",
+            post(auth_users::totp_disable_handler),
+        )
+        .route(
+            "/api/auth/mfa/recovery-codes",
+            post(auth_users::recovery_codes_handler),
+        )
+        .route(
+            "/api/auth/mfa/webauthn/register",
+            post(auth_users::passkey_register_begin_handler),
+        )
+        .route(
+            "/api/auth/mfa/webauthn/register/finish",
+            post(auth_users::passkey_register_finish_handler),
+        )
+        .route(
+            "/api/auth/mfa/webauthn/:id/remove",
+            post(auth_users::passkey_remove_handler),
+        )
+        .route(
+            "/api/auth/mfa/challenge",
+            post(auth_users::step_up_challenge_handler),
+        )
         // Apply hybrid authentication middleware (supports both JWT tokens and API keys)
         .route_layer(axum::middleware::from_fn_with_state(
             state.clone(),
@@ -258,6 +316,17 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/devices/webhook",
             post(devices::webhook_generic_handler),
         )
+        // ChirpStack HTTP integration (LoRaWAN uplinks); optional X-API-Key
+        // is checked by the ChirpStack adapter
+        .route(
+            "/api/chirpstack/events",
+            post(devices::chirpstack_event_handler),
+        )
+        // Device provisioning: the device redeems its one-time claim code
+        .route(
+            "/api/provisioning/claim",
+            post(provisioning::redeem_claim_handler),
+        )
         // Webhook body limit: 8MB accommodates a 1080p JPEG frame (typical
         // 300KB-1MB) plus JSON envelope and multipart framing overhead. 4K
         // single-frame raw uploads exceed this — devices shooting 4K should
@@ -283,6 +352,7 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/data/sources",
             get(data::list_all_data_sources_handler),
         )
+        .route("/api/data/query", post(data::query_data_handler))
         .route("/api/stats/system", get(stats::get_system_stats_handler))
         // Diagnostic log archive download (admin/auth-only) — bundles
         // data/logs/*.log.* into a zip for support flows.
@@ -307,6 +377,10 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/llm-backends/stats",
             get(llm_backends::get_backend_stats_handler),
         )
+        .route(
+            "/api/llm-backends/failover",
+            get(llm_backends::get_failover_chain_handler),
+        )
         .route(
             "/api/llm-backends/ollama/models",
             get(llm_backends::list_ollama_models_handler),
@@ -347,6 +421,11 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/extensions/:id/commands/:cmd/enabled",
             axum::routing::patch(extensions::set_extension_command_enabled_handler),
         )
+        .route(
+            "/api/extensions/:id/permissions",
+            get(extensions::get_extension_permissions_handler)
+                .put(extensions::update_extension_permissions_handler),
+        )
         .route(
             "/api/extensions/:id/logs",
             get(extensions::get_extension_logs_handler)
@@ -404,6 +483,191 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             delete(sessions::delete_session_handler),
         )
         .route("/api/sessions/:id/chat", post(sessions::chat_handler))
+        .route(
+            "/api/sessions/:id/fork",
+            post(sessions::fork_session_handler),
+        )
+        .route("/api/sessions/:id/forks", get(sessions::list_forks_handler))
+        .route(
+            "/api/sessions/:id/archive",
+            post(sessions::archive_session_handler),
+        )
+        .route(
+            "/api/sessions/:id/export",
+            get(sessions::export_session_handler),
+        )
+        .route(
+            "/api/sessions/:id/purge",
+            post(sessions::purge_session_handler),
+        )
+        .route(
+            "/api/sessions/:id/context",
+            get(sessions::get_context_health_handler),
+        )
+        // Intent feedback, fine-tuning export and custom intent profile
+        .route(
+            "/api/sessions/:id/intent-feedback",
+            post(intent::submit_intent_feedback_handler),
+        )
+        .route(
+            "/api/intent/examples",
+            get(intent::list_intent_examples_handler),
+        )
+        .route(
+            "/api/intent/examples/export",
+            get(intent::export_intent_examples_handler),
+        )
+        .route(
+            "/api/intent/profile",
+            get(intent::get_intent_profile_handler),
+        )
+        .route(
+            "/api/intent/profile",
+            put(intent::update_intent_profile_handler)
+                .route_layer(require_permission!(Permission::ManageConfig)),
+        )
+        .route(
+            "/api/intent/profile",
+            delete(intent::delete_intent_profile_handler)
+                .route_layer(require_permission!(Permission::ManageConfig)),
+        )
+        // Voice input (speech-to-text, then chat)
+        .route("/api/chat/audio", post(audio::audio_chat_handler))
+        // LLM token usage and cost
+        .route("/api/usage", get(usage::get_usage_handler))
+        .route(
+            "/api/usage/budget",
+            put(usage::update_budget_handler)
+                .route_layer(require_permission!(Permission::ManageBudget)),
+        )
+        .route(
+            "/api/usage/:session_id",
+            get(usage::get_session_usage_handler),
+        )
+        // Energy consumption and cost
+        .route("/api/energy", get(energy::get_energy_report_handler))
+        .route("/api/energy/config", get(energy::get_energy_config_handler))
+        .route(
+            "/api/energy/config",
+            put(energy::update_energy_config_handler),
+        )
+        // Telemetry export jobs
+        .route("/api/exports", get(exports::list_exports_handler))
+        .route("/api/exports", post(exports::create_export_handler))
+        .route("/api/exports/:id", get(exports::get_export_handler))
+        .route("/api/exports/:id", delete(exports::delete_export_handler))
+        .route(
+            "/api/exports/:id/download",
+            get(exports::download_export_handler),
+        )
+        // Report templates and generated reports
+        .route(
+            "/api/reports/templates",
+            get(reports::list_templates_handler),
+        )
+        .route(
+            "/api/reports/templates",
+            post(reports::create_template_handler),
+        )
+        .route(
+            "/api/reports/templates/:id",
+            get(reports::get_template_handler),
+        )
+        .route(
+            "/api/reports/templates/:id",
+            put(reports::update_template_handler),
+        )
+        .route(
+            "/api/reports/templates/:id",
+            delete(reports::delete_template_handler),
+        )
+        .route(
+            "/api/reports/templates/:id/run",
+            post(reports::run_template_handler),
+        )
+        .route("/api/reports", get(reports::list_reports_handler))
+        .route("/api/reports/:id", get(reports::get_report_handler))
+        .route("/api/reports/:id", delete(reports::delete_report_handler))
+        .route(
+            "/api/reports/:id/download",
+            get(reports::download_report_handler),
+        )
+        // Knowledge base (document ingestion and retrieval)
+        .route(
+            "/api/knowledge/documents",
+            get(knowledge::list_documents_handler),
+        )
+        .route(
+            "/api/knowledge/documents",
+            post(knowledge::upload_document_handler)
+                .layer(DefaultBodyLimit::max(knowledge::MAX_DOCUMENT_SIZE)),
+        )
+        .route(
+            "/api/knowledge/documents/:id",
+            delete(knowledge::delete_document_handler),
+        )
+        .route(
+            "/api/knowledge/search",
+            post(knowledge::search_knowledge_handler),
+        )
+        .route(
+            "/api/knowledge/config",
+            get(knowledge::get_knowledge_config_handler),
+        )
+        .route(
+            "/api/knowledge/config",
+            put(knowledge::update_knowledge_config_handler),
+        )
+        .route(
+            "/api/knowledge/reindex",
+            post(knowledge::reindex_knowledge_handler),
+        )
+        .route(
+            "/api/knowledge/reindex",
+            get(knowledge::get_reindex_handler),
+        )
+        // Maintenance windows
+        .route(
+            "/api/maintenance",
+            get(maintenance::list_maintenance_windows_handler),
+        )
+        .route(
+            "/api/maintenance",
+            post(maintenance::create_maintenance_window_handler),
+        )
+        .route(
+            "/api/maintenance/active",
+            get(maintenance::list_active_maintenance_windows_handler),
+        )
+        .route(
+            "/api/maintenance/:id",
+            put(maintenance::update_maintenance_window_handler),
+        )
+        .route(
+            "/api/maintenance/:id",
+            delete(maintenance::delete_maintenance_window_handler),
+        )
+        // Secrets (values are write-only)
+        .route(
+            "/api/secrets",
+            get(secrets::list_secrets_handler)
+                .route_layer(require_permission!(Permission::ManageSecrets)),
+        )
+        .route(
+            "/api/secrets",
+            post(secrets::create_secret_handler)
+                .route_layer(require_permission!(Permission::ManageSecrets)),
+        )
+        .route(
+            "/api/secrets/:name/rotate",
+            post(secrets::rotate_secret_handler)
+                .route_layer(require_permission!(Permission::ManageSecrets)),
+        )
+        .route(
+            "/api/secrets/:name",
+            delete(secrets::delete_secret_handler)
+                .route_layer(require_permission!(Permission::ManageSecrets)),
+        )
         // Skills API (protected - write operations)
         .route("/api/skills", post(skills::create_skill_handler))
         .route("/api/skills/reload", post(skills::reload_skills_handler))
@@ -428,19 +692,74 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             post(devices::ble_provision_handler),
         )
         .route("/api/devices/:id", get(devices::get_device_handler))
-        .route("/api/devices/:id", put(devices::update_device_handler))
-        .route("/api/devices/:id", delete(devices::delete_device_handler))
+        .route(
+            "/api/devices/:id",
+            put(devices::update_device_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        .route(
+            "/api/devices/:id",
+            delete(devices::delete_device_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
         .route(
             "/api/devices/:id/current",
             get(devices::get_device_current_handler),
         )
+        .route(
+            "/api/devices/:id/members",
+            get(devices::get_virtual_members_handler),
+        )
+        .route(
+            "/api/devices/:id/state",
+            get(devices::get_device_state_at_handler),
+        )
         .route(
             "/api/devices/current-batch",
             post(devices::get_devices_current_batch_handler),
         )
         .route(
             "/api/devices/:id/command/:command",
-            post(devices::send_command_handler),
+            post(devices::send_command_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        .route(
+            "/api/devices/command-batch",
+            post(devices::send_command_group_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        // Device groups
+        .route(
+            "/api/device-groups",
+            get(devices::list_device_groups_handler),
+        )
+        .route(
+            "/api/device-groups",
+            post(devices::create_device_group_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        .route(
+            "/api/device-groups/:id",
+            get(devices::get_device_group_handler),
+        )
+        .route(
+            "/api/device-groups/:id",
+            put(devices::update_device_group_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        .route(
+            "/api/device-groups/:id",
+            delete(devices::delete_device_group_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        .route(
+            "/api/device-groups/:id/devices",
+            get(devices::get_device_group_devices_handler),
+        )
+        .route(
+            "/api/device-groups/:id/command/:command",
+            post(devices::send_device_group_command_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
         )
         .route(
             "/api/devices/:id/telemetry",
@@ -458,6 +777,15 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/devices/:id/commands",
             get(devices::get_device_command_history_handler),
         )
+        .route(
+            "/api/devices/:id/commands/scheduled",
+            get(devices::list_scheduled_commands_handler),
+        )
+        .route(
+            "/api/devices/:id/commands/scheduled/:schedule_id",
+            delete(devices::cancel_scheduled_command_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
         // Device Types API
         .route("/api/device-types", get(devices::list_device_types_handler))
         .route(
@@ -490,6 +818,45 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/devices/generate-mdl",
             post(devices::generate_mdl_handler),
         )
+        // Telemetry anomalies flagged against learned baselines
+        .route(
+            "/api/devices/anomalies",
+            get(devices::list_anomalies_handler),
+        )
+        // Fleet availability derived from heartbeat state
+        .route(
+            "/api/devices/health/summary",
+            get(devices::get_fleet_health_summary_handler),
+        )
+        // Telemetry load shedding statistics
+        .route(
+            "/api/devices/ingest/report",
+            get(devices::get_ingest_report_handler),
+        )
+        // Network discovery queue - mDNS/SSDP scans awaiting approval
+        .route(
+            "/api/devices/discovered",
+            get(devices::list_discovered_endpoints_handler),
+        )
+        .route(
+            "/api/devices/discovered/scan",
+            post(devices::scan_network_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        .route(
+            "/api/devices/discovered/:id",
+            get(devices::get_discovered_endpoint_handler),
+        )
+        .route(
+            "/api/devices/discovered/:id/approve",
+            post(devices::approve_discovered_endpoint_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        .route(
+            "/api/devices/discovered/:id/ignore",
+            post(devices::ignore_discovered_endpoint_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
         // Draft Devices API - auto-onboarding
         .route("/api/devices/drafts", get(devices::list_draft_devices))
         .route(
@@ -542,14 +909,34 @@ pub fn create_router_with_state(state: ServerState) -> Router {
         )
         // Rules API - specific routes first, then parameterized routes
         .route("/api/rules", get(rules::list_rules_handler))
-        .route("/api/rules", post(rules::create_rule_handler))
+        .route(
+            "/api/rules",
+            post(rules::create_rule_handler)
+                .route_layer(require_permission!(Permission::RuleWrite)),
+        )
         .route("/api/rules/export", get(rules::export_rules_handler))
-        .route("/api/rules/import", post(rules::import_rules_handler))
+        .route(
+            "/api/rules/import",
+            post(rules::import_rules_handler)
+                .route_layer(require_permission!(Permission::RuleWrite)),
+        )
         .route("/api/rules/resources", get(rules::get_resources_handler))
         .route("/api/rules/validate", post(rules::validate_rule_handler))
+        .route("/api/rules/simulate", post(rules::simulate_rule_handler))
+        .route(
+            "/api/rules/conflicts",
+            get(rules::analyze_rule_conflicts_handler).post(rules::rule_impact_handler),
+        )
         .route("/api/rules/:id", get(rules::get_rule_handler))
-        .route("/api/rules/:id", put(rules::update_rule_handler))
-        .route("/api/rules/:id", delete(rules::delete_rule_handler))
+        .route(
+            "/api/rules/:id",
+            put(rules::update_rule_handler).route_layer(require_permission!(Permission::RuleWrite)),
+        )
+        .route(
+            "/api/rules/:id",
+            delete(rules::delete_rule_handler)
+                .route_layer(require_permission!(Permission::RuleDelete)),
+        )
         .route(
             "/api/rules/:id/enable",
             post(rules::set_rule_status_handler),
@@ -560,10 +947,74 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/rules/:id/history",
             get(rules::get_rule_history_handler),
         )
+        .route(
+            "/api/rules/executions/:execution_id",
+            get(rules::explain_rule_execution_handler),
+        )
+        // State machine automations
+        .route(
+            "/api/state-machines",
+            get(state_machines::list_machines_handler),
+        )
+        .route(
+            "/api/state-machines",
+            post(state_machines::create_machine_handler)
+                .route_layer(require_permission!(Permission::RuleWrite)),
+        )
+        .route(
+            "/api/state-machines/:id",
+            get(state_machines::get_machine_handler),
+        )
+        .route(
+            "/api/state-machines/:id",
+            put(state_machines::update_machine_handler)
+                .route_layer(require_permission!(Permission::RuleWrite)),
+        )
+        .route(
+            "/api/state-machines/:id",
+            delete(state_machines::delete_machine_handler)
+                .route_layer(require_permission!(Permission::RuleDelete)),
+        )
+        .route(
+            "/api/state-machines/:id/graph",
+            get(state_machines::get_machine_graph_handler),
+        )
+        .route(
+            "/api/state-machines/:id/events",
+            post(state_machines::post_machine_event_handler),
+        )
+        .route(
+            "/api/state-machines/:id/reset",
+            post(state_machines::reset_machine_handler),
+        )
         // Messages API
         .route("/api/messages", get(messages::list_messages_handler))
         .route("/api/messages", post(messages::create_message_handler))
         .route("/api/messages/stats", get(messages::message_stats_handler))
+        .route(
+            "/api/messages/groups",
+            get(messages::list_alert_groups_handler),
+        )
+        .route(
+            "/api/messages/grouping",
+            get(messages::get_grouping_config_handler),
+        )
+        .route(
+            "/api/messages/grouping",
+            put(messages::update_grouping_config_handler),
+        )
+        .route(
+            "/api/messages/escalation",
+            get(messages::get_escalation_config_handler),
+        )
+        .route(
+            "/api/messages/escalation",
+            put(messages::update_escalation_config_handler),
+        )
+        .route(
+            "/api/messages/escalation/on-call",
+            get(messages::get_on_call_handler),
+        )
         .route("/api/messages/cleanup", post(messages::cleanup_handler))
         .route(
             "/api/messages/acknowledge",
@@ -692,6 +1143,10 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/settings/retention/cleanup",
             post(settings::trigger_retention_cleanup),
         )
+        .route(
+            "/api/settings/retention/preview",
+            post(settings::preview_retention),
+        )
         // Unified Automations API
         .route(
             "/api/automations",
@@ -903,12 +1358,64 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/mqtt/broker-config/tls/ca-cert",
             get(mqtt::download_ca_cert_handler),
         )
+        // Device provisioning (claim codes and per-device broker identities)
+        .route(
+            "/api/provisioning/claims",
+            get(provisioning::list_claims_handler),
+        )
+        .route(
+            "/api/provisioning/claims",
+            post(provisioning::create_claim_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        .route(
+            "/api/provisioning/claims/:code",
+            delete(provisioning::delete_claim_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        .route(
+            "/api/provisioning/devices",
+            get(provisioning::list_identities_handler),
+        )
+        .route(
+            "/api/provisioning/devices/:id/revoke",
+            post(provisioning::revoke_identity_handler)
+                .route_layer(require_permission!(Permission::DeviceControl)),
+        )
+        // Agent action approvals (tool calls held for an operator)
+        .route("/api/approvals", get(approvals::list_approvals_handler))
+        .route("/api/approvals/:id", get(approvals::get_approval_handler))
+        .route(
+            "/api/approvals/:id/approve",
+            post(approvals::approve_handler)
+                .route_layer(require_permission!(Permission::ApproveActions)),
+        )
+        .route(
+            "/api/approvals/:id/reject",
+            post(approvals::reject_handler)
+                .route_layer(require_permission!(Permission::ApproveActions)),
+        )
         // Stats API (devices and rules require auth, system info is public)
         .route("/api/stats/devices", get(stats::get_device_stats_handler))
         .route("/api/stats/rules", get(stats::get_rule_stats_handler))
+        // Runtime config API
+        .route("/api/config", get(config::get_runtime_config_handler))
+        .route(
+            "/api/config",
+            put(config::update_runtime_config_handler)
+                .route_layer(require_permission!(Permission::ManageConfig)),
+        )
+        .route(
+            "/api/config/history",
+            get(config::get_config_history_handler),
+        )
         // Config Import/Export API
         .route("/api/config/export", get(config::export_config_handler))
-        .route("/api/config/import", post(config::import_config_handler))
+        .route(
+            "/api/config/import",
+            post(config::import_config_handler)
+                .route_layer(require_permission!(Permission::BackupRestore)),
+        )
         .route(
             "/api/config/validate",
             post(config::validate_config_handler),
@@ -976,19 +1483,31 @@ pub fn create_router_with_state(state: ServerState) -> Router {
         )
         // Auth management API (also protected)
         .route("/api/auth/keys", get(auth_handlers::list_keys_handler))
-        .route("/api/auth/keys", post(auth_handlers::create_key_handler))
+        .route(
+            "/api/auth/keys",
+            post(auth_handlers::create_key_handler)
+                .route_layer(require_permission!(Permission::ManageApiKeys)),
+        )
         .route(
             "/api/auth/keys/:id",
-            delete(auth_handlers::delete_key_handler),
+            delete(auth_handlers::delete_key_handler)
+                .route_layer(require_permission!(Permission::ManageApiKeys)),
+        )
+        .route(
+            "/api/auth/keys/:id/limits",
+            put(auth_handlers::update_key_limits_handler)
+                .route_layer(require_permission!(Permission::ManageApiKeys)),
         )
         // Extensions API (write operations - protected)
         .route(
             "/api/extensions",
-            post(extensions::register_extension_handler),
+            post(extensions::register_extension_handler)
+                .route_layer(require_permission!(Permission::ManageExtensions)),
         )
         .route(
             "/api/extensions/:id/uninstall",
-            delete(extensions::uninstall_extension_handler),
+            delete(extensions::uninstall_extension_handler)
+                .route_layer(require_permission!(Permission::ManageExtensions)),
         )
         .route(
             "/api/extensions/:id/start",
@@ -1010,7 +1529,17 @@ pub fn create_router_with_state(state: ServerState) -> Router {
         // Extension Marketplace (install endpoint - protected)
         .route(
             "/api/extensions/market/install",
-            post(extensions::install_marketplace_extension_handler),
+            post(extensions::install_marketplace_extension_handler)
+                .route_layer(require_permission!(Permission::ManageExtensions)),
+        )
+        // Trusted extension publishers (protected)
+        .route(
+            "/api/extensions/signature-policy",
+            get(extensions::get_signature_policy_handler),
+        )
+        .route(
+            "/api/extensions/signature-policy",
+            put(extensions::update_signature_policy_handler),
         )
         // Extension sync (protected - manual sync from /extensions/ directory)
         .route(
@@ -1034,6 +1563,10 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/llm-backends/:id",
             delete(llm_backends::delete_backend_handler),
         )
+        .route(
+            "/api/llm-backends/failover",
+            put(llm_backends::update_failover_chain_handler),
+        )
         .route(
             "/api/llm-backends/:id/activate",
             post(llm_backends::activate_backend_handler),
@@ -1062,7 +1595,8 @@ pub fn create_router_with_state(state: ServerState) -> Router {
         // Frontend Component API (protected - install/uninstall/list)
         .route(
             "/api/frontend-components/market/install",
-            post(frontend_components::market_install_handler),
+            post(frontend_components::market_install_handler)
+                .route_layer(require_permission!(Permission::ManageExtensions)),
         )
         .route(
             "/api/frontend-components/updates",
@@ -1074,9 +1608,16 @@ pub fn create_router_with_state(state: ServerState) -> Router {
         )
         .route(
             "/api/frontend-components/:id",
-            get(frontend_components::get_component_handler)
-                .delete(frontend_components::uninstall_component_handler),
+            get(frontend_components::get_component_handler),
+        )
+        .route(
+            "/api/frontend-components/:id",
+            delete(frontend_components::uninstall_component_handler)
+                .route_layer(require_permission!(Permission::ManageExtensions)),
         )
+        // Read-only roles may not modify anything, whatever the route's own
+        // permission layer (runs after authentication)
+        .route_layer(axum::middleware::from_fn(reject_read_only_writes))
         // Apply rate limiting middleware to all protected routes
         .route_layer(axum::middleware::from_fn_with_state(
             state.clone(),
@@ -1097,6 +1638,14 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             "/api/users/:username",
             delete(auth_users::delete_user_handler),
         )
+        .route(
+            "/api/users/:username/role",
+            put(auth_users::update_user_role_handler),
+        )
+        .route(
+            "/api/users/:username/mfa",
+            delete(auth_users::reset_user_mfa_handler),
+        )
         // Apply JWT authentication middleware
         .route_layer(axum::middleware::from_fn_with_state(
             state.clone(),
@@ -1112,6 +1661,8 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             post(extensions::upload_extension_file_handler)
                 .layer(DefaultBodyLimit::max(MAX_EXTENSION_UPLOAD_SIZE)),
         )
+        .route_layer(require_permission!(Permission::ManageExtensions))
+        .route_layer(axum::middleware::from_fn(reject_read_only_writes))
         // Apply hybrid authentication middleware
         .route_layer(axum::middleware::from_fn_with_state(
             state.clone(),
@@ -1131,6 +1682,8 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             post(frontend_components::install_component_handler)
                 .layer(DefaultBodyLimit::max(5 * 1024 * 1024)),
         )
+        .route_layer(require_permission!(Permission::ManageExtensions))
+        .route_layer(axum::middleware::from_fn(reject_read_only_writes))
         // Apply hybrid authentication middleware
         .route_layer(axum::middleware::from_fn_with_state(
             state.clone(),
@@ -1142,6 +1695,18 @@ pub fn create_router_with_state(state: ServerState) -> Router {
             rate_limit_middleware,
         ));
 
+    // Prometheus scrape endpoint (feature `prometheus`). Scrapers authenticate
+    // with an API key (`authorization: Bearer <key>`).
+    #[cfg(feature = "prometheus")]
+    let protected_routes = protected_routes.merge(
+        Router::new()
+            .route("/metrics", get(crate::handlers::metrics::metrics_handler))
+            .route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                hybrid_auth_middleware,
+            )),
+    );
+
     // Combine all routes
     // IMPORTANT: More specific routes must come before catch-all routes.
     // Also, routes with their own middleware must be merged BEFORE routes
@@ -1159,6 +1724,7 @@ pub fn create_router_with_state(state: ServerState) -> Router {
         );
 
     let router = public_routes
+        .merge(auth_routes) // Login and register (rate-limited, no auth)
         .merge(websocket_routes) // WebSocket routes with custom auth
         .merge(webhook_routes); // Device webhook POSTs (rate-limited, no auth)
 
@@ -6,14 +6,20 @@
 //! - AgentManager for executing user-defined agents
 //! - MarkdownMemoryStore for system-level memory
 //! - MemoryScheduler for background memory tasks
+//! - AudioTranscriber for voice input
+//! - StreamBuffers for resumable chat streams
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use neomind_agent::llm_backends::WhisperTranscriber;
 use neomind_agent::memory::MemoryScheduler;
 use neomind_agent::SessionManager;
+use neomind_core::llm::modality::AudioTranscriber;
 use neomind_storage::{AgentStore, MarkdownMemoryStore, MemoryConfig};
 
+use crate::handlers::ws::StreamBuffers;
+
 /// AI Agent manager type alias.
 pub type AgentManager = Arc<neomind_agent::ai_agent::AiAgentManager>;
 
@@ -39,6 +45,12 @@ pub struct AgentState {
 
     /// Memory scheduler for background extraction/compression (lazy-initialized).
     pub memory_scheduler: Arc<RwLock<Option<MemoryScheduler>>>,
+
+    /// Speech-to-text for voice input (`None` when not configured).
+    pub transcriber: Option<Arc<dyn AudioTranscriber>>,
+
+    /// Recent chat stream events per session, for WebSocket stream resumption.
+    pub stream_buffers: Arc<StreamBuffers>,
 }
 
 impl AgentState {
@@ -56,10 +68,13 @@ impl AgentState {
             system_memory_store,
             memory_session_handle: Arc::new(RwLock::new(None)),
             memory_scheduler: Arc::new(RwLock::new(None)),
+            transcriber: WhisperTranscriber::from_env()
+                .map(|t| Arc::new(t) as Arc<dyn AudioTranscriber>),
+            stream_buffers: Arc::new(StreamBuffers::default()),
         }
     }
 
-    /// Start the memory scheduler (temp file cleanup).
+    /// Start the memory scheduler (temp file cleanup, chat retention).
     /// Idempotent: if a scheduler is already running, returns Ok without creating a duplicate.
     pub async fn start_memory_scheduler(&self) -> Result<(), String> {
         // Idempotency check — avoid spawning duplicate background tasks
@@ -73,14 +88,16 @@ impl AgentState {
 
         let config = MemoryConfig::load();
 
-        if !config.enabled {
+        // Chat retention runs even with the memory system disabled
+        if !config.enabled && config.chat_retention_days == 0 {
             tracing::info!("Memory system disabled, not starting scheduler");
             return Ok(());
         }
 
         let store = Arc::new(RwLock::new((*self.system_memory_store).clone()));
 
-        let mut scheduler = MemoryScheduler::new(store, config);
+        let mut scheduler =
+            MemoryScheduler::new(store, config).with_sessions(self.session_manager.clone());
 
         scheduler.start();
 
@@ -112,6 +129,8 @@ impl AgentState {
             )),
             memory_session_handle: Arc::new(RwLock::new(None)),
             memory_scheduler: Arc::new(RwLock::new(None)),
+            transcriber: None,
+            stream_buffers: Arc::new(StreamBuffers::default()),
         }
     }
 }
@@ -5,10 +5,15 @@
 //! - RuleStore for persistent rule storage
 //! - AutomationStore for unified automations
 //! - TransformEngine for data processing
+//! - AnomalyDetector for telemetry baselines
 
 use std::sync::Arc;
 
-use crate::automation::{store::SharedAutomationStore, transform::TransformEngine};
+use crate::automation::{
+    anomaly::{AnomalyConfig, AnomalyDetector},
+    store::SharedAutomationStore,
+    transform::TransformEngine,
+};
 use neomind_rules::{store::RuleStore, RuleEngine, UnifiedValueProvider};
 
 /// Automation and rules state.
@@ -30,6 +35,9 @@ pub struct AutomationState {
 
     /// Transform engine for data processing.
     pub transform_engine: Option<Arc<TransformEngine>>,
+
+    /// Anomaly detector for device telemetry.
+    pub anomaly_detector: Arc<AnomalyDetector>,
 }
 
 impl AutomationState {
@@ -52,6 +60,7 @@ impl AutomationState {
             rule_store,
             automation_store,
             transform_engine,
+            anomaly_detector: Arc::new(AnomalyDetector::new(AnomalyConfig::default())),
         }
     }
 
@@ -68,6 +77,7 @@ impl AutomationState {
             rule_store: None,
             automation_store: None,
             transform_engine: None,
+            anomaly_detector: Arc::new(AnomalyDetector::new(AnomalyConfig::default())),
         }
     }
 }
@@ -3,13 +3,14 @@
 //! Contains fundamental services used across the application:
 //! - EventBus for event-driven communication
 //! - MessageManager for unified messaging
+//! - EscalationManager for alert escalation
 //!
 //! Note: ExtensionRegistry has been moved to ExtensionState for proper decoupling.
 
 use std::sync::Arc;
 
 use neomind_core::EventBus;
-use neomind_messages::MessageManager;
+use neomind_messages::{EscalationManager, MessageManager};
 
 /// Core system services state.
 ///
@@ -21,14 +22,19 @@ pub struct CoreState {
 
     /// Message manager for unified messages/notifications system.
     pub message_manager: Arc<MessageManager>,
+
+    /// Escalation of unacknowledged alerts to on-call channels.
+    pub escalation: Arc<EscalationManager>,
 }
 
 impl CoreState {
     /// Create a new core state.
     pub fn new(event_bus: Option<Arc<EventBus>>, message_manager: Arc<MessageManager>) -> Self {
+        let escalation = Arc::new(EscalationManager::new(message_manager.clone()));
         Self {
             event_bus,
             message_manager,
+            escalation,
         }
     }
 
@@ -36,9 +42,11 @@ impl CoreState {
     #[cfg(test)]
     pub fn minimal() -> Self {
         use neomind_core::EventBus;
+        let message_manager = Arc::new(MessageManager::new());
         Self {
             event_bus: Some(Arc::new(EventBus::new())),
-            message_manager: Arc::new(MessageManager::new()),
+            escalation: Arc::new(EscalationManager::new(message_manager.clone())),
+            message_manager,
         }
     }
 }
@@ -5,11 +5,13 @@
 //! - DeviceService for unified device operations
 //! - TimeSeriesStorage for device metrics/telemetry
 //! - EmbeddedBroker (optional) for MQTT
+//! - NetworkDiscovery for mDNS/SSDP scanning and its approval queue
 //! - Device status broadcast channel
 
 use std::sync::Arc;
 use tokio::sync::broadcast;
 
+use crate::automation::discovery::{NetworkDiscovery, NetworkDiscoveryConfig};
 use neomind_devices::{DeviceRegistry, DeviceService, TimeSeriesStorage};
 
 #[cfg(feature = "embedded-broker")]
@@ -35,6 +37,9 @@ pub struct DeviceState {
 
     /// Device status update broadcast sender.
     pub update_tx: broadcast::Sender<DeviceStatusUpdate>,
+
+    /// mDNS/SSDP network discovery and its pending-approval queue.
+    pub network_discovery: Arc<NetworkDiscovery>,
 }
 
 impl DeviceState {
@@ -46,11 +51,16 @@ impl DeviceState {
         telemetry: Arc<TimeSeriesStorage>,
         update_tx: broadcast::Sender<DeviceStatusUpdate>,
     ) -> Self {
+        let network_discovery = Arc::new(NetworkDiscovery::new(
+            service.clone(),
+            NetworkDiscoveryConfig::from_env().unwrap_or_default(),
+        ));
         Self {
             registry,
             service,
             telemetry,
             update_tx,
+            network_discovery,
             #[cfg(feature = "embedded-broker")]
             embedded_broker: Arc::new(std::sync::RwLock::new(None)),
         }
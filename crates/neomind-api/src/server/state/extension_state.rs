@@ -266,6 +266,17 @@ impl ExtensionState {
         // Use the shared extension store from state
         let store = &self.store;
 
+        // Restore operator approvals before any extension can make requests
+        if let Ok(all) = store.load_all() {
+            let permissions = self.runtime.permissions();
+            for record in all {
+                permissions.approve(
+                    &record.id,
+                    record.granted_permissions.into_iter().collect(),
+                );
+            }
+        }
+
         // Load all auto-start extensions
         let records = store
             .load_auto_start()
@@ -9,12 +9,16 @@ pub type CredentialValidator = Arc<dyn Fn(&str, &str) -> bool + Send + Sync>;
 /// In-memory cache for MQTT credentials (I5: avoid redb hit on every CONNECT).
 ///
 /// Stores user credentials (username -> bcrypt hash) and the system password
-/// so the auth hook can validate without touching redb.
+/// so the auth hook can validate without touching redb. Provisioned device
+/// identities are loaded as user credentials and also recorded in `devices`
+/// for the topic ACL.
 #[cfg(feature = "embedded-broker")]
 #[derive(Default)]
 pub struct CredentialCache {
     /// User credentials: username -> bcrypt password hash.
     pub users: std::collections::HashMap<String, String>,
+    /// Provisioned devices: username -> device_id (revoked ones excluded).
+    pub devices: std::collections::HashMap<String, String>,
     /// System password for `__neomind_internal__` (plaintext, never logged).
     pub system_password: Option<String>,
 }
@@ -24,6 +28,7 @@ impl std::fmt::Debug for CredentialCache {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CredentialCache")
             .field("users", &format!("{} entries", self.users.len()))
+            .field("devices", &format!("{} entries", self.devices.len()))
             .field(
                 "system_password",
                 &self.system_password.as_ref().map(|_| "***REDACTED***"),
@@ -63,8 +68,41 @@ impl CredentialCache {
             }
         }
 
+        // Load provisioned device identities
+        match store.list_device_identities() {
+            Ok(identities) => {
+                for identity in identities.iter().filter(|i| !i.is_revoked()) {
+                    cache
+                        .users
+                        .insert(identity.username.clone(), identity.password_hash.clone());
+                    cache
+                        .devices
+                        .insert(identity.username.clone(), identity.device_id.clone());
+                }
+            }
+            Err(e) => {
+                return Err(format!("Failed to load device identities: {}", e));
+            }
+        }
+
         Ok(cache)
     }
+
+    /// Topic ACL decision for a client.
+    ///
+    /// Device usernames may only use their own device's topics; revoked
+    /// devices are refused everything. Other clients are not restricted.
+    pub fn topic_allowed(&self, username: Option<&str>, topic: &str) -> bool {
+        let Some(username) = username else {
+            return true;
+        };
+        if !username.starts_with(neomind_storage::settings::DEVICE_MQTT_USERNAME_PREFIX) {
+            return true;
+        }
+        self.devices
+            .get(username)
+            .is_some_and(|device_id| neomind_devices::device_topic_allowed(device_id, topic))
+    }
 }
 
 use neomind_agent::SessionManager;
@@ -80,6 +118,7 @@ use neomind_storage::dashboards::DashboardStore;
 use neomind_storage::frontend_components::FrontendComponentStore;
 use neomind_storage::instances::InstanceStore;
 use neomind_storage::llm_backends::LlmBackendStore;
+use neomind_storage::reports::ReportStore;
 
 use crate::automation::{
     store::SharedAutomationStore, transform::TransformEngine, AutoOnboardManager,
@@ -98,7 +137,7 @@ use crate::server::state::{
 };
 
 #[cfg(feature = "embedded-broker")]
-use neomind_devices::{EmbeddedBroker, TopicResolverFn};
+use neomind_devices::{AclCheckerFn, EmbeddedBroker, TopicResolverFn};
 
 /// Maximum request body size (10 MB)
 pub const MAX_REQUEST_BODY_SIZE: usize = 10 * 1024 * 1024;
@@ -126,6 +165,13 @@ pub const MAX_EXTENSION_UPLOAD_SIZE: usize = 512 * 1024 * 1024;
 /// reported) plus a running byte counter during streaming.
 pub const MAX_EXTENSION_DOWNLOAD_SIZE: u64 = 1024 * 1024 * 1024;
 
+/// Event log consumer name under which the rule engine commits its cursor.
+const RULE_ENGINE_EVENT_CONSUMER: &str = "rule_engine";
+
+/// How often a durable event consumer commits its cursor. Events delivered
+/// after the last commit are evaluated again after a restart.
+const EVENT_CURSOR_COMMIT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
 /// Server state shared across all handlers.
 ///
 /// Organized into logical sub-states for better maintainability.
@@ -158,6 +204,15 @@ pub struct ServerState {
     /// Data directory for persistent storage (e.g. skills, extensions).
     pub data_dir: std::path::PathBuf,
 
+    /// Background telemetry export jobs.
+    pub exports: Arc<crate::exports::ExportManager>,
+
+    /// Report templates, scheduled generation and past reports.
+    pub reports: Arc<crate::reports::ReportManager>,
+
+    /// Knowledge base of uploaded documents for retrieval.
+    pub knowledge: Arc<crate::knowledge::KnowledgeBase>,
+
     /// Data push manager (lazy-initialized).
     pub data_push: Arc<tokio::sync::RwLock<Option<PushManager>>>,
 
@@ -348,6 +403,7 @@ impl ServerState {
         if let Some(resolver) = self.build_topic_resolver() {
             broker.set_topic_resolver(resolver);
         }
+        broker.set_acl_checker(self.build_acl_checker());
 
         if let Err(e) = broker.start().await {
             // Attempt rollback: restart with the old config and rebuild adapter
@@ -372,6 +428,7 @@ impl ServerState {
                 if let Some(resolver) = self.build_topic_resolver() {
                     rollback_broker.set_topic_resolver(resolver);
                 }
+                rollback_broker.set_acl_checker(self.build_acl_checker());
                 if let Some(bus) = self.core.event_bus.as_ref() {
                     rollback_broker.set_event_bus(bus.clone());
                 }
@@ -412,6 +469,7 @@ impl ServerState {
                             discovery_prefix: "device".to_string(),
                             auto_discovery: true,
                             storage_dir: Some("data".to_string()),
+                            auto_mapping: Default::default(),
                         };
                         if let Some(event_bus) = self.core.event_bus.as_ref() {
                             if let Ok(val) = serde_json::to_value(&rollback_mqtt_config) {
@@ -490,6 +548,7 @@ impl ServerState {
             discovery_prefix: "device".to_string(),
             auto_discovery: true,
             storage_dir: Some("data".to_string()),
+            auto_mapping: Default::default(),
         };
 
         let Some(event_bus) = self.core.event_bus.as_ref() else {
@@ -574,6 +633,16 @@ impl ServerState {
         }))
     }
 
+    /// Build the topic ACL for `EmbeddedBroker::set_acl_checker`, backed by
+    /// the credential cache so revocations apply without a broker restart.
+    #[cfg(feature = "embedded-broker")]
+    fn build_acl_checker(&self) -> AclCheckerFn {
+        let cache = self.credential_cache.clone();
+        Arc::new(move |username: Option<&str>, topic: &str| {
+            cache.read().unwrap().topic_allowed(username, topic)
+        })
+    }
+
     /// Get device service (backward compatibility).
     pub fn device_service(&self) -> Arc<DeviceService> {
         self.devices.service.clone()
@@ -683,7 +752,25 @@ impl ServerState {
 
         // ========== Build CORE STATE ==========
         // Create event bus FIRST (needed for adapters to publish events)
-        let event_bus = Some(Arc::new(EventBus::new()));
+        // Events are journaled so consumers can resume from a cursor after a restart
+        let max_events = neomind_core::config::device_env_vars::event_log_max_events();
+        let event_bus = match neomind_storage::PersistentEventLog::open(
+            data_dir.join("events.redb"),
+        ) {
+            Ok(log) => {
+                tracing::info!(
+                    max_events,
+                    "Event log initialized at {}/events.redb",
+                    data_dir.display()
+                );
+                EventBus::new().with_persistence(Arc::new(log.with_max_events(max_events)))
+            }
+            Err(e) => {
+                tracing::warn!(category = "storage", error = %e, "Failed to open event log, events will not be persisted");
+                EventBus::new()
+            }
+        };
+        let event_bus = Some(Arc::new(event_bus));
 
         // Create message manager with persistent storage
         let message_manager = match MessageManager::with_storage("data") {
@@ -927,6 +1014,9 @@ impl ServerState {
         rule_engine
             .set_message_manager(core.message_manager.clone())
             .await;
+        rule_engine
+            .set_maintenance_registry(core.message_manager.maintenance())
+            .await;
 
         // Wire rule engine to device service
         let device_service_for_action = devices.service.clone();
@@ -950,6 +1040,40 @@ impl ServerState {
         if let Some(ref bus) = event_bus {
             core.message_manager.set_event_bus(bus.clone()).await;
         }
+        // Device alerts belong to the device's tenant
+        core.message_manager
+            .set_tenant_resolver(device_tenant_resolver(devices.service.clone()))
+            .await;
+
+        // Restore alert grouping settings and start the digest loop
+        if let Some(config) = neomind_storage::SettingsStore::open("data/settings.redb")
+            .ok()
+            .and_then(|store| {
+                store
+                    .load(neomind_messages::grouping::ALERT_GROUPING_SETTINGS_KEY)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|json| serde_json::from_str(&json).ok())
+        {
+            core.message_manager.set_grouping_config(config).await;
+        }
+        core.message_manager.start_digest_task();
+
+        // Restore escalation policies and on-call schedules
+        if let Some(config) = neomind_storage::SettingsStore::open("data/settings.redb")
+            .ok()
+            .and_then(|store| {
+                store
+                    .load(neomind_messages::escalation::ESCALATION_SETTINGS_KEY)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|json| serde_json::from_str(&json).ok())
+        {
+            core.escalation.set_config(config).await;
+        }
+        core.escalation.start(30);
 
         // Await parallel-opened rule store
         let rule_store = rule_store_h.await.expect("rule_store task panicked");
@@ -977,6 +1101,28 @@ impl ServerState {
                 }
             }
 
+            match store.list_machines() {
+                Ok(machines) => {
+                    tracing::info!(
+                        "Loading {} state machines from persistent store",
+                        machines.len()
+                    );
+                    for machine in machines {
+                        let id = machine.id.clone();
+                        if let Err(e) = rule_engine.add_machine(machine).await {
+                            tracing::warn!("Failed to load state machine {}: {}", id, e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        category = "storage",
+                        error = %e,
+                        "Failed to load state machines from store"
+                    );
+                }
+            }
+
             // Clean up execution history older than 30 days to prevent unbounded growth
             match store.cleanup_history(30) {
                 Ok(removed) if removed > 0 => {
@@ -1021,14 +1167,20 @@ impl ServerState {
         tracing::info!("Transform engine initialized with extension registry");
 
         // Spawn DeviceStatusEmitter — 60s tick that refreshes the
-        // __last_seen_age_secs virtual metric for devices referenced by rules.
+        // __last_seen_age_secs virtual metric for devices referenced by rules,
+        // plus the event-driven __online connectivity metric.
         let device_status_emitter = std::sync::Arc::new(DeviceStatusEmitter::new(
             rule_engine.clone(),
             value_provider.clone(),
             devices.service.clone(),
         ));
+        let _device_connectivity_handle = device_status_emitter
+            .clone()
+            .start_connectivity_listener();
         let _device_status_emitter_handle = device_status_emitter.start();
-        tracing::info!("DeviceStatusEmitter spawned (60s tick for __last_seen_age_secs)");
+        tracing::info!(
+            "DeviceStatusEmitter spawned (60s tick for __last_seen_age_secs, events for __online)"
+        );
 
         let automation = AutomationState::new(
             value_provider,
@@ -1158,6 +1310,26 @@ impl ServerState {
             });
         }
 
+        let exports = Arc::new(crate::exports::ExportManager::open(
+            data_dir.join("exports"),
+            devices.telemetry.clone(),
+        ));
+        let knowledge = Arc::new(crate::knowledge::KnowledgeBase::new(
+            data_dir.join("knowledge.redb"),
+        ));
+        let report_store = ReportStore::open(data_dir.join("reports.redb")).unwrap_or_else(|e| {
+            tracing::error!(category = "storage", error = %e, "Failed to open report store, using temporary store");
+            ReportStore::memory().expect("Failed to create temporary report store")
+        });
+        let reports = Arc::new(crate::reports::ReportManager::new(
+            data_dir.join("reports"),
+            report_store,
+            devices.service.clone(),
+            devices.telemetry.clone(),
+            core.message_manager.clone(),
+        ));
+        reports.start_scheduler();
+
         Self {
             core,
             devices,
@@ -1182,6 +1354,9 @@ impl ServerState {
             extension_event_subscription_service: Arc::new(tokio::sync::Mutex::new(None)),
             telemetry_query_semaphore: Arc::new(tokio::sync::Semaphore::new(16)),
             data_dir,
+            exports,
+            reports,
+            knowledge,
             data_push: {
                 let push_manager = match PushManager::new_with_telemetry(
                     std::path::Path::new("data"),
@@ -1282,6 +1457,9 @@ impl ServerState {
         rule_engine
             .set_message_manager(core.message_manager.clone())
             .await;
+        rule_engine
+            .set_maintenance_registry(core.message_manager.maintenance())
+            .await;
 
         let device_service_for_action = devices.service.clone();
         let device_action_executor = Arc::new(DeviceActionExecutor::with_device_service(
@@ -1302,6 +1480,9 @@ impl ServerState {
         if let Some(ref bus) = event_bus {
             core.message_manager.set_event_bus(bus.clone()).await;
         }
+        core.message_manager
+            .set_tenant_resolver(device_tenant_resolver(devices.service.clone()))
+            .await;
 
         // In-memory stores
         let automation_store = Some(Arc::new(SharedAutomationStore::memory().unwrap()));
@@ -1351,6 +1532,20 @@ impl ServerState {
         // Empty GPU info for testing
         let gpu_info = Arc::new(std::sync::OnceLock::from(vec![]));
         let data_push_telemetry = time_series_storage.clone();
+        let exports = Arc::new(crate::exports::ExportManager::new(
+            std::path::PathBuf::from("data/exports"),
+            devices.telemetry.clone(),
+        ));
+        let knowledge = Arc::new(crate::knowledge::KnowledgeBase::new(
+            std::path::PathBuf::from("data/knowledge.redb"),
+        ));
+        let reports = Arc::new(crate::reports::ReportManager::new(
+            std::env::temp_dir().join(format!("neomind-test-reports-{}", uuid::Uuid::new_v4())),
+            ReportStore::memory().expect("Failed to create test report store"),
+            devices.service.clone(),
+            devices.telemetry.clone(),
+            core.message_manager.clone(),
+        ));
 
         Self {
             core,
@@ -1376,6 +1571,9 @@ impl ServerState {
             extension_event_subscription_service: Arc::new(tokio::sync::Mutex::new(None)),
             telemetry_query_semaphore: Arc::new(tokio::sync::Semaphore::new(16)),
             data_dir: std::path::PathBuf::from("data"),
+            exports,
+            reports,
+            knowledge,
             data_push: {
                 let push_manager = PushManager::memory_with_telemetry(data_push_telemetry).ok();
                 Arc::new(tokio::sync::RwLock::new(push_manager))
@@ -1397,6 +1595,14 @@ impl ServerState {
         // Device registry storage is initialized automatically on first use
         tracing::info!(category = "storage", "Data directory created/verified");
 
+        // Answer dashboard and rule reads of recent windows from memory. Set
+        // up here rather than in `new` so a window saved in the runtime
+        // config applies.
+        if let Some(config) = neomind_devices::HotCacheConfig::from_env() {
+            let cache = neomind_devices::MetricCache::new(100).with_hot_window(config);
+            self.devices.telemetry.set_metric_cache(Some(Arc::new(cache)));
+        }
+
         // Seed built-in device type templates (NE101, NE301, etc.)
         match neomind_storage::DeviceRegistryStore::open("data/devices.redb") {
             Ok(store) => match store.seed_builtin_templates() {
@@ -1621,6 +1827,8 @@ impl ServerState {
         if let Some(resolver) = self.build_topic_resolver() {
             broker.set_topic_resolver(resolver);
         }
+        // Confine provisioned devices to their own topics.
+        broker.set_acl_checker(self.build_acl_checker());
 
         match broker.start().await {
             Ok(_) => {
@@ -1652,6 +1860,24 @@ impl ServerState {
         // Start device service to listen for EventBus events
         self.devices.service.start().await;
 
+        // Queue commands for offline devices and deliver them on reconnect
+        if let Some(config) = neomind_devices::StoreForwardConfig::from_env() {
+            self.devices.service.start_store_and_forward(config).await;
+        }
+
+        // Send commands scheduled for a later time or on a recurrence
+        self.devices.service.start_command_scheduler();
+
+        // Downsample telemetry from devices publishing faster than storage keeps up
+        if let Some(config) = neomind_devices::IngestQosConfig::from_env() {
+            self.devices.service.enable_ingest_qos(config).await;
+        }
+
+        // Scan the LAN for mDNS/SSDP devices and queue them for approval
+        if crate::automation::discovery::NetworkDiscoveryConfig::from_env().is_some() {
+            self.devices.network_discovery.start();
+        }
+
         // Create and register the internal MQTT adapter.
         // When auth is enabled, system credentials are needed for the handler.
         // When auth is disabled, providing credentials is harmless since
@@ -1699,6 +1925,7 @@ impl ServerState {
             discovery_prefix: "device".to_string(),
             auto_discovery: true,
             storage_dir: Some("data".to_string()),
+            auto_mapping: Default::default(),
         };
 
         // Create the MQTT adapter
@@ -1800,6 +2027,82 @@ impl ServerState {
             }
         }
 
+        // Create and register the Modbus TCP polling adapter
+        {
+            use neomind_devices::adapters::{create_adapter, modbus::ModbusTcpAdapterConfig};
+
+            let modbus_config = ModbusTcpAdapterConfig::new("internal-modbus-tcp");
+            let modbus_config_value = serde_json::to_value(&modbus_config)
+                .unwrap_or_else(|_| serde_json::json!({ "name": "internal-modbus-tcp" }));
+
+            if let Some(event_bus) = self.core.event_bus.as_ref() {
+                match create_adapter("modbus-tcp", &modbus_config_value, event_bus) {
+                    Ok(adapter) => {
+                        // Pollers resolve host/registers from the shared registry
+                        if let Some(modbus) = adapter
+                            .as_any()
+                            .downcast_ref::<neomind_devices::adapters::ModbusTcpAdapter>(
+                        ) {
+                            modbus
+                                .set_shared_device_registry(self.devices.service.get_registry())
+                                .await;
+                        }
+
+                        self.devices
+                            .service
+                            .register_adapter("internal-modbus-tcp".to_string(), adapter.clone())
+                            .await;
+                        if let Err(e) = adapter.start().await {
+                            tracing::warn!("Failed to start Modbus TCP adapter: {}", e);
+                        } else {
+                            tracing::info!("Modbus TCP adapter started successfully");
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to create Modbus TCP adapter: {}", e);
+                    }
+                }
+            }
+        }
+
+        // Create and register the ChirpStack (LoRaWAN) integration adapter
+        {
+            use neomind_devices::adapters::{chirpstack::CHIRPSTACK_ADAPTER_TYPE, create_adapter};
+
+            let chirpstack_config = crate::config::load_chirpstack_config("internal-chirpstack");
+            let chirpstack_config_value = serde_json::to_value(&chirpstack_config)
+                .unwrap_or_else(|_| serde_json::json!({ "name": "internal-chirpstack" }));
+
+            if let Some(event_bus) = self.core.event_bus.as_ref() {
+                match create_adapter(CHIRPSTACK_ADAPTER_TYPE, &chirpstack_config_value, event_bus) {
+                    Ok(adapter) => {
+                        // DevEUI lookups and auto-registration use the shared registry
+                        if let Some(chirpstack) = adapter
+                            .as_any()
+                            .downcast_ref::<neomind_devices::adapters::ChirpStackAdapter>(
+                        ) {
+                            chirpstack
+                                .set_shared_device_registry(self.devices.service.get_registry())
+                                .await;
+                        }
+
+                        self.devices
+                            .service
+                            .register_adapter("internal-chirpstack".to_string(), adapter.clone())
+                            .await;
+                        if let Err(e) = adapter.start().await {
+                            tracing::warn!("Failed to start ChirpStack adapter: {}", e);
+                        } else {
+                            tracing::info!("ChirpStack adapter started successfully");
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to create ChirpStack adapter: {}", e);
+                    }
+                }
+            }
+        }
+
         // Load and reconnect external MQTT brokers
         self.reconnect_external_mqtt_brokers().await;
     }
@@ -1892,13 +2195,18 @@ impl ServerState {
         let mut registry = ToolRegistryBuilder::new()
             // Extension registry for scanning extension-provided tools
             .with_extension_registry(self.extensions.registry.clone())
-            // Shell tool — CLI-first: domain operations via `neomind` commands
+            // Shell tool — CLI-first: domain operations via `neomind` commands,
+            // authenticated with a key bound to the session's tenant
+            .with_tenant_keys(self.agent_key_issuer())
             .with_shell_tool(Some(neomind_agent::toolkit::ShellConfig {
                 enabled: true,
                 timeout_secs: 30,
                 max_output_chars: 10000,
             }))
-            // Scan extensions and register their tools (dynamic, keep)
+            // Scan extensions and register their tools (dynamic, keep);
+            // extensions with tools switched off are skipped
+            .without_extensions(tool_disabled_extensions())
+            .with_execution_policy(tool_execution_policy())
             .with_extensions_scanned()
             .await
             .build();
@@ -1979,6 +2287,12 @@ impl ServerState {
         );
     }
 
+    /// Issues the tenant-bound API keys agent tools run `neomind` commands with.
+    fn agent_key_issuer(&self) -> neomind_agent::toolkit::TenantKeyIssuer {
+        let keys = self.auth.api_key_state.clone();
+        Arc::new(move |tenant| Some(keys.agent_key(tenant)))
+    }
+
     /// Refresh extension tools in the tool registry.
     ///
     /// Should be called after extensions are loaded (`init_extensions`) to ensure
@@ -1992,11 +2306,14 @@ impl ServerState {
         // Rebuild the registry with extensions now loaded
         let mut registry = ToolRegistryBuilder::new()
             .with_extension_registry(self.extensions.registry.clone())
+            .with_tenant_keys(self.agent_key_issuer())
             .with_shell_tool(Some(neomind_agent::toolkit::ShellConfig {
                 enabled: true,
                 timeout_secs: 30,
                 max_output_chars: 10000,
             }))
+            .without_extensions(tool_disabled_extensions())
+            .with_execution_policy(tool_execution_policy())
             .with_extensions_scanned()
             .await
             .build();
@@ -2068,7 +2385,7 @@ impl ServerState {
         // Apply persisted tool-disable state so previously toggled-off tools
         // stay hidden from the LLM after a server restart. Built-ins are never
         // disabled; only extension tools (and only those listed in storage).
-        apply_persisted_tool_disabled_state(&self.extensions.runtime, &tool_registry).await;
+        apply_persisted_tool_disabled_state(&tool_registry);
 
         tracing::info!(
             category = "ai",
@@ -2219,8 +2536,10 @@ impl ServerState {
         }
 
         // Start a task to update the UnifiedValueProvider when device metrics arrive
-        // This is needed for rule evaluation to work with current values
-        let mut rx = event_bus.filter().device_events();
+        // This is needed for rule evaluation to work with current values.
+        // It resumes from its committed cursor, so metrics journaled while the
+        // server was down are still evaluated on startup.
+        let mut rx = event_bus.subscribe_durable(RULE_ENGINE_EVENT_CONSUMER);
         let value_provider = self.automation.value_provider.clone();
         let rule_engine_for_update = rule_engine.clone();
 
@@ -2229,6 +2548,7 @@ impl ServerState {
 
             tracing::info!("Starting value provider update task for rule engine");
 
+            let mut last_commit = std::time::Instant::now();
             while let Some((event, _metadata)) = rx.recv().await {
                 if let NeoMindEvent::DeviceMetric {
                     device_id,
@@ -2361,6 +2681,11 @@ impl ServerState {
                         }
                     }
                 }
+
+                if last_commit.elapsed() >= EVENT_CURSOR_COMMIT_INTERVAL {
+                    rx.commit();
+                    last_commit = std::time::Instant::now();
+                }
             }
 
             tracing::warn!("Value provider update task ended");
@@ -2468,6 +2793,9 @@ impl ServerState {
                     ticker.tick().await;
                     let now = Utc::now();
 
+                    // Time-in-state transitions of state machines share the tick
+                    rule_engine.tick_machines().await;
+
                     let schedule_rules = rule_engine.list_schedule_rules().await;
                     for (rule_id, cron_expr) in schedule_rules {
                         // Parse cron and check if it should fire now (within the last 30s window)
@@ -2756,6 +3084,88 @@ impl ServerState {
         }
     }
 
+    /// Start learning device metric baselines and publishing anomalies.
+    pub fn init_anomaly_detection(&self) {
+        let Some(event_bus) = &self.core.event_bus else {
+            tracing::warn!("Anomaly detection not started: event bus not available");
+            return;
+        };
+        self.automation
+            .anomaly_detector
+            .start(event_bus.clone(), self.devices.telemetry.clone());
+    }
+
+    /// Load persisted runtime configuration and publish changes on the event bus.
+    pub fn init_config_service(&self) {
+        let service = neomind_core::config::service::global();
+        match neomind_storage::SettingsStore::open("data/settings.redb") {
+            Ok(store) => {
+                if let Err(e) = service.attach_store(store) {
+                    tracing::warn!("Failed to load runtime config: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!(
+                "Runtime config not persisted: settings store unavailable: {}",
+                e
+            ),
+        }
+        if let Some(bus) = &self.core.event_bus {
+            service.set_event_bus((**bus).clone());
+        }
+    }
+
+    /// Push maintenance windows, the timezone they recur in and the members
+    /// of the groups they cover to the shared maintenance registry.
+    pub fn refresh_maintenance_windows(&self) {
+        let Ok(store) = neomind_storage::SettingsStore::open("data/settings.redb") else {
+            tracing::warn!("Maintenance windows not refreshed: settings store unavailable");
+            return;
+        };
+        let windows = match store.load_maintenance_windows() {
+            Ok(windows) => windows,
+            Err(e) => {
+                tracing::warn!("Failed to load maintenance windows: {}", e);
+                return;
+            }
+        };
+        let group_members = windows
+            .iter()
+            .flat_map(|w| w.groups.iter())
+            .filter_map(|group_id| {
+                let members = self.devices.service.get_group_devices(group_id).ok()?;
+                Some((
+                    group_id.clone(),
+                    members.into_iter().map(|d| d.device_id).collect(),
+                ))
+            })
+            .collect();
+
+        let registry = self.core.message_manager.maintenance();
+        registry.set_timezone(
+            store
+                .get_global_timezone()
+                .parse::<chrono_tz::Tz>()
+                .unwrap_or(chrono_tz::UTC),
+        );
+        registry.set_group_members(group_members);
+        registry.set_windows(windows);
+    }
+
+    /// Load maintenance windows and keep group membership and timezone
+    /// current as devices and settings change.
+    pub fn init_maintenance_windows(&self) {
+        self.refresh_maintenance_windows();
+        let state = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                state.refresh_maintenance_windows();
+            }
+        });
+    }
+
     /// Get or initialize the AI Agent manager.
     pub async fn get_or_init_agent_manager(
         &self,
@@ -2926,6 +3336,7 @@ impl ServerState {
             tool_registry: self.agents.session_manager.get_tool_registry().await,
             memory_store: Some(self.agents.system_memory_store.clone()),
             backend_semaphores: None,
+            execution_limiter: None,
             skill_registry: Some(self.agents.session_manager.skill_registry()),
         };
 
@@ -3099,17 +3510,67 @@ impl ServerState {
     }
 }
 
+/// Owner lookup for alerts raised about a device: the device is the message
+/// source or is named by `metadata.device_id`.
+fn device_tenant_resolver(devices: Arc<DeviceService>) -> neomind_messages::TenantResolver {
+    Arc::new(move |message: &neomind_messages::Message| {
+        let device_id = if message.source_type == "device" {
+            Some(message.source.as_str())
+        } else {
+            message
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("device_id"))
+                .and_then(|v| v.as_str())
+        }?;
+        devices.get_device(device_id).map(|config| config.tenant_id)
+    })
+}
+
+/// Extensions whose tool master switch is off (`enabled=false`). Their tools
+/// are left out of the ToolRegistry when it is built.
+fn tool_disabled_extensions() -> std::collections::HashSet<String> {
+    let Ok(store) = neomind_storage::ExtensionStore::open("data/extensions.redb") else {
+        return Default::default();
+    };
+    store
+        .load_all()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| !r.uninstalled && !r.enabled)
+        .map(|r| r.id)
+        .collect()
+}
+
+/// Execution limits for the shared ToolRegistry, from the `agent.tool_*`
+/// runtime config. Unset values leave that limit off.
+fn tool_execution_policy() -> neomind_agent::toolkit::ToolExecutionPolicy {
+    use neomind_core::config::agent_env_vars;
+
+    let mut policy = neomind_agent::toolkit::ToolExecutionPolicy::new();
+    if let Some(secs) = agent_env_vars::tool_timeout_secs() {
+        policy = policy.with_default_timeout(std::time::Duration::from_secs(secs));
+    }
+    if let Some(max) = agent_env_vars::tool_max_concurrent() {
+        policy = policy.with_max_concurrent(max);
+    }
+    if let Some(max) = agent_env_vars::tool_max_output_bytes() {
+        policy = policy.with_max_output_bytes(max);
+    }
+    policy
+}
+
 /// Rebuild the ToolRegistry disabled set from the persisted ExtensionRecord
-/// state and push it live. Built-in tools are never disabled; only extension
-/// tools whose parent extension is `enabled=false` (master off) or whose
-/// command name appears in `disabled_commands` (per-command off) are hidden.
+/// state and push it live. Built-in tools are never disabled; extension tools
+/// whose command name appears in `disabled_commands` (per-command off) are
+/// hidden. Master-off extensions have no tools registered at all.
 ///
 /// Called at startup (after extensions load + tool registry finalized) and
-/// after every toggle API call. Cheap: O(n_extensions × n_commands).
-async fn apply_persisted_tool_disabled_state(
-    runtime: &neomind_core::extension::ExtensionRuntime,
+/// after every registry rebuild. Cheap: O(n_extensions × n_commands).
+fn apply_persisted_tool_disabled_state(
     tool_registry: &std::sync::Arc<neomind_agent::toolkit::ToolRegistry>,
 ) {
+    use neomind_agent::toolkit::extension_tools::extension_tool_name;
     use std::collections::HashSet;
 
     let records = match neomind_storage::ExtensionStore::open("data/extensions.redb") {
@@ -3125,20 +3586,12 @@ async fn apply_persisted_tool_disabled_state(
 
     let mut disabled: HashSet<String> = HashSet::new();
     for r in records {
-        if r.uninstalled {
-            continue;
-        }
-        if !r.enabled {
-            // Master off: enumerate live commands to materialize tool names.
-            if let Some(info) = runtime.get(&r.id).await {
-                for cmd in &info.commands {
-                    disabled.insert(format!("{}:{}", r.id, cmd.name));
-                }
-            }
+        // Master off: the tools were never registered
+        if r.uninstalled || !r.enabled {
             continue;
         }
         for cmd_name in &r.disabled_commands {
-            disabled.insert(format!("{}:{}", r.id, cmd_name));
+            disabled.insert(extension_tool_name(&r.id, cmd_name));
         }
     }
 
@@ -0,0 +1,69 @@
+//! OpenTelemetry trace export (feature `otel`).
+//!
+//! [`otel_layer`] builds a `tracing` layer that ships spans to an OTLP/gRPC
+//! collector configured through [`OtelConfig`]. The agent pipeline's
+//! `agent.turn` → `agent.intent` / `agent.context` / `agent.llm_call` /
+//! `agent.tool` spans then show up as one trace per chat turn. Call
+//! [`shutdown`] before exit to flush spans still queued in the batch exporter.
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::config::OtelConfig;
+
+/// Build the OTLP export layer from the environment.
+///
+/// Returns `None` when no endpoint is configured or the exporter cannot be
+/// created; the server then runs with local logging only. Must be called from
+/// within a Tokio runtime.
+pub fn otel_layer<S>() -> Option<OpenTelemetryLayer<S, Tracer>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let config = OtelConfig::from_env()?;
+
+    let exporter = match opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(config.endpoint.clone())
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            // The subscriber is not installed yet, so tracing macros would be lost.
+            eprintln!(
+                "Failed to create OTLP exporter for {}: {}",
+                config.endpoint, e
+            );
+            return None;
+        }
+    };
+
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio,
+        ))))
+        .with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            config.service_name.clone(),
+        )]))
+        .build();
+    let tracer = provider.tracer("neomind");
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry::global::set_tracer_provider(provider);
+
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Flush queued spans and shut the exporter down.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
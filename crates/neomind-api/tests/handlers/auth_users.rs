@@ -4,7 +4,7 @@ use axum::extract::{Extension, Path, State};
 use axum::http::StatusCode;
 use axum::Json;
 use neomind_api::auth_users::{
-    ChangePasswordRequest, LoginRequest, RegisterRequest, SessionInfo, UserRole,
+    ChangePasswordRequest, LoginRequest, RegisterRequest, SessionInfo, UpdateRoleRequest, UserRole,
 };
 use neomind_api::handlers::auth_users::*;
 use neomind_api::handlers::ServerState;
@@ -38,7 +38,8 @@ mod tests {
         let req = RegisterRequest {
             username: username.clone(),
             password: "test_password_123".to_string(),
-            role: Some(UserRole::User),
+            role: Some(UserRole::Operator),
+            tenant_id: None,
         };
         let result = register_handler(State(state), Json(req)).await;
         assert!(result.is_ok());
@@ -60,6 +61,7 @@ mod tests {
             username: username.clone(),
             password: "admin_password_123".to_string(),
             role: Some(UserRole::Admin),
+            tenant_id: None,
         };
         let result = register_handler(State(state), Json(req)).await;
         assert!(result.is_ok());
@@ -67,6 +69,8 @@ mod tests {
         assert_eq!(status, StatusCode::CREATED);
         let value = response.0;
         assert!(value.get("token").is_some());
+        // Self-registration is public, so the requested role is ignored
+        assert_eq!(value["user"]["role"], "viewer");
     }
 
     #[tokio::test]
@@ -80,6 +84,7 @@ mod tests {
             username: username.clone(),
             password: "password123".to_string(),
             role: None, // Should default to User
+            tenant_id: None,
         };
         let result = register_handler(State(state), Json(req)).await;
         assert!(result.is_ok());
@@ -94,9 +99,10 @@ mod tests {
         let user_info = SessionInfo {
             user_id: "test_id".to_string(),
             username: "testuser".to_string(),
-            role: UserRole::User,
+            role: UserRole::Operator,
             created_at: now,
             expires_at: now + 3600,
+            tenant_id: None,
         };
         let result = logout_handler(State(state), Extension(user_info)).await;
         assert!(result.is_ok());
@@ -114,6 +120,7 @@ mod tests {
             role: UserRole::Admin,
             created_at: now,
             expires_at: now + 3600,
+            tenant_id: None,
         };
         let result = get_current_user_handler(Extension(user_info)).await;
         assert!(result.is_ok());
@@ -131,9 +138,10 @@ mod tests {
         let user_info = SessionInfo {
             user_id: "nonexistent_id".to_string(),
             username: "nonexistent".to_string(),
-            role: UserRole::User,
+            role: UserRole::Operator,
             created_at: now,
             expires_at: now + 3600,
+            tenant_id: None,
         };
         let req = ChangePasswordRequest {
             old_password: "wrong_old".to_string(),
@@ -150,9 +158,10 @@ mod tests {
         let user_info = SessionInfo {
             user_id: "test_id".to_string(),
             username: "testuser".to_string(),
-            role: UserRole::User, // Not an admin
+            role: UserRole::Operator, // Not an admin
             created_at: now,
             expires_at: now + 3600,
+            tenant_id: None,
         };
         let result = list_users_handler(State(state), Extension(user_info)).await;
         assert!(result.is_err());
@@ -168,6 +177,7 @@ mod tests {
             role: UserRole::Admin,
             created_at: now,
             expires_at: now + 3600,
+            tenant_id: None,
         };
         let result = list_users_handler(State(state), Extension(user_info)).await;
         assert!(result.is_ok());
@@ -183,14 +193,16 @@ mod tests {
         let user_info = SessionInfo {
             user_id: "test_id".to_string(),
             username: "testuser".to_string(),
-            role: UserRole::User, // Not an admin
+            role: UserRole::Operator, // Not an admin
             created_at: now,
             expires_at: now + 3600,
+            tenant_id: None,
         };
         let req = RegisterRequest {
             username: "newuser".to_string(),
             password: "password123".to_string(),
-            role: Some(UserRole::User),
+            role: Some(UserRole::Operator),
+            tenant_id: None,
         };
         let result = create_user_handler(State(state), Extension(user_info), Json(req)).await;
         assert!(result.is_err());
@@ -206,6 +218,7 @@ mod tests {
             role: UserRole::Admin,
             created_at: now,
             expires_at: now + 3600,
+            tenant_id: None,
         };
         let username = format!(
             "created_user_{}",
@@ -214,7 +227,8 @@ mod tests {
         let req = RegisterRequest {
             username: username.clone(),
             password: "password123".to_string(),
-            role: Some(UserRole::User),
+            role: Some(UserRole::Operator),
+            tenant_id: None,
         };
         let result = create_user_handler(State(state), Extension(admin_info), Json(req)).await;
         assert!(result.is_ok());
@@ -224,6 +238,60 @@ mod tests {
         assert!(value.get("user").is_some());
     }
 
+    #[tokio::test]
+    async fn test_update_user_role_handler() {
+        let state = create_test_server_state().await;
+        let username = format!(
+            "promoted_user_{}",
+            uuid::Uuid::new_v4().to_string().replace('-', "")
+        );
+        let req = RegisterRequest {
+            username: username.clone(),
+            password: "password123".to_string(),
+            role: None,
+            tenant_id: None,
+        };
+        let (_, response) = register_handler(State(state.clone()), Json(req))
+            .await
+            .unwrap();
+        let token = response.0["token"].as_str().unwrap().to_string();
+
+        let now = chrono::Utc::now().timestamp();
+        let session = |role: UserRole| SessionInfo {
+            user_id: "admin_id".to_string(),
+            username: "admin".to_string(),
+            role,
+            created_at: now,
+            expires_at: now + 3600,
+            tenant_id: None,
+        };
+        let promote = || UpdateRoleRequest {
+            role: UserRole::Operator,
+        };
+
+        let result = update_user_role_handler(
+            State(state.clone()),
+            Extension(session(UserRole::Operator)),
+            Path(username.clone()),
+            Json(promote()),
+        )
+        .await;
+        assert!(result.is_err());
+
+        let response = update_user_role_handler(
+            State(state.clone()),
+            Extension(session(UserRole::Admin)),
+            Path(username.clone()),
+            Json(promote()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.0["user"]["role"], "operator");
+
+        // The token issued with the old role no longer works
+        assert!(state.auth.user_state.validate_token(&token).is_err());
+    }
+
     #[tokio::test]
     async fn test_delete_user_handler_non_admin() {
         let state = create_test_server_state().await;
@@ -231,9 +299,10 @@ mod tests {
         let user_info = SessionInfo {
             user_id: "test_id".to_string(),
             username: "testuser".to_string(),
-            role: UserRole::User, // Not an admin
+            role: UserRole::Operator, // Not an admin
             created_at: now,
             expires_at: now + 3600,
+            tenant_id: None,
         };
         let result = delete_user_handler(
             State(state),
@@ -254,6 +323,7 @@ mod tests {
             role: UserRole::Admin,
             created_at: now,
             expires_at: now + 3600,
+            tenant_id: None,
         };
         let result = delete_user_handler(
             State(state),
@@ -272,7 +342,7 @@ mod tests {
     #[tokio::test]
     async fn test_user_role_display() {
         assert_eq!(UserRole::Admin.as_str(), "admin");
-        assert_eq!(UserRole::User.as_str(), "user");
+        assert_eq!(UserRole::Operator.as_str(), "operator");
         assert_eq!(UserRole::Viewer.as_str(), "viewer");
     }
 
@@ -285,6 +355,7 @@ mod tests {
             role: UserRole::Admin,
             created_at: 1234567890,
             expires_at: 1234577890,
+            tenant_id: None,
         };
         assert_eq!(info.user_id, "user123");
         assert_eq!(info.username, "testuser");
@@ -309,6 +380,7 @@ mod tests {
             username: "newuser".to_string(),
             password: "newpass".to_string(),
             role: Some(UserRole::Admin),
+            tenant_id: None,
         };
         assert_eq!(req.username, "newuser");
         assert_eq!(req.password, "newpass");
@@ -324,4 +396,218 @@ mod tests {
         assert_eq!(req.old_password, "oldpass");
         assert_eq!(req.new_password, "newpass");
     }
+
+    #[tokio::test]
+    async fn test_viewer_cannot_modify_devices() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let state = create_test_server_state().await;
+        // Self-service registration always yields an Operator, so create the
+        // accounts directly with the role under test.
+        let token_for = |role: UserRole| {
+            let state = state.clone();
+            async move {
+                let username = format!(
+                    "{}_{}",
+                    role.as_str(),
+                    uuid::Uuid::new_v4().to_string().replace('-', "")
+                );
+                let (_, token) = state
+                    .auth
+                    .user_state
+                    .register(&username, "role_password_123", role)
+                    .await
+                    .unwrap();
+                token
+            }
+        };
+        let viewer = token_for(UserRole::Viewer).await;
+        let operator = token_for(UserRole::Operator).await;
+        let app = neomind_api::server::create_router_with_state(state);
+
+        let request = |method: &str, uri: &str, token: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(Body::from("{}"))
+                .unwrap()
+        };
+
+        for method in ["PUT", "DELETE"] {
+            let response = app
+                .clone()
+                .oneshot(request(method, "/api/devices/sensor-1", &viewer))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{method}");
+        }
+
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/api/devices", &viewer))
+            .await
+            .unwrap();
+        assert_ne!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app
+            .oneshot(request("DELETE", "/api/devices/sensor-1", &operator))
+            .await
+            .unwrap();
+        assert_ne!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_requires_manage_extensions() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let state = create_test_server_state().await;
+        let username = format!(
+            "operator_{}",
+            uuid::Uuid::new_v4().to_string().replace('-', "")
+        );
+        let (_, operator) = state
+            .auth
+            .user_state
+            .register(&username, "role_password_123", UserRole::Operator)
+            .await
+            .unwrap();
+        let app = neomind_api::server::create_router_with_state(state);
+
+        let request = |method: &str, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", operator))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for uri in [
+            "/api/extensions/weather/uninstall",
+            "/api/frontend-components/weather-card",
+        ] {
+            let response = app.clone().oneshot(request("DELETE", uri)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{uri}");
+        }
+
+        // Reading a component stays open to every role
+        let response = app
+            .oneshot(request("GET", "/api/frontend-components/weather-card"))
+            .await
+            .unwrap();
+        assert_ne!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_users_only_see_their_tenant() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use neomind_api::auth::RequestTenant;
+        use neomind_api::handlers::devices::add_device_handler;
+        use neomind_api::handlers::devices::models::AddDeviceRequest;
+        use neomind_core::tenant::{TenantId, TenantScope};
+        use neomind_devices::DeviceTypeTemplate;
+        use tower::ServiceExt;
+
+        let state = create_test_server_state().await;
+        let acme = TenantId::new("acme").unwrap();
+        let globex = TenantId::new("globex").unwrap();
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+
+        let device_type = format!("lamp-{}", suffix);
+        state
+            .devices
+            .service
+            .registry()
+            .register_template(DeviceTypeTemplate::new(&device_type, "Lamp"))
+            .await
+            .unwrap();
+
+        let mut tokens = Vec::new();
+        let mut devices = Vec::new();
+        for tenant in [&acme, &globex] {
+            let (user, token) = state
+                .auth
+                .user_state
+                .register_in_tenant(
+                    &format!("{}_{}", tenant, suffix),
+                    "password123",
+                    UserRole::Operator,
+                    Some(tenant.clone()),
+                )
+                .await
+                .unwrap();
+            assert_eq!(user.tenant_id.as_ref(), Some(tenant));
+            tokens.push(token);
+
+            let device_id = format!("{}-lamp-{}", tenant, suffix);
+            let req = AddDeviceRequest {
+                device_type: device_type.clone(),
+                device_id: Some(device_id.clone()),
+                name: "Lamp".to_string(),
+                adapter_type: "mqtt".to_string(),
+                connection_config: serde_json::json!({}),
+                tags: vec![],
+                location: None,
+            };
+            let tenant_scope = RequestTenant(TenantScope::Tenant(tenant.clone()));
+            let _ = add_device_handler(State(state.clone()), tenant_scope, Json(req))
+                .await
+                .unwrap();
+            devices.push(device_id);
+        }
+
+        let app = neomind_api::server::create_router_with_state(state);
+        let call = |method: &str, uri: &str, token: &str, tenant: Option<&str>| {
+            let mut builder = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token));
+            if let Some(tenant) = tenant {
+                builder = builder.header("x-neomind-tenant", tenant);
+            }
+            let request = builder.body(Body::empty()).unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (status, String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+
+        let mut sessions = Vec::new();
+        for token in &tokens {
+            let (status, body) = call("POST", "/api/sessions", token, None).await;
+            assert!(status.is_success(), "{}", body);
+            let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+            sessions.push(value["data"]["sessionId"].as_str().unwrap().to_string());
+        }
+
+        for (own, other) in [(0, 1), (1, 0)] {
+            let (status, body) = call("GET", "/api/devices", &tokens[own], None).await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(body.contains(&devices[own]));
+            assert!(!body.contains(&devices[other]));
+
+            let (status, body) = call("GET", "/api/sessions", &tokens[own], None).await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(body.contains(&sessions[own]));
+            assert!(!body.contains(&sessions[other]));
+        }
+
+        // A bound user can't widen the scope with the tenant header
+        let (status, _) = call("GET", "/api/devices", &tokens[0], Some("globex")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = call("GET", "/api/devices", &tokens[0], Some("acme")).await;
+        assert_eq!(status, StatusCode::OK);
+    }
 }
@@ -0,0 +1,98 @@
+//! Tests for dashboards handlers.
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use neomind_api::auth::RequestTenant;
+use neomind_api::handlers::dashboards::*;
+use neomind_api::handlers::ServerState;
+use serde_json::json;
+
+async fn create_test_server_state() -> ServerState {
+    crate::common::create_test_server_state().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_core::tenant::{TenantId, TenantScope};
+    use neomind_storage::dashboards::{Dashboard as StoredDashboard, DashboardLayout};
+
+    fn tenant(name: &str) -> RequestTenant {
+        RequestTenant(TenantScope::Tenant(TenantId::new(name).unwrap()))
+    }
+
+    async fn listed_ids(state: &ServerState, scope: RequestTenant) -> Vec<String> {
+        let params: PaginationParams = serde_json::from_value(json!({})).unwrap();
+        list_dashboards_handler(State(state.clone()), scope, None, Query(params))
+            .await
+            .unwrap()
+            .0
+            .data
+            .unwrap()
+            .dashboards
+            .into_iter()
+            .map(|d| d.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_dashboards_are_scoped_to_tenant() {
+        let state = create_test_server_state().await;
+
+        let req: CreateDashboardRequest =
+            serde_json::from_value(json!({ "name": "Acme" })).unwrap();
+        let created =
+            create_dashboard_handler(State(state.clone()), tenant("acme"), None, Json(req))
+                .await
+                .unwrap();
+        let id = created.0.data.unwrap().id;
+
+        // Rows from before tenancy belong to the default tenant only
+        let legacy: StoredDashboard = serde_json::from_value(json!({
+            "id": "legacy",
+            "name": "Legacy",
+            "layout": serde_json::to_value(DashboardLayout::default_layout()).unwrap(),
+            "components": [],
+            "created_at": 0,
+            "updated_at": 0,
+        }))
+        .unwrap();
+        state.dashboard_store.save(&legacy).unwrap();
+
+        assert_eq!(listed_ids(&state, tenant("acme")).await, vec![id.clone()]);
+        assert!(listed_ids(&state, tenant("other")).await.is_empty());
+        let all = listed_ids(&state, RequestTenant::default()).await;
+        assert!(all.contains(&id) && all.contains(&"legacy".to_string()));
+
+        let err = get_dashboard_handler(
+            State(state.clone()),
+            tenant("other"),
+            None,
+            Path(id.clone()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::NOT_FOUND);
+        let err = delete_dashboard_handler(
+            State(state.clone()),
+            tenant("acme"),
+            None,
+            Path("legacy".into()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::NOT_FOUND);
+
+        // A copy belongs to the tenant that made it
+        let copy = duplicate_dashboard_handler(
+            State(state.clone()),
+            RequestTenant::default(),
+            None,
+            Path(id),
+        )
+        .await
+        .unwrap();
+        let copy_id = copy.0.data.unwrap().id;
+        assert!(!listed_ids(&state, tenant("acme")).await.contains(&copy_id));
+    }
+}
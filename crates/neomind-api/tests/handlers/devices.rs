@@ -1,8 +1,8 @@
 //! Tests for device management handlers.
 
 use neomind_api::handlers::devices::models::{
-    AddDeviceRequest, BatchCurrentValuesRequest, PaginationQuery, TimeRangeQuery,
-    UpdateDeviceRequest,
+    AddDeviceRequest, BatchCurrentValuesRequest, PaginationQuery, SendCommandRequest,
+    TimeRangeQuery, UpdateDeviceRequest,
 };
 use serde_json::json;
 use uuid::Uuid;
@@ -85,6 +85,8 @@ mod tests {
                 "address": "localhost:1883",
                 "topic": "test/topic",
             }),
+            tags: Vec::new(),
+            location: None,
         };
 
         assert_eq!(request.device_id, Some(device_id));
@@ -93,6 +95,23 @@ mod tests {
         assert_eq!(request.adapter_type, "mqtt");
     }
 
+    #[tokio::test]
+    async fn test_send_command_request_schedule() {
+        let request: SendCommandRequest = serde_json::from_value(json!({
+            "params": {"duration": 30},
+            "schedule_at": "2030-01-01T06:00:00Z",
+            "recurrence": "0 0 6 * * *",
+        }))
+        .unwrap();
+        assert_eq!(request.schedule_at.unwrap().timestamp(), 1_893_477_600);
+        assert_eq!(request.recurrence.as_deref(), Some("0 0 6 * * *"));
+
+        // Plain commands carry neither
+        let request: SendCommandRequest = serde_json::from_value(json!({})).unwrap();
+        assert!(request.schedule_at.is_none());
+        assert!(request.recurrence.is_none());
+    }
+
     #[tokio::test]
     async fn test_update_device_request() {
         let request = UpdateDeviceRequest {
@@ -101,6 +120,8 @@ mod tests {
             adapter_type: None,
             adapter_id: None,
             offline_timeout_secs: None,
+            tags: None,
+            location: None,
         };
 
         assert_eq!(request.name, Some("Updated Device".to_string()));
@@ -188,6 +209,8 @@ mod tests {
             connection_config: json!({
                 "address": "localhost:1883",
             }),
+            tags: Vec::new(),
+            location: None,
         };
 
         assert!(request.device_id.is_none());
@@ -204,6 +227,8 @@ mod tests {
             connection_config: None,
             adapter_id: None,
             offline_timeout_secs: None,
+            tags: None,
+            location: None,
         };
 
         assert!(request.name.is_none());
@@ -221,6 +246,8 @@ mod tests {
             connection_config: None,
             adapter_id: Some("adapter-123".to_string()),
             offline_timeout_secs: None,
+            tags: None,
+            location: None,
         };
 
         assert_eq!(request.name, Some("New Name".to_string()));
@@ -228,4 +255,412 @@ mod tests {
         assert!(request.connection_config.is_none());
         assert_eq!(request.adapter_id, Some("adapter-123".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_device_groups_are_scoped_to_tenant() {
+        use axum::extract::{Path, State};
+        use axum::http::StatusCode;
+        use axum::Json;
+        use neomind_api::auth::RequestTenant;
+        use neomind_api::handlers::devices::{
+            add_device_handler, create_device_group_handler, get_device_group_devices_handler,
+            list_device_groups_handler, send_device_group_command_handler, DeviceGroupRequest,
+            GroupCommandRequest,
+        };
+        use neomind_core::tenant::{TenantId, TenantScope};
+        use neomind_devices::DeviceTypeTemplate;
+
+        let state = crate::common::create_test_server_state().await;
+        let acme = || RequestTenant(TenantScope::Tenant(TenantId::new("acme").unwrap()));
+        let other = || RequestTenant(TenantScope::Tenant(TenantId::new("other").unwrap()));
+
+        let device_type = test_device_type();
+        state
+            .devices
+            .service
+            .registry()
+            .register_template(DeviceTypeTemplate::new(&device_type, "Lamp"))
+            .await
+            .unwrap();
+        let acme_lamp = test_device_id();
+        let other_lamp = test_device_id();
+        for (tenant, device_id) in [(acme(), &acme_lamp), (other(), &other_lamp)] {
+            let req = AddDeviceRequest {
+                device_type: device_type.clone(),
+                device_id: Some(device_id.clone()),
+                name: "Lamp".to_string(),
+                adapter_type: "mqtt".to_string(),
+                connection_config: json!({}),
+                tags: vec!["light".to_string()],
+                location: None,
+            };
+            let _ = add_device_handler(State(state.clone()), tenant, Json(req))
+                .await
+                .unwrap();
+        }
+
+        // Naming the other tenant's device or matching it by tag doesn't make it a member
+        let group_id = format!("lights-{}", Uuid::new_v4());
+        let req = DeviceGroupRequest {
+            id: Some(group_id.clone()),
+            name: "Lights".to_string(),
+            description: None,
+            members: vec![other_lamp.clone()],
+            selector: Some(serde_json::from_value(json!({ "tags": ["light"] })).unwrap()),
+        };
+        let _ = create_device_group_handler(State(state.clone()), acme(), Json(req))
+            .await
+            .unwrap();
+
+        let members = get_device_group_devices_handler(
+            State(state.clone()),
+            acme(),
+            Path(group_id.clone()),
+        )
+        .await
+        .unwrap()
+        .0
+        .data
+        .unwrap();
+        let ids: Vec<&str> = members["devices"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["device_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec![acme_lamp.as_str()]);
+
+        let listed = list_device_groups_handler(State(state.clone()), other())
+            .await
+            .unwrap()
+            .0
+            .data
+            .unwrap();
+        assert_eq!(listed["count"], 0);
+
+        let err = send_device_group_command_handler(
+            State(state.clone()),
+            other(),
+            Path((group_id.clone(), "turn_on".to_string())),
+            Json(GroupCommandRequest {
+                params: Default::default(),
+                ordering: None,
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+
+        let err = get_device_group_devices_handler(State(state), other(), Path(group_id))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_data_query_is_scoped_to_tenant() {
+        use axum::extract::State;
+        use axum::Json;
+        use neomind_api::auth::RequestTenant;
+        use neomind_api::handlers::data::{query_data_handler, DataQueryRequest};
+        use neomind_api::handlers::devices::add_device_handler;
+        use neomind_core::tenant::{TenantId, TenantScope};
+        use neomind_devices::telemetry::DataPoint;
+        use neomind_devices::{DeviceTypeTemplate, MetricValue};
+
+        let state = crate::common::create_test_server_state().await;
+        let acme = || RequestTenant(TenantScope::Tenant(TenantId::new("acme").unwrap()));
+        let other = || RequestTenant(TenantScope::Tenant(TenantId::new("other").unwrap()));
+
+        let device_type = test_device_type();
+        state
+            .devices
+            .service
+            .registry()
+            .register_template(DeviceTypeTemplate::new(&device_type, "Sensor"))
+            .await
+            .unwrap();
+        let acme_sensor = test_device_id();
+        let other_sensor = test_device_id();
+        let now = chrono::Utc::now().timestamp();
+        for (tenant, device_id) in [(acme(), &acme_sensor), (other(), &other_sensor)] {
+            let req = AddDeviceRequest {
+                device_type: device_type.clone(),
+                device_id: Some(device_id.clone()),
+                name: "Sensor".to_string(),
+                adapter_type: "mqtt".to_string(),
+                connection_config: json!({}),
+                tags: vec![],
+                location: None,
+            };
+            let _ = add_device_handler(State(state.clone()), tenant, Json(req))
+                .await
+                .unwrap();
+            state
+                .devices
+                .telemetry
+                .write(
+                    &format!("device:{}", device_id),
+                    "temp",
+                    DataPoint::new(now - 60, MetricValue::Float(21.5)),
+                )
+                .await
+                .unwrap();
+        }
+
+        let query = |text: String| Json(DataQueryRequest { query: text });
+        let sources = |result: &neomind_storage::QueryResult| -> Vec<String> {
+            result
+                .rows
+                .iter()
+                .map(|row| row[1].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        // The wildcard only expands to the caller's own devices
+        let result = query_data_handler(
+            State(state.clone()),
+            other(),
+            query("SELECT count(temp) FROM device:*".to_string()),
+        )
+        .await
+        .unwrap()
+        .0
+        .data
+        .unwrap();
+        let matched = sources(&result);
+        assert!(matched.contains(&format!("device:{}", other_sensor)));
+        assert!(!matched.contains(&format!("device:{}", acme_sensor)));
+
+        // Naming another tenant's device reads it as empty
+        let result = query_data_handler(
+            State(state),
+            other(),
+            query(format!("SELECT count(temp) FROM device:{}", acme_sensor)),
+        )
+        .await
+        .unwrap()
+        .0
+        .data
+        .unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0][2], json!(0));
+    }
+
+    #[tokio::test]
+    async fn test_energy_report_is_scoped_to_tenant() {
+        use axum::extract::{Query, State};
+        use axum::http::StatusCode;
+        use axum::Json;
+        use neomind_api::auth::RequestTenant;
+        use neomind_api::handlers::devices::{
+            add_device_handler, create_device_group_handler, DeviceGroupRequest,
+        };
+        use neomind_api::handlers::energy::{get_energy_report_handler, EnergyReportQuery};
+        use neomind_core::tenant::{TenantId, TenantScope};
+        use neomind_devices::DeviceTypeTemplate;
+
+        let state = crate::common::create_test_server_state().await;
+        let acme = || RequestTenant(TenantScope::Tenant(TenantId::new("acme").unwrap()));
+        let other = || RequestTenant(TenantScope::Tenant(TenantId::new("other").unwrap()));
+
+        let device_type = test_device_type();
+        state
+            .devices
+            .service
+            .registry()
+            .register_template(DeviceTypeTemplate::new(&device_type, "Meter"))
+            .await
+            .unwrap();
+        let acme_meter = test_device_id();
+        let req = AddDeviceRequest {
+            device_type,
+            device_id: Some(acme_meter.clone()),
+            name: "Meter".to_string(),
+            adapter_type: "mqtt".to_string(),
+            connection_config: json!({}),
+            tags: vec![],
+            location: None,
+        };
+        let _ = add_device_handler(State(state.clone()), acme(), Json(req))
+            .await
+            .unwrap();
+        let group_id = format!("meters-{}", Uuid::new_v4());
+        let req = DeviceGroupRequest {
+            id: Some(group_id.clone()),
+            name: "Meters".to_string(),
+            description: None,
+            members: vec![acme_meter.clone()],
+            selector: None,
+        };
+        let _ = create_device_group_handler(State(state.clone()), acme(), Json(req))
+            .await
+            .unwrap();
+
+        let query = |device_id: Option<String>, group_id: Option<String>| {
+            Query(EnergyReportQuery {
+                start: None,
+                end: None,
+                bucket: Default::default(),
+                device_id,
+                group_id,
+            })
+        };
+
+        // Another tenant can name neither the device nor its group
+        let err = get_energy_report_handler(
+            State(state.clone()),
+            other(),
+            query(Some(acme_meter), None),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+
+        let err = get_energy_report_handler(State(state), other(), query(None, Some(group_id)))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_report_templates_are_scoped_to_tenant() {
+        use axum::extract::{Path, Query, State};
+        use axum::http::StatusCode;
+        use axum::Json;
+        use neomind_api::auth::RequestTenant;
+        use neomind_api::handlers::devices::add_device_handler;
+        use neomind_api::handlers::reports::{
+            create_template_handler, delete_template_handler, get_template_handler,
+            list_reports_handler, list_templates_handler, run_template_handler,
+            update_template_handler, ListReportsQuery,
+        };
+        use neomind_core::tenant::{TenantId, TenantScope};
+        use neomind_devices::DeviceTypeTemplate;
+        use neomind_storage::{ReportRecord, ReportTemplate};
+
+        let state = crate::common::create_test_server_state().await;
+        let acme = || RequestTenant(TenantScope::Tenant(TenantId::new("acme").unwrap()));
+        let other = || RequestTenant(TenantScope::Tenant(TenantId::new("other").unwrap()));
+
+        let device_type = test_device_type();
+        state
+            .devices
+            .service
+            .registry()
+            .register_template(DeviceTypeTemplate::new(&device_type, "Boiler"))
+            .await
+            .unwrap();
+        let acme_boiler = test_device_id();
+        let req = AddDeviceRequest {
+            device_type,
+            device_id: Some(acme_boiler.clone()),
+            name: "Boiler".to_string(),
+            adapter_type: "mqtt".to_string(),
+            connection_config: json!({}),
+            tags: vec![],
+            location: None,
+        };
+        let _ = add_device_handler(State(state.clone()), acme(), Json(req))
+            .await
+            .unwrap();
+
+        let template = |device_id: &str| -> ReportTemplate {
+            serde_json::from_value(json!({
+                "name": "Daily",
+                "sections": [{ "title": "Boilers", "device_ids": [device_id] }],
+            }))
+            .unwrap()
+        };
+
+        // Templates may only name devices in the caller's scope
+        let err =
+            create_template_handler(State(state.clone()), other(), Json(template(&acme_boiler)))
+                .await
+                .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+
+        let created =
+            create_template_handler(State(state.clone()), acme(), Json(template(&acme_boiler)))
+                .await
+                .unwrap()
+                .0
+                .data
+                .unwrap();
+        assert_eq!(created.tenant_id.as_str(), "acme");
+        let id = created.id.clone();
+
+        // The owner sees it; another tenant can neither list nor name it
+        let listed = list_templates_handler(State(state.clone()), acme())
+            .await
+            .unwrap()
+            .0
+            .data
+            .unwrap();
+        assert_eq!(listed["count"], 1);
+        let listed = list_templates_handler(State(state.clone()), other())
+            .await
+            .unwrap()
+            .0
+            .data
+            .unwrap();
+        assert_eq!(listed["count"], 0);
+
+        let err = get_template_handler(State(state.clone()), other(), Path(id.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        let err = update_template_handler(
+            State(state.clone()),
+            other(),
+            Path(id.clone()),
+            Json(template(&acme_boiler)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        let err = run_template_handler(State(state.clone()), other(), Path(id.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        let err = delete_template_handler(State(state.clone()), other(), Path(id.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+
+        // Generated reports carry the template's tenant
+        let record = ReportRecord {
+            id: Uuid::new_v4().to_string(),
+            template_id: id.clone(),
+            template_name: "Daily".to_string(),
+            format: Default::default(),
+            range_start: 0,
+            range_end: 86_400,
+            generated_at: 86_400,
+            size_bytes: 0,
+            trigger: "manual".to_string(),
+            deliveries: vec![],
+            tenant_id: created.tenant_id.clone(),
+        };
+        state.reports.store().save_report(&record).unwrap();
+        let query = || Query(ListReportsQuery { template_id: None });
+        let listed = list_reports_handler(State(state.clone()), acme(), query())
+            .await
+            .unwrap()
+            .0
+            .data
+            .unwrap();
+        assert_eq!(listed["count"], 1);
+        let listed = list_reports_handler(State(state.clone()), other(), query())
+            .await
+            .unwrap()
+            .0
+            .data
+            .unwrap();
+        assert_eq!(listed["count"], 0);
+
+        let _ = delete_template_handler(State(state), acme(), Path(id))
+            .await
+            .unwrap();
+    }
 }
@@ -2,6 +2,7 @@
 
 pub mod auth_users;
 pub mod basic;
+pub mod dashboards;
 // llm_backends.rs deprecated - API changed significantly
 pub mod devices;
 pub mod extensions;
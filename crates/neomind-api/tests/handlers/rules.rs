@@ -2,6 +2,7 @@
 
 use axum::extract::{Path, Query, State};
 use axum::Json;
+use neomind_api::auth::RequestTenant;
 use neomind_api::handlers::rules::*;
 use neomind_api::handlers::ServerState;
 use serde_json::json;
@@ -17,7 +18,7 @@ mod tests {
     #[tokio::test]
     async fn test_list_rules_handler() {
         let state = create_test_server_state().await;
-        let result = list_rules_handler(State(state)).await;
+        let result = list_rules_handler(State(state), RequestTenant::default()).await;
         assert!(result.is_ok());
         let response = result.unwrap();
         let value = response.0.data.unwrap();
@@ -31,7 +32,12 @@ mod tests {
     #[tokio::test]
     async fn test_get_rule_handler_invalid_id() {
         let state = create_test_server_state().await;
-        let result = get_rule_handler(State(state), Path("invalid_id".to_string())).await;
+        let result = get_rule_handler(
+            State(state),
+            RequestTenant::default(),
+            Path("invalid_id".to_string()),
+        )
+        .await;
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.status, axum::http::StatusCode::BAD_REQUEST);
@@ -41,7 +47,12 @@ mod tests {
     async fn test_get_rule_handler_not_found() {
         let state = create_test_server_state().await;
         let fake_id = "00000000-0000-0000-0000-000000000000";
-        let result = get_rule_handler(State(state), Path(fake_id.to_string())).await;
+        let result = get_rule_handler(
+            State(state),
+            RequestTenant::default(),
+            Path(fake_id.to_string()),
+        )
+        .await;
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.status, axum::http::StatusCode::NOT_FOUND);
@@ -54,15 +65,25 @@ mod tests {
             "name": "Updated Name",
             "enabled": true
         });
-        let result =
-            update_rule_handler(State(state), Path("invalid_id".to_string()), Json(req)).await;
+        let result = update_rule_handler(
+            State(state),
+            RequestTenant::default(),
+            Path("invalid_id".to_string()),
+            Json(req),
+        )
+        .await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_delete_rule_handler_invalid_id() {
         let state = create_test_server_state().await;
-        let result = delete_rule_handler(State(state), Path("invalid_id".to_string())).await;
+        let result = delete_rule_handler(
+            State(state),
+            RequestTenant::default(),
+            Path("invalid_id".to_string()),
+        )
+        .await;
         assert!(result.is_err());
     }
 
@@ -70,8 +91,13 @@ mod tests {
     async fn test_set_rule_status_handler_invalid_id() {
         let state = create_test_server_state().await;
         let req = SetRuleStatusRequest { enabled: true };
-        let result =
-            set_rule_status_handler(State(state), Path("invalid_id".to_string()), Json(req)).await;
+        let result = set_rule_status_handler(
+            State(state),
+            RequestTenant::default(),
+            Path("invalid_id".to_string()),
+            Json(req),
+        )
+        .await;
         assert!(result.is_err());
     }
 
@@ -80,6 +106,7 @@ mod tests {
         let state = create_test_server_state().await;
         let result = test_rule_handler(
             State(state),
+            RequestTenant::default(),
             Path("invalid_id".to_string()),
             Query(std::collections::HashMap::new()),
             None,
@@ -91,7 +118,12 @@ mod tests {
     #[tokio::test]
     async fn test_get_rule_history_handler_invalid_id() {
         let state = create_test_server_state().await;
-        let result = get_rule_history_handler(State(state), Path("invalid_id".to_string())).await;
+        let result = get_rule_history_handler(
+            State(state),
+            RequestTenant::default(),
+            Path("invalid_id".to_string()),
+        )
+        .await;
         assert!(result.is_err());
     }
 
@@ -99,7 +131,7 @@ mod tests {
     async fn test_create_rule_handler_missing_name() {
         let state = create_test_server_state().await;
         let req = json!({ "invalid": "data" });
-        let result = create_rule_handler(State(state), Json(req)).await;
+        let result = create_rule_handler(State(state), RequestTenant::default(), Json(req)).await;
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.message.contains("Missing 'name' field"));
@@ -2,6 +2,7 @@
 
 use axum::extract::{Path, Query, State};
 use axum::Json;
+use neomind_api::auth::RequestTenant;
 use neomind_api::handlers::sessions::*;
 use neomind_api::handlers::ServerState;
 use neomind_api::models::ChatRequest;
@@ -17,7 +18,7 @@ mod tests {
     #[tokio::test]
     async fn test_create_session_handler() {
         let state = create_test_server_state().await;
-        let result = create_session_handler(State(state), None).await;
+        let result = create_session_handler(State(state), RequestTenant::default(), None).await;
         assert!(result.is_ok());
         let response = result.unwrap();
         let value = response.0.data.unwrap();
@@ -31,7 +32,12 @@ mod tests {
             page: 1,
             page_size: 20,
         };
-        let result = list_sessions_handler(State(state), Query(query)).await;
+        let result = list_sessions_handler(
+            State(state),
+            RequestTenant::default(),
+            Query(query),
+        )
+        .await;
         assert!(result.is_ok());
         let response = result.unwrap();
         let value = response.0.data.unwrap();
@@ -46,7 +52,12 @@ mod tests {
             page: 1,
             page_size: 10,
         };
-        let result = list_sessions_handler(State(state), Query(query)).await;
+        let result = list_sessions_handler(
+            State(state),
+            RequestTenant::default(),
+            Query(query),
+        )
+        .await;
         assert!(result.is_ok());
         let response = result.unwrap();
         assert!(response.0.data.is_some());
@@ -57,8 +68,12 @@ mod tests {
     #[tokio::test]
     async fn test_get_session_handler_not_found() {
         let state = create_test_server_state().await;
-        let result =
-            get_session_handler(State(state), Path("nonexistent_session".to_string())).await;
+        let result = get_session_handler(
+            State(state),
+            RequestTenant::default(),
+            Path("nonexistent_session".to_string()),
+        )
+        .await;
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.status, axum::http::StatusCode::NOT_FOUND);
@@ -67,9 +82,12 @@ mod tests {
     #[tokio::test]
     async fn test_get_session_history_handler_not_found() {
         let state = create_test_server_state().await;
-        let result =
-            get_session_history_handler(State(state), Path("nonexistent_session".to_string()))
-                .await;
+        let result = get_session_history_handler(
+            State(state),
+            RequestTenant::default(),
+            Path("nonexistent_session".to_string()),
+        )
+        .await;
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert_eq!(err.status, axum::http::StatusCode::NOT_FOUND);
@@ -78,8 +96,12 @@ mod tests {
     #[tokio::test]
     async fn test_delete_session_handler_not_found() {
         let state = create_test_server_state().await;
-        let result =
-            delete_session_handler(State(state), Path("nonexistent_session".to_string())).await;
+        let result = delete_session_handler(
+            State(state),
+            RequestTenant::default(),
+            Path("nonexistent_session".to_string()),
+        )
+        .await;
         assert!(result.is_err());
         let err = result.unwrap_err();
         // Handler returns NOT_FOUND for sessions that don't exist
@@ -94,6 +116,7 @@ mod tests {
         };
         let result = update_session_handler(
             State(state),
+            RequestTenant::default(),
             Path("nonexistent_session".to_string()),
             Json(req),
         )
@@ -126,7 +149,9 @@ mod tests {
         };
         let result = chat_handler(
             State(state),
+            RequestTenant::default(),
             Path("nonexistent_session".to_string()),
+            None,
             Json(req),
         )
         .await;
@@ -136,13 +161,162 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_chat_handler_creates_session() {
+    async fn test_sessions_are_scoped_to_tenant() {
+        use neomind_core::tenant::{TenantId, TenantScope};
+
         let state = create_test_server_state().await;
+        let acme = RequestTenant(TenantScope::Tenant(TenantId::new("acme").unwrap()));
+        let other = RequestTenant(TenantScope::Tenant(TenantId::new("other").unwrap()));
 
-        // First create a session
-        let create_result = create_session_handler(State(state.clone()), None)
+        let created = create_session_handler(State(state.clone()), acme.clone(), None)
+            .await
+            .unwrap();
+        let session_id = created.0.data.unwrap()["sessionId"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        assert!(get_session_handler(State(state.clone()), acme, Path(session_id.clone()))
+            .await
+            .is_ok());
+        let err = get_session_handler(State(state.clone()), other, Path(session_id.clone()))
             .await
+            .unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::NOT_FOUND);
+        assert!(
+            get_session_handler(State(state), RequestTenant::default(), Path(session_id))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audio_chat_rejects_other_tenants_session() {
+        use axum::extract::{FromRequest, Multipart};
+        use neomind_api::handlers::audio::audio_chat_handler;
+        use neomind_core::tenant::{TenantId, TenantScope};
+
+        let state = create_test_server_state().await;
+        let acme = RequestTenant(TenantScope::Tenant(TenantId::new("acme").unwrap()));
+        let other = RequestTenant(TenantScope::Tenant(TenantId::new("other").unwrap()));
+
+        let created = create_session_handler(State(state.clone()), acme, None)
+            .await
+            .unwrap();
+        let session_id = created.0.data.unwrap()["sessionId"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let body = format!(
+            "--X\r\nContent-Disposition: form-data; name=\"session_id\"\r\n\r\n{}\r\n\
+             --X\r\nContent-Disposition: form-data; name=\"audio\"; filename=\"rec.wav\"\r\n\
+             Content-Type: audio/wav\r\n\r\nRIFF\r\n--X--\r\n",
+            session_id
+        );
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/api/chat/audio")
+            .header("content-type", "multipart/form-data; boundary=X")
+            .body(axum::body::Body::from(body))
             .unwrap();
+        let multipart = Multipart::from_request(request, &()).await.unwrap();
+
+        let err = audio_chat_handler(State(state), other, None, multipart)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_approvals_are_scoped_to_session_tenant() {
+        use neomind_agent::agent::{pending_actions, ApprovalStatus};
+        use neomind_api::handlers::approvals::{
+            get_approval_handler, list_approvals_handler, reject_handler,
+        };
+        use neomind_core::tenant::{TenantId, TenantScope};
+
+        let state = create_test_server_state().await;
+        let acme = || RequestTenant(TenantScope::Tenant(TenantId::new("acme").unwrap()));
+        let other = || RequestTenant(TenantScope::Tenant(TenantId::new("other").unwrap()));
+
+        let created = create_session_handler(State(state.clone()), acme(), None)
+            .await
+            .unwrap();
+        let session_id = created.0.data.unwrap()["sessionId"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let held = pending_actions().create(
+            &session_id,
+            "device",
+            "device.control",
+            Vec::new(),
+            serde_json::json!({"action": "control"}),
+            60,
+        );
+
+        let listed = |scope: RequestTenant| {
+            let state = state.clone();
+            let id = held.id.clone();
+            async move {
+                let list = list_approvals_handler(State(state), scope)
+                    .await
+                    .unwrap()
+                    .0
+                    .data
+                    .unwrap();
+                list["actions"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|a| a["id"] == id.as_str())
+            }
+        };
+        assert!(listed(acme()).await);
+        assert!(!listed(other()).await);
+
+        // Another tenant can neither read nor decide the held call
+        let err = get_approval_handler(State(state.clone()), other(), Path(held.id.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::NOT_FOUND);
+        let err = reject_handler(
+            State(state.clone()),
+            other(),
+            None,
+            Path(held.id.clone()),
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status, axum::http::StatusCode::NOT_FOUND);
+        assert_eq!(
+            pending_actions().get(&held.id).unwrap().status,
+            ApprovalStatus::Pending
+        );
+
+        let rejected = reject_handler(State(state), acme(), None, Path(held.id.clone()), None)
+            .await
+            .unwrap()
+            .0
+            .data
+            .unwrap();
+        assert_eq!(rejected.status, ApprovalStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_chat_handler_creates_session() {
+        let state = create_test_server_state().await;
+
+        // First create a session
+        let create_result = create_session_handler(
+            State(state.clone()),
+            RequestTenant::default(),
+            None,
+        )
+        .await
+        .unwrap();
         let session_id = create_result
             .0
             .data
@@ -163,7 +337,14 @@ mod tests {
             page_context: None,
             session_config: None,
         };
-        let result = chat_handler(State(state), Path(session_id.clone()), Json(req)).await;
+        let result = chat_handler(
+            State(state),
+            RequestTenant::default(),
+            Path(session_id.clone()),
+            None,
+            Json(req),
+        )
+        .await;
         // Either Ok with timeout message or Err with something other than NOT_FOUND
         match result {
             Ok(resp) => {
@@ -235,4 +416,37 @@ mod tests {
         assert_eq!(query.page, 1);
         assert_eq!(query.page_size, 20);
     }
+
+    #[tokio::test]
+    async fn test_init_tools_applies_configured_execution_policy() {
+        use neomind_core::config::service::{global, keys};
+        use std::collections::BTreeMap;
+        use std::time::Duration;
+
+        let limits = BTreeMap::from([
+            (keys::AGENT_TOOL_TIMEOUT_SECS.to_string(), 45.into()),
+            (keys::AGENT_TOOL_MAX_CONCURRENT.to_string(), 2.into()),
+            (keys::AGENT_TOOL_MAX_OUTPUT_BYTES.to_string(), 4096.into()),
+        ]);
+        let reset = limits
+            .keys()
+            .map(|key| (key.clone(), serde_json::Value::Null))
+            .collect();
+        global().update(limits, "test").unwrap();
+
+        let state = create_test_server_state().await;
+        state.init_tools().await;
+        global().update(reset, "test").unwrap();
+
+        let registry = state
+            .agents
+            .session_manager
+            .get_tool_registry()
+            .await
+            .expect("init_tools installs the shared registry");
+        let policy = registry.execution_policy();
+        assert_eq!(policy.timeout_for("shell"), Some(Duration::from_secs(45)));
+        assert_eq!(policy.max_concurrent, Some(2));
+        assert_eq!(policy.max_output_bytes, Some(4096));
+    }
 }
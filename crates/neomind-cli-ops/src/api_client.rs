@@ -1,5 +1,7 @@
 use anyhow::Result;
+use neomind_core::tenant::{TenantId, TENANT_ENV, TENANT_HEADER};
 use reqwest::Client;
+use std::future::Future;
 use std::sync::RwLock;
 use std::time::Duration;
 
@@ -7,6 +9,31 @@ const DEFAULT_BASE_URL: &str = "http://localhost:9375/api";
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 const MAX_RETRIES: usize = 1;
 
+/// Tenant to act for: the agent's tenant scope when dispatched in-process,
+/// otherwise `NEOMIND_TENANT`.
+fn current_tenant() -> Option<String> {
+    TenantId::current()
+        .map(String::from)
+        .or_else(|| std::env::var(TENANT_ENV).ok().filter(|t| !t.is_empty()))
+}
+
+tokio::task_local! {
+    static SCOPED_API_KEY: String;
+}
+
+/// Run `f` with every `ApiClient` created inside it authenticating as `key`.
+///
+/// The agent dispatches `neomind` commands in-process with a key bound to the
+/// session's tenant. A task-local keeps concurrent sessions apart, where a
+/// process-wide `NEOMIND_API_KEY` would leak one session's key to another.
+pub async fn with_api_key<F: Future>(key: String, f: F) -> F::Output {
+    SCOPED_API_KEY.scope(key, f).await
+}
+
+fn scoped_api_key() -> Option<String> {
+    SCOPED_API_KEY.try_with(String::clone).ok()
+}
+
 pub struct ApiClient {
     base_url: String,
     client: Client,
@@ -27,8 +54,8 @@ impl ApiClient {
     }
 
     pub fn with_base_url(base_url: &str) -> Self {
-        let api_key = std::env::var("NEOMIND_API_KEY")
-            .ok()
+        let api_key = scoped_api_key()
+            .or_else(|| std::env::var("NEOMIND_API_KEY").ok())
             .or_else(crate::auto_auth::read_default_api_key);
         let client = Client::builder()
             .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
@@ -47,10 +74,14 @@ impl ApiClient {
 
     fn add_auth(&self, req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         let key = self.api_key.read().unwrap().clone();
-        if let Some(key) = key {
+        let req = if let Some(key) = key {
             req.header("Authorization", format!("Bearer {}", key))
         } else {
             req
+        };
+        match current_tenant() {
+            Some(tenant) => req.header(TENANT_HEADER, tenant),
+            None => req,
         }
     }
 
@@ -60,7 +91,13 @@ impl ApiClient {
     /// in the initial load and just failed. Go straight to redb for a fresh
     /// key. This prevents stale-key lockout when the credential file key has
     /// been revoked or the server was re-initialized.
+    ///
+    /// A key set with [`with_api_key`] is never swapped: it is the only
+    /// credential that caller may use.
     fn refresh_api_key(&self) {
+        if scoped_api_key().is_some() {
+            return;
+        }
         let new_key = crate::auto_auth::read_default_api_key_from(
             &crate::auto_auth::resolve_data_dir(),
         );
@@ -358,6 +395,16 @@ mod tests {
         assert_eq!(DEFAULT_BASE_URL, "http://localhost:9375/api");
     }
 
+    #[tokio::test]
+    async fn test_scoped_api_key_is_task_local() {
+        let client = with_api_key("nmk_scoped".to_string(), async {
+            ApiClient::with_base_url(DEFAULT_BASE_URL)
+        })
+        .await;
+        assert_eq!(client.api_key.read().unwrap().as_deref(), Some("nmk_scoped"));
+        assert!(scoped_api_key().is_none());
+    }
+
     #[test]
     fn test_api_client_timeout_const() {
         assert_eq!(DEFAULT_TIMEOUT_SECS, 30);
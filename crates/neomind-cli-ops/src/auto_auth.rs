@@ -34,6 +34,24 @@ struct ApiKeyInfo {
     active: bool,
 }
 
+/// Tenant a key is bound to. Only keys written with the full layout (rate
+/// limits and tenant) can be bound; older layouts fail to decode here.
+fn key_tenant(info_bytes: &[u8]) -> Option<String> {
+    type FullKeyInfo = (
+        String,
+        String,
+        i64,
+        Vec<String>,
+        bool,
+        Option<u32>,
+        Option<u64>,
+        Option<String>,
+    );
+    bincode::deserialize::<FullKeyInfo>(info_bytes)
+        .ok()
+        .and_then(|info| info.7)
+}
+
 /// Resolve the CLI config directory for storing credentials.
 ///
 /// Priority:
@@ -229,6 +247,10 @@ pub fn read_default_api_key_from(data_dir: &str) -> Option<String> {
         // Check metadata for active + wildcard permissions
         if let Some(ref ht) = hash_table {
             if let Ok(Some(info_bytes)) = ht.get(hash_key.value()) {
+                // Keys bound to a tenant can't see the whole instance
+                if key_tenant(info_bytes.value()).is_some() {
+                    continue;
+                }
                 if let Ok(info) = bincode::deserialize::<ApiKeyInfo>(info_bytes.value()) {
                     if info.active && info.permissions.contains(&"*".to_string()) {
                         debug!("Auto-auth: using API key '{}' ({})", info.name, info.id);
@@ -275,6 +297,33 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_key_tenant() {
+        let base = (
+            "id".to_string(),
+            "name".to_string(),
+            0i64,
+            vec!["*".to_string()],
+            true,
+        );
+        let legacy = bincode::serialize(&base).unwrap();
+        assert_eq!(key_tenant(&legacy), None);
+
+        let (id, name, created_at, permissions, active) = base;
+        let bound = bincode::serialize(&(
+            id,
+            name,
+            created_at,
+            permissions,
+            active,
+            None::<u32>,
+            None::<u64>,
+            Some("acme".to_string()),
+        ))
+        .unwrap();
+        assert_eq!(key_tenant(&bound), Some("acme".to_string()));
+    }
+
     #[test]
     fn test_resolve_data_dir_env_override() {
         // NEOMIND_DATA_DIR takes priority over platform default and legacy "data".
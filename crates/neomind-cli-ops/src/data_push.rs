@@ -17,12 +17,19 @@ fn parse_source_patterns(s: &str) -> Vec<String> {
         .collect()
 }
 
-/// Lift mistakenly-nested top-level fields (data_filter, schedule, template)
-/// out of `--config` into the request body. Prevents the common error where
-/// `--config '{"...","data_filter":{...}}'` silently no-ops because those are
-/// PushTarget top-level fields, not webhook/mqtt config.
+/// Lift mistakenly-nested top-level fields (data_filter, schedule, template,
+/// batch_config, retry_config) out of `--config` into the request body.
+/// Prevents the common error where `--config '{"...","data_filter":{...}}'`
+/// silently no-ops because those are PushTarget top-level fields, not
+/// target-specific config.
 fn lift_top_level_fields(config: &mut serde_json::Value, body: &mut serde_json::Value) {
-    const TOP_LEVEL: &[&str] = &["data_filter", "schedule", "template"];
+    const TOP_LEVEL: &[&str] = &[
+        "data_filter",
+        "schedule",
+        "template",
+        "batch_config",
+        "retry_config",
+    ];
     if let Some(obj) = config.as_object_mut() {
         for key in TOP_LEVEL {
             if let Some(val) = obj.remove(*key) {
@@ -99,7 +106,7 @@ pub async fn create_target(
         return Ok(CliResponse::error_with_suggestion(
             "Target type is required. Use --type <TYPE>.",
             "MISSING_TYPE",
-            "Valid types: webhook, mqtt.",
+            "Valid types: webhook, mqtt, influxdb, telegraf.",
         ));
     }
 
@@ -113,6 +120,8 @@ pub async fn create_target(
                 match target_type {
                     "webhook" => "Example: --config '{\"url\":\"https://example.com/webhook\"}'",
                     "mqtt" => "Example: --config '{\"broker\":\"tcp://broker:1883\",\"topic\":\"neomind/data\"}'",
+                    "influxdb" => "Example: --config '{\"url\":\"http://influx:8086\",\"bucket\":\"neomind\",\"org\":\"my-org\",\"token\":\"TOKEN\"}'",
+                    "telegraf" => "Example: --config '{\"address\":\"telegraf:8094\"}'",
                     _ => "Provide a valid JSON object for --config.",
                 },
             ));
@@ -121,12 +130,12 @@ pub async fn create_target(
 
     // 4. Validate target_type is known
     match target_type {
-        "webhook" | "mqtt" => {}
+        "webhook" | "mqtt" | "influxdb" | "telegraf" => {}
         _ => {
             return Ok(CliResponse::error_with_suggestion(
                 format!("Unknown target type '{}'.", target_type),
                 "UNKNOWN_TYPE",
-                "Valid types: webhook, mqtt.",
+                "Valid types: webhook, mqtt, influxdb, telegraf.",
             ));
         }
     }
@@ -1,6 +1,6 @@
 use crate::types::{BuildMeta, CliResponse};
 use crate::ApiClient;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde_json::json;
 use std::collections::BTreeMap;
 
@@ -294,7 +294,7 @@ fn build_example(current_data: &serde_json::Value) -> (serde_json::Value, serde_
     let mut field_names: Vec<serde_json::Value> = Vec::new();
     if let Some(metrics_obj) = metrics.and_then(|m| m.as_object()) {
         for (name, info) in metrics_obj {
-            if info.get("value").map_or(false, |v| !v.is_null()) {
+            if info.get("value").is_some_and(|v| !v.is_null()) {
                 field_names.push(json!(name));
             }
         }
@@ -641,6 +641,20 @@ fn format_ts(ts_ms: i64) -> String {
         .unwrap_or_else(|| ts_ms.to_string())
 }
 
+/// Parse a point in time given as Unix seconds, RFC 3339, or a relative age
+/// ("2h" = two hours before `now_ts`).
+fn parse_point_in_time(s: &str, now_ts: i64) -> Result<i64> {
+    let s = s.trim();
+    if let Ok(ts) = s.parse::<i64>() {
+        return Ok(ts);
+    }
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Ok(t.timestamp());
+    }
+    parse_time_range_to_timestamp(s, now_ts)
+        .ok_or_else(|| anyhow!("Invalid time '{}' (Unix seconds, RFC 3339 or e.g. 2h)", s))
+}
+
 /// Parse a human-readable time range string (e.g., "1h", "24h", "7d", "30d") to a start timestamp.
 fn parse_time_range_to_timestamp(range: &str, now_ts: i64) -> Option<i64> {
     let range = range.trim();
@@ -681,6 +695,168 @@ pub async fn control_device(
     Ok(CliResponse::success(data, "Command sent"))
 }
 
+/// List device groups
+pub async fn list_device_groups(client: &ApiClient) -> Result<CliResponse> {
+    let data = client.get("/device-groups").await?;
+    Ok(CliResponse::success(data, "Device groups listed"))
+}
+
+/// Send a control command to every device in a group
+pub async fn control_device_group(
+    client: &ApiClient,
+    group: &str,
+    command: &str,
+    params: serde_json::Value,
+    sequential: bool,
+) -> Result<CliResponse> {
+    let ordering = if sequential { "sequential" } else { "parallel" };
+    let body = json!({ "params": params, "ordering": ordering });
+    let data = client
+        .post(
+            &format!("/device-groups/{}/command/{}", group, command),
+            &body,
+        )
+        .await?;
+    let status = data
+        .get("status")
+        .and_then(|s| s.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+    Ok(CliResponse::success(
+        data,
+        format!("Group command finished: {}", status),
+    ))
+}
+
+/// List the members a virtual device aggregates over
+pub async fn get_virtual_members(client: &ApiClient, id: &str) -> Result<CliResponse> {
+    let data = client.get(&format!("/devices/{}/members", id)).await?;
+    let count = data.get("count").and_then(|c| c.as_u64()).unwrap_or(0);
+    Ok(CliResponse::success(data, format!("{} members", count)))
+}
+
+/// List recent telemetry anomalies, optionally for one device and metric
+pub async fn get_device_anomalies(
+    client: &ApiClient,
+    device_id: Option<&str>,
+    metric: Option<&str>,
+    limit: usize,
+) -> Result<CliResponse> {
+    let mut path = format!("/devices/anomalies?limit={}", limit);
+    if let Some(device_id) = device_id {
+        path.push_str(&format!("&device_id={}", device_id));
+    }
+    if let Some(metric) = metric {
+        path.push_str(&format!("&metric={}", metric));
+    }
+    let data = client.get(&path).await?;
+    let count = data.get("count").and_then(|c| c.as_u64()).unwrap_or(0);
+    Ok(CliResponse::success(
+        data,
+        format!("{} anomalies found", count),
+    ))
+}
+
+/// Device state as of a past moment
+pub async fn get_device_state_at(
+    client: &ApiClient,
+    id: &str,
+    at: Option<&str>,
+) -> Result<CliResponse> {
+    let mut path = format!("/devices/{}/state", id);
+    if let Some(at) = at {
+        let ts = parse_point_in_time(at, chrono::Utc::now().timestamp())?;
+        path.push_str(&format!("?at={}", ts));
+    }
+    let data = client.get(&path).await?;
+    let metrics = data
+        .get("metrics")
+        .and_then(|m| m.as_object())
+        .map_or(0, |m| m.len());
+    let online = match data.get("online").and_then(|v| v.as_bool()) {
+        Some(true) => "online",
+        Some(false) => "offline",
+        None => "status unknown",
+    };
+    Ok(CliResponse::success(data, format!("{} metrics, {}", metrics, online)))
+}
+
+/// Fleet availability summary
+pub async fn get_health_summary(client: &ApiClient) -> Result<CliResponse> {
+    let data = client.get("/devices/health/summary").await?;
+    let fleet = data.get("fleet").cloned().unwrap_or_default();
+    let online = fleet.get("online").and_then(|v| v.as_u64()).unwrap_or(0);
+    let total = fleet.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+    let availability = fleet
+        .get("availability")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+    Ok(CliResponse::success(
+        data,
+        format!("{}/{} devices online ({:.1}%)", online, total, availability),
+    ))
+}
+
+/// Energy consumption and cost report
+pub async fn get_energy_report(
+    client: &ApiClient,
+    device_id: Option<&str>,
+    group_id: Option<&str>,
+    time_range: Option<&str>,
+    bucket: Option<&str>,
+) -> Result<CliResponse> {
+    let end = chrono::Utc::now().timestamp();
+    let start = time_range
+        .and_then(|tr| parse_time_range_to_timestamp(tr, end))
+        .unwrap_or(end - 86400);
+    let mut path = format!("/energy?start={}&end={}", start, end);
+    if let Some(device_id) = device_id {
+        path.push_str(&format!("&device_id={}", device_id));
+    }
+    if let Some(group_id) = group_id {
+        path.push_str(&format!("&group_id={}", group_id));
+    }
+    if let Some(bucket) = bucket {
+        path.push_str(&format!("&bucket={}", bucket));
+    }
+    let data = client.get(&path).await?;
+    let kwh = data
+        .get("total_kwh")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+    let cost = data
+        .get("total_cost")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+    let currency = data.get("currency").and_then(|v| v.as_str()).unwrap_or("");
+    let message = format!("{:.2} kWh, {:.2} {}", kwh, cost, currency);
+    Ok(CliResponse::success(data, message))
+}
+
+/// Run a SQL-like telemetry query
+pub async fn query_telemetry(client: &ApiClient, query: &str) -> Result<CliResponse> {
+    let data = crate::api_client::extract_inner_data(
+        client
+            .post("/data/query", &json!({ "query": query }))
+            .await?,
+    );
+    let rows = data
+        .get("rows")
+        .and_then(|v| v.as_array())
+        .map(|a| a.len())
+        .unwrap_or(0);
+    let truncated = data
+        .get("truncated")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let message = if truncated {
+        format!("{} rows (truncated, raise LIMIT or narrow the query)", rows)
+    } else {
+        format!("{} rows", rows)
+    };
+    Ok(CliResponse::success(data, message))
+}
+
 /// List device types
 pub async fn list_device_types(client: &ApiClient) -> Result<CliResponse> {
     let data = client.get("/device-types").await?;
@@ -1222,4 +1398,14 @@ mod tests {
         let s = format_ts(ts);
         assert!(s.starts_with("2026-07-08"), "expected 2026-07-08 in {}", s);
     }
+
+    /// state-at accepts Unix seconds, RFC 3339 and relative ages.
+    #[test]
+    fn test_parse_point_in_time() {
+        let now = 1_000_000;
+        assert_eq!(parse_point_in_time("999000", now).unwrap(), 999_000);
+        assert_eq!(parse_point_in_time("1970-01-02T00:00:00Z", now).unwrap(), 86_400);
+        assert_eq!(parse_point_in_time("2h", now).unwrap(), now - 7200);
+        assert!(parse_point_in_time("yesterday", now).is_err());
+    }
 }
@@ -66,11 +66,12 @@ pub async fn dispatch(argv: &[String]) -> Result<CliResponse, DispatchError> {
         | Command::Logs { .. }
         | Command::CheckUpdate
         | Command::Upgrade { .. }
-        | Command::Uninstall { .. } => Err(DispatchError::NotInProcess),
+        | Command::Uninstall { .. }
+        | Command::Simulate(_) => Err(DispatchError::NotInProcess),
 
         // --- Local-only commands (need redb/auth from neomind-api, or print
         //     directly to stdout and rely on subprocess capture) ---
-        Command::ApiKey { .. } => Err(DispatchError::NotInProcess),
+        Command::ApiKey { .. } | Command::Db { .. } => Err(DispatchError::NotInProcess),
         Command::Extension { extension_cmd } => {
             if handlers::is_local_extension_command(&extension_cmd) {
                 Err(DispatchError::NotInProcess)
@@ -155,6 +156,12 @@ pub async fn dispatch(argv: &[String]) -> Result<CliResponse, DispatchError> {
                 .map_err(|e| DispatchError::Api(e.to_string()))?;
             Ok(resp)
         }
+        Command::Knowledge { knowledge_cmd } => {
+            let (resp, _) = handlers::run_knowledge_cmd(knowledge_cmd)
+                .await
+                .map_err(|e| DispatchError::Api(e.to_string()))?;
+            Ok(resp)
+        }
         Command::Login { data_dir, force } => {
             let (resp, _) = handlers::run_login_cmd(data_dir, force)
                 .await
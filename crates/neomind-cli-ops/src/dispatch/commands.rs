@@ -22,6 +22,82 @@ pub struct Args {
     pub verbose: bool,
 }
 
+/// Options of `neomind simulate`.
+#[derive(clap::Args, Debug, Clone)]
+pub struct SimulateArgs {
+    /// Load to generate: telemetry (default) | chat | rules.
+    #[arg(long, default_value = "telemetry")]
+    pub mode: String,
+    /// Number of simulated devices, or of chat sessions / rule-evaluation
+    /// workers with `--mode chat` / `--mode rules`.
+    #[arg(short = 'n', long, default_value_t = 10)]
+    pub count: usize,
+    /// Comma-separated device types, assigned to devices round-robin.
+    #[arg(long, value_delimiter = ',', default_value = "env_sensor")]
+    pub types: Vec<String>,
+    /// Messages per second per device (or per session / worker).
+    #[arg(long, default_value_t = 1.0)]
+    pub rate: f64,
+    /// Stop after this many seconds (default: run until Ctrl-C).
+    #[arg(long)]
+    pub duration: Option<u64>,
+    /// Device ID prefix; devices are named `<prefix>-<type>-<n>`.
+    #[arg(long, default_value = "sim")]
+    pub prefix: String,
+    /// Transport: mqtt (default) | http.
+    #[arg(long, default_value = "mqtt")]
+    pub transport: String,
+    /// MQTT broker host.
+    #[arg(long, default_value = "localhost")]
+    pub mqtt_host: String,
+    /// MQTT broker port.
+    #[arg(long, default_value_t = 1883)]
+    pub mqtt_port: u16,
+    /// MQTT username.
+    #[arg(long)]
+    pub mqtt_username: Option<String>,
+    /// MQTT password.
+    #[arg(long)]
+    pub mqtt_password: Option<String>,
+    /// Webhook token for the http transport, sent as a Bearer token.
+    #[arg(long)]
+    pub token: Option<String>,
+    /// Create the device types and devices through the API before starting.
+    #[arg(long)]
+    pub register: bool,
+    /// Probability that a message is silently dropped.
+    #[arg(long, default_value_t = 0.0)]
+    pub drop_rate: f64,
+    /// Probability that a message is sent as malformed JSON.
+    #[arg(long, default_value_t = 0.0)]
+    pub malformed_rate: f64,
+    /// Probability that a message carries an out-of-range spike value.
+    #[arg(long, default_value_t = 0.0)]
+    pub spike_rate: f64,
+    /// Probability, per message, that the device goes offline.
+    #[arg(long, default_value_t = 0.0)]
+    pub offline_rate: f64,
+    /// How long an offline device stays silent (seconds).
+    #[arg(long, default_value_t = 60)]
+    pub offline_secs: u64,
+    /// Random seed, for reproducible runs.
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Write every message sent to this file (JSON lines), for `--replay`.
+    #[arg(long, conflicts_with = "replay")]
+    pub record: Option<std::path::PathBuf>,
+    /// Send the messages of a `--record` file, with their original payloads
+    /// and spacing, instead of generating new ones.
+    #[arg(long)]
+    pub replay: Option<std::path::PathBuf>,
+    /// Message each chat session sends (`--mode chat`).
+    #[arg(long, default_value = "What are the latest sensor readings?")]
+    pub message: String,
+    /// Comma-separated rule IDs to evaluate (`--mode rules`; default: every rule).
+    #[arg(long, value_delimiter = ',')]
+    pub rules: Vec<String>,
+}
+
 /// Available commands.
 #[derive(Subcommand, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -124,6 +200,18 @@ pub enum Command {
         #[arg(long)]
         yes: bool,
     },
+    /// Simulate devices publishing telemetry to a running instance.
+    ///
+    /// Each device sends a JSON uplink at `--rate` messages per second over
+    /// MQTT (`device/{type}/{id}/uplink`) or the device webhook. Fault flags
+    /// drop messages, send malformed JSON, inject value spikes and take
+    /// devices offline for a while. Runs until Ctrl-C or `--duration`.
+    ///
+    /// Device types: temperature_sensor, env_sensor, power_meter, switch.
+    ///
+    /// Example: `neomind simulate --count 20 --types env_sensor,power_meter --register`
+    /// Example: `neomind simulate --transport http --drop-rate 0.1 --offline-rate 0.01`
+    Simulate(SimulateArgs),
     /// LLM backend management commands.
     Llm {
         #[command(subcommand)]
@@ -139,6 +227,16 @@ pub enum Command {
         #[command(subcommand)]
         key_cmd: ApiKeyCommand,
     },
+    /// Offline storage administration on the data directory.
+    ///
+    /// Opens the `.redb` stores directly, so stop the server first.
+    ///
+    /// Example: `neomind db inspect`
+    /// Example: `neomind db export devices --output backup/`
+    Db {
+        #[command(subcommand)]
+        db_cmd: DbCommand,
+    },
     /// Device management commands.
     Device {
         #[command(subcommand)]
@@ -172,7 +270,7 @@ pub enum Command {
     /// Data push management commands.
     ///
     /// Forward device metrics and extension outputs to external systems.
-    /// Target types: webhook, mqtt. Schedule types: event (real-time), interval.
+    /// Target types: webhook, mqtt, influxdb, telegraf. Schedule types: event (real-time), interval.
     Push {
         #[command(subcommand)]
         push_cmd: PushCommand,
@@ -192,6 +290,11 @@ pub enum Command {
         #[command(subcommand)]
         connector_cmd: ConnectorCommand,
     },
+    /// Knowledge base of uploaded documents (device manuals, procedures).
+    Knowledge {
+        #[command(subcommand)]
+        knowledge_cmd: KnowledgeCommand,
+    },
     /// System settings (timezone, data retention).
     Settings {
         #[command(subcommand)]
@@ -261,6 +364,73 @@ pub enum ApiKeyCommand {
     },
 }
 
+/// Offline storage administration subcommands.
+#[derive(Subcommand, Debug)]
+pub enum DbCommand {
+    /// Show each store's file size, tables and key counts.
+    ///
+    /// Example: `neomind db inspect`
+    /// Example: `neomind db inspect telemetry --data-dir /var/lib/neomind/data`
+    Inspect {
+        /// Store name, e.g. `devices` for devices.redb (default: all stores).
+        store: Option<String>,
+        /// Data directory path.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+    },
+    /// Compact stores to return free pages to the filesystem.
+    ///
+    /// Example: `neomind db compact telemetry`
+    Compact {
+        /// Store name (default: all stores).
+        store: Option<String>,
+        /// Data directory path.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+    },
+    /// Export stores to JSON, one `<store>.json` file per store.
+    ///
+    /// Example: `neomind db export --output backup/`
+    Export {
+        /// Store name (default: all stores).
+        store: Option<String>,
+        /// Directory to write the JSON files to.
+        #[arg(short, long, default_value = "export")]
+        output: String,
+        /// Data directory path.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+    },
+    /// Import stores from JSON files written by `neomind db export`.
+    ///
+    /// Entries overwrite existing keys; `--replace` clears each imported
+    /// table first.
+    ///
+    /// Example: `neomind db import backup/devices.json --replace`
+    Import {
+        /// Export files to import.
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Clear each imported table before writing its entries.
+        #[arg(long)]
+        replace: bool,
+        /// Data directory path.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+    },
+    /// Run pending schema migrations.
+    ///
+    /// Example: `neomind db migrate --dry-run`
+    Migrate {
+        /// Only list migrations and whether they are applied.
+        #[arg(long)]
+        dry_run: bool,
+        /// Data directory path.
+        #[arg(long, default_value = "data")]
+        data_dir: String,
+    },
+}
+
 /// LLM backend subcommands.
 #[derive(Subcommand, Debug)]
 pub enum LlmCommand {
@@ -432,7 +602,10 @@ pub enum ExtensionCommand {
     /// Install a .nep extension package.
     ///
     /// Installs from a local file path. The extension is loaded immediately.
+    /// An http(s) URL is downloaded by the server, which checks the package
+    /// signature against the trusted publishers before installing.
     /// Example: `neomind extension install ./weather-forecast-v2.nep`
+    /// Example: `neomind extension install https://example.com/weather-forecast-2.1.0.nep`
     Install {
         /// Path to the .nep file or URL.
         #[arg(required = true)]
@@ -441,12 +614,45 @@ pub enum ExtensionCommand {
     /// Uninstall an extension.
     ///
     /// Stops the extension process and removes all files. This is irreversible.
+    /// `remove` is a visible alias.
     /// Example: `neomind extension uninstall weather-forecast`
+    #[command(visible_alias = "remove")]
     Uninstall {
         /// Extension ID.
         #[arg(required = true)]
         id: String,
     },
+    /// Upgrade an installed extension.
+    ///
+    /// Replaces the running extension with a newer version of the package,
+    /// from a local file or an http(s) URL. Fails if the extension isn't
+    /// installed, the package isn't newer, or its signature isn't trusted.
+    /// Example: `neomind extension upgrade ./weather-forecast-2.1.0.nep`
+    Upgrade {
+        /// Path to the .nep file or URL.
+        #[arg(required = true)]
+        package: String,
+    },
+    /// Sign a .nep package as a publisher.
+    ///
+    /// Adds an Ed25519 signature over the package contents. The key file
+    /// holds a base64 32-byte private key (e.g. `openssl rand -base64 32`);
+    /// the matching public key is printed for operators to trust.
+    /// Example: `neomind extension sign ./my-extension.nep --key ./publisher.key --publisher acme`
+    Sign {
+        /// Path to the .nep file.
+        #[arg(required = true)]
+        package: std::path::PathBuf,
+        /// File containing the base64 private key.
+        #[arg(long)]
+        key: std::path::PathBuf,
+        /// Publisher name operators trust the key under.
+        #[arg(long)]
+        publisher: String,
+        /// Output path (defaults to signing in place).
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
     /// Create a new extension scaffold.
     ///
     /// Generates a complete extension project with Cargo.toml, lib.rs, and manifest.
@@ -604,7 +810,7 @@ pub enum DeviceCommand {
         /// or use any custom name (e.g., "sensor", "camera", "switch").
         #[arg(short, long)]
         device_type: String,
-        /// Adapter type: mqtt (default) | webhook.
+        /// Adapter type: mqtt (default) | webhook | virtual.
         #[arg(short, long)]
         adapter_type: String,
         /// Optional explicit device ID. Auto-generated if omitted. Use this when
@@ -614,6 +820,8 @@ pub enum DeviceCommand {
         device_id: Option<String>,
         /// Connection config JSON. For MQTT: '{"topic":"sensor/data"}'.
         /// For webhook: omit (auto-generated URL).
+        /// For virtual: '{"virtual":{"selector":{"tags":["office"]},"metrics":
+        /// [{"name":"avg_temperature","source_metric":"temperature","aggregation":"mean"}]}}'.
         #[arg(short, long)]
         config: Option<String>,
     },
@@ -710,6 +918,158 @@ pub enum DeviceCommand {
         #[arg(short, long)]
         params: Option<String>,
     },
+    /// List device groups.
+    ///
+    /// Groups have static members plus an optional selector on device tags,
+    /// location (path prefix, e.g. "hq/floor-2") and device type.
+    ///
+    /// Example: `neomind device groups`
+    Groups,
+    /// Send one command to every device in a group.
+    ///
+    /// Fans the command out to all current group members whose device type
+    /// supports it and reports the outcome per device. Use this instead of
+    /// looping `device control` when the user targets a set of devices
+    /// ("turn off all lights on floor 2").
+    ///
+    /// Workflow:
+    ///   1. `device groups` — find the group ID
+    ///   2. `device control-group <GROUP> <command> --params '<json>'`
+    ///
+    /// Example: `neomind device control-group floor-2-lights turn_off`
+    ControlGroup {
+        /// Group ID.
+        #[arg(required = true)]
+        group: String,
+        /// Command name (e.g., "turn_off", "set_brightness").
+        #[arg(required = true)]
+        command: String,
+        /// Command parameters JSON, sent to every device. Example: '{"level":30}'
+        #[arg(short, long)]
+        params: Option<String>,
+        /// Send one device at a time instead of all at once.
+        #[arg(long)]
+        sequential: bool,
+    },
+    /// List the member devices of a virtual device.
+    ///
+    /// Virtual devices (adapter type `virtual`) compute their metrics from
+    /// other devices, e.g. the mean temperature of every device tagged
+    /// "office". Commands sent to a virtual device with `device control` fan
+    /// out to these members.
+    ///
+    /// Example: `neomind device members office-climate`
+    Members {
+        /// Virtual device ID.
+        #[arg(required = true)]
+        id: String,
+    },
+    /// List recent telemetry anomalies.
+    ///
+    /// The server learns a baseline (mean and spread, per hour of day) for
+    /// every numeric metric and flags values far outside it. Each anomaly
+    /// carries the observed value, the expected value and a z-score (how many
+    /// standard deviations off it was); use these to explain what happened
+    /// before suggesting a cause.
+    ///
+    /// Workflow:
+    ///   1. `device anomalies <ID>` — what was unusual and how unusual
+    ///   2. `device history <ID> --metric <metric>` — look at the surrounding data
+    ///
+    /// Example: `neomind device anomalies sensor-01 --metric temperature`
+    Anomalies {
+        /// Device ID (all devices if omitted).
+        id: Option<String>,
+        /// Only this metric.
+        #[arg(long)]
+        metric: Option<String>,
+        /// Maximum number of anomalies to return.
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+    /// Fleet availability summary.
+    ///
+    /// Counts online, offline and never-seen devices across the fleet and per
+    /// device type, and lists devices currently past their offline timeout
+    /// (longest outage first). A device is offline once it has not reported
+    /// for its offline timeout: the device override, else the type's default,
+    /// else its expected telemetry interval plus a grace period.
+    ///
+    /// Workflow:
+    ///   1. `device health-summary` — availability and who is offline
+    ///   2. `device get <ID>` — inspect an offline device
+    ///
+    /// Example: `neomind device health-summary`
+    #[command(alias = "health_summary")]
+    HealthSummary,
+    /// Reconstruct a device's state at a past moment.
+    ///
+    /// Returns the last value of every metric reported at or before the given
+    /// time (with its age) and whether the device was online, for incident
+    /// questions like "what was the valve position when the alarm fired?".
+    /// The online flag comes from recorded online/offline events; without one
+    /// it is inferred from how recently the device had reported.
+    ///
+    /// Workflow:
+    ///   1. `message list` / `device anomalies` — find when the incident happened
+    ///   2. `device state-at <ID> --at <time>` — device state at that moment
+    ///
+    /// Example: `neomind device state-at valve-3 --at 2026-03-01T14:05:00Z`
+    #[command(alias = "state_at")]
+    StateAt {
+        /// Device ID.
+        #[arg(required = true)]
+        id: String,
+        /// Point in time: Unix seconds, RFC 3339, or a relative age like "2h"
+        /// (two hours ago). Defaults to now.
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// Energy consumption and cost report.
+    ///
+    /// Covers the metrics registered as energy meters (power in W/kW or
+    /// cumulative energy in Wh/kWh, see `/api/energy/config`) and prices them
+    /// with the configured time-of-use tariff. Returns totals, per-device
+    /// consumption (highest first), a time series and a per-tariff-period
+    /// breakdown.
+    ///
+    /// Workflow:
+    ///   1. `device energy --time-range 7d --bucket day` — weekly overview
+    ///   2. `device energy --group <GROUP>` — one area or device set
+    ///
+    /// Example: `neomind device energy --time-range 30d --bucket day`
+    Energy {
+        /// Only this device.
+        #[arg(long)]
+        device: Option<String>,
+        /// Only members of this device group.
+        #[arg(long)]
+        group: Option<String>,
+        /// Time range: "24h", "7d", "30d" (default: 24h).
+        #[arg(short, long)]
+        time_range: Option<String>,
+        /// Series bucket: hour, day or month (default: day).
+        #[arg(short, long)]
+        bucket: Option<String>,
+    },
+    /// Query telemetry with a SQL-like statement.
+    ///
+    /// Aggregates and filters stored telemetry across devices in one call.
+    /// Sources are `device:<ID>` (or `device:*` for every device); metrics are
+    /// field names from `device get`. Aggregates: avg, min, max, sum, count,
+    /// first, last. Time bounds: `now() - 1h`, unix seconds or an RFC 3339
+    /// string (default: last 24h). Returns columns plus rows.
+    ///
+    /// Workflow:
+    ///   1. `device get <ID>` — find metric names
+    ///   2. `device query "SELECT avg(temperature) FROM device:<ID> WHERE time > now() - 1h GROUP BY 5m"`
+    ///
+    /// Example: `neomind device query "SELECT max(temperature) FROM device:* WHERE temperature > 30"`
+    Query {
+        /// Query text (quote it).
+        #[arg(required = true)]
+        query: String,
+    },
     /// Device type management.
     Types {
         #[command(subcommand)]
@@ -1119,7 +1479,8 @@ pub enum RuleCommand {
     /// Get rule execution history.
     ///
     /// Shows recent rule evaluations with timestamps, input data, and results.
-    /// Useful for debugging why a rule did or did not trigger.
+    /// Useful for debugging why a rule did or did not trigger. Each entry has
+    /// an `execution_id` that `rule explain` accepts.
     ///
     /// Example: `neomind rule history rule-001`
     History {
@@ -1127,6 +1488,68 @@ pub enum RuleCommand {
         #[arg(required = true)]
         id: String,
     },
+    /// Explain one rule execution step by step.
+    ///
+    /// Shows what triggered the rule, the value each condition saw and
+    /// whether it matched, and the result of every action. Use this to answer
+    /// "why did X happen?" from recorded evidence. Get the execution ID from
+    /// `rule history <ID>`.
+    ///
+    /// Example: `neomind rule explain 3f2b9c1e-7d4a-4e8b-9a61-0c5d2e8f1a47`
+    Explain {
+        /// Execution ID (from `rule history`).
+        #[arg(required = true)]
+        execution_id: String,
+    },
+    /// Simulate a rule against stored history.
+    ///
+    /// Replays recorded metric data through a rule and reports when it would
+    /// have fired and which actions would have run. Nothing is executed and
+    /// nothing is saved. Pass either an existing rule ID or a candidate rule
+    /// via --body (same JSON as `rule create`).
+    ///
+    /// Use this to sanity-check thresholds, cooldown and for_duration before
+    /// `rule create`: zero firings usually means the threshold is unrealistic,
+    /// dozens of firings means the cooldown is too short.
+    ///
+    /// Example: `neomind rule simulate --body '{"name":"HighTemp","condition":{...},"actions":[...]}' --hours 48`
+    /// Example: `neomind rule simulate rule-001 --hours 168`
+    Simulate {
+        /// Existing rule ID (omit when using --body).
+        id: Option<String>,
+        /// Candidate rule definition as JSON string.
+        #[arg(short, long)]
+        body: Option<String>,
+        /// Look-back window in hours (default 24). Ignored when --start is set.
+        #[arg(long)]
+        hours: Option<i64>,
+        /// Range start as Unix timestamp in seconds.
+        #[arg(long)]
+        start: Option<i64>,
+        /// Range end as Unix timestamp in seconds (default now).
+        #[arg(long)]
+        end: Option<i64>,
+    },
+    /// Find conflicts between rules.
+    ///
+    /// Without arguments, analyzes all enabled rules and reports rules whose
+    /// conditions can hold together while their actions contradict (one turns
+    /// a device on, another turns it off), rules shadowed by another rule with
+    /// the same actions, and rules that trigger each other in a loop through
+    /// device commands.
+    ///
+    /// With an ID or --body, reports only the conflicts that rule would have
+    /// with the rest of the fleet. Run this before `rule create`.
+    ///
+    /// Example: `neomind rule conflicts`
+    /// Example: `neomind rule conflicts --body '{"name":"FanOff","condition":{...},"actions":[...]}'`
+    Conflicts {
+        /// Existing rule ID to check against the other rules.
+        id: Option<String>,
+        /// Candidate rule definition as JSON string.
+        #[arg(short, long)]
+        body: Option<String>,
+    },
 }
 
 /// Transform subcommands.
@@ -1660,7 +2083,7 @@ pub enum MessageCommand {
     },
     /// List available channel types.
     ///
-    /// Shows channel types that can be created (webhook, email, telegram, wecom, dingtalk, slack, feishu).
+    /// Shows channel types that can be created (webhook, email, telegram, wecom, dingtalk, slack, feishu, mqtt).
     /// Example: `neomind message channel-types`
     ChannelTypes,
     /// Get config schema and examples for a channel type.
@@ -1669,7 +2092,7 @@ pub enum MessageCommand {
     /// Run before `channel-create` to know what --config needs.
     /// Example: `neomind message channel-type-schema telegram`
     ChannelTypeSchema {
-        /// Channel type (webhook, email, telegram, wecom, dingtalk, slack, feishu).
+        /// Channel type (webhook, email, telegram, wecom, dingtalk, slack, feishu, mqtt).
         #[arg(required = true)]
         channel_type: String,
     },
@@ -1689,6 +2112,7 @@ pub enum MessageCommand {
     ///   dingtalk: '{"access_token":"xxxx","secret":"SECxxxx"}'
     ///   slack:    '{"webhook_url":"https://hooks.slack.com/services/T00/B00/xxx"}'
     ///   feishu:   '{"hook_id":"xxxxxxxx","secret":"optional_sign_secret"}'
+    ///   mqtt:     '{"embedded_broker":true,"topic":"scada/alerts/{severity}","qos":1,"retain":false}'
     ///
     /// Example: `neomind message channel-create --name "alerts" --type webhook --config '{"url":"https://hooks.slack.com/..."}'`
     ChannelCreate {
@@ -1758,13 +2182,16 @@ pub enum PushCommand {
     /// Target types & config:
     ///   webhook: '{"url":"https://example.com/api","headers":{"Authorization":"Bearer TOKEN"}}'
     ///   mqtt:    '{"broker":"tcp://broker:1883","topic":"neomind/data","username":"user","password":"pass"}'
+    ///   influxdb: '{"url":"http://influx:8086","bucket":"neomind","org":"my-org","token":"TOKEN"}' (v2)
+    ///             '{"url":"http://influx:8086","database":"neomind"}' (v1)
+    ///   telegraf: '{"address":"telegraf:8094","protocol":"tcp"}' (socket_listener, tcp or udp)
     ///
     /// Example: `neomind push create --name my-webhook --type webhook --config '{"url":"https://httpbin.org/post"}'`
     Create {
         /// Target name (unique identifier).
         #[arg(long)]
         name: String,
-        /// Target type (webhook, mqtt).
+        /// Target type (webhook, mqtt, influxdb, telegraf).
         #[arg(long, visible_alias = "target-type")]
         #[arg(short = 't', long = "type", hide = true)]
         target_type: Option<String>,
@@ -1982,6 +2409,69 @@ pub enum SystemCommand {
     /// Example: `neomind system info`
     Info {
     },
+    /// List maintenance windows.
+    ///
+    /// While a window is active, rules covering its devices/groups/rules skip their
+    /// actions and matching alerts are recorded (tagged "maintenance") without
+    /// notifying channels. Use --active to show only windows in effect now.
+    ///
+    /// Example: `neomind system maintenance --active`
+    Maintenance {
+        /// Only show windows in effect now.
+        #[arg(long)]
+        active: bool,
+    },
+    /// Create a maintenance window (one-off or weekly recurring).
+    ///
+    /// One-off: give --duration (minutes) or --end. Recurring: give --at HH:MM and
+    /// --duration, optionally --weekdays; times are in the global timezone. With no
+    /// --devices/--groups/--rules the window covers everything.
+    ///
+    /// Workflow: `neomind device list` / `neomind group list` / `neomind rule list` to find
+    /// IDs → `neomind system maintenance-create` → `neomind system maintenance --active`.
+    ///
+    /// Example: `neomind system maintenance-create --name "Line 2 retrofit" --groups line-2 --duration 240`
+    /// Example: `neomind system maintenance-create --name "Nightly backup" --devices nas-1 --at 02:00 --duration 60 --weekdays mon,wed,fri`
+    MaintenanceCreate {
+        /// Window name.
+        #[arg(long)]
+        name: String,
+        /// Why the window exists (shown alongside suppressed alerts).
+        #[arg(long)]
+        reason: Option<String>,
+        /// Start as RFC 3339 (e.g. 2026-10-20T08:00:00+08:00). Default: now.
+        #[arg(long)]
+        start: Option<String>,
+        /// End as RFC 3339. One-off windows need --end or --duration.
+        #[arg(long)]
+        end: Option<String>,
+        /// Length in minutes (of the window, or of each recurring occurrence).
+        #[arg(long)]
+        duration: Option<u32>,
+        /// Recurring: daily opening time HH:MM in the global timezone.
+        #[arg(long)]
+        at: Option<String>,
+        /// Recurring: days the window opens (comma-separated, e.g. "mon,tue"). Default: every day.
+        #[arg(long)]
+        weekdays: Option<String>,
+        /// Device IDs covered (comma-separated).
+        #[arg(long)]
+        devices: Option<String>,
+        /// Device group IDs covered (comma-separated).
+        #[arg(long)]
+        groups: Option<String>,
+        /// Rule IDs covered (comma-separated).
+        #[arg(long)]
+        rules: Option<String>,
+    },
+    /// Delete a maintenance window.
+    ///
+    /// Example: `neomind system maintenance-delete <id>`
+    MaintenanceDelete {
+        /// Maintenance window ID.
+        #[arg(required = true)]
+        id: String,
+    },
 }
 
 /// System settings subcommands (timezone, data retention).
@@ -2209,6 +2699,64 @@ pub enum ConnectorCommand {
     },
 }
 
+/// Knowledge base subcommands.
+#[derive(Subcommand, Debug)]
+pub enum KnowledgeCommand {
+    /// Search uploaded documents for passages relevant to a question.
+    ///
+    /// Returns the best-matching chunks with their document name and a
+    /// similarity score. Answer from the returned text and cite the document;
+    /// if nothing relevant comes back, say the manuals don't cover it rather
+    /// than guessing.
+    ///
+    /// Example: `neomind knowledge search "how to replace the P-100 pump seal"`
+    Search {
+        /// Question or keywords.
+        #[arg(required_unless_present = "query_flag")]
+        query: Option<String>,
+        /// Same as the positional query.
+        #[arg(long = "query", hide = true)]
+        query_flag: Option<String>,
+        /// Passages to return (1-20, default 5).
+        #[arg(short = 'k', long, alias = "top_k")]
+        top_k: Option<usize>,
+    },
+    /// List uploaded documents.
+    ///
+    /// Example: `neomind knowledge list`
+    List,
+    /// Upload a document (PDF, Markdown, HTML or text).
+    ///
+    /// The document is split into chunks and embedded for search. Scanned
+    /// PDFs without a text layer can't be searched.
+    ///
+    /// Example: `neomind knowledge upload ./manuals/p100.pdf`
+    Upload {
+        /// Path to the file.
+        #[arg(required = true)]
+        file: String,
+    },
+    /// Delete a document.
+    ///
+    /// Example: `neomind knowledge delete <ID>`
+    Delete {
+        /// Document ID.
+        #[arg(required = true)]
+        id: String,
+    },
+    /// Re-embed documents not embedded by the current embedding model.
+    ///
+    /// Changing the embedding model already starts a reindex; run this to
+    /// finish one interrupted by a restart. Runs in the background.
+    ///
+    /// Example: `neomind knowledge reindex`
+    Reindex,
+    /// Show progress of the running or last reindex.
+    ///
+    /// Example: `neomind knowledge reindex-status`
+    ReindexStatus,
+}
+
 /// Parse human duration like "30s", "5m", "1h", "2d" to seconds
 pub fn parse_duration(s: &str) -> u64 {
     let s = s.trim();
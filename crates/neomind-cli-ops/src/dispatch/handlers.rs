@@ -12,7 +12,7 @@ use anyhow::Result;
 use super::commands::*;
 
 /// Returns true for extension subcommands that touch the local filesystem
-/// (`validate`, `install`, `uninstall`, `create`, `build`, `info`) and must
+/// (`validate`, `install`, `uninstall`, `sign`, `create`, `build`, `info`) and must
 /// run as a subprocess so their stdout is captured by the agent.
 pub fn is_local_extension_command(cmd: &ExtensionCommand) -> bool {
     matches!(
@@ -20,6 +20,7 @@ pub fn is_local_extension_command(cmd: &ExtensionCommand) -> bool {
         ExtensionCommand::Validate { .. }
             | ExtensionCommand::Install { .. }
             | ExtensionCommand::Uninstall { .. }
+            | ExtensionCommand::Sign { .. }
             | ExtensionCommand::Create { .. }
             | ExtensionCommand::Build { .. }
             | ExtensionCommand::Get { .. }
@@ -68,6 +69,9 @@ pub async fn run_extension_cmd(cmd: ExtensionCommand) -> Result<(CliResponse, Ou
             )
             .await?
         }
+        ExtensionCommand::Upgrade { package } => {
+            crate::extension::upgrade_extension(&client, &package).await?
+        }
         ExtensionCommand::MarketList => {
             crate::extension::list_marketplace(&client).await?
         }
@@ -261,6 +265,50 @@ pub async fn run_device_cmd(cmd: DeviceCommand) -> Result<(CliResponse, OutputFo
                 base_format,
             )
         }
+        DeviceCommand::Groups => (list_device_groups(&client).await?, base_format),
+        DeviceCommand::ControlGroup {
+            group,
+            command,
+            params,
+            sequential,
+        } => {
+            let params_json = if let Some(params_str) = params {
+                serde_json::from_str(&params_str)?
+            } else {
+                serde_json::json!({})
+            };
+            (
+                control_device_group(&client, &group, &command, params_json, sequential).await?,
+                base_format,
+            )
+        }
+        DeviceCommand::Members { id } => (get_virtual_members(&client, &id).await?, base_format),
+        DeviceCommand::Anomalies { id, metric, limit } => (
+            get_device_anomalies(&client, id.as_deref(), metric.as_deref(), limit).await?,
+            base_format,
+        ),
+        DeviceCommand::HealthSummary => (get_health_summary(&client).await?, base_format),
+        DeviceCommand::StateAt { id, at } => (
+            get_device_state_at(&client, &id, at.as_deref()).await?,
+            base_format,
+        ),
+        DeviceCommand::Query { query } => (query_telemetry(&client, &query).await?, base_format),
+        DeviceCommand::Energy {
+            device,
+            group,
+            time_range,
+            bucket,
+        } => (
+            get_energy_report(
+                &client,
+                device.as_deref(),
+                group.as_deref(),
+                time_range.as_deref(),
+                bucket.as_deref(),
+            )
+            .await?,
+            base_format,
+        ),
         DeviceCommand::Types { type_cmd } => {
             return run_device_type_cmd(client, type_cmd, base_format).await;
         }
@@ -494,6 +542,29 @@ pub async fn run_rule_cmd(cmd: RuleCommand) -> Result<(CliResponse, OutputFormat
             test_rule(&client, &id, input_json).await?
         }
         RuleCommand::History { id } => get_rule_history(&client, &id).await?,
+        RuleCommand::Explain { execution_id } => {
+            explain_rule_execution(&client, &execution_id).await?
+        }
+        RuleCommand::Simulate {
+            id,
+            body,
+            hours,
+            start,
+            end,
+        } => {
+            simulate_rule(
+                &client,
+                id.as_deref(),
+                body.as_deref(),
+                hours,
+                start,
+                end,
+            )
+            .await?
+        }
+        RuleCommand::Conflicts { id, body } => {
+            rule_conflicts(&client, id.as_deref(), body.as_deref()).await?
+        }
     };
 
     // Format and print output
@@ -906,6 +977,44 @@ pub async fn run_system_cmd(cmd: SystemCommand) -> Result<(CliResponse, OutputFo
             let resp = crate::system::system_info(&client).await?;
             (resp, base_format)
         }
+        SystemCommand::Maintenance { active } => {
+            let resp = crate::system::list_maintenance(&client, active).await?;
+            (resp, base_format)
+        }
+        SystemCommand::MaintenanceCreate {
+            name,
+            reason,
+            start,
+            end,
+            duration,
+            at,
+            weekdays,
+            devices,
+            groups,
+            rules,
+        } => {
+            let resp = crate::system::create_maintenance(
+                &client,
+                crate::system::MaintenanceArgs {
+                    name,
+                    reason,
+                    start,
+                    end,
+                    duration,
+                    at,
+                    weekdays,
+                    devices,
+                    groups,
+                    rules,
+                },
+            )
+            .await?;
+            (resp, base_format)
+        }
+        SystemCommand::MaintenanceDelete { id } => {
+            let resp = crate::system::delete_maintenance(&client, &id).await?;
+            (resp, base_format)
+        }
     };
     Ok(result)
 }
@@ -959,6 +1068,34 @@ pub async fn run_settings_cmd(cmd: SettingsCommand) -> Result<(CliResponse, Outp
     Ok(result)
 }
 
+pub async fn run_knowledge_cmd(cmd: KnowledgeCommand) -> Result<(CliResponse, OutputFormat)> {
+    let client = crate::ApiClient::new();
+    let base_format = if std::env::var("NEOMIND_JSON").is_ok() {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+
+    let resp = match cmd {
+        KnowledgeCommand::Search {
+            query,
+            query_flag,
+            top_k,
+        } => {
+            let query = query.or(query_flag).unwrap_or_default();
+            crate::knowledge::search_knowledge(&client, &query, top_k).await?
+        }
+        KnowledgeCommand::List => crate::knowledge::list_documents(&client).await?,
+        KnowledgeCommand::Upload { file } => {
+            crate::knowledge::upload_document(&client, &file).await?
+        }
+        KnowledgeCommand::Delete { id } => crate::knowledge::delete_document(&client, &id).await?,
+        KnowledgeCommand::Reindex => crate::knowledge::reindex(&client).await?,
+        KnowledgeCommand::ReindexStatus => crate::knowledge::reindex_status(&client).await?,
+    };
+    Ok((resp, base_format))
+}
+
 pub async fn run_connector_cmd(cmd: ConnectorCommand) -> Result<(CliResponse, OutputFormat)> {
     let client = crate::ApiClient::new();
     let base_format = if std::env::var("NEOMIND_JSON").is_ok() {
@@ -1,3 +1,4 @@
+use crate::api_client::extract_inner_data;
 use crate::types::{BuildMeta, CliResponse};
 use crate::ApiClient;
 use anyhow::Result;
@@ -116,6 +117,73 @@ pub async fn install_extension_file(client: &ApiClient, file_path: &str) -> Resu
     ))
 }
 
+/// Request body for `/extensions/upload/file`: URLs are passed through for
+/// the server to download, files are sent base64-encoded.
+fn package_body(package: &str, upgrade: bool) -> Result<serde_json::Value> {
+    if package.starts_with("http://") || package.starts_with("https://") {
+        return Ok(json!({ "url": package, "upgrade": upgrade }));
+    }
+    let buf = std::fs::read(package)?;
+    let filename = std::path::Path::new(package)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("extension.nep");
+    Ok(json!({
+        "data": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &buf),
+        "filename": filename,
+        "upgrade": upgrade,
+    }))
+}
+
+/// Install extension from a URL. The server downloads the package and checks
+/// its signature.
+pub async fn install_extension_url(client: &ApiClient, url: &str) -> Result<CliResponse> {
+    let data = extract_inner_data(
+        client
+            .post("/extensions/upload/file", &package_body(url, false)?)
+            .await?,
+    );
+    let ext_id = data
+        .get("extension_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let meta = BuildMeta {
+        r#type: "extension".to_string(),
+        action: "install".to_string(),
+        entity_id: ext_id.clone(),
+        entity_name: ext_id.clone().into(),
+        undo_command: format!("neomind extension uninstall {}", ext_id),
+    };
+
+    Ok(CliResponse::success_with_meta(
+        data,
+        "Extension installed",
+        meta,
+    ))
+}
+
+/// Upgrade an installed extension from a file or URL.
+pub async fn upgrade_extension(client: &ApiClient, package: &str) -> Result<CliResponse> {
+    let data = extract_inner_data(
+        client
+            .post("/extensions/upload/file", &package_body(package, true)?)
+            .await?,
+    );
+    let ext_id = data
+        .get("extension_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let from = data
+        .get("previous_version")
+        .and_then(|v| v.as_str())
+        .unwrap_or("?");
+    let to = data.get("version").and_then(|v| v.as_str()).unwrap_or("?");
+    let message = format!("Extension {} upgraded from {} to {}", ext_id, from, to);
+    Ok(CliResponse::success(data, message))
+}
+
 /// Install extension from marketplace
 pub async fn install_extension_market(
     client: &ApiClient,
@@ -0,0 +1,97 @@
+use crate::api_client::extract_inner_data;
+use crate::types::CliResponse;
+use crate::ApiClient;
+use anyhow::Result;
+use serde_json::json;
+
+/// Retrieve the document chunks most relevant to a question.
+pub async fn search_knowledge(
+    client: &ApiClient,
+    query: &str,
+    top_k: Option<usize>,
+) -> Result<CliResponse> {
+    let mut body = json!({ "query": query });
+    if let Some(top_k) = top_k {
+        body["top_k"] = json!(top_k);
+    }
+    let data = extract_inner_data(client.post("/knowledge/search", &body).await?);
+    let hits = data.as_array().map(|a| a.len()).unwrap_or(0);
+    let message = if hits == 0 {
+        "No matching passages; the knowledge base may not cover this".to_string()
+    } else {
+        format!("{} passage(s) found", hits)
+    };
+    Ok(CliResponse::success(data, message))
+}
+
+/// List uploaded documents.
+pub async fn list_documents(client: &ApiClient) -> Result<CliResponse> {
+    let data = extract_inner_data(client.get("/knowledge/documents").await?);
+    let count = data.get("count").and_then(|v| v.as_u64()).unwrap_or(0);
+    Ok(CliResponse::success(
+        data,
+        format!("{} document(s) listed", count),
+    ))
+}
+
+/// Upload a PDF, Markdown, HTML or text file.
+pub async fn upload_document(client: &ApiClient, file: &str) -> Result<CliResponse> {
+    let data = extract_inner_data(
+        client
+            .post_file_named("/knowledge/documents", file, "file")
+            .await?,
+    );
+    let chunks = data.get("chunks").and_then(|v| v.as_u64()).unwrap_or(0);
+    Ok(CliResponse::success(
+        data,
+        format!("Document ingested ({} chunks)", chunks),
+    ))
+}
+
+/// Delete a document and its chunks.
+pub async fn delete_document(client: &ApiClient, id: &str) -> Result<CliResponse> {
+    let data = client
+        .delete(&format!("/knowledge/documents/{}", id))
+        .await?;
+    Ok(CliResponse::success(
+        data,
+        format!("Document {} deleted", id),
+    ))
+}
+
+/// Start re-embedding documents with the currently configured embedder.
+pub async fn reindex(client: &ApiClient) -> Result<CliResponse> {
+    let data = extract_inner_data(client.post("/knowledge/reindex", &json!({})).await?);
+    let message = reindex_message(&data);
+    Ok(CliResponse::success(data, message))
+}
+
+/// Progress of the running or last reindex.
+pub async fn reindex_status(client: &ApiClient) -> Result<CliResponse> {
+    let data = extract_inner_data(client.get("/knowledge/reindex").await?);
+    let message = reindex_message(&data);
+    Ok(CliResponse::success(data, message))
+}
+
+fn reindex_message(job: &serde_json::Value) -> String {
+    let total = job
+        .get("total_chunks")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let processed = job
+        .get("processed_chunks")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    match job.get("status").and_then(|v| v.as_str()) {
+        Some("running") => format!("Reindexing: {}/{} chunk(s)", processed, total),
+        Some("failed") => format!(
+            "Reindex failed after {}/{} chunk(s): {}",
+            processed,
+            total,
+            job.get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown error")
+        ),
+        _ => format!("Reindex complete: {} chunk(s) re-embedded", total),
+    }
+}
@@ -13,6 +13,7 @@ pub mod dashboard;
 pub mod data_push;
 pub mod device;
 pub mod extension;
+pub mod knowledge;
 pub mod llm;
 pub mod message;
 pub mod rule;
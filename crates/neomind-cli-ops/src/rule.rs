@@ -158,3 +158,108 @@ pub async fn get_rule_history(client: &ApiClient, id: &str) -> Result<CliRespons
     let data = client.get(&format!("/rules/{}/history", id)).await?;
     Ok(CliResponse::success(data, "Rule history retrieved"))
 }
+
+/// Explain a recorded rule execution step by step.
+pub async fn explain_rule_execution(client: &ApiClient, execution_id: &str) -> Result<CliResponse> {
+    let data = client
+        .get(&format!("/rules/executions/{}", execution_id))
+        .await?;
+    let explanation = data
+        .get("data")
+        .unwrap_or(&data)
+        .get("explanation")
+        .and_then(|e| e.as_str())
+        .unwrap_or("Rule execution retrieved")
+        .to_string();
+    Ok(CliResponse::success(data, explanation))
+}
+
+/// Simulate a rule against stored history without executing actions.
+///
+/// Either `id` (existing rule) or `json_body` (candidate rule) must be given.
+pub async fn simulate_rule(
+    client: &ApiClient,
+    id: Option<&str>,
+    json_body: Option<&str>,
+    hours: Option<i64>,
+    start: Option<i64>,
+    end: Option<i64>,
+) -> Result<CliResponse> {
+    let mut body = json!({ "hours": hours, "start": start, "end": end });
+    match (json_body, id) {
+        (Some(raw), _) => match serde_json::from_str::<serde_json::Value>(raw) {
+            Ok(rule) => body["rule"] = rule,
+            Err(e) => {
+                return Ok(CliResponse::error_with_suggestion(
+                    format!("Invalid JSON: {}", e),
+                    "INVALID_JSON",
+                    "Example: --body '{\"name\":\"HighTemp\",\"condition\":{\"condition_type\":\"comparison\",\"source\":\"device:sensor-001:temperature\",\"operator\":\"greater_than\",\"threshold\":30},\"actions\":[{\"type\":\"notify\",\"message\":\"Too hot!\"}]}'",
+                ));
+            }
+        },
+        (None, Some(id)) => body["rule_id"] = json!(id),
+        (None, None) => {
+            return Ok(CliResponse::error_with_suggestion(
+                "Nothing to simulate",
+                "MISSING_RULE",
+                "Pass an existing rule ID (`neomind rule simulate <ID>`) or a candidate rule with --body '<JSON>'",
+            ));
+        }
+    }
+
+    let data = client.post("/rules/simulate", &body).await?;
+    let report = data.get("data").unwrap_or(&data);
+    let firings = report
+        .get("report")
+        .and_then(|r| r.get("firings"))
+        .and_then(|f| f.as_array())
+        .map(|f| f.len())
+        .unwrap_or(0);
+
+    Ok(CliResponse::success(
+        data,
+        format!("Rule would have fired {} time(s)", firings),
+    ))
+}
+
+/// Analyze rule conflicts.
+///
+/// With neither `id` nor `json_body`, analyzes all enabled rules; otherwise
+/// reports only the conflicts involving the given rule.
+pub async fn rule_conflicts(
+    client: &ApiClient,
+    id: Option<&str>,
+    json_body: Option<&str>,
+) -> Result<CliResponse> {
+    let data = match (json_body, id) {
+        (Some(raw), _) => match serde_json::from_str::<serde_json::Value>(raw) {
+            Ok(rule) => client.post("/rules/conflicts", &json!({ "rule": rule })).await?,
+            Err(e) => {
+                return Ok(CliResponse::error_with_suggestion(
+                    format!("Invalid JSON: {}", e),
+                    "INVALID_JSON",
+                    "Pass the same rule JSON as `neomind rule create --body '<JSON>'`",
+                ));
+            }
+        },
+        (None, Some(id)) => {
+            client
+                .post("/rules/conflicts", &json!({ "rule_id": id }))
+                .await?
+        }
+        (None, None) => client.get("/rules/conflicts").await?,
+    };
+
+    let count = data
+        .get("data")
+        .unwrap_or(&data)
+        .get("count")
+        .and_then(|c| c.as_u64())
+        .unwrap_or(0);
+    let message = if count == 0 {
+        "No rule conflicts found".to_string()
+    } else {
+        format!("Found {} rule conflict(s)", count)
+    };
+    Ok(CliResponse::success(data, message))
+}
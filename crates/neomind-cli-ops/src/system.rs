@@ -1,6 +1,6 @@
 use crate::types::CliResponse;
 use crate::ApiClient;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde_json::json;
 
 /// Get system infrastructure info: MQTT broker, network, webhook URLs
@@ -168,3 +168,124 @@ pub async fn system_info(client: &ApiClient) -> Result<CliResponse> {
 
     Ok(CliResponse::success(info, "System info retrieved"))
 }
+
+/// Arguments for `neomind system maintenance-create`.
+pub struct MaintenanceArgs {
+    pub name: String,
+    pub reason: Option<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub duration: Option<u32>,
+    pub at: Option<String>,
+    pub weekdays: Option<String>,
+    pub devices: Option<String>,
+    pub groups: Option<String>,
+    pub rules: Option<String>,
+}
+
+fn split_ids(s: Option<&str>) -> Vec<String> {
+    s.unwrap_or_default()
+        .split(',')
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+fn parse_rfc3339(s: &str) -> Result<i64> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|t| t.timestamp())
+        .map_err(|e| anyhow!("Invalid time '{}' (expected RFC 3339): {}", s, e))
+}
+
+fn parse_hhmm(s: &str) -> Result<u32> {
+    let (h, m) = s
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Invalid --at '{}' (expected HH:MM)", s))?;
+    match (h.parse::<u32>(), m.parse::<u32>()) {
+        (Ok(h), Ok(m)) if h < 24 && m < 60 => Ok(h * 60 + m),
+        _ => Err(anyhow!("Invalid --at '{}' (expected HH:MM)", s)),
+    }
+}
+
+fn parse_weekdays(s: &str) -> Result<Vec<u8>> {
+    const DAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+    split_ids(Some(s))
+        .iter()
+        .map(|d| {
+            let d = d.to_lowercase();
+            DAYS.iter()
+                .position(|day| d.starts_with(day))
+                .map(|i| i as u8)
+                .ok_or_else(|| anyhow!("Invalid weekday '{}' (use mon..sun)", d))
+        })
+        .collect()
+}
+
+/// List maintenance windows, optionally only those in effect now
+pub async fn list_maintenance(client: &ApiClient, active: bool) -> Result<CliResponse> {
+    let path = if active {
+        "/maintenance/active"
+    } else {
+        "/maintenance"
+    };
+    let data = client.get(path).await?;
+    Ok(CliResponse::success(data, "Maintenance windows retrieved"))
+}
+
+/// Create a one-off or weekly recurring maintenance window
+pub async fn create_maintenance(client: &ApiClient, args: MaintenanceArgs) -> Result<CliResponse> {
+    let start = match args.start.as_deref() {
+        Some(s) => parse_rfc3339(s)?,
+        None => chrono::Utc::now().timestamp(),
+    };
+    let end = args.end.as_deref().map(parse_rfc3339).transpose()?;
+
+    let mut body = json!({
+        "id": "",
+        "name": args.name,
+        "start": start,
+        "devices": split_ids(args.devices.as_deref()),
+        "groups": split_ids(args.groups.as_deref()),
+        "rules": split_ids(args.rules.as_deref()),
+    });
+    if let Some(reason) = args.reason {
+        body["reason"] = json!(reason);
+    }
+
+    match (args.at.as_deref(), args.duration) {
+        (Some(at), Some(duration)) => {
+            let weekdays = args
+                .weekdays
+                .as_deref()
+                .map(parse_weekdays)
+                .transpose()?
+                .unwrap_or_default();
+            body["recurrence"] = json!({
+                "weekdays": weekdays,
+                "start_minute": parse_hhmm(at)?,
+                "duration_minutes": duration,
+            });
+            if let Some(end) = end {
+                body["end"] = json!(end);
+            }
+        }
+        (Some(_), None) => {
+            return Err(anyhow!("Recurring windows (--at) need --duration"));
+        }
+        (None, duration) => {
+            let end = end
+                .or_else(|| duration.map(|d| start + d as i64 * 60))
+                .ok_or_else(|| anyhow!("One-off windows need --end or --duration"))?;
+            body["end"] = json!(end);
+        }
+    }
+
+    let data = client.post("/maintenance", &body).await?;
+    Ok(CliResponse::success(data, "Maintenance window created"))
+}
+
+/// Delete a maintenance window
+pub async fn delete_maintenance(client: &ApiClient, id: &str) -> Result<CliResponse> {
+    let data = client.delete(&format!("/maintenance/{}", id)).await?;
+    Ok(CliResponse::success(data, "Maintenance window deleted"))
+}
@@ -0,0 +1,206 @@
+//! `neomind db`: offline administration of the stores in a data directory.
+//!
+//! Thin front end over [`neomind_storage::admin`]. The stores are opened
+//! directly, so every subcommand fails with a "stop the server first" error
+//! while the server holds them.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use neomind_cli_ops::dispatch::commands::DbCommand;
+use neomind_storage::admin::{self, StoreExport};
+
+/// Run a `neomind db` subcommand.
+pub fn run_db_cmd(cmd: DbCommand) -> Result<()> {
+    match cmd {
+        DbCommand::Inspect { store, data_dir } => {
+            for path in select_stores(Path::new(&data_dir), store.as_deref())? {
+                inspect(&path)?;
+            }
+        }
+        DbCommand::Compact { store, data_dir } => {
+            for path in select_stores(Path::new(&data_dir), store.as_deref())? {
+                let report = admin::compact_store(&path)
+                    .with_context(|| format!("Failed to compact {}", path.display()))?;
+                let saved = report.before_bytes.saturating_sub(report.after_bytes);
+                println!(
+                    "{}: {} -> {} ({} reclaimed)",
+                    admin::store_name(&path),
+                    format_bytes(report.before_bytes),
+                    format_bytes(report.after_bytes),
+                    format_bytes(saved)
+                );
+            }
+        }
+        DbCommand::Export {
+            store,
+            output,
+            data_dir,
+        } => {
+            let stores = select_stores(Path::new(&data_dir), store.as_deref())?;
+            std::fs::create_dir_all(&output)
+                .with_context(|| format!("Failed to create {}", output))?;
+            for path in stores {
+                export(&path, Path::new(&output))?;
+            }
+        }
+        DbCommand::Import {
+            files,
+            replace,
+            data_dir,
+        } => {
+            std::fs::create_dir_all(&data_dir)?;
+            for file in files {
+                import(Path::new(&file), Path::new(&data_dir), replace)?;
+            }
+        }
+        DbCommand::Migrate { dry_run, data_dir } => migrate(Path::new(&data_dir), dry_run)?,
+    }
+    Ok(())
+}
+
+/// The store named on the command line, or every store in the data directory.
+fn select_stores(data_dir: &Path, store: Option<&str>) -> Result<Vec<PathBuf>> {
+    if !data_dir.is_dir() {
+        bail!("Data directory {} not found", data_dir.display());
+    }
+    if let Some(store) = store {
+        let path = admin::store_path(data_dir, store);
+        if !path.is_file() {
+            bail!("Store '{}' not found at {}", store, path.display());
+        }
+        return Ok(vec![path]);
+    }
+    let stores = admin::list_stores(data_dir)?;
+    if stores.is_empty() {
+        bail!("No .redb stores found in {}", data_dir.display());
+    }
+    Ok(stores)
+}
+
+fn inspect(path: &Path) -> Result<()> {
+    let info = admin::inspect_store(path)
+        .with_context(|| format!("Failed to inspect {}", path.display()))?;
+
+    println!("{} ({})", info.name, format_bytes(info.file_bytes));
+    if info.tables.is_empty() {
+        println!("  (no tables)");
+    } else {
+        println!("  {:<32} {:>10} {:>12} {:>12}", "TABLE", "ENTRIES", "STORED", "FRAGMENTED");
+        for table in &info.tables {
+            println!(
+                "  {:<32} {:>10} {:>12} {:>12}",
+                table.name,
+                table.entries,
+                format_bytes(table.stored_bytes + table.metadata_bytes),
+                format_bytes(table.fragmented_bytes)
+            );
+        }
+    }
+    if info.journal_files > 0 {
+        println!(
+            "  ⚠️  {} write-ahead journal file(s) not yet replayed; the server \
+             commits them on its next start",
+            info.journal_files
+        );
+    }
+    println!();
+    Ok(())
+}
+
+fn export(path: &Path, output: &Path) -> Result<()> {
+    let export = admin::export_store(path)
+        .with_context(|| format!("Failed to export {}", path.display()))?;
+    let target = output.join(format!("{}.json", export.store));
+    let json = serde_json::to_vec_pretty(&export)?;
+    std::fs::write(&target, json)
+        .with_context(|| format!("Failed to write {}", target.display()))?;
+
+    println!(
+        "Exported {}: {} table(s), {} entries -> {}",
+        export.store,
+        export.tables.len(),
+        export.entry_count(),
+        target.display()
+    );
+    if !export.skipped_tables.is_empty() {
+        println!(
+            "  ⚠️  Skipped tables with unsupported layouts: {}",
+            export.skipped_tables.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn import(file: &Path, data_dir: &Path, replace: bool) -> Result<()> {
+    let content = std::fs::read(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let export: StoreExport = serde_json::from_slice(&content)
+        .with_context(|| format!("{} is not a `neomind db export` file", file.display()))?;
+    if export.store.is_empty() || export.store.contains(['/', '\\']) {
+        bail!("{} names an invalid store '{}'", file.display(), export.store);
+    }
+
+    let path = admin::store_path(data_dir, &export.store);
+    let report = admin::import_store(&path, &export, replace)
+        .with_context(|| format!("Failed to import into {}", path.display()))?;
+    println!(
+        "Imported {}: {} table(s), {} entries -> {}",
+        export.store,
+        report.tables,
+        report.entries,
+        path.display()
+    );
+    Ok(())
+}
+
+fn migrate(data_dir: &Path, dry_run: bool) -> Result<()> {
+    if !data_dir.is_dir() {
+        bail!("Data directory {} not found", data_dir.display());
+    }
+
+    if dry_run {
+        let statuses = admin::migration_status(data_dir)?;
+        if statuses.is_empty() {
+            println!("No migrations apply to the stores in {}", data_dir.display());
+        }
+        for status in statuses {
+            let state = match status.applied_at {
+                Some(applied_at) => chrono::DateTime::from_timestamp(applied_at, 0)
+                    .map(|at| format!("applied {}", at.format("%Y-%m-%d %H:%M:%S UTC")))
+                    .unwrap_or_else(|| "applied".to_string()),
+                None => "pending".to_string(),
+            };
+            println!("{:<36} {:<12} {:<28} {}", status.id, status.store, state, status.description);
+        }
+        return Ok(());
+    }
+
+    let outcomes = admin::run_migrations(data_dir)?;
+    if outcomes.is_empty() {
+        println!("No pending migrations.");
+    }
+    for outcome in outcomes {
+        println!(
+            "Applied {} to {}: {} record(s) changed",
+            outcome.id, outcome.store, outcome.changed
+        );
+    }
+    Ok(())
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.2} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.2} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.2} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
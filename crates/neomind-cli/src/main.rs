@@ -11,6 +11,7 @@ use neomind_agent::{LlmBackend, SessionManager};
 use neomind_core::config::{
     endpoints, env_vars, models, normalize_ollama_endpoint, normalize_openai_endpoint,
 };
+use neomind_core::secrets::RedactingMakeWriter;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::Layer;
@@ -19,17 +20,32 @@ use tracing_subscriber::Layer;
 // Clap command types now live in neomind_cli_ops::dispatch::commands
 use neomind_cli_ops::dispatch::commands::*;
 
+mod db;
 mod self_update;
+mod simulate;
 
 // Jemalloc global allocator (Linux only): glibc malloc's per-thread arenas
 // fragment over time and don't return freed memory to the OS (server RSS
 // climbed to 4-6 GB over days). jemalloc packs allocations tightly and
 // releases freed pages promptly. macOS/Windows use their own allocators
 // (not glibc) so they don't have this problem.
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "script-heap-limit")))]
 #[global_allocator]
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
+// With `script-heap-limit`, the allocator is wrapped in MeteredAlloc so
+// Script transforms get a heap cap, at the cost of a thread-local check on
+// every allocation.
+#[cfg(all(target_os = "linux", feature = "script-heap-limit"))]
+#[global_allocator]
+static GLOBAL: neomind_api::automation::MeteredAlloc<tikv_jemallocator::Jemalloc> =
+    neomind_api::automation::MeteredAlloc(tikv_jemallocator::Jemalloc);
+
+#[cfg(all(not(target_os = "linux"), feature = "script-heap-limit"))]
+#[global_allocator]
+static GLOBAL: neomind_api::automation::MeteredAlloc<std::alloc::System> =
+    neomind_api::automation::MeteredAlloc(std::alloc::System);
+
 
 // Custom runtime with increased worker threads for better concurrent performance
 // Default is num_cpus, but we use more to handle block_in_place alternatives
@@ -61,7 +77,9 @@ async fn main() -> Result<()> {
         }
     });
 
-    // For serve command: dual output (stdout + file); for others: stdout only
+    // For serve command: dual output (stdout + file); for others: stdout only.
+    // Every command masks secret values in its log output, since any of them
+    // may resolve credentials.
     let file_logging = matches!(args.command, Command::Serve { .. });
 
     if file_logging {
@@ -72,10 +90,12 @@ async fn main() -> Result<()> {
             tracing_subscriber::fmt::layer()
                 .json()
                 .with_target(true)
+                .with_writer(RedactingMakeWriter::new(std::io::stdout))
                 .with_filter(env_filter.clone())
                 .boxed()
         } else {
             tracing_subscriber::fmt::layer()
+                .with_writer(RedactingMakeWriter::new(std::io::stdout))
                 .with_target(false)
                 .with_thread_ids(false)
                 .with_file(false)
@@ -88,22 +108,26 @@ async fn main() -> Result<()> {
 
         let file_layer = tracing_subscriber::fmt::layer()
             .with_target(true)
-            .with_writer(file_appender)
+            .with_writer(RedactingMakeWriter::new(file_appender))
             .with_filter(env_filter);
 
-        tracing_subscriber::registry()
+        let registry = tracing_subscriber::registry()
             .with(stdout_layer)
-            .with(file_layer)
-            .init();
+            .with(file_layer);
+        #[cfg(feature = "otel")]
+        let registry = registry.with(neomind_api::telemetry::otel_layer());
+        registry.init();
     } else if json_logging {
         tracing_subscriber::fmt()
             .json()
             .with_env_filter(env_filter)
             .with_target(true)
+            .with_writer(RedactingMakeWriter::new(std::io::stdout))
             .init();
     } else {
         tracing_subscriber::fmt()
             .with_env_filter(env_filter)
+            .with_writer(RedactingMakeWriter::new(std::io::stdout))
             .with_target(false)
             .with_thread_ids(false)
             .with_file(false)
@@ -134,7 +158,9 @@ async fn main() -> Result<()> {
         Command::CheckUpdate => run_check_update().await,
         Command::Upgrade { version, yes } => self_update::run_upgrade(version, yes).await,
         Command::Uninstall { purge, yes } => self_update::run_uninstall(purge, yes).await,
+        Command::Simulate(sim_args) => simulate::run_simulate(sim_args).await,
         Command::ApiKey { key_cmd } => run_api_key_cmd(key_cmd).await,
+        Command::Db { db_cmd } => db::run_db_cmd(db_cmd),
         Command::Llm { llm_cmd } => print_result(
             neomind_cli_ops::dispatch::handlers::run_llm_cmd(llm_cmd).await,
         ),
@@ -168,6 +194,9 @@ async fn main() -> Result<()> {
         Command::Connector { connector_cmd } => print_result(
             neomind_cli_ops::dispatch::handlers::run_connector_cmd(connector_cmd).await,
         ),
+        Command::Knowledge { knowledge_cmd } => print_result(
+            neomind_cli_ops::dispatch::handlers::run_knowledge_cmd(knowledge_cmd).await,
+        ),
         Command::Settings { settings_cmd } => print_result(
             neomind_cli_ops::dispatch::handlers::run_settings_cmd(settings_cmd).await,
         ),
@@ -374,6 +403,19 @@ async fn run_chat(session_id: Option<String>) -> Result<()> {
                                 println!("[Tool {} failed]\n", tool);
                             }
                         }
+                        neomind_agent::AgentEvent::ToolProgress { tool, data, .. } => {
+                            println!("[Tool {}: {}]", tool, data);
+                        }
+                        neomind_agent::AgentEvent::AwaitingApproval {
+                            action_id,
+                            category,
+                            ..
+                        } => {
+                            println!(
+                                "\n[Waiting for approval of {} (action {})]",
+                                category, action_id
+                            );
+                        }
                         neomind_agent::AgentEvent::Error { message } => {
                             eprintln!("\nError: {}", message);
                         }
@@ -388,6 +430,9 @@ async fn run_chat(session_id: Option<String>) -> Result<()> {
                                 confidence.unwrap_or(0.0) * 100.0
                             );
                         }
+                        neomind_agent::AgentEvent::ContextStats { .. } => {
+                            // Context diagnostics are shown by the API only
+                        }
                         neomind_agent::AgentEvent::Plan { step, stage } => {
                             println!("[Plan: {} - {}]", stage, step);
                         }
@@ -606,7 +651,12 @@ async fn run_server(host: String, port: u16) -> Result<()> {
         }
     });
 
-    neomind_api::run(addr).await
+    let result = neomind_api::run(addr).await;
+
+    #[cfg(feature = "otel")]
+    neomind_api::telemetry::shutdown();
+
+    result
 }
 
 /// Clean up log files older than 7 days in data/logs/.
@@ -970,6 +1020,18 @@ async fn run_extension_cmd(cmd: ExtensionCommand) -> Result<()> {
             }
             unreachable!()
         }
+        ExtensionCommand::Sign { .. } => {
+            if let ExtensionCommand::Sign {
+                package,
+                key,
+                publisher,
+                output,
+            } = cmd
+            {
+                return sign_extension_package(&package, &key, &publisher, output);
+            }
+            unreachable!()
+        }
         ExtensionCommand::Create { .. } => {
             if let ExtensionCommand::Create {
                 name,
@@ -1183,6 +1245,15 @@ async fn show_extension_info(id_or_path: &str) -> Result<()> {
 
 /// Install an extension from .nep package.
 async fn install_extension(package: &str) -> Result<()> {
+    // URLs are downloaded and signature-checked by the server.
+    if package.starts_with("http://") || package.starts_with("https://") {
+        let client = neomind_cli_ops::ApiClient::new();
+        return print_result(Ok((
+            neomind_cli_ops::extension::install_extension_url(&client, package).await?,
+            neomind_cli_ops::types::OutputFormat::Human,
+        )));
+    }
+
     let source_path = std::path::PathBuf::from(package);
 
     if !source_path.exists() {
@@ -1213,6 +1284,32 @@ async fn install_extension(package: &str) -> Result<()> {
     Ok(())
 }
 
+/// Sign a .nep package with a publisher key.
+fn sign_extension_package(
+    package: &std::path::Path,
+    key_path: &std::path::Path,
+    publisher: &str,
+    output: Option<std::path::PathBuf>,
+) -> Result<()> {
+    use neomind_core::extension::signing;
+
+    let key = signing::decode_signing_key(&std::fs::read_to_string(key_path)?)
+        .map_err(|e| anyhow::anyhow!("{}: {}", key_path.display(), e))?;
+    let data = std::fs::read(package)?;
+    let signed = signing::sign_package(&data, publisher, &key)?;
+    let output = output.unwrap_or_else(|| package.to_path_buf());
+    std::fs::write(&output, signed)?;
+
+    println!("✅ Package signed by '{}'", publisher);
+    println!("   Output: {}", output.display());
+    println!("   Public key: {}", signing::public_key(&key));
+    println!();
+    println!("Servers accept the package once this key is added for '{}'", publisher);
+    println!("via PUT /api/extensions/signature-policy.");
+
+    Ok(())
+}
+
 /// Uninstall an extension.
 async fn uninstall_extension(id: &str) -> Result<()> {
     use std::fs;
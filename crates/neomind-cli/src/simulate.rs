@@ -0,0 +1,1250 @@
+//! `neomind simulate`: fake devices publishing telemetry to a running
+//! instance, so the full ingest stack can be exercised without hardware.
+//!
+//! Every simulated device sends a JSON uplink at a fixed rate, either to the
+//! MQTT broker on `device/{type}/{id}/uplink` or to the device webhook
+//! (`POST /api/devices/{id}/webhook`). Readings random-walk within a range
+//! per device type. Fault flags drop messages, send malformed JSON, inject
+//! out-of-range spikes and take devices offline for a while.
+//!
+//! With `--seed` every device generates the same readings on every run.
+//! `--record` writes each message sent to a JSON-lines file and `--replay`
+//! sends such a file again, byte for byte and with its original spacing,
+//! so a test against the rules engine or the time-series store can be
+//! repeated exactly.
+//!
+//! Progress lines report throughput and send-latency percentiles, which is
+//! what sizing edge hardware for a fleet needs. Over MQTT, latency is the
+//! time to hand a message to the client. Over HTTP, it is the full webhook
+//! round trip through ingestion.
+//!
+//! `--mode chat` and `--mode rules` load the agent and the rules engine
+//! instead: `-n` sessions each send `--message` to
+//! `POST /api/sessions/{id}/chat`, or `-n` workers each evaluate a rule with
+//! `POST /api/rules/{id}/test` and a random input. Every session or worker
+//! waits for its reply before sending the next request, at most `--rate` per
+//! second, and latency is the full round trip.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use neomind_cli_ops::dispatch::commands::SimulateArgs;
+use neomind_cli_ops::ApiClient;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rumqttc::{AsyncClient, MqttOptions, QoS};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::task::JoinSet;
+
+/// How a metric's value evolves from message to message.
+#[derive(Debug, Clone, Copy)]
+enum Signal {
+    /// Random walk of at most `step` per message, kept within `[min, max]`
+    Walk {
+        start: f64,
+        step: f64,
+        min: f64,
+        max: f64,
+    },
+    /// Monotonic total, growing by up to `step` per message
+    Counter { step: f64 },
+    /// Boolean flipping with probability `flip` per message
+    Toggle { flip: f64 },
+}
+
+#[derive(Debug)]
+struct MetricSpec {
+    name: &'static str,
+    display_name: &'static str,
+    unit: &'static str,
+    /// Reported as an integer
+    integer: bool,
+    signal: Signal,
+}
+
+impl MetricSpec {
+    fn data_type(&self) -> &'static str {
+        match self.signal {
+            Signal::Toggle { .. } => "Boolean",
+            _ if self.integer => "Integer",
+            _ => "Float",
+        }
+    }
+}
+
+/// A simulated device type.
+#[derive(Debug)]
+struct Profile {
+    device_type: &'static str,
+    name: &'static str,
+    metrics: &'static [MetricSpec],
+}
+
+const fn walk(start: f64, step: f64, min: f64, max: f64) -> Signal {
+    Signal::Walk {
+        start,
+        step,
+        min,
+        max,
+    }
+}
+
+const PROFILES: &[Profile] = &[
+    Profile {
+        device_type: "temperature_sensor",
+        name: "Simulated Temperature Sensor",
+        metrics: &[
+            MetricSpec {
+                name: "temperature",
+                display_name: "Temperature",
+                unit: "°C",
+                integer: false,
+                signal: walk(22.0, 0.3, -10.0, 45.0),
+            },
+            MetricSpec {
+                name: "humidity",
+                display_name: "Humidity",
+                unit: "%",
+                integer: false,
+                signal: walk(50.0, 1.0, 10.0, 95.0),
+            },
+            MetricSpec {
+                name: "battery",
+                display_name: "Battery",
+                unit: "%",
+                integer: true,
+                signal: walk(95.0, 0.2, 5.0, 100.0),
+            },
+        ],
+    },
+    Profile {
+        device_type: "env_sensor",
+        name: "Simulated Environment Sensor",
+        metrics: &[
+            MetricSpec {
+                name: "temperature",
+                display_name: "Temperature",
+                unit: "°C",
+                integer: false,
+                signal: walk(23.0, 0.2, -10.0, 45.0),
+            },
+            MetricSpec {
+                name: "humidity",
+                display_name: "Humidity",
+                unit: "%",
+                integer: false,
+                signal: walk(45.0, 1.0, 10.0, 95.0),
+            },
+            MetricSpec {
+                name: "co2",
+                display_name: "CO2",
+                unit: "ppm",
+                integer: true,
+                signal: walk(600.0, 25.0, 400.0, 2000.0),
+            },
+            MetricSpec {
+                name: "pm25",
+                display_name: "PM2.5",
+                unit: "µg/m³",
+                integer: false,
+                signal: walk(12.0, 1.5, 0.0, 150.0),
+            },
+        ],
+    },
+    Profile {
+        device_type: "power_meter",
+        name: "Simulated Power Meter",
+        metrics: &[
+            MetricSpec {
+                name: "voltage",
+                display_name: "Voltage",
+                unit: "V",
+                integer: false,
+                signal: walk(230.0, 1.0, 207.0, 253.0),
+            },
+            MetricSpec {
+                name: "current",
+                display_name: "Current",
+                unit: "A",
+                integer: false,
+                signal: walk(5.0, 0.4, 0.0, 32.0),
+            },
+            MetricSpec {
+                name: "energy",
+                display_name: "Energy",
+                unit: "kWh",
+                integer: false,
+                signal: Signal::Counter { step: 0.01 },
+            },
+        ],
+    },
+    Profile {
+        device_type: "switch",
+        name: "Simulated Switch",
+        metrics: &[
+            MetricSpec {
+                name: "state",
+                display_name: "State",
+                unit: "",
+                integer: false,
+                signal: Signal::Toggle { flip: 0.05 },
+            },
+            MetricSpec {
+                name: "rssi",
+                display_name: "RSSI",
+                unit: "dBm",
+                integer: true,
+                signal: walk(-60.0, 2.0, -95.0, -30.0),
+            },
+        ],
+    },
+];
+
+fn profile(device_type: &str) -> Option<&'static Profile> {
+    PROFILES.iter().find(|p| p.device_type == device_type)
+}
+
+fn uplink_topic(device_type: &str, device_id: &str) -> String {
+    format!("device/{}/{}/uplink", device_type, device_id)
+}
+
+/// What `neomind simulate` puts load on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Telemetry,
+    Chat,
+    Rules,
+}
+
+impl Mode {
+    fn parse(mode: &str) -> Result<Self> {
+        match mode {
+            "telemetry" => Ok(Self::Telemetry),
+            "chat" => Ok(Self::Chat),
+            "rules" => Ok(Self::Rules),
+            _ => bail!("--mode must be 'telemetry', 'chat' or 'rules'"),
+        }
+    }
+}
+
+/// Fault injection settings.
+#[derive(Debug, Clone)]
+struct Faults {
+    drop_rate: f64,
+    malformed_rate: f64,
+    spike_rate: f64,
+    offline_rate: f64,
+    offline: Duration,
+}
+
+/// What a device does on one tick.
+#[derive(Debug, PartialEq)]
+enum Tick {
+    Send(Value),
+    Malformed(String),
+    Dropped,
+    Offline,
+}
+
+/// One simulated device and its current readings.
+struct SimDevice {
+    device_id: String,
+    profile: &'static Profile,
+    values: Vec<f64>,
+    offline_until: Option<Instant>,
+    rng: StdRng,
+}
+
+impl SimDevice {
+    fn new(device_id: String, profile: &'static Profile, mut rng: StdRng) -> Self {
+        let values = profile
+            .metrics
+            .iter()
+            .map(|m| match m.signal {
+                Signal::Walk { start, step, .. } => start + rng.gen_range(-step..=step) * 5.0,
+                Signal::Counter { .. } => rng.gen_range(0.0..1000.0),
+                Signal::Toggle { .. } => f64::from(u8::from(rng.gen_bool(0.5))),
+            })
+            .collect();
+        Self {
+            device_id,
+            profile,
+            values,
+            offline_until: None,
+            rng,
+        }
+    }
+
+    fn topic(&self) -> String {
+        uplink_topic(self.profile.device_type, &self.device_id)
+    }
+
+    /// Advance every reading by one message.
+    fn step(&mut self) {
+        for (value, metric) in self.values.iter_mut().zip(self.profile.metrics) {
+            *value = match metric.signal {
+                Signal::Walk { step, min, max, .. } => {
+                    (*value + self.rng.gen_range(-step..=step)).clamp(min, max)
+                }
+                Signal::Counter { step } => *value + self.rng.gen_range(0.0..=step),
+                Signal::Toggle { flip } if self.rng.gen_bool(flip) => 1.0 - *value,
+                Signal::Toggle { .. } => *value,
+            };
+        }
+    }
+
+    /// The current readings; with `spike`, one ranged reading is replaced by
+    /// a value far outside its range.
+    fn payload(&mut self, spike: bool) -> Value {
+        let ranged: Vec<usize> = (0..self.values.len())
+            .filter(|&i| matches!(self.profile.metrics[i].signal, Signal::Walk { .. }))
+            .collect();
+        let spiked = (spike && !ranged.is_empty())
+            .then(|| ranged[self.rng.gen_range(0..ranged.len())]);
+
+        let mut payload = serde_json::Map::new();
+        for (i, (value, metric)) in self.values.iter().zip(self.profile.metrics).enumerate() {
+            let value = match metric.signal {
+                Signal::Walk { min, max, .. } if spiked == Some(i) => {
+                    json!(max + (max - min) * 5.0)
+                }
+                Signal::Toggle { .. } => json!(*value >= 0.5),
+                _ if metric.integer => json!(value.round() as i64),
+                _ => json!((value * 100.0).round() / 100.0),
+            };
+            payload.insert(metric.name.to_string(), value);
+        }
+        payload.insert("ts".to_string(), json!(chrono::Utc::now().timestamp()));
+        Value::Object(payload)
+    }
+
+    fn tick(&mut self, faults: &Faults, now: Instant) -> Tick {
+        if let Some(until) = self.offline_until {
+            if now < until {
+                return Tick::Offline;
+            }
+            self.offline_until = None;
+        }
+        if self.rng.gen_bool(faults.offline_rate) {
+            self.offline_until = Some(now + faults.offline);
+            return Tick::Offline;
+        }
+
+        self.step();
+        if self.rng.gen_bool(faults.drop_rate) {
+            return Tick::Dropped;
+        }
+        let spike = self.rng.gen_bool(faults.spike_rate);
+        let payload = self.payload(spike);
+        if self.rng.gen_bool(faults.malformed_rate) {
+            // Truncated mid-object, as a flaky radio link would deliver it
+            let text = payload.to_string();
+            return Tick::Malformed(text[..text.len() / 2].to_string());
+        }
+        Tick::Send(payload)
+    }
+}
+
+/// Where uplinks go.
+#[derive(Clone)]
+enum Sink {
+    Mqtt(AsyncClient),
+    Http {
+        client: reqwest::Client,
+        api_base: String,
+        token: Option<String>,
+    },
+}
+
+impl Sink {
+    async fn send(&self, device_id: &str, topic: String, body: String) -> Result<()> {
+        match self {
+            Sink::Mqtt(client) => {
+                client.publish(topic, QoS::AtLeastOnce, false, body).await?;
+            }
+            Sink::Http {
+                client,
+                api_base,
+                token,
+            } => {
+                let url = format!("{}/devices/{}/webhook", api_base, device_id);
+                let mut req = client
+                    .post(url)
+                    .header("Content-Type", "application/json")
+                    .body(body);
+                if let Some(token) = token {
+                    req = req.bearer_auth(token);
+                }
+                let resp = req.send().await?;
+                if !resp.status().is_success() {
+                    bail!("webhook returned {}", resp.status());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Latency buckets per doubling; a reported percentile is within about 9%
+/// of the exact value.
+const LATENCY_BUCKETS_PER_DOUBLING: f64 = 8.0;
+/// 32 doublings of a microsecond is over an hour.
+const LATENCY_BUCKETS: usize = 8 * 32;
+
+/// Histogram of successful send latencies, in logarithmic buckets.
+struct LatencyHistogram {
+    buckets: Vec<AtomicU64>,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: (0..LATENCY_BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn record(&self, latency: Duration) {
+        let micros = latency.as_micros().max(1) as f64;
+        let bucket = (micros.log2() * LATENCY_BUCKETS_PER_DOUBLING) as usize;
+        self.buckets[bucket.min(LATENCY_BUCKETS - 1)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The latency within which a fraction `q` of sends completed, or
+    /// `None` before the first send.
+    fn percentile(&self, q: f64) -> Option<Duration> {
+        let counts: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|b| b.load(Ordering::Relaxed))
+            .collect();
+        let total: u64 = counts.iter().sum();
+        let rank = ((q * total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in counts.into_iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                // Upper bound of the bucket
+                let exponent = (bucket + 1) as f64 / LATENCY_BUCKETS_PER_DOUBLING;
+                return Some(Duration::from_secs_f64(exponent.exp2() / 1e6));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    sent: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+    malformed: AtomicU64,
+    offline: AtomicU64,
+    latency: LatencyHistogram,
+}
+
+impl Stats {
+    fn summary(&self, elapsed: Duration) -> String {
+        let sent = self.sent.load(Ordering::Relaxed);
+        let throughput = sent as f64 / elapsed.as_secs_f64().max(0.001);
+        let ms = |q| match self.latency.percentile(q) {
+            Some(latency) => format!("{:.1}ms", latency.as_secs_f64() * 1000.0),
+            None => "-".to_string(),
+        };
+        format!(
+            "sent={} failed={} dropped={} malformed={} offline_ticks={} \
+             throughput={:.1}/s latency p50={} p95={} p99={}",
+            sent,
+            self.failed.load(Ordering::Relaxed),
+            self.dropped.load(Ordering::Relaxed),
+            self.malformed.load(Ordering::Relaxed),
+            self.offline.load(Ordering::Relaxed),
+            throughput,
+            ms(0.50),
+            ms(0.95),
+            ms(0.99),
+        )
+    }
+}
+
+/// One message of a `--record` file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Recorded {
+    /// Milliseconds after the start of the run
+    at_ms: u64,
+    device_id: String,
+    device_type: String,
+    body: String,
+}
+
+/// Writes the messages of a run to a `--record` file.
+struct Recorder {
+    file: Mutex<BufWriter<File>>,
+    start: Instant,
+}
+
+impl Recorder {
+    fn create(path: &Path) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        Ok(Self {
+            file: Mutex::new(BufWriter::new(file)),
+            start: Instant::now(),
+        })
+    }
+
+    fn record(&self, device: &SimDevice, body: &str) -> Result<()> {
+        let line = serde_json::to_string(&Recorded {
+            at_ms: self.start.elapsed().as_millis() as u64,
+            device_id: device.device_id.clone(),
+            device_type: device.profile.device_type.to_string(),
+            body: body.to_string(),
+        })?;
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<()> {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Read a `--record` file, in send order.
+fn load_recording(path: &Path) -> Result<Vec<Recorded>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut messages = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message: Recorded = serde_json::from_str(line)
+            .with_context(|| format!("{}:{}: not a recorded message", path.display(), i + 1))?;
+        if profile(&message.device_type).is_none() {
+            bail!(
+                "{}:{}: unknown device type '{}'",
+                path.display(),
+                i + 1,
+                message.device_type
+            );
+        }
+        messages.push(message);
+    }
+    if messages.is_empty() {
+        bail!("{} contains no messages", path.display());
+    }
+    // Concurrent devices may have written slightly out of order
+    messages.sort_by_key(|m| m.at_ms);
+    Ok(messages)
+}
+
+/// The devices a recording sends from, for `--register`.
+fn replay_devices(messages: &[Recorded]) -> Vec<SimDevice> {
+    let mut devices: Vec<SimDevice> = Vec::new();
+    for message in messages {
+        if devices.iter().any(|d| d.device_id == message.device_id) {
+            continue;
+        }
+        let profile = profile(&message.device_type).expect("validated");
+        devices.push(SimDevice::new(
+            message.device_id.clone(),
+            profile,
+            StdRng::seed_from_u64(0),
+        ));
+    }
+    devices
+}
+
+fn validate(args: &SimulateArgs) -> Result<Faults> {
+    if Mode::parse(&args.mode)? != Mode::Telemetry
+        && (args.record.is_some() || args.replay.is_some())
+    {
+        bail!("--record and --replay only apply to --mode telemetry");
+    }
+    if args.count == 0 {
+        bail!("--count must be at least 1");
+    }
+    let rate_ok = args.rate > 0.0 && args.rate <= 1000.0;
+    if !rate_ok {
+        bail!("--rate must be between 0 and 1000 messages per second");
+    }
+    if args.transport != "mqtt" && args.transport != "http" {
+        bail!("--transport must be 'mqtt' or 'http'");
+    }
+    if args.types.is_empty() {
+        bail!("--types must name at least one device type");
+    }
+    for device_type in &args.types {
+        if profile(device_type).is_none() {
+            let known: Vec<_> = PROFILES.iter().map(|p| p.device_type).collect();
+            bail!(
+                "Unknown device type '{}' (available: {})",
+                device_type,
+                known.join(", ")
+            );
+        }
+    }
+    let rates = [
+        ("--drop-rate", args.drop_rate),
+        ("--malformed-rate", args.malformed_rate),
+        ("--spike-rate", args.spike_rate),
+        ("--offline-rate", args.offline_rate),
+    ];
+    for (flag, rate) in rates {
+        if !(0.0..=1.0).contains(&rate) {
+            bail!("{} must be between 0 and 1", flag);
+        }
+    }
+    Ok(Faults {
+        drop_rate: args.drop_rate,
+        malformed_rate: args.malformed_rate,
+        spike_rate: args.spike_rate,
+        offline_rate: args.offline_rate,
+        offline: Duration::from_secs(args.offline_secs),
+    })
+}
+
+fn build_devices(args: &SimulateArgs) -> Vec<SimDevice> {
+    (0..args.count)
+        .map(|i| {
+            let profile = profile(&args.types[i % args.types.len()]).expect("validated");
+            let rng = match args.seed {
+                Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(i as u64)),
+                None => StdRng::from_entropy(),
+            };
+            let device_id = format!("{}-{}-{}", args.prefix, profile.device_type, i + 1);
+            SimDevice::new(device_id, profile, rng)
+        })
+        .collect()
+}
+
+/// Create the simulated device types and devices. Ones that already exist
+/// are kept as they are.
+async fn register(devices: &[SimDevice], adapter_type: &str) {
+    let client = ApiClient::new();
+
+    let mut registered_types = Vec::new();
+    for device in devices {
+        let profile = device.profile;
+        if registered_types.contains(&profile.device_type) {
+            continue;
+        }
+        registered_types.push(profile.device_type);
+
+        let metrics: Vec<Value> = profile
+            .metrics
+            .iter()
+            .map(|m| {
+                json!({
+                    "name": m.name,
+                    "display_name": m.display_name,
+                    "data_type": m.data_type(),
+                    "unit": m.unit,
+                })
+            })
+            .collect();
+        let body = json!({
+            "device_type": profile.device_type,
+            "name": profile.name,
+            "description": "Created by `neomind simulate`",
+            "categories": [],
+            "mode": "simple",
+            "metrics": metrics,
+            "uplink_samples": [],
+            "parameters": [],
+            "commands": [],
+        });
+        if let Err(e) = client.post("/device-types", &body).await {
+            println!("Device type {} not created: {}", profile.device_type, e);
+        }
+    }
+
+    let mut created = 0;
+    for device in devices {
+        let body = json!({
+            "device_id": device.device_id,
+            "name": device.device_id,
+            "device_type": device.profile.device_type,
+            "adapter_type": adapter_type,
+            "connection_config": {},
+        });
+        match client.post("/devices", &body).await {
+            Ok(_) => created += 1,
+            Err(e) => println!("Device {} not created: {}", device.device_id, e),
+        }
+    }
+    println!("Registered {} of {} devices", created, devices.len());
+}
+
+async fn connect(args: &SimulateArgs) -> Result<Sink> {
+    if args.transport == "http" {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .context("Failed to build HTTP client")?;
+        return Ok(Sink::Http {
+            client,
+            api_base: ApiClient::new().base_url().trim_end_matches('/').to_string(),
+            token: args.token.clone(),
+        });
+    }
+
+    let mut options = MqttOptions::new(
+        format!("neomind-simulate-{}", std::process::id()),
+        args.mqtt_host.clone(),
+        args.mqtt_port,
+    );
+    options.set_keep_alive(Duration::from_secs(30));
+    if let Some(username) = &args.mqtt_username {
+        options.set_credentials(username, args.mqtt_password.clone().unwrap_or_default());
+    }
+    let (client, mut eventloop) = AsyncClient::new(options, args.count.max(64));
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = eventloop.poll().await {
+                eprintln!("MQTT connection error: {}", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    });
+    Ok(Sink::Mqtt(client))
+}
+
+async fn deliver(sink: &Sink, device_id: &str, topic: String, body: String, stats: &Stats) {
+    let started = Instant::now();
+    match sink.send(device_id, topic, body).await {
+        Ok(()) => {
+            stats.latency.record(started.elapsed());
+            stats.sent.fetch_add(1, Ordering::Relaxed)
+        }
+        Err(e) => {
+            tracing::debug!(device_id = %device_id, error = %e, "Uplink failed");
+            stats.failed.fetch_add(1, Ordering::Relaxed)
+        }
+    };
+}
+
+async fn run_device(
+    mut device: SimDevice,
+    sink: Sink,
+    faults: Faults,
+    period: Duration,
+    stats: Arc<Stats>,
+    recorder: Option<Arc<Recorder>>,
+) {
+    // Spread devices over the first period instead of publishing in bursts
+    let offset = device.rng.gen_range(0.0..1.0);
+    tokio::time::sleep(period.mul_f64(offset)).await;
+    let mut ticker = tokio::time::interval(period);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        let body = match device.tick(&faults, Instant::now()) {
+            Tick::Send(payload) => payload.to_string(),
+            Tick::Malformed(text) => {
+                stats.malformed.fetch_add(1, Ordering::Relaxed);
+                text
+            }
+            Tick::Dropped => {
+                stats.dropped.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            Tick::Offline => {
+                stats.offline.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+        };
+        if let Some(recorder) = &recorder {
+            if let Err(e) = recorder.record(&device, &body) {
+                tracing::warn!(device_id = %device.device_id, error = %e, "Recording failed");
+            }
+        }
+        deliver(&sink, &device.device_id, device.topic(), body, &stats).await;
+    }
+}
+
+/// Send recorded messages in order, keeping their original spacing.
+async fn run_replay(messages: Vec<Recorded>, sink: Sink, stats: Arc<Stats>) {
+    let start = tokio::time::Instant::now();
+    for message in messages {
+        tokio::time::sleep_until(start + Duration::from_millis(message.at_ms)).await;
+        let topic = uplink_topic(&message.device_type, &message.device_id);
+        deliver(&sink, &message.device_id, topic, message.body, &stats).await;
+    }
+}
+
+/// POST to the API, counting the request and its round-trip latency.
+async fn timed_post(client: &ApiClient, path: &str, body: &Value, stats: &Stats) {
+    let started = Instant::now();
+    match client.post(path, body).await {
+        Ok(_) => {
+            stats.latency.record(started.elapsed());
+            stats.sent.fetch_add(1, Ordering::Relaxed)
+        }
+        Err(e) => {
+            tracing::debug!(path = %path, error = %e, "Request failed");
+            stats.failed.fetch_add(1, Ordering::Relaxed)
+        }
+    };
+}
+
+/// One chat session sending `message` once per period, waiting for each reply.
+async fn run_chat_session(
+    client: Arc<ApiClient>,
+    message: String,
+    period: Duration,
+    stats: Arc<Stats>,
+) {
+    let session_id = match client.post("/sessions", &json!({})).await {
+        Ok(resp) => match resp["data"]["sessionId"].as_str() {
+            Some(id) => id.to_string(),
+            None => {
+                eprintln!("Session not created: no sessionId in response");
+                return;
+            }
+        },
+        Err(e) => {
+            eprintln!("Session not created: {}", e);
+            return;
+        }
+    };
+    let path = format!("/sessions/{}/chat", session_id);
+    let body = json!({ "message": message });
+    let mut ticker = tokio::time::interval(period);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        timed_post(&client, &path, &body, &stats).await;
+    }
+}
+
+/// The IDs in a `GET /api/rules` response.
+fn listed_rule_ids(resp: &Value) -> Vec<String> {
+    resp["data"]["rules"]
+        .as_array()
+        .map(|rules| {
+            rules
+                .iter()
+                .filter_map(|r| r["id"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// One worker evaluating a random rule with a random input once per period.
+async fn run_rule_worker(
+    client: Arc<ApiClient>,
+    rule_ids: Arc<[String]>,
+    period: Duration,
+    stats: Arc<Stats>,
+    mut rng: StdRng,
+) {
+    // Spread workers over the first period, as devices are
+    let offset = rng.gen_range(0.0..1.0);
+    tokio::time::sleep(period.mul_f64(offset)).await;
+    let mut ticker = tokio::time::interval(period);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let rule_id = &rule_ids[rng.gen_range(0..rule_ids.len())];
+        let body = json!({ "value": (rng.gen_range(0.0..100.0f64) * 100.0).round() / 100.0 });
+        let path = format!("/rules/{}/test", rule_id);
+        timed_post(&client, &path, &body, &stats).await;
+    }
+}
+
+/// Print progress every 10 seconds until `--duration` passes, Ctrl-C is
+/// pressed or every task has finished.
+async fn report_until_done(
+    tasks: &mut JoinSet<()>,
+    duration: Option<u64>,
+    stats: &Stats,
+    started: Instant,
+) {
+    let deadline = async {
+        match duration {
+            Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+            None => std::future::pending().await,
+        }
+    };
+    let mut report = tokio::time::interval(Duration::from_secs(10));
+    report.tick().await;
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            _ = tokio::signal::ctrl_c() => break,
+            _ = report.tick() => println!("{}", stats.summary(started.elapsed())),
+            // Only a replay, or sessions that could not start, run out
+            Some(_) = tasks.join_next() => {
+                if tasks.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+    tasks.abort_all();
+}
+
+/// Run `--mode chat` or `--mode rules`.
+async fn run_api_load(args: &SimulateArgs, mode: Mode) -> Result<()> {
+    let client = Arc::new(ApiClient::new());
+    let api_base = client.base_url().trim_end_matches('/').to_string();
+    let period = Duration::from_secs_f64(1.0 / args.rate);
+    let stats = Arc::new(Stats::default());
+    let mut tasks = JoinSet::new();
+
+    if mode == Mode::Chat {
+        println!(
+            "Simulating {} chat session(s) at up to {} msg/s each -> {}/sessions/<id>/chat (Ctrl-C to stop)",
+            args.count, args.rate, api_base
+        );
+        for _ in 0..args.count {
+            tasks.spawn(run_chat_session(
+                client.clone(),
+                args.message.clone(),
+                period,
+                stats.clone(),
+            ));
+        }
+    } else {
+        let rule_ids: Arc<[String]> = if args.rules.is_empty() {
+            listed_rule_ids(&client.get("/rules").await?).into()
+        } else {
+            args.rules.clone().into()
+        };
+        if rule_ids.is_empty() {
+            bail!("No rules to evaluate; create one or pass --rules");
+        }
+        println!(
+            "Evaluating {} rule(s) with {} worker(s) at up to {} eval/s each -> {}/rules/<id>/test (Ctrl-C to stop)",
+            rule_ids.len(),
+            args.count,
+            args.rate,
+            api_base
+        );
+        for i in 0..args.count {
+            let rng = match args.seed {
+                Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(i as u64)),
+                None => StdRng::from_entropy(),
+            };
+            tasks.spawn(run_rule_worker(
+                client.clone(),
+                rule_ids.clone(),
+                period,
+                stats.clone(),
+                rng,
+            ));
+        }
+    }
+
+    let started = Instant::now();
+    report_until_done(&mut tasks, args.duration, &stats, started).await;
+    println!("Simulation finished: {}", stats.summary(started.elapsed()));
+    Ok(())
+}
+
+/// Run `neomind simulate`.
+pub async fn run_simulate(args: SimulateArgs) -> Result<()> {
+    let faults = validate(&args)?;
+    let mode = Mode::parse(&args.mode)?;
+    if mode != Mode::Telemetry {
+        return run_api_load(&args, mode).await;
+    }
+    let replay = args.replay.as_deref().map(load_recording).transpose()?;
+    let devices = match &replay {
+        Some(messages) => replay_devices(messages),
+        None => build_devices(&args),
+    };
+
+    if args.register {
+        let adapter_type = if args.transport == "http" {
+            "webhook"
+        } else {
+            "mqtt"
+        };
+        register(&devices, adapter_type).await;
+    }
+
+    let sink = connect(&args).await?;
+    let target = match &sink {
+        Sink::Mqtt(_) => format!("mqtt://{}:{}", args.mqtt_host, args.mqtt_port),
+        Sink::Http { api_base, .. } => format!("{}/devices/<id>/webhook", api_base),
+    };
+    match &replay {
+        Some(messages) => println!(
+            "Replaying {} message(s) from {} device(s) -> {} (Ctrl-C to stop)",
+            messages.len(),
+            devices.len(),
+            target
+        ),
+        None => println!(
+            "Simulating {} device(s) at {} msg/s each -> {} (Ctrl-C to stop)",
+            devices.len(),
+            args.rate,
+            target
+        ),
+    }
+
+    let recorder = match &args.record {
+        Some(path) => Some(Arc::new(Recorder::create(path)?)),
+        None => None,
+    };
+    let period = Duration::from_secs_f64(1.0 / args.rate);
+    let stats = Arc::new(Stats::default());
+    let started = Instant::now();
+    let mut tasks = JoinSet::new();
+    match replay {
+        Some(messages) => {
+            tasks.spawn(run_replay(messages, sink.clone(), stats.clone()));
+        }
+        None => {
+            for device in devices {
+                tasks.spawn(run_device(
+                    device,
+                    sink.clone(),
+                    faults.clone(),
+                    period,
+                    stats.clone(),
+                    recorder.clone(),
+                ));
+            }
+        }
+    }
+
+    report_until_done(&mut tasks, args.duration, &stats, started).await;
+
+    if let (Some(recorder), Some(path)) = (&recorder, &args.record) {
+        recorder.finish()?;
+        println!("Recorded messages to {}", path.display());
+    }
+
+    if let Sink::Mqtt(client) = &sink {
+        let _ = client.disconnect().await;
+    }
+    println!("Simulation finished: {}", stats.summary(started.elapsed()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use neomind_cli_ops::dispatch::commands::{Args, Command};
+
+    fn parse(argv: &[&str]) -> SimulateArgs {
+        let argv = ["neomind", "simulate"].iter().chain(argv);
+        match Args::try_parse_from(argv).unwrap().command {
+            Command::Simulate(args) => args,
+            other => panic!("parsed {:?}", other),
+        }
+    }
+
+    fn no_faults() -> Faults {
+        Faults {
+            drop_rate: 0.0,
+            malformed_rate: 0.0,
+            spike_rate: 0.0,
+            offline_rate: 0.0,
+            offline: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let args = parse(&["--types", "env_sensor,switch", "--drop-rate", "0.2"]);
+        assert_eq!(validate(&args).unwrap().drop_rate, 0.2);
+        assert!(validate(&parse(&["--types", "toaster"])).is_err());
+        assert!(validate(&parse(&["--transport", "coap"])).is_err());
+        assert!(validate(&parse(&["--rate", "0"])).is_err());
+        assert!(validate(&parse(&["--offline-rate", "1.5"])).is_err());
+    }
+
+    #[test]
+    fn test_load_modes() {
+        assert_eq!(Mode::parse("chat").unwrap(), Mode::Chat);
+        assert_eq!(Mode::parse("rules").unwrap(), Mode::Rules);
+        assert!(validate(&parse(&["--mode", "chat", "-n", "4"])).is_ok());
+        assert!(validate(&parse(&["--mode", "storm"])).is_err());
+        assert!(validate(&parse(&["--mode", "rules", "--record", "run.jsonl"])).is_err());
+
+        let args = parse(&["--mode", "rules", "--rules", "r1,r2"]);
+        assert_eq!(args.rules, vec!["r1", "r2"]);
+
+        let listed = json!({
+            "success": true,
+            "data": {"rules": [{"id": "a", "name": "A"}, {"id": "b"}, {"name": "no id"}]},
+        });
+        assert_eq!(listed_rule_ids(&listed), vec!["a", "b"]);
+        assert!(listed_rule_ids(&json!({"data": {}})).is_empty());
+    }
+
+    #[test]
+    fn test_devices_round_robin_and_seeded() {
+        let args = parse(&["-n", "3", "--types", "power_meter,switch", "--seed", "7"]);
+        let devices = build_devices(&args);
+        let ids: Vec<_> = devices.iter().map(|d| d.device_id.as_str()).collect();
+        assert_eq!(ids, vec!["sim-power_meter-1", "sim-switch-2", "sim-power_meter-3"]);
+        assert_eq!(devices[1].topic(), "device/switch/sim-switch-2/uplink");
+
+        let again = build_devices(&args);
+        assert_eq!(devices[0].values, again[0].values);
+    }
+
+    #[test]
+    fn test_seeded_devices_repeat_their_stream() {
+        let args = parse(&["-n", "2", "--types", "env_sensor,power_meter", "--seed", "42"]);
+        let stream = || {
+            let faults = Faults {
+                spike_rate: 0.1,
+                drop_rate: 0.1,
+                ..no_faults()
+            };
+            let now = Instant::now();
+            let mut ticks = Vec::new();
+            for mut device in build_devices(&args) {
+                for _ in 0..50 {
+                    ticks.push(match device.tick(&faults, now) {
+                        // The timestamp is the wall clock, not part of the stream
+                        Tick::Send(mut payload) => {
+                            payload.as_object_mut().unwrap().remove("ts");
+                            Tick::Send(payload)
+                        }
+                        tick => tick,
+                    });
+                }
+            }
+            ticks
+        };
+        assert_eq!(stream(), stream());
+    }
+
+    #[test]
+    fn test_record_and_load_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+        let args = parse(&["-n", "2", "--types", "switch", "--seed", "3"]);
+        let devices = build_devices(&args);
+
+        let recorder = Recorder::create(&path).unwrap();
+        recorder.record(&devices[1], r#"{"state":true}"#).unwrap();
+        recorder.record(&devices[0], r#"{"state":false}"#).unwrap();
+        recorder.record(&devices[1], r#"{"state":fal"#).unwrap();
+        recorder.finish().unwrap();
+
+        let messages = load_recording(&path).unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].device_id, "sim-switch-2");
+        assert_eq!(messages[0].body, r#"{"state":true}"#);
+        // Malformed payloads are replayed as they were sent
+        assert_eq!(messages[2].body, r#"{"state":fal"#);
+        assert!(messages.windows(2).all(|w| w[0].at_ms <= w[1].at_ms));
+
+        let ids: Vec<_> = replay_devices(&messages)
+            .into_iter()
+            .map(|d| d.device_id)
+            .collect();
+        assert_eq!(ids, vec!["sim-switch-2", "sim-switch-1"]);
+
+        std::fs::write(&path, "{\"at_ms\": 0}\n").unwrap();
+        assert!(load_recording(&path).is_err());
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let stats = Stats::default();
+        assert!(stats.latency.percentile(0.5).is_none());
+        assert!(stats.summary(Duration::from_secs(1)).contains("p50=-"));
+
+        for _ in 0..90 {
+            stats.latency.record(Duration::from_millis(2));
+        }
+        for _ in 0..10 {
+            stats.latency.record(Duration::from_millis(200));
+        }
+        let within = |q, expected: f64| {
+            let ms = stats.latency.percentile(q).unwrap().as_secs_f64() * 1000.0;
+            assert!(
+                ms >= expected && ms <= expected * 1.1,
+                "p{} = {}ms, expected ~{}ms",
+                q,
+                ms,
+                expected
+            );
+        };
+        within(0.5, 2.0);
+        within(0.9, 2.0);
+        within(0.95, 200.0);
+        within(0.99, 200.0);
+
+        stats.sent.store(100, Ordering::Relaxed);
+        let summary = stats.summary(Duration::from_secs(4));
+        assert!(summary.contains("throughput=25.0/s"), "{}", summary);
+    }
+
+    #[test]
+    fn test_tick_payloads_and_faults() {
+        let args = parse(&["--types", "env_sensor", "--seed", "1"]);
+        let mut device = build_devices(&args).remove(0);
+        let now = Instant::now();
+
+        let Tick::Send(payload) = device.tick(&no_faults(), now) else {
+            panic!("expected a payload");
+        };
+        for name in ["temperature", "humidity", "co2", "pm25", "ts"] {
+            assert!(payload.get(name).is_some(), "missing {}", name);
+        }
+        assert!(payload["co2"].is_i64());
+        let co2 = payload["co2"].as_f64().unwrap();
+        assert!((400.0..=2000.0).contains(&co2));
+
+        let spiky = Faults {
+            spike_rate: 1.0,
+            ..no_faults()
+        };
+        let Tick::Send(payload) = device.tick(&spiky, now) else {
+            panic!("expected a payload");
+        };
+        let out_of_range = device.profile.metrics.iter().any(|m| match m.signal {
+            Signal::Walk { max, .. } => payload[m.name].as_f64().unwrap() > max,
+            _ => false,
+        });
+        assert!(out_of_range);
+
+        let malformed = Faults {
+            malformed_rate: 1.0,
+            ..no_faults()
+        };
+        let Tick::Malformed(text) = device.tick(&malformed, now) else {
+            panic!("expected a malformed payload");
+        };
+        assert!(serde_json::from_str::<Value>(&text).is_err());
+
+        let dropping = Faults {
+            drop_rate: 1.0,
+            ..no_faults()
+        };
+        assert_eq!(device.tick(&dropping, now), Tick::Dropped);
+
+        let flaky = Faults {
+            offline_rate: 1.0,
+            ..no_faults()
+        };
+        assert_eq!(device.tick(&flaky, now), Tick::Offline);
+        // Stays offline until the outage ends, even without further faults
+        assert_eq!(device.tick(&no_faults(), now), Tick::Offline);
+        let later = now + Duration::from_secs(61);
+        assert!(matches!(device.tick(&no_faults(), later), Tick::Send(_)));
+    }
+}
@@ -0,0 +1,82 @@
+//! Tests for the `db` command.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Test db help lists the subcommands.
+#[test]
+fn test_db_help() {
+    let mut cmd = Command::cargo_bin("neomind").unwrap();
+    cmd.arg("db").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("inspect"))
+        .stdout(predicate::str::contains("compact"))
+        .stdout(predicate::str::contains("export"))
+        .stdout(predicate::str::contains("import"))
+        .stdout(predicate::str::contains("migrate"));
+}
+
+/// Test inspect fails on a data directory without stores.
+#[test]
+fn test_db_inspect_empty_data_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cmd = Command::cargo_bin("neomind").unwrap();
+    cmd.arg("db").arg("inspect").arg("--data-dir").arg(dir.path());
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No .redb stores found"));
+}
+
+/// Test an import can be inspected and exported again.
+#[test]
+fn test_db_import_inspect_export() {
+    let dir = tempfile::tempdir().unwrap();
+    let data_dir = dir.path().join("data");
+    let file = dir.path().join("devices.json");
+    let export = r#"{
+        "format_version": 1,
+        "store": "devices",
+        "exported_at": 0,
+        "tables": [{
+            "name": "devices",
+            "key_type": "str",
+            "value_type": "bytes",
+            "entries": [{ "key": "sensor-1", "value": { "name": "Sensor 1" } }]
+        }]
+    }"#;
+    std::fs::write(&file, export).unwrap();
+
+    Command::cargo_bin("neomind")
+        .unwrap()
+        .args(["db", "import"])
+        .arg(&file)
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported devices: 1 table(s), 1 entries"));
+
+    Command::cargo_bin("neomind")
+        .unwrap()
+        .args(["db", "inspect", "devices"])
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ENTRIES"));
+
+    let output = dir.path().join("export");
+    Command::cargo_bin("neomind")
+        .unwrap()
+        .args(["db", "export", "--output"])
+        .arg(&output)
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .assert()
+        .success();
+    let exported = std::fs::read_to_string(output.join("devices.json")).unwrap();
+    assert!(exported.contains("Sensor 1"));
+}
@@ -2,6 +2,7 @@
 mod agent_test;
 mod chat_test;
 mod dashboard_test;
+mod db_test;
 mod device_test;
 mod list_models_test;
 mod message_test;
@@ -9,5 +10,6 @@ mod plugin_test;
 mod prompt_test;
 mod rule_test;
 mod serve_test;
+mod simulate_test;
 mod transform_test;
 mod widget_test;
@@ -0,0 +1,56 @@
+//! Tests for the `simulate` command.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Test simulate help lists the device and fault options.
+#[test]
+fn test_simulate_help() {
+    let mut cmd = Command::cargo_bin("neomind").unwrap();
+    cmd.arg("simulate").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--count"))
+        .stdout(predicate::str::contains("--types"))
+        .stdout(predicate::str::contains("--rate"))
+        .stdout(predicate::str::contains("--transport"))
+        .stdout(predicate::str::contains("--drop-rate"))
+        .stdout(predicate::str::contains("--offline-rate"));
+}
+
+/// Test that an unknown device type is rejected before connecting.
+#[test]
+fn test_simulate_unknown_type_rejected() {
+    let mut cmd = Command::cargo_bin("neomind").unwrap();
+    cmd.arg("simulate").arg("--types").arg("toaster");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown device type 'toaster'"));
+}
+
+/// Test that fault probabilities outside 0..=1 are rejected.
+#[test]
+fn test_simulate_invalid_fault_rate_rejected() {
+    let mut cmd = Command::cargo_bin("neomind").unwrap();
+    cmd.arg("simulate").arg("--drop-rate").arg("2");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--drop-rate must be between 0 and 1"));
+}
+
+/// Test that a missing replay file is reported before connecting.
+#[test]
+fn test_simulate_missing_replay_file_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cmd = Command::cargo_bin("neomind").unwrap();
+    cmd.arg("simulate")
+        .arg("--replay")
+        .arg(dir.path().join("missing.jsonl"));
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to read"));
+}
@@ -0,0 +1,385 @@
+//! Fault injection for resilience testing (feature `chaos`).
+//!
+//! A [`ChaosController`] holds the faults to inject (added latency, a random
+//! error rate and a simulated disconnect) and can be changed while the system
+//! is running. Wrappers consult it before every call they forward:
+//! [`ChaosLlmRuntime`] for LLM backends and `neomind_devices::chaos::ChaosAdapter`
+//! for device adapters. One controller can drive several wrappers.
+//!
+//! ```ignore
+//! let chaos = ChaosController::with_seed(7);
+//! let llm: Arc<dyn LlmRuntime> = Arc::new(ChaosLlmRuntime::new(backend, chaos.clone()));
+//!
+//! chaos.set_error_rate(0.3); // 30% of calls fail with a 503
+//! chaos.disconnect();        // every call fails until `reconnect()`
+//! ```
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+
+use crate::llm::backend::{
+    BackendCapabilities, BackendId, BackendMetrics, LlmError, LlmInput, LlmOutput, LlmRuntime,
+    StreamChunk,
+};
+
+/// Faults applied to every wrapped call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChaosConfig {
+    /// Delay added before each call.
+    pub latency: Duration,
+    /// Extra random delay, uniform in `0..=jitter`.
+    pub jitter: Duration,
+    /// Fraction of calls (0.0 to 1.0) that fail.
+    pub error_rate: f64,
+    /// While set, every call fails as if the peer were unreachable.
+    pub disconnected: bool,
+}
+
+/// A fault injected into one call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChaosFault {
+    /// Random failure drawn from the error rate.
+    Error,
+    /// The wrapped component is disconnected.
+    Disconnected,
+}
+
+impl fmt::Display for ChaosFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "chaos: injected error"),
+            Self::Disconnected => write!(f, "chaos: disconnected"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ChaosState {
+    config: ChaosConfig,
+    rng: u64,
+    calls: u64,
+    faults: u64,
+}
+
+impl ChaosState {
+    /// SplitMix64, so a seeded controller replays the same faults.
+    fn next_u64(&mut self) -> u64 {
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Runtime handle for the faults injected by chaos wrappers.
+///
+/// Clones share state, so a test keeps one clone and hands the others to
+/// the wrappers it builds.
+#[derive(Debug, Clone, Default)]
+pub struct ChaosController {
+    state: Arc<Mutex<ChaosState>>,
+}
+
+impl ChaosController {
+    /// Controller with no faults configured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Controller whose random draws repeat for the same seed.
+    pub fn with_seed(seed: u64) -> Self {
+        let controller = Self::default();
+        controller.state.lock().rng = seed;
+        controller
+    }
+
+    pub fn config(&self) -> ChaosConfig {
+        self.state.lock().config.clone()
+    }
+
+    pub fn set_config(&self, config: ChaosConfig) {
+        self.state.lock().config = config;
+    }
+
+    pub fn set_latency(&self, latency: Duration, jitter: Duration) {
+        let mut state = self.state.lock();
+        state.config.latency = latency;
+        state.config.jitter = jitter;
+    }
+
+    /// Fail this fraction of calls; clamped to `0.0..=1.0`.
+    pub fn set_error_rate(&self, rate: f64) {
+        self.state.lock().config.error_rate = rate.clamp(0.0, 1.0);
+    }
+
+    pub fn disconnect(&self) {
+        self.state.lock().config.disconnected = true;
+    }
+
+    pub fn reconnect(&self) {
+        self.state.lock().config.disconnected = false;
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        self.state.lock().config.disconnected
+    }
+
+    /// Stop injecting faults. Counters are kept.
+    pub fn reset(&self) {
+        self.state.lock().config = ChaosConfig::default();
+    }
+
+    /// Calls seen by all wrappers sharing this controller.
+    pub fn calls(&self) -> u64 {
+        self.state.lock().calls
+    }
+
+    /// Calls that had a fault injected.
+    pub fn faults(&self) -> u64 {
+        self.state.lock().faults
+    }
+
+    /// Apply the configured faults to one call: wait out the latency, then
+    /// return the fault to inject, if any.
+    pub async fn inject(&self) -> Result<(), ChaosFault> {
+        let (delay, fault) = {
+            let mut state = self.state.lock();
+            state.calls += 1;
+            let mut delay = state.config.latency;
+            let ChaosConfig {
+                jitter,
+                error_rate,
+                disconnected,
+                ..
+            } = state.config;
+            if !jitter.is_zero() {
+                delay += jitter.mul_f64(state.next_f64());
+            }
+            let fault = if disconnected {
+                Some(ChaosFault::Disconnected)
+            } else if error_rate > 0.0 && state.next_f64() < error_rate {
+                Some(ChaosFault::Error)
+            } else {
+                None
+            };
+            if fault.is_some() {
+                state.faults += 1;
+            }
+            (delay, fault)
+        };
+
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        match fault {
+            Some(fault) => Err(fault),
+            None => Ok(()),
+        }
+    }
+}
+
+/// [`LlmRuntime`] wrapper that injects faults from a [`ChaosController`].
+///
+/// Injected errors are `503` API errors and a disconnect surfaces as a
+/// network error, so both are treated as transient by failover and retry
+/// logic. Stream chunks read after a disconnect fail too.
+pub struct ChaosLlmRuntime {
+    inner: Arc<dyn LlmRuntime>,
+    chaos: ChaosController,
+}
+
+impl ChaosLlmRuntime {
+    pub fn new(inner: Arc<dyn LlmRuntime>, chaos: ChaosController) -> Self {
+        Self { inner, chaos }
+    }
+
+    pub fn controller(&self) -> &ChaosController {
+        &self.chaos
+    }
+
+    async fn inject(&self) -> Result<(), LlmError> {
+        self.chaos.inject().await.map_err(llm_error)
+    }
+}
+
+fn llm_error(fault: ChaosFault) -> LlmError {
+    match fault {
+        ChaosFault::Error => LlmError::Api {
+            status: 503,
+            body: fault.to_string(),
+        },
+        ChaosFault::Disconnected => LlmError::Network(fault.to_string()),
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmRuntime for ChaosLlmRuntime {
+    fn backend_id(&self) -> BackendId {
+        self.inner.backend_id()
+    }
+
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    async fn is_available(&self) -> bool {
+        !self.chaos.is_disconnected() && self.inner.is_available().await
+    }
+
+    async fn warmup(&self) -> Result<(), LlmError> {
+        self.inject().await?;
+        self.inner.warmup().await
+    }
+
+    async fn generate(&self, input: LlmInput) -> Result<LlmOutput, LlmError> {
+        self.inject().await?;
+        self.inner.generate(input).await
+    }
+
+    async fn generate_stream(
+        &self,
+        input: LlmInput,
+    ) -> Result<Pin<Box<dyn Stream<Item = StreamChunk> + Send>>, LlmError> {
+        self.inject().await?;
+        let stream = self.inner.generate_stream(input).await?;
+        let chaos = self.chaos.clone();
+        Ok(Box::pin(stream.map(move |chunk| {
+            if chaos.is_disconnected() {
+                Err(llm_error(ChaosFault::Disconnected))
+            } else {
+                chunk
+            }
+        })))
+    }
+
+    async fn generate_to_completion(&self, input: LlmInput) -> Result<LlmOutput, LlmError> {
+        self.inject().await?;
+        self.inner.generate_to_completion(input).await
+    }
+
+    fn max_context_length(&self) -> usize {
+        self.inner.max_context_length()
+    }
+
+    fn estimate_tokens(&self, text: &str) -> usize {
+        self.inner.estimate_tokens(text)
+    }
+
+    fn supports_multimodal(&self) -> bool {
+        self.inner.supports_multimodal()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn metrics(&self) -> BackendMetrics {
+        self.inner.metrics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::backend::FinishReason;
+
+    struct EchoBackend;
+
+    #[async_trait::async_trait]
+    impl LlmRuntime for EchoBackend {
+        fn backend_id(&self) -> BackendId {
+            BackendId::new(BackendId::MOCK)
+        }
+
+        fn model_name(&self) -> &str {
+            "echo"
+        }
+
+        async fn generate(&self, _input: LlmInput) -> Result<LlmOutput, LlmError> {
+            Ok(LlmOutput {
+                text: "ok".to_string(),
+                finish_reason: FinishReason::Stop,
+                usage: None,
+                thinking: None,
+                tool_calls: None,
+            })
+        }
+
+        async fn generate_stream(
+            &self,
+            _input: LlmInput,
+        ) -> Result<Pin<Box<dyn Stream<Item = StreamChunk> + Send>>, LlmError> {
+            let chunks: Vec<StreamChunk> = vec![Ok(("a".into(), false)), Ok(("b".into(), false))];
+            Ok(Box::pin(futures::stream::iter(chunks)))
+        }
+
+        fn max_context_length(&self) -> usize {
+            4096
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_rate_is_seeded_and_counted() {
+        let outcomes = |seed| async move {
+            let chaos = ChaosController::with_seed(seed);
+            chaos.set_error_rate(0.5);
+            let mut failed = Vec::new();
+            for _ in 0..64 {
+                failed.push(chaos.inject().await.is_err());
+            }
+            (failed, chaos.calls(), chaos.faults())
+        };
+
+        let (first, calls, faults) = outcomes(42).await;
+        assert_eq!(first, outcomes(42).await.0);
+        assert_eq!(calls, 64);
+        assert_eq!(faults, first.iter().filter(|f| **f).count() as u64);
+        assert!(faults > 10 && faults < 54, "{} faults", faults);
+
+        let chaos = ChaosController::new();
+        chaos.set_error_rate(7.0);
+        assert_eq!(chaos.config().error_rate, 1.0);
+        chaos.reset();
+        assert!(chaos.inject().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_llm_wrapper_faults() {
+        let chaos = ChaosController::new();
+        let llm = ChaosLlmRuntime::new(Arc::new(EchoBackend), chaos.clone());
+        assert_eq!(llm.generate(LlmInput::new("hi")).await.unwrap().text, "ok");
+
+        chaos.set_error_rate(1.0);
+        let err = llm.generate(LlmInput::new("hi")).await.unwrap_err();
+        assert!(matches!(err, LlmError::Api { status: 503, .. }));
+
+        chaos.reset();
+        chaos.set_latency(Duration::from_millis(20), Duration::ZERO);
+        let started = std::time::Instant::now();
+        llm.generate(LlmInput::new("hi")).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        // A disconnect during a stream fails the remaining chunks
+        chaos.reset();
+        let mut stream = llm.generate_stream(LlmInput::new("hi")).await.unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap().0, "a");
+        chaos.disconnect();
+        assert!(matches!(stream.next().await, Some(Err(LlmError::Network(_)))));
+        assert!(!llm.is_available().await);
+        assert!(llm.generate(LlmInput::new("hi")).await.is_err());
+
+        chaos.reconnect();
+        assert!(llm.is_available().await);
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! 这个模块提供了项目中所有配置的默认值和辅助函数，
 //! 避免在多个 crate 中重复定义相同的常量和逻辑。
+//!
+//! 可在运行时修改的配置项由 [`service`] 统一管理。
+
+pub mod service;
 
 /// LLM 提供商类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -103,11 +107,21 @@ pub mod agent {
     pub const DEFAULT_CONCURRENT_LIMIT: usize = 3;
     /// 默认上下文选择器 token 预算
     pub const DEFAULT_CONTEXT_SELECTOR_TOKENS: usize = 4000;
+    /// 默认待审批操作有效期（秒）
+    pub const DEFAULT_APPROVAL_TTL_SECS: u64 = 300;
+    /// 默认提示注入处理方式（off / flag / strip）
+    pub const DEFAULT_INJECTION_DETECTION: &str = "flag";
+    /// 默认提示注入判定阈值
+    pub const DEFAULT_INJECTION_THRESHOLD: f32 = 0.5;
 }
 
 /// Agent 配置环境变量
+///
+/// 读取函数经过 [`super::service::global`]：环境变量优先，其次是运行时保存的值，
+/// 最后是默认值。
 pub mod agent_env_vars {
     use super::agent;
+    use super::service::{global, keys};
 
     pub const MAX_CONTEXT_TOKENS: &str = "AGENT_MAX_CONTEXT_TOKENS";
     pub const TEMPERATURE: &str = "AGENT_TEMPERATURE";
@@ -117,56 +131,156 @@ pub mod agent_env_vars {
     pub const CONTEXT_SELECTOR_TOKENS: &str = "AGENT_CONTEXT_SELECTOR_TOKENS";
     /// LLM request timeout in seconds (for Ollama and OpenAI backends)
     pub const LLM_TIMEOUT_SECS: &str = "AGENT_LLM_TIMEOUT_SECS";
+    /// LLM 响应缓存有效期（秒），0 或未设置表示禁用缓存
+    pub const LLM_CACHE_TTL_SECS: &str = "AGENT_LLM_CACHE_TTL_SECS";
+    /// 语义缓存命中所需的最小余弦相似度；设置后启用语义缓存（使用知识库的嵌入模型）
+    pub const LLM_CACHE_SIMILARITY: &str = "AGENT_LLM_CACHE_SIMILARITY";
+    /// 需要人工审批的工具操作（逗号分隔，如 `device.control,rule.delete`）
+    pub const APPROVAL_REQUIRED: &str = "AGENT_APPROVAL_REQUIRED";
+    /// 待审批操作的有效期（秒）
+    pub const APPROVAL_TTL_SECS: &str = "AGENT_APPROVAL_TTL_SECS";
+    /// 批准时需要二次验证的工具操作（逗号分隔，格式同 `APPROVAL_REQUIRED`）
+    pub const APPROVAL_MFA_REQUIRED: &str = "AGENT_APPROVAL_MFA_REQUIRED";
+    /// 提示注入处理方式（off / flag / strip）
+    pub const INJECTION_DETECTION: &str = "AGENT_INJECTION_DETECTION";
+    /// 提示注入判定阈值（0-1）
+    pub const INJECTION_THRESHOLD: &str = "AGENT_INJECTION_THRESHOLD";
+    /// 是否用 LLM 复核可疑的用户消息
+    pub const INJECTION_LLM_CHECK: &str = "AGENT_INJECTION_LLM_CHECK";
+    /// 单次工具调用的超时时间（秒），未设置表示不限制
+    pub const TOOL_TIMEOUT_SECS: &str = "AGENT_TOOL_TIMEOUT_SECS";
+    /// 同时执行的工具调用数上限
+    pub const TOOL_MAX_CONCURRENT: &str = "AGENT_TOOL_MAX_CONCURRENT";
+    /// 工具输出的最大字节数，超出部分会被截断
+    pub const TOOL_MAX_OUTPUT_BYTES: &str = "AGENT_TOOL_MAX_OUTPUT_BYTES";
 
-    /// 从环境变量获取最大上下文 token 数，或返回默认值
+    /// 获取最大上下文 token 数，或返回默认值
     pub fn max_context_tokens() -> usize {
-        std::env::var(MAX_CONTEXT_TOKENS)
-            .ok()
-            .and_then(|s| s.parse().ok())
+        global()
+            .get_u64(keys::AGENT_MAX_CONTEXT_TOKENS)
+            .map(|v| v as usize)
             .unwrap_or(agent::DEFAULT_MAX_CONTEXT_TOKENS)
     }
 
-    /// 从环境变量获取温度，或返回默认值
+    /// 获取温度，或返回默认值
     pub fn temperature() -> f32 {
-        std::env::var(TEMPERATURE)
-            .ok()
-            .and_then(|s| s.parse().ok())
+        global()
+            .get_f64(keys::AGENT_TEMPERATURE)
+            .map(|v| v as f32)
             .unwrap_or(agent::DEFAULT_TEMPERATURE)
     }
 
-    /// 从环境变量获取 top-p，或返回默认值
+    /// 获取 top-p，或返回默认值
     pub fn top_p() -> f32 {
-        std::env::var(TOP_P)
-            .ok()
-            .and_then(|s| s.parse().ok())
+        global()
+            .get_f64(keys::AGENT_TOP_P)
+            .map(|v| v as f32)
             .unwrap_or(agent::DEFAULT_TOP_P)
     }
 
-    /// 从环境变量获取最大生成 token 数，或返回默认值
+    /// 获取最大生成 token 数，或返回默认值
     pub fn max_tokens() -> usize {
-        std::env::var(MAX_TOKENS)
-            .ok()
-            .and_then(|s| s.parse().ok())
+        global()
+            .get_u64(keys::AGENT_MAX_TOKENS)
+            .map(|v| v as usize)
             .unwrap_or(agent::DEFAULT_MAX_TOKENS)
     }
 
-    /// 从环境变量获取最大并发请求数，或返回默认值
+    /// 获取最大并发请求数，或返回默认值
     pub fn concurrent_limit() -> usize {
-        std::env::var(CONCURRENT_LIMIT)
-            .ok()
-            .and_then(|s| s.parse().ok())
+        global()
+            .get_u64(keys::AGENT_CONCURRENT_LIMIT)
+            .map(|v| v as usize)
             .unwrap_or(agent::DEFAULT_CONCURRENT_LIMIT)
     }
 
-    /// 从环境变量获取上下文选择器 token 预算，或返回默认值
+    /// 获取上下文选择器 token 预算，或返回默认值
     pub fn context_selector_tokens() -> usize {
-        std::env::var(CONTEXT_SELECTOR_TOKENS)
-            .ok()
-            .and_then(|s| s.parse().ok())
+        global()
+            .get_u64(keys::AGENT_CONTEXT_SELECTOR_TOKENS)
+            .map(|v| v as usize)
             .unwrap_or(agent::DEFAULT_CONTEXT_SELECTOR_TOKENS)
     }
 
-    /// 从环境变量获取 LLM 请求超时时间（秒），或返回默认值
+    /// 获取需要人工审批的工具操作列表，未配置时为空
+    pub fn approval_required() -> Vec<String> {
+        global()
+            .get(keys::AGENT_APPROVAL_REQUIRED)
+            .and_then(|v| v.as_str().map(str::to_string))
+            .map(|s| {
+                s.split(',')
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 获取批准前需要二次验证的高风险操作列表，默认为全部（`*`）
+    pub fn approval_mfa_required() -> Vec<String> {
+        global()
+            .get(keys::AGENT_APPROVAL_MFA_REQUIRED)
+            .and_then(|v| v.as_str().map(str::to_string))
+            .map(|s| {
+                s.split(',')
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 获取待审批操作的有效期（秒），或返回默认值
+    pub fn approval_ttl_secs() -> u64 {
+        global()
+            .get_u64(keys::AGENT_APPROVAL_TTL_SECS)
+            .unwrap_or(agent::DEFAULT_APPROVAL_TTL_SECS)
+    }
+
+    /// 获取提示注入处理方式，或返回默认值
+    pub fn injection_detection() -> String {
+        global()
+            .get(keys::AGENT_INJECTION_DETECTION)
+            .and_then(|v| v.as_str().map(|s| s.trim().to_ascii_lowercase()))
+            .unwrap_or_else(|| agent::DEFAULT_INJECTION_DETECTION.to_string())
+    }
+
+    /// 获取提示注入判定阈值，或返回默认值
+    pub fn injection_threshold() -> f32 {
+        global()
+            .get_f64(keys::AGENT_INJECTION_THRESHOLD)
+            .map(|v| v as f32)
+            .unwrap_or(agent::DEFAULT_INJECTION_THRESHOLD)
+    }
+
+    /// 是否用 LLM 复核可疑的用户消息
+    pub fn injection_llm_check() -> bool {
+        global()
+            .get(keys::AGENT_INJECTION_LLM_CHECK)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// 获取单次工具调用的超时时间（秒），未设置时返回 None
+    pub fn tool_timeout_secs() -> Option<u64> {
+        global().get_u64(keys::AGENT_TOOL_TIMEOUT_SECS)
+    }
+
+    /// 获取同时执行的工具调用数上限，未设置时返回 None
+    pub fn tool_max_concurrent() -> Option<usize> {
+        global()
+            .get_u64(keys::AGENT_TOOL_MAX_CONCURRENT)
+            .map(|v| v as usize)
+    }
+
+    /// 获取工具输出的最大字节数，未设置时返回 None
+    pub fn tool_max_output_bytes() -> Option<usize> {
+        global()
+            .get_u64(keys::AGENT_TOOL_MAX_OUTPUT_BYTES)
+            .map(|v| v as usize)
+    }
+
+    /// 获取 LLM 请求超时时间（秒），或返回默认值
     ///
     /// 默认值：
     /// - Ollama: 120 秒
@@ -174,9 +288,21 @@ pub mod agent_env_vars {
     ///
     /// 此环境变量会同时应用于两种后端
     pub fn llm_timeout_secs() -> Option<u64> {
-        std::env::var(LLM_TIMEOUT_SECS)
-            .ok()
-            .and_then(|s| s.parse().ok())
+        global().get_u64(keys::LLM_TIMEOUT_SECS)
+    }
+
+    /// 获取 LLM 响应缓存有效期（秒），未设置或为 0 时返回 None
+    pub fn llm_cache_ttl_secs() -> Option<u64> {
+        global()
+            .get_u64(keys::LLM_CACHE_TTL_SECS)
+            .filter(|ttl| *ttl > 0)
+    }
+
+    /// 获取语义缓存相似度阈值
+    pub fn llm_cache_similarity() -> Option<f32> {
+        global()
+            .get_f64(keys::LLM_CACHE_SIMILARITY)
+            .map(|v| v as f32)
     }
 
     /// 获取 Ollama 后端的超时时间（秒）
@@ -190,6 +316,100 @@ pub mod agent_env_vars {
     }
 }
 
+/// 设备与遥测配置常量
+pub mod device {
+    /// 默认命令幂等键有效期（秒）
+    pub const DEFAULT_COMMAND_IDEMPOTENCY_TTL_SECS: u64 = 86_400;
+    /// 默认单个设备每秒数据点上限
+    pub const DEFAULT_INGEST_DEVICE_MAX_POINTS_PER_SEC: u32 = 100;
+    /// 默认降采样时每 N 个数据点保留一个
+    pub const DEFAULT_INGEST_KEEP_EVERY: u32 = 10;
+    /// 默认热数据窗口（小时）
+    pub const DEFAULT_HOT_CACHE_HOURS: u64 = 2;
+    /// 默认最多保留的热序列数
+    pub const DEFAULT_HOT_CACHE_MAX_SERIES: usize = 128;
+    /// 默认事件日志最多保留的事件数
+    pub const DEFAULT_EVENT_LOG_MAX_EVENTS: u64 = 100_000;
+}
+
+/// 设备与遥测配置环境变量
+///
+/// 读取函数经过 [`super::service::global`]，优先级与 [`agent_env_vars`] 相同。
+pub mod device_env_vars {
+    use super::device;
+    use super::service::{global, keys};
+
+    /// 命令幂等键有效期（秒）
+    pub const COMMAND_IDEMPOTENCY_TTL_SECS: &str = "NEOMIND_COMMAND_IDEMPOTENCY_TTL_SECS";
+    /// 所有设备合计每秒数据点上限，未设置或为 0 表示不降采样
+    pub const INGEST_MAX_POINTS_PER_SEC: &str = "NEOMIND_INGEST_MAX_POINTS_PER_SEC";
+    /// 单个设备每秒数据点上限
+    pub const INGEST_DEVICE_MAX_POINTS_PER_SEC: &str = "NEOMIND_INGEST_DEVICE_MAX_POINTS_PER_SEC";
+    /// 降采样时每 N 个数据点保留一个
+    pub const INGEST_KEEP_EVERY: &str = "NEOMIND_INGEST_KEEP_EVERY";
+    /// 热数据窗口（小时），0 表示禁用
+    pub const HOT_CACHE_HOURS: &str = "NEOMIND_HOT_CACHE_HOURS";
+    /// 最多保留的热序列数
+    pub const HOT_CACHE_MAX_SERIES: &str = "NEOMIND_HOT_CACHE_MAX_SERIES";
+    /// 事件日志最多保留的事件数（更早的事件会被清理）
+    pub const EVENT_LOG_MAX_EVENTS: &str = "NEOMIND_EVENT_LOG_MAX_EVENTS";
+
+    /// 获取命令幂等键有效期（秒），或返回默认值
+    pub fn command_idempotency_ttl_secs() -> u64 {
+        global()
+            .get_u64(keys::DEVICES_COMMAND_IDEMPOTENCY_TTL_SECS)
+            .unwrap_or(device::DEFAULT_COMMAND_IDEMPOTENCY_TTL_SECS)
+    }
+
+    /// 获取所有设备合计每秒数据点上限，未设置或为 0 时返回 None
+    pub fn ingest_max_points_per_sec() -> Option<u32> {
+        global()
+            .get_u64(keys::TELEMETRY_INGEST_MAX_POINTS_PER_SEC)
+            .filter(|max| *max > 0)
+            .map(|max| u32::try_from(max).unwrap_or(u32::MAX))
+    }
+
+    /// 获取单个设备每秒数据点上限，或返回默认值
+    pub fn ingest_device_max_points_per_sec() -> u32 {
+        global()
+            .get_u64(keys::TELEMETRY_INGEST_DEVICE_MAX_POINTS_PER_SEC)
+            .map(|max| u32::try_from(max).unwrap_or(u32::MAX))
+            .unwrap_or(device::DEFAULT_INGEST_DEVICE_MAX_POINTS_PER_SEC)
+    }
+
+    /// 获取降采样保留间隔，或返回默认值
+    pub fn ingest_keep_every() -> u32 {
+        global()
+            .get_u64(keys::TELEMETRY_INGEST_KEEP_EVERY)
+            .map(|n| u32::try_from(n).unwrap_or(u32::MAX))
+            .unwrap_or(device::DEFAULT_INGEST_KEEP_EVERY)
+    }
+
+    /// 获取热数据窗口（小时），或返回默认值
+    pub fn hot_cache_hours() -> u64 {
+        global()
+            .get_u64(keys::TELEMETRY_HOT_CACHE_HOURS)
+            .unwrap_or(device::DEFAULT_HOT_CACHE_HOURS)
+    }
+
+    /// 获取最多保留的热序列数，或返回默认值
+    pub fn hot_cache_max_series() -> usize {
+        global()
+            .get_u64(keys::TELEMETRY_HOT_CACHE_MAX_SERIES)
+            .filter(|max| *max > 0)
+            .map(|max| usize::try_from(max).unwrap_or(usize::MAX))
+            .unwrap_or(device::DEFAULT_HOT_CACHE_MAX_SERIES)
+    }
+
+    /// 获取事件日志最多保留的事件数，或返回默认值
+    pub fn event_log_max_events() -> u64 {
+        global()
+            .get_u64(keys::EVENTS_LOG_MAX_EVENTS)
+            .filter(|max| *max > 0)
+            .unwrap_or(device::DEFAULT_EVENT_LOG_MAX_EVENTS)
+    }
+}
+
 /// 标准化 Ollama 端点 (移除 /v1 后缀)
 ///
 /// Ollama 使用原生 API，不需要 /v1 后缀
@@ -0,0 +1,735 @@
+//! 运行时配置服务
+//!
+//! 为可在运行时调整的配置项提供统一的类型化 schema、校验、持久化和变更通知。
+//! 配置值的优先级为：环境变量 > 已保存的值 > 默认值。
+//!
+//! 服务启动时通过 [`ConfigService::attach_store`] 加载已保存的值；通过
+//! [`ConfigService::update`] 修改的值会写入存储（同时记录变更历史），并在事件
+//! 总线上发布 [`NeoMindEvent::ConfigChanged`]。[`super::agent_env_vars`] 中的
+//! 读取函数都经过此服务，因此修改无需重启即可生效（`requires_restart` 的配置项
+//! 除外）。
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{agent, agent_env_vars, device, device_env_vars};
+use crate::event::NeoMindEvent;
+use crate::eventbus::EventBus;
+
+/// 配置项键名
+pub mod keys {
+    pub const AGENT_MAX_CONTEXT_TOKENS: &str = "agent.max_context_tokens";
+    pub const AGENT_TEMPERATURE: &str = "agent.temperature";
+    pub const AGENT_TOP_P: &str = "agent.top_p";
+    pub const AGENT_MAX_TOKENS: &str = "agent.max_tokens";
+    pub const AGENT_CONCURRENT_LIMIT: &str = "agent.concurrent_limit";
+    pub const AGENT_CONTEXT_SELECTOR_TOKENS: &str = "agent.context_selector_tokens";
+    pub const AGENT_APPROVAL_REQUIRED: &str = "agent.approval_required";
+    pub const AGENT_APPROVAL_TTL_SECS: &str = "agent.approval_ttl_secs";
+    pub const AGENT_APPROVAL_MFA_REQUIRED: &str = "agent.approval_mfa_required";
+    pub const AGENT_INJECTION_DETECTION: &str = "agent.injection_detection";
+    pub const AGENT_INJECTION_THRESHOLD: &str = "agent.injection_threshold";
+    pub const AGENT_INJECTION_LLM_CHECK: &str = "agent.injection_llm_check";
+    pub const AGENT_TOOL_TIMEOUT_SECS: &str = "agent.tool_timeout_secs";
+    pub const AGENT_TOOL_MAX_CONCURRENT: &str = "agent.tool_max_concurrent";
+    pub const AGENT_TOOL_MAX_OUTPUT_BYTES: &str = "agent.tool_max_output_bytes";
+    pub const LLM_TIMEOUT_SECS: &str = "llm.timeout_secs";
+    pub const LLM_CACHE_TTL_SECS: &str = "llm.cache_ttl_secs";
+    pub const LLM_CACHE_SIMILARITY: &str = "llm.cache_similarity";
+    pub const DEVICES_COMMAND_IDEMPOTENCY_TTL_SECS: &str = "devices.command_idempotency_ttl_secs";
+    pub const TELEMETRY_INGEST_MAX_POINTS_PER_SEC: &str = "telemetry.ingest_max_points_per_sec";
+    pub const TELEMETRY_INGEST_DEVICE_MAX_POINTS_PER_SEC: &str =
+        "telemetry.ingest_device_max_points_per_sec";
+    pub const TELEMETRY_INGEST_KEEP_EVERY: &str = "telemetry.ingest_keep_every";
+    pub const TELEMETRY_HOT_CACHE_HOURS: &str = "telemetry.hot_cache_hours";
+    pub const TELEMETRY_HOT_CACHE_MAX_SERIES: &str = "telemetry.hot_cache_max_series";
+    pub const EVENTS_LOG_MAX_EVENTS: &str = "events.log_max_events";
+}
+
+/// 配置项的值类型及取值范围
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConfigType {
+    Bool,
+    Integer { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    String,
+}
+
+/// 配置项定义
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigField {
+    pub key: &'static str,
+    pub description: &'static str,
+    #[serde(flatten)]
+    pub kind: ConfigType,
+    /// 默认值，`null` 表示由使用方决定（例如按后端区分的超时）
+    pub default: Value,
+    /// 覆盖此配置项的环境变量
+    pub env: Option<&'static str>,
+    /// 修改后是否需要重启才能生效
+    pub requires_restart: bool,
+}
+
+impl ConfigField {
+    /// 校验并规范化配置值（类型和取值范围）
+    pub fn validate(&self, value: &Value) -> Result<Value, ConfigError> {
+        let invalid = |reason: String| ConfigError::Invalid {
+            key: self.key.to_string(),
+            reason,
+        };
+        match &self.kind {
+            ConfigType::Bool => value
+                .as_bool()
+                .map(Value::Bool)
+                .ok_or_else(|| invalid("expected a boolean".to_string())),
+            ConfigType::Integer { min, max } => {
+                let n = value
+                    .as_i64()
+                    .ok_or_else(|| invalid("expected an integer".to_string()))?;
+                if n < *min || n > *max {
+                    return Err(invalid(format!("must be between {} and {}", min, max)));
+                }
+                Ok(Value::from(n))
+            }
+            ConfigType::Float { min, max } => {
+                let n = value
+                    .as_f64()
+                    .ok_or_else(|| invalid("expected a number".to_string()))?;
+                if n < *min || n > *max {
+                    return Err(invalid(format!("must be between {} and {}", min, max)));
+                }
+                Ok(Value::from(n))
+            }
+            ConfigType::String => value
+                .as_str()
+                .map(|s| Value::String(s.to_string()))
+                .ok_or_else(|| invalid("expected a string".to_string())),
+        }
+    }
+
+    /// 读取环境变量覆盖值
+    ///
+    /// 只检查类型，不检查范围，与引入配置服务之前的行为保持一致。
+    fn env_value(&self) -> Option<Value> {
+        let raw = std::env::var(self.env?).ok()?;
+        let raw = raw.trim();
+        match self.kind {
+            ConfigType::Bool => raw.parse::<bool>().ok().map(Value::Bool),
+            ConfigType::Integer { .. } => raw
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| raw.parse::<u64>().map(Value::from))
+                .ok(),
+            ConfigType::Float { .. } => raw.parse::<f64>().ok().map(Value::from),
+            ConfigType::String => Some(Value::String(raw.to_string())),
+        }
+    }
+}
+
+/// 通过 Display 转换 f32，避免 `0.3f32 as f64` 产生 0.30000001192092896
+fn f32_value(value: f32) -> Value {
+    value
+        .to_string()
+        .parse::<f64>()
+        .map(Value::from)
+        .unwrap_or(Value::Null)
+}
+
+static SCHEMA: Lazy<Vec<ConfigField>> = Lazy::new(|| {
+    vec![
+        ConfigField {
+            key: keys::AGENT_MAX_CONTEXT_TOKENS,
+            description: "Maximum context window used by the agent, in tokens",
+            kind: ConfigType::Integer {
+                min: 1024,
+                max: 2_000_000,
+            },
+            default: Value::from(agent::DEFAULT_MAX_CONTEXT_TOKENS),
+            env: Some(agent_env_vars::MAX_CONTEXT_TOKENS),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_TEMPERATURE,
+            description: "Sampling temperature for agent LLM calls",
+            kind: ConfigType::Float { min: 0.0, max: 2.0 },
+            default: f32_value(agent::DEFAULT_TEMPERATURE),
+            env: Some(agent_env_vars::TEMPERATURE),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_TOP_P,
+            description: "Nucleus sampling (top-p) for agent LLM calls",
+            kind: ConfigType::Float { min: 0.0, max: 1.0 },
+            default: f32_value(agent::DEFAULT_TOP_P),
+            env: Some(agent_env_vars::TOP_P),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_MAX_TOKENS,
+            description: "Maximum tokens generated per LLM response",
+            kind: ConfigType::Integer {
+                min: 1,
+                max: 1_000_000,
+            },
+            default: Value::from(agent::DEFAULT_MAX_TOKENS),
+            env: Some(agent_env_vars::MAX_TOKENS),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_CONCURRENT_LIMIT,
+            description: "Maximum concurrent LLM requests",
+            kind: ConfigType::Integer { min: 1, max: 100 },
+            default: Value::from(agent::DEFAULT_CONCURRENT_LIMIT),
+            env: Some(agent_env_vars::CONCURRENT_LIMIT),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::AGENT_CONTEXT_SELECTOR_TOKENS,
+            description: "Token budget of the context selector",
+            kind: ConfigType::Integer {
+                min: 0,
+                max: 1_000_000,
+            },
+            default: Value::from(agent::DEFAULT_CONTEXT_SELECTOR_TOKENS),
+            env: Some(agent_env_vars::CONTEXT_SELECTOR_TOKENS),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_APPROVAL_REQUIRED,
+            description: "Comma-separated tool actions that need operator approval, \
+                          e.g. device.control,rule.delete",
+            kind: ConfigType::String,
+            default: Value::from(""),
+            env: Some(agent_env_vars::APPROVAL_REQUIRED),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_APPROVAL_TTL_SECS,
+            description: "Seconds an operator has to approve a held tool action",
+            kind: ConfigType::Integer { min: 10, max: 86_400 },
+            default: Value::from(agent::DEFAULT_APPROVAL_TTL_SECS),
+            env: Some(agent_env_vars::APPROVAL_TTL_SECS),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_APPROVAL_MFA_REQUIRED,
+            description: "Comma-separated held tool actions whose approval needs a second \
+                          factor, e.g. device.control,rule.*; empty turns the check off",
+            kind: ConfigType::String,
+            default: Value::from("*"),
+            env: Some(agent_env_vars::APPROVAL_MFA_REQUIRED),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_INJECTION_DETECTION,
+            description: "Handling of suspected prompt injections: off, flag or strip",
+            kind: ConfigType::String,
+            default: Value::from(agent::DEFAULT_INJECTION_DETECTION),
+            env: Some(agent_env_vars::INJECTION_DETECTION),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_INJECTION_THRESHOLD,
+            description: "Score at which text is treated as a prompt injection",
+            kind: ConfigType::Float { min: 0.0, max: 1.0 },
+            default: f32_value(agent::DEFAULT_INJECTION_THRESHOLD),
+            env: Some(agent_env_vars::INJECTION_THRESHOLD),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_INJECTION_LLM_CHECK,
+            description: "Ask the LLM to classify borderline user messages",
+            kind: ConfigType::Bool,
+            default: Value::Bool(false),
+            env: Some(agent_env_vars::INJECTION_LLM_CHECK),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::AGENT_TOOL_TIMEOUT_SECS,
+            description: "Timeout for a single tool call in seconds (null means unbounded)",
+            kind: ConfigType::Integer { min: 1, max: 3600 },
+            default: Value::Null,
+            env: Some(agent_env_vars::TOOL_TIMEOUT_SECS),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::AGENT_TOOL_MAX_CONCURRENT,
+            description: "Maximum tool calls running at once (null means unbounded)",
+            kind: ConfigType::Integer { min: 1, max: 1000 },
+            default: Value::Null,
+            env: Some(agent_env_vars::TOOL_MAX_CONCURRENT),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::AGENT_TOOL_MAX_OUTPUT_BYTES,
+            description: "Tool output size in bytes above which results are truncated \
+                          (null means unlimited)",
+            kind: ConfigType::Integer {
+                min: 256,
+                max: 100_000_000,
+            },
+            default: Value::Null,
+            env: Some(agent_env_vars::TOOL_MAX_OUTPUT_BYTES),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::LLM_TIMEOUT_SECS,
+            description: "LLM request timeout in seconds (null uses the backend default)",
+            kind: ConfigType::Integer { min: 1, max: 3600 },
+            default: Value::Null,
+            env: Some(agent_env_vars::LLM_TIMEOUT_SECS),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::LLM_CACHE_TTL_SECS,
+            description: "LLM response cache lifetime in seconds (0 disables the cache)",
+            kind: ConfigType::Integer { min: 0, max: 86_400 },
+            default: Value::from(0),
+            env: Some(agent_env_vars::LLM_CACHE_TTL_SECS),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::LLM_CACHE_SIMILARITY,
+            description: "Minimum cosine similarity for a semantic cache hit; setting it \
+                          enables the semantic layer, which embeds queries with the \
+                          knowledge base embedder",
+            kind: ConfigType::Float { min: 0.0, max: 1.0 },
+            default: Value::Null,
+            env: Some(agent_env_vars::LLM_CACHE_SIMILARITY),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::DEVICES_COMMAND_IDEMPOTENCY_TTL_SECS,
+            description: "Seconds a command idempotency key deduplicates retried submissions",
+            kind: ConfigType::Integer {
+                min: 1,
+                max: 31_536_000,
+            },
+            default: Value::from(device::DEFAULT_COMMAND_IDEMPOTENCY_TTL_SECS),
+            env: Some(device_env_vars::COMMAND_IDEMPOTENCY_TTL_SECS),
+            requires_restart: false,
+        },
+        ConfigField {
+            key: keys::TELEMETRY_INGEST_MAX_POINTS_PER_SEC,
+            description: "Telemetry points per second across all devices before storage is \
+                          downsampled (0 disables load shedding)",
+            kind: ConfigType::Integer {
+                min: 0,
+                max: u32::MAX as i64,
+            },
+            default: Value::from(0),
+            env: Some(device_env_vars::INGEST_MAX_POINTS_PER_SEC),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::TELEMETRY_INGEST_DEVICE_MAX_POINTS_PER_SEC,
+            description: "Telemetry points per second from one device before it is downsampled",
+            kind: ConfigType::Integer {
+                min: 1,
+                max: u32::MAX as i64,
+            },
+            default: Value::from(device::DEFAULT_INGEST_DEVICE_MAX_POINTS_PER_SEC),
+            env: Some(device_env_vars::INGEST_DEVICE_MAX_POINTS_PER_SEC),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::TELEMETRY_INGEST_KEEP_EVERY,
+            description: "While downsampling, keep every Nth point of a metric",
+            kind: ConfigType::Integer { min: 1, max: 10_000 },
+            default: Value::from(device::DEFAULT_INGEST_KEEP_EVERY),
+            env: Some(device_env_vars::INGEST_KEEP_EVERY),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::TELEMETRY_HOT_CACHE_HOURS,
+            description: "Hours of recent history kept in memory for frequently read series \
+                          (0 disables the hot cache)",
+            kind: ConfigType::Integer { min: 0, max: 168 },
+            default: Value::from(device::DEFAULT_HOT_CACHE_HOURS),
+            env: Some(device_env_vars::HOT_CACHE_HOURS),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::TELEMETRY_HOT_CACHE_MAX_SERIES,
+            description: "Most series kept in the hot cache",
+            kind: ConfigType::Integer {
+                min: 1,
+                max: 100_000,
+            },
+            default: Value::from(device::DEFAULT_HOT_CACHE_MAX_SERIES),
+            env: Some(device_env_vars::HOT_CACHE_MAX_SERIES),
+            requires_restart: true,
+        },
+        ConfigField {
+            key: keys::EVENTS_LOG_MAX_EVENTS,
+            description: "Most events kept in the persistent event log; older events are pruned",
+            kind: ConfigType::Integer {
+                min: 1_000,
+                max: 100_000_000,
+            },
+            default: Value::from(device::DEFAULT_EVENT_LOG_MAX_EVENTS),
+            env: Some(device_env_vars::EVENT_LOG_MAX_EVENTS),
+            requires_restart: true,
+        },
+    ]
+});
+
+/// 所有配置项定义
+pub fn schema() -> &'static [ConfigField] {
+    &SCHEMA
+}
+
+/// 按键名查找配置项定义
+pub fn field(key: &str) -> Option<&'static ConfigField> {
+    SCHEMA.iter().find(|f| f.key == key)
+}
+
+/// 配置值的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Env,
+    Stored,
+    Default,
+}
+
+/// 配置项及其当前生效值
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigEntry {
+    #[serde(flatten)]
+    pub field: ConfigField,
+    pub value: Value,
+    pub source: ConfigSource,
+}
+
+/// 配置服务错误
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ConfigError {
+    #[error("unknown config key '{0}'")]
+    UnknownKey(String),
+    #[error("invalid value for '{key}': {reason}")]
+    Invalid { key: String, reason: String },
+    #[error("'{key}' is set by environment variable {env} and cannot be changed at runtime")]
+    EnvOverride { key: String, env: String },
+    #[error("config store error: {0}")]
+    Store(String),
+}
+
+/// 配置值的持久化存储
+pub trait ConfigStore: Send + Sync {
+    /// 加载所有已保存的配置值
+    fn load_config_values(&self) -> Result<BTreeMap<String, Value>, String>;
+
+    /// 保存配置值并记录变更历史，`new` 为 `None` 表示删除（恢复默认值）
+    fn save_config_value(
+        &self,
+        key: &str,
+        old: Option<&Value>,
+        new: Option<&Value>,
+        source: &str,
+    ) -> Result<(), String>;
+}
+
+/// 运行时配置服务
+#[derive(Default)]
+pub struct ConfigService {
+    stored: RwLock<BTreeMap<String, Value>>,
+    store: RwLock<Option<Arc<dyn ConfigStore>>>,
+    event_bus: RwLock<Option<EventBus>>,
+}
+
+static GLOBAL: Lazy<ConfigService> = Lazy::new(ConfigService::new);
+
+/// 进程内共享的配置服务
+pub fn global() -> &'static ConfigService {
+    &GLOBAL
+}
+
+impl ConfigService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 绑定持久化存储并加载已保存的值
+    ///
+    /// 未知或不再合法的已保存值会被忽略（并记录警告），不会阻止启动。
+    pub fn attach_store(&self, store: Arc<dyn ConfigStore>) -> Result<(), ConfigError> {
+        let values = store.load_config_values().map_err(ConfigError::Store)?;
+        let mut stored = BTreeMap::new();
+        for (key, value) in values {
+            let Some(field) = field(&key) else {
+                tracing::warn!(key = %key, "Ignoring stored value for unknown config key");
+                continue;
+            };
+            match field.validate(&value) {
+                Ok(value) => {
+                    stored.insert(key, value);
+                }
+                Err(e) => tracing::warn!(error = %e, "Ignoring invalid stored config value"),
+            }
+        }
+        *self.stored.write() = stored;
+        *self.store.write() = Some(store);
+        Ok(())
+    }
+
+    /// 设置用于发布配置变更事件的事件总线
+    pub fn set_event_bus(&self, event_bus: EventBus) {
+        *self.event_bus.write() = Some(event_bus);
+    }
+
+    /// 当前生效值，未配置且无默认值时返回 `None`
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let value = self.entry(key)?.value;
+        (!value.is_null()).then_some(value)
+    }
+
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        self.get(key).and_then(|v| v.as_u64())
+    }
+
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(|v| v.as_f64())
+    }
+
+    /// 配置项的定义、当前值及来源
+    pub fn entry(&self, key: &str) -> Option<ConfigEntry> {
+        let field = field(key)?;
+        let (value, source) = if let Some(value) = field.env_value() {
+            (value, ConfigSource::Env)
+        } else if let Some(value) = self.stored.read().get(key) {
+            (value.clone(), ConfigSource::Stored)
+        } else {
+            (field.default.clone(), ConfigSource::Default)
+        };
+        Some(ConfigEntry {
+            field: field.clone(),
+            value,
+            source,
+        })
+    }
+
+    /// 所有配置项的当前值
+    pub fn entries(&self) -> Vec<ConfigEntry> {
+        SCHEMA.iter().filter_map(|f| self.entry(f.key)).collect()
+    }
+
+    /// 批量修改配置
+    ///
+    /// 先校验全部修改，任一项不合法则不做任何修改。值为 `null` 表示恢复默认值。
+    /// 由环境变量设置的配置项不能在运行时修改。返回修改后的配置项。
+    pub fn update(
+        &self,
+        changes: BTreeMap<String, Value>,
+        source: &str,
+    ) -> Result<Vec<ConfigEntry>, ConfigError> {
+        let mut validated = Vec::with_capacity(changes.len());
+        for (key, value) in changes {
+            let field = field(&key).ok_or_else(|| ConfigError::UnknownKey(key.clone()))?;
+            if let (Some(env), Some(_)) = (field.env, field.env_value()) {
+                return Err(ConfigError::EnvOverride {
+                    key,
+                    env: env.to_string(),
+                });
+            }
+            let value = if value.is_null() {
+                None
+            } else {
+                Some(field.validate(&value)?)
+            };
+            validated.push((key, value));
+        }
+
+        let store = self.store.read().clone();
+        let event_bus = self.event_bus.read().clone();
+        let mut updated = Vec::with_capacity(validated.len());
+        for (key, value) in validated {
+            let old = self.stored.read().get(&key).cloned();
+            if old == value {
+                updated.extend(self.entry(&key));
+                continue;
+            }
+            if let Some(store) = &store {
+                store
+                    .save_config_value(&key, old.as_ref(), value.as_ref(), source)
+                    .map_err(ConfigError::Store)?;
+            }
+            {
+                let mut stored = self.stored.write();
+                match &value {
+                    Some(value) => stored.insert(key.clone(), value.clone()),
+                    None => stored.remove(&key),
+                };
+            }
+
+            let Some(entry) = self.entry(&key) else {
+                continue;
+            };
+            tracing::info!(key = %key, value = %entry.value, source, "Config changed");
+            if let Some(bus) = &event_bus {
+                bus.publish_with_source_sync(
+                    NeoMindEvent::ConfigChanged {
+                        key: key.clone(),
+                        value: entry.value.clone(),
+                        source: source.to_string(),
+                        timestamp: chrono::Utc::now().timestamp(),
+                    },
+                    "config",
+                );
+            }
+            updated.push(entry);
+        }
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    type HistoryEntry = (String, Option<Value>, Option<Value>);
+
+    #[derive(Default)]
+    struct MemoryStore {
+        values: Mutex<BTreeMap<String, Value>>,
+        history: Mutex<Vec<HistoryEntry>>,
+    }
+
+    impl ConfigStore for MemoryStore {
+        fn load_config_values(&self) -> Result<BTreeMap<String, Value>, String> {
+            Ok(self.values.lock().clone())
+        }
+
+        fn save_config_value(
+            &self,
+            key: &str,
+            old: Option<&Value>,
+            new: Option<&Value>,
+            _source: &str,
+        ) -> Result<(), String> {
+            match new {
+                Some(v) => self.values.lock().insert(key.to_string(), v.clone()),
+                None => self.values.lock().remove(key),
+            };
+            self.history
+                .lock()
+                .push((key.to_string(), old.cloned(), new.cloned()));
+            Ok(())
+        }
+    }
+
+    fn changes(pairs: &[(&str, Value)]) -> BTreeMap<String, Value> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    #[test]
+    fn test_schema_defaults_are_valid() {
+        for field in schema() {
+            if !field.default.is_null() {
+                assert_eq!(field.validate(&field.default).as_ref(), Ok(&field.default));
+            }
+        }
+        assert_eq!(field(keys::AGENT_TEMPERATURE).unwrap().default, serde_json::json!(0.3));
+        assert!(field("agent.unknown").is_none());
+    }
+
+    #[test]
+    fn test_validate_type_and_range() {
+        let field = field(keys::AGENT_TOP_P).unwrap();
+        assert!(field.validate(&serde_json::json!(0.9)).is_ok());
+        assert!(field.validate(&serde_json::json!(1.5)).is_err());
+        assert!(field.validate(&serde_json::json!("0.9")).is_err());
+
+        let field = super::field(keys::AGENT_MAX_TOKENS).unwrap();
+        assert!(field.validate(&serde_json::json!(2048)).is_ok());
+        assert!(field.validate(&serde_json::json!(0)).is_err());
+        assert!(field.validate(&serde_json::json!(1.5)).is_err());
+    }
+
+    #[test]
+    fn test_update_persists_and_publishes() {
+        let service = ConfigService::new();
+        let store = Arc::new(MemoryStore::default());
+        service.attach_store(store.clone()).unwrap();
+        let bus = EventBus::new();
+        let mut rx = bus.subscribe();
+        service.set_event_bus(bus);
+
+        let key = keys::AGENT_CONTEXT_SELECTOR_TOKENS;
+        let updated = service
+            .update(changes(&[(key, serde_json::json!(8000))]), "api")
+            .unwrap();
+        assert_eq!(updated[0].source, ConfigSource::Stored);
+        assert_eq!(service.get_u64(key), Some(8000));
+        assert_eq!(store.values.lock().get(key), Some(&serde_json::json!(8000)));
+        match rx.try_recv().unwrap().0 {
+            NeoMindEvent::ConfigChanged { key: k, value, .. } => {
+                assert_eq!(k, key);
+                assert_eq!(value, serde_json::json!(8000));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // null 恢复默认值
+        service.update(changes(&[(key, Value::Null)]), "api").unwrap();
+        assert_eq!(service.entry(key).unwrap().source, ConfigSource::Default);
+        assert_eq!(store.history.lock().len(), 2);
+
+        // 重新加载已保存的值
+        service
+            .update(changes(&[(key, serde_json::json!(6000))]), "api")
+            .unwrap();
+        let reloaded = ConfigService::new();
+        reloaded.attach_store(store).unwrap();
+        assert_eq!(reloaded.get_u64(key), Some(6000));
+    }
+
+    #[test]
+    fn test_update_is_all_or_nothing() {
+        let service = ConfigService::new();
+        let result = service.update(
+            changes(&[
+                (keys::AGENT_MAX_TOKENS, serde_json::json!(1000)),
+                (keys::AGENT_TOP_P, serde_json::json!(7)),
+            ]),
+            "api",
+        );
+        assert!(matches!(result, Err(ConfigError::Invalid { .. })));
+        assert_eq!(
+            service.entry(keys::AGENT_MAX_TOKENS).unwrap().source,
+            ConfigSource::Default
+        );
+
+        let result = service.update(changes(&[("nope", serde_json::json!(1))]), "api");
+        assert_eq!(result.unwrap_err(), ConfigError::UnknownKey("nope".to_string()));
+    }
+
+    #[test]
+    fn test_env_overrides_stored_value() {
+        let service = ConfigService::new();
+        let key = keys::LLM_CACHE_SIMILARITY;
+        service
+            .update(changes(&[(key, serde_json::json!(0.8))]), "api")
+            .unwrap();
+        assert_eq!(service.get_f64(key), Some(0.8));
+
+        unsafe {
+            std::env::set_var(agent_env_vars::LLM_CACHE_SIMILARITY, "0.9");
+        }
+        assert_eq!(service.entry(key).unwrap().source, ConfigSource::Env);
+        assert_eq!(service.get_f64(key), Some(0.9));
+        let result = service.update(changes(&[(key, serde_json::json!(0.7))]), "api");
+        assert!(matches!(result, Err(ConfigError::EnvOverride { .. })));
+        unsafe {
+            std::env::remove_var(agent_env_vars::LLM_CACHE_SIMILARITY);
+        }
+        assert_eq!(service.get_f64(key), Some(0.8));
+    }
+}
@@ -75,6 +75,15 @@ pub enum NeoMindEvent {
         timestamp: i64,
     },
 
+    /// A command queued for an offline device expired before delivery
+    DeviceCommandExpired {
+        device_id: String,
+        command_id: String,
+        command: String,
+        queued_at: i64,
+        timestamp: i64,
+    },
+
     /// Unknown device discovered by an adapter (MQTT, Webhook, etc.)
     ///
     /// Emitted when an adapter receives data from a device that is not
@@ -90,6 +99,20 @@ pub enum NeoMindEvent {
         timestamp: i64,
     },
 
+    /// A device metric deviated from its learned baseline
+    Anomaly {
+        device_id: String,
+        metric: String,
+        value: f64,
+        /// Baseline value expected at this time
+        expected: f64,
+        /// Deviation in standard deviations (signed)
+        z_score: f64,
+        /// "warning" or "critical"
+        severity: String,
+        timestamp: i64,
+    },
+
     // ========== Rule Events ==========
     /// Rule condition was evaluated
     RuleEvaluated {
@@ -253,6 +276,17 @@ pub enum NeoMindEvent {
         timestamp: i64,
     },
 
+    /// The LLM failover chain switched backends
+    ///
+    /// Emitted when requests move to a fallback backend after failures, and
+    /// again when the primary is healthy and traffic moves back to it.
+    LlmBackendFailover {
+        from_backend: String,
+        to_backend: String,
+        reason: String,
+        timestamp: i64,
+    },
+
     // ========== Dashboard Events ==========
     /// Dashboard was created, updated, or deleted
     DashboardUpdated {
@@ -261,6 +295,18 @@ pub enum NeoMindEvent {
         timestamp: i64,
     },
 
+    // ========== Configuration Events ==========
+    /// A runtime configuration value changed
+    ///
+    /// `value` is the new effective value (the default when the stored value
+    /// was cleared). Subsystems that cache configuration reload on this.
+    ConfigChanged {
+        key: String,
+        value: serde_json::Value,
+        source: String,
+        timestamp: i64,
+    },
+
     // ========== User Events ==========
     /// User message (for LLM)
     UserMessage {
@@ -473,7 +519,9 @@ impl NeoMindEvent {
             Self::DeviceTransportOffline { .. } => "DeviceTransportOffline",
             Self::DeviceMetric { .. } => "DeviceMetric",
             Self::DeviceCommandResult { .. } => "DeviceCommandResult",
+            Self::DeviceCommandExpired { .. } => "DeviceCommandExpired",
             Self::DeviceDiscovered { .. } => "DeviceDiscovered",
+            Self::Anomaly { .. } => "Anomaly",
             Self::RuleEvaluated { .. } => "RuleEvaluated",
             Self::RuleTriggered { .. } => "RuleTriggered",
             Self::RuleExecuted { .. } => "RuleExecuted",
@@ -491,6 +539,7 @@ impl NeoMindEvent {
             Self::PeriodicReviewTriggered { .. } => "PeriodicReviewTriggered",
             Self::LlmDecisionProposed { .. } => "LlmDecisionProposed",
             Self::LlmDecisionExecuted { .. } => "LlmDecisionExecuted",
+            Self::LlmBackendFailover { .. } => "LlmBackendFailover",
             Self::UserMessage { .. } => "UserMessage",
             Self::LlmResponse { .. } => "LlmResponse",
             Self::ToolExecutionStart { .. } => "ToolExecutionStart",
@@ -502,6 +551,7 @@ impl NeoMindEvent {
             Self::ExtensionCommandCompleted { .. } => "ExtensionCommandCompleted",
             Self::ExtensionCommandFailed { .. } => "ExtensionCommandFailed",
             Self::DashboardUpdated { .. } => "DashboardUpdated",
+            Self::ConfigChanged { .. } => "ConfigChanged",
             Self::Custom { .. } => "Custom",
             Self::AgentStreamChunk { .. } => "AgentStreamChunk",
             Self::AgentStreamEnd { .. } => "AgentStreamEnd",
@@ -527,7 +577,9 @@ impl NeoMindEvent {
             | Self::DeviceTransportOffline { timestamp, .. }
             | Self::DeviceMetric { timestamp, .. }
             | Self::DeviceCommandResult { timestamp, .. }
+            | Self::DeviceCommandExpired { timestamp, .. }
             | Self::DeviceDiscovered { timestamp, .. }
+            | Self::Anomaly { timestamp, .. }
             | Self::RuleEvaluated { timestamp, .. }
             | Self::RuleTriggered { timestamp, .. }
             | Self::RuleExecuted { timestamp, .. }
@@ -545,6 +597,7 @@ impl NeoMindEvent {
             | Self::PeriodicReviewTriggered { timestamp, .. }
             | Self::LlmDecisionProposed { timestamp, .. }
             | Self::LlmDecisionExecuted { timestamp, .. }
+            | Self::LlmBackendFailover { timestamp, .. }
             | Self::UserMessage { timestamp, .. }
             | Self::LlmResponse { timestamp, .. }
             | Self::ToolExecutionStart { timestamp, .. }
@@ -556,6 +609,7 @@ impl NeoMindEvent {
             | Self::ExtensionCommandCompleted { timestamp, .. }
             | Self::ExtensionCommandFailed { timestamp, .. }
             | Self::DashboardUpdated { timestamp, .. }
+            | Self::ConfigChanged { timestamp, .. }
             | Self::AgentStreamChunk { timestamp, .. }
             | Self::AgentStreamEnd { timestamp, .. } => *timestamp,
             Self::Custom { .. } => {
@@ -575,6 +629,7 @@ impl NeoMindEvent {
                 | Self::DeviceTransportOffline { .. }
                 | Self::DeviceMetric { .. }
                 | Self::DeviceCommandResult { .. }
+                | Self::DeviceCommandExpired { .. }
                 | Self::DeviceDiscovered { .. }
         )
     }
@@ -636,6 +691,7 @@ impl NeoMindEvent {
                 | Self::PeriodicReviewTriggered { .. }
                 | Self::LlmDecisionProposed { .. }
                 | Self::LlmDecisionExecuted { .. }
+                | Self::LlmBackendFailover { .. }
                 | Self::UserMessage { .. }
                 | Self::LlmResponse { .. }
                 | Self::ToolExecutionStart { .. }
@@ -672,6 +728,32 @@ impl NeoMindEvent {
         )
     }
 
+    /// Device this event refers to, if any.
+    pub fn device_id(&self) -> Option<&str> {
+        match self {
+            Self::DeviceOnline { device_id, .. }
+            | Self::DeviceOffline { device_id, .. }
+            | Self::DeviceTransportOnline { device_id, .. }
+            | Self::DeviceTransportOffline { device_id, .. }
+            | Self::DeviceMetric { device_id, .. }
+            | Self::DeviceCommandResult { device_id, .. }
+            | Self::DeviceCommandExpired { device_id, .. }
+            | Self::DeviceDiscovered { device_id, .. }
+            | Self::Anomaly { device_id, .. } => Some(device_id),
+            _ => None,
+        }
+    }
+
+    /// Severity of alert and message events.
+    pub fn severity(&self) -> Option<&str> {
+        match self {
+            Self::AlertCreated { severity, .. }
+            | Self::MessageCreated { severity, .. }
+            | Self::Anomaly { severity, .. } => Some(severity),
+            _ => None,
+        }
+    }
+
     /// Phase 2.1: Check if this is an extension event.
     pub fn is_extension_event(&self) -> bool {
         matches!(
@@ -871,6 +953,9 @@ pub struct EventMetadata {
     pub source: String,
     /// Event timestamp
     pub timestamp: i64,
+    /// Position in the persistent event log, if the bus has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
 }
 
 impl EventMetadata {
@@ -882,6 +967,7 @@ impl EventMetadata {
             causation_id: None,
             source: source.into(),
             timestamp: chrono::Utc::now().timestamp(),
+            sequence: None,
         }
     }
 
@@ -926,6 +1012,30 @@ mod tests {
         assert!(!event.is_rule_event());
     }
 
+    #[test]
+    fn test_event_device_id_and_severity() {
+        let metric = NeoMindEvent::DeviceMetric {
+            device_id: "sensor1".to_string(),
+            metric: "temp".to_string(),
+            value: MetricValue::float(25.0),
+            timestamp: 0,
+            quality: None,
+            is_virtual: None,
+        };
+        assert_eq!(metric.device_id(), Some("sensor1"));
+        assert_eq!(metric.severity(), None);
+
+        let alert = NeoMindEvent::AlertCreated {
+            alert_id: "a1".to_string(),
+            title: "High temp".to_string(),
+            severity: "critical".to_string(),
+            message: "Too hot".to_string(),
+            timestamp: 0,
+        };
+        assert_eq!(alert.severity(), Some("critical"));
+        assert_eq!(alert.device_id(), None);
+    }
+
     #[test]
     fn test_metric_value_conversions() {
         let mv = MetricValue::float(42.0);
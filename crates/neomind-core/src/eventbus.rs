@@ -4,13 +4,71 @@
 //! communicate through publishing and subscribing to events.
 
 use crate::event::{EventMetadata, NeoMindEvent};
+use crate::metrics::{self, Counter, Gauge};
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::broadcast;
 
 /// Default channel capacity for the event bus.
 pub const DEFAULT_CHANNEL_CAPACITY: usize = 1000;
 
+/// Number of persisted events a replaying receiver loads at a time.
+pub const REPLAY_BATCH_SIZE: usize = 256;
+
+static EVENTS_PUBLISHED: Lazy<Arc<Counter>> = Lazy::new(|| {
+    metrics::global().counter(
+        "neomind_eventbus_published_total",
+        "Events published on the event bus",
+        &[],
+    )
+});
+
+static QUEUE_DEPTH: Lazy<Arc<Gauge>> = Lazy::new(|| {
+    metrics::global().gauge(
+        "neomind_eventbus_queue_depth",
+        "Events buffered for the slowest event bus subscriber",
+        &[],
+    )
+});
+
+/// An event read back from a persistent event log.
+#[derive(Debug, Clone)]
+pub struct PersistedEvent {
+    /// Monotonic position in the log, starting at 1.
+    pub sequence: u64,
+    pub event: NeoMindEvent,
+    pub metadata: EventMetadata,
+}
+
+/// Durable storage for published events.
+///
+/// When attached with [`EventBus::with_persistence`], every published event is
+/// appended before it is broadcast and its sequence number is stamped into
+/// [`EventMetadata::sequence`]. Receivers created with
+/// [`EventBus::subscribe_from`] replay the log from a cursor before switching
+/// to live events.
+pub trait EventPersistence: Send + Sync {
+    /// Append an event and return its sequence number, or `None` if it could
+    /// not be stored (the event is still broadcast).
+    fn append(&self, event: &NeoMindEvent, metadata: &EventMetadata) -> Option<u64>;
+
+    /// Read up to `limit` events with `sequence >= cursor`, oldest first.
+    fn read_from(&self, cursor: u64, limit: usize) -> Vec<PersistedEvent>;
+
+    /// Sequence number of the newest stored event (0 when empty).
+    fn last_sequence(&self) -> u64;
+
+    /// Cursor last committed by a named consumer, if any.
+    fn load_cursor(&self, _consumer: &str) -> Option<u64> {
+        None
+    }
+
+    /// Record the cursor a named consumer should resume from.
+    fn store_cursor(&self, _consumer: &str, _cursor: u64) {}
+}
+
 /// Event bus for NeoMind.
 ///
 /// The event bus uses a broadcast channel to distribute events to all
@@ -24,6 +82,10 @@ pub struct EventBus {
     tx: broadcast::Sender<(NeoMindEvent, EventMetadata)>,
     /// Event bus name for identification
     name: String,
+    /// Optional durable event log.
+    persistence: Option<Arc<dyn EventPersistence>>,
+    /// Serializes append + send so live events arrive in sequence order.
+    publish_lock: Arc<Mutex<()>>,
 }
 
 impl EventBus {
@@ -40,6 +102,8 @@ impl EventBus {
         Self {
             tx,
             name: "default".to_string(),
+            persistence: None,
+            publish_lock: Arc::new(Mutex::new(())),
         }
     }
 
@@ -48,9 +112,25 @@ impl EventBus {
         Self {
             tx: broadcast::channel(DEFAULT_CHANNEL_CAPACITY).0,
             name: name.into(),
+            persistence: None,
+            publish_lock: Arc::new(Mutex::new(())),
         }
     }
 
+    /// Attach a durable event log.
+    ///
+    /// Every subsequent publish performs a synchronous append, so use a
+    /// backend whose writes are cheap relative to the event rate.
+    pub fn with_persistence(mut self, persistence: Arc<dyn EventPersistence>) -> Self {
+        self.persistence = Some(persistence);
+        self
+    }
+
+    /// The attached event log, if any.
+    pub fn persistence(&self) -> Option<&Arc<dyn EventPersistence>> {
+        self.persistence.as_ref()
+    }
+
     /// Get the name of this event bus.
     pub fn name(&self) -> &str {
         &self.name
@@ -101,14 +181,31 @@ impl EventBus {
         event: NeoMindEvent,
         metadata: EventMetadata,
     ) -> bool {
-        self.tx.send((event, metadata)).is_ok()
+        self.send(event, metadata)
     }
 
     /// Publish an event with custom metadata (synchronous version).
     ///
     /// This version can be called from any context, including non-async contexts.
     pub fn publish_with_metadata_sync(&self, event: NeoMindEvent, metadata: EventMetadata) -> bool {
-        self.tx.send((event, metadata)).is_ok()
+        self.send(event, metadata)
+    }
+
+    fn send(&self, event: NeoMindEvent, mut metadata: EventMetadata) -> bool {
+        EVENTS_PUBLISHED.inc();
+        let sent = match &self.persistence {
+            None => self.tx.send((event, metadata)).is_ok(),
+            Some(persistence) => {
+                let _guard = self
+                    .publish_lock
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                metadata.sequence = persistence.append(&event, &metadata);
+                self.tx.send((event, metadata)).is_ok()
+            }
+        };
+        QUEUE_DEPTH.set(self.tx.len() as i64);
+        sent
     }
 
     /// Subscribe to all events.
@@ -119,7 +216,47 @@ impl EventBus {
         EventBusReceiver {
             rx: self.tx.subscribe(),
             dropped: AtomicU64::new(0),
+            persistence: None,
+            replay_from: None,
+            backlog: VecDeque::new(),
+            last_sequence: 0,
+            consumer: None,
+        }
+    }
+
+    /// Subscribe starting at `cursor` in the persistent event log.
+    ///
+    /// Stored events with `sequence >= cursor` are delivered first, then live
+    /// events, without duplicates. If the receiver lags behind the broadcast
+    /// buffer it falls back to the log instead of dropping events. Without
+    /// persistence this is the same as [`subscribe`](Self::subscribe).
+    pub fn subscribe_from(&self, cursor: u64) -> EventBusReceiver {
+        let mut receiver = self.subscribe();
+        if let Some(persistence) = &self.persistence {
+            receiver.persistence = Some(persistence.clone());
+            receiver.replay_from = Some(cursor.max(1));
+            receiver.last_sequence = cursor.saturating_sub(1);
         }
+        receiver
+    }
+
+    /// Subscribe as a named consumer that resumes where it left off.
+    ///
+    /// Starts at the cursor last committed with
+    /// [`EventBusReceiver::commit`], or at the next new event if the consumer
+    /// has never committed. Without persistence this is the same as
+    /// [`subscribe`](Self::subscribe).
+    pub fn subscribe_durable(&self, consumer: impl Into<String>) -> EventBusReceiver {
+        let Some(persistence) = &self.persistence else {
+            return self.subscribe();
+        };
+        let consumer = consumer.into();
+        let cursor = persistence
+            .load_cursor(&consumer)
+            .unwrap_or_else(|| persistence.last_sequence() + 1);
+        let mut receiver = self.subscribe_from(cursor);
+        receiver.consumer = Some(consumer);
+        receiver
     }
 
     /// Subscribe to events matching a filter.
@@ -154,6 +291,15 @@ pub struct EventBusReceiver {
     /// Events silently dropped because this receiver lagged behind the buffer.
     /// Non-zero ⇒ silent event loss (telemetry/rules/agents missed).
     dropped: AtomicU64,
+    /// Event log used for cursor replay (only set by `subscribe_from`).
+    persistence: Option<Arc<dyn EventPersistence>>,
+    /// Next log sequence to read while replaying; `None` once caught up.
+    replay_from: Option<u64>,
+    backlog: VecDeque<PersistedEvent>,
+    /// Highest sequence delivered so far.
+    last_sequence: u64,
+    /// Consumer name the cursor is committed under (only set by `subscribe_durable`).
+    consumer: Option<String>,
 }
 
 impl EventBusReceiver {
@@ -161,11 +307,25 @@ impl EventBusReceiver {
     ///
     /// Returns `None` if the event bus is closed.
     pub async fn recv(&mut self) -> Option<(NeoMindEvent, EventMetadata)> {
+        if let Some(event) = self.next_replayed() {
+            return Some(event);
+        }
         loop {
             match self.rx.recv().await {
-                Ok(event) => return Some(event),
+                Ok(event) => {
+                    if self.accept_live(&event.1) {
+                        return Some(event);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) if self.persistence.is_some() => {
+                    // The log has everything we missed; resume from it.
+                    self.replay_from = Some(self.last_sequence + 1);
+                    if let Some(event) = self.next_replayed() {
+                        return Some(event);
+                    }
+                }
                 Err(broadcast::error::RecvError::Lagged(n)) => {
-                    let total = self.dropped.fetch_add(n as u64, Ordering::Relaxed) + n as u64;
+                    let total = self.dropped.fetch_add(n, Ordering::Relaxed) + n;
                     tracing::warn!(
                         dropped_total = total,
                         this_batch = n,
@@ -189,7 +349,25 @@ impl EventBusReceiver {
 
     /// Try to receive an event without blocking.
     pub fn try_recv(&mut self) -> Option<(NeoMindEvent, EventMetadata)> {
-        self.rx.try_recv().ok()
+        if let Some(event) = self.next_replayed() {
+            return Some(event);
+        }
+        loop {
+            match self.rx.try_recv() {
+                Ok(event) => {
+                    if self.accept_live(&event.1) {
+                        return Some(event);
+                    }
+                }
+                Err(broadcast::error::TryRecvError::Lagged(_)) if self.persistence.is_some() => {
+                    self.replay_from = Some(self.last_sequence + 1);
+                    if let Some(event) = self.next_replayed() {
+                        return Some(event);
+                    }
+                }
+                Err(_) => return None,
+            }
+        }
     }
 
     /// Total events silently dropped by this receiver because it lagged behind
@@ -198,6 +376,56 @@ impl EventBusReceiver {
         self.dropped.load(Ordering::Relaxed)
     }
 
+    /// Cursor to pass to [`EventBus::subscribe_from`] to resume after the
+    /// last event this receiver delivered.
+    pub fn cursor(&self) -> u64 {
+        self.last_sequence + 1
+    }
+
+    /// Commit [`cursor`](Self::cursor) for a durable consumer, so a later
+    /// [`EventBus::subscribe_durable`] resumes after the last delivered event.
+    /// Does nothing for other receivers.
+    pub fn commit(&self) {
+        if let (Some(persistence), Some(consumer)) = (&self.persistence, &self.consumer) {
+            persistence.store_cursor(consumer, self.cursor());
+        }
+    }
+
+    /// Pop the next event from the log while replaying.
+    fn next_replayed(&mut self) -> Option<(NeoMindEvent, EventMetadata)> {
+        let next = self.replay_from?;
+        if self.backlog.is_empty() {
+            if let Some(persistence) = &self.persistence {
+                self.backlog
+                    .extend(persistence.read_from(next, REPLAY_BATCH_SIZE));
+            }
+        }
+        let Some(entry) = self.backlog.pop_front() else {
+            // Caught up: everything newer arrives on the live channel.
+            self.replay_from = None;
+            return None;
+        };
+        self.replay_from = Some(entry.sequence + 1);
+        self.last_sequence = entry.sequence;
+        let mut metadata = entry.metadata;
+        metadata.sequence = Some(entry.sequence);
+        Some((entry.event, metadata))
+    }
+
+    /// Skip live events already delivered from the log.
+    fn accept_live(&mut self, metadata: &EventMetadata) -> bool {
+        match metadata.sequence {
+            Some(seq) if self.persistence.is_some() => {
+                if seq <= self.last_sequence {
+                    return false;
+                }
+                self.last_sequence = seq;
+                true
+            }
+            _ => true,
+        }
+    }
+
     /// Get the underlying broadcast receiver.
     pub fn into_inner(self) -> broadcast::Receiver<(NeoMindEvent, EventMetadata)> {
         self.rx
@@ -240,7 +468,7 @@ where
                     // Event didn't match filter, continue waiting
                 }
                 Err(broadcast::error::RecvError::Lagged(n)) => {
-                    let total = self.dropped.fetch_add(n as u64, Ordering::Relaxed) + n as u64;
+                    let total = self.dropped.fetch_add(n, Ordering::Relaxed) + n;
                     tracing::warn!(
                         dropped_total = total,
                         this_batch = n,
@@ -732,6 +960,104 @@ mod tests {
         assert_eq!(received.0.type_name(), "DeviceOnline");
     }
 
+    /// Vec-backed log for exercising cursor replay.
+    #[derive(Default)]
+    struct MemoryLog {
+        events: Mutex<Vec<PersistedEvent>>,
+    }
+
+    impl EventPersistence for MemoryLog {
+        fn append(&self, event: &NeoMindEvent, metadata: &EventMetadata) -> Option<u64> {
+            let mut events = self.events.lock().unwrap();
+            let sequence = events.len() as u64 + 1;
+            events.push(PersistedEvent {
+                sequence,
+                event: event.clone(),
+                metadata: metadata.clone(),
+            });
+            Some(sequence)
+        }
+
+        fn read_from(&self, cursor: u64, limit: usize) -> Vec<PersistedEvent> {
+            let events = self.events.lock().unwrap();
+            events
+                .iter()
+                .filter(|e| e.sequence >= cursor)
+                .take(limit)
+                .cloned()
+                .collect()
+        }
+
+        fn last_sequence(&self) -> u64 {
+            self.events.lock().unwrap().len() as u64
+        }
+    }
+
+    fn online(id: &str) -> NeoMindEvent {
+        NeoMindEvent::DeviceOnline {
+            device_id: id.to_string(),
+            device_type: "sensor".to_string(),
+            timestamp: 0,
+        }
+    }
+
+    fn device_id(event: &NeoMindEvent) -> &str {
+        match event {
+            NeoMindEvent::DeviceOnline { device_id, .. } => device_id,
+            _ => panic!("unexpected event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_from_cursor_replays_then_goes_live() {
+        let bus = EventBus::new().with_persistence(Arc::new(MemoryLog::default()));
+        for id in ["a", "b", "c"] {
+            bus.publish(online(id)).await;
+        }
+
+        let mut rx = bus.subscribe_from(2);
+        bus.publish(online("d")).await;
+
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            let (event, meta) = rx.recv().await.unwrap();
+            seen.push((device_id(&event).to_string(), meta.sequence));
+        }
+        assert_eq!(
+            seen,
+            vec![
+                ("b".to_string(), Some(2)),
+                ("c".to_string(), Some(3)),
+                ("d".to_string(), Some(4)),
+            ]
+        );
+        // "d" was both in the log and on the live channel; no duplicate.
+        assert!(rx.try_recv().is_none());
+        assert_eq!(rx.cursor(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_lagged_cursor_receiver_recovers_from_log() {
+        let bus = EventBus::with_capacity(2).with_persistence(Arc::new(MemoryLog::default()));
+        let mut rx = bus.subscribe_from(1);
+
+        bus.publish(online("dev0")).await;
+        assert_eq!(device_id(&rx.recv().await.unwrap().0), "dev0");
+        // Replay is exhausted and the live copy of dev0 is skipped.
+        assert!(rx.try_recv().is_none());
+
+        // Overflow the broadcast buffer (capacity 2) while live.
+        for i in 1..6 {
+            bus.publish(online(&format!("dev{i}"))).await;
+        }
+
+        for i in 1..6 {
+            let (event, _) = rx.recv().await.unwrap();
+            assert_eq!(device_id(&event), format!("dev{i}"));
+        }
+        assert_eq!(rx.dropped_count(), 0);
+    }
+
     // Phase 2.2: Extension event filter tests
     #[tokio::test]
     async fn test_extension_events_filter() {
@@ -21,7 +21,7 @@
 //! └─────────────────────────┘    └─────────────────────────┘
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -33,6 +33,7 @@ use super::process::{IsolatedExtension, IsolatedExtensionConfig};
 use super::{IsolatedExtensionError, IsolatedResult};
 use crate::extension::event_dispatcher::EventDispatcher;
 use crate::extension::loader::{IsolatedExtensionLoader, IsolatedLoaderConfig};
+use crate::extension::permissions::{ExtensionPermissions, PermissionedProvider};
 use crate::extension::system::{ExtensionMetadata, ExtensionMetricValue};
 
 /// Configuration for the isolated extension manager
@@ -113,6 +114,8 @@ pub struct IsolatedExtensionManager {
     /// Capability provider for handling capability requests from extensions
     capability_provider:
         AsyncRwLock<Option<Arc<dyn super::super::context::ExtensionCapabilityProvider>>>,
+    /// Declared and approved capabilities, checked on every capability request
+    permissions: Arc<ExtensionPermissions>,
     /// Death notification channel for monitoring extension crashes
     death_channel: (broadcast::Sender<()>, AsyncRwLock<broadcast::Receiver<()>>),
     /// Optional callback invoked after crash recovery restart, to apply saved config etc.
@@ -151,6 +154,7 @@ impl IsolatedExtensionManager {
             loader: IsolatedExtensionLoader::new(loader_config),
             event_dispatcher,
             capability_provider: AsyncRwLock::new(None),
+            permissions: Arc::new(ExtensionPermissions::new()),
             death_channel,
             loading_locks: AsyncRwLock::new(HashMap::new()),
             on_crash_recovery_restart: std::sync::RwLock::new(None),
@@ -574,11 +578,20 @@ impl IsolatedExtensionManager {
 
         // Update all existing extensions
         let extensions = self.extensions.read().await;
-        for (_, ext) in extensions.iter() {
-            ext.set_capability_provider(provider.clone());
+        for (id, ext) in extensions.iter() {
+            ext.set_capability_provider(PermissionedProvider::wrap(
+                id,
+                provider.clone(),
+                self.permissions.clone(),
+            ));
         }
     }
 
+    /// Get the extension permission grants
+    pub fn permissions(&self) -> Arc<ExtensionPermissions> {
+        self.permissions.clone()
+    }
+
     /// Get the event dispatcher
     pub fn event_dispatcher(&self) -> Arc<EventDispatcher> {
         self.event_dispatcher.clone()
@@ -594,6 +607,31 @@ impl IsolatedExtensionManager {
     /// This is used to acquire a loading lock BEFORE spawning to prevent
     /// race conditions where multiple concurrent loads could spawn duplicate processes.
     fn read_extension_id_from_manifest(path: &Path) -> Option<String> {
+        let (manifest_path, manifest) = Self::read_manifest(path)?;
+        let id = manifest.get("id").and_then(|v| v.as_str())?;
+        tracing::debug!(
+            manifest_path = %manifest_path.display(),
+            extension_id = %id,
+            "Read extension ID from manifest.json for loading lock"
+        );
+        Some(id.to_string())
+    }
+
+    /// Capability names listed in the manifest's `permissions`
+    fn read_permissions_from_manifest(path: &Path) -> BTreeSet<String> {
+        Self::read_manifest(path)
+            .and_then(|(_, manifest)| {
+                manifest.get("permissions").and_then(|v| v.as_array()).map(|list| {
+                    list.iter()
+                        .filter_map(|p| p.as_str().map(str::to_string))
+                        .collect()
+                })
+            })
+            .unwrap_or_default()
+    }
+
+    /// Find and parse the manifest.json belonging to an extension binary
+    fn read_manifest(path: &Path) -> Option<(PathBuf, serde_json::Value)> {
         // Try to find manifest.json in the extension directory
         // For .nep packages: path is binaries/<platform>/extension.dylib, manifest is at root
         // For legacy: path is extension.dylib, manifest is in same dir
@@ -613,14 +651,7 @@ impl IsolatedExtensionManager {
 
             if let Ok(content) = std::fs::read_to_string(&manifest_path) {
                 if let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(id) = manifest.get("id").and_then(|v| v.as_str()) {
-                        tracing::debug!(
-                            manifest_path = %manifest_path.display(),
-                            extension_id = %id,
-                            "Read extension ID from manifest.json for loading lock"
-                        );
-                        return Some(id.to_string());
-                    }
+                    return Some((manifest_path, manifest));
                 }
             }
         }
@@ -755,9 +786,17 @@ impl IsolatedExtensionManager {
             .await
             .insert(id.clone(), loaded.clone());
 
+        // Refresh the declared permissions; approvals come from the operator
+        self.permissions
+            .declare(&id, Self::read_permissions_from_manifest(path));
+
         // Set capability provider if configured
         if let Some(provider) = self.capability_provider.read().await.as_ref() {
-            loaded.set_capability_provider(provider.clone());
+            loaded.set_capability_provider(PermissionedProvider::wrap(
+                &id,
+                provider.clone(),
+                self.permissions.clone(),
+            ));
 
             // Set up death notification for auto-restart
             loaded
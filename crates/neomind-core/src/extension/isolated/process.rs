@@ -2514,7 +2514,7 @@ impl IsolatedExtension {
                     );
 
                     let mut process_guard = self.process.lock().await;
-                    self.kill_internal(&mut *process_guard).await;
+                    self.kill_internal(&mut process_guard).await;
                     drop(process_guard);
 
                     // Record crash for crash loop detection
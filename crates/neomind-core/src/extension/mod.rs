@@ -20,11 +20,13 @@ pub mod extension_event_subscription;
 pub mod isolated;
 pub mod loader;
 pub mod package;
+pub mod permissions;
 pub mod accel;
 pub mod proxy;
 pub mod registry;
 pub mod runtime;
 pub mod safety;
+pub mod signing;
 pub mod stream;
 pub mod system;
 pub mod tracing;
@@ -50,8 +52,10 @@ pub use package::{
     detect_platform, ExtensionPackage, InstallResult, CURRENT_ABI_VERSION, MIN_ABI_VERSION,
     PACKAGE_FORMAT,
 };
+pub use permissions::{ExtensionPermissions, PermissionGrant};
 pub use registry::{ExtensionInfo, ExtensionRegistry, ExtensionRegistryTrait};
 pub use runtime::{ExtensionRuntime, ExtensionRuntimeConfig, ExtensionRuntimeInfo};
+pub use signing::{SignaturePolicy, SignatureStatus, TrustedPublisher};
 pub use stream::{
     ClientInfo, DataChunk, FlowControl, SessionStats, StreamCapability, StreamDataType,
     StreamDirection, StreamError, StreamMode, StreamResult, StreamSession,
@@ -19,9 +19,11 @@
 //! │   │   └── extension.so
 //! │   ├── windows_amd64/
 //! │   │   └── extension.dll
-//! │   └── wasm/
-//! │       ├── extension.wasm
-//! │       └── extension.json
+//! │   ├── wasm/
+//! │   │   ├── extension.wasm
+//! │   │   └── extension.json
+//! │   └── python/
+//! │       └── extension.py
 //! └── frontend/
 //!     ├── dist/
 //!     │   ├── bundle.js
@@ -362,6 +364,9 @@ pub enum PackageError {
 
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+
+    #[error("Signature check failed: {0}")]
+    Signature(String),
 }
 
 impl From<PackageError> for ExtensionError {
@@ -494,15 +499,19 @@ impl ExtensionPackage {
     }
 
     /// Get the binary path for the current platform
-    /// Falls back to wasm if no native binary is available
+    /// Falls back to wasm, then python, if no native binary is available
     pub fn get_binary_path(&self) -> Option<String> {
         let platform = detect_platform();
         // First try native binary for current platform
         if let Some(path) = self.manifest.binaries.get(&platform) {
             return Some(path.clone());
         }
-        // Fall back to wasm (universal platform)
-        self.manifest.binaries.get("wasm").cloned()
+        // Fall back to wasm or python (universal platforms)
+        self.manifest
+            .binaries
+            .get("wasm")
+            .or_else(|| self.manifest.binaries.get("python"))
+            .cloned()
     }
 
     /// Install the package to a target directory
@@ -714,6 +723,7 @@ impl ExtensionPackage {
             .binaries
             .get(&platform)
             .or_else(|| manifest.binaries.get("wasm"))
+            .or_else(|| manifest.binaries.get("python"))
             .cloned()
             .ok_or_else(|| {
                 let available_platforms: Vec<String> = manifest.binaries.keys().cloned().collect();
@@ -0,0 +1,218 @@
+//! Extension permission grants.
+//!
+//! Extensions list the host capabilities they need in the package manifest's
+//! `permissions` (capability names such as `device_control` or
+//! `storage_query`). Declaring a capability is not enough: an operator grants
+//! it, and every capability request coming back from an extension process is
+//! checked against the grants before it reaches the provider. Extensions
+//! that declare no permissions predate this model and keep unrestricted
+//! access.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use super::context::{
+    CapabilityError, CapabilityManifest, ExtensionCapability, ExtensionCapabilityProvider,
+};
+
+/// Declared and approved capabilities of one extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionGrant {
+    /// Capability names declared in the manifest
+    pub requested: BTreeSet<String>,
+    /// Capability names approved by an operator
+    pub granted: BTreeSet<String>,
+}
+
+impl PermissionGrant {
+    /// Requested capabilities still awaiting approval.
+    pub fn pending(&self) -> Vec<String> {
+        self.requested.difference(&self.granted).cloned().collect()
+    }
+
+    /// Whether the extension is subject to checks at all.
+    pub fn is_enforced(&self) -> bool {
+        !self.requested.is_empty()
+    }
+
+    fn check(&self, capability: &str) -> Result<(), String> {
+        if !self.is_enforced() || self.granted.contains(capability) {
+            return Ok(());
+        }
+        if self.requested.contains(capability) {
+            Err(format!(
+                "Permission '{}' has not been granted to this extension",
+                capability
+            ))
+        } else {
+            Err(format!(
+                "Permission '{}' is not declared in the extension manifest",
+                capability
+            ))
+        }
+    }
+}
+
+/// Grants for all loaded extensions.
+#[derive(Debug, Default)]
+pub struct ExtensionPermissions {
+    grants: RwLock<HashMap<String, PermissionGrant>>,
+}
+
+impl ExtensionPermissions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, extension_id: &str, grant: PermissionGrant) {
+        self.grants.write().insert(extension_id.to_string(), grant);
+    }
+
+    pub fn remove(&self, extension_id: &str) {
+        self.grants.write().remove(extension_id);
+    }
+
+    pub fn get(&self, extension_id: &str) -> Option<PermissionGrant> {
+        self.grants.read().get(extension_id).cloned()
+    }
+
+    /// Record the capabilities an extension's manifest declares, keeping
+    /// approvals for capabilities that are still declared.
+    pub fn declare(&self, extension_id: &str, requested: BTreeSet<String>) {
+        let mut grants = self.grants.write();
+        let grant = grants.entry(extension_id.to_string()).or_default();
+        grant.granted.retain(|name| requested.contains(name));
+        grant.requested = requested;
+    }
+
+    /// Replace the approved capabilities of an extension.
+    pub fn approve(&self, extension_id: &str, granted: BTreeSet<String>) {
+        self.grants
+            .write()
+            .entry(extension_id.to_string())
+            .or_default()
+            .granted = granted;
+    }
+
+    /// Whether `extension_id` may use `capability`.
+    pub fn check(
+        &self,
+        extension_id: &str,
+        capability: &ExtensionCapability,
+    ) -> Result<(), String> {
+        match self.grants.read().get(extension_id) {
+            Some(grant) => grant.check(&capability.name()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Capability provider handed to one extension: checks its grants, then
+/// delegates to the shared provider.
+pub(crate) struct PermissionedProvider {
+    extension_id: String,
+    inner: Arc<dyn ExtensionCapabilityProvider>,
+    permissions: Arc<ExtensionPermissions>,
+}
+
+impl PermissionedProvider {
+    pub(crate) fn wrap(
+        extension_id: &str,
+        inner: Arc<dyn ExtensionCapabilityProvider>,
+        permissions: Arc<ExtensionPermissions>,
+    ) -> Arc<dyn ExtensionCapabilityProvider> {
+        Arc::new(Self {
+            extension_id: extension_id.to_string(),
+            inner,
+            permissions,
+        })
+    }
+}
+
+#[async_trait]
+impl ExtensionCapabilityProvider for PermissionedProvider {
+    fn capability_manifest(&self) -> CapabilityManifest {
+        self.inner.capability_manifest()
+    }
+
+    async fn invoke_capability(
+        &self,
+        capability: ExtensionCapability,
+        params: &serde_json::Value,
+    ) -> Result<serde_json::Value, CapabilityError> {
+        if let Err(reason) = self.permissions.check(&self.extension_id, &capability) {
+            tracing::warn!(
+                extension_id = %self.extension_id,
+                capability = %capability.name(),
+                "Capability request denied: {}",
+                reason
+            );
+            return Err(CapabilityError::ProviderError(format!(
+                "Permission denied: {}",
+                reason
+            )));
+        }
+        self.inner.invoke_capability(capability, params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> BTreeSet<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_permission_checks() {
+        let permissions = ExtensionPermissions::new();
+        permissions.set(
+            "weather",
+            PermissionGrant {
+                requested: names(&["device_metrics_read", "device_control"]),
+                granted: names(&["device_metrics_read"]),
+            },
+        );
+
+        assert!(permissions
+            .check("weather", &ExtensionCapability::DeviceMetricsRead)
+            .is_ok());
+        let err = permissions
+            .check("weather", &ExtensionCapability::DeviceControl)
+            .unwrap_err();
+        assert!(err.contains("not been granted"));
+        let err = permissions
+            .check("weather", &ExtensionCapability::AgentTrigger)
+            .unwrap_err();
+        assert!(err.contains("not declared"));
+        assert_eq!(
+            permissions.get("weather").unwrap().pending(),
+            vec!["device_control".to_string()]
+        );
+
+        // Legacy extensions without declared permissions are unrestricted.
+        permissions.set("legacy", PermissionGrant::default());
+        assert!(permissions
+            .check("legacy", &ExtensionCapability::DeviceControl)
+            .is_ok());
+        assert!(permissions
+            .check("unknown", &ExtensionCapability::DeviceControl)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_declare_keeps_approvals_still_requested() {
+        let permissions = ExtensionPermissions::new();
+        permissions.approve("weather", names(&["device_control", "storage_query"]));
+        permissions.declare("weather", names(&["device_control", "device_metrics_read"]));
+
+        let grant = permissions.get("weather").unwrap();
+        assert_eq!(grant.granted, names(&["device_control"]));
+        assert_eq!(grant.pending(), vec!["device_metrics_read".to_string()]);
+    }
+}
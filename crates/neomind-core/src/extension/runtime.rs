@@ -333,6 +333,11 @@ impl ExtensionRuntime {
             .await;
     }
 
+    /// Declared and approved capabilities of loaded extensions.
+    pub fn permissions(&self) -> Arc<super::permissions::ExtensionPermissions> {
+        self.isolated_manager.permissions()
+    }
+
     /// Get a proxy extension for streaming operations.
     pub async fn get_extension(
         &self,
@@ -0,0 +1,489 @@
+//! Extension package signatures.
+//!
+//! A signed `.nep` carries `signature.json` at the archive root: an Ed25519
+//! signature by a named publisher. The signature covers the manifest id and
+//! every other entry — the digest starts with `id \0`, then entries are
+//! sorted by name and each contributes `name \0 sha256(content)` — so it
+//! survives re-zipping but not any change to a file's content or name.
+//!
+//! [`SignaturePolicy`] holds the trusted publisher keys. A signed package
+//! must verify against its publisher's key; unsigned packages are accepted
+//! unless `require_signature` is set.
+//!
+//! Installing a signed package pins its key in [`SIGNER_FILE`] inside the
+//! extension's directory; later installs of the same id must be signed with
+//! that key until the extension is uninstalled.
+
+use std::io::{Cursor, Read, Seek, Write};
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::extension::package::PackageError;
+
+/// Signature entry at the package root
+pub const SIGNATURE_FILE: &str = "signature.json";
+/// Signer pinned in an installed extension's directory
+pub const SIGNER_FILE: &str = "signer.json";
+const MANIFEST_FILE: &str = "manifest.json";
+const ALGORITHM: &str = "ed25519";
+
+/// Contents of `signature.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageSignature {
+    pub publisher: String,
+    pub algorithm: String,
+    /// Base64 signature over the package digest
+    pub signature: String,
+}
+
+/// A publisher whose packages are trusted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedPublisher {
+    pub name: String,
+    /// Base64 Ed25519 public key (32 bytes)
+    pub public_key: String,
+}
+
+/// Which package signatures are accepted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SignaturePolicy {
+    /// Reject unsigned packages
+    #[serde(default)]
+    pub require_signature: bool,
+    #[serde(default)]
+    pub publishers: Vec<TrustedPublisher>,
+}
+
+impl SignaturePolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, publisher) in self.publishers.iter().enumerate() {
+            if publisher.name.trim().is_empty() {
+                return Err("Publisher name must not be empty".to_string());
+            }
+            if self.publishers[..i]
+                .iter()
+                .any(|p| p.name == publisher.name)
+            {
+                return Err(format!("Duplicate publisher: {}", publisher.name));
+            }
+            decode_public_key(&publisher.public_key)
+                .map_err(|e| format!("Publisher {}: {}", publisher.name, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of a successful verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum SignatureStatus {
+    /// No signature, accepted because the policy doesn't require one
+    Unsigned,
+    Verified {
+        publisher: String,
+        /// Base64 public key the package verified against
+        public_key: String,
+    },
+}
+
+/// Contents of [`SIGNER_FILE`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinnedSigner {
+    pub publisher: String,
+    pub public_key: String,
+}
+
+/// Parse a base64 Ed25519 public key.
+pub fn decode_public_key(key: &str) -> Result<VerifyingKey, String> {
+    let bytes: [u8; 32] = STANDARD
+        .decode(key.trim())
+        .map_err(|e| format!("Invalid base64 public key: {}", e))?
+        .try_into()
+        .map_err(|_| "Public key must be 32 bytes".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("Invalid public key: {}", e))
+}
+
+/// Parse a base64 Ed25519 private key seed (32 bytes).
+pub fn decode_signing_key(key: &str) -> Result<SigningKey, String> {
+    let bytes: [u8; 32] = STANDARD
+        .decode(key.trim())
+        .map_err(|e| format!("Invalid base64 signing key: {}", e))?
+        .try_into()
+        .map_err(|_| "Signing key must be 32 bytes".to_string())?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+/// Base64 public key for `signing_key`, for publishing to operators.
+pub fn public_key(signing_key: &SigningKey) -> String {
+    STANDARD.encode(signing_key.verifying_key().to_bytes())
+}
+
+/// The `id` field of the package's manifest.
+pub fn manifest_id<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<String, PackageError> {
+    let entry = archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|e| PackageError::Zip(format!("{}: {}", MANIFEST_FILE, e)))?;
+    let manifest: serde_json::Value = serde_json::from_reader(entry)?;
+    manifest
+        .get("id")
+        .and_then(|id| id.as_str())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| PackageError::Signature(format!("{} has no id", MANIFEST_FILE)))
+}
+
+/// Digest of the manifest id and every entry except the signature.
+pub fn package_digest<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<[u8; 32], PackageError> {
+    let id = manifest_id(archive)?;
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|name| *name != SIGNATURE_FILE && !name.ends_with('/'))
+        .map(str::to_string)
+        .collect();
+    names.sort();
+
+    let mut digest = Sha256::new();
+    digest.update(id.as_bytes());
+    digest.update([0u8]);
+    for name in names {
+        let mut entry = archive
+            .by_name(&name)
+            .map_err(|e| PackageError::Zip(e.to_string()))?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut entry, &mut hasher)?;
+        digest.update(name.as_bytes());
+        digest.update([0u8]);
+        digest.update(hasher.finalize());
+    }
+    Ok(digest.finalize().into())
+}
+
+fn read_signature<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<Option<PackageSignature>, PackageError> {
+    let mut entry = match archive.by_name(SIGNATURE_FILE) {
+        Ok(entry) => entry,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(PackageError::Zip(e.to_string())),
+    };
+    let mut content = String::new();
+    entry.read_to_string(&mut content)?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| PackageError::Signature(format!("Invalid {}: {}", SIGNATURE_FILE, e)))
+}
+
+/// Check an opened package against `policy`.
+pub fn verify_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    policy: &SignaturePolicy,
+) -> Result<SignatureStatus, PackageError> {
+    let Some(signature) = read_signature(archive)? else {
+        if policy.require_signature {
+            return Err(PackageError::Signature(
+                "Package is unsigned and unsigned packages are not allowed".to_string(),
+            ));
+        }
+        return Ok(SignatureStatus::Unsigned);
+    };
+
+    if signature.algorithm != ALGORITHM {
+        return Err(PackageError::Signature(format!(
+            "Unsupported signature algorithm: {}",
+            signature.algorithm
+        )));
+    }
+    let publisher = policy
+        .publishers
+        .iter()
+        .find(|p| p.name == signature.publisher)
+        .ok_or_else(|| {
+            PackageError::Signature(format!(
+                "Publisher '{}' is not trusted",
+                signature.publisher
+            ))
+        })?;
+    let key = decode_public_key(&publisher.public_key).map_err(PackageError::Signature)?;
+    let bytes = STANDARD
+        .decode(&signature.signature)
+        .map_err(|e| PackageError::Signature(format!("Invalid base64 signature: {}", e)))?;
+    let sig = Signature::from_slice(&bytes)
+        .map_err(|e| PackageError::Signature(format!("Invalid signature: {}", e)))?;
+
+    let digest = package_digest(archive)?;
+    key.verify(&digest, &sig).map_err(|_| {
+        PackageError::Signature(format!(
+            "Signature by '{}' does not match the package contents",
+            signature.publisher
+        ))
+    })?;
+    Ok(SignatureStatus::Verified {
+        publisher: signature.publisher,
+        public_key: publisher.public_key.trim().to_string(),
+    })
+}
+
+/// Check `status` against the signer pinned by an earlier install in
+/// `ext_dir`. Nothing is pinned for fresh or unsigned installs.
+pub fn check_pinned_signer(ext_dir: &Path, status: &SignatureStatus) -> Result<(), PackageError> {
+    let content = match std::fs::read_to_string(ext_dir.join(SIGNER_FILE)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    let pinned: PinnedSigner = serde_json::from_str(&content)
+        .map_err(|e| PackageError::Signature(format!("Invalid {}: {}", SIGNER_FILE, e)))?;
+    match status {
+        SignatureStatus::Verified { public_key, .. } if *public_key == pinned.public_key => Ok(()),
+        _ => Err(PackageError::Signature(format!(
+            "Installed extension is signed by '{}'; upgrades must be signed with the same key",
+            pinned.publisher
+        ))),
+    }
+}
+
+/// Pin the signer of a just-installed package in `ext_dir`, or clear the pin
+/// for an unsigned one.
+pub fn record_signer(ext_dir: &Path, status: &SignatureStatus) -> std::io::Result<()> {
+    let path = ext_dir.join(SIGNER_FILE);
+    match status {
+        SignatureStatus::Verified {
+            publisher,
+            public_key,
+        } => {
+            let pinned = PinnedSigner {
+                publisher: publisher.clone(),
+                public_key: public_key.clone(),
+            };
+            std::fs::write(path, serde_json::to_vec_pretty(&pinned)?)
+        }
+        SignatureStatus::Unsigned => match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        },
+    }
+}
+
+/// Check an opened package against `policy` and against the signer pinned
+/// by an earlier install of the same extension under `extensions_dir`.
+pub fn verify_install_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    policy: &SignaturePolicy,
+    extensions_dir: &Path,
+) -> Result<SignatureStatus, PackageError> {
+    let status = verify_archive(archive, policy)?;
+    let id = manifest_id(archive)?;
+    check_pinned_signer(&extensions_dir.join(id), &status)?;
+    Ok(status)
+}
+
+/// Check an in-memory package against `policy`.
+pub fn verify_bytes(
+    data: &[u8],
+    policy: &SignaturePolicy,
+) -> Result<SignatureStatus, PackageError> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(data)).map_err(|e| PackageError::Zip(e.to_string()))?;
+    verify_archive(&mut archive, policy)
+}
+
+/// Check a package file against `policy` without reading it into memory.
+pub fn verify_file(path: &Path, policy: &SignaturePolicy) -> Result<SignatureStatus, PackageError> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = ZipArchive::new(file).map_err(|e| PackageError::Zip(e.to_string()))?;
+    verify_archive(&mut archive, policy)
+}
+
+/// [`verify_install_archive`] for an in-memory package.
+pub fn verify_install_bytes(
+    data: &[u8],
+    policy: &SignaturePolicy,
+    extensions_dir: &Path,
+) -> Result<SignatureStatus, PackageError> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(data)).map_err(|e| PackageError::Zip(e.to_string()))?;
+    verify_install_archive(&mut archive, policy, extensions_dir)
+}
+
+/// [`verify_install_archive`] for a package file.
+pub fn verify_install_file(
+    path: &Path,
+    policy: &SignaturePolicy,
+    extensions_dir: &Path,
+) -> Result<SignatureStatus, PackageError> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = ZipArchive::new(file).map_err(|e| PackageError::Zip(e.to_string()))?;
+    verify_install_archive(&mut archive, policy, extensions_dir)
+}
+
+/// Return a copy of the package signed by `publisher`, replacing any existing
+/// signature.
+pub fn sign_package(
+    data: &[u8],
+    publisher: &str,
+    signing_key: &SigningKey,
+) -> Result<Vec<u8>, PackageError> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(data)).map_err(|e| PackageError::Zip(e.to_string()))?;
+    let digest = package_digest(&mut archive)?;
+    let signature = PackageSignature {
+        publisher: publisher.to_string(),
+        algorithm: ALGORITHM.to_string(),
+        signature: STANDARD.encode(signing_key.sign(&digest).to_bytes()),
+    };
+
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| PackageError::Zip(e.to_string()))?;
+        if entry.name() == SIGNATURE_FILE {
+            continue;
+        }
+        writer
+            .raw_copy_file(entry)
+            .map_err(|e| PackageError::Zip(e.to_string()))?;
+    }
+    writer
+        .start_file(SIGNATURE_FILE, zip::write::SimpleFileOptions::default())
+        .map_err(|e| PackageError::Zip(e.to_string()))?;
+    writer.write_all(&serde_json::to_vec_pretty(&signature)?)?;
+    let cursor = writer
+        .finish()
+        .map_err(|e| PackageError::Zip(e.to_string()))?;
+    Ok(cursor.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEATHER: &str = r#"{"id":"weather"}"#;
+
+    fn package(manifest: &str) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("manifest.json", options).unwrap();
+        writer.write_all(manifest.as_bytes()).unwrap();
+        writer
+            .start_file("binaries/linux_amd64/extension.so", options)
+            .unwrap();
+        writer.write_all(b"\x7fELF").unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn policy(key: &SigningKey) -> SignaturePolicy {
+        SignaturePolicy {
+            require_signature: true,
+            publishers: vec![TrustedPublisher {
+                name: "acme".to_string(),
+                public_key: public_key(key),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let signed = sign_package(&package(WEATHER), "acme", &key).unwrap();
+        assert_eq!(
+            verify_bytes(&signed, &policy(&key)).unwrap(),
+            SignatureStatus::Verified {
+                publisher: "acme".to_string(),
+                public_key: public_key(&key),
+            }
+        );
+
+        // Re-signing replaces the signature rather than adding a second one.
+        let resigned = sign_package(&signed, "acme", &key).unwrap();
+        assert!(verify_bytes(&resigned, &policy(&key)).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_tampered_and_untrusted() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let signed = sign_package(&package(WEATHER), "acme", &key).unwrap();
+
+        // Same signature on different contents.
+        let mut archive = ZipArchive::new(Cursor::new(signed.as_slice())).unwrap();
+        let signature = read_signature(&mut archive).unwrap().unwrap();
+        let mut writer =
+            zip::ZipWriter::new_append(Cursor::new(package("{\"id\":\"evil\"}"))).unwrap();
+        writer
+            .start_file(SIGNATURE_FILE, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer
+            .write_all(&serde_json::to_vec(&signature).unwrap())
+            .unwrap();
+        let tampered = writer.finish().unwrap().into_inner();
+        assert!(verify_bytes(&tampered, &policy(&key)).is_err());
+
+        let other = SigningKey::from_bytes(&[9; 32]);
+        assert!(verify_bytes(&signed, &policy(&other)).is_err());
+        assert!(verify_bytes(&signed, &SignaturePolicy::default()).is_err());
+    }
+
+    #[test]
+    fn test_unsigned_policy() {
+        let unsigned = package(WEATHER);
+        assert_eq!(
+            verify_bytes(&unsigned, &SignaturePolicy::default()).unwrap(),
+            SignatureStatus::Unsigned
+        );
+        let key = SigningKey::from_bytes(&[7; 32]);
+        assert!(verify_bytes(&unsigned, &policy(&key)).is_err());
+    }
+
+    #[test]
+    fn test_signature_covers_manifest_id() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut archive = ZipArchive::new(Cursor::new(package(WEATHER))).unwrap();
+        let weather = package_digest(&mut archive).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(package(r#"{"id":"weather2"}"#))).unwrap();
+        assert_ne!(weather, package_digest(&mut archive).unwrap());
+
+        // A manifest without an id can't be signed
+        assert!(sign_package(&package("{}"), "acme", &key).is_err());
+    }
+
+    #[test]
+    fn test_upgrade_must_keep_signing_key() {
+        let dir = std::env::temp_dir().join(format!("neomind-signer-{}", uuid::Uuid::new_v4()));
+        let ext_dir = dir.join("weather");
+        std::fs::create_dir_all(&ext_dir).unwrap();
+
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let other = SigningKey::from_bytes(&[9; 32]);
+        let mut both = policy(&key);
+        both.require_signature = false;
+        both.publishers.push(TrustedPublisher {
+            name: "mallory".to_string(),
+            public_key: public_key(&other),
+        });
+
+        // Nothing pinned yet: any trusted signature installs and gets pinned
+        let signed = sign_package(&package(WEATHER), "acme", &key).unwrap();
+        let status = verify_install_bytes(&signed, &both, &dir).unwrap();
+        record_signer(&ext_dir, &status).unwrap();
+
+        // Same key upgrades; another trusted key or no signature does not
+        assert!(verify_install_bytes(&signed, &both, &dir).is_ok());
+        let hijack = sign_package(&package(WEATHER), "mallory", &other).unwrap();
+        assert!(verify_install_bytes(&hijack, &both, &dir).is_err());
+        assert!(verify_install_bytes(&package(WEATHER), &both, &dir).is_err());
+
+        // Uninstalling removes the pin along with the directory
+        std::fs::remove_dir_all(&ext_dir).unwrap();
+        assert!(verify_install_bytes(&hijack, &both, &dir).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -4,6 +4,8 @@
 
 // alerts module removed - use neomind_messages instead
 pub mod brand;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod config;
 pub mod dashboard;
 pub mod datasource;
@@ -13,6 +15,9 @@ pub mod eventbus;
 pub mod extension;
 pub mod llm;
 pub mod message;
+pub mod metrics;
+pub mod secrets;
+pub mod tenant;
 pub mod tools;
 
 pub use llm::LlmError;
@@ -10,6 +10,7 @@ use std::time::Duration;
 
 use super::modality::{ImageContent, ModalityContent};
 use crate::message::{Message, MessageRole};
+use crate::metrics;
 
 /// LLM backend identifier.
 ///
@@ -526,6 +527,29 @@ impl BackendMetrics {
             + latency_ms as f64)
             / self.total_requests as f64;
         self.last_request = Some(std::time::SystemTime::now());
+
+        let registry = metrics::global();
+        registry
+            .counter(
+                "neomind_llm_requests_total",
+                "LLM backend requests",
+                &[("status", "success")],
+            )
+            .inc();
+        registry
+            .counter(
+                "neomind_llm_completion_tokens_total",
+                "Tokens generated by LLM backends",
+                &[],
+            )
+            .inc_by(tokens);
+        registry
+            .histogram(
+                "neomind_llm_request_seconds",
+                "LLM backend request latency",
+                &[],
+            )
+            .observe_duration(Duration::from_millis(latency_ms));
     }
 
     /// Record a failed request.
@@ -533,6 +557,14 @@ impl BackendMetrics {
         self.total_requests += 1;
         self.failed_requests += 1;
         self.last_request = Some(std::time::SystemTime::now());
+
+        metrics::global()
+            .counter(
+                "neomind_llm_requests_total",
+                "LLM backend requests",
+                &[("status", "error")],
+            )
+            .inc();
     }
 
     /// Get success rate (0.0 to 1.0).
@@ -20,5 +20,8 @@ pub use capability::{
 pub use compaction::{
     compact_messages, estimate_tokens, CompactionConfig, CompactionResult, MessagePriority,
 };
-pub use modality::{ImageContent, ImageInput, ModalityContent};
+pub use modality::{
+    AudioContent, AudioFormat, AudioTranscriber, ImageContent, ImageInput, ModalityContent,
+    Transcription,
+};
 pub use models::*;
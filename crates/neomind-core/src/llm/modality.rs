@@ -17,6 +17,9 @@ pub enum ModalityContent {
     /// Image content.
     Image(ImageContent),
 
+    /// Audio content (speech). Transcribed to text before reaching the LLM.
+    Audio(AudioContent),
+
     /// Mixed content (multiple parts).
     Mixed(Vec<ModalityContent>),
 }
@@ -47,6 +50,11 @@ impl ModalityContent {
         }))
     }
 
+    /// Create audio content from raw bytes.
+    pub fn audio_bytes(data: Vec<u8>, format: AudioFormat) -> Self {
+        Self::Audio(AudioContent { data, format })
+    }
+
     /// Create mixed content.
     pub fn mixed(parts: Vec<ModalityContent>) -> Self {
         Self::Mixed(parts)
@@ -62,15 +70,26 @@ impl ModalityContent {
         match self {
             Self::Text(_) => false,
             Self::Image(_) => true,
+            Self::Audio(_) => false,
             Self::Mixed(parts) => parts.iter().any(|p| p.has_images()),
         }
     }
 
+    /// Check if this content contains audio.
+    pub fn has_audio(&self) -> bool {
+        match self {
+            Self::Audio(_) => true,
+            Self::Mixed(parts) => parts.iter().any(|p| p.has_audio()),
+            _ => false,
+        }
+    }
+
     /// Get the text representation.
     pub fn as_text(&self) -> String {
         match self {
             Self::Text(s) => s.clone(),
             Self::Image(img) => format!("[Image: {}]", img.description()),
+            Self::Audio(audio) => format!("[Audio: {}]", audio.format.mime_type()),
             Self::Mixed(parts) => parts
                 .iter()
                 .map(|p| p.as_text())
@@ -83,7 +102,7 @@ impl ModalityContent {
     pub fn extract_text(&self) -> String {
         match self {
             Self::Text(s) => s.clone(),
-            Self::Image(_) => String::new(),
+            Self::Image(_) | Self::Audio(_) => String::new(),
             Self::Mixed(parts) => parts
                 .iter()
                 .map(|p| p.extract_text())
@@ -170,6 +189,107 @@ impl ImageContent {
     }
 }
 
+/// Audio content for multimodal messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioContent {
+    /// Encoded audio bytes.
+    pub data: Vec<u8>,
+    /// Container/codec of `data`.
+    pub format: AudioFormat,
+}
+
+/// Audio format hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    Wav,
+    Mp3,
+    Ogg,
+    Webm,
+    Flac,
+    M4a,
+}
+
+impl AudioFormat {
+    /// Get MIME type for this format.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            Self::Mp3 => "audio/mpeg",
+            Self::Ogg => "audio/ogg",
+            Self::Webm => "audio/webm",
+            Self::Flac => "audio/flac",
+            Self::M4a => "audio/mp4",
+        }
+    }
+
+    /// Get the canonical file extension.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Mp3 => "mp3",
+            Self::Ogg => "ogg",
+            Self::Webm => "webm",
+            Self::Flac => "flac",
+            Self::M4a => "m4a",
+        }
+    }
+
+    /// Detect format from file extension.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        let ext_lower = ext.to_lowercase();
+        let ext = ext_lower.trim_start_matches('.');
+        match ext {
+            "wav" => Some(Self::Wav),
+            "mp3" => Some(Self::Mp3),
+            "ogg" | "oga" | "opus" => Some(Self::Ogg),
+            "webm" => Some(Self::Webm),
+            "flac" => Some(Self::Flac),
+            "m4a" | "mp4" => Some(Self::M4a),
+            _ => None,
+        }
+    }
+
+    /// Detect format from a MIME type (parameters such as `;codecs=opus` are ignored).
+    pub fn from_mime_type(mime: &str) -> Option<Self> {
+        let essence = mime.split(';').next().unwrap_or_default().trim();
+        match essence.to_lowercase().as_str() {
+            "audio/wav" | "audio/x-wav" | "audio/wave" => Some(Self::Wav),
+            "audio/mpeg" | "audio/mp3" => Some(Self::Mp3),
+            "audio/ogg" | "audio/opus" => Some(Self::Ogg),
+            "audio/webm" | "video/webm" => Some(Self::Webm),
+            "audio/flac" | "audio/x-flac" => Some(Self::Flac),
+            "audio/mp4" | "audio/m4a" | "audio/x-m4a" => Some(Self::M4a),
+            _ => None,
+        }
+    }
+}
+
+/// Result of a speech-to-text run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Transcription {
+    /// Recognized text.
+    pub text: String,
+    /// Detected or requested language (ISO 639-1), if known.
+    pub language: Option<String>,
+    /// Audio duration in seconds, if reported.
+    pub duration_secs: Option<f32>,
+}
+
+/// Speech-to-text engine turning audio input into a text prompt.
+#[async_trait::async_trait]
+pub trait AudioTranscriber: Send + Sync {
+    /// Engine name, for logs.
+    fn name(&self) -> &str;
+
+    /// Transcribe `audio`. `language` is an optional ISO 639-1 hint.
+    async fn transcribe(
+        &self,
+        audio: &AudioContent,
+        language: Option<&str>,
+    ) -> Result<Transcription, super::backend::LlmError>;
+}
+
 /// Input for image processing.
 ///
 /// This is a lower-level representation used for backend
@@ -299,6 +419,22 @@ mod tests {
         assert_eq!(format, ImageFormat::Jpeg);
     }
 
+    #[test]
+    fn test_audio_content() {
+        let content = ModalityContent::mixed(vec![
+            ModalityContent::text("Transcribe:"),
+            ModalityContent::audio_bytes(vec![0u8; 4], AudioFormat::Wav),
+        ]);
+        assert!(content.has_audio());
+        assert!(!content.has_images());
+        assert_eq!(content.as_text(), "Transcribe: [Audio: audio/wav]");
+        assert_eq!(
+            AudioFormat::from_mime_type("audio/webm;codecs=opus"),
+            Some(AudioFormat::Webm)
+        );
+        assert_eq!(AudioFormat::from_extension(".MP3"), Some(AudioFormat::Mp3));
+    }
+
     #[test]
     fn test_image_input() {
         let input = ImageInput::from_url("https://example.com/image.png");
@@ -0,0 +1,363 @@
+//! Process-wide metrics registry.
+//!
+//! Crates record counters, gauges and histograms into the shared [`global`]
+//! registry and the API server renders it in the Prometheus text exposition
+//! format (`GET /metrics`, feature `prometheus`). Recording is a handful of
+//! atomic operations, so instrumentation stays in place whether or not
+//! anything scrapes it.
+//!
+//! Metric handles are cheap to clone; hot paths should look a handle up once
+//! (e.g. in a `Lazy`) instead of on every call.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+/// Default histogram buckets for latencies, in seconds.
+pub const DEFAULT_LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+static GLOBAL: Lazy<MetricsRegistry> = Lazy::new(MetricsRegistry::new);
+
+/// The registry shared by all crates in the process.
+pub fn global() -> &'static MetricsRegistry {
+    &GLOBAL
+}
+
+/// Monotonically increasing counter.
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    pub fn inc_by(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Value that can go up and down.
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Distribution of observed values over fixed buckets.
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    /// Non-cumulative count per bucket; the last entry is `+Inf`.
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    /// Sum of observations, stored as `f64` bits.
+    sum: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &[f64]) -> Self {
+        Self {
+            bounds: bounds.to_vec(),
+            buckets: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        let idx = self
+            .bounds
+            .iter()
+            .position(|b| value <= *b)
+            .unwrap_or(self.bounds.len());
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let _ = self
+            .sum
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                Some((f64::from_bits(bits) + value).to_bits())
+            });
+    }
+
+    /// Observe a duration in seconds.
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn sum(&self) -> f64 {
+        f64::from_bits(self.sum.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Clone)]
+enum Metric {
+    Counter(Arc<Counter>),
+    Gauge(Arc<Gauge>),
+    Histogram(Arc<Histogram>),
+}
+
+impl Metric {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Metric::Counter(_) => "counter",
+            Metric::Gauge(_) => "gauge",
+            Metric::Histogram(_) => "histogram",
+        }
+    }
+}
+
+struct Family {
+    help: &'static str,
+    /// Rendered label set (`a="x",b="y"`) -> series
+    series: BTreeMap<String, Metric>,
+}
+
+/// Named metric families with optional labels.
+#[derive(Default)]
+pub struct MetricsRegistry {
+    families: RwLock<BTreeMap<&'static str, Family>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get or register a counter.
+    pub fn counter(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+    ) -> Arc<Counter> {
+        match self.get_or_register(name, help, labels, || {
+            Metric::Counter(Arc::new(Counter::default()))
+        }) {
+            Metric::Counter(c) => c,
+            _ => Arc::new(Counter::default()),
+        }
+    }
+
+    /// Get or register a gauge.
+    pub fn gauge(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+    ) -> Arc<Gauge> {
+        match self.get_or_register(name, help, labels, || {
+            Metric::Gauge(Arc::new(Gauge::default()))
+        }) {
+            Metric::Gauge(g) => g,
+            _ => Arc::new(Gauge::default()),
+        }
+    }
+
+    /// Get or register a histogram with [`DEFAULT_LATENCY_BUCKETS`].
+    pub fn histogram(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+    ) -> Arc<Histogram> {
+        match self.get_or_register(name, help, labels, || {
+            Metric::Histogram(Arc::new(Histogram::new(DEFAULT_LATENCY_BUCKETS)))
+        }) {
+            Metric::Histogram(h) => h,
+            _ => Arc::new(Histogram::new(DEFAULT_LATENCY_BUCKETS)),
+        }
+    }
+
+    /// Look up a series, registering it on first use. A name already used
+    /// for a different metric type yields a detached metric (and a warning)
+    /// rather than corrupting the exposition.
+    fn get_or_register(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        make: impl FnOnce() -> Metric,
+    ) -> Metric {
+        let key = render_labels(labels);
+        if let Some(metric) = self
+            .families
+            .read()
+            .get(name)
+            .and_then(|f| f.series.get(&key))
+        {
+            return metric.clone();
+        }
+
+        let created = make();
+        let mut families = self.families.write();
+        let family = families.entry(name).or_insert_with(|| Family {
+            help,
+            series: BTreeMap::new(),
+        });
+        if let Some(existing) = family.series.values().next() {
+            if existing.type_name() != created.type_name() {
+                tracing::warn!(
+                    metric = name,
+                    registered = existing.type_name(),
+                    requested = created.type_name(),
+                    "Metric registered with a different type"
+                );
+                return created;
+            }
+        }
+        family.series.entry(key).or_insert(created).clone()
+    }
+
+    /// Render all metrics in the Prometheus text exposition format (0.0.4).
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (name, family) in self.families.read().iter() {
+            let Some(first) = family.series.values().next() else {
+                continue;
+            };
+            let _ = writeln!(out, "# HELP {} {}", name, family.help);
+            let _ = writeln!(out, "# TYPE {} {}", name, first.type_name());
+            for (labels, metric) in &family.series {
+                match metric {
+                    Metric::Counter(c) => write_sample(&mut out, name, labels, c.get()),
+                    Metric::Gauge(g) => write_sample(&mut out, name, labels, g.get()),
+                    Metric::Histogram(h) => write_histogram(&mut out, name, labels, h),
+                }
+            }
+        }
+        out
+    }
+}
+
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    let mut sorted: Vec<_> = labels.to_vec();
+    sorted.sort_by_key(|(k, _)| *k);
+    sorted
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn write_sample(out: &mut String, name: &str, labels: &str, value: impl std::fmt::Display) {
+    if labels.is_empty() {
+        let _ = writeln!(out, "{} {}", name, value);
+    } else {
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+    }
+}
+
+fn write_histogram(out: &mut String, name: &str, labels: &str, histogram: &Histogram) {
+    let sep = if labels.is_empty() { "" } else { "," };
+    let mut cumulative = 0;
+    for (i, bucket) in histogram.buckets.iter().enumerate() {
+        cumulative += bucket.load(Ordering::Relaxed);
+        let le = histogram
+            .bounds
+            .get(i)
+            .map(|b| b.to_string())
+            .unwrap_or_else(|| "+Inf".to_string());
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}{}le=\"{}\"}} {}",
+            name, labels, sep, le, cumulative
+        );
+    }
+    write_sample(out, &format!("{}_sum", name), labels, histogram.sum());
+    write_sample(out, &format!("{}_count", name), labels, histogram.count());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_series_is_shared() {
+        let registry = MetricsRegistry::new();
+        registry
+            .counter("requests_total", "Requests", &[("status", "ok")])
+            .inc();
+        registry
+            .counter("requests_total", "Requests", &[("status", "ok")])
+            .inc_by(2);
+        registry
+            .counter("requests_total", "Requests", &[("status", "error")])
+            .inc();
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE requests_total counter"));
+        assert!(text.contains("requests_total{status=\"ok\"} 3"));
+        assert!(text.contains("requests_total{status=\"error\"} 1"));
+    }
+
+    #[test]
+    fn test_histogram_rendering() {
+        let registry = MetricsRegistry::new();
+        let h = registry.histogram("op_seconds", "Latency", &[("op", "read")]);
+        h.observe(0.003);
+        h.observe(0.2);
+        h.observe(100.0);
+
+        let text = registry.render();
+        assert!(text.contains("op_seconds_bucket{op=\"read\",le=\"0.005\"} 1"));
+        assert!(text.contains("op_seconds_bucket{op=\"read\",le=\"0.25\"} 2"));
+        assert!(text.contains("op_seconds_bucket{op=\"read\",le=\"+Inf\"} 3"));
+        assert!(text.contains("op_seconds_count{op=\"read\"} 3"));
+    }
+
+    #[test]
+    fn test_type_conflict_is_detached() {
+        let registry = MetricsRegistry::new();
+        registry.gauge("depth", "Depth", &[]).set(5);
+        registry.counter("depth", "Depth", &[]).inc();
+        let text = registry.render();
+        assert!(text.contains("# TYPE depth gauge"));
+        assert!(text.contains("depth 5"));
+    }
+
+    #[test]
+    fn test_label_escaping() {
+        assert_eq!(
+            render_labels(&[("b", "x\"y"), ("a", "1")]),
+            "a=\"1\",b=\"x\\\"y\""
+        );
+    }
+}
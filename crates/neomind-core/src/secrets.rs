@@ -0,0 +1,323 @@
+//! Secret handles and redaction.
+//!
+//! Credentials used by integrations (LLM API keys, MQTT and SMTP passwords)
+//! can be kept out of plain configuration by storing them in the encrypted
+//! secrets store and referencing them by handle, e.g. `secret://smtp-password`.
+//! Code that consumes a credential passes the configured value through
+//! [`resolve`]: plain values are returned unchanged, handles are looked up in
+//! the [`SecretResolver`] registered at startup.
+//!
+//! Resolved values are remembered so [`redact_text`] (and the
+//! [`RedactingMakeWriter`] wrapped around log output) can mask them wherever
+//! they end up; [`redact_json`] masks plain credentials in configuration
+//! that is exported or recorded in history.
+
+use std::borrow::Cow;
+use std::io;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde_json::Value;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Prefix of a secret handle.
+pub const SECRET_SCHEME: &str = "secret://";
+
+/// Replacement for redacted values.
+pub const REDACTED: &str = "***";
+
+/// Shorter values are not redacted from free text, they would match too much.
+const MIN_REDACT_LEN: usize = 6;
+
+const MAX_SECRET_NAME_LEN: usize = 64;
+
+/// Configuration keys whose values are credentials.
+const SENSITIVE_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "api_key",
+    "apikey",
+    "secret",
+    "token",
+    "access_key",
+    "private_key",
+    "client_key",
+    "credential",
+    "credentials",
+];
+
+static RESOLVER: Lazy<RwLock<Option<Arc<dyn SecretResolver>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Plaintext secret values, longest first.
+static KNOWN_VALUES: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Secret lookup or management failure.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SecretError {
+    #[error("invalid secret name '{0}': use 1-64 lowercase letters, digits, '-', '_' or '.'")]
+    InvalidName(String),
+    #[error("secret '{0}' not found")]
+    NotFound(String),
+    #[error("secret '{0}' already exists")]
+    AlreadyExists(String),
+    #[error("no secrets store available to resolve '{0}'")]
+    NoResolver(String),
+    #[error("secrets store error: {0}")]
+    Store(String),
+}
+
+/// Source of secret values, registered once with [`set_resolver`].
+pub trait SecretResolver: Send + Sync {
+    /// Plaintext value of the named secret, `None` if it doesn't exist.
+    fn resolve_secret(&self, name: &str) -> Result<Option<String>, SecretError>;
+}
+
+/// Register the resolver used by [`resolve`].
+pub fn set_resolver(resolver: Arc<dyn SecretResolver>) {
+    *RESOLVER.write() = Some(resolver);
+}
+
+/// Check that `name` can be used as a secret name.
+pub fn validate_name(name: &str) -> Result<(), SecretError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SECRET_NAME_LEN
+        && name.chars().all(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_' || c == '.'
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(SecretError::InvalidName(name.to_string()))
+    }
+}
+
+/// Handle referencing the named secret.
+pub fn handle(name: &str) -> String {
+    format!("{}{}", SECRET_SCHEME, name)
+}
+
+/// Secret name if `value` is a handle.
+pub fn parse_handle(value: &str) -> Option<&str> {
+    value.strip_prefix(SECRET_SCHEME)
+}
+
+/// Resolve a configured credential: handles are looked up, anything else is
+/// returned unchanged.
+pub fn resolve(value: &str) -> Result<Cow<'_, str>, SecretError> {
+    let Some(name) = parse_handle(value) else {
+        return Ok(Cow::Borrowed(value));
+    };
+    let resolver = RESOLVER
+        .read()
+        .clone()
+        .ok_or_else(|| SecretError::NoResolver(value.to_string()))?;
+    let secret = resolver
+        .resolve_secret(name)?
+        .ok_or_else(|| SecretError::NotFound(name.to_string()))?;
+    register_value(&secret);
+    Ok(Cow::Owned(secret))
+}
+
+/// Resolve every handle among the string values of a JSON document.
+pub fn resolve_json(value: &mut Value) -> Result<(), SecretError> {
+    match value {
+        Value::String(s) if parse_handle(s).is_some() => {
+            *s = resolve(s)?.into_owned();
+        }
+        Value::Array(items) => {
+            for item in items {
+                resolve_json(item)?;
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                resolve_json(item)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Whether values under this configuration key are credentials.
+pub fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SENSITIVE_KEYS.iter().any(|k| key.ends_with(k))
+}
+
+/// Mask plain credentials in a JSON document.
+///
+/// Non-empty strings under [sensitive keys](is_sensitive_key) are replaced
+/// with [`REDACTED`]; secret handles are kept since they reveal nothing.
+pub fn redact_json(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                match item {
+                    Value::String(s) if is_sensitive_key(key) => {
+                        if !s.is_empty() && parse_handle(s).is_none() {
+                            *s = REDACTED.to_string();
+                        }
+                    }
+                    _ => redact_json(item),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Remember a plaintext secret so it is masked by [`redact_text`].
+pub fn register_value(value: &str) {
+    if value.len() < MIN_REDACT_LEN {
+        return;
+    }
+    let mut known = KNOWN_VALUES.write();
+    if !known.iter().any(|v| v == value) {
+        known.push(value.to_string());
+        known.sort_by_key(|v| std::cmp::Reverse(v.len()));
+    }
+}
+
+/// Forget a secret value, e.g. after it was rotated or deleted.
+pub fn unregister_value(value: &str) {
+    KNOWN_VALUES.write().retain(|v| v != value);
+}
+
+/// Mask every known secret value in `text`.
+pub fn redact_text(text: &str) -> Cow<'_, str> {
+    let known = KNOWN_VALUES.read();
+    if !known.iter().any(|v| text.contains(v.as_str())) {
+        return Cow::Borrowed(text);
+    }
+    let mut redacted = text.to_string();
+    for value in known.iter() {
+        if redacted.contains(value.as_str()) {
+            redacted = redacted.replace(value.as_str(), REDACTED);
+        }
+    }
+    Cow::Owned(redacted)
+}
+
+/// Log writer factory that masks known secret values.
+///
+/// ```ignore
+/// tracing_subscriber::fmt::layer().with_writer(RedactingMakeWriter::new(std::io::stdout))
+/// ```
+#[derive(Debug, Clone)]
+pub struct RedactingMakeWriter<M>(M);
+
+impl<M> RedactingMakeWriter<M> {
+    pub fn new(inner: M) -> Self {
+        Self(inner)
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+/// Writer produced by [`RedactingMakeWriter`].
+///
+/// Formatters write one complete record per call, so each write is redacted
+/// on its own.
+#[derive(Debug)]
+pub struct RedactingWriter<W>(W);
+
+impl<W: io::Write> io::Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf).map(redact_text) {
+            Ok(Cow::Owned(text)) => self.0.write_all(text.as_bytes())?,
+            _ => self.0.write_all(buf)?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct MapResolver(HashMap<String, String>);
+
+    impl SecretResolver for MapResolver {
+        fn resolve_secret(&self, name: &str) -> Result<Option<String>, SecretError> {
+            Ok(self.0.get(name).cloned())
+        }
+    }
+
+    #[test]
+    fn test_handles_and_names() {
+        assert!(validate_name("smtp-password").is_ok());
+        assert!(validate_name("llm.openai_key").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("Upper").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert_eq!(handle("smtp-password"), "secret://smtp-password");
+        assert_eq!(parse_handle("secret://smtp-password"), Some("smtp-password"));
+        assert_eq!(parse_handle("hunter2"), None);
+    }
+
+    #[test]
+    fn test_resolve_and_redact() {
+        set_resolver(Arc::new(MapResolver(HashMap::from([(
+            "smtp-password".to_string(),
+            "s3cret-smtp-value".to_string(),
+        )]))));
+
+        assert_eq!(resolve("plain-value").unwrap(), "plain-value");
+        assert_eq!(resolve("secret://smtp-password").unwrap(), "s3cret-smtp-value");
+        assert_eq!(
+            resolve("secret://missing").unwrap_err(),
+            SecretError::NotFound("missing".to_string())
+        );
+
+        let mut config = serde_json::json!({
+            "smtp_server": "mail.example.com",
+            "password": "secret://smtp-password",
+            "nested": [{"token": "secret://smtp-password"}]
+        });
+        resolve_json(&mut config).unwrap();
+        assert_eq!(config["password"], "s3cret-smtp-value");
+        assert_eq!(config["nested"][0]["token"], "s3cret-smtp-value");
+
+        // Resolved values are masked in free text
+        assert_eq!(
+            redact_text("login with s3cret-smtp-value failed"),
+            "login with *** failed"
+        );
+        assert!(matches!(redact_text("nothing here"), Cow::Borrowed(_)));
+
+        let mut out = RedactingWriter(Vec::new());
+        io::Write::write_all(&mut out, b"pw=s3cret-smtp-value\n").unwrap();
+        assert_eq!(out.0, b"pw=***\n");
+    }
+
+    #[test]
+    fn test_redact_json_keeps_handles() {
+        let mut config = serde_json::json!({
+            "broker": "mqtt.local",
+            "password": "hunter2",
+            "api_key": "secret://openai",
+            "client_secret": "",
+            "targets": [{"auth_token": "abc"}]
+        });
+        redact_json(&mut config);
+        assert_eq!(config["broker"], "mqtt.local");
+        assert_eq!(config["password"], REDACTED);
+        assert_eq!(config["api_key"], "secret://openai");
+        assert_eq!(config["client_secret"], "");
+        assert_eq!(config["targets"][0]["auth_token"], REDACTED);
+    }
+}
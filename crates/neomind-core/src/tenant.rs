@@ -0,0 +1,207 @@
+//! Tenant namespaces.
+//!
+//! One NeoMind instance can serve several independent customers or buildings.
+//! Devices, rules, chat sessions and messages record the [`TenantId`] that owns
+//! them; records created before tenants existed belong to the default tenant.
+//!
+//! A request runs in a [`TenantScope`]: users and API keys bound to a tenant
+//! only ever see that tenant's records, while unbound ones see all of them
+//! unless they narrow the scope with the [`TENANT_HEADER`] header.
+//! Agent tool calls run inside [`TenantId::scope`], so the CLI commands they
+//! issue carry the session's tenant.
+
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Tenant that owns records created without an explicit tenant.
+pub const DEFAULT_TENANT: &str = "default";
+
+/// HTTP header that narrows a request to one tenant.
+pub const TENANT_HEADER: &str = "x-neomind-tenant";
+
+/// Environment variable carrying the tenant into spawned `neomind` commands.
+pub const TENANT_ENV: &str = "NEOMIND_TENANT";
+
+const MAX_TENANT_ID_LEN: usize = 64;
+
+tokio::task_local! {
+    static CURRENT_TENANT: TenantId;
+}
+
+/// Invalid tenant identifier.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid tenant id '{0}': use 1-64 lowercase letters, digits, '-' or '_'")]
+pub struct InvalidTenantId(pub String);
+
+/// Identifier of a tenant namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TenantId(String);
+
+impl TenantId {
+    /// Validate and wrap a tenant identifier.
+    pub fn new(id: impl Into<String>) -> Result<Self, InvalidTenantId> {
+        let id = id.into();
+        let valid = !id.is_empty()
+            && id.len() <= MAX_TENANT_ID_LEN
+            && id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if valid {
+            Ok(Self(id))
+        } else {
+            Err(InvalidTenantId(id))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.0 == DEFAULT_TENANT
+    }
+
+    /// Tenant of the task currently running inside [`TenantId::scope`].
+    pub fn current() -> Option<TenantId> {
+        CURRENT_TENANT.try_with(|tenant| tenant.clone()).ok()
+    }
+
+    /// Run `f` with this tenant as [`TenantId::current`].
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        CURRENT_TENANT.scope(self, f).await
+    }
+}
+
+/// Run `f` in the scope of `tenant`, or unscoped if it is `None`.
+///
+/// Spawned tasks don't inherit task-local state; capture
+/// [`TenantId::current`] before spawning and pass it here.
+pub async fn with_tenant<F: Future>(tenant: Option<TenantId>, f: F) -> F::Output {
+    match tenant {
+        Some(tenant) => tenant.scope(f).await,
+        None => f.await,
+    }
+}
+
+impl Default for TenantId {
+    fn default() -> Self {
+        Self(DEFAULT_TENANT.to_string())
+    }
+}
+
+impl fmt::Display for TenantId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for TenantId {
+    type Err = InvalidTenantId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for TenantId {
+    type Error = InvalidTenantId;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::new(s)
+    }
+}
+
+impl From<TenantId> for String {
+    fn from(id: TenantId) -> Self {
+        id.0
+    }
+}
+
+/// Records a request may access.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TenantScope {
+    /// Every tenant (users and API keys not bound to a tenant).
+    #[default]
+    All,
+    /// Only records owned by this tenant.
+    Tenant(TenantId),
+}
+
+impl TenantScope {
+    /// Whether a record owned by `tenant` is visible in this scope.
+    pub fn allows(&self, tenant: &TenantId) -> bool {
+        match self {
+            Self::All => true,
+            Self::Tenant(own) => own == tenant,
+        }
+    }
+
+    /// The tenant this scope is limited to, if any.
+    pub fn tenant(&self) -> Option<&TenantId> {
+        match self {
+            Self::All => None,
+            Self::Tenant(tenant) => Some(tenant),
+        }
+    }
+
+    /// Owner of records created in this scope.
+    pub fn owner(&self) -> TenantId {
+        self.tenant().cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tenant_id_validation() {
+        assert!(TenantId::new("building-a_2").is_ok());
+        assert!(TenantId::new("").is_err());
+        assert!(TenantId::new("Acme").is_err());
+        assert!(TenantId::new("a/b").is_err());
+        assert!(TenantId::new("x".repeat(65)).is_err());
+
+        assert!(TenantId::default().is_default());
+        assert!(serde_json::from_str::<TenantId>("\"../etc\"").is_err());
+        let id: TenantId = serde_json::from_str("\"acme\"").unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"acme\"");
+    }
+
+    #[test]
+    fn test_scope_access() {
+        let acme = TenantId::new("acme").unwrap();
+        let other = TenantId::new("other").unwrap();
+        assert!(TenantScope::All.allows(&acme));
+        assert_eq!(TenantScope::All.owner(), TenantId::default());
+
+        let scope = TenantScope::Tenant(acme.clone());
+        assert!(scope.allows(&acme));
+        assert!(!scope.allows(&other));
+        assert!(!scope.allows(&TenantId::default()));
+        assert_eq!(scope.owner(), acme);
+    }
+
+    #[tokio::test]
+    async fn test_current_tenant_scope() {
+        assert_eq!(TenantId::current(), None);
+        let acme = TenantId::new("acme").unwrap();
+        let seen = acme.clone().scope(async { TenantId::current() }).await;
+        assert_eq!(seen, Some(acme.clone()));
+
+        let spawned = acme
+            .clone()
+            .scope(async {
+                let tenant = TenantId::current();
+                tokio::spawn(with_tenant(tenant, async { TenantId::current() }))
+                    .await
+                    .unwrap()
+            })
+            .await;
+        assert_eq!(spawned, Some(acme));
+    }
+}
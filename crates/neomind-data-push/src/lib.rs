@@ -1,9 +1,10 @@
 //! NeoMind Data Push Module
 //!
 //! Independent module for pushing device telemetry and extension output
-//! to external systems (Webhook, MQTT, etc.).
+//! to external systems (Webhook, MQTT, InfluxDB, Telegraf).
 
 pub mod filter;
+pub mod line_protocol;
 pub mod manager;
 pub mod scheduler;
 pub mod store;
@@ -0,0 +1,104 @@
+//! InfluxDB line protocol rendering for the `influxdb` and `telegraf` targets.
+//!
+//! A point `device:sensor1:temperature = 23.5 @ 1700000000` becomes
+//! `device,id=sensor1 temperature=23.5 1700000000` — measurement is the source
+//! type (or the target's configured `measurement`, which then adds a
+//! `source_type` tag), the source ID is a tag and the metric is the field.
+//! Timestamps are in seconds, so writers must use `precision=s`.
+
+/// Render one point. Returns `None` for values line protocol can't carry
+/// (null, arrays, objects) and for `_raw` payload dumps.
+pub fn to_line(
+    source_id: &str,
+    value: &serde_json::Value,
+    timestamp: i64,
+    measurement: Option<&str>,
+) -> Option<String> {
+    let mut parts = source_id.splitn(3, ':');
+    let source_type = parts.next().filter(|s| !s.is_empty())?;
+    let id = parts.next().filter(|s| !s.is_empty())?;
+    let field = parts.next().filter(|s| !s.is_empty() && *s != "_raw")?;
+
+    let field_value = match value {
+        serde_json::Value::Number(n) => n.as_f64()?.to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::String(s) => {
+            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+        _ => return None,
+    };
+
+    let series = match measurement {
+        Some(m) => format!(
+            "{},source_type={},id={}",
+            escape_measurement(m),
+            escape_key(source_type),
+            escape_key(id)
+        ),
+        None => format!("{},id={}", escape_measurement(source_type), escape_key(id)),
+    };
+    Some(format!(
+        "{} {}={} {}",
+        series,
+        escape_key(field),
+        field_value,
+        timestamp
+    ))
+}
+
+/// Render a batch, one point per line, skipping values [`to_line`] rejects.
+pub fn to_lines(points: &[(String, serde_json::Value, i64)], measurement: Option<&str>) -> String {
+    points
+        .iter()
+        .filter_map(|(source_id, value, ts)| to_line(source_id, value, *ts, measurement))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn escape_measurement(s: &str) -> String {
+    s.replace(',', "\\,").replace(' ', "\\ ")
+}
+
+/// Escape a tag key, tag value or field key.
+fn escape_key(s: &str) -> String {
+    s.replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_line() {
+        assert_eq!(
+            to_line("device:sensor1:temperature", &json!(23.5), 1700000000, None).unwrap(),
+            "device,id=sensor1 temperature=23.5 1700000000"
+        );
+        assert_eq!(
+            to_line("device:pump 1:running", &json!(true), 5, Some("plant")).unwrap(),
+            "plant,source_type=device,id=pump\\ 1 running=true 5"
+        );
+        assert_eq!(
+            to_line("extension:ocr:text", &json!("say \"hi\""), 5, None).unwrap(),
+            "extension,id=ocr text=\"say \\\"hi\\\"\" 5"
+        );
+        assert!(to_line("device:cam:_raw", &json!(1), 5, None).is_none());
+        assert!(to_line("device:cam:boxes", &json!([1, 2]), 5, None).is_none());
+    }
+
+    #[test]
+    fn test_to_lines_skips_unsupported() {
+        let points = vec![
+            ("device:a:t".to_string(), json!(1), 1),
+            ("device:a:meta".to_string(), json!({"k": 1}), 1),
+            ("device:b:t".to_string(), json!(2), 2),
+        ];
+        assert_eq!(
+            to_lines(&points, None),
+            "device,id=a t=1 1\ndevice,id=b t=2 2"
+        );
+    }
+}
@@ -100,11 +100,8 @@ impl PushManager {
     /// Create a new push target.
     pub async fn create_target(&self, config: CreateTargetRequest) -> Result<PushTarget> {
         // Validate the target config by creating a destination
-        let target_type: PushTargetType = match config.target_type.as_str() {
-            "webhook" => PushTargetType::Webhook,
-            "mqtt" => PushTargetType::Mqtt,
-            other => return Err(anyhow!("Unknown target type: {}", other)),
-        };
+        let target_type = PushTargetType::parse(&config.target_type)
+            .ok_or_else(|| anyhow!("Unknown target type: {}", config.target_type))?;
 
         let dest = create_destination(&target_type, &config.config)?;
         dest.validate_config(&config.config)?;
@@ -149,11 +146,8 @@ impl PushManager {
             target.enabled = enabled;
         }
         if let Some(target_type_str) = &config.target_type {
-            target.target_type = match target_type_str.as_str() {
-                "webhook" => PushTargetType::Webhook,
-                "mqtt" => PushTargetType::Mqtt,
-                other => return Err(anyhow!("Unknown target type: {}", other)),
-            };
+            target.target_type = PushTargetType::parse(target_type_str)
+                .ok_or_else(|| anyhow!("Unknown target type: {}", target_type_str))?;
         }
         if let Some(config_val) = config.config {
             let dest = create_destination(&target.target_type, &config_val)?;
@@ -481,14 +481,21 @@ async fn deliver_with_retry(
     timestamp: i64,
     cancel: Option<&tokio::sync::watch::Receiver<bool>>,
 ) -> Result<()> {
-    let ctx = TemplateContext {
-        source_id: source_id.to_string(),
-        value: value.clone(),
-        timestamp,
+    let payload = if target.target_type.uses_line_protocol() {
+        match crate::line_protocol::to_line(source_id, value, timestamp, line_measurement(target)) {
+            Some(line) => line,
+            // Arrays/objects have no line protocol representation
+            None => return Ok(()),
+        }
+    } else {
+        let ctx = TemplateContext {
+            source_id: source_id.to_string(),
+            value: value.clone(),
+            timestamp,
+        };
+        renderer.render(&target.template, &ctx)?
     };
 
-    let payload = renderer.render(&target.template, &ctx)?;
-
     let log_id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().timestamp();
 
@@ -691,6 +698,15 @@ fn merge_json(dst: &mut serde_json::Value, src: serde_json::Value) {
     }
 }
 
+/// Measurement override from an `influxdb` / `telegraf` target's config.
+fn line_measurement(target: &PushTarget) -> Option<&str> {
+    target
+        .config
+        .get("measurement")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+}
+
 /// True if the metric is the `_raw` whole-payload dump (source_id field == `_raw`).
 fn is_raw_metric(source_id: &str) -> bool {
     source_id
@@ -721,6 +737,13 @@ async fn flush_batch(
     // and redundant when structured metrics are also emitted) — not useful in
     // push output, so drop it before building the payload.
     buffer.retain(|(source_id, _, _)| !is_raw_metric(source_id));
+    // Line protocol can't carry arrays/objects; drop them rather than
+    // sending an empty write.
+    if target.target_type.uses_line_protocol() {
+        buffer.retain(|(source_id, value, ts)| {
+            crate::line_protocol::to_line(source_id, value, *ts, None).is_some()
+        });
+    }
     if buffer.is_empty() {
         return;
     }
@@ -729,6 +752,9 @@ async fn flush_batch(
     let source_ids: Vec<&str> = buffer.iter().map(|(s, _, _)| s.as_str()).collect();
 
     let payload_str = match target.batch_config.format {
+        _ if target.target_type.uses_line_protocol() => {
+            crate::line_protocol::to_lines(buffer, line_measurement(target))
+        }
         BatchFormat::Nested => {
             let nested = build_nested_batch_payload(buffer);
             serde_json::to_string(&nested).unwrap_or_default()
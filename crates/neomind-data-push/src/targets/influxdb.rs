@@ -0,0 +1,183 @@
+//! InfluxDB push target (line protocol over HTTP).
+//!
+//! Writes to the v2 API (`/api/v2/write`) when `bucket` is set, otherwise to
+//! the v1 API (`/write`) using `database`. InfluxDB 3 and VictoriaMetrics
+//! accept either.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::PushDestination;
+
+/// InfluxDB configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfluxDbConfig {
+    /// Base URL, e.g. `http://influxdb:8086`.
+    pub url: String,
+    /// v2: bucket to write to.
+    pub bucket: Option<String>,
+    /// v2: organization.
+    pub org: Option<String>,
+    /// v2: API token.
+    pub token: Option<String>,
+    /// v1: database to write to.
+    pub database: Option<String>,
+    /// v1: optional retention policy.
+    pub retention_policy: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Measurement name; defaults to the source type (`device`, `extension`).
+    pub measurement: Option<String>,
+    /// Request timeout in seconds.
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+impl InfluxDbConfig {
+    fn parse(config: &serde_json::Value) -> Result<Self> {
+        let ic: InfluxDbConfig = serde_json::from_value(config.clone())
+            .map_err(|e| anyhow!("Invalid InfluxDB config: {}", e))?;
+        if ic.url.is_empty() {
+            return Err(anyhow!("InfluxDB URL is required"));
+        }
+        if ic.bucket.is_none() && ic.database.is_none() {
+            return Err(anyhow!("InfluxDB bucket (v2) or database (v1) is required"));
+        }
+        Ok(ic)
+    }
+
+    /// Write endpoint with query parameters.
+    fn write_url(&self) -> Result<reqwest::Url> {
+        let base = self.url.trim_end_matches('/');
+        let mut params = vec![("precision", "s")];
+        let path = match &self.bucket {
+            Some(bucket) => {
+                params.push(("bucket", bucket.as_str()));
+                if let Some(org) = &self.org {
+                    params.push(("org", org.as_str()));
+                }
+                "/api/v2/write"
+            }
+            None => {
+                if let Some(db) = &self.database {
+                    params.push(("db", db.as_str()));
+                }
+                if let Some(rp) = &self.retention_policy {
+                    params.push(("rp", rp.as_str()));
+                }
+                "/write"
+            }
+        };
+        reqwest::Url::parse_with_params(&format!("{}{}", base, path), &params)
+            .map_err(|e| anyhow!("Invalid InfluxDB URL: {}", e))
+    }
+}
+
+/// InfluxDB push destination.
+pub struct InfluxDbTarget {
+    client: reqwest::Client,
+    config: InfluxDbConfig,
+    url: reqwest::Url,
+}
+
+impl InfluxDbTarget {
+    pub fn from_config(config: &serde_json::Value) -> Result<Self> {
+        let ic = InfluxDbConfig::parse(config)?;
+        let url = ic.write_url()?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(ic.timeout_secs))
+            .build()?;
+        Ok(Self {
+            client,
+            config: ic,
+            url,
+        })
+    }
+}
+
+#[async_trait]
+impl PushDestination for InfluxDbTarget {
+    async fn send(&self, payload: &str) -> std::result::Result<(), super::DeliveryError> {
+        let mut builder = self
+            .client
+            .post(self.url.clone())
+            .header("Content-Type", "text/plain; charset=utf-8");
+        if let Some(ref token) = self.config.token {
+            builder = builder.header("Authorization", format!("Token {}", token));
+        } else if let Some(ref user) = self.config.username {
+            builder = builder.basic_auth(user, self.config.password.as_ref());
+        }
+
+        let response = builder
+            .body(payload.to_string())
+            .send()
+            .await
+            .map_err(|e| super::DeliveryError::Other(anyhow!("InfluxDB write failed: {}", e)))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        if status.as_u16() == 429 || status.as_u16() == 503 {
+            let retry_after = super::webhook::parse_retry_after(response.headers());
+            return Err(super::DeliveryError::RateLimited { retry_after });
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        Err(super::DeliveryError::Other(anyhow!(
+            "InfluxDB returned status {}: {}",
+            status,
+            body.chars().take(500).collect::<String>()
+        )))
+    }
+
+    fn validate_config(&self, config: &serde_json::Value) -> Result<()> {
+        InfluxDbConfig::parse(config)?.write_url()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn write_url_v2() {
+        let target = InfluxDbTarget::from_config(&json!({
+            "url": "http://influx:8086/",
+            "bucket": "neomind",
+            "org": "plant a",
+            "token": "t"
+        }))
+        .unwrap();
+        assert_eq!(
+            target.url.as_str(),
+            "http://influx:8086/api/v2/write?precision=s&bucket=neomind&org=plant+a"
+        );
+    }
+
+    #[test]
+    fn write_url_v1() {
+        let target = InfluxDbTarget::from_config(&json!({
+            "url": "http://influx:8086",
+            "database": "telemetry"
+        }))
+        .unwrap();
+        assert_eq!(
+            target.url.as_str(),
+            "http://influx:8086/write?precision=s&db=telemetry"
+        );
+    }
+
+    #[test]
+    fn requires_bucket_or_database() {
+        assert!(InfluxDbTarget::from_config(&json!({ "url": "http://influx:8086" })).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 //! Push target trait and registry.
 
+pub mod influxdb;
 pub mod mqtt;
+pub mod telegraf;
 pub mod webhook;
 
 use crate::types::PushTargetType;
@@ -61,6 +63,14 @@ pub fn create_destination(
             let target = mqtt::MqttTarget::from_config(config)?;
             Box::new(target)
         }
+        PushTargetType::Influxdb => {
+            let target = influxdb::InfluxDbTarget::from_config(config)?;
+            Box::new(target)
+        }
+        PushTargetType::Telegraf => {
+            let target = telegraf::TelegrafTarget::from_config(config)?;
+            Box::new(target)
+        }
     };
     Ok(dest)
 }
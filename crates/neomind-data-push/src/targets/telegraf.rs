@@ -0,0 +1,139 @@
+//! Telegraf push target (line protocol over a `socket_listener`).
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use super::PushDestination;
+
+/// Largest UDP datagram sent; bigger batches are split on line boundaries.
+const MAX_DATAGRAM_BYTES: usize = 8192;
+
+/// Socket transport.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TelegrafProtocol {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+/// Telegraf configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegrafConfig {
+    /// `host:port` of the Telegraf `socket_listener`.
+    pub address: String,
+    #[serde(default)]
+    pub protocol: TelegrafProtocol,
+    /// Measurement name; defaults to the source type (`device`, `extension`).
+    pub measurement: Option<String>,
+}
+
+impl TelegrafConfig {
+    fn parse(config: &serde_json::Value) -> Result<Self> {
+        let tc: TelegrafConfig = serde_json::from_value(config.clone())
+            .map_err(|e| anyhow!("Invalid Telegraf config: {}", e))?;
+        if tc.address.is_empty() || !tc.address.contains(':') {
+            return Err(anyhow!("Telegraf address (host:port) is required"));
+        }
+        Ok(tc)
+    }
+}
+
+/// Telegraf push destination. TCP connections are opened lazily and
+/// re-established after a failed write.
+pub struct TelegrafTarget {
+    config: TelegrafConfig,
+    stream: Mutex<Option<tokio::net::TcpStream>>,
+}
+
+impl TelegrafTarget {
+    pub fn from_config(config: &serde_json::Value) -> Result<Self> {
+        Ok(Self {
+            config: TelegrafConfig::parse(config)?,
+            stream: Mutex::new(None),
+        })
+    }
+
+    async fn send_tcp(&self, data: &[u8]) -> Result<()> {
+        let mut guard = self.stream.lock().await;
+        if guard.is_none() {
+            *guard = Some(tokio::net::TcpStream::connect(&self.config.address).await?);
+        }
+        let result = match guard.as_mut() {
+            Some(stream) => stream.write_all(data).await,
+            None => return Err(anyhow!("Telegraf connection not initialized")),
+        };
+        if let Err(e) = result {
+            *guard = None;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    async fn send_udp(&self, payload: &str) -> Result<()> {
+        let socket = tokio::net::UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(&self.config.address).await?;
+        for datagram in split_datagrams(payload, MAX_DATAGRAM_BYTES) {
+            socket.send(datagram.as_bytes()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Group lines into chunks of at most `max` bytes (a single longer line is
+/// sent on its own).
+fn split_datagrams(payload: &str, max: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in payload.lines() {
+        if !current.is_empty() && current.len() + line.len() + 1 > max {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[async_trait]
+impl PushDestination for TelegrafTarget {
+    async fn send(&self, payload: &str) -> std::result::Result<(), super::DeliveryError> {
+        let result = match self.config.protocol {
+            TelegrafProtocol::Tcp => self.send_tcp(format!("{}\n", payload).as_bytes()).await,
+            TelegrafProtocol::Udp => self.send_udp(payload).await,
+        };
+        result.map_err(|e| super::DeliveryError::Other(anyhow!("Telegraf send failed: {}", e)))
+    }
+
+    fn validate_config(&self, config: &serde_json::Value) -> Result<()> {
+        TelegrafConfig::parse(config)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_datagrams_on_line_boundaries() {
+        let chunks = split_datagrams("aaaa\nbbbb\ncc", 10);
+        assert_eq!(chunks, vec!["aaaa\nbbbb\n", "cc\n"]);
+    }
+
+    #[test]
+    fn requires_host_and_port() {
+        assert!(
+            TelegrafTarget::from_config(&serde_json::json!({ "address": "telegraf" })).is_err()
+        );
+        assert!(
+            TelegrafTarget::from_config(&serde_json::json!({ "address": "telegraf:8094" })).is_ok()
+        );
+    }
+}
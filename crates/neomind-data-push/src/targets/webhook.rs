@@ -121,7 +121,9 @@ impl PushDestination for WebhookTarget {
 /// Parse the `Retry-After` header in delta-seconds form. Returns None for the
 /// HTTP-date form (not supported) or absent/malformed values. Caps at 10 min so
 /// a hostile/misconfigured server can't stall delivery indefinitely.
-fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<std::time::Duration> {
+pub(crate) fn parse_retry_after(
+    headers: &reqwest::header::HeaderMap,
+) -> Option<std::time::Duration> {
     let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
     let secs: u64 = value.trim().parse().ok()?;
     Some(std::time::Duration::from_secs(secs.min(600)))
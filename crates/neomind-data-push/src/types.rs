@@ -101,6 +101,29 @@ impl BatchConfig {
 pub enum PushTargetType {
     Webhook,
     Mqtt,
+    /// InfluxDB HTTP write API (line protocol)
+    Influxdb,
+    /// Telegraf `socket_listener` (line protocol)
+    Telegraf,
+}
+
+impl PushTargetType {
+    /// Parse the API/CLI name of a target type.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "webhook" => Some(Self::Webhook),
+            "mqtt" => Some(Self::Mqtt),
+            "influxdb" => Some(Self::Influxdb),
+            "telegraf" => Some(Self::Telegraf),
+            _ => None,
+        }
+    }
+
+    /// Whether payloads are rendered as InfluxDB line protocol instead of
+    /// JSON / templates.
+    pub fn uses_line_protocol(&self) -> bool {
+        matches!(self, Self::Influxdb | Self::Telegraf)
+    }
 }
 
 impl std::fmt::Display for PushTargetType {
@@ -108,6 +131,8 @@ impl std::fmt::Display for PushTargetType {
         match self {
             Self::Webhook => write!(f, "webhook"),
             Self::Mqtt => write!(f, "mqtt"),
+            Self::Influxdb => write!(f, "influxdb"),
+            Self::Telegraf => write!(f, "telegraf"),
         }
     }
 }
@@ -0,0 +1,177 @@
+//! MQTT ingestion pipeline benchmarks using Criterion.rs
+//!
+//! Feeds 10k JSON telemetry messages through extraction, the metric cache
+//! and the adapter's broadcast channel (two subscribers, one converting to
+//! event bus events), comparing the owned-copy path the MQTT adapter used
+//! to take with the shared path it takes now:
+//!
+//! - `owned`: payload copied out of the publish buffer, one
+//!   `DeviceEvent::Metric` per metric, every stage cloning names and values
+//! - `shared`: payload kept as `Bytes` in a `RawPayload`, one
+//!   `DeviceEvent::Metrics` per message with the batch behind an `Arc`
+//!
+//! Throughput is reported in messages per second; a 10k msg/s stream needs
+//! a full iteration to finish in under one second.
+//!
+//! Run with: cargo bench -p neomind-devices --bench mqtt_ingest
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use neomind_devices::registry::DeviceRegistry;
+use neomind_devices::unified_extractor::{ExtractedMetric, RawPayload, UnifiedExtractor};
+use neomind_devices::{DataPoint, DeviceEvent, MetricValue};
+use serde_json::{json, Value};
+use tokio::runtime::Runtime;
+use tokio::sync::broadcast;
+
+const MESSAGES: usize = 10_000;
+const DEVICE_ID: &str = "env-sensor-01";
+const DEVICE_TYPE: &str = "env_sensor";
+
+/// Typical environment sensor uplinks: flat readings plus nested groups.
+fn create_messages(count: usize) -> Vec<Bytes> {
+    (0..count)
+        .map(|i| {
+            let message = json!({
+                "temperature": 20.0 + (i % 100) as f64 / 10.0,
+                "humidity": 40 + i % 30,
+                "battery": 98,
+                "online": true,
+                "status": "ok",
+                "values": { "co2": 400 + i % 200, "pm25": 12.5, "voc": 0.3 },
+                "location": { "lat": 31.23, "lon": 121.47 },
+            });
+            Bytes::from(serde_json::to_vec(&message).unwrap())
+        })
+        .collect()
+}
+
+struct Pipeline {
+    tx: broadcast::Sender<DeviceEvent>,
+    forwarder: broadcast::Receiver<DeviceEvent>,
+    observer: broadcast::Receiver<DeviceEvent>,
+    cache: HashMap<String, MetricValue>,
+}
+
+impl Pipeline {
+    fn new() -> Self {
+        let (tx, forwarder) = broadcast::channel(1024);
+        let observer = tx.subscribe();
+        Self {
+            tx,
+            forwarder,
+            observer,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Drain both subscribers. The forwarder clones each event before
+    /// converting it, as the adapter's forwarding task did.
+    fn drain(&mut self, clone_before_convert: bool) -> usize {
+        let mut published = 0;
+        while let Ok(event) = self.forwarder.try_recv() {
+            let event = if clone_before_convert {
+                event.clone()
+            } else {
+                event
+            };
+            published += event.into_neomind_events().len();
+        }
+        while let Ok(event) = self.observer.try_recv() {
+            black_box(event);
+        }
+        published
+    }
+}
+
+async fn ingest_owned(extractor: &UnifiedExtractor, messages: &[Bytes]) -> usize {
+    let mut pipeline = Pipeline::new();
+    let mut published = 0;
+    for message in messages {
+        let payload = message.to_vec();
+        let json: Value = serde_json::from_slice(&payload).unwrap();
+        let result = extractor.extract(DEVICE_ID, DEVICE_TYPE, &json).await;
+        for metric in result.metrics {
+            let value = metric.value.clone();
+            pipeline.cache.insert(metric.name.clone(), value.clone());
+            black_box(DataPoint {
+                timestamp: 0,
+                value: value.clone(),
+                quality: None,
+            });
+            let _ = pipeline.tx.send(DeviceEvent::Metric {
+                device_id: DEVICE_ID.to_string(),
+                metric: metric.name.clone(),
+                value: value.clone(),
+                timestamp: 0,
+            });
+        }
+        published += pipeline.drain(true);
+    }
+    published
+}
+
+async fn ingest_shared(extractor: &UnifiedExtractor, messages: &[Bytes]) -> usize {
+    let mut pipeline = Pipeline::new();
+    let mut published = 0;
+    for message in messages {
+        let payload = RawPayload::new(message.clone());
+        let result = extractor
+            .extract_raw(DEVICE_ID, DEVICE_TYPE, &payload)
+            .await
+            .unwrap();
+        let metrics: Arc<[ExtractedMetric]> = result.metrics.into();
+        for metric in metrics.iter() {
+            pipeline
+                .cache
+                .insert(metric.name.clone(), metric.value.clone());
+            black_box(DataPoint {
+                timestamp: 0,
+                value: metric.value.clone(),
+                quality: None,
+            });
+        }
+        let _ = pipeline.tx.send(DeviceEvent::Metrics {
+            device_id: DEVICE_ID.to_string(),
+            metrics,
+            timestamp: 0,
+        });
+        published += pipeline.drain(false);
+    }
+    published
+}
+
+/// Benchmark both ingestion paths over a 10k message stream
+fn bench_mqtt_ingest(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let extractor = UnifiedExtractor::new(Arc::new(DeviceRegistry::new()));
+    let messages = create_messages(MESSAGES);
+
+    let extractor = &extractor;
+    let messages = &messages;
+
+    let mut group = c.benchmark_group("mqtt_ingest_10k");
+    group.throughput(Throughput::Elements(MESSAGES as u64));
+    group.sample_size(10);
+
+    group.bench_function("owned", |b| {
+        b.to_async(&rt).iter(|| async move {
+            black_box(ingest_owned(extractor, messages).await)
+        });
+    });
+
+    group.bench_function("shared", |b| {
+        b.to_async(&rt).iter(|| async move {
+            black_box(ingest_shared(extractor, messages).await)
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(mqtt_ingest_benches, bench_mqtt_ingest);
+
+criterion_main!(mqtt_ingest_benches);
@@ -4,6 +4,7 @@
 //! (MQTT, HASS, HTTP, etc.) into the NeoMind platform through a unified interface.
 
 use crate::mdl::MetricValue;
+use crate::unified_extractor::ExtractedMetric;
 use async_trait::async_trait;
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
@@ -64,6 +65,16 @@ pub enum DeviceEvent {
         timestamp: i64,
     },
 
+    /// All metrics extracted from one device message.
+    ///
+    /// The batch sits behind an `Arc`, so the broadcast channel hands it to
+    /// every subscriber without copying names or values.
+    Metrics {
+        device_id: String,
+        metrics: Arc<[ExtractedMetric]>,
+        timestamp: i64,
+    },
+
     /// Device state change
     State {
         device_id: String,
@@ -90,6 +101,7 @@ impl DeviceEvent {
     pub fn device_id(&self) -> Option<&str> {
         match self {
             Self::Metric { device_id, .. }
+            | Self::Metrics { device_id, .. }
             | Self::State { device_id, .. }
             | Self::CommandResult { device_id, .. } => Some(device_id),
             Self::Discovery { .. } => None,
@@ -100,15 +112,36 @@ impl DeviceEvent {
     pub fn timestamp(&self) -> i64 {
         match self {
             Self::Metric { timestamp, .. }
+            | Self::Metrics { timestamp, .. }
             | Self::State { timestamp, .. }
             | Self::CommandResult { timestamp, .. } => *timestamp,
             Self::Discovery { device } => device.timestamp,
         }
     }
 
-    /// Convert to NeoMindEvent.
-    pub fn to_neomind_event(self) -> NeoMindEvent {
-        match self {
+    /// Convert to NeoMindEvents.
+    ///
+    /// A [`DeviceEvent::Metrics`] batch becomes one `DeviceMetric` per
+    /// metric; every other event converts to exactly one event.
+    pub fn into_neomind_events(self) -> Vec<NeoMindEvent> {
+        let event = match self {
+            Self::Metrics {
+                device_id,
+                metrics,
+                timestamp,
+            } => {
+                return metrics
+                    .iter()
+                    .map(|metric| NeoMindEvent::DeviceMetric {
+                        device_id: device_id.clone(),
+                        metric: metric.name.clone(),
+                        value: convert_metric_value(metric.value.clone()),
+                        timestamp,
+                        quality: None,
+                        is_virtual: None,
+                    })
+                    .collect();
+            }
             Self::Metric {
                 device_id,
                 metric,
@@ -193,7 +226,8 @@ impl DeviceEvent {
                 result: result.map(|r| serde_json::json!(r)),
                 timestamp,
             },
-        }
+        };
+        vec![event]
     }
 }
 
@@ -495,8 +529,11 @@ impl EventPublishingAdapter {
                                 format!("adapter:{}", device_id)
                             }
                         };
-                        let neomind_event = event.to_neomind_event();
-                        event_bus.publish_with_source(neomind_event, source).await;
+                        for neomind_event in event.into_neomind_events() {
+                            event_bus
+                                .publish_with_source(neomind_event, source.clone())
+                                .await;
+                        }
                     }
                     None => break,
                 }
@@ -681,8 +718,8 @@ mod tests {
             timestamp: 1234567890,
         };
 
-        let neomind = event.to_neomind_event();
-        assert!(matches!(neomind, NeoMindEvent::DeviceMetric { .. }));
+        let neomind = event.into_neomind_events();
+        assert!(matches!(neomind.as_slice(), [NeoMindEvent::DeviceMetric { .. }]));
     }
 
     #[test]
@@ -694,8 +731,40 @@ mod tests {
             timestamp: 0,
         };
 
-        let neomind = event.to_neomind_event();
-        assert!(matches!(neomind, NeoMindEvent::DeviceOnline { .. }));
+        let neomind = event.into_neomind_events();
+        assert!(matches!(neomind.as_slice(), [NeoMindEvent::DeviceOnline { .. }]));
+    }
+
+    #[test]
+    fn test_device_event_metrics_batch_to_neomind() {
+        let metrics: Arc<[ExtractedMetric]> = ["temperature", "humidity"]
+            .into_iter()
+            .map(|name| ExtractedMetric {
+                name: name.to_string(),
+                value: MetricValue::Float(1.0),
+                source_path: format!("$.{}", name),
+            })
+            .collect();
+        let event = DeviceEvent::Metrics {
+            device_id: "sensor1".to_string(),
+            metrics,
+            timestamp: 42,
+        };
+        assert_eq!(event.device_id(), Some("sensor1"));
+
+        // Clones share the batch
+        let shared = |event: &DeviceEvent| match event {
+            DeviceEvent::Metrics { metrics, .. } => metrics.clone(),
+            _ => unreachable!(),
+        };
+        assert!(Arc::ptr_eq(&shared(&event), &shared(&event.clone())));
+
+        let events = event.into_neomind_events();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[1],
+            NeoMindEvent::DeviceMetric { metric, timestamp: 42, .. } if metric == "humidity"
+        ));
     }
 
     #[tokio::test]
@@ -0,0 +1,921 @@
+//! ChirpStack (LoRaWAN network server) adapter for NeoMind event-driven
+//! architecture.
+//!
+//! LoRaWAN devices never talk to NeoMind directly: ChirpStack terminates the
+//! radio side and forwards device events through its integrations. This
+//! adapter consumes those events and sends downlinks back through ChirpStack.
+//!
+//! ## Features
+//!
+//! - Uplinks from the MQTT integration (`application/{id}/device/{dev_eui}/event/up`)
+//!   or the HTTP integration (`POST /api/chirpstack/events?event=up`)
+//! - Payload decoding with the device type's binary codec, or ChirpStack's
+//!   own decoded `object` when the type has no codec
+//! - DevEUI → device mapping, with auto-registration by device profile
+//! - Downlinks published to the MQTT integration's `command/down` topic
+//!
+//! ## Device Configuration
+//!
+//! Devices use `adapter_type = "chirpstack"`:
+//!
+//! ```json
+//! {
+//!   "dev_eui": "0004a30b001c0530",
+//!   "application_id": "6f1a1d8e-52f9-4a5d-9c07-2b3d0ab1b9c4",
+//!   "f_port": 10,
+//!   "confirmed": false
+//! }
+//! ```
+//!
+//! `dev_eui` defaults to the device ID; `application_id` and `f_port` default
+//! to the adapter configuration.
+//!
+//! An uplink from an unknown DevEUI registers a device when its ChirpStack
+//! device profile maps to a NeoMind device type (`profile_device_types`, or a
+//! template whose `device_type` equals the profile name). Otherwise a
+//! discovery event is emitted.
+//!
+//! ## Downlinks
+//!
+//! When the device type's binary codec defines a frame for the command, the
+//! encoded frame is sent as `data`. Otherwise the rendered JSON payload is
+//! sent as `object`, for ChirpStack to encode with the device profile codec.
+//! Downlinks require the MQTT integration; the HTTP integration is
+//! uplink-only.
+
+use crate::adapter::{
+    AdapterError, AdapterResult, ConnectionStatus, DeviceAdapter, DeviceEvent, DiscoveredDeviceInfo,
+};
+use crate::binary_codec::BinaryCodec;
+use crate::mdl::MetricValue;
+use crate::registry::{ConnectionConfig, DeviceConfig, DeviceRegistry};
+use crate::telemetry::TimeSeriesStorage;
+use crate::unified_extractor::UnifiedExtractor;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use dashmap::DashMap;
+use futures::Stream;
+use neomind_core::{EventBus, NeoMindEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, info, warn};
+
+/// Adapter type identifier used in `DeviceConfig::adapter_type`.
+pub const CHIRPSTACK_ADAPTER_TYPE: &str = "chirpstack";
+
+/// ChirpStack adapter configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChirpStackAdapterConfig {
+    /// Adapter name
+    pub name: String,
+    /// Host of the broker ChirpStack's MQTT integration publishes to.
+    /// `None` = HTTP integration only (no downlinks).
+    #[serde(default)]
+    pub broker: Option<String>,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Application to subscribe to; `+` subscribes to all applications
+    #[serde(default = "default_application_id")]
+    pub application_id: String,
+    /// FPort for downlinks when the device doesn't set `f_port`
+    #[serde(default = "default_f_port")]
+    pub downlink_f_port: u8,
+    /// Shared secret the HTTP integration must send in `X-API-Key`
+    /// (configured as a custom header in ChirpStack).
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// ChirpStack device profile name → NeoMind device type, used to
+    /// register devices from unknown DevEUIs.
+    #[serde(default)]
+    pub profile_device_types: HashMap<String, String>,
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_application_id() -> String {
+    "+".to_string()
+}
+
+fn default_f_port() -> u8 {
+    1
+}
+
+impl ChirpStackAdapterConfig {
+    /// Create a configuration for the HTTP integration only.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            broker: None,
+            port: default_port(),
+            username: None,
+            password: None,
+            application_id: default_application_id(),
+            downlink_f_port: default_f_port(),
+            api_key: None,
+            profile_device_types: HashMap::new(),
+        }
+    }
+
+    /// Consume events from the MQTT integration on this broker.
+    pub fn with_broker(mut self, host: impl Into<String>, port: u16) -> Self {
+        self.broker = Some(host.into());
+        self.port = port;
+        self
+    }
+
+    /// Topic filter for the MQTT integration's device events.
+    pub fn event_topic(&self) -> String {
+        format!("application/{}/device/+/event/+", self.application_id)
+    }
+}
+
+/// Downlink target of a ChirpStack device, resolved from its
+/// `ConnectionConfig`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoraDeviceConfig {
+    pub dev_eui: String,
+    pub application_id: String,
+    pub f_port: u8,
+    pub confirmed: bool,
+}
+
+impl LoraDeviceConfig {
+    /// Resolve the device's LoRaWAN identity, falling back to the adapter
+    /// configuration.
+    pub fn from_device(
+        device: &DeviceConfig,
+        adapter: &ChirpStackAdapterConfig,
+    ) -> AdapterResult<Self> {
+        let extra = &device.connection_config.extra;
+        let dev_eui = device_dev_eui(device);
+        let application_id = extra
+            .get("application_id")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| Some(adapter.application_id.clone()).filter(|id| id != "+"))
+            .ok_or_else(|| {
+                AdapterError::Configuration(format!(
+                    "ChirpStack device '{}' has no application_id",
+                    device.device_id
+                ))
+            })?;
+        let f_port = extra
+            .get("f_port")
+            .and_then(|v| v.as_u64())
+            .map(|p| p as u8)
+            .unwrap_or(adapter.downlink_f_port);
+        let confirmed = extra
+            .get("confirmed")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        Ok(Self {
+            dev_eui,
+            application_id,
+            f_port,
+            confirmed,
+        })
+    }
+
+    /// MQTT integration topic for this device's downlinks.
+    pub fn downlink_topic(&self) -> String {
+        format!(
+            "application/{}/device/{}/command/down",
+            self.application_id, self.dev_eui
+        )
+    }
+}
+
+/// DevEUI of a device: `dev_eui` in its connection config, else its ID.
+fn device_dev_eui(device: &DeviceConfig) -> String {
+    device
+        .connection_config
+        .extra
+        .get("dev_eui")
+        .and_then(|v| v.as_str())
+        .unwrap_or(&device.device_id)
+        .to_lowercase()
+}
+
+/// The parts of a ChirpStack uplink event NeoMind uses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoraUplink {
+    pub dev_eui: String,
+    pub application_id: Option<String>,
+    pub device_name: Option<String>,
+    pub device_profile: Option<String>,
+    /// Raw frame (`data`, base64 in the event)
+    pub data: Option<Vec<u8>>,
+    /// Payload decoded by the ChirpStack device profile codec
+    pub object: Option<Value>,
+}
+
+impl LoraUplink {
+    /// Parse a ChirpStack v4 JSON uplink event.
+    pub fn parse(event: &Value) -> Option<Self> {
+        let info = event.get("deviceInfo")?;
+        let text = |v: &Value, key: &str| v.get(key).and_then(|s| s.as_str()).map(str::to_string);
+
+        Some(Self {
+            dev_eui: text(info, "devEui")?.to_lowercase(),
+            application_id: text(info, "applicationId"),
+            device_name: text(info, "deviceName"),
+            device_profile: text(info, "deviceProfileName"),
+            data: event
+                .get("data")
+                .and_then(|d| d.as_str())
+                .and_then(|d| STANDARD.decode(d).ok()),
+            object: event.get("object").filter(|o| !o.is_null()).cloned(),
+        })
+    }
+}
+
+/// Build a ChirpStack downlink request from a rendered command payload.
+///
+/// With a codec frame for the command, `payload` is the codec's text-encoded
+/// frame and is sent as `data`; otherwise it must be JSON and is sent as
+/// `object`.
+pub fn build_downlink(
+    device: &LoraDeviceConfig,
+    codec: Option<&BinaryCodec>,
+    payload: &str,
+) -> AdapterResult<Value> {
+    let mut request = json!({
+        "devEui": device.dev_eui,
+        "confirmed": device.confirmed,
+        "fPort": device.f_port,
+    });
+    match codec {
+        Some(codec) => {
+            let frame = codec
+                .decode_text(payload)
+                .map_err(|e| AdapterError::Configuration(e.to_string()))?;
+            request["data"] = Value::String(STANDARD.encode(frame));
+        }
+        None => {
+            let object: Value = serde_json::from_str(payload).map_err(|e| {
+                AdapterError::Configuration(format!(
+                    "ChirpStack downlink payload must be JSON or a binary codec frame: {}",
+                    e
+                ))
+            })?;
+            request["object"] = object;
+        }
+    }
+    Ok(request)
+}
+
+/// ChirpStack device adapter.
+#[derive(Clone)]
+pub struct ChirpStackAdapter {
+    /// Adapter name
+    name: String,
+    /// Configuration
+    config: ChirpStackAdapterConfig,
+    /// Event bus
+    event_bus: Option<Arc<EventBus>>,
+    /// Device registry (shared with DeviceService)
+    device_registry: Arc<RwLock<Arc<DeviceRegistry>>>,
+    /// Event channel
+    event_tx: broadcast::Sender<DeviceEvent>,
+    /// Running state
+    running: Arc<AtomicBool>,
+    /// DevEUI (lowercase hex) → device_id
+    dev_euis: Arc<DashMap<String, String>>,
+    /// Last uplink timestamp per device_id
+    last_uplink: Arc<DashMap<String, i64>>,
+    /// MQTT integration client, when a broker is configured
+    client: Arc<RwLock<Option<rumqttc::AsyncClient>>>,
+    /// Telemetry storage
+    telemetry_storage: Arc<RwLock<Option<Arc<TimeSeriesStorage>>>>,
+}
+
+impl ChirpStackAdapter {
+    /// Create a new ChirpStack adapter.
+    pub fn new(
+        config: ChirpStackAdapterConfig,
+        event_bus: Option<Arc<EventBus>>,
+        device_registry: Arc<DeviceRegistry>,
+    ) -> Self {
+        let (event_tx, _) = broadcast::channel(1000);
+
+        Self {
+            name: config.name.clone(),
+            config,
+            event_bus,
+            device_registry: Arc::new(RwLock::new(device_registry)),
+            event_tx,
+            running: Arc::new(AtomicBool::new(false)),
+            dev_euis: Arc::new(DashMap::new()),
+            last_uplink: Arc::new(DashMap::new()),
+            client: Arc::new(RwLock::new(None)),
+            telemetry_storage: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Set the device registry (shared with DeviceService).
+    pub async fn set_shared_device_registry(&self, registry: Arc<DeviceRegistry>) {
+        *self.device_registry.write().await = registry;
+    }
+
+    /// Adapter configuration.
+    pub fn config(&self) -> &ChirpStackAdapterConfig {
+        &self.config
+    }
+
+    /// Check the `X-API-Key` sent by the HTTP integration.
+    pub fn verify_api_key(&self, provided: Option<&str>) -> AdapterResult<()> {
+        match (&self.config.api_key, provided) {
+            (None, _) => Ok(()),
+            (Some(expected), Some(key)) if expected == key => Ok(()),
+            (Some(_), Some(_)) => Err(AdapterError::Connection("Invalid API key".to_string())),
+            (Some(_), None) => Err(AdapterError::Connection("Missing API key".to_string())),
+        }
+    }
+
+    /// Handle a ChirpStack integration event (`up`, `join`, ...).
+    ///
+    /// Shared by the MQTT integration loop and the HTTP integration
+    /// endpoint. Events other than uplinks and joins are ignored.
+    pub async fn handle_event(&self, event: &str, body: &Value) -> AdapterResult<()> {
+        match event {
+            "up" => self.handle_uplink(body).await,
+            "join" => {
+                if let Some(uplink) = LoraUplink::parse(body) {
+                    let registry = self.device_registry.read().await.clone();
+                    if let Some(device) = self.resolve_device(&registry, &uplink).await? {
+                        self.mark_online(&device).await;
+                    }
+                }
+                Ok(())
+            }
+            other => {
+                debug!("ChirpStack adapter '{}': ignoring '{}' event", self.name, other);
+                Ok(())
+            }
+        }
+    }
+
+    async fn handle_uplink(&self, body: &Value) -> AdapterResult<()> {
+        let uplink = LoraUplink::parse(body).ok_or_else(|| {
+            AdapterError::Configuration("ChirpStack uplink has no deviceInfo.devEui".to_string())
+        })?;
+        let registry = self.device_registry.read().await.clone();
+        let Some(device) = self.resolve_device(&registry, &uplink).await? else {
+            return Ok(());
+        };
+
+        // Decode with the device type's codec when it has one; otherwise use
+        // what ChirpStack's device profile codec produced.
+        let extractor = UnifiedExtractor::new(registry.clone());
+        let has_codec = registry
+            .get_template(&device.device_type)
+            .is_some_and(|t| t.binary_codec.is_some());
+        let result = match (&uplink.data, &uplink.object) {
+            (Some(frame), _) if has_codec => {
+                extractor
+                    .extract_binary(&device.device_id, &device.device_type, frame)
+                    .await
+            }
+            (_, Some(object)) => Some(
+                extractor
+                    .extract(&device.device_id, &device.device_type, object)
+                    .await,
+            ),
+            _ => None,
+        };
+
+        self.mark_online(&device).await;
+        let Some(result) = result else {
+            warn!(
+                "ChirpStack uplink for '{}' has no decodable payload (no binary codec, no object)",
+                device.device_id
+            );
+            return Ok(());
+        };
+
+        let timestamp = chrono::Utc::now().timestamp();
+        for metric in result.metrics {
+            self.emit_metric(&device.device_id, &metric.name, metric.value, timestamp)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Find the device for an uplink's DevEUI, registering it when its
+    /// device profile maps to a known device type.
+    async fn resolve_device(
+        &self,
+        registry: &Arc<DeviceRegistry>,
+        uplink: &LoraUplink,
+    ) -> AdapterResult<Option<DeviceConfig>> {
+        if let Some(device_id) = self.dev_euis.get(&uplink.dev_eui).map(|e| e.clone()) {
+            if let Some(device) = registry.get_device(&device_id) {
+                return Ok(Some(device));
+            }
+        }
+
+        let template = uplink.device_profile.as_ref().and_then(|profile| {
+            let device_type = self
+                .config
+                .profile_device_types
+                .get(profile)
+                .unwrap_or(profile);
+            registry.get_template(device_type)
+        });
+        let Some(template) = template else {
+            let mut info = DiscoveredDeviceInfo::new(
+                uplink.dev_eui.clone(),
+                uplink.device_profile.clone().unwrap_or_default(),
+            );
+            info.name = uplink.device_name.clone();
+            info.endpoint = Some(format!("lorawan:{}", uplink.dev_eui));
+            info.metadata = json!({
+                "adapter": CHIRPSTACK_ADAPTER_TYPE,
+                "dev_eui": uplink.dev_eui,
+                "application_id": uplink.application_id,
+                "device_profile": uplink.device_profile,
+            });
+            let _ = self.event_tx.send(DeviceEvent::Discovery { device: info });
+            return Ok(None);
+        };
+
+        let mut connection_config = ConnectionConfig::new();
+        connection_config
+            .extra
+            .insert("dev_eui".to_string(), json!(uplink.dev_eui));
+        if let Some(application_id) = &uplink.application_id {
+            connection_config
+                .extra
+                .insert("application_id".to_string(), json!(application_id));
+        }
+        let device = DeviceConfig {
+            device_id: uplink.dev_eui.clone(),
+            name: uplink
+                .device_name
+                .clone()
+                .unwrap_or_else(|| uplink.dev_eui.clone()),
+            device_type: template.device_type.clone(),
+            adapter_type: CHIRPSTACK_ADAPTER_TYPE.to_string(),
+            connection_config,
+            adapter_id: Some(self.name.clone()),
+            last_seen: 0,
+            offline_timeout_secs: template.default_offline_timeout_secs,
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
+        };
+        registry
+            .register_device(device.clone())
+            .await
+            .map_err(|e| AdapterError::Configuration(e.to_string()))?;
+        self.dev_euis
+            .insert(uplink.dev_eui.clone(), device.device_id.clone());
+        info!(
+            "ChirpStack adapter '{}' registered device '{}' (type '{}')",
+            self.name, device.device_id, device.device_type
+        );
+        Ok(Some(device))
+    }
+
+    async fn mark_online(&self, device: &DeviceConfig) {
+        let timestamp = chrono::Utc::now().timestamp();
+        if self
+            .last_uplink
+            .insert(device.device_id.clone(), timestamp)
+            .is_some()
+        {
+            return;
+        }
+
+        let _ = self.event_tx.send(DeviceEvent::State {
+            device_id: device.device_id.clone(),
+            old_state: ConnectionStatus::Disconnected,
+            new_state: ConnectionStatus::Connected,
+            timestamp,
+        });
+        if let Some(bus) = &self.event_bus {
+            bus.publish(NeoMindEvent::DeviceOnline {
+                device_id: device.device_id.clone(),
+                device_type: device.device_type.clone(),
+                timestamp,
+            })
+            .await;
+        }
+    }
+
+    async fn emit_metric(&self, device_id: &str, metric: &str, value: MetricValue, timestamp: i64) {
+        let event = DeviceEvent::Metric {
+            device_id: device_id.to_string(),
+            metric: metric.to_string(),
+            value: value.clone(),
+            timestamp,
+        };
+        let _ = self.event_tx.send(event.clone());
+
+        if let Some(storage) = self.telemetry_storage.read().await.as_ref() {
+            let data_point = crate::telemetry::DataPoint {
+                timestamp,
+                value,
+                quality: None,
+            };
+            if let Err(e) = storage
+                .write(&format!("device:{}", device_id), metric, data_point)
+                .await
+            {
+                warn!(
+                    "Failed to write telemetry for {}/{}: {}",
+                    device_id, metric, e
+                );
+            }
+        }
+
+        if let Some(bus) = &self.event_bus {
+            for neomind_event in event.into_neomind_events() {
+                bus.publish(neomind_event).await;
+            }
+        }
+    }
+
+    /// Whether this adapter should manage the given device.
+    fn manages(&self, device: &DeviceConfig) -> bool {
+        device.adapter_type == CHIRPSTACK_ADAPTER_TYPE
+            && device
+                .adapter_id
+                .as_deref()
+                .map(|id| id == self.name)
+                .unwrap_or(true)
+    }
+
+    /// Handle a message from the MQTT integration; the event type is the
+    /// last topic segment.
+    async fn handle_publish(&self, topic: &str, payload: &[u8]) {
+        let event = topic.rsplit('/').next().unwrap_or_default();
+        match serde_json::from_slice::<Value>(payload) {
+            Ok(body) => {
+                if let Err(e) = self.handle_event(event, &body).await {
+                    warn!("ChirpStack '{}' event on {}: {}", event, topic, e);
+                }
+            }
+            Err(e) => warn!(
+                "ChirpStack event on {} is not JSON (set the integration marshaler to json): {}",
+                topic, e
+            ),
+        }
+    }
+
+    /// Connect to the MQTT integration broker and process device events
+    /// until the adapter stops.
+    fn spawn_mqtt_integration(&self, host: String) {
+        let client_id = format!("neomind-chirpstack-{}", uuid::Uuid::new_v4().simple());
+        let mut options = rumqttc::MqttOptions::new(client_id, host.clone(), self.config.port);
+        options.set_keep_alive(Duration::from_secs(60));
+        if let (Some(user), Some(pass)) = (&self.config.username, &self.config.password) {
+            options.set_credentials(user, pass);
+        }
+        let (client, mut eventloop) = rumqttc::AsyncClient::new(options, 100);
+        let topic = self.config.event_topic();
+        let adapter = self.clone();
+
+        tokio::spawn(async move {
+            *adapter.client.write().await = Some(client.clone());
+            let mut error_count: u32 = 0;
+
+            while adapter.running.load(Ordering::Relaxed) {
+                match eventloop.poll().await {
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(_))) => {
+                        error_count = 0;
+                        // (Re)subscribe on every connect: clean sessions drop
+                        // subscriptions across reconnects
+                        if let Err(e) = client
+                            .subscribe(topic.clone(), rumqttc::QoS::AtLeastOnce)
+                            .await
+                        {
+                            warn!("ChirpStack subscribe to '{}' failed: {}", topic, e);
+                        } else {
+                            info!("ChirpStack integration connected to {} ({})", host, topic);
+                        }
+                    }
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish))) => {
+                        adapter
+                            .handle_publish(&publish.topic, &publish.payload)
+                            .await;
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error_count += 1;
+                        // rumqttc reconnects on the next poll; back off 1s..30s
+                        let backoff = Duration::from_secs((1u64 << error_count.min(5)).min(30));
+                        warn!(
+                            "ChirpStack broker {} error ({}), reconnecting in {:?}: {}",
+                            host, error_count, backoff, e
+                        );
+                        tokio::time::sleep(backoff).await;
+                    }
+                }
+            }
+
+            let _ = client.disconnect().await;
+            *adapter.client.write().await = None;
+        });
+    }
+}
+
+#[async_trait]
+impl DeviceAdapter for ChirpStackAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn adapter_type(&self) -> &'static str {
+        CHIRPSTACK_ADAPTER_TYPE
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    async fn start(&self) -> AdapterResult<()> {
+        if self.running.swap(true, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        {
+            let registry = self.device_registry.read().await;
+            for device in registry.list_devices() {
+                if self.manages(&device) {
+                    self.dev_euis
+                        .insert(device_dev_eui(&device), device.device_id.clone());
+                }
+            }
+        }
+
+        if let Some(host) = self.config.broker.clone() {
+            self.spawn_mqtt_integration(host);
+        }
+
+        info!(
+            "ChirpStack adapter '{}' started ({} devices, MQTT integration: {})",
+            self.name,
+            self.dev_euis.len(),
+            self.config.broker.as_deref().unwrap_or("off")
+        );
+        Ok(())
+    }
+
+    async fn stop(&self) -> AdapterResult<()> {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(client) = self.client.read().await.as_ref() {
+            let _ = client.disconnect().await;
+        }
+        info!("ChirpStack adapter '{}' stopped", self.name);
+        Ok(())
+    }
+
+    fn subscribe(&self) -> Pin<Box<dyn Stream<Item = DeviceEvent> + Send + '_>> {
+        let rx = self.event_tx.subscribe();
+        Box::pin(async_stream::stream! {
+            let mut rx = rx;
+            while let Ok(event) = rx.recv().await {
+                yield event;
+            }
+        })
+    }
+
+    fn set_telemetry_storage(&self, storage: Arc<TimeSeriesStorage>) {
+        let telemetry_storage = self.telemetry_storage.clone();
+        tokio::spawn(async move {
+            *telemetry_storage.write().await = Some(storage);
+        });
+    }
+
+    fn device_count(&self) -> usize {
+        self.dev_euis.len()
+    }
+
+    fn list_devices(&self) -> Vec<String> {
+        self.dev_euis.iter().map(|e| e.value().clone()).collect()
+    }
+
+    async fn send_command(
+        &self,
+        device_id: &str,
+        command_name: &str,
+        payload: String,
+        _topic: Option<String>,
+    ) -> AdapterResult<()> {
+        let registry = self.device_registry.read().await.clone();
+        let device = registry
+            .get_device(device_id)
+            .ok_or_else(|| AdapterError::DeviceNotFound(device_id.to_string()))?;
+        let lora = LoraDeviceConfig::from_device(&device, &self.config)?;
+        let codec = registry
+            .get_template(&device.device_type)
+            .and_then(|t| t.binary_codec)
+            .filter(|c| c.commands.contains_key(command_name));
+        let request = build_downlink(&lora, codec.as_ref(), &payload)?;
+
+        let client = self.client.read().await.clone().ok_or_else(|| {
+            AdapterError::Connection(
+                "ChirpStack downlinks need the MQTT integration (no broker connected)".to_string(),
+            )
+        })?;
+        let result = client
+            .publish(
+                lora.downlink_topic(),
+                rumqttc::QoS::AtLeastOnce,
+                false,
+                request.to_string(),
+            )
+            .await
+            .map_err(|e| AdapterError::Communication(e.to_string()));
+
+        let _ = self.event_tx.send(DeviceEvent::CommandResult {
+            device_id: device_id.to_string(),
+            command: command_name.to_string(),
+            success: result.is_ok(),
+            result: result.as_ref().err().map(|e| e.to_string()),
+            timestamp: chrono::Utc::now().timestamp(),
+        });
+        result
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        if !self.is_running() {
+            ConnectionStatus::Disconnected
+        } else if self.config.broker.is_some()
+            && self.client.try_read().map(|c| c.is_none()).unwrap_or(false)
+        {
+            ConnectionStatus::Connecting
+        } else {
+            ConnectionStatus::Connected
+        }
+    }
+
+    async fn subscribe_device(&self, device_id: &str) -> AdapterResult<()> {
+        let registry = self.device_registry.read().await;
+        let device = registry
+            .get_device(device_id)
+            .ok_or_else(|| AdapterError::DeviceNotFound(device_id.to_string()))?;
+        self.dev_euis
+            .insert(device_dev_eui(&device), device.device_id.clone());
+        Ok(())
+    }
+
+    async fn unsubscribe_device(&self, device_id: &str) -> AdapterResult<()> {
+        self.dev_euis.retain(|_, id| id != device_id);
+        self.last_uplink.remove(device_id);
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Create a ChirpStack adapter from configuration.
+pub fn create_chirpstack_adapter(
+    config: ChirpStackAdapterConfig,
+    event_bus: &EventBus,
+    device_registry: Arc<DeviceRegistry>,
+) -> Arc<ChirpStackAdapter> {
+    Arc::new(ChirpStackAdapter::new(
+        config,
+        Some(Arc::new(event_bus.clone())),
+        device_registry,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::DeviceTypeTemplate;
+
+    fn uplink_event(data: &str) -> Value {
+        json!({
+            "deviceInfo": {
+                "applicationId": "app-1",
+                "deviceProfileName": "th-sensor",
+                "deviceName": "Greenhouse TH",
+                "devEui": "0004A30B001C0530"
+            },
+            "fPort": 1,
+            "data": data,
+            "rxInfo": [{ "rssi": -97, "snr": 7.5 }]
+        })
+    }
+
+    #[test]
+    fn test_parse_uplink() {
+        let uplink = LoraUplink::parse(&uplink_event("AOst")).unwrap();
+        assert_eq!(uplink.dev_eui, "0004a30b001c0530");
+        assert_eq!(uplink.application_id.as_deref(), Some("app-1"));
+        assert_eq!(uplink.device_profile.as_deref(), Some("th-sensor"));
+        assert_eq!(uplink.data, Some(vec![0x00, 0xeb, 0x2d]));
+        assert_eq!(uplink.object, None);
+
+        assert!(LoraUplink::parse(&json!({ "data": "AOst" })).is_none());
+    }
+
+    #[test]
+    fn test_build_downlink() {
+        let device = LoraDeviceConfig {
+            dev_eui: "0004a30b001c0530".to_string(),
+            application_id: "app-1".to_string(),
+            f_port: 10,
+            confirmed: true,
+        };
+        assert_eq!(
+            device.downlink_topic(),
+            "application/app-1/device/0004a30b001c0530/command/down"
+        );
+
+        let codec = BinaryCodec::default();
+        let request = build_downlink(&device, Some(&codec), "0102").unwrap();
+        assert_eq!(request["data"], "AQI=");
+        assert_eq!(request["fPort"], 10);
+        assert_eq!(request["confirmed"], true);
+
+        let request = build_downlink(&device, None, r#"{"interval": 600}"#).unwrap();
+        assert_eq!(request["object"]["interval"], 600);
+        assert!(build_downlink(&device, None, "ON").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_uplink_registers_and_decodes() {
+        let registry = Arc::new(DeviceRegistry::new());
+        let mut template = DeviceTypeTemplate::new("th_sensor", "LoRa TH Sensor");
+        template.binary_codec = Some(
+            serde_json::from_value(json!({
+                "fields": [
+                    { "name": "temperature", "offset": 0, "length": 2, "kind": "int", "scale": 0.1 },
+                    { "name": "humidity", "offset": 2, "length": 1 }
+                ]
+            }))
+            .unwrap(),
+        );
+        registry.register_template(template).await.unwrap();
+
+        let mut config = ChirpStackAdapterConfig::new("test-chirpstack");
+        config
+            .profile_device_types
+            .insert("th-sensor".to_string(), "th_sensor".to_string());
+        let adapter = ChirpStackAdapter::new(config, None, registry.clone());
+        let mut events = adapter.event_tx.subscribe();
+
+        adapter
+            .handle_event("up", &uplink_event("AOst"))
+            .await
+            .unwrap();
+
+        let device = registry.get_device("0004a30b001c0530").unwrap();
+        assert_eq!(device.device_type, "th_sensor");
+        assert_eq!(device.adapter_type, CHIRPSTACK_ADAPTER_TYPE);
+
+        let mut metrics = HashMap::new();
+        while let Ok(event) = events.try_recv() {
+            if let DeviceEvent::Metric { metric, value, .. } = event {
+                metrics.insert(metric, value);
+            }
+        }
+        match metrics["temperature"] {
+            MetricValue::Float(t) => assert!((t - 23.5).abs() < 1e-9),
+            ref other => panic!("unexpected temperature {:?}", other),
+        }
+        assert_eq!(metrics["humidity"], MetricValue::Integer(45));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_profile_emits_discovery() {
+        let registry = Arc::new(DeviceRegistry::new());
+        let adapter = ChirpStackAdapter::new(
+            ChirpStackAdapterConfig::new("test-chirpstack"),
+            None,
+            registry.clone(),
+        );
+        let mut events = adapter.event_tx.subscribe();
+
+        adapter
+            .handle_event("up", &uplink_event("AOst"))
+            .await
+            .unwrap();
+
+        assert!(registry.get_device("0004a30b001c0530").is_none());
+        assert!(matches!(
+            events.try_recv(),
+            Ok(DeviceEvent::Discovery { .. })
+        ));
+    }
+}
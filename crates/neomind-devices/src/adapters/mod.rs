@@ -9,6 +9,8 @@
 //! |---------|-------------|
 //! | `mqtt` | MQTT protocol support (default) |
 //! | `webhook` | Webhook adapter (default) |
+//! | `modbus-tcp` | Modbus TCP polling adapter (always available) |
+//! | `chirpstack` | ChirpStack LoRaWAN integration (requires `mqtt`) |
 //! | `embedded-broker` | Embedded MQTT broker |
 
 // MQTT adapter (feature-gated)
@@ -21,6 +23,20 @@ pub use mqtt::{create_mqtt_adapter, MqttAdapter, MqttAdapterConfig};
 pub mod webhook;
 pub use webhook::{create_webhook_adapter, WebhookAdapter, WebhookAdapterConfig, WebhookPayload};
 
+// Modbus TCP adapter (always available, no extra dependencies)
+pub mod modbus;
+pub use modbus::{
+    create_modbus_tcp_adapter, ModbusRegister, ModbusTcpAdapter, ModbusTcpAdapterConfig,
+};
+
+// ChirpStack LoRaWAN adapter (uses the MQTT client for its integration)
+#[cfg(feature = "mqtt")]
+pub mod chirpstack;
+#[cfg(feature = "mqtt")]
+pub use chirpstack::{
+    create_chirpstack_adapter, ChirpStackAdapter, ChirpStackAdapterConfig, LoraUplink,
+};
+
 use crate::adapter::{AdapterResult, DeviceAdapter};
 use neomind_core::EventBus;
 use serde_json::Value;
@@ -55,6 +71,29 @@ pub fn create_adapter(
             let device_registry = Arc::new(crate::registry::DeviceRegistry::new());
             Ok(create_webhook_adapter(cfg, event_bus, device_registry))
         }
+        modbus::MODBUS_TCP_ADAPTER_TYPE => {
+            let cfg: ModbusTcpAdapterConfig =
+                serde_json::from_value(config.clone()).map_err(|e| {
+                    crate::adapter::AdapterError::Configuration(format!(
+                        "Invalid Modbus TCP config: {}",
+                        e
+                    ))
+                })?;
+            let device_registry = Arc::new(crate::registry::DeviceRegistry::new());
+            Ok(create_modbus_tcp_adapter(cfg, event_bus, device_registry))
+        }
+        #[cfg(feature = "mqtt")]
+        chirpstack::CHIRPSTACK_ADAPTER_TYPE => {
+            let cfg: ChirpStackAdapterConfig =
+                serde_json::from_value(config.clone()).map_err(|e| {
+                    crate::adapter::AdapterError::Configuration(format!(
+                        "Invalid ChirpStack config: {}",
+                        e
+                    ))
+                })?;
+            let device_registry = Arc::new(crate::registry::DeviceRegistry::new());
+            Ok(create_chirpstack_adapter(cfg, event_bus, device_registry))
+        }
         _ => Err(crate::adapter::AdapterError::Configuration(format!(
             "Unknown adapter type: {}. Available adapters: {}",
             adapter_type,
@@ -66,12 +105,16 @@ pub fn create_adapter(
 /// Get list of available adapter types (based on enabled features).
 #[allow(clippy::vec_init_then_push)]
 pub fn available_adapters() -> Vec<&'static str> {
-    let mut adapters = Vec::with_capacity(2);
+    let mut adapters = Vec::with_capacity(4);
 
     #[cfg(feature = "mqtt")]
     adapters.push("mqtt");
 
     adapters.push("webhook");
+    adapters.push(modbus::MODBUS_TCP_ADAPTER_TYPE);
+
+    #[cfg(feature = "mqtt")]
+    adapters.push(chirpstack::CHIRPSTACK_ADAPTER_TYPE);
 
     adapters
 }
@@ -85,6 +128,7 @@ mod tests {
         let adapters = available_adapters();
         assert!(!adapters.is_empty());
         assert!(adapters.contains(&"webhook"));
+        assert!(adapters.contains(&"modbus-tcp"));
     }
 
     #[test]
@@ -0,0 +1,1366 @@
+//! Modbus TCP device adapter for NeoMind event-driven architecture.
+//!
+//! This adapter polls PLCs and industrial gateways over Modbus TCP and converts
+//! raw registers into metric values, so field equipment can be onboarded
+//! without an external protocol bridge.
+//!
+//! ## Features
+//!
+//! - Per-device polling task with configurable interval
+//! - Coils, discrete inputs, input registers and holding registers
+//! - Typed decoding (bool, u16, i16, u32, i32, f32) with scale/offset
+//! - Command support via single coil / register writes
+//! - Online/offline transitions based on poll success
+//!
+//! ## Device Configuration
+//!
+//! Devices use `adapter_type = "modbus-tcp"` and carry their connection in
+//! `ConnectionConfig`:
+//!
+//! ```json
+//! {
+//!   "host": "192.168.1.50",
+//!   "port": 502,
+//!   "slave_id": 1,
+//!   "poll_interval_ms": 2000,
+//!   "register_map": { "setpoint": 40 },
+//!   "modbus_registers": [
+//!     { "metric": "temperature", "address": 0, "register_type": "input_register",
+//!       "data_type": "i16", "scale": 0.1 },
+//!     { "metric": "pump_running", "address": 3, "register_type": "coil" }
+//!   ]
+//! }
+//! ```
+//!
+//! `register_map` is a shorthand for unsigned 16-bit holding registers;
+//! `modbus_registers` allows the full register description.
+//!
+//! ## Command Payload Format
+//!
+//! ```json
+//! { "metric": "setpoint", "value": 215 }
+//! { "address": 3, "register_type": "coil", "value": true }
+//! ```
+
+use crate::adapter::{AdapterError, AdapterResult, ConnectionStatus, DeviceAdapter, DeviceEvent};
+use crate::mdl::MetricValue;
+use crate::registry::{ConnectionConfig, DeviceConfig, DeviceRegistry};
+use crate::telemetry::TimeSeriesStorage;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use futures::Stream;
+use neomind_core::{EventBus, NeoMindEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Adapter type identifier used in `DeviceConfig::adapter_type`.
+pub const MODBUS_TCP_ADAPTER_TYPE: &str = "modbus-tcp";
+
+/// Default Modbus TCP port.
+pub const DEFAULT_MODBUS_PORT: u16 = 502;
+
+/// Most registers a single Write Multiple Registers (0x10) request may carry.
+const MAX_WRITE_REGISTERS: usize = 123;
+
+/// Modbus TCP adapter configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusTcpAdapterConfig {
+    /// Adapter name
+    pub name: String,
+    /// Poll interval used when a device doesn't set `poll_interval_ms`
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Timeout for connecting and for each request/response round trip
+    #[serde(default = "default_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+}
+
+fn default_poll_interval_ms() -> u64 {
+    5000
+}
+
+fn default_request_timeout_ms() -> u64 {
+    3000
+}
+
+impl ModbusTcpAdapterConfig {
+    /// Create a new Modbus TCP adapter configuration.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            poll_interval_ms: default_poll_interval_ms(),
+            request_timeout_ms: default_request_timeout_ms(),
+        }
+    }
+
+    /// Set the default poll interval.
+    pub fn with_poll_interval_ms(mut self, interval_ms: u64) -> Self {
+        self.poll_interval_ms = interval_ms;
+        self
+    }
+
+    /// Set the request timeout.
+    pub fn with_request_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.request_timeout_ms = timeout_ms;
+        self
+    }
+}
+
+/// Modbus data table a register lives in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterType {
+    /// Read/write single bit (function codes 0x01 / 0x05)
+    Coil,
+    /// Read-only single bit (function code 0x02)
+    DiscreteInput,
+    /// Read-only 16-bit word (function code 0x04)
+    InputRegister,
+    /// Read/write 16-bit word (function codes 0x03 / 0x06 / 0x10)
+    #[default]
+    HoldingRegister,
+}
+
+impl RegisterType {
+    fn read_function_code(self) -> u8 {
+        match self {
+            Self::Coil => 0x01,
+            Self::DiscreteInput => 0x02,
+            Self::HoldingRegister => 0x03,
+            Self::InputRegister => 0x04,
+        }
+    }
+
+    fn is_bit(self) -> bool {
+        matches!(self, Self::Coil | Self::DiscreteInput)
+    }
+
+    fn is_writable(self) -> bool {
+        matches!(self, Self::Coil | Self::HoldingRegister)
+    }
+}
+
+/// How the raw register words are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterDataType {
+    Bool,
+    #[default]
+    U16,
+    I16,
+    U32,
+    I32,
+    F32,
+}
+
+impl RegisterDataType {
+    /// Number of 16-bit registers occupied by this type.
+    pub fn word_count(self) -> u16 {
+        match self {
+            Self::Bool | Self::U16 | Self::I16 => 1,
+            Self::U32 | Self::I32 | Self::F32 => 2,
+        }
+    }
+}
+
+/// Word order for 32-bit values spanning two registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordOrder {
+    /// High word first (Modbus convention, "ABCD")
+    #[default]
+    BigEndian,
+    /// Low word first ("CDAB", common on some PLC vendors)
+    LittleEndian,
+}
+
+/// A single register mapped to a metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModbusRegister {
+    /// Metric name emitted for this register
+    pub metric: String,
+    /// Zero-based register / coil address
+    pub address: u16,
+    #[serde(default)]
+    pub register_type: RegisterType,
+    #[serde(default)]
+    pub data_type: RegisterDataType,
+    /// Multiplier applied to numeric values (e.g. 0.1 for tenths of a degree)
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    /// Offset added after scaling
+    #[serde(default)]
+    pub offset: f64,
+    #[serde(default)]
+    pub word_order: WordOrder,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+impl ModbusRegister {
+    /// Create a holding register mapping with default (u16) decoding.
+    pub fn holding(metric: impl Into<String>, address: u16) -> Self {
+        Self {
+            metric: metric.into(),
+            address,
+            register_type: RegisterType::HoldingRegister,
+            data_type: RegisterDataType::U16,
+            scale: 1.0,
+            offset: 0.0,
+            word_order: WordOrder::BigEndian,
+        }
+    }
+
+    /// Number of registers (or bits) to read for this mapping.
+    pub fn quantity(&self) -> u16 {
+        if self.register_type.is_bit() {
+            1
+        } else {
+            self.data_type.word_count()
+        }
+    }
+
+    /// Convert raw register words into a metric value.
+    ///
+    /// For bit tables, `words` holds one entry per bit (0 or 1).
+    pub fn decode(&self, words: &[u16]) -> Option<MetricValue> {
+        if words.len() < self.quantity() as usize {
+            return None;
+        }
+        if self.register_type.is_bit() || self.data_type == RegisterDataType::Bool {
+            return Some(MetricValue::Boolean(words[0] != 0));
+        }
+
+        let raw = match self.data_type {
+            RegisterDataType::U16 => words[0] as f64,
+            RegisterDataType::I16 => words[0] as i16 as f64,
+            RegisterDataType::U32 => self.combine(words) as f64,
+            RegisterDataType::I32 => self.combine(words) as i32 as f64,
+            RegisterDataType::F32 => f32::from_bits(self.combine(words)) as f64,
+            RegisterDataType::Bool => unreachable!(),
+        };
+
+        let is_integral = self.data_type != RegisterDataType::F32
+            && self.scale.fract() == 0.0
+            && self.offset.fract() == 0.0;
+        let value = raw * self.scale + self.offset;
+        if is_integral {
+            Some(MetricValue::Integer(value as i64))
+        } else {
+            Some(MetricValue::Float(value))
+        }
+    }
+
+    /// Convert an engineering value back into register words (inverse of `decode`).
+    pub fn encode(&self, value: &Value) -> AdapterResult<Vec<u16>> {
+        if self.register_type.is_bit() || self.data_type == RegisterDataType::Bool {
+            let on = match value {
+                Value::Bool(b) => *b,
+                Value::Number(n) => n.as_f64().map(|f| f != 0.0).unwrap_or(false),
+                Value::String(s) => matches!(s.as_str(), "true" | "on" | "1"),
+                _ => {
+                    return Err(AdapterError::Configuration(format!(
+                        "Invalid boolean value for '{}': {}",
+                        self.metric, value
+                    )))
+                }
+            };
+            return Ok(vec![on as u16]);
+        }
+
+        let engineering = value.as_f64().ok_or_else(|| {
+            AdapterError::Configuration(format!(
+                "Invalid numeric value for '{}': {}",
+                self.metric, value
+            ))
+        })?;
+        let scale = if self.scale == 0.0 { 1.0 } else { self.scale };
+        let raw = (engineering - self.offset) / scale;
+
+        let words = match self.data_type {
+            RegisterDataType::U16 => vec![raw.round().clamp(0.0, u16::MAX as f64) as u16],
+            RegisterDataType::I16 => {
+                vec![raw.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16 as u16]
+            }
+            RegisterDataType::U32 => self.split(raw.round().clamp(0.0, u32::MAX as f64) as u32),
+            RegisterDataType::I32 => {
+                self.split(raw.round().clamp(i32::MIN as f64, i32::MAX as f64) as i32 as u32)
+            }
+            RegisterDataType::F32 => self.split((raw as f32).to_bits()),
+            RegisterDataType::Bool => unreachable!(),
+        };
+        Ok(words)
+    }
+
+    fn combine(&self, words: &[u16]) -> u32 {
+        let (hi, lo) = match self.word_order {
+            WordOrder::BigEndian => (words[0], words[1]),
+            WordOrder::LittleEndian => (words[1], words[0]),
+        };
+        ((hi as u32) << 16) | lo as u32
+    }
+
+    fn split(&self, value: u32) -> Vec<u16> {
+        let hi = (value >> 16) as u16;
+        let lo = (value & 0xFFFF) as u16;
+        match self.word_order {
+            WordOrder::BigEndian => vec![hi, lo],
+            WordOrder::LittleEndian => vec![lo, hi],
+        }
+    }
+}
+
+/// Resolved per-device Modbus settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModbusDeviceConfig {
+    pub host: String,
+    pub port: u16,
+    pub slave_id: u8,
+    pub poll_interval_ms: u64,
+    pub registers: Vec<ModbusRegister>,
+}
+
+impl ModbusDeviceConfig {
+    /// Build the device settings from a device's `ConnectionConfig`.
+    ///
+    /// `default_poll_interval_ms` is used when the device doesn't set
+    /// `poll_interval_ms` in its extra parameters.
+    pub fn from_connection_config(
+        config: &ConnectionConfig,
+        default_poll_interval_ms: u64,
+    ) -> AdapterResult<Self> {
+        let host = config
+            .host
+            .clone()
+            .filter(|h| !h.trim().is_empty())
+            .ok_or_else(|| {
+                AdapterError::Configuration("Modbus device requires 'host'".to_string())
+            })?;
+
+        let mut registers: Vec<ModbusRegister> = match config.extra.get("modbus_registers") {
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
+                AdapterError::Configuration(format!("Invalid modbus_registers: {}", e))
+            })?,
+            None => Vec::new(),
+        };
+
+        if let Some(map) = &config.register_map {
+            let mut shorthand: Vec<_> = map
+                .iter()
+                .filter(|(metric, _)| !registers.iter().any(|r| &r.metric == *metric))
+                .map(|(metric, address)| ModbusRegister::holding(metric.clone(), *address))
+                .collect();
+            // HashMap iteration order is random; keep polling order stable
+            shorthand.sort_by_key(|r| r.address);
+            registers.extend(shorthand);
+        }
+
+        let poll_interval_ms = config
+            .extra
+            .get("poll_interval_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(default_poll_interval_ms)
+            .max(100);
+
+        Ok(Self {
+            host,
+            port: config.port.unwrap_or(DEFAULT_MODBUS_PORT),
+            slave_id: config.slave_id.unwrap_or(1),
+            poll_interval_ms,
+            registers,
+        })
+    }
+
+    fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    fn find_register(&self, metric: &str) -> Option<&ModbusRegister> {
+        self.registers.iter().find(|r| r.metric == metric)
+    }
+}
+
+// ============================================================================
+// Modbus TCP framing
+// ============================================================================
+
+/// Build an MBAP-framed request (header + PDU).
+fn build_frame(transaction_id: u16, unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(7 + pdu.len());
+    frame.extend_from_slice(&transaction_id.to_be_bytes());
+    frame.extend_from_slice(&0u16.to_be_bytes()); // protocol id
+    frame.extend_from_slice(&((pdu.len() + 1) as u16).to_be_bytes());
+    frame.push(unit_id);
+    frame.extend_from_slice(pdu);
+    frame
+}
+
+fn read_pdu(function_code: u8, address: u16, quantity: u16) -> Vec<u8> {
+    let mut pdu = vec![function_code];
+    pdu.extend_from_slice(&address.to_be_bytes());
+    pdu.extend_from_slice(&quantity.to_be_bytes());
+    pdu
+}
+
+fn write_pdu(register_type: RegisterType, address: u16, words: &[u16]) -> AdapterResult<Vec<u8>> {
+    let mut pdu = Vec::new();
+    match (register_type, words) {
+        (RegisterType::Coil, [value]) => {
+            pdu.push(0x05);
+            pdu.extend_from_slice(&address.to_be_bytes());
+            pdu.extend_from_slice(&(if *value != 0 { 0xFF00u16 } else { 0 }).to_be_bytes());
+        }
+        (RegisterType::HoldingRegister, [value]) => {
+            pdu.push(0x06);
+            pdu.extend_from_slice(&address.to_be_bytes());
+            pdu.extend_from_slice(&value.to_be_bytes());
+        }
+        (RegisterType::HoldingRegister, words) if words.len() > MAX_WRITE_REGISTERS => {
+            return Err(AdapterError::Configuration(format!(
+                "Cannot write {} registers in one request (at most {})",
+                words.len(),
+                MAX_WRITE_REGISTERS
+            )))
+        }
+        (RegisterType::HoldingRegister, words) if !words.is_empty() => {
+            pdu.push(0x10);
+            pdu.extend_from_slice(&address.to_be_bytes());
+            pdu.extend_from_slice(&(words.len() as u16).to_be_bytes());
+            pdu.push((words.len() * 2) as u8);
+            for w in words {
+                pdu.extend_from_slice(&w.to_be_bytes());
+            }
+        }
+        _ => {
+            return Err(AdapterError::Configuration(format!(
+                "Register type {:?} is not writable",
+                register_type
+            )))
+        }
+    }
+    Ok(pdu)
+}
+
+/// Validate a response PDU and extract register words (or bits) from a read.
+fn parse_read_response(
+    function_code: u8,
+    quantity: u16,
+    is_bit: bool,
+    pdu: &[u8],
+) -> AdapterResult<Vec<u16>> {
+    check_exception(function_code, pdu)?;
+    let byte_count = *pdu
+        .get(1)
+        .ok_or_else(|| AdapterError::Communication("Truncated Modbus response".to_string()))?
+        as usize;
+    let data = pdu
+        .get(2..2 + byte_count)
+        .ok_or_else(|| AdapterError::Communication("Truncated Modbus response".to_string()))?;
+
+    if is_bit {
+        let bits = (0..quantity as usize)
+            .map(|i| {
+                data.get(i / 8)
+                    .map(|byte| ((byte >> (i % 8)) & 1) as u16)
+                    .unwrap_or(0)
+            })
+            .collect();
+        Ok(bits)
+    } else {
+        if data.len() < quantity as usize * 2 {
+            return Err(AdapterError::Communication(format!(
+                "Expected {} registers, got {} bytes",
+                quantity,
+                data.len()
+            )));
+        }
+        Ok(data
+            .chunks_exact(2)
+            .take(quantity as usize)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect())
+    }
+}
+
+fn check_exception(function_code: u8, pdu: &[u8]) -> AdapterResult<()> {
+    match pdu.first() {
+        Some(fc) if *fc == function_code => Ok(()),
+        Some(fc) if *fc == function_code | 0x80 => Err(AdapterError::Communication(format!(
+            "Modbus exception {} for function 0x{:02X}",
+            exception_name(pdu.get(1).copied().unwrap_or(0)),
+            function_code
+        ))),
+        Some(fc) => Err(AdapterError::Communication(format!(
+            "Unexpected function code 0x{:02X} (expected 0x{:02X})",
+            fc, function_code
+        ))),
+        None => Err(AdapterError::Communication(
+            "Empty Modbus response".to_string(),
+        )),
+    }
+}
+
+fn exception_name(code: u8) -> &'static str {
+    match code {
+        0x01 => "IllegalFunction",
+        0x02 => "IllegalDataAddress",
+        0x03 => "IllegalDataValue",
+        0x04 => "ServerDeviceFailure",
+        0x06 => "ServerDeviceBusy",
+        0x0A => "GatewayPathUnavailable",
+        0x0B => "GatewayTargetFailedToRespond",
+        _ => "Unknown",
+    }
+}
+
+/// Minimal Modbus TCP client over a single connection.
+struct ModbusTcpClient {
+    stream: TcpStream,
+    unit_id: u8,
+    timeout: Duration,
+    next_transaction: u16,
+}
+
+impl ModbusTcpClient {
+    async fn connect(address: &str, unit_id: u8, timeout: Duration) -> AdapterResult<Self> {
+        let stream = tokio::time::timeout(timeout, TcpStream::connect(address))
+            .await
+            .map_err(|_| AdapterError::Timeout(timeout.as_millis() as u64))?
+            .map_err(|e| AdapterError::Connection(format!("{}: {}", address, e)))?;
+        let _ = stream.set_nodelay(true);
+        Ok(Self {
+            stream,
+            unit_id,
+            timeout,
+            next_transaction: 1,
+        })
+    }
+
+    async fn request(&mut self, pdu: &[u8]) -> AdapterResult<Vec<u8>> {
+        let timeout = self.timeout;
+        tokio::time::timeout(timeout, self.round_trip(pdu))
+            .await
+            .map_err(|_| AdapterError::Timeout(timeout.as_millis() as u64))?
+    }
+
+    async fn round_trip(&mut self, pdu: &[u8]) -> AdapterResult<Vec<u8>> {
+        let tid = self.next_transaction;
+        self.next_transaction = self.next_transaction.wrapping_add(1);
+        let frame = build_frame(tid, self.unit_id, pdu);
+        self.stream
+            .write_all(&frame)
+            .await
+            .map_err(|e| AdapterError::Communication(e.to_string()))?;
+
+        let mut header = [0u8; 7];
+        self.stream
+            .read_exact(&mut header)
+            .await
+            .map_err(|e| AdapterError::Communication(e.to_string()))?;
+        let resp_tid = u16::from_be_bytes([header[0], header[1]]);
+        let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+        if !(2..=254).contains(&length) {
+            return Err(AdapterError::Communication(format!(
+                "Invalid MBAP length {}",
+                length
+            )));
+        }
+
+        let mut body = vec![0u8; length - 1];
+        self.stream
+            .read_exact(&mut body)
+            .await
+            .map_err(|e| AdapterError::Communication(e.to_string()))?;
+        if resp_tid != tid {
+            return Err(AdapterError::Communication(format!(
+                "Transaction id mismatch: sent {}, got {}",
+                tid, resp_tid
+            )));
+        }
+        Ok(body)
+    }
+
+    async fn read(&mut self, register: &ModbusRegister) -> AdapterResult<Vec<u16>> {
+        let fc = register.register_type.read_function_code();
+        let quantity = register.quantity();
+        let response = self
+            .request(&read_pdu(fc, register.address, quantity))
+            .await?;
+        parse_read_response(fc, quantity, register.register_type.is_bit(), &response)
+    }
+
+    async fn write(
+        &mut self,
+        register_type: RegisterType,
+        address: u16,
+        words: &[u16],
+    ) -> AdapterResult<()> {
+        let pdu = write_pdu(register_type, address, words)?;
+        let response = self.request(&pdu).await?;
+        check_exception(pdu[0], &response)
+    }
+}
+
+// ============================================================================
+// Adapter
+// ============================================================================
+
+/// Shared state handed to each device polling task.
+#[derive(Clone)]
+struct PollContext {
+    adapter_name: String,
+    event_tx: broadcast::Sender<DeviceEvent>,
+    event_bus: Option<Arc<EventBus>>,
+    telemetry_storage: Arc<RwLock<Option<Arc<TimeSeriesStorage>>>>,
+    request_timeout: Duration,
+}
+
+impl PollContext {
+    async fn emit_metric(&self, device_id: &str, metric: &str, value: MetricValue, timestamp: i64) {
+        let event = DeviceEvent::Metric {
+            device_id: device_id.to_string(),
+            metric: metric.to_string(),
+            value: value.clone(),
+            timestamp,
+        };
+        let _ = self.event_tx.send(event.clone());
+
+        {
+            let storage_guard = self.telemetry_storage.read().await;
+            if let Some(storage) = storage_guard.as_ref() {
+                let data_point = crate::telemetry::DataPoint {
+                    timestamp,
+                    value,
+                    quality: None,
+                };
+                if let Err(e) = storage
+                    .write(&format!("device:{}", device_id), metric, data_point)
+                    .await
+                {
+                    warn!(
+                        "Failed to write telemetry for {}/{}: {}",
+                        device_id, metric, e
+                    );
+                }
+            }
+        }
+
+        if let Some(bus) = &self.event_bus {
+            for neomind_event in event.into_neomind_events() {
+                bus.publish(neomind_event).await;
+            }
+        }
+    }
+
+    async fn emit_state(
+        &self,
+        device_id: &str,
+        device_type: &str,
+        old_state: ConnectionStatus,
+        new_state: ConnectionStatus,
+        reason: Option<String>,
+    ) {
+        let timestamp = chrono::Utc::now().timestamp();
+        let _ = self.event_tx.send(DeviceEvent::State {
+            device_id: device_id.to_string(),
+            old_state,
+            new_state,
+            timestamp,
+        });
+
+        if let Some(bus) = &self.event_bus {
+            let event = if new_state == ConnectionStatus::Connected {
+                NeoMindEvent::DeviceOnline {
+                    device_id: device_id.to_string(),
+                    device_type: device_type.to_string(),
+                    timestamp,
+                }
+            } else {
+                NeoMindEvent::DeviceOffline {
+                    device_id: device_id.to_string(),
+                    reason,
+                    timestamp,
+                }
+            };
+            bus.publish(event).await;
+        }
+    }
+}
+
+/// Modbus TCP device adapter.
+///
+/// Each subscribed device gets its own polling task holding a persistent
+/// connection; the connection is re-established on the next tick after a
+/// failure.
+pub struct ModbusTcpAdapter {
+    /// Adapter name
+    name: String,
+    /// Configuration
+    config: ModbusTcpAdapterConfig,
+    /// Event bus
+    event_bus: Option<Arc<EventBus>>,
+    /// Device registry (shared with DeviceService)
+    device_registry: Arc<RwLock<Arc<DeviceRegistry>>>,
+    /// Event channel
+    event_tx: broadcast::Sender<DeviceEvent>,
+    /// Running state
+    running: Arc<AtomicBool>,
+    /// Polling tasks indexed by device_id
+    pollers: Arc<DashMap<String, JoinHandle<()>>>,
+    /// Last known connection state per device
+    device_states: Arc<DashMap<String, ConnectionStatus>>,
+    /// Telemetry storage
+    telemetry_storage: Arc<RwLock<Option<Arc<TimeSeriesStorage>>>>,
+}
+
+impl ModbusTcpAdapter {
+    /// Create a new Modbus TCP adapter.
+    pub fn new(
+        config: ModbusTcpAdapterConfig,
+        event_bus: Option<Arc<EventBus>>,
+        device_registry: Arc<DeviceRegistry>,
+    ) -> Self {
+        let (event_tx, _) = broadcast::channel(1000);
+
+        Self {
+            name: config.name.clone(),
+            config,
+            event_bus,
+            device_registry: Arc::new(RwLock::new(device_registry)),
+            event_tx,
+            running: Arc::new(AtomicBool::new(false)),
+            pollers: Arc::new(DashMap::new()),
+            device_states: Arc::new(DashMap::new()),
+            telemetry_storage: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Set the device registry (shared with DeviceService).
+    pub async fn set_shared_device_registry(&self, registry: Arc<DeviceRegistry>) {
+        *self.device_registry.write().await = registry;
+    }
+
+    fn poll_context(&self) -> PollContext {
+        PollContext {
+            adapter_name: self.name.clone(),
+            event_tx: self.event_tx.clone(),
+            event_bus: self.event_bus.clone(),
+            telemetry_storage: self.telemetry_storage.clone(),
+            request_timeout: Duration::from_millis(self.config.request_timeout_ms),
+        }
+    }
+
+    /// Whether this adapter should manage the given device.
+    fn manages(&self, device: &DeviceConfig) -> bool {
+        device.adapter_type == MODBUS_TCP_ADAPTER_TYPE
+            && device
+                .adapter_id
+                .as_deref()
+                .map(|id| id == self.name)
+                .unwrap_or(true)
+    }
+
+    async fn device_config(
+        &self,
+        device_id: &str,
+    ) -> AdapterResult<(DeviceConfig, ModbusDeviceConfig)> {
+        let registry = self.device_registry.read().await;
+        let device = registry
+            .get_device(device_id)
+            .ok_or_else(|| AdapterError::DeviceNotFound(device_id.to_string()))?;
+        let modbus = ModbusDeviceConfig::from_connection_config(
+            &device.connection_config,
+            self.config.poll_interval_ms,
+        )?;
+        Ok((device, modbus))
+    }
+
+    /// Start (or restart) the polling task for a device.
+    async fn spawn_poller(&self, device_id: &str) -> AdapterResult<()> {
+        let (device, modbus) = self.device_config(device_id).await?;
+        if modbus.registers.is_empty() {
+            warn!(
+                "Modbus device '{}' has no registers configured; nothing to poll",
+                device_id
+            );
+        }
+
+        let ctx = self.poll_context();
+        let states = self.device_states.clone();
+        let running = self.running.clone();
+        let device_id_owned = device_id.to_string();
+        let device_type = device.device_type.clone();
+
+        let handle = tokio::spawn(async move {
+            poll_device(ctx, states, running, device_id_owned, device_type, modbus).await;
+        });
+
+        if let Some(previous) = self.pollers.insert(device_id.to_string(), handle) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    fn stop_poller(&self, device_id: &str) {
+        if let Some((_, handle)) = self.pollers.remove(device_id) {
+            handle.abort();
+        }
+        self.device_states.remove(device_id);
+    }
+}
+
+/// Poll loop for a single device.
+async fn poll_device(
+    ctx: PollContext,
+    states: Arc<DashMap<String, ConnectionStatus>>,
+    running: Arc<AtomicBool>,
+    device_id: String,
+    device_type: String,
+    config: ModbusDeviceConfig,
+) {
+    let address = config.address();
+    let mut client: Option<ModbusTcpClient> = None;
+    let mut ticker = tokio::time::interval(Duration::from_millis(config.poll_interval_ms));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    debug!(
+        "Modbus adapter '{}' polling '{}' at {} every {}ms",
+        ctx.adapter_name, device_id, address, config.poll_interval_ms
+    );
+
+    while running.load(Ordering::Relaxed) {
+        ticker.tick().await;
+
+        if client.is_none() {
+            match ModbusTcpClient::connect(&address, config.slave_id, ctx.request_timeout).await {
+                Ok(c) => client = Some(c),
+                Err(e) => {
+                    mark_state(
+                        &ctx,
+                        &states,
+                        &device_id,
+                        &device_type,
+                        ConnectionStatus::Error,
+                        Some(e.to_string()),
+                    )
+                    .await;
+                    continue;
+                }
+            }
+        }
+
+        let Some(conn) = client.as_mut() else {
+            continue;
+        };
+
+        let timestamp = chrono::Utc::now().timestamp();
+        let mut failure = None;
+        for register in &config.registers {
+            match conn.read(register).await {
+                Ok(words) => match register.decode(&words) {
+                    Some(value) => {
+                        ctx.emit_metric(&device_id, &register.metric, value, timestamp)
+                            .await
+                    }
+                    None => warn!(
+                        "Modbus device '{}': could not decode register {} for '{}'",
+                        device_id, register.address, register.metric
+                    ),
+                },
+                // Exception responses are per-register problems (bad address,
+                // read-only table); the link itself is healthy.
+                Err(AdapterError::Communication(msg)) if msg.starts_with("Modbus exception") => {
+                    warn!(
+                        "Modbus device '{}' register '{}': {}",
+                        device_id, register.metric, msg
+                    );
+                }
+                Err(e) => {
+                    failure = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+
+        match failure {
+            Some(reason) => {
+                // Drop the connection so the next tick reconnects
+                client = None;
+                mark_state(
+                    &ctx,
+                    &states,
+                    &device_id,
+                    &device_type,
+                    ConnectionStatus::Error,
+                    Some(reason),
+                )
+                .await;
+            }
+            None => {
+                mark_state(
+                    &ctx,
+                    &states,
+                    &device_id,
+                    &device_type,
+                    ConnectionStatus::Connected,
+                    None,
+                )
+                .await;
+            }
+        }
+    }
+}
+
+/// Record a device state and emit an event only when it changes.
+async fn mark_state(
+    ctx: &PollContext,
+    states: &DashMap<String, ConnectionStatus>,
+    device_id: &str,
+    device_type: &str,
+    new_state: ConnectionStatus,
+    reason: Option<String>,
+) {
+    let old_state = states
+        .insert(device_id.to_string(), new_state)
+        .unwrap_or(ConnectionStatus::Disconnected);
+    if old_state == new_state {
+        return;
+    }
+    if let Some(reason) = &reason {
+        warn!(
+            "Modbus adapter '{}': device '{}' unreachable: {}",
+            ctx.adapter_name, device_id, reason
+        );
+    } else {
+        info!(
+            "Modbus adapter '{}': device '{}' connected",
+            ctx.adapter_name, device_id
+        );
+    }
+    ctx.emit_state(device_id, device_type, old_state, new_state, reason)
+        .await;
+}
+
+#[async_trait]
+impl DeviceAdapter for ModbusTcpAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn adapter_type(&self) -> &'static str {
+        MODBUS_TCP_ADAPTER_TYPE
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    async fn start(&self) -> AdapterResult<()> {
+        if self.running.swap(true, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let devices: Vec<String> = {
+            let registry = self.device_registry.read().await;
+            registry
+                .list_devices()
+                .into_iter()
+                .filter(|d| self.manages(d))
+                .map(|d| d.device_id)
+                .collect()
+        };
+
+        for device_id in &devices {
+            if let Err(e) = self.spawn_poller(device_id).await {
+                warn!(
+                    "Modbus adapter '{}': skipping device '{}': {}",
+                    self.name, device_id, e
+                );
+            }
+        }
+
+        info!(
+            "Modbus TCP adapter '{}' started ({} devices)",
+            self.name,
+            self.pollers.len()
+        );
+        Ok(())
+    }
+
+    async fn stop(&self) -> AdapterResult<()> {
+        self.running.store(false, Ordering::Relaxed);
+        let device_ids: Vec<String> = self.pollers.iter().map(|e| e.key().clone()).collect();
+        for device_id in device_ids {
+            self.stop_poller(&device_id);
+        }
+
+        info!("Modbus TCP adapter '{}' stopped", self.name);
+        Ok(())
+    }
+
+    fn subscribe(&self) -> Pin<Box<dyn Stream<Item = DeviceEvent> + Send + '_>> {
+        let rx = self.event_tx.subscribe();
+        Box::pin(async_stream::stream! {
+            let mut rx = rx;
+            while let Ok(event) = rx.recv().await {
+                yield event;
+            }
+        })
+    }
+
+    fn set_telemetry_storage(&self, storage: Arc<TimeSeriesStorage>) {
+        let telemetry_storage = self.telemetry_storage.clone();
+        tokio::spawn(async move {
+            *telemetry_storage.write().await = Some(storage);
+        });
+    }
+
+    fn device_count(&self) -> usize {
+        self.pollers.len()
+    }
+
+    fn list_devices(&self) -> Vec<String> {
+        self.pollers.iter().map(|e| e.key().clone()).collect()
+    }
+
+    async fn send_command(
+        &self,
+        device_id: &str,
+        command_name: &str,
+        payload: String,
+        _topic: Option<String>,
+    ) -> AdapterResult<()> {
+        let (_, modbus) = self.device_config(device_id).await?;
+        let request: Value = serde_json::from_str(&payload).map_err(|e| {
+            AdapterError::Configuration(format!("Modbus command payload must be JSON: {}", e))
+        })?;
+
+        // Resolve the target register: by metric name, then by explicit address,
+        // then by the command name itself.
+        let register = if let Some(metric) = request.get("metric").and_then(|v| v.as_str()) {
+            modbus.find_register(metric).cloned().ok_or_else(|| {
+                AdapterError::Configuration(format!("No register mapped for metric '{}'", metric))
+            })?
+        } else if let Some(address) = request.get("address").and_then(|v| v.as_u64()) {
+            let mut register: ModbusRegister = serde_json::from_value(serde_json::json!({
+                "metric": command_name,
+                "address": address,
+            }))
+            .map_err(|e| AdapterError::Configuration(e.to_string()))?;
+            if let Some(rt) = request.get("register_type") {
+                register.register_type = serde_json::from_value(rt.clone())
+                    .map_err(|e| AdapterError::Configuration(e.to_string()))?;
+            }
+            if let Some(dt) = request.get("data_type") {
+                register.data_type = serde_json::from_value(dt.clone())
+                    .map_err(|e| AdapterError::Configuration(e.to_string()))?;
+            }
+            register
+        } else {
+            modbus.find_register(command_name).cloned().ok_or_else(|| {
+                AdapterError::Configuration(format!(
+                    "Command '{}' needs a 'metric' or 'address' in its payload",
+                    command_name
+                ))
+            })?
+        };
+
+        if !register.register_type.is_writable() {
+            return Err(AdapterError::Configuration(format!(
+                "Register '{}' ({:?}) is read-only",
+                register.metric, register.register_type
+            )));
+        }
+
+        let value = request.get("value").cloned().unwrap_or(Value::Null);
+        let words = register.encode(&value)?;
+
+        let timeout = Duration::from_millis(self.config.request_timeout_ms);
+        let mut client =
+            ModbusTcpClient::connect(&modbus.address(), modbus.slave_id, timeout).await?;
+        let result = client
+            .write(register.register_type, register.address, &words)
+            .await;
+
+        let _ = self.event_tx.send(DeviceEvent::CommandResult {
+            device_id: device_id.to_string(),
+            command: command_name.to_string(),
+            success: result.is_ok(),
+            result: result.as_ref().err().map(|e| e.to_string()),
+            timestamp: chrono::Utc::now().timestamp(),
+        });
+        result
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        if !self.is_running() {
+            return ConnectionStatus::Disconnected;
+        }
+        // Polling adapter: report Error only when every device is unreachable
+        if !self.device_states.is_empty()
+            && self
+                .device_states
+                .iter()
+                .all(|s| *s.value() == ConnectionStatus::Error)
+        {
+            ConnectionStatus::Error
+        } else {
+            ConnectionStatus::Connected
+        }
+    }
+
+    async fn subscribe_device(&self, device_id: &str) -> AdapterResult<()> {
+        if !self.is_running() {
+            // Picked up by start()
+            return Ok(());
+        }
+        self.spawn_poller(device_id).await
+    }
+
+    async fn unsubscribe_device(&self, device_id: &str) -> AdapterResult<()> {
+        self.stop_poller(device_id);
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Create a Modbus TCP adapter from configuration.
+pub fn create_modbus_tcp_adapter(
+    config: ModbusTcpAdapterConfig,
+    event_bus: &EventBus,
+    device_registry: Arc<DeviceRegistry>,
+) -> Arc<ModbusTcpAdapter> {
+    Arc::new(ModbusTcpAdapter::new(
+        config,
+        Some(Arc::new(event_bus.clone())),
+        device_registry,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_config_defaults() {
+        let config = ModbusTcpAdapterConfig::new("plc");
+        assert_eq!(config.poll_interval_ms, 5000);
+        assert_eq!(config.request_timeout_ms, 3000);
+
+        let parsed: ModbusTcpAdapterConfig =
+            serde_json::from_value(json!({"name": "plc"})).unwrap();
+        assert_eq!(parsed.poll_interval_ms, 5000);
+    }
+
+    #[test]
+    fn test_decode_register_types() {
+        let mut reg = ModbusRegister::holding("t", 0);
+        assert_eq!(reg.decode(&[250]), Some(MetricValue::Integer(250)));
+
+        reg.data_type = RegisterDataType::I16;
+        reg.scale = 0.1;
+        match reg.decode(&[0xFFF6]) {
+            Some(MetricValue::Float(v)) => assert!((v + 1.0).abs() < 1e-9),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        reg.data_type = RegisterDataType::F32;
+        reg.scale = 1.0;
+        let bits = 21.5f32.to_bits();
+        assert_eq!(
+            reg.decode(&[(bits >> 16) as u16, bits as u16]),
+            Some(MetricValue::Float(21.5))
+        );
+
+        reg.data_type = RegisterDataType::U32;
+        reg.word_order = WordOrder::LittleEndian;
+        assert_eq!(
+            reg.decode(&[0x0002, 0x0001]),
+            Some(MetricValue::Integer(0x0001_0002))
+        );
+
+        let coil = ModbusRegister {
+            register_type: RegisterType::Coil,
+            ..ModbusRegister::holding("pump", 3)
+        };
+        assert_eq!(coil.decode(&[1]), Some(MetricValue::Boolean(true)));
+        assert_eq!(reg.decode(&[1]), None);
+    }
+
+    #[test]
+    fn test_encode_roundtrip() {
+        let reg = ModbusRegister {
+            data_type: RegisterDataType::I32,
+            scale: 0.1,
+            ..ModbusRegister::holding("setpoint", 10)
+        };
+        let words = reg.encode(&json!(-12.3)).unwrap();
+        assert_eq!(words.len(), 2);
+        match reg.decode(&words) {
+            Some(MetricValue::Float(v)) => assert!((v + 12.3).abs() < 1e-9),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let coil = ModbusRegister {
+            register_type: RegisterType::Coil,
+            ..ModbusRegister::holding("pump", 3)
+        };
+        assert_eq!(coil.encode(&json!(true)).unwrap(), vec![1]);
+        assert!(ModbusRegister::holding("x", 0)
+            .encode(&json!("abc"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_device_config_from_connection_config() {
+        let mut cc = ConnectionConfig::modbus_tcp("10.0.0.5", 1502, 7);
+        cc.register_map = Some([("setpoint".to_string(), 40u16)].into_iter().collect());
+        cc.extra.insert("poll_interval_ms".into(), json!(2000));
+        cc.extra.insert(
+            "modbus_registers".into(),
+            json!([{ "metric": "temperature", "address": 0,
+                     "register_type": "input_register", "data_type": "i16", "scale": 0.1 }]),
+        );
+
+        let cfg = ModbusDeviceConfig::from_connection_config(&cc, 5000).unwrap();
+        assert_eq!(cfg.address(), "10.0.0.5:1502");
+        assert_eq!(cfg.slave_id, 7);
+        assert_eq!(cfg.poll_interval_ms, 2000);
+        assert_eq!(cfg.registers.len(), 2);
+        assert_eq!(cfg.registers[0].register_type, RegisterType::InputRegister);
+        assert_eq!(cfg.find_register("setpoint").unwrap().address, 40);
+
+        assert!(
+            ModbusDeviceConfig::from_connection_config(&ConnectionConfig::new(), 5000).is_err()
+        );
+    }
+
+    #[test]
+    fn test_frame_and_response_parsing() {
+        let frame = build_frame(0x0102, 1, &read_pdu(0x03, 0x0010, 2));
+        assert_eq!(
+            frame,
+            vec![0x01, 0x02, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x10, 0x00, 0x02]
+        );
+
+        let words =
+            parse_read_response(0x03, 2, false, &[0x03, 0x04, 0x00, 0x2A, 0x12, 0x34]).unwrap();
+        assert_eq!(words, vec![42, 0x1234]);
+
+        let bits = parse_read_response(0x01, 3, true, &[0x01, 0x01, 0b0000_0101]).unwrap();
+        assert_eq!(bits, vec![1, 0, 1]);
+
+        let err = parse_read_response(0x03, 1, false, &[0x83, 0x02]).unwrap_err();
+        assert!(err.to_string().contains("IllegalDataAddress"));
+    }
+
+    #[test]
+    fn test_write_pdu() {
+        assert_eq!(
+            write_pdu(RegisterType::Coil, 3, &[1]).unwrap(),
+            vec![0x05, 0x00, 0x03, 0xFF, 0x00]
+        );
+        assert_eq!(
+            write_pdu(RegisterType::HoldingRegister, 10, &[0x1234, 0x5678]).unwrap(),
+            vec![0x10, 0x00, 0x0A, 0x00, 0x02, 0x04, 0x12, 0x34, 0x56, 0x78]
+        );
+        assert!(write_pdu(RegisterType::InputRegister, 0, &[1]).is_err());
+
+        let max = vec![0u16; MAX_WRITE_REGISTERS];
+        assert_eq!(
+            write_pdu(RegisterType::HoldingRegister, 0, &max).unwrap()[5],
+            246
+        );
+        let too_many = vec![0u16; MAX_WRITE_REGISTERS + 1];
+        assert!(matches!(
+            write_pdu(RegisterType::HoldingRegister, 0, &too_many),
+            Err(AdapterError::Configuration(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_poll_against_mock_server() {
+        use futures::StreamExt;
+        use tokio::net::TcpListener;
+
+        // Minimal server answering every read-holding-registers request with 215
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 12];
+            while socket.read_exact(&mut buf).await.is_ok() {
+                let resp = [buf[0], buf[1], 0, 0, 0, 5, buf[6], 0x03, 0x02, 0x00, 0xD7];
+                if socket.write_all(&resp).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let registry = Arc::new(DeviceRegistry::new());
+        registry
+            .register_template(crate::registry::DeviceTypeTemplate::new("plc", "PLC"))
+            .await
+            .unwrap();
+        let mut connection_config = ConnectionConfig::modbus_tcp("127.0.0.1", port, 1);
+        connection_config.register_map =
+            Some([("setpoint".to_string(), 0u16)].into_iter().collect());
+        connection_config
+            .extra
+            .insert("poll_interval_ms".into(), json!(100));
+        registry
+            .register_device(DeviceConfig {
+                device_id: "plc-1".into(),
+                name: "PLC".into(),
+                device_type: "plc".into(),
+                adapter_type: MODBUS_TCP_ADAPTER_TYPE.into(),
+                connection_config,
+                adapter_id: None,
+                last_seen: 0,
+                offline_timeout_secs: None,
+                tags: Vec::new(),
+                location: None,
+                tenant_id: Default::default(),
+            })
+            .await
+            .unwrap();
+
+        let adapter = ModbusTcpAdapter::new(ModbusTcpAdapterConfig::new("modbus"), None, registry);
+        let mut stream = adapter.subscribe();
+        adapter.start().await.unwrap();
+        assert_eq!(adapter.list_devices(), vec!["plc-1".to_string()]);
+
+        let metric = tokio::time::timeout(Duration::from_secs(2), async {
+            while let Some(event) = stream.next().await {
+                if let DeviceEvent::Metric { metric, value, .. } = event {
+                    return Some((metric, value));
+                }
+            }
+            None
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            metric,
+            Some(("setpoint".to_string(), MetricValue::Integer(215)))
+        );
+
+        adapter.stop().await.unwrap();
+        assert_eq!(adapter.device_count(), 0);
+    }
+}
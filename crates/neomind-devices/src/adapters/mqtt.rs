@@ -18,15 +18,22 @@
 //! ├─ humidity capability     ──→ sensor/${id}/humidity
 //! └─ set_interval command    ──→ sensor/${id}/command
 //! ```
+//!
+//! ## Auto-mapping
+//!
+//! Zigbee2MQTT device lists and Home Assistant discovery configs are turned
+//! into device types and devices as they are published (see
+//! [`crate::protocol::auto_mapping`]); `auto_mapping` selects the topics.
 
 use crate::adapter::{AdapterError, AdapterResult, ConnectionStatus, DeviceAdapter, DeviceEvent};
 use crate::image_storage::save_image_binary;
 use crate::mdl::MetricValue;
 use crate::mqtt::MqttConfig;
-use crate::protocol::ProtocolMapping;
+use crate::protocol::auto_mapping::merge_template;
+use crate::protocol::{AutoMappingConfig, MappingUpdate, ProtocolMapping};
 use crate::registry::DeviceRegistry;
 use crate::telemetry::TimeSeriesStorage;
-use crate::unified_extractor::UnifiedExtractor;
+use crate::unified_extractor::{ExtractedMetric, RawPayload, UnifiedExtractor};
 
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
@@ -65,6 +72,9 @@ pub struct MqttAdapterConfig {
     pub auto_discovery: bool,
     /// Storage directory for persistence
     pub storage_dir: Option<String>,
+    /// Zigbee2MQTT / Home Assistant discovery auto-mapping
+    #[serde(default)]
+    pub auto_mapping: AutoMappingConfig,
 }
 
 impl MqttAdapterConfig {
@@ -79,6 +89,7 @@ impl MqttAdapterConfig {
             discovery_prefix: "neomind".to_string(),
             auto_discovery: true,
             storage_dir: None,
+            auto_mapping: AutoMappingConfig::default(),
         }
     }
 
@@ -117,6 +128,12 @@ impl MqttAdapterConfig {
         self.mqtt = self.mqtt.with_port(port);
         self
     }
+
+    /// Set which device-description topics are mapped automatically.
+    pub fn with_auto_mapping(mut self, auto_mapping: AutoMappingConfig) -> Self {
+        self.auto_mapping = auto_mapping;
+        self
+    }
 }
 
 /// Single MQTT broker connection
@@ -614,6 +631,7 @@ impl MqttAdapter {
                             &telemetry_storage,
                             &device_registry,
                             &connection_status,
+                            &mqtt_clients,
                             &broker_id_clone,
                             &extractor,
                             &topic_to_device,
@@ -653,7 +671,9 @@ impl MqttAdapter {
         // and devices publishing to custom topics (e.g. "ne101/abc") are never seen.
         // add_broker_with_tls (external brokers) takes subscribe_topics as an explicit
         // parameter and is unaffected.
-        let initial_topics = normalized_initial_subscription_topics(&self.config.subscribe_topics);
+        let mut configured_topics = self.config.subscribe_topics.clone();
+        configured_topics.extend(self.config.auto_mapping.subscription_topics());
+        let initial_topics = normalized_initial_subscription_topics(&configured_topics);
 
         // Bug 5: track subscription success so a total failure surfaces as an error
         // instead of silently marking the broker as "connected".
@@ -865,6 +885,7 @@ impl MqttAdapter {
         let device_registry = self.device_registry.clone();
         let connection_status = self.connection_status.clone();
         let mqtt_clients = self.mqtt_clients.clone();
+        let handler_mqtt_clients = self.mqtt_clients.clone();
         let broker_id_clone = broker_id.clone();
         let broker_id_clone2 = broker_id.clone();
         let extractor = self.extractor.clone();
@@ -889,6 +910,7 @@ impl MqttAdapter {
                             &telemetry_storage,
                             &device_registry,
                             &connection_status,
+                            &handler_mqtt_clients,
                             &broker_id_clone,
                             &extractor,
                             &topic_to_device,
@@ -953,7 +975,9 @@ impl MqttAdapter {
         // self.config.subscribe_topics loop was removed (the API handler already sets
         // config.subscribe_topics from the same broker data, so adding it twice
         // caused rumqttc to receive duplicate SUBSCRIBE requests).
-        let initial_topics = normalized_initial_subscription_topics(&subscribe_topics);
+        let mut configured_topics = subscribe_topics;
+        configured_topics.extend(self.config.auto_mapping.subscription_topics());
+        let initial_topics = normalized_initial_subscription_topics(&configured_topics);
 
         // Bug 5: track subscription success so a total failure surfaces as an error
         // instead of silently marking the broker as "connected".
@@ -1183,9 +1207,9 @@ impl MqttAdapter {
     }
 
     /// Default value parsing (when no protocol mapping is available).
-    fn default_parse_value(payload: &[u8]) -> Result<MetricValue, String> {
+    fn default_parse_value(payload: &RawPayload) -> Result<MetricValue, String> {
         // Try JSON first
-        if let Ok(json) = serde_json::from_slice::<Value>(payload) {
+        if let Some(json) = payload.json() {
             if let Some(num) = json.as_f64() {
                 return Ok(MetricValue::Float(num));
             } else if let Some(s) = json.as_str() {
@@ -1690,6 +1714,252 @@ impl MqttAdapter {
         }
     }
 
+    /// Cache, store and emit the metrics extracted from one message.
+    ///
+    /// The batch is sent as a single [`DeviceEvent::Metrics`], so the
+    /// broadcast channel shares it with every subscriber instead of cloning
+    /// each metric per receiver.
+    async fn publish_extracted(
+        device_id: &str,
+        metrics: Vec<ExtractedMetric>,
+        now: chrono::DateTime<chrono::Utc>,
+        event_tx: &broadcast::Sender<DeviceEvent>,
+        metric_cache: &Arc<
+            RwLock<HashMap<String, HashMap<String, (MetricValue, chrono::DateTime<chrono::Utc>)>>>,
+        >,
+        telemetry_storage: &Arc<RwLock<Option<Arc<TimeSeriesStorage>>>>,
+        data_dir: Option<&PathBuf>,
+    ) {
+        if metrics.is_empty() {
+            return;
+        }
+        let timestamp = now.timestamp();
+
+        // Convert Binary to URL before storage + event bus (fork point)
+        let metrics: Arc<[ExtractedMetric]> = metrics
+            .into_iter()
+            .map(|metric| {
+                let value = Self::convert_binary_to_url(
+                    device_id,
+                    &metric.name,
+                    timestamp,
+                    metric.value,
+                    data_dir,
+                );
+                ExtractedMetric {
+                    name: metric.name,
+                    value,
+                    source_path: metric.source_path,
+                }
+            })
+            .collect();
+
+        // Update metric cache
+        {
+            let mut cache = metric_cache.write().await;
+            let device_cache = cache.entry(device_id.to_string()).or_default();
+            for metric in metrics.iter() {
+                device_cache.insert(metric.name.clone(), (metric.value.clone(), now));
+            }
+        }
+
+        // Store in telemetry storage
+        if let Some(storage) = telemetry_storage.read().await.as_ref() {
+            let source_id = format!("device:{}", device_id);
+            for metric in metrics.iter() {
+                let data_point = crate::telemetry::DataPoint {
+                    timestamp,
+                    value: metric.value.clone(),
+                    quality: None,
+                };
+                if let Err(e) = storage.write(&source_id, &metric.name, data_point).await {
+                    error!(
+                        "Failed to write telemetry for {}/{}: {}",
+                        device_id, metric.name, e
+                    );
+                }
+            }
+        }
+
+        // Emit to device event channel - event forwarding task will publish to EventBus
+        let count = metrics.len();
+        if let Err(e) = event_tx.send(DeviceEvent::Metrics {
+            device_id: device_id.to_string(),
+            metrics,
+            timestamp,
+        }) {
+            error!(
+                "Failed to send {} metric events to channel for {}: {}",
+                count, device_id, e
+            );
+        }
+    }
+
+    /// Register or update the devices described by a Zigbee2MQTT / Home
+    /// Assistant discovery message and route their telemetry topics.
+    async fn apply_mapping_update(
+        update: MappingUpdate,
+        event_bus: &Option<Arc<EventBus>>,
+        device_types: &Arc<RwLock<HashMap<String, String>>>,
+        device_registry: &Arc<RwLock<Arc<DeviceRegistry>>>,
+        topic_to_device: &Arc<RwLock<HashMap<String, String>>>,
+        mqtt_clients: &Arc<RwLock<HashMap<String, MqttClientInner>>>,
+        broker_id: &str,
+    ) {
+        let registry = device_registry.read().await.clone();
+        let devices = match update {
+            MappingUpdate::Upsert(devices) => devices,
+            MappingUpdate::Left { device_id } => {
+                if registry.get_device(&device_id).is_some() {
+                    info!("Mapped device '{}' left the network", device_id);
+                    if let Some(bus) = event_bus {
+                        bus.publish(NeoMindEvent::DeviceOffline {
+                            device_id,
+                            reason: Some("left the network".to_string()),
+                            timestamp: chrono::Utc::now().timestamp(),
+                        })
+                        .await;
+                    }
+                }
+                return;
+            }
+        };
+
+        for mapped in devices {
+            let existing = registry.get_device(&mapped.device.device_id);
+            if existing.as_ref().is_some_and(|d| !mapped.applies_to(d)) {
+                debug!(
+                    "Skipping mapped entity for '{}': topics differ from the registered device",
+                    mapped.device.device_id
+                );
+                continue;
+            }
+
+            // The device type is shared by every device of the same model;
+            // only ever add to it.
+            let template = mapped.template();
+            let template = match registry.get_template(&template.device_type) {
+                Some(current) => merge_template(&current, &template),
+                None => Some(template),
+            };
+            if let Some(template) = template {
+                let device_type = template.device_type.clone();
+                if let Err(e) = registry.register_template(template).await {
+                    warn!("Failed to register mapped device type '{}': {}", device_type, e);
+                    continue;
+                }
+                Self::publish_registration(
+                    event_bus,
+                    "DeviceTypeRegistered",
+                    serde_json::json!({ "device_type": device_type }),
+                )
+                .await;
+            }
+
+            let device = match existing {
+                None => {
+                    let device = mapped.device.clone();
+                    if let Err(e) = registry.register_device(device.clone()).await {
+                        warn!("Failed to register mapped device '{}': {}", device.device_id, e);
+                        continue;
+                    }
+                    info!(
+                        "Auto-mapped device '{}' (type '{}') from broker {}",
+                        device.device_id, device.device_type, broker_id
+                    );
+                    Self::publish_registration(
+                        event_bus,
+                        "DeviceRegistered",
+                        serde_json::json!({
+                            "device_id": device.device_id,
+                            "device_type": device.device_type,
+                        }),
+                    )
+                    .await;
+                    device
+                }
+                Some(existing) => match mapped.merge_device(&existing) {
+                    Some(merged) => {
+                        if let Err(e) = registry
+                            .update_device(&merged.device_id, merged.clone())
+                            .await
+                        {
+                            warn!("Failed to update mapped device '{}': {}", merged.device_id, e);
+                            continue;
+                        }
+                        if let Some(old_topic) = &existing.connection_config.telemetry_topic {
+                            topic_to_device.write().await.remove(old_topic);
+                        }
+                        merged
+                    }
+                    None => existing,
+                },
+            };
+
+            device_types
+                .write()
+                .await
+                .insert(device.device_id.clone(), device.device_type.clone());
+            if let Some(topic) = &device.connection_config.telemetry_topic {
+                topic_to_device
+                    .write()
+                    .await
+                    .insert(topic.clone(), device.device_id.clone());
+                Self::ensure_broker_subscription(mqtt_clients, broker_id, topic).await;
+            }
+        }
+    }
+
+    async fn publish_registration(
+        event_bus: &Option<Arc<EventBus>>,
+        event_type: &str,
+        mut data: Value,
+    ) {
+        let Some(bus) = event_bus else {
+            return;
+        };
+        data["timestamp"] = serde_json::json!(chrono::Utc::now().timestamp());
+        bus.publish(NeoMindEvent::Custom {
+            event_type: event_type.to_string(),
+            data,
+        })
+        .await;
+    }
+
+    /// Subscribe `topic` on one broker unless an existing subscription covers
+    /// it. Runs on the broker's event loop, so it must not wait on the
+    /// request channel.
+    async fn ensure_broker_subscription(
+        mqtt_clients: &Arc<RwLock<HashMap<String, MqttClientInner>>>,
+        broker_id: &str,
+        topic: &str,
+    ) {
+        let clients = mqtt_clients.read().await;
+        let Some(inner) = clients.get(broker_id) else {
+            return;
+        };
+        let mut subscribed = inner.subscribed_topics.write().await;
+        if subscribed
+            .iter()
+            .any(|existing| topic_filter_covers(existing, topic))
+        {
+            return;
+        }
+        match inner
+            .client
+            .try_subscribe(topic, rumqttc::QoS::AtLeastOnce)
+        {
+            Ok(()) => {
+                subscribed.insert(topic.to_string());
+                info!("Subscribed to topic {} on broker {}", topic, broker_id);
+            }
+            Err(e) => warn!(
+                "Failed to subscribe to {} on broker {}: {}",
+                topic, broker_id, e
+            ),
+        }
+    }
+
     /// Handle MQTT notification from a specific broker.
     /// This is a static method that processes incoming messages.
     async fn handle_mqtt_notification(
@@ -1702,8 +1972,9 @@ impl MqttAdapter {
             RwLock<HashMap<String, HashMap<String, (MetricValue, chrono::DateTime<chrono::Utc>)>>>,
         >,
         telemetry_storage: &Arc<RwLock<Option<Arc<TimeSeriesStorage>>>>,
-        _device_registry: &Arc<RwLock<Arc<DeviceRegistry>>>,
+        device_registry: &Arc<RwLock<Arc<DeviceRegistry>>>,
         _connection_status: &Arc<RwLock<ConnectionStatus>>,
+        mqtt_clients: &Arc<RwLock<HashMap<String, MqttClientInner>>>,
         broker_id: &str,
         extractor: &Arc<UnifiedExtractor>,
         topic_to_device: &Arc<RwLock<HashMap<String, String>>>,
@@ -1713,7 +1984,8 @@ impl MqttAdapter {
         match notification {
             rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish)) => {
                 let topic = publish.topic.to_string();
-                let payload = publish.payload.to_vec();
+                // Shares the publish buffer; parsed as JSON at most once
+                let payload = RawPayload::new(publish.payload);
 
                 debug!(
                     "Received MQTT message on topic: {}, payload length: {}",
@@ -1773,6 +2045,25 @@ impl MqttAdapter {
                     return;
                 }
 
+                // Zigbee2MQTT bridge and HA discovery topics describe devices
+                // instead of carrying telemetry: register what they describe
+                // and keep them out of auto-onboarding.
+                if config.auto_mapping.is_description_topic(&topic) {
+                    if let Some(update) = config.auto_mapping.parse(&topic, &payload) {
+                        Self::apply_mapping_update(
+                            update,
+                            event_bus,
+                            device_types,
+                            device_registry,
+                            topic_to_device,
+                            mqtt_clients,
+                            broker_id,
+                        )
+                        .await;
+                    }
+                    return;
+                }
+
                 // Check if this is a standard uplink format first
                 let parts: Vec<&str> = topic.split('/').collect();
                 let mut is_standard_uplink =
@@ -1807,18 +2098,16 @@ impl MqttAdapter {
                         // Check if this is an uplink message with device_type
                         // Topic format: device/{device_type}/{device_id}/uplink
                         if let Some(dt) = &device_type {
-                            // Try to parse as JSON
-                            if let Ok(json_value) =
-                                serde_json::from_slice::<serde_json::Value>(&payload)
+                            // Use UnifiedExtractor to extract metrics (JSON, or a
+                            // binary frame when the device type has a codec)
+                            if let Some(result) =
+                                extractor.extract_raw(&device_id, dt, &payload).await
                             {
                                 info!(
                                     "Processing uplink message for device {} (type: {})",
                                     device_id, dt
                                 );
 
-                                // Use UnifiedExtractor to extract metrics
-                                let result = extractor.extract(&device_id, dt, &json_value).await;
-
                                 debug!(
                                     "Extraction result for device '{}': mode={:?}, metrics={}",
                                     device_id,
@@ -1832,69 +2121,19 @@ impl MqttAdapter {
                                     types.insert(device_id.clone(), dt.to_string());
                                 }
 
-                                // Emit all extracted metrics
-                                for metric in result.metrics {
-                                    // Convert Binary to URL before storage + event bus (fork point)
-                                    let value = Self::convert_binary_to_url(
-                                        &device_id,
-                                        &metric.name,
-                                        now.timestamp(),
-                                        metric.value.clone(),
-                                        data_dir,
-                                    );
-
-                                    // Update metric cache
-                                    {
-                                        let mut cache = metric_cache.write().await;
-                                        cache
-                                            .entry(device_id.clone())
-                                            .or_default()
-                                            .insert(metric.name.clone(), (value.clone(), now));
-                                    }
-
-                                    // Store in telemetry storage
-                                    if let Some(storage) = telemetry_storage.read().await.as_ref() {
-                                        let data_point = crate::telemetry::DataPoint {
-                                            timestamp: now.timestamp(),
-                                            value: value.clone(),
-                                            quality: None,
-                                        };
-                                        if let Err(e) = storage
-                                            .write(
-                                                &format!("device:{}", device_id),
-                                                &metric.name,
-                                                data_point,
-                                            )
-                                            .await
-                                        {
-                                            error!(
-                                                "Failed to write telemetry for {}/{}: {}",
-                                                device_id, metric.name, e
-                                            );
-                                        } else {
-                                            debug!(
-                                                "Stored metric {} = {:?} for device {}",
-                                                metric.name, value, device_id
-                                            );
-                                        }
-                                    }
-
-                                    // Emit to device event channel - event forwarding task will publish to EventBus
-                                    if let Err(e) = event_tx.send(DeviceEvent::Metric {
-                                        device_id: device_id.clone(),
-                                        metric: metric.name.clone(),
-                                        value: value.clone(),
-                                        timestamp: now.timestamp(),
-                                    }) {
-                                        error!(
-                                            "Failed to send metric event to channel: {}/{} - {}",
-                                            device_id, metric.name, e
-                                        );
-                                    }
-
-                                    // Note: Do NOT publish to EventBus here - the event forwarding task
-                                    // in create_mqtt_adapter handles all EventBus publishing to avoid duplicates
-                                }
+                                // Store and emit all extracted metrics. The
+                                // event forwarding task in create_mqtt_adapter
+                                // handles all EventBus publishing to avoid duplicates
+                                Self::publish_extracted(
+                                    &device_id,
+                                    result.metrics,
+                                    now,
+                                    event_tx,
+                                    metric_cache,
+                                    telemetry_storage,
+                                    data_dir,
+                                )
+                                .await;
 
                                 // Publish DeviceOnline event for new devices
                                 if let Some(bus) = event_bus {
@@ -1912,10 +2151,7 @@ impl MqttAdapter {
 
                                 return;
                             } else {
-                                warn!(
-                                    "Failed to parse uplink payload as JSON for device {}",
-                                    device_id
-                                );
+                                warn!("Failed to parse uplink payload for device {}", device_id);
                             }
                         }
                     }
@@ -2056,16 +2292,14 @@ impl MqttAdapter {
 
                         debug!("Device type for {}: {:?}", device_id, device_type_opt);
 
-                        // Parse payload and process for the registered device
-                        if let Ok(json_data) = serde_json::from_slice::<serde_json::Value>(&payload)
-                        {
-                            debug!("Successfully parsed JSON payload for device {}", device_id);
-
-                            // Use UnifiedExtractor with the full JSON data
-                            // The extractor handles dot-notation paths including "data.field" prefixes
-                            // DO NOT pre-extract the "data" field - it causes double-extraction issues
-                            if let Some(dt) = device_type_opt {
-                                let result = extractor.extract(device_id, &dt, &json_data).await;
+                        // Parse payload and process for the registered device.
+                        // The extractor handles dot-notation paths including "data.field"
+                        // prefixes, and binary frames for device types with a binary codec.
+                        // DO NOT pre-extract the "data" field - it causes double-extraction issues
+                        if let Some(dt) = device_type_opt {
+                            if let Some(result) =
+                                extractor.extract_raw(device_id, &dt, &payload).await
+                            {
                                 debug!(
                                     "Extraction result for device {}: mode={:?}, metrics={}",
                                     device_id,
@@ -2080,114 +2314,69 @@ impl MqttAdapter {
                                     );
                                 }
 
-                                for metric in result.metrics {
-                                    // Convert Binary to URL before storage + event bus (fork point)
-                                    let value = Self::convert_binary_to_url(
-                                        device_id,
-                                        &metric.name,
-                                        now.timestamp(),
-                                        metric.value.clone(),
-                                        data_dir,
-                                    );
+                                Self::publish_extracted(
+                                    device_id,
+                                    result.metrics,
+                                    now,
+                                    event_tx,
+                                    metric_cache,
+                                    telemetry_storage,
+                                    data_dir,
+                                )
+                                .await;
+                            }
+                        } else if payload.json().is_some() {
+                            // No device type - try simple value extraction
+                            if let Ok(value) = MqttAdapter::default_parse_value(&payload) {
+                                let metric_name = "value";
 
-                                    // Update metric cache
-                                    {
-                                        let mut cache = metric_cache.write().await;
-                                        cache
-                                            .entry(device_id.clone())
-                                            .or_default()
-                                            .insert(metric.name.clone(), (value.clone(), now));
-                                    }
-
-                                    // Store in telemetry storage
-                                    if let Some(storage) = telemetry_storage.read().await.as_ref() {
-                                        let data_point = crate::telemetry::DataPoint {
-                                            timestamp: now.timestamp(),
-                                            value: value.clone(),
-                                            quality: None,
-                                        };
-                                        if let Err(e) = storage
-                                            .write(
-                                                &format!("device:{}", device_id),
-                                                &metric.name,
-                                                data_point,
-                                            )
-                                            .await
-                                        {
-                                            error!(
-                                                "Failed to write telemetry for {}/{}: {}",
-                                                device_id, metric.name, e
-                                            );
-                                        }
-                                    }
-
-                                    // Emit to device event channel - event forwarding task will publish to EventBus
-                                    // This ensures single publish path to avoid duplicate events
-                                    if let Err(e) = event_tx.send(DeviceEvent::Metric {
-                                        device_id: device_id.clone(),
-                                        metric: metric.name.clone(),
-                                        value: value.clone(),
-                                        timestamp: now.timestamp(),
-                                    }) {
-                                        error!(
-                                            "Failed to send metric event to channel: {}/{} - {}",
-                                            device_id, metric.name, e
-                                        );
-                                    }
+                                // Convert Binary to URL before storage + event bus (fork point)
+                                let value = Self::convert_binary_to_url(
+                                    device_id,
+                                    metric_name,
+                                    now.timestamp(),
+                                    value,
+                                    data_dir,
+                                );
+
+                                // Update metric cache
+                                {
+                                    let mut cache = metric_cache.write().await;
+                                    cache
+                                        .entry(device_id.clone())
+                                        .or_default()
+                                        .insert(metric_name.to_string(), (value.clone(), now));
                                 }
-                            } else {
-                                // No device type - try simple value extraction
-                                if let Ok(value) = MqttAdapter::default_parse_value(&payload) {
-                                    let metric_name = "value";
-
-                                    // Convert Binary to URL before storage + event bus (fork point)
-                                    let value = Self::convert_binary_to_url(
-                                        device_id,
-                                        metric_name,
-                                        now.timestamp(),
-                                        value,
-                                        data_dir,
-                                    );
 
-                                    // Update metric cache
-                                    {
-                                        let mut cache = metric_cache.write().await;
-                                        cache
-                                            .entry(device_id.clone())
-                                            .or_default()
-                                            .insert(metric_name.to_string(), (value.clone(), now));
-                                    }
-
-                                    // Store in telemetry storage
-                                    if let Some(storage) = telemetry_storage.read().await.as_ref() {
-                                        let data_point = crate::telemetry::DataPoint {
-                                            timestamp: now.timestamp(),
-                                            value: value.clone(),
-                                            quality: None,
-                                        };
-                                        let _ = storage
-                                            .write(
-                                                &format!("device:{}", device_id),
-                                                metric_name,
-                                                data_point,
-                                            )
-                                            .await;
-                                    }
-
-                                    // Emit to device event channel - event forwarding task will publish to EventBus
-                                    if let Err(e) = event_tx.send(DeviceEvent::Metric {
-                                        device_id: device_id.clone(),
-                                        metric: metric_name.to_string(),
-                                        value: value.clone(),
+                                // Store in telemetry storage
+                                if let Some(storage) = telemetry_storage.read().await.as_ref() {
+                                    let data_point = crate::telemetry::DataPoint {
                                         timestamp: now.timestamp(),
-                                    }) {
-                                        error!(
-                                            "Failed to send metric event to channel: {}/{} - {}",
-                                            device_id, metric_name, e
-                                        );
-                                    }
-                                    // Note: Do NOT publish DeviceMetric to EventBus here - the event forwarding task handles it
+                                        value: value.clone(),
+                                        quality: None,
+                                    };
+                                    let _ = storage
+                                        .write(
+                                            &format!("device:{}", device_id),
+                                            metric_name,
+                                            data_point,
+                                        )
+                                        .await;
                                 }
+
+                                // Emit to device event channel - event forwarding task will publish to EventBus
+                                if let Err(e) = event_tx.send(DeviceEvent::Metric {
+                                    device_id: device_id.clone(),
+                                    metric: metric_name.to_string(),
+                                    value: value.clone(),
+                                    timestamp: now.timestamp(),
+                                }) {
+                                    error!(
+                                        "Failed to send metric event to channel: {}/{} - {}",
+                                        device_id, metric_name, e
+                                    );
+                                }
+                                // Note: Do NOT publish DeviceMetric to EventBus here - the event forwarding task handles it
                             }
                         }
                         // Skip auto-onboarding for registered devices - message already handled
@@ -2258,11 +2447,11 @@ impl MqttAdapter {
 
                         // Determine data format and prepare sample
                         // Extract the actual device data from payload.data if it exists
-                        let (sample_data, is_binary, data_format) = if let Ok(json_data) =
-                            serde_json::from_slice::<serde_json::Value>(&payload)
+                        let (sample_data, is_binary, data_format) = if let Some(json_data) =
+                            payload.json()
                         {
                             // Check if payload has a 'data' field containing the actual device data
-                            let actual_data = json_data.get("data").unwrap_or(&json_data);
+                            let actual_data = json_data.get("data").unwrap_or(json_data);
                             (actual_data.clone(), false, "json")
                         } else {
                             // Not JSON - store as base64 encoded binary data
@@ -2613,8 +2802,11 @@ pub fn create_mqtt_adapter(
         while let Some(event) = rx.next().await {
             if let Some(device_id) = event.device_id() {
                 let source = format!("adapter:mqtt:{}", device_id);
-                let neomind_event = event.clone().to_neomind_event();
-                event_bus.publish_with_source(neomind_event, source).await;
+                for neomind_event in event.into_neomind_events() {
+                    event_bus
+                        .publish_with_source(neomind_event, source.clone())
+                        .await;
+                }
             }
         }
     });
@@ -2645,8 +2837,11 @@ pub fn create_mqtt_adapter_with_mapping(
         while let Some(event) = rx.next().await {
             if let Some(device_id) = event.device_id() {
                 let source = format!("adapter:mqtt:{}", device_id);
-                let neomind_event = event.clone().to_neomind_event();
-                event_bus.publish_with_source(neomind_event, source).await;
+                for neomind_event in event.into_neomind_events() {
+                    event_bus
+                        .publish_with_source(neomind_event, source.clone())
+                        .await;
+                }
             }
         }
     });
@@ -2765,20 +2960,70 @@ mod tests {
     #[test]
     fn test_default_parse_value() {
         assert!(matches!(
-            MqttAdapter::default_parse_value(b"25.5"),
+            MqttAdapter::default_parse_value(&RawPayload::new("25.5")),
             Ok(MetricValue::Float(25.5))
         ));
         assert!(matches!(
-            MqttAdapter::default_parse_value(b"true"),
+            MqttAdapter::default_parse_value(&RawPayload::new("true")),
             Ok(MetricValue::Boolean(true))
         ));
         // Test string value parsing
-        match MqttAdapter::default_parse_value(b"\"hello\"") {
+        match MqttAdapter::default_parse_value(&RawPayload::new("\"hello\"")) {
             Ok(MetricValue::String(s)) => assert_eq!(s, "hello"),
             _ => panic!("Expected String value"),
         }
     }
 
+    #[tokio::test]
+    async fn test_apply_mapping_update_registers_and_renames() {
+        let registry = Arc::new(DeviceRegistry::new());
+        let device_registry = Arc::new(RwLock::new(registry.clone()));
+        let device_types = Arc::new(RwLock::new(HashMap::new()));
+        let topic_to_device = Arc::new(RwLock::new(HashMap::new()));
+        let mqtt_clients = Arc::new(RwLock::new(HashMap::new()));
+        let config = AutoMappingConfig::default();
+
+        for friendly_name in ["plug", "kitchen/plug"] {
+            let payload = serde_json::json!([{
+                "ieee_address": "0x00158d0001a2b3c4",
+                "friendly_name": friendly_name,
+                "definition": {
+                    "model": "ZNCZ04LM",
+                    "vendor": "Aqara",
+                    "exposes": [{ "type": "numeric", "property": "power", "access": 1 }]
+                }
+            }]);
+            let update = config
+                .parse("zigbee2mqtt/bridge/devices", payload.to_string().as_bytes())
+                .unwrap();
+            MqttAdapter::apply_mapping_update(
+                update,
+                &None,
+                &device_types,
+                &device_registry,
+                &topic_to_device,
+                &mqtt_clients,
+                "internal",
+            )
+            .await;
+        }
+
+        let device = registry.get_device("0x00158d0001a2b3c4").unwrap();
+        assert_eq!(device.name, "kitchen/plug");
+        assert!(registry.get_template("z2m_aqara_zncz04lm").is_some());
+        let topics = topic_to_device.read().await;
+        assert_eq!(
+            topics.get("zigbee2mqtt/kitchen/plug").map(String::as_str),
+            Some("0x00158d0001a2b3c4")
+        );
+        assert!(!topics.contains_key("zigbee2mqtt/plug"));
+        let types = device_types.read().await;
+        assert_eq!(
+            types.get("0x00158d0001a2b3c4").map(String::as_str),
+            Some("z2m_aqara_zncz04lm")
+        );
+    }
+
     #[test]
     fn test_normalized_initial_topics_removes_topics_covered_by_hash() {
         let topics = normalized_initial_subscription_topics(&["#".to_string()]);
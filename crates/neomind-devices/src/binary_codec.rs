@@ -0,0 +1,640 @@
+//! Declarative codec for binary device payloads.
+//!
+//! Proprietary sensors often send fixed-layout byte frames instead of JSON.
+//! A [`BinaryCodec`] attached to a device type template describes those
+//! frames field by field, so such devices can be onboarded without writing
+//! a parser:
+//!
+//! ```json
+//! {
+//!   "encoding": "hex",
+//!   "fields": [
+//!     { "name": "temperature", "offset": 0, "length": 2, "kind": "int", "scale": 0.01 },
+//!     { "name": "humidity", "offset": 2, "length": 1 },
+//!     { "name": "mode", "offset": 3, "length": 1, "enum_map": { "0": "idle", "1": "heating" } }
+//!   ],
+//!   "commands": {
+//!     "set_mode": [
+//!       { "name": "opcode", "offset": 0, "length": 1, "value": 16 },
+//!       { "name": "mode", "offset": 1, "length": 1, "enum_map": { "0": "idle", "1": "heating" } }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! The same field descriptions drive both directions: [`BinaryCodec::decode`]
+//! turns an uplink frame into metrics, [`BinaryCodec::encode_command`] turns
+//! command parameters into a downlink frame.
+//!
+//! Decoded value = raw × `scale` + `bias`; encoding applies the inverse and
+//! rounds to the nearest integer.
+
+use std::collections::{BTreeMap, HashMap};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+
+use crate::mdl::MetricValue;
+
+/// Largest frame a codec may describe, in bytes. Every field must end
+/// within it.
+pub const MAX_FRAME_LEN: usize = 4096;
+
+/// Error returned when encoding or decoding a binary payload.
+#[derive(Debug, thiserror::Error)]
+pub enum CodecError {
+    /// A field description is unusable (bad length for its kind, outside
+    /// the frame, or a scale that can't be inverted).
+    #[error("field {name}: {reason}")]
+    InvalidField { name: String, reason: String },
+
+    /// The codec defines no frame for the requested command.
+    #[error("no binary frame defined for command {0}")]
+    UnknownCommand(String),
+
+    /// A command field has neither a constant value nor a parameter.
+    #[error("parameter {0} was not given a value")]
+    MissingParameter(String),
+
+    /// A parameter cannot be represented by its field.
+    #[error("parameter {name}: {reason}")]
+    InvalidValue { name: String, reason: String },
+
+    /// Text-encoded payload is not valid hex / base64.
+    #[error("invalid {encoding} payload: {reason}")]
+    InvalidEncoding {
+        encoding: &'static str,
+        reason: String,
+    },
+}
+
+/// How bytes are carried when the payload travels as text — inside a JSON
+/// uplink (`payload_path`) or as a downlink command payload.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// Byte order of a multi-byte field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Endianness {
+    #[default]
+    Big,
+    Little,
+}
+
+/// How the bytes of a field are interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    /// Unsigned integer, 1-8 bytes
+    #[default]
+    Uint,
+    /// Two's-complement signed integer, 1-8 bytes
+    Int,
+    /// IEEE 754 float, 4 or 8 bytes
+    Float,
+    /// Any non-zero byte is `true`
+    Bool,
+    /// Opaque bytes, surfaced as a hex string
+    Bytes,
+}
+
+/// One field of a binary frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinaryField {
+    /// Metric name (uplink) or parameter name (downlink)
+    pub name: String,
+    /// Byte offset of the field within the frame
+    pub offset: usize,
+    /// Field length in bytes
+    pub length: usize,
+    #[serde(default)]
+    pub kind: FieldKind,
+    #[serde(default)]
+    pub endianness: Endianness,
+    /// Multiplier applied to the raw numeric value
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    /// Added to the raw numeric value after scaling
+    #[serde(default)]
+    pub bias: f64,
+    /// Raw integer → label; decoded values with a label become strings
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub enum_map: BTreeMap<i64, String>,
+    /// Constant written on encode (e.g. an opcode byte); the field is not
+    /// read from command parameters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+/// Field-level description of a device's binary uplink and downlink frames.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BinaryCodec {
+    /// Text encoding of the bytes when they travel as a string
+    #[serde(default)]
+    pub encoding: PayloadEncoding,
+    /// Dot path of the encoded frame inside a JSON uplink (e.g. `"data"` for
+    /// network servers that wrap device bytes). `None` = the uplink payload
+    /// itself is the frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_path: Option<String>,
+    /// Uplink frame fields
+    #[serde(default)]
+    pub fields: Vec<BinaryField>,
+    /// Downlink frame fields by command name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub commands: HashMap<String, Vec<BinaryField>>,
+}
+
+impl BinaryCodec {
+    /// Check every field description for lengths its kind can hold, bounds
+    /// within [`MAX_FRAME_LEN`] and usable scale and bias.
+    pub fn validate(&self) -> Result<(), CodecError> {
+        self.fields
+            .iter()
+            .chain(self.commands.values().flatten())
+            .try_for_each(BinaryField::validate)
+    }
+
+    /// Whether the codec declares an uplink field with this name.
+    pub fn has_field(&self, name: &str) -> bool {
+        self.fields.iter().any(|f| f.name == name)
+    }
+
+    /// Decode an uplink frame into metrics.
+    ///
+    /// Fields that extend past the end of the frame are skipped, so devices
+    /// that send shorter frames for some message types still decode.
+    pub fn decode(&self, frame: &[u8]) -> Vec<(String, MetricValue)> {
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                let end = field.offset.checked_add(field.length)?;
+                let bytes = frame.get(field.offset..end)?;
+                Some((field.name.clone(), field.decode(bytes)))
+            })
+            .collect()
+    }
+
+    /// Encode a downlink frame for `command` from its parameters.
+    pub fn encode_command(
+        &self,
+        command: &str,
+        params: &HashMap<String, MetricValue>,
+    ) -> Result<Vec<u8>, CodecError> {
+        let fields = self
+            .commands
+            .get(command)
+            .ok_or_else(|| CodecError::UnknownCommand(command.to_string()))?;
+
+        let mut len = 0;
+        for field in fields {
+            len = len.max(field.end()?);
+        }
+        let mut frame = vec![0u8; len];
+        for field in fields {
+            let value = match &field.value {
+                Some(constant) => json_to_metric(constant),
+                None => params
+                    .get(&field.name)
+                    .cloned()
+                    .ok_or_else(|| CodecError::MissingParameter(field.name.clone()))?,
+            };
+            let bytes = field.encode(&value)?;
+            frame[field.offset..field.offset + field.length].copy_from_slice(&bytes);
+        }
+        Ok(frame)
+    }
+
+    /// Decode a text-encoded frame.
+    pub fn decode_text(&self, text: &str) -> Result<Vec<u8>, CodecError> {
+        let text = text.trim();
+        match self.encoding {
+            PayloadEncoding::Hex => decode_hex(text),
+            PayloadEncoding::Base64 => STANDARD.decode(text).map_err(|e| {
+                CodecError::InvalidEncoding {
+                    encoding: "base64",
+                    reason: e.to_string(),
+                }
+            }),
+        }
+    }
+
+    /// Encode a frame as text.
+    pub fn encode_text(&self, frame: &[u8]) -> String {
+        match self.encoding {
+            PayloadEncoding::Hex => encode_hex(frame),
+            PayloadEncoding::Base64 => STANDARD.encode(frame),
+        }
+    }
+}
+
+impl BinaryField {
+    fn validate(&self) -> Result<(), CodecError> {
+        let ok = match self.kind {
+            FieldKind::Uint | FieldKind::Int => (1..=8).contains(&self.length),
+            FieldKind::Float => self.length == 4 || self.length == 8,
+            FieldKind::Bool | FieldKind::Bytes => self.length > 0,
+        };
+        if !ok {
+            return Err(self.invalid(format!(
+                "length {} is not valid for {:?}",
+                self.length, self.kind
+            )));
+        }
+        self.end()?;
+        if !self.scale.is_finite() || self.scale == 0.0 {
+            return Err(self.invalid(format!(
+                "scale {} is not a finite non-zero number",
+                self.scale
+            )));
+        }
+        if !self.bias.is_finite() {
+            return Err(self.invalid(format!("bias {} is not a finite number", self.bias)));
+        }
+        Ok(())
+    }
+
+    /// Offset just past the field, if it lies within [`MAX_FRAME_LEN`].
+    fn end(&self) -> Result<usize, CodecError> {
+        self.offset
+            .checked_add(self.length)
+            .filter(|end| *end <= MAX_FRAME_LEN)
+            .ok_or_else(|| {
+                self.invalid(format!(
+                    "offset {} + length {} exceeds the {}-byte frame limit",
+                    self.offset, self.length, MAX_FRAME_LEN
+                ))
+            })
+    }
+
+    fn invalid(&self, reason: String) -> CodecError {
+        CodecError::InvalidField {
+            name: self.name.clone(),
+            reason,
+        }
+    }
+
+    /// Read the field's bytes as an unsigned integer in its byte order.
+    fn read_uint(&self, bytes: &[u8]) -> u64 {
+        let fold = |acc: u64, b: &u8| (acc << 8) | u64::from(*b);
+        match self.endianness {
+            Endianness::Big => bytes.iter().fold(0, fold),
+            Endianness::Little => bytes.iter().rev().fold(0, fold),
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> MetricValue {
+        let raw = match self.kind {
+            FieldKind::Bytes => return MetricValue::String(encode_hex(bytes)),
+            FieldKind::Bool => return MetricValue::Boolean(bytes.iter().any(|b| *b != 0)),
+            FieldKind::Float => {
+                let bits = self.read_uint(bytes);
+                let value = if self.length == 4 {
+                    f64::from(f32::from_bits(bits as u32))
+                } else {
+                    f64::from_bits(bits)
+                };
+                return self.scaled(value);
+            }
+            FieldKind::Uint => self.read_uint(bytes) as i64,
+            FieldKind::Int => {
+                // Sign-extend from the field width
+                let shift = 64u32.saturating_sub(8 * bytes.len() as u32);
+                ((self.read_uint(bytes) << shift) as i64) >> shift
+            }
+        };
+
+        if let Some(label) = self.enum_map.get(&raw) {
+            return MetricValue::String(label.clone());
+        }
+        if self.scale == 1.0 && self.bias == 0.0 {
+            MetricValue::Integer(raw)
+        } else {
+            self.scaled(raw as f64)
+        }
+    }
+
+    fn scaled(&self, value: f64) -> MetricValue {
+        MetricValue::Float(value * self.scale + self.bias)
+    }
+
+    fn encode(&self, value: &MetricValue) -> Result<Vec<u8>, CodecError> {
+        let invalid = |reason: String| CodecError::InvalidValue {
+            name: self.name.clone(),
+            reason,
+        };
+
+        let bits = match (self.kind, value) {
+            (FieldKind::Bytes, MetricValue::Binary(bytes)) => return self.exact(bytes.clone()),
+            (FieldKind::Bytes, MetricValue::String(s)) => {
+                return self.exact(decode_hex(s).map_err(|e| invalid(e.to_string()))?);
+            }
+            (FieldKind::Bool, _) => {
+                let flag = match value {
+                    MetricValue::Boolean(b) => *b,
+                    other => other.as_f64().ok_or_else(|| {
+                        invalid(format!("expected boolean, got {}", other.type_name()))
+                    })? != 0.0,
+                };
+                u64::from(flag)
+            }
+            (FieldKind::Float, _) => {
+                let value = self.unscaled(value)?;
+                if self.length == 4 {
+                    u64::from((value as f32).to_bits())
+                } else {
+                    value.to_bits()
+                }
+            }
+            (FieldKind::Uint | FieldKind::Int, _) => {
+                let raw = match value {
+                    MetricValue::String(label) if !self.enum_map.is_empty() => self
+                        .enum_map
+                        .iter()
+                        .find(|(_, l)| *l == label)
+                        .map(|(raw, _)| *raw)
+                        .ok_or_else(|| invalid(format!("unknown value '{}'", label)))?,
+                    _ => self.unscaled(value)?.round() as i64,
+                };
+                self.check_range(raw).map_err(invalid)?;
+                raw as u64
+            }
+            (FieldKind::Bytes, other) => {
+                return Err(invalid(format!(
+                    "expected bytes or hex string, got {}",
+                    other.type_name()
+                )));
+            }
+        };
+
+        let be = bits.to_be_bytes();
+        let mut bytes = be[8 - self.length.min(8)..].to_vec();
+        if self.endianness == Endianness::Little {
+            bytes.reverse();
+        }
+        self.exact(bytes)
+    }
+
+    /// Inverse of [`Self::scaled`], rejecting results that aren't finite.
+    fn unscaled(&self, value: &MetricValue) -> Result<f64, CodecError> {
+        let raw = (self.number(value)? - self.bias) / self.scale;
+        if raw.is_finite() {
+            Ok(raw)
+        } else {
+            Err(CodecError::InvalidValue {
+                name: self.name.clone(),
+                reason: format!("scale {} cannot be inverted", self.scale),
+            })
+        }
+    }
+
+    fn number(&self, value: &MetricValue) -> Result<f64, CodecError> {
+        match value {
+            MetricValue::Boolean(b) => Ok(f64::from(u8::from(*b))),
+            other => other.as_f64().ok_or_else(|| CodecError::InvalidValue {
+                name: self.name.clone(),
+                reason: format!("expected number, got {}", other.type_name()),
+            }),
+        }
+    }
+
+    /// Reject values that do not fit in the field width.
+    fn check_range(&self, raw: i64) -> Result<(), String> {
+        let bits = 8 * self.length as u32;
+        if bits >= 64 {
+            return Ok(());
+        }
+        let (min, max) = match self.kind {
+            FieldKind::Int => (-(1i64 << (bits - 1)), (1i64 << (bits - 1)) - 1),
+            _ => (0, (1i64 << bits) - 1),
+        };
+        if (min..=max).contains(&raw) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} does not fit in {} byte(s) ({}..={})",
+                raw, self.length, min, max
+            ))
+        }
+    }
+
+    fn exact(&self, bytes: Vec<u8>) -> Result<Vec<u8>, CodecError> {
+        if bytes.len() == self.length {
+            Ok(bytes)
+        } else {
+            Err(CodecError::InvalidValue {
+                name: self.name.clone(),
+                reason: format!("expected {} byte(s), got {}", self.length, bytes.len()),
+            })
+        }
+    }
+}
+
+fn json_to_metric(value: &serde_json::Value) -> MetricValue {
+    match value {
+        serde_json::Value::Bool(b) => MetricValue::Boolean(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => MetricValue::Integer(i),
+            None => MetricValue::Float(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => MetricValue::String(s.clone()),
+        _ => MetricValue::Null,
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, CodecError> {
+    let invalid = |reason: &str| CodecError::InvalidEncoding {
+        encoding: "hex",
+        reason: reason.to_string(),
+    };
+    let text = text.strip_prefix("0x").unwrap_or(text);
+    if !text.len().is_multiple_of(2) {
+        return Err(invalid("odd number of digits"));
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            text.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| invalid("non-hex digit"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn codec() -> BinaryCodec {
+        serde_json::from_value(json!({
+            "fields": [
+                { "name": "temperature", "offset": 0, "length": 2, "kind": "int", "scale": 0.01 },
+                { "name": "humidity", "offset": 2, "length": 1 },
+                { "name": "battery_mv", "offset": 3, "length": 2, "endianness": "little" },
+                { "name": "mode", "offset": 5, "length": 1, "enum_map": { "0": "idle", "1": "heating" } },
+                { "name": "door_open", "offset": 6, "length": 1, "kind": "bool" }
+            ],
+            "commands": {
+                "set_mode": [
+                    { "name": "opcode", "offset": 0, "length": 1, "value": 16 },
+                    { "name": "mode", "offset": 1, "length": 1, "enum_map": { "0": "idle", "1": "heating" } },
+                    { "name": "setpoint", "offset": 2, "length": 2, "kind": "int", "scale": 0.1 }
+                ]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_frame() {
+        let codec = codec();
+        codec.validate().unwrap();
+
+        // -12.34°C, 55%, 3300mV (LE), heating, door open
+        let frame = codec.decode_text("fb2e37e40c0101").unwrap();
+        let metrics: HashMap<_, _> = codec.decode(&frame).into_iter().collect();
+
+        match metrics["temperature"] {
+            MetricValue::Float(t) => assert!((t + 12.34).abs() < 1e-9),
+            ref other => panic!("unexpected temperature {:?}", other),
+        }
+        assert_eq!(metrics["humidity"], MetricValue::Integer(55));
+        assert_eq!(metrics["battery_mv"], MetricValue::Integer(3300));
+        assert_eq!(metrics["mode"], MetricValue::String("heating".into()));
+        assert_eq!(metrics["door_open"], MetricValue::Boolean(true));
+
+        // A short frame decodes the fields it contains
+        assert_eq!(codec.decode(&frame[..3]).len(), 2);
+    }
+
+    #[test]
+    fn test_encode_command() {
+        let codec = codec();
+        let params = HashMap::from([
+            ("mode".to_string(), MetricValue::String("heating".into())),
+            ("setpoint".to_string(), MetricValue::Float(-2.5)),
+        ]);
+        let frame = codec.encode_command("set_mode", &params).unwrap();
+        assert_eq!(codec.encode_text(&frame), "1001ffe7");
+
+        let bad = HashMap::from([
+            ("mode".to_string(), MetricValue::String("cooling".into())),
+            ("setpoint".to_string(), MetricValue::Integer(0)),
+        ]);
+        assert!(codec.encode_command("set_mode", &bad).is_err());
+        assert!(matches!(
+            codec.encode_command("set_mode", &HashMap::new()),
+            Err(CodecError::MissingParameter(_))
+        ));
+        assert!(matches!(
+            codec.encode_command("reboot", &params),
+            Err(CodecError::UnknownCommand(_))
+        ));
+    }
+
+    #[test]
+    fn test_field_validation() {
+        let mut codec = codec();
+        codec.fields.push(BinaryField {
+            name: "pressure".into(),
+            offset: 7,
+            length: 3,
+            kind: FieldKind::Float,
+            endianness: Endianness::Big,
+            scale: 1.0,
+            bias: 0.0,
+            enum_map: BTreeMap::new(),
+            value: None,
+        });
+        assert!(codec.validate().is_err());
+    }
+
+    fn field(offset: usize, length: usize, kind: FieldKind, scale: f64) -> BinaryField {
+        BinaryField {
+            name: "f".into(),
+            offset,
+            length,
+            kind,
+            endianness: Endianness::Big,
+            scale,
+            bias: 0.0,
+            enum_map: BTreeMap::new(),
+            value: None,
+        }
+    }
+
+    #[test]
+    fn test_field_bounds_validation() {
+        for bad in [
+            field(usize::MAX, 1, FieldKind::Uint, 1.0),
+            field(MAX_FRAME_LEN, 1, FieldKind::Uint, 1.0),
+            field(0, MAX_FRAME_LEN + 1, FieldKind::Bytes, 1.0),
+            field(0, 2, FieldKind::Int, 0.0),
+            field(0, 4, FieldKind::Float, f64::NAN),
+            field(0, 4, FieldKind::Float, f64::INFINITY),
+        ] {
+            let codec = BinaryCodec {
+                fields: vec![bad.clone()],
+                ..Default::default()
+            };
+            assert!(codec.validate().is_err(), "{:?}", bad);
+        }
+        let codec = BinaryCodec {
+            fields: vec![field(MAX_FRAME_LEN - 1, 1, FieldKind::Uint, 1.0)],
+            ..Default::default()
+        };
+        codec.validate().unwrap();
+    }
+
+    #[test]
+    fn test_unvalidated_fields_do_not_panic_or_allocate() {
+        // Fields that would overflow or blow past the frame limit are
+        // skipped on decode and rejected on encode without building a frame.
+        let codec = BinaryCodec {
+            fields: vec![
+                field(usize::MAX, 2, FieldKind::Uint, 1.0),
+                field(0, 1, FieldKind::Uint, 1.0),
+            ],
+            commands: HashMap::from([
+                (
+                    "huge".to_string(),
+                    vec![field(usize::MAX / 2, 1, FieldKind::Uint, 1.0)],
+                ),
+                (
+                    "wrap".to_string(),
+                    vec![field(usize::MAX, 2, FieldKind::Uint, 1.0)],
+                ),
+                ("zero".to_string(), vec![field(0, 2, FieldKind::Int, 0.0)]),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(codec.decode(&[7]).len(), 1);
+
+        let params = HashMap::from([("f".to_string(), MetricValue::Integer(1))]);
+        for command in ["huge", "wrap"] {
+            assert!(matches!(
+                codec.encode_command(command, &params),
+                Err(CodecError::InvalidField { .. })
+            ));
+        }
+        assert!(matches!(
+            codec.encode_command("zero", &params),
+            Err(CodecError::InvalidValue { .. })
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+//! Fault injection for device adapters (feature `chaos`).
+//!
+//! [`ChaosAdapter`] wraps any [`DeviceAdapter`] and applies the faults of a
+//! [`ChaosController`] to it: added latency and random failures on starts,
+//! commands and subscriptions, and a simulated disconnect that also drops
+//! incoming events and reports the adapter as disconnected.
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::{future, Stream, StreamExt};
+use neomind_core::chaos::{ChaosController, ChaosFault};
+
+use crate::adapter::{AdapterError, AdapterResult, ConnectionStatus, DeviceAdapter, DeviceEvent};
+
+/// [`DeviceAdapter`] wrapper that injects faults from a [`ChaosController`].
+pub struct ChaosAdapter {
+    inner: Arc<dyn DeviceAdapter>,
+    chaos: ChaosController,
+}
+
+impl ChaosAdapter {
+    pub fn new(inner: Arc<dyn DeviceAdapter>, chaos: ChaosController) -> Self {
+        Self { inner, chaos }
+    }
+
+    pub fn controller(&self) -> &ChaosController {
+        &self.chaos
+    }
+
+    /// The wrapped adapter.
+    pub fn inner(&self) -> &Arc<dyn DeviceAdapter> {
+        &self.inner
+    }
+
+    async fn inject(&self) -> AdapterResult<()> {
+        self.chaos.inject().await.map_err(|fault| match fault {
+            ChaosFault::Error => AdapterError::Communication(fault.to_string()),
+            ChaosFault::Disconnected => AdapterError::Connection(fault.to_string()),
+        })
+    }
+}
+
+#[async_trait]
+impl DeviceAdapter for ChaosAdapter {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn adapter_type(&self) -> &'static str {
+        self.inner.adapter_type()
+    }
+
+    fn is_running(&self) -> bool {
+        self.inner.is_running()
+    }
+
+    async fn start(&self) -> AdapterResult<()> {
+        self.inject().await?;
+        self.inner.start().await
+    }
+
+    async fn stop(&self) -> AdapterResult<()> {
+        self.inner.stop().await
+    }
+
+    fn subscribe(&self) -> Pin<Box<dyn Stream<Item = DeviceEvent> + Send + '_>> {
+        let chaos = self.chaos.clone();
+        Box::pin(
+            self.inner
+                .subscribe()
+                .filter(move |_| future::ready(!chaos.is_disconnected())),
+        )
+    }
+
+    fn set_telemetry_storage(&self, storage: Arc<crate::TimeSeriesStorage>) {
+        self.inner.set_telemetry_storage(storage);
+    }
+
+    fn device_count(&self) -> usize {
+        self.inner.device_count()
+    }
+
+    fn list_devices(&self) -> Vec<String> {
+        self.inner.list_devices()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn send_command(
+        &self,
+        device_id: &str,
+        command_name: &str,
+        payload: String,
+        topic: Option<String>,
+    ) -> AdapterResult<()> {
+        self.inject().await?;
+        self.inner
+            .send_command(device_id, command_name, payload, topic)
+            .await
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        if self.chaos.is_disconnected() {
+            ConnectionStatus::Disconnected
+        } else {
+            self.inner.connection_status()
+        }
+    }
+
+    async fn subscribe_device(&self, device_id: &str) -> AdapterResult<()> {
+        self.inject().await?;
+        self.inner.subscribe_device(device_id).await
+    }
+
+    async fn unsubscribe_device(&self, device_id: &str) -> AdapterResult<()> {
+        self.inject().await?;
+        self.inner.unsubscribe_device(device_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::MockAdapter;
+    use crate::mdl::MetricValue;
+    use std::time::Duration;
+
+    fn metric(value: i64) -> DeviceEvent {
+        DeviceEvent::Metric {
+            device_id: "sensor1".to_string(),
+            metric: "temperature".to_string(),
+            value: MetricValue::Integer(value),
+            timestamp: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chaos_adapter_faults() {
+        let mock = Arc::new(MockAdapter::new("mock").with_device("sensor1"));
+        let chaos = ChaosController::new();
+        let adapter = ChaosAdapter::new(mock.clone(), chaos.clone());
+        adapter.start().await.unwrap();
+        assert_eq!(adapter.connection_status(), ConnectionStatus::Connected);
+
+        chaos.set_error_rate(1.0);
+        let err = adapter
+            .send_command("sensor1", "reset", "{}".to_string(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AdapterError::Communication(_)));
+
+        // Events are dropped while disconnected and flow again afterwards
+        chaos.reset();
+        let mut events = adapter.subscribe();
+        chaos.disconnect();
+        assert_eq!(adapter.connection_status(), ConnectionStatus::Disconnected);
+        assert!(matches!(
+            adapter.subscribe_device("sensor1").await,
+            Err(AdapterError::Connection(_))
+        ));
+        mock.publish_event(metric(1)).unwrap();
+        let dropped = tokio::time::timeout(Duration::from_millis(50), events.next()).await;
+        assert!(dropped.is_err(), "event delivered while disconnected");
+        chaos.reconnect();
+        mock.publish_event(metric(2)).unwrap();
+        match events.next().await {
+            Some(DeviceEvent::Metric { value, .. }) => assert_eq!(value, MetricValue::Integer(2)),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        assert!(adapter
+            .send_command("sensor1", "reset", "{}".to_string(), None)
+            .await
+            .is_ok());
+        assert_eq!(chaos.faults(), 2);
+    }
+}
@@ -0,0 +1,472 @@
+//! Command groups: several device commands submitted as one batch.
+//!
+//! A [`CommandGroup`] runs its commands in parallel or in order. When the
+//! group is atomic and a command fails, every command that already succeeded
+//! is undone by sending its declared inverse (`rollback`) command, newest
+//! first. Commands without an inverse can't be undone; the group then ends in
+//! [`GroupStatus::RollbackIncomplete`] so the caller knows devices were left
+//! in a mixed state.
+//!
+//! Submitted groups are limited to [`MAX_GROUP_COMMANDS`] commands, and a
+//! parallel group keeps at most [`MAX_PARALLEL_COMMANDS`] commands in flight.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::mdl::DeviceError;
+use crate::service::{CommandStatus, DeviceService};
+
+/// Maximum commands in a submitted group.
+pub const MAX_GROUP_COMMANDS: usize = 100;
+
+/// Maximum commands of a parallel group sent at the same time.
+pub const MAX_PARALLEL_COMMANDS: usize = 16;
+
+/// How the commands of a group are dispatched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupOrdering {
+    /// Send all commands at once.
+    Parallel,
+    /// Send one after another; stop at the first failure.
+    #[default]
+    Sequential,
+}
+
+/// Command sent to undo a [`GroupCommand`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InverseCommand {
+    pub command: String,
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+}
+
+/// One command in a group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupCommand {
+    pub device_id: String,
+    pub command: String,
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+    /// Inverse command used for rollback, if the device supports one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollback: Option<InverseCommand>,
+}
+
+/// A batch of device commands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandGroup {
+    pub commands: Vec<GroupCommand>,
+    #[serde(default)]
+    pub ordering: GroupOrdering,
+    /// All-or-nothing: roll back successful commands when any command fails.
+    #[serde(default = "default_atomic")]
+    pub atomic: bool,
+}
+
+fn default_atomic() -> bool {
+    true
+}
+
+/// Overall outcome of a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupStatus {
+    /// Every command succeeded.
+    Success,
+    /// Non-atomic group where some commands failed.
+    PartialFailure,
+    /// A command failed and all successful commands were undone.
+    RolledBack,
+    /// A command failed and at least one successful command could not be undone.
+    RollbackIncomplete,
+    /// Nothing succeeded.
+    Failed,
+}
+
+/// Outcome of one command in a group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupCommandResult {
+    pub index: usize,
+    pub device_id: String,
+    pub command: String,
+    /// `Pending` when the command was never sent (sequential group stopped early).
+    pub status: CommandStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The inverse command was sent successfully.
+    pub rolled_back: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollback_error: Option<String>,
+}
+
+/// Result of running a [`CommandGroup`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandGroupResult {
+    pub group_id: String,
+    pub status: GroupStatus,
+    pub results: Vec<GroupCommandResult>,
+}
+
+/// Sends a single device command. Implemented by [`DeviceService`].
+#[async_trait]
+pub trait CommandSender: Send + Sync {
+    async fn send(
+        &self,
+        device_id: &str,
+        command: &str,
+        params: HashMap<String, serde_json::Value>,
+    ) -> Result<(), DeviceError>;
+}
+
+#[async_trait]
+impl CommandSender for DeviceService {
+    async fn send(
+        &self,
+        device_id: &str,
+        command: &str,
+        params: HashMap<String, serde_json::Value>,
+    ) -> Result<(), DeviceError> {
+        self.send_command(device_id, command, params)
+            .await
+            .map(|_| ())
+    }
+}
+
+impl CommandGroup {
+    pub fn new(commands: Vec<GroupCommand>, ordering: GroupOrdering) -> Self {
+        Self {
+            commands,
+            ordering,
+            atomic: true,
+        }
+    }
+
+    /// Check that the group has between 1 and [`MAX_GROUP_COMMANDS`] commands.
+    pub fn validate(&self) -> Result<(), DeviceError> {
+        if self.commands.is_empty() {
+            return Err(DeviceError::InvalidParameter(
+                "Command group is empty".to_string(),
+            ));
+        }
+        if self.commands.len() > MAX_GROUP_COMMANDS {
+            return Err(DeviceError::InvalidParameter(format!(
+                "Command group has {} commands; at most {} are allowed",
+                self.commands.len(),
+                MAX_GROUP_COMMANDS
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run the group through `sender`.
+    pub async fn execute(&self, sender: &dyn CommandSender) -> CommandGroupResult {
+        let group_id = uuid::Uuid::new_v4().to_string();
+        let mut results: Vec<GroupCommandResult> = self
+            .commands
+            .iter()
+            .enumerate()
+            .map(|(index, cmd)| GroupCommandResult {
+                index,
+                device_id: cmd.device_id.clone(),
+                command: cmd.command.clone(),
+                status: CommandStatus::Pending,
+                error: None,
+                rolled_back: false,
+                rollback_error: None,
+            })
+            .collect();
+
+        match self.ordering {
+            GroupOrdering::Sequential => {
+                for (cmd, result) in self.commands.iter().zip(results.iter_mut()) {
+                    let outcome = sender
+                        .send(&cmd.device_id, &cmd.command, cmd.params.clone())
+                        .await;
+                    record(result, outcome);
+                    if result.status == CommandStatus::Failed {
+                        break;
+                    }
+                }
+            }
+            GroupOrdering::Parallel => {
+                // Sends are lazy; buffer_unordered starts at most N of them at a time.
+                let sends: Vec<_> = self
+                    .commands
+                    .iter()
+                    .enumerate()
+                    .map(|(index, cmd)| {
+                        let send = sender.send(&cmd.device_id, &cmd.command, cmd.params.clone());
+                        async move { (index, send.await) }
+                    })
+                    .collect();
+                let outcomes: Vec<_> = futures::stream::iter(sends)
+                    .buffer_unordered(MAX_PARALLEL_COMMANDS)
+                    .collect()
+                    .await;
+                for (index, outcome) in outcomes {
+                    record(&mut results[index], outcome);
+                }
+            }
+        }
+
+        let any_failed = results.iter().any(|r| r.status == CommandStatus::Failed);
+        let succeeded = results
+            .iter()
+            .filter(|r| r.status == CommandStatus::Success)
+            .count();
+
+        let status = if !any_failed {
+            GroupStatus::Success
+        } else if succeeded == 0 {
+            GroupStatus::Failed
+        } else if !self.atomic {
+            GroupStatus::PartialFailure
+        } else if self.rollback(sender, &mut results).await {
+            GroupStatus::RolledBack
+        } else {
+            GroupStatus::RollbackIncomplete
+        };
+
+        tracing::info!(
+            group_id = %group_id,
+            commands = self.commands.len(),
+            status = ?status,
+            "Command group finished"
+        );
+
+        CommandGroupResult {
+            group_id,
+            status,
+            results,
+        }
+    }
+
+    /// Undo successful commands, newest first. Returns whether all were undone.
+    async fn rollback(
+        &self,
+        sender: &dyn CommandSender,
+        results: &mut [GroupCommandResult],
+    ) -> bool {
+        let mut complete = true;
+        for (cmd, result) in self.commands.iter().zip(results.iter_mut()).rev() {
+            if result.status != CommandStatus::Success {
+                continue;
+            }
+            let Some(inverse) = &cmd.rollback else {
+                result.rollback_error = Some("No inverse command defined".to_string());
+                complete = false;
+                continue;
+            };
+            match sender
+                .send(&cmd.device_id, &inverse.command, inverse.params.clone())
+                .await
+            {
+                Ok(()) => result.rolled_back = true,
+                Err(e) => {
+                    tracing::warn!(
+                        device_id = %cmd.device_id,
+                        command = %inverse.command,
+                        error = %e,
+                        "Command group rollback failed"
+                    );
+                    result.rollback_error = Some(e.to_string());
+                    complete = false;
+                }
+            }
+        }
+        complete
+    }
+}
+
+fn record(result: &mut GroupCommandResult, outcome: Result<(), DeviceError>) {
+    match outcome {
+        Ok(()) => result.status = CommandStatus::Success,
+        Err(e) => {
+            result.status = CommandStatus::Failed;
+            result.error = Some(e.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records every send; fails commands named in `failing`.
+    struct FakeSender {
+        failing: Vec<&'static str>,
+        sent: Mutex<Vec<String>>,
+    }
+
+    impl FakeSender {
+        fn new(failing: Vec<&'static str>) -> Self {
+            Self {
+                failing,
+                sent: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn sent(&self) -> Vec<String> {
+            self.sent.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl CommandSender for FakeSender {
+        async fn send(
+            &self,
+            device_id: &str,
+            command: &str,
+            _params: HashMap<String, serde_json::Value>,
+        ) -> Result<(), DeviceError> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push(format!("{}:{}", device_id, command));
+            if self.failing.contains(&command) {
+                return Err(DeviceError::InvalidCommand(command.to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    fn cmd(device: &str, command: &str, inverse: Option<&str>) -> GroupCommand {
+        GroupCommand {
+            device_id: device.to_string(),
+            command: command.to_string(),
+            params: HashMap::new(),
+            rollback: inverse.map(|c| InverseCommand {
+                command: c.to_string(),
+                params: HashMap::new(),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequential_success() {
+        let sender = FakeSender::new(vec![]);
+        let group = CommandGroup::new(
+            vec![cmd("fan", "on", Some("off")), cmd("lamp", "on", None)],
+            GroupOrdering::Sequential,
+        );
+        let result = group.execute(&sender).await;
+        assert_eq!(result.status, GroupStatus::Success);
+        assert_eq!(sender.sent(), vec!["fan:on", "lamp:on"]);
+    }
+
+    #[tokio::test]
+    async fn test_sequential_failure_rolls_back_in_reverse() {
+        let sender = FakeSender::new(vec!["open"]);
+        let group = CommandGroup::new(
+            vec![
+                cmd("fan", "on", Some("off")),
+                cmd("heater", "heat", Some("stop")),
+                cmd("valve", "open", Some("close")),
+                cmd("lamp", "on", Some("off")),
+            ],
+            GroupOrdering::Sequential,
+        );
+        let result = group.execute(&sender).await;
+        assert_eq!(result.status, GroupStatus::RolledBack);
+        assert_eq!(
+            sender.sent(),
+            vec![
+                "fan:on",
+                "heater:heat",
+                "valve:open",
+                "heater:stop",
+                "fan:off"
+            ]
+        );
+        assert_eq!(result.results[3].status, CommandStatus::Pending);
+        assert!(result.results[0].rolled_back && result.results[1].rolled_back);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_rollback_incomplete_without_inverse() {
+        let sender = FakeSender::new(vec!["open"]);
+        let group = CommandGroup::new(
+            vec![cmd("fan", "on", None), cmd("valve", "open", Some("close"))],
+            GroupOrdering::Parallel,
+        );
+        let result = group.execute(&sender).await;
+        assert_eq!(result.status, GroupStatus::RollbackIncomplete);
+        assert!(result.results[0].rollback_error.is_some());
+        assert_eq!(result.results[1].status, CommandStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_non_atomic_partial_failure() {
+        let sender = FakeSender::new(vec!["open"]);
+        let mut group = CommandGroup::new(
+            vec![cmd("fan", "on", Some("off")), cmd("valve", "open", None)],
+            GroupOrdering::Parallel,
+        );
+        group.atomic = false;
+        let result = group.execute(&sender).await;
+        assert_eq!(result.status, GroupStatus::PartialFailure);
+        assert!(!sender.sent().contains(&"fan:off".to_string()));
+    }
+
+    /// Tracks how many sends are in flight at once.
+    #[derive(Default)]
+    struct SlowSender {
+        in_flight: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CommandSender for SlowSender {
+        async fn send(
+            &self,
+            _device_id: &str,
+            _command: &str,
+            _params: HashMap<String, serde_json::Value>,
+        ) -> Result<(), DeviceError> {
+            use std::sync::atomic::Ordering;
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parallel_concurrency_is_bounded() {
+        let sender = SlowSender::default();
+        let commands = (0..MAX_PARALLEL_COMMANDS * 3)
+            .map(|i| cmd(&format!("lamp{}", i), "on", None))
+            .collect();
+        let group = CommandGroup::new(commands, GroupOrdering::Parallel);
+        let result = group.execute(&sender).await;
+        assert_eq!(result.status, GroupStatus::Success);
+        assert!(result
+            .results
+            .iter()
+            .enumerate()
+            .all(|(i, r)| r.index == i && r.device_id == format!("lamp{}", i)));
+        let peak = sender.peak.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(peak > 1 && peak <= MAX_PARALLEL_COMMANDS, "peak {}", peak);
+    }
+
+    #[test]
+    fn test_validate_group_size() {
+        let group = |n: usize| {
+            CommandGroup::new(
+                (0..n)
+                    .map(|i| cmd(&format!("d{}", i), "on", None))
+                    .collect(),
+                GroupOrdering::Parallel,
+            )
+        };
+        assert!(group(0).validate().is_err());
+        assert!(group(MAX_GROUP_COMMANDS).validate().is_ok());
+        assert!(group(MAX_GROUP_COMMANDS + 1).validate().is_err());
+    }
+}
@@ -12,8 +12,26 @@
 //! from a shared credential store (passed in at construction time).
 //!
 //! Changing `auth_enabled` takes effect immediately without restarting
-//! the broker. Only `listen`, `port`, or `tls_enabled` changes require
-//! a broker restart.
+//! the broker. Only `listen`, `port`, the TLS options or the MQTT 5 session
+//! options require a broker restart.
+//!
+//! ## Device ACL
+//!
+//! When an ACL checker is set (see [`EmbeddedBroker::set_acl_checker`]),
+//! every PUBLISH and SUBSCRIBE is checked against it. Provisioned devices
+//! are confined to topics that contain their own device id as a level
+//! (see [`device_topic_allowed`]); other clients are not restricted.
+//!
+//! With `tls_client_auth` the TLS listener also requires a client
+//! certificate issued by the broker CA (mutual TLS).
+//!
+//! ## MQTT 5
+//!
+//! The listener accepts both MQTT 3.1.1 and MQTT 5 clients. For MQTT 5 the
+//! broker honours shared subscriptions (`$share/<group>/<filter>`), so
+//! several NeoMind instances can load-balance one device fleet, caps the
+//! session expiry interval a client may request, and negotiates topic
+//! aliases up to `max_topic_aliases`.
 
 use std::collections::HashMap;
 use std::net::SocketAddr;
@@ -41,6 +59,29 @@ use neomind_core::{EventBus, NeoMindEvent};
 /// `DeviceRegistry::find_device_by_telemetry_topic`).
 pub type TopicResolverFn = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
 
+/// Topic ACL used by the broker's publish/subscribe checks.
+///
+/// Called with the client's username (if any) and the topic name or
+/// subscription filter; returns whether the operation is allowed.
+pub type AclCheckerFn = Arc<dyn Fn(Option<&str>, &str) -> bool + Send + Sync>;
+
+/// Whether a device may publish to or subscribe on `topic`.
+///
+/// One of the topic levels must be the device id, and no multi-level
+/// wildcard may come before it, so `device/+/sensor01/#` is allowed for
+/// `sensor01` but `#` or `device/#` are not.
+pub fn device_topic_allowed(device_id: &str, topic: &str) -> bool {
+    for level in topic.split('/') {
+        if level == device_id {
+            return true;
+        }
+        if level == "#" {
+            return false;
+        }
+    }
+    false
+}
+
 /// Embedded MQTT broker error type
 #[derive(Debug, Error)]
 pub enum EmbeddedBrokerError {
@@ -86,6 +127,22 @@ pub struct EmbeddedBrokerConfig {
 
     #[serde(default)]
     pub tls_ca_path: Option<String>,
+
+    /// Require TLS clients to present a certificate issued by the broker CA.
+    #[serde(default)]
+    pub tls_client_auth: bool,
+
+    /// Allow MQTT 5 shared subscriptions (`$share/<group>/<filter>`).
+    #[serde(default = "default_shared_subscriptions")]
+    pub shared_subscriptions: bool,
+
+    /// Maximum session expiry interval granted to clients, in seconds.
+    #[serde(default = "default_session_expiry_secs")]
+    pub session_expiry_secs: u64,
+
+    /// Maximum number of MQTT 5 topic aliases per connection (0 = disabled).
+    #[serde(default = "default_max_topic_aliases")]
+    pub max_topic_aliases: u16,
 }
 
 fn default_listen_addr() -> String {
@@ -103,6 +160,15 @@ fn default_max_payload() -> usize {
 fn default_dynamic_filters() -> bool {
     true
 }
+fn default_shared_subscriptions() -> bool {
+    true
+}
+fn default_session_expiry_secs() -> u64 {
+    7200 // 2 hours
+}
+fn default_max_topic_aliases() -> u16 {
+    32
+}
 
 impl Default for EmbeddedBrokerConfig {
     fn default() -> Self {
@@ -117,6 +183,10 @@ impl Default for EmbeddedBrokerConfig {
             tls_cert_path: None,
             tls_key_path: None,
             tls_ca_path: None,
+            tls_client_auth: false,
+            shared_subscriptions: default_shared_subscriptions(),
+            session_expiry_secs: default_session_expiry_secs(),
+            max_topic_aliases: default_max_topic_aliases(),
         }
     }
 }
@@ -141,6 +211,32 @@ impl EmbeddedBrokerConfig {
         self
     }
 
+    pub fn with_shared_subscriptions(mut self, enabled: bool) -> Self {
+        self.shared_subscriptions = enabled;
+        self
+    }
+
+    pub fn with_session_expiry_secs(mut self, secs: u64) -> Self {
+        self.session_expiry_secs = secs;
+        self
+    }
+
+    pub fn with_max_topic_aliases(mut self, max: u16) -> Self {
+        self.max_topic_aliases = max;
+        self
+    }
+
+    /// Whether switching from `self` to `other` requires restarting the broker.
+    pub fn requires_restart(&self, other: &EmbeddedBrokerConfig) -> bool {
+        self.listen != other.listen
+            || self.port != other.port
+            || self.tls_enabled != other.tls_enabled
+            || self.tls_client_auth != other.tls_client_auth
+            || self.shared_subscriptions != other.shared_subscriptions
+            || self.session_expiry_secs != other.session_expiry_secs
+            || self.max_topic_aliases != other.max_topic_aliases
+    }
+
     pub fn socket_addr(&self) -> Result<SocketAddr, EmbeddedBrokerError> {
         format!("{}:{}", self.listen, self.port)
             .parse()
@@ -240,6 +336,63 @@ impl rmqtt::hook::Handler for NeoMindAuthHandler {
     }
 }
 
+/// ACL hook for the embedded broker.
+///
+/// Rejects publishes and subscriptions the [`AclCheckerFn`] refuses and
+/// defers everything else to the broker's default handling. Superusers
+/// (the internal client) bypass ACL checks in rmqtt itself.
+struct DeviceAclHook {
+    acl_checker: AclCheckerFn,
+}
+
+#[async_trait]
+impl rmqtt::hook::Handler for DeviceAclHook {
+    async fn hook(
+        &self,
+        param: &rmqtt::hook::Parameter,
+        acc: Option<rmqtt::hook::HookResult>,
+    ) -> rmqtt::hook::ReturnType {
+        match param {
+            rmqtt::hook::Parameter::MessagePublishCheckAcl(session, publish) => {
+                let username = session.id.username.as_deref();
+                if !(self.acl_checker)(username, &publish.topic) {
+                    tracing::warn!(
+                        "ACL hook: publish to '{}' denied for client '{}'",
+                        publish.topic,
+                        session.id.client_id
+                    );
+                    return (
+                        false,
+                        Some(rmqtt::hook::HookResult::PublishAclResult(
+                            rmqtt::types::PublishAclResult::rejected(false, None),
+                        )),
+                    );
+                }
+            }
+            rmqtt::hook::Parameter::ClientSubscribeCheckAcl(session, subscribe) => {
+                let username = session.id.username.as_deref();
+                if !(self.acl_checker)(username, &subscribe.topic_filter) {
+                    tracing::warn!(
+                        "ACL hook: subscribe to '{}' denied for client '{}'",
+                        subscribe.topic_filter,
+                        session.id.client_id
+                    );
+                    return (
+                        false,
+                        Some(rmqtt::hook::HookResult::SubscribeAclResult(
+                            rmqtt::types::SubscribeAclResult::new_failure(
+                                rmqtt::codec::v5::SubscribeAckReason::NotAuthorized,
+                            ),
+                        )),
+                    );
+                }
+            }
+            _ => {}
+        }
+        (true, acc)
+    }
+}
+
 /// Hook that emits `DeviceTransportOnline` / `DeviceTransportOffline` events
 /// to the NeoMind EventBus whenever an MQTT client connects or disconnects
 /// at the transport layer.
@@ -448,6 +601,9 @@ pub struct EmbeddedBroker {
     /// to NeoMind device_id (necessary when devices use a client_id
     /// different from their registered device_id).
     topic_resolver: Mutex<Option<TopicResolverFn>>,
+    /// Optional topic ACL. Set via `set_acl_checker` before `start()` is
+    /// called; if `None`, authenticated clients may use any topic.
+    acl_checker: Mutex<Option<AclCheckerFn>>,
 }
 
 impl EmbeddedBroker {
@@ -465,6 +621,7 @@ impl EmbeddedBroker {
             credential_validator,
             event_bus: Mutex::new(None),
             topic_resolver: Mutex::new(None),
+            acl_checker: Mutex::new(None),
         }
     }
 
@@ -493,6 +650,12 @@ impl EmbeddedBroker {
         *self.topic_resolver.lock().unwrap() = Some(resolver);
     }
 
+    /// Provide the topic ACL applied to PUBLISH and SUBSCRIBE. Must be called
+    /// before `start()`.
+    pub fn set_acl_checker(&self, checker: AclCheckerFn) {
+        *self.acl_checker.lock().unwrap() = Some(checker);
+    }
+
     /// Check if the broker is running
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::Relaxed)
@@ -586,6 +749,25 @@ impl EmbeddedBroker {
         )
         .await;
 
+        // Register ACL hooks so provisioned devices stay on their own topics
+        let acl_checker = self.acl_checker.lock().unwrap().clone();
+        if let Some(checker) = acl_checker {
+            reg.add(
+                rmqtt::hook::Type::MessagePublishCheckAcl,
+                Box::new(DeviceAclHook {
+                    acl_checker: checker.clone(),
+                }),
+            )
+            .await;
+            reg.add(
+                rmqtt::hook::Type::ClientSubscribeCheckAcl,
+                Box::new(DeviceAclHook {
+                    acl_checker: checker,
+                }),
+            )
+            .await;
+        }
+
         // Register presence hook (transport-level connect/disconnect) if an
         // EventBus was provided. This is what makes "device connected to MQTT
         // but hasn't published yet" show up correctly in the UI instead of
@@ -657,9 +839,12 @@ impl EmbeddedBroker {
         reg.start().await;
 
         tracing::info!(
-            "MQTT Broker Listening on neomind-broker {} (auth_enabled={})",
+            "MQTT Broker Listening on neomind-broker {} (auth_enabled={}, shared_subscriptions={}, session_expiry={}s, max_topic_aliases={})",
             addr,
-            self.auth_enabled.load(Ordering::Relaxed)
+            self.auth_enabled.load(Ordering::Relaxed),
+            config.shared_subscriptions,
+            config.session_expiry_secs,
+            config.max_topic_aliases
         );
 
         // Build listener
@@ -670,7 +855,10 @@ impl EmbeddedBroker {
             .name("neomind-broker")
             .laddr(addr)
             .reuseaddr(Some(true))
-            .allow_anonymous(false);
+            .allow_anonymous(false)
+            .shared_subscription(config.shared_subscriptions)
+            .session_expiry_interval(std::time::Duration::from_secs(config.session_expiry_secs))
+            .max_topic_aliases(config.max_topic_aliases);
 
         let listener = if config.tls_enabled {
             let cert_path = config.tls_cert_path.as_deref().ok_or_else(|| {
@@ -695,8 +883,14 @@ impl EmbeddedBroker {
                 )));
             }
 
-            tracing::info!("TLS enabled with cert: {}, key: {}", cert_path, key_path);
+            tracing::info!(
+                "TLS enabled with cert: {}, key: {} (client_auth={})",
+                cert_path,
+                key_path,
+                config.tls_client_auth
+            );
             builder
+                .tls_cross_certificate(config.tls_client_auth)
                 .tls_cert(Some(cert_path.to_string()))
                 .tls_key(Some(key_path.to_string()))
                 .bind()
@@ -779,6 +973,38 @@ mod tests {
         assert_eq!(config.port, 1883);
         assert_eq!(config.max_connections, 1000);
         assert!(!config.auth_enabled);
+        assert!(config.shared_subscriptions);
+        assert_eq!(config.session_expiry_secs, 7200);
+    }
+
+    #[test]
+    fn test_mqtt5_options_default_when_missing() {
+        // Configs saved before MQTT 5 options existed must still load.
+        let config: EmbeddedBrokerConfig =
+            serde_json::from_value(serde_json::json!({"port": 1884})).unwrap();
+        assert_eq!(config.port, 1884);
+        assert!(config.shared_subscriptions);
+        assert_eq!(config.max_topic_aliases, 32);
+    }
+
+    #[test]
+    fn test_requires_restart() {
+        let base = EmbeddedBrokerConfig::default();
+        let mut auth_only = base.clone();
+        auth_only.auth_enabled = true;
+        assert!(!base.requires_restart(&auth_only));
+        assert!(base.requires_restart(&base.clone().with_session_expiry_secs(60)));
+        assert!(base.requires_restart(&base.clone().with_shared_subscriptions(false)));
+    }
+
+    #[test]
+    fn test_device_topic_allowed() {
+        assert!(device_topic_allowed("sensor01", "device/dht22/sensor01/uplink"));
+        assert!(device_topic_allowed("sensor01", "device/+/sensor01/#"));
+        assert!(!device_topic_allowed("sensor01", "device/dht22/sensor02/uplink"));
+        assert!(!device_topic_allowed("sensor01", "device/#"));
+        assert!(!device_topic_allowed("sensor01", "#"));
+        assert!(!device_topic_allowed("sensor01", "device/dht22/sensor011/uplink"));
     }
 
     #[test]
@@ -0,0 +1,122 @@
+//! Device groups.
+//!
+//! A group has static members (device IDs) and an optional dynamic
+//! [`GroupSelector`] matched against device tags, location and type, so
+//! "all lights on floor 2" keeps tracking devices as they are added or moved.
+//! Groups are stored by [`DeviceRegistry`](crate::DeviceRegistry); group-level
+//! queries and commands live on [`DeviceService`](crate::DeviceService).
+
+pub use neomind_storage::device_registry::{DeviceGroup, GroupSelector};
+
+use crate::registry::DeviceConfig;
+
+/// Whether `device` matches `selector`. An empty selector matches nothing.
+pub fn selector_matches(selector: &GroupSelector, device: &DeviceConfig) -> bool {
+    if selector.tags.is_empty() && selector.location.is_none() && selector.device_type.is_none() {
+        return false;
+    }
+    if !selector
+        .tags
+        .iter()
+        .all(|tag| device.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    {
+        return false;
+    }
+    if let Some(location) = &selector.location {
+        match &device.location {
+            Some(device_location) if location_contains(location, device_location) => {}
+            _ => return false,
+        }
+    }
+    if let Some(device_type) = &selector.device_type {
+        if &device.device_type != device_type {
+            return false;
+        }
+    }
+    true
+}
+
+/// `hq/floor-2` contains `hq/floor-2` and `hq/floor-2/room-201`, but not
+/// `hq/floor-20`.
+fn location_contains(parent: &str, location: &str) -> bool {
+    let parent = parent.trim_end_matches('/');
+    location == parent
+        || location
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Whether `device` belongs to `group`, statically or through its selector.
+///
+/// Groups never span tenants: devices of another tenant are not members even
+/// when listed by ID or matched by the selector.
+pub fn is_member(group: &DeviceGroup, device: &DeviceConfig) -> bool {
+    if device.tenant_id != group.tenant_id {
+        return false;
+    }
+    group.members.contains(&device.device_id)
+        || group
+            .selector
+            .as_ref()
+            .is_some_and(|s| selector_matches(s, device))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::ConnectionConfig;
+
+    fn device(id: &str, device_type: &str, tags: &[&str], location: Option<&str>) -> DeviceConfig {
+        DeviceConfig {
+            device_id: id.to_string(),
+            name: id.to_string(),
+            device_type: device_type.to_string(),
+            adapter_type: "mqtt".to_string(),
+            connection_config: ConnectionConfig::new(),
+            adapter_id: None,
+            last_seen: 0,
+            offline_timeout_secs: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            location: location.map(str::to_string),
+            tenant_id: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_selector_matching() {
+        let selector = GroupSelector {
+            tags: vec!["light".to_string()],
+            location: Some("hq/floor-2".to_string()),
+            device_type: None,
+        };
+        let lamp = device("lamp-1", "switch", &["Light"], Some("hq/floor-2/room-201"));
+        let lamp_other_floor = device("lamp-2", "switch", &["light"], Some("hq/floor-20"));
+        let fan = device("fan-1", "switch", &["fan"], Some("hq/floor-2"));
+
+        assert!(selector_matches(&selector, &lamp));
+        assert!(!selector_matches(&selector, &lamp_other_floor));
+        assert!(!selector_matches(&selector, &fan));
+        assert!(!selector_matches(&GroupSelector::default(), &lamp));
+    }
+
+    #[test]
+    fn test_static_and_dynamic_membership() {
+        let group = DeviceGroup {
+            id: "g".to_string(),
+            name: "Switches".to_string(),
+            members: vec!["pump-1".to_string()],
+            selector: Some(GroupSelector {
+                device_type: Some("switch".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(is_member(&group, &device("pump-1", "pump", &[], None)));
+        assert!(is_member(&group, &device("sw-1", "switch", &[], None)));
+        assert!(!is_member(&group, &device("pump-2", "pump", &[], None)));
+
+        let mut foreign = device("sw-2", "switch", &[], None);
+        foreign.tenant_id = neomind_core::tenant::TenantId::new("acme").unwrap();
+        assert!(!is_member(&group, &foreign));
+    }
+}
@@ -0,0 +1,458 @@
+//! Idempotency keys for device commands.
+//!
+//! A client that retries a command after an HTTP timeout cannot tell whether
+//! the first attempt reached the device. When a submission carries an
+//! idempotency key, [`DeviceService`](crate::DeviceService) records the key
+//! and the command's outcome here; a retry with the same key within the TTL
+//! gets that outcome back instead of actuating the device a second time.
+//! Keys belong to the tenant or principal that submitted them, so two
+//! clients picking the same key never see each other's submissions. Keys
+//! are persisted with the device registry so retries across a restart are
+//! deduplicated too. The TTL comes from the runtime config service
+//! (`devices.command_idempotency_ttl_secs`), so changing it applies to the
+//! next key.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use neomind_core::config::device_env_vars;
+use neomind_storage::device_registry::CommandIdempotencyRecord;
+use neomind_storage::DeviceRegistryStore;
+use tokio::sync::RwLock;
+
+use crate::mdl::{DeviceError, MetricValue};
+
+/// Key TTL in seconds.
+pub const COMMAND_IDEMPOTENCY_TTL_ENV: &str = device_env_vars::COMMAND_IDEMPOTENCY_TTL_SECS;
+
+/// Longest accepted idempotency key.
+pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Recorded for keys whose first submission never finished (the request
+/// was cancelled, or the server restarted).
+const INTERRUPTED_ERROR: &str =
+    "Command submission was interrupted before it finished; its outcome is unknown";
+
+/// Idempotency key settings.
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    /// How long a key deduplicates retries (seconds).
+    pub ttl_secs: u64,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: neomind_core::config::device::DEFAULT_COMMAND_IDEMPOTENCY_TTL_SECS,
+        }
+    }
+}
+
+impl IdempotencyConfig {
+    /// The TTL currently set in the config service, where
+    /// `NEOMIND_COMMAND_IDEMPOTENCY_TTL_SECS` overrides the stored value.
+    pub fn from_env() -> Self {
+        Self {
+            ttl_secs: device_env_vars::command_idempotency_ttl_secs(),
+        }
+    }
+
+    /// Expiry of a key reserved at `now`. An oversized TTL never expires
+    /// instead of overflowing.
+    fn expires_at(&self, now: i64) -> i64 {
+        now.saturating_add(i64::try_from(self.ttl_secs).unwrap_or(i64::MAX))
+    }
+}
+
+/// Outcome of a command submitted with an idempotency key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandSubmission {
+    /// Command history ID; `None` if the command was rejected before it
+    /// was recorded (unknown device, invalid parameters, ...)
+    pub command_id: Option<String>,
+    /// Value returned by the command, or the error it failed with
+    pub outcome: Result<Option<MetricValue>, String>,
+    /// Whether the outcome was recorded by an earlier submission
+    pub replayed: bool,
+}
+
+impl CommandSubmission {
+    fn from_record(record: &CommandIdempotencyRecord) -> Self {
+        let outcome = match &record.error {
+            Some(error) => Err(error.clone()),
+            None => Ok(record
+                .result
+                .clone()
+                .and_then(|v| serde_json::from_value(v).ok())),
+        };
+        Self {
+            command_id: record.command_id.clone(),
+            outcome,
+            replayed: true,
+        }
+    }
+}
+
+/// What [`IdempotencyKeys::reserve`] found for a key.
+#[derive(Debug)]
+pub enum Reservation {
+    /// The key is new and now in flight; the caller runs the command and
+    /// completes the key. Holds the record to persist.
+    New(Box<CommandIdempotencyRecord>),
+    /// An earlier submission finished; its outcome
+    Completed(CommandSubmission),
+    /// An earlier submission is still running
+    InFlight,
+}
+
+/// Idempotency keys seen within the TTL, completed or in flight, by
+/// [`CommandIdempotencyRecord::id`].
+#[derive(Debug, Default)]
+pub struct IdempotencyKeys {
+    /// Fixed settings; `None` follows the config service.
+    config: Option<IdempotencyConfig>,
+    records: HashMap<String, CommandIdempotencyRecord>,
+}
+
+impl IdempotencyKeys {
+    pub fn new(config: IdempotencyConfig) -> Self {
+        Self {
+            config: Some(config),
+            records: HashMap::new(),
+        }
+    }
+
+    pub fn set_config(&mut self, config: IdempotencyConfig) {
+        self.config = Some(config);
+    }
+
+    fn config(&self) -> IdempotencyConfig {
+        self.config
+            .clone()
+            .unwrap_or_else(IdempotencyConfig::from_env)
+    }
+
+    /// Claim `owner`'s `key` for a command at `now`. A key can only be reused
+    /// for the same device, command and parameters.
+    pub fn reserve(
+        &mut self,
+        owner: &str,
+        key: &str,
+        device_id: &str,
+        command_name: &str,
+        params: &HashMap<String, serde_json::Value>,
+        now: i64,
+    ) -> Result<Reservation, DeviceError> {
+        if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+            return Err(DeviceError::InvalidParameter(format!(
+                "Idempotency key must be 1 to {} bytes long",
+                MAX_IDEMPOTENCY_KEY_LEN
+            )));
+        }
+
+        let id = CommandIdempotencyRecord::record_id(owner, key);
+        if let Some(record) = self.records.get(&id).filter(|r| r.expires_at > now) {
+            if record.device_id != device_id
+                || record.command_name != command_name
+                || &record.parameters != params
+            {
+                return Err(DeviceError::InvalidParameter(format!(
+                    "Idempotency key '{}' was already used for a different command",
+                    key
+                )));
+            }
+            return Ok(match record.completed_at {
+                Some(_) => Reservation::Completed(CommandSubmission::from_record(record)),
+                None => Reservation::InFlight,
+            });
+        }
+
+        let record = CommandIdempotencyRecord {
+            owner: owner.to_string(),
+            key: key.to_string(),
+            device_id: device_id.to_string(),
+            command_name: command_name.to_string(),
+            parameters: params.clone(),
+            command_id: None,
+            result: None,
+            error: None,
+            created_at: now,
+            completed_at: None,
+            expires_at: self.config().expires_at(now),
+        };
+        self.records.insert(id, record.clone());
+        Ok(Reservation::New(Box::new(record)))
+    }
+
+    /// Record the outcome of the command that reserved `owner`'s `key`.
+    /// Returns the record to persist, or `None` if the key is no longer
+    /// tracked.
+    pub fn complete(
+        &mut self,
+        owner: &str,
+        key: &str,
+        command_id: Option<String>,
+        outcome: &Result<Option<MetricValue>, String>,
+        now: i64,
+    ) -> Option<CommandIdempotencyRecord> {
+        let id = CommandIdempotencyRecord::record_id(owner, key);
+        let record = self.records.get_mut(&id)?;
+        record.command_id = command_id;
+        match outcome {
+            Ok(value) => {
+                record.result = value.as_ref().and_then(|v| serde_json::to_value(v).ok());
+                record.error = None;
+            }
+            Err(error) => {
+                record.result = None;
+                record.error = Some(error.clone());
+            }
+        }
+        record.completed_at = Some(now);
+        Some(record.clone())
+    }
+
+    /// Complete a key whose submission was abandoned before the command
+    /// finished. The key is not released, since the device may already have
+    /// acted on the command; retries get an error instead.
+    pub fn abandon(
+        &mut self,
+        owner: &str,
+        key: &str,
+        now: i64,
+    ) -> Option<CommandIdempotencyRecord> {
+        let outcome = Err(INTERRUPTED_ERROR.to_string());
+        self.complete(owner, key, None, &outcome, now)
+    }
+
+    /// Restore persisted keys, dropping those expired at `now`. Keys still
+    /// in flight belonged to submissions cut short by a restart and are
+    /// abandoned. Returns the abandoned records, to persist.
+    pub fn restore(
+        &mut self,
+        records: Vec<CommandIdempotencyRecord>,
+        now: i64,
+    ) -> Vec<CommandIdempotencyRecord> {
+        let mut interrupted = Vec::new();
+        for mut record in records {
+            if record.expires_at <= now {
+                continue;
+            }
+            if record.completed_at.is_none() {
+                record.error = Some(INTERRUPTED_ERROR.to_string());
+                record.completed_at = Some(now);
+                interrupted.push(record.clone());
+            }
+            self.records.insert(record.id(), record);
+        }
+        interrupted
+    }
+
+    /// Remove the keys whose TTL has passed at `now` and return their record
+    /// IDs.
+    pub fn expire(&mut self, now: i64) -> Vec<String> {
+        let mut expired = Vec::new();
+        self.records.retain(|id, record| {
+            if record.expires_at <= now {
+                expired.push(id.clone());
+                false
+            } else {
+                true
+            }
+        });
+        expired
+    }
+
+    /// Number of tracked keys.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+/// Abandons a reserved key if its submission is dropped before the command
+/// finishes, e.g. when the HTTP request is cancelled mid-command.
+pub(crate) struct PendingKey {
+    keys: Arc<RwLock<IdempotencyKeys>>,
+    store: Option<Arc<DeviceRegistryStore>>,
+    owner: String,
+    key: Option<String>,
+}
+
+impl PendingKey {
+    pub(crate) fn new(
+        keys: Arc<RwLock<IdempotencyKeys>>,
+        store: Option<Arc<DeviceRegistryStore>>,
+        owner: &str,
+        key: &str,
+    ) -> Self {
+        Self {
+            keys,
+            store,
+            owner: owner.to_string(),
+            key: Some(key.to_string()),
+        }
+    }
+
+    /// The command finished; the caller completes the key itself.
+    pub(crate) fn disarm(mut self) {
+        self.key = None;
+    }
+}
+
+impl Drop for PendingKey {
+    fn drop(&mut self) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        // Drop can't await the lock
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let keys = self.keys.clone();
+        let store = self.store.take();
+        let owner = std::mem::take(&mut self.owner);
+        runtime.spawn(async move {
+            let now = chrono::Utc::now().timestamp();
+            let Some(record) = keys.write().await.abandon(&owner, &key, now) else {
+                return;
+            };
+            if let Some(store) = store {
+                if let Err(e) = store.save_idempotency_record(&record) {
+                    tracing::warn!("Failed to save idempotency key {}: {}", key, e);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(value: i64) -> HashMap<String, serde_json::Value> {
+        HashMap::from([("position".to_string(), serde_json::json!(value))])
+    }
+
+    fn open(keys: &mut IdempotencyKeys, key: &str, position: i64, now: i64) -> Reservation {
+        keys.reserve("tenant:acme", key, "valve1", "open", &params(position), now)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reserve_complete_replay() {
+        let mut keys = IdempotencyKeys::new(IdempotencyConfig { ttl_secs: 60 });
+        let reserved = open(&mut keys, "k1", 50, 100);
+        assert!(matches!(reserved, Reservation::New(r) if r.expires_at == 160));
+        assert!(matches!(open(&mut keys, "k1", 50, 101), Reservation::InFlight));
+
+        let outcome = Ok(Some(MetricValue::Integer(50)));
+        let command_id = Some("cmd_1".to_string());
+        assert!(keys
+            .complete("tenant:acme", "k1", command_id, &outcome, 102)
+            .is_some());
+        match open(&mut keys, "k1", 50, 103) {
+            Reservation::Completed(submission) => {
+                assert_eq!(submission.command_id.as_deref(), Some("cmd_1"));
+                assert_eq!(submission.outcome, outcome);
+                assert!(submission.replayed);
+            }
+            other => panic!("expected a replay, got {:?}", other),
+        }
+
+        // Same key, different request
+        let other_device = keys.reserve("tenant:acme", "k1", "valve2", "open", &params(50), 104);
+        let Err(DeviceError::InvalidParameter(msg)) = other_device else {
+            panic!("expected a conflict");
+        };
+        assert!(!msg.contains("valve1"));
+        let other_params = keys.reserve("tenant:acme", "k1", "valve1", "open", &params(80), 104);
+        assert!(other_params.is_err());
+        assert!(keys
+            .reserve("tenant:acme", "", "valve1", "open", &params(50), 104)
+            .is_err());
+
+        // Another owner's key of the same name is independent
+        let other_owner = keys.reserve("tenant:globex", "k1", "valve2", "open", &params(50), 104);
+        assert!(matches!(other_owner, Ok(Reservation::New(_))));
+
+        // After the TTL the key is free again
+        assert!(matches!(open(&mut keys, "k1", 80, 160), Reservation::New(_)));
+    }
+
+    #[tokio::test]
+    async fn test_pending_key_abandoned_on_drop() {
+        let now = chrono::Utc::now().timestamp();
+        let keys = Arc::new(RwLock::new(IdempotencyKeys::default()));
+        open(&mut *keys.write().await, "k1", 1, now);
+        open(&mut *keys.write().await, "k2", 1, now);
+
+        PendingKey::new(keys.clone(), None, "tenant:acme", "k1").disarm();
+        drop(PendingKey::new(keys.clone(), None, "tenant:acme", "k2"));
+        tokio::task::yield_now().await;
+
+        let mut keys = keys.write().await;
+        assert!(matches!(open(&mut keys, "k1", 1, now), Reservation::InFlight));
+        match open(&mut keys, "k2", 1, now) {
+            Reservation::Completed(submission) => assert!(submission.outcome.is_err()),
+            other => panic!("expected a replay, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_restore_and_expire() {
+        let mut keys = IdempotencyKeys::new(IdempotencyConfig::default());
+        let Reservation::New(in_flight) = open(&mut keys, "k1", 1, 100) else {
+            panic!("k1 is new");
+        };
+        let Reservation::New(mut done) = open(&mut keys, "k2", 2, 100) else {
+            panic!("k2 is new");
+        };
+        done.completed_at = Some(100);
+        done.expires_at = 200;
+        let mut stale = done.clone();
+        stale.key = "k3".to_string();
+        stale.expires_at = 150;
+
+        let mut restored = IdempotencyKeys::new(IdempotencyConfig::default());
+        let interrupted = restored.restore(vec![*in_flight, *done, *stale], 150);
+        assert_eq!(restored.len(), 2);
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].key, "k1");
+        match open(&mut restored, "k1", 1, 151) {
+            Reservation::Completed(submission) => assert!(submission.outcome.is_err()),
+            other => panic!("expected a replay, got {:?}", other),
+        }
+
+        assert_eq!(restored.expire(200), vec!["tenant:acme\0k2".to_string()]);
+        assert_eq!(restored.len(), 1);
+    }
+
+    #[test]
+    fn test_ttl_follows_config_service() {
+        use neomind_core::config::service::{global, keys as config_keys};
+        use std::collections::BTreeMap;
+
+        let mut keys = IdempotencyKeys::new(IdempotencyConfig { ttl_secs: u64::MAX });
+        let reserved = open(&mut keys, "k1", 1, 100);
+        assert!(matches!(reserved, Reservation::New(r) if r.expires_at == i64::MAX));
+
+        let set_ttl = |ttl: serde_json::Value| {
+            let changes = BTreeMap::from([(
+                config_keys::DEVICES_COMMAND_IDEMPOTENCY_TTL_SECS.to_string(),
+                ttl,
+            )]);
+            global().update(changes, "test").unwrap();
+        };
+        let mut keys = IdempotencyKeys::default();
+        set_ttl(serde_json::json!(30));
+        let reserved = open(&mut keys, "k1", 1, 100);
+        assert!(matches!(reserved, Reservation::New(r) if r.expires_at == 130));
+        set_ttl(serde_json::Value::Null);
+        let reserved = open(&mut keys, "k2", 1, 100);
+        assert!(matches!(reserved, Reservation::New(r) if r.expires_at == 86_500));
+    }
+}
@@ -15,7 +15,7 @@
 //! The device management system uses a simplified architecture:
 //! - **DeviceRegistry**: Storage for device configurations and type templates
 //! - **DeviceService**: Unified interface for all device operations
-//! - **DeviceAdapter**: Protocol-specific adapter interface (MQTT)
+//! - **DeviceAdapter**: Protocol-specific adapter interface (MQTT, Webhook, Modbus TCP)
 //! - **DeviceAdapterPluginRegistry**: Plugin system for managing adapters
 //!
 //! Devices are configured using `DeviceConfig` and accessed through `DeviceService`.
@@ -32,6 +32,30 @@ pub mod telemetry;
 pub mod registry;
 pub mod service;
 
+// Batched, optionally transactional command execution
+pub mod command_group;
+
+// Command queueing for offline devices
+pub mod store_forward;
+
+// Deduplication of retried command submissions
+pub mod idempotency;
+
+// Commands scheduled for a later time or on a recurrence
+pub mod scheduled;
+
+// Load shedding for telemetry ingestion
+pub mod qos;
+
+// Static and tag/location-based device groups
+pub mod group;
+
+// Devices whose metrics are computed from other devices
+pub mod virtual_device;
+
+// Past device state reconstruction ("as-of" queries)
+pub mod state_history;
+
 // Protocol mapping layer - decouples MDL from protocol implementations
 pub mod protocol;
 
@@ -44,21 +68,38 @@ pub mod adapters;
 // Unified data extraction for all adapters
 pub mod unified_extractor;
 
+// Declarative codec for binary device payloads
+pub mod binary_codec;
+
 #[cfg(feature = "embedded-broker")]
 pub mod embedded_broker;
 
+// Fault injection for resilience tests
+#[cfg(feature = "chaos")]
+pub mod chaos;
+
 // Re-exports (only types used externally via crate-root shortcut path)
 pub use adapter::{AdapterResult, ConnectionStatus, DeviceAdapter, DeviceEvent};
+pub use command_group::{CommandGroup, CommandGroupResult, GroupOrdering, GroupStatus};
+pub use group::{DeviceGroup, GroupSelector};
+pub use idempotency::{CommandSubmission, IdempotencyConfig};
 pub use mdl::{DeviceError, MetricDataType, MetricValue};
 pub use mdl_format::{CommandDefinition, MetricDefinition as MdlMetricDefinition};
 pub use registry::{
     ConnectionConfig, DeviceConfig, DeviceRegistry, DeviceTypeMode, DeviceTypeTemplate,
 };
 pub use service::{CommandStatus, DeviceService, ExtensionCommandRouterFn};
-pub use telemetry::{DataPoint, TimeSeriesStorage};
+pub use state_history::{DeviceStateSnapshot, DeviceStateStore, StatusSource};
+pub use qos::{IngestQos, IngestQosConfig, SheddingReport};
+pub use scheduled::MAX_SCHEDULED_PER_DEVICE;
+pub use store_forward::{QueuedCommand, StoreForwardConfig};
+pub use telemetry::{DataPoint, HotCacheConfig, HotCacheStats, MetricCache, TimeSeriesStorage};
+pub use virtual_device::{VirtualAggregation, VirtualDeviceSpec, VirtualMetricSpec};
 
 #[cfg(feature = "embedded-broker")]
-pub use embedded_broker::{EmbeddedBroker, EmbeddedBrokerConfig, TopicResolverFn};
+pub use embedded_broker::{
+    device_topic_allowed, AclCheckerFn, EmbeddedBroker, EmbeddedBrokerConfig, TopicResolverFn,
+};
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
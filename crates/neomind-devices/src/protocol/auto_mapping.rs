@@ -0,0 +1,373 @@
+//! Automatic device mapping for self-describing MQTT ecosystems.
+//!
+//! Zigbee2MQTT and Home Assistant MQTT discovery both publish machine-readable
+//! descriptions of the devices behind them. This module turns those
+//! descriptions into NeoMind device types ([`DeviceTypeDefinition`]), topic
+//! mappings ([`MqttMapping`]) and device configurations, so common smart-home
+//! gear is onboarded without hand-written MDL.
+//!
+//! | Source | Topic | Module |
+//! |--------|-------|--------|
+//! | Zigbee2MQTT device list (retained) | `{base}/bridge/devices` | [`super::zigbee2mqtt`] |
+//! | Zigbee2MQTT interview / leave | `{base}/bridge/event` | [`super::zigbee2mqtt`] |
+//! | HA discovery | `{prefix}/{component}/[{node}/]{object}/config` | [`super::hass_discovery`] |
+//!
+//! The MQTT adapter feeds every message on these topics to
+//! [`AutoMappingConfig::parse`] and registers the resulting devices, so the
+//! registry follows devices as they join.
+
+use serde::{Deserialize, Serialize};
+
+use super::mqtt_mapping::{MqttMapping, MqttMappingBuilder, MqttValueParser};
+use super::{hass_discovery, zigbee2mqtt};
+use crate::mdl_format::DeviceTypeDefinition;
+use crate::registry::{DeviceConfig, DeviceTypeMode, DeviceTypeTemplate};
+
+/// Which device-description topics the MQTT adapter maps automatically.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoMappingConfig {
+    /// Zigbee2MQTT base topic (`None` disables Zigbee2MQTT mapping)
+    #[serde(default = "default_zigbee2mqtt_base_topic")]
+    pub zigbee2mqtt_base_topic: Option<String>,
+    /// Home Assistant discovery prefix (`None` disables discovery mapping)
+    #[serde(default = "default_hass_discovery_prefix")]
+    pub hass_discovery_prefix: Option<String>,
+}
+
+fn default_zigbee2mqtt_base_topic() -> Option<String> {
+    Some("zigbee2mqtt".to_string())
+}
+
+fn default_hass_discovery_prefix() -> Option<String> {
+    Some("homeassistant".to_string())
+}
+
+impl Default for AutoMappingConfig {
+    fn default() -> Self {
+        Self {
+            zigbee2mqtt_base_topic: default_zigbee2mqtt_base_topic(),
+            hass_discovery_prefix: default_hass_discovery_prefix(),
+        }
+    }
+}
+
+/// Where a mapped device was described.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingSource {
+    Zigbee2Mqtt,
+    HassDiscovery,
+}
+
+/// A device generated from its ecosystem's description.
+#[derive(Debug, Clone)]
+pub struct MappedDevice {
+    pub source: MappingSource,
+    /// Device type, shared by all devices of the same model
+    pub definition: DeviceTypeDefinition,
+    /// Device configuration (MQTT telemetry and command topics)
+    pub device: DeviceConfig,
+}
+
+/// Change requested by a device-description message.
+#[derive(Debug, Clone)]
+pub enum MappingUpdate {
+    /// Register these devices, or update them when already registered
+    Upsert(Vec<MappedDevice>),
+    /// The device left the network
+    Left { device_id: String },
+}
+
+impl AutoMappingConfig {
+    /// Whether `topic` carries device descriptions rather than telemetry.
+    pub fn is_description_topic(&self, topic: &str) -> bool {
+        let under = |prefix: &Option<String>, rest: &str| {
+            prefix
+                .as_deref()
+                .and_then(|p| topic.strip_prefix(p))
+                .is_some_and(|t| t.starts_with(rest))
+        };
+        under(&self.zigbee2mqtt_base_topic, "/bridge/")
+            || (under(&self.hass_discovery_prefix, "/") && topic.ends_with("/config"))
+    }
+
+    /// Topic filters carrying the descriptions this config maps.
+    pub fn subscription_topics(&self) -> Vec<String> {
+        let mut topics = Vec::new();
+        if let Some(base) = &self.zigbee2mqtt_base_topic {
+            topics.push(format!("{}/bridge/devices", base));
+            topics.push(format!("{}/bridge/event", base));
+        }
+        if let Some(prefix) = &self.hass_discovery_prefix {
+            topics.push(format!("{}/+/+/config", prefix));
+            topics.push(format!("{}/+/+/+/config", prefix));
+        }
+        topics
+    }
+
+    /// Interpret a message on a description topic.
+    ///
+    /// Returns `None` for other topics and for messages that describe
+    /// nothing mappable (bridge state, unsupported devices, removed
+    /// discovery configs).
+    pub fn parse(&self, topic: &str, payload: &[u8]) -> Option<MappingUpdate> {
+        if let Some(base) = self.zigbee2mqtt_base_topic.as_deref() {
+            let bridge_topic = topic
+                .strip_prefix(base)
+                .and_then(|t| t.strip_prefix("/bridge/"));
+            match bridge_topic {
+                Some("devices") => {
+                    let devices = zigbee2mqtt::map_bridge_devices(base, payload)?;
+                    return Some(MappingUpdate::Upsert(devices));
+                }
+                Some("event") => return zigbee2mqtt::map_bridge_event(base, payload),
+                Some(_) => return None,
+                None => {}
+            }
+        }
+
+        let prefix = self.hass_discovery_prefix.as_deref()?;
+        let skip_zigbee2mqtt = self.zigbee2mqtt_base_topic.is_some();
+        hass_discovery::map_config(prefix, topic, payload, skip_zigbee2mqtt)
+            .map(|device| MappingUpdate::Upsert(vec![device]))
+    }
+}
+
+impl MappedDevice {
+    /// Device type template for the registry.
+    pub fn template(&self) -> DeviceTypeTemplate {
+        let definition = &self.definition;
+        let mut template = DeviceTypeTemplate::new(&definition.device_type, &definition.name)
+            .with_description(&definition.description);
+        template.categories = definition.categories.clone();
+        template.mode = DeviceTypeMode::Full;
+        template.metrics = definition.uplink.metrics.clone();
+        template.commands = definition.downlink.commands.clone();
+        template
+    }
+
+    /// MQTT mapping with this device's topics: every metric is a JSON path in
+    /// the telemetry payload, every command a template on the command topic.
+    pub fn mqtt_mapping(&self) -> MqttMapping {
+        let connection = &self.device.connection_config;
+        let telemetry_topic = connection.telemetry_topic.clone().unwrap_or_default();
+        let mut builder = MqttMappingBuilder::new(&self.definition.device_type);
+        for metric in &self.definition.uplink.metrics {
+            builder = builder.add_metric_with_parser(
+                &metric.name,
+                &telemetry_topic,
+                MqttValueParser::json_path(format!("$.{}", metric.name)),
+            );
+        }
+        if let Some(command_topic) = &connection.command_topic {
+            for command in &self.definition.downlink.commands {
+                builder = builder.add_command_with_payload(
+                    &command.name,
+                    command_topic,
+                    &command.payload_template,
+                );
+            }
+        }
+        builder.build()
+    }
+
+    /// Whether this description may update `existing`.
+    ///
+    /// Zigbee2MQTT is authoritative for its devices (a changed topic is a
+    /// rename). A registered device has one telemetry and one command topic,
+    /// so HA entities that use different topics are not merged into it.
+    pub fn applies_to(&self, existing: &DeviceConfig) -> bool {
+        let compatible = |current: &Option<String>, update: &Option<String>| {
+            current.is_none() || update.is_none() || current == update
+        };
+        let current = &existing.connection_config;
+        let update = &self.device.connection_config;
+        self.source == MappingSource::Zigbee2Mqtt
+            || (compatible(&current.telemetry_topic, &update.telemetry_topic)
+                && compatible(&current.command_topic, &update.command_topic))
+    }
+
+    /// `existing` updated with this description's name and topics, or
+    /// `None` when nothing changed. User-set fields are kept.
+    pub fn merge_device(&self, existing: &DeviceConfig) -> Option<DeviceConfig> {
+        let mut merged = existing.clone();
+        let update = &self.device.connection_config;
+        let connection = &mut merged.connection_config;
+        if self.source == MappingSource::Zigbee2Mqtt {
+            merged.name.clone_from(&self.device.name);
+            connection.telemetry_topic.clone_from(&update.telemetry_topic);
+            connection.command_topic.clone_from(&update.command_topic);
+        } else {
+            if connection.telemetry_topic.is_none() {
+                connection.telemetry_topic.clone_from(&update.telemetry_topic);
+            }
+            if connection.command_topic.is_none() {
+                connection.command_topic.clone_from(&update.command_topic);
+            }
+        }
+
+        let changed = merged.name != existing.name
+            || merged.connection_config.telemetry_topic
+                != existing.connection_config.telemetry_topic
+            || merged.connection_config.command_topic != existing.connection_config.command_topic;
+        changed.then_some(merged)
+    }
+}
+
+/// `existing` extended with the metrics and commands of `update` it lacks,
+/// or `None` when it already has them all.
+pub fn merge_template(
+    existing: &DeviceTypeTemplate,
+    update: &DeviceTypeTemplate,
+) -> Option<DeviceTypeTemplate> {
+    let mut merged = existing.clone();
+    for metric in &update.metrics {
+        if !merged.metrics.iter().any(|m| m.name == metric.name) {
+            merged.metrics.push(metric.clone());
+        }
+    }
+    for command in &update.commands {
+        if !merged.commands.iter().any(|c| c.name == command.name) {
+            merged.commands.push(command.clone());
+        }
+    }
+    for category in &update.categories {
+        if !merged.categories.contains(category) {
+            merged.categories.push(category.clone());
+        }
+    }
+
+    let changed = merged.metrics.len() != existing.metrics.len()
+        || merged.commands.len() != existing.commands.len()
+        || merged.categories.len() != existing.categories.len();
+    changed.then_some(merged)
+}
+
+/// Lowercase `text` and collapse everything but ASCII letters and digits into
+/// single underscores, for device type and device IDs.
+pub(crate) fn sanitize_id(text: &str) -> String {
+    let mut id = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            id.push(c.to_ascii_lowercase());
+        } else if !id.is_empty() && !id.ends_with('_') {
+            id.push('_');
+        }
+    }
+    id.trim_end_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mdl::MetricValue;
+    use crate::protocol::ProtocolMapping;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn bridge_devices() -> Vec<u8> {
+        json!([{
+            "ieee_address": "0x00158d0001a2b3c4",
+            "friendly_name": "kitchen/plug",
+            "type": "Router",
+            "interview_completed": true,
+            "definition": {
+                "model": "ZNCZ04LM",
+                "vendor": "Aqara",
+                "description": "Smart plug EU",
+                "exposes": [
+                    { "type": "switch", "features": [{
+                        "type": "binary", "name": "state", "property": "state", "access": 7,
+                        "value_on": "ON", "value_off": "OFF"
+                    }]},
+                    { "type": "numeric", "name": "power", "property": "power", "access": 1,
+                      "unit": "W" }
+                ]
+            }
+        }])
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_description_topics() {
+        let config = AutoMappingConfig::default();
+        assert!(config.is_description_topic("zigbee2mqtt/bridge/devices"));
+        assert!(config.is_description_topic("homeassistant/sensor/node/temp/config"));
+        assert!(!config.is_description_topic("zigbee2mqtt/kitchen/plug"));
+        assert!(!config.is_description_topic("homeassistant/status"));
+        assert_eq!(config.subscription_topics().len(), 4);
+
+        let disabled = AutoMappingConfig {
+            zigbee2mqtt_base_topic: None,
+            hass_discovery_prefix: None,
+        };
+        assert!(!disabled.is_description_topic("zigbee2mqtt/bridge/devices"));
+        assert!(disabled
+            .parse("zigbee2mqtt/bridge/devices", &bridge_devices())
+            .is_none());
+    }
+
+    #[test]
+    fn test_mqtt_mapping_from_mapped_device() {
+        let config = AutoMappingConfig::default();
+        let Some(MappingUpdate::Upsert(devices)) =
+            config.parse("zigbee2mqtt/bridge/devices", &bridge_devices())
+        else {
+            panic!("expected an upsert");
+        };
+        let mapping = devices[0].mqtt_mapping();
+
+        assert_eq!(mapping.device_type(), "z2m_aqara_zncz04lm");
+        assert_eq!(
+            mapping.metric_topic("0x00158d0001a2b3c4", "power").as_deref(),
+            Some("zigbee2mqtt/kitchen/plug")
+        );
+        assert_eq!(
+            mapping.parse_metric("power", br#"{"power": 12.5, "state": "ON"}"#).unwrap(),
+            MetricValue::Float(12.5)
+        );
+
+        let params = HashMap::from([("state".to_string(), MetricValue::String("OFF".into()))]);
+        let payload = mapping.serialize_command("set_state", &params).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&payload).unwrap(),
+            json!({ "state": "OFF" })
+        );
+    }
+
+    #[test]
+    fn test_merge_template_and_device() {
+        let config = AutoMappingConfig::default();
+        let Some(MappingUpdate::Upsert(devices)) =
+            config.parse("zigbee2mqtt/bridge/devices", &bridge_devices())
+        else {
+            panic!("expected an upsert");
+        };
+        let mapped = &devices[0];
+        let template = mapped.template();
+        assert!(merge_template(&template, &template).is_none());
+
+        let mut partial = template.clone();
+        partial.metrics.retain(|m| m.name != "power");
+        let merged = merge_template(&partial, &template).unwrap();
+        assert_eq!(merged.metrics.len(), template.metrics.len());
+
+        assert!(mapped.merge_device(&mapped.device).is_none());
+        let mut renamed = mapped.device.clone();
+        renamed.connection_config.telemetry_topic = Some("zigbee2mqtt/old_name".into());
+        renamed.tags = vec!["kitchen".into()];
+        let merged = mapped.merge_device(&renamed).unwrap();
+        assert_eq!(
+            merged.connection_config.telemetry_topic.as_deref(),
+            Some("zigbee2mqtt/kitchen/plug")
+        );
+        assert_eq!(merged.tags, vec!["kitchen".to_string()]);
+    }
+
+    #[test]
+    fn test_sanitize_id() {
+        assert_eq!(sanitize_id("Aqara ZNCZ04LM"), "aqara_zncz04lm");
+        assert_eq!(sanitize_id("  IKEA of Sweden / E1743 "), "ikea_of_sweden_e1743");
+        assert_eq!(sanitize_id("0x00158d0001a2b3c4"), "0x00158d0001a2b3c4");
+    }
+}
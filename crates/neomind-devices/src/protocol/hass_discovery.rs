@@ -0,0 +1,561 @@
+//! Home Assistant MQTT discovery payloads.
+//!
+//! Devices that speak HA discovery (Tasmota, ESPHome, Shelly, ...) publish one
+//! retained config per entity on
+//! `{prefix}/{component}/[{node_id}/]{object_id}/config`. Entities of the same
+//! physical device share a `device.identifiers` entry and are mapped onto a
+//! single NeoMind device:
+//!
+//! | Component | Metric | Command |
+//! |-----------|--------|---------|
+//! | `sensor` | Float with a unit, else String | - |
+//! | `binary_sensor` | Boolean or on/off enum | - |
+//! | `switch`, `light`, `fan`, `lock` | Boolean or on/off enum | `set_{object_id}` |
+//! | `number` | Float | `set_{object_id}` |
+//! | `select` | enum of `options` | `set_{object_id}` |
+//! | `button` | - | `press_{object_id}` |
+//!
+//! A metric needs a JSON state payload: its path is taken from
+//! `value_template` (`{{ value_json.a.b }}`). Entities with raw state payloads
+//! or templates beyond a path lookup are skipped.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use super::auto_mapping::{sanitize_id, MappedDevice, MappingSource};
+use crate::mdl::{MetricDataType, MetricValue};
+use crate::mdl_format::{
+    CommandDefinition, DeviceTypeDefinition, DeviceTypeMode, DownlinkConfig, MetricDefinition,
+    ParameterDefinition, UplinkConfig,
+};
+use crate::registry::{ConnectionConfig, DeviceConfig};
+
+/// Abbreviated config keys used by compact discovery payloads.
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("cmd_t", "command_topic"),
+    ("cmd_tpl", "command_template"),
+    ("dev", "device"),
+    ("dev_cla", "device_class"),
+    ("ops", "options"),
+    ("pl_lock", "payload_lock"),
+    ("pl_off", "payload_off"),
+    ("pl_on", "payload_on"),
+    ("pl_prs", "payload_press"),
+    ("pl_unlk", "payload_unlock"),
+    ("stat_cla", "state_class"),
+    ("stat_locked", "state_locked"),
+    ("stat_t", "state_topic"),
+    ("stat_unlocked", "state_unlocked"),
+    ("stat_val_tpl", "state_value_template"),
+    ("uniq_id", "unique_id"),
+    ("unit_of_meas", "unit_of_measurement"),
+    ("val_tpl", "value_template"),
+];
+
+/// Abbreviated keys of the `device` object.
+const DEVICE_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("ids", "identifiers"),
+    ("mdl", "model"),
+    ("mf", "manufacturer"),
+    ("sw", "sw_version"),
+];
+
+/// Map the discovery config published on `topic`.
+///
+/// Returns `None` for malformed topics, removed entities (empty payload),
+/// unsupported components and entities with nothing mappable. With
+/// `skip_zigbee2mqtt`, entities Zigbee2MQTT announces are left to its own
+/// device list, which describes them more precisely.
+pub fn map_config(
+    prefix: &str,
+    topic: &str,
+    payload: &[u8],
+    skip_zigbee2mqtt: bool,
+) -> Option<MappedDevice> {
+    let path = topic.strip_prefix(prefix)?.strip_prefix('/')?;
+    let parts: Vec<&str> = path.strip_suffix("/config")?.split('/').collect();
+    let (component, node_id, object_id) = match parts[..] {
+        [component, object_id] => (component, None, object_id),
+        [component, node_id, object_id] => (component, Some(node_id), object_id),
+        _ => return None,
+    };
+    if payload.is_empty() {
+        return None;
+    }
+    let Value::Object(config) = serde_json::from_slice(payload).ok()? else {
+        return None;
+    };
+    let entity = Entity {
+        component,
+        object_id,
+        config: expand(config),
+    };
+
+    let identifier = entity
+        .device_text("identifiers")
+        .or_else(|| entity.text("unique_id"))
+        .unwrap_or_else(|| match node_id {
+            Some(node_id) => format!("{}_{}", node_id, object_id),
+            None => object_id.to_string(),
+        });
+    if skip_zigbee2mqtt && identifier.starts_with("zigbee2mqtt_") {
+        return None;
+    }
+
+    let metric = entity.metric();
+    let command = entity.command();
+    if metric.is_none() && command.is_none() {
+        return None;
+    }
+
+    let model = match (entity.device_text("manufacturer"), entity.device_text("model")) {
+        (Some(manufacturer), Some(model)) => Some(format!("{} {}", manufacturer, model)),
+        (None, Some(model)) => Some(model),
+        _ => None,
+    };
+    let device_type = format!("hass_{}", sanitize_id(model.as_deref().unwrap_or(&identifier)));
+    let name = entity
+        .device_text("name")
+        .or_else(|| entity.text("name"))
+        .unwrap_or_else(|| identifier.clone());
+
+    let definition = DeviceTypeDefinition {
+        device_type: device_type.clone(),
+        name: model.clone().unwrap_or_else(|| name.clone()),
+        description: model
+            .map(|model| format!("{} (Home Assistant discovery)", model))
+            .unwrap_or_else(|| "Home Assistant discovery device".to_string()),
+        categories: vec!["home-assistant".to_string(), component.to_string()],
+        mode: DeviceTypeMode::Full,
+        uplink: UplinkConfig {
+            metrics: metric.into_iter().collect(),
+            samples: Vec::new(),
+        },
+        downlink: DownlinkConfig {
+            commands: command.into_iter().collect(),
+        },
+    };
+
+    let mut connection_config = ConnectionConfig {
+        telemetry_topic: entity.text("state_topic"),
+        command_topic: entity.text("command_topic"),
+        ..Default::default()
+    };
+    connection_config
+        .extra
+        .insert("hass_identifier".to_string(), Value::String(identifier.clone()));
+
+    Some(MappedDevice {
+        source: MappingSource::HassDiscovery,
+        definition,
+        device: DeviceConfig {
+            device_id: format!("hass_{}", sanitize_id(&identifier)),
+            name,
+            device_type,
+            adapter_type: "mqtt".to_string(),
+            connection_config,
+            adapter_id: None,
+            last_seen: 0,
+            offline_timeout_secs: None,
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
+        },
+    })
+}
+
+/// Replace abbreviated keys and expand `~` in topics.
+fn expand(config: Map<String, Value>) -> Map<String, Value> {
+    let full_key = |table: &[(&str, &str)], key: String| {
+        table
+            .iter()
+            .find(|(short, _)| *short == key)
+            .map_or(key, |(_, full)| full.to_string())
+    };
+    let base = config.get("~").and_then(Value::as_str).map(str::to_string);
+
+    config
+        .into_iter()
+        .map(|(key, value)| {
+            let key = full_key(ABBREVIATIONS, key);
+            let value = match (value, base.as_deref()) {
+                (Value::String(topic), Some(base)) if key.ends_with("_topic") => {
+                    Value::String(expand_base(&topic, base))
+                }
+                (Value::Object(device), _) if key == "device" => Value::Object(
+                    device
+                        .into_iter()
+                        .map(|(key, value)| (full_key(DEVICE_ABBREVIATIONS, key), value))
+                        .collect(),
+                ),
+                (value, _) => value,
+            };
+            (key, value)
+        })
+        .collect()
+}
+
+/// `~` at the start or end of a topic stands for the `~` base topic.
+fn expand_base(topic: &str, base: &str) -> String {
+    if let Some(rest) = topic.strip_prefix('~') {
+        format!("{}{}", base, rest)
+    } else if let Some(rest) = topic.strip_suffix('~') {
+        format!("{}{}", rest, base)
+    } else {
+        topic.to_string()
+    }
+}
+
+/// One discovery config with its keys expanded.
+struct Entity<'a> {
+    component: &'a str,
+    object_id: &'a str,
+    config: Map<String, Value>,
+}
+
+impl Entity<'_> {
+    fn text(&self, key: &str) -> Option<String> {
+        self.config.get(key).and_then(scalar_text)
+    }
+
+    fn text_or(&self, key: &str, default: &str) -> String {
+        self.text(key).unwrap_or_else(|| default.to_string())
+    }
+
+    fn number(&self, key: &str) -> Option<f64> {
+        self.config.get(key).and_then(Value::as_f64)
+    }
+
+    /// A `device` field; for lists (`identifiers`) the first entry.
+    fn device_text(&self, key: &str) -> Option<String> {
+        match self.config.get("device")?.get(key)? {
+            Value::Array(values) => values.first().and_then(scalar_text),
+            value => scalar_text(value),
+        }
+    }
+
+    /// Lights with the JSON schema report and accept `{"state": ...}`.
+    fn is_json_light(&self) -> bool {
+        self.component == "light" && self.text("schema").as_deref() == Some("json")
+    }
+
+    fn display_name(&self) -> String {
+        self.text("name")
+            .unwrap_or_else(|| self.object_id.replace('_', " "))
+    }
+
+    /// On/off payloads of a two-state entity, with HA's defaults.
+    fn two_states(&self) -> Option<(Value, Value)> {
+        let (on, off, on_default, off_default) = match self.component {
+            "binary_sensor" | "switch" | "light" | "fan" => {
+                ("payload_on", "payload_off", "ON", "OFF")
+            }
+            "lock" => ("state_locked", "state_unlocked", "LOCKED", "UNLOCKED"),
+            _ => return None,
+        };
+        let state = |key: &str, default: &str| {
+            self.config
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| Value::String(default.to_string()))
+        };
+        Some((state(on, on_default), state(off, off_default)))
+    }
+
+    fn data_type(&self) -> Option<MetricDataType> {
+        if let Some((on, off)) = self.two_states() {
+            return Some(match on {
+                Value::Bool(_) => MetricDataType::Boolean,
+                on => MetricDataType::Enum {
+                    options: vec![json_text(&on), json_text(&off)],
+                },
+            });
+        }
+        match self.component {
+            "sensor" if self.config.contains_key("unit_of_measurement") => {
+                Some(MetricDataType::Float)
+            }
+            "sensor" => Some(MetricDataType::String),
+            "number" => Some(MetricDataType::Float),
+            "select" => Some(MetricDataType::Enum {
+                options: self
+                    .config
+                    .get("options")?
+                    .as_array()?
+                    .iter()
+                    .map(json_text)
+                    .collect(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Path of the entity's value in its JSON state payload.
+    fn state_path(&self) -> Option<String> {
+        if self.is_json_light() {
+            return Some("state".to_string());
+        }
+        let template = self
+            .text("state_value_template")
+            .or_else(|| self.text("value_template"))?;
+        value_json_path(&template)
+    }
+
+    fn metric(&self) -> Option<MetricDefinition> {
+        self.config.get("state_topic")?;
+        Some(MetricDefinition {
+            name: self.state_path()?,
+            display_name: self.display_name(),
+            data_type: self.data_type()?,
+            unit: self.text("unit_of_measurement").unwrap_or_default(),
+            min: self.number("min"),
+            max: self.number("max"),
+            required: false,
+        })
+    }
+
+    fn command(&self) -> Option<CommandDefinition> {
+        self.config.get("command_topic")?;
+        let object_id = sanitize_id(self.object_id);
+
+        if self.component == "button" {
+            return Some(command(
+                format!("press_{}", object_id),
+                format!("Press {}", self.display_name()),
+                self.text_or("payload_press", "PRESS"),
+                Vec::new(),
+            ));
+        }
+
+        let data_type = match self.component {
+            "lock" => MetricDataType::Enum {
+                options: vec![
+                    self.text_or("payload_lock", "LOCK"),
+                    self.text_or("payload_unlock", "UNLOCK"),
+                ],
+            },
+            "switch" | "light" | "fan" | "number" | "select" => self.data_type()?,
+            _ => return None,
+        };
+        let payload_template = match self.text("command_template") {
+            Some(template) => value_placeholder(&template)?,
+            None if self.is_json_light() => r#"{"state":"${value}"}"#.to_string(),
+            None => "${value}".to_string(),
+        };
+        let allowed_values = match &data_type {
+            MetricDataType::Enum { options } => {
+                options.iter().cloned().map(MetricValue::String).collect()
+            }
+            _ => Vec::new(),
+        };
+        let parameter = ParameterDefinition {
+            name: "value".to_string(),
+            display_name: self.display_name(),
+            data_type,
+            default_value: None,
+            min: self.number("min"),
+            max: self.number("max"),
+            unit: self.text("unit_of_measurement").unwrap_or_default(),
+            allowed_values,
+            required: true,
+            visible_when: None,
+            group: None,
+            help_text: String::new(),
+            validation: Vec::new(),
+        };
+        Some(command(
+            format!("set_{}", object_id),
+            format!("Set {}", self.display_name()),
+            payload_template,
+            vec![parameter],
+        ))
+    }
+}
+
+fn command(
+    name: String,
+    display_name: String,
+    payload_template: String,
+    parameters: Vec<ParameterDefinition>,
+) -> CommandDefinition {
+    CommandDefinition {
+        description: format!("{} through Home Assistant discovery", display_name),
+        name,
+        display_name,
+        payload_template,
+        parameters,
+        fixed_values: HashMap::new(),
+        samples: Vec::new(),
+        parameter_groups: Vec::new(),
+    }
+}
+
+/// Extract `a.b` from `{{ value_json.a.b }}` or `{{ value_json['a']['b'] | float }}`.
+fn value_json_path(template: &str) -> Option<String> {
+    let expression = template
+        .trim()
+        .strip_prefix("{{")?
+        .strip_suffix("}}")?
+        .split('|')
+        .next()?
+        .trim();
+    let mut rest = expression.strip_prefix("value_json")?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            segments.push(&after[..end]);
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix("['").or_else(|| rest.strip_prefix("[\"")) {
+            let end = after.find(['\'', '"'])?;
+            segments.push(&after[..end]);
+            rest = after[end + 1..].strip_prefix(']')?;
+        } else {
+            return None;
+        }
+    }
+    if segments.is_empty() || segments.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+    Some(segments.join("."))
+}
+
+/// Convert a `command_template` that only interpolates `{{ value }}` into a
+/// payload template; `None` for templates with other logic.
+fn value_placeholder(template: &str) -> Option<String> {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let end = start + rest[start..].find("}}")?;
+        if rest[start + 2..end].trim() != "value" {
+            return None;
+        }
+        result.push_str(&rest[..start]);
+        result.push_str("${value}");
+        rest = &rest[end + 2..];
+    }
+    if rest.contains("{%") {
+        return None;
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Array(_) | Value::Object(_) | Value::Null => None,
+        value => Some(json_text(value)),
+    }
+}
+
+/// String form of a JSON scalar (`"ON"` → `ON`, `true` → `true`).
+fn json_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(topic: &str, payload: Value) -> Option<MappedDevice> {
+        map_config("homeassistant", topic, payload.to_string().as_bytes(), true)
+    }
+
+    #[test]
+    fn test_abbreviated_sensor() {
+        let mapped = config(
+            "homeassistant/sensor/tasmota_1A2B3C/temperature/config",
+            json!({
+                "~": "tele/tasmota_1A2B3C",
+                "name": "Temperature",
+                "stat_t": "~/SENSOR",
+                "val_tpl": "{{ value_json['DS18B20'].Temperature | round(1) }}",
+                "unit_of_meas": "°C",
+                "uniq_id": "1A2B3C_temperature",
+                "dev": { "ids": ["1A2B3C"], "name": "Boiler", "mdl": "Sonoff TH", "mf": "Tasmota" }
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(mapped.source, MappingSource::HassDiscovery);
+        assert_eq!(mapped.device.device_id, "hass_1a2b3c");
+        assert_eq!(mapped.device.name, "Boiler");
+        assert_eq!(mapped.definition.device_type, "hass_tasmota_sonoff_th");
+        assert_eq!(
+            mapped.device.connection_config.telemetry_topic.as_deref(),
+            Some("tele/tasmota_1A2B3C/SENSOR")
+        );
+        let metric = &mapped.definition.uplink.metrics[0];
+        assert_eq!(metric.name, "DS18B20.Temperature");
+        assert_eq!(metric.data_type, MetricDataType::Float);
+        assert_eq!(metric.unit, "°C");
+    }
+
+    #[test]
+    fn test_switch_command() {
+        let mapped = config(
+            "homeassistant/switch/plug_01/relay/config",
+            json!({
+                "name": "Relay",
+                "state_topic": "plug_01/state",
+                "value_template": "{{ value_json.relay }}",
+                "command_topic": "plug_01/relay/set",
+                "command_template": "{\"relay\": \"{{ value }}\"}",
+                "payload_on": "on",
+                "payload_off": "off",
+                "device": { "identifiers": "plug_01" }
+            }),
+        )
+        .unwrap();
+
+        let command = &mapped.definition.downlink.commands[0];
+        assert_eq!(command.name, "set_relay");
+        let params = HashMap::from([("value".to_string(), MetricValue::String("off".into()))]);
+        let rendered =
+            crate::payload_template::render(&command.payload_template, &params).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&rendered).unwrap(),
+            json!({ "relay": "off" })
+        );
+        assert_eq!(
+            mapped.definition.uplink.metrics[0].data_type,
+            MetricDataType::Enum {
+                options: vec!["on".into(), "off".into()]
+            }
+        );
+    }
+
+    #[test]
+    fn test_skipped_configs() {
+        // Removed entity
+        assert!(map_config("homeassistant", "homeassistant/sensor/x/config", b"", true).is_none());
+        // Announced by Zigbee2MQTT
+        let z2m = json!({
+            "state_topic": "zigbee2mqtt/plug",
+            "value_template": "{{ value_json.power }}",
+            "device": { "identifiers": ["zigbee2mqtt_0x00158d0001a2b3c4"] }
+        });
+        assert!(config("homeassistant/sensor/0x00158d0001a2b3c4/power/config", z2m).is_none());
+        // Raw state payload
+        let raw = json!({ "state_topic": "sensors/temp", "unit_of_measurement": "°C" });
+        assert!(config("homeassistant/sensor/temp/config", raw).is_none());
+    }
+
+    #[test]
+    fn test_templates() {
+        assert_eq!(value_json_path("{{ value_json.a.b }}").as_deref(), Some("a.b"));
+        assert_eq!(value_json_path("{{value_json[\"a\"].b|float}}").as_deref(), Some("a.b"));
+        assert!(value_json_path("{{ value }}").is_none());
+        assert!(value_json_path("{{ value_json.a * 10 }}").is_none());
+
+        assert_eq!(value_placeholder("{{value}}").as_deref(), Some("${value}"));
+        assert!(value_placeholder("{{ value | int * 2 }}").is_none());
+    }
+}
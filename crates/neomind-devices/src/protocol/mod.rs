@@ -12,11 +12,19 @@
 //! ├─ humidity capability     ──────→  └─ MQTT: sensor/${id}/humidity
 //! └─ relay_state capability  ──────→  └─ MQTT: relay/${id}/state
 //! ```
+//!
+//! Self-describing ecosystems (Zigbee2MQTT, Home Assistant MQTT discovery)
+//! don't need hand-written MDL: [`auto_mapping`] generates both sides from
+//! the descriptions they publish.
 
+pub mod auto_mapping;
+pub mod hass_discovery;
 pub mod mapping;
 pub mod mqtt_mapping;
+pub mod zigbee2mqtt;
 
 // Re-exports
+pub use auto_mapping::{AutoMappingConfig, MappedDevice, MappingSource, MappingUpdate};
 pub use mapping::{
     Address, Capability, CapabilityType, MappingConfig, MappingError, MetricParser,
     PayloadSerializer, ProtocolMapping, SharedMapping,
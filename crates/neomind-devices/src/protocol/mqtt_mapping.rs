@@ -293,6 +293,13 @@ impl MqttMapping {
                     .trim_start_matches("0x")
                     .replace([' ', '\n', '\r', '\t'], "");
 
+                // Byte-wise slicing below needs single-byte characters
+                if !hex_clean.is_ascii() {
+                    return Err(MappingError::ParseError(
+                        "Invalid hex characters".to_string(),
+                    ));
+                }
+
                 if hex_clean.len() % 2 != 0 {
                     return Err(MappingError::ParseError(
                         "Hex string must have even length".to_string(),
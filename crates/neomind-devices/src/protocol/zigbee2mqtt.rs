@@ -0,0 +1,489 @@
+//! Zigbee2MQTT device descriptions.
+//!
+//! Zigbee2MQTT publishes its device list (retained) on `{base}/bridge/devices`
+//! and interview / leave events on `{base}/bridge/event`. A device definition
+//! lists its *exposes*: typed features with an access mask, which map onto
+//! NeoMind metrics (published) and commands (settable).
+//!
+//! Devices report their whole state as one JSON object on
+//! `{base}/{friendly_name}` and accept changes on `{base}/{friendly_name}/set`,
+//! so each feature's property is both a metric path and a command payload key:
+//!
+//! ```text
+//! light  { state (binary, access 7), brightness (numeric, access 7),
+//!          color (composite: x, y) }
+//!   → metrics  state, brightness, color.x, color.y
+//!   → commands set_state, set_brightness, set_color {"color":{"x":…,"y":…}}
+//! ```
+//!
+//! Devices are identified by IEEE address, so renames keep their history.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::auto_mapping::{sanitize_id, MappedDevice, MappingSource, MappingUpdate};
+use crate::mdl::{MetricDataType, MetricValue};
+use crate::mdl_format::{
+    CommandDefinition, DeviceTypeDefinition, DeviceTypeMode, DownlinkConfig, MetricDefinition,
+    ParameterDefinition, UplinkConfig,
+};
+use crate::registry::{ConnectionConfig, DeviceConfig};
+
+/// Access bit: the feature is reported in the device state.
+const ACCESS_PUBLISHED: u64 = 1;
+/// Access bit: the feature can be set through `{base}/{friendly_name}/set`.
+const ACCESS_SET: u64 = 2;
+
+/// Expose types that group features into a device class.
+const DEVICE_CLASSES: &[&str] = &["light", "switch", "lock", "climate", "fan", "cover"];
+
+/// Zigbee2MQTT's default availability timeout for battery devices (25 h);
+/// they may legitimately stay silent that long.
+const BATTERY_OFFLINE_TIMEOUT_SECS: u64 = 25 * 60 * 60;
+
+/// A device entry of `{base}/bridge/devices`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Z2mDevice {
+    pub ieee_address: String,
+    pub friendly_name: String,
+    /// `Coordinator`, `Router` or `EndDevice`
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub power_source: Option<String>,
+    #[serde(default = "default_true")]
+    pub interview_completed: bool,
+    #[serde(default)]
+    pub disabled: bool,
+    /// `None` for the coordinator and unsupported devices
+    #[serde(default)]
+    pub definition: Option<Z2mDefinition>,
+}
+
+/// The model definition of a supported device.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Z2mDefinition {
+    pub model: String,
+    pub vendor: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub exposes: Vec<Value>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Map the `{base}/bridge/devices` list. Returns `None` if it isn't a list.
+pub fn map_bridge_devices(base_topic: &str, payload: &[u8]) -> Option<Vec<MappedDevice>> {
+    let entries: Vec<Value> = serde_json::from_slice(payload).ok()?;
+    Some(
+        entries
+            .into_iter()
+            .filter_map(|entry| serde_json::from_value::<Z2mDevice>(entry).ok())
+            .filter_map(|device| map_device(base_topic, &device))
+            .collect(),
+    )
+}
+
+/// Map a `{base}/bridge/event`.
+///
+/// A successful interview carries the full definition, so a device joining
+/// is registered as soon as Zigbee2MQTT knows what it is.
+pub fn map_bridge_event(base_topic: &str, payload: &[u8]) -> Option<MappingUpdate> {
+    let event: Value = serde_json::from_slice(payload).ok()?;
+    let data = event.get("data")?;
+    match event.get("type")?.as_str()? {
+        "device_interview" if data.get("status")?.as_str()? == "successful" => {
+            let device: Z2mDevice = serde_json::from_value(data.clone()).ok()?;
+            let mapped = map_device(base_topic, &device)?;
+            Some(MappingUpdate::Upsert(vec![mapped]))
+        }
+        "device_leave" => Some(MappingUpdate::Left {
+            device_id: data.get("ieee_address")?.as_str()?.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+/// Generate the device type and device configuration for one device.
+///
+/// Returns `None` for the coordinator and for disabled, unsupported or
+/// not yet interviewed devices.
+pub fn map_device(base_topic: &str, device: &Z2mDevice) -> Option<MappedDevice> {
+    if device.kind.as_deref() == Some("Coordinator")
+        || device.disabled
+        || !device.interview_completed
+    {
+        return None;
+    }
+    let definition = device.definition.as_ref()?;
+
+    let mut features = Vec::new();
+    for expose in &definition.exposes {
+        collect_features(expose, &[], &mut features);
+    }
+    let metrics: Vec<MetricDefinition> = features
+        .iter()
+        .filter(|f| f.access & ACCESS_PUBLISHED != 0)
+        .filter_map(Feature::metric)
+        .collect();
+    let commands = commands(&features);
+
+    let mut categories = vec!["zigbee".to_string()];
+    for expose in &definition.exposes {
+        let kind = expose.get("type").and_then(Value::as_str).unwrap_or_default();
+        let category = if DEVICE_CLASSES.contains(&kind) {
+            kind
+        } else {
+            "sensor"
+        };
+        if !categories.iter().any(|c| c == category) {
+            categories.push(category.to_string());
+        }
+    }
+
+    let name = format!("{} {}", definition.vendor, definition.model);
+    let device_type = DeviceTypeDefinition {
+        device_type: format!("z2m_{}", sanitize_id(&name)),
+        name,
+        description: definition.description.clone(),
+        categories,
+        mode: DeviceTypeMode::Full,
+        uplink: UplinkConfig {
+            metrics,
+            samples: Vec::new(),
+        },
+        downlink: DownlinkConfig { commands },
+    };
+
+    let state_topic = format!("{}/{}", base_topic, device.friendly_name);
+    let mut connection_config =
+        ConnectionConfig::mqtt(state_topic.clone(), Some(format!("{}/set", state_topic)));
+    connection_config.extra.insert(
+        "ieee_address".to_string(),
+        Value::String(device.ieee_address.clone()),
+    );
+    let battery = device.power_source.as_deref() == Some("Battery");
+
+    Some(MappedDevice {
+        source: MappingSource::Zigbee2Mqtt,
+        device: DeviceConfig {
+            device_id: device.ieee_address.clone(),
+            name: device.friendly_name.clone(),
+            device_type: device_type.device_type.clone(),
+            adapter_type: "mqtt".to_string(),
+            connection_config,
+            adapter_id: None,
+            last_seen: 0,
+            offline_timeout_secs: battery.then_some(BATTERY_OFFLINE_TIMEOUT_SECS),
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
+        },
+        definition: device_type,
+    })
+}
+
+/// A leaf feature and its property path in the device state.
+struct Feature<'a> {
+    path: Vec<&'a str>,
+    access: u64,
+    expose: &'a Value,
+}
+
+fn collect_features<'a>(expose: &'a Value, prefix: &[&'a str], out: &mut Vec<Feature<'a>>) {
+    let property = expose.get("property").and_then(Value::as_str);
+    let mut path = prefix.to_vec();
+    match expose.get("features").and_then(Value::as_array) {
+        Some(features) => {
+            // Composites (e.g. `color`) nest their features under their own
+            // property; device classes (light, switch, ...) don't.
+            path.extend(property);
+            for feature in features {
+                collect_features(feature, &path, out);
+            }
+        }
+        None => {
+            let Some(property) = property else {
+                return;
+            };
+            path.push(property);
+            out.push(Feature {
+                path,
+                access: expose.get("access").and_then(Value::as_u64).unwrap_or(0),
+                expose,
+            });
+        }
+    }
+}
+
+impl Feature<'_> {
+    fn text(&self, key: &str) -> Option<String> {
+        self.expose.get(key).map(json_text)
+    }
+
+    fn number(&self, key: &str) -> Option<f64> {
+        self.expose.get(key).and_then(Value::as_f64)
+    }
+
+    fn display_name(&self) -> String {
+        self.text("label")
+            .or_else(|| self.text("name"))
+            .unwrap_or_else(|| self.path.join("."))
+    }
+
+    /// NeoMind type of the feature; `None` for lists and unknown types.
+    fn data_type(&self) -> Option<MetricDataType> {
+        match self.expose.get("type")?.as_str()? {
+            "numeric" => Some(MetricDataType::Float),
+            "text" => Some(MetricDataType::String),
+            "binary" => match self.expose.get("value_on") {
+                Some(Value::Bool(_)) => Some(MetricDataType::Boolean),
+                _ => Some(MetricDataType::Enum {
+                    options: ["value_on", "value_off"]
+                        .iter()
+                        .filter_map(|key| self.text(key))
+                        .collect(),
+                }),
+            },
+            "enum" => Some(MetricDataType::Enum {
+                options: self
+                    .expose
+                    .get("values")?
+                    .as_array()?
+                    .iter()
+                    .map(json_text)
+                    .collect(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn metric(&self) -> Option<MetricDefinition> {
+        Some(MetricDefinition {
+            name: self.path.join("."),
+            display_name: self.display_name(),
+            data_type: self.data_type()?,
+            unit: self.text("unit").unwrap_or_default(),
+            min: self.number("value_min"),
+            max: self.number("value_max"),
+            required: false,
+        })
+    }
+
+    fn parameter(&self) -> Option<ParameterDefinition> {
+        let data_type = self.data_type()?;
+        let allowed_values = match &data_type {
+            MetricDataType::Enum { options } => {
+                options.iter().cloned().map(MetricValue::String).collect()
+            }
+            _ => Vec::new(),
+        };
+        Some(ParameterDefinition {
+            name: self.path.join("_"),
+            display_name: self.display_name(),
+            data_type,
+            default_value: None,
+            min: self.number("value_min"),
+            max: self.number("value_max"),
+            unit: self.text("unit").unwrap_or_default(),
+            allowed_values,
+            required: true,
+            visible_when: None,
+            group: None,
+            help_text: self.text("description").unwrap_or_default(),
+            validation: Vec::new(),
+        })
+    }
+}
+
+/// One `set_{property}` command per settable top-level property; a
+/// composite's settable features become parameters of the same command.
+fn commands(features: &[Feature<'_>]) -> Vec<CommandDefinition> {
+    let mut by_root: BTreeMap<&str, Vec<&Feature<'_>>> = BTreeMap::new();
+    for feature in features.iter().filter(|f| f.access & ACCESS_SET != 0) {
+        by_root.entry(feature.path[0]).or_default().push(feature);
+    }
+
+    by_root
+        .into_iter()
+        .filter_map(|(root, features)| {
+            let mut payload = serde_json::Map::new();
+            let mut parameters = Vec::new();
+            for feature in features {
+                let parameter = feature.parameter()?;
+                insert_placeholder(&mut payload, &feature.path, &parameter.name);
+                parameters.push(parameter);
+            }
+            Some(CommandDefinition {
+                name: format!("set_{}", root),
+                display_name: format!("Set {}", root.replace('_', " ")),
+                description: format!("Set {} through Zigbee2MQTT", root),
+                payload_template: Value::Object(payload).to_string(),
+                parameters,
+                fixed_values: HashMap::new(),
+                samples: Vec::new(),
+                parameter_groups: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Put a `${name}` placeholder at `path` in a nested JSON payload.
+fn insert_placeholder(payload: &mut serde_json::Map<String, Value>, path: &[&str], name: &str) {
+    match path {
+        [] => {}
+        [last] => {
+            payload.insert(last.to_string(), Value::String(format!("${{{}}}", name)));
+        }
+        [first, rest @ ..] => {
+            let child = payload
+                .entry(first.to_string())
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if let Value::Object(child) = child {
+                insert_placeholder(child, rest, name);
+            }
+        }
+    }
+}
+
+/// String form of a JSON scalar (`"ON"` → `ON`, `true` → `true`).
+fn json_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bulb() -> Value {
+        json!({
+            "ieee_address": "0x000b57fffec6a5b2",
+            "friendly_name": "living_room/bulb",
+            "type": "Router",
+            "power_source": "Mains (single phase)",
+            "interview_completed": true,
+            "definition": {
+                "model": "LED1545G12",
+                "vendor": "IKEA",
+                "description": "TRADFRI bulb E26/E27, white spectrum",
+                "exposes": [
+                    { "type": "light", "features": [
+                        { "type": "binary", "name": "state", "property": "state", "access": 7,
+                          "value_on": "ON", "value_off": "OFF", "value_toggle": "TOGGLE" },
+                        { "type": "numeric", "name": "brightness", "property": "brightness",
+                          "access": 7, "value_min": 0, "value_max": 254 },
+                        { "type": "composite", "name": "color_xy", "property": "color", "access": 7,
+                          "features": [
+                            { "type": "numeric", "name": "x", "property": "x", "access": 7 },
+                            { "type": "numeric", "name": "y", "property": "y", "access": 7 }
+                          ] }
+                    ]},
+                    { "type": "enum", "name": "effect", "property": "effect", "access": 2,
+                      "values": ["blink", "breathe", "okay"] },
+                    { "type": "numeric", "name": "linkquality", "property": "linkquality",
+                      "access": 1, "unit": "lqi" }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn test_map_light() {
+        let payload = json!([{ "ieee_address": "0x00124b0000000000", "friendly_name": "Coordinator",
+                               "type": "Coordinator", "definition": null }, bulb()]);
+        let devices = map_bridge_devices("zigbee2mqtt", payload.to_string().as_bytes()).unwrap();
+        assert_eq!(devices.len(), 1);
+        let mapped = &devices[0];
+
+        assert_eq!(mapped.definition.device_type, "z2m_ikea_led1545g12");
+        assert_eq!(mapped.definition.categories, vec!["zigbee", "light", "sensor"]);
+        assert_eq!(mapped.device.device_id, "0x000b57fffec6a5b2");
+        assert_eq!(
+            mapped.device.connection_config.command_topic.as_deref(),
+            Some("zigbee2mqtt/living_room/bulb/set")
+        );
+        assert_eq!(mapped.device.offline_timeout_secs, None);
+
+        let metrics: Vec<_> = mapped
+            .definition
+            .uplink
+            .metrics
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(metrics, vec!["state", "brightness", "color.x", "color.y", "linkquality"]);
+        assert_eq!(
+            mapped.definition.uplink.metrics[0].data_type,
+            MetricDataType::Enum {
+                options: vec!["ON".into(), "OFF".into()]
+            }
+        );
+
+        let commands: HashMap<_, _> = mapped
+            .definition
+            .downlink
+            .commands
+            .iter()
+            .map(|c| (c.name.as_str(), c))
+            .collect();
+        assert_eq!(commands.len(), 4);
+        let set_color = commands["set_color"];
+        assert_eq!(set_color.parameters.len(), 2);
+
+        let params = HashMap::from([
+            ("color_x".to_string(), MetricValue::Float(0.3)),
+            ("color_y".to_string(), MetricValue::Float(0.4)),
+        ]);
+        let rendered =
+            crate::payload_template::render(&set_color.payload_template, &params).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&rendered).unwrap(),
+            json!({ "color": { "x": 0.3, "y": 0.4 } })
+        );
+        assert_eq!(commands["set_effect"].parameters[0].allowed_values.len(), 3);
+    }
+
+    #[test]
+    fn test_bridge_events() {
+        let interview = json!({
+            "type": "device_interview",
+            "data": {
+                "friendly_name": "0x000b57fffec6a5b2",
+                "ieee_address": "0x000b57fffec6a5b2",
+                "status": "successful",
+                "supported": true,
+                "definition": bulb()["definition"]
+            }
+        });
+        let Some(MappingUpdate::Upsert(devices)) =
+            map_bridge_event("zigbee2mqtt", interview.to_string().as_bytes())
+        else {
+            panic!("expected an upsert");
+        };
+        assert_eq!(devices[0].device.name, "0x000b57fffec6a5b2");
+
+        let started = json!({
+            "type": "device_interview",
+            "data": { "ieee_address": "0x1", "friendly_name": "0x1", "status": "started" }
+        });
+        assert!(map_bridge_event("zigbee2mqtt", started.to_string().as_bytes()).is_none());
+
+        let leave = json!({
+            "type": "device_leave",
+            "data": { "ieee_address": "0x000b57fffec6a5b2", "friendly_name": "bulb" }
+        });
+        assert!(matches!(
+            map_bridge_event("zigbee2mqtt", leave.to_string().as_bytes()),
+            Some(MappingUpdate::Left { device_id }) if device_id == "0x000b57fffec6a5b2"
+        ));
+    }
+}
@@ -0,0 +1,446 @@
+//! Adaptive load shedding for device telemetry.
+//!
+//! When enabled on [`DeviceService`](crate::DeviceService), every device data
+//! point written to [`TimeSeriesStorage`](crate::TimeSeriesStorage) passes
+//! through an [`IngestQos`] first. While a device publishes faster than
+//! `device_max_points_per_sec`, or all devices together faster than
+//! `max_points_per_sec`, only every `keep_every`-th point of each of the
+//! affected metrics is stored. Metrics matching `priority_metrics` (alarms,
+//! faults, command acknowledgements, ...) are always stored.
+//!
+//! Only storage is shed: events still reach rules and agents, so alerts
+//! raised from telemetry keep firing. Commands don't go through telemetry and
+//! are never affected.
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use neomind_core::config::{device, device_env_vars};
+use serde::{Deserialize, Serialize};
+
+use super::telemetry::DataPoint;
+
+/// Points per second across all devices; unset or `0` disables shedding.
+pub const INGEST_MAX_POINTS_ENV: &str = device_env_vars::INGEST_MAX_POINTS_PER_SEC;
+/// Points per second from a single device.
+pub const INGEST_DEVICE_MAX_POINTS_ENV: &str = device_env_vars::INGEST_DEVICE_MAX_POINTS_PER_SEC;
+/// Keep every Nth point while shedding.
+pub const INGEST_KEEP_EVERY_ENV: &str = device_env_vars::INGEST_KEEP_EVERY;
+
+/// Closed shedding episodes kept for the report.
+const MAX_EPISODES: usize = 50;
+/// Recent points remembered per metric to recognise rewrites of a point.
+const RECENT_POINTS: usize = 16;
+
+/// Load shedding settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IngestQosConfig {
+    /// Points per second across all devices before shedding starts.
+    pub max_points_per_sec: u32,
+    /// Points per second from one device before that device is shed.
+    pub device_max_points_per_sec: u32,
+    /// While shedding, keep every Nth point of a metric.
+    pub keep_every: u32,
+    /// Metric names that are never shed; `*` matches any prefix or suffix.
+    pub priority_metrics: Vec<String>,
+}
+
+impl Default for IngestQosConfig {
+    fn default() -> Self {
+        Self {
+            max_points_per_sec: 1000,
+            device_max_points_per_sec: device::DEFAULT_INGEST_DEVICE_MAX_POINTS_PER_SEC,
+            keep_every: device::DEFAULT_INGEST_KEEP_EVERY,
+            priority_metrics: [
+                "alarm*", "*_alarm", "alert*", "*_alert", "fault*", "*_fault", "command*",
+                "*_ack",
+            ]
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+        }
+    }
+}
+
+impl IngestQosConfig {
+    /// Defaults with the limits set in the config service
+    /// (`telemetry.ingest_*`), where `NEOMIND_INGEST_MAX_POINTS_PER_SEC`,
+    /// `NEOMIND_INGEST_DEVICE_MAX_POINTS_PER_SEC` and
+    /// `NEOMIND_INGEST_KEEP_EVERY` override the stored values. Returns `None`
+    /// unless the global limit is set to a positive number.
+    pub fn from_env() -> Option<Self> {
+        Some(Self {
+            max_points_per_sec: device_env_vars::ingest_max_points_per_sec()?,
+            device_max_points_per_sec: device_env_vars::ingest_device_max_points_per_sec(),
+            keep_every: device_env_vars::ingest_keep_every(),
+            ..Self::default()
+        })
+    }
+
+    /// Whether `metric` is never shed.
+    pub fn is_priority(&self, metric: &str) -> bool {
+        self.priority_metrics.iter().any(|pattern| {
+            match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+                (Some(suffix), _) if !suffix.is_empty() => metric.ends_with(suffix),
+                (_, Some(prefix)) => metric.starts_with(prefix),
+                _ => metric == pattern,
+            }
+        })
+    }
+}
+
+/// Why a device is being shed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShedReason {
+    /// The device itself is over `device_max_points_per_sec`
+    DeviceRate,
+    /// All devices together are over `max_points_per_sec`
+    GlobalRate,
+}
+
+/// A period during which a device's telemetry was downsampled.
+#[derive(Debug, Clone, Serialize)]
+pub struct SheddingEpisode {
+    pub device_id: String,
+    pub reason: ShedReason,
+    /// Unix seconds
+    pub started_at: i64,
+    /// `None` while the episode is still going on
+    pub ended_at: Option<i64>,
+    /// Points dropped during the episode
+    pub shed: u64,
+    /// Highest rate seen during the episode (points per second)
+    pub peak_rate: u32,
+}
+
+/// Ingestion figures for one device.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceShedStats {
+    pub device_id: String,
+    /// Points in the last full second
+    pub rate: u32,
+    pub seen: u64,
+    pub shed: u64,
+    pub shedding: bool,
+    pub last_shed_at: Option<i64>,
+}
+
+/// What the load shedder has done since it was enabled.
+#[derive(Debug, Clone, Serialize)]
+pub struct SheddingReport {
+    pub config: IngestQosConfig,
+    /// Points across all devices in the last full second
+    pub rate: u32,
+    pub shedding: bool,
+    pub seen: u64,
+    pub stored: u64,
+    pub shed: u64,
+    /// Points stored despite shedding because their metric has priority
+    pub priority_stored: u64,
+    /// Devices that have had points shed, most shed first
+    pub devices: Vec<DeviceShedStats>,
+    /// Most recent episodes, newest first
+    pub episodes: Vec<SheddingEpisode>,
+}
+
+/// Points counted in whole-second windows.
+#[derive(Default)]
+struct RateWindow {
+    second: u64,
+    count: u32,
+    last_rate: u32,
+}
+
+impl RateWindow {
+    fn roll(&mut self, second: u64) {
+        if second != self.second {
+            // A gap of more than a second means the last full second was quiet
+            self.last_rate = if second == self.second + 1 {
+                self.count
+            } else {
+                0
+            };
+            self.second = second;
+            self.count = 0;
+        }
+    }
+
+    /// The current rate: the last full second, or this one once it is busier.
+    fn rate(&self) -> u32 {
+        self.last_rate.max(self.count)
+    }
+}
+
+#[derive(Default)]
+struct SeriesState {
+    counter: u64,
+    /// Fingerprints of recent points
+    recent: VecDeque<u64>,
+}
+
+#[derive(Default)]
+struct DeviceState {
+    window: RateWindow,
+    seen: u64,
+    shed: u64,
+    last_shed_at: Option<i64>,
+    episode: Option<SheddingEpisode>,
+    series: HashMap<String, SeriesState>,
+}
+
+#[derive(Default)]
+struct QosState {
+    window: RateWindow,
+    seen: u64,
+    stored: u64,
+    shed: u64,
+    priority_stored: u64,
+    devices: HashMap<String, DeviceState>,
+    episodes: VecDeque<SheddingEpisode>,
+}
+
+/// Decides which device data points are stored while ingestion is over its
+/// limits.
+pub struct IngestQos {
+    config: IngestQosConfig,
+    started: Instant,
+    state: Mutex<QosState>,
+}
+
+impl IngestQos {
+    pub fn new(mut config: IngestQosConfig) -> Self {
+        config.keep_every = config.keep_every.max(1);
+        Self {
+            config,
+            started: Instant::now(),
+            state: Mutex::new(QosState::default()),
+        }
+    }
+
+    pub fn config(&self) -> &IngestQosConfig {
+        &self.config
+    }
+
+    /// Whether to store `point` for `device_id`/`metric`.
+    ///
+    /// Adapters store a point when they receive it and the device service
+    /// stores it again from the event bus. A point identical to a recent one
+    /// was already stored or shed, so it is skipped and not counted twice.
+    pub fn admit(&self, device_id: &str, metric: &str, point: &DataPoint) -> bool {
+        let second = self.started.elapsed().as_secs();
+        self.admit_at(device_id, metric, fingerprint(point), second)
+    }
+
+    fn admit_at(&self, device_id: &str, metric: &str, fingerprint: u64, second: u64) -> bool {
+        let mut guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let state = &mut *guard;
+        let device = state.devices.entry(device_id.to_string()).or_default();
+        let series = device.series.entry(metric.to_string()).or_default();
+        if series.recent.contains(&fingerprint) {
+            return false;
+        }
+
+        state.window.roll(second);
+        device.window.roll(second);
+        state.window.count += 1;
+        device.window.count += 1;
+        state.seen += 1;
+        device.seen += 1;
+
+        let now = chrono::Utc::now().timestamp();
+        let reason = if device.window.rate() > self.config.device_max_points_per_sec {
+            Some(ShedReason::DeviceRate)
+        } else if state.window.rate() > self.config.max_points_per_sec {
+            Some(ShedReason::GlobalRate)
+        } else {
+            None
+        };
+        match (reason, device.episode.is_some()) {
+            (Some(reason), false) => {
+                tracing::warn!(
+                    device_id = %device_id,
+                    reason = ?reason,
+                    rate = device.window.rate(),
+                    "Telemetry ingestion over limit, downsampling device metrics"
+                );
+                device.episode = Some(SheddingEpisode {
+                    device_id: device_id.to_string(),
+                    reason,
+                    started_at: now,
+                    ended_at: None,
+                    shed: 0,
+                    peak_rate: 0,
+                });
+            }
+            (None, true) => {
+                if let Some(mut episode) = device.episode.take() {
+                    tracing::info!(
+                        device_id = %device_id,
+                        shed = episode.shed,
+                        "Telemetry ingestion back under limit"
+                    );
+                    episode.ended_at = Some(now);
+                    state.episodes.push_front(episode);
+                    state.episodes.truncate(MAX_EPISODES);
+                }
+            }
+            _ => {}
+        }
+
+        let priority = self.config.is_priority(metric);
+        let store = match device.episode {
+            Some(ref mut episode) if !priority => {
+                episode.peak_rate = episode.peak_rate.max(device.window.rate());
+                let keep = series.counter.is_multiple_of(u64::from(self.config.keep_every));
+                series.counter += 1;
+                if !keep {
+                    episode.shed += 1;
+                }
+                keep
+            }
+            Some(_) => {
+                state.priority_stored += 1;
+                true
+            }
+            None => {
+                series.counter = 0;
+                true
+            }
+        };
+
+        if store {
+            state.stored += 1;
+        } else {
+            state.shed += 1;
+            device.shed += 1;
+            device.last_shed_at = Some(now);
+        }
+        series.recent.push_front(fingerprint);
+        series.recent.truncate(RECENT_POINTS);
+        store
+    }
+
+    /// Shedding statistics and recent episodes.
+    pub fn report(&self) -> SheddingReport {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut devices: Vec<DeviceShedStats> = state
+            .devices
+            .iter()
+            .filter(|(_, d)| d.shed > 0 || d.episode.is_some())
+            .map(|(id, d)| DeviceShedStats {
+                device_id: id.clone(),
+                rate: d.window.rate(),
+                seen: d.seen,
+                shed: d.shed,
+                shedding: d.episode.is_some(),
+                last_shed_at: d.last_shed_at,
+            })
+            .collect();
+        devices.sort_by_key(|d| std::cmp::Reverse(d.shed));
+
+        let mut open: Vec<SheddingEpisode> = state
+            .devices
+            .values()
+            .filter_map(|d| d.episode.clone())
+            .collect();
+        open.sort_by_key(|e| std::cmp::Reverse(e.started_at));
+        let episodes = open
+            .into_iter()
+            .chain(state.episodes.iter().cloned())
+            .take(MAX_EPISODES)
+            .collect();
+
+        SheddingReport {
+            config: self.config.clone(),
+            rate: state.window.rate(),
+            shedding: state.window.rate() > self.config.max_points_per_sec,
+            seen: state.seen,
+            stored: state.stored,
+            shed: state.shed,
+            priority_stored: state.priority_stored,
+            devices,
+            episodes,
+        }
+    }
+}
+
+/// Identity of a data point within its metric.
+fn fingerprint(point: &DataPoint) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    point.timestamp.hash(&mut hasher);
+    serde_json::to_string(&point.value)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qos() -> IngestQos {
+        IngestQos::new(IngestQosConfig {
+            max_points_per_sec: 1000,
+            device_max_points_per_sec: 10,
+            keep_every: 5,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_downsamples_noisy_device() {
+        let qos = qos();
+        let mut fp = 0;
+        let mut admit = |device: &str, metric: &str, second: u64| {
+            fp += 1;
+            qos.admit_at(device, metric, fp, second)
+        };
+
+        // 100 points in one second: the first ten are under the limit, then
+        // every fifth of the rest is stored
+        let stored = (0..100).filter(|_| admit("noisy", "temp", 0)).count();
+        assert_eq!(stored, 10 + 18);
+        // Alarms always get through, and other devices aren't affected
+        assert!((0..20).all(|_| admit("noisy", "smoke_alarm", 0)));
+        assert!(admit("quiet", "temp", 0));
+
+        // Still shed in the next second because of the last second's rate
+        let stored = (0..10).filter(|_| admit("noisy", "temp", 1)).count();
+        assert_eq!(stored, 2);
+
+        // Quiet for a while: the episode ends
+        assert!(admit("noisy", "temp", 5));
+        let report = qos.report();
+        assert_eq!(report.seen, 132);
+        assert_eq!(report.shed, 72 + 8);
+        assert_eq!(report.priority_stored, 20);
+        assert_eq!(report.devices.len(), 1);
+        assert!(!report.devices[0].shedding);
+        assert_eq!(report.episodes.len(), 1);
+        assert_eq!(report.episodes[0].reason, ShedReason::DeviceRate);
+        assert_eq!(report.episodes[0].shed, 80);
+    }
+
+    #[test]
+    fn test_rewrite_of_point_is_not_counted() {
+        let qos = qos();
+        for fp in 0..20 {
+            qos.admit_at("d", "temp", fp, 0);
+        }
+        let report = qos.report();
+        assert!(!qos.admit_at("d", "temp", 19, 0));
+        assert_eq!(qos.report().seen, report.seen);
+    }
+
+    #[test]
+    fn test_priority_patterns() {
+        let config = IngestQosConfig::default();
+        assert!(config.is_priority("alarm"));
+        assert!(config.is_priority("door_alarm"));
+        assert!(config.is_priority("command_result"));
+        assert!(!config.is_priority("temperature"));
+    }
+}
@@ -19,12 +19,16 @@
 //! ```
 
 use dashmap::DashMap;
+use neomind_core::tenant::TenantId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
+use super::binary_codec::BinaryCodec;
+use super::group::{is_member, DeviceGroup};
+use super::virtual_device::{is_virtual_device, VirtualDeviceSpec};
 use super::mdl::DeviceError;
 use super::mdl::MetricDataType;
 use super::mdl::MetricValue;
@@ -186,12 +190,21 @@ pub struct DeviceTypeTemplate {
     /// overrides it. `None` = use the global default.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_offline_timeout_secs: Option<u64>,
+    /// Expected seconds between telemetry reports. When no explicit offline
+    /// threshold is set, devices of this type go offline after this interval
+    /// plus `HeartbeatConfig::grace_period`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_interval_secs: Option<u64>,
     /// If `Some(false)`, skip storing the `_raw` metric (full payload snapshot)
     /// for devices of this type. `None` (default) = inherit the extractor's
     /// `store_raw` config. Set `Some(false)` for large-payload devices (e.g.
     /// cameras) whose structured metrics already cover all fields.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub store_raw: Option<bool>,
+    /// Field layout of binary uplink/downlink frames, for devices that send
+    /// raw bytes instead of JSON. See [`crate::binary_codec`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_codec: Option<BinaryCodec>,
 }
 
 impl DeviceTypeTemplate {
@@ -207,7 +220,9 @@ impl DeviceTypeTemplate {
             uplink_samples: Vec::new(),
             commands: Vec::new(),
             default_offline_timeout_secs: None,
+            expected_interval_secs: None,
             store_raw: None,
+            binary_codec: None,
         }
     }
 
@@ -263,6 +278,15 @@ pub struct DeviceConfig {
     /// `effective_offline_timeout()` in `service.rs`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub offline_timeout_secs: Option<u64>,
+    /// Free-form tags (e.g. "light", "hvac") used by dynamic device groups.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Location path (e.g. "hq/floor-2/room-201") used by dynamic device groups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Tenant that owns this device.
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
 }
 
 /// Unified connection configuration for different protocols
@@ -281,6 +305,18 @@ pub struct ConnectionConfig {
     /// Home Assistant entity ID
     pub entity_id: Option<String>,
 
+    // Modbus-specific
+    /// Modbus TCP host (IP or hostname of the PLC / gateway)
+    pub host: Option<String>,
+    /// Modbus TCP port (defaults to 502 when unset)
+    pub port: Option<u16>,
+    /// Modbus unit / slave identifier (defaults to 1 when unset)
+    pub slave_id: Option<u8>,
+    /// Shorthand register map: metric name -> holding register address (u16).
+    /// Richer definitions (register type, data type, scale) go in
+    /// `extra["modbus_registers"]`; see `adapters::modbus::ModbusRegister`.
+    pub register_map: Option<HashMap<String, u16>>,
+
     // Generic metadata
     /// Additional protocol-specific parameters
     #[serde(flatten)]
@@ -312,6 +348,16 @@ impl ConnectionConfig {
             ..Default::default()
         }
     }
+
+    /// Create Modbus TCP connection config
+    pub fn modbus_tcp(host: impl Into<String>, port: u16, slave_id: u8) -> Self {
+        Self {
+            host: Some(host.into()),
+            port: Some(port),
+            slave_id: Some(slave_id),
+            ..Default::default()
+        }
+    }
 }
 
 // ========== Conversion Functions for Storage ==========
@@ -375,6 +421,10 @@ fn convert_connection_config(
         command_topic: storage_config.command_topic,
         json_path: storage_config.json_path,
         entity_id: storage_config.entity_id,
+        host: storage_config.host,
+        port: storage_config.port,
+        slave_id: storage_config.slave_id,
+        register_map: storage_config.register_map,
         extra: storage_config.extra,
     }
 }
@@ -387,10 +437,10 @@ fn convert_connection_config_to_storage(
         telemetry_topic: local_config.telemetry_topic,
         command_topic: local_config.command_topic,
         json_path: local_config.json_path,
-        host: None,
-        port: None,
-        slave_id: None,
-        register_map: None,
+        host: local_config.host,
+        port: local_config.port,
+        slave_id: local_config.slave_id,
+        register_map: local_config.register_map,
         entity_id: local_config.entity_id,
         extra: local_config.extra,
     }
@@ -405,6 +455,8 @@ pub struct DeviceRegistry {
     devices: DashMap<String, DeviceConfig>,
     /// Index: device_type -> set of device_ids
     type_index: DashMap<String, Vec<String>>,
+    /// Device groups indexed by group id
+    groups: DashMap<String, DeviceGroup>,
     /// Optional persistent storage backend
     storage: Option<Arc<DeviceRegistryStore>>,
     /// Whether to auto-save after modifications
@@ -424,6 +476,7 @@ impl DeviceRegistry {
             templates: DashMap::new(),
             devices: DashMap::new(),
             type_index: DashMap::new(),
+            groups: DashMap::new(),
             storage: None,
             auto_save: AtomicBool::new(false),
         }
@@ -464,6 +517,7 @@ impl DeviceRegistry {
             templates: DashMap::new(),
             devices: DashMap::new(),
             type_index: DashMap::new(),
+            groups: DashMap::new(),
             storage: Some(store),
             auto_save: AtomicBool::new(true),
         };
@@ -561,7 +615,11 @@ impl DeviceRegistry {
                     })
                     .collect(),
                 default_offline_timeout_secs: storage_template.default_offline_timeout_secs,
+                expected_interval_secs: storage_template.expected_interval_secs,
                 store_raw: storage_template.store_raw,
+                binary_codec: storage_template
+                    .binary_codec
+                    .and_then(|v| serde_json::from_value(v).ok()),
             };
 
             self.templates
@@ -596,6 +654,9 @@ impl DeviceRegistry {
                 adapter_id: storage_device.adapter_id,
                 last_seen,
                 offline_timeout_secs: storage_device.offline_timeout_secs,
+                tags: storage_device.tags,
+                location: storage_device.location,
+                tenant_id: storage_device.tenant_id,
             };
 
             let device_id = config.device_id.clone();
@@ -609,6 +670,14 @@ impl DeviceRegistry {
                 .push(device_id);
         }
 
+        // Load device groups
+        let storage_groups = store
+            .list_groups()
+            .map_err(|e| DeviceError::Storage(format!("Failed to load device groups: {}", e)))?;
+        for group in storage_groups {
+            self.groups.insert(group.id.clone(), group);
+        }
+
         // Persist last_seen migration for old devices
         if !migrated_devices.is_empty() {
             tracing::info!(
@@ -715,7 +784,12 @@ impl DeviceRegistry {
                     })
                     .collect(),
                 default_offline_timeout_secs: template.default_offline_timeout_secs,
+                expected_interval_secs: template.expected_interval_secs,
                 store_raw: template.store_raw,
+                binary_codec: template
+                    .binary_codec
+                    .as_ref()
+                    .and_then(|c| serde_json::to_value(c).ok()),
                 builtin_version: None,
             };
             store
@@ -738,6 +812,9 @@ impl DeviceRegistry {
                 adapter_id: device.adapter_id.clone(),
                 last_seen: device.last_seen,
                 offline_timeout_secs: device.offline_timeout_secs,
+                tags: device.tags.clone(),
+                location: device.location.clone(),
+                tenant_id: device.tenant_id.clone(),
             };
             store
                 .save_device(&storage_config)
@@ -852,7 +929,12 @@ impl DeviceRegistry {
                 })
                 .collect(),
             default_offline_timeout_secs: template.default_offline_timeout_secs,
+            expected_interval_secs: template.expected_interval_secs,
             store_raw: template.store_raw,
+            binary_codec: template
+                .binary_codec
+                .as_ref()
+                .and_then(|c| serde_json::to_value(c).ok()),
             builtin_version: None,
         };
 
@@ -944,6 +1026,12 @@ impl DeviceRegistry {
             }
         }
 
+        if let Some(codec) = &template.binary_codec {
+            codec
+                .validate()
+                .map_err(|e| DeviceError::InvalidParameter(format!("binary_codec: {}", e)))?;
+        }
+
         Ok(())
     }
 
@@ -966,6 +1054,9 @@ impl DeviceRegistry {
             ));
         }
 
+        // Virtual devices must carry a valid spec
+        VirtualDeviceSpec::from_config(&config)?;
+
         let device_id = config.device_id.clone();
         let device_type = config.device_type.clone();
 
@@ -996,6 +1087,9 @@ impl DeviceRegistry {
                             adapter_id: updated.adapter_id.clone(),
                             last_seen: updated.last_seen,
                             offline_timeout_secs: updated.offline_timeout_secs,
+                            tags: updated.tags.clone(),
+                            location: updated.location.clone(),
+                            tenant_id: updated.tenant_id.clone(),
                         };
                         let _ = storage.save_device(&sc);
                     }
@@ -1018,6 +1112,9 @@ impl DeviceRegistry {
                 adapter_id: config.adapter_id.clone(),
                 last_seen: config.last_seen,
                 offline_timeout_secs: config.offline_timeout_secs,
+                tags: config.tags.clone(),
+                location: config.location.clone(),
+                tenant_id: config.tenant_id.clone(),
             })
         } else {
             None
@@ -1073,6 +1170,15 @@ impl DeviceRegistry {
         self.devices.iter().map(|e| e.value().clone()).collect()
     }
 
+    /// List virtual (computed) devices
+    pub fn list_virtual_devices(&self) -> Vec<DeviceConfig> {
+        self.devices
+            .iter()
+            .filter(|e| is_virtual_device(e.value()))
+            .map(|e| e.value().clone())
+            .collect()
+    }
+
     /// Find a device by its telemetry topic
     /// This is used by MQTT adapters to route messages from custom topics
     pub fn find_device_by_telemetry_topic(&self, topic: &str) -> Option<(String, DeviceConfig)> {
@@ -1149,6 +1255,7 @@ impl DeviceRegistry {
                 "device_id in config must match the parameter".into(),
             ));
         }
+        VirtualDeviceSpec::from_config(&config)?;
 
         // Save new device_type before moving config
         let new_device_type = config.device_type.clone();
@@ -1166,6 +1273,9 @@ impl DeviceRegistry {
                 adapter_id: config.adapter_id.clone(),
                 last_seen: config.last_seen,
                 offline_timeout_secs: config.offline_timeout_secs,
+                tags: config.tags.clone(),
+                location: config.location.clone(),
+                tenant_id: config.tenant_id.clone(),
             })
         } else {
             None
@@ -1226,6 +1336,73 @@ impl DeviceRegistry {
         self.templates.len()
     }
 
+    // ========== Device Groups ==========
+
+    /// Create or replace a device group.
+    pub fn save_group(&self, mut group: DeviceGroup) -> Result<DeviceGroup, DeviceError> {
+        if group.id.trim().is_empty() || group.name.trim().is_empty() {
+            return Err(DeviceError::InvalidParameter(
+                "Group id and name are required".into(),
+            ));
+        }
+        let now = chrono::Utc::now().timestamp();
+        group.created_at = self
+            .groups
+            .get(&group.id)
+            .map(|g| g.created_at)
+            .unwrap_or(now);
+        group.updated_at = now;
+
+        if let Some(store) = &self.storage {
+            store
+                .save_group(&group)
+                .map_err(|e| DeviceError::Storage(format!("Failed to save group: {}", e)))?;
+        }
+        self.groups.insert(group.id.clone(), group.clone());
+        Ok(group)
+    }
+
+    /// Get a device group
+    pub fn get_group(&self, group_id: &str) -> Option<DeviceGroup> {
+        self.groups.get(group_id).map(|g| g.value().clone())
+    }
+
+    /// List all device groups
+    pub fn list_groups(&self) -> Vec<DeviceGroup> {
+        let mut groups: Vec<DeviceGroup> = self.groups.iter().map(|g| g.value().clone()).collect();
+        groups.sort_by(|a, b| a.name.cmp(&b.name));
+        groups
+    }
+
+    /// Delete a device group (member devices are untouched)
+    pub fn delete_group(&self, group_id: &str) -> Result<(), DeviceError> {
+        if self.groups.remove(group_id).is_none() {
+            return Err(DeviceError::NotFoundStr(format!("Group '{}'", group_id)));
+        }
+        if let Some(store) = &self.storage {
+            store
+                .delete_group(group_id)
+                .map_err(|e| DeviceError::Storage(format!("Failed to delete group: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Resolve the current members of a group: static members that still
+    /// exist plus every device matching the group's selector.
+    pub fn resolve_group(&self, group_id: &str) -> Result<Vec<DeviceConfig>, DeviceError> {
+        let group = self
+            .get_group(group_id)
+            .ok_or_else(|| DeviceError::NotFoundStr(format!("Group '{}'", group_id)))?;
+        let mut devices: Vec<DeviceConfig> = self
+            .devices
+            .iter()
+            .filter(|d| is_member(&group, d.value()))
+            .map(|d| d.value().clone())
+            .collect();
+        devices.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        Ok(devices)
+    }
+
     /// Get reference to storage backend (for command history, etc.)
     pub fn storage(&self) -> Option<&Arc<DeviceRegistryStore>> {
         self.storage.as_ref()
@@ -1235,6 +1412,7 @@ impl DeviceRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::group::GroupSelector;
 
     #[tokio::test]
     async fn test_template_registration() {
@@ -1277,6 +1455,9 @@ mod tests {
             adapter_id: None,
             last_seen: 0,
             offline_timeout_secs: None,
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
         };
 
         registry.register_device(config).await.unwrap();
@@ -1299,6 +1480,9 @@ mod tests {
             adapter_id: None,
             last_seen: 0,
             offline_timeout_secs: None,
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
         };
 
         let result = registry.register_device(config).await;
@@ -1324,6 +1508,9 @@ mod tests {
                 adapter_id: None,
                 last_seen: 0,
                 offline_timeout_secs: None,
+                tags: Vec::new(),
+                location: None,
+                tenant_id: Default::default(),
             };
             registry.register_device(config).await.unwrap();
         }
@@ -1332,6 +1519,59 @@ mod tests {
         assert_eq!(devices.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_resolve_group() {
+        let registry = DeviceRegistry::new();
+        let template = DeviceTypeTemplate::new("light", "Light");
+        registry.register_template(template).await.unwrap();
+
+        for (id, location) in [
+            ("l1", "hq/floor-2"),
+            ("l2", "hq/floor-2/room-1"),
+            ("l3", "hq/floor-3"),
+        ] {
+            let config = DeviceConfig {
+                device_id: id.to_string(),
+                name: id.to_string(),
+                device_type: "light".to_string(),
+                adapter_type: "mqtt".to_string(),
+                connection_config: ConnectionConfig::new(),
+                adapter_id: None,
+                last_seen: 0,
+                offline_timeout_secs: None,
+                tags: vec!["light".to_string()],
+                location: Some(location.to_string()),
+                tenant_id: Default::default(),
+            };
+            registry.register_device(config).await.unwrap();
+        }
+
+        registry
+            .save_group(DeviceGroup {
+                id: "floor2-lights".to_string(),
+                name: "Floor 2 lights".to_string(),
+                members: vec!["l3".to_string(), "gone".to_string()],
+                selector: Some(GroupSelector {
+                    tags: vec!["light".to_string()],
+                    location: Some("hq/floor-2".to_string()),
+                    device_type: None,
+                }),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let ids: Vec<_> = registry
+            .resolve_group("floor2-lights")
+            .unwrap()
+            .into_iter()
+            .map(|d| d.device_id)
+            .collect();
+        assert_eq!(ids, vec!["l1", "l2", "l3"]);
+
+        registry.delete_group("floor2-lights").unwrap();
+        assert!(registry.resolve_group("floor2-lights").is_err());
+    }
+
     #[tokio::test]
     async fn test_unregister_template_with_devices_fails() {
         let registry = DeviceRegistry::new();
@@ -1349,6 +1589,9 @@ mod tests {
             adapter_id: None,
             last_seen: 0,
             offline_timeout_secs: None,
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
         };
         registry.register_device(config).await.unwrap();
 
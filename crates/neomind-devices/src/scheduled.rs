@@ -0,0 +1,199 @@
+//! Scheduled and recurring device commands.
+//!
+//! A command can be set to run once at a given time or on a cron schedule
+//! ("turn on irrigation at 6am daily") without writing a rule or workflow.
+//! [`DeviceService`](crate::DeviceService) keeps the schedule here, persists
+//! it with the device registry, and a scheduler task sends each command
+//! through the normal command path when it comes due. Cron expressions use
+//! the six-field form with seconds (`0 0 6 * * *`) and are evaluated in UTC.
+//!
+//! A recurring command that came due while the server was down runs once on
+//! startup and then continues from the next occurrence; missed occurrences
+//! are not replayed.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use neomind_storage::device_registry::ScheduledCommandRecord;
+
+use crate::mdl::DeviceError;
+
+/// Maximum scheduled commands per device.
+pub const MAX_SCHEDULED_PER_DEVICE: usize = 100;
+
+/// Parse a cron recurrence.
+pub fn parse_recurrence(expr: &str) -> Result<cron::Schedule, DeviceError> {
+    cron::Schedule::from_str(expr)
+        .map_err(|e| DeviceError::InvalidParameter(format!("Invalid recurrence '{}': {}", expr, e)))
+}
+
+/// Next occurrence of `schedule` strictly after `after` (unix seconds).
+pub fn next_occurrence(schedule: &cron::Schedule, after: i64) -> Option<i64> {
+    let after = DateTime::<Utc>::from_timestamp(after, 0)?;
+    schedule.after(&after).next().map(|t| t.timestamp())
+}
+
+/// Pending scheduled commands, by schedule ID.
+#[derive(Debug, Default)]
+pub struct CommandSchedule {
+    commands: HashMap<String, ScheduledCommandRecord>,
+}
+
+impl CommandSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a command, or replace one with the same ID. Fails if the device
+    /// already has [`MAX_SCHEDULED_PER_DEVICE`] commands scheduled.
+    pub fn insert(&mut self, record: ScheduledCommandRecord) -> Result<(), DeviceError> {
+        let scheduled = self
+            .commands
+            .values()
+            .filter(|c| c.device_id == record.device_id && c.id != record.id)
+            .count();
+        if scheduled >= MAX_SCHEDULED_PER_DEVICE {
+            return Err(DeviceError::InvalidParameter(format!(
+                "Device '{}' already has {} scheduled commands",
+                record.device_id, MAX_SCHEDULED_PER_DEVICE
+            )));
+        }
+        self.commands.insert(record.id.clone(), record);
+        Ok(())
+    }
+
+    /// Remove a command of `device_id`. Returns it if it existed.
+    pub fn remove(&mut self, device_id: &str, id: &str) -> Option<ScheduledCommandRecord> {
+        if self.commands.get(id)?.device_id != device_id {
+            return None;
+        }
+        self.commands.remove(id)
+    }
+
+    /// Commands due at `now`, oldest first. One-off commands are removed;
+    /// recurring ones are kept with `next_run` moved to their next
+    /// occurrence after `now`, or removed if they have none.
+    pub fn take_due(&mut self, now: i64) -> Vec<ScheduledCommandRecord> {
+        let mut due: Vec<ScheduledCommandRecord> = self
+            .commands
+            .values()
+            .filter(|c| c.next_run <= now)
+            .cloned()
+            .collect();
+        due.sort_by(|a, b| a.next_run.cmp(&b.next_run).then_with(|| a.id.cmp(&b.id)));
+
+        for record in &due {
+            let next = record
+                .recurrence
+                .as_deref()
+                .and_then(|expr| parse_recurrence(expr).ok())
+                .and_then(|schedule| next_occurrence(&schedule, now));
+            match (next, self.commands.get_mut(&record.id)) {
+                (Some(next), Some(command)) => command.next_run = next,
+                _ => {
+                    self.commands.remove(&record.id);
+                }
+            }
+        }
+        due
+    }
+
+    /// The stored form of a command, if it is still scheduled.
+    pub fn get(&self, id: &str) -> Option<&ScheduledCommandRecord> {
+        self.commands.get(id)
+    }
+
+    /// Mutable access to a scheduled command.
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut ScheduledCommandRecord> {
+        self.commands.get_mut(id)
+    }
+
+    /// Commands scheduled for a device, soonest first.
+    pub fn for_device(&self, device_id: &str) -> Vec<ScheduledCommandRecord> {
+        let mut commands: Vec<_> = self
+            .commands
+            .values()
+            .filter(|c| c.device_id == device_id)
+            .cloned()
+            .collect();
+        commands.sort_by(|a, b| a.next_run.cmp(&b.next_run).then_with(|| a.id.cmp(&b.id)));
+        commands
+    }
+
+    /// Total number of scheduled commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduled(id: &str, next_run: i64, recurrence: Option<&str>) -> ScheduledCommandRecord {
+        ScheduledCommandRecord {
+            id: id.to_string(),
+            device_id: "pump1".to_string(),
+            command_name: "on".to_string(),
+            parameters: HashMap::new(),
+            recurrence: recurrence.map(str::to_string),
+            next_run,
+            created_at: 0,
+            last_run: None,
+            last_command_id: None,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_recurrence() {
+        let daily = parse_recurrence("0 0 6 * * *").unwrap();
+        // 1970-01-01 00:00:00 -> 06:00 the same day, then 06:00 the next day
+        assert_eq!(next_occurrence(&daily, 0), Some(6 * 3600));
+        assert_eq!(next_occurrence(&daily, 6 * 3600), Some(30 * 3600));
+        assert!(parse_recurrence("every morning").is_err());
+    }
+
+    #[test]
+    fn test_take_due() {
+        let mut schedule = CommandSchedule::new();
+        schedule.insert(scheduled("once", 100, None)).unwrap();
+        schedule
+            .insert(scheduled("daily", 50, Some("0 0 6 * * *")))
+            .unwrap();
+        schedule.insert(scheduled("later", 500, None)).unwrap();
+
+        let due: Vec<_> = schedule.take_due(100).into_iter().map(|c| c.id).collect();
+        assert_eq!(due, vec!["daily", "once"]);
+
+        // The one-off command is gone, the recurring one moved on
+        assert_eq!(schedule.len(), 2);
+        assert_eq!(schedule.get("daily").unwrap().next_run, 6 * 3600);
+        assert!(schedule.take_due(100).is_empty());
+    }
+
+    #[test]
+    fn test_per_device_limit_and_remove() {
+        let mut schedule = CommandSchedule::new();
+        for i in 0..MAX_SCHEDULED_PER_DEVICE {
+            schedule
+                .insert(scheduled(&format!("s{}", i), 100, None))
+                .unwrap();
+        }
+        assert!(schedule.insert(scheduled("extra", 100, None)).is_err());
+        // Replacing an existing command is not a new one
+        schedule.insert(scheduled("s0", 200, None)).unwrap();
+
+        assert!(schedule.remove("other-device", "s0").is_none());
+        assert!(schedule.remove("pump1", "s0").is_some());
+        assert_eq!(
+            schedule.for_device("pump1").len(),
+            MAX_SCHEDULED_PER_DEVICE - 1
+        );
+    }
+}
@@ -12,9 +12,21 @@ use tokio::sync::RwLock;
 use tokio::time::{interval, Duration};
 
 use super::adapter::{ConnectionStatus, DeviceAdapter};
+use super::binary_codec::BinaryCodec;
+use super::command_group::{
+    CommandGroup, CommandGroupResult, GroupCommand, GroupOrdering, GroupStatus,
+};
+use super::idempotency::{
+    CommandSubmission, IdempotencyConfig, IdempotencyKeys, PendingKey, Reservation,
+};
 use super::mdl::{DeviceError, MetricValue};
+use super::qos::{IngestQos, IngestQosConfig, SheddingReport};
 use super::registry::{DeviceConfig, DeviceRegistry, DeviceTypeTemplate};
+use super::scheduled::{self, CommandSchedule};
+use super::state_history::{DeviceStateSnapshot, DeviceStateStore};
+use super::store_forward::{OfflineCommandQueue, QueuedCommand, StoreForwardConfig};
 use super::telemetry::TimeSeriesStorage;
+use super::virtual_device::{self, VirtualDeviceSpec, VIRTUAL_ADAPTER_TYPE};
 use neomind_core::EventBus;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -43,7 +55,8 @@ pub type ExtensionCommandRouterFn = Arc<
 
 // Import storage types for command history persistence
 use neomind_storage::device_registry::{
-    CommandHistoryRecord as StorageCommandRecord, CommandStatus as StorageCommandStatus,
+    CommandHistoryRecord as StorageCommandRecord, CommandIdempotencyRecord,
+    CommandStatus as StorageCommandStatus, ScheduledCommandRecord,
 };
 
 /// Command history record
@@ -82,12 +95,17 @@ pub enum CommandStatus {
     Failed,
     /// Command timed out
     Timeout,
+    /// Command was queued for an offline device and its TTL ran out
+    Expired,
 }
 
 impl CommandStatus {
     /// Check if command is a terminal state
     pub fn is_terminal(&self) -> bool {
-        matches!(self, Self::Success | Self::Failed | Self::Timeout)
+        matches!(
+            self,
+            Self::Success | Self::Failed | Self::Timeout | Self::Expired
+        )
     }
 
     /// Convert to storage command status
@@ -98,6 +116,7 @@ impl CommandStatus {
             Self::Success => StorageCommandStatus::Completed,
             Self::Failed => StorageCommandStatus::Failed,
             Self::Timeout => StorageCommandStatus::Timeout,
+            Self::Expired => StorageCommandStatus::Expired,
         }
     }
 
@@ -109,6 +128,7 @@ impl CommandStatus {
             StorageCommandStatus::Completed => Self::Success,
             StorageCommandStatus::Failed => Self::Failed,
             StorageCommandStatus::Timeout => Self::Timeout,
+            StorageCommandStatus::Expired => Self::Expired,
         }
     }
 }
@@ -143,6 +163,24 @@ fn command_from_storage(record: StorageCommandRecord) -> CommandHistoryRecord {
     }
 }
 
+/// Persist an online/offline change so past status can be reconstructed
+async fn record_status_change(
+    telemetry_storage: &RwLock<Option<Arc<TimeSeriesStorage>>>,
+    device_id: &str,
+    online: bool,
+    timestamp: i64,
+) {
+    let Some(storage) = telemetry_storage.read().await.clone() else {
+        return;
+    };
+    if let Err(e) = DeviceStateStore::new(storage)
+        .record_status(device_id, online, timestamp)
+        .await
+    {
+        tracing::warn!("Failed to record status change for {}: {}", device_id, e);
+    }
+}
+
 /// Adapter information for API responses.
 ///
 /// This provides a simplified view of adapter state without the plugin system overhead.
@@ -225,6 +263,9 @@ pub struct HeartbeatConfig {
     pub offline_timeout: u64,
     /// Whether to automatically mark stale devices as offline
     pub auto_mark_offline: bool,
+    /// Extra seconds tolerated on top of a template's `expected_interval_secs`
+    /// before a device is considered offline (default: 60)
+    pub grace_period: u64,
 }
 
 impl Default for HeartbeatConfig {
@@ -233,6 +274,7 @@ impl Default for HeartbeatConfig {
             heartbeat_interval: 60,
             offline_timeout: 300,
             auto_mark_offline: true,
+            grace_period: 60,
         }
     }
 }
@@ -244,7 +286,42 @@ impl HeartbeatConfig {
             heartbeat_interval: interval_secs,
             offline_timeout: timeout_secs,
             auto_mark_offline: true,
+            grace_period: 60,
+        }
+    }
+
+    /// Set the grace period added to declared telemetry intervals
+    pub fn with_grace_period(mut self, grace_secs: u64) -> Self {
+        self.grace_period = grace_secs;
+        self
+    }
+
+    /// Resolve the offline timeout (seconds) for a device.
+    ///
+    /// Priority order (highest first):
+    /// 1. Per-device override (`DeviceConfig::offline_timeout_secs`)
+    /// 2. Template default (`DeviceTypeTemplate::default_offline_timeout_secs`)
+    /// 3. Template `expected_interval_secs` plus `grace_period`
+    /// 4. Global `offline_timeout`
+    pub fn offline_timeout_for(
+        &self,
+        device: &DeviceConfig,
+        template: Option<&DeviceTypeTemplate>,
+    ) -> u64 {
+        if let Some(secs) = device.offline_timeout_secs {
+            return secs;
         }
+        let Some(template) = template else {
+            return self.offline_timeout;
+        };
+        template
+            .default_offline_timeout_secs
+            .or_else(|| {
+                template
+                    .expected_interval_secs
+                    .map(|interval| interval.saturating_add(self.grace_period))
+            })
+            .unwrap_or(self.offline_timeout)
     }
 
     /// Get the interval as Duration
@@ -258,7 +335,8 @@ impl HeartbeatConfig {
     /// Uses the GLOBAL `offline_timeout` and silently ignores per-device overrides
     /// and template defaults. Callers MUST use
     /// `DeviceService::effective_offline_timeout(device_id)` instead, which resolves
-    /// the correct timeout via: device override > template default > global.
+    /// the correct timeout via: device override > template default >
+    /// expected interval + grace > global.
     #[deprecated(
         since = "0.8.18",
         note = "use DeviceService::effective_offline_timeout() instead"
@@ -327,6 +405,16 @@ pub struct DeviceService {
     /// Extension command router for extension-registered devices
     /// When set, commands for devices with adapter_type="extension" are routed through this callback
     extension_command_router: Arc<RwLock<Option<ExtensionCommandRouterFn>>>,
+    /// Store-and-forward settings; `None` means commands to offline devices are sent anyway
+    store_forward: Arc<RwLock<Option<StoreForwardConfig>>>,
+    /// Commands waiting for their device to come back online
+    offline_queue: Arc<RwLock<OfflineCommandQueue>>,
+    /// Telemetry load shedder; `None` means every point is stored
+    ingest_qos: Arc<RwLock<Option<Arc<IngestQos>>>>,
+    /// Idempotency keys of recent command submissions
+    idempotency: Arc<RwLock<IdempotencyKeys>>,
+    /// Commands scheduled for a later time or on a recurrence
+    schedule: Arc<RwLock<CommandSchedule>>,
 }
 
 impl DeviceService {
@@ -344,6 +432,11 @@ impl DeviceService {
             heartbeat_config: HeartbeatConfig::default(),
             heartbeat_running: Arc::new(RwLock::new(false)),
             extension_command_router: Arc::new(RwLock::new(None)),
+            store_forward: Arc::new(RwLock::new(None)),
+            offline_queue: Arc::new(RwLock::new(OfflineCommandQueue::new())),
+            ingest_qos: Arc::new(RwLock::new(None)),
+            idempotency: Arc::new(RwLock::new(IdempotencyKeys::default())),
+            schedule: Arc::new(RwLock::new(CommandSchedule::new())),
         }
     }
 
@@ -365,6 +458,11 @@ impl DeviceService {
             heartbeat_config,
             heartbeat_running: Arc::new(RwLock::new(false)),
             extension_command_router: Arc::new(RwLock::new(None)),
+            store_forward: Arc::new(RwLock::new(None)),
+            offline_queue: Arc::new(RwLock::new(OfflineCommandQueue::new())),
+            ingest_qos: Arc::new(RwLock::new(None)),
+            idempotency: Arc::new(RwLock::new(IdempotencyKeys::default())),
+            schedule: Arc::new(RwLock::new(CommandSchedule::new())),
         }
     }
 
@@ -386,30 +484,44 @@ impl DeviceService {
 
     /// Resolve the effective offline timeout (seconds) for a specific device.
     ///
-    /// Priority order (highest first):
-    /// 1. Per-device override (`DeviceConfig::offline_timeout_secs`)
-    /// 2. Template default (`DeviceTypeTemplate::default_offline_timeout_secs`)
-    /// 3. Global `HeartbeatConfig::offline_timeout`
+    /// See `HeartbeatConfig::offline_timeout_for` for the priority order.
+    /// Unknown devices fall back to the global `HeartbeatConfig::offline_timeout`.
     ///
     /// This is the canonical resolution used by API handlers when building
     /// `DeviceDto.online` and any consumer-visible "online within" check.
     pub fn effective_offline_timeout(&self, device_id: &str) -> u64 {
-        // Global fallback
-        let global = self.heartbeat_config.offline_timeout;
-
-        // Try per-device override
-        if let Some(device) = self.registry.get_device(device_id) {
-            if let Some(secs) = device.offline_timeout_secs {
-                return secs;
-            }
-            // Try template default
-            if let Some(template) = self.registry.get_template(&device.device_type) {
-                if let Some(secs) = template.default_offline_timeout_secs {
-                    return secs;
-                }
+        match self.registry.get_device(device_id) {
+            Some(device) => {
+                let template = self.registry.get_template(&device.device_type);
+                self.heartbeat_config
+                    .offline_timeout_for(&device, template.as_ref())
             }
+            None => self.heartbeat_config.offline_timeout,
         }
-        global
+    }
+
+    /// Reconstruct a device's metric values and online status at `at` (Unix
+    /// seconds). When no status change was recorded before `at`, the status
+    /// is inferred from telemetry using the device's offline timeout.
+    pub async fn state_at(
+        &self,
+        device_id: &str,
+        at: i64,
+    ) -> Result<DeviceStateSnapshot, DeviceError> {
+        if self.registry.get_device(device_id).is_none() {
+            return Err(DeviceError::NotFoundStr(device_id.to_string()));
+        }
+        let storage = self
+            .telemetry_storage
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| DeviceError::Storage("Telemetry storage not configured".into()))?;
+        let mut snapshot = DeviceStateStore::new(storage)
+            .state_at(device_id, at)
+            .await?;
+        snapshot.infer_status(self.effective_offline_timeout(device_id));
+        Ok(snapshot)
     }
 
     /// Start the device service - listens for device events and updates status
@@ -423,6 +535,16 @@ impl DeviceService {
             .unwrap_or_else(|e| {
                 tracing::warn!("Failed to load command history from storage: {}", e);
             });
+        self.load_idempotency_keys_from_storage()
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load command idempotency keys: {}", e);
+            });
+        self.load_scheduled_commands_from_storage()
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load scheduled commands: {}", e);
+            });
 
         // Migrate last_seen for old devices that have telemetry data but last_seen == 1 (sentinel)
         self.migrate_last_seen_from_telemetry().await;
@@ -448,15 +570,31 @@ impl DeviceService {
             let mut rx = event_bus.subscribe_filtered(filter);
             while let Some((event, _)) = rx.recv().await {
                 match event {
-                    neomind_core::NeoMindEvent::DeviceOnline { device_id, .. } => {
-                        let mut status = device_status.write().await;
-                        let entry = status.entry(device_id.clone()).or_default();
-                        entry.update(ConnectionStatus::Connected);
+                    neomind_core::NeoMindEvent::DeviceOnline {
+                        device_id,
+                        timestamp,
+                        ..
+                    } => {
+                        {
+                            let mut status = device_status.write().await;
+                            let entry = status.entry(device_id.clone()).or_default();
+                            entry.update(ConnectionStatus::Connected);
+                        }
+                        record_status_change(&telemetry_storage, &device_id, true, timestamp)
+                            .await;
                     }
-                    neomind_core::NeoMindEvent::DeviceOffline { device_id, .. } => {
-                        let mut status = device_status.write().await;
-                        let entry = status.entry(device_id.clone()).or_default();
-                        entry.update(ConnectionStatus::Disconnected);
+                    neomind_core::NeoMindEvent::DeviceOffline {
+                        device_id,
+                        timestamp,
+                        ..
+                    } => {
+                        {
+                            let mut status = device_status.write().await;
+                            let entry = status.entry(device_id.clone()).or_default();
+                            entry.update(ConnectionStatus::Disconnected);
+                        }
+                        record_status_change(&telemetry_storage, &device_id, false, timestamp)
+                            .await;
                     }
                     neomind_core::NeoMindEvent::DeviceTransportOnline {
                         device_id,
@@ -523,8 +661,11 @@ impl DeviceService {
                                     device_id
                                 );
                                 drop(status);
-                                // Publish DeviceOnline event so frontend can refresh
-                                let device_type = "_unknown".to_string(); // Will be looked up by frontend
+                                // Publish DeviceOnline event so frontend and rules can react
+                                let device_type = registry
+                                    .get_device(&device_id)
+                                    .map(|dc| dc.device_type)
+                                    .unwrap_or_else(|| "_unknown".to_string());
                                 event_bus_for_publish
                                     .publish(neomind_core::NeoMindEvent::DeviceOnline {
                                         device_id: device_id.clone(),
@@ -582,6 +723,20 @@ impl DeviceService {
                             {
                                 tracing::warn!("Failed to write telemetry to storage: {}", e);
                             }
+
+                            // Recompute virtual devices that aggregate this metric
+                            if !neomind_core::NeoMindEvent::is_virtual_device_metric(
+                                is_virtual, &metric,
+                            ) {
+                                virtual_device::refresh_dependents(
+                                    &registry,
+                                    storage,
+                                    &event_bus_for_publish,
+                                    &device_id,
+                                    &metric,
+                                )
+                                .await;
+                            }
                         } else {
                             tracing::warn!(
                                 "DeviceService telemetry_storage is None, cannot write metric {} for device {}",
@@ -638,17 +793,8 @@ impl DeviceService {
                     let device_checks: Vec<(_, u64)> = registered_devices
                         .iter()
                         .map(|dc| {
-                            let timeout = {
-                                let mut t = config.offline_timeout;
-                                if let Some(secs) = dc.offline_timeout_secs {
-                                    t = secs;
-                                } else if let Some(tpl) = registry.get_template(&dc.device_type) {
-                                    if let Some(secs) = tpl.default_offline_timeout_secs {
-                                        t = secs;
-                                    }
-                                }
-                                t
-                            };
+                            let template = registry.get_template(&dc.device_type);
+                            let timeout = config.offline_timeout_for(dc, template.as_ref());
                             (dc.clone(), timeout)
                         })
                         .collect();
@@ -793,12 +939,82 @@ impl DeviceService {
                         elapsed_since_last_seen: elapsed,
                         is_stale,
                         health_score,
+                        offline_timeout: effective_timeout as u64,
                     },
                 )
             })
             .collect()
     }
 
+    /// Summarize fleet availability across all registered devices.
+    ///
+    /// A device counts as online when it is connected and has reported within
+    /// its effective offline timeout; devices that never reported are counted
+    /// separately from devices that went offline.
+    pub async fn health_summary(&self) -> FleetHealthSummary {
+        self.health_summary_where(|_| true).await
+    }
+
+    /// Like [`Self::health_summary`], restricted to devices matching `include`
+    /// (e.g. a tenant scope check).
+    pub async fn health_summary_where<F>(&self, include: F) -> FleetHealthSummary
+    where
+        F: Fn(&DeviceConfig) -> bool,
+    {
+        let status_map = self.device_status.read().await;
+        let now = chrono::Utc::now().timestamp();
+        let mut summary = FleetHealthSummary::default();
+
+        for device in self.registry.list_devices() {
+            if !include(&device) {
+                continue;
+            }
+            let template = self.registry.get_template(&device.device_type);
+            let timeout = self
+                .heartbeat_config
+                .offline_timeout_for(&device, template.as_ref());
+            let status = status_map.get(&device.device_id);
+            let online = status.is_some_and(|s| s.is_connected_within(timeout));
+            let never_seen = status.is_none_or(|s| s.last_seen == 0);
+
+            let by_type = summary
+                .by_device_type
+                .entry(device.device_type.clone())
+                .or_default();
+            for counts in [&mut summary.fleet, by_type] {
+                counts.total += 1;
+                if online {
+                    counts.online += 1;
+                } else if never_seen {
+                    counts.never_seen += 1;
+                } else {
+                    counts.offline += 1;
+                }
+            }
+
+            if !online && !never_seen {
+                if let Some(status) = status {
+                    summary.offline_devices.push(OfflineDevice {
+                        device_id: device.device_id.clone(),
+                        device_type: device.device_type.clone(),
+                        last_seen: status.last_seen,
+                        offline_for_secs: (now - status.last_seen).max(0),
+                    });
+                }
+            }
+        }
+
+        summary.fleet.update_availability();
+        for counts in summary.by_device_type.values_mut() {
+            counts.update_availability();
+        }
+        summary
+            .offline_devices
+            .sort_by_key(|d| std::cmp::Reverse(d.offline_for_secs));
+        summary.generated_at = now;
+        summary
+    }
+
     /// Stop the heartbeat monitoring
     pub async fn stop_heartbeat(&self) {
         *self.heartbeat_running.write().await = false;
@@ -820,6 +1036,58 @@ pub struct DeviceHealth {
     pub is_stale: bool,
     /// Health score (0-100)
     pub health_score: u8,
+    /// Effective offline timeout applied to this device (seconds)
+    #[serde(default)]
+    pub offline_timeout: u64,
+}
+
+/// Online/offline counts for a group of devices
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct AvailabilityCounts {
+    /// Registered devices in the group
+    pub total: usize,
+    /// Devices connected and reporting within their offline timeout
+    pub online: usize,
+    /// Devices that reported before but are now past their offline timeout
+    pub offline: usize,
+    /// Devices that have never reported
+    pub never_seen: usize,
+    /// Percentage of devices online (0-100)
+    pub availability: f64,
+}
+
+impl AvailabilityCounts {
+    fn update_availability(&mut self) {
+        self.availability = if self.total == 0 {
+            0.0
+        } else {
+            self.online as f64 * 100.0 / self.total as f64
+        };
+    }
+}
+
+/// Device that is currently past its offline timeout
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct OfflineDevice {
+    pub device_id: String,
+    pub device_type: String,
+    /// Last activity timestamp
+    pub last_seen: i64,
+    /// Seconds since last activity
+    pub offline_for_secs: i64,
+}
+
+/// Fleet-wide availability statistics
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct FleetHealthSummary {
+    /// Counts across all registered devices
+    pub fleet: AvailabilityCounts,
+    /// Counts per device type
+    pub by_device_type: HashMap<String, AvailabilityCounts>,
+    /// Offline devices, longest outage first
+    pub offline_devices: Vec<OfflineDevice>,
+    /// Timestamp the summary was computed at
+    pub generated_at: i64,
 }
 
 impl DeviceService {
@@ -875,6 +1143,9 @@ impl DeviceService {
 
     /// Set telemetry storage
     pub async fn set_telemetry_storage(&self, storage: Arc<TimeSeriesStorage>) {
+        if let Some(qos) = self.ingest_qos.read().await.as_ref() {
+            storage.set_ingest_qos(Some(qos.clone()));
+        }
         *self.telemetry_storage.write().await = Some(storage);
     }
 
@@ -1068,6 +1339,7 @@ impl DeviceService {
         let device_type = config.device_type.clone();
         let adapter_type = config.adapter_type.clone();
         let target_adapter_id = config.adapter_id.clone();
+        let persisted_last_seen = config.last_seen;
 
         // First register the device in the registry
         self.registry.register_device(config).await?;
@@ -1082,7 +1354,9 @@ impl DeviceService {
                     device_id.clone(),
                     DeviceStatus {
                         status: ConnectionStatus::Disconnected,
-                        last_seen: chrono::Utc::now().timestamp(),
+                        // Registering is not a sighting: keep 0 for devices
+                        // that never reported so they count as never seen
+                        last_seen: persisted_last_seen,
                         adapter_id: target_adapter_id.clone(),
                         transport_connected: false,
                         transport_changed_at: 0,
@@ -1097,7 +1371,8 @@ impl DeviceService {
 
         // Then notify adapter(s) to subscribe to this device's telemetry topic.
         // Skip adapter subscription for extension-managed devices - the extension
-        // handles data collection itself via produce_metrics.
+        // handles data collection itself via produce_metrics. Virtual devices are
+        // computed from their members and have no adapter either.
         //
         // Binding rules (resolves the adapter_id=None ambiguity):
         // - adapter_id specified  → notify ONLY that exact adapter (deterministic).
@@ -1109,7 +1384,7 @@ impl DeviceService {
         //   data flows regardless. This matches the server-restart path where
         //   add_broker / add_broker_with_tls re-subscribe all registered devices'
         //   telemetry topics on every adapter.
-        if adapter_type != "extension" {
+        if adapter_type != "extension" && adapter_type != VIRTUAL_ADAPTER_TYPE {
             // Collect matching adapters first, then subscribe outside the lock to
             // avoid holding the adapters read lock across multiple async awaits.
             let matched = {
@@ -1358,6 +1633,29 @@ impl DeviceService {
         command_name: &str,
         params: HashMap<String, serde_json::Value>,
     ) -> Result<Option<MetricValue>, DeviceError> {
+        let mut command_id = None;
+        self.send_command_recorded(device_id, command_name, params, &mut command_id)
+            .await
+    }
+
+    /// Body of [`send_command`](Self::send_command). Sets `recorded_id` to the
+    /// command history ID once the command has been recorded.
+    async fn send_command_recorded(
+        &self,
+        device_id: &str,
+        command_name: &str,
+        params: HashMap<String, serde_json::Value>,
+        recorded_id: &mut Option<String>,
+    ) -> Result<Option<MetricValue>, DeviceError> {
+        // Virtual devices fan the command out to their members
+        if let Some(config) = self.registry.get_device(device_id) {
+            if let Some(spec) = VirtualDeviceSpec::from_config(&config)? {
+                return self
+                    .send_virtual_command(&config, &spec, command_name, params)
+                    .await;
+            }
+        }
+
         // Get device config and template
         let (config, template) = self.get_device_with_template(device_id).await?;
 
@@ -1380,6 +1678,35 @@ impl DeviceService {
         let command_id = self
             .add_command_to_history(device_id, command_name, params.clone())
             .await;
+        *recorded_id = Some(command_id.clone());
+
+        // Park the command if the device is offline and store-and-forward is on.
+        // Extension devices are always routed directly.
+        let store_forward = self.store_forward.read().await.clone();
+        if let Some(sf) = store_forward {
+            if config.adapter_type != "extension" && self.is_device_offline(device_id).await {
+                self.enqueue_offline_command(&sf, device_id, command_name, params, &command_id)
+                    .await;
+                return Ok(None);
+            }
+        }
+
+        self.dispatch_command(&config, command_def, &validated_params, params, &command_id)
+            .await
+    }
+
+    /// Deliver an already-recorded command through the extension router or
+    /// the device's adapter(s), updating its history status.
+    async fn dispatch_command(
+        &self,
+        config: &DeviceConfig,
+        command_def: &super::mdl_format::CommandDefinition,
+        validated_params: &HashMap<String, MetricValue>,
+        params: HashMap<String, serde_json::Value>,
+        command_id: &str,
+    ) -> Result<Option<MetricValue>, DeviceError> {
+        let device_id = config.device_id.as_str();
+        let command_name = command_def.name.as_str();
 
         // Route extension devices through the extension command router
         // (skip payload building — extensions receive raw params, not MQTT payloads)
@@ -1407,7 +1734,7 @@ impl DeviceService {
                     Ok(()) => {
                         self.update_command_status(
                             device_id,
-                            &command_id,
+                            command_id,
                             CommandStatus::Success,
                             Some("Command sent to extension successfully".into()),
                             None,
@@ -1418,7 +1745,7 @@ impl DeviceService {
                     Err(e) => {
                         self.update_command_status(
                             device_id,
-                            &command_id,
+                            command_id,
                             CommandStatus::Failed,
                             None,
                             Some(e.clone()),
@@ -1430,7 +1757,7 @@ impl DeviceService {
             } else {
                 self.update_command_status(
                     device_id,
-                    &command_id,
+                    command_id,
                     CommandStatus::Failed,
                     None,
                     Some("No extension command router configured".into()),
@@ -1442,8 +1769,18 @@ impl DeviceService {
             }
         }
 
-        // Build command payload from template (MQTT/adapter devices only)
-        let payload = self.build_command_payload(command_def, &validated_params)?;
+        // Build command payload (MQTT/adapter devices only): a binary frame
+        // when the device type's codec defines one for this command,
+        // otherwise the payload template
+        let codec = self
+            .registry
+            .get_template(&config.device_type)
+            .and_then(|t| t.binary_codec)
+            .filter(|c| c.commands.contains_key(command_name));
+        let payload = match codec {
+            Some(codec) => Self::build_binary_payload(&codec, command_def, validated_params)?,
+            None => self.build_command_payload(command_def, validated_params)?,
+        };
 
         // Determine command topic from device connection config
         let command_topic = config.connection_config.command_topic.clone();
@@ -1483,7 +1820,7 @@ impl DeviceService {
             };
             self.update_command_status(
                 device_id,
-                &command_id,
+                command_id,
                 CommandStatus::Failed,
                 None,
                 Some(err_msg.clone()),
@@ -1515,7 +1852,7 @@ impl DeviceService {
         if success_count > 0 {
             self.update_command_status(
                 device_id,
-                &command_id,
+                command_id,
                 CommandStatus::Success,
                 Some(format!("Command sent to {} adapter(s)", success_count)),
                 None,
@@ -1527,7 +1864,7 @@ impl DeviceService {
                 last_error.unwrap_or_else(|| "Failed to send command on any adapter".to_string());
             self.update_command_status(
                 device_id,
-                &command_id,
+                command_id,
                 CommandStatus::Failed,
                 None,
                 Some(err_msg.clone()),
@@ -1688,15 +2025,13 @@ impl DeviceService {
         }
     }
 
-    /// Build command payload from template
-    fn build_command_payload(
-        &self,
+    /// Merge fixed_values (template-declared constants the user never
+    /// sees) under user-supplied params. User params win on key
+    /// collision — fixed_values are defaults, not overrides.
+    fn merge_fixed_values(
         command_def: &super::mdl_format::CommandDefinition,
         params: &HashMap<String, MetricValue>,
-    ) -> Result<String, DeviceError> {
-        // Merge fixed_values (template-declared constants the user
-        // never sees) under user-supplied params. User params win on
-        // key collision — fixed_values are defaults, not overrides.
+    ) -> Result<HashMap<String, MetricValue>, DeviceError> {
         let mut merged = HashMap::new();
         for (k, v) in &command_def.fixed_values {
             merged.insert(k.clone(), Self::infer_metric_from_json(v)?);
@@ -1704,6 +2039,30 @@ impl DeviceService {
         for (k, v) in params {
             merged.insert(k.clone(), v.clone());
         }
+        Ok(merged)
+    }
+
+    /// Build a binary command frame with the device type's codec, text
+    /// encoded (hex / base64) as adapters carry payloads as strings.
+    fn build_binary_payload(
+        codec: &BinaryCodec,
+        command_def: &super::mdl_format::CommandDefinition,
+        params: &HashMap<String, MetricValue>,
+    ) -> Result<String, DeviceError> {
+        let merged = Self::merge_fixed_values(command_def, params)?;
+        let frame = codec
+            .encode_command(&command_def.name, &merged)
+            .map_err(|e| DeviceError::InvalidParameter(format!("binary payload: {e}")))?;
+        Ok(codec.encode_text(&frame))
+    }
+
+    /// Build command payload from template
+    fn build_command_payload(
+        &self,
+        command_def: &super::mdl_format::CommandDefinition,
+        params: &HashMap<String, MetricValue>,
+    ) -> Result<String, DeviceError> {
+        let mut merged = Self::merge_fixed_values(command_def, params)?;
 
         // Auto-inject system-level placeholders that should never
         // surface to the user. Today this is just `request_id` (used
@@ -1901,6 +2260,149 @@ impl DeviceService {
         Ok(result)
     }
 
+    // ========== Device Groups ==========
+
+    /// Current members of a device group
+    pub fn get_group_devices(&self, group_id: &str) -> Result<Vec<DeviceConfig>, DeviceError> {
+        self.registry.resolve_group(group_id)
+    }
+
+    /// Current members of a device group together with their status
+    pub async fn get_group_status(
+        &self,
+        group_id: &str,
+    ) -> Result<Vec<(DeviceConfig, DeviceStatus)>, DeviceError> {
+        let devices = self.registry.resolve_group(group_id)?;
+        let status_map = self.device_status.read().await;
+        Ok(devices
+            .into_iter()
+            .map(|d| {
+                let status = status_map.get(&d.device_id).cloned().unwrap_or_default();
+                (d, status)
+            })
+            .collect())
+    }
+
+    /// Send one command to every member of a group that supports it.
+    ///
+    /// Members whose template doesn't define the command are skipped. The
+    /// batch is non-atomic: one failing device doesn't undo the others.
+    pub async fn send_group_command(
+        &self,
+        group_id: &str,
+        command_name: &str,
+        params: HashMap<String, serde_json::Value>,
+        ordering: GroupOrdering,
+    ) -> Result<CommandGroupResult, DeviceError> {
+        let commands: Vec<GroupCommand> = self
+            .registry
+            .resolve_group(group_id)?
+            .into_iter()
+            .filter(|d| {
+                self.registry
+                    .get_template(&d.device_type)
+                    .is_some_and(|t| t.commands.iter().any(|c| c.name == command_name))
+            })
+            .map(|d| GroupCommand {
+                device_id: d.device_id,
+                command: command_name.to_string(),
+                params: params.clone(),
+                rollback: None,
+            })
+            .collect();
+
+        if commands.is_empty() {
+            return Err(DeviceError::InvalidCommand(format!(
+                "No device in group '{}' supports command '{}'",
+                group_id, command_name
+            )));
+        }
+
+        let mut group = CommandGroup::new(commands, ordering);
+        group.atomic = false;
+        Ok(group.execute(self).await)
+    }
+
+    /// Current members of a virtual device.
+    pub fn get_virtual_members(&self, device_id: &str) -> Result<Vec<DeviceConfig>, DeviceError> {
+        let config = self
+            .registry
+            .get_device(device_id)
+            .ok_or_else(|| DeviceError::NotFoundStr(format!("Device '{}'", device_id)))?;
+        let spec = VirtualDeviceSpec::from_config(&config)?.ok_or_else(|| {
+            DeviceError::InvalidParameter(format!("'{}' is not a virtual device", device_id))
+        })?;
+        Ok(self.virtual_members(&config, &spec))
+    }
+
+    fn virtual_members(
+        &self,
+        config: &DeviceConfig,
+        spec: &VirtualDeviceSpec,
+    ) -> Vec<DeviceConfig> {
+        let mut members: Vec<DeviceConfig> = self
+            .registry
+            .list_devices()
+            .into_iter()
+            .filter(|d| spec.includes(config, d))
+            .collect();
+        members.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        members
+    }
+
+    /// Send a command to every member of a virtual device that supports it.
+    ///
+    /// Non-atomic like [`Self::send_group_command`]; the per-member outcome is
+    /// returned as a JSON string.
+    async fn send_virtual_command(
+        &self,
+        config: &DeviceConfig,
+        spec: &VirtualDeviceSpec,
+        command_name: &str,
+        params: HashMap<String, serde_json::Value>,
+    ) -> Result<Option<MetricValue>, DeviceError> {
+        let commands: Vec<GroupCommand> = self
+            .virtual_members(config, spec)
+            .into_iter()
+            .filter(|d| {
+                self.registry
+                    .get_template(&d.device_type)
+                    .is_some_and(|t| t.commands.iter().any(|c| c.name == command_name))
+            })
+            .map(|d| GroupCommand {
+                device_id: d.device_id,
+                command: command_name.to_string(),
+                params: params.clone(),
+                rollback: None,
+            })
+            .collect();
+
+        if commands.is_empty() {
+            return Err(DeviceError::InvalidCommand(format!(
+                "No member of virtual device '{}' supports command '{}'",
+                config.device_id, command_name
+            )));
+        }
+
+        let mut group = CommandGroup::new(commands, GroupOrdering::Parallel);
+        group.atomic = false;
+        let result = group.execute(self).await;
+        if result.status == GroupStatus::Failed {
+            let errors: Vec<String> = result
+                .results
+                .iter()
+                .filter_map(|r| r.error.as_ref().map(|e| format!("{}: {}", r.device_id, e)))
+                .collect();
+            return Err(DeviceError::Communication(format!(
+                "Command '{}' failed on every member of '{}': {}",
+                command_name,
+                config.device_id,
+                errors.join("; ")
+            )));
+        }
+        Ok(serde_json::to_string(&result).ok().map(MetricValue::String))
+    }
+
     // ========== Helper Methods ==========
 
     /// Get registry reference (for external use)
@@ -1986,58 +2488,645 @@ impl DeviceService {
             .collect()
     }
 
-    // ========== Command History Management ==========
+    // ========== Ingestion QoS ==========
 
-    /// Add a command to history
-    pub async fn add_command_to_history(
-        &self,
-        device_id: &str,
-        command_name: &str,
-        parameters: HashMap<String, serde_json::Value>,
-    ) -> String {
-        let command_id = format!(
-            "cmd_{}",
-            self.command_id_counter.fetch_add(1, Ordering::Relaxed)
+    /// Enable telemetry load shedding: while a device, or all devices
+    /// together, publish faster than the configured rates, only every Nth
+    /// point of low-priority metrics is stored.
+    ///
+    /// Applies to the current telemetry storage and any set later; adapters
+    /// share that storage, so their writes are shed too.
+    pub async fn enable_ingest_qos(&self, config: IngestQosConfig) {
+        let qos = Arc::new(IngestQos::new(config));
+        if let Some(storage) = self.telemetry_storage.read().await.as_ref() {
+            storage.set_ingest_qos(Some(qos.clone()));
+        }
+        tracing::info!(
+            max_points_per_sec = qos.config().max_points_per_sec,
+            device_max_points_per_sec = qos.config().device_max_points_per_sec,
+            keep_every = qos.config().keep_every,
+            "Telemetry ingestion load shedding enabled"
         );
+        *self.ingest_qos.write().await = Some(qos);
+    }
 
-        let record = CommandHistoryRecord {
-            command_id: command_id.clone(),
-            device_id: device_id.to_string(),
-            command_name: command_name.to_string(),
-            parameters,
-            status: CommandStatus::Pending,
-            result: None,
-            error: None,
-            created_at: chrono::Utc::now().timestamp(),
-            completed_at: None,
-        };
-
-        // Clone for storage before moving into the HashMap
-        let record_for_storage = record.clone();
+    /// What the load shedder has dropped so far, or `None` if shedding is
+    /// not enabled.
+    pub async fn ingest_report(&self) -> Option<SheddingReport> {
+        self.ingest_qos.read().await.as_ref().map(|qos| qos.report())
+    }
 
-        let mut history = self.command_history.write().await;
-        let device_commands = history
-            .entry(device_id.to_string())
-            .or_insert_with(Vec::new);
-        device_commands.push(record);
+    // ========== Store-and-Forward ==========
 
-        // Trim history if exceeds max
-        if device_commands.len() > self.max_history_entries {
-            device_commands.remove(0);
-        }
-        drop(history);
+    /// Enable store-and-forward: commands for offline devices are queued and
+    /// delivered when the device comes back online, or expire after the TTL.
+    ///
+    /// Spawns a task that listens for `DeviceOnline` events and sweeps
+    /// expired commands every `sweep_interval_secs`.
+    pub async fn start_store_and_forward(self: &Arc<Self>, config: StoreForwardConfig) {
+        let sweep = Duration::from_secs(config.sweep_interval_secs.max(1));
+        *self.store_forward.write().await = Some(config);
+
+        let service = Arc::downgrade(self);
+        let mut rx = self
+            .event_bus
+            .subscribe_filtered(|event: &neomind_core::NeoMindEvent| {
+                matches!(event, neomind_core::NeoMindEvent::DeviceOnline { .. })
+            });
+        tokio::spawn(async move {
+            let mut timer = interval(sweep);
+            loop {
+                tokio::select! {
+                    event = rx.recv() => {
+                        let Some((event, _)) = event else { break };
+                        let Some(service) = service.upgrade() else { break };
+                        if let neomind_core::NeoMindEvent::DeviceOnline { device_id, .. } = event {
+                            service.flush_offline_commands(&device_id).await;
+                        }
+                    }
+                    _ = timer.tick() => {
+                        let Some(service) = service.upgrade() else { break };
+                        service.expire_offline_commands().await;
+                    }
+                }
+            }
+        });
 
-        // Persist to storage
-        self.save_command_to_storage(&record_for_storage).await;
+        tracing::info!("Device command store-and-forward enabled");
+    }
 
-        command_id
+    /// Whether the device is known to be offline. Devices without any status
+    /// yet (e.g. right after startup) are treated as reachable.
+    async fn is_device_offline(&self, device_id: &str) -> bool {
+        let status_map = self.device_status.read().await;
+        status_map
+            .get(device_id)
+            .is_some_and(|s| s.status == ConnectionStatus::Disconnected)
     }
 
-    /// Update command status in history
-    pub async fn update_command_status(
+    async fn enqueue_offline_command(
         &self,
+        config: &StoreForwardConfig,
         device_id: &str,
-        command_id: &str,
+        command_name: &str,
+        params: HashMap<String, serde_json::Value>,
+        command_id: &str,
+    ) {
+        let now = chrono::Utc::now().timestamp();
+        let queued = QueuedCommand {
+            command_id: command_id.to_string(),
+            device_id: device_id.to_string(),
+            command_name: command_name.to_string(),
+            params,
+            queued_at: now,
+            expires_at: now + config.ttl_secs as i64,
+        };
+        let dropped = self
+            .offline_queue
+            .write()
+            .await
+            .push(queued, config.max_per_device);
+
+        self.update_command_status(
+            device_id,
+            command_id,
+            CommandStatus::Pending,
+            Some("Device offline, command queued for delivery".into()),
+            None,
+        )
+        .await;
+        tracing::info!(
+            "Device {} offline, queued command {} ({})",
+            device_id,
+            command_name,
+            command_id
+        );
+
+        if let Some(dropped) = dropped {
+            self.mark_command_expired(&dropped, "Offline command queue full")
+                .await;
+        }
+    }
+
+    /// Deliver all queued commands for a device, oldest first.
+    pub async fn flush_offline_commands(&self, device_id: &str) {
+        let queued = self.offline_queue.write().await.take(device_id);
+        if queued.is_empty() {
+            return;
+        }
+        tracing::info!(
+            "Device {} back online, delivering {} queued command(s)",
+            device_id,
+            queued.len()
+        );
+
+        let now = chrono::Utc::now().timestamp();
+        for cmd in queued {
+            if cmd.expires_at <= now {
+                self.mark_command_expired(&cmd, "Device stayed offline past the command TTL")
+                    .await;
+                continue;
+            }
+            let prepared =
+                self.get_device_with_template(device_id)
+                    .await
+                    .and_then(|(config, template)| {
+                        let command_def = template
+                            .commands
+                            .iter()
+                            .find(|c| c.name == cmd.command_name)
+                            .cloned()
+                            .ok_or_else(|| {
+                                DeviceError::InvalidCommand(format!(
+                                    "Command '{}' no longer exists in template '{}'",
+                                    cmd.command_name, template.device_type
+                                ))
+                            })?;
+                        let validated =
+                            self.validate_command_params(&command_def, cmd.params.clone())?;
+                        Ok((config, command_def, validated))
+                    });
+            match prepared {
+                Ok((config, command_def, validated)) => {
+                    if let Err(e) = self
+                        .dispatch_command(
+                            &config,
+                            &command_def,
+                            &validated,
+                            cmd.params,
+                            &cmd.command_id,
+                        )
+                        .await
+                    {
+                        tracing::warn!(
+                            "Failed to deliver queued command {} to {}: {}",
+                            cmd.command_id,
+                            device_id,
+                            e
+                        );
+                    }
+                }
+                Err(e) => {
+                    self.update_command_status(
+                        device_id,
+                        &cmd.command_id,
+                        CommandStatus::Failed,
+                        None,
+                        Some(e.to_string()),
+                    )
+                    .await;
+                }
+            }
+        }
+    }
+
+    /// Drop queued commands whose TTL has passed, marking them `Expired`.
+    pub async fn expire_offline_commands(&self) {
+        let now = chrono::Utc::now().timestamp();
+        let expired = self.offline_queue.write().await.expire(now);
+        for cmd in expired {
+            self.mark_command_expired(&cmd, "Device stayed offline past the command TTL")
+                .await;
+        }
+    }
+
+    /// Commands currently queued for an offline device.
+    pub async fn get_queued_commands(&self, device_id: &str) -> Vec<QueuedCommand> {
+        self.offline_queue.read().await.pending(device_id)
+    }
+
+    async fn mark_command_expired(&self, cmd: &QueuedCommand, reason: &str) {
+        self.update_command_status(
+            &cmd.device_id,
+            &cmd.command_id,
+            CommandStatus::Expired,
+            None,
+            Some(reason.to_string()),
+        )
+        .await;
+        self.event_bus
+            .publish(neomind_core::NeoMindEvent::DeviceCommandExpired {
+                device_id: cmd.device_id.clone(),
+                command_id: cmd.command_id.clone(),
+                command: cmd.command_name.clone(),
+                queued_at: cmd.queued_at,
+                timestamp: chrono::Utc::now().timestamp(),
+            })
+            .await;
+        tracing::info!(
+            "Queued command {} for device {} expired: {}",
+            cmd.command_id,
+            cmd.device_id,
+            reason
+        );
+    }
+
+    // ========== Command Idempotency ==========
+
+    /// Set the idempotency key TTL. Keys already tracked keep their expiry.
+    pub async fn set_idempotency_config(&self, config: IdempotencyConfig) {
+        self.idempotency.write().await.set_config(config);
+    }
+
+    /// Send a command under an idempotency key. A retry with the same key
+    /// within the key TTL gets the first submission's outcome back, with
+    /// `replayed` set, instead of sending the command again. Keys are scoped
+    /// to `owner`, the submitting tenant or principal.
+    ///
+    /// Fails with `InvalidParameter` if the key was already used for a
+    /// different command, and with `AlreadyExists` while the first
+    /// submission is still running. A failed command is an `Err` outcome,
+    /// not an error, so that it can be replayed too.
+    pub async fn send_command_idempotent(
+        &self,
+        device_id: &str,
+        command_name: &str,
+        params: HashMap<String, serde_json::Value>,
+        owner: &str,
+        idempotency_key: &str,
+    ) -> Result<CommandSubmission, DeviceError> {
+        let now = chrono::Utc::now().timestamp();
+        let (reservation, expired) = {
+            let mut keys = self.idempotency.write().await;
+            let expired = keys.expire(now);
+            let reservation =
+                keys.reserve(owner, idempotency_key, device_id, command_name, &params, now);
+            (reservation, expired)
+        };
+        self.delete_idempotency_records(&expired);
+
+        let record = match reservation? {
+            Reservation::New(record) => *record,
+            Reservation::Completed(submission) => return Ok(submission),
+            Reservation::InFlight => {
+                return Err(DeviceError::AlreadyExists(format!(
+                    "Command with idempotency key '{}' is still in progress",
+                    idempotency_key
+                )));
+            }
+        };
+        self.save_idempotency_record(&record);
+
+        let pending = PendingKey::new(
+            self.idempotency.clone(),
+            self.registry.storage().cloned(),
+            owner,
+            idempotency_key,
+        );
+        let mut command_id = None;
+        let outcome = self
+            .send_command_recorded(device_id, command_name, params, &mut command_id)
+            .await
+            .map_err(|e| e.to_string());
+        pending.disarm();
+
+        let completed = self.idempotency.write().await.complete(
+            owner,
+            idempotency_key,
+            command_id.clone(),
+            &outcome,
+            chrono::Utc::now().timestamp(),
+        );
+        if let Some(record) = completed {
+            self.save_idempotency_record(&record);
+        }
+
+        Ok(CommandSubmission {
+            command_id,
+            outcome,
+            replayed: false,
+        })
+    }
+
+    /// Restore idempotency keys from storage (called on startup)
+    async fn load_idempotency_keys_from_storage(&self) -> Result<(), DeviceError> {
+        let Some(store) = self.registry.storage() else {
+            return Ok(());
+        };
+
+        let records = store.list_idempotency_records().map_err(|e| {
+            DeviceError::Storage(format!("Failed to load command idempotency keys: {}", e))
+        })?;
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let expired: Vec<String> = records
+            .iter()
+            .filter(|r| r.expires_at <= now)
+            .map(|r| r.id())
+            .collect();
+        let interrupted = self.idempotency.write().await.restore(records, now);
+        for record in &interrupted {
+            self.save_idempotency_record(record);
+        }
+        self.delete_idempotency_records(&expired);
+
+        tracing::info!(
+            "Loaded {} command idempotency keys from storage ({} interrupted)",
+            self.idempotency.read().await.len(),
+            interrupted.len()
+        );
+        Ok(())
+    }
+
+    fn save_idempotency_record(&self, record: &CommandIdempotencyRecord) {
+        let Some(store) = self.registry.storage() else {
+            return;
+        };
+        if let Err(e) = store.save_idempotency_record(record) {
+            tracing::warn!("Failed to save idempotency key {}: {}", record.key, e);
+        }
+    }
+
+    fn delete_idempotency_records(&self, keys: &[String]) {
+        let Some(store) = self.registry.storage() else {
+            return;
+        };
+        if keys.is_empty() {
+            return;
+        }
+        if let Err(e) = store.delete_idempotency_records(keys) {
+            tracing::warn!("Failed to delete expired idempotency keys: {}", e);
+        }
+    }
+
+    // ========== Scheduled Commands ==========
+
+    /// Schedule a command to run once at `run_at` (unix seconds), or on a
+    /// cron `recurrence` (six fields with seconds, UTC). With both, the
+    /// recurrence starts at `run_at`. The command and its parameters are
+    /// validated now, so a schedule that can never succeed is rejected up
+    /// front; device state (online, reachable) is only checked when it runs.
+    pub async fn schedule_command(
+        &self,
+        device_id: &str,
+        command_name: &str,
+        params: HashMap<String, serde_json::Value>,
+        run_at: Option<i64>,
+        recurrence: Option<&str>,
+    ) -> Result<ScheduledCommandRecord, DeviceError> {
+        let now = chrono::Utc::now().timestamp();
+        let next_run = match (run_at, recurrence) {
+            (None, None) => {
+                return Err(DeviceError::InvalidParameter(
+                    "A scheduled command needs a run time or a recurrence".into(),
+                ))
+            }
+            (Some(at), None) if at <= now => {
+                return Err(DeviceError::InvalidParameter(
+                    "Scheduled time is in the past".into(),
+                ))
+            }
+            (Some(at), None) => at,
+            (start, Some(expr)) => {
+                let schedule = scheduled::parse_recurrence(expr)?;
+                // Occurrences strictly after `start - 1` include `start` itself
+                let after = start.unwrap_or(now).max(now).saturating_sub(1);
+                scheduled::next_occurrence(&schedule, after).ok_or_else(|| {
+                    DeviceError::InvalidParameter(format!(
+                        "Recurrence '{}' has no upcoming occurrence",
+                        expr
+                    ))
+                })?
+            }
+        };
+
+        self.validate_scheduled_command(device_id, command_name, &params)
+            .await?;
+
+        let record = ScheduledCommandRecord {
+            id: format!("sched_{}", uuid::Uuid::new_v4()),
+            device_id: device_id.to_string(),
+            command_name: command_name.to_string(),
+            parameters: params,
+            recurrence: recurrence.map(str::to_string),
+            next_run,
+            created_at: now,
+            last_run: None,
+            last_command_id: None,
+            last_error: None,
+        };
+        self.schedule.write().await.insert(record.clone())?;
+        self.save_scheduled_command(&record);
+
+        tracing::info!(
+            "Scheduled command '{}' for device {} ({}), next run at {}",
+            command_name,
+            device_id,
+            record.id,
+            next_run
+        );
+        Ok(record)
+    }
+
+    /// Commands scheduled for a device, soonest first.
+    pub async fn list_scheduled_commands(&self, device_id: &str) -> Vec<ScheduledCommandRecord> {
+        self.schedule.read().await.for_device(device_id)
+    }
+
+    /// Cancel a scheduled command. Returns whether it was scheduled.
+    pub async fn cancel_scheduled_command(&self, device_id: &str, id: &str) -> bool {
+        let removed = self.schedule.write().await.remove(device_id, id).is_some();
+        if removed {
+            self.delete_scheduled_command(id);
+        }
+        removed
+    }
+
+    /// Start the task that sends scheduled commands when they come due.
+    pub fn start_command_scheduler(self: &Arc<Self>) {
+        let service = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut timer = interval(Duration::from_secs(1));
+            loop {
+                timer.tick().await;
+                let Some(service) = service.upgrade() else {
+                    break;
+                };
+                service
+                    .run_due_commands(chrono::Utc::now().timestamp())
+                    .await;
+            }
+        });
+
+        tracing::info!("Device command scheduler started");
+    }
+
+    /// Send every scheduled command due at `now` through the normal command
+    /// path and record the outcome. Commands whose device is gone are
+    /// dropped from the schedule.
+    async fn run_due_commands(&self, now: i64) {
+        let due = self.schedule.write().await.take_due(now);
+        for command in due {
+            let mut command_id = None;
+            let result = self
+                .send_command_recorded(
+                    &command.device_id,
+                    &command.command_name,
+                    command.parameters.clone(),
+                    &mut command_id,
+                )
+                .await;
+            if let Err(e) = &result {
+                tracing::warn!(
+                    "Scheduled command {} ('{}' on {}) failed: {}",
+                    command.id,
+                    command.command_name,
+                    command.device_id,
+                    e
+                );
+            }
+
+            let device_gone = matches!(
+                result,
+                Err(DeviceError::NotFound(_)) | Err(DeviceError::NotFoundStr(_))
+            ) && self.registry.get_device(&command.device_id).is_none();
+            let updated = {
+                let mut schedule = self.schedule.write().await;
+                if device_gone {
+                    schedule.remove(&command.device_id, &command.id);
+                }
+                schedule.get_mut(&command.id).map(|scheduled| {
+                    scheduled.last_run = Some(now);
+                    scheduled.last_command_id = command_id;
+                    scheduled.last_error = result.as_ref().err().map(|e| e.to_string());
+                    scheduled.clone()
+                })
+            };
+            match updated {
+                // Recurring: keep it with its next run and this run's outcome
+                Some(scheduled) => self.save_scheduled_command(&scheduled),
+                // One-off, cancelled meanwhile, or its device is gone
+                None => self.delete_scheduled_command(&command.id),
+            }
+        }
+    }
+
+    /// Check that a command can be sent to a device before scheduling it.
+    async fn validate_scheduled_command(
+        &self,
+        device_id: &str,
+        command_name: &str,
+        params: &HashMap<String, serde_json::Value>,
+    ) -> Result<(), DeviceError> {
+        // Virtual devices validate against their members when sent
+        if let Some(config) = self.registry.get_device(device_id) {
+            if VirtualDeviceSpec::from_config(&config)?.is_some() {
+                return Ok(());
+            }
+        }
+
+        let (_, template) = self.get_device_with_template(device_id).await?;
+        let command_def = template
+            .commands
+            .iter()
+            .find(|cmd| cmd.name == command_name)
+            .ok_or_else(|| {
+                DeviceError::InvalidCommand(format!(
+                    "Command '{}' not found in template '{}'",
+                    command_name, template.device_type
+                ))
+            })?;
+        self.validate_command_params(command_def, params.clone())?;
+        Ok(())
+    }
+
+    /// Restore scheduled commands from storage (called on startup)
+    async fn load_scheduled_commands_from_storage(&self) -> Result<(), DeviceError> {
+        let Some(store) = self.registry.storage() else {
+            return Ok(());
+        };
+
+        let records = store.list_scheduled_commands().map_err(|e| {
+            DeviceError::Storage(format!("Failed to load scheduled commands: {}", e))
+        })?;
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut schedule = self.schedule.write().await;
+        for record in records {
+            let id = record.id.clone();
+            if let Err(e) = schedule.insert(record) {
+                tracing::warn!("Dropping scheduled command {}: {}", id, e);
+            }
+        }
+        tracing::info!("Loaded {} scheduled commands from storage", schedule.len());
+        Ok(())
+    }
+
+    fn save_scheduled_command(&self, record: &ScheduledCommandRecord) {
+        let Some(store) = self.registry.storage() else {
+            return;
+        };
+        if let Err(e) = store.save_scheduled_command(record) {
+            tracing::warn!("Failed to save scheduled command {}: {}", record.id, e);
+        }
+    }
+
+    fn delete_scheduled_command(&self, id: &str) {
+        let Some(store) = self.registry.storage() else {
+            return;
+        };
+        if let Err(e) = store.delete_scheduled_command(id) {
+            tracing::warn!("Failed to delete scheduled command {}: {}", id, e);
+        }
+    }
+
+    // ========== Command History Management ==========
+
+    /// Add a command to history
+    pub async fn add_command_to_history(
+        &self,
+        device_id: &str,
+        command_name: &str,
+        parameters: HashMap<String, serde_json::Value>,
+    ) -> String {
+        let command_id = format!(
+            "cmd_{}",
+            self.command_id_counter.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let record = CommandHistoryRecord {
+            command_id: command_id.clone(),
+            device_id: device_id.to_string(),
+            command_name: command_name.to_string(),
+            parameters,
+            status: CommandStatus::Pending,
+            result: None,
+            error: None,
+            created_at: chrono::Utc::now().timestamp(),
+            completed_at: None,
+        };
+
+        // Clone for storage before moving into the HashMap
+        let record_for_storage = record.clone();
+
+        let mut history = self.command_history.write().await;
+        let device_commands = history
+            .entry(device_id.to_string())
+            .or_insert_with(Vec::new);
+        device_commands.push(record);
+
+        // Trim history if exceeds max
+        if device_commands.len() > self.max_history_entries {
+            device_commands.remove(0);
+        }
+        drop(history);
+
+        // Persist to storage
+        self.save_command_to_storage(&record_for_storage).await;
+
+        command_id
+    }
+
+    /// Update command status in history
+    pub async fn update_command_status(
+        &self,
+        device_id: &str,
+        command_id: &str,
         status: CommandStatus,
         result: Option<String>,
         error: Option<String>,
@@ -2213,6 +3302,9 @@ mod tests {
             adapter_id: None,
             last_seen: 0,
             offline_timeout_secs: None,
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
         };
 
         service.register_device(config).await.unwrap();
@@ -2221,6 +3313,233 @@ mod tests {
         assert!(retrieved.is_some());
     }
 
+    fn test_device(device_id: &str, device_type: &str) -> DeviceConfig {
+        DeviceConfig {
+            device_id: device_id.to_string(),
+            name: device_id.to_string(),
+            device_type: device_type.to_string(),
+            adapter_type: "mqtt".to_string(),
+            connection_config: ConnectionConfig::new(),
+            adapter_id: None,
+            last_seen: 0,
+            offline_timeout_secs: None,
+            tags: Vec::new(),
+            location: None,
+            tenant_id: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_offline_timeout_resolution() {
+        let config = HeartbeatConfig::new(60, 300).with_grace_period(30);
+        let mut device = test_device("sensor1", "test_sensor");
+        let mut template = DeviceTypeTemplate::new("test_sensor", "Test Sensor");
+
+        assert_eq!(config.offline_timeout_for(&device, None), 300);
+        assert_eq!(config.offline_timeout_for(&device, Some(&template)), 300);
+
+        template.expected_interval_secs = Some(120);
+        assert_eq!(config.offline_timeout_for(&device, Some(&template)), 150);
+
+        template.default_offline_timeout_secs = Some(600);
+        assert_eq!(config.offline_timeout_for(&device, Some(&template)), 600);
+
+        device.offline_timeout_secs = Some(45);
+        assert_eq!(config.offline_timeout_for(&device, Some(&template)), 45);
+    }
+
+    #[tokio::test]
+    async fn test_health_summary() {
+        let registry = Arc::new(DeviceRegistry::new());
+        let service = DeviceService::new(registry.clone(), EventBus::new());
+
+        let template = DeviceTypeTemplate::new("test_sensor", "Test Sensor");
+        service.register_template(template).await.unwrap();
+        for id in ["online", "offline", "silent"] {
+            service
+                .register_device(test_device(id, "test_sensor"))
+                .await
+                .unwrap();
+        }
+
+        service
+            .update_device_status("online", ConnectionStatus::Connected)
+            .await;
+        service
+            .update_device_status("offline", ConnectionStatus::Disconnected)
+            .await;
+
+        let summary = service.health_summary().await;
+        assert_eq!(summary.fleet.total, 3);
+        assert_eq!(summary.fleet.online, 1);
+        assert_eq!(summary.fleet.offline, 1);
+        assert_eq!(summary.fleet.never_seen, 1);
+        assert!((summary.fleet.availability - 100.0 / 3.0).abs() < 1e-9);
+        assert_eq!(summary.by_device_type["test_sensor"].total, 3);
+        assert_eq!(summary.offline_devices.len(), 1);
+        assert_eq!(summary.offline_devices[0].device_id, "offline");
+    }
+
+    #[tokio::test]
+    async fn test_send_command_idempotent() {
+        use crate::mdl_format::CommandDefinition;
+        use std::sync::atomic::AtomicUsize;
+
+        let registry = Arc::new(DeviceRegistry::new());
+        let service = DeviceService::new(registry.clone(), EventBus::new());
+
+        let mut template = DeviceTypeTemplate::new("valve", "Valve");
+        template.commands.push(CommandDefinition {
+            name: "open".to_string(),
+            display_name: "Open".to_string(),
+            payload_template: r#"{"cmd": "open"}"#.to_string(),
+            parameters: vec![],
+            samples: vec![],
+            description: String::new(),
+            fixed_values: HashMap::new(),
+            parameter_groups: vec![],
+        });
+        service.register_template(template).await.unwrap();
+        let mut device = test_device("valve1", "valve");
+        device.adapter_type = "extension".to_string();
+        device.adapter_id = Some("valve-ext".to_string());
+        service.register_device(device).await.unwrap();
+
+        let sent = Arc::new(AtomicUsize::new(0));
+        let counter = sent.clone();
+        service
+            .set_extension_command_router(Arc::new(move |_, _, _, _| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Ok(()) })
+            }))
+            .await;
+
+        let first = service
+            .send_command_idempotent("valve1", "open", HashMap::new(), "tenant:acme", "retry-1")
+            .await
+            .unwrap();
+        assert!(!first.replayed);
+        assert_eq!(first.outcome, Ok(None));
+
+        // The retry is answered from the key, not sent again
+        let retry = service
+            .send_command_idempotent("valve1", "open", HashMap::new(), "tenant:acme", "retry-1")
+            .await
+            .unwrap();
+        assert!(retry.replayed);
+        assert_eq!(retry.command_id, first.command_id);
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+        // Reusing the key for another command is rejected
+        let params = HashMap::from([("force".to_string(), serde_json::json!(true))]);
+        let reused = service
+            .send_command_idempotent("valve1", "open", params, "tenant:acme", "retry-1")
+            .await;
+        assert!(matches!(reused, Err(DeviceError::InvalidParameter(_))));
+
+        // Failures are replayed as well
+        let failed = service
+            .send_command_idempotent("valve1", "close", HashMap::new(), "tenant:acme", "retry-2")
+            .await
+            .unwrap();
+        assert!(failed.outcome.is_err());
+        assert!(failed.command_id.is_none());
+        let failed_retry = service
+            .send_command_idempotent("valve1", "close", HashMap::new(), "tenant:acme", "retry-2")
+            .await
+            .unwrap();
+        assert!(failed_retry.replayed);
+        assert_eq!(failed_retry.outcome, failed.outcome);
+
+        // Another owner's key of the same name sends its own command
+        let other = service
+            .send_command_idempotent("valve1", "open", HashMap::new(), "tenant:globex", "retry-1")
+            .await
+            .unwrap();
+        assert!(!other.replayed);
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_commands() {
+        use crate::mdl_format::CommandDefinition;
+        use std::sync::atomic::AtomicUsize;
+
+        let registry = Arc::new(DeviceRegistry::new());
+        let service = DeviceService::new(registry.clone(), EventBus::new());
+
+        let mut template = DeviceTypeTemplate::new("pump", "Pump");
+        template.commands.push(CommandDefinition {
+            name: "on".to_string(),
+            display_name: "On".to_string(),
+            payload_template: r#"{"cmd": "on"}"#.to_string(),
+            parameters: vec![],
+            samples: vec![],
+            description: String::new(),
+            fixed_values: HashMap::new(),
+            parameter_groups: vec![],
+        });
+        service.register_template(template).await.unwrap();
+        let mut device = test_device("pump1", "pump");
+        device.adapter_type = "extension".to_string();
+        device.adapter_id = Some("pump-ext".to_string());
+        service.register_device(device).await.unwrap();
+
+        let sent = Arc::new(AtomicUsize::new(0));
+        let counter = sent.clone();
+        service
+            .set_extension_command_router(Arc::new(move |_, _, _, _| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Ok(()) })
+            }))
+            .await;
+
+        let now = chrono::Utc::now().timestamp();
+        let once = service
+            .schedule_command("pump1", "on", HashMap::new(), Some(now + 60), None)
+            .await
+            .unwrap();
+        let daily = service
+            .schedule_command("pump1", "on", HashMap::new(), None, Some("0 0 6 * * *"))
+            .await
+            .unwrap();
+        assert_eq!(service.list_scheduled_commands("pump1").await.len(), 2);
+
+        // Rejected up front: unknown command, past time, bad recurrence
+        assert!(service
+            .schedule_command("pump1", "off", HashMap::new(), Some(now + 60), None)
+            .await
+            .is_err());
+        assert!(service
+            .schedule_command("pump1", "on", HashMap::new(), Some(now - 60), None)
+            .await
+            .is_err());
+        assert!(service
+            .schedule_command("pump1", "on", HashMap::new(), None, Some("daily"))
+            .await
+            .is_err());
+
+        // Nothing is due yet
+        service.run_due_commands(now).await;
+        assert_eq!(sent.load(Ordering::SeqCst), 0);
+
+        // The one-off command runs and is gone; the daily one runs and moves on
+        service
+            .run_due_commands(daily.next_run.max(once.next_run))
+            .await;
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+        let remaining = service.list_scheduled_commands("pump1").await;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, daily.id);
+        assert!(remaining[0].next_run > daily.next_run);
+        assert!(remaining[0].last_command_id.is_some());
+        assert!(remaining[0].last_error.is_none());
+
+        assert!(!service.cancel_scheduled_command("other", &daily.id).await);
+        assert!(service.cancel_scheduled_command("pump1", &daily.id).await);
+        assert!(service.list_scheduled_commands("pump1").await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_command_parameter_validation() {
         let event_bus = EventBus::new();
@@ -2302,6 +3621,35 @@ mod tests {
         assert!(payload.contains("25.5"));
     }
 
+    #[test]
+    fn test_build_binary_payload() {
+        use crate::mdl_format::CommandDefinition;
+
+        let codec: BinaryCodec = serde_json::from_value(serde_json::json!({
+            "commands": {
+                "set_interval": [
+                    { "name": "opcode", "offset": 0, "length": 1 },
+                    { "name": "seconds", "offset": 1, "length": 2 }
+                ]
+            }
+        }))
+        .unwrap();
+        let command_def = CommandDefinition {
+            name: "set_interval".to_string(),
+            display_name: "Set Interval".to_string(),
+            payload_template: String::new(),
+            parameters: vec![],
+            samples: vec![],
+            description: String::new(),
+            fixed_values: HashMap::from([("opcode".to_string(), serde_json::json!(2))]),
+            parameter_groups: vec![],
+        };
+
+        let params = HashMap::from([("seconds".to_string(), MetricValue::Integer(600))]);
+        let payload = DeviceService::build_binary_payload(&codec, &command_def, &params).unwrap();
+        assert_eq!(payload, "020258");
+    }
+
     #[test]
     fn test_resolve_command_image_urls_resolves_api_images() {
         // A command param carrying an /api/images/ internal URL must be resolved
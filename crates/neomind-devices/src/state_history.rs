@@ -0,0 +1,224 @@
+//! Historical device state ("as-of" queries).
+//!
+//! Reconstructs what a device looked like at a past moment: the last value
+//! of every metric reported at or before that time, and whether the device
+//! was online. Metric values come straight from telemetry; online/offline
+//! changes are recorded by the device service as a small timeseries of their
+//! own (`device_status:{id}` / `online`) whenever a `DeviceOnline` or
+//! `DeviceOffline` event is seen.
+//!
+//! This answers incident questions such as "what was the valve position when
+//! the alarm fired, and was the valve even connected?".
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use super::mdl::{DeviceError, MetricValue};
+use super::telemetry::{DataPoint, TimeSeriesStorage};
+
+/// Source ID prefix for recorded status changes
+pub const STATUS_SOURCE_PREFIX: &str = "device_status:";
+
+/// Metric holding the recorded online flag
+pub const STATUS_METRIC: &str = "online";
+
+fn status_source(device_id: &str) -> String {
+    format!("{}{}", STATUS_SOURCE_PREFIX, device_id)
+}
+
+/// A metric's value as of the snapshot time.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricAt {
+    pub value: serde_json::Value,
+    /// When this value was reported
+    pub timestamp: i64,
+    /// Seconds between the report and the snapshot time
+    pub age_secs: i64,
+}
+
+/// Where a snapshot's online flag came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusSource {
+    /// A recorded online/offline event
+    Recorded,
+    /// Derived from how recently the device reported telemetry
+    Inferred,
+    /// No status event and no telemetry before the snapshot time
+    Unknown,
+}
+
+/// A device's reconstructed state at a past moment.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceStateSnapshot {
+    pub device_id: String,
+    /// Snapshot time (Unix seconds)
+    pub at: i64,
+    pub online: Option<bool>,
+    pub status_source: StatusSource,
+    /// Time of the status event the online flag is based on
+    pub status_changed_at: Option<i64>,
+    /// Newest telemetry report at or before `at`
+    pub last_seen: Option<i64>,
+    /// Last known value per metric
+    pub metrics: BTreeMap<String, MetricAt>,
+}
+
+impl DeviceStateSnapshot {
+    /// Fill in the online flag from telemetry when no status event was
+    /// recorded before the snapshot time: online if the device reported
+    /// within `offline_timeout` seconds.
+    pub fn infer_status(&mut self, offline_timeout: u64) {
+        if self.status_source != StatusSource::Unknown {
+            return;
+        }
+        if let Some(last_seen) = self.last_seen {
+            self.online = Some(self.at - last_seen < offline_timeout as i64);
+            self.status_source = StatusSource::Inferred;
+        }
+    }
+}
+
+/// Reads and records the history needed for as-of queries.
+#[derive(Clone)]
+pub struct DeviceStateStore {
+    storage: Arc<TimeSeriesStorage>,
+}
+
+impl DeviceStateStore {
+    pub fn new(storage: Arc<TimeSeriesStorage>) -> Self {
+        Self { storage }
+    }
+
+    /// Record an online/offline change
+    pub async fn record_status(
+        &self,
+        device_id: &str,
+        online: bool,
+        timestamp: i64,
+    ) -> Result<(), DeviceError> {
+        let point = DataPoint::new(timestamp, MetricValue::Boolean(online));
+        self.storage
+            .write(&status_source(device_id), STATUS_METRIC, point)
+            .await
+    }
+
+    /// Reconstruct a device's metric values and status at `at` (Unix seconds).
+    ///
+    /// The status is `Unknown` when no event was recorded before `at`; see
+    /// [`DeviceStateSnapshot::infer_status`].
+    pub async fn state_at(
+        &self,
+        device_id: &str,
+        at: i64,
+    ) -> Result<DeviceStateSnapshot, DeviceError> {
+        let source = format!("device:{}", device_id);
+        let mut metrics = BTreeMap::new();
+        for metric in self.storage.list_metrics(&source).await? {
+            // Raw payloads are large and duplicate the extracted metrics
+            if metric.is_empty() || metric == "_raw" {
+                continue;
+            }
+            if let Some(point) = self.storage.latest_at(&source, &metric, at).await? {
+                metrics.insert(
+                    metric,
+                    MetricAt {
+                        value: point.value.to_json_value(),
+                        timestamp: point.timestamp,
+                        age_secs: at - point.timestamp,
+                    },
+                );
+            }
+        }
+        let last_seen = metrics.values().map(|m| m.timestamp).max();
+
+        let status = self
+            .storage
+            .latest_at(&status_source(device_id), STATUS_METRIC, at)
+            .await?;
+        let (online, status_source, status_changed_at) = match status {
+            Some(point) => (
+                point.value.as_bool(),
+                StatusSource::Recorded,
+                Some(point.timestamp),
+            ),
+            None => (None, StatusSource::Unknown, None),
+        };
+
+        Ok(DeviceStateSnapshot {
+            device_id: device_id.to_string(),
+            at,
+            online,
+            status_source,
+            status_changed_at,
+            last_seen,
+            metrics,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_state_at() {
+        let storage = Arc::new(TimeSeriesStorage::memory().unwrap());
+        for (ts, position) in [(1000, 0), (1100, 40), (1300, 100)] {
+            let point = DataPoint::new(ts, MetricValue::Integer(position));
+            storage
+                .write("device:valve-1", "position", point)
+                .await
+                .unwrap();
+        }
+        let raw = DataPoint::new(1100, MetricValue::String("{}".into()));
+        storage.write("device:valve-1", "_raw", raw).await.unwrap();
+
+        let store = DeviceStateStore::new(storage.clone());
+        store.record_status("valve-1", true, 1000).await.unwrap();
+        store.record_status("valve-1", false, 1250).await.unwrap();
+        storage.flush().unwrap();
+
+        let state = store.state_at("valve-1", 1200).await.unwrap();
+        assert_eq!(state.metrics["position"].value, serde_json::json!(40));
+        assert_eq!(state.metrics["position"].age_secs, 100);
+        assert!(!state.metrics.contains_key("_raw"));
+        assert_eq!(state.online, Some(true));
+        assert_eq!(state.status_source, StatusSource::Recorded);
+        assert_eq!(state.last_seen, Some(1100));
+
+        let state = store.state_at("valve-1", 1260).await.unwrap();
+        assert_eq!(state.online, Some(false));
+        assert_eq!(state.status_changed_at, Some(1250));
+
+        // Before any telemetry or status event
+        let mut state = store.state_at("valve-1", 900).await.unwrap();
+        assert!(state.metrics.is_empty());
+        state.infer_status(300);
+        assert_eq!(state.status_source, StatusSource::Unknown);
+        assert_eq!(state.online, None);
+    }
+
+    #[tokio::test]
+    async fn test_infer_status() {
+        let storage = Arc::new(TimeSeriesStorage::memory().unwrap());
+        let point = DataPoint::new(1000, MetricValue::Float(21.5));
+        storage
+            .write("device:t1", "temperature", point)
+            .await
+            .unwrap();
+        storage.flush().unwrap();
+        let store = DeviceStateStore::new(storage);
+
+        let mut state = store.state_at("t1", 1100).await.unwrap();
+        state.infer_status(300);
+        assert_eq!(state.online, Some(true));
+        assert_eq!(state.status_source, StatusSource::Inferred);
+
+        let mut state = store.state_at("t1", 2000).await.unwrap();
+        state.infer_status(300);
+        assert_eq!(state.online, Some(false));
+    }
+}
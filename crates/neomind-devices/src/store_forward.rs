@@ -0,0 +1,172 @@
+//! Store-and-forward for commands sent to offline devices.
+//!
+//! When enabled on [`DeviceService`](crate::DeviceService), a command for a
+//! device known to be offline is parked here instead of failing. The queue is
+//! drained as soon as the device reports back online; commands still waiting
+//! when their TTL runs out are dropped and marked `CommandStatus::Expired`.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+/// Queue TTL in seconds; `0` disables store-and-forward.
+pub const COMMAND_QUEUE_TTL_ENV: &str = "NEOMIND_COMMAND_QUEUE_TTL_SECS";
+
+/// Store-and-forward settings.
+#[derive(Debug, Clone)]
+pub struct StoreForwardConfig {
+    /// How long a queued command stays deliverable (seconds).
+    pub ttl_secs: u64,
+    /// Maximum queued commands per device; the oldest is dropped beyond this.
+    pub max_per_device: usize,
+    /// How often expired commands are swept (seconds).
+    pub sweep_interval_secs: u64,
+}
+
+impl Default for StoreForwardConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 3600,
+            max_per_device: 50,
+            sweep_interval_secs: 30,
+        }
+    }
+}
+
+impl StoreForwardConfig {
+    /// Defaults with the TTL taken from `NEOMIND_COMMAND_QUEUE_TTL_SECS`.
+    /// Returns `None` when the TTL is set to `0`.
+    pub fn from_env() -> Option<Self> {
+        let mut config = Self::default();
+        if let Some(ttl) = std::env::var(COMMAND_QUEUE_TTL_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+        {
+            config.ttl_secs = ttl;
+        }
+        (config.ttl_secs > 0).then_some(config)
+    }
+}
+
+/// A command waiting for its device to come back online.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedCommand {
+    /// Command history ID
+    pub command_id: String,
+    pub device_id: String,
+    pub command_name: String,
+    pub params: HashMap<String, serde_json::Value>,
+    /// When the command was queued (unix seconds)
+    pub queued_at: i64,
+    /// When the command expires (unix seconds)
+    pub expires_at: i64,
+}
+
+/// Per-device FIFO queues of undelivered commands.
+#[derive(Debug, Default)]
+pub struct OfflineCommandQueue {
+    queues: HashMap<String, VecDeque<QueuedCommand>>,
+}
+
+impl OfflineCommandQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a command. Returns the oldest command for the device if it had
+    /// to be dropped to stay within `max_per_device`.
+    pub fn push(&mut self, command: QueuedCommand, max_per_device: usize) -> Option<QueuedCommand> {
+        let queue = self.queues.entry(command.device_id.clone()).or_default();
+        queue.push_back(command);
+        if queue.len() > max_per_device.max(1) {
+            queue.pop_front()
+        } else {
+            None
+        }
+    }
+
+    /// Remove and return all queued commands for a device, oldest first.
+    pub fn take(&mut self, device_id: &str) -> Vec<QueuedCommand> {
+        self.queues
+            .remove(device_id)
+            .map(Vec::from)
+            .unwrap_or_default()
+    }
+
+    /// Remove and return every command whose TTL has passed at `now`.
+    pub fn expire(&mut self, now: i64) -> Vec<QueuedCommand> {
+        let mut expired = Vec::new();
+        self.queues.retain(|_, queue| {
+            queue.retain(|cmd| {
+                if cmd.expires_at <= now {
+                    expired.push(cmd.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            !queue.is_empty()
+        });
+        expired
+    }
+
+    /// Commands currently queued for a device, oldest first.
+    pub fn pending(&self, device_id: &str) -> Vec<QueuedCommand> {
+        self.queues
+            .get(device_id)
+            .map(|q| q.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Total number of queued commands.
+    pub fn len(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(device: &str, id: &str, expires_at: i64) -> QueuedCommand {
+        QueuedCommand {
+            command_id: id.to_string(),
+            device_id: device.to_string(),
+            command_name: "set".to_string(),
+            params: HashMap::new(),
+            queued_at: 0,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_push_and_take_fifo() {
+        let mut queue = OfflineCommandQueue::new();
+        assert!(queue.push(queued("d1", "cmd_1", 100), 2).is_none());
+        assert!(queue.push(queued("d1", "cmd_2", 100), 2).is_none());
+        let dropped = queue.push(queued("d1", "cmd_3", 100), 2).unwrap();
+        assert_eq!(dropped.command_id, "cmd_1");
+
+        let ids: Vec<_> = queue.take("d1").into_iter().map(|c| c.command_id).collect();
+        assert_eq!(ids, vec!["cmd_2", "cmd_3"]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_expire() {
+        let mut queue = OfflineCommandQueue::new();
+        queue.push(queued("d1", "cmd_1", 10), 10);
+        queue.push(queued("d1", "cmd_2", 20), 10);
+        queue.push(queued("d2", "cmd_3", 10), 10);
+
+        let expired = queue.expire(10);
+        assert_eq!(expired.len(), 2);
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.pending("d1")[0].command_id, "cmd_2");
+        assert!(queue.pending("d2").is_empty());
+    }
+}
@@ -3,18 +3,22 @@
 //! This module provides time-series storage for device metrics using redb
 //! via the neomind_storage crate.
 
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use neomind_core::config::{device, device_env_vars};
 use neomind_storage::DataPoint as StorageDataPoint;
 use neomind_storage::TimeSeriesStore as StorageTimeSeriesStore;
 
 use super::mdl::{DeviceError, MetricValue};
+use super::qos::IngestQos;
 
 /// Time series data point
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,6 +151,10 @@ pub struct AggregatedData {
 /// All MetricValue types (Integer, Float, String, Boolean, Binary, Null) are stored.
 pub struct TimeSeriesStorage {
     store: std::sync::RwLock<Arc<StorageTimeSeriesStore>>,
+    /// Load shedder consulted for `device:` sources; see [`crate::qos`]
+    qos: std::sync::RwLock<Option<Arc<IngestQos>>>,
+    /// Hot series answering recent range reads; see [`MetricCache`]
+    metric_cache: std::sync::RwLock<Option<Arc<MetricCache>>>,
 }
 
 impl TimeSeriesStorage {
@@ -167,6 +175,8 @@ impl TimeSeriesStorage {
             .map_err(|e| DeviceError::Io(std::io::Error::other(e.to_string())))?;
         Ok(Self {
             store: std::sync::RwLock::new(store),
+            qos: std::sync::RwLock::new(None),
+            metric_cache: std::sync::RwLock::new(None),
         })
     }
 
@@ -176,6 +186,8 @@ impl TimeSeriesStorage {
             .map_err(|e| DeviceError::Io(std::io::Error::other(e.to_string())))?;
         Ok(Self {
             store: std::sync::RwLock::new(store),
+            qos: std::sync::RwLock::new(None),
+            metric_cache: std::sync::RwLock::new(None),
         })
     }
 
@@ -186,21 +198,133 @@ impl TimeSeriesStorage {
             Ok(mut guard) => *guard = new_store,
             Err(poisoned) => *poisoned.into_inner() = new_store,
         }
+        if let Some(cache) = self.metric_cache() {
+            cache.clear_hot();
+        }
+    }
+
+    /// Install or remove the cache that answers recent range reads.
+    pub fn set_metric_cache(&self, cache: Option<Arc<MetricCache>>) {
+        match self.metric_cache.write() {
+            Ok(mut guard) => *guard = cache,
+            Err(poisoned) => *poisoned.into_inner() = cache,
+        }
+    }
+
+    fn metric_cache(&self) -> Option<Arc<MetricCache>> {
+        match self.metric_cache.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Hot cache figures, if a metric cache with a hot window is installed.
+    pub fn hot_cache_stats(&self) -> Option<HotCacheStats> {
+        self.metric_cache()?.hot_stats()
     }
 
-    /// Write a data point (all value types are stored)
+    /// Load the recent window of a newly hot series from storage.
+    async fn load_hot_series(&self, cache: &MetricCache, source_id: &str, metric: &str) {
+        let Some(window_secs) = cache.hot.as_ref().map(|hot| hot.config.window_secs) else {
+            return;
+        };
+        let store = self.store();
+        // Buffered points aren't visible to range scans until flushed
+        let flush_store = store.clone();
+        match tokio::task::spawn_blocking(move || flush_store.flush()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::debug!("Flush before hot load failed: {}", e),
+            Err(e) => tracing::debug!("Flush before hot load panicked: {}", e),
+        }
+
+        // The newest point tells whether the series uses seconds or millis
+        let latest = match store.query_latest(source_id, metric).await {
+            Ok(Some(point)) => point,
+            Ok(None) => {
+                cache.hot_abort_load(source_id, metric);
+                return;
+            }
+            Err(e) => {
+                tracing::debug!("Hot load of {}/{} failed: {}", source_id, metric, e);
+                cache.hot_abort_load(source_id, metric);
+                return;
+            }
+        };
+        let millis = latest.timestamp > MILLIS_THRESHOLD;
+        let cutoff = window_cutoff(window_secs, millis);
+
+        match store.query_range(source_id, metric, cutoff, i64::MAX, None).await {
+            Ok(result) => {
+                let points = result
+                    .points
+                    .into_iter()
+                    .filter_map(DataPoint::from_storage)
+                    .collect();
+                cache.hot_finish_load(source_id, metric, points, cutoff, millis);
+            }
+            Err(e) => {
+                tracing::debug!("Hot load of {}/{} failed: {}", source_id, metric, e);
+                cache.hot_abort_load(source_id, metric);
+            }
+        }
+    }
+
+    /// Serve a range read from the hot cache when it covers the range.
+    async fn read_hot(
+        &self,
+        source_id: &str,
+        metric: &str,
+        start: i64,
+        end: i64,
+        limit: Option<usize>,
+    ) -> Option<Vec<DataPoint>> {
+        let cache = self.metric_cache()?;
+        match cache.hot_read(source_id, metric, start, end, limit) {
+            HotRead::Hit(points) => Some(points),
+            HotRead::Miss => None,
+            HotRead::Promote => {
+                self.load_hot_series(&cache, source_id, metric).await;
+                None
+            }
+        }
+    }
+
+    /// Install or remove the load shedder for device telemetry.
+    pub fn set_ingest_qos(&self, qos: Option<Arc<IngestQos>>) {
+        match self.qos.write() {
+            Ok(mut guard) => *guard = qos,
+            Err(poisoned) => *poisoned.into_inner() = qos,
+        }
+    }
+
+    /// Write a data point (all value types are stored).
+    ///
+    /// Points for `device:` sources may be dropped by the load shedder while
+    /// ingestion is over its limits; that is not an error.
     pub async fn write(
         &self,
         source_id: &str,
         metric: &str,
         point: DataPoint,
     ) -> Result<(), DeviceError> {
+        if let Some(device_id) = source_id.strip_prefix("device:") {
+            let qos = match self.qos.read() {
+                Ok(guard) => guard.clone(),
+                Err(poisoned) => poisoned.into_inner().clone(),
+            };
+            if qos.is_some_and(|qos| !qos.admit(device_id, metric, &point)) {
+                return Ok(());
+            }
+        }
         let storage_point = point.to_storage();
         self.store()
             .write(source_id, metric, storage_point)
             .await
             .map_err(|e| DeviceError::Io(std::io::Error::other(e.to_string())))?;
 
+        if let Some(cache) = self.metric_cache() {
+            cache.hot_record(source_id, metric, &point);
+        }
         Ok(())
     }
 
@@ -218,6 +342,11 @@ impl TimeSeriesStorage {
             .await
             .map_err(|e| DeviceError::Io(std::io::Error::other(e.to_string())))?;
 
+        if let Some(cache) = self.metric_cache() {
+            for point in &points {
+                cache.hot_record(source_id, metric, point);
+            }
+        }
         Ok(())
     }
 
@@ -263,6 +392,13 @@ impl TimeSeriesStorage {
             limit,
         );
 
+        if let Some(points) = self
+            .read_hot(source_id, metric, start_timestamp, end_timestamp, limit)
+            .await
+        {
+            return Ok(points);
+        }
+
         let result = if limit.is_some() {
             // Reverse scan → newest N points, then reverse to ASC for callers.
             let rev = self
@@ -335,6 +471,14 @@ impl TimeSeriesStorage {
             source_id, metric, start_timestamp, end_timestamp, limit
         );
 
+        // Neither storage scan reports a total here, so the cache doesn't either
+        if let Some(points) = self
+            .read_hot(source_id, metric, start_timestamp, end_timestamp, limit)
+            .await
+        {
+            return Ok((points, None));
+        }
+
         let (filtered, total_count) = if limit.is_some() {
             let rev = self
                 .store()
@@ -437,6 +581,22 @@ impl TimeSeriesStorage {
         Ok(result.and_then(DataPoint::from_storage))
     }
 
+    /// Get the newest data point at or before `at` (Unix seconds)
+    pub async fn latest_at(
+        &self,
+        source_id: &str,
+        metric: &str,
+        at: i64,
+    ) -> Result<Option<DataPoint>, DeviceError> {
+        let result = self
+            .store()
+            .query_latest_at(source_id, metric, at)
+            .await
+            .map_err(|e| DeviceError::Io(std::io::Error::other(e.to_string())))?;
+
+        Ok(result.and_then(DataPoint::from_storage))
+    }
+
     /// Batch query latest data points for multiple metrics of a source.
     ///
     /// Uses a single read transaction, avoiding N+1 query overhead.
@@ -515,6 +675,9 @@ impl TimeSeriesStorage {
 
     /// Delete old data (for cleanup/retention)
     pub async fn delete_before(&self, before_timestamp: i64) -> Result<(), DeviceError> {
+        if let Some(cache) = self.metric_cache() {
+            cache.clear_hot();
+        }
         let store = self.store();
         // Get all metrics for this device and delete old data
         // This is a simplified implementation - for production you'd want to track all devices
@@ -596,13 +759,363 @@ impl TimeSeriesStorage {
         self.store().get_stats().await
     }
 
+    /// Get write buffer flush statistics from the underlying storage.
+    pub fn flush_stats(&self) -> neomind_storage::timeseries::FlushStats {
+        self.store().flush_stats()
+    }
+
     /// Reset performance statistics in the underlying storage.
     pub async fn reset_stats(&self) {
         self.store().reset_stats().await;
     }
 }
 
-/// In-memory cache for recent metric values
+/// Hours of history kept for hot series; `0` disables the hot cache.
+pub const HOT_CACHE_HOURS_ENV: &str = device_env_vars::HOT_CACHE_HOURS;
+/// Most series kept hot at once.
+pub const HOT_CACHE_MAX_SERIES_ENV: &str = device_env_vars::HOT_CACHE_MAX_SERIES;
+
+/// Timestamps above this are taken to be milliseconds rather than seconds.
+const MILLIS_THRESHOLD: i64 = 10_000_000_000;
+/// Series whose reads are counted towards promotion at once.
+const MAX_TRACKED_READS: usize = 4096;
+
+/// Settings for the hot-series layer of [`MetricCache`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotCacheConfig {
+    /// How much recent history of a hot series is kept, in seconds.
+    pub window_secs: u64,
+    /// Range reads within `idle_ttl_secs` before a series is kept hot.
+    pub promote_after: u32,
+    /// Hot series that go unread this long are dropped, in seconds.
+    pub idle_ttl_secs: u64,
+    /// Most series kept hot; the least recently read one makes room.
+    pub max_series: usize,
+    /// Most points kept per series.
+    pub max_points_per_series: usize,
+}
+
+impl Default for HotCacheConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: device::DEFAULT_HOT_CACHE_HOURS * 3600,
+            promote_after: 3,
+            idle_ttl_secs: 30 * 60,
+            max_series: device::DEFAULT_HOT_CACHE_MAX_SERIES,
+            max_points_per_series: 10_000,
+        }
+    }
+}
+
+impl HotCacheConfig {
+    /// Defaults with the window and series limit set in the config service
+    /// (`telemetry.hot_cache_*`), where `NEOMIND_HOT_CACHE_HOURS` and
+    /// `NEOMIND_HOT_CACHE_MAX_SERIES` override the stored values. Returns
+    /// `None` when the window is set to `0`.
+    pub fn from_env() -> Option<Self> {
+        let hours = device_env_vars::hot_cache_hours();
+        if hours == 0 {
+            return None;
+        }
+        Some(Self {
+            window_secs: hours.saturating_mul(3600),
+            max_series: device_env_vars::hot_cache_max_series(),
+            ..Self::default()
+        })
+    }
+}
+
+/// Hot cache figures.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HotCacheStats {
+    pub window_secs: u64,
+    /// Series currently hot
+    pub series: usize,
+    /// Points held across all hot series
+    pub points: usize,
+    /// Range reads answered from memory
+    pub hits: u64,
+    /// Range reads passed on to storage
+    pub misses: u64,
+    pub promotions: u64,
+    /// Series dropped for being idle or to make room
+    pub evictions: u64,
+}
+
+/// Oldest timestamp inside a window ending now.
+fn window_cutoff(window_secs: u64, millis: bool) -> i64 {
+    let now = Utc::now();
+    let (now, span) = if millis {
+        (now.timestamp_millis(), window_secs.saturating_mul(1000))
+    } else {
+        (now.timestamp(), window_secs)
+    };
+    now.saturating_sub(i64::try_from(span).unwrap_or(i64::MAX))
+}
+
+/// Values of a hot series, kept as one column of the narrowest type that
+/// holds all of them.
+enum ValueColumn {
+    Float(VecDeque<f64>),
+    Integer(VecDeque<i64>),
+    Boolean(VecDeque<bool>),
+    Mixed(VecDeque<MetricValue>),
+}
+
+impl ValueColumn {
+    /// An empty column for values like `value`.
+    fn for_value(value: &MetricValue) -> Self {
+        match value {
+            MetricValue::Float(_) => Self::Float(VecDeque::new()),
+            MetricValue::Integer(_) => Self::Integer(VecDeque::new()),
+            MetricValue::Boolean(_) => Self::Boolean(VecDeque::new()),
+            _ => Self::Mixed(VecDeque::new()),
+        }
+    }
+
+    fn get(&self, index: usize) -> MetricValue {
+        match self {
+            Self::Float(column) => MetricValue::Float(column[index]),
+            Self::Integer(column) => MetricValue::Integer(column[index]),
+            Self::Boolean(column) => MetricValue::Boolean(column[index]),
+            Self::Mixed(column) => column[index].clone(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Float(column) => column.len(),
+            Self::Integer(column) => column.len(),
+            Self::Boolean(column) => column.len(),
+            Self::Mixed(column) => column.len(),
+        }
+    }
+
+    /// Switch to the mixed representation, keeping the values.
+    fn as_mixed(&mut self) -> &mut VecDeque<MetricValue> {
+        if !matches!(self, Self::Mixed(_)) {
+            let mixed = (0..self.len()).map(|index| self.get(index)).collect();
+            *self = Self::Mixed(mixed);
+        }
+        match self {
+            Self::Mixed(column) => column,
+            _ => unreachable!("column was just made mixed"),
+        }
+    }
+
+    fn insert(&mut self, index: usize, value: MetricValue) {
+        match (self, value) {
+            (Self::Float(column), MetricValue::Float(f)) => column.insert(index, f),
+            (Self::Integer(column), MetricValue::Integer(n)) => column.insert(index, n),
+            (Self::Boolean(column), MetricValue::Boolean(b)) => column.insert(index, b),
+            (column, value) => column.as_mixed().insert(index, value),
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        match self {
+            Self::Float(column) => {
+                column.remove(index);
+            }
+            Self::Integer(column) => {
+                column.remove(index);
+            }
+            Self::Boolean(column) => {
+                column.remove(index);
+            }
+            Self::Mixed(column) => {
+                column.remove(index);
+            }
+        }
+    }
+}
+
+/// Recent history of one series, sorted by timestamp.
+struct HotSeries {
+    timestamps: VecDeque<i64>,
+    values: ValueColumn,
+    /// Only allocated once a point carries a quality; `NAN` means none
+    qualities: Option<VecDeque<f32>>,
+    /// Every stored point at or after this timestamp is held; `None` while
+    /// the series is being loaded
+    complete_from: Option<i64>,
+    /// Whether timestamps are milliseconds; known once loaded
+    millis: bool,
+    last_read: Instant,
+}
+
+impl HotSeries {
+    fn loading() -> Self {
+        Self {
+            timestamps: VecDeque::new(),
+            values: ValueColumn::Mixed(VecDeque::new()),
+            qualities: None,
+            complete_from: None,
+            millis: false,
+            last_read: Instant::now(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Insert a point in timestamp order. A point with a timestamp already
+    /// held replaces it if `replace` is set.
+    fn upsert(&mut self, point: &DataPoint, replace: bool) {
+        if self.timestamps.is_empty() {
+            self.values = ValueColumn::for_value(&point.value);
+        }
+        let index = match self.timestamps.back() {
+            Some(&last) if point.timestamp > last => self.timestamps.len(),
+            None => 0,
+            Some(_) => match self.timestamps.binary_search(&point.timestamp) {
+                Ok(index) if replace => {
+                    self.values.remove(index);
+                    self.timestamps.remove(index);
+                    if let Some(qualities) = self.qualities.as_mut() {
+                        qualities.remove(index);
+                    }
+                    index
+                }
+                Ok(_) => return,
+                Err(index) => index,
+            },
+        };
+
+        if point.quality.is_some() && self.qualities.is_none() {
+            self.qualities = Some(std::iter::repeat_n(f32::NAN, self.len()).collect());
+        }
+        self.timestamps.insert(index, point.timestamp);
+        self.values.insert(index, point.value.clone());
+        if let Some(qualities) = self.qualities.as_mut() {
+            qualities.insert(index, point.quality.unwrap_or(f32::NAN));
+        }
+    }
+
+    /// Drop points older than the window and beyond `max_points`.
+    fn trim(&mut self, window_secs: u64, max_points: usize) {
+        let Some(complete_from) = self.complete_from else {
+            return;
+        };
+        let cutoff = window_cutoff(window_secs, self.millis);
+        let mut complete_from = complete_from.max(cutoff);
+        while let Some(&oldest) = self.timestamps.front() {
+            if oldest >= cutoff && self.len() <= max_points {
+                break;
+            }
+            complete_from = complete_from.max(oldest.saturating_add(1));
+            self.timestamps.pop_front();
+            self.values.remove(0);
+            if let Some(qualities) = self.qualities.as_mut() {
+                qualities.pop_front();
+            }
+        }
+        self.complete_from = Some(complete_from);
+    }
+
+    fn point(&self, index: usize) -> DataPoint {
+        DataPoint {
+            timestamp: self.timestamps[index],
+            value: self.values.get(index),
+            quality: self
+                .qualities
+                .as_ref()
+                .map(|qualities| qualities[index])
+                .filter(|quality| !quality.is_nan()),
+        }
+    }
+
+    /// Points in `[start, end]` in ascending order, the newest `limit` of
+    /// them if given. `None` when storage may hold matching points that
+    /// aren't in memory.
+    fn select(&self, start: i64, end: i64, limit: Option<usize>) -> Option<Vec<DataPoint>> {
+        let complete_from = self.complete_from?;
+        let lo = self.timestamps.partition_point(|&ts| ts < start.max(complete_from));
+        let hi = self.timestamps.partition_point(|&ts| ts <= end).max(lo);
+        let lo = match limit {
+            Some(n) if hi - lo >= n => hi - n,
+            _ if start >= complete_from => lo,
+            _ => return None,
+        };
+        Some((lo..hi).map(|index| self.point(index)).collect())
+    }
+}
+
+/// Outcome of a range read against the hot cache.
+enum HotRead {
+    Hit(Vec<DataPoint>),
+    Miss,
+    /// The series just became hot; load it with
+    /// [`TimeSeriesStorage::load_hot_series`]
+    Promote,
+}
+
+#[derive(Default)]
+struct HotState {
+    /// source id -> metric -> series
+    series: HashMap<String, HashMap<String, HotSeries>>,
+    /// Range reads of series that aren't hot yet: (count, first read)
+    reads: HashMap<(String, String), (u32, Instant)>,
+    hits: u64,
+    misses: u64,
+    promotions: u64,
+    evictions: u64,
+}
+
+impl HotState {
+    fn series_mut(&mut self, source_id: &str, metric: &str) -> Option<&mut HotSeries> {
+        self.series.get_mut(source_id)?.get_mut(metric)
+    }
+
+    fn remove(&mut self, source_id: &str, metric: &str) {
+        if let Some(metrics) = self.series.get_mut(source_id) {
+            metrics.remove(metric);
+            if metrics.is_empty() {
+                self.series.remove(source_id);
+            }
+        }
+    }
+
+    fn series_count(&self) -> usize {
+        self.series.values().map(HashMap::len).sum()
+    }
+
+    /// Drop the least recently read series.
+    fn evict_coldest(&mut self) {
+        let coldest = self
+            .series
+            .iter()
+            .flat_map(|(source_id, metrics)| {
+                metrics
+                    .iter()
+                    .map(move |(metric, series)| (series.last_read, source_id, metric))
+            })
+            .min_by_key(|(last_read, _, _)| *last_read)
+            .map(|(_, source_id, metric)| (source_id.clone(), metric.clone()));
+        if let Some((source_id, metric)) = coldest {
+            self.remove(&source_id, &metric);
+            self.evictions += 1;
+        }
+    }
+}
+
+/// Hot series of a [`MetricCache`].
+struct HotSeriesCache {
+    config: HotCacheConfig,
+    state: Mutex<HotState>,
+}
+
+/// In-memory cache for recent metric values.
+///
+/// A cache built [`with_hot_window`](Self::with_hot_window) also keeps the
+/// recent history of frequently queried series in columnar form. Installed
+/// on [`TimeSeriesStorage`], it answers range queries inside that window
+/// without reading redb: a series becomes hot after `promote_after` range
+/// reads, is loaded from storage once and is then kept current by writes,
+/// including points still waiting in the write buffer. Points older than the
+/// window are trimmed, series unread for `idle_ttl_secs` are dropped, and the
+/// least recently read series makes room beyond `max_series`.
 pub struct MetricCache {
     cache: Arc<
         tokio::sync::RwLock<
@@ -613,6 +1126,7 @@ pub struct MetricCache {
         >,
     >,
     max_entries_per_source: usize,
+    hot: Option<HotSeriesCache>,
 }
 
 impl MetricCache {
@@ -621,9 +1135,19 @@ impl MetricCache {
         Self {
             cache: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
             max_entries_per_source,
+            hot: None,
         }
     }
 
+    /// Also keep hot series for range reads.
+    pub fn with_hot_window(mut self, config: HotCacheConfig) -> Self {
+        self.hot = Some(HotSeriesCache {
+            config,
+            state: Mutex::new(HotState::default()),
+        });
+        self
+    }
+
     /// Set a metric value
     pub async fn set(&self, source_id: &str, metric: &str, value: MetricValue) {
         let mut cache = self.cache.write().await;
@@ -645,6 +1169,151 @@ impl MetricCache {
         let cache = self.cache.read().await;
         cache.get(source_id)?.get(metric).cloned()
     }
+
+    /// Hot cache figures, if the hot cache is enabled.
+    pub fn hot_stats(&self) -> Option<HotCacheStats> {
+        let hot = self.hot.as_ref()?;
+        let state = hot.state.lock().unwrap_or_else(|e| e.into_inner());
+        let series = state.series.values().flat_map(HashMap::values);
+        Some(HotCacheStats {
+            window_secs: hot.config.window_secs,
+            series: state.series_count(),
+            points: series.map(HotSeries::len).sum(),
+            hits: state.hits,
+            misses: state.misses,
+            promotions: state.promotions,
+            evictions: state.evictions,
+        })
+    }
+
+    /// Drop all hot series, e.g. after storage changed underneath.
+    pub fn clear_hot(&self) {
+        if let Some(hot) = &self.hot {
+            let mut state = hot.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.series.clear();
+            state.reads.clear();
+        }
+    }
+
+    /// Add a stored point to its series if the series is hot.
+    fn hot_record(&self, source_id: &str, metric: &str, point: &DataPoint) {
+        let Some(hot) = &self.hot else {
+            return;
+        };
+        let mut state = hot.state.lock().unwrap_or_else(|e| e.into_inner());
+        let idle_ttl = Duration::from_secs(hot.config.idle_ttl_secs);
+        let Some(series) = state.series_mut(source_id, metric) else {
+            return;
+        };
+        if series.last_read.elapsed() >= idle_ttl {
+            state.remove(source_id, metric);
+            state.evictions += 1;
+            return;
+        }
+        series.upsert(point, true);
+        series.trim(hot.config.window_secs, hot.config.max_points_per_series);
+    }
+
+    /// Answer a range read from memory, or count it towards promoting the
+    /// series.
+    fn hot_read(
+        &self,
+        source_id: &str,
+        metric: &str,
+        start: i64,
+        end: i64,
+        limit: Option<usize>,
+    ) -> HotRead {
+        let Some(hot) = &self.hot else {
+            return HotRead::Miss;
+        };
+        let config = &hot.config;
+        let mut guard = hot.state.lock().unwrap_or_else(|e| e.into_inner());
+        let state = &mut *guard;
+
+        if let Some(series) = state.series_mut(source_id, metric) {
+            series.last_read = Instant::now();
+            series.trim(config.window_secs, config.max_points_per_series);
+            return match series.select(start, end, limit) {
+                Some(points) => {
+                    state.hits += 1;
+                    HotRead::Hit(points)
+                }
+                None => {
+                    state.misses += 1;
+                    HotRead::Miss
+                }
+            };
+        }
+        state.misses += 1;
+
+        let now = Instant::now();
+        let idle_ttl = Duration::from_secs(config.idle_ttl_secs);
+        if state.reads.len() >= MAX_TRACKED_READS {
+            state
+                .reads
+                .retain(|_, (_, first)| now.duration_since(*first) < idle_ttl);
+            if state.reads.len() >= MAX_TRACKED_READS {
+                state.reads.clear();
+            }
+        }
+        let key = (source_id.to_string(), metric.to_string());
+        let (count, first) = state.reads.entry(key.clone()).or_insert((0, now));
+        if now.duration_since(*first) >= idle_ttl {
+            (*count, *first) = (0, now);
+        }
+        *count += 1;
+        if *count < config.promote_after {
+            return HotRead::Miss;
+        }
+
+        state.reads.remove(&key);
+        while state.series_count() >= config.max_series.max(1) {
+            state.evict_coldest();
+        }
+        state
+            .series
+            .entry(key.0)
+            .or_default()
+            .insert(key.1, HotSeries::loading());
+        state.promotions += 1;
+        HotRead::Promote
+    }
+
+    /// Merge the stored window into a series being loaded and mark it
+    /// complete from `cutoff`.
+    fn hot_finish_load(
+        &self,
+        source_id: &str,
+        metric: &str,
+        points: Vec<DataPoint>,
+        cutoff: i64,
+        millis: bool,
+    ) {
+        let Some(hot) = &self.hot else {
+            return;
+        };
+        let mut state = hot.state.lock().unwrap_or_else(|e| e.into_inner());
+        // Gone if evicted or cleared while loading
+        let Some(series) = state.series_mut(source_id, metric) else {
+            return;
+        };
+        // Points written during the load are newer than what was read
+        for point in &points {
+            series.upsert(point, false);
+        }
+        series.complete_from = Some(cutoff);
+        series.millis = millis;
+        series.trim(hot.config.window_secs, hot.config.max_points_per_series);
+    }
+
+    /// Give up on a series being loaded.
+    fn hot_abort_load(&self, source_id: &str, metric: &str) {
+        if let Some(hot) = &self.hot {
+            let mut state = hot.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.remove(source_id, metric);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -675,6 +1344,70 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_hot_series_select_and_trim() {
+        let now = Utc::now().timestamp();
+        let mut series = HotSeries::loading();
+        for offset in [30, 10, 20] {
+            let point = DataPoint::new(now - offset, MetricValue::Float(offset as f64));
+            series.upsert(&point, true);
+        }
+        // Nothing is answered before the stored window is loaded
+        assert!(series.select(i64::MIN, i64::MAX, None).is_none());
+        series.complete_from = Some(now - 25);
+
+        let rewrite = DataPoint::new(now - 20, MetricValue::Integer(7)).with_quality(0.5);
+        series.upsert(&rewrite, true);
+        assert!(matches!(series.values, ValueColumn::Mixed(_)));
+        assert_eq!(series.len(), 3);
+
+        let points = series.select(now - 25, now, None).unwrap();
+        let timestamps: Vec<i64> = points.iter().map(|p| p.timestamp).collect();
+        assert_eq!(timestamps, vec![now - 20, now - 10]);
+        assert_eq!(points[0].value, MetricValue::Integer(7));
+        assert_eq!((points[0].quality, points[1].quality), (Some(0.5), None));
+
+        // Older ranges are only answerable when the newest points suffice
+        assert!(series.select(i64::MIN, now, None).is_none());
+        let newest = series.select(i64::MIN, now, Some(1)).unwrap();
+        assert_eq!(newest[0].timestamp, now - 10);
+
+        series.trim(15, 100);
+        assert_eq!(series.len(), 1);
+        assert!(series.select(now - 20, now, None).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_hot_cache_serves_recent_reads() {
+        let storage = TimeSeriesStorage::memory().unwrap();
+        let config = HotCacheConfig {
+            promote_after: 2,
+            ..Default::default()
+        };
+        let cache = MetricCache::new(10).with_hot_window(config);
+        storage.set_metric_cache(Some(Arc::new(cache)));
+
+        let now = Utc::now().timestamp();
+        for offset in (1..=5).rev() {
+            let point = DataPoint::new(now - offset, MetricValue::Float(offset as f64));
+            storage.write("device:s1", "temp", point).await.unwrap();
+        }
+        // The second read makes the series hot and loads it
+        for _ in 0..2 {
+            storage.query("device:s1", "temp", now - 60, now).await.unwrap();
+        }
+
+        // Still in the write buffer, so only the cache can return it
+        let point = DataPoint::new(now, MetricValue::Float(0.0));
+        storage.write("device:s1", "temp", point).await.unwrap();
+        let points = storage.query("device:s1", "temp", now - 60, now).await.unwrap();
+        assert_eq!(points.len(), 6);
+
+        let stats = storage.hot_cache_stats().unwrap();
+        assert_eq!((stats.series, stats.points), (1, 6));
+        assert_eq!((stats.promotions, stats.hits), (1, 1));
+    }
+
     #[tokio::test]
     async fn test_datapoint_conversion() {
         let point = DataPoint::new(1000, MetricValue::Float(25.5));
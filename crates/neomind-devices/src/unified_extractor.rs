@@ -9,7 +9,9 @@
 //! - Raw data preservation as `_raw` metric
 //! - Template-driven extraction based on device type definitions
 //! - Auto-extraction fallback for undefined devices
+//! - Binary frame decoding via the template's [`BinaryCodec`]
 //! - Consistent MetricValue conversion
+//! - Shared, lazily parsed payloads ([`RawPayload`]) so one message is parsed once
 //!
 //! ## Extraction Modes
 //!
@@ -17,10 +19,13 @@
 //! 2. **Auto-extraction**: When no template exists, extract all top-level fields
 //! 3. **Raw-only**: Store only `_raw` for debugging/replay
 
+use crate::binary_codec::BinaryCodec;
 use crate::mdl::MetricValue;
 use crate::registry::DeviceRegistry;
+use bytes::Bytes;
 use serde_json::Value;
-use std::sync::Arc;
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock};
 use tracing::{debug, info, trace, warn};
 
 /// Configuration for the extraction process.
@@ -84,6 +89,59 @@ pub enum ExtractionMode {
     NoData,
 }
 
+/// An undecoded adapter payload.
+///
+/// Wraps the bytes as received, so a payload that already arrives as
+/// [`Bytes`] (MQTT publishes do) is shared rather than copied. The JSON parse
+/// is done on first use and cached: extraction, the scalar fallback and
+/// auto-onboarding all read the same [`Value`].
+#[derive(Debug, Default)]
+pub struct RawPayload {
+    bytes: Bytes,
+    json: OnceLock<Option<Value>>,
+}
+
+impl RawPayload {
+    /// Wrap a payload without copying it.
+    pub fn new(bytes: impl Into<Bytes>) -> Self {
+        Self {
+            bytes: bytes.into(),
+            json: OnceLock::new(),
+        }
+    }
+
+    /// Wrap a copy of a borrowed payload.
+    pub fn copy_from_slice(payload: &[u8]) -> Self {
+        Self::new(Bytes::copy_from_slice(payload))
+    }
+
+    /// The underlying buffer.
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// The payload parsed as JSON, or `None` if it is not valid JSON.
+    pub fn json(&self) -> Option<&Value> {
+        self.json
+            .get_or_init(|| serde_json::from_slice(&self.bytes).ok())
+            .as_ref()
+    }
+}
+
+impl Deref for RawPayload {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl AsRef<[u8]> for RawPayload {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
 /// System-level metric keys that bypass template matching and are always
 /// extracted when present in the payload.
 ///
@@ -158,16 +216,16 @@ impl UnifiedExtractor {
         // (`__webhook_image`). Mirrors the `__last_seen_age_secs` convention
         // used by the rule-engine's DeviceStatusEmitter.
         for sys_key in SYSTEM_PASS_THROUGH_KEYS {
-            // Skip when the key is absent OR explicitly null. `extract_by_path`
+            // Skip when the key is absent OR explicitly null. `lookup_path`
             // returns `Ok(Some(Value::Null))` for missing keys (legacy
             // semantics), so we must treat Null as "not present" here —
             // otherwise every webhook payload would synthesize a phantom
             // `__webhook_image: null` metric.
-            if let Ok(Some(value)) = self.extract_by_path(raw_data, sys_key, 0) {
+            if let Ok(Some(value)) = self.lookup_path(raw_data, sys_key, 0) {
                 if value.is_null() {
                     continue;
                 }
-                let metric_value = self.value_to_metric_value(&value);
+                let metric_value = self.value_to_metric_value(value);
                 debug!(
                     "System metric '{}' extracted for device '{}': value_type={}",
                     sys_key,
@@ -203,6 +261,11 @@ impl UnifiedExtractor {
             raw_stored = true;
         }
 
+        // Binary frame codec, if the device type declares one. Its fields are
+        // decoded in Step 3, so template metrics with the same names are not
+        // looked up as JSON paths.
+        let codec = template.as_ref().and_then(|t| t.binary_codec.clone());
+
         // Step 2: Template-driven metric extraction (template fetched above)
         let mode = if let Some(template) = template {
             // Check if template has defined metrics
@@ -219,15 +282,18 @@ impl UnifiedExtractor {
                 );
 
                 for metric_def in &template.metrics {
+                    if codec.as_ref().is_some_and(|c| c.has_field(&metric_def.name)) {
+                        continue;
+                    }
                     trace!(
                         "Attempting to extract metric '{}' (path: {}) for device '{}'",
                         metric_def.name,
                         metric_def.name,
                         device_id
                     );
-                    match self.extract_by_path(raw_data, &metric_def.name, 0) {
+                    match self.lookup_path(raw_data, &metric_def.name, 0) {
                         Ok(Some(value)) => {
-                            let metric_value = self.value_to_metric_value(&value);
+                            let metric_value = self.value_to_metric_value(value);
                             info!(
                                 "Successfully extracted metric '{}' for device '{}': value={:?}",
                                 metric_def.name, device_id, metric_value
@@ -278,6 +344,24 @@ impl UnifiedExtractor {
             ExtractionMode::RawOnly
         };
 
+        // Step 3: Binary frame carried as hex/base64 text, either at the
+        // codec's `payload_path` or as the whole (string) payload.
+        if let Some(codec) = &codec {
+            let encoded = match &codec.payload_path {
+                Some(path) => self.lookup_path(raw_data, path, 0).ok().flatten(),
+                None => Some(raw_data),
+            };
+            if let Some(Value::String(text)) = encoded {
+                match codec.decode_text(text) {
+                    Ok(frame) => Self::push_decoded(codec, &frame, &mut metrics),
+                    Err(e) => {
+                        warn!("Failed to decode binary payload for device '{}': {}", device_id, e);
+                        warnings.push(format!("binary_codec: {}", e));
+                    }
+                }
+            }
+        }
+
         ExtractionResult {
             raw_stored,
             metrics,
@@ -286,6 +370,95 @@ impl UnifiedExtractor {
         }
     }
 
+    /// Extract metrics from an undecoded adapter payload.
+    ///
+    /// Copies the payload; adapters that already hold the message as
+    /// [`Bytes`] should wrap it in a [`RawPayload`] and call
+    /// [`Self::extract_raw`].
+    pub async fn extract_payload(
+        &self,
+        device_id: &str,
+        device_type: &str,
+        payload: &[u8],
+    ) -> Option<ExtractionResult> {
+        self.extract_raw(device_id, device_type, &RawPayload::copy_from_slice(payload))
+            .await
+    }
+
+    /// Extract metrics from a [`RawPayload`].
+    ///
+    /// JSON payloads go through [`Self::extract`], reusing the payload's
+    /// cached parse. A device type whose binary codec has no `payload_path`
+    /// sends bare frames, so its payloads are always decoded as bytes.
+    /// Returns `None` when the payload is neither JSON nor decodable by a
+    /// codec.
+    pub async fn extract_raw(
+        &self,
+        device_id: &str,
+        device_type: &str,
+        payload: &RawPayload,
+    ) -> Option<ExtractionResult> {
+        let bare_frames = self
+            .device_registry
+            .get_template(device_type)
+            .and_then(|t| t.binary_codec)
+            .is_some_and(|c| c.payload_path.is_none());
+        if !bare_frames {
+            if let Some(json) = payload.json() {
+                return Some(self.extract(device_id, device_type, json).await);
+            }
+        }
+        self.extract_binary(device_id, device_type, payload).await
+    }
+
+    /// Decode a binary frame with the device type's codec.
+    ///
+    /// Returns `None` when the device type has no binary codec. The frame is
+    /// stored as a hex string in `_raw`.
+    pub async fn extract_binary(
+        &self,
+        device_id: &str,
+        device_type: &str,
+        frame: &[u8],
+    ) -> Option<ExtractionResult> {
+        let template = self.device_registry.get_template(device_type)?;
+        let codec = template.binary_codec.as_ref()?;
+
+        let mut metrics = Vec::new();
+        let raw_stored = template.store_raw.unwrap_or(self.config.store_raw);
+        if raw_stored {
+            metrics.push(ExtractedMetric {
+                name: "_raw".to_string(),
+                value: MetricValue::String(codec.encode_text(frame)),
+                source_path: "$".to_string(),
+            });
+        }
+        Self::push_decoded(codec, frame, &mut metrics);
+        debug!(
+            "Decoded {}-byte binary frame for device '{}' of type '{}'",
+            frame.len(),
+            device_id,
+            device_type
+        );
+
+        Some(ExtractionResult {
+            raw_stored,
+            metrics,
+            mode: ExtractionMode::TemplateDriven,
+            warnings: Vec::new(),
+        })
+    }
+
+    fn push_decoded(codec: &BinaryCodec, frame: &[u8], metrics: &mut Vec<ExtractedMetric>) {
+        for (name, value) in codec.decode(frame) {
+            metrics.push(ExtractedMetric {
+                source_path: format!("binary:{}", name),
+                name,
+                value,
+            });
+        }
+    }
+
     /// Extract a value using dot notation path.
     ///
     /// Supports:
@@ -308,6 +481,18 @@ impl UnifiedExtractor {
         path: &str,
         depth: usize,
     ) -> Result<Option<Value>, String> {
+        self.lookup_path(data, path, depth)
+            .map(|value| value.cloned())
+    }
+
+    /// Borrowing form of [`Self::extract_by_path`], used by extraction so
+    /// that nested objects are converted in place instead of cloned first.
+    fn lookup_path<'a>(
+        &self,
+        data: &'a Value,
+        path: &str,
+        depth: usize,
+    ) -> Result<Option<&'a Value>, String> {
         // Handle empty path - return None
         let trimmed = path.trim();
         if trimmed.is_empty() {
@@ -316,7 +501,7 @@ impl UnifiedExtractor {
 
         // Handle root notation
         if trimmed == "$" {
-            return Ok(Some(data.clone()));
+            return Ok(Some(data));
         }
 
         // Handle trailing dot - malformed path
@@ -359,7 +544,9 @@ impl UnifiedExtractor {
 
             // Handle array notation [index]
             if let Some(bracket_start) = part.find('[') {
-                if let Some(bracket_end) = part.find(']') {
+                // Only a `]` after the `[` closes it ("a]b[0" is not an index)
+                let bracket_end = part[bracket_start..].find(']').map(|i| bracket_start + i);
+                if let Some(bracket_end) = bracket_end {
                     let key = &part[0..bracket_start];
                     let index_str = &part[bracket_start + 1..bracket_end];
 
@@ -417,7 +604,7 @@ impl UnifiedExtractor {
             // Note: We continue even if current is null, as null values are valid metrics
         }
 
-        Ok(Some(current.clone()))
+        Ok(Some(current))
     }
 
     /// Auto-extract metrics from top-level JSON fields.
@@ -847,4 +1034,62 @@ mod tests {
             result.metrics.iter().map(|m| &m.name).collect::<Vec<_>>()
         );
     }
+
+    #[tokio::test]
+    async fn test_raw_payload_parsed_once() {
+        let payload = RawPayload::new(Bytes::from_static(br#"{"temp": 21.5, "rssi": -70}"#));
+        // Both calls see the same cached parse
+        assert!(std::ptr::eq(payload.json().unwrap(), payload.json().unwrap()));
+        assert!(RawPayload::new("not json").json().is_none());
+
+        let extractor = UnifiedExtractor::new(create_test_registry());
+        let result = extractor
+            .extract_raw("dev1", "unknown_type", &payload)
+            .await
+            .unwrap();
+        let names: Vec<_> = result.metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["_raw", "temp", "rssi"]);
+        assert_eq!(&payload[..], br#"{"temp": 21.5, "rssi": -70}"#);
+    }
+
+    #[tokio::test]
+    async fn test_binary_codec_extraction() {
+        use crate::registry::DeviceTypeTemplate;
+
+        let registry = create_test_registry();
+        let mut template = DeviceTypeTemplate::new("th_sensor", "Binary TH Sensor");
+        template.binary_codec = Some(
+            serde_json::from_value(json!({
+                "encoding": "base64",
+                "payload_path": "data",
+                "fields": [
+                    { "name": "temperature", "offset": 0, "length": 2, "kind": "int", "scale": 0.1 },
+                    { "name": "humidity", "offset": 2, "length": 1 }
+                ]
+            }))
+            .unwrap(),
+        );
+        registry.register_template(template).await.unwrap();
+        let extractor = UnifiedExtractor::new(registry);
+
+        // 0x00eb = 23.5°C, 0x2d = 45%, wrapped as base64 in a JSON envelope
+        let payload = br#"{"rssi": -80, "data": "AOst"}"#;
+        let result = extractor
+            .extract_payload("th1", "th_sensor", payload)
+            .await
+            .unwrap();
+        let metrics: std::collections::HashMap<_, _> = result
+            .metrics
+            .into_iter()
+            .map(|m| (m.name, m.value))
+            .collect();
+        assert_eq!(metrics["humidity"], MetricValue::Integer(45));
+        assert_eq!(metrics["temperature"], MetricValue::Float(23.5));
+
+        // No codec: non-JSON payloads are not extracted
+        assert!(extractor
+            .extract_payload("x", "unknown_type", &[0x00, 0xeb])
+            .await
+            .is_none());
+    }
 }
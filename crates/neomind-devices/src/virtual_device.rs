@@ -0,0 +1,405 @@
+//! Virtual (computed) devices.
+//!
+//! A virtual device is a regular [`DeviceConfig`] with `adapter_type = "virtual"`
+//! whose metrics are computed from other devices instead of reported by
+//! hardware, e.g. "average office temperature". Its [`VirtualDeviceSpec`] is
+//! stored in `connection_config.extra["virtual"]`:
+//!
+//! ```json
+//! {
+//!   "members": ["sensor-01"],
+//!   "selector": { "tags": ["temperature"], "location": "hq/office" },
+//!   "metrics": [
+//!     { "name": "avg_temperature", "source_metric": "temperature", "aggregation": "mean" },
+//!     { "name": "temperatures", "source_metric": "temperature", "aggregation": "collect" }
+//!   ]
+//! }
+//! ```
+//!
+//! Members are resolved like [group](crate::group) members (static IDs plus an
+//! optional selector), restricted to the virtual device's tenant. Virtual
+//! devices are never members of other virtual devices.
+//!
+//! Whenever a member reports a source metric, the dependent metrics are
+//! recomputed and published as ordinary `DeviceMetric` events for the virtual
+//! device, so storage, rules, transforms and the agent treat it like a real
+//! device. `collect` publishes the per-member values as a JSON object, which a
+//! `TransformAutomation` scoped to the virtual device can reduce with custom
+//! logic. Commands sent to a virtual device fan out to every member that
+//! supports them (see `DeviceService::send_command`).
+
+use std::collections::HashSet;
+
+use neomind_core::{EventBus, MetricValue as CoreMetricValue, NeoMindEvent};
+use serde::{Deserialize, Serialize};
+
+use crate::group::{selector_matches, GroupSelector};
+use crate::mdl::{DeviceError, MetricValue};
+use crate::registry::{DeviceConfig, DeviceRegistry};
+use crate::telemetry::TimeSeriesStorage;
+
+/// `adapter_type` of virtual devices.
+pub const VIRTUAL_ADAPTER_TYPE: &str = "virtual";
+
+/// Key of the spec in `ConnectionConfig::extra`.
+const SPEC_KEY: &str = "virtual";
+
+/// How member values are combined into one virtual metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VirtualAggregation {
+    #[default]
+    Mean,
+    Min,
+    Max,
+    Sum,
+    /// Number of members currently reporting the source metric.
+    Count,
+    Median,
+    /// JSON object of member ID → value, for transforms to reduce.
+    Collect,
+}
+
+impl VirtualAggregation {
+    /// Combine `(member_id, value)` pairs. `None` when there is nothing to
+    /// aggregate (except `Count`, which is 0).
+    pub fn apply(&self, values: &[(String, f64)]) -> Option<CoreMetricValue> {
+        if values.is_empty() {
+            return match self {
+                Self::Count => Some(CoreMetricValue::Integer(0)),
+                _ => None,
+            };
+        }
+        let numbers = values.iter().map(|(_, v)| *v);
+        let value = match self {
+            Self::Mean => CoreMetricValue::Float(numbers.sum::<f64>() / values.len() as f64),
+            Self::Min => CoreMetricValue::Float(numbers.fold(f64::INFINITY, f64::min)),
+            Self::Max => CoreMetricValue::Float(numbers.fold(f64::NEG_INFINITY, f64::max)),
+            Self::Sum => CoreMetricValue::Float(numbers.sum()),
+            Self::Count => CoreMetricValue::Integer(values.len() as i64),
+            Self::Median => {
+                let mut sorted: Vec<f64> = numbers.collect();
+                sorted.sort_by(f64::total_cmp);
+                let mid = sorted.len() / 2;
+                let median = if sorted.len().is_multiple_of(2) {
+                    (sorted[mid - 1] + sorted[mid]) / 2.0
+                } else {
+                    sorted[mid]
+                };
+                CoreMetricValue::Float(median)
+            }
+            Self::Collect => CoreMetricValue::Json(serde_json::Value::Object(
+                values
+                    .iter()
+                    .map(|(id, v)| (id.clone(), serde_json::json!(v)))
+                    .collect(),
+            )),
+        };
+        Some(value)
+    }
+}
+
+/// One computed metric of a virtual device.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VirtualMetricSpec {
+    /// Metric name on the virtual device.
+    pub name: String,
+    /// Metric read from every member.
+    pub source_metric: String,
+    #[serde(default)]
+    pub aggregation: VirtualAggregation,
+}
+
+/// Definition of a virtual device: its members and computed metrics.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VirtualDeviceSpec {
+    /// Device IDs that are always members.
+    #[serde(default)]
+    pub members: Vec<String>,
+    /// Devices matching this selector are members too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<GroupSelector>,
+    pub metrics: Vec<VirtualMetricSpec>,
+}
+
+/// Whether `config` describes a virtual device.
+pub fn is_virtual_device(config: &DeviceConfig) -> bool {
+    config.adapter_type == VIRTUAL_ADAPTER_TYPE
+}
+
+impl VirtualDeviceSpec {
+    /// Read the spec of a virtual device. `Ok(None)` for regular devices.
+    pub fn from_config(config: &DeviceConfig) -> Result<Option<Self>, DeviceError> {
+        if !is_virtual_device(config) {
+            return Ok(None);
+        }
+        let raw = config
+            .connection_config
+            .extra
+            .get(SPEC_KEY)
+            .cloned()
+            .ok_or_else(|| {
+                DeviceError::InvalidParameter(format!(
+                    "Virtual device '{}' has no connection_config.{}",
+                    config.device_id, SPEC_KEY
+                ))
+            })?;
+        let spec: Self = serde_json::from_value(raw).map_err(|e| {
+            DeviceError::InvalidParameter(format!(
+                "Invalid virtual device spec for '{}': {}",
+                config.device_id, e
+            ))
+        })?;
+        spec.validate()?;
+        Ok(Some(spec))
+    }
+
+    /// Check the spec is usable: at least one metric, unique metric names and
+    /// some way to select members.
+    pub fn validate(&self) -> Result<(), DeviceError> {
+        if self.metrics.is_empty() {
+            return Err(DeviceError::InvalidParameter(
+                "Virtual device must define at least one metric".to_string(),
+            ));
+        }
+        let mut names = HashSet::new();
+        for metric in &self.metrics {
+            if metric.name.is_empty() || metric.source_metric.is_empty() {
+                return Err(DeviceError::InvalidParameter(
+                    "Virtual metric name and source_metric must not be empty".to_string(),
+                ));
+            }
+            if !names.insert(metric.name.as_str()) {
+                return Err(DeviceError::InvalidParameter(format!(
+                    "Duplicate virtual metric '{}'",
+                    metric.name
+                )));
+            }
+        }
+        if self.members.is_empty() && self.selector.is_none() {
+            return Err(DeviceError::InvalidParameter(
+                "Virtual device needs members or a selector".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether `device` is a member of `virtual_device` (described by this spec).
+    pub fn includes(&self, virtual_device: &DeviceConfig, device: &DeviceConfig) -> bool {
+        if is_virtual_device(device)
+            || device.device_id == virtual_device.device_id
+            || device.tenant_id != virtual_device.tenant_id
+        {
+            return false;
+        }
+        self.members.contains(&device.device_id)
+            || self
+                .selector
+                .as_ref()
+                .is_some_and(|s| selector_matches(s, device))
+    }
+
+    /// Metrics computed from `source_metric`.
+    pub fn metrics_for_source<'a>(
+        &'a self,
+        source_metric: &'a str,
+    ) -> impl Iterator<Item = &'a VirtualMetricSpec> + 'a {
+        self.metrics
+            .iter()
+            .filter(move |m| m.source_metric == source_metric)
+    }
+}
+
+/// Numeric view of a stored member value; booleans count as 0/1.
+fn numeric_value(value: &MetricValue) -> Option<f64> {
+    match value {
+        MetricValue::Boolean(b) => Some(if *b { 1.0 } else { 0.0 }),
+        other => other.as_f64(),
+    }
+}
+
+/// Compute one virtual metric from the latest stored values of `members`.
+pub async fn compute_metric(
+    storage: &TimeSeriesStorage,
+    members: &[DeviceConfig],
+    metric: &VirtualMetricSpec,
+) -> Option<CoreMetricValue> {
+    let mut values = Vec::with_capacity(members.len());
+    for member in members {
+        let source_id = format!("device:{}", member.device_id);
+        if let Ok(Some(point)) = storage.latest(&source_id, &metric.source_metric).await {
+            if let Some(v) = numeric_value(&point.value) {
+                values.push((member.device_id.clone(), v));
+            }
+        }
+    }
+    metric.aggregation.apply(&values)
+}
+
+/// Recompute and publish the virtual metrics that depend on `device_id`'s
+/// `metric`. Called by the device service after the member value is stored.
+pub(crate) async fn refresh_dependents(
+    registry: &DeviceRegistry,
+    storage: &TimeSeriesStorage,
+    event_bus: &EventBus,
+    device_id: &str,
+    metric: &str,
+) {
+    let virtual_devices = registry.list_virtual_devices();
+    if virtual_devices.is_empty() {
+        return;
+    }
+    let Some(source) = registry.get_device(device_id) else {
+        return;
+    };
+
+    let mut all_devices: Option<Vec<DeviceConfig>> = None;
+    for virtual_device in virtual_devices {
+        let spec = match VirtualDeviceSpec::from_config(&virtual_device) {
+            Ok(Some(spec)) => spec,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::debug!("Skipping virtual device: {}", e);
+                continue;
+            }
+        };
+        if !spec.includes(&virtual_device, &source) {
+            continue;
+        }
+        let dependent: Vec<&VirtualMetricSpec> = spec.metrics_for_source(metric).collect();
+        if dependent.is_empty() {
+            continue;
+        }
+
+        let members: Vec<DeviceConfig> = all_devices
+            .get_or_insert_with(|| registry.list_devices())
+            .iter()
+            .filter(|d| spec.includes(&virtual_device, d))
+            .cloned()
+            .collect();
+        for metric_spec in dependent {
+            let Some(value) = compute_metric(storage, &members, metric_spec).await else {
+                continue;
+            };
+            event_bus
+                .publish(NeoMindEvent::DeviceMetric {
+                    device_id: virtual_device.device_id.clone(),
+                    metric: metric_spec.name.clone(),
+                    value,
+                    timestamp: chrono::Utc::now().timestamp(),
+                    quality: None,
+                    is_virtual: None,
+                })
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::ConnectionConfig;
+
+    fn device(id: &str, adapter_type: &str, tags: &[&str]) -> DeviceConfig {
+        DeviceConfig {
+            device_id: id.to_string(),
+            name: id.to_string(),
+            device_type: "sensor".to_string(),
+            adapter_type: adapter_type.to_string(),
+            connection_config: ConnectionConfig::new(),
+            adapter_id: None,
+            last_seen: 0,
+            offline_timeout_secs: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            location: None,
+            tenant_id: Default::default(),
+        }
+    }
+
+    fn virtual_device(spec: serde_json::Value) -> DeviceConfig {
+        let mut config = device("office", VIRTUAL_ADAPTER_TYPE, &[]);
+        config
+            .connection_config
+            .extra
+            .insert(SPEC_KEY.to_string(), spec);
+        config
+    }
+
+    #[test]
+    fn test_aggregations() {
+        let values = [
+            ("a".to_string(), 20.0),
+            ("b".to_string(), 24.0),
+            ("c".to_string(), 22.0),
+        ];
+        let float = |agg: VirtualAggregation| match agg.apply(&values) {
+            Some(CoreMetricValue::Float(v)) => v,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(float(VirtualAggregation::Mean), 22.0);
+        assert_eq!(float(VirtualAggregation::Min), 20.0);
+        assert_eq!(float(VirtualAggregation::Max), 24.0);
+        assert_eq!(float(VirtualAggregation::Sum), 66.0);
+        assert_eq!(float(VirtualAggregation::Median), 22.0);
+        assert!(matches!(
+            VirtualAggregation::Count.apply(&values),
+            Some(CoreMetricValue::Integer(3))
+        ));
+        match VirtualAggregation::Collect.apply(&values) {
+            Some(CoreMetricValue::Json(v)) => assert_eq!(v["b"], 24.0),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(VirtualAggregation::Mean.apply(&[]).is_none());
+        assert!(matches!(
+            VirtualAggregation::Count.apply(&[]),
+            Some(CoreMetricValue::Integer(0))
+        ));
+    }
+
+    #[test]
+    fn test_spec_parsing_and_membership() {
+        let office = virtual_device(serde_json::json!({
+            "members": ["sensor-1"],
+            "selector": { "tags": ["office"] },
+            "metrics": [{ "name": "avg_temperature", "source_metric": "temperature" }]
+        }));
+        let spec = VirtualDeviceSpec::from_config(&office).unwrap().unwrap();
+        assert_eq!(spec.metrics[0].aggregation, VirtualAggregation::Mean);
+        assert_eq!(spec.metrics_for_source("temperature").count(), 1);
+        assert_eq!(spec.metrics_for_source("humidity").count(), 0);
+
+        assert!(spec.includes(&office, &device("sensor-1", "mqtt", &[])));
+        assert!(spec.includes(&office, &device("sensor-2", "mqtt", &["office"])));
+        assert!(!spec.includes(&office, &device("sensor-3", "mqtt", &["lab"])));
+        // Virtual devices never aggregate other virtual devices
+        assert!(!spec.includes(&office, &device("sensor-1", VIRTUAL_ADAPTER_TYPE, &[])));
+
+        assert!(VirtualDeviceSpec::from_config(&device("sensor-1", "mqtt", &[]))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        let no_metrics = virtual_device(serde_json::json!({ "members": ["a"], "metrics": [] }));
+        assert!(VirtualDeviceSpec::from_config(&no_metrics).is_err());
+
+        let no_members = virtual_device(serde_json::json!({
+            "metrics": [{ "name": "avg", "source_metric": "temperature" }]
+        }));
+        assert!(VirtualDeviceSpec::from_config(&no_members).is_err());
+
+        let duplicate = virtual_device(serde_json::json!({
+            "members": ["a"],
+            "metrics": [
+                { "name": "avg", "source_metric": "temperature" },
+                { "name": "avg", "source_metric": "humidity" }
+            ]
+        }));
+        assert!(VirtualDeviceSpec::from_config(&duplicate).is_err());
+
+        let missing = device("office", VIRTUAL_ADAPTER_TYPE, &[]);
+        assert!(VirtualDeviceSpec::from_config(&missing).is_err());
+    }
+}
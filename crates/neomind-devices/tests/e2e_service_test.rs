@@ -195,6 +195,9 @@ async fn test_e2e_device_registration_and_command() {
         adapter_id: Some("test_adapter".to_string()),
         last_seen: 0,
         offline_timeout_secs: None,
+        tags: Vec::new(),
+        location: None,
+        tenant_id: Default::default(),
     };
 
     service
@@ -357,6 +360,9 @@ async fn test_e2e_device_with_template() {
         adapter_id: None,
         last_seen: 0,
         offline_timeout_secs: None,
+        tags: Vec::new(),
+        location: None,
+        tenant_id: Default::default(),
     };
 
     service.register_device(device_config).await.unwrap();
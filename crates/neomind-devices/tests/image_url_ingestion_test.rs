@@ -95,6 +95,7 @@ async fn test_convert_binary_to_url_without_data_dir() {
 }
 
 #[tokio::test]
+#[allow(clippy::approx_constant)]
 async fn test_convert_binary_to_url_non_binary_values() {
     // Test that non-Binary values pass through unchanged
     let temp_dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,542 @@
+//! Property-style fuzzing of device type definitions, MQTT mappings and
+//! payloads.
+//!
+//! A seeded generator builds random device types (as MDL JSON), the MQTT
+//! mapping for them, and payloads that either conform to the type or deviate
+//! from it (wrong types, missing or extra fields, odd paths, random bytes).
+//! Everything is fed through `MdlRegistry`, `DeviceRegistry`,
+//! `UnifiedExtractor` and `MqttMapping`: no input may panic, and conforming
+//! payloads must yield every declared metric.
+//!
+//! `NEOMIND_FUZZ_CASES` raises the number of cases per test. A failing case
+//! prints its seed; rerun it alone with `NEOMIND_FUZZ_SEED=<seed>`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use neomind_devices::mdl::MetricValue;
+use neomind_devices::mdl_format::{DeviceTypeDefinition, MdlRegistry};
+use neomind_devices::protocol::{
+    BinaryFormat, MqttMapping, MqttMappingBuilder, MqttValueParser, ProtocolMapping,
+};
+use neomind_devices::registry::{DeviceRegistry, DeviceTypeMode, DeviceTypeTemplate};
+use neomind_devices::unified_extractor::{ExtractionMode, UnifiedExtractor};
+use serde_json::{json, Map, Value};
+
+// ============================================================================
+// Generator
+// ============================================================================
+
+/// SplitMix64: small, seedable and good enough to drive generators.
+struct Gen(u64);
+
+impl Gen {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n` (`n > 0`).
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// True with probability `percent`%.
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    fn ident(&mut self) -> String {
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789_";
+        let len = 1 + self.below(8);
+        let mut s = String::from(*self.pick(&["t", "h", "v", "s", "x"]));
+        for _ in 0..len {
+            s.push(*self.pick(CHARS) as char);
+        }
+        s
+    }
+
+    /// Strings that have broken path, topic or template parsers before.
+    fn nasty_string(&mut self) -> String {
+        const NASTY: &[&str] = &[
+            "", " ", ".", "..", "a..b", "a.", ".a", "$", "$.", "$.a", "$..a", "[", "]", "[]",
+            "[0]", "a[", "a]", "a]b[0", "a[0", "a[x]", "a[-1]", "a[99999999999999999999]",
+            "a[0][1]", "a.0", "é", "aéb", "日本", "\u{0}", "\u{feff}x", "__webhook_image", "_raw",
+            "0x", "0xZZ", "${", "${}", "${x", "${x}", "${ x }", "{\"a\":${x}}", "[${x}]", "ON",
+        ];
+        let mut s = self.pick(NASTY).to_string();
+        if self.chance(30) {
+            s.push_str(&self.ident());
+        }
+        s
+    }
+
+    fn string(&mut self) -> String {
+        if self.chance(30) {
+            self.nasty_string()
+        } else {
+            self.ident()
+        }
+    }
+
+    fn number(&mut self) -> Value {
+        match self.below(6) {
+            0 => json!(0),
+            1 => json!(self.next() as i64),
+            2 => json!(self.next()),
+            3 => json!(f64::MAX),
+            4 => json!(-(self.below(1000) as f64) - 0.5),
+            _ => json!(self.below(100) as f64 + 0.25),
+        }
+    }
+
+    fn json(&mut self, depth: usize) -> Value {
+        let leaf = depth == 0 || self.chance(50);
+        match (leaf, self.below(4)) {
+            (true, 0) => Value::Null,
+            (true, 1) => json!(self.chance(50)),
+            (true, 2) => self.number(),
+            (true, _) => json!(self.string()),
+            (false, 0 | 1) => {
+                let len = self.below(4);
+                Value::Array((0..len).map(|_| self.json(depth - 1)).collect())
+            }
+            (false, _) => {
+                let len = self.below(4);
+                let mut map = Map::new();
+                for _ in 0..len {
+                    let key = self.string();
+                    map.insert(key, self.json(depth - 1));
+                }
+                Value::Object(map)
+            }
+        }
+    }
+
+    fn bytes(&mut self) -> Vec<u8> {
+        let len = self.below(24);
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+
+    fn metric_value(&mut self) -> MetricValue {
+        match self.below(6) {
+            0 => MetricValue::Integer(self.next() as i64),
+            1 => MetricValue::Float(self.below(1000) as f64 / 7.0),
+            2 => MetricValue::String(self.string()),
+            3 => MetricValue::Boolean(self.chance(50)),
+            4 => MetricValue::Array(vec![MetricValue::Integer(1), MetricValue::Null]),
+            _ => MetricValue::Null,
+        }
+    }
+}
+
+/// Seeds to run: `NEOMIND_FUZZ_SEED` alone, or `NEOMIND_FUZZ_CASES` derived
+/// from a fixed base so CI runs are reproducible.
+fn seeds(base: u64, default_cases: u64) -> Vec<u64> {
+    if let Some(seed) = std::env::var("NEOMIND_FUZZ_SEED")
+        .ok()
+        .and_then(|s| s.parse().ok())
+    {
+        return vec![seed];
+    }
+    let cases = std::env::var("NEOMIND_FUZZ_CASES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default_cases);
+    (0..cases).map(|i| base.wrapping_add(i)).collect()
+}
+
+/// Prints the seed of the case that panicked.
+struct SeedGuard(u64);
+
+impl Drop for SeedGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!("fuzz case failed, replay with NEOMIND_FUZZ_SEED={}", self.0);
+        }
+    }
+}
+
+// ============================================================================
+// Device types and payloads
+// ============================================================================
+
+const DATA_TYPES: &[&str] = &["integer", "float", "string", "boolean", "array"];
+
+/// A generated device type plus a payload that conforms to it.
+struct Case {
+    definition: Value,
+    /// Metric path -> (declared data type, value placed in the payload)
+    metrics: Vec<(String, &'static str, Value)>,
+    payload: Value,
+}
+
+/// A well-formed MDL definition whose metric paths don't overlap, so one
+/// payload can carry all of them.
+fn conforming_case(g: &mut Gen) -> Case {
+    let device_type = format!("fuzz_{}", g.ident());
+    let mut metrics = Vec::new();
+    let mut payload = Map::new();
+    let mut roots = HashSet::new();
+
+    for _ in 0..1 + g.below(6) {
+        let root = g.ident();
+        if !roots.insert(root.clone()) {
+            continue;
+        }
+        let mut segments = vec![root];
+        for _ in 0..g.below(3) {
+            segments.push(g.ident());
+        }
+        let data_type = *g.pick(DATA_TYPES);
+        let value = match data_type {
+            "integer" => json!(g.below(10_000) as i64 - 5_000),
+            "float" => json!(g.below(10_000) as f64 / 8.0 + 0.125),
+            "string" => json!(g.ident()),
+            "boolean" => json!(g.chance(50)),
+            _ => json!([g.below(10), g.below(10)]),
+        };
+
+        // Nest the value under its path
+        let mut nested = value.clone();
+        for segment in segments[1..].iter().rev() {
+            let mut map = Map::new();
+            map.insert(segment.clone(), nested);
+            nested = Value::Object(map);
+        }
+        payload.insert(segments[0].clone(), nested);
+        metrics.push((segments.join("."), data_type, value));
+    }
+
+    let commands: Vec<Value> = (0..g.below(3))
+        .map(|i| {
+            let param = g.ident();
+            json!({
+                "name": format!("cmd{}_{}", i, g.ident()),
+                "description": "",
+                "payload_template": format!("{{\"{}\": \"${{{}}}\"}}", param, param),
+                "parameters": [{ "name": param, "data_type": "string" }],
+            })
+        })
+        .collect();
+
+    let definition = json!({
+        "device_type": device_type,
+        "name": g.string(),
+        "mode": "full",
+        "uplink": {
+            "metrics": metrics
+                .iter()
+                .map(|(name, data_type, _)| json!({ "name": name, "data_type": data_type }))
+                .collect::<Vec<_>>(),
+        },
+        "downlink": { "commands": commands },
+    });
+
+    Case {
+        definition,
+        metrics,
+        payload: Value::Object(payload),
+    }
+}
+
+/// Randomly damage a definition: nasty names, wrong field types, min > max,
+/// unknown data types, missing fields.
+fn mutate_definition(g: &mut Gen, definition: &mut Value) {
+    for _ in 0..1 + g.below(3) {
+        match g.below(7) {
+            0 => definition["device_type"] = json!(g.nasty_string()),
+            1 => definition["mode"] = g.json(1),
+            2 => definition["uplink"]["metrics"] = g.json(2),
+            3 => {
+                if let Some(metric) = definition["uplink"]["metrics"]
+                    .as_array_mut()
+                    .and_then(|m| m.first_mut())
+                {
+                    metric["name"] = json!(g.nasty_string());
+                    metric["data_type"] = json!(g.pick(&["Enum", "enum", "", "FLOAT", "binary"]));
+                    metric["min"] = json!(10.0);
+                    metric["max"] = json!(-10.0);
+                }
+            }
+            4 => definition["downlink"] = json!({ "commands": [{ "name": g.nasty_string() }] }),
+            5 => {
+                if let Some(obj) = definition.as_object_mut() {
+                    obj.remove(*g.pick(&["name", "device_type", "uplink"]));
+                }
+            }
+            _ => {
+                let key = g.string();
+                definition[key] = g.json(2);
+            }
+        }
+    }
+}
+
+/// Randomly damage a conforming payload.
+fn mutate_payload(g: &mut Gen, payload: &Value) -> Value {
+    let mut payload = payload.clone();
+    match g.below(5) {
+        0 => return g.json(4),
+        1 => return json!([payload]),
+        _ => {}
+    }
+    if let Some(obj) = payload.as_object_mut() {
+        let keys: Vec<String> = obj.keys().cloned().collect();
+        for _ in 0..1 + g.below(3) {
+            let key = if keys.is_empty() || g.chance(30) {
+                g.string()
+            } else {
+                g.pick(&keys).clone()
+            };
+            if g.chance(30) {
+                obj.remove(&key);
+            } else {
+                obj.insert(key, g.json(3));
+            }
+        }
+    }
+    payload
+}
+
+fn template_from(definition: &DeviceTypeDefinition) -> DeviceTypeTemplate {
+    let mut template = DeviceTypeTemplate::new(&definition.device_type, &definition.name)
+        .with_description(&definition.description);
+    template.mode = DeviceTypeMode::Full;
+    template.metrics = definition.uplink.metrics.clone();
+    template.commands = definition.downlink.commands.clone();
+    template
+}
+
+fn mapping_from(definition: &DeviceTypeDefinition) -> MqttMapping {
+    let mut builder = MqttMappingBuilder::new(&definition.device_type);
+    for metric in &definition.uplink.metrics {
+        builder = builder.add_metric_with_parser(
+            &metric.name,
+            "device/${device_id}/telemetry",
+            MqttValueParser::json_path(format!("$.{}", metric.name)),
+        );
+    }
+    for command in &definition.downlink.commands {
+        builder = builder.add_command_with_payload(
+            &command.name,
+            "device/${device_id}/command",
+            &command.payload_template,
+        );
+    }
+    builder.build()
+}
+
+/// Run one payload through every parser; none may panic.
+async fn exercise_payload(
+    g: &mut Gen,
+    extractor: &UnifiedExtractor,
+    mdl: &MdlRegistry,
+    definition: &DeviceTypeDefinition,
+    mapping: &MqttMapping,
+    payload: &[u8],
+) {
+    let device_type = &definition.device_type;
+    if let Some(result) = extractor.extract_payload("dev1", device_type, payload).await {
+        assert!(result.raw_stored, "_raw must be stored by default");
+    }
+    if let Ok(json) = serde_json::from_slice::<Value>(payload) {
+        let path = g.nasty_string();
+        let _ = extractor.extract_by_path(&json, &path, 0);
+    }
+    for metric in &definition.uplink.metrics {
+        let _ = mdl.parse_metric_value(metric, payload);
+        let _ = mapping.parse_metric(&metric.name, payload);
+    }
+
+    const FORMATS: &[BinaryFormat] = &[
+        BinaryFormat::Raw,
+        BinaryFormat::Float32Le,
+        BinaryFormat::Float64Le,
+        BinaryFormat::Int16Le,
+        BinaryFormat::Int32Le,
+        BinaryFormat::Float32Be,
+        BinaryFormat::Float64Be,
+        BinaryFormat::HexString,
+        BinaryFormat::Base64Hex,
+    ];
+    let binary = MqttMappingBuilder::new(device_type.as_str())
+        .add_metric_with_parser("frame", "t", MqttValueParser::binary(g.pick(FORMATS).clone()))
+        .add_metric_with_parser("path", "t", MqttValueParser::json_path(g.nasty_string()))
+        .build();
+    let _ = binary.parse_metric("frame", payload);
+    let _ = binary.parse_metric("path", payload);
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[tokio::test]
+async fn fuzz_conforming_payloads_extract_every_metric() {
+    for seed in seeds(0x4E45_4F4D_0001, 200) {
+        let _guard = SeedGuard(seed);
+        let mut g = Gen(seed);
+        let case = conforming_case(&mut g);
+
+        let definition: DeviceTypeDefinition =
+            serde_json::from_value(case.definition.clone()).expect("conforming definition");
+        let mdl = MdlRegistry::new();
+        mdl.register(definition.clone())
+            .await
+            .expect("conforming definition registers");
+        let registry = Arc::new(DeviceRegistry::new());
+        registry
+            .register_template(template_from(&definition))
+            .await
+            .expect("conforming template registers");
+        let extractor = UnifiedExtractor::new(registry);
+
+        let result = extractor
+            .extract("dev1", &definition.device_type, &case.payload)
+            .await;
+        assert_eq!(result.mode, ExtractionMode::TemplateDriven);
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+        let extracted: HashMap<&str, &MetricValue> = result
+            .metrics
+            .iter()
+            .map(|m| (m.name.as_str(), &m.value))
+            .collect();
+
+        let mapping = mapping_from(&definition);
+        let bytes = serde_json::to_vec(&case.payload).unwrap();
+        for (name, data_type, value) in &case.metrics {
+            let expected = extractor.value_to_metric_value(value);
+            assert_eq!(extracted.get(name.as_str()), Some(&&expected), "metric {}", name);
+
+            let mapped = mapping.parse_metric(name, &bytes);
+            assert!(mapped.is_ok(), "mapping for {}: {:?}", name, mapped);
+
+            let metric = definition
+                .uplink
+                .metrics
+                .iter()
+                .find(|m| &m.name == name)
+                .unwrap();
+            let parsed = mdl.parse_metric_value(metric, &bytes);
+            if *data_type != "array" {
+                assert_eq!(parsed.ok(), Some(expected), "MDL parse of {}", name);
+            }
+        }
+
+        // Commands render with a value for every parameter
+        for command in &definition.downlink.commands {
+            let params: HashMap<String, MetricValue> = command
+                .parameters
+                .iter()
+                .map(|p| (p.name.clone(), MetricValue::String(g.ident())))
+                .collect();
+            let rendered = mapping.serialize_command(&command.name, &params);
+            assert!(rendered.is_ok(), "command {}: {:?}", command.name, rendered);
+        }
+    }
+}
+
+#[tokio::test]
+async fn fuzz_deviating_payloads_never_panic() {
+    for seed in seeds(0x4E45_4F4D_0002, 300) {
+        let _guard = SeedGuard(seed);
+        let mut g = Gen(seed);
+        let case = conforming_case(&mut g);
+        let definition: DeviceTypeDefinition =
+            serde_json::from_value(case.definition).expect("conforming definition");
+
+        let mdl = MdlRegistry::new();
+        let registry = Arc::new(DeviceRegistry::new());
+        registry
+            .register_template(template_from(&definition))
+            .await
+            .expect("conforming template registers");
+        let extractor = UnifiedExtractor::new(registry);
+        let mapping = mapping_from(&definition);
+
+        for _ in 0..4 {
+            let payload = match g.below(4) {
+                0 => g.bytes(),
+                1 => g.nasty_string().into_bytes(),
+                _ => serde_json::to_vec(&mutate_payload(&mut g, &case.payload)).unwrap(),
+            };
+            exercise_payload(&mut g, &extractor, &mdl, &definition, &mapping, &payload).await;
+        }
+
+        // Unknown device types fall back to auto-extraction
+        let payload = g.json(4);
+        let result = extractor.extract("dev2", "no_such_type", &payload).await;
+        assert_ne!(result.mode, ExtractionMode::TemplateDriven);
+
+        // Commands with missing or odd parameters fail cleanly
+        for command in &definition.downlink.commands {
+            let params: HashMap<String, MetricValue> = (0..g.below(3))
+                .map(|_| (g.string(), g.metric_value()))
+                .collect();
+            let _ = mapping.serialize_command(&command.name, &params);
+        }
+        let _ = mapping.serialize_command(&g.nasty_string(), &HashMap::new());
+    }
+}
+
+#[tokio::test]
+async fn fuzz_malformed_definitions_never_panic() {
+    for seed in seeds(0x4E45_4F4D_0003, 300) {
+        let _guard = SeedGuard(seed);
+        let mut g = Gen(seed);
+        let case = conforming_case(&mut g);
+        let mut raw = case.definition;
+        mutate_definition(&mut g, &mut raw);
+
+        let _ = serde_json::from_value::<DeviceTypeTemplate>(raw.clone());
+        let Ok(definition) = serde_json::from_value::<DeviceTypeDefinition>(raw) else {
+            continue;
+        };
+        let mdl = MdlRegistry::new();
+        let registered = mdl.register(definition.clone()).await.is_ok();
+        let registry = Arc::new(DeviceRegistry::new());
+        let template_registered = registry
+            .register_template(template_from(&definition))
+            .await
+            .is_ok();
+
+        // The registries agree on what a usable device type identifier is
+        let valid_id = !definition.device_type.is_empty()
+            && definition
+                .device_type
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        if !valid_id {
+            assert!(!registered && !template_registered);
+        }
+
+        let extractor = UnifiedExtractor::new(registry);
+        let mapping = mapping_from(&definition);
+        let payload = serde_json::to_vec(&mutate_payload(&mut g, &case.payload)).unwrap();
+        exercise_payload(&mut g, &extractor, &mdl, &definition, &mapping, &payload).await;
+    }
+}
+
+#[test]
+fn fuzz_regressions() {
+    let registry = Arc::new(DeviceRegistry::new());
+    let extractor = UnifiedExtractor::new(registry);
+
+    // `]` before `[` used to slice with start > end; it is now a plain key
+    let data = json!({ "a]b[0": 7 });
+    assert_eq!(extractor.extract_by_path(&data, "a]b[0", 0), Ok(Some(json!(7))));
+
+    // Multi-byte characters used to split mid-character in hex parsing
+    let mapping = MqttMappingBuilder::new("t")
+        .add_metric_with_parser("m", "t", MqttValueParser::binary(BinaryFormat::HexString))
+        .build();
+    assert!(mapping.parse_metric("m", "aéb".as_bytes()).is_err());
+}
@@ -79,6 +79,9 @@ async fn test_registry_device_crud() {
         adapter_id: Some("mqtt_adapter".to_string()),
         last_seen: 0,
         offline_timeout_secs: None,
+        tags: Vec::new(),
+        location: None,
+        tenant_id: Default::default(),
     };
 
     // Register device
@@ -193,6 +196,9 @@ async fn test_service_device_operations() {
         adapter_id: None,
         last_seen: 0,
         offline_timeout_secs: None,
+        tags: Vec::new(),
+        location: None,
+        tenant_id: Default::default(),
     };
 
     service.register_device(device_config).await.unwrap();
@@ -260,6 +266,9 @@ async fn test_service_command_validation() {
         adapter_id: None,
         last_seen: 0,
         offline_timeout_secs: None,
+        tags: Vec::new(),
+        location: None,
+        tenant_id: Default::default(),
     };
     service.register_device(device_config).await.unwrap();
 
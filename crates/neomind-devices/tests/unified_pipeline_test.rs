@@ -53,7 +53,9 @@ async fn create_test_registry_with_template() -> DeviceRegistry {
         commands: vec![],
         uplink_samples: vec![],
         default_offline_timeout_secs: None,
+        expected_interval_secs: None,
         store_raw: None,
+        binary_codec: None,
     };
 
     registry.register_template(template).await.unwrap();
@@ -388,7 +390,9 @@ async fn test_extract_store_raw_false_skips_raw_metric() {
         uplink_samples: vec![],
         commands: vec![],
         default_offline_timeout_secs: None,
+        expected_interval_secs: None,
         store_raw: Some(false),
+        binary_codec: None,
     };
     registry.register_template(template).await.unwrap();
     let extractor = UnifiedExtractor::new(std::sync::Arc::new(registry));
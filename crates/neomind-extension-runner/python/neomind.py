@@ -0,0 +1,136 @@
+"""NeoMind Python extension SDK.
+
+Bundled into neomind-extension-runner and importable as ``neomind`` from an
+extension script. A script creates one ``Extension`` and registers metrics
+and commands on it with decorators:
+
+    import neomind
+
+    ext = neomind.Extension("room-comfort", "Room Comfort", "0.1.0")
+
+    @ext.metric("comfort_index", data_type="float", unit="%")
+    def comfort_index():
+        return 72.5
+
+    @ext.command("set_target", description="Set the target temperature")
+    def set_target(args):
+        return {"target": args["celsius"]}
+
+Everything exchanged with the runner is JSON, matching the descriptor and
+result formats of WASM extensions.
+"""
+
+import json
+
+_registered = None
+
+_DATA_TYPES = ("float", "integer", "boolean", "string", "binary")
+
+
+class Extension:
+    """A NeoMind extension implemented in Python."""
+
+    def __init__(self, id, name, version="0.1.0", description=None, author=None):
+        global _registered
+        if _registered is not None:
+            raise RuntimeError("a script can define only one neomind.Extension")
+        self.id = id
+        self.name = name
+        self.version = version
+        self.description = description
+        self.author = author
+        self.config = {}
+        self._metrics = {}
+        self._commands = {}
+        self._on_configure = None
+        self._health = None
+        _registered = self
+
+    def metric(self, name, data_type="float", unit="", display_name=None, min=None, max=None):
+        """Register a function returning the current value of a metric."""
+        if data_type not in _DATA_TYPES:
+            raise ValueError("data_type must be one of %s" % ", ".join(_DATA_TYPES))
+
+        def decorator(func):
+            self._metrics[name] = (
+                {
+                    "name": name,
+                    "display_name": display_name or name,
+                    "data_type": data_type,
+                    "unit": unit,
+                    "min": min,
+                    "max": max,
+                },
+                func,
+            )
+            return func
+
+        return decorator
+
+    def command(self, name, description="", parameters=None, display_name=None):
+        """Register a function called with the command arguments as a dict.
+
+        ``parameters`` is a list of dicts with ``name``, ``param_type`` and
+        optionally ``description`` and ``required``.
+        """
+
+        def decorator(func):
+            self._commands[name] = (
+                {
+                    "name": name,
+                    "display_name": display_name or name,
+                    "description": description,
+                    "parameters": parameters or [],
+                },
+                func,
+            )
+            return func
+
+        return decorator
+
+    def on_configure(self, func):
+        """Register a function called with the new config dict."""
+        self._on_configure = func
+        return func
+
+    def health(self, func):
+        """Register a function returning whether the extension is healthy."""
+        self._health = func
+        return func
+
+    # Called by the runner -------------------------------------------------
+
+    def _descriptor(self):
+        return json.dumps(
+            {
+                "metadata": {
+                    "id": self.id,
+                    "name": self.name,
+                    "version": self.version,
+                    "description": self.description,
+                    "author": self.author,
+                },
+                "metrics": [spec for spec, _ in self._metrics.values()],
+                "commands": [spec for spec, _ in self._commands.values()],
+            }
+        )
+
+    def _produce_metrics(self):
+        values = []
+        for name, (_, func) in self._metrics.items():
+            value = func()
+            if value is not None:
+                values.append({"name": name, "value": value})
+        return json.dumps(values)
+
+    def _execute(self, command, args_json):
+        _, func = self._commands[command]
+        return json.dumps(func(json.loads(args_json)))
+
+    def _configure(self, config_json):
+        self.config = json.loads(config_json)
+        if self._on_configure is not None:
+            self._on_configure(self.config)
+
+    def _health_check(self):
+        return bool(self._health()) if self._health is not None else True
@@ -10,12 +10,14 @@
 //!
 //! - Native libraries (.so, .dylib, .dll)
 //! - WebAssembly modules (.wasm)
+//! - Python scripts (.py, requires the `python` feature)
 //!
 //! # Usage
 //!
 //! ```bash
 //! neomind-extension-runner --extension-path /path/to/extension.dylib
 //! neomind-extension-runner --extension-path /path/to/extension.wasm
+//! neomind-extension-runner --extension-path /path/to/extension.py
 //! ```
 //!
 //! # Protocol
@@ -41,10 +43,10 @@ use wasmtime_wasi::preview1::{self, WasiP1Ctx};
 use wasmtime_wasi::WasiCtxBuilder;
 
 use neomind_extension_sdk::{
-    capability_constants as cap, BatchCommand, BatchResult, DataChunk, ErrorKind, ExtensionCommand,
-    ExtensionDescriptor, ExtensionMetadata, ExtensionMetricValue, ExtensionStats, IpcFrame,
-    IpcMessage, IpcResponse, MetricDataType, MetricDescriptor, ParameterDefinition, SessionStats,
-    StreamCapability, StreamDataChunk, StreamDataType, StreamResult, ABI_VERSION,
+    capability_constants as cap, BatchCommand, BatchResult, DataChunk, ErrorKind, Extension,
+    ExtensionCommand, ExtensionDescriptor, ExtensionMetadata, ExtensionMetricValue, ExtensionStats,
+    IpcFrame, IpcMessage, IpcResponse, MetricDataType, MetricDescriptor, ParameterDefinition,
+    SessionStats, StreamCapability, StreamDataChunk, StreamDataType, StreamResult, ABI_VERSION,
 };
 
 // Resource limits module
@@ -59,6 +61,10 @@ use dylib_validation::validate_library;
 mod event_handler;
 use event_handler::get_global_event_state;
 
+// Python extension runtime
+#[cfg(feature = "python")]
+mod python;
+
 // IPC routing module
 mod ipc_routing;
 use ipc_routing::{
@@ -78,6 +84,7 @@ use ipc_routing::{
 enum ExtensionType {
     Native,
     Wasm,
+    Python,
 }
 
 impl ExtensionType {
@@ -87,6 +94,7 @@ impl ExtensionType {
             .and_then(|e| e.to_str())
             .map(|ext| match ext.to_lowercase().as_str() {
                 "wasm" => ExtensionType::Wasm,
+                "py" => ExtensionType::Python,
                 _ => ExtensionType::Native,
             })
             .unwrap_or(ExtensionType::Native)
@@ -98,7 +106,7 @@ impl ExtensionType {
 #[command(name = "neomind-extension-runner")]
 #[command(about = "Run a NeoMind extension in isolated mode")]
 struct Args {
-    /// Path to the extension library (.so, .dylib, .dll, or .wasm)
+    /// Path to the extension library (.so, .dylib, .dll, .wasm, or .py)
     #[arg(long, short = 'e')]
     extension_path: PathBuf,
 
@@ -129,6 +137,8 @@ struct Runner {
     extension: Option<NativeExtensionBridge>,
     /// WASM runtime (for WASM)
     wasm_runtime: Option<WasmRuntime>,
+    /// Interpreted extension (for Python)
+    python_extension: Option<Box<dyn Extension>>,
     /// Extension descriptor (unified capabilities)
     descriptor: ExtensionDescriptor,
     /// Extension type
@@ -2149,14 +2159,18 @@ impl Runner {
         );
 
         // Load the extension based on type
-        let (extension, wasm_runtime, descriptor) = match extension_type {
+        let (extension, wasm_runtime, python_extension, descriptor) = match extension_type {
             ExtensionType::Native => {
                 let (ext, desc) = Self::load_native(extension_path).await?;
-                (Some(ext), None, desc)
+                (Some(ext), None, None, desc)
             }
             ExtensionType::Wasm => {
                 let (runtime, descriptor) = Self::load_wasm(extension_path).await?;
-                (None, Some(runtime), descriptor)
+                (None, Some(runtime), None, descriptor)
+            }
+            ExtensionType::Python => {
+                let (ext, descriptor) = Self::load_python(extension_path).await?;
+                (None, None, Some(ext), descriptor)
             }
         };
 
@@ -2191,6 +2205,7 @@ impl Runner {
         Ok(Self {
             extension,
             wasm_runtime,
+            python_extension,
             descriptor,
             extension_type,
             runtime: runtime_handle,
@@ -2302,6 +2317,35 @@ impl Runner {
         }
     }
 
+    /// Load a Python extension script and return its descriptor
+    async fn load_python(
+        extension_path: &Path,
+    ) -> Result<(Box<dyn Extension>, ExtensionDescriptor), String> {
+        #[cfg(feature = "python")]
+        {
+            // The script's top-level code runs during load; keep it off the workers
+            let path = extension_path.to_path_buf();
+            let ext = tokio::task::spawn_blocking(move || python::PythonExtension::load(&path))
+                .await
+                .map_err(|e| format!("Python load task failed: {}", e))??;
+            let descriptor = ext.descriptor().ok_or("Python extension has no descriptor")?;
+            info!(
+                "Descriptor created: id='{}', commands={}, metrics={}",
+                descriptor.metadata.id,
+                descriptor.commands.len(),
+                descriptor.metrics.len()
+            );
+            Ok((Box::new(ext), descriptor))
+        }
+        #[cfg(not(feature = "python"))]
+        {
+            Err(format!(
+                "{} is a Python extension, but this runner was built without the `python` feature",
+                extension_path.display()
+            ))
+        }
+    }
+
     /// Load WASM metadata (fallback from sidecar files)
     fn load_wasm_metadata(extension_path: &Path) -> Result<ExtensionMetadata, String> {
         // Try sidecar JSON
@@ -2912,6 +2956,7 @@ impl Runner {
         let result = match self.extension_type {
             ExtensionType::Native => self.execute_native_command(&command, &args).await,
             ExtensionType::Wasm => self.execute_wasm_command(&command, &args),
+            ExtensionType::Python => self.execute_python_command(&command, &args).await,
         };
 
         match result {
@@ -2941,6 +2986,13 @@ impl Runner {
                 ext.configure(&config)
             }
             ExtensionType::Wasm => Ok(()),
+            ExtensionType::Python => {
+                let ext = self
+                    .python_extension
+                    .as_mut()
+                    .ok_or("No Python extension loaded")?;
+                ext.configure(&config).await.map_err(|e| e.to_string())
+            }
         }
     }
 
@@ -2960,6 +3012,9 @@ impl Runner {
             let result = match self.extension_type {
                 ExtensionType::Native => self.execute_native_command(&cmd.command, &cmd.args).await,
                 ExtensionType::Wasm => self.execute_wasm_command(&cmd.command, &cmd.args),
+                ExtensionType::Python => {
+                    self.execute_python_command(&cmd.command, &cmd.args).await
+                }
             };
 
             results.push(BatchResult {
@@ -2990,6 +3045,20 @@ impl Runner {
         ext.execute_command(command, args)
     }
 
+    async fn execute_python_command(
+        &self,
+        command: &str,
+        args: &serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let ext = self
+            .python_extension
+            .as_ref()
+            .ok_or("No Python extension loaded")?;
+        ext.execute_command(command, args)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     fn execute_wasm_command(
         &self,
         command: &str,
@@ -3038,6 +3107,7 @@ impl Runner {
         let result = match self.extension_type {
             ExtensionType::Native => self.produce_native_metrics().await,
             ExtensionType::Wasm => self.produce_wasm_metrics(),
+            ExtensionType::Python => self.produce_python_metrics(),
         };
 
         match result {
@@ -3070,6 +3140,14 @@ impl Runner {
         runtime.produce_metrics()
     }
 
+    fn produce_python_metrics(&self) -> Result<Vec<ExtensionMetricValue>, String> {
+        let ext = self
+            .python_extension
+            .as_ref()
+            .ok_or("No Python extension loaded")?;
+        tokio::task::block_in_place(|| ext.produce_metrics()).map_err(|e| e.to_string())
+    }
+
     /// Build a fresh descriptor by asking the extension (native FFI or WASM)
     /// to regenerate metadata/commands/metrics. The returned descriptor
     /// reflects the extension's current runtime state, enabling dynamic
@@ -3090,6 +3168,8 @@ impl Runner {
                     .ok_or("No WASM runtime loaded")?;
                 runtime.get_descriptor_blocking()
             }
+            // Python descriptors are fixed once the script has run
+            ExtensionType::Python => Ok(self.descriptor.clone()),
         }
     }
 
@@ -3129,6 +3209,7 @@ impl Runner {
         let healthy = match self.extension_type {
             ExtensionType::Native => self.native_health_check(),
             ExtensionType::Wasm => self.wasm_health_check(),
+            ExtensionType::Python => self.python_health_check(),
         };
 
         self.send_response(IpcResponse::Health {
@@ -3187,6 +3268,21 @@ impl Runner {
         })
     }
 
+    fn python_health_check(&self) -> bool {
+        let ext = match &self.python_extension {
+            Some(e) => e,
+            None => return false,
+        };
+
+        let runtime_handle = match tokio::runtime::Handle::try_current() {
+            Ok(h) => h,
+            Err(_) => return false,
+        };
+        tokio::task::block_in_place(|| {
+            runtime_handle.block_on(async { ext.health_check().await.unwrap_or(false) })
+        })
+    }
+
     // =========================================================================
     // Statistics Support
     // =========================================================================
@@ -3196,8 +3292,8 @@ impl Runner {
 
         let stats = match self.extension_type {
             ExtensionType::Native => self.get_native_stats(),
-            ExtensionType::Wasm => {
-                // WASM extensions don't support stats yet
+            ExtensionType::Wasm | ExtensionType::Python => {
+                // WASM and Python extensions don't support stats yet
                 // Return default stats
                 ExtensionStats::default()
             }
@@ -3250,8 +3346,8 @@ impl Runner {
 
         let capability = match self.extension_type {
             ExtensionType::Native => self.get_native_stream_capability().await,
-            ExtensionType::Wasm => {
-                Ok(None) // WASM doesn't support streaming yet
+            ExtensionType::Wasm | ExtensionType::Python => {
+                Ok(None) // WASM and Python don't support streaming yet
             }
         };
 
@@ -3290,7 +3386,9 @@ impl Runner {
 
         let result = match self.extension_type {
             ExtensionType::Native => self.init_native_stream_session(&session_id, config).await,
-            ExtensionType::Wasm => Err("WASM streaming not supported".to_string()),
+            ExtensionType::Wasm | ExtensionType::Python => {
+                Err(format!("{:?} streaming not supported", self.extension_type))
+            }
         };
 
         match result {
@@ -3333,7 +3431,9 @@ impl Runner {
 
         let result = match self.extension_type {
             ExtensionType::Native => self.process_native_stream_chunk(&session_id, chunk),
-            ExtensionType::Wasm => Err("WASM streaming not supported".to_string()),
+            ExtensionType::Wasm | ExtensionType::Python => {
+                Err(format!("{:?} streaming not supported", self.extension_type))
+            }
         };
 
         match result {
@@ -3375,7 +3475,7 @@ impl Runner {
 
         let result = match self.extension_type {
             ExtensionType::Native => self.close_native_stream_session(&session_id),
-            ExtensionType::Wasm => Ok(SessionStats::default()),
+            ExtensionType::Wasm | ExtensionType::Python => Ok(SessionStats::default()),
         };
 
         match result {
@@ -3418,9 +3518,10 @@ impl Runner {
 
         let result = match self.extension_type {
             ExtensionType::Native => self.process_native_chunk(chunk),
-            ExtensionType::Wasm => {
-                Err("Stateless chunk processing not supported for WASM".to_string())
-            }
+            ExtensionType::Wasm | ExtensionType::Python => Err(format!(
+                "Stateless chunk processing not supported for {:?}",
+                self.extension_type
+            )),
         };
 
         match result {
@@ -3466,7 +3567,10 @@ impl Runner {
 
         let result = match self.extension_type {
             ExtensionType::Native => self.start_native_push(&session_id),
-            ExtensionType::Wasm => Err("Push mode not supported for WASM".to_string()),
+            ExtensionType::Wasm | ExtensionType::Python => Err(format!(
+                "Push mode not supported for {:?}",
+                self.extension_type
+            )),
         };
 
         match result {
@@ -3506,8 +3610,8 @@ impl Runner {
 
         let result = match self.extension_type {
             ExtensionType::Native => self.stop_native_push(&session_id),
-            ExtensionType::Wasm => {
-                Ok(()) // WASM doesn't support push, just return success
+            ExtensionType::Wasm | ExtensionType::Python => {
+                Ok(()) // WASM and Python don't support push, just return success
             }
         };
 
@@ -3647,6 +3751,13 @@ mod tests {
         assert_eq!(ext_type, ExtensionType::Wasm);
     }
 
+    #[test]
+    fn test_extension_type_detection_python() {
+        let path = PathBuf::from("/tmp/test.py");
+        let ext_type = ExtensionType::from_path(&path);
+        assert_eq!(ext_type, ExtensionType::Python);
+    }
+
     #[test]
     fn test_extension_type_detection_wasm_uppercase() {
         let path = PathBuf::from("/tmp/test.WASM");
@@ -0,0 +1,377 @@
+//! Python extension runtime.
+//!
+//! Runs a `.py` extension script in an embedded CPython interpreter. The
+//! script imports the bundled `neomind` SDK (`python/neomind.py`), creates
+//! one `neomind.Extension` and registers metric and command functions on it.
+//! Calls into the interpreter exchange JSON strings, so descriptors use the
+//! same format as WASM modules and are parsed by the same code.
+//!
+//! Script code can run for as long as it likes while holding the GIL, so the
+//! async entry points make their interpreter calls on the blocking pool
+//! rather than on a runtime worker.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+
+use neomind_extension_sdk::{
+    Extension, ExtensionDescriptor, ExtensionError, ExtensionMetadata, ExtensionMetricValue,
+    MetricDataType, MetricDescriptor, Result,
+};
+
+use crate::WasmRuntime;
+
+/// Source of the `neomind` module made importable to extension scripts.
+const SDK_SOURCE: &str = include_str!("../python/neomind.py");
+
+/// An extension implemented by a Python script.
+pub struct PythonExtension {
+    descriptor: ExtensionDescriptor,
+    /// Metric data types by name, to type the values the script returns
+    metric_types: HashMap<String, MetricDataType>,
+    /// The script's `neomind.Extension` instance, shared with blocking tasks
+    extension: Arc<Py<PyAny>>,
+}
+
+impl PythonExtension {
+    /// Run the script at `path` and read back the extension it defines.
+    ///
+    /// Runs the script's top-level code on the calling thread; async callers
+    /// should call this from `spawn_blocking`.
+    pub fn load(path: &Path) -> std::result::Result<Self, String> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let module_name = path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .unwrap_or("extension")
+            .to_string();
+
+        let (extension, descriptor_json) = Python::with_gil(|py| -> PyResult<_> {
+            let sdk = PyModule::from_code_bound(py, SDK_SOURCE, "neomind.py", "neomind")?;
+            let sys = py.import_bound("sys")?;
+            sys.getattr("modules")?.set_item("neomind", &sdk)?;
+            // Let the script import modules shipped next to it
+            if let Some(dir) = path.parent() {
+                sys.getattr("path")?
+                    .downcast_into::<PyList>()?
+                    .insert(0, dir.to_string_lossy().into_owned())?;
+            }
+
+            PyModule::from_code_bound(py, &source, &path.to_string_lossy(), &module_name)?;
+
+            let extension = sdk.getattr("_registered")?;
+            if extension.is_none() {
+                return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                    "script does not create a neomind.Extension",
+                ));
+            }
+            let descriptor_json: String = extension.call_method0("_descriptor")?.extract()?;
+            Ok((extension.unbind(), descriptor_json))
+        })
+        .map_err(|e| format!("Failed to load Python extension: {}", e))?;
+
+        let json: serde_json::Value = serde_json::from_str(&descriptor_json)
+            .map_err(|e| format!("Invalid Python extension descriptor: {}", e))?;
+        let descriptor = WasmRuntime::parse_descriptor_json(&json)?;
+        let metric_types = descriptor
+            .metrics
+            .iter()
+            .map(|m| (m.name.clone(), m.data_type.clone()))
+            .collect();
+
+        Ok(Self {
+            descriptor,
+            metric_types,
+            extension: Arc::new(extension),
+        })
+    }
+
+    /// Call a method on the script's extension object and extract its result.
+    fn call<A, T>(extension: &Py<PyAny>, method: &str, args: A) -> Result<T>
+    where
+        A: IntoPy<Py<PyTuple>>,
+        T: for<'py> FromPyObject<'py>,
+    {
+        Python::with_gil(|py| extension.bind(py).call_method1(method, args)?.extract())
+            .map_err(|e| ExtensionError::ExecutionFailed(e.to_string()))
+    }
+
+    /// [`Self::call`] on the blocking pool, keeping script code off the
+    /// async runtime's workers.
+    async fn call_blocking<A, T>(&self, method: &'static str, args: A) -> Result<T>
+    where
+        A: IntoPy<Py<PyTuple>> + Send + 'static,
+        T: for<'py> FromPyObject<'py> + Send + 'static,
+    {
+        let extension = Arc::clone(&self.extension);
+        tokio::task::spawn_blocking(move || Self::call(&extension, method, args))
+            .await
+            .map_err(|e| ExtensionError::ExecutionFailed(format!("Python task failed: {}", e)))?
+    }
+
+    fn parse_json(text: &str) -> Result<serde_json::Value> {
+        serde_json::from_str(text).map_err(|e| ExtensionError::InvalidFormat(e.to_string()))
+    }
+
+    fn metric_value(
+        &self,
+        name: String,
+        value: &serde_json::Value,
+    ) -> Option<ExtensionMetricValue> {
+        let value = match self.metric_types.get(&name)? {
+            MetricDataType::Float => value.as_f64()?.into(),
+            MetricDataType::Integer => value.as_i64()?.into(),
+            MetricDataType::Boolean => value.as_bool()?.into(),
+            _ => match value.as_str() {
+                Some(s) => s.into(),
+                None => value.to_string().into(),
+            },
+        };
+        Some(ExtensionMetricValue {
+            name,
+            value,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        })
+    }
+}
+
+#[async_trait]
+impl Extension for PythonExtension {
+    fn metadata(&self) -> &ExtensionMetadata {
+        &self.descriptor.metadata
+    }
+
+    fn descriptor(&self) -> Option<ExtensionDescriptor> {
+        Some(self.descriptor.clone())
+    }
+
+    fn metrics(&self) -> Vec<MetricDescriptor> {
+        self.descriptor.metrics.clone()
+    }
+
+    fn commands(&self) -> Vec<neomind_extension_sdk::CommandDescriptor> {
+        self.descriptor.commands.clone()
+    }
+
+    /// Runs the script's metric functions on the calling thread, as the trait
+    /// is synchronous; the runner calls this under `block_in_place`.
+    fn produce_metrics(&self) -> Result<Vec<ExtensionMetricValue>> {
+        let text: String = Self::call(&self.extension, "_produce_metrics", ())?;
+        let values = Self::parse_json(&text)?;
+        Ok(values
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .filter_map(|v| {
+                        let name = v.get("name")?.as_str()?.to_string();
+                        self.metric_value(name, v.get("value")?)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.call_blocking("_health_check", ()).await
+    }
+
+    async fn configure(&mut self, config: &serde_json::Value) -> Result<()> {
+        let _: Py<PyAny> = self
+            .call_blocking("_configure", (config.to_string(),))
+            .await?;
+        Ok(())
+    }
+
+    async fn execute_command(
+        &self,
+        command_name: &str,
+        args: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        if !self
+            .descriptor
+            .commands
+            .iter()
+            .any(|c| c.name == command_name)
+        {
+            return Err(ExtensionError::CommandNotFound(command_name.to_string()));
+        }
+        let text: String = self
+            .call_blocking("_execute", (command_name.to_string(), args.to_string()))
+            .await?;
+        Self::parse_json(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_extension_sdk::MetricValue;
+    use std::sync::Mutex;
+
+    /// Each load swaps `sys.modules["neomind"]`, so load one script at a time
+    static LOAD_LOCK: Mutex<()> = Mutex::new(());
+
+    const SCRIPT: &str = r#"
+import neomind
+
+ext = neomind.Extension("py-test", "Python Test", "1.2.3", description="Test extension")
+
+@ext.metric("temperature", data_type="float", unit="C")
+def temperature():
+    return 21.5
+
+@ext.metric("count", data_type="integer")
+def count():
+    return 7
+
+@ext.metric("online", data_type="boolean")
+def online():
+    return True
+
+@ext.metric("label", data_type="string")
+def label():
+    return {"room": 1}
+
+@ext.metric("missing", data_type="float")
+def missing():
+    return None
+
+@ext.command("echo", description="Echo the text back",
+             parameters=[{"name": "text", "param_type": "string"}])
+def echo(args):
+    return {"echo": args["text"]}
+
+@ext.command("fail")
+def fail(args):
+    raise ValueError("boom")
+
+@ext.command("not_json")
+def not_json(args):
+    return {1, 2}
+
+@ext.health
+def health():
+    return ext.config.get("healthy", True)
+"#;
+
+    fn load_script(source: &str) -> std::result::Result<PythonExtension, String> {
+        let _guard = LOAD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test_extension.py");
+        std::fs::write(&path, source).unwrap();
+        PythonExtension::load(&path)
+    }
+
+    #[test]
+    fn test_load_parses_descriptor() {
+        let ext = load_script(SCRIPT).unwrap();
+
+        let metadata = ext.metadata();
+        assert_eq!(metadata.id, "py-test");
+        assert_eq!(metadata.name, "Python Test");
+        assert_eq!(metadata.version, "1.2.3");
+        assert_eq!(metadata.description.as_deref(), Some("Test extension"));
+
+        let metrics = ext.metrics();
+        assert_eq!(metrics.len(), 5);
+        let temperature = metrics.iter().find(|m| m.name == "temperature").unwrap();
+        assert!(matches!(temperature.data_type, MetricDataType::Float));
+        assert_eq!(temperature.unit, "C");
+
+        let commands = ext.commands();
+        assert_eq!(commands.len(), 3);
+        let echo = commands.iter().find(|c| c.name == "echo").unwrap();
+        assert_eq!(echo.description, "Echo the text back");
+        assert_eq!(echo.parameters.len(), 1);
+        assert_eq!(echo.parameters[0].name, "text");
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let err = load_script("import neomind\n").err().unwrap();
+        assert!(
+            err.contains("does not create a neomind.Extension"),
+            "{}",
+            err
+        );
+
+        let err = load_script("def broken(:\n").err().unwrap();
+        assert!(err.contains("Failed to load Python extension"), "{}", err);
+
+        let err = PythonExtension::load(Path::new("/nonexistent/extension.py"))
+            .err()
+            .unwrap();
+        assert!(err.contains("Failed to read"), "{}", err);
+    }
+
+    #[test]
+    fn test_produce_metrics_types_values() {
+        let ext = load_script(SCRIPT).unwrap();
+        let values = ext.produce_metrics().unwrap();
+
+        let value = |name: &str| {
+            values
+                .iter()
+                .find(|v| v.name == name)
+                .map(|v| v.value.clone())
+        };
+        assert!(matches!(value("temperature"), Some(MetricValue::Float(v)) if v == 21.5));
+        assert!(matches!(value("count"), Some(MetricValue::Integer(7))));
+        assert!(matches!(value("online"), Some(MetricValue::Boolean(true))));
+        // Non-string values of string metrics are sent as their JSON text
+        assert!(matches!(value("label"), Some(MetricValue::String(s)) if s == r#"{"room":1}"#));
+        // Metrics returning None are left out
+        assert!(value("missing").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_command() {
+        let ext = load_script(SCRIPT).unwrap();
+        let result = ext
+            .execute_command("echo", &serde_json::json!({ "text": "hello" }))
+            .await
+            .unwrap();
+        assert_eq!(result, serde_json::json!({ "echo": "hello" }));
+    }
+
+    #[tokio::test]
+    async fn test_command_errors_are_mapped() {
+        let ext = load_script(SCRIPT).unwrap();
+        let args = serde_json::json!({});
+
+        let err = ext.execute_command("missing", &args).await.unwrap_err();
+        assert!(matches!(err, ExtensionError::CommandNotFound(ref name) if name == "missing"));
+
+        // Exceptions raised by the script surface as execution failures
+        let err = ext.execute_command("fail", &args).await.unwrap_err();
+        assert!(
+            matches!(err, ExtensionError::ExecutionFailed(ref msg) if msg.contains("boom")),
+            "{:?}",
+            err
+        );
+
+        // So do results the SDK cannot encode as JSON
+        let err = ext.execute_command("not_json", &args).await.unwrap_err();
+        assert!(
+            matches!(err, ExtensionError::ExecutionFailed(_)),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_configure_and_health_check() {
+        let mut ext = load_script(SCRIPT).unwrap();
+        assert!(ext.health_check().await.unwrap());
+
+        ext.configure(&serde_json::json!({ "healthy": false }))
+            .await
+            .unwrap();
+        assert!(!ext.health_check().await.unwrap());
+    }
+}
@@ -304,21 +304,37 @@ impl MessageChannel for EmailChannel {
             email_builder = email_builder.to(mailbox);
         }
 
-        let email = email_builder
-            .multipart(
-                lettre::message::MultiPart::alternative()
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(lettre::message::header::ContentType::TEXT_PLAIN)
-                            .body(format!("{}\n\n{}", message.title, message.message)),
-                    )
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(lettre::message::header::ContentType::TEXT_HTML)
-                            .body(html_body),
-                    ),
+        let body = lettre::message::MultiPart::alternative()
+            .singlepart(
+                lettre::message::SinglePart::builder()
+                    .header(lettre::message::header::ContentType::TEXT_PLAIN)
+                    .body(format!("{}\n\n{}", message.title, message.message)),
             )
-            .map_err(|e| Error::SendFailed(format!("Failed to build email: {}", e)))?;
+            .singlepart(
+                lettre::message::SinglePart::builder()
+                    .header(lettre::message::header::ContentType::TEXT_HTML)
+                    .body(html_body),
+            );
+        let attachments = message.attachments();
+        let email = if attachments.is_empty() {
+            email_builder.multipart(body)
+        } else {
+            use lettre::message::header::ContentType;
+
+            let mut mixed = lettre::message::MultiPart::mixed().multipart(body);
+            for attachment in attachments {
+                let data = attachment.data().ok_or_else(|| {
+                    Error::SendFailed(format!("Invalid attachment '{}'", attachment.filename))
+                })?;
+                let content_type = ContentType::parse(&attachment.content_type)
+                    .unwrap_or(ContentType::TEXT_PLAIN);
+                mixed = mixed.singlepart(
+                    lettre::message::Attachment::new(attachment.filename).body(data, content_type),
+                );
+            }
+            email_builder.multipart(mixed)
+        }
+        .map_err(|e| Error::SendFailed(format!("Failed to build email: {}", e)))?;
 
         let smtp_server = self.smtp_server.clone();
         let smtp_port = self.smtp_port;
@@ -394,11 +410,14 @@ impl super::ChannelFactory for EmailChannelFactory {
             .ok_or_else(|| Error::InvalidConfiguration("Missing username".to_string()))?
             .to_string();
 
+        // The password may be a `secret://` handle
         let password = config
             .get("password")
             .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::InvalidConfiguration("Missing password".to_string()))?
-            .to_string();
+            .ok_or_else(|| Error::InvalidConfiguration("Missing password".to_string()))?;
+        let password = neomind_core::secrets::resolve(password)
+            .map_err(|e| Error::InvalidConfiguration(e.to_string()))?
+            .into_owned();
 
         let from_address = config
             .get("from_address")
@@ -23,6 +23,9 @@ pub mod slack;
 #[cfg(feature = "feishu")]
 pub mod feishu;
 
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+
 pub use filter::ChannelFilter;
 
 use async_trait::async_trait;
@@ -56,6 +59,9 @@ pub use slack::{SlackChannel, SlackChannelFactory};
 #[cfg(feature = "feishu")]
 pub use feishu::{FeishuChannel, FeishuChannelFactory};
 
+#[cfg(feature = "mqtt")]
+pub use mqtt::{MqttChannel, MqttChannelConfig, MqttChannelFactory};
+
 /// Trait for message channels.
 #[async_trait]
 pub trait MessageChannel: Send + Sync {
@@ -1094,6 +1100,16 @@ pub fn list_channel_types() -> Vec<ChannelTypeInfo> {
             icon: "messages-square".to_string(),
             category: "external".to_string(),
         },
+        #[cfg(feature = "mqtt")]
+        ChannelTypeInfo {
+            id: "mqtt".to_string(),
+            name: "MQTT".to_string(),
+            name_zh: "MQTT".to_string(),
+            description: "Publish messages as JSON to an MQTT topic".to_string(),
+            description_zh: "以 JSON 格式将消息发布到 MQTT 主题".to_string(),
+            icon: "radio".to_string(),
+            category: "external".to_string(),
+        },
     ]
 }
 
@@ -1173,6 +1189,23 @@ pub fn get_channel_schema(channel_type: &str) -> Option<serde_json::Value> {
             },
             "required": ["hook_id"]
         })),
+        #[cfg(feature = "mqtt")]
+        "mqtt" => Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "broker": {"type": "string", "description": "Broker host (optional with embedded_broker)"},
+                "port": {"type": "integer", "description": "Broker port (default: 1883)"},
+                "embedded_broker": {"type": "boolean", "description": "Publish to the embedded broker"},
+                "topic": {"type": "string", "description": "Topic; supports {category}, {severity} and {source} (default: neomind/messages/{category}/{severity})"},
+                "qos": {"type": "integer", "enum": [0, 1, 2], "description": "QoS level (default: 1)"},
+                "retain": {"type": "boolean", "description": "Publish as retained message"},
+                "username": {"type": "string"},
+                "password": {"type": "string"},
+                "client_id": {"type": "string", "description": "Client ID prefix"}
+            },
+            "required": []
+        })),
         _ => None,
     }
 }
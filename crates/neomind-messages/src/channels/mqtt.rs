@@ -0,0 +1,293 @@
+//! MQTT notification channel.
+//!
+//! Publishes each message as JSON to a topic so machine consumers (SCADA,
+//! historians, PLC gateways) can subscribe to alerts instead of polling the
+//! HTTP API. The topic may contain `{category}`, `{severity}` and `{source}`
+//! placeholders.
+
+#[cfg(feature = "mqtt")]
+use async_trait::async_trait;
+#[cfg(feature = "mqtt")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "mqtt")]
+use tokio::sync::Mutex;
+
+#[cfg(feature = "mqtt")]
+use super::super::{Error, Message, Result};
+#[cfg(feature = "mqtt")]
+use super::MessageChannel;
+
+/// MQTT channel configuration.
+#[cfg(feature = "mqtt")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MqttChannelConfig {
+    /// Broker host. Defaults to localhost when `embedded_broker` is set.
+    #[serde(default)]
+    pub broker: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Publish to NeoMind's embedded broker
+    #[serde(default)]
+    pub embedded_broker: bool,
+    #[serde(default = "default_topic")]
+    pub topic: String,
+    #[serde(default = "default_qos")]
+    pub qos: u8,
+    /// Publish as retained so late subscribers get the latest message
+    #[serde(default)]
+    pub retain: bool,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Client ID prefix (random suffix appended)
+    #[serde(default = "default_client_id")]
+    pub client_id: String,
+}
+
+#[cfg(feature = "mqtt")]
+fn default_port() -> u16 {
+    1883
+}
+
+#[cfg(feature = "mqtt")]
+fn default_topic() -> String {
+    "neomind/messages/{category}/{severity}".to_string()
+}
+
+#[cfg(feature = "mqtt")]
+fn default_qos() -> u8 {
+    1
+}
+
+#[cfg(feature = "mqtt")]
+fn default_client_id() -> String {
+    "neomind-messages".to_string()
+}
+
+#[cfg(feature = "mqtt")]
+impl MqttChannelConfig {
+    fn host(&self) -> &str {
+        if self.broker.is_empty() && self.embedded_broker {
+            "127.0.0.1"
+        } else {
+            &self.broker
+        }
+    }
+
+    fn qos(&self) -> rumqttc::QoS {
+        match self.qos {
+            0 => rumqttc::QoS::AtMostOnce,
+            2 => rumqttc::QoS::ExactlyOnce,
+            _ => rumqttc::QoS::AtLeastOnce,
+        }
+    }
+
+    /// Topic for `message` with placeholders filled in.
+    pub fn topic_for(&self, message: &Message) -> String {
+        self.topic
+            .replace("{category}", &message.category)
+            .replace("{severity}", message.severity.as_str())
+            .replace("{source}", &message.source)
+    }
+}
+
+/// MQTT channel publishing messages as JSON.
+#[cfg(feature = "mqtt")]
+pub struct MqttChannel {
+    name: String,
+    enabled: bool,
+    config: MqttChannelConfig,
+    client: Mutex<Option<rumqttc::AsyncClient>>,
+}
+
+#[cfg(feature = "mqtt")]
+impl MqttChannel {
+    pub fn new(name: String, config: MqttChannelConfig) -> Self {
+        Self {
+            name,
+            enabled: true,
+            config,
+            client: Mutex::new(None),
+        }
+    }
+
+    pub fn disabled(mut self) -> Self {
+        self.enabled = false;
+        self
+    }
+
+    /// Connect lazily. The event loop runs in a background task that keeps
+    /// reconnecting, so publishes queue while the broker is unreachable.
+    async fn client(&self) -> rumqttc::AsyncClient {
+        let mut guard = self.client.lock().await;
+        if let Some(client) = guard.as_ref() {
+            return client.clone();
+        }
+
+        let client_id = format!(
+            "{}-{}",
+            self.config.client_id,
+            uuid::Uuid::new_v4().as_simple()
+        );
+        let mut options =
+            rumqttc::MqttOptions::new(client_id, self.config.host(), self.config.port);
+        options.set_keep_alive(std::time::Duration::from_secs(30));
+        if let (Some(user), Some(pass)) = (&self.config.username, &self.config.password) {
+            options.set_credentials(user, pass);
+        }
+
+        let (client, mut eventloop) = rumqttc::AsyncClient::new(options, 64);
+        let name = self.name.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = eventloop.poll().await {
+                    tracing::warn!("MQTT channel '{}' connection error: {}", name, e);
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                }
+            }
+        });
+        *guard = Some(client.clone());
+        client
+    }
+}
+
+#[cfg(feature = "mqtt")]
+#[async_trait]
+impl MessageChannel for MqttChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn channel_type(&self) -> &str {
+        "mqtt"
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    async fn send(&self, message: &Message) -> Result<()> {
+        if !self.enabled {
+            return Err(Error::ChannelDisabled(self.name.clone()));
+        }
+
+        let payload = serde_json::to_vec(message)
+            .map_err(|e| Error::SendFailed(format!("Failed to serialize message: {}", e)))?;
+        self.client()
+            .await
+            .publish(
+                self.config.topic_for(message),
+                self.config.qos(),
+                self.config.retain,
+                payload,
+            )
+            .await
+            .map_err(|e| Error::SendFailed(format!("MQTT publish failed: {}", e)))
+    }
+}
+
+/// Factory for creating MQTT channels.
+#[cfg(feature = "mqtt")]
+pub struct MqttChannelFactory;
+
+#[cfg(feature = "mqtt")]
+impl super::ChannelFactory for MqttChannelFactory {
+    fn channel_type(&self) -> &str {
+        "mqtt"
+    }
+
+    fn create(&self, config: &serde_json::Value) -> Result<std::sync::Arc<dyn MessageChannel>> {
+        let mut mqtt_config: MqttChannelConfig = serde_json::from_value(config.clone())
+            .map_err(|e| Error::InvalidConfiguration(format!("Invalid MQTT config: {}", e)))?;
+        if let Some(password) = &mqtt_config.password {
+            mqtt_config.password = Some(
+                neomind_core::secrets::resolve(password)
+                    .map_err(|e| Error::InvalidConfiguration(e.to_string()))?
+                    .into_owned(),
+            );
+        }
+        if mqtt_config.broker.is_empty() && !mqtt_config.embedded_broker {
+            return Err(Error::InvalidConfiguration(
+                "Missing broker (or set embedded_broker)".to_string(),
+            ));
+        }
+        if mqtt_config.topic.is_empty() {
+            return Err(Error::InvalidConfiguration("Missing topic".to_string()));
+        }
+        if mqtt_config.qos > 2 {
+            return Err(Error::InvalidConfiguration(
+                "qos must be 0, 1 or 2".to_string(),
+            ));
+        }
+
+        let name = config
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("mqtt")
+            .to_string();
+
+        let mut channel = MqttChannel::new(name, mqtt_config);
+
+        if !config
+            .get("enabled")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true)
+        {
+            channel = channel.disabled();
+        }
+
+        Ok(std::sync::Arc::new(channel))
+    }
+}
+
+#[cfg(feature = "mqtt")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::ChannelFactory;
+    use crate::MessageSeverity;
+
+    #[test]
+    fn test_factory_requires_broker() {
+        let factory = MqttChannelFactory;
+        let result = factory.create(&serde_json::json!({}));
+        assert!(result.err().unwrap().to_string().contains("broker"));
+
+        let channel = factory
+            .create(&serde_json::json!({ "embedded_broker": true }))
+            .unwrap();
+        assert_eq!(channel.channel_type(), "mqtt");
+        assert!(channel.is_enabled());
+
+        let result = factory.create(&serde_json::json!({ "broker": "scada.local", "qos": 3 }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_topic_placeholders() {
+        let config: MqttChannelConfig = serde_json::from_value(serde_json::json!({
+            "broker": "scada.local",
+            "topic": "plant/{category}/{severity}/{source}",
+            "retain": true
+        }))
+        .unwrap();
+        let message = Message::alert(
+            MessageSeverity::Critical,
+            "Pump failure".to_string(),
+            "Pressure dropped".to_string(),
+            "pump-1".to_string(),
+        );
+        assert_eq!(config.topic_for(&message), "plant/alert/critical/pump-1");
+        assert_eq!(config.host(), "scada.local");
+        assert_eq!(config.port, 1883);
+    }
+
+    #[test]
+    fn test_embedded_broker_defaults_to_localhost() {
+        let config: MqttChannelConfig =
+            serde_json::from_value(serde_json::json!({ "embedded_broker": true })).unwrap();
+        assert_eq!(config.host(), "127.0.0.1");
+        assert_eq!(config.topic, "neomind/messages/{category}/{severity}");
+    }
+}
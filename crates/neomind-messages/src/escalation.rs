@@ -0,0 +1,444 @@
+//! Alert escalation policies and on-call schedules.
+//!
+//! An [`EscalationPolicy`] is a list of steps, each sent a fixed delay after
+//! the alert was created while it is still unacknowledged: "notify #ops now,
+//! after 10 minutes page the on-call engineer, after 30 minutes the team
+//! lead". A step targets channels directly and/or whoever is on call in an
+//! [`OnCallSchedule`] (a rotation with one-off overrides), reached through
+//! their personal channel. [`EscalationManager`] checks active alerts
+//! periodically and sends the steps that have come due.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{Message, MessageId, MessageManager, MessageSeverity, MessageStatus};
+
+/// Settings key under which [`EscalationConfig`] is persisted.
+pub const ESCALATION_SETTINGS_KEY: &str = "alert_escalation";
+
+/// A person in an on-call rotation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OnCallMember {
+    pub name: String,
+    /// Channel that reaches this person (e.g. their Telegram or email channel)
+    pub channel: String,
+}
+
+/// Replaces the rotation for a time range (holidays, swaps).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OnCallOverride {
+    pub start: i64,
+    pub end: i64,
+    /// Name of the member on call instead
+    pub member: String,
+}
+
+/// A rotation handing off every `rotation_hours`, starting at
+/// `rotation_start` with the first member.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OnCallSchedule {
+    pub id: String,
+    pub name: String,
+    pub members: Vec<OnCallMember>,
+    /// Unix timestamp of the first handoff
+    pub rotation_start: i64,
+    /// Shift length in hours (168 = weekly)
+    #[serde(default = "default_rotation_hours")]
+    pub rotation_hours: u32,
+    #[serde(default)]
+    pub overrides: Vec<OnCallOverride>,
+}
+
+fn default_rotation_hours() -> u32 {
+    168
+}
+
+impl OnCallSchedule {
+    /// Who is on call at `timestamp`.
+    pub fn on_call_at(&self, timestamp: i64) -> Option<&OnCallMember> {
+        if let Some(member) = self
+            .overrides
+            .iter()
+            .find(|o| o.start <= timestamp && timestamp < o.end)
+            .and_then(|o| self.members.iter().find(|m| m.name == o.member))
+        {
+            return Some(member);
+        }
+        if self.members.is_empty() || self.rotation_hours == 0 {
+            return None;
+        }
+        let shift = (timestamp - self.rotation_start).div_euclid(self.rotation_hours as i64 * 3600);
+        let index = shift.rem_euclid(self.members.len() as i64) as usize;
+        self.members.get(index)
+    }
+}
+
+/// One escalation step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EscalationStep {
+    /// Seconds after the alert was created
+    pub delay_secs: i64,
+    /// Channels to notify
+    #[serde(default)]
+    pub channels: Vec<String>,
+    /// Also notify whoever is on call in this schedule
+    #[serde(default)]
+    pub schedule_id: Option<String>,
+}
+
+/// Steps applied to unacknowledged alerts of at least `min_severity`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EscalationPolicy {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(
+        serialize_with = "MessageSeverity::serialize",
+        deserialize_with = "MessageSeverity::deserialize"
+    )]
+    pub min_severity: MessageSeverity,
+    /// Only alerts from this source (rule or device ID); all if unset
+    #[serde(default)]
+    pub source: Option<String>,
+    pub steps: Vec<EscalationStep>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl EscalationPolicy {
+    pub fn applies_to(&self, message: &Message) -> bool {
+        self.enabled
+            && message.category == "alert"
+            && message.severity >= self.min_severity
+            && self.source.as_ref().is_none_or(|s| *s == message.source)
+    }
+}
+
+/// Escalation policies and the schedules they reference.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EscalationConfig {
+    #[serde(default)]
+    pub policies: Vec<EscalationPolicy>,
+    #[serde(default)]
+    pub schedules: Vec<OnCallSchedule>,
+}
+
+impl EscalationConfig {
+    /// Check that steps are ordered and referenced schedules and override
+    /// members exist.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        for schedule in &self.schedules {
+            if schedule.members.is_empty() {
+                return Err(format!("Schedule '{}' has no members", schedule.id));
+            }
+            if let Some(o) = schedule
+                .overrides
+                .iter()
+                .find(|o| !schedule.members.iter().any(|m| m.name == o.member))
+            {
+                return Err(format!(
+                    "Schedule '{}' override names unknown member '{}'",
+                    schedule.id, o.member
+                ));
+            }
+        }
+        for policy in &self.policies {
+            if policy.steps.is_empty() {
+                return Err(format!("Policy '{}' has no steps", policy.id));
+            }
+            if policy
+                .steps
+                .windows(2)
+                .any(|w| w[1].delay_secs < w[0].delay_secs)
+            {
+                return Err(format!(
+                    "Policy '{}' steps must be ordered by delay",
+                    policy.id
+                ));
+            }
+            for step in &policy.steps {
+                if step.channels.is_empty() && step.schedule_id.is_none() {
+                    return Err(format!(
+                        "Policy '{}' has a step with no channel or schedule",
+                        policy.id
+                    ));
+                }
+                if let Some(id) = &step.schedule_id {
+                    if !self.schedules.iter().any(|s| &s.id == id) {
+                        return Err(format!(
+                            "Policy '{}' references unknown schedule '{}'",
+                            policy.id, id
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn schedule(&self, id: &str) -> Option<&OnCallSchedule> {
+        self.schedules.iter().find(|s| s.id == id)
+    }
+}
+
+/// A step that was sent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EscalationNotice {
+    pub message_id: String,
+    pub policy_id: String,
+    /// Step number, starting at 1
+    pub step: usize,
+    pub channels: Vec<String>,
+    pub on_call: Option<String>,
+}
+
+/// Sends escalation steps for unacknowledged alerts.
+pub struct EscalationManager {
+    messages: Arc<MessageManager>,
+    config: RwLock<EscalationConfig>,
+    /// Steps already sent, per alert
+    progress: RwLock<HashMap<MessageId, usize>>,
+}
+
+impl EscalationManager {
+    pub fn new(messages: Arc<MessageManager>) -> Self {
+        Self {
+            messages,
+            config: RwLock::new(EscalationConfig::default()),
+            progress: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn config(&self) -> EscalationConfig {
+        self.config.read().await.clone()
+    }
+
+    pub async fn set_config(&self, config: EscalationConfig) {
+        *self.config.write().await = config;
+    }
+
+    /// Who is on call in every schedule at `timestamp`.
+    pub async fn on_call(&self, timestamp: i64) -> Vec<(String, Option<OnCallMember>)> {
+        self.config
+            .read()
+            .await
+            .schedules
+            .iter()
+            .map(|s| (s.id.clone(), s.on_call_at(timestamp).cloned()))
+            .collect()
+    }
+
+    /// Send every step that has come due at `now`. Acknowledging or
+    /// resolving an alert stops its escalation.
+    pub async fn tick(&self, now: i64) -> Vec<EscalationNotice> {
+        let config = self.config.read().await.clone();
+        if config.policies.is_empty() {
+            return Vec::new();
+        }
+        let active = self
+            .messages
+            .list_messages_by_status(MessageStatus::Active)
+            .await;
+
+        let mut notices = Vec::new();
+        let mut progress = self.progress.write().await;
+        progress.retain(|id, _| active.iter().any(|m| &m.id == id));
+
+        for message in &active {
+            if message.tags.iter().any(|t| t == "maintenance") {
+                continue;
+            }
+            let Some(policy) = config.policies.iter().find(|p| p.applies_to(message)) else {
+                continue;
+            };
+            let elapsed = now - message.timestamp.timestamp();
+            let done = progress.entry(message.id.clone()).or_insert(0);
+            while let Some(step) = policy.steps.get(*done) {
+                if elapsed < step.delay_secs {
+                    break;
+                }
+                *done += 1;
+                let on_call = step
+                    .schedule_id
+                    .as_deref()
+                    .and_then(|id| config.schedule(id))
+                    .and_then(|s| s.on_call_at(now));
+                let mut channels = step.channels.clone();
+                if let Some(member) = on_call {
+                    if !channels.contains(&member.channel) {
+                        channels.push(member.channel.clone());
+                    }
+                }
+
+                let mut notification = message.clone();
+                notification.title = format!("[Escalation {}] {}", *done, message.title);
+                notification.message = format!(
+                    "{}\n\nUnacknowledged for {} min.{}",
+                    message.message,
+                    elapsed / 60,
+                    on_call
+                        .map(|m| format!(" On call: {}.", m.name))
+                        .unwrap_or_default()
+                );
+                self.messages
+                    .send_to_named_channels(&channels, &notification)
+                    .await;
+
+                tracing::info!(
+                    message_id = %message.id,
+                    policy = %policy.id,
+                    step = *done,
+                    "Escalated alert '{}'",
+                    message.title
+                );
+                notices.push(EscalationNotice {
+                    message_id: message.id.to_string(),
+                    policy_id: policy.id.clone(),
+                    step: *done,
+                    channels,
+                    on_call: on_call.map(|m| m.name.clone()),
+                });
+            }
+        }
+        notices
+    }
+
+    /// Check for due steps every `interval_secs`.
+    pub fn start(self: &Arc<Self>, interval_secs: u64) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(std::time::Duration::from_secs(interval_secs.max(5)));
+            loop {
+                interval.tick().await;
+                manager.tick(chrono::Utc::now().timestamp()).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> OnCallSchedule {
+        let member = |name: &str| OnCallMember {
+            name: name.to_string(),
+            channel: format!("{}-telegram", name),
+        };
+        OnCallSchedule {
+            id: "ops".to_string(),
+            name: "Ops".to_string(),
+            members: vec![member("alice"), member("bob")],
+            rotation_start: 0,
+            rotation_hours: 168,
+            overrides: vec![OnCallOverride {
+                start: 1000,
+                end: 2000,
+                member: "bob".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_weekly_rotation_with_override() {
+        let schedule = schedule();
+        let week = 168 * 3600;
+        assert_eq!(schedule.on_call_at(0).unwrap().name, "alice");
+        assert_eq!(schedule.on_call_at(1500).unwrap().name, "bob");
+        assert_eq!(schedule.on_call_at(week).unwrap().name, "bob");
+        assert_eq!(schedule.on_call_at(2 * week + 1).unwrap().name, "alice");
+        assert_eq!(schedule.on_call_at(-1).unwrap().name, "bob");
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut config = EscalationConfig {
+            policies: vec![EscalationPolicy {
+                id: "critical".to_string(),
+                name: "Critical".to_string(),
+                enabled: true,
+                min_severity: MessageSeverity::Critical,
+                source: None,
+                steps: vec![EscalationStep {
+                    delay_secs: 0,
+                    channels: vec![],
+                    schedule_id: Some("ops".to_string()),
+                }],
+            }],
+            schedules: vec![schedule()],
+        };
+        assert!(config.validate().is_ok());
+        config.schedules.clear();
+        assert!(config.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_steps_sent_until_acknowledged() {
+        let messages = Arc::new(MessageManager::new());
+        let manager = EscalationManager::new(messages.clone());
+        manager
+            .set_config(EscalationConfig {
+                policies: vec![EscalationPolicy {
+                    id: "critical".to_string(),
+                    name: "Critical".to_string(),
+                    enabled: true,
+                    min_severity: MessageSeverity::Critical,
+                    source: None,
+                    steps: vec![
+                        EscalationStep {
+                            delay_secs: 0,
+                            channels: vec!["ops".to_string()],
+                            schedule_id: None,
+                        },
+                        EscalationStep {
+                            delay_secs: 600,
+                            channels: vec![],
+                            schedule_id: Some("ops".to_string()),
+                        },
+                    ],
+                }],
+                schedules: vec![schedule()],
+            })
+            .await;
+
+        let alert = messages
+            .create_message(Message::alert(
+                MessageSeverity::Critical,
+                "Pump failure".to_string(),
+                "Pressure dropped".to_string(),
+                "pump-1".to_string(),
+            ))
+            .await
+            .unwrap();
+        messages
+            .create_message(Message::alert(
+                MessageSeverity::Warning,
+                "Low battery".to_string(),
+                "12%".to_string(),
+                "sensor-1".to_string(),
+            ))
+            .await
+            .unwrap();
+        let created = alert.timestamp.timestamp();
+
+        let first = manager.tick(created).await;
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].channels, vec!["ops".to_string()]);
+        assert!(manager.tick(created + 60).await.is_empty());
+
+        let second = manager.tick(created + 600).await;
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].step, 2);
+        assert!(second[0].on_call.is_some());
+
+        messages.acknowledge(&alert.id).await.unwrap();
+        assert!(manager.tick(created + 3600).await.is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+//! Alert deduplication, digests and flap suppression.
+//!
+//! Alerts are keyed by a [`fingerprint`] of their source and title. While
+//! the first alert of a fingerprint is still open, repeats collapse onto it
+//! and bump an occurrence counter instead of creating new messages and
+//! notifications. Collapsed repeats are reported periodically in a single
+//! digest notification. An alert that keeps re-firing shortly after being
+//! resolved is marked as flapping and stored without notifying channels.
+
+use serde::{Deserialize, Serialize};
+
+pub use neomind_storage::AlertGroupState;
+
+use crate::Message;
+
+/// Settings key under which the grouping configuration is persisted.
+pub const ALERT_GROUPING_SETTINGS_KEY: &str = "alert_grouping";
+
+/// Alert grouping configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertGroupingConfig {
+    /// Collapse repeats of open alerts
+    pub enabled: bool,
+    /// Repeats are collapsed only while the open alert fired within this window
+    pub dedup_window_secs: i64,
+    /// How often collapsed repeats are sent as a digest (0 disables digests)
+    pub digest_interval_secs: u64,
+    /// Re-fires after resolve, within `flap_window_secs`, that mark an alert as flapping
+    pub flap_threshold: usize,
+    pub flap_window_secs: i64,
+}
+
+impl Default for AlertGroupingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dedup_window_secs: 3600,
+            digest_interval_secs: 300,
+            flap_threshold: 3,
+            flap_window_secs: 1800,
+        }
+    }
+}
+
+/// What to do with an incoming alert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupDecision {
+    /// New alert: store and notify
+    Notify,
+    /// Repeat of an open alert: bump the counter on this message
+    Collapse {
+        message_id: String,
+        occurrences: u64,
+    },
+    /// Flapping: store without notifying
+    Suppress,
+}
+
+/// Key identifying "the same alert": tenant, category, source, the device it
+/// concerns (if recorded in metadata) and the normalized title.
+pub fn fingerprint(message: &Message) -> String {
+    let device_id = message
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("device_id"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let fingerprint = format!(
+        "{}|{}|{}|{}|{}",
+        message.category,
+        message.source_type,
+        message.source,
+        device_id,
+        message.title.trim().to_lowercase()
+    );
+    // Alerts never collapse across tenants; default-tenant keys are unchanged
+    if message.tenant_id.is_default() {
+        fingerprint
+    } else {
+        format!("{}|{}", message.tenant_id, fingerprint)
+    }
+}
+
+/// Update `group` for an alert firing at `now` and decide how to handle it.
+///
+/// `group` is `None` for a fingerprint seen for the first time. `open` tells
+/// whether the group's current message is still active or acknowledged, and
+/// `new_message_id` is the id the alert gets if it is not collapsed.
+pub fn classify(
+    config: &AlertGroupingConfig,
+    group: &mut Option<AlertGroupState>,
+    fingerprint: &str,
+    open: bool,
+    new_message_id: &str,
+    now: i64,
+) -> GroupDecision {
+    let Some(state) = group else {
+        *group = Some(AlertGroupState {
+            fingerprint: fingerprint.to_string(),
+            message_id: new_message_id.to_string(),
+            occurrences: 1,
+            first_seen: now,
+            last_seen: now,
+            ..Default::default()
+        });
+        return GroupDecision::Notify;
+    };
+
+    if open && now - state.last_seen <= config.dedup_window_secs {
+        state.occurrences += 1;
+        state.pending_digest += 1;
+        state.last_seen = now;
+        return GroupDecision::Collapse {
+            message_id: state.message_id.clone(),
+            occurrences: state.occurrences,
+        };
+    }
+
+    // Fired again after the previous alert was closed (or went stale)
+    if !open {
+        state.refires.push(now);
+    }
+    state
+        .refires
+        .retain(|t| now - *t <= config.flap_window_secs);
+    state.flapping = config.flap_threshold > 0 && state.refires.len() >= config.flap_threshold;
+    state.message_id = new_message_id.to_string();
+    state.occurrences = 1;
+    state.pending_digest = 0;
+    state.first_seen = now;
+    state.last_seen = now;
+
+    if state.flapping {
+        GroupDecision::Suppress
+    } else {
+        GroupDecision::Notify
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MessageSeverity;
+
+    fn config() -> AlertGroupingConfig {
+        AlertGroupingConfig {
+            dedup_window_secs: 600,
+            flap_threshold: 2,
+            flap_window_secs: 1000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_case_and_message() {
+        let a = Message::alert(
+            MessageSeverity::Warning,
+            "High Temperature".to_string(),
+            "81°C".to_string(),
+            "rule-1".to_string(),
+        );
+        let b = Message::alert(
+            MessageSeverity::Warning,
+            "high temperature ".to_string(),
+            "83°C".to_string(),
+            "rule-1".to_string(),
+        );
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+
+        let mut other_tenant = b.clone();
+        other_tenant.tenant_id = neomind_core::tenant::TenantId::new("acme").unwrap();
+        assert_ne!(fingerprint(&a), fingerprint(&other_tenant));
+    }
+
+    #[test]
+    fn test_collapse_while_open() {
+        let config = config();
+        let mut group = None;
+        assert_eq!(
+            classify(&config, &mut group, "fp", true, "m1", 0),
+            GroupDecision::Notify
+        );
+        assert_eq!(
+            classify(&config, &mut group, "fp", true, "m2", 100),
+            GroupDecision::Collapse {
+                message_id: "m1".to_string(),
+                occurrences: 2,
+            }
+        );
+        let state = group.as_ref().unwrap();
+        assert_eq!(state.occurrences, 2);
+        assert_eq!(state.pending_digest, 1);
+
+        // Outside the window the alert starts over
+        assert_eq!(
+            classify(&config, &mut group, "fp", true, "m3", 1000),
+            GroupDecision::Notify
+        );
+        assert_eq!(group.as_ref().unwrap().message_id, "m3");
+    }
+
+    #[test]
+    fn test_flapping_is_suppressed() {
+        let config = config();
+        let mut group = None;
+        classify(&config, &mut group, "fp", true, "m1", 0);
+        // Resolved, fires again: first re-fire still notifies
+        assert_eq!(
+            classify(&config, &mut group, "fp", false, "m2", 100),
+            GroupDecision::Notify
+        );
+        // Second re-fire within the flap window is suppressed
+        assert_eq!(
+            classify(&config, &mut group, "fp", false, "m3", 200),
+            GroupDecision::Suppress
+        );
+        assert!(group.as_ref().unwrap().flapping);
+        // Quiet for longer than the window: notifies again
+        assert_eq!(
+            classify(&config, &mut group, "fp", false, "m4", 5000),
+            GroupDecision::Notify
+        );
+        assert!(!group.as_ref().unwrap().flapping);
+    }
+}
@@ -17,6 +17,9 @@
 //! - **Severity Levels**: Info, Warning, Critical, Emergency
 //! - **Notification Channels**: Webhook, Email (extensible)
 //! - **Plugin System**: Extensible channel architecture
+//! - **Alert Grouping**: Deduplication, digests and flap suppression
+//! - **Escalation**: Multi-step policies bound to on-call rotations
+//! - **Maintenance Windows**: Record alerts without notifying during planned work
 //!
 //! ## Example
 //!
@@ -43,6 +46,9 @@
 
 pub mod channels;
 pub mod error;
+pub mod escalation;
+pub mod grouping;
+pub mod maintenance;
 pub mod manager;
 pub mod message;
 
@@ -52,8 +58,11 @@ pub use channels::{
     MessageChannel,
 };
 pub use error::{Error, Result};
-pub use manager::MessageManager;
-pub use message::{Message, MessageId, MessageSeverity, MessageStatus};
+pub use escalation::{EscalationConfig, EscalationManager};
+pub use grouping::AlertGroupingConfig;
+pub use maintenance::MaintenanceRegistry;
+pub use manager::{MessageManager, TenantResolver};
+pub use message::{Message, MessageAttachment, MessageId, MessageSeverity, MessageStatus};
 
 // Feature-gated channel factories (used by API handler for channel registration)
 #[cfg(feature = "webhook")]
@@ -70,6 +79,8 @@ pub use channels::DingTalkChannelFactory;
 pub use channels::SlackChannelFactory;
 #[cfg(feature = "feishu")]
 pub use channels::FeishuChannelFactory;
+#[cfg(feature = "mqtt")]
+pub use channels::MqttChannelFactory;
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
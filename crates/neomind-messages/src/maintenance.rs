@@ -0,0 +1,189 @@
+//! Maintenance windows.
+//!
+//! While a [`MaintenanceWindow`] is active, alerts about the devices and
+//! rules it covers are recorded without notifying channels, and the rule
+//! engine skips the actions of covered rules. Group scopes are resolved to
+//! device IDs by the owner of the device registry via
+//! [`MaintenanceRegistry::set_group_members`].
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use chrono::{Datelike, TimeZone, Timelike};
+
+pub use neomind_storage::{MaintenanceRecurrence, MaintenanceWindow};
+
+use crate::Message;
+
+/// Active maintenance windows, shared by the message manager and the rule
+/// engine.
+pub struct MaintenanceRegistry {
+    windows: RwLock<Vec<MaintenanceWindow>>,
+    /// Group ID -> member device IDs
+    group_members: RwLock<HashMap<String, Vec<String>>>,
+    /// Timezone recurring windows are evaluated in
+    timezone: RwLock<chrono_tz::Tz>,
+}
+
+impl Default for MaintenanceRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MaintenanceRegistry {
+    pub fn new() -> Self {
+        Self {
+            windows: RwLock::new(Vec::new()),
+            group_members: RwLock::new(HashMap::new()),
+            timezone: RwLock::new(chrono_tz::UTC),
+        }
+    }
+
+    pub fn windows(&self) -> Vec<MaintenanceWindow> {
+        self.windows.read().unwrap().clone()
+    }
+
+    pub fn set_windows(&self, windows: Vec<MaintenanceWindow>) {
+        *self.windows.write().unwrap() = windows;
+    }
+
+    pub fn set_group_members(&self, members: HashMap<String, Vec<String>>) {
+        *self.group_members.write().unwrap() = members;
+    }
+
+    pub fn set_timezone(&self, timezone: chrono_tz::Tz) {
+        *self.timezone.write().unwrap() = timezone;
+    }
+
+    /// Windows in effect at `timestamp`.
+    pub fn active(&self, timestamp: i64) -> Vec<MaintenanceWindow> {
+        let Some(local) = self
+            .timezone
+            .read()
+            .unwrap()
+            .timestamp_opt(timestamp, 0)
+            .single()
+        else {
+            return Vec::new();
+        };
+        let weekday = local.weekday().num_days_from_monday() as u8;
+        let minute = local.hour() * 60 + local.minute();
+        self.windows
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|w| w.active_at(timestamp, weekday, minute))
+            .cloned()
+            .collect()
+    }
+
+    fn covers_device(&self, window: &MaintenanceWindow, device_id: &str) -> bool {
+        window.devices.iter().any(|d| d == device_id) || {
+            let members = self.group_members.read().unwrap();
+            window
+                .groups
+                .iter()
+                .filter_map(|g| members.get(g))
+                .any(|m| m.iter().any(|d| d == device_id))
+        }
+    }
+
+    /// Active window covering a device.
+    pub fn window_for_device(&self, device_id: &str, timestamp: i64) -> Option<MaintenanceWindow> {
+        self.active(timestamp)
+            .into_iter()
+            .find(|w| w.is_global() || self.covers_device(w, device_id))
+    }
+
+    /// Active window covering a rule, either directly or through one of the
+    /// devices it reads or commands.
+    pub fn window_for_rule(
+        &self,
+        rule_id: &str,
+        device_ids: &[String],
+        timestamp: i64,
+    ) -> Option<MaintenanceWindow> {
+        self.active(timestamp).into_iter().find(|w| {
+            w.is_global()
+                || w.rules.iter().any(|r| r == rule_id)
+                || device_ids.iter().any(|d| self.covers_device(w, d))
+        })
+    }
+
+    /// Active window covering the device or rule an alert is about.
+    pub fn window_for_message(
+        &self,
+        message: &Message,
+        timestamp: i64,
+    ) -> Option<MaintenanceWindow> {
+        let metadata_device = message
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("device_id"))
+            .and_then(|v| v.as_str());
+        self.active(timestamp).into_iter().find(|w| {
+            w.is_global()
+                || (message.source_type == "device" && self.covers_device(w, &message.source))
+                || (message.source_type == "rule" && w.rules.contains(&message.source))
+                || metadata_device.is_some_and(|d| self.covers_device(w, d))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MessageSeverity;
+
+    fn registry() -> MaintenanceRegistry {
+        let registry = MaintenanceRegistry::new();
+        registry.set_windows(vec![MaintenanceWindow {
+            id: "line-2".to_string(),
+            name: "Line 2 retrofit".to_string(),
+            start: 1000,
+            end: Some(2000),
+            groups: vec!["line-2".to_string()],
+            rules: vec!["rule-9".to_string()],
+            enabled: true,
+            ..Default::default()
+        }]);
+        registry.set_group_members(HashMap::from([(
+            "line-2".to_string(),
+            vec!["press-1".to_string()],
+        )]));
+        registry
+    }
+
+    #[test]
+    fn test_scope_matching() {
+        let registry = registry();
+        assert!(registry.window_for_device("press-1", 1500).is_some());
+        assert!(registry.window_for_device("press-1", 2500).is_none());
+        assert!(registry.window_for_device("press-2", 1500).is_none());
+        assert!(registry.window_for_rule("rule-9", &[], 1500).is_some());
+        assert!(registry
+            .window_for_rule("rule-1", &["press-1".to_string()], 1500)
+            .is_some());
+        assert!(registry.window_for_rule("rule-1", &[], 1500).is_none());
+    }
+
+    #[test]
+    fn test_message_matching() {
+        let registry = registry();
+        let device_alert = Message::device(
+            MessageSeverity::Critical,
+            "Overpressure".to_string(),
+            "12 bar".to_string(),
+            "press-1".to_string(),
+        );
+        assert!(registry.window_for_message(&device_alert, 1500).is_some());
+        let other = Message::device(
+            MessageSeverity::Critical,
+            "Overpressure".to_string(),
+            "12 bar".to_string(),
+            "press-2".to_string(),
+        );
+        assert!(registry.window_for_message(&other, 1500).is_none());
+    }
+}
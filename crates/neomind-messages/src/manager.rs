@@ -8,14 +8,22 @@ use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use neomind_core::tenant::TenantId;
+
 use super::channels::{ChannelFactory, ChannelFilter, ChannelRegistry};
 use super::error::{Error, Result};
+use super::grouping::{self, AlertGroupState, AlertGroupingConfig, GroupDecision};
+use super::maintenance::MaintenanceRegistry;
 use super::{Message, MessageId, MessageSeverity, MessageStatus};
 
 /// Minimum interval (in seconds) between duplicate messages with the same
 /// title + source + severity key. Prevents message bombing from rules engine.
 const DEDUP_INTERVAL_SECS: i64 = 60;
 
+/// Resolves the owner of a message created without a tenant, e.g. the tenant
+/// of the device an alert is about.
+pub type TenantResolver = Arc<dyn Fn(&Message) -> Option<TenantId> + Send + Sync>;
+
 /// Persistent message manager with storage backend.
 #[derive(Clone)]
 pub struct MessageManager {
@@ -29,6 +37,14 @@ pub struct MessageManager {
     event_bus: Arc<RwLock<Option<Arc<neomind_core::EventBus>>>>,
     /// Deduplication cache: (title, source, severity) -> last send timestamp
     dedup_cache: Arc<RwLock<HashMap<String, chrono::DateTime<chrono::Utc>>>>,
+    /// Alert grouping configuration
+    grouping: Arc<RwLock<AlertGroupingConfig>>,
+    /// Alert grouping state by fingerprint
+    alert_groups: Arc<RwLock<HashMap<String, AlertGroupState>>>,
+    /// Maintenance windows suppressing alert notifications
+    maintenance: Arc<MaintenanceRegistry>,
+    /// Owner lookup for messages created in the default tenant
+    tenant_resolver: Arc<RwLock<Option<TenantResolver>>>,
 }
 
 impl Default for MessageManager {
@@ -46,6 +62,10 @@ impl MessageManager {
             channels: Arc::new(RwLock::new(ChannelRegistry::new())),
             event_bus: Arc::new(RwLock::new(None)),
             dedup_cache: Arc::new(RwLock::new(HashMap::new())),
+            grouping: Arc::new(RwLock::new(AlertGroupingConfig::default())),
+            alert_groups: Arc::new(RwLock::new(HashMap::new())),
+            maintenance: Arc::new(MaintenanceRegistry::new()),
+            tenant_resolver: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -95,6 +115,17 @@ impl MessageManager {
             }
         }
 
+        let alert_groups: HashMap<String, AlertGroupState> = match store.list_alert_groups() {
+            Ok(groups) => groups
+                .into_iter()
+                .map(|g| (g.fingerprint.clone(), g))
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to load alert groups: {}", e);
+                HashMap::new()
+            }
+        };
+
         // Create persistent channel registry
         let channels = ChannelRegistry::with_storage(data_dir)
             .map_err(|e| Error::Storage(format!("Failed to create channel registry: {}", e)))?;
@@ -105,6 +136,10 @@ impl MessageManager {
             channels: Arc::new(RwLock::new(channels)),
             event_bus: Arc::new(RwLock::new(None)),
             dedup_cache: Arc::new(RwLock::new(HashMap::new())),
+            grouping: Arc::new(RwLock::new(AlertGroupingConfig::default())),
+            alert_groups: Arc::new(RwLock::new(alert_groups)),
+            maintenance: Arc::new(MaintenanceRegistry::new()),
+            tenant_resolver: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -160,6 +195,36 @@ impl MessageManager {
                     let factory = crate::EmailChannelFactory;
                     factory.create(&config).map(Some)
                 }
+                #[cfg(feature = "telegram")]
+                "telegram" => {
+                    let factory = crate::TelegramChannelFactory;
+                    factory.create(&config).map(Some)
+                }
+                #[cfg(feature = "wecom")]
+                "wecom" => {
+                    let factory = crate::WeComChannelFactory;
+                    factory.create(&config).map(Some)
+                }
+                #[cfg(feature = "dingtalk")]
+                "dingtalk" => {
+                    let factory = crate::DingTalkChannelFactory;
+                    factory.create(&config).map(Some)
+                }
+                #[cfg(feature = "slack")]
+                "slack" => {
+                    let factory = crate::SlackChannelFactory;
+                    factory.create(&config).map(Some)
+                }
+                #[cfg(feature = "feishu")]
+                "feishu" => {
+                    let factory = crate::FeishuChannelFactory;
+                    factory.create(&config).map(Some)
+                }
+                #[cfg(feature = "mqtt")]
+                "mqtt" => {
+                    let factory = crate::MqttChannelFactory;
+                    factory.create(&config).map(Some)
+                }
                 _ => {
                     tracing::warn!("Unknown channel type: {}, skipping", stored.channel_type);
                     Ok(None)
@@ -227,6 +292,7 @@ impl MessageManager {
             status: MessageStatus::from_string(&stored.status).unwrap_or(MessageStatus::Active),
             metadata: stored.metadata,
             tags: stored.tags.unwrap_or_default(),
+            tenant_id: stored.tenant_id,
         }
     }
 
@@ -251,6 +317,7 @@ impl MessageManager {
             acknowledged_at: None,
             resolved_at: None,
             acknowledged_by: None,
+            tenant_id: msg.tenant_id.clone(),
         }
     }
 
@@ -259,6 +326,11 @@ impl MessageManager {
         *self.event_bus.write().await = Some(event_bus);
     }
 
+    /// Set the owner lookup for messages created without a tenant.
+    pub async fn set_tenant_resolver(&self, resolver: TenantResolver) {
+        *self.tenant_resolver.write().await = Some(resolver);
+    }
+
     /// Get the channel registry.
     pub async fn channels(&self) -> Arc<RwLock<ChannelRegistry>> {
         self.channels.clone()
@@ -272,11 +344,86 @@ impl MessageManager {
     }
 
     /// Create and send a message.
-    pub async fn create_message(&self, message: Message) -> Result<Message> {
+    ///
+    /// Alerts go through [grouping](crate::grouping): repeats of an open
+    /// alert are collapsed onto it and the existing message is returned, and
+    /// flapping alerts are stored without notifying channels.
+    ///
+    /// Messages created without a tenant are owned by the tenant of the
+    /// running task, or else by the one the [`TenantResolver`] picks.
+    pub async fn create_message(&self, mut message: Message) -> Result<Message> {
+        if message.tenant_id.is_default() {
+            let owner = match TenantId::current() {
+                Some(tenant) => Some(tenant),
+                None => self
+                    .tenant_resolver
+                    .read()
+                    .await
+                    .as_ref()
+                    .and_then(|resolve| resolve(&message)),
+            };
+            if let Some(owner) = owner {
+                message.tenant_id = owner;
+            }
+        }
+
         let id = message.id.clone();
-        let _is_active = message.is_active();
         let severity = message.severity;
 
+        let mut notify = true;
+        let grouped = message.category == "alert" && self.grouping.read().await.enabled;
+        if grouped {
+            match self.group_alert(&message).await {
+                GroupDecision::Notify => {}
+                GroupDecision::Collapse {
+                    message_id,
+                    occurrences,
+                } => {
+                    if let Some(updated) = self
+                        .collapse_into(&message_id, &message, occurrences)
+                        .await?
+                    {
+                        return Ok(updated);
+                    }
+                }
+                GroupDecision::Suppress => {
+                    tracing::info!(
+                        "Alert '{}' from {} is flapping, notifications suppressed",
+                        message.title,
+                        message.source
+                    );
+                    message.tags.push("flapping".to_string());
+                    notify = false;
+                }
+            }
+        }
+
+        // Maintenance: record the alert but don't notify anyone
+        if message.category == "alert" {
+            if let Some(window) = self
+                .maintenance
+                .window_for_message(&message, message.timestamp.timestamp())
+            {
+                tracing::info!(
+                    "Alert '{}' from {} suppressed by maintenance window '{}'",
+                    message.title,
+                    message.source,
+                    window.name
+                );
+                message.tags.push("maintenance".to_string());
+                let metadata = message
+                    .metadata
+                    .get_or_insert_with(|| serde_json::json!({}));
+                if let Some(obj) = metadata.as_object_mut() {
+                    obj.insert(
+                        "maintenance_window".to_string(),
+                        serde_json::json!(window.id),
+                    );
+                }
+                notify = false;
+            }
+        }
+
         // Deduplication: skip if same title+source+severity was sent recently
         let dedup_key = format!(
             "{}|{}|{}",
@@ -284,7 +431,7 @@ impl MessageManager {
             message.source,
             message.severity.as_str()
         );
-        {
+        if !grouped {
             let cache = self.dedup_cache.read().await;
             if let Some(last_sent) = cache.get(&dedup_key) {
                 let elapsed = (chrono::Utc::now() - *last_sent).num_seconds();
@@ -324,6 +471,49 @@ impl MessageManager {
         }
 
         // Send through channels (don't fail if channels fail - message is already stored)
+        if notify {
+            self.send_to_channels(&message).await;
+        }
+
+        // Publish MessageCreated event to EventBus if configured
+        if let Some(event_bus) = self.event_bus.read().await.as_ref() {
+            use neomind_core::NeoMindEvent;
+            let severity_str = format!("{:?}", severity).to_lowercase();
+            let _ = event_bus
+                .publish(NeoMindEvent::MessageCreated {
+                    message_id: id.to_string(),
+                    title: message.title.clone(),
+                    severity: severity_str,
+                    message: message.message.clone(),
+                    timestamp: message.timestamp.timestamp(),
+                })
+                .await;
+            tracing::debug!("Published MessageCreated event for message {}", id);
+        }
+
+        // Update dedup cache
+        {
+            let mut cache = self.dedup_cache.write().await;
+            cache.insert(dedup_key, chrono::Utc::now());
+            // Prune old entries (keep only last 5 minutes)
+            let cutoff = chrono::Utc::now() - chrono::Duration::seconds(DEDUP_INTERVAL_SECS * 5);
+            cache.retain(|_, ts| *ts > cutoff);
+        }
+
+        tracing::info!(
+            "Message created successfully: id={}, title={}, severity={:?}, category={}",
+            id,
+            message.title,
+            severity,
+            message.category
+        );
+
+        Ok(message)
+    }
+
+    /// Send a message through every enabled channel whose filter accepts it.
+    /// Channel failures are logged, not returned.
+    async fn send_to_channels(&self, message: &Message) {
         let channels = self.channels.read().await;
         let channel_names = channels.list_names().await;
         let mut send_results: Vec<(String, std::result::Result<(), String>)> = Vec::new();
@@ -337,7 +527,7 @@ impl MessageManager {
                 if channels.is_enabled_effective(channel_name).await {
                     // Apply filter before sending
                     let filter = channels.get_filter(channel_name).await;
-                    if !filter.matches(&message) {
+                    if !filter.matches(message) {
                         tracing::debug!(
                             "Channel '{}' filter rejected message '{}'",
                             channel_name,
@@ -352,7 +542,7 @@ impl MessageManager {
                         channel.channel_type()
                     );
 
-                    match channel.send(&message).await {
+                    match channel.send(message).await {
                         Ok(()) => {
                             tracing::info!(
                                 "Successfully sent message through channel '{}'",
@@ -382,41 +572,219 @@ impl MessageManager {
                 message.title
             );
         }
+    }
 
-        // Publish MessageCreated event to EventBus if configured
-        if let Some(event_bus) = self.event_bus.read().await.as_ref() {
-            use neomind_core::NeoMindEvent;
-            let severity_str = format!("{:?}", severity).to_lowercase();
-            let _ = event_bus
-                .publish(NeoMindEvent::MessageCreated {
-                    message_id: id.to_string(),
-                    title: message.title.clone(),
-                    severity: severity_str,
-                    message: message.message.clone(),
-                    timestamp: message.timestamp.timestamp(),
+    /// Send a message through specific channels, bypassing channel filters.
+    /// Used for escalations, which target channels explicitly. Disabled and
+    /// unknown channels are skipped.
+    pub async fn send_to_named_channels(&self, names: &[String], message: &Message) {
+        let channels = self.channels.read().await;
+        for name in names {
+            let Some(channel) = channels.get(name).await else {
+                tracing::warn!("Escalation channel '{}' not found", name);
+                continue;
+            };
+            if !channels.is_enabled_effective(name).await {
+                tracing::debug!("Escalation channel '{}' is disabled", name);
+                continue;
+            }
+            if let Err(e) = channel.send(message).await {
+                tracing::warn!("Failed to send message through channel '{}': {}", name, e);
+            }
+        }
+    }
+
+    /// Run an alert through grouping, updating and persisting its group state.
+    async fn group_alert(&self, message: &Message) -> GroupDecision {
+        let config = self.grouping.read().await.clone();
+        let fingerprint = grouping::fingerprint(message);
+
+        let (decision, state) = {
+            let mut groups = self.alert_groups.write().await;
+            let mut group = groups.remove(&fingerprint);
+            let open = match &group {
+                Some(state) => match MessageId::from_string(&state.message_id) {
+                    Ok(id) => self.messages.read().await.get(&id).is_some_and(|m| {
+                        matches!(
+                            m.status,
+                            MessageStatus::Active | MessageStatus::Acknowledged
+                        )
+                    }),
+                    Err(_) => false,
+                },
+                None => false,
+            };
+            let decision = grouping::classify(
+                &config,
+                &mut group,
+                &fingerprint,
+                open,
+                &message.id.to_string(),
+                message.timestamp.timestamp(),
+            );
+            let state = group.expect("classify always leaves a group state");
+            groups.insert(fingerprint, state.clone());
+            (decision, state)
+        };
+
+        if let Some(store) = self.storage.read().await.as_ref() {
+            if let Err(e) = store.save_alert_group_async(state).await {
+                tracing::warn!("Failed to persist alert group: {}", e);
+            }
+        }
+        decision
+    }
+
+    /// Fold a repeated alert into the open message it duplicates. Returns
+    /// `None` if that message no longer exists.
+    async fn collapse_into(
+        &self,
+        message_id: &str,
+        repeat: &Message,
+        occurrences: u64,
+    ) -> Result<Option<Message>> {
+        let Ok(id) = MessageId::from_string(message_id) else {
+            return Ok(None);
+        };
+        let updated = {
+            let mut messages = self.messages.write().await;
+            let Some(existing) = messages.get_mut(&id) else {
+                return Ok(None);
+            };
+            // A repeat can escalate the severity, never lower it
+            existing.severity = existing.severity.max(repeat.severity);
+            let metadata = existing
+                .metadata
+                .get_or_insert_with(|| serde_json::json!({}));
+            if let Some(obj) = metadata.as_object_mut() {
+                obj.insert("occurrences".to_string(), occurrences.into());
+                obj.insert("last_seen".to_string(), repeat.timestamp.timestamp().into());
+                obj.insert("last_message".to_string(), repeat.message.clone().into());
+            }
+            existing.clone()
+        };
+
+        if let Some(store) = self.storage.read().await.as_ref() {
+            store
+                .update_async(Self::message_to_stored(&updated))
+                .await
+                .map_err(|e| Error::Storage(format!("Failed to update message: {}", e)))?;
+        }
+        tracing::debug!(
+            "Collapsed repeat of alert '{}' ({} occurrences)",
+            updated.title,
+            occurrences
+        );
+        Ok(Some(updated))
+    }
+
+    /// Maintenance windows applied to incoming alerts.
+    pub fn maintenance(&self) -> Arc<MaintenanceRegistry> {
+        self.maintenance.clone()
+    }
+
+    /// Get the alert grouping configuration.
+    pub async fn grouping_config(&self) -> AlertGroupingConfig {
+        self.grouping.read().await.clone()
+    }
+
+    /// Replace the alert grouping configuration.
+    pub async fn set_grouping_config(&self, config: AlertGroupingConfig) {
+        *self.grouping.write().await = config;
+    }
+
+    /// Current alert groups, most recently seen first.
+    pub async fn list_alert_groups(&self) -> Vec<AlertGroupState> {
+        let mut groups: Vec<AlertGroupState> =
+            self.alert_groups.read().await.values().cloned().collect();
+        groups.sort_by_key(|g| std::cmp::Reverse(g.last_seen));
+        groups
+    }
+
+    /// Send one digest notification summarizing alert repeats collapsed
+    /// since the previous digest. Returns the digest, or `None` if nothing
+    /// repeated. Digests are delivered to channels but not stored.
+    pub async fn flush_digest(&self) -> Option<Message> {
+        let pending: Vec<AlertGroupState> = {
+            let mut groups = self.alert_groups.write().await;
+            groups
+                .values_mut()
+                .filter(|g| g.pending_digest > 0)
+                .map(|g| {
+                    let snapshot = g.clone();
+                    g.pending_digest = 0;
+                    snapshot
                 })
-                .await;
-            tracing::debug!("Published MessageCreated event for message {}", id);
+                .collect()
+        };
+        if pending.is_empty() {
+            return None;
         }
 
-        // Update dedup cache
+        let mut severity = MessageSeverity::Info;
+        let mut lines = Vec::with_capacity(pending.len());
         {
-            let mut cache = self.dedup_cache.write().await;
-            cache.insert(dedup_key, chrono::Utc::now());
-            // Prune old entries (keep only last 5 minutes)
-            let cutoff = chrono::Utc::now() - chrono::Duration::seconds(DEDUP_INTERVAL_SECS * 5);
-            cache.retain(|_, ts| *ts > cutoff);
+            let messages = self.messages.read().await;
+            for group in &pending {
+                let original = MessageId::from_string(&group.message_id)
+                    .ok()
+                    .and_then(|id| messages.get(&id));
+                let (title, source) = match original {
+                    Some(m) => {
+                        severity = severity.max(m.severity);
+                        (m.title.as_str(), m.source.as_str())
+                    }
+                    None => (group.fingerprint.as_str(), ""),
+                };
+                lines.push(format!(
+                    "- {} ({}): {} new repeats, {} total since {}",
+                    title,
+                    source,
+                    group.pending_digest,
+                    group.occurrences,
+                    chrono::DateTime::from_timestamp(group.first_seen, 0)
+                        .map(|t| t.to_rfc3339())
+                        .unwrap_or_default()
+                ));
+            }
         }
 
-        tracing::info!(
-            "Message created successfully: id={}, title={}, severity={:?}, category={}",
-            id,
-            message.title,
+        if let Some(store) = self.storage.read().await.as_ref() {
+            for mut group in pending.iter().cloned() {
+                group.pending_digest = 0;
+                if let Err(e) = store.save_alert_group_async(group).await {
+                    tracing::warn!("Failed to persist alert group: {}", e);
+                }
+            }
+        }
+
+        let mut digest = Message::new(
+            "alert",
             severity,
-            message.category
+            format!("Alert digest: {} alerts repeating", pending.len()),
+            lines.join("\n"),
+            "alert_digest".to_string(),
         );
-
-        Ok(message)
+        digest.tags.push("digest".to_string());
+        self.send_to_channels(&digest).await;
+        Some(digest)
+    }
+
+    /// Periodically send alert digests, at the configured interval.
+    pub fn start_digest_task(&self) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let interval = manager.grouping.read().await.digest_interval_secs;
+                if interval == 0 {
+                    // Digests disabled; check again later in case that changes
+                    tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+                    continue;
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(interval.max(10))).await;
+                manager.flush_digest().await;
+            }
+        });
     }
 
     /// Create a simple alert message.
@@ -690,15 +1058,21 @@ impl MessageManager {
 
     /// Get message statistics.
     pub async fn get_stats(&self) -> MessageStats {
+        self.get_stats_where(|_| true).await
+    }
+
+    /// Statistics over the messages `include` accepts.
+    pub async fn get_stats_where(&self, include: impl Fn(&Message) -> bool) -> MessageStats {
         let messages = self.messages.read().await;
+        let messages: Vec<&Message> = messages.values().filter(|m| include(m)).collect();
         let total = messages.len();
-        let active = messages.values().filter(|m| m.is_active()).count();
+        let active = messages.iter().filter(|m| m.is_active()).count();
 
         let mut by_category = HashMap::new();
         let mut by_severity = HashMap::new();
         let mut by_status = HashMap::new();
 
-        for message in messages.values() {
+        for message in messages {
             *by_category.entry(message.category.clone()).or_insert(0) += 1;
             *by_severity
                 .entry(message.severity.as_str().to_string())
@@ -964,6 +1338,64 @@ mod tests {
         assert_eq!(*stats.by_category.get("system").unwrap_or(&0), 1);
     }
 
+    #[tokio::test]
+    async fn test_repeated_alert_collapses() {
+        let manager = MessageManager::new();
+        let alert = || {
+            Message::alert(
+                MessageSeverity::Warning,
+                "High Temperature".to_string(),
+                "Temperature above 80°C".to_string(),
+                "rule-1".to_string(),
+            )
+        };
+
+        let first = manager.create_message(alert()).await.unwrap();
+        let repeat = manager.create_message(alert()).await.unwrap();
+        assert_eq!(repeat.id, first.id);
+        assert_eq!(repeat.metadata.unwrap()["occurrences"], 2);
+        assert_eq!(manager.list_messages().await.len(), 1);
+
+        let digest = manager.flush_digest().await.unwrap();
+        assert!(digest.message.contains("High Temperature"));
+        assert!(manager.flush_digest().await.is_none());
+
+        // Once resolved, the next alert is a new message
+        manager.resolve(&first.id).await.unwrap();
+        let refired = manager.create_message(alert()).await.unwrap();
+        assert_ne!(refired.id, first.id);
+    }
+
+    #[tokio::test]
+    async fn test_alert_recorded_during_maintenance() {
+        let manager = MessageManager::new();
+        let now = chrono::Utc::now().timestamp();
+        manager
+            .maintenance()
+            .set_windows(vec![neomind_storage::MaintenanceWindow {
+                id: "retrofit".to_string(),
+                name: "Retrofit".to_string(),
+                start: now - 60,
+                end: Some(now + 3600),
+                devices: vec!["press-1".to_string()],
+                enabled: true,
+                ..Default::default()
+            }]);
+
+        let alert = manager
+            .device_alert(
+                MessageSeverity::Critical,
+                "Overpressure".to_string(),
+                "12 bar".to_string(),
+                "press-1".to_string(),
+            )
+            .await
+            .unwrap();
+        assert!(alert.tags.contains(&"maintenance".to_string()));
+        assert_eq!(alert.metadata.unwrap()["maintenance_window"], "retrofit");
+        assert_eq!(manager.list_messages().await.len(), 1);
+    }
+
     #[test]
     fn test_always_true_rule() {
         let msg = Message::system("Test".to_string(), "Test".to_string());
@@ -996,8 +1428,10 @@ mod tests {
     async fn test_message_filtering_by_source_type() {
         use crate::channels::ChannelFilter;
 
-        let mut filter = ChannelFilter::default();
-        filter.source_types = vec!["device".to_string()];
+        let filter = ChannelFilter {
+            source_types: vec!["device".to_string()],
+            ..Default::default()
+        };
 
         let device_msg = Message::device(
             MessageSeverity::Warning,
@@ -1624,6 +2058,50 @@ mod tests {
         assert_eq!(msg.severity, MessageSeverity::Critical);
     }
 
+    #[cfg(feature = "telegram")]
+    #[tokio::test]
+    async fn test_persisted_bot_channel_is_restored() {
+        use crate::ChannelFactory;
+
+        let id = uuid::Uuid::new_v4();
+        let data_dir = std::env::temp_dir().join(format!("neomind-channels-{}", id));
+        let config = serde_json::json!({
+            "name": "ops-telegram",
+            "token": "123456:test-token",
+            "chat_id": "-1001",
+        });
+        {
+            let manager = MessageManager::with_storage(&data_dir).unwrap();
+            let channel = crate::TelegramChannelFactory.create(&config).unwrap();
+            let registry = manager.channels().await;
+            registry
+                .read()
+                .await
+                .register_with_config("ops-telegram".to_string(), channel, config.clone())
+                .await;
+        }
+
+        // A fresh manager on the same data directory, as after a restart
+        let manager = MessageManager::with_storage(&data_dir).unwrap();
+        let registry = manager.channels().await;
+        assert!(registry.read().await.get("ops-telegram").await.is_none());
+        manager.load_persisted_channels().await;
+
+        let info = registry
+            .read()
+            .await
+            .get_info("ops-telegram")
+            .await
+            .unwrap();
+        assert_eq!(info.channel_type, "telegram");
+        assert!(info.enabled);
+        assert_eq!(info.config, Some(config));
+
+        drop(registry);
+        drop(manager);
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
     #[tokio::test]
     async fn test_rule_alert_creation() {
         let manager = MessageManager::new();
@@ -1,6 +1,8 @@
 //! Message types.
 
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use chrono::{DateTime, Utc};
+use neomind_core::tenant::TenantId;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::Uuid;
 
@@ -246,6 +248,9 @@ pub struct Message {
     /// Associated tags
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Tenant that owns the message
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
 }
 
 impl Message {
@@ -270,6 +275,7 @@ impl Message {
             status: MessageStatus::Active,
             metadata: None,
             tags: Vec::new(),
+            tenant_id: TenantId::default(),
         }
     }
 
@@ -348,6 +354,35 @@ impl Message {
         self
     }
 
+    /// Attach a file, stored under `metadata.attachments`. Email sends it as
+    /// a MIME attachment; webhooks receive it base64-encoded in the payload.
+    pub fn with_attachment(mut self, attachment: MessageAttachment) -> Self {
+        let metadata = self
+            .metadata
+            .get_or_insert_with(|| serde_json::Value::Object(Default::default()));
+        if !metadata.is_object() {
+            *metadata = serde_json::json!({ "value": metadata.take() });
+        }
+        let attachments = metadata
+            .as_object_mut()
+            .expect("metadata is an object")
+            .entry("attachments")
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        if let Some(list) = attachments.as_array_mut() {
+            list.push(serde_json::json!(attachment));
+        }
+        self
+    }
+
+    /// Files attached with [`Self::with_attachment`].
+    pub fn attachments(&self) -> Vec<MessageAttachment> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.get("attachments"))
+            .and_then(|a| serde_json::from_value(a.clone()).ok())
+            .unwrap_or_default()
+    }
+
     /// Acknowledge the message.
     pub fn acknowledge(&mut self) {
         self.status = MessageStatus::Acknowledged;
@@ -369,6 +404,30 @@ impl Message {
     }
 }
 
+/// A file sent along with a message (e.g. a generated report).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageAttachment {
+    pub filename: String,
+    pub content_type: String,
+    /// Base64-encoded content
+    pub content_base64: String,
+}
+
+impl MessageAttachment {
+    pub fn new(filename: impl Into<String>, content_type: impl Into<String>, data: &[u8]) -> Self {
+        Self {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            content_base64: BASE64.encode(data),
+        }
+    }
+
+    /// Decoded content, `None` if the base64 is invalid.
+    pub fn data(&self) -> Option<Vec<u8>> {
+        BASE64.decode(&self.content_base64).ok()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -714,4 +773,17 @@ mod tests {
         assert_eq!(msg.metadata, Some(metadata));
         assert_eq!(msg.tags.len(), 2);
     }
+
+    #[test]
+    fn test_attachments_keep_existing_metadata() {
+        let attachment = MessageAttachment::new("report.pdf", "application/pdf", b"%PDF-1.4");
+        let msg = Message::system("Report".to_string(), "Daily report".to_string())
+            .with_metadata(serde_json::json!({"report_id": "r1"}))
+            .with_attachment(attachment.clone());
+
+        assert_eq!(msg.attachments(), vec![attachment]);
+        assert_eq!(msg.attachments()[0].data().unwrap(), b"%PDF-1.4");
+        assert_eq!(msg.metadata.unwrap()["report_id"], "r1");
+        assert!(Message::system("a".into(), "b".into()).attachments().is_empty());
+    }
 }
@@ -0,0 +1,913 @@
+//! Rule conflict detection and impact analysis.
+//!
+//! [`RuleValidator::analyze_conflicts`] looks at a set of rules together
+//! instead of one at a time and reports:
+//!
+//! - `CONFLICTING_ACTIONS`: conditions can hold together, actions send
+//!   opposite commands to the same device
+//! - `SHADOWED_RULE` / `DUPLICATE_RULES`: whenever a rule fires, another one
+//!   fires too and already runs all of its actions
+//! - `CIRCULAR_TRIGGER_CHAIN`: rules trigger each other through device commands
+//! - `SELF_TRIGGERING_RULE`: a rule commands a device its own condition watches
+//! - `UNSATISFIABLE_CONDITION`: the condition can never be true
+//!
+//! Conditions are modelled as a union of regions, each bounding the values of
+//! the data sources it mentions (numeric intervals, text equality). Operators
+//! that can't be bounded (`contains`, `regex`, ...) leave their source
+//! unconstrained, so the analysis errs towards reporting a possible overlap.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use neomind_core::datasource::DataSourceType;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    ComparisonOperator, CompiledRule, ExecuteTarget, LogicalOperator, RuleAction, RuleCondition,
+    RuleId, RuleTrigger,
+};
+use crate::validator::{RuleValidator, ValidationIssue, ValidationSeverity};
+
+/// Conditions expanding into more regions than this are not analyzed.
+const MAX_REGIONS: usize = 64;
+
+/// Command name tokens that undo each other (`turn_on` / `turn_off`).
+const OPPOSITE_TOKENS: &[(&str, &str)] = &[
+    ("on", "off"),
+    ("open", "close"),
+    ("lock", "unlock"),
+    ("start", "stop"),
+    ("enable", "disable"),
+    ("arm", "disarm"),
+    ("up", "down"),
+    ("activate", "deactivate"),
+    ("increase", "decrease"),
+    ("raise", "lower"),
+];
+
+/// A [`ValidationIssue`] spanning several rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleConflict {
+    /// Rules involved, in the order the message names them.
+    pub rule_ids: Vec<RuleId>,
+    #[serde(flatten)]
+    pub issue: ValidationIssue,
+}
+
+impl RuleConflict {
+    fn new(
+        rule_ids: Vec<RuleId>,
+        code: &str,
+        severity: ValidationSeverity,
+        field: &str,
+        message: String,
+    ) -> Self {
+        Self {
+            rule_ids,
+            issue: ValidationIssue {
+                code: code.to_string(),
+                message,
+                field: Some(field.to_string()),
+                severity,
+            },
+        }
+    }
+
+    /// Whether `id` is one of the rules involved.
+    pub fn involves(&self, id: &RuleId) -> bool {
+        self.rule_ids.contains(id)
+    }
+}
+
+impl RuleValidator {
+    /// Analyze the enabled rules of `rules` for conflicts with each other.
+    pub fn analyze_conflicts(rules: &[CompiledRule]) -> Vec<RuleConflict> {
+        let analyzed: Vec<AnalyzedRule> = rules
+            .iter()
+            .filter(|r| r.enabled)
+            .map(AnalyzedRule::new)
+            .collect();
+
+        let mut conflicts = Vec::new();
+        for AnalyzedRule { rule, regions, .. } in &analyzed {
+            if regions.as_ref().is_some_and(|r| r.is_empty()) {
+                let name = &rule.name;
+                conflicts.push(RuleConflict::new(
+                    vec![rule.id.clone()],
+                    "UNSATISFIABLE_CONDITION",
+                    ValidationSeverity::Warning,
+                    "condition",
+                    format!("Rule '{}' can never fire: its condition is never true", name),
+                ));
+            }
+        }
+        for (i, a) in analyzed.iter().enumerate() {
+            for b in &analyzed[i + 1..] {
+                conflicts.extend(action_conflict(a, b));
+                conflicts.extend(shadowing(a, b));
+            }
+        }
+        conflicts.extend(trigger_cycles(&analyzed));
+        conflicts
+    }
+
+    /// Impact of adding or updating `candidate`: the conflicts it would have
+    /// with the other rules in `rules`.
+    pub fn analyze_rule_impact(
+        candidate: &CompiledRule,
+        rules: &[CompiledRule],
+    ) -> Vec<RuleConflict> {
+        let mut candidate = candidate.clone();
+        candidate.enabled = true;
+        let mut fleet: Vec<CompiledRule> = rules
+            .iter()
+            .filter(|r| r.id != candidate.id)
+            .cloned()
+            .collect();
+        fleet.push(candidate.clone());
+
+        Self::analyze_conflicts(&fleet)
+            .into_iter()
+            .filter(|c| c.involves(&candidate.id))
+            .collect()
+    }
+}
+
+/// A rule with its condition pre-computed into regions.
+struct AnalyzedRule<'a> {
+    rule: &'a CompiledRule,
+    /// `None` when the rule has no condition or it is too complex to model.
+    regions: Option<Vec<Region>>,
+    /// Storage keys of the data sources the condition reads.
+    sources: BTreeSet<String>,
+}
+
+impl<'a> AnalyzedRule<'a> {
+    fn new(rule: &'a CompiledRule) -> Self {
+        let condition = rule.condition.as_ref();
+        Self {
+            rule,
+            regions: condition.and_then(|c| regions(c, false)),
+            sources: condition
+                .map(|c| c.extract_sources().iter().map(|s| s.storage_key()).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Whether the rule reacts to data (rather than a schedule or a user).
+    fn is_data_driven(&self) -> bool {
+        matches!(self.rule.trigger, RuleTrigger::DataChange { .. }) && self.rule.condition.is_some()
+    }
+
+    /// Devices the condition reads.
+    fn watched_devices(&self) -> BTreeSet<String> {
+        self.rule
+            .condition
+            .iter()
+            .flat_map(|c| c.extract_sources())
+            .filter(|s| s.source_type == DataSourceType::Device)
+            .map(|s| s.source_id)
+            .collect()
+    }
+
+    /// Devices the actions command.
+    fn commanded_devices(&self) -> BTreeSet<String> {
+        self.rule
+            .actions
+            .iter()
+            .filter_map(|action| match action {
+                RuleAction::Execute {
+                    target,
+                    target_type: ExecuteTarget::Device,
+                    ..
+                } => Some(target.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether both rules can be true at the same time.
+    fn overlaps(&self, other: &AnalyzedRule<'_>) -> bool {
+        match (&self.regions, &other.regions) {
+            (Some(a), Some(b)) => a
+                .iter()
+                .any(|ra| b.iter().any(|rb| intersect(ra, rb).is_some())),
+            // Unmodelled conditions may overlap with anything
+            _ => true,
+        }
+    }
+
+    /// Whether this rule's condition implies `other`'s.
+    fn implies(&self, other: &AnalyzedRule<'_>) -> bool {
+        match (&self.regions, &other.regions) {
+            (Some(a), Some(b)) => {
+                !a.is_empty() && a.iter().all(|ra| b.iter().any(|rb| region_contains(rb, ra)))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Rules that can be true together but send opposite commands to a device.
+fn action_conflict(a: &AnalyzedRule<'_>, b: &AnalyzedRule<'_>) -> Option<RuleConflict> {
+    if !a.is_data_driven() || !b.is_data_driven() || !a.overlaps(b) {
+        return None;
+    }
+    let (device, detail) = a.rule.actions.iter().find_map(|x| {
+        b.rule
+            .actions
+            .iter()
+            .find_map(|y| contradiction(x, y))
+    })?;
+
+    // Conditions on unrelated sources can hold together, but far less
+    // predictably than ones reading the same data.
+    let shared = !a.sources.is_disjoint(&b.sources);
+    let severity = if shared {
+        ValidationSeverity::Warning
+    } else {
+        ValidationSeverity::Info
+    };
+    Some(RuleConflict::new(
+        vec![a.rule.id.clone(), b.rule.id.clone()],
+        "CONFLICTING_ACTIONS",
+        severity,
+        "actions",
+        format!(
+            "Rules '{}' and '{}' can fire together but send opposite commands to device '{}' ({})",
+            a.rule.name, b.rule.name, device, detail
+        ),
+    ))
+}
+
+/// The device two actions disagree on, and how.
+fn contradiction(a: &RuleAction, b: &RuleAction) -> Option<(String, String)> {
+    let (
+        RuleAction::Execute {
+            target: target_a,
+            target_type: ExecuteTarget::Device,
+            command: command_a,
+            params: params_a,
+        },
+        RuleAction::Execute {
+            target: target_b,
+            target_type: ExecuteTarget::Device,
+            command: command_b,
+            params: params_b,
+        },
+    ) = (a, b)
+    else {
+        return None;
+    };
+    if target_a != target_b {
+        return None;
+    }
+
+    if command_a == command_b {
+        let (Some(pa), Some(pb)) = (params_a.as_object(), params_b.as_object()) else {
+            return None;
+        };
+        let key = pa
+            .iter()
+            .find(|(k, v)| pb.get(*k).is_some_and(|w| w != *v))
+            .map(|(k, _)| k)?;
+        return Some((
+            target_a.clone(),
+            format!("{} with {}={} vs {}={}", command_a, key, pa[key], key, pb[key]),
+        ));
+    }
+
+    opposite_commands(command_a, command_b)
+        .then(|| (target_a.clone(), format!("{} vs {}", command_a, command_b)))
+}
+
+/// `turn_on` / `turn_off`, `open_valve` / `close_valve`, ...
+fn opposite_commands(a: &str, b: &str) -> bool {
+    let tokens = |s: &str| -> Vec<String> {
+        s.split(|c: char| c == '_' || c == '-' || c == '.' || c.is_whitespace())
+            .filter(|t| !t.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (ta, tb) = (tokens(a), tokens(b));
+    if ta.len() != tb.len() {
+        return false;
+    }
+    let differing: Vec<(&str, &str)> = ta
+        .iter()
+        .zip(&tb)
+        .filter(|(x, y)| x != y)
+        .map(|(x, y)| (x.as_str(), y.as_str()))
+        .collect();
+    match differing[..] {
+        [(x, y)] => OPPOSITE_TOKENS
+            .iter()
+            .any(|&(p, q)| (x == p && y == q) || (x == q && y == p)),
+        _ => false,
+    }
+}
+
+/// One rule never fires without the other also firing and running all of
+/// its actions.
+fn shadowing(a: &AnalyzedRule<'_>, b: &AnalyzedRule<'_>) -> Option<RuleConflict> {
+    if !a.is_data_driven() || !b.is_data_driven() {
+        return None;
+    }
+    let a_in_b = a.implies(b) && actions_covered(a.rule, b.rule);
+    let b_in_a = b.implies(a) && actions_covered(b.rule, a.rule);
+    match (a_in_b, b_in_a) {
+        (true, true) => Some(RuleConflict::new(
+            vec![a.rule.id.clone(), b.rule.id.clone()],
+            "DUPLICATE_RULES",
+            ValidationSeverity::Warning,
+            "condition",
+            format!(
+                "Rules '{}' and '{}' are equivalent; their actions run twice",
+                a.rule.name, b.rule.name
+            ),
+        )),
+        (true, false) => Some(shadowed(a.rule, b.rule)),
+        (false, true) => Some(shadowed(b.rule, a.rule)),
+        (false, false) => None,
+    }
+}
+
+fn shadowed(rule: &CompiledRule, by: &CompiledRule) -> RuleConflict {
+    RuleConflict::new(
+        vec![rule.id.clone(), by.id.clone()],
+        "SHADOWED_RULE",
+        ValidationSeverity::Info,
+        "condition",
+        format!(
+            "Rule '{}' is shadowed by '{}', which always fires with it and runs the same actions",
+            rule.name, by.name
+        ),
+    )
+}
+
+/// Every action of `rule` is also an action of `by`.
+fn actions_covered(rule: &CompiledRule, by: &CompiledRule) -> bool {
+    let json = |action: &RuleAction| serde_json::to_value(action).ok();
+    let theirs: Vec<_> = by.actions.iter().map(json).collect();
+    !rule.actions.is_empty() && rule.actions.iter().all(|a| theirs.contains(&json(a)))
+}
+
+/// Chains of data-driven rules that trigger each other through device
+/// commands: rule A commands device D, rule B watches D and commands a
+/// device A watches, and so on.
+fn trigger_cycles(rules: &[AnalyzedRule<'_>]) -> Vec<RuleConflict> {
+    let watched: Vec<BTreeSet<String>> = rules.iter().map(|r| r.watched_devices()).collect();
+    let edges: Vec<Vec<usize>> = rules
+        .iter()
+        .map(|rule| {
+            let commanded = rule.commanded_devices();
+            (0..rules.len())
+                .filter(|&j| rules[j].is_data_driven())
+                .filter(|&j| !watched[j].is_disjoint(&commanded))
+                .collect()
+        })
+        .collect();
+
+    let mut conflicts = Vec::new();
+    for component in strongly_connected(&edges) {
+        if let [i] = component[..] {
+            if edges[i].contains(&i) {
+                let rule = rules[i].rule;
+                conflicts.push(RuleConflict::new(
+                    vec![rule.id.clone()],
+                    "SELF_TRIGGERING_RULE",
+                    ValidationSeverity::Info,
+                    "actions",
+                    format!(
+                        "Rule '{}' commands a device its condition watches and may retrigger",
+                        rule.name
+                    ),
+                ));
+            }
+            continue;
+        }
+        let names: Vec<&str> = component.iter().map(|&i| rules[i].rule.name.as_str()).collect();
+        conflicts.push(RuleConflict::new(
+            component.iter().map(|&i| rules[i].rule.id.clone()).collect(),
+            "CIRCULAR_TRIGGER_CHAIN",
+            ValidationSeverity::Warning,
+            "actions",
+            format!(
+                "Rules {} trigger each other through device commands and may loop: '{}'",
+                names.len(),
+                names.join("' → '")
+            ),
+        ));
+    }
+    conflicts
+}
+
+/// Tarjan's algorithm; components are returned in discovery order with
+/// their members sorted.
+fn strongly_connected(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct State<'e> {
+        edges: &'e [Vec<usize>],
+        index: Vec<Option<usize>>,
+        low: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next: usize,
+        components: Vec<Vec<usize>>,
+    }
+
+    fn visit(s: &mut State<'_>, v: usize) {
+        s.index[v] = Some(s.next);
+        s.low[v] = s.next;
+        s.next += 1;
+        s.stack.push(v);
+        s.on_stack[v] = true;
+        for &w in &s.edges[v] {
+            match s.index[w] {
+                None => {
+                    visit(s, w);
+                    s.low[v] = s.low[v].min(s.low[w]);
+                }
+                Some(index) if s.on_stack[w] => s.low[v] = s.low[v].min(index),
+                Some(_) => {}
+            }
+        }
+        if Some(s.low[v]) == s.index[v] {
+            let mut component = Vec::new();
+            while let Some(w) = s.stack.pop() {
+                s.on_stack[w] = false;
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            component.sort_unstable();
+            s.components.push(component);
+        }
+    }
+
+    let n = edges.len();
+    let mut state = State {
+        edges,
+        index: vec![None; n],
+        low: vec![0; n],
+        on_stack: vec![false; n],
+        stack: Vec::new(),
+        next: 0,
+        components: Vec::new(),
+    };
+    for v in 0..n {
+        if state.index[v].is_none() {
+            visit(&mut state, v);
+        }
+    }
+    state.components
+}
+
+// ---------------------------------------------------------------------------
+// Condition regions
+// ---------------------------------------------------------------------------
+
+/// Values a data source may take within one region.
+#[derive(Debug, Clone, PartialEq)]
+enum Constraint {
+    Number(Interval),
+    Text(String),
+}
+
+/// Conjunction of per-source constraints; sources not listed are free.
+type Region = BTreeMap<String, Constraint>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Interval {
+    lo: f64,
+    lo_open: bool,
+    hi: f64,
+    hi_open: bool,
+}
+
+impl Interval {
+    fn above(lo: f64, open: bool) -> Self {
+        Self {
+            lo,
+            lo_open: open,
+            hi: f64::INFINITY,
+            hi_open: true,
+        }
+    }
+
+    fn below(hi: f64, open: bool) -> Self {
+        Self {
+            lo: f64::NEG_INFINITY,
+            lo_open: true,
+            hi,
+            hi_open: open,
+        }
+    }
+
+    fn closed(lo: f64, hi: f64) -> Self {
+        Self {
+            lo,
+            lo_open: false,
+            hi,
+            hi_open: false,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lo > self.hi || (self.lo == self.hi && (self.lo_open || self.hi_open))
+    }
+
+    fn intersect(&self, other: &Self) -> Option<Self> {
+        let (lo, lo_open) = if self.lo == other.lo {
+            (self.lo, self.lo_open || other.lo_open)
+        } else if self.lo > other.lo {
+            (self.lo, self.lo_open)
+        } else {
+            (other.lo, other.lo_open)
+        };
+        let (hi, hi_open) = if self.hi == other.hi {
+            (self.hi, self.hi_open || other.hi_open)
+        } else if self.hi < other.hi {
+            (self.hi, self.hi_open)
+        } else {
+            (other.hi, other.hi_open)
+        };
+        let result = Self {
+            lo,
+            lo_open,
+            hi,
+            hi_open,
+        };
+        (!result.is_empty()).then_some(result)
+    }
+
+    fn contains(&self, other: &Self) -> bool {
+        let lo_ok = self.lo < other.lo || (self.lo == other.lo && (!self.lo_open || other.lo_open));
+        let hi_ok = self.hi > other.hi || (self.hi == other.hi && (!self.hi_open || other.hi_open));
+        lo_ok && hi_ok
+    }
+}
+
+impl Constraint {
+    fn intersect(&self, other: &Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.intersect(b).map(Self::Number),
+            (Self::Text(a), Self::Text(b)) => (a == b).then(|| self.clone()),
+            // A source compared both as text and as number: can't tell
+            _ => Some(self.clone()),
+        }
+    }
+
+    fn contains(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.contains(b),
+            (Self::Text(a), Self::Text(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+fn intersect(a: &Region, b: &Region) -> Option<Region> {
+    let mut region = a.clone();
+    for (key, constraint) in b {
+        let merged = match region.get(key) {
+            Some(existing) => existing.intersect(constraint)?,
+            None => constraint.clone(),
+        };
+        region.insert(key.clone(), merged);
+    }
+    Some(region)
+}
+
+/// Whether `inner` lies within `outer`.
+fn region_contains(outer: &Region, inner: &Region) -> bool {
+    outer
+        .iter()
+        .all(|(key, c)| inner.get(key).is_some_and(|i| c.contains(i)))
+}
+
+/// Regions where `condition` (or its negation) holds; `None` when the
+/// condition expands into more than [`MAX_REGIONS`] regions.
+fn regions(condition: &RuleCondition, negated: bool) -> Option<Vec<Region>> {
+    let single = |key: String, constraint: Option<Constraint>| {
+        let mut region = Region::new();
+        if let Some(constraint) = constraint {
+            region.insert(key, constraint);
+        }
+        region
+    };
+
+    match condition {
+        RuleCondition::Comparison {
+            source,
+            operator,
+            threshold,
+            threshold_value,
+        } => {
+            let key = source.storage_key();
+            let constraints =
+                comparison(*operator, *threshold, threshold_value.as_deref(), negated);
+            Some(
+                constraints
+                    .into_iter()
+                    .map(|c| single(key.clone(), c))
+                    .collect(),
+            )
+        }
+        RuleCondition::Range { source, min, max } => {
+            let key = source.storage_key();
+            let intervals = if negated {
+                vec![Interval::below(*min, true), Interval::above(*max, true)]
+            } else {
+                vec![Interval::closed(*min, *max)]
+            };
+            Some(
+                intervals
+                    .into_iter()
+                    .filter(|i| !i.is_empty())
+                    .map(|i| single(key.clone(), Some(Constraint::Number(i))))
+                    .collect(),
+            )
+        }
+        RuleCondition::Logical {
+            operator,
+            conditions,
+        } => {
+            // NOT holds when none of its sub-conditions does (NOR)
+            let (all, negate_children) = match (operator, negated) {
+                (LogicalOperator::And, false) => (true, false),
+                (LogicalOperator::Or, false) => (false, false),
+                (LogicalOperator::Not, false) => (true, true),
+                (LogicalOperator::And, true) => (false, true),
+                (LogicalOperator::Or, true) => (true, true),
+                (LogicalOperator::Not, true) => (false, false),
+            };
+            let children = conditions
+                .iter()
+                .map(|c| regions(c, negate_children))
+                .collect::<Option<Vec<_>>>()?;
+            let result = if all {
+                children
+                    .iter()
+                    .try_fold(vec![Region::new()], |acc, child| conjoin(&acc, child))?
+            } else {
+                children.concat()
+            };
+            (result.len() <= MAX_REGIONS).then_some(result)
+        }
+    }
+}
+
+/// Pairwise intersections of two region unions.
+fn conjoin(a: &[Region], b: &[Region]) -> Option<Vec<Region>> {
+    let result: Vec<Region> = a
+        .iter()
+        .flat_map(|ra| b.iter().filter_map(move |rb| intersect(ra, rb)))
+        .collect();
+    (result.len() <= MAX_REGIONS).then_some(result)
+}
+
+/// Constraints (as a union) for one comparison; `None` leaves the source
+/// unconstrained.
+fn comparison(
+    operator: ComparisonOperator,
+    threshold: f64,
+    text: Option<&str>,
+    negated: bool,
+) -> Vec<Option<Constraint>> {
+    use ComparisonOperator::*;
+
+    if operator.is_string_op() {
+        return vec![None];
+    }
+    if let Some(text) = text {
+        return match (operator, negated) {
+            (Equal, false) | (NotEqual, true) => vec![Some(Constraint::Text(text.to_string()))],
+            _ => vec![None],
+        };
+    }
+
+    let operator = match (operator, negated) {
+        (op, false) => op,
+        (GreaterThan, true) => LessEqual,
+        (GreaterEqual, true) => LessThan,
+        (LessThan, true) => GreaterEqual,
+        (LessEqual, true) => GreaterThan,
+        (Equal, true) => NotEqual,
+        (NotEqual, true) => Equal,
+        (op, true) => op,
+    };
+    let number = |interval| Some(Constraint::Number(interval));
+    match operator {
+        GreaterThan => vec![number(Interval::above(threshold, true))],
+        GreaterEqual => vec![number(Interval::above(threshold, false))],
+        LessThan => vec![number(Interval::below(threshold, true))],
+        LessEqual => vec![number(Interval::below(threshold, false))],
+        Equal => vec![number(Interval::closed(threshold, threshold))],
+        NotEqual => vec![
+            number(Interval::below(threshold, true)),
+            number(Interval::above(threshold, true)),
+        ],
+        Contains | StartsWith | EndsWith | Regex => vec![None],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_core::datasource::DataSourceId;
+    use serde_json::json;
+
+    fn rule(name: &str, condition: RuleCondition, actions: Vec<RuleAction>) -> CompiledRule {
+        let mut rule = CompiledRule::new(name);
+        rule.condition = Some(condition);
+        rule.trigger = RuleTrigger::from_condition(&rule.condition);
+        rule.actions = actions;
+        rule.finalize();
+        rule
+    }
+
+    fn above(device: &str, metric: &str, threshold: f64) -> RuleCondition {
+        RuleCondition::Comparison {
+            source: DataSourceId::device(device, metric),
+            operator: ComparisonOperator::GreaterThan,
+            threshold,
+            threshold_value: None,
+        }
+    }
+
+    fn execute(target: &str, command: &str, params: serde_json::Value) -> RuleAction {
+        RuleAction::Execute {
+            target: target.to_string(),
+            target_type: ExecuteTarget::Device,
+            command: command.to_string(),
+            params,
+        }
+    }
+
+    fn codes(conflicts: &[RuleConflict]) -> Vec<&str> {
+        conflicts.iter().map(|c| c.issue.code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_overlapping_rules_with_opposite_commands() {
+        let cool = rule(
+            "Cool",
+            above("sensor1", "temperature", 28.0),
+            vec![execute("fan1", "turn_on", json!({}))],
+        );
+        let quiet = rule(
+            "Quiet",
+            above("sensor1", "temperature", 25.0),
+            vec![execute("fan1", "turn_off", json!({}))],
+        );
+        let conflicts = RuleValidator::analyze_conflicts(&[cool.clone(), quiet]);
+        assert_eq!(codes(&conflicts), vec!["CONFLICTING_ACTIONS"]);
+        assert!(matches!(conflicts[0].issue.severity, ValidationSeverity::Warning));
+
+        // Same command, different parameter values
+        let low = rule(
+            "Low",
+            RuleCondition::Range {
+                source: DataSourceId::device("sensor1", "temperature"),
+                min: 26.0,
+                max: 40.0,
+            },
+            vec![execute("fan1", "set_speed", json!({"speed": 1}))],
+        );
+        let high = rule(
+            "High",
+            above("sensor1", "temperature", 30.0),
+            vec![execute("fan1", "set_speed", json!({"speed": 3}))],
+        );
+        assert_eq!(
+            codes(&RuleValidator::analyze_conflicts(&[low, high])),
+            vec!["CONFLICTING_ACTIONS"]
+        );
+
+        // Disjoint conditions never fire together
+        let heat = rule(
+            "Heat",
+            RuleCondition::Comparison {
+                source: DataSourceId::device("sensor1", "temperature"),
+                operator: ComparisonOperator::LessEqual,
+                threshold: 28.0,
+                threshold_value: None,
+            },
+            vec![execute("fan1", "turn_off", json!({}))],
+        );
+        assert!(RuleValidator::analyze_conflicts(&[cool, heat]).is_empty());
+    }
+
+    #[test]
+    fn test_shadowed_and_duplicate_rules() {
+        let notify = RuleAction::Notify {
+            message: "Hot".into(),
+            severity: Default::default(),
+        };
+        let broad = rule("Broad", above("s1", "temp", 30.0), vec![notify.clone()]);
+        let narrow = rule(
+            "Narrow",
+            RuleCondition::Logical {
+                operator: LogicalOperator::And,
+                conditions: vec![above("s1", "temp", 35.0), above("s1", "humidity", 50.0)],
+            },
+            vec![notify.clone()],
+        );
+        let conflicts = RuleValidator::analyze_conflicts(&[broad.clone(), narrow.clone()]);
+        assert_eq!(codes(&conflicts), vec!["SHADOWED_RULE"]);
+        assert_eq!(conflicts[0].rule_ids, vec![narrow.id.clone(), broad.id.clone()]);
+
+        let copy = rule("Copy", above("s1", "temp", 30.0), vec![notify]);
+        assert_eq!(
+            codes(&RuleValidator::analyze_conflicts(&[broad, copy])),
+            vec!["DUPLICATE_RULES"]
+        );
+    }
+
+    #[test]
+    fn test_circular_trigger_chain() {
+        let a = rule(
+            "Valve follows pump",
+            above("pump1", "pressure", 2.0),
+            vec![execute("valve1", "open", json!({}))],
+        );
+        let b = rule(
+            "Pump follows valve",
+            above("valve1", "flow", 5.0),
+            vec![execute("pump1", "set_power", json!({"power": 80}))],
+        );
+        let c = rule(
+            "Unrelated",
+            above("sensor1", "temperature", 30.0),
+            vec![execute("fan1", "turn_on", json!({}))],
+        );
+        let conflicts = RuleValidator::analyze_conflicts(&[a.clone(), b.clone(), c]);
+        assert_eq!(codes(&conflicts), vec!["CIRCULAR_TRIGGER_CHAIN"]);
+        assert!(conflicts[0].involves(&a.id) && conflicts[0].involves(&b.id));
+
+        let own = rule(
+            "Relay guard",
+            above("relay1", "current", 10.0),
+            vec![execute("relay1", "turn_off", json!({}))],
+        );
+        assert_eq!(
+            codes(&RuleValidator::analyze_conflicts(&[own])),
+            vec!["SELF_TRIGGERING_RULE"]
+        );
+    }
+
+    #[test]
+    fn test_negation_and_unsatisfiable_condition() {
+        // NOT (temp > 30) AND temp > 40 is never true
+        let never = rule(
+            "Never",
+            RuleCondition::Logical {
+                operator: LogicalOperator::And,
+                conditions: vec![
+                    RuleCondition::Logical {
+                        operator: LogicalOperator::Not,
+                        conditions: vec![above("s1", "temp", 30.0)],
+                    },
+                    above("s1", "temp", 40.0),
+                ],
+            },
+            vec![execute("fan1", "turn_on", json!({}))],
+        );
+        let conflicts = RuleValidator::analyze_conflicts(&[never]);
+        assert_eq!(codes(&conflicts), vec!["UNSATISFIABLE_CONDITION"]);
+    }
+
+    #[test]
+    fn test_rule_impact_only_reports_candidate() {
+        let on = rule(
+            "On",
+            above("s1", "temp", 30.0),
+            vec![execute("fan1", "turn_on", json!({}))],
+        );
+        let mut off = rule(
+            "Off",
+            above("s1", "temp", 20.0),
+            vec![execute("fan1", "turn_off", json!({}))],
+        );
+        off.enabled = false;
+        let candidate = rule(
+            "Candidate",
+            above("s2", "temp", 10.0),
+            vec![execute("heater1", "turn_on", json!({}))],
+        );
+
+        // The disabled rule is ignored fleet-wide but analyzed as a candidate
+        assert!(RuleValidator::analyze_conflicts(&[on.clone(), off.clone()]).is_empty());
+        let impact = RuleValidator::analyze_rule_impact(&off, &[on.clone(), off.clone()]);
+        assert_eq!(codes(&impact), vec!["CONFLICTING_ACTIONS"]);
+        assert!(RuleValidator::analyze_rule_impact(&candidate, &[on, off]).is_empty());
+    }
+
+    #[test]
+    fn test_opposite_commands() {
+        assert!(opposite_commands("turn_on", "turn_off"));
+        assert!(opposite_commands("OPEN-valve", "close-valve"));
+        assert!(!opposite_commands("turn_on", "set_speed"));
+        assert!(!opposite_commands("on", "on"));
+    }
+}
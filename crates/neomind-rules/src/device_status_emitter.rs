@@ -14,6 +14,13 @@
 //! On reconnect the emitter pushes 0 once to clear the offline state, then
 //! subsequent ticks skip the push (value unchanged) until the device goes
 //! offline again.
+//!
+//! ## Connectivity changes
+//!
+//! `__online` is event-driven rather than ticked: the connectivity listener
+//! (see [`DeviceStatusEmitter::start_connectivity_listener`]) pushes 1 on
+//! `DeviceOnline` and 0 on `DeviceOffline` for every device a rule subscribes
+//! to, so rules react to the heartbeat watchdog without waiting for a tick.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -22,6 +29,7 @@ use std::time::Duration;
 
 use chrono::Utc;
 use neomind_core::datasource::DataSourceId;
+use neomind_core::NeoMindEvent;
 use neomind_devices::DeviceService;
 use tokio::task::JoinHandle;
 
@@ -35,6 +43,9 @@ const DEFAULT_TICK_INTERVAL: Duration = Duration::from_secs(60);
 /// Virtual metric name emitted by this task.
 pub const VIRTUAL_METRIC_NAME: &str = "__last_seen_age_secs";
 
+/// Event-driven connectivity metric: 1 when the device is online, 0 when offline.
+pub const CONNECTIVITY_METRIC_NAME: &str = "__online";
+
 pub struct DeviceStatusEmitter {
     rule_engine: Arc<RuleEngine>,
     provider: Arc<UnifiedValueProvider>,
@@ -83,6 +94,54 @@ impl DeviceStatusEmitter {
         })
     }
 
+    /// Spawn the connectivity listener. Holds an `Arc<Self>`.
+    ///
+    /// Subscribes to `DeviceOnline` / `DeviceOffline` on the device service's
+    /// event bus and forwards them as `__online` updates. Same handle semantics
+    /// as [`Self::start`].
+    pub fn start_connectivity_listener(self: Arc<Self>) -> JoinHandle<()> {
+        let filter = |event: &NeoMindEvent| {
+            matches!(event, NeoMindEvent::DeviceOnline { .. } | NeoMindEvent::DeviceOffline { .. })
+        };
+        let mut rx = self.device_service.event_bus().subscribe_filtered(filter);
+        tokio::spawn(async move {
+            while let Some((event, _)) = rx.recv().await {
+                match event {
+                    NeoMindEvent::DeviceOnline { device_id, .. } => {
+                        self.emit_connectivity(&device_id, true).await;
+                    }
+                    NeoMindEvent::DeviceOffline { device_id, .. } => {
+                        self.emit_connectivity(&device_id, false).await;
+                    }
+                    _ => {}
+                }
+            }
+        })
+    }
+
+    /// Push a connectivity change to `__online` if any rule subscribes to it
+    /// for this device.
+    pub(crate) async fn emit_connectivity(&self, device_id: &str, online: bool) {
+        let subscribed = self
+            .rule_engine
+            .subscribed_virtual_metric_devices(CONNECTIVITY_METRIC_NAME)
+            .iter()
+            .any(|id| id == device_id);
+        if !subscribed {
+            return;
+        }
+        let value = if online { 1.0 } else { 0.0 };
+        self.provider
+            .update_device_value(device_id, CONNECTIVITY_METRIC_NAME, value)
+            .await;
+        self.rule_engine
+            .on_data_update(
+                &DataSourceId::device(device_id, CONNECTIVITY_METRIC_NAME),
+                RuleValue::Number(value),
+            )
+            .await;
+    }
+
     /// One tick: refresh every subscribed device's `__last_seen_age_secs`.
     ///
     /// Push semantics:
@@ -147,5 +206,6 @@ mod tests {
     #[test]
     fn test_virtual_metric_name_constant() {
         assert_eq!(VIRTUAL_METRIC_NAME, "__last_seen_age_secs");
+        assert_eq!(CONNECTIVITY_METRIC_NAME, "__online");
     }
 }
@@ -3,6 +3,8 @@
 //! The engine evaluates rules **only** when data changes arrive via
 //! [`RuleEngine::on_data_update`]. A subscription index maps each
 //! `DataSourceId` → relevant `RuleId`s so only affected rules are evaluated.
+//! [State machines](crate::state_machine) are indexed the same way and also
+//! advance on posted events and on [`RuleEngine::tick_machines`].
 
 use std::collections::{HashMap, VecDeque};
 use std::panic::{self, AssertUnwindSafe};
@@ -11,7 +13,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use chrono::Utc;
-use neomind_core::datasource::DataSourceId;
+use neomind_core::datasource::{DataSourceId, DataSourceType};
 use parking_lot::RwLock as StdRwLock;
 use tokio::sync::RwLock;
 
@@ -22,7 +24,9 @@ use crate::models::{
     CompiledRule, ExecuteTarget, NotifySeverity, RuleAction, RuleCondition, RuleExecutionResult,
     RuleId, RuleTrigger, RuleValue, ValueProvider,
 };
+use crate::state_machine::{MachineTransition, MachineTrigger, StateMachineAutomation};
 use crate::store::RuleStore;
+use crate::trace::{new_execution_id, ActionTrace, ConditionTrace, ExecutionTrace};
 
 // ---------------------------------------------------------------------------
 // Type aliases for optional dependencies
@@ -31,6 +35,8 @@ use crate::store::RuleStore;
 type OptionMessageManager = Arc<tokio::sync::RwLock<Option<Arc<neomind_messages::MessageManager>>>>;
 type OptionDeviceActionExecutor = Arc<tokio::sync::RwLock<Option<Arc<DeviceActionExecutor>>>>;
 type OptionExtensionActionExecutor = Arc<tokio::sync::RwLock<Option<Arc<ExtensionActionExecutor>>>>;
+type OptionMaintenanceRegistry =
+    Arc<tokio::sync::RwLock<Option<Arc<neomind_messages::MaintenanceRegistry>>>>;
 
 pub type AgentTriggerCallback = Arc<
     dyn Fn(
@@ -116,8 +122,14 @@ pub struct RuleEngine {
     device_action_executor: OptionDeviceActionExecutor,
     extension_action_executor: OptionExtensionActionExecutor,
     agent_trigger: OptionAgentTriggerCallback,
+    /// Maintenance windows during which actions are skipped.
+    maintenance: OptionMaintenanceRegistry,
     /// Persistent rule store.
     rule_store: Arc<StdRwLock<Option<Arc<RuleStore>>>>,
+    /// State machine automations by ID.
+    machines: Arc<RwLock<HashMap<String, StateMachineAutomation>>>,
+    /// Subscription index: DataSourceId → state machine IDs
+    machine_index: Arc<StdRwLock<HashMap<String, Vec<String>>>>,
 }
 
 impl RuleEngine {
@@ -133,7 +145,10 @@ impl RuleEngine {
             device_action_executor: Arc::new(tokio::sync::RwLock::new(None)),
             extension_action_executor: Arc::new(tokio::sync::RwLock::new(None)),
             agent_trigger: Arc::new(tokio::sync::RwLock::new(None)),
+            maintenance: Arc::new(tokio::sync::RwLock::new(None)),
             rule_store: Arc::new(StdRwLock::new(None)),
+            machines: Arc::new(RwLock::new(HashMap::new())),
+            machine_index: Arc::new(StdRwLock::new(HashMap::new())),
         }
     }
 
@@ -159,6 +174,13 @@ impl RuleEngine {
         *self.agent_trigger.write().await = Some(cb);
     }
 
+    pub async fn set_maintenance_registry(
+        &self,
+        registry: Arc<neomind_messages::MaintenanceRegistry>,
+    ) {
+        *self.maintenance.write().await = Some(registry);
+    }
+
     // -- Rule CRUD --
 
     /// Add a compiled rule. Rebuilds subscription index for the rule.
@@ -291,15 +313,19 @@ impl RuleEngine {
             idx.get(&source_key).cloned().unwrap_or_default()
         };
 
-        if affected.is_empty() {
-            return;
-        }
-
         for rule_id in &affected {
-            if let Err(e) = self.evaluate_and_fire(rule_id).await {
+            if let Err(e) = self.evaluate_and_fire(rule_id, source).await {
                 tracing::warn!(rule_id = %rule_id, error = %e, "Rule evaluation failed");
             }
         }
+
+        let machines: Vec<String> = {
+            let idx = self.machine_index.read();
+            idx.get(&source_key).cloned().unwrap_or_default()
+        };
+        for machine_id in &machines {
+            self.advance_machine(machine_id, MachineTrigger::Data(source)).await;
+        }
     }
 
     /// Manually trigger a rule by ID (for Manual / Schedule triggers).
@@ -321,6 +347,8 @@ impl RuleEngine {
                 error: Some("Rule not found".to_string()),
                 duration_ms: 0,
                 triggered_at: now,
+                execution_id: new_execution_id(),
+                trace: None,
             };
         };
 
@@ -335,29 +363,33 @@ impl RuleEngine {
                 error: Some("Rule is in cooldown".to_string()),
                 duration_ms: 0,
                 triggered_at: now,
+                execution_id: new_execution_id(),
+                trace: None,
             };
         }
 
         // Evaluate condition (if any)
+        let mut trace = ExecutionTrace::new(&rule.trigger, None);
         if let Some(ref cond) = rule.condition {
             let eval_result = panic::catch_unwind(AssertUnwindSafe(|| {
-                cond.evaluate(self.value_provider.as_ref())
+                ConditionTrace::evaluate(cond, self.value_provider.as_ref())
             }));
-            match eval_result {
-                Ok(true) => {}
-                Ok(false) | Err(_) => {
-                    // Reset condition_since on failure
-                    self.update_condition_since(id, false).await;
-                    return RuleExecutionResult {
-                        rule_id: id.clone(),
-                        rule_name: rule.name.clone(),
-                        success: false,
-                        actions_executed: Vec::new(),
-                        error: Some("Condition not met".to_string()),
-                        duration_ms: start.elapsed().as_millis() as u64,
-                        triggered_at: now,
-                    };
-                }
+            let matched = eval_result.as_ref().is_ok_and(|c| c.matched);
+            trace.condition = eval_result.ok();
+            if !matched {
+                // Reset condition_since on failure
+                self.update_condition_since(id, false).await;
+                return RuleExecutionResult {
+                    rule_id: id.clone(),
+                    rule_name: rule.name.clone(),
+                    success: false,
+                    actions_executed: Vec::new(),
+                    error: Some("Condition not met".to_string()),
+                    duration_ms: start.elapsed().as_millis() as u64,
+                    triggered_at: now,
+                    execution_id: new_execution_id(),
+                    trace: Some(trace),
+                };
             }
         }
 
@@ -389,6 +421,8 @@ impl RuleEngine {
                             )),
                             duration_ms: start.elapsed().as_millis() as u64,
                             triggered_at: now,
+                            execution_id: new_execution_id(),
+                            trace: Some(trace),
                         };
                     }
                 }
@@ -404,6 +438,8 @@ impl RuleEngine {
                         ),
                         duration_ms: start.elapsed().as_millis() as u64,
                         triggered_at: now,
+                        execution_id: new_execution_id(),
+                        trace: Some(trace),
                     };
                 }
             }
@@ -420,9 +456,17 @@ impl RuleEngine {
                 error: Some("Rule is in cooldown".to_string()),
                 duration_ms: start.elapsed().as_millis() as u64,
                 triggered_at: now,
+                execution_id: new_execution_id(),
+                trace: Some(trace),
             };
         }
 
+        if let Some(window) = self.maintenance_window(&rule).await {
+            let result = Self::suppressed_result(&rule, &window, start, now, trace);
+            self.record_history(result.clone()).await;
+            return result;
+        }
+
         // Extract trigger value for message placeholder substitution
         let (trigger_value, trigger_source) = rule
             .condition
@@ -435,10 +479,14 @@ impl RuleEngine {
         let mut error = None;
 
         for action in &rule.actions {
-            match self
+            let action_start = Instant::now();
+            let outcome = self
                 .execute_action(action, trigger_value, trigger_source.as_deref())
-                .await
-            {
+                .await;
+            trace
+                .actions
+                .push(ActionTrace::new(action, &outcome, action_start.elapsed()));
+            match outcome {
                 Ok(name) => actions_executed.push(name),
                 Err(e) => {
                     tracing::warn!(
@@ -465,6 +513,8 @@ impl RuleEngine {
             error,
             duration_ms: start.elapsed().as_millis() as u64,
             triggered_at: now,
+            execution_id: new_execution_id(),
+            trace: Some(trace),
         };
 
         self.record_history(result.clone()).await;
@@ -472,7 +522,11 @@ impl RuleEngine {
     }
 
     /// Evaluate a single rule and fire actions if conditions are met.
-    async fn evaluate_and_fire(&self, rule_id: &RuleId) -> Result<(), RuleError> {
+    async fn evaluate_and_fire(
+        &self,
+        rule_id: &RuleId,
+        source: &DataSourceId,
+    ) -> Result<(), RuleError> {
         let rule = {
             let rules = self.rules.read().await;
             rules.get(rule_id).cloned()
@@ -503,6 +557,7 @@ impl RuleEngine {
             cond.evaluate(self.value_provider.as_ref())
         }))
         .unwrap_or(false);
+        record_evaluation(condition_met);
 
         if !condition_met {
             // Reset condition_since
@@ -533,6 +588,32 @@ impl RuleEngine {
             return Ok(());
         }
 
+        // Re-evaluate with tracing only now that the rule fires, keeping the
+        // hot path above free of per-update allocations.
+        let mut trace = ExecutionTrace::new(&rule.trigger, Some(source));
+        trace.condition = panic::catch_unwind(AssertUnwindSafe(|| {
+            ConditionTrace::evaluate(cond, self.value_provider.as_ref())
+        }))
+        .ok();
+
+        if let Some(window) = self.maintenance_window(&rule).await {
+            tracing::info!(
+                rule_id = %rule_id,
+                window = %window.name,
+                "Rule actions skipped during maintenance window"
+            );
+            self.record_history(Self::suppressed_result(
+                &rule,
+                &window,
+                Instant::now(),
+                Utc::now(),
+                trace,
+            ))
+            .await;
+            return Ok(());
+        }
+        record_firing();
+
         // Extract trigger value for message placeholder substitution
         let (trigger_value, trigger_source) =
             Self::extract_trigger_value(cond, self.value_provider.as_ref());
@@ -542,10 +623,14 @@ impl RuleEngine {
         let mut actions_executed = Vec::new();
         let mut first_error = None;
         for action in &rule.actions {
-            match self
+            let action_start = Instant::now();
+            let outcome = self
                 .execute_action(action, trigger_value, trigger_source.as_deref())
-                .await
-            {
+                .await;
+            trace
+                .actions
+                .push(ActionTrace::new(action, &outcome, action_start.elapsed()));
+            match outcome {
                 Ok(name) => actions_executed.push(name),
                 Err(e) => {
                     tracing::warn!(
@@ -573,16 +658,79 @@ impl RuleEngine {
             error: first_error,
             duration_ms: start.elapsed().as_millis() as u64,
             triggered_at: Utc::now(),
+            execution_id: new_execution_id(),
+            trace: Some(trace),
         })
         .await;
 
         Ok(())
     }
 
+    // -- Maintenance --
+
+    /// Active maintenance window covering the rule or a device it reads or
+    /// commands.
+    async fn maintenance_window(
+        &self,
+        rule: &CompiledRule,
+    ) -> Option<neomind_messages::maintenance::MaintenanceWindow> {
+        let devices = Self::touched_devices(rule.condition.iter(), rule.actions.iter());
+        let registry = self.maintenance.read().await.clone()?;
+        registry.window_for_rule(&rule.id.to_string(), &devices, Utc::now().timestamp())
+    }
+
+    /// Devices read by `conditions` or commanded by `actions`.
+    fn touched_devices<'a>(
+        conditions: impl Iterator<Item = &'a RuleCondition>,
+        actions: impl Iterator<Item = &'a RuleAction>,
+    ) -> Vec<String> {
+        let mut devices: Vec<String> = conditions
+            .flat_map(|c| c.extract_sources())
+            .filter(|s| s.source_type == DataSourceType::Device)
+            .map(|s| s.source_id)
+            .collect();
+        devices.extend(actions.filter_map(|a| match a {
+            RuleAction::Execute {
+                target,
+                target_type: ExecuteTarget::Device,
+                ..
+            } => Some(target.clone()),
+            _ => None,
+        }));
+        devices
+    }
+
+    fn suppressed_result(
+        rule: &CompiledRule,
+        window: &neomind_messages::maintenance::MaintenanceWindow,
+        start: Instant,
+        triggered_at: chrono::DateTime<Utc>,
+        trace: ExecutionTrace,
+    ) -> RuleExecutionResult {
+        RuleExecutionResult {
+            rule_id: rule.id.clone(),
+            rule_name: rule.name.clone(),
+            success: false,
+            actions_executed: Vec::new(),
+            error: Some(format!(
+                "Actions skipped: maintenance window '{}'",
+                window.name
+            )),
+            duration_ms: start.elapsed().as_millis() as u64,
+            triggered_at,
+            execution_id: new_execution_id(),
+            trace: Some(trace),
+        }
+    }
+
     // -- Action execution --
 
     /// Substitute `{value}` and `{source_id}` placeholders in a message template.
-    fn substitute_placeholders(message: &str, value: Option<f64>, source: Option<&str>) -> String {
+    pub(crate) fn substitute_placeholders(
+        message: &str,
+        value: Option<f64>,
+        source: Option<&str>,
+    ) -> String {
         let mut result = message.to_string();
         if let Some(v) = value {
             result = result.replace("{value}", &format!("{}", v));
@@ -595,7 +743,7 @@ impl RuleEngine {
 
     /// Extract the primary trigger value and source from a condition tree.
     /// Returns the first leaf condition's current value and source key.
-    fn extract_trigger_value(
+    pub(crate) fn extract_trigger_value(
         condition: &RuleCondition,
         provider: &dyn ValueProvider,
     ) -> (Option<f64>, Option<String>) {
@@ -831,6 +979,30 @@ impl RuleEngine {
             .collect()
     }
 
+    /// Find an execution by its ID, in memory first, then in the rule store.
+    pub async fn get_execution(&self, execution_id: &str) -> Option<RuleExecutionResult> {
+        let cached = self
+            .history
+            .read()
+            .await
+            .iter()
+            .rev()
+            .find(|r| r.execution_id == execution_id)
+            .cloned();
+        if cached.is_some() {
+            return cached;
+        }
+
+        let store = self.rule_store.read().clone()?;
+        match store.load_execution(execution_id) {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::warn!("Failed to load rule execution {}: {}", execution_id, e);
+                None
+            }
+        }
+    }
+
     /// List only Schedule-type rules with their cron expressions.
     pub async fn list_schedule_rules(&self) -> Vec<(RuleId, String)> {
         let rules = self.rules.read().await;
@@ -850,6 +1022,218 @@ impl RuleEngine {
             .collect()
     }
 
+    // -- State machines --
+
+    /// Add or replace a state machine. The machine is validated and put in
+    /// its initial state if its current state is unset or undefined.
+    pub async fn add_machine(&self, mut machine: StateMachineAutomation) -> Result<(), RuleError> {
+        machine.validate()?;
+        machine.finalize();
+        self.machines.write().await.insert(machine.id.clone(), machine);
+        self.rebuild_machine_subscriptions();
+        Ok(())
+    }
+
+    /// Remove a state machine.
+    pub async fn remove_machine(&self, id: &str) -> bool {
+        let removed = self.machines.write().await.remove(id).is_some();
+        if removed {
+            self.rebuild_machine_subscriptions();
+        }
+        removed
+    }
+
+    pub async fn get_machine(&self, id: &str) -> Option<StateMachineAutomation> {
+        self.machines.read().await.get(id).cloned()
+    }
+
+    pub async fn list_machines(&self) -> Vec<StateMachineAutomation> {
+        self.machines.read().await.values().cloned().collect()
+    }
+
+    /// Post a named event to a machine. Returns the transition taken, if any.
+    pub async fn fire_machine_event(
+        &self,
+        id: &str,
+        event: &str,
+    ) -> Result<Option<MachineTransition>, RuleError> {
+        if !self.machines.read().await.contains_key(id) {
+            return Err(RuleError::Validation(format!("State machine not found: {}", id)));
+        }
+        Ok(self.advance_machine(id, MachineTrigger::Event(event)).await)
+    }
+
+    /// Put a machine back in its initial state without running any actions.
+    pub async fn reset_machine(&self, id: &str) -> Result<StateMachineAutomation, RuleError> {
+        let machine = {
+            let mut machines = self.machines.write().await;
+            let machine = machines
+                .get_mut(id)
+                .ok_or_else(|| RuleError::Validation(format!("State machine not found: {}", id)))?;
+            let initial = machine.initial.clone();
+            machine.enter(&initial, Utc::now());
+            machine.clone()
+        };
+        self.persist_machine(&machine);
+        Ok(machine)
+    }
+
+    /// Take due time-based transitions. Called periodically by the server,
+    /// so `after` durations are honoured to the tick interval.
+    pub async fn tick_machines(&self) -> Vec<MachineTransition> {
+        let timed: Vec<String> = self
+            .machines
+            .read()
+            .await
+            .values()
+            .filter(|m| m.enabled && m.has_timed_transitions())
+            .map(|m| m.id.clone())
+            .collect();
+        let mut taken = Vec::new();
+        for id in &timed {
+            if let Some(transition) = self.advance_machine(id, MachineTrigger::Tick).await {
+                taken.push(transition);
+            }
+        }
+        taken
+    }
+
+    /// Take the first transition `trigger` fires, then run the exit, transition
+    /// and entry actions.
+    async fn advance_machine(
+        &self,
+        id: &str,
+        trigger: MachineTrigger<'_>,
+    ) -> Option<MachineTransition> {
+        let now = Utc::now();
+        // Decide and move under the write lock so concurrent updates can't
+        // take two transitions out of the same state
+        let (machine, transition, from) = {
+            let mut machines = self.machines.write().await;
+            let machine = machines.get_mut(id)?;
+            if !machine.enabled {
+                return None;
+            }
+            let transition = panic::catch_unwind(AssertUnwindSafe(|| {
+                machine
+                    .next_transition(&trigger, self.value_provider.as_ref(), now)
+                    .cloned()
+            }))
+            .ok()
+            .flatten()?;
+            let from = machine.enter(&transition.to, now);
+            (machine.clone(), transition, from)
+        };
+
+        let mut actions: Vec<&RuleAction> = machine
+            .state(&from)
+            .map(|s| s.on_exit.iter().collect())
+            .unwrap_or_default();
+        actions.extend(&transition.actions);
+        if let Some(state) = machine.state(&transition.to) {
+            actions.extend(&state.on_entry);
+        }
+
+        let mut record = MachineTransition {
+            machine_id: machine.id.clone(),
+            from,
+            to: transition.to.clone(),
+            trigger: trigger.describe(),
+            at: now,
+            actions_executed: Vec::new(),
+            error: None,
+        };
+        let conditions = machine
+            .transitions
+            .iter()
+            .filter_map(|t| t.condition.as_ref());
+        let devices = Self::touched_devices(conditions, actions.iter().copied());
+        let registry = self.maintenance.read().await.clone();
+        let window = registry.and_then(|r| r.window_for_rule(id, &devices, now.timestamp()));
+
+        if let Some(window) = window {
+            // The state still changes so the machine keeps tracking reality
+            record.error = Some(format!("Actions skipped: maintenance window '{}'", window.name));
+        } else {
+            let (trigger_value, condition_source) = transition
+                .condition
+                .as_ref()
+                .map(|c| Self::extract_trigger_value(c, self.value_provider.as_ref()))
+                .unwrap_or((None, None));
+            let trigger_source = match trigger {
+                MachineTrigger::Data(source) => Some(source.storage_key()),
+                _ => condition_source,
+            };
+            for action in actions {
+                match self
+                    .execute_action(action, trigger_value, trigger_source.as_deref())
+                    .await
+                {
+                    Ok(name) => record.actions_executed.push(name),
+                    Err(e) => {
+                        tracing::warn!(
+                            machine_id = %id,
+                            action = ?action,
+                            error = %e,
+                            "State machine action failed"
+                        );
+                        if record.error.is_none() {
+                            record.error = Some(e);
+                        }
+                    }
+                }
+            }
+        }
+
+        tracing::info!(
+            machine_id = %id,
+            from = %record.from,
+            to = %record.to,
+            trigger = %record.trigger,
+            "State machine transition"
+        );
+        let snapshot = {
+            let mut machines = self.machines.write().await;
+            machines.get_mut(id).map(|m| {
+                m.runtime.last_transition = Some(record.clone());
+                m.clone()
+            })
+        };
+        if let Some(machine) = snapshot {
+            self.persist_machine(&machine);
+        }
+        Some(record)
+    }
+
+    fn persist_machine(&self, machine: &StateMachineAutomation) {
+        if let Some(store) = self.rule_store.read().as_ref() {
+            if let Err(e) = store.save_machine(machine) {
+                tracing::warn!(
+                    machine_id = %machine.id,
+                    error = %e,
+                    "Failed to persist state machine"
+                );
+            }
+        }
+    }
+
+    fn rebuild_machine_subscriptions(&self) {
+        // Same contention handling as `rebuild_all_subscriptions`
+        let Ok(machines) = self.machines.try_read() else {
+            tracing::debug!("rebuild_machine_subscriptions: lock contended, keeping index");
+            return;
+        };
+        let mut idx: HashMap<String, Vec<String>> = HashMap::new();
+        for machine in machines.values() {
+            for source in machine.sources() {
+                idx.entry(source.storage_key())
+                    .or_default()
+                    .push(machine.id.clone());
+            }
+        }
+        *self.machine_index.write() = idx;
+    }
+
     // -- Value provider access --
 
     pub fn get_value_provider(&self) -> Arc<dyn ValueProvider> {
@@ -857,6 +1241,32 @@ impl RuleEngine {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Metrics
+// ---------------------------------------------------------------------------
+
+/// Count a data-driven condition evaluation in the shared metrics registry.
+fn record_evaluation(matched: bool) {
+    neomind_core::metrics::global()
+        .counter(
+            "neomind_rule_evaluations_total",
+            "Rule condition evaluations triggered by data updates",
+            &[("result", if matched { "matched" } else { "not_matched" })],
+        )
+        .inc();
+}
+
+/// Count a rule firing (actions about to run).
+fn record_firing() {
+    neomind_core::metrics::global()
+        .counter(
+            "neomind_rule_firings_total",
+            "Rules whose actions were executed",
+            &[],
+        )
+        .inc();
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -919,6 +1329,20 @@ mod tests {
         // Check trigger count
         let r = engine.get_rule(&rule_id).await.unwrap();
         assert_eq!(r.state.trigger_count, 1);
+
+        // The execution is traced and can be looked up by ID
+        let history = engine.get_rule_history(&rule_id).await;
+        let execution = engine
+            .get_execution(&history[0].execution_id)
+            .await
+            .unwrap();
+        let trace = execution.trace.unwrap();
+        assert_eq!(trace.trigger, "data change on device:sensor1:temperature");
+        let condition = trace.condition.unwrap();
+        assert!(condition.matched);
+        assert_eq!(condition.value, Some(RuleValue::Number(75.0)));
+        assert_eq!(trace.actions.len(), 1);
+        assert!(trace.actions[0].success);
     }
 
     #[tokio::test]
@@ -1019,6 +1443,43 @@ mod tests {
         assert_eq!(r.state.trigger_count, 1);
     }
 
+    #[tokio::test]
+    async fn test_maintenance_window_skips_actions() {
+        let provider = Arc::new(InMemoryValueProvider::new());
+        let engine = RuleEngine::new(provider.clone());
+
+        let mut rule = CompiledRule::new("Pump Rule");
+        rule.trigger = RuleTrigger::Manual;
+        rule.actions = vec![RuleAction::Execute {
+            target: "pump-1".into(),
+            target_type: ExecuteTarget::Device,
+            command: "stop".into(),
+            params: serde_json::Value::Null,
+        }];
+        rule.finalize();
+        let rule_id = rule.id.clone();
+        engine.add_rule(rule).await.unwrap();
+
+        let registry = Arc::new(neomind_messages::MaintenanceRegistry::new());
+        let now = Utc::now().timestamp();
+        registry.set_windows(vec![neomind_messages::maintenance::MaintenanceWindow {
+            id: "pump-service".into(),
+            name: "Pump service".into(),
+            start: now - 60,
+            end: Some(now + 3600),
+            devices: vec!["pump-1".into()],
+            enabled: true,
+            ..Default::default()
+        }]);
+        engine.set_maintenance_registry(registry).await;
+
+        let result = engine.execute_rule(&rule_id).await;
+        assert!(!result.success);
+        assert!(result.actions_executed.is_empty());
+        assert!(result.error.unwrap().contains("Pump service"));
+        assert_eq!(engine.get_rule_history(&rule_id).await.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_subscription_index_selective() {
         let provider = Arc::new(InMemoryValueProvider::new());
@@ -1143,6 +1604,69 @@ mod tests {
         assert_eq!(r.state.trigger_count, 1);
     }
 
+    #[tokio::test]
+    async fn test_state_machine_events_and_data() {
+        use crate::state_machine::{MachineState, Transition};
+
+        let provider = Arc::new(InMemoryValueProvider::new());
+        let engine = RuleEngine::new(provider.clone());
+        engine.set_rule_store(crate::store::RuleStore::memory().unwrap());
+
+        let mut machine = StateMachineAutomation::new("Alarm", "disarmed");
+        let mut armed = MachineState::new("armed");
+        armed.on_entry.push(RuleAction::Notify {
+            message: "Armed".into(),
+            severity: NotifySeverity::Info,
+        });
+        machine.states.push(armed);
+        machine.states.push(MachineState::new("triggered"));
+        let mut arm = Transition::new("disarmed", "armed");
+        arm.event = Some("arm".into());
+        let mut door = Transition::new("armed", "triggered");
+        door.condition = Some(RuleCondition::Comparison {
+            source: DataSourceId::device("door1", "open"),
+            operator: ComparisonOperator::Equal,
+            threshold: 1.0,
+            threshold_value: None,
+        });
+        machine.transitions = vec![arm, door];
+        let id = machine.id.clone();
+        engine.add_machine(machine).await.unwrap();
+
+        assert!(engine
+            .fire_machine_event(&id, "disarm")
+            .await
+            .unwrap()
+            .is_none());
+        let taken = engine
+            .fire_machine_event(&id, "arm")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((taken.from.as_str(), taken.to.as_str()), ("disarmed", "armed"));
+        assert_eq!(taken.actions_executed, vec!["NOTIFY: Armed (logged only)"]);
+
+        provider.set_value("device:door1:open", 1.0);
+        engine
+            .on_data_update(
+                &DataSourceId::device("door1", "open"),
+                RuleValue::Number(1.0),
+            )
+            .await;
+        let machine = engine.get_machine(&id).await.unwrap();
+        assert_eq!(machine.runtime.current, "triggered");
+        assert_eq!(machine.runtime.transition_count, 2);
+
+        // The current state is persisted with the definition
+        let store = engine.rule_store.read().clone().unwrap();
+        let stored = store.list_machines().unwrap();
+        assert_eq!(stored[0].runtime.current, "triggered");
+
+        let reset = engine.reset_machine(&id).await.unwrap();
+        assert_eq!(reset.runtime.current, "disarmed");
+        assert!(engine.fire_machine_event("missing", "arm").await.is_err());
+    }
+
     #[test]
     fn test_subscribed_virtual_metric_devices_returns_only_matching() {
         use neomind_core::datasource::DataSourceId;
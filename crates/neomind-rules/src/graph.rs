@@ -0,0 +1,337 @@
+//! Graph representation of state machines for visual editors.
+//!
+//! A [`MachineGraph`] is the node/edge form of a
+//! [`StateMachineAutomation`]: one node per state, one edge per transition
+//! and an optional `*` node for transitions that leave any state. Node
+//! positions live in the machine's `layout`, so
+//! `from_graph(to_graph(m))` gives back the same definition.
+//!
+//! Edges are kept in array order because transitions are tried in
+//! declaration order. Edge IDs are only used to report errors and are
+//! regenerated as `t0`, `t1`, … on export.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use crate::error::RuleError;
+use crate::models::{
+    default_true, deserialize_duration_opt, serialize_duration_opt, RuleAction, RuleCondition,
+};
+use crate::state_machine::{MachineState, StateMachineAutomation, Transition, ANY_STATE};
+
+/// Position of a node on the editor canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct NodePosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// What a node stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphNodeKind {
+    /// The state the machine starts in. Exactly one per graph.
+    Initial,
+    #[default]
+    State,
+    /// The `*` pseudo-state; edges from it leave every state.
+    Any,
+}
+
+/// A node: a state or the `*` pseudo-state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// State name.
+    pub id: String,
+    #[serde(default)]
+    pub kind: GraphNodeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_entry: Vec<RuleAction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_exit: Vec<RuleAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<NodePosition>,
+}
+
+/// An edge: a transition between two nodes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<RuleCondition>,
+    #[serde(
+        serialize_with = "serialize_duration_opt",
+        deserialize_with = "deserialize_duration_opt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub after: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<RuleAction>,
+}
+
+/// Node/edge form of a state machine definition. Runtime state is not part
+/// of the graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineGraph {
+    /// Empty for a machine that doesn't exist yet.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub nodes: Vec<GraphNode>,
+    #[serde(default)]
+    pub edges: Vec<GraphEdge>,
+}
+
+impl StateMachineAutomation {
+    /// Export the definition as a graph.
+    pub fn to_graph(&self) -> MachineGraph {
+        let mut nodes: Vec<GraphNode> = self
+            .states
+            .iter()
+            .map(|state| GraphNode {
+                id: state.name.clone(),
+                kind: if state.name == self.initial {
+                    GraphNodeKind::Initial
+                } else {
+                    GraphNodeKind::State
+                },
+                description: state.description.clone(),
+                on_entry: state.on_entry.clone(),
+                on_exit: state.on_exit.clone(),
+                position: self.layout.get(&state.name).copied(),
+            })
+            .collect();
+        let any_position = self.layout.get(ANY_STATE).copied();
+        if any_position.is_some() || self.transitions.iter().any(|t| t.from == ANY_STATE) {
+            nodes.push(GraphNode {
+                id: ANY_STATE.to_string(),
+                kind: GraphNodeKind::Any,
+                description: None,
+                on_entry: Vec::new(),
+                on_exit: Vec::new(),
+                position: any_position,
+            });
+        }
+
+        let edges = self
+            .transitions
+            .iter()
+            .enumerate()
+            .map(|(i, t)| GraphEdge {
+                id: format!("t{}", i),
+                source: t.from.clone(),
+                target: t.to.clone(),
+                event: t.event.clone(),
+                condition: t.condition.clone(),
+                after: t.after,
+                actions: t.actions.clone(),
+            })
+            .collect();
+
+        MachineGraph {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            enabled: self.enabled,
+            nodes,
+            edges,
+        }
+    }
+
+    /// Build a definition from a graph. Checks the graph shape (unique IDs,
+    /// one initial node, edges between known nodes) and then the machine
+    /// itself with [`validate`](Self::validate).
+    ///
+    /// The result has a fresh runtime; callers replacing an existing machine
+    /// carry its ID, tenant and runtime over.
+    pub fn from_graph(graph: MachineGraph) -> Result<Self, RuleError> {
+        let invalid = |msg: String| Err(RuleError::Validation(msg));
+
+        let mut ids = HashSet::new();
+        for node in &graph.nodes {
+            if !ids.insert(node.id.as_str()) {
+                return invalid(format!("Duplicate node '{}'", node.id));
+            }
+            let is_any = node.id == ANY_STATE;
+            if is_any != (node.kind == GraphNodeKind::Any) {
+                return invalid(format!(
+                    "Node '{}': the '{}' node, and only it, is of kind 'any'",
+                    node.id, ANY_STATE
+                ));
+            }
+            if is_any && !(node.on_entry.is_empty() && node.on_exit.is_empty()) {
+                return invalid(format!("Node '{}' cannot have actions", ANY_STATE));
+            }
+        }
+        let mut initial = graph
+            .nodes
+            .iter()
+            .filter(|n| n.kind == GraphNodeKind::Initial);
+        let Some(initial_node) = initial.next() else {
+            return invalid("Graph has no initial node".to_string());
+        };
+        if let Some(other) = initial.next() {
+            return invalid(format!(
+                "Graph has more than one initial node ('{}', '{}')",
+                initial_node.id, other.id
+            ));
+        }
+
+        let mut edge_ids = HashSet::new();
+        for edge in &graph.edges {
+            if !edge_ids.insert(edge.id.as_str()) {
+                return invalid(format!("Duplicate edge '{}'", edge.id));
+            }
+            // `*` may be used as a source without drawing its node
+            if edge.source != ANY_STATE && !ids.contains(edge.source.as_str()) {
+                return invalid(format!(
+                    "Edge '{}': unknown source node '{}'",
+                    edge.id, edge.source
+                ));
+            }
+            if edge.target == ANY_STATE || !ids.contains(edge.target.as_str()) {
+                return invalid(format!(
+                    "Edge '{}': invalid target node '{}'",
+                    edge.id, edge.target
+                ));
+            }
+        }
+
+        let mut machine = Self::new(graph.name, initial_node.id.clone());
+        if !graph.id.is_empty() {
+            machine.id = graph.id;
+        }
+        machine.description = graph.description;
+        machine.enabled = graph.enabled;
+
+        let mut layout = BTreeMap::new();
+        machine.states = Vec::with_capacity(graph.nodes.len());
+        for node in graph.nodes {
+            if let Some(position) = node.position {
+                layout.insert(node.id.clone(), position);
+            }
+            if node.kind == GraphNodeKind::Any {
+                continue;
+            }
+            machine.states.push(MachineState {
+                name: node.id,
+                description: node.description,
+                on_entry: node.on_entry,
+                on_exit: node.on_exit,
+            });
+        }
+        machine.layout = layout;
+        machine.transitions = graph
+            .edges
+            .into_iter()
+            .map(|edge| Transition {
+                from: edge.source,
+                to: edge.target,
+                event: edge.event,
+                condition: edge.condition,
+                after: edge.after,
+                actions: edge.actions,
+            })
+            .collect();
+
+        machine.validate()?;
+        Ok(machine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NotifySeverity;
+
+    fn graph() -> MachineGraph {
+        let json = serde_json::json!({
+            "name": "Alarm",
+            "nodes": [
+                { "id": "disarmed", "kind": "initial", "position": { "x": 0.0, "y": 0.0 } },
+                {
+                    "id": "armed",
+                    "on_entry": [{ "type": "notify", "message": "Armed" }],
+                    "position": { "x": 200.0, "y": 0.0 }
+                },
+                { "id": "*", "kind": "any", "position": { "x": 100.0, "y": 150.0 } }
+            ],
+            "edges": [
+                { "id": "e1", "source": "disarmed", "target": "armed", "event": "arm" },
+                { "id": "e2", "source": "*", "target": "disarmed", "event": "disarm" },
+                { "id": "e3", "source": "armed", "target": "disarmed", "after": 60000 }
+            ]
+        });
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_graph_round_trip() {
+        let mut machine = StateMachineAutomation::from_graph(graph()).unwrap();
+        assert_eq!(machine.initial, "disarmed");
+        assert_eq!(machine.states.len(), 2);
+        assert_eq!(machine.transitions[1].from, ANY_STATE);
+        assert_eq!(machine.transitions[2].after, Some(Duration::from_secs(60)));
+        assert_eq!(machine.layout[ANY_STATE], NodePosition { x: 100.0, y: 150.0 });
+        machine.states[0].on_exit.push(RuleAction::Notify {
+            message: "Leaving".into(),
+            severity: NotifySeverity::Info,
+        });
+
+        let graph = machine.to_graph();
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.nodes[0].kind, GraphNodeKind::Initial);
+        assert_eq!(graph.edges[2].id, "t2");
+
+        let json = serde_json::to_value(&graph).unwrap();
+        let back =
+            StateMachineAutomation::from_graph(serde_json::from_value(json).unwrap()).unwrap();
+        assert_eq!(back.id, machine.id);
+        assert_eq!(back.layout, machine.layout);
+        assert_eq!(
+            serde_json::to_value(&back.states).unwrap(),
+            serde_json::to_value(&machine.states).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&back.transitions).unwrap(),
+            serde_json::to_value(&machine.transitions).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_graph_validation() {
+        let mut g = graph();
+        g.nodes[1].kind = GraphNodeKind::Initial;
+        assert!(StateMachineAutomation::from_graph(g).is_err());
+
+        let mut g = graph();
+        g.edges[0].target = "missing".into();
+        let err = StateMachineAutomation::from_graph(g).unwrap_err();
+        assert!(err.to_string().contains("Edge 'e1'"));
+
+        let mut g = graph();
+        g.edges[1].target = ANY_STATE.into();
+        assert!(StateMachineAutomation::from_graph(g).is_err());
+
+        let mut g = graph();
+        g.nodes[2].kind = GraphNodeKind::State;
+        assert!(StateMachineAutomation::from_graph(g).is_err());
+
+        // Machine-level checks still apply
+        let mut g = graph();
+        g.edges[0].event = None;
+        assert!(StateMachineAutomation::from_graph(g).is_err());
+    }
+}
@@ -30,28 +30,41 @@
 //! }
 //! ```
 
+pub mod conflicts;
 pub mod device_integration;
 pub mod device_status_emitter;
 pub mod engine;
 pub mod error;
 pub mod extension_integration;
+pub mod graph;
 pub mod models;
 pub mod preview;
+pub mod simulation;
+pub mod state_machine;
 pub mod store;
+pub mod trace;
 pub mod unified_provider;
 pub mod validator;
 
 // Re-exports
+pub use conflicts::RuleConflict;
 pub use device_status_emitter::{
-    DeviceStatusEmitter, VIRTUAL_METRIC_NAME as DEVICE_LAST_SEEN_AGE_METRIC,
+    DeviceStatusEmitter, CONNECTIVITY_METRIC_NAME as DEVICE_ONLINE_METRIC,
+    VIRTUAL_METRIC_NAME as DEVICE_LAST_SEEN_AGE_METRIC,
 };
 pub use engine::{AgentTriggerCallback, InMemoryValueProvider, RuleEngine};
 pub use error::RuleError;
+pub use graph::{GraphEdge, GraphNode, GraphNodeKind, MachineGraph, NodePosition};
 pub use models::{
     ComparisonOperator, CompiledRule, ExecuteTarget, LogicalOperator, NotifySeverity, RuleAction,
     RuleCondition, RuleExecutionResult, RuleId, RuleState, RuleTrigger, RuleValue, ValueProvider,
 };
 pub use preview::to_dsl_preview;
+pub use simulation::{HistoricalSample, SimulatedFiring, SimulationReport};
+pub use state_machine::{
+    MachineRuntime, MachineState, MachineTransition, StateMachineAutomation, Transition,
+};
+pub use trace::{explain_execution, ActionTrace, ConditionTrace, ExecutionTrace};
 pub use unified_provider::UnifiedValueProvider;
 pub use validator::{
     AlertChannelInfo, CommandInfo, DeviceInfo, MetricDataType, MetricInfo, ParameterInfo,
@@ -14,10 +14,13 @@
 
 use chrono::{DateTime, Utc};
 use neomind_core::datasource::DataSourceId;
+use neomind_core::tenant::TenantId;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::time::Duration;
 use uuid::Uuid;
 
+use crate::trace::ExecutionTrace;
+
 // ---------------------------------------------------------------------------
 // IDs
 // ---------------------------------------------------------------------------
@@ -281,29 +284,9 @@ impl RuleCondition {
     /// Evaluate the condition against a value provider.
     pub fn evaluate(&self, provider: &dyn ValueProvider) -> bool {
         match self {
-            RuleCondition::Comparison {
-                source,
-                operator,
-                threshold,
-                threshold_value,
-            } => match provider.get_by_source(source) {
-                Some(rv) => match rv {
-                    RuleValue::Number(v) => operator.evaluate(v, *threshold),
-                    RuleValue::Text(s) => {
-                        let fallback = threshold.to_string();
-                        let t = threshold_value.as_deref().unwrap_or(&fallback);
-                        operator.evaluate_str(&s, t)
-                    }
-                },
-                None => false,
-            },
-            RuleCondition::Range { source, min, max } => match provider.get_by_source(source) {
-                Some(rv) => rv
-                    .as_number()
-                    .map(|v| v >= *min && v <= *max)
-                    .unwrap_or(false),
-                None => false,
-            },
+            RuleCondition::Comparison { source, .. } | RuleCondition::Range { source, .. } => {
+                self.leaf_matches(provider.get_by_source(source).as_ref())
+            }
             RuleCondition::Logical {
                 operator,
                 conditions,
@@ -318,6 +301,35 @@ impl RuleCondition {
             },
         }
     }
+
+    /// Whether a comparison or range condition holds for `value`.
+    ///
+    /// Always `false` for logical conditions.
+    pub(crate) fn leaf_matches(&self, value: Option<&RuleValue>) -> bool {
+        match (self, value) {
+            (
+                RuleCondition::Comparison {
+                    operator,
+                    threshold,
+                    threshold_value,
+                    ..
+                },
+                Some(rv),
+            ) => match rv {
+                RuleValue::Number(v) => operator.evaluate(*v, *threshold),
+                RuleValue::Text(s) => {
+                    let fallback = threshold.to_string();
+                    let t = threshold_value.as_deref().unwrap_or(&fallback);
+                    operator.evaluate_str(s, t)
+                }
+            },
+            (RuleCondition::Range { min, max, .. }, Some(rv)) => rv
+                .as_number()
+                .map(|v| v >= *min && v <= *max)
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -448,6 +460,9 @@ pub struct CompiledRule {
     pub enabled: bool,
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Tenant that owns this rule.
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
 
     pub trigger: RuleTrigger,
     /// None = unconditional (Schedule / Manual).
@@ -498,6 +513,7 @@ impl CompiledRule {
             description: None,
             enabled: true,
             tags: Vec::new(),
+            tenant_id: TenantId::default(),
             trigger: RuleTrigger::Manual,
             condition: None,
             actions: Vec::new(),
@@ -540,13 +556,20 @@ pub struct RuleExecutionResult {
     pub error: Option<String>,
     pub duration_ms: u64,
     pub triggered_at: DateTime<Utc>,
+    /// Unique ID of this execution (empty for entries recorded before
+    /// execution tracing).
+    #[serde(default)]
+    pub execution_id: String,
+    /// Step-level record of the trigger, condition values and action results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<ExecutionTrace>,
 }
 
 // ---------------------------------------------------------------------------
 // Serde helpers
 // ---------------------------------------------------------------------------
 
-fn default_true() -> bool {
+pub(crate) fn default_true() -> bool {
     true
 }
 
@@ -563,7 +586,7 @@ fn deserialize_duration<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Durati
     Ok(Duration::from_millis(ms))
 }
 
-fn serialize_duration_opt<S: serde::Serializer>(
+pub(crate) fn serialize_duration_opt<S: serde::Serializer>(
     d: &Option<Duration>,
     s: S,
 ) -> Result<S::Ok, S::Error> {
@@ -573,7 +596,7 @@ fn serialize_duration_opt<S: serde::Serializer>(
     }
 }
 
-fn deserialize_duration_opt<'de, D: serde::Deserializer<'de>>(
+pub(crate) fn deserialize_duration_opt<'de, D: serde::Deserializer<'de>>(
     d: D,
 ) -> Result<Option<Duration>, D::Error> {
     let opt = Option::<u64>::deserialize(d)?;
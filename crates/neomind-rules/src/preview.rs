@@ -50,7 +50,7 @@ pub fn to_dsl_preview(rule: &CompiledRule) -> String {
     lines.join("\n")
 }
 
-fn render_condition(cond: &RuleCondition) -> String {
+pub(crate) fn render_condition(cond: &RuleCondition) -> String {
     match cond {
         RuleCondition::Comparison {
             source,
@@ -123,7 +123,7 @@ fn render_condition(cond: &RuleCondition) -> String {
     }
 }
 
-fn render_action(action: &RuleAction) -> String {
+pub(crate) fn render_action(action: &RuleAction) -> String {
     match action {
         RuleAction::Notify { message, severity } => {
             let sev = render_severity(*severity);
@@ -178,7 +178,7 @@ fn render_severity(sev: NotifySeverity) -> &'static str {
     }
 }
 
-fn render_duration(dur: std::time::Duration) -> String {
+pub(crate) fn render_duration(dur: std::time::Duration) -> String {
     let secs = dur.as_secs();
     if secs >= 60 && secs.is_multiple_of(60) {
         format!("{}min", secs / 60)
@@ -0,0 +1,380 @@
+//! Rule dry-run — replay stored history through a candidate rule.
+//!
+//! [`RuleEngine::simulate`] pulls the time series referenced by a rule's
+//! condition, merges them into one timestamp-ordered stream and feeds it
+//! through the same evaluation path the live engine uses (latest value per
+//! source, `for_duration`, cooldown). Actions are only described, never
+//! executed, so it is safe to run against LLM-generated rules before they
+//! are saved.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use neomind_core::datasource::DataSourceId;
+use neomind_devices::{MetricValue, TimeSeriesStorage};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::RuleEngine;
+use crate::error::RuleError;
+use crate::models::{
+    CompiledRule, ExecuteTarget, RuleAction, RuleTrigger, RuleValue, ValueProvider,
+};
+
+/// Maximum number of stored points replayed per data source.
+pub const MAX_SIMULATION_POINTS_PER_SOURCE: usize = 10_000;
+
+/// One historical value of a data source.
+#[derive(Debug, Clone)]
+pub struct HistoricalSample {
+    /// Unix timestamp in seconds.
+    pub timestamp: i64,
+    pub source: DataSourceId,
+    pub value: RuleValue,
+}
+
+/// A point in time at which the rule would have fired.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedFiring {
+    /// Unix timestamp in seconds of the sample that triggered the rule.
+    pub timestamp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_source: Option<String>,
+    /// Actions that would have run, in the same format as
+    /// `RuleExecutionResult::actions_executed`.
+    pub actions: Vec<String>,
+}
+
+/// Outcome of a rule simulation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub rule_name: String,
+    pub start: i64,
+    pub end: i64,
+    /// Number of samples the condition was evaluated against.
+    pub samples_evaluated: usize,
+    /// Samples where the condition held.
+    pub condition_matches: usize,
+    /// Matches that were suppressed because the rule was still cooling down.
+    pub suppressed_by_cooldown: usize,
+    pub firings: Vec<SimulatedFiring>,
+    /// Referenced sources that had no data in the range.
+    pub sources_without_data: Vec<String>,
+    /// True when at least one source hit [`MAX_SIMULATION_POINTS_PER_SOURCE`]
+    /// and only the newest points were replayed.
+    pub truncated: bool,
+}
+
+/// Value provider holding the latest replayed value of each source.
+#[derive(Default)]
+struct ReplayValueProvider {
+    values: HashMap<String, RuleValue>,
+}
+
+impl ValueProvider for ReplayValueProvider {
+    fn get_by_source(&self, source: &DataSourceId) -> Option<RuleValue> {
+        self.values.get(&source.storage_key()).cloned()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl RuleEngine {
+    /// Replay stored telemetry in `[start, end]` (seconds) through `rule`
+    /// and report when it would have fired.
+    ///
+    /// Only `data_change` rules can be simulated; schedule and manual rules
+    /// are not driven by data and are rejected.
+    pub async fn simulate(
+        rule: &CompiledRule,
+        telemetry: &TimeSeriesStorage,
+        start: i64,
+        end: i64,
+    ) -> Result<SimulationReport, RuleError> {
+        if !matches!(rule.trigger, RuleTrigger::DataChange { .. }) {
+            return Err(RuleError::Validation(
+                "Only data_change rules can be simulated".to_string(),
+            ));
+        }
+        let Some(condition) = &rule.condition else {
+            return Err(RuleError::Validation(
+                "Rule has no condition to simulate".to_string(),
+            ));
+        };
+        if start > end {
+            return Err(RuleError::Validation(format!(
+                "Invalid range: start {} is after end {}",
+                start, end
+            )));
+        }
+
+        let mut sources = condition.extract_sources();
+        sources.sort_by_key(|s| s.storage_key());
+        sources.dedup_by_key(|s| s.storage_key());
+
+        let mut samples = Vec::new();
+        let mut sources_without_data = Vec::new();
+        let mut truncated = false;
+        for source in sources {
+            let points = telemetry
+                .query_limited(
+                    &source.source_part(),
+                    source.metric_part(),
+                    start,
+                    end,
+                    Some(MAX_SIMULATION_POINTS_PER_SOURCE),
+                )
+                .await
+                .map_err(|e| {
+                    RuleError::Validation(format!(
+                        "Failed to load history for {}: {}",
+                        source.storage_key(),
+                        e
+                    ))
+                })?;
+
+            if points.is_empty() {
+                sources_without_data.push(source.storage_key());
+                continue;
+            }
+            truncated |= points.len() >= MAX_SIMULATION_POINTS_PER_SOURCE;
+
+            samples.extend(points.into_iter().filter_map(|p| {
+                metric_to_rule_value(&p.value).map(|value| HistoricalSample {
+                    timestamp: p.timestamp,
+                    source: source.clone(),
+                    value,
+                })
+            }));
+        }
+
+        let mut report = Self::simulate_samples(rule, samples);
+        report.start = start;
+        report.end = end;
+        report.sources_without_data = sources_without_data;
+        report.truncated = truncated;
+        Ok(report)
+    }
+
+    /// Replay an explicit list of samples through `rule`.
+    ///
+    /// Samples are sorted by timestamp; the condition is evaluated after each
+    /// one against the latest value of every source, mirroring
+    /// `on_data_update`. `for_duration` and `cooldown` are measured on the
+    /// sample timestamps rather than the wall clock.
+    pub fn simulate_samples(
+        rule: &CompiledRule,
+        mut samples: Vec<HistoricalSample>,
+    ) -> SimulationReport {
+        samples.sort_by_key(|s| s.timestamp);
+
+        let mut report = SimulationReport {
+            rule_name: rule.name.clone(),
+            start: samples.first().map(|s| s.timestamp).unwrap_or_default(),
+            end: samples.last().map(|s| s.timestamp).unwrap_or_default(),
+            samples_evaluated: 0,
+            condition_matches: 0,
+            suppressed_by_cooldown: 0,
+            firings: Vec::new(),
+            sources_without_data: Vec::new(),
+            truncated: false,
+        };
+        let Some(condition) = &rule.condition else {
+            return report;
+        };
+
+        let mut provider = ReplayValueProvider::default();
+        let mut condition_since: Option<i64> = None;
+        let mut last_fired: Option<i64> = None;
+
+        for sample in samples {
+            provider
+                .values
+                .insert(sample.source.storage_key(), sample.value);
+            report.samples_evaluated += 1;
+
+            if !condition.evaluate(&provider) {
+                condition_since = None;
+                continue;
+            }
+            report.condition_matches += 1;
+
+            if let Some(dur) = rule.for_duration {
+                let since = *condition_since.get_or_insert(sample.timestamp);
+                if elapsed(since, sample.timestamp) < dur {
+                    continue;
+                }
+            }
+
+            if let Some(last) = last_fired {
+                if elapsed(last, sample.timestamp) < rule.cooldown {
+                    report.suppressed_by_cooldown += 1;
+                    continue;
+                }
+            }
+
+            let (trigger_value, trigger_source) = Self::extract_trigger_value(condition, &provider);
+            let actions = rule
+                .actions
+                .iter()
+                .map(|a| describe_action(a, trigger_value, trigger_source.as_deref()))
+                .collect();
+            report.firings.push(SimulatedFiring {
+                timestamp: sample.timestamp,
+                trigger_value,
+                trigger_source,
+                actions,
+            });
+            last_fired = Some(sample.timestamp);
+            condition_since = None;
+        }
+
+        report
+    }
+}
+
+fn elapsed(from: i64, to: i64) -> Duration {
+    Duration::from_secs(to.saturating_sub(from).max(0) as u64)
+}
+
+/// Map a stored metric value to the value the live engine would have seen.
+fn metric_to_rule_value(value: &MetricValue) -> Option<RuleValue> {
+    match value {
+        MetricValue::Float(v) => Some(RuleValue::Number(*v)),
+        MetricValue::Integer(v) => Some(RuleValue::Number(*v as f64)),
+        MetricValue::Boolean(v) => Some(RuleValue::Number(if *v { 1.0 } else { 0.0 })),
+        MetricValue::String(s) => Some(RuleValue::Text(s.clone())),
+        MetricValue::Array(_) | MetricValue::Binary(_) | MetricValue::Null => None,
+    }
+}
+
+/// Describe an action the way `execute_action` reports it, without running it.
+fn describe_action(action: &RuleAction, value: Option<f64>, source: Option<&str>) -> String {
+    match action {
+        RuleAction::Notify { message, .. } => format!(
+            "NOTIFY: {}",
+            RuleEngine::substitute_placeholders(message, value, source)
+        ),
+        RuleAction::Execute {
+            target,
+            target_type: ExecuteTarget::Device,
+            command,
+            ..
+        } => format!("EXECUTE: {}.{}", target, command),
+        RuleAction::Execute {
+            target,
+            target_type: ExecuteTarget::Extension,
+            command,
+            ..
+        } => format!("EXTENSION: {}.{}", target, command),
+        RuleAction::TriggerAgent { agent_id, .. } => format!("TRIGGER_AGENT: {}", agent_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ComparisonOperator, NotifySeverity, RuleCondition};
+
+    fn temp_rule() -> CompiledRule {
+        let mut rule = CompiledRule::new("High Temp");
+        rule.condition = Some(RuleCondition::Comparison {
+            source: DataSourceId::device("sensor1", "temperature"),
+            operator: ComparisonOperator::GreaterThan,
+            threshold: 30.0,
+            threshold_value: None,
+        });
+        rule.trigger = RuleTrigger::from_condition(&rule.condition);
+        rule.actions = vec![RuleAction::Notify {
+            message: "Temp {value}".into(),
+            severity: NotifySeverity::Warning,
+        }];
+        rule.cooldown = Duration::from_secs(60);
+        rule.finalize();
+        rule
+    }
+
+    fn samples(values: &[(i64, f64)]) -> Vec<HistoricalSample> {
+        values
+            .iter()
+            .map(|(ts, v)| HistoricalSample {
+                timestamp: *ts,
+                source: DataSourceId::device("sensor1", "temperature"),
+                value: RuleValue::Number(*v),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_simulate_respects_cooldown() {
+        let rule = temp_rule();
+        let report = RuleEngine::simulate_samples(
+            &rule,
+            samples(&[(0, 25.0), (10, 31.0), (20, 35.0), (100, 32.0)]),
+        );
+
+        assert_eq!(report.samples_evaluated, 4);
+        assert_eq!(report.condition_matches, 3);
+        assert_eq!(report.suppressed_by_cooldown, 1);
+        let fired: Vec<i64> = report.firings.iter().map(|f| f.timestamp).collect();
+        assert_eq!(fired, vec![10, 100]);
+        assert_eq!(
+            report.firings[0].actions,
+            vec!["NOTIFY: Temp 31".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_simulate_for_duration_uses_sample_time() {
+        let mut rule = temp_rule();
+        rule.for_duration = Some(Duration::from_secs(30));
+        // Dips below threshold at t=20, so the clock restarts at t=30.
+        let report = RuleEngine::simulate_samples(
+            &rule,
+            samples(&[
+                (0, 31.0),
+                (10, 32.0),
+                (20, 29.0),
+                (30, 33.0),
+                (50, 33.0),
+                (60, 34.0),
+            ]),
+        );
+
+        let fired: Vec<i64> = report.firings.iter().map(|f| f.timestamp).collect();
+        assert_eq!(fired, vec![60]);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_from_telemetry() {
+        let telemetry = TimeSeriesStorage::memory().unwrap();
+        for (ts, v) in [(100, 20.0), (200, 40.0), (250, 45.0)] {
+            telemetry
+                .write(
+                    "device:sensor1",
+                    "temperature",
+                    neomind_devices::DataPoint::new(ts, MetricValue::Float(v)),
+                )
+                .await
+                .unwrap();
+        }
+        telemetry.flush().unwrap();
+
+        let rule = temp_rule();
+        let report = RuleEngine::simulate(&rule, &telemetry, 0, 1000)
+            .await
+            .unwrap();
+        assert_eq!(report.firings.len(), 1);
+        assert_eq!(report.firings[0].timestamp, 200);
+        assert!(report.sources_without_data.is_empty());
+
+        let mut manual = temp_rule();
+        manual.trigger = RuleTrigger::Manual;
+        assert!(RuleEngine::simulate(&manual, &telemetry, 0, 1000)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,484 @@
+//! State machine automations.
+//!
+//! Automations that remember where they are — an alarm that is disarmed,
+//! armed or triggered, a pump alternating between running and resting — are
+//! awkward as flat rules. A [`StateMachineAutomation`] has named states with
+//! entry and exit actions, and transitions between them that fire on:
+//!
+//! - a named **event** posted through the API (`arm`, `disarm`),
+//! - a **condition** on metrics, evaluated when one of its sources changes,
+//! - time spent in the current state (**after**), checked on the engine tick.
+//!
+//! Transitions are tried in declaration order and at most one is taken per
+//! trigger. Taking one runs the exit actions of the old state, the
+//! transition's actions and the entry actions of the new state. The current
+//! state is persisted with the definition, so a restart resumes where the
+//! machine left off.
+//!
+//! Definitions are pure JSON like rules; `dsl_preview` is a read-only
+//! rendering.
+
+use chrono::{DateTime, Utc};
+use neomind_core::datasource::DataSourceId;
+use neomind_core::tenant::TenantId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use crate::error::RuleError;
+use crate::graph::NodePosition;
+use crate::models::{
+    default_true, deserialize_duration_opt, serialize_duration_opt, RuleAction, RuleCondition,
+    ValueProvider,
+};
+use crate::preview::{render_action, render_condition, render_duration};
+
+/// `from` value matching every state.
+pub const ANY_STATE: &str = "*";
+
+/// A named state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineState {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Run when the machine enters this state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_entry: Vec<RuleAction>,
+    /// Run when the machine leaves this state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_exit: Vec<RuleAction>,
+}
+
+impl MachineState {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            on_entry: Vec::new(),
+            on_exit: Vec::new(),
+        }
+    }
+}
+
+/// A transition between two states.
+///
+/// An `event` transition is only taken when that event is posted; the
+/// `condition` and `after` are extra guards. Without an event, a transition
+/// with a `condition` is checked when its sources change and one with only
+/// `after` on the engine tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transition {
+    /// Source state, or [`ANY_STATE`].
+    pub from: String,
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<RuleCondition>,
+    /// Minimum time spent in the current state.
+    #[serde(
+        serialize_with = "serialize_duration_opt",
+        deserialize_with = "deserialize_duration_opt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub after: Option<Duration>,
+    /// Run between the exit and entry actions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<RuleAction>,
+}
+
+impl Transition {
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            event: None,
+            condition: None,
+            after: None,
+            actions: Vec::new(),
+        }
+    }
+}
+
+/// What woke the machine up.
+#[derive(Debug, Clone, Copy)]
+pub enum MachineTrigger<'a> {
+    Data(&'a DataSourceId),
+    Event(&'a str),
+    Tick,
+}
+
+impl MachineTrigger<'_> {
+    /// Human-readable description, recorded with the transition.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Data(source) => format!("data change on {}", source.storage_key()),
+            Self::Event(event) => format!("event '{}'", event),
+            Self::Tick => "timer".to_string(),
+        }
+    }
+}
+
+/// A transition that was taken.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineTransition {
+    pub machine_id: String,
+    pub from: String,
+    pub to: String,
+    pub trigger: String,
+    pub at: DateTime<Utc>,
+    #[serde(default)]
+    pub actions_executed: Vec<String>,
+    /// First failed action, or why the actions were skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Runtime state of a machine (persisted).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MachineRuntime {
+    /// Current state; empty until the machine is finalized.
+    #[serde(default)]
+    pub current: String,
+    pub entered_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub transition_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_transition: Option<MachineTransition>,
+}
+
+/// A state machine automation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateMachineAutomation {
+    #[serde(default = "new_machine_id")]
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Tenant that owns this machine.
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
+
+    /// State the machine starts in.
+    pub initial: String,
+    pub states: Vec<MachineState>,
+    #[serde(default)]
+    pub transitions: Vec<Transition>,
+    /// Editor positions by state name (and [`ANY_STATE`]); see
+    /// [`graph`](crate::graph).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub layout: BTreeMap<String, NodePosition>,
+
+    #[serde(default)]
+    pub runtime: MachineRuntime,
+
+    /// Auto-generated human-readable preview (read-only).
+    #[serde(default)]
+    pub dsl_preview: String,
+
+    #[serde(default)]
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub updated_at: DateTime<Utc>,
+}
+
+fn new_machine_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+impl StateMachineAutomation {
+    /// Create a machine with a single initial state.
+    pub fn new(name: impl Into<String>, initial: impl Into<String>) -> Self {
+        let initial = initial.into();
+        let now = Utc::now();
+        Self {
+            id: new_machine_id(),
+            name: name.into(),
+            description: None,
+            enabled: true,
+            tenant_id: TenantId::default(),
+            states: vec![MachineState::new(initial.clone())],
+            initial,
+            transitions: Vec::new(),
+            layout: BTreeMap::new(),
+            runtime: MachineRuntime::default(),
+            dsl_preview: String::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn state(&self, name: &str) -> Option<&MachineState> {
+        self.states.iter().find(|s| s.name == name)
+    }
+
+    /// Check the structure: unique states, known transition endpoints and a
+    /// trigger on every transition.
+    pub fn validate(&self) -> Result<(), RuleError> {
+        let invalid = |msg: String| Err(RuleError::Validation(msg));
+        if self.name.trim().is_empty() {
+            return invalid("State machine name is empty".to_string());
+        }
+        if self.states.is_empty() {
+            return invalid("State machine has no states".to_string());
+        }
+        let mut names = HashSet::new();
+        for state in &self.states {
+            if state.name.trim().is_empty() || state.name == ANY_STATE {
+                return invalid(format!("Invalid state name '{}'", state.name));
+            }
+            if !names.insert(state.name.as_str()) {
+                return invalid(format!("Duplicate state '{}'", state.name));
+            }
+        }
+        if !names.contains(self.initial.as_str()) {
+            return invalid(format!("Initial state '{}' is not defined", self.initial));
+        }
+        for (i, t) in self.transitions.iter().enumerate() {
+            if t.from != ANY_STATE && !names.contains(t.from.as_str()) {
+                return invalid(format!("Transition {}: unknown state '{}'", i, t.from));
+            }
+            if !names.contains(t.to.as_str()) {
+                return invalid(format!("Transition {}: unknown state '{}'", i, t.to));
+            }
+            if t.event.as_deref().is_some_and(|e| e.trim().is_empty()) {
+                return invalid(format!("Transition {}: event name is empty", i));
+            }
+            if t.event.is_none() && t.condition.is_none() && t.after.is_none() {
+                return invalid(format!(
+                    "Transition {} ({} -> {}) needs an event, a condition or 'after'",
+                    i, t.from, t.to
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Put the machine in its initial state if its current state is unset
+    /// or no longer defined, drop positions of removed states and
+    /// regenerate the preview.
+    pub fn finalize(&mut self) {
+        if self.state(&self.runtime.current).is_none() {
+            self.runtime.current = self.initial.clone();
+            self.runtime.entered_at = Some(Utc::now());
+        }
+        let states: HashSet<&str> = self.states.iter().map(|s| s.name.as_str()).collect();
+        self.layout
+            .retain(|node, _| node == ANY_STATE || states.contains(node.as_str()));
+        self.dsl_preview = to_dsl_preview(self);
+        self.updated_at = Utc::now();
+    }
+
+    /// Data sources referenced by transition conditions.
+    pub fn sources(&self) -> Vec<DataSourceId> {
+        let mut sources: Vec<DataSourceId> = Vec::new();
+        for source in self
+            .transitions
+            .iter()
+            .filter_map(|t| t.condition.as_ref())
+            .flat_map(|c| c.extract_sources())
+        {
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+        sources
+    }
+
+    /// Whether any transition waits on time alone.
+    pub fn has_timed_transitions(&self) -> bool {
+        self.transitions
+            .iter()
+            .any(|t| t.event.is_none() && t.after.is_some())
+    }
+
+    /// The first transition out of the current state that `trigger` fires.
+    pub fn next_transition(
+        &self,
+        trigger: &MachineTrigger<'_>,
+        provider: &dyn ValueProvider,
+        now: DateTime<Utc>,
+    ) -> Option<&Transition> {
+        let current = self.runtime.current.as_str();
+        let in_state = self
+            .runtime
+            .entered_at
+            .and_then(|at| now.signed_duration_since(at).to_std().ok())
+            .unwrap_or(Duration::ZERO);
+
+        self.transitions.iter().find(|t| {
+            let leaves = t.from == current || (t.from == ANY_STATE && t.to != current);
+            let fired_by = match (trigger, &t.event) {
+                (MachineTrigger::Event(name), Some(event)) => event == name,
+                (MachineTrigger::Data(_), None) => t.condition.is_some(),
+                (MachineTrigger::Tick, None) => t.after.is_some(),
+                _ => false,
+            };
+            leaves
+                && fired_by
+                && t.after.is_none_or(|after| in_state >= after)
+                && t.condition.as_ref().is_none_or(|c| c.evaluate(provider))
+        })
+    }
+
+    /// Move to `to` and return the state left.
+    pub(crate) fn enter(&mut self, to: &str, at: DateTime<Utc>) -> String {
+        self.runtime.entered_at = Some(at);
+        self.runtime.transition_count += 1;
+        std::mem::replace(&mut self.runtime.current, to.to_string())
+    }
+}
+
+/// Human-readable rendering of a machine. Never parsed back.
+pub fn to_dsl_preview(machine: &StateMachineAutomation) -> String {
+    let mut lines = vec![
+        format!("MACHINE \"{}\"", machine.name),
+        format!("INITIAL {}", machine.initial),
+    ];
+    for state in &machine.states {
+        lines.push(format!("STATE {}", state.name));
+        for action in &state.on_entry {
+            lines.push(format!("    ON ENTRY {}", render_action(action)));
+        }
+        for action in &state.on_exit {
+            lines.push(format!("    ON EXIT {}", render_action(action)));
+        }
+    }
+    for t in &machine.transitions {
+        let mut line = format!("TRANSITION {} -> {}", t.from, t.to);
+        if let Some(event) = &t.event {
+            line.push_str(&format!(" ON EVENT \"{}\"", event));
+        }
+        if let Some(cond) = &t.condition {
+            line.push_str(&format!(" WHEN {}", render_condition(cond)));
+        }
+        if let Some(after) = t.after {
+            line.push_str(&format!(" AFTER {}", render_duration(after)));
+        }
+        lines.push(line);
+        for action in &t.actions {
+            lines.push(format!("    DO {}", render_action(action)));
+        }
+    }
+    lines.push("END".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::InMemoryValueProvider;
+    use crate::models::{ComparisonOperator, NotifySeverity};
+
+    fn alarm() -> StateMachineAutomation {
+        let mut machine = StateMachineAutomation::new("Alarm", "disarmed");
+        machine.states.push(MachineState::new("armed"));
+        machine.states.push(MachineState::new("triggered"));
+
+        let mut arm = Transition::new("disarmed", "armed");
+        arm.event = Some("arm".into());
+        let mut door = Transition::new("armed", "triggered");
+        door.condition = Some(RuleCondition::Comparison {
+            source: DataSourceId::device("door1", "open"),
+            operator: ComparisonOperator::Equal,
+            threshold: 1.0,
+            threshold_value: None,
+        });
+        door.actions.push(RuleAction::Notify {
+            message: "Intrusion".into(),
+            severity: NotifySeverity::Critical,
+        });
+        let mut disarm = Transition::new(ANY_STATE, "disarmed");
+        disarm.event = Some("disarm".into());
+        machine.transitions = vec![arm, door, disarm];
+        machine.finalize();
+        machine
+    }
+
+    #[test]
+    fn test_validate() {
+        let machine = alarm();
+        assert!(machine.validate().is_ok());
+        assert_eq!(machine.runtime.current, "disarmed");
+
+        let mut bad = machine.clone();
+        bad.states.push(MachineState::new("armed"));
+        assert!(bad.validate().is_err());
+
+        let mut bad = machine.clone();
+        bad.transitions.push(Transition::new("armed", "disarmed"));
+        assert!(bad.validate().unwrap_err().to_string().contains("needs an event"));
+
+        let mut bad = machine;
+        bad.initial = "off".into();
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_next_transition() {
+        let provider = InMemoryValueProvider::new();
+        let door = DataSourceId::device("door1", "open");
+        let now = Utc::now();
+        let mut machine = alarm();
+
+        // Door opening while disarmed does nothing
+        provider.set_value("device:door1:open", 1.0);
+        assert!(machine
+            .next_transition(&MachineTrigger::Data(&door), &provider, now)
+            .is_none());
+
+        let t = machine
+            .next_transition(&MachineTrigger::Event("arm"), &provider, now)
+            .unwrap();
+        assert_eq!(t.to, "armed");
+        let to = t.to.clone();
+        assert_eq!(machine.enter(&to, now), "disarmed");
+
+        let t = machine
+            .next_transition(&MachineTrigger::Data(&door), &provider, now)
+            .unwrap();
+        assert_eq!(t.to, "triggered");
+
+        // "*" does not match a transition into the current state
+        machine.enter("disarmed", now);
+        assert!(machine
+            .next_transition(&MachineTrigger::Event("disarm"), &provider, now)
+            .is_none());
+        assert_eq!(machine.runtime.transition_count, 2);
+    }
+
+    #[test]
+    fn test_timed_transition_and_preview() {
+        let provider = InMemoryValueProvider::new();
+        let mut machine = StateMachineAutomation::new("Pump duty cycle", "running");
+        machine.states.push(MachineState::new("resting"));
+        let mut rest = Transition::new("running", "resting");
+        rest.after = Some(Duration::from_secs(1800));
+        machine.transitions.push(rest);
+        machine.finalize();
+        assert!(machine.has_timed_transitions());
+
+        let entered = machine.runtime.entered_at.unwrap();
+        let early = entered + chrono::Duration::minutes(10);
+        assert!(machine
+            .next_transition(&MachineTrigger::Tick, &provider, early)
+            .is_none());
+        let late = entered + chrono::Duration::minutes(30);
+        assert!(machine
+            .next_transition(&MachineTrigger::Tick, &provider, late)
+            .is_some());
+
+        assert!(machine
+            .dsl_preview
+            .contains("TRANSITION running -> resting AFTER 30min"));
+
+        let json = serde_json::to_string(&machine).unwrap();
+        let back: StateMachineAutomation = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.runtime.current, "running");
+        assert_eq!(back.transitions[0].after, Some(Duration::from_secs(1800)));
+    }
+}
@@ -3,6 +3,7 @@
 //! Provides persistent storage for rule definitions and execution history.
 
 use crate::models::{CompiledRule, RuleId};
+use crate::state_machine::StateMachineAutomation;
 use parking_lot::Mutex;
 use redb::{Database, ReadableTable, TableDefinition};
 use std::path::{Path, PathBuf};
@@ -11,6 +12,7 @@ use std::sync::Arc;
 // Table definitions
 const RULES_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("rules");
 const HISTORY_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("rule_history");
+const MACHINES_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("state_machines");
 
 /// Error type for rule storage operations.
 #[derive(Debug, thiserror::Error)]
@@ -208,6 +210,48 @@ impl RuleStore {
         Ok(rules)
     }
 
+    /// Save a state machine, including its current state.
+    pub fn save_machine(&self, machine: &StateMachineAutomation) -> Result<()> {
+        let value = serde_json::to_vec(machine)?;
+
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(MACHINES_TABLE)?;
+            table.insert(machine.id.as_str(), value.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Delete a state machine by ID.
+    pub fn delete_machine(&self, id: &str) -> Result<bool> {
+        let write_txn = self.db.begin_write()?;
+        let existed = {
+            let mut table = write_txn.open_table(MACHINES_TABLE)?;
+            let existed = table.remove(id)?.is_some();
+            existed
+        };
+        write_txn.commit()?;
+        Ok(existed)
+    }
+
+    /// List all state machines.
+    pub fn list_machines(&self) -> Result<Vec<StateMachineAutomation>> {
+        let mut machines = Vec::new();
+
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(MACHINES_TABLE) {
+            Ok(t) => t,
+            Err(_) => return Ok(machines), // Table doesn't exist yet
+        };
+
+        for item in table.iter()? {
+            let (_, value) = item?;
+            machines.push(serde_json::from_slice(value.value())?);
+        }
+        Ok(machines)
+    }
+
     /// Save an execution result to history.
     pub fn save_history(&self, result: &crate::models::RuleExecutionResult) -> Result<()> {
         // Key: timestamp + rule_id for ordering
@@ -254,6 +298,28 @@ impl RuleStore {
         Ok(results)
     }
 
+    /// Load a single execution by its `execution_id`.
+    pub fn load_execution(
+        &self,
+        execution_id: &str,
+    ) -> Result<Option<crate::models::RuleExecutionResult>> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(HISTORY_TABLE) {
+            Ok(t) => t,
+            Err(_) => return Ok(None), // Table doesn't exist yet
+        };
+
+        // Newest first: keys are ordered by timestamp
+        for item in table.iter()?.rev() {
+            let (_, value) = item?;
+            let entry: crate::models::RuleExecutionResult = serde_json::from_slice(value.value())?;
+            if entry.execution_id == execution_id {
+                return Ok(Some(entry));
+            }
+        }
+        Ok(None)
+    }
+
     /// Count history entries since a timestamp (only actual triggers with executed actions).
     pub fn count_history_since(&self, since_timestamp: i64) -> Result<u64> {
         let read_txn = self.db.begin_read()?;
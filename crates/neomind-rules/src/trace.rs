@@ -0,0 +1,342 @@
+//! Step-level rule execution traces.
+//!
+//! Recorded [`RuleExecutionResult`]s carry an [`ExecutionTrace`]: what caused
+//! the evaluation, the value every condition leaf saw and whether it matched,
+//! and the outcome of each action. [`explain_execution`] renders it as plain
+//! text so "why did the AC turn on at 3am?" can be answered from evidence.
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use neomind_core::datasource::DataSourceId;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{
+    LogicalOperator, RuleAction, RuleCondition, RuleExecutionResult, RuleTrigger, RuleValue,
+    ValueProvider,
+};
+use crate::preview::{render_action, render_condition};
+
+/// How a single rule execution unfolded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    /// What caused the evaluation, e.g. `data change on device:sensor1:temperature`.
+    pub trigger: String,
+    /// Condition evaluation tree (`None` for rules without a condition).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<ConditionTrace>,
+    /// Actions that were run, in order.
+    #[serde(default)]
+    pub actions: Vec<ActionTrace>,
+}
+
+impl ExecutionTrace {
+    /// Start a trace for an evaluation caused by `source` changing, or by
+    /// the rule's schedule / a manual run when `source` is `None`.
+    pub fn new(trigger: &RuleTrigger, source: Option<&DataSourceId>) -> Self {
+        let trigger = match (trigger, source) {
+            (_, Some(source)) => format!("data change on {}", source.storage_key()),
+            (RuleTrigger::Schedule { cron }, None) => format!("schedule '{}'", cron),
+            _ => "manual execution".to_string(),
+        };
+        Self {
+            trigger,
+            condition: None,
+            actions: Vec::new(),
+        }
+    }
+}
+
+/// Evaluation of one condition node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionTrace {
+    /// Rendered condition, e.g. `device:sensor1:temperature > 28`.
+    pub expression: String,
+    pub matched: bool,
+    /// Set for AND / OR / NOT nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<LogicalOperator>,
+    /// Storage key of the data source read by a comparison or range.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Value the comparison or range saw (`None` when no value was available).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<RuleValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ConditionTrace>,
+}
+
+impl ConditionTrace {
+    /// Evaluate `condition`, recording every value read.
+    ///
+    /// Unlike [`RuleCondition::evaluate`] this does not short-circuit, so the
+    /// trace shows every branch; `matched` is the same either way.
+    pub fn evaluate(condition: &RuleCondition, provider: &dyn ValueProvider) -> Self {
+        match condition {
+            RuleCondition::Comparison { source, .. } | RuleCondition::Range { source, .. } => {
+                let value = provider.get_by_source(source);
+                Self {
+                    expression: render_condition(condition),
+                    matched: condition.leaf_matches(value.as_ref()),
+                    operator: None,
+                    source: Some(source.storage_key()),
+                    value,
+                    children: Vec::new(),
+                }
+            }
+            RuleCondition::Logical {
+                operator,
+                conditions,
+            } => {
+                let children: Vec<Self> = conditions
+                    .iter()
+                    .map(|c| Self::evaluate(c, provider))
+                    .collect();
+                let matched = match operator {
+                    LogicalOperator::And => children.iter().all(|c| c.matched),
+                    LogicalOperator::Or => children.iter().any(|c| c.matched),
+                    LogicalOperator::Not => !children.iter().any(|c| c.matched),
+                };
+                Self {
+                    expression: render_condition(condition),
+                    matched,
+                    operator: Some(*operator),
+                    source: None,
+                    value: None,
+                    children,
+                }
+            }
+        }
+    }
+}
+
+/// Outcome of one action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionTrace {
+    /// Rendered action, e.g. `EXECUTE device.ac1 turn_on`.
+    pub action: String,
+    pub success: bool,
+    /// Executor output on success, error message on failure.
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+impl ActionTrace {
+    pub fn new(action: &RuleAction, outcome: &Result<String, String>, elapsed: Duration) -> Self {
+        let (success, detail) = match outcome {
+            Ok(output) => (true, output.clone()),
+            Err(e) => (false, e.clone()),
+        };
+        Self {
+            action: render_action(action),
+            success,
+            detail,
+            duration_ms: elapsed.as_millis() as u64,
+        }
+    }
+}
+
+/// Fresh ID for a [`RuleExecutionResult`].
+pub(crate) fn new_execution_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Render an execution as a step-by-step explanation.
+pub fn explain_execution(result: &RuleExecutionResult) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "Rule '{}' ({}) ran at {}",
+        result.rule_name,
+        result.rule_id,
+        result.triggered_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+
+    match &result.trace {
+        Some(trace) => {
+            let _ = writeln!(out, "Trigger: {}", trace.trigger);
+            match &trace.condition {
+                Some(condition) => {
+                    let _ = writeln!(out, "Condition: {}", verdict(condition.matched));
+                    write_condition(&mut out, condition, 1);
+                }
+                None => out.push_str("Condition: none\n"),
+            }
+            if trace.actions.is_empty() {
+                out.push_str("Actions: none run\n");
+            } else {
+                out.push_str("Actions:\n");
+                for (i, action) in trace.actions.iter().enumerate() {
+                    let _ = writeln!(
+                        out,
+                        "  {}. {} -> {}: {} ({} ms)",
+                        i + 1,
+                        action.action,
+                        if action.success { "ok" } else { "failed" },
+                        action.detail,
+                        action.duration_ms
+                    );
+                }
+            }
+        }
+        None => {
+            out.push_str("No step-level trace was recorded for this execution.\n");
+            if !result.actions_executed.is_empty() {
+                out.push_str("Actions:\n");
+                for action in &result.actions_executed {
+                    let _ = writeln!(out, "  - {}", action);
+                }
+            }
+        }
+    }
+
+    match &result.error {
+        None => {
+            let _ = write!(out, "Outcome: succeeded in {} ms", result.duration_ms);
+        }
+        Some(e) => {
+            let _ = write!(out, "Outcome: {} ({} ms)", e, result.duration_ms);
+        }
+    }
+    out
+}
+
+fn verdict(matched: bool) -> &'static str {
+    if matched {
+        "matched"
+    } else {
+        "not matched"
+    }
+}
+
+fn write_condition(out: &mut String, condition: &ConditionTrace, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let matched = verdict(condition.matched);
+    match condition.operator {
+        Some(operator) => {
+            let label = match operator {
+                LogicalOperator::And => "ALL of",
+                LogicalOperator::Or => "ANY of",
+                LogicalOperator::Not => "NONE of",
+            };
+            let _ = writeln!(out, "{}- {}: {}", indent, label, matched);
+            for child in &condition.children {
+                write_condition(out, child, depth + 1);
+            }
+        }
+        None => {
+            let value = match &condition.value {
+                Some(RuleValue::Number(v)) => v.to_string(),
+                Some(RuleValue::Text(s)) => format!("'{}'", s),
+                None => "no value".to_string(),
+            };
+            let _ = writeln!(
+                out,
+                "{}- {} (value: {}): {}",
+                indent, condition.expression, value, matched
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::InMemoryValueProvider;
+    use crate::models::{ComparisonOperator, RuleId};
+
+    #[test]
+    fn test_condition_trace_records_values_and_branches() {
+        let provider = InMemoryValueProvider::new();
+        provider.set_value("device:sensor1:temperature", 29.5);
+        provider.set_string_value("device:door1:state", "open");
+
+        let condition = RuleCondition::Logical {
+            operator: LogicalOperator::Or,
+            conditions: vec![
+                RuleCondition::Comparison {
+                    source: DataSourceId::device("sensor1", "temperature"),
+                    operator: ComparisonOperator::GreaterThan,
+                    threshold: 28.0,
+                    threshold_value: None,
+                },
+                RuleCondition::Comparison {
+                    source: DataSourceId::device("door1", "state"),
+                    operator: ComparisonOperator::Equal,
+                    threshold: 0.0,
+                    threshold_value: Some("closed".to_string()),
+                },
+                RuleCondition::Range {
+                    source: DataSourceId::device("sensor1", "humidity"),
+                    min: 0.0,
+                    max: 100.0,
+                },
+            ],
+        };
+
+        let trace = ConditionTrace::evaluate(&condition, &provider);
+        assert!(trace.matched);
+        assert_eq!(trace.matched, condition.evaluate(&provider));
+        assert_eq!(trace.operator, Some(LogicalOperator::Or));
+        let matched: Vec<bool> = trace.children.iter().map(|c| c.matched).collect();
+        assert_eq!(matched, vec![true, false, false]);
+        assert_eq!(trace.children[0].value, Some(RuleValue::Number(29.5)));
+        assert_eq!(trace.children[1].value, Some(RuleValue::Text("open".into())));
+        assert_eq!(trace.children[2].value, None);
+    }
+
+    #[test]
+    fn test_explain_execution() {
+        let provider = InMemoryValueProvider::new();
+        provider.set_value("device:sensor1:temperature", 29.5);
+        let condition = RuleCondition::Comparison {
+            source: DataSourceId::device("sensor1", "temperature"),
+            operator: ComparisonOperator::GreaterThan,
+            threshold: 28.0,
+            threshold_value: None,
+        };
+        let action = RuleAction::Execute {
+            target: "ac1".to_string(),
+            target_type: crate::models::ExecuteTarget::Device,
+            command: "turn_on".to_string(),
+            params: serde_json::json!({}),
+        };
+
+        let source = DataSourceId::device("sensor1", "temperature");
+        let trigger = RuleTrigger::DataChange {
+            sources: vec![source.clone()],
+        };
+        let mut trace = ExecutionTrace::new(&trigger, Some(&source));
+        trace.condition = Some(ConditionTrace::evaluate(&condition, &provider));
+        trace.actions.push(ActionTrace::new(
+            &action,
+            &Ok("EXECUTE: ac1.turn_on".to_string()),
+            Duration::from_millis(12),
+        ));
+        let result = RuleExecutionResult {
+            rule_id: RuleId::new(),
+            rule_name: "Cool down".to_string(),
+            success: true,
+            actions_executed: vec!["EXECUTE: ac1.turn_on".to_string()],
+            error: None,
+            duration_ms: 15,
+            triggered_at: chrono::Utc::now(),
+            execution_id: new_execution_id(),
+            trace: Some(trace),
+        };
+
+        let text = explain_execution(&result);
+        assert!(text.contains("Trigger: data change on device:sensor1:temperature"));
+        assert!(text.contains("device:sensor1:temperature > 28 (value: 29.5): matched"));
+        assert!(text.contains("1. EXECUTE device.ac1 turn_on -> ok: EXECUTE: ac1.turn_on"));
+        assert!(text.ends_with("Outcome: succeeded in 15 ms"));
+
+        // Entries recorded before tracing still explain what they can
+        let untraced = RuleExecutionResult {
+            trace: None,
+            ..result
+        };
+        assert!(explain_execution(&untraced).contains("No step-level trace"));
+    }
+}
@@ -3,13 +3,14 @@
 //! Provides validation functions to check that referenced resources
 //! (devices, metrics, extensions) exist and are properly configured.
 
+use crate::device_status_emitter::{CONNECTIVITY_METRIC_NAME, VIRTUAL_METRIC_NAME};
 use crate::models::{CompiledRule, ComparisonOperator, ExecuteTarget, RuleAction, RuleCondition};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Virtual metrics emitted by the rule engine itself (not by devices).
 /// These bypass the strict device-metric-existence check in [`validate_simple_condition`].
-pub const VIRTUAL_METRICS: &[&str] = &["__last_seen_age_secs"];
+pub const VIRTUAL_METRICS: &[&str] = &[VIRTUAL_METRIC_NAME, CONNECTIVITY_METRIC_NAME];
 
 /// Result type for validation operations.
 pub type ValidationResult<T> = Result<T, ValidationError>;
@@ -302,13 +303,15 @@ impl RuleValidator {
     /// are emitted by the rule engine itself, not by the device. The device must
     /// still exist (otherwise [`ValidationError::DeviceNotFound`] is returned).
     ///
-    /// Operators other than `>` / `>=` (and the equality forms `==` / `!=`) are
-    /// semantically meaningless for a monotonically-increasing age counter, so a
-    /// [`ValidationIssue`] with code `VIRTUAL_METRIC_BAD_OPERATOR` is emitted as a
-    /// warning. The rule remains valid — the user may have a legitimate reason.
+    /// For `__last_seen_age_secs`, operators other than `>` / `>=` (and the
+    /// equality forms `==` / `!=`) are semantically meaningless for a
+    /// monotonically-increasing age counter, so a [`ValidationIssue`] with code
+    /// `VIRTUAL_METRIC_BAD_OPERATOR` is emitted as a warning. The rule remains
+    /// valid — the user may have a legitimate reason. `__online` is a 0/1 flag
+    /// and accepts any numeric comparison.
     fn validate_virtual_metric_condition(
         device_id: &str,
-        _metric: &str,
+        metric: &str,
         operator: &ComparisonOperator,
         _threshold: &f64,
         context: &ValidationContext,
@@ -320,15 +323,16 @@ impl RuleValidator {
         }
 
         let mut issues = Vec::new();
-        let bad_op = matches!(
-            operator,
-            ComparisonOperator::LessThan
-                | ComparisonOperator::LessEqual
-                | ComparisonOperator::Contains
-                | ComparisonOperator::StartsWith
-                | ComparisonOperator::EndsWith
-                | ComparisonOperator::Regex
-        );
+        let bad_op = metric == VIRTUAL_METRIC_NAME
+            && matches!(
+                operator,
+                ComparisonOperator::LessThan
+                    | ComparisonOperator::LessEqual
+                    | ComparisonOperator::Contains
+                    | ComparisonOperator::StartsWith
+                    | ComparisonOperator::EndsWith
+                    | ComparisonOperator::Regex
+            );
         if bad_op {
             issues.push(ValidationIssue {
                 code: "VIRTUAL_METRIC_BAD_OPERATOR".to_string(),
@@ -501,7 +505,10 @@ impl RuleValidator {
     /// would fire every 60 seconds. This guard requires cooldown >= 60s so
     /// that at most one firing per emitter tick is possible.
     ///
-    /// Returns `Ok(())` if the rule does not use any virtual metric, or if the
+    /// The event-driven `__online` metric only changes on connectivity
+    /// transitions and is exempt.
+    ///
+    /// Returns `Ok(())` if the rule does not use a ticked virtual metric, or if the
     /// configured cooldown meets the floor. Returns `Err(message)` otherwise.
     pub fn validate_virtual_metric_cooldown(rule: &CompiledRule) -> Result<(), String> {
         let uses_virtual = rule
@@ -510,7 +517,7 @@ impl RuleValidator {
             .map(|c| {
                 c.extract_sources().iter().any(|s| {
                     s.source_type == neomind_core::datasource::DataSourceType::Device
-                        && VIRTUAL_METRICS.contains(&s.field_path.as_str())
+                        && s.field_path == VIRTUAL_METRIC_NAME
                 })
             })
             .unwrap_or(false);
@@ -523,7 +530,7 @@ impl RuleValidator {
             return Err(format!(
                 "Rules using virtual metrics ({}) must set cooldown >= {} ms (got {} ms). \
                  Without a cooldown the rule would fire every 60 seconds.",
-                VIRTUAL_METRICS.join(", "),
+                VIRTUAL_METRIC_NAME,
                 Self::MIN_VIRTUAL_METRIC_COOLDOWN.as_millis(),
                 rule.cooldown.as_millis()
             ));
@@ -742,6 +749,28 @@ mod tests {
         assert!(matches!(issues[0].severity, ValidationSeverity::Warning));
     }
 
+    /// 5. Connectivity metric `__online` accepts `<` / `==` without warnings
+    ///    and is exempt from the cooldown floor (event-driven, not ticked).
+    #[test]
+    fn test_connectivity_metric_condition() {
+        let context = make_ctx_with_temp_device();
+        let condition = RuleCondition::Comparison {
+            source: DataSourceId::device("sensor1", "__online"),
+            operator: ComparisonOperator::LessThan,
+            threshold: 1.0,
+            threshold_value: None,
+        };
+        let issues = RuleValidator::validate_condition(&condition, &context)
+            .expect("__online should bypass metric-existence check");
+        assert!(issues.is_empty(), "unexpected warnings: {:?}", issues);
+
+        let mut rule = CompiledRule::new("test");
+        rule.condition = Some(condition);
+        rule.cooldown = std::time::Duration::from_secs(0);
+        rule.finalize();
+        assert!(RuleValidator::validate_virtual_metric_cooldown(&rule).is_ok());
+    }
+
     // ----- Virtual-metric cooldown tests (Task 2) ---------------------------
 
     /// Short cooldown (30s) on a virtual-metric rule must be rejected — shorter
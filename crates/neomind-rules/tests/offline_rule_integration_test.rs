@@ -62,6 +62,9 @@ fn make_device_config(device_id: &str) -> DeviceConfig {
         adapter_id: None,
         last_seen: 0,
         offline_timeout_secs: Some(60),
+        tags: Vec::new(),
+        location: None,
+        tenant_id: Default::default(),
     }
 }
 
@@ -0,0 +1,761 @@
+//! Offline administration of the redb stores in a data directory.
+//!
+//! Backs the `neomind db` commands: inspecting table sizes, compacting,
+//! exporting and importing stores as JSON, and running schema migrations.
+//! Everything here opens the `.redb` files directly, so the server must be
+//! stopped first. redb locks a file while it is open, and opening a store
+//! the server holds fails with [`Error::Storage`].
+//!
+//! Exports are keyed by table layout rather than by store type, so any store
+//! can be exported without knowing its record types. Keys are written as JSON
+//! (strings, numbers, or arrays for tuple keys). Byte values holding JSON are
+//! inlined as JSON; other byte values (e.g. bincode) are base64 encoded and
+//! marked `binary`. Supported layouts, as `key -> value` labels:
+//!
+//! | Key | Value |
+//! |-----|-------|
+//! | `str` | `bytes`, `byte_vec`, `str`, `i64` |
+//! | `u64` | `bytes` |
+//! | `(str,u64)` | `byte_vec` |
+//! | `(str,str)` | `str` |
+//! | `(str,str,i64)` | `bytes` |
+//!
+//! Tables with any other layout are listed in `skipped_tables`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::Engine as _;
+use redb::{
+    Database, DatabaseError, MultimapTableHandle, ReadTransaction, ReadableTable,
+    ReadableTableMetadata, TableDefinition, TableError, TableHandle, WriteTransaction,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::wal;
+
+/// Version written to [`StoreExport::format_version`].
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Applied migrations, recorded in each migrated store: id -> applied_at.
+const MIGRATIONS_TABLE: TableDefinition<&str, i64> = TableDefinition::new("schema_migrations");
+
+/// Size and key count of one table.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableInfo {
+    pub name: String,
+    pub entries: u64,
+    /// Bytes of keys and values.
+    pub stored_bytes: u64,
+    /// Bytes of b-tree bookkeeping.
+    pub metadata_bytes: u64,
+    /// Bytes allocated to the table but unused.
+    pub fragmented_bytes: u64,
+}
+
+/// Summary of one store file.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoreInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub file_bytes: u64,
+    pub tables: Vec<TableInfo>,
+    /// Time series write-ahead journal files not yet replayed.
+    pub journal_files: usize,
+}
+
+/// Result of compacting one store.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompactReport {
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+    /// Whether redb found anything to compact.
+    pub compacted: bool,
+}
+
+/// JSON export of one store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreExport {
+    pub format_version: u32,
+    /// Store name, the file stem of the `.redb` file.
+    pub store: String,
+    pub exported_at: i64,
+    pub tables: Vec<TableExport>,
+    /// Tables whose layout could not be exported.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_tables: Vec<String>,
+}
+
+impl StoreExport {
+    /// Total number of exported entries.
+    pub fn entry_count(&self) -> usize {
+        self.tables.iter().map(|t| t.entries.len()).sum()
+    }
+}
+
+/// JSON export of one table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableExport {
+    pub name: String,
+    /// Key layout label (see the module docs).
+    pub key_type: String,
+    /// Value layout label (see the module docs).
+    pub value_type: String,
+    pub entries: Vec<ExportEntry>,
+}
+
+/// One exported key/value pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportEntry {
+    pub key: Value,
+    pub value: Value,
+    /// `value` is base64 of raw bytes rather than inline JSON.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub binary: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Result of importing one store.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub tables: usize,
+    pub entries: usize,
+}
+
+/// A schema migration of one store.
+///
+/// Migrations are idempotent, and applied ones are recorded in the store's
+/// `schema_migrations` table so `neomind db migrate` only runs pending ones.
+pub struct Migration {
+    /// Stable id, ordered by prefix.
+    pub id: &'static str,
+    /// Store the migration applies to.
+    pub store: &'static str,
+    pub description: &'static str,
+    /// Returns the number of records changed.
+    apply: fn(&Database) -> Result<u64>,
+}
+
+/// All known migrations, in the order they run.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    id: "0001_timeseries_device_prefix",
+    store: "telemetry",
+    description: "Prefix bare device ids in time series keys with \"device:\"",
+    apply: crate::timeseries::migrate_device_prefix_keys,
+}];
+
+/// Whether a migration has been applied to its store.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub id: &'static str,
+    pub store: &'static str,
+    pub description: &'static str,
+    pub applied_at: Option<i64>,
+}
+
+/// A migration run by [`run_migrations`].
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationOutcome {
+    pub id: &'static str,
+    pub store: &'static str,
+    pub changed: u64,
+}
+
+/// Path of a store in a data directory. Accepts `devices` or `devices.redb`.
+pub fn store_path(data_dir: &Path, store: &str) -> PathBuf {
+    let file = if store.ends_with(".redb") {
+        store.to_string()
+    } else {
+        format!("{}.redb", store)
+    };
+    data_dir.join(file)
+}
+
+/// Store name of a store file: its file stem.
+pub fn store_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// All `.redb` files directly in a data directory, sorted by name.
+pub fn list_stores(data_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut stores = Vec::new();
+    for entry in fs::read_dir(data_dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "redb") {
+            stores.push(path);
+        }
+    }
+    stores.sort();
+    Ok(stores)
+}
+
+/// Map redb's lock failure to an error that says what to do about it.
+fn locked_error(path: &Path, e: DatabaseError) -> Error {
+    match e {
+        DatabaseError::DatabaseAlreadyOpen => Error::Storage(format!(
+            "{} is in use by another process; stop the NeoMind server first",
+            path.display()
+        )),
+        e => e.into(),
+    }
+}
+
+fn open_existing(path: &Path) -> Result<Database> {
+    if !path.is_file() {
+        return Err(Error::NotFound(path.display().to_string()));
+    }
+    Database::open(path).map_err(|e| locked_error(path, e))
+}
+
+/// Table sizes and key counts of a store.
+pub fn inspect_store(path: &Path) -> Result<StoreInfo> {
+    let db = open_existing(path)?;
+    let txn = db.begin_read()?;
+
+    let mut tables = Vec::new();
+    for handle in txn.list_tables()? {
+        let name = handle.name().to_string();
+        let table = txn.open_untyped_table(handle)?;
+        let stats = table.stats()?;
+        tables.push(TableInfo {
+            name,
+            entries: table.len()?,
+            stored_bytes: stats.stored_bytes(),
+            metadata_bytes: stats.metadata_bytes(),
+            fragmented_bytes: stats.fragmented_bytes(),
+        });
+    }
+
+    Ok(StoreInfo {
+        name: store_name(path),
+        path: path.to_path_buf(),
+        file_bytes: fs::metadata(path)?.len(),
+        tables,
+        journal_files: wal::journal_files(path)?.len(),
+    })
+}
+
+/// Compact a store, returning the file size before and after.
+pub fn compact_store(path: &Path) -> Result<CompactReport> {
+    let before_bytes = fs::metadata(path)?.len();
+    let compacted = {
+        let mut db = open_existing(path)?;
+        db.compact()?
+    };
+    Ok(CompactReport {
+        before_bytes,
+        after_bytes: fs::metadata(path)?.len(),
+        compacted,
+    })
+}
+
+/// Encoding of a value read from a table.
+trait EncodeValue {
+    /// The JSON value and whether it is base64 of raw bytes.
+    fn encode(&self) -> (Value, bool);
+}
+
+fn encode_bytes(bytes: &[u8]) -> (Value, bool) {
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(value) => (value, false),
+        Err(_) => (
+            Value::String(base64::engine::general_purpose::STANDARD.encode(bytes)),
+            true,
+        ),
+    }
+}
+
+impl EncodeValue for &[u8] {
+    fn encode(&self) -> (Value, bool) {
+        encode_bytes(self)
+    }
+}
+
+impl EncodeValue for Vec<u8> {
+    fn encode(&self) -> (Value, bool) {
+        encode_bytes(self)
+    }
+}
+
+impl EncodeValue for &str {
+    fn encode(&self) -> (Value, bool) {
+        (Value::from(*self), false)
+    }
+}
+
+impl EncodeValue for i64 {
+    fn encode(&self) -> (Value, bool) {
+        (Value::from(*self), false)
+    }
+}
+
+/// Decoding of an exported value back to its owned table type.
+trait DecodeValue: Sized {
+    fn decode(entry: &ExportEntry) -> Result<Self>;
+}
+
+impl DecodeValue for Vec<u8> {
+    fn decode(entry: &ExportEntry) -> Result<Self> {
+        if !entry.binary {
+            return Ok(serde_json::to_vec(&entry.value)?);
+        }
+        let encoded = entry
+            .value
+            .as_str()
+            .ok_or_else(|| Error::InvalidInput("binary value is not a string".to_string()))?;
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| Error::Serialization(format!("Invalid base64 value: {}", e)))
+    }
+}
+
+impl DecodeValue for String {
+    fn decode(entry: &ExportEntry) -> Result<Self> {
+        entry
+            .value
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::InvalidInput(format!("expected a string, got {}", entry.value)))
+    }
+}
+
+impl DecodeValue for i64 {
+    fn decode(entry: &ExportEntry) -> Result<Self> {
+        entry
+            .value
+            .as_i64()
+            .ok_or_else(|| Error::InvalidInput(format!("expected an integer, got {}", entry.value)))
+    }
+}
+
+/// Export `$name` if it has the given layout, returning from the enclosing
+/// function; fall through on a layout mismatch.
+macro_rules! try_export {
+    ($txn:expr, $name:expr, $key:literal: $k:ty, $value:literal: $v:ty) => {
+        match $txn.open_table(TableDefinition::<$k, $v>::new($name)) {
+            Ok(table) => {
+                let mut entries = Vec::new();
+                for item in table.iter()? {
+                    let (key, value) = item?;
+                    let (encoded, binary) = value.value().encode();
+                    entries.push(ExportEntry {
+                        key: serde_json::to_value(key.value())?,
+                        value: encoded,
+                        binary,
+                    });
+                }
+                return Ok(Some(TableExport {
+                    name: $name.to_string(),
+                    key_type: $key.to_string(),
+                    value_type: $value.to_string(),
+                    entries,
+                }));
+            }
+            Err(TableError::TableTypeMismatch { .. }) => {}
+            Err(e) => return Err(e.into()),
+        }
+    };
+}
+
+/// Export one table, or `None` if its layout is not supported.
+fn export_table(txn: &ReadTransaction, name: &str) -> Result<Option<TableExport>> {
+    try_export!(txn, name, "str": &str, "bytes": &[u8]);
+    try_export!(txn, name, "str": &str, "byte_vec": Vec<u8>);
+    try_export!(txn, name, "str": &str, "str": &str);
+    try_export!(txn, name, "str": &str, "i64": i64);
+    try_export!(txn, name, "u64": u64, "bytes": &[u8]);
+    try_export!(txn, name, "(str,u64)": (&str, u64), "byte_vec": Vec<u8>);
+    try_export!(txn, name, "(str,str)": (&str, &str), "str": &str);
+    try_export!(txn, name, "(str,str,i64)": (&str, &str, i64), "bytes": &[u8]);
+    Ok(None)
+}
+
+/// Export every table of a store.
+pub fn export_store(path: &Path) -> Result<StoreExport> {
+    let db = open_existing(path)?;
+    let txn = db.begin_read()?;
+
+    let mut tables = Vec::new();
+    let mut skipped_tables = Vec::new();
+    for handle in txn.list_tables()? {
+        let name = handle.name().to_string();
+        match export_table(&txn, &name)? {
+            Some(table) => tables.push(table),
+            None => skipped_tables.push(name),
+        }
+    }
+    for handle in txn.list_multimap_tables()? {
+        skipped_tables.push(handle.name().to_string());
+    }
+
+    Ok(StoreExport {
+        format_version: EXPORT_FORMAT_VERSION,
+        store: store_name(path),
+        exported_at: chrono::Utc::now().timestamp(),
+        tables,
+        skipped_tables,
+    })
+}
+
+/// Write the entries of `$table` with the given layout. The closures turn
+/// the owned key and value decoded from JSON into what `insert` takes.
+macro_rules! import_entries {
+    (
+        $txn:expr, $table:expr, $replace:expr, $k:ty, $v:ty,
+        |$key:ident: $owned_key:ty| $borrow_key:expr,
+        |$value:ident: $owned_value:ty| $borrow_value:expr
+    ) => {{
+        let definition = TableDefinition::<$k, $v>::new(&$table.name);
+        if $replace {
+            $txn.delete_table(definition)?;
+        }
+        let mut target = $txn.open_table(definition)?;
+        for entry in &$table.entries {
+            let $key: $owned_key = serde_json::from_value(entry.key.clone())?;
+            let $value = <$owned_value as DecodeValue>::decode(entry)?;
+            target.insert($borrow_key, $borrow_value)?;
+        }
+    }};
+}
+
+fn import_table(txn: &WriteTransaction, table: &TableExport, replace: bool) -> Result<()> {
+    match (table.key_type.as_str(), table.value_type.as_str()) {
+        ("str", "bytes") => import_entries!(
+            txn,
+            table,
+            replace,
+            &str,
+            &[u8],
+            |k: String| k.as_str(),
+            |v: Vec<u8>| v.as_slice()
+        ),
+        ("str", "byte_vec") => import_entries!(
+            txn,
+            table,
+            replace,
+            &str,
+            Vec<u8>,
+            |k: String| k.as_str(),
+            |v: Vec<u8>| v
+        ),
+        ("str", "str") => import_entries!(
+            txn,
+            table,
+            replace,
+            &str,
+            &str,
+            |k: String| k.as_str(),
+            |v: String| v.as_str()
+        ),
+        ("str", "i64") => import_entries!(
+            txn,
+            table,
+            replace,
+            &str,
+            i64,
+            |k: String| k.as_str(),
+            |v: i64| v
+        ),
+        ("u64", "bytes") => import_entries!(
+            txn,
+            table,
+            replace,
+            u64,
+            &[u8],
+            |k: u64| k,
+            |v: Vec<u8>| v.as_slice()
+        ),
+        ("(str,u64)", "byte_vec") => import_entries!(
+            txn,
+            table,
+            replace,
+            (&str, u64),
+            Vec<u8>,
+            |k: (String, u64)| (k.0.as_str(), k.1),
+            |v: Vec<u8>| v
+        ),
+        ("(str,str)", "str") => import_entries!(
+            txn,
+            table,
+            replace,
+            (&str, &str),
+            &str,
+            |k: (String, String)| (k.0.as_str(), k.1.as_str()),
+            |v: String| v.as_str()
+        ),
+        ("(str,str,i64)", "bytes") => import_entries!(
+            txn,
+            table,
+            replace,
+            (&str, &str, i64),
+            &[u8],
+            |k: (String, String, i64)| (k.0.as_str(), k.1.as_str(), k.2),
+            |v: Vec<u8>| v.as_slice()
+        ),
+        (key, value) => {
+            return Err(Error::InvalidInput(format!(
+                "table '{}' has unsupported layout {} -> {}",
+                table.name, key, value
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Import an export into a store, creating the store if needed.
+///
+/// Entries are written over existing keys. With `replace`, each exported
+/// table is cleared first. All tables are written in one transaction, so a
+/// failed import leaves the store unchanged.
+pub fn import_store(path: &Path, export: &StoreExport, replace: bool) -> Result<ImportReport> {
+    if export.format_version > EXPORT_FORMAT_VERSION {
+        return Err(Error::InvalidInput(format!(
+            "export format version {} is newer than supported version {}",
+            export.format_version, EXPORT_FORMAT_VERSION
+        )));
+    }
+
+    let db = Database::create(path).map_err(|e| locked_error(path, e))?;
+    let txn = db.begin_write()?;
+    for table in &export.tables {
+        import_table(&txn, table, replace)?;
+    }
+    txn.commit()?;
+
+    Ok(ImportReport {
+        tables: export.tables.len(),
+        entries: export.entry_count(),
+    })
+}
+
+fn applied_at(db: &Database, id: &str) -> Result<Option<i64>> {
+    let txn = db.begin_read()?;
+    let table = match txn.open_table(MIGRATIONS_TABLE) {
+        Ok(table) => table,
+        Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(table.get(id)?.map(|applied| applied.value()))
+}
+
+/// Status of every migration whose store exists in the data directory.
+pub fn migration_status(data_dir: &Path) -> Result<Vec<MigrationStatus>> {
+    let mut statuses = Vec::new();
+    for migration in MIGRATIONS {
+        let path = store_path(data_dir, migration.store);
+        if !path.is_file() {
+            continue;
+        }
+        let db = open_existing(&path)?;
+        statuses.push(MigrationStatus {
+            id: migration.id,
+            store: migration.store,
+            description: migration.description,
+            applied_at: applied_at(&db, migration.id)?,
+        });
+    }
+    Ok(statuses)
+}
+
+/// Run every pending migration whose store exists in the data directory.
+///
+/// Stores that do not exist yet are skipped: the server creates them in the
+/// current schema.
+pub fn run_migrations(data_dir: &Path) -> Result<Vec<MigrationOutcome>> {
+    let mut outcomes = Vec::new();
+    for migration in MIGRATIONS {
+        let path = store_path(data_dir, migration.store);
+        if !path.is_file() {
+            continue;
+        }
+        let db = open_existing(&path)?;
+        if applied_at(&db, migration.id)?.is_some() {
+            continue;
+        }
+
+        let changed = (migration.apply)(&db)?;
+        let txn = db.begin_write()?;
+        {
+            let mut table = txn.open_table(MIGRATIONS_TABLE)?;
+            table.insert(migration.id, chrono::Utc::now().timestamp())?;
+        }
+        txn.commit()?;
+
+        outcomes.push(MigrationOutcome {
+            id: migration.id,
+            store: migration.store,
+            changed,
+        });
+    }
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("records");
+    const HISTORY_TABLE: TableDefinition<(&str, u64), Vec<u8>> = TableDefinition::new("history");
+    const SERIES_TABLE: TableDefinition<(&str, &str, i64), &[u8]> =
+        TableDefinition::new("timeseries");
+
+    fn seed(path: &Path) {
+        let db = Database::create(path).unwrap();
+        let txn = db.begin_write().unwrap();
+        {
+            let mut table = txn.open_table(JSON_TABLE).unwrap();
+            table.insert("a", br#"{"name":"first","n":1}"#.as_slice()).unwrap();
+            table.insert("b", [0u8, 159, 146, 150].as_slice()).unwrap();
+            let mut table = txn.open_table(HISTORY_TABLE).unwrap();
+            table.insert(("s1", 0), b"\"hello\"".to_vec()).unwrap();
+        }
+        txn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_store_paths() {
+        let dir = Path::new("/data");
+        assert_eq!(store_path(dir, "devices"), Path::new("/data/devices.redb"));
+        assert_eq!(store_path(dir, "devices.redb"), Path::new("/data/devices.redb"));
+        assert_eq!(store_name(Path::new("/data/devices.redb")), "devices");
+    }
+
+    #[test]
+    fn test_inspect_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("records.redb");
+        seed(&path);
+        fs::write(dir.path().join("notes.txt"), "not a store").unwrap();
+
+        assert_eq!(list_stores(dir.path()).unwrap(), vec![path.clone()]);
+
+        let info = inspect_store(&path).unwrap();
+        assert_eq!(info.name, "records");
+        assert_eq!(info.journal_files, 0);
+        let records = info.tables.iter().find(|t| t.name == "records").unwrap();
+        assert_eq!(records.entries, 2);
+        assert!(records.stored_bytes > 0);
+
+        assert!(matches!(
+            inspect_store(&dir.path().join("missing.redb")),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("records.redb");
+        seed(&source);
+
+        let export = export_store(&source).unwrap();
+        assert_eq!(export.store, "records");
+        assert!(export.skipped_tables.is_empty());
+        assert_eq!(export.entry_count(), 3);
+
+        let records = export.tables.iter().find(|t| t.name == "records").unwrap();
+        assert_eq!((records.key_type.as_str(), records.value_type.as_str()), ("str", "bytes"));
+        assert_eq!(records.entries[0].value["name"], "first");
+        assert!(!records.entries[0].binary);
+        assert!(records.entries[1].binary);
+
+        // Survives a trip through the JSON file format
+        let json = serde_json::to_string(&export).unwrap();
+        let export: StoreExport = serde_json::from_str(&json).unwrap();
+
+        let target = dir.path().join("copy.redb");
+        let report = import_store(&target, &export, false).unwrap();
+        assert_eq!(report.tables, 2);
+        assert_eq!(report.entries, 3);
+
+        let db = Database::open(&target).unwrap();
+        let txn = db.begin_read().unwrap();
+        let table = txn.open_table(JSON_TABLE).unwrap();
+        let first = table.get("a").unwrap().unwrap();
+        assert_eq!(first.value(), br#"{"name":"first","n":1}"#);
+        let second = table.get("b").unwrap().unwrap();
+        assert_eq!(second.value(), [0u8, 159, 146, 150]);
+        let table = txn.open_table(HISTORY_TABLE).unwrap();
+        assert_eq!(table.get(("s1", 0)).unwrap().unwrap().value(), b"\"hello\"");
+    }
+
+    #[test]
+    fn test_import_replace_clears_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("records.redb");
+        seed(&path);
+
+        let mut export = export_store(&path).unwrap();
+        export.tables.retain(|t| t.name == "records");
+        export.tables[0].entries.truncate(1);
+
+        let records_len = |path: &Path| {
+            let info = inspect_store(path).unwrap();
+            info.tables.iter().find(|t| t.name == "records").unwrap().entries
+        };
+
+        import_store(&path, &export, false).unwrap();
+        assert_eq!(records_len(&path), 2);
+
+        import_store(&path, &export, true).unwrap();
+        assert_eq!(records_len(&path), 1);
+
+        export.format_version = EXPORT_FORMAT_VERSION + 1;
+        assert!(matches!(
+            import_store(&path, &export, false),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_compact_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("records.redb");
+        seed(&path);
+
+        let report = compact_store(&path).unwrap();
+        assert!(report.after_bytes <= report.before_bytes);
+        assert_eq!(inspect_store(&path).unwrap().tables.len(), 2);
+    }
+
+    #[test]
+    fn test_run_migrations_once() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(run_migrations(dir.path()).unwrap().is_empty());
+
+        let path = store_path(dir.path(), "telemetry");
+        {
+            let db = Database::create(&path).unwrap();
+            let txn = db.begin_write().unwrap();
+            {
+                let mut table = txn.open_table(SERIES_TABLE).unwrap();
+                table.insert(("sensor1", "temp", 1), b"{}".as_slice()).unwrap();
+                table.insert(("device:sensor2", "temp", 1), b"{}".as_slice()).unwrap();
+            }
+            txn.commit().unwrap();
+        }
+
+        let status = migration_status(dir.path()).unwrap();
+        assert_eq!(status.len(), 1);
+        assert!(status[0].applied_at.is_none());
+
+        let outcomes = run_migrations(dir.path()).unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].changed, 1);
+        assert!(migration_status(dir.path()).unwrap()[0].applied_at.is_some());
+        assert!(run_migrations(dir.path()).unwrap().is_empty());
+
+        let db = Database::open(&path).unwrap();
+        let txn = db.begin_read().unwrap();
+        let table = txn.open_table(SERIES_TABLE).unwrap();
+        assert!(table.get(("device:sensor1", "temp", 1)).unwrap().is_some());
+        assert!(table.get(("sensor1", "temp", 1)).unwrap().is_none());
+    }
+}
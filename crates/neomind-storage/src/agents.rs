@@ -100,6 +100,52 @@ pub struct AiAgent {
     /// Current consecutive failure count (reset to 0 on success)
     #[serde(default)]
     pub consecutive_failures: u32,
+    /// Limits on simultaneous executions of this agent
+    #[serde(default)]
+    pub concurrency: ConcurrencyPolicy,
+}
+
+/// What to do with an execution that arrives while its agent (or the whole
+/// system) is at its concurrency limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Wait for a free slot, first come first served
+    #[default]
+    Queue,
+    /// Skip the execution
+    Drop,
+    /// Wait for a free slot, but only the newest waiting execution is kept
+    ReplaceLatest,
+}
+
+/// Per-agent concurrency limits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcurrencyPolicy {
+    /// Executions of this agent allowed at once (None = only the global cap applies)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+    /// Behaviour when a limit is hit
+    #[serde(default)]
+    pub on_limit: OverflowPolicy,
+    /// Longest queue before further executions are dropped (`queue` only)
+    #[serde(default = "default_max_queue")]
+    pub max_queue: usize,
+}
+
+impl Default for ConcurrencyPolicy {
+    fn default() -> Self {
+        Self {
+            max_concurrent: None,
+            on_limit: OverflowPolicy::default(),
+            max_queue: default_max_queue(),
+        }
+    }
+}
+
+/// Default value for the execution queue length.
+fn default_max_queue() -> usize {
+    16
 }
 
 /// Tool configuration for AI Agent function calling mode.
@@ -1322,6 +1368,7 @@ mod tests {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
         };
 
         store.save_agent(&agent).await.unwrap();
@@ -1368,6 +1415,7 @@ mod tests {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
         };
 
         store.save_agent(&agent).await.unwrap();
@@ -1447,6 +1495,7 @@ mod tests {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
         };
 
         // Save initial agent
@@ -1513,6 +1562,7 @@ mod tests {
             system_prompt: None,
             max_retries: 0,
             consecutive_failures: 0,
+            concurrency: Default::default(),
         };
 
         store.save_agent(&agent).await.unwrap();
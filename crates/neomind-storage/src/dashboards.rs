@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 use serde_json;
 
 use crate::Error;
+use neomind_core::tenant::TenantId;
 
 // Dashboard table: key = dashboard_id, value = JSON dashboard (serialized)
 const DASHBOARDS_TABLE: TableDefinition<&str, Vec<u8>> = TableDefinition::new("dashboards");
@@ -106,6 +107,18 @@ pub struct Dashboard {
     /// of the list via `i32::MAX` tiebreak at the API layer.
     #[serde(skip_serializing_if = "Option::is_none", alias = "sort_order")]
     pub sort_order: Option<i32>,
+    /// User ID of the owner. `None` for dashboards created before per-user
+    /// ownership or through an API key; those are visible to every user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Seconds between data refreshes of the whole dashboard; `None` leaves
+    /// refreshing to the individual components.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_interval: Option<u32>,
+    /// Tenant that owns this dashboard. Rows from before tenancy belong to
+    /// the default tenant.
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
 }
 
 /// Dashboard template.
@@ -147,6 +160,9 @@ impl Dashboard {
             updated_at: now,
             is_default: None,
             sort_order: None,
+            owner: None,
+            refresh_interval: None,
+            tenant_id: TenantId::default(),
         }
     }
 
@@ -162,6 +178,12 @@ impl Dashboard {
         self.is_default = Some(is_default);
         self
     }
+
+    /// Whether `user_id` may see this dashboard: its owner, or anyone when
+    /// it has no owner.
+    pub fn is_visible_to(&self, user_id: &str) -> bool {
+        self.owner.as_deref().is_none_or(|owner| owner == user_id)
+    }
 }
 
 impl DashboardLayout {
@@ -768,6 +790,9 @@ mod tests {
             updated_at: 12346,
             is_default: Some(true),
             sort_order: Some(0),
+            owner: Some("user-1".to_string()),
+            refresh_interval: Some(30),
+            tenant_id: TenantId::default(),
         };
 
         let serialized = serde_json::to_string(&dashboard).unwrap();
@@ -777,6 +802,31 @@ mod tests {
         assert_eq!(deserialized.name, "Test Dashboard");
         assert_eq!(deserialized.layout.columns, 12);
         assert_eq!(deserialized.is_default, Some(true));
+        assert_eq!(deserialized.owner.as_deref(), Some("user-1"));
+        assert_eq!(deserialized.refresh_interval, Some(30));
+    }
+
+    #[test]
+    fn test_dashboard_visibility() {
+        let mut dashboard = Dashboard::new("Mine".to_string(), DashboardLayout::default_layout());
+        assert!(dashboard.is_visible_to("alice"));
+
+        dashboard.owner = Some("alice".to_string());
+        assert!(dashboard.is_visible_to("alice"));
+        assert!(!dashboard.is_visible_to("bob"));
+
+        // Rows stored before ownership existed have no owner field
+        let legacy: Dashboard = serde_json::from_value(serde_json::json!({
+            "id": "old",
+            "name": "Old",
+            "layout": DashboardLayout::default_layout(),
+            "components": [],
+            "created_at": 1,
+            "updated_at": 1,
+        }))
+        .unwrap();
+        assert!(legacy.owner.is_none());
+        assert!(legacy.is_visible_to("bob"));
     }
 
     #[test]
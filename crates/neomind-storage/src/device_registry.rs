@@ -6,6 +6,7 @@ use parking_lot::Mutex;
 use std::path::Path;
 use std::sync::Arc;
 
+use neomind_core::tenant::TenantId;
 use redb::{Database, ReadableTable, TableDefinition};
 use serde::{Deserialize, Serialize};
 
@@ -24,6 +25,21 @@ const TYPE_INDEX_TABLE: TableDefinition<&str, &str> = TableDefinition::new("devi
 const COMMAND_HISTORY_TABLE: TableDefinition<(&str, &str), &str> =
     TableDefinition::new("command_history");
 
+// Device groups table: key = group_id, value = DeviceGroup (JSON)
+const DEVICE_GROUPS_TABLE: TableDefinition<&str, &str> = TableDefinition::new("device_groups");
+
+// Network discovery queue: key = endpoint id, value = DiscoveredEndpoint (JSON)
+const DISCOVERED_ENDPOINTS_TABLE: TableDefinition<&str, &str> =
+    TableDefinition::new("discovered_endpoints");
+
+// Command idempotency keys: key = record ID (owner + key), value = CommandIdempotencyRecord (JSON)
+const COMMAND_IDEMPOTENCY_TABLE: TableDefinition<&str, &str> =
+    TableDefinition::new("command_idempotency");
+
+// Scheduled commands: key = schedule ID, value = ScheduledCommandRecord (JSON)
+const SCHEDULED_COMMANDS_TABLE: TableDefinition<&str, &str> =
+    TableDefinition::new("scheduled_commands");
+
 /// Device type mode: simple (raw data + LLM) or full (structured definitions)
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -56,6 +72,9 @@ pub struct DeviceTypeTemplate {
     /// `None` = use global default. Forward-compatible via `#[serde(default)]`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_offline_timeout_secs: Option<u64>,
+    /// Expected seconds between telemetry reports. `None` = not declared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_interval_secs: Option<u64>,
     /// If `Some(false)`, skip storing the `_raw` metric (full payload snapshot)
     /// for devices of this type. `None` (default) = inherit the extractor's
     /// `store_raw` config (currently always true). Set `Some(false)` for
@@ -63,6 +82,10 @@ pub struct DeviceTypeTemplate {
     /// cover all fields. Forward-compatible via `#[serde(default)]`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub store_raw: Option<bool>,
+    /// Binary frame codec (`neomind_devices::binary_codec::BinaryCodec`),
+    /// kept as JSON since this crate does not depend on the devices crate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_codec: Option<serde_json::Value>,
     /// Builtin template version — historical/provenance marker only.
     /// Older NeoMind builds set this when seeding built-in templates; the
     /// seeder is now insert-only (never overwrites existing templates), so
@@ -281,6 +304,99 @@ pub struct DeviceConfig {
     /// to None via `#[serde(default)]`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub offline_timeout_secs: Option<u64>,
+    /// Free-form tags used by dynamic device groups (e.g. "light").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Location path used by dynamic device groups (e.g. "building-a/floor-2").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Tenant that owns this device.
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
+}
+
+/// Device group: static members plus an optional dynamic selector.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct DeviceGroup {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Device IDs that are always members.
+    #[serde(default)]
+    pub members: Vec<String>,
+    /// Devices matching this selector are members too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<GroupSelector>,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+    /// Tenant that owns this group; only that tenant's devices are members.
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
+}
+
+/// Dynamic membership rule. Every set criterion must match; an empty
+/// selector matches nothing.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct GroupSelector {
+    /// Device must carry all of these tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Device location must equal this path or lie below it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Device type must equal this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_type: Option<String>,
+}
+
+/// Review state of a network-discovered endpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscoveryStatus {
+    /// Waiting for an operator decision.
+    #[default]
+    Pending,
+    /// Registered as a device.
+    Approved,
+    /// Dismissed; not queued again when seen in later scans.
+    Ignored,
+}
+
+/// An endpoint found by an mDNS or SSDP scan.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct DiscoveredEndpoint {
+    /// Stable ID derived from protocol, address and service identity.
+    pub id: String,
+    /// "mdns" or "ssdp"
+    pub protocol: String,
+    /// IP address of the endpoint
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Service instance name or UPnP friendly name
+    pub name: String,
+    /// mDNS service type (e.g. "_http._tcp.local") or SSDP search target
+    pub service_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manufacturer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// mDNS TXT records or SSDP headers
+    #[serde(default)]
+    pub properties: std::collections::HashMap<String, String>,
+    /// Device type suggested by correlation with the known templates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_type: Option<String>,
+    #[serde(default)]
+    pub status: DiscoveryStatus,
+    /// Device registered from this endpoint, once approved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    pub first_seen: i64,
+    pub last_seen: i64,
 }
 
 /// Connection configuration.
@@ -328,6 +444,66 @@ pub enum CommandStatus {
     Completed,
     Failed,
     Timeout,
+    Expired,
+}
+
+/// A device command set to run at a later time, once or on a cron schedule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledCommandRecord {
+    pub id: String,
+    pub device_id: String,
+    pub command_name: String,
+    pub parameters: ::std::collections::HashMap<String, serde_json::Value>,
+    /// Cron expression (UTC) the command recurs on; `None` runs it once
+    pub recurrence: Option<String>,
+    /// When the command runs next (unix seconds)
+    pub next_run: i64,
+    pub created_at: i64,
+    /// When the command last ran
+    pub last_run: Option<i64>,
+    /// Command history ID of the last run
+    pub last_command_id: Option<String>,
+    /// Error of the last run, if it failed
+    pub last_error: Option<String>,
+}
+
+/// A command submission's idempotency key and the outcome it recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandIdempotencyRecord {
+    /// Tenant or principal that submitted the key; keys of different owners
+    /// never collide
+    #[serde(default)]
+    pub owner: String,
+    pub key: String,
+    pub device_id: String,
+    pub command_name: String,
+    pub parameters: ::std::collections::HashMap<String, serde_json::Value>,
+    /// Command history ID, once the command was recorded
+    pub command_id: Option<String>,
+    /// Value returned by the command (JSON-encoded `MetricValue`)
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub created_at: i64,
+    /// `None` while the first submission is still in flight
+    pub completed_at: Option<i64>,
+    pub expires_at: i64,
+}
+
+impl CommandIdempotencyRecord {
+    /// Table key of the record, unique per owner and idempotency key.
+    pub fn id(&self) -> String {
+        Self::record_id(&self.owner, &self.key)
+    }
+
+    /// Table key for `key` submitted by `owner`. Records saved before keys
+    /// had owners are stored under the bare key.
+    pub fn record_id(owner: &str, key: &str) -> String {
+        if owner.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}\0{}", owner, key)
+        }
+    }
 }
 
 /// Device registry store using redb.
@@ -378,6 +554,10 @@ impl DeviceRegistryStore {
                 let _devices = write_txn.open_table(DEVICES_TABLE)?;
                 let _type_index = write_txn.open_table(TYPE_INDEX_TABLE)?;
                 let _commands = write_txn.open_table(COMMAND_HISTORY_TABLE)?;
+                let _groups = write_txn.open_table(DEVICE_GROUPS_TABLE)?;
+                let _discovered = write_txn.open_table(DISCOVERED_ENDPOINTS_TABLE)?;
+                let _idempotency = write_txn.open_table(COMMAND_IDEMPOTENCY_TABLE)?;
+                let _scheduled = write_txn.open_table(SCHEDULED_COMMANDS_TABLE)?;
             }
             write_txn.commit()?;
             true
@@ -397,6 +577,10 @@ impl DeviceRegistryStore {
                         let _devices = write_txn.open_table(DEVICES_TABLE)?;
                         let _type_index = write_txn.open_table(TYPE_INDEX_TABLE)?;
                         let _commands = write_txn.open_table(COMMAND_HISTORY_TABLE)?;
+                        let _groups = write_txn.open_table(DEVICE_GROUPS_TABLE)?;
+                        let _discovered = write_txn.open_table(DISCOVERED_ENDPOINTS_TABLE)?;
+                        let _idempotency = write_txn.open_table(COMMAND_IDEMPOTENCY_TABLE)?;
+                        let _scheduled = write_txn.open_table(SCHEDULED_COMMANDS_TABLE)?;
                     }
                     write_txn.commit()?;
                     return Ok(Arc::new(DeviceRegistryStore {
@@ -852,6 +1036,191 @@ impl DeviceRegistryStore {
         Ok(commands)
     }
 
+    // ========== Device Groups ==========
+
+    /// Save (insert or replace) a device group.
+    pub fn save_group(&self, group: &DeviceGroup) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(DEVICE_GROUPS_TABLE)?;
+            let json = serde_json::to_string(group)?;
+            table.insert(group.id.as_str(), json.as_str())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// List all device groups.
+    pub fn list_groups(&self) -> Result<Vec<DeviceGroup>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(DEVICE_GROUPS_TABLE) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut groups = Vec::new();
+        for result in table.iter()? {
+            let (_key, value) = result?;
+            if let Ok(group) = serde_json::from_str::<DeviceGroup>(value.value()) {
+                groups.push(group);
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Delete a device group. Returns whether it existed.
+    pub fn delete_group(&self, group_id: &str) -> Result<bool, Error> {
+        let write_txn = self.db.begin_write()?;
+        let existed = {
+            let mut table = write_txn.open_table(DEVICE_GROUPS_TABLE)?;
+            let removed = table.remove(group_id)?;
+            removed.is_some()
+        };
+        write_txn.commit()?;
+        Ok(existed)
+    }
+
+    // ========== Network Discovery ==========
+
+    /// Save (insert or replace) a discovered endpoint.
+    pub fn save_discovered_endpoint(&self, endpoint: &DiscoveredEndpoint) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(DISCOVERED_ENDPOINTS_TABLE)?;
+            let json = serde_json::to_string(endpoint)?;
+            table.insert(endpoint.id.as_str(), json.as_str())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// List all discovered endpoints, in any status.
+    pub fn list_discovered_endpoints(&self) -> Result<Vec<DiscoveredEndpoint>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(DISCOVERED_ENDPOINTS_TABLE) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut endpoints = Vec::new();
+        for result in table.iter()? {
+            let (_key, value) = result?;
+            if let Ok(endpoint) = serde_json::from_str::<DiscoveredEndpoint>(value.value()) {
+                endpoints.push(endpoint);
+            }
+        }
+        Ok(endpoints)
+    }
+
+    /// Delete a discovered endpoint. Returns whether it existed.
+    pub fn delete_discovered_endpoint(&self, id: &str) -> Result<bool, Error> {
+        let write_txn = self.db.begin_write()?;
+        let existed = {
+            let mut table = write_txn.open_table(DISCOVERED_ENDPOINTS_TABLE)?;
+            let removed = table.remove(id)?;
+            removed.is_some()
+        };
+        write_txn.commit()?;
+        Ok(existed)
+    }
+
+    // ========== Command Idempotency Keys ==========
+
+    /// Save (insert or replace) an idempotency key record.
+    pub fn save_idempotency_record(&self, record: &CommandIdempotencyRecord) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(COMMAND_IDEMPOTENCY_TABLE)?;
+            let json = serde_json::to_string(record)?;
+            table.insert(record.id().as_str(), json.as_str())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// List all idempotency key records, expired ones included.
+    pub fn list_idempotency_records(&self) -> Result<Vec<CommandIdempotencyRecord>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(COMMAND_IDEMPOTENCY_TABLE) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut records = Vec::new();
+        for result in table.iter()? {
+            let (_key, value) = result?;
+            if let Ok(record) = serde_json::from_str::<CommandIdempotencyRecord>(value.value()) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Delete idempotency key records by [`CommandIdempotencyRecord::id`] in
+    /// one transaction. Returns how many existed.
+    pub fn delete_idempotency_records(&self, ids: &[String]) -> Result<usize, Error> {
+        let write_txn = self.db.begin_write()?;
+        let mut removed = 0;
+        {
+            let mut table = write_txn.open_table(COMMAND_IDEMPOTENCY_TABLE)?;
+            for id in ids {
+                if table.remove(id.as_str())?.is_some() {
+                    removed += 1;
+                }
+            }
+        }
+        write_txn.commit()?;
+        Ok(removed)
+    }
+
+    // ========== Scheduled Commands ==========
+
+    /// Save (insert or replace) a scheduled command.
+    pub fn save_scheduled_command(&self, record: &ScheduledCommandRecord) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(SCHEDULED_COMMANDS_TABLE)?;
+            let json = serde_json::to_string(record)?;
+            table.insert(record.id.as_str(), json.as_str())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// List all scheduled commands.
+    pub fn list_scheduled_commands(&self) -> Result<Vec<ScheduledCommandRecord>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(SCHEDULED_COMMANDS_TABLE) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut records = Vec::new();
+        for result in table.iter()? {
+            let (_key, value) = result?;
+            if let Ok(record) = serde_json::from_str::<ScheduledCommandRecord>(value.value()) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Delete a scheduled command. Returns whether it existed.
+    pub fn delete_scheduled_command(&self, id: &str) -> Result<bool, Error> {
+        let write_txn = self.db.begin_write()?;
+        let existed = {
+            let mut table = write_txn.open_table(SCHEDULED_COMMANDS_TABLE)?;
+            let removed = table.remove(id)?;
+            removed.is_some()
+        };
+        write_txn.commit()?;
+        Ok(existed)
+    }
+
     // ========== Builtin Templates ==========
 
     /// Seed built-in device type templates (NE101, NE301, etc.).
@@ -1009,7 +1378,9 @@ mod tests {
             uplink_samples: vec![],
             commands: vec![],
             default_offline_timeout_secs: None,
+            expected_interval_secs: None,
             store_raw: None,
+            binary_codec: None,
             builtin_version: None,
         };
 
@@ -1043,7 +1414,10 @@ mod tests {
             },
             adapter_id: Some("main-mqtt".to_string()),
             offline_timeout_secs: None,
+            tags: Vec::new(),
+            location: None,
             last_seen: 0,
+            tenant_id: Default::default(),
         };
 
         store.save_device(&config).unwrap();
@@ -1075,6 +1449,9 @@ mod tests {
                 adapter_id: None,
                 last_seen: 0,
                 offline_timeout_secs: None,
+                tags: Vec::new(),
+                location: None,
+                tenant_id: Default::default(),
             })
             .unwrap();
 
@@ -1088,6 +1465,9 @@ mod tests {
                 adapter_id: None,
                 last_seen: 0,
                 offline_timeout_secs: None,
+                tags: Vec::new(),
+                location: None,
+                tenant_id: Default::default(),
             })
             .unwrap();
 
@@ -1101,6 +1481,9 @@ mod tests {
                 adapter_id: None,
                 last_seen: 0,
                 offline_timeout_secs: None,
+                tags: Vec::new(),
+                location: None,
+                tenant_id: Default::default(),
             })
             .unwrap();
 
@@ -1111,6 +1494,56 @@ mod tests {
         assert_eq!(switch_devices.len(), 1);
     }
 
+    #[test]
+    fn test_device_groups() {
+        let store = create_temp_store();
+        assert!(store.list_groups().unwrap().is_empty());
+
+        let group = DeviceGroup {
+            id: "floor2-lights".to_string(),
+            name: "Floor 2 lights".to_string(),
+            members: vec!["lamp-lobby".to_string()],
+            selector: Some(GroupSelector {
+                tags: vec!["light".to_string()],
+                location: Some("hq/floor-2".to_string()),
+                device_type: None,
+            }),
+            ..Default::default()
+        };
+        store.save_group(&group).unwrap();
+        assert_eq!(store.list_groups().unwrap(), vec![group]);
+
+        assert!(store.delete_group("floor2-lights").unwrap());
+        assert!(!store.delete_group("floor2-lights").unwrap());
+        assert!(store.list_groups().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_discovered_endpoints() {
+        let store = create_temp_store();
+        let mut endpoint = DiscoveredEndpoint {
+            id: "mdns:192.168.1.20:lamp".to_string(),
+            protocol: "mdns".to_string(),
+            address: "192.168.1.20".to_string(),
+            port: Some(80),
+            name: "lamp".to_string(),
+            service_type: "_http._tcp.local".to_string(),
+            first_seen: 100,
+            last_seen: 100,
+            ..Default::default()
+        };
+        store.save_discovered_endpoint(&endpoint).unwrap();
+
+        endpoint.status = DiscoveryStatus::Ignored;
+        store.save_discovered_endpoint(&endpoint).unwrap();
+        assert_eq!(store.list_discovered_endpoints().unwrap(), vec![endpoint]);
+
+        assert!(store
+            .delete_discovered_endpoint("mdns:192.168.1.20:lamp")
+            .unwrap());
+        assert!(store.list_discovered_endpoints().unwrap().is_empty());
+    }
+
     #[test]
     fn test_command_history() {
         let store = create_temp_store();
@@ -1137,6 +1570,72 @@ mod tests {
         assert_eq!(commands.len(), 1);
     }
 
+    #[test]
+    fn test_idempotency_records() {
+        let store = create_temp_store();
+        assert!(store.list_idempotency_records().unwrap().is_empty());
+
+        let mut record = CommandIdempotencyRecord {
+            owner: "tenant:acme".to_string(),
+            key: "retry-7f3a".to_string(),
+            device_id: "valve1".to_string(),
+            command_name: "open".to_string(),
+            parameters: std::collections::HashMap::new(),
+            command_id: Some("cmd_4".to_string()),
+            result: None,
+            error: None,
+            created_at: 100,
+            completed_at: None,
+            expires_at: 86_500,
+        };
+        store.save_idempotency_record(&record).unwrap();
+
+        record.completed_at = Some(101);
+        store.save_idempotency_record(&record).unwrap();
+        assert_eq!(store.list_idempotency_records().unwrap(), vec![record.clone()]);
+
+        // Another owner's key of the same name is a separate record
+        let mut other = record.clone();
+        other.owner = "tenant:globex".to_string();
+        store.save_idempotency_record(&other).unwrap();
+        assert_eq!(store.list_idempotency_records().unwrap().len(), 2);
+
+        let ids = vec![record.id(), "retry-7f3a".to_string()];
+        assert_eq!(store.delete_idempotency_records(&ids).unwrap(), 1);
+        assert_eq!(store.list_idempotency_records().unwrap(), vec![other.clone()]);
+        assert_eq!(store.delete_idempotency_records(&[other.id()]).unwrap(), 1);
+        assert!(store.list_idempotency_records().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scheduled_commands() {
+        let store = create_temp_store();
+        assert!(store.list_scheduled_commands().unwrap().is_empty());
+
+        let mut record = ScheduledCommandRecord {
+            id: "sched_1".to_string(),
+            device_id: "pump1".to_string(),
+            command_name: "on".to_string(),
+            parameters: std::collections::HashMap::new(),
+            recurrence: Some("0 0 6 * * *".to_string()),
+            next_run: 1_000,
+            created_at: 100,
+            last_run: None,
+            last_command_id: None,
+            last_error: None,
+        };
+        store.save_scheduled_command(&record).unwrap();
+
+        record.last_run = Some(1_000);
+        record.next_run = 87_400;
+        store.save_scheduled_command(&record).unwrap();
+        assert_eq!(store.list_scheduled_commands().unwrap(), vec![record]);
+
+        assert!(store.delete_scheduled_command("sched_1").unwrap());
+        assert!(!store.delete_scheduled_command("sched_1").unwrap());
+        assert!(store.list_scheduled_commands().unwrap().is_empty());
+    }
+
     #[test]
     fn test_bulk_operations() {
         let store = create_temp_store();
@@ -1225,7 +1724,9 @@ mod tests {
             uplink_samples: vec![],
             commands: vec![],
             default_offline_timeout_secs: None,
+            expected_interval_secs: None,
             store_raw: None,
+            binary_codec: None,
             builtin_version: None,
         };
         store.save_template(&custom).unwrap();
@@ -1255,8 +1756,10 @@ mod tests {
             uplink_samples: vec![],
             commands: vec![],
             default_offline_timeout_secs: None,
+            expected_interval_secs: None,
             builtin_version: None, // No version = user-created
             store_raw: None,
+            binary_codec: None,
         };
         store.save_template(&user_template).unwrap();
 
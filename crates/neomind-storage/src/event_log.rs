@@ -0,0 +1,321 @@
+//! Persistent event log using redb.
+//!
+//! Appends every `NeoMindEvent` published on an `EventBus` under a monotonic
+//! sequence number so consumers can resume from a cursor after a restart
+//! (see `EventBus::subscribe_from`).
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use neomind_core::event::{EventMetadata, NeoMindEvent};
+use neomind_core::eventbus::{EventPersistence, PersistedEvent};
+use redb::{Database, ReadableTable, ReadableTableMetadata, TableDefinition};
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+// Event log table: key = sequence, value = StoredEvent (JSON)
+const EVENT_LOG_TABLE: TableDefinition<u64, &[u8]> = TableDefinition::new("event_log");
+
+// Consumer cursors: key = consumer name, value = next sequence to deliver
+const CURSOR_TABLE: TableDefinition<&str, u64> = TableDefinition::new("event_log_cursors");
+
+/// Prune every this many appends when a cap is configured.
+const PRUNE_INTERVAL: u64 = 1024;
+
+#[derive(Serialize, Deserialize)]
+struct StoredEvent {
+    event: NeoMindEvent,
+    metadata: EventMetadata,
+}
+
+/// Append-only event log with sequence numbers.
+pub struct PersistentEventLog {
+    db: Arc<Database>,
+    /// Sequence of the newest stored event.
+    last_sequence: AtomicU64,
+    /// Keep at most this many events (oldest are pruned).
+    max_events: Option<u64>,
+}
+
+impl PersistentEventLog {
+    /// Open (or create) an event log at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let db = Database::create(path)
+            .map_err(|e| Error::Storage(format!("Failed to open event log: {}", e)))?;
+        Self::from_db(db)
+    }
+
+    /// Create an event log in a temporary file for testing.
+    pub fn memory() -> Result<Self, Error> {
+        let db_path = std::env::temp_dir().join(format!(
+            "event_log_test_{}_{}.redb",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        Self::open(db_path)
+    }
+
+    fn from_db(db: Database) -> Result<Self, Error> {
+        let write_txn = db.begin_write()?;
+        let last = {
+            let table = write_txn.open_table(EVENT_LOG_TABLE)?;
+            let last = table.last()?.map(|(k, _)| k.value());
+            write_txn.open_table(CURSOR_TABLE)?;
+            last.unwrap_or(0)
+        };
+        write_txn.commit()?;
+
+        Ok(Self {
+            db: Arc::new(db),
+            last_sequence: AtomicU64::new(last),
+            max_events: None,
+        })
+    }
+
+    /// Cap the log at `max` events; older events are pruned periodically.
+    pub fn with_max_events(mut self, max: u64) -> Self {
+        self.max_events = Some(max.max(1));
+        self
+    }
+
+    /// Append an event and return its sequence number.
+    pub fn append_event(
+        &self,
+        event: &NeoMindEvent,
+        metadata: &EventMetadata,
+    ) -> Result<u64, Error> {
+        let bytes = serde_json::to_vec(&StoredEvent {
+            event: event.clone(),
+            metadata: metadata.clone(),
+        })?;
+
+        // EventBus serializes publishes, so the counter only races with itself
+        // if the log is shared between buses; fetch_add keeps keys unique.
+        let sequence = self.last_sequence.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(EVENT_LOG_TABLE)?;
+            table.insert(sequence, bytes.as_slice())?;
+        }
+        write_txn.commit()?;
+
+        if let Some(max) = self.max_events {
+            if sequence.is_multiple_of(PRUNE_INTERVAL) && sequence > max {
+                if let Err(e) = self.prune_before(sequence - max + 1) {
+                    tracing::warn!(error = %e, "Failed to prune event log");
+                }
+            }
+        }
+
+        Ok(sequence)
+    }
+
+    /// Read up to `limit` events with `sequence >= cursor`, oldest first.
+    pub fn read_events(&self, cursor: u64, limit: usize) -> Result<Vec<PersistedEvent>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(EVENT_LOG_TABLE)?;
+
+        let mut events = Vec::new();
+        for result in table.range(cursor..)?.take(limit) {
+            let (key, value) = result?;
+            match serde_json::from_slice::<StoredEvent>(value.value()) {
+                Ok(stored) => events.push(PersistedEvent {
+                    sequence: key.value(),
+                    event: stored.event,
+                    metadata: stored.metadata,
+                }),
+                Err(e) => {
+                    tracing::warn!(sequence = key.value(), error = %e, "Skipping unreadable event");
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    /// Delete all events with `sequence < cursor`. Returns how many were removed.
+    pub fn prune_before(&self, cursor: u64) -> Result<usize, Error> {
+        let write_txn = self.db.begin_write()?;
+        let removed = {
+            let mut table = write_txn.open_table(EVENT_LOG_TABLE)?;
+            let keys: Vec<u64> = table
+                .range(..cursor)?
+                .filter_map(|r| r.ok().map(|(k, _)| k.value()))
+                .collect();
+            for key in &keys {
+                table.remove(key)?;
+            }
+            keys.len()
+        };
+        write_txn.commit()?;
+        Ok(removed)
+    }
+
+    /// Number of stored events.
+    pub fn len(&self) -> Result<u64, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(EVENT_LOG_TABLE)?;
+        Ok(table.len()?)
+    }
+
+    /// Whether the log is empty.
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Cursor stored for a consumer.
+    pub fn get_cursor(&self, consumer: &str) -> Result<Option<u64>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(CURSOR_TABLE)?;
+        Ok(table.get(consumer)?.map(|v| v.value()))
+    }
+
+    /// Store the cursor a consumer resumes from.
+    pub fn set_cursor(&self, consumer: &str, cursor: u64) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(CURSOR_TABLE)?;
+            table.insert(consumer, cursor)?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+}
+
+impl EventPersistence for PersistentEventLog {
+    fn append(&self, event: &NeoMindEvent, metadata: &EventMetadata) -> Option<u64> {
+        match self.append_event(event, metadata) {
+            Ok(sequence) => Some(sequence),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to persist event");
+                None
+            }
+        }
+    }
+
+    fn read_from(&self, cursor: u64, limit: usize) -> Vec<PersistedEvent> {
+        self.read_events(cursor, limit).unwrap_or_else(|e| {
+            tracing::warn!(cursor, error = %e, "Failed to read event log");
+            Vec::new()
+        })
+    }
+
+    fn last_sequence(&self) -> u64 {
+        self.last_sequence.load(Ordering::SeqCst)
+    }
+
+    fn load_cursor(&self, consumer: &str) -> Option<u64> {
+        self.get_cursor(consumer).unwrap_or_else(|e| {
+            tracing::warn!(consumer, error = %e, "Failed to read event log cursor");
+            None
+        })
+    }
+
+    fn store_cursor(&self, consumer: &str, cursor: u64) {
+        if let Err(e) = self.set_cursor(consumer, cursor) {
+            tracing::warn!(consumer, cursor, error = %e, "Failed to store event log cursor");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neomind_core::EventBus;
+
+    fn online(id: &str) -> NeoMindEvent {
+        NeoMindEvent::DeviceOnline {
+            device_id: id.to_string(),
+            device_type: "sensor".to_string(),
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_append_read_and_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.redb");
+
+        {
+            let log = PersistentEventLog::open(&path).unwrap();
+            for id in ["a", "b", "c"] {
+                log.append_event(&online(id), &EventMetadata::new("test"))
+                    .unwrap();
+            }
+            let events = log.read_events(2, 10).unwrap();
+            assert_eq!(
+                events.iter().map(|e| e.sequence).collect::<Vec<_>>(),
+                vec![2, 3]
+            );
+        }
+
+        // Sequence numbers continue after reopening.
+        let log = PersistentEventLog::open(&path).unwrap();
+        assert_eq!(log.last_sequence(), 3);
+        let seq = log
+            .append_event(&online("d"), &EventMetadata::new("test"))
+            .unwrap();
+        assert_eq!(seq, 4);
+
+        assert_eq!(log.prune_before(3).unwrap(), 2);
+        assert_eq!(log.len().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_event_bus_resumes_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.redb");
+
+        let cursor = {
+            let log = Arc::new(PersistentEventLog::open(&path).unwrap());
+            let bus = EventBus::new().with_persistence(log);
+            let mut rx = bus.subscribe_from(1);
+            bus.publish(online("a")).await;
+            rx.recv().await.unwrap();
+            let cursor = rx.cursor();
+            // Published while the consumer is "down".
+            bus.publish(online("b")).await;
+            cursor
+        };
+
+        let log = Arc::new(PersistentEventLog::open(&path).unwrap());
+        let bus = EventBus::new().with_persistence(log);
+        let mut rx = bus.subscribe_from(cursor);
+        let (event, meta) = rx.recv().await.unwrap();
+        assert!(
+            matches!(event, NeoMindEvent::DeviceOnline { ref device_id, .. } if device_id == "b")
+        );
+        assert_eq!(meta.sequence, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_durable_consumer_resumes_from_committed_cursor() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.redb");
+
+        {
+            let log = Arc::new(PersistentEventLog::open(&path).unwrap());
+            let bus = EventBus::new().with_persistence(log);
+            // Published before the consumer ever ran: not replayed.
+            bus.publish(online("old")).await;
+            let mut rx = bus.subscribe_durable("rules");
+            bus.publish(online("a")).await;
+            rx.recv().await.unwrap();
+            rx.commit();
+            // Published after the last commit, e.g. while shutting down.
+            bus.publish(online("b")).await;
+        }
+
+        let log = Arc::new(PersistentEventLog::open(&path).unwrap());
+        assert_eq!(log.get_cursor("rules").unwrap(), Some(3));
+        let bus = EventBus::new().with_persistence(log);
+        let mut rx = bus.subscribe_durable("rules");
+        let (event, meta) = rx.recv().await.unwrap();
+        assert!(
+            matches!(event, NeoMindEvent::DeviceOnline { ref device_id, .. } if device_id == "b")
+        );
+        assert_eq!(meta.sequence, Some(3));
+    }
+}
@@ -62,6 +62,12 @@ pub struct ExtensionRecord {
     #[serde(default)]
     pub disabled_commands: Vec<String>,
 
+    /// Capabilities from the manifest's `permissions` approved by an
+    /// operator. Requests for declared but unapproved capabilities are
+    /// refused.
+    #[serde(default)]
+    pub granted_permissions: Vec<String>,
+
     /// Extension configuration (key-value pairs)
     /// This config is passed to the extension when loaded
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -112,6 +118,7 @@ impl ExtensionRecord {
             enabled: true,
             uninstalled: false,
             disabled_commands: Vec::new(),
+            granted_permissions: Vec::new(),
             config: None,
             last_error: None,
             last_error_at: None,
@@ -0,0 +1,539 @@
+//! Hierarchical Navigable Small World (HNSW) index.
+//!
+//! Approximate nearest-neighbour index used by `PersistentVectorStore` when
+//! the `hnsw` feature is enabled. Search cost is roughly O(log n) instead of
+//! the linear scan done by `VectorStore`, which keeps semantic search over
+//! thousands of knowledge entries in the low milliseconds on edge hardware.
+//!
+//! The index supports incremental inserts. Deletes are tombstones: the node
+//! stays in the graph as a routing hop but is never returned. `rebuilt()`
+//! produces a compacted copy without tombstones; callers swap it in.
+//!
+//! Only the graph (ids, levels, neighbour lists) is serialized by
+//! `to_bytes`. Vectors already live in the document table, so `from_bytes`
+//! takes them back from the caller instead of storing them twice.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Embedding, SimilarityMetric};
+use crate::Error;
+
+/// HNSW construction and search parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HnswConfig {
+    /// Max neighbours per node on layers above 0 (layer 0 allows `2 * m`).
+    pub m: usize,
+    /// Candidate list size while inserting (higher = better recall, slower build).
+    pub ef_construction: usize,
+    /// Candidate list size while searching (clamped to at least `top_k`).
+    pub ef_search: usize,
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 100,
+            ef_search: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    id: String,
+    vector: Embedding,
+    /// Neighbour indices per layer; `neighbors.len() - 1` is the node's level.
+    neighbors: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+/// Candidate ordered by score (max-heap pops the most similar first).
+#[derive(Debug, Clone, Copy)]
+struct Scored {
+    score: f32,
+    idx: u32,
+}
+
+impl PartialEq for Scored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.idx.cmp(&self.idx))
+    }
+}
+
+/// Serialized graph layout (see module docs).
+#[derive(Serialize, Deserialize)]
+struct PersistedGraph {
+    config: HnswConfig,
+    entry: Option<u32>,
+    nodes: Vec<PersistedNode>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedNode {
+    id: String,
+    neighbors: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+/// In-memory HNSW graph.
+#[derive(Debug, Clone)]
+pub struct HnswIndex {
+    config: HnswConfig,
+    metric: SimilarityMetric,
+    nodes: Vec<Node>,
+    by_id: HashMap<String, u32>,
+    entry: Option<u32>,
+    deleted: usize,
+    /// Level normalisation factor, 1 / ln(m).
+    level_mult: f64,
+}
+
+impl HnswIndex {
+    /// Create an empty index.
+    pub fn new(config: HnswConfig, metric: SimilarityMetric) -> Self {
+        let m = config.m.max(2);
+        Self {
+            config: HnswConfig { m, ..config },
+            metric,
+            nodes: Vec::new(),
+            by_id: HashMap::new(),
+            entry: None,
+            deleted: 0,
+            level_mult: 1.0 / (m as f64).ln(),
+        }
+    }
+
+    /// Index parameters.
+    pub fn config(&self) -> HnswConfig {
+        self.config
+    }
+
+    /// Number of live (non-deleted) entries.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.deleted
+    }
+
+    /// Whether the index has no live entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of tombstoned entries still in the graph.
+    pub fn deleted_count(&self) -> usize {
+        self.deleted
+    }
+
+    /// Whether `id` is a live entry.
+    pub fn contains(&self, id: &str) -> bool {
+        self.by_id
+            .get(id)
+            .is_some_and(|&i| !self.nodes[i as usize].deleted)
+    }
+
+    /// Ids of all live entries.
+    pub fn live_ids(&self) -> Vec<String> {
+        self.by_id.keys().cloned().collect()
+    }
+
+    /// Stored vector for a live entry.
+    pub fn vector(&self, id: &str) -> Option<&Embedding> {
+        self.by_id.get(id).map(|&i| &self.nodes[i as usize].vector)
+    }
+
+    /// Insert or replace a vector.
+    ///
+    /// Replacing an existing id tombstones the old node and inserts a fresh
+    /// one, since its neighbourhood is no longer valid.
+    pub fn insert(&mut self, id: impl Into<String>, vector: Embedding) {
+        let id = id.into();
+        self.remove(&id);
+
+        let level = self.random_level();
+        let idx = self.nodes.len() as u32;
+        self.nodes.push(Node {
+            id: id.clone(),
+            vector,
+            neighbors: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.by_id.insert(id, idx);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(idx);
+            return;
+        };
+
+        let top = self.level_of(entry);
+        let query = self.nodes[idx as usize].vector.clone();
+        let mut ep = entry;
+
+        // Greedy descent through layers above the new node's level
+        for layer in (level + 1..=top).rev() {
+            ep = self.greedy_closest(&query, ep, layer);
+        }
+
+        let mut entry_points = vec![ep];
+        for layer in (0..=level.min(top)).rev() {
+            let candidates =
+                self.search_layer(&query, &entry_points, self.config.ef_construction, layer);
+            let max_conn = self.max_connections(layer);
+            let selected: Vec<u32> = candidates.iter().take(max_conn).map(|c| c.idx).collect();
+
+            self.nodes[idx as usize].neighbors[layer] = selected.clone();
+            for &n in &selected {
+                self.connect(n, idx, layer);
+            }
+            entry_points = candidates.into_iter().map(|c| c.idx).collect();
+        }
+
+        if level > top {
+            self.entry = Some(idx);
+        }
+    }
+
+    /// Tombstone an entry. Returns whether it was live.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(&idx) = self.by_id.get(id) else {
+            return false;
+        };
+        let node = &mut self.nodes[idx as usize];
+        if node.deleted {
+            return false;
+        }
+        node.deleted = true;
+        self.deleted += 1;
+        self.by_id.remove(id);
+        true
+    }
+
+    /// Approximate top-k search. Returns `(id, score)` pairs, best first.
+    pub fn search(&self, query: &[f32], top_k: usize) -> Vec<(String, f32)> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        if top_k == 0 || self.is_empty() {
+            return Vec::new();
+        }
+
+        let mut ep = entry;
+        for layer in (1..=self.level_of(entry)).rev() {
+            ep = self.greedy_closest(query, ep, layer);
+        }
+
+        // Widen the beam when tombstones could crowd out live results
+        let ef = self.config.ef_search.max(top_k) + self.deleted.min(top_k * 4);
+        self.search_layer(query, &[ep], ef, 0)
+            .into_iter()
+            .filter(|c| !self.nodes[c.idx as usize].deleted)
+            .take(top_k)
+            .map(|c| (self.nodes[c.idx as usize].id.clone(), c.score))
+            .collect()
+    }
+
+    /// Build a compacted copy without tombstones.
+    ///
+    /// Vectors are re-inserted in their original order, so the result does
+    /// not depend on when the rebuild ran.
+    pub fn rebuilt(&self) -> Self {
+        let mut fresh = Self::new(self.config, self.metric);
+        for node in self.nodes.iter().filter(|n| !n.deleted) {
+            fresh.insert(node.id.clone(), node.vector.clone());
+        }
+        fresh
+    }
+
+    /// Serialize the graph structure (without vectors).
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let graph = PersistedGraph {
+            config: self.config,
+            entry: self.entry,
+            nodes: self
+                .nodes
+                .iter()
+                .map(|n| PersistedNode {
+                    id: n.id.clone(),
+                    neighbors: n.neighbors.clone(),
+                    deleted: n.deleted,
+                })
+                .collect(),
+        };
+        Ok(bincode::serialize(&graph)?)
+    }
+
+    /// Restore a graph saved with `to_bytes`, re-attaching vectors by id.
+    ///
+    /// Live nodes whose vector is missing are tombstoned rather than failing
+    /// the load; the next rebuild drops them.
+    pub fn from_bytes(
+        bytes: &[u8],
+        metric: SimilarityMetric,
+        vectors: &HashMap<String, Embedding>,
+    ) -> Result<Self, Error> {
+        let graph: PersistedGraph = bincode::deserialize(bytes)?;
+        let mut index = Self::new(graph.config, metric);
+        let count = graph.nodes.len() as u32;
+
+        for (i, node) in graph.nodes.into_iter().enumerate() {
+            if node.neighbors.is_empty() || node.neighbors.iter().flatten().any(|&n| n >= count) {
+                return Err(Error::Storage(format!(
+                    "Corrupt HNSW graph at node {}",
+                    node.id
+                )));
+            }
+            let vector = vectors.get(&node.id).cloned();
+            let deleted = node.deleted || vector.is_none();
+            if deleted {
+                index.deleted += 1;
+            } else {
+                index.by_id.insert(node.id.clone(), i as u32);
+            }
+            index.nodes.push(Node {
+                id: node.id,
+                vector: vector.unwrap_or_default(),
+                neighbors: node.neighbors,
+                deleted,
+            });
+        }
+
+        index.entry = match graph.entry {
+            Some(e) if e < count => Some(e),
+            Some(_) => return Err(Error::Storage("Corrupt HNSW entry point".to_string())),
+            None => None,
+        };
+        Ok(index)
+    }
+
+    fn level_of(&self, idx: u32) -> usize {
+        self.nodes[idx as usize].neighbors.len() - 1
+    }
+
+    fn max_connections(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.config.m * 2
+        } else {
+            self.config.m
+        }
+    }
+
+    fn random_level(&self) -> usize {
+        let r: f64 = rand::random::<f64>().max(f64::MIN_POSITIVE);
+        ((-r.ln() * self.level_mult) as usize).min(16)
+    }
+
+    fn score(&self, query: &[f32], idx: u32) -> f32 {
+        let vector = &self.nodes[idx as usize].vector;
+        // Tombstones restored without a vector are pure routing hops
+        if vector.is_empty() {
+            return f32::MIN;
+        }
+        self.metric.score(query, vector)
+    }
+
+    /// Follow the best neighbour on `layer` until no improvement.
+    fn greedy_closest(&self, query: &[f32], start: u32, layer: usize) -> u32 {
+        let mut best = start;
+        let mut best_score = self.score(query, start);
+        loop {
+            let mut improved = false;
+            if let Some(neighbors) = self.nodes[best as usize].neighbors.get(layer) {
+                for &n in neighbors {
+                    let s = self.score(query, n);
+                    if s > best_score {
+                        best_score = s;
+                        best = n;
+                        improved = true;
+                    }
+                }
+            }
+            if !improved {
+                return best;
+            }
+        }
+    }
+
+    /// Beam search on one layer. Returns up to `ef` candidates, best first.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[u32],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Scored> {
+        let mut visited: HashSet<u32> = entry_points.iter().copied().collect();
+        // Max-heap of candidates to expand
+        let mut candidates: BinaryHeap<Scored> = BinaryHeap::new();
+        // Min-heap (via Reverse) of the current best `ef` results
+        let mut results: BinaryHeap<std::cmp::Reverse<Scored>> = BinaryHeap::new();
+
+        for &ep in entry_points {
+            let c = Scored {
+                score: self.score(query, ep),
+                idx: ep,
+            };
+            candidates.push(c);
+            results.push(std::cmp::Reverse(c));
+            if results.len() > ef {
+                results.pop();
+            }
+        }
+
+        while let Some(current) = candidates.pop() {
+            let worst = results.peek().map(|r| r.0.score).unwrap_or(f32::MIN);
+            if current.score < worst && results.len() >= ef {
+                break;
+            }
+            let Some(neighbors) = self.nodes[current.idx as usize].neighbors.get(layer) else {
+                continue;
+            };
+            for &n in neighbors {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let c = Scored {
+                    score: self.score(query, n),
+                    idx: n,
+                };
+                let worst = results.peek().map(|r| r.0.score).unwrap_or(f32::MIN);
+                if results.len() < ef || c.score > worst {
+                    candidates.push(c);
+                    results.push(std::cmp::Reverse(c));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        let mut out: Vec<Scored> = results.into_iter().map(|r| r.0).collect();
+        out.sort_by(|a, b| b.cmp(a));
+        out
+    }
+
+    /// Add `to` to `from`'s neighbour list on `layer`, pruning to the best
+    /// `max_connections` when full.
+    fn connect(&mut self, from: u32, to: u32, layer: usize) {
+        let max_conn = self.max_connections(layer);
+        if self.nodes[from as usize].neighbors.len() <= layer {
+            return;
+        }
+        if self.nodes[from as usize].neighbors[layer].contains(&to) {
+            return;
+        }
+        self.nodes[from as usize].neighbors[layer].push(to);
+        if self.nodes[from as usize].neighbors[layer].len() <= max_conn {
+            return;
+        }
+
+        let base = self.nodes[from as usize].vector.clone();
+        let mut scored: Vec<Scored> = self.nodes[from as usize].neighbors[layer]
+            .iter()
+            .map(|&n| Scored {
+                score: self.score(&base, n),
+                idx: n,
+            })
+            .collect();
+        scored.sort_by(|a, b| b.cmp(a));
+        scored.truncate(max_conn);
+        self.nodes[from as usize].neighbors[layer] = scored.into_iter().map(|c| c.idx).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(angle: f32) -> Embedding {
+        vec![angle.cos(), angle.sin()]
+    }
+
+    fn brute_force(vectors: &[(String, Embedding)], query: &[f32], k: usize) -> Vec<String> {
+        let mut scored: Vec<_> = vectors
+            .iter()
+            .map(|(id, v)| (id.clone(), SimilarityMetric::Cosine.score(query, v)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(k).map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn test_search_matches_brute_force() {
+        let mut index = HnswIndex::new(HnswConfig::default(), SimilarityMetric::Cosine);
+        let vectors: Vec<(String, Embedding)> = (0..500)
+            .map(|i| (format!("doc{}", i), unit(i as f32 * 0.0125)))
+            .collect();
+        for (id, v) in &vectors {
+            index.insert(id.clone(), v.clone());
+        }
+        assert_eq!(index.len(), 500);
+
+        let query = unit(1.0);
+        let got: Vec<String> = index
+            .search(&query, 5)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(got, brute_force(&vectors, &query, 5));
+    }
+
+    #[test]
+    fn test_remove_and_rebuild() {
+        let mut index = HnswIndex::new(HnswConfig::default(), SimilarityMetric::Cosine);
+        for i in 0..50 {
+            index.insert(format!("doc{}", i), unit(i as f32 * 0.1));
+        }
+
+        assert!(index.remove("doc10"));
+        assert!(!index.remove("doc10"));
+        assert!(!index.contains("doc10"));
+        assert_eq!(index.deleted_count(), 1);
+
+        let hits = index.search(&unit(1.0), 3);
+        assert!(hits.iter().all(|(id, _)| id != "doc10"));
+
+        let compacted = index.rebuilt();
+        assert_eq!(compacted.len(), 49);
+        assert_eq!(compacted.deleted_count(), 0);
+    }
+
+    #[test]
+    fn test_persist_roundtrip() {
+        let mut index = HnswIndex::new(HnswConfig::default(), SimilarityMetric::Cosine);
+        let mut vectors = HashMap::new();
+        for i in 0..100 {
+            let v = unit(i as f32 * 0.05);
+            vectors.insert(format!("doc{}", i), v.clone());
+            index.insert(format!("doc{}", i), v);
+        }
+
+        let bytes = index.to_bytes().unwrap();
+        let restored = HnswIndex::from_bytes(&bytes, SimilarityMetric::Cosine, &vectors).unwrap();
+        assert_eq!(restored.len(), 100);
+        assert_eq!(restored.search(&unit(2.0), 3), index.search(&unit(2.0), 3));
+
+        // Missing vectors are tombstoned, not fatal
+        vectors.remove("doc0");
+        let partial = HnswIndex::from_bytes(&bytes, SimilarityMetric::Cosine, &vectors).unwrap();
+        assert_eq!(partial.len(), 99);
+        assert!(!partial.contains("doc0"));
+    }
+}
@@ -44,40 +44,58 @@
 //! }
 //! ```
 
+pub mod admin;
 pub mod agents;
 pub mod atomic_write;
 pub mod business;
 pub mod dashboards;
 pub mod device_registry;
 pub mod error;
+pub mod event_log;
 pub mod extensions;
 pub mod frontend_components;
+#[cfg(feature = "hnsw")]
+pub mod hnsw;
 pub mod instances;
 pub mod llm_backends;
 pub mod memory_config;
 pub mod messages;
+pub mod query;
+pub mod reports;
 pub mod session;
 pub mod settings;
 pub mod system_memory;
 pub mod timeseries;
 pub mod vector;
+mod wal;
 
 // Re-exports
 pub use error::{Error, Result};
 
 pub use timeseries::{compress_series_adaptive, DataPoint, TimeSeriesStore};
 
-pub use vector::{VectorDocument, VectorStore};
+pub use query::{Query, QueryResult};
+
+pub use vector::{PersistentVectorStore, SearchOptions, SearchResult, VectorDocument, VectorStore};
 
 pub use session::{
-    PendingStreamState, SessionMessage, SessionMessageImage, SessionStore, StreamStage,
+    IntentExample, IntentFeedback, IntentFeedbackSource, PendingStreamState, SessionMessage,
+    SessionMessageImage, SessionMetadata, SessionStore, StreamStage,
 };
 
-pub use messages::{MessageStore, StoredMessage};
+pub use messages::{AlertGroupState, MessageStore, StoredMessage};
+
+pub use event_log::PersistentEventLog;
+
+pub use reports::{
+    ReportDelivery, ReportFormat, ReportRecord, ReportSection, ReportStore, ReportTemplate,
+};
 
 pub use settings::{
-    ExternalBroker, LlmBackendType, LlmSettings, MqttSettings, SecurityLevel, SettingsStore,
-    DEFAULT_GLOBAL_TIMEZONE,
+    ConfigChangeEntry, DeviceAuthMethod, DeviceClaim, DeviceIdentity, EnergyConfig, EnergyMeter,
+    EnergyMeterKind, EnergyTariff, ExternalBroker, KnowledgeConfig, LlmBackendType, LlmSettings,
+    MaintenanceRecurrence, MaintenanceWindow, MqttSettings, SecurityLevel, SettingsStore,
+    TariffPeriod, DEFAULT_GLOBAL_TIMEZONE,
 };
 
 pub use llm_backends::{
@@ -90,10 +108,11 @@ pub use extensions::{ExtensionRecord, ExtensionStore};
 
 pub use agents::{
     ActionExecuted, AgentExecutionRecord, AgentFilter, AgentMemory, AgentResource, AgentSchedule,
-    AgentStats, AgentStatus, AgentStore, AgentToolConfig, AiAgent, DataCollected, DataSummary,
-    Decision, DecisionProcess, ExecutionJournal, ExecutionMode, ExecutionRecord, ExecutionResult,
-    ExecutionStatus, GeneratedReport, IntentType, KnowledgeFileRef, NotificationSent, ParsedIntent,
-    ReasoningStep, ResourceType, ScheduleType, UserMessage,
+    AgentStats, AgentStatus, AgentStore, AgentToolConfig, AiAgent, ConcurrencyPolicy,
+    DataCollected, DataSummary, Decision, DecisionProcess, ExecutionJournal, ExecutionMode,
+    ExecutionRecord, ExecutionResult, ExecutionStatus, GeneratedReport, IntentType,
+    KnowledgeFileRef, NotificationSent, OverflowPolicy, ParsedIntent, ReasoningStep, ResourceType,
+    ScheduleType, UserMessage,
 };
 
 pub use device_registry::DeviceRegistryStore;
@@ -102,7 +121,7 @@ pub use device_registry::DeviceRegistryStore;
 pub use system_memory::{CategoryStats, MarkdownMemoryStore, MemoryCategory, MemoryFileInfo};
 
 // Memory configuration exports
-pub use memory_config::MemoryConfig;
+pub use memory_config::{MemoryConfig, SummarizerKind};
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -16,7 +16,8 @@ use crate::{settings::LlmBackendType, Error};
 // LLM backend instances table: key = instance_id, value = LlmBackendInstance (serialized)
 const LLM_BACKENDS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("llm_backends");
 
-// Active backend tracking: key = "active_backend", value = instance_id
+// Active backend tracking: key = "active_backend", value = instance_id;
+// key = "failover_chain", value = JSON array of fallback instance ids
 const ACTIVE_BACKEND_TABLE: TableDefinition<&str, &str> =
     TableDefinition::new("active_llm_backend");
 
@@ -680,6 +681,37 @@ impl LlmBackendStore {
         }
     }
 
+    /// Get the failover chain: backend ids tried in order after the active
+    /// backend fails
+    pub fn get_failover_chain(&self) -> Result<Vec<String>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(ACTIVE_BACKEND_TABLE)?;
+
+        match table.get("failover_chain")? {
+            Some(value) => serde_json::from_str(value.value())
+                .map_err(|e| Error::Serialization(e.to_string())),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Set the failover chain. An empty chain turns failover off.
+    pub fn set_failover_chain(&self, ids: &[String]) -> Result<(), Error> {
+        for id in ids {
+            if self.load_instance(id)?.is_none() {
+                return Err(Error::NotFound(format!("Backend instance {}", id)));
+            }
+        }
+        let value = serde_json::to_string(ids).map_err(|e| Error::Serialization(e.to_string()))?;
+
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(ACTIVE_BACKEND_TABLE)?;
+            table.insert("failover_chain", value.as_str())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
     /// Generate a unique ID for a new instance
     pub fn generate_id(prefix: &str) -> String {
         format!(
@@ -693,10 +725,12 @@ impl LlmBackendStore {
     pub fn export_instances(&self) -> Result<serde_json::Value, Error> {
         let instances = self.load_all_instances()?;
         let active_id = self.get_active_backend_id()?;
+        let failover_chain = self.get_failover_chain()?;
 
         Ok(serde_json::json!({
             "instances": instances,
             "active_id": active_id,
+            "failover_chain": failover_chain,
         }))
     }
 
@@ -718,6 +752,16 @@ impl LlmBackendStore {
             }
         }
 
+        if let Some(chain) = data.get("failover_chain").and_then(|v| v.as_array()) {
+            let ids: Vec<String> = chain
+                .iter()
+                .filter_map(|v| v.as_str())
+                .filter(|id| matches!(self.load_instance(id), Ok(Some(_))))
+                .map(str::to_string)
+                .collect();
+            self.set_failover_chain(&ids)?;
+        }
+
         Ok(())
     }
 
@@ -814,4 +858,22 @@ mod tests {
         assert_ne!(id1, id2);
         assert_eq!(id1.len(), "ollama_".len() + 8);
     }
+
+    #[test]
+    fn test_failover_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LlmBackendStore::open(dir.path().join("llm_backends.redb")).unwrap();
+        assert!(store.get_failover_chain().unwrap().is_empty());
+
+        let backup = LlmBackendInstance::new(
+            "backup".to_string(),
+            "Backup".to_string(),
+            LlmBackendType::Ollama,
+        );
+        store.save_instance(&backup).unwrap();
+        assert!(store.set_failover_chain(&["missing".to_string()]).is_err());
+
+        store.set_failover_chain(&["backup".to_string()]).unwrap();
+        assert_eq!(store.get_failover_chain().unwrap(), vec!["backup"]);
+    }
 }
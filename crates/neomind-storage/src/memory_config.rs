@@ -28,6 +28,10 @@ pub struct MemoryConfig {
     /// TTL in days for session temp files
     #[serde(default = "default_ttl")]
     pub temp_file_ttl_days: u64,
+    /// Days after which chat sessions are purged with their derived memories
+    /// (0 = keep forever)
+    #[serde(default)]
+    pub chat_retention_days: u64,
     /// Interval in seconds for system context resource inventory refresh
     #[serde(default = "default_context_interval")]
     pub system_context_interval_secs: u64,
@@ -37,6 +41,23 @@ pub struct MemoryConfig {
     /// LLM backend ID for summarization. None = use active backend.
     #[serde(default)]
     pub summary_backend_id: Option<String>,
+    /// How archived sessions are condensed into memory
+    #[serde(default)]
+    pub summarizer: SummarizerKind,
+    /// Sentences kept by the extractive summarizer
+    #[serde(default = "default_extractive_sentences")]
+    pub extractive_sentences: usize,
+}
+
+/// Session summarizer used for memory consolidation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummarizerKind {
+    /// The session's LLM writes a digest; extractive is the fallback
+    #[default]
+    Llm,
+    /// TextRank over the conversation's own sentences, no model call
+    Extractive,
 }
 
 fn default_enabled() -> bool {
@@ -66,6 +87,9 @@ fn default_context_interval() -> u64 {
 fn default_summary_interval() -> u64 {
     7200
 }
+fn default_extractive_sentences() -> usize {
+    4
+}
 
 impl Default for MemoryConfig {
     fn default() -> Self {
@@ -77,9 +101,12 @@ impl Default for MemoryConfig {
             procedures_char_limit: default_procedures_limit(),
             agent_char_limit: default_agent_limit(),
             temp_file_ttl_days: default_ttl(),
+            chat_retention_days: 0,
             system_context_interval_secs: default_context_interval(),
             summary_interval_secs: default_summary_interval(),
             summary_backend_id: None,
+            summarizer: SummarizerKind::default(),
+            extractive_sentences: default_extractive_sentences(),
         }
     }
 }
@@ -123,6 +150,8 @@ mod tests {
         assert_eq!(config.system_context_interval_secs, 600);
         assert_eq!(config.summary_interval_secs, 7200);
         assert!(config.summary_backend_id.is_none());
+        assert_eq!(config.summarizer, SummarizerKind::Llm);
+        assert_eq!(config.extractive_sentences, 4);
     }
 
     #[test]
@@ -5,6 +5,7 @@
 use std::path::Path;
 use std::sync::Arc;
 
+use neomind_core::tenant::TenantId;
 use redb::{Database, ReadableTable, TableDefinition};
 use serde::{Deserialize, Serialize};
 use serde_json;
@@ -21,6 +22,31 @@ const HISTORY_TABLE: TableDefinition<&str, &str> = TableDefinition::new("message
 // Active messages index: key = message_id, value = "1" if active
 const ACTIVE_TABLE: TableDefinition<&str, &str> = TableDefinition::new("messages_active");
 
+// Alert grouping state: key = fingerprint, value = AlertGroupState (JSON)
+const ALERT_GROUPS_TABLE: TableDefinition<&str, &str> = TableDefinition::new("alert_groups");
+
+/// Deduplication state for one alert fingerprint.
+///
+/// Repeats of an alert collapse onto `message_id` and bump `occurrences`;
+/// `pending_digest` counts repeats not yet reported in a digest, and
+/// `refires` holds recent times the alert fired again after being resolved,
+/// used to detect flapping.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertGroupState {
+    pub fingerprint: String,
+    /// Message that repeats collapse onto
+    pub message_id: String,
+    pub occurrences: u64,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    #[serde(default)]
+    pub pending_digest: u64,
+    #[serde(default)]
+    pub refires: Vec<i64>,
+    #[serde(default)]
+    pub flapping: bool,
+}
+
 /// Stored message representation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredMessage {
@@ -52,6 +78,9 @@ pub struct StoredMessage {
     pub resolved_at: Option<i64>,
     /// Who acknowledged it
     pub acknowledged_by: Option<String>,
+    /// Tenant that owns the message
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
 }
 
 impl StoredMessage {
@@ -79,6 +108,7 @@ impl StoredMessage {
             acknowledged_at: None,
             resolved_at: None,
             acknowledged_by: None,
+            tenant_id: TenantId::default(),
         }
     }
 
@@ -132,6 +162,9 @@ impl MessageStore {
             write_txn
                 .open_table(ACTIVE_TABLE)
                 .map_err(|e| Error::Storage(format!("Failed to open active table: {}", e)))?;
+            write_txn
+                .open_table(ALERT_GROUPS_TABLE)
+                .map_err(|e| Error::Storage(format!("Failed to open alert groups table: {}", e)))?;
         }
 
         write_txn
@@ -164,6 +197,9 @@ impl MessageStore {
             write_txn
                 .open_table(ACTIVE_TABLE)
                 .map_err(|e| Error::Storage(format!("Failed to open active table: {}", e)))?;
+            write_txn
+                .open_table(ALERT_GROUPS_TABLE)
+                .map_err(|e| Error::Storage(format!("Failed to open alert groups table: {}", e)))?;
         }
 
         write_txn
@@ -460,6 +496,96 @@ impl MessageStore {
             .map_err(|e| Error::Storage(format!("spawn_blocking list_active: {}", e)))?
     }
 
+    // ========================================================================
+    // Alert grouping state
+    // ========================================================================
+
+    /// Insert or replace the grouping state of a fingerprint.
+    pub fn save_alert_group(&self, group: &AlertGroupState) -> Result<(), Error> {
+        let write_txn = self
+            .db
+            .begin_write()
+            .map_err(|e| Error::Storage(format!("Failed to begin write: {}", e)))?;
+        let json = serde_json::to_string(group)
+            .map_err(|e| Error::Storage(format!("Failed to serialize alert group: {}", e)))?;
+        {
+            let mut table = write_txn
+                .open_table(ALERT_GROUPS_TABLE)
+                .map_err(|e| Error::Storage(format!("Failed to open alert groups table: {}", e)))?;
+            table
+                .insert(group.fingerprint.as_str(), json.as_str())
+                .map_err(|e| Error::Storage(format!("Failed to save alert group: {}", e)))?;
+        }
+        write_txn
+            .commit()
+            .map_err(|e| Error::Storage(format!("Failed to commit: {}", e)))?;
+        Ok(())
+    }
+
+    /// Load every alert group.
+    pub fn list_alert_groups(&self) -> Result<Vec<AlertGroupState>, Error> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| Error::Storage(format!("Failed to begin read: {}", e)))?;
+        let table = match read_txn.open_table(ALERT_GROUPS_TABLE) {
+            Ok(table) => table,
+            // Databases created before alert grouping existed
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(Error::Storage(format!(
+                    "Failed to open alert groups table: {}",
+                    e
+                )))
+            }
+        };
+        let mut groups = Vec::new();
+        for (_, value) in table
+            .iter()
+            .map_err(|e| Error::Storage(format!("Failed to iterate: {}", e)))?
+            .flatten()
+        {
+            match serde_json::from_str::<AlertGroupState>(value.value()) {
+                Ok(group) => groups.push(group),
+                Err(e) => tracing::warn!("Skipping malformed alert group: {}", e),
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Remove the grouping state of a fingerprint.
+    pub fn delete_alert_group(&self, fingerprint: &str) -> Result<bool, Error> {
+        let write_txn = self
+            .db
+            .begin_write()
+            .map_err(|e| Error::Storage(format!("Failed to begin write: {}", e)))?;
+        let removed = {
+            let mut table = write_txn
+                .open_table(ALERT_GROUPS_TABLE)
+                .map_err(|e| Error::Storage(format!("Failed to open alert groups table: {}", e)))?;
+            let removed = table
+                .remove(fingerprint)
+                .map_err(|e| Error::Storage(format!("Failed to delete alert group: {}", e)))?
+                .is_some();
+            removed
+        };
+        write_txn
+            .commit()
+            .map_err(|e| Error::Storage(format!("Failed to commit: {}", e)))?;
+        Ok(removed)
+    }
+
+    /// Async version of [`save_alert_group`](Self::save_alert_group).
+    pub async fn save_alert_group_async(
+        self: &Arc<Self>,
+        group: AlertGroupState,
+    ) -> Result<(), Error> {
+        let this = Arc::clone(self);
+        tokio::task::spawn_blocking(move || this.save_alert_group(&group))
+            .await
+            .map_err(|e| Error::Storage(format!("spawn_blocking save_alert_group: {}", e)))?
+    }
+
     /// List messages by category.
     pub fn list_by_category(&self, category: &str) -> Result<Vec<StoredMessage>, Error> {
         let all = self.list()?;
@@ -634,6 +760,24 @@ pub struct MessageStats {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_alert_groups() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MessageStore::open(dir.path().join("messages.redb")).unwrap();
+        let group = AlertGroupState {
+            fingerprint: "alert|device|sensor1|High temperature".to_string(),
+            message_id: "m1".to_string(),
+            occurrences: 3,
+            first_seen: 100,
+            last_seen: 300,
+            ..Default::default()
+        };
+        store.save_alert_group(&group).unwrap();
+        assert_eq!(store.list_alert_groups().unwrap(), vec![group.clone()]);
+        assert!(store.delete_alert_group(&group.fingerprint).unwrap());
+        assert!(store.list_alert_groups().unwrap().is_empty());
+    }
+
     #[test]
     fn test_stored_message_creation() {
         let msg = StoredMessage::new(
@@ -0,0 +1,946 @@
+//! SQL-like query language over the time series store.
+//!
+//! ```text
+//! SELECT avg(temperature), max(humidity) FROM device:sensor_1, device:sensor_2
+//! WHERE time > now() - 1h AND temperature > 0
+//! GROUP BY 5m
+//! ORDER BY time DESC
+//! LIMIT 100
+//! ```
+//!
+//! - `SELECT *` or a list of metrics returns raw points, one row per source
+//!   and timestamp. Aggregates (`avg`, `min`, `max`, `sum`, `count`, `first`,
+//!   `last`) return one row per source, or per source and bucket with
+//!   `GROUP BY <duration>` (also written `GROUP BY time(5m)`). Raw metrics and
+//!   aggregates can't be mixed.
+//! - Sources are DataSourceId prefixes (`device:<id>`, `extension:<id>`,
+//!   `transform:<id>`); a trailing `*` matches every stored source with that
+//!   prefix (`device:*`).
+//! - `time` bounds take `now() [+|- <duration>]`, unix seconds or an RFC 3339
+//!   string; without them the last 24 hours are queried. Any other condition
+//!   (`temperature > 30`) filters the points of the metric it names.
+//! - Durations are `<n>s`, `m`, `h`, `d` or `w`. `LIMIT` defaults to 1000.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::timeseries::{DataPoint, TimeSeriesStore};
+use crate::Error;
+
+/// Range queried when the query has no `time` bounds.
+const DEFAULT_RANGE_SECS: i64 = 86400;
+const DEFAULT_LIMIT: usize = 1000;
+const MAX_LIMIT: usize = 10_000;
+/// Sources a wildcard may expand to.
+const MAX_SOURCES: usize = 100;
+/// Points scanned per source and metric.
+const MAX_SCAN_POINTS: usize = 1_000_000;
+
+/// Aggregate function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregate {
+    Avg,
+    Min,
+    Max,
+    Sum,
+    Count,
+    First,
+    Last,
+}
+
+impl Aggregate {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "avg" | "mean" => Some(Self::Avg),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "sum" => Some(Self::Sum),
+            "count" => Some(Self::Count),
+            "first" => Some(Self::First),
+            "last" => Some(Self::Last),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Sum => "sum",
+            Self::Count => "count",
+            Self::First => "first",
+            Self::Last => "last",
+        }
+    }
+
+    /// Apply to points in ascending time order.
+    fn apply(&self, points: &[&DataPoint]) -> serde_json::Value {
+        match self {
+            Self::Count => return serde_json::json!(points.len()),
+            Self::First => return points.first().map(|p| p.value.clone()).unwrap_or_default(),
+            Self::Last => return points.last().map(|p| p.value.clone()).unwrap_or_default(),
+            _ => {}
+        }
+        let nums: Vec<f64> = points.iter().filter_map(|p| p.as_f64()).collect();
+        if nums.is_empty() {
+            return serde_json::Value::Null;
+        }
+        let value = match self {
+            Self::Avg => nums.iter().sum::<f64>() / nums.len() as f64,
+            Self::Min => nums.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Max => nums.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            _ => nums.iter().sum::<f64>(),
+        };
+        serde_json::json!(value)
+    }
+}
+
+/// One selected column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectItem {
+    pub aggregate: Option<Aggregate>,
+    pub metric: String,
+    pub alias: Option<String>,
+}
+
+impl SelectItem {
+    fn column(&self) -> String {
+        match (&self.alias, self.aggregate) {
+            (Some(alias), _) => alias.clone(),
+            (None, Some(agg)) => format!("{}({})", agg.name(), self.metric),
+            (None, None) => self.metric.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    fn matches(&self, a: f64, b: f64) -> bool {
+        match self {
+            Self::Gt => a > b,
+            Self::Ge => a >= b,
+            Self::Lt => a < b,
+            Self::Le => a <= b,
+            Self::Eq => a == b,
+            Self::Ne => a != b,
+        }
+    }
+}
+
+/// `<metric> <op> <number>` condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueFilter {
+    pub metric: String,
+    pub op: CompareOp,
+    pub value: f64,
+}
+
+/// A parsed query with its time bounds resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    /// `None` for `SELECT *`
+    pub select: Option<Vec<SelectItem>>,
+    pub sources: Vec<String>,
+    /// Inclusive range, unix seconds
+    pub start: i64,
+    pub end: i64,
+    pub filters: Vec<ValueFilter>,
+    /// Bucket size in seconds
+    pub group_by: Option<i64>,
+    pub descending: bool,
+    pub limit: usize,
+}
+
+impl Query {
+    /// Parse `input`, resolving `now()` to `now` (unix seconds).
+    pub fn parse(input: &str, now: i64) -> Result<Self, Error> {
+        let tokens = tokenize(input)?;
+        Parser {
+            tokens,
+            pos: 0,
+            now,
+        }
+        .parse()
+    }
+
+    fn is_aggregate(&self) -> bool {
+        self.select
+            .as_ref()
+            .is_some_and(|items| items.iter().any(|i| i.aggregate.is_some()))
+    }
+}
+
+/// Tabular query result. The first two columns are `time` and `source`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub start: i64,
+    pub end: i64,
+    /// More rows matched than `LIMIT` allowed
+    pub truncated: bool,
+}
+
+fn invalid(msg: impl Into<String>) -> Error {
+    Error::InvalidInput(msg.into())
+}
+
+fn out_of_range() -> Error {
+    invalid("Time out of range")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(f64),
+    /// Duration in seconds
+    Duration(i64),
+    LParen,
+    RParen,
+    Comma,
+    Star,
+    Plus,
+    Minus,
+    Op(CompareOp),
+}
+
+fn duration_secs(n: f64, unit: &str) -> Option<i64> {
+    let scale = match unit {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        "d" => 86400.0,
+        "w" => 604800.0,
+        _ => return None,
+    };
+    to_secs((n * scale).trunc())
+}
+
+/// `n` as whole seconds, or `None` if it is fractional or outside the i64
+/// range (`as` would saturate it silently).
+fn to_secs(n: f64) -> Option<i64> {
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0; // 2^63
+    (n.fract() == 0.0 && (-LIMIT..LIMIT).contains(&n)).then_some(n as i64)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                i += 1;
+            }
+            '+' => {
+                tokens.push(Token::Plus);
+                i += 1;
+            }
+            '-' => {
+                tokens.push(Token::Minus);
+                i += 1;
+            }
+            ';' => i += 1,
+            '>' | '<' | '=' | '!' => {
+                let next = chars.get(i + 1).copied();
+                let (op, len) = match (c, next) {
+                    ('>', Some('=')) => (CompareOp::Ge, 2),
+                    ('<', Some('=')) => (CompareOp::Le, 2),
+                    ('<', Some('>')) | ('!', Some('=')) => (CompareOp::Ne, 2),
+                    ('>', _) => (CompareOp::Gt, 1),
+                    ('<', _) => (CompareOp::Lt, 1),
+                    ('=', Some('=')) => (CompareOp::Eq, 2),
+                    ('=', _) => (CompareOp::Eq, 1),
+                    _ => return Err(invalid(format!("Unexpected '{}' at {}", c, i))),
+                };
+                tokens.push(Token::Op(op));
+                i += len;
+            }
+            '\'' | '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or_else(|| invalid(format!("Unterminated string at {}", i)))?;
+                tokens.push(Token::Str(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let number: String = chars[start..i].iter().collect();
+                let n: f64 = number
+                    .parse()
+                    .map_err(|_| invalid(format!("Invalid number '{}'", number)))?;
+                let unit_start = i;
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                if unit_start == i {
+                    tokens.push(Token::Number(n));
+                } else {
+                    let unit: String = chars[unit_start..i].iter().collect();
+                    let secs = duration_secs(n, &unit.to_ascii_lowercase())
+                        .ok_or_else(|| invalid(format!("Invalid duration '{}{}'", number, unit)))?;
+                    tokens.push(Token::Duration(secs));
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.' | ':' | '-'))
+                {
+                    i += 1;
+                }
+                // Trailing wildcard on a source prefix: `device:*`
+                if chars.get(i) == Some(&'*') && chars[i - 1] == ':' {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            _ => return Err(invalid(format!("Unexpected '{}' at {}", c, i))),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    now: i64,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s)) if s.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(invalid(format!("Expected {}", keyword)))
+        }
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), Error> {
+        if self.advance() == Some(token) {
+            Ok(())
+        } else {
+            Err(invalid(format!("Expected {}", what)))
+        }
+    }
+
+    fn name(&mut self, what: &str) -> Result<String, Error> {
+        match self.advance() {
+            Some(Token::Ident(s)) | Some(Token::Str(s)) => Ok(s),
+            _ => Err(invalid(format!("Expected {}", what))),
+        }
+    }
+
+    fn parse(mut self) -> Result<Query, Error> {
+        self.expect_keyword("SELECT")?;
+        let select = if self.peek() == Some(&Token::Star) {
+            self.pos += 1;
+            None
+        } else {
+            let mut items = vec![self.select_item()?];
+            while self.peek() == Some(&Token::Comma) {
+                self.pos += 1;
+                items.push(self.select_item()?);
+            }
+            Some(items)
+        };
+
+        self.expect_keyword("FROM")?;
+        let mut sources = vec![self.source()?];
+        while self.peek() == Some(&Token::Comma) {
+            self.pos += 1;
+            sources.push(self.source()?);
+        }
+
+        let mut start = None;
+        let mut end = None;
+        let mut filters = Vec::new();
+        if self.eat_keyword("WHERE") {
+            loop {
+                self.condition(&mut start, &mut end, &mut filters)?;
+                if !self.eat_keyword("AND") {
+                    break;
+                }
+            }
+        }
+
+        let mut group_by = None;
+        if self.eat_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            let wrapped = self.eat_keyword("time");
+            if wrapped {
+                self.expect(Token::LParen, "'(' after time")?;
+            }
+            match self.advance() {
+                Some(Token::Duration(secs)) if secs > 0 => group_by = Some(secs),
+                _ => return Err(invalid("Expected a duration after GROUP BY, e.g. 5m")),
+            }
+            if wrapped {
+                self.expect(Token::RParen, "')'")?;
+            }
+        }
+
+        let mut descending = false;
+        if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            self.expect_keyword("time")?;
+            if self.eat_keyword("DESC") {
+                descending = true;
+            } else {
+                self.eat_keyword("ASC");
+            }
+        }
+
+        let mut limit = DEFAULT_LIMIT;
+        if self.eat_keyword("LIMIT") {
+            match self.advance() {
+                Some(Token::Number(n)) if n >= 1.0 => limit = (n as usize).min(MAX_LIMIT),
+                _ => return Err(invalid("Expected a positive number after LIMIT")),
+            }
+        }
+
+        if let Some(token) = self.peek() {
+            return Err(invalid(format!("Unexpected {:?}", token)));
+        }
+
+        let end = end.unwrap_or(self.now);
+        let start = start.unwrap_or(end.saturating_sub(DEFAULT_RANGE_SECS));
+        if end < start {
+            return Err(invalid("Time range is empty"));
+        }
+
+        let query = Query {
+            select,
+            sources,
+            start,
+            end,
+            filters,
+            group_by,
+            descending,
+            limit,
+        };
+        if let Some(items) = &query.select {
+            if query.is_aggregate() && items.iter().any(|i| i.aggregate.is_none()) {
+                return Err(invalid(
+                    "Cannot mix aggregates and raw metrics; wrap every metric in a function",
+                ));
+            }
+        }
+        if query.group_by.is_some() && !query.is_aggregate() {
+            return Err(invalid(
+                "GROUP BY needs aggregate functions, e.g. avg(temperature)",
+            ));
+        }
+        Ok(query)
+    }
+
+    fn select_item(&mut self) -> Result<SelectItem, Error> {
+        let name = self.name("a metric or function")?;
+        let (aggregate, metric) = if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let aggregate = Aggregate::parse(&name)
+                .ok_or_else(|| invalid(format!("Unknown function '{}'", name)))?;
+            if self.peek() == Some(&Token::Star) {
+                return Err(invalid(format!(
+                    "{}(*) is not supported; name a metric",
+                    aggregate.name()
+                )));
+            }
+            let metric = self.name("a metric")?;
+            self.expect(Token::RParen, "')'")?;
+            (Some(aggregate), metric)
+        } else {
+            (None, name)
+        };
+        let alias = if self.eat_keyword("AS") {
+            Some(self.name("an alias")?)
+        } else {
+            None
+        };
+        Ok(SelectItem {
+            aggregate,
+            metric,
+            alias,
+        })
+    }
+
+    fn source(&mut self) -> Result<String, Error> {
+        let source = self.name("a source such as device:<id>")?;
+        match source.split_once(':') {
+            Some((kind, id)) if !kind.is_empty() && !id.is_empty() => Ok(source),
+            _ => Err(invalid(format!(
+                "Invalid source '{}'; use device:<id>, extension:<id> or device:*",
+                source
+            ))),
+        }
+    }
+
+    fn condition(
+        &mut self,
+        start: &mut Option<i64>,
+        end: &mut Option<i64>,
+        filters: &mut Vec<ValueFilter>,
+    ) -> Result<(), Error> {
+        let lhs = self.name("a condition")?;
+        let op = match self.advance() {
+            Some(Token::Op(op)) => op,
+            _ => return Err(invalid(format!("Expected a comparison after '{}'", lhs))),
+        };
+
+        if lhs.eq_ignore_ascii_case("time") {
+            let t = self.time_expr()?;
+            let (lo, hi) = match op {
+                CompareOp::Gt => (Some(t.checked_add(1).ok_or_else(out_of_range)?), None),
+                CompareOp::Ge => (Some(t), None),
+                CompareOp::Lt => (None, Some(t.checked_sub(1).ok_or_else(out_of_range)?)),
+                CompareOp::Le => (None, Some(t)),
+                CompareOp::Eq => (Some(t), Some(t)),
+                CompareOp::Ne => return Err(invalid("time != is not supported")),
+            };
+            if let Some(lo) = lo {
+                *start = Some(start.map_or(lo, |s| s.max(lo)));
+            }
+            if let Some(hi) = hi {
+                *end = Some(end.map_or(hi, |e| e.min(hi)));
+            }
+            return Ok(());
+        }
+
+        let negative = self.peek() == Some(&Token::Minus);
+        if negative {
+            self.pos += 1;
+        }
+        let value = match self.advance() {
+            Some(Token::Number(n)) => n,
+            _ => {
+                return Err(invalid(format!(
+                    "Expected a number to compare '{}' with",
+                    lhs
+                )))
+            }
+        };
+        filters.push(ValueFilter {
+            metric: lhs,
+            op,
+            value: if negative { -value } else { value },
+        });
+        Ok(())
+    }
+
+    fn time_expr(&mut self) -> Result<i64, Error> {
+        match self.advance() {
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case("now") => {
+                self.expect(Token::LParen, "'(' after now")?;
+                self.expect(Token::RParen, "')'")?;
+                let sign: i64 = match self.peek() {
+                    Some(Token::Plus) => 1,
+                    Some(Token::Minus) => -1,
+                    _ => return Ok(self.now),
+                };
+                self.pos += 1;
+                match self.advance() {
+                    Some(Token::Duration(secs)) => sign
+                        .checked_mul(secs)
+                        .and_then(|offset| self.now.checked_add(offset))
+                        .ok_or_else(out_of_range),
+                    _ => Err(invalid("Expected a duration after now() +/-, e.g. 1h")),
+                }
+            }
+            Some(Token::Number(n)) => to_secs(n)
+                .ok_or_else(|| invalid(format!("Invalid time {}: expected whole unix seconds", n))),
+            Some(Token::Str(s)) => chrono::DateTime::parse_from_rfc3339(&s)
+                .map(|t| t.timestamp())
+                .map_err(|e| invalid(format!("Invalid time '{}': {}", s, e))),
+            _ => Err(invalid(
+                "Expected now(), unix seconds or an RFC 3339 string",
+            )),
+        }
+    }
+}
+
+/// Run a parsed query.
+pub async fn execute(store: &TimeSeriesStore, query: &Query) -> Result<QueryResult, Error> {
+    execute_where(store, query, |_| true).await
+}
+
+/// Run a parsed query over the sources `allowed` accepts.
+///
+/// Rejected sources are treated like sources without data: wildcards skip
+/// them and named ones are queried as empty, so the result doesn't reveal
+/// whether they exist.
+pub async fn execute_where(
+    store: &TimeSeriesStore,
+    query: &Query,
+    allowed: impl Fn(&str) -> bool,
+) -> Result<QueryResult, Error> {
+    // Make buffered writes visible to range scans.
+    store.flush()?;
+
+    let mut sources: Vec<String> = Vec::new();
+    let mut hidden: Vec<String> = Vec::new();
+    let grouped = if query.sources.iter().any(|s| s.ends_with('*')) {
+        Some(store.list_all_metrics_grouped().await?)
+    } else {
+        None
+    };
+    for pattern in &query.sources {
+        let wildcard = pattern.ends_with('*');
+        let matched = match (pattern.strip_suffix('*'), &grouped) {
+            (Some(prefix), Some(grouped)) => {
+                let mut matched: Vec<String> = grouped
+                    .keys()
+                    .filter(|s| s.starts_with(prefix))
+                    .cloned()
+                    .collect();
+                matched.sort();
+                matched
+            }
+            _ => vec![pattern.clone()],
+        };
+        for source in matched {
+            if sources.contains(&source) {
+                continue;
+            }
+            if !allowed(&source) {
+                if wildcard {
+                    continue;
+                }
+                hidden.push(source.clone());
+            }
+            sources.push(source);
+        }
+    }
+    if sources.len() > MAX_SOURCES {
+        return Err(invalid(format!(
+            "Query matches {} sources (max {}); narrow the FROM clause",
+            sources.len(),
+            MAX_SOURCES
+        )));
+    }
+
+    let mut columns = vec!["time".to_string(), "source".to_string()];
+    let mut rows: Vec<Vec<serde_json::Value>> = Vec::new();
+
+    match &query.select {
+        Some(items) if query.is_aggregate() => {
+            columns.extend(items.iter().map(SelectItem::column));
+            for source in &sources {
+                let mut series: BTreeMap<&str, Vec<DataPoint>> = BTreeMap::new();
+                for item in items {
+                    if !series.contains_key(item.metric.as_str()) {
+                        let points = if hidden.contains(source) {
+                            Vec::new()
+                        } else {
+                            scan(store, query, source, &item.metric).await?
+                        };
+                        series.insert(&item.metric, points);
+                    }
+                }
+
+                // Bucket start -> per-item point refs
+                let bucket_of = |ts: i64| match query.group_by {
+                    Some(size) => ts - ts.rem_euclid(size),
+                    None => query.start,
+                };
+                let mut buckets: BTreeMap<i64, Vec<Vec<&DataPoint>>> = BTreeMap::new();
+                for (i, item) in items.iter().enumerate() {
+                    for point in &series[item.metric.as_str()] {
+                        buckets
+                            .entry(bucket_of(point.timestamp))
+                            .or_insert_with(|| vec![Vec::new(); items.len()])[i]
+                            .push(point);
+                    }
+                }
+                if query.group_by.is_none() && buckets.is_empty() {
+                    buckets.insert(query.start, vec![Vec::new(); items.len()]);
+                }
+
+                for (time, per_item) in buckets {
+                    let mut row = vec![serde_json::json!(time), serde_json::json!(source)];
+                    for (item, points) in items.iter().zip(&per_item) {
+                        let aggregate = item.aggregate.unwrap_or(Aggregate::Last);
+                        row.push(aggregate.apply(points));
+                    }
+                    rows.push(row);
+                }
+            }
+        }
+        select => {
+            let metrics: Vec<String> = match select {
+                Some(items) => items.iter().map(|i| i.metric.clone()).collect(),
+                None => Vec::new(),
+            };
+            if let Some(items) = select {
+                columns.extend(items.iter().map(SelectItem::column));
+            }
+            // SELECT * takes the union of every source's metrics as columns.
+            let mut per_source = Vec::new();
+            for source in &sources {
+                let source_metrics = if hidden.contains(source) {
+                    Vec::new()
+                } else if select.is_none() {
+                    store.list_metrics(source).await?
+                } else {
+                    metrics.clone()
+                };
+                for metric in &source_metrics {
+                    if select.is_none() && !columns.contains(metric) {
+                        columns.push(metric.clone());
+                    }
+                }
+                per_source.push((source, source_metrics));
+            }
+
+            for (source, source_metrics) in per_source {
+                let mut by_time: BTreeMap<i64, Vec<serde_json::Value>> = BTreeMap::new();
+                for (i, metric) in source_metrics.iter().enumerate() {
+                    let column = if select.is_none() {
+                        columns.iter().position(|c| c == metric).unwrap_or(0)
+                    } else {
+                        i + 2
+                    };
+                    for point in scan(store, query, source, metric).await? {
+                        by_time.entry(point.timestamp).or_insert_with(|| {
+                            let mut row = vec![serde_json::Value::Null; columns.len()];
+                            row[0] = serde_json::json!(point.timestamp);
+                            row[1] = serde_json::json!(source);
+                            row
+                        })[column] = point.value;
+                    }
+                }
+                rows.extend(by_time.into_values());
+            }
+        }
+    }
+
+    rows.sort_by_key(|row| row[0].as_i64().unwrap_or(0));
+    if query.descending {
+        rows.reverse();
+    }
+    let truncated = rows.len() > query.limit;
+    rows.truncate(query.limit);
+
+    Ok(QueryResult {
+        columns,
+        rows,
+        start: query.start,
+        end: query.end,
+        truncated,
+    })
+}
+
+/// Points of one metric in range, with the query's value filters applied.
+async fn scan(
+    store: &TimeSeriesStore,
+    query: &Query,
+    source: &str,
+    metric: &str,
+) -> Result<Vec<DataPoint>, Error> {
+    let mut points = store
+        .query_range(
+            source,
+            metric,
+            query.start,
+            query.end,
+            Some(MAX_SCAN_POINTS + 1),
+        )
+        .await?
+        .points;
+    if points.len() > MAX_SCAN_POINTS {
+        return Err(invalid(format!(
+            "{}:{} has more than {} points in range; narrow the time range",
+            source, metric, MAX_SCAN_POINTS
+        )));
+    }
+    let filters: Vec<&ValueFilter> = query
+        .filters
+        .iter()
+        .filter(|f| f.metric == metric)
+        .collect();
+    if !filters.is_empty() {
+        points.retain(|p| {
+            p.as_f64()
+                .is_some_and(|v| filters.iter().all(|f| f.op.matches(v, f.value)))
+        });
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Multiple of 300 so `GROUP BY 5m` buckets line up with the test data.
+    const NOW: i64 = 1_700_000_100;
+
+    #[test]
+    fn test_parse() {
+        let query = Query::parse(
+            "SELECT avg(temperature) AS t, max(humidity) FROM device:sensor_1 \
+             WHERE time > now()-1h AND temperature >= -5 GROUP BY 5m ORDER BY time DESC LIMIT 10",
+            NOW,
+        )
+        .unwrap();
+        let items = query.select.as_ref().unwrap();
+        assert_eq!(items[0].column(), "t");
+        assert_eq!(items[1].column(), "max(humidity)");
+        assert_eq!(query.sources, vec!["device:sensor_1"]);
+        assert_eq!((query.start, query.end), (NOW - 3600 + 1, NOW));
+        assert_eq!(
+            query.filters,
+            vec![ValueFilter {
+                metric: "temperature".to_string(),
+                op: CompareOp::Ge,
+                value: -5.0
+            }]
+        );
+        assert_eq!(query.group_by, Some(300));
+        assert!(query.descending);
+        assert_eq!(query.limit, 10);
+
+        let query = Query::parse("select * from device:*", NOW).unwrap();
+        assert_eq!(query.select, None);
+        assert_eq!(query.sources, vec!["device:*"]);
+        assert_eq!(query.start, NOW - DEFAULT_RANGE_SECS);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in [
+            "SELECT temperature, avg(humidity) FROM device:a",
+            "SELECT temperature FROM device:a GROUP BY 5m",
+            "SELECT median(t) FROM device:a",
+            "SELECT count(*) FROM device:a",
+            "SELECT t FROM sensor",
+            "SELECT t FROM device:a WHERE time > now() - 1y",
+            "SELECT t FROM device:a LIMIT",
+            "SELECT t FROM device:a WHERE time < now() + 1000000000000000000000000000000s",
+        ] {
+            assert!(Query::parse(bad, NOW).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_time_bounds() {
+        // Literals that don't fit an i64 or aren't whole seconds
+        for bad in [
+            "SELECT t FROM device:a WHERE time > 9223372036854775807",
+            "SELECT t FROM device:a WHERE time > 99999999999999999999",
+            "SELECT t FROM device:a WHERE time > 1700000000.5",
+        ] {
+            assert!(Query::parse(bad, NOW).is_err(), "{}", bad);
+        }
+
+        // Strict bounds at either end of the i64 range
+        assert!(Query::parse("SELECT t FROM device:a WHERE time > now()", i64::MAX).is_err());
+        assert!(Query::parse("SELECT t FROM device:a WHERE time < now()", i64::MIN).is_err());
+        let query = Query::parse("SELECT t FROM device:a WHERE time >= now()", i64::MAX).unwrap();
+        assert_eq!(query.start, i64::MAX);
+        let query = Query::parse("SELECT t FROM device:a WHERE time <= now()", i64::MIN).unwrap();
+        assert_eq!(query.end, i64::MIN);
+    }
+
+    #[tokio::test]
+    async fn test_execute() {
+        let store = TimeSeriesStore::memory().unwrap();
+        for i in 0..10i64 {
+            let ts = NOW - 600 + i * 60;
+            store
+                .write("device:s1", "temp", DataPoint::new(ts, i as f64))
+                .await
+                .unwrap();
+            store
+                .write("device:s2", "temp", DataPoint::new(ts, 100.0))
+                .await
+                .unwrap();
+        }
+
+        let query = Query::parse(
+            "SELECT avg(temp), count(temp) FROM device:* WHERE time >= now() - 10m GROUP BY 5m",
+            NOW,
+        )
+        .unwrap();
+        let result = execute(&store, &query).await.unwrap();
+        assert_eq!(
+            result.columns,
+            vec!["time", "source", "avg(temp)", "count(temp)"]
+        );
+        let s1: Vec<_> = result.rows.iter().filter(|r| r[1] == "device:s1").collect();
+        assert_eq!(s1.len(), 2);
+        assert_eq!(s1[0][3], serde_json::json!(5));
+        assert_eq!(s1[0][2], serde_json::json!(2.0));
+
+        let query = Query::parse(
+            "SELECT temp FROM device:s1 WHERE temp > 7 ORDER BY time DESC",
+            NOW,
+        )
+        .unwrap();
+        let result = execute(&store, &query).await.unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0][2], serde_json::json!(9.0));
+
+        // A rejected source reads exactly like one that was never written
+        let query = Query::parse("SELECT count(temp) FROM device:*, device:s2", NOW).unwrap();
+        let result = execute_where(&store, &query, |source| source == "device:s1")
+            .await
+            .unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0][2], serde_json::json!(10));
+        assert_eq!(result.rows[1][1], "device:s2");
+        assert_eq!(result.rows[1][2], serde_json::json!(0));
+    }
+}
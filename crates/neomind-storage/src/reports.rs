@@ -0,0 +1,377 @@
+//! Report template storage using redb.
+//!
+//! A [`ReportTemplate`] describes what a report contains (devices, metrics,
+//! look-back range, charts), how it is rendered, when it runs and which
+//! message channels receive it. Every generated report is recorded as a
+//! [`ReportRecord`] with its delivery outcome; the rendered file itself is
+//! kept on disk by the report manager in the API crate.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use neomind_core::tenant::TenantId;
+use redb::{Database, ReadableTable, TableDefinition};
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// JSON values keyed by id.
+type JsonTable = TableDefinition<'static, &'static str, &'static [u8]>;
+
+// Templates table: key = template_id, value = ReportTemplate (JSON)
+const TEMPLATES_TABLE: JsonTable = TableDefinition::new("report_templates");
+
+// Generated reports table: key = report_id, value = ReportRecord (JSON)
+const REPORTS_TABLE: JsonTable = TableDefinition::new("report_records");
+
+/// Output format of a rendered report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Html,
+    Pdf,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Pdf => "pdf",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Html => "text/html; charset=utf-8",
+            Self::Pdf => "application/pdf",
+        }
+    }
+}
+
+/// One block of a report: a set of devices and the metrics to summarize.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSection {
+    pub title: String,
+    pub device_ids: Vec<String>,
+    /// Metrics to include; empty includes every numeric metric stored for
+    /// each device
+    #[serde(default)]
+    pub metrics: Vec<String>,
+    /// Draw a line chart per metric next to the summary table
+    #[serde(default = "default_true")]
+    pub chart: bool,
+}
+
+/// A report definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportTemplate {
+    /// Generated on creation when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub sections: Vec<ReportSection>,
+    /// Look-back window ending at generation time, in seconds
+    #[serde(default = "default_range_secs")]
+    pub range_secs: u64,
+    #[serde(default)]
+    pub format: ReportFormat,
+    /// Cron expression (seconds field first, UTC); `None` runs on demand only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Message channels that receive each generated report
+    #[serde(default)]
+    pub channels: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<i64>,
+    /// Tenant that owns this template; its sections only name that tenant's
+    /// devices.
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_range_secs() -> u64 {
+    86_400
+}
+
+impl ReportTemplate {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        if self.sections.is_empty() {
+            return Err("a report needs at least one section".to_string());
+        }
+        if let Some(section) = self.sections.iter().find(|s| s.device_ids.is_empty()) {
+            return Err(format!("section '{}' has no devices", section.title));
+        }
+        if self.range_secs == 0 {
+            return Err("range_secs must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of sending a report through one channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportDelivery {
+    pub channel: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A generated report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportRecord {
+    pub id: String,
+    pub template_id: String,
+    pub template_name: String,
+    pub format: ReportFormat,
+    /// Covered range (unix seconds)
+    pub range_start: i64,
+    pub range_end: i64,
+    pub generated_at: i64,
+    pub size_bytes: u64,
+    /// "schedule" or "manual"
+    pub trigger: String,
+    #[serde(default)]
+    pub deliveries: Vec<ReportDelivery>,
+    /// Tenant of the template that produced this report.
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
+}
+
+impl ReportRecord {
+    pub fn file_name(&self) -> String {
+        format!("report-{}.{}", self.id, self.format.extension())
+    }
+}
+
+/// Persistent store for report templates and generated report records.
+pub struct ReportStore {
+    db: Arc<Database>,
+}
+
+impl ReportStore {
+    /// Open or create a report store at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Arc<Self>, Error> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let db = Database::create(path)?;
+        Ok(Arc::new(Self { db: Arc::new(db) }))
+    }
+
+    /// Create a store in a temporary file for testing.
+    pub fn memory() -> Result<Arc<Self>, Error> {
+        let temp_path =
+            std::env::temp_dir().join(format!("reports_test_{}.redb", uuid::Uuid::new_v4()));
+        Self::open(temp_path)
+    }
+
+    pub fn save_template(&self, template: &ReportTemplate) -> Result<(), Error> {
+        self.put(TEMPLATES_TABLE, &template.id, template)
+    }
+
+    pub fn get_template(&self, id: &str) -> Result<Option<ReportTemplate>, Error> {
+        self.get(TEMPLATES_TABLE, id)
+    }
+
+    /// All templates, sorted by name.
+    pub fn list_templates(&self) -> Result<Vec<ReportTemplate>, Error> {
+        let mut templates: Vec<ReportTemplate> = self.list(TEMPLATES_TABLE)?;
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(templates)
+    }
+
+    /// Returns whether the template existed.
+    pub fn delete_template(&self, id: &str) -> Result<bool, Error> {
+        self.remove(TEMPLATES_TABLE, id)
+    }
+
+    pub fn save_report(&self, report: &ReportRecord) -> Result<(), Error> {
+        self.put(REPORTS_TABLE, &report.id, report)
+    }
+
+    pub fn get_report(&self, id: &str) -> Result<Option<ReportRecord>, Error> {
+        self.get(REPORTS_TABLE, id)
+    }
+
+    /// Generated reports, newest first, optionally for one template.
+    pub fn list_reports(&self, template_id: Option<&str>) -> Result<Vec<ReportRecord>, Error> {
+        let mut reports: Vec<ReportRecord> = self.list(REPORTS_TABLE)?;
+        reports.retain(|r| template_id.is_none_or(|id| r.template_id == id));
+        reports.sort_by_key(|r| std::cmp::Reverse(r.generated_at));
+        Ok(reports)
+    }
+
+    /// Returns whether the record existed.
+    pub fn delete_report(&self, id: &str) -> Result<bool, Error> {
+        self.remove(REPORTS_TABLE, id)
+    }
+
+    fn put<T: Serialize>(&self, table_def: JsonTable, key: &str, value: &T) -> Result<(), Error> {
+        let bytes = serde_json::to_vec(value)?;
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(table_def)?;
+            table.insert(key, bytes.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    fn get<T: for<'de> Deserialize<'de>>(
+        &self,
+        table_def: JsonTable,
+        key: &str,
+    ) -> Result<Option<T>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(table_def) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        match table.get(key)? {
+            Some(value) => Ok(Some(serde_json::from_slice(value.value())?)),
+            None => Ok(None),
+        }
+    }
+
+    fn list<T: for<'de> Deserialize<'de>>(&self, table_def: JsonTable) -> Result<Vec<T>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(table_def) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut items = Vec::new();
+        for result in table.iter()? {
+            let (_key, value) = result?;
+            match serde_json::from_slice(value.value()) {
+                Ok(item) => items.push(item),
+                Err(e) => tracing::warn!("Skipping unreadable report entry: {}", e),
+            }
+        }
+        Ok(items)
+    }
+
+    fn remove(&self, table_def: JsonTable, key: &str) -> Result<bool, Error> {
+        let write_txn = self.db.begin_write()?;
+        let existed = {
+            let mut table = write_txn.open_table(table_def)?;
+            let removed = table.remove(key)?;
+            removed.is_some()
+        };
+        write_txn.commit()?;
+        Ok(existed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(id: &str, name: &str) -> ReportTemplate {
+        ReportTemplate {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            sections: vec![ReportSection {
+                title: "Boilers".to_string(),
+                device_ids: vec!["boiler-1".to_string()],
+                metrics: vec![],
+                chart: true,
+            }],
+            range_secs: 86_400,
+            format: ReportFormat::Html,
+            schedule: Some("0 0 6 * * *".to_string()),
+            channels: vec![],
+            enabled: true,
+            created_at: 0,
+            updated_at: 0,
+            last_run_at: None,
+            tenant_id: TenantId::default(),
+        }
+    }
+
+    fn record(id: &str, template_id: &str, generated_at: i64) -> ReportRecord {
+        ReportRecord {
+            id: id.to_string(),
+            template_id: template_id.to_string(),
+            template_name: "Daily".to_string(),
+            format: ReportFormat::Pdf,
+            range_start: generated_at - 86_400,
+            range_end: generated_at,
+            generated_at,
+            size_bytes: 1024,
+            trigger: "schedule".to_string(),
+            deliveries: vec![],
+            tenant_id: TenantId::default(),
+        }
+    }
+
+    #[test]
+    fn test_template_roundtrip() {
+        let store = ReportStore::memory().unwrap();
+        store.save_template(&template("t2", "Weekly")).unwrap();
+        store.save_template(&template("t1", "Daily")).unwrap();
+
+        let names: Vec<_> = store
+            .list_templates()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, ["Daily", "Weekly"]);
+        let daily = store.get_template("t1").unwrap().unwrap();
+        assert_eq!(daily.schedule.as_deref(), Some("0 0 6 * * *"));
+
+        assert!(store.delete_template("t1").unwrap());
+        assert!(!store.delete_template("t1").unwrap());
+        assert!(store.get_template("t1").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_reports_newest_first() {
+        let store = ReportStore::memory().unwrap();
+        store.save_report(&record("r1", "t1", 100)).unwrap();
+        store.save_report(&record("r2", "t2", 300)).unwrap();
+        store.save_report(&record("r3", "t1", 200)).unwrap();
+
+        let ids: Vec<_> = store
+            .list_reports(None)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, ["r2", "r3", "r1"]);
+        assert_eq!(store.list_reports(Some("t1")).unwrap().len(), 2);
+        assert_eq!(store.get_report("r1").unwrap().unwrap().file_name(), "report-r1.pdf");
+    }
+
+    #[test]
+    fn test_template_validation() {
+        assert!(template("t", "Daily").validate().is_ok());
+
+        let mut t = template("t", " ");
+        assert!(t.validate().is_err());
+        t.name = "Daily".to_string();
+        t.sections[0].device_ids.clear();
+        assert!(t.validate().unwrap_err().contains("Boilers"));
+    }
+}
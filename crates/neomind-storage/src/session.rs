@@ -5,6 +5,7 @@
 //! NOTE: Uses JSON serialization instead of Bincode for better schema compatibility.
 //! JSON is more forgiving when fields are added/removed from structs over time.
 
+use neomind_core::tenant::TenantId;
 use parking_lot::Mutex;
 use std::path::Path;
 use std::sync::Arc;
@@ -29,6 +30,10 @@ const HISTORY_TABLE: TableDefinition<(&str, u64), Vec<u8>> = TableDefinition::ne
 const PENDING_STREAM_TABLE: TableDefinition<&str, Vec<u8>> =
     TableDefinition::new("pending_streams");
 
+// Intent examples: key = example id, value = IntentExample (JSON)
+const INTENT_EXAMPLES_TABLE: TableDefinition<&str, Vec<u8>> =
+    TableDefinition::new("intent_examples");
+
 /// Default value for memory_enabled: true.
 const fn default_memory_enabled() -> bool {
     true
@@ -52,6 +57,27 @@ pub struct SessionMetadata {
     /// Preview text derived from the first user message (truncated)
     #[serde(default)]
     pub preview: Option<String>,
+    /// Session this one was forked from
+    #[serde(default)]
+    pub parent_session_id: Option<String>,
+    /// Index of the last parent message copied into this session
+    #[serde(default)]
+    pub forked_at_index: Option<u64>,
+    /// LLM summary written when the session was archived or went idle
+    #[serde(default)]
+    pub archive_summary: Option<String>,
+    /// Key entities (devices, rules, locations) extracted with the summary
+    #[serde(default)]
+    pub key_entities: Vec<String>,
+    /// When `archive_summary` was generated (Unix seconds)
+    #[serde(default)]
+    pub summarized_at: Option<i64>,
+    /// Tenant that owns this session
+    #[serde(default, skip_serializing_if = "TenantId::is_default")]
+    pub tenant_id: TenantId,
+    /// Tools the session may use; `None` means every registered tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 impl Default for SessionMetadata {
@@ -62,6 +88,13 @@ impl Default for SessionMetadata {
             conversation_summary: None,
             summary_up_to_index: None,
             preview: None,
+            parent_session_id: None,
+            forked_at_index: None,
+            archive_summary: None,
+            key_entities: Vec::new(),
+            summarized_at: None,
+            tenant_id: TenantId::default(),
+            allowed_tools: None,
         }
     }
 }
@@ -299,6 +332,56 @@ impl PendingStreamState {
     }
 }
 
+/// A user message with the intent the classifier predicted for it.
+///
+/// Examples that received feedback naming the correct intent are labeled
+/// training data for the intent classifier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentExample {
+    pub id: String,
+    pub session_id: String,
+    pub message: String,
+    /// Predicted intent category (snake_case name)
+    pub predicted: String,
+    #[serde(default)]
+    pub confidence: f32,
+    /// Keywords the prediction matched
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback: Option<IntentFeedback>,
+}
+
+impl IntentExample {
+    /// The correct intent, if feedback supplied one.
+    pub fn label(&self) -> Option<&str> {
+        self.feedback.as_ref().and_then(|f| f.correct.as_deref())
+    }
+}
+
+/// Feedback that a prediction was wrong.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentFeedback {
+    pub source: IntentFeedbackSource,
+    /// Correct intent category, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct: Option<String>,
+    pub at: i64,
+}
+
+/// Where intent feedback came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntentFeedbackSource {
+    /// The user marked the answer as wrong
+    ThumbsDown,
+    /// The user picked the correct intent
+    Correction,
+    /// The next message in the session contradicted the prediction
+    FollowUp,
+}
+
 /// Session storage using redb.
 pub struct SessionStore {
     db: Arc<Database>,
@@ -467,7 +550,11 @@ impl SessionStore {
     /// Skips corrupted messages and logs warnings instead of failing completely.
     pub fn load_history(&self, session_id: &str) -> Result<Vec<SessionMessage>, Error> {
         let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(HISTORY_TABLE)?;
+        let table = match read_txn.open_table(HISTORY_TABLE) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
 
         let start_key = (session_id, 0u64);
         let end_key = (session_id, u64::MAX);
@@ -714,6 +801,29 @@ impl SessionStore {
         }
     }
 
+    /// List sessions forked directly from `parent_id`.
+    pub fn list_child_sessions(&self, parent_id: &str) -> Result<Vec<String>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(SESSIONS_META_TABLE) {
+            Ok(t) => t,
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        let mut children = Vec::new();
+        for result in table.iter()? {
+            let (key, value) = result?;
+            let Ok(metadata) = serde_json::from_slice::<SessionMetadata>(value.value().as_slice())
+            else {
+                continue;
+            };
+            if metadata.parent_session_id.as_deref() == Some(parent_id) {
+                children.push(key.value().to_string());
+            }
+        }
+
+        Ok(children)
+    }
+
     /// Toggle memory enabled state for a session.
     pub fn toggle_memory(&self, session_id: &str, enabled: bool) -> Result<(), Error> {
         let mut metadata = self.get_session_metadata(session_id)?;
@@ -851,6 +961,95 @@ impl SessionStore {
         Ok(states)
     }
 
+    // ========== Intent examples ==========
+
+    /// Save or replace an intent example.
+    pub fn save_intent_example(&self, example: &IntentExample) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(INTENT_EXAMPLES_TABLE)?;
+            let value = serde_json::to_vec(example)?;
+            table.insert(example.id.as_str(), value)?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Get an intent example by id.
+    pub fn get_intent_example(&self, id: &str) -> Result<Option<IntentExample>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(INTENT_EXAMPLES_TABLE) {
+            Ok(t) => t,
+            Err(_) => return Ok(None),
+        };
+        match table.get(id)? {
+            Some(value) => Ok(Some(serde_json::from_slice(value.value().as_slice())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Intent examples, oldest first, optionally for one session.
+    pub fn list_intent_examples(
+        &self,
+        session_id: Option<&str>,
+    ) -> Result<Vec<IntentExample>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(INTENT_EXAMPLES_TABLE) {
+            Ok(t) => t,
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        let mut examples = Vec::new();
+        for result in table.iter()? {
+            let (_key, value) = result?;
+            let Ok(example) = serde_json::from_slice::<IntentExample>(value.value().as_slice())
+            else {
+                continue;
+            };
+            if session_id.is_none_or(|id| example.session_id == id) {
+                examples.push(example);
+            }
+        }
+        examples.sort_by_key(|e| e.created_at);
+        Ok(examples)
+    }
+
+    /// Delete intent examples matching `filter`. Returns the number removed.
+    fn delete_intent_examples_where(
+        &self,
+        filter: impl Fn(&IntentExample) -> bool,
+    ) -> Result<usize, Error> {
+        let ids: Vec<String> = self
+            .list_intent_examples(None)?
+            .into_iter()
+            .filter(&filter)
+            .map(|e| e.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(INTENT_EXAMPLES_TABLE)?;
+            for id in &ids {
+                table.remove(id.as_str())?;
+            }
+        }
+        write_txn.commit()?;
+        Ok(ids.len())
+    }
+
+    /// Delete every intent example recorded in a session.
+    pub fn delete_intent_examples(&self, session_id: &str) -> Result<usize, Error> {
+        self.delete_intent_examples_where(|e| e.session_id == session_id)
+    }
+
+    /// Delete examples without feedback created before `before` (Unix
+    /// seconds). Labeled examples are kept.
+    pub fn prune_intent_examples(&self, before: i64) -> Result<usize, Error> {
+        self.delete_intent_examples_where(|e| e.feedback.is_none() && e.created_at < before)
+    }
+
     /// Clean up stale pending stream states (older than 10 minutes).
     /// Returns Ok(0) if the table doesn't exist yet (graceful handling for new databases).
     pub fn cleanup_stale_pending_streams(&self) -> Result<usize, Error> {
@@ -1270,6 +1469,51 @@ mod tests {
         assert!(!meta.memory_enabled);
     }
 
+    #[test]
+    fn test_intent_examples() {
+        let store = create_temp_store();
+        let example = |id: &str, session_id: &str, created_at: i64| IntentExample {
+            id: id.to_string(),
+            session_id: session_id.to_string(),
+            message: "how warm is the lobby".to_string(),
+            predicted: "device".to_string(),
+            confidence: 0.4,
+            keywords: vec![],
+            created_at,
+            feedback: None,
+        };
+        store.save_intent_example(&example("b", "s1", 20)).unwrap();
+        store.save_intent_example(&example("a", "s1", 10)).unwrap();
+        store.save_intent_example(&example("c", "s2", 5)).unwrap();
+
+        let mut labeled = store.get_intent_example("a").unwrap().unwrap();
+        assert!(labeled.label().is_none());
+        labeled.feedback = Some(IntentFeedback {
+            source: IntentFeedbackSource::Correction,
+            correct: Some("data".to_string()),
+            at: 30,
+        });
+        store.save_intent_example(&labeled).unwrap();
+
+        let ids: Vec<_> = store
+            .list_intent_examples(Some("s1"))
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(
+            store.get_intent_example("a").unwrap().unwrap().label(),
+            Some("data")
+        );
+
+        // Only unlabeled examples are pruned
+        assert_eq!(store.prune_intent_examples(15).unwrap(), 1);
+        assert_eq!(store.list_intent_examples(None).unwrap().len(), 2);
+        assert_eq!(store.delete_intent_examples("s1").unwrap(), 2);
+        assert!(store.list_intent_examples(None).unwrap().is_empty());
+    }
+
     #[test]
     fn test_delete_session_metadata() {
         let store = create_temp_store();
@@ -1419,6 +1663,13 @@ mod tests {
             conversation_summary: Some("This is a summary".to_string()),
             summary_up_to_index: Some(5),
             preview: None,
+            parent_session_id: Some("parent-session".to_string()),
+            forked_at_index: Some(3),
+            archive_summary: Some("Changed the HVAC setpoint".to_string()),
+            key_entities: vec!["HVAC-1".to_string()],
+            summarized_at: Some(1_700_000_000),
+            tenant_id: Default::default(),
+            allowed_tools: Some(vec!["shell".to_string()]),
         };
         store
             .save_session_metadata("test-session", &metadata)
@@ -1433,6 +1684,44 @@ mod tests {
             Some("This is a summary".to_string())
         );
         assert_eq!(loaded.summary_up_to_index, Some(5));
+        assert_eq!(loaded.parent_session_id.as_deref(), Some("parent-session"));
+        assert_eq!(loaded.forked_at_index, Some(3));
+        assert_eq!(
+            loaded.archive_summary.as_deref(),
+            Some("Changed the HVAC setpoint")
+        );
+        assert_eq!(loaded.key_entities, vec!["HVAC-1"]);
+        assert_eq!(loaded.allowed_tools, Some(vec!["shell".to_string()]));
+        assert_eq!(loaded.summarized_at, Some(1_700_000_000));
+    }
+
+    #[test]
+    fn test_list_child_sessions() {
+        let store = create_temp_store();
+
+        for id in ["parent", "child-a", "child-b", "other"] {
+            store.save_session_id(id).unwrap();
+        }
+        for child in ["child-a", "child-b"] {
+            store
+                .save_session_metadata(
+                    child,
+                    &SessionMetadata {
+                        parent_session_id: Some("parent".to_string()),
+                        forked_at_index: Some(1),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        }
+        store
+            .save_session_metadata("other", &SessionMetadata::default())
+            .unwrap();
+
+        let mut children = store.list_child_sessions("parent").unwrap();
+        children.sort();
+        assert_eq!(children, vec!["child-a", "child-b"]);
+        assert!(store.list_child_sessions("child-a").unwrap().is_empty());
     }
 
     #[test]
@@ -1815,7 +2104,7 @@ mod tests {
 
         // Elapsed time should be small
         let elapsed = state.elapsed_secs();
-        assert!(elapsed >= 0 && elapsed < 2);
+        assert!((0..2).contains(&elapsed));
 
         // Should not be stale
         assert!(!state.is_stale());
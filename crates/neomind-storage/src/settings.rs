@@ -3,9 +3,12 @@
 //! Provides persistent storage for LLM and MQTT configuration.
 
 use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::sync::Arc;
 
+use neomind_core::config::service::ConfigStore;
+use neomind_core::extension::SignaturePolicy;
 use redb::{Database, ReadableTable, TableDefinition};
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +22,10 @@ pub const KEY_LLM_CONFIG: &str = "llm_config";
 pub const KEY_MQTT_CONFIG: &str = "mqtt_config";
 pub const KEY_GLOBAL_TIMEZONE: &str = "global_timezone";
 pub const KEY_RETENTION_CONFIG: &str = "retention_config";
+pub const KEY_ENERGY_CONFIG: &str = "energy_config";
+pub const KEY_MAINTENANCE_WINDOWS: &str = "maintenance_windows";
+pub const KEY_KNOWLEDGE_CONFIG: &str = "knowledge_config";
+pub const KEY_EXTENSION_SIGNATURE_POLICY: &str = "extension_signature_policy";
 
 /// Default global timezone (IANA format)
 pub const DEFAULT_GLOBAL_TIMEZONE: &str = "Asia/Shanghai";
@@ -30,10 +37,24 @@ const EXTERNAL_BROKERS_TABLE: TableDefinition<&str, &[u8]> =
 // Config history table: key = timestamp_id, value = ConfigChangeEntry (serialized)
 const CONFIG_HISTORY_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("config_history");
 
+// Runtime config values table: key = config key, value = JSON value
+const CONFIG_VALUES_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("config_values");
+
 // MQTT credentials table: key = username, value = MqttCredential (serialized)
 pub const MQTT_CREDENTIALS_TABLE: TableDefinition<&str, &[u8]> =
     TableDefinition::new("mqtt_credentials");
 
+// Device claims table: key = claim code, value = DeviceClaim (serialized)
+const DEVICE_CLAIMS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("device_claims");
+
+// Device identities table: key = device_id, value = DeviceIdentity (serialized)
+const DEVICE_IDENTITIES_TABLE: TableDefinition<&str, &[u8]> =
+    TableDefinition::new("device_identities");
+
+/// Username prefix of provisioned device credentials. The `__neomind`
+/// prefix keeps them out of the user credential API.
+pub const DEVICE_MQTT_USERNAME_PREFIX: &str = "__neomind_device_";
+
 // Settings keys for embedded broker
 pub const KEY_MQTT_BROKER_CONFIG: &str = "embedded_broker_config";
 pub const KEY_SYSTEM_MQTT_CREDENTIAL: &str = "system_mqtt_internal_credential";
@@ -85,6 +106,66 @@ pub struct MqttCredential {
     pub password_hash: String,
 }
 
+/// How a provisioned device authenticates to the embedded broker.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceAuthMethod {
+    /// Username and password.
+    #[default]
+    Password,
+    /// Username and password over mutual TLS with a client certificate.
+    Certificate,
+}
+
+/// One-time claim code that a device exchanges for its broker identity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceClaim {
+    pub code: String,
+    /// Device the claimed identity is bound to.
+    pub device_id: String,
+    #[serde(default)]
+    pub auth_method: DeviceAuthMethod,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+impl DeviceClaim {
+    /// Whether the claim can no longer be redeemed at `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Broker identity issued to a device by claiming a code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceIdentity {
+    pub device_id: String,
+    /// MQTT username, `DEVICE_MQTT_USERNAME_PREFIX` + device id.
+    pub username: String,
+    /// Bcrypt hash of the MQTT password.
+    pub password_hash: String,
+    #[serde(default)]
+    pub auth_method: DeviceAuthMethod,
+    /// SHA-256 fingerprint of the client certificate, if one was issued.
+    #[serde(default)]
+    pub cert_fingerprint: Option<String>,
+    pub claimed_at: i64,
+    /// Set when the identity is revoked; revoked identities can't connect.
+    #[serde(default)]
+    pub revoked_at: Option<i64>,
+}
+
+impl DeviceIdentity {
+    /// MQTT username for a device.
+    pub fn username_for(device_id: &str) -> String {
+        format!("{}{}", DEVICE_MQTT_USERNAME_PREFIX, device_id)
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
+
 /// Global settings store singleton (thread-safe).
 /// Keeps the database open across all calls to avoid lock conflicts.
 static SETTINGS_STORE_SINGLETON: Mutex<Option<Arc<SettingsStore>>> = Mutex::new(None);
@@ -319,6 +400,264 @@ impl MqttSettings {
     }
 }
 
+/// How a metric measures electricity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnergyMeterKind {
+    /// Instantaneous power (W / kW), integrated over time.
+    Power,
+    /// Cumulative energy counter (Wh / kWh); consumption is the increase.
+    Energy,
+}
+
+/// A device metric that reports power or energy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnergyMeter {
+    pub device_id: String,
+    pub metric: String,
+    pub kind: EnergyMeterKind,
+    /// "W", "kW", "Wh" or "kWh".
+    pub unit: String,
+}
+
+impl EnergyMeter {
+    /// Factor converting the metric's unit to kW (power) or kWh (energy).
+    pub fn scale(&self) -> Option<f64> {
+        match (self.kind, self.unit.to_ascii_lowercase().as_str()) {
+            (EnergyMeterKind::Power, "w") | (EnergyMeterKind::Energy, "wh") => Some(0.001),
+            (EnergyMeterKind::Power, "kw") | (EnergyMeterKind::Energy, "kwh") => Some(1.0),
+            _ => None,
+        }
+    }
+}
+
+/// A time-of-use tariff window. `start_hour..end_hour` is in local time and
+/// may wrap midnight (e.g. 22..6).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TariffPeriod {
+    /// Display name (e.g. "peak", "off-peak").
+    pub name: String,
+    pub start_hour: u8,
+    pub end_hour: u8,
+    /// Days the window applies to, 0 = Monday .. 6 = Sunday (empty = every day).
+    #[serde(default)]
+    pub weekdays: Vec<u8>,
+    /// Price per kWh.
+    pub rate: f64,
+}
+
+impl TariffPeriod {
+    /// Whether the window covers `hour` on `weekday` (0 = Monday).
+    pub fn covers(&self, weekday: u8, hour: u8) -> bool {
+        let in_hours = if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        };
+        in_hours && (self.weekdays.is_empty() || self.weekdays.contains(&weekday))
+    }
+}
+
+/// Electricity tariff schedule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnergyTariff {
+    /// Currency code shown in reports.
+    #[serde(default = "default_energy_currency")]
+    pub currency: String,
+    /// Price per kWh outside every period.
+    #[serde(default)]
+    pub default_rate: f64,
+    /// Time-of-use periods; the first matching one wins.
+    #[serde(default)]
+    pub periods: Vec<TariffPeriod>,
+}
+
+fn default_energy_currency() -> String {
+    "CNY".to_string()
+}
+
+impl Default for EnergyTariff {
+    fn default() -> Self {
+        Self {
+            currency: default_energy_currency(),
+            default_rate: 0.0,
+            periods: Vec::new(),
+        }
+    }
+}
+
+impl EnergyTariff {
+    /// The matching period (if any) and rate for a local weekday and hour.
+    pub fn rate_at(&self, weekday: u8, hour: u8) -> (Option<&TariffPeriod>, f64) {
+        match self.periods.iter().find(|p| p.covers(weekday, hour)) {
+            Some(period) => (Some(period), period.rate),
+            None => (None, self.default_rate),
+        }
+    }
+}
+
+/// Energy monitoring configuration: which metrics are meters and what
+/// electricity costs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnergyConfig {
+    #[serde(default)]
+    pub meters: Vec<EnergyMeter>,
+    #[serde(default)]
+    pub tariff: EnergyTariff,
+}
+
+/// Weekly recurrence of a maintenance window, in the global timezone.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceRecurrence {
+    /// Days the window opens, 0 = Monday; empty means every day
+    #[serde(default)]
+    pub weekdays: Vec<u8>,
+    /// Opening time as minutes after local midnight
+    pub start_minute: u32,
+    /// Length of each occurrence; may run past midnight
+    pub duration_minutes: u32,
+}
+
+impl MaintenanceRecurrence {
+    fn opens_on(&self, weekday: u8) -> bool {
+        self.weekdays.is_empty() || self.weekdays.contains(&weekday)
+    }
+
+    /// Whether an occurrence covers local `weekday` / `minute_of_day`,
+    /// including one that opened the previous day.
+    pub fn covers(&self, weekday: u8, minute_of_day: u32) -> bool {
+        let end = self.start_minute + self.duration_minutes;
+        (self.opens_on(weekday) && self.start_minute <= minute_of_day && minute_of_day < end)
+            || (self.opens_on((weekday + 6) % 7) && minute_of_day + 1440 < end)
+    }
+}
+
+/// A period during which rule actions are skipped and alerts are recorded
+/// without notifying. Scoped to devices, device groups and rules; a window
+/// with no scope covers everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Unix timestamp the window (or the recurring series) starts
+    pub start: i64,
+    /// Unix timestamp it ends; open-ended recurring series leave this unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<MaintenanceRecurrence>,
+    #[serde(default)]
+    pub devices: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub rules: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl MaintenanceWindow {
+    /// Whether the window covers everything.
+    pub fn is_global(&self) -> bool {
+        self.devices.is_empty() && self.groups.is_empty() && self.rules.is_empty()
+    }
+
+    /// Whether the window is in effect at `timestamp`, which falls on local
+    /// `weekday` (0 = Monday) at `minute_of_day`.
+    pub fn active_at(&self, timestamp: i64, weekday: u8, minute_of_day: u32) -> bool {
+        if !self.enabled || timestamp < self.start || self.end.is_some_and(|e| timestamp >= e) {
+            return false;
+        }
+        match &self.recurrence {
+            Some(recurrence) => recurrence.covers(weekday, minute_of_day),
+            None => self.end.is_some(),
+        }
+    }
+
+    /// Check the window is well-formed.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Maintenance window name is required".to_string());
+        }
+        if self.end.is_some_and(|e| e <= self.start) {
+            return Err("Maintenance window must end after it starts".to_string());
+        }
+        match &self.recurrence {
+            None if self.end.is_none() => {
+                Err("One-off maintenance windows need an end time".to_string())
+            }
+            Some(r) if r.duration_minutes == 0 || r.start_minute >= 1440 => Err(
+                "Recurrence needs a start minute below 1440 and a non-zero duration".to_string(),
+            ),
+            Some(r) if r.weekdays.iter().any(|d| *d > 6) => {
+                Err("Recurrence weekdays must be 0 (Monday) to 6 (Sunday)".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Knowledge base configuration: how uploaded documents are chunked and
+/// which model embeds them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnowledgeConfig {
+    /// LLM backend instance whose endpoint serves embeddings (Ollama or
+    /// OpenAI-compatible). `None` uses the built-in local embedder.
+    #[serde(default)]
+    pub embedding_backend_id: Option<String>,
+    /// Embedding model name, e.g. `nomic-embed-text`
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Target chunk size in characters
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+    /// Characters repeated between consecutive chunks
+    #[serde(default = "default_chunk_overlap")]
+    pub chunk_overlap: usize,
+}
+
+fn default_chunk_size() -> usize {
+    800
+}
+
+fn default_chunk_overlap() -> usize {
+    100
+}
+
+impl Default for KnowledgeConfig {
+    fn default() -> Self {
+        Self {
+            embedding_backend_id: None,
+            embedding_model: None,
+            chunk_size: default_chunk_size(),
+            chunk_overlap: default_chunk_overlap(),
+        }
+    }
+}
+
+impl KnowledgeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.embedding_backend_id.is_some() && self.embedding_model.is_none() {
+            return Err("embedding_model is required with embedding_backend_id".to_string());
+        }
+        if !(100..=8000).contains(&self.chunk_size) {
+            return Err("chunk_size must be between 100 and 8000".to_string());
+        }
+        if self.chunk_overlap >= self.chunk_size / 2 {
+            return Err("chunk_overlap must be less than half of chunk_size".to_string());
+        }
+        Ok(())
+    }
+}
+
 /// Retention configuration for data cleanup.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetentionConfig {
@@ -337,6 +676,16 @@ pub struct RetentionConfig {
     /// Retention period in hours for image/binary data (None = forever).
     #[serde(default = "default_retention_image")]
     pub image_retention: Option<u64>,
+
+    /// Per-device-type retention in hours (None = forever), keyed by
+    /// device type id. Overrides `default_retention`.
+    #[serde(default)]
+    pub device_type_overrides: HashMap<String, Option<u64>>,
+
+    /// Per-metric retention in hours (None = forever), keyed by metric
+    /// name. Takes precedence over device type overrides.
+    #[serde(default)]
+    pub metric_overrides: HashMap<String, Option<u64>>,
 }
 
 fn default_retention_enabled() -> bool {
@@ -362,6 +711,8 @@ impl Default for RetentionConfig {
             interval_hours: default_retention_interval(),
             default_retention: default_retention_default(),
             image_retention: default_retention_image(),
+            device_type_overrides: HashMap::new(),
+            metric_overrides: HashMap::new(),
         }
     }
 }
@@ -376,6 +727,12 @@ impl RetentionConfig {
         // `__webhook_image`, `detection_frame`, etc. without registering
         // every alias explicitly.
         policy.set_image_retention(self.image_retention);
+        for (device_type, hours) in &self.device_type_overrides {
+            policy.set_device_type_retention(device_type.clone(), *hours);
+        }
+        for (metric, hours) in &self.metric_overrides {
+            policy.set_metric_retention(metric.clone(), *hours);
+        }
         policy
     }
 }
@@ -695,8 +1052,13 @@ impl SettingsStore {
             let _ = write_txn.open_table(EXTERNAL_BROKERS_TABLE)?;
             // Open or create the config_history table
             let _ = write_txn.open_table(CONFIG_HISTORY_TABLE)?;
+            // Open or create the config_values table
+            let _ = write_txn.open_table(CONFIG_VALUES_TABLE)?;
             // Open or create the mqtt_credentials table
             let _ = write_txn.open_table(MQTT_CREDENTIALS_TABLE)?;
+            // Open or create the device provisioning tables
+            let _ = write_txn.open_table(DEVICE_CLAIMS_TABLE)?;
+            let _ = write_txn.open_table(DEVICE_IDENTITIES_TABLE)?;
         }
         write_txn.commit()?;
         Ok(())
@@ -760,12 +1122,20 @@ impl SettingsStore {
     }
 
     /// Record a configuration change in history.
+    ///
+    /// Plain credentials in the recorded values are redacted.
     pub fn record_config_change(&self, entry: &ConfigChangeEntry) -> Result<(), Error> {
+        let mut entry = entry.clone();
+        if let Some(old_value) = entry.old_value.as_mut() {
+            neomind_core::secrets::redact_json(old_value);
+        }
+        neomind_core::secrets::redact_json(&mut entry.new_value);
+
         let write_txn = self.db.begin_write()?;
         {
             let mut table = write_txn.open_table(CONFIG_HISTORY_TABLE)?;
             let value =
-                serde_json::to_vec(entry).map_err(|e| Error::Serialization(e.to_string()))?;
+                serde_json::to_vec(&entry).map_err(|e| Error::Serialization(e.to_string()))?;
             table.insert(entry.id.as_str(), value.as_slice())?;
         }
         write_txn.commit()?;
@@ -1109,6 +1479,150 @@ impl SettingsStore {
             .unwrap_or_default()
     }
 
+    // ========================================================================
+    // Energy Configuration
+    // ========================================================================
+
+    /// Save energy meters and tariff.
+    pub fn save_energy_config(&self, config: &EnergyConfig) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(SETTINGS_TABLE)?;
+            let value =
+                serde_json::to_vec(config).map_err(|e| Error::Serialization(e.to_string()))?;
+            table.insert(KEY_ENERGY_CONFIG, value.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Load energy meters and tariff.
+    pub fn load_energy_config(&self) -> Result<Option<EnergyConfig>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(SETTINGS_TABLE)?;
+
+        if let Some(data) = table.get(KEY_ENERGY_CONFIG)? {
+            let config: EnergyConfig = serde_json::from_slice(data.value())
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            Ok(Some(config))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Get energy configuration, returning an empty one if not set.
+    pub fn get_energy_config(&self) -> EnergyConfig {
+        self.load_energy_config().ok().flatten().unwrap_or_default()
+    }
+
+    // ========================================================================
+    // Knowledge Base Configuration
+    // ========================================================================
+
+    /// Save knowledge base configuration.
+    pub fn save_knowledge_config(&self, config: &KnowledgeConfig) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(SETTINGS_TABLE)?;
+            let value =
+                serde_json::to_vec(config).map_err(|e| Error::Serialization(e.to_string()))?;
+            table.insert(KEY_KNOWLEDGE_CONFIG, value.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Load knowledge base configuration.
+    pub fn load_knowledge_config(&self) -> Result<Option<KnowledgeConfig>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(SETTINGS_TABLE)?;
+
+        if let Some(data) = table.get(KEY_KNOWLEDGE_CONFIG)? {
+            let config: KnowledgeConfig = serde_json::from_slice(data.value())
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            Ok(Some(config))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Get knowledge base configuration, returning defaults if not set.
+    pub fn get_knowledge_config(&self) -> KnowledgeConfig {
+        self.load_knowledge_config()
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    // ========================================================================
+    // Extension Signature Policy
+    // ========================================================================
+
+    /// Save the trusted extension publishers.
+    pub fn save_signature_policy(&self, policy: &SignaturePolicy) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(SETTINGS_TABLE)?;
+            let value =
+                serde_json::to_vec(policy).map_err(|e| Error::Serialization(e.to_string()))?;
+            table.insert(KEY_EXTENSION_SIGNATURE_POLICY, value.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Load the trusted extension publishers.
+    pub fn load_signature_policy(&self) -> Result<Option<SignaturePolicy>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(SETTINGS_TABLE)?;
+
+        if let Some(data) = table.get(KEY_EXTENSION_SIGNATURE_POLICY)? {
+            let policy: SignaturePolicy = serde_json::from_slice(data.value())
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            Ok(Some(policy))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Get the signature policy; by default no publishers are trusted and
+    /// unsigned packages are accepted.
+    pub fn get_signature_policy(&self) -> SignaturePolicy {
+        self.load_signature_policy()
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    // ========================================================================
+    // Maintenance Windows
+    // ========================================================================
+
+    /// Save all maintenance windows.
+    pub fn save_maintenance_windows(&self, windows: &[MaintenanceWindow]) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(SETTINGS_TABLE)?;
+            let value =
+                serde_json::to_vec(windows).map_err(|e| Error::Serialization(e.to_string()))?;
+            table.insert(KEY_MAINTENANCE_WINDOWS, value.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Load maintenance windows.
+    pub fn load_maintenance_windows(&self) -> Result<Vec<MaintenanceWindow>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(SETTINGS_TABLE)?;
+
+        if let Some(data) = table.get(KEY_MAINTENANCE_WINDOWS)? {
+            serde_json::from_slice(data.value()).map_err(|e| Error::Serialization(e.to_string()))
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
     // ========================================================================
     // Embedded MQTT Broker Configuration
     // ========================================================================
@@ -1255,6 +1769,174 @@ impl SettingsStore {
         write_txn.commit()?;
         Ok(())
     }
+
+    /// Store a device claim code.
+    pub fn create_device_claim(&self, claim: &DeviceClaim) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(DEVICE_CLAIMS_TABLE)?;
+            let value =
+                serde_json::to_vec(claim).map_err(|e| Error::Serialization(e.to_string()))?;
+            table.insert(claim.code.as_str(), value.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// List all pending device claims.
+    pub fn list_device_claims(&self) -> Result<Vec<DeviceClaim>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(DEVICE_CLAIMS_TABLE)?;
+
+        let mut claims = Vec::new();
+        for result in table.iter()? {
+            let (_, data) = result?;
+            let claim: DeviceClaim = serde_json::from_slice(data.value())
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            claims.push(claim);
+        }
+        Ok(claims)
+    }
+
+    /// Remove and return a claim, so each code can be redeemed only once.
+    pub fn take_device_claim(&self, code: &str) -> Result<Option<DeviceClaim>, Error> {
+        let write_txn = self.db.begin_write()?;
+        let claim = {
+            let mut table = write_txn.open_table(DEVICE_CLAIMS_TABLE)?;
+            let removed = table.remove(code)?;
+            match removed {
+                Some(data) => Some(
+                    serde_json::from_slice::<DeviceClaim>(data.value())
+                        .map_err(|e| Error::Serialization(e.to_string()))?,
+                ),
+                None => None,
+            }
+        };
+        write_txn.commit()?;
+        Ok(claim)
+    }
+
+    /// Delete a claim code without redeeming it.
+    pub fn delete_device_claim(&self, code: &str) -> Result<bool, Error> {
+        Ok(self.take_device_claim(code)?.is_some())
+    }
+
+    /// Save a device identity, replacing any previous one for the device.
+    pub fn save_device_identity(&self, identity: &DeviceIdentity) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(DEVICE_IDENTITIES_TABLE)?;
+            let value =
+                serde_json::to_vec(identity).map_err(|e| Error::Serialization(e.to_string()))?;
+            table.insert(identity.device_id.as_str(), value.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Get the identity of a device.
+    pub fn get_device_identity(&self, device_id: &str) -> Result<Option<DeviceIdentity>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(DEVICE_IDENTITIES_TABLE)?;
+
+        if let Some(data) = table.get(device_id)? {
+            let identity = serde_json::from_slice(data.value())
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            Ok(Some(identity))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// List all device identities, including revoked ones.
+    pub fn list_device_identities(&self) -> Result<Vec<DeviceIdentity>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(DEVICE_IDENTITIES_TABLE)?;
+
+        let mut identities = Vec::new();
+        for result in table.iter()? {
+            let (_, data) = result?;
+            let identity: DeviceIdentity = serde_json::from_slice(data.value())
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            identities.push(identity);
+        }
+        Ok(identities)
+    }
+
+    /// Revoke a device identity. The record is kept for auditing.
+    ///
+    /// Returns the revoked identity, or `None` if the device has none.
+    pub fn revoke_device_identity(&self, device_id: &str) -> Result<Option<DeviceIdentity>, Error> {
+        let Some(mut identity) = self.get_device_identity(device_id)? else {
+            return Ok(None);
+        };
+        if identity.revoked_at.is_none() {
+            identity.revoked_at = Some(chrono::Utc::now().timestamp());
+            self.save_device_identity(&identity)?;
+        }
+        Ok(Some(identity))
+    }
+}
+
+/// Runtime config values for [`neomind_core::config::service`].
+impl ConfigStore for SettingsStore {
+    fn load_config_values(&self) -> Result<BTreeMap<String, serde_json::Value>, String> {
+        let read_txn = self.db.begin_read().map_err(|e| e.to_string())?;
+        let table = read_txn
+            .open_table(CONFIG_VALUES_TABLE)
+            .map_err(|e| e.to_string())?;
+
+        let mut values = BTreeMap::new();
+        for result in table.iter().map_err(|e| e.to_string())? {
+            let (key, data) = result.map_err(|e| e.to_string())?;
+            match serde_json::from_slice(data.value()) {
+                Ok(value) => {
+                    values.insert(key.value().to_string(), value);
+                }
+                Err(e) => tracing::warn!(
+                    key = key.value(),
+                    error = %e,
+                    "Skipping undecodable config value"
+                ),
+            }
+        }
+        Ok(values)
+    }
+
+    fn save_config_value(
+        &self,
+        key: &str,
+        old: Option<&serde_json::Value>,
+        new: Option<&serde_json::Value>,
+        source: &str,
+    ) -> Result<(), String> {
+        let write_txn = self.db.begin_write().map_err(|e| e.to_string())?;
+        {
+            let mut table = write_txn
+                .open_table(CONFIG_VALUES_TABLE)
+                .map_err(|e| e.to_string())?;
+            match new {
+                Some(value) => {
+                    let data = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+                    table
+                        .insert(key, data.as_slice())
+                        .map_err(|e| e.to_string())?;
+                }
+                None => {
+                    table.remove(key).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        write_txn.commit().map_err(|e| e.to_string())?;
+
+        let entry = ConfigChangeEntry::new(
+            key.to_string(),
+            old.cloned(),
+            new.cloned().unwrap_or(serde_json::Value::Null),
+            source.to_string(),
+        );
+        self.record_config_change(&entry).map_err(|e| e.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -1307,4 +1989,192 @@ mod tests {
         assert!(store.delete_llm_settings().unwrap());
         assert!(!store.has_llm_settings());
     }
+
+    #[test]
+    fn test_maintenance_window_recurrence() {
+        // Saturdays 22:00 for four hours, i.e. into Sunday 02:00
+        let window = MaintenanceWindow {
+            id: "nightly".to_string(),
+            name: "Firmware updates".to_string(),
+            start: 1000,
+            recurrence: Some(MaintenanceRecurrence {
+                weekdays: vec![5],
+                start_minute: 22 * 60,
+                duration_minutes: 240,
+            }),
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(window.validate().is_ok());
+        assert!(window.is_global());
+        assert!(window.active_at(2000, 5, 23 * 60));
+        assert!(window.active_at(2000, 6, 60));
+        assert!(!window.active_at(2000, 6, 3 * 60));
+        assert!(!window.active_at(2000, 4, 23 * 60));
+        assert!(!window.active_at(500, 5, 23 * 60));
+
+        let one_off = MaintenanceWindow {
+            name: "Pump swap".to_string(),
+            start: 1000,
+            end: Some(2000),
+            devices: vec!["pump-1".to_string()],
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(one_off.active_at(1500, 0, 0));
+        assert!(!one_off.active_at(2000, 0, 0));
+        assert!(MaintenanceWindow {
+            end: None,
+            ..one_off
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_energy_tariff_periods() {
+        let tariff = EnergyTariff {
+            currency: "EUR".to_string(),
+            default_rate: 0.20,
+            periods: vec![
+                TariffPeriod {
+                    name: "off-peak".to_string(),
+                    start_hour: 22,
+                    end_hour: 6,
+                    weekdays: vec![],
+                    rate: 0.10,
+                },
+                TariffPeriod {
+                    name: "peak".to_string(),
+                    start_hour: 17,
+                    end_hour: 20,
+                    weekdays: vec![0, 1, 2, 3, 4],
+                    rate: 0.35,
+                },
+            ],
+        };
+        assert_eq!(tariff.rate_at(2, 23).1, 0.10);
+        assert_eq!(tariff.rate_at(2, 3).1, 0.10);
+        assert_eq!(tariff.rate_at(2, 18).1, 0.35);
+        assert_eq!(tariff.rate_at(6, 18).1, 0.20);
+        assert!(tariff.rate_at(2, 12).0.is_none());
+
+        let meter = EnergyMeter {
+            device_id: "plug-1".to_string(),
+            metric: "power".to_string(),
+            kind: EnergyMeterKind::Power,
+            unit: "W".to_string(),
+        };
+        assert_eq!(meter.scale(), Some(0.001));
+        let mismatched = EnergyMeter {
+            unit: "kWh".to_string(),
+            ..meter
+        };
+        assert_eq!(mismatched.scale(), None);
+    }
+
+    #[test]
+    fn test_retention_config_overrides() {
+        // Configs saved before overrides existed still load
+        let legacy: RetentionConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "interval_hours": 1,
+            "default_retention": 720,
+            "image_retention": 72
+        }))
+        .unwrap();
+        assert!(legacy.device_type_overrides.is_empty());
+
+        let mut config = RetentionConfig::default();
+        config
+            .device_type_overrides
+            .insert("camera".to_string(), Some(24 * 7));
+        config
+            .metric_overrides
+            .insert("temperature".to_string(), Some(24 * 365));
+
+        let policy = config.to_retention_policy();
+        assert_eq!(policy.get_retention_hours("camera", "heartbeat"), Some(168));
+        assert_eq!(
+            policy.get_retention_hours("camera", "temperature"),
+            Some(8760)
+        );
+        assert_eq!(policy.get_retention_hours("sensor", "humidity"), Some(720));
+    }
+
+    #[test]
+    fn test_config_values_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SettingsStore::open(dir.path().join("settings.redb")).unwrap();
+        let key = "agent.max_tokens";
+
+        store
+            .save_config_value(key, None, Some(&serde_json::json!(2048)), "api")
+            .unwrap();
+        let values = store.load_config_values().unwrap();
+        assert_eq!(values.get(key), Some(&serde_json::json!(2048)));
+
+        store
+            .save_config_value(key, Some(&serde_json::json!(2048)), None, "api")
+            .unwrap();
+        assert!(store.load_config_values().unwrap().is_empty());
+
+        let history = store.get_config_history(key, 10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().any(|e| e.new_value.is_null()));
+
+        // Credentials never reach the history table
+        let entry = ConfigChangeEntry::new(
+            "llm_settings".to_string(),
+            None,
+            serde_json::json!({"model": "gpt-4o", "api_key": "sk-plain"}),
+            "api".to_string(),
+        );
+        store.record_config_change(&entry).unwrap();
+        let history = store.get_config_history("llm_settings", 1).unwrap();
+        assert_eq!(history[0].new_value["api_key"], neomind_core::secrets::REDACTED);
+        assert_eq!(history[0].new_value["model"], "gpt-4o");
+    }
+
+    #[test]
+    fn test_device_claims_and_identities() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SettingsStore::open(dir.path().join("settings.redb")).unwrap();
+
+        let claim = DeviceClaim {
+            code: "K7QX-2MPD".to_string(),
+            device_id: "sensor01".to_string(),
+            auth_method: DeviceAuthMethod::Password,
+            created_at: 100,
+            expires_at: 200,
+        };
+        assert!(!claim.is_expired(199));
+        assert!(claim.is_expired(200));
+        store.create_device_claim(&claim).unwrap();
+        assert_eq!(store.list_device_claims().unwrap().len(), 1);
+
+        // A claim code can be redeemed only once
+        let taken = store.take_device_claim("K7QX-2MPD").unwrap().unwrap();
+        assert_eq!(taken.device_id, "sensor01");
+        assert!(store.take_device_claim("K7QX-2MPD").unwrap().is_none());
+
+        let identity = DeviceIdentity {
+            device_id: "sensor01".to_string(),
+            username: DeviceIdentity::username_for("sensor01"),
+            password_hash: "hash".to_string(),
+            auth_method: DeviceAuthMethod::Password,
+            cert_fingerprint: None,
+            claimed_at: 150,
+            revoked_at: None,
+        };
+        assert_eq!(identity.username, "__neomind_device_sensor01");
+        store.save_device_identity(&identity).unwrap();
+
+        let revoked = store.revoke_device_identity("sensor01").unwrap().unwrap();
+        assert!(revoked.is_revoked());
+        let stored = store.get_device_identity("sensor01").unwrap().unwrap();
+        assert_eq!(stored.revoked_at, revoked.revoked_at);
+        assert_eq!(store.list_device_identities().unwrap().len(), 1);
+        assert!(store.revoke_device_identity("missing").unwrap().is_none());
+    }
 }
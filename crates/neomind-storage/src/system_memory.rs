@@ -980,6 +980,19 @@ impl MarkdownMemoryStore {
         Ok(deleted_count)
     }
 
+    /// Delete all temp files of one session. Returns `false` if it had none.
+    pub fn delete_session_files(&self, session_id: &str) -> Result<bool> {
+        Self::validate_session_id(session_id)?;
+        let session_dir = self.base_path.join("sessions").join(session_id);
+        if !session_dir.exists() {
+            return Ok(false);
+        }
+        fs::remove_dir_all(&session_dir)
+            .map_err(|e| Error::Storage(format!("Failed to delete session directory: {}", e)))?;
+        debug!(session_id = %session_id, "Deleted session temp files");
+        Ok(true)
+    }
+
     // ========================================================================
     // Custom files API (domain-specific memory files)
     // ========================================================================
@@ -1960,9 +1973,12 @@ mod tests_new_layout {
             procedures_char_limit: 3000,
             agent_char_limit: 5000,
             temp_file_ttl_days: 7,
+            chat_retention_days: 0,
             system_context_interval_secs: 600,
             summary_interval_secs: 7200,
             summary_backend_id: None,
+            summarizer: crate::memory_config::SummarizerKind::Llm,
+            extractive_sentences: 4,
         };
         let store = MarkdownMemoryStore::with_config(temp_dir.path(), config);
         store.init().unwrap();
@@ -2026,6 +2042,20 @@ mod tests_new_layout {
         assert_eq!(todo, "");
     }
 
+    #[tokio::test]
+    async fn test_delete_session_files() {
+        let (_temp, store) = create_test_store().await;
+        store
+            .write_session_file("s-delete", "notes", "Notes")
+            .await
+            .unwrap();
+
+        assert!(store.delete_session_files("s-delete").unwrap());
+        assert_eq!(store.read_session_file("s-delete", "notes").await.unwrap(), "");
+        assert!(!store.delete_session_files("s-delete").unwrap());
+        assert!(store.delete_session_files("../escape").is_err());
+    }
+
     #[tokio::test]
     async fn test_section_replace() {
         let (_temp, store) = create_test_store().await;
@@ -2400,6 +2430,6 @@ mod tests {
         let result = replace_section_in_content(content, "NewSection", "NewContent");
         let lines: Vec<&str> = result.lines().collect();
         assert_eq!(lines[0], "## NewSection");
-        assert!(lines.iter().any(|l| *l == "NewContent"));
+        assert!(lines.contains(&"NewContent"));
     }
 }
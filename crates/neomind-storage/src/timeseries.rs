@@ -7,11 +7,14 @@
 //! - **Retention Policies**: Configure data retention per metric or globally
 //! - **Memory Cache**: Latest values cached for fast access
 //! - **Batch Optimization**: Group writes by device for efficiency
+//! - **Write-Ahead Buffering**: Buffered writes are journaled and committed
+//!   in one transaction per flush
 //! - **Performance Monitoring**: Track operation latency and throughput
 
 use parking_lot::Mutex;
-use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -23,6 +26,7 @@ use redb::{Database, ReadableTable, TableDefinition};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{RwLock, Semaphore};
 
+use crate::wal::{self, Journal};
 use crate::Error;
 
 // redb table definition: key = (source_id, metric, timestamp), value = DataPoint (serialized)
@@ -389,12 +393,14 @@ impl PerformanceStats {
     pub fn record_write(&mut self, duration: Duration) {
         self.write_count += 1;
         self.total_write_ns += duration.as_nanos() as u64;
+        record_operation_latency("write", duration);
     }
 
     /// Record a read operation.
     pub fn record_read(&mut self, duration: Duration) {
         self.read_count += 1;
         self.total_read_ns += duration.as_nanos() as u64;
+        record_operation_latency("read", duration);
     }
 
     /// Record a cache hit.
@@ -408,6 +414,58 @@ impl PerformanceStats {
     }
 }
 
+/// Feed a storage operation latency into the shared metrics registry.
+fn record_operation_latency(op: &str, duration: Duration) {
+    neomind_core::metrics::global()
+        .histogram(
+            "neomind_storage_operation_seconds",
+            "Time series storage operation latency",
+            &[("op", op)],
+        )
+        .observe_duration(duration);
+}
+
+/// Statistics for flushes of the write buffer.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FlushStats {
+    /// Flushes that had points to write
+    pub flushes: u64,
+    /// Points committed by flushes
+    pub points_flushed: u64,
+    /// Points committed by the most recent flush
+    pub last_flush_points: u64,
+    /// Duration of the most recent flush in milliseconds
+    pub last_flush_ms: f64,
+    /// Longest flush in milliseconds
+    pub max_flush_ms: f64,
+    /// Total time spent flushing in milliseconds
+    pub total_flush_ms: f64,
+    /// Points put back in the buffer after a failed write
+    pub requeued_points: u64,
+    /// Points dropped because the buffer was at its hard cap
+    pub dropped_points: u64,
+    /// Points recovered from the journal when the store was opened
+    pub replayed_points: u64,
+    /// Unreadable journal lines skipped when the store was opened
+    pub skipped_journal_lines: u64,
+    /// Journal appends that failed
+    pub journal_errors: u64,
+    /// Points waiting in the buffer
+    pub pending_points: usize,
+    /// Whether buffered points are journaled
+    pub journal_enabled: bool,
+}
+
+impl FlushStats {
+    /// Average flush duration in milliseconds.
+    pub fn avg_flush_ms(&self) -> f64 {
+        if self.flushes == 0 {
+            return 0.0;
+        }
+        self.total_flush_ms / self.flushes as f64
+    }
+}
+
 /// Batch write request grouped by device.
 #[derive(Debug, Clone)]
 pub struct BatchWriteRequest {
@@ -476,9 +534,17 @@ struct BufferedWrite {
 }
 
 /// Write-behind buffer for batching single-point writes into efficient batch transactions.
+///
+/// With a journal, every buffered point is also appended to the write-ahead
+/// log (see [`crate::wal`]) so it survives a crash before the next flush.
+/// Lock order is journal, then pending.
 struct WriteBuffer {
     /// Pending writes, guarded by a parking_lot mutex (non-async, held briefly).
     pending: Mutex<Vec<BufferedWrite>>,
+    /// Write-ahead journal for the pending writes, if enabled.
+    journal: Mutex<Option<Journal>>,
+    /// Journal appends that failed.
+    journal_errors: AtomicU64,
     /// Maximum number of buffered points before automatic flush.
     max_size: usize,
     /// Handle to the background flush task (for graceful shutdown).
@@ -491,23 +557,70 @@ impl WriteBuffer {
     fn new(max_size: usize) -> Self {
         Self {
             pending: Mutex::new(Vec::with_capacity(max_size)),
+            journal: Mutex::new(None),
+            journal_errors: AtomicU64::new(0),
             max_size,
             flush_task: Mutex::new(None),
             shutdown: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
         }
     }
 
+    fn journal_enabled(&self) -> bool {
+        self.journal.lock().is_some()
+    }
+
+    fn len(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    /// Append a write to the journal, if enabled. A failed append is logged,
+    /// counted and returned; the journal stays on, and replay skips the torn
+    /// line it may leave.
+    fn journal_write(
+        &self,
+        journal: &mut Option<Journal>,
+        write: &BufferedWrite,
+    ) -> io::Result<()> {
+        let Some(active) = journal.as_mut() else {
+            return Ok(());
+        };
+        active
+            .append(&write.source_id, &write.metric, &write.point)
+            .inspect_err(|e| {
+                self.journal_errors.fetch_add(1, Ordering::Relaxed);
+                tracing::error!("Time series journal append failed: {}", e);
+            })
+    }
+
     /// Push a write into the buffer. Returns `true` if the buffer is full and should be flushed.
-    fn push(&self, write: BufferedWrite) -> bool {
+    /// A write that can't be journaled is not buffered; the journal error is
+    /// returned instead.
+    fn push(&self, write: BufferedWrite) -> io::Result<bool> {
+        let mut journal = self.journal.lock();
+        self.journal_write(&mut journal, &write)?;
         let mut pending = self.pending.lock();
         pending.push(write);
-        pending.len() >= self.max_size
+        Ok(pending.len() >= self.max_size)
     }
 
-    /// Drain all pending writes, returning them.
-    fn drain(&self) -> Vec<BufferedWrite> {
+    /// Drain all pending writes, returning them with the sealed journal
+    /// segment that holds them. Delete the segment once they are committed.
+    fn drain(&self) -> (Vec<BufferedWrite>, Option<PathBuf>) {
+        let mut journal = self.journal.lock();
+        let sealed = match journal.as_mut().map(Journal::seal) {
+            Some(Ok(sealed)) => sealed,
+            Some(Err(e)) => {
+                tracing::error!(
+                    "Failed to seal time series journal, disabling write-ahead log: {}",
+                    e
+                );
+                *journal = None;
+                None
+            }
+            None => None,
+        };
         let mut pending = self.pending.lock();
-        std::mem::take(&mut *pending)
+        (std::mem::take(&mut *pending), sealed)
     }
 
     /// Re-queue writes whose batch failed to flush, so the next flush retries
@@ -516,6 +629,7 @@ impl WriteBuffer {
     /// grow memory without bound — once at the cap, further failed writes are
     /// dropped and the caller logs them. Returns the number dropped.
     fn requeue(&self, writes: Vec<BufferedWrite>) -> usize {
+        let mut journal = self.journal.lock();
         let mut pending = self.pending.lock();
         let hard_cap = self.max_size.saturating_mul(10);
         let mut dropped = 0;
@@ -523,6 +637,10 @@ impl WriteBuffer {
             if pending.len() >= hard_cap {
                 dropped += 1;
             } else {
+                // The drained segment is deleted after the flush, so
+                // re-queued points go into the active one. If that fails the
+                // point is still retried, just not crash-safe until then
+                let _ = self.journal_write(&mut journal, &w);
                 pending.push(w);
             }
         }
@@ -577,10 +695,14 @@ pub struct TimeSeriesStore {
     write_semaphore: Arc<Semaphore>,
     /// Cache TTL
     cache_ttl: Duration,
+    /// Largest encoded point accepted by a write
+    max_point_bytes: usize,
     /// Storage path for singleton
     path: String,
     /// Write-behind buffer for batching single-point writes.
     write_buffer: WriteBuffer,
+    /// Write buffer flush statistics.
+    flush_stats: Mutex<FlushStats>,
     /// Whether metrics_info has been populated at least once (prevents cold-start full scan).
     metrics_initialized: AtomicBool,
     /// Guards concurrent apply_retention() invocations.
@@ -591,6 +713,10 @@ pub struct TimeSeriesStore {
     /// piling up on redb's single-writer lock. The flag is set on entry and
     /// cleared on exit (including error paths via the RAII guard).
     retention_in_progress: AtomicBool,
+    /// Device id -> device type, used to resolve `device_type_overrides`
+    /// during retention. Telemetry keys only carry the source id, so the
+    /// owner of the device registry pushes this mapping in.
+    device_types: DashMap<String, String>,
 }
 
 /// Global time series store singleton (thread-safe).
@@ -622,6 +748,62 @@ fn telemetry_cache_size_bytes() -> usize {
         * 1024
 }
 
+/// Rewrite bare device_id keys in a time series database to the "device:"
+/// prefix format. Shared by [`TimeSeriesStore::migrate_device_prefix`] and the
+/// offline migrations in [`crate::admin`].
+pub(crate) fn migrate_device_prefix_keys(db: &Database) -> Result<u64, Error> {
+    // Known prefixes that are already correct — skip them
+    // ("device_status:" holds recorded online/offline changes)
+    const KNOWN_PREFIXES: &[&str] = &["device:", "device_status:", "extension:", "transform:"];
+
+    let write_txn = db.begin_write()?;
+    let migrated;
+
+    {
+        let mut table = write_txn.open_table(TIMESERIES_TABLE)?;
+
+        // Collect keys that need migration
+        let mut to_migrate: Vec<((String, String, i64), Vec<u8>)> = Vec::new();
+
+        for result in table.iter()? {
+            let (key, value) = result?;
+            let (source_id, metric, ts) = key.value();
+            let sid = source_id;
+
+            // Only migrate bare IDs (no colon prefix)
+            if KNOWN_PREFIXES.iter().any(|p| sid.starts_with(p)) {
+                continue;
+            }
+
+            let new_source_id = format!("device:{}", sid);
+            to_migrate.push((
+                (new_source_id, metric.to_string(), ts),
+                value.value().to_vec(),
+            ));
+        }
+
+        // Write new keys and delete old ones
+        for (new_key, value) in &to_migrate {
+            table.insert(
+                (new_key.0.as_str(), new_key.1.as_str(), new_key.2),
+                value.as_slice(),
+            )?;
+        }
+
+        // Delete old keys (use original bare source_id)
+        for ((new_source, metric, ts), _) in &to_migrate {
+            // Extract bare ID from "device:{id}"
+            let bare_id = &new_source[7..]; // Skip "device:"
+            table.remove((bare_id, metric.as_str(), *ts))?;
+        }
+
+        migrated = to_migrate.len() as u64;
+    }
+
+    write_txn.commit()?;
+    Ok(migrated)
+}
+
 impl TimeSeriesStore {
     /// Open or create a time series store at the given path.
     /// Uses a singleton pattern to prevent multiple opens of the same database.
@@ -660,6 +842,13 @@ impl TimeSeriesStore {
             builder.create(path_ref)?
         };
 
+        let recovered = if config.write_ahead_log {
+            wal::seal_leftover(path_ref)?;
+            Some(wal::recover(path_ref)?)
+        } else {
+            None
+        };
+
         let store = Arc::new(TimeSeriesStore {
             db: Arc::new(db),
             metrics_info: DashMap::with_capacity(64), // Pre-allocate for typical metrics
@@ -670,12 +859,41 @@ impl TimeSeriesStore {
             stats: Arc::new(RwLock::new(PerformanceStats::default())),
             write_semaphore: Arc::new(Semaphore::new(config.max_concurrent_writes)),
             cache_ttl: config.cache_ttl,
+            max_point_bytes: config.max_point_bytes,
             path: path_str,
             write_buffer: WriteBuffer::new(config.write_buffer_size),
+            flush_stats: Mutex::new(FlushStats::default()),
             metrics_initialized: AtomicBool::new(false),
             retention_in_progress: AtomicBool::new(false),
+            device_types: DashMap::new(),
         });
 
+        // Commit points journaled before the last shutdown or crash, and only
+        // then start a fresh journal. Points that could not be committed are
+        // journaled again before the old segments are deleted, and the next
+        // flush retries them.
+        if let Some(wal::Recovered {
+            records,
+            files,
+            skipped,
+        }) = recovered
+        {
+            store.flush_stats.lock().skipped_journal_lines = skipped as u64;
+            let failed = store.replay_journal(records);
+            let journal = Journal::create(path_ref, config.wal_sync_interval)?;
+            *store.write_buffer.journal.lock() = Some(journal);
+            // If any could not be journaled again, keep the old segments for
+            // the next open; replaying committed points again is harmless
+            let kept = store.requeue_unreplayed(failed);
+            if kept && store.write_buffer.journal_enabled() {
+                for file in files {
+                    if let Err(e) = std::fs::remove_file(&file) {
+                        tracing::warn!("Failed to remove journal file {}: {}", file.display(), e);
+                    }
+                }
+            }
+        }
+
         // Start background flush task
         store
             .write_buffer
@@ -699,55 +917,7 @@ impl TimeSeriesStore {
     ///
     /// Returns the number of migrated keys.
     pub fn migrate_device_prefix(&self) -> Result<u64, Error> {
-        // Known prefixes that are already correct — skip them
-        const KNOWN_PREFIXES: &[&str] = &["device:", "extension:", "transform:"];
-
-        let write_txn = self.db.begin_write()?;
-        let migrated;
-
-        {
-            let mut table = write_txn.open_table(TIMESERIES_TABLE)?;
-
-            // Collect keys that need migration
-            let mut to_migrate: Vec<((String, String, i64), Vec<u8>)> = Vec::new();
-
-            for result in table.iter()? {
-                let (key, value) = result?;
-                let (source_id, metric, ts) = key.value();
-                let sid = source_id;
-
-                // Only migrate bare IDs (no colon prefix)
-                if KNOWN_PREFIXES.iter().any(|p| sid.starts_with(p)) {
-                    continue;
-                }
-
-                let new_source_id = format!("device:{}", sid);
-                to_migrate.push((
-                    (new_source_id, metric.to_string(), ts),
-                    value.value().to_vec(),
-                ));
-            }
-
-            // Write new keys and delete old ones
-            for (new_key, value) in &to_migrate {
-                table.insert(
-                    (new_key.0.as_str(), new_key.1.as_str(), new_key.2),
-                    value.as_slice(),
-                )?;
-            }
-
-            // Delete old keys (use original bare source_id)
-            for ((new_source, metric, ts), _) in &to_migrate {
-                // Extract bare ID from "device:{id}"
-                let bare_id = &new_source[7..]; // Skip "device:"
-                table.remove((bare_id, metric.as_str(), *ts))?;
-            }
-
-            migrated = to_migrate.len() as u64;
-        }
-
-        write_txn.commit()?;
-        Ok(migrated)
+        migrate_device_prefix_keys(&self.db)
     }
 
     /// Get performance statistics.
@@ -771,6 +941,23 @@ impl TimeSeriesStore {
         *self.retention_policy.write().await = policy;
     }
 
+    /// Replace the device id -> device type mapping used by retention.
+    ///
+    /// Keys are bare device ids; telemetry stored under `device:{id}` picks
+    /// up the matching `device_type_overrides` entry.
+    pub fn set_device_types(&self, device_types: std::collections::HashMap<String, String>) {
+        self.device_types.clear();
+        for (device_id, device_type) in device_types {
+            self.device_types.insert(device_id, device_type);
+        }
+    }
+
+    /// Device type for a telemetry source id, if known.
+    fn device_type_for_source(&self, source_id: &str) -> Option<String> {
+        let device_id = source_id.strip_prefix("device:").unwrap_or(source_id);
+        self.device_types.get(device_id).map(|t| t.value().clone())
+    }
+
     /// Clean stale cache entries.
     pub async fn clean_cache(&self) -> usize {
         let before = self.latest_cache.entry_count() as usize;
@@ -809,6 +996,9 @@ impl TimeSeriesStore {
     /// - On the periodic background flush interval
     /// - When `flush()` is called explicitly
     ///
+    /// With `write_ahead_log` enabled the point is journaled first, so it is
+    /// recovered on the next open if the process dies before the flush.
+    ///
     /// This amortizes transaction overhead across many data points, significantly
     /// improving throughput for high-frequency device telemetry.
     pub async fn write(
@@ -817,14 +1007,17 @@ impl TimeSeriesStore {
         metric: &str,
         point: DataPoint,
     ) -> Result<(), Error> {
-        // Update cache immediately (reads need latest value)
-        self.update_cache(source_id, metric, point.clone()).await;
+        let should_flush = self
+            .write_buffer
+            .push(BufferedWrite {
+                source_id: source_id.to_string(),
+                metric: metric.to_string(),
+                point: point.clone(),
+            })
+            .map_err(|e| Error::Storage(format!("Failed to journal write: {}", e)))?;
 
-        let should_flush = self.write_buffer.push(BufferedWrite {
-            source_id: source_id.to_string(),
-            metric: metric.to_string(),
-            point,
-        });
+        // Update cache immediately (reads need latest value)
+        self.update_cache(source_id, metric, point).await;
 
         if should_flush {
             let store = Arc::clone(self);
@@ -836,14 +1029,16 @@ impl TimeSeriesStore {
         Ok(())
     }
 
-    /// Flush all buffered writes to redb in batched transactions.
+    /// Flush all buffered writes to redb.
     ///
-    /// Groups buffered points by (source_id, metric) and writes each group
-    /// as a single transaction. Called automatically by the background task
-    /// and when the buffer is full.
+    /// Groups buffered points by (source_id, metric) and commits them through
+    /// [`Self::write_groups_isolated`], re-queueing the points that fail.
+    /// Called automatically by the background task and when the buffer is
+    /// full.
     fn flush_buffer(&self) {
-        let drained = self.write_buffer.drain();
+        let (drained, sealed) = self.write_buffer.drain();
         if drained.is_empty() {
+            self.remove_sealed_journal(sealed);
             return;
         }
 
@@ -861,27 +1056,148 @@ impl TimeSeriesStore {
 
         let total_count: usize = groups.values().map(|v| v.len()).sum();
 
-        // Write each group in a single transaction. On failure, isolate the
-        // offending point by retrying per-point in its own transaction —
-        // otherwise a single poison payload (e.g. a value exceeding redb's
-        // max_value_size) aborts the whole (source, metric) batch every flush
-        // and blocks fresh writes for that metric forever. Only the genuinely
-        // unwritable points are re-queued.
-        let mut requeue: Vec<BufferedWrite> = Vec::new();
+        // Only the genuinely unwritable points are re-queued
+        let requeue = self.write_groups_isolated(groups);
+
+        // Re-queue failed points (bounded — drops once at the hard cap).
+        let mut requeued_count: usize = 0;
+        let mut dropped: usize = 0;
+        if !requeue.is_empty() {
+            requeued_count = requeue.len();
+            dropped = self.write_buffer.requeue(requeue);
+            if dropped > 0 {
+                tracing::error!(
+                    "Write buffer at hard cap under persistent flush failure — {} points re-queued, {} dropped",
+                    requeued_count - dropped,
+                    dropped
+                );
+            }
+        }
+        // Everything in the sealed segment is now committed or journaled again
+        self.remove_sealed_journal(sealed);
+
+        // Record stats — count only points that actually landed. Counting the
+        // full drained set would double-count re-queued points on every retry.
+        let elapsed = start.elapsed();
+        let written = (total_count - requeued_count) as u64;
+        record_operation_latency("flush", elapsed);
+        neomind_core::metrics::global()
+            .counter(
+                "neomind_storage_flushed_points_total",
+                "Time series points committed by write buffer flushes",
+                &[],
+            )
+            .inc_by(written);
+        {
+            let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+            let mut flush_stats = self.flush_stats.lock();
+            flush_stats.flushes += 1;
+            flush_stats.points_flushed += written;
+            flush_stats.last_flush_points = written;
+            flush_stats.last_flush_ms = elapsed_ms;
+            flush_stats.max_flush_ms = flush_stats.max_flush_ms.max(elapsed_ms);
+            flush_stats.total_flush_ms += elapsed_ms;
+            flush_stats.requeued_points += (requeued_count - dropped) as u64;
+            flush_stats.dropped_points += dropped as u64;
+        }
+        if let Ok(mut stats) = self.stats.try_write() {
+            stats.write_count += written;
+            stats.total_write_ns += elapsed.as_nanos() as u64;
+        }
+    }
+
+    /// Delete a journal segment whose points have been committed.
+    fn remove_sealed_journal(&self, sealed: Option<PathBuf>) {
+        if let Some(path) = sealed {
+            if let Err(e) = std::fs::remove_file(&path) {
+                // Left behind, it is replayed on the next open; rewriting the
+                // same keys is harmless
+                tracing::warn!("Failed to remove journal segment {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Commit points recovered from the journal, isolating failures the same
+    /// way a flush does. Returns the points that still fail; a poison point
+    /// must not stop the store from opening, but it is not dropped either.
+    fn replay_journal(&self, records: Vec<wal::Record>) -> Vec<BufferedWrite> {
+        if records.is_empty() {
+            return Vec::new();
+        }
+        let count = records.len();
+        let mut groups: std::collections::HashMap<(String, String), Vec<DataPoint>> =
+            std::collections::HashMap::new();
+        for record in records {
+            groups
+                .entry((record.source_id, record.metric))
+                .or_default()
+                .push(record.point);
+        }
+        let failed = self.write_groups_isolated(groups);
+        self.flush_stats.lock().replayed_points += (count - failed.len()) as u64;
+        tracing::info!(
+            "Replayed {} time series points from the write-ahead journal",
+            count - failed.len()
+        );
+        failed
+    }
+
+    /// Queue points that failed to replay for the next flush. Call once the
+    /// fresh journal is in place, so they are journaled again. Returns
+    /// whether none were dropped at the buffer's hard cap.
+    fn requeue_unreplayed(&self, failed: Vec<BufferedWrite>) -> bool {
+        if failed.is_empty() {
+            return true;
+        }
+        let count = failed.len();
+        let dropped = self.write_buffer.requeue(failed);
+        {
+            let mut flush_stats = self.flush_stats.lock();
+            flush_stats.requeued_points += (count - dropped) as u64;
+            flush_stats.dropped_points += dropped as u64;
+        }
+        tracing::error!(
+            "{} time series points from the write-ahead journal could not be written — {} re-queued, {} dropped",
+            count,
+            count - dropped,
+            dropped
+        );
+        dropped == 0
+    }
+
+    /// Commit grouped points in one transaction. If that fails, each group is
+    /// retried in its own transaction, and a group that still fails is
+    /// written point by point — otherwise a single poison payload (e.g. a
+    /// value over `max_point_bytes`) aborts the whole (source, metric) batch
+    /// every time and blocks fresh writes for that metric forever. Returns
+    /// the points that could not be written.
+    fn write_groups_isolated(
+        &self,
+        groups: std::collections::HashMap<(String, String), Vec<DataPoint>>,
+    ) -> Vec<BufferedWrite> {
+        let mut failed_writes = Vec::new();
+        let Err(e) = self.write_groups_sync(&groups) else {
+            return failed_writes;
+        };
+        tracing::error!(
+            "Failed to write {} points in one transaction: {} — retrying per metric",
+            groups.values().map(|v| v.len()).sum::<usize>(),
+            e
+        );
         for ((source_id, metric), points) in groups {
             if let Err(e) = self.write_batch_sync(&source_id, &metric, &points) {
                 tracing::error!(
-                    "Failed to flush batch for {}/{}: {} — isolating per-point",
+                    "Failed to write batch for {}/{}: {} — isolating per-point",
                     source_id, metric, e
                 );
                 let failed = self.write_points_isolated(&source_id, &metric, points);
                 if !failed.is_empty() {
                     tracing::error!(
-                        "Per-point isolation {}/{}: {} poison point(s) failed and were re-queued",
+                        "Per-point isolation {}/{}: {} poison point(s) failed",
                         source_id, metric, failed.len()
                     );
                     for point in failed {
-                        requeue.push(BufferedWrite {
+                        failed_writes.push(BufferedWrite {
                             source_id: source_id.clone(),
                             metric: metric.clone(),
                             point,
@@ -890,27 +1206,16 @@ impl TimeSeriesStore {
                 }
             }
         }
+        failed_writes
+    }
 
-        // Re-queue failed points (bounded — drops once at the hard cap).
-        let mut requeued_count: usize = 0;
-        if !requeue.is_empty() {
-            requeued_count = requeue.len();
-            let dropped = self.write_buffer.requeue(requeue);
-            if dropped > 0 {
-                tracing::error!(
-                    "Write buffer at hard cap under persistent flush failure — {} points re-queued, {} dropped",
-                    requeued_count - dropped,
-                    dropped
-                );
-            }
-        }
-
-        // Record stats — count only points that actually landed. Counting the
-        // full drained set would double-count re-queued points on every retry.
-        if let Ok(mut stats) = self.stats.try_write() {
-            stats.write_count += (total_count - requeued_count) as u64;
-            stats.total_write_ns += start.elapsed().as_nanos() as u64;
-        }
+    /// Get write buffer flush statistics.
+    pub fn flush_stats(&self) -> FlushStats {
+        let mut stats = self.flush_stats.lock().clone();
+        stats.pending_points = self.write_buffer.len();
+        stats.journal_enabled = self.write_buffer.journal_enabled();
+        stats.journal_errors = self.write_buffer.journal_errors.load(Ordering::Relaxed);
+        stats
     }
 
     /// Write each point in its OWN transaction, returning only the points that
@@ -936,7 +1241,32 @@ impl TimeSeriesStore {
         failed
     }
 
-    /// Synchronous batch write (used by flush_buffer).
+    /// Synchronous write of several (source_id, metric) groups in a single
+    /// transaction (used by flush_buffer and journal replay).
+    fn write_groups_sync(
+        &self,
+        groups: &std::collections::HashMap<(String, String), Vec<DataPoint>>,
+    ) -> Result<(), Error> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(TIMESERIES_TABLE)?;
+            for ((source_id, metric), points) in groups {
+                for point in points {
+                    let key = (source_id.as_str(), metric.as_str(), point.timestamp);
+                    let value = self.encode_point(point)?;
+                    table.insert(key, value.as_slice())?;
+                }
+            }
+        }
+        write_txn.commit()?;
+
+        for ((source_id, metric), points) in groups {
+            self.record_metric_info(source_id, metric, points);
+        }
+        Ok(())
+    }
+
+    /// Synchronous batch write for one (source_id, metric) group.
     fn write_batch_sync(
         &self,
         source_id: &str,
@@ -948,13 +1278,31 @@ impl TimeSeriesStore {
             let mut table = write_txn.open_table(TIMESERIES_TABLE)?;
             for point in points {
                 let key = (source_id, metric, point.timestamp);
-                let value = serde_json::to_vec(point)?;
+                let value = self.encode_point(point)?;
                 table.insert(key, value.as_slice())?;
             }
         }
         write_txn.commit()?;
 
-        // Update metrics info
+        self.record_metric_info(source_id, metric, points);
+        Ok(())
+    }
+
+    /// Serialize a point for storage, refusing ones over `max_point_bytes`.
+    fn encode_point(&self, point: &DataPoint) -> Result<Vec<u8>, Error> {
+        let value = serde_json::to_vec(point)?;
+        if value.len() > self.max_point_bytes {
+            return Err(Error::InvalidInput(format!(
+                "Time series point of {} bytes exceeds the {} byte limit",
+                value.len(),
+                self.max_point_bytes
+            )));
+        }
+        Ok(value)
+    }
+
+    /// Update metrics info after committing points.
+    fn record_metric_info(&self, source_id: &str, metric: &str, points: &[DataPoint]) {
         let metric_key = format!("{}:{}", source_id, metric);
         let last_ts = points.last().map(|p| p.timestamp).unwrap_or(0);
         self.metrics_info
@@ -970,8 +1318,6 @@ impl TimeSeriesStore {
 
         // Mark metrics_info as populated (prevents cold-start full scan in list_metrics)
         self.metrics_initialized.store(true, Ordering::Release);
-
-        Ok(())
     }
 
     /// Flush all buffered writes and stop the background flush task.
@@ -1510,6 +1856,35 @@ impl TimeSeriesStore {
         Ok(latest)
     }
 
+    /// Get the newest data point at or before `at` (Unix seconds).
+    ///
+    /// Used for "as-of" reconstruction of past device state. Bypasses the
+    /// latest-value cache, which only ever holds the current value.
+    pub async fn query_latest_at(
+        &self,
+        source_id: &str,
+        metric: &str,
+        at: i64,
+    ) -> Result<Option<DataPoint>, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(TIMESERIES_TABLE) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(Error::Storage(format!("Failed to open table: {}", e))),
+        };
+        let start_key = (source_id, metric, i64::MIN);
+        let end_key = (source_id, metric, at);
+        let point = table
+            .range(start_key..=end_key)?
+            .next_back()
+            .map(|result| -> Result<DataPoint, Error> {
+                let (_key, value) = result?;
+                Ok(serde_json::from_slice(value.value())?)
+            })
+            .transpose()?;
+        Ok(point)
+    }
+
     /// Read the latest data point WITHOUT touching the LRU cache or read stats.
     ///
     /// Used by `apply_retention()` to peek at the most recent value for
@@ -1745,6 +2120,9 @@ impl TimeSeriesStore {
     }
 
     /// Flush all buffered writes to disk, then sync redb.
+    ///
+    /// Blocks until the buffered points are committed, so tests and callers
+    /// that read back right after writing see every point.
     pub fn flush(&self) -> Result<(), Error> {
         self.flush_buffer();
         // redb auto-manages persistence, no additional sync needed
@@ -2029,83 +2407,18 @@ impl TimeSeriesStore {
         let mut metrics_cleaned: std::collections::HashSet<String> =
             std::collections::HashSet::new();
 
-        let read_txn = self.db.begin_read()?;
-
-        // Handle case where table doesn't exist yet (no data has been written)
-        let table = match read_txn.open_table(TIMESERIES_TABLE) {
-            Ok(t) => t,
-            Err(redb::TableError::TableDoesNotExist(_)) => {
-                tracing::debug!(
-                    "apply_retention: table 'timeseries' does not exist yet, returning empty result"
-                );
-                return Ok(RetentionPolicyCleanupResult {
-                    points_removed: 0,
-                    metrics_cleaned: Vec::new(),
-                });
-            }
-            Err(e) => return Err(Error::Storage(format!("Failed to open table: {}", e))),
-        };
-
-        // Collect all (source_id, metric) pairs
-        let mut metric_pairs: std::collections::HashSet<(String, String)> =
-            std::collections::HashSet::new();
-        let start_key = ("", "", i64::MIN);
-        let end_key = ("\u{FF}", "\u{FF}", i64::MAX);
-
-        for result in table.range(start_key..=end_key)? {
-            let (key, _) = result?;
-            let (source_id, metric, _) = key.value();
-            metric_pairs.insert((source_id.to_string(), metric.to_string()));
-        }
-        drop(read_txn);
-        drop(table);
-
+        let metric_pairs = self.collect_metric_pairs()?;
         let now = Utc::now().timestamp();
 
         // Process each metric pair
         for (source_id, metric) in &metric_pairs {
-            // Get device type from metrics_info if available
             let metric_key = format!("{}:{}", source_id, metric);
-            let device_type = ""; // Could be enhanced to look up device type
-
-            // Resolve effective retention hours.
-            //
-            // Priority: explicit metric_overrides → device_type_overrides →
-            // image_retention_hours (if the latest sample actually looks
-            // like image data) → default_hours.
-            //
-            // The image-content check is content-based (peeks at the latest
-            // data point's value), NOT name-based, so it works for any
-            // metric name as long as the payload really is image data.
-            let explicit_hours = policy.get_retention_hours(device_type, metric);
-            // Do NOT also require explicit_hours.is_some(): when
-            // default_retention=null but image_retention is set, explicit_hours
-            // is None — gating on .is_some() would skip this branch so image
-            // rows never purge here while image_cleanup deletes their files
-            // → dangling 404 URLs forever.
-            let effective_hours = if explicit_hours == policy.default_hours {
-                // No explicit override — fell through to default. Check
-                // whether this metric actually carries image data, and if
-                // so, apply image_retention instead.
-                let use_image = match policy.image_retention_hours {
-                    Some(img_hours) if Some(img_hours) != explicit_hours => {
-                        match self.query_latest_uncached(source_id, metric).await {
-                            Ok(Some(latest)) => value_looks_like_image(&latest.value),
-                            _ => false, // no sample → don't risk misclassifying
-                        }
-                    }
-                    _ => false,
-                };
-                if use_image {
-                    policy.image_retention_hours
-                } else {
-                    explicit_hours
-                }
-            } else {
-                explicit_hours
-            };
+            let device_type = self.device_type_for_source(source_id).unwrap_or_default();
 
-            if let Some(hours) = effective_hours {
+            if let Some(hours) = self
+                .effective_retention_hours(&policy, source_id, &device_type, metric)
+                .await
+            {
                 let cutoff = now - (hours as i64 * 3600);
                 if cutoff < now {
                     let removed = self
@@ -2132,6 +2445,153 @@ impl TimeSeriesStore {
         })
     }
 
+    /// Report what `policy` would delete right now, without deleting.
+    ///
+    /// Uses the same resolution rules as `apply_retention` (metric override →
+    /// device type override → image fallback → default), so the preview
+    /// matches what the next cleanup run removes. Only metrics with at least
+    /// one expired point are listed.
+    pub async fn preview_retention(
+        &self,
+        policy: &RetentionPolicy,
+    ) -> Result<RetentionPreview, Error> {
+        let metric_pairs = self.collect_metric_pairs()?;
+        let now = Utc::now().timestamp();
+        let mut entries = Vec::new();
+
+        for (source_id, metric) in &metric_pairs {
+            let device_type = self.device_type_for_source(source_id);
+            let Some(hours) = self
+                .effective_retention_hours(
+                    policy,
+                    source_id,
+                    device_type.as_deref().unwrap_or_default(),
+                    metric,
+                )
+                .await
+            else {
+                continue;
+            };
+
+            let cutoff = now - (hours as i64 * 3600);
+            let points = self.count_range(source_id, metric, i64::MIN, cutoff)?;
+            if points > 0 {
+                entries.push(RetentionPreviewEntry {
+                    source_id: source_id.clone(),
+                    metric: metric.clone(),
+                    device_type,
+                    retention_hours: hours,
+                    cutoff_timestamp: cutoff,
+                    points_to_delete: points,
+                });
+            }
+        }
+
+        entries.sort_by(|a, b| {
+            b.points_to_delete
+                .cmp(&a.points_to_delete)
+                .then_with(|| a.source_id.cmp(&b.source_id))
+                .then_with(|| a.metric.cmp(&b.metric))
+        });
+
+        Ok(RetentionPreview {
+            total_points: entries.iter().map(|e| e.points_to_delete).sum(),
+            entries,
+        })
+    }
+
+    /// All distinct (source_id, metric) pairs in the table.
+    fn collect_metric_pairs(&self) -> Result<std::collections::HashSet<(String, String)>, Error> {
+        let mut metric_pairs = std::collections::HashSet::new();
+        let read_txn = self.db.begin_read()?;
+
+        // Handle case where table doesn't exist yet (no data has been written)
+        let table = match read_txn.open_table(TIMESERIES_TABLE) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => {
+                tracing::debug!("retention: table 'timeseries' does not exist yet");
+                return Ok(metric_pairs);
+            }
+            Err(e) => return Err(Error::Storage(format!("Failed to open table: {}", e))),
+        };
+
+        let start_key = ("", "", i64::MIN);
+        let end_key = ("\u{FF}", "\u{FF}", i64::MAX);
+        for result in table.range(start_key..=end_key)? {
+            let (key, _) = result?;
+            let (source_id, metric, _) = key.value();
+            metric_pairs.insert((source_id.to_string(), metric.to_string()));
+        }
+        Ok(metric_pairs)
+    }
+
+    /// Count points in `[start, end]` for one metric.
+    fn count_range(
+        &self,
+        source_id: &str,
+        metric: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<u64, Error> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(TIMESERIES_TABLE) {
+            Ok(t) => t,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(0),
+            Err(e) => return Err(Error::Storage(format!("Failed to open table: {}", e))),
+        };
+        let mut count = 0u64;
+        for result in table.range((source_id, metric, start)..=(source_id, metric, end))? {
+            result?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Resolve effective retention hours for one metric.
+    ///
+    /// Priority: explicit metric_overrides → device_type_overrides →
+    /// image_retention_hours (if the latest sample actually looks
+    /// like image data) → default_hours.
+    ///
+    /// The image-content check is content-based (peeks at the latest
+    /// data point's value), NOT name-based, so it works for any
+    /// metric name as long as the payload really is image data.
+    async fn effective_retention_hours(
+        &self,
+        policy: &RetentionPolicy,
+        source_id: &str,
+        device_type: &str,
+        metric: &str,
+    ) -> Option<u64> {
+        let explicit_hours = policy.get_retention_hours(device_type, metric);
+        // Do NOT also require explicit_hours.is_some(): when
+        // default_retention=null but image_retention is set, explicit_hours
+        // is None — gating on .is_some() would skip this branch so image
+        // rows never purge here while image_cleanup deletes their files
+        // → dangling 404 URLs forever.
+        if explicit_hours != policy.default_hours {
+            return explicit_hours;
+        }
+
+        // No explicit override — fell through to default. Check
+        // whether this metric actually carries image data, and if
+        // so, apply image_retention instead.
+        let use_image = match policy.image_retention_hours {
+            Some(img_hours) if Some(img_hours) != explicit_hours => {
+                match self.query_latest_uncached(source_id, metric).await {
+                    Ok(Some(latest)) => value_looks_like_image(&latest.value),
+                    _ => false, // no sample → don't risk misclassifying
+                }
+            }
+            _ => false,
+        };
+        if use_image {
+            policy.image_retention_hours
+        } else {
+            explicit_hours
+        }
+    }
+
     /// Query data points with uniform time-bucket downsampling.
     ///
     /// Scans the time range in a single forward pass, divides it into `target_count`
@@ -2293,6 +2753,32 @@ pub struct RetentionPolicyCleanupResult {
     pub metrics_cleaned: Vec<String>,
 }
 
+/// Dry-run result of a retention policy (see `preview_retention`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPreview {
+    /// Total number of data points that would be removed
+    pub total_points: u64,
+    /// Per-metric breakdown, largest first
+    pub entries: Vec<RetentionPreviewEntry>,
+}
+
+/// One metric's share of a retention preview.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPreviewEntry {
+    /// Telemetry source id (e.g. `device:sensor1`)
+    pub source_id: String,
+    /// Metric name
+    pub metric: String,
+    /// Device type, when the source is a known device
+    pub device_type: Option<String>,
+    /// Effective retention applied to this metric
+    pub retention_hours: u64,
+    /// Points at or before this timestamp would be removed
+    pub cutoff_timestamp: i64,
+    /// Number of points that would be removed
+    pub points_to_delete: u64,
+}
+
 /// Configuration for time series store.
 #[derive(Debug, Clone)]
 pub struct TimeSeriesConfig {
@@ -2309,6 +2795,15 @@ pub struct TimeSeriesConfig {
     pub write_buffer_size: usize,
     /// How often the background task flushes buffered writes to disk.
     pub write_buffer_flush_interval: Duration,
+    /// Journal buffered writes to `<db>.wal` so they survive a crash before
+    /// the next flush.
+    pub write_ahead_log: bool,
+    /// Longest time a journaled write may sit in the OS page cache before
+    /// it is synced to disk.
+    pub wal_sync_interval: Duration,
+    /// Largest encoded point a buffered write accepts; bigger points fail to
+    /// flush and are isolated like any other poison point.
+    pub max_point_bytes: usize,
 }
 
 impl Default for TimeSeriesConfig {
@@ -2320,6 +2815,9 @@ impl Default for TimeSeriesConfig {
             max_concurrent_writes: 10,
             write_buffer_size: 200,
             write_buffer_flush_interval: Duration::from_millis(500),
+            write_ahead_log: true,
+            wal_sync_interval: Duration::from_millis(100),
+            max_point_bytes: 64 * 1024 * 1024,
         }
     }
 }
@@ -2521,6 +3019,102 @@ mod tests {
         assert_eq!(buf.pending.lock().len(), 100);
     }
 
+    #[tokio::test]
+    async fn test_journal_replayed_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telemetry.redb");
+
+        // Points accepted before a crash, never flushed
+        let mut journal = Journal::create(&path, Duration::ZERO).unwrap();
+        for ts in [1000, 1001] {
+            let point = DataPoint::new(ts, ts as f64);
+            journal.append("device:s1", "temp", &point).unwrap();
+        }
+        drop(journal);
+
+        let config = TimeSeriesConfig::default();
+        let store = TimeSeriesStore::with_config(&path, config).unwrap();
+        let result = store
+            .query_range("device:s1", "temp", 0, i64::MAX, None)
+            .await
+            .unwrap();
+        assert_eq!(result.points.len(), 2);
+        assert_eq!(store.flush_stats().replayed_points, 2);
+
+        store
+            .write("device:s1", "temp", DataPoint::new(1002, 3.0))
+            .await
+            .unwrap();
+        assert_eq!(store.flush_stats().pending_points, 1);
+        store.flush().unwrap();
+
+        let stats = store.flush_stats();
+        assert!(stats.journal_enabled);
+        assert_eq!((stats.flushes, stats.points_flushed, stats.pending_points), (1, 1, 0));
+        assert!(wal::recover(&path).unwrap().records.is_empty());
+        store.shutdown();
+    }
+
+    #[tokio::test]
+    async fn test_poison_point_in_journal_does_not_block_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telemetry.redb");
+
+        // A point that failed every flush was re-queued into the journal
+        // before shutdown, next to a good one
+        let mut journal = Journal::create(&path, Duration::ZERO).unwrap();
+        let oversized = DataPoint::new_with_value(1000, serde_json::json!("x".repeat(4096)));
+        journal.append("device:s1", "image", &oversized).unwrap();
+        journal
+            .append("device:s1", "temp", &DataPoint::new(1001, 21.5))
+            .unwrap();
+        journal.seal().unwrap();
+        drop(journal);
+
+        let config = TimeSeriesConfig {
+            max_point_bytes: 1024,
+            wal_sync_interval: Duration::ZERO,
+            ..Default::default()
+        };
+        let store = TimeSeriesStore::with_config(&path, config).unwrap();
+
+        let good = store
+            .query_range("device:s1", "temp", 0, i64::MAX, None)
+            .await
+            .unwrap();
+        assert_eq!(good.points.len(), 1);
+        let poison = store
+            .query_range("device:s1", "image", 0, i64::MAX, None)
+            .await
+            .unwrap();
+        assert!(poison.points.is_empty());
+
+        // The poison point is kept for retry, in memory and in the fresh
+        // journal, rather than dropped with the old segments
+        let stats = store.flush_stats();
+        assert_eq!((stats.replayed_points, stats.dropped_points), (1, 0));
+        assert_eq!((stats.requeued_points, stats.pending_points), (1, 1));
+        let leftover = wal::recover(&path).unwrap();
+        assert_eq!((leftover.records.len(), leftover.files.len()), (1, 1));
+        assert_eq!(leftover.records[0].metric, "image");
+        store.shutdown();
+
+        // Once the point fits, opening with that journal commits it
+        let moved = dir.path().join("moved.redb");
+        let files = wal::recover(&path).unwrap().files;
+        let mut journal_copy = moved.clone().into_os_string();
+        journal_copy.push(".wal");
+        std::fs::copy(&files[0], journal_copy).unwrap();
+        let store = TimeSeriesStore::with_config(&moved, TimeSeriesConfig::default()).unwrap();
+        let recovered = store
+            .query_range("device:s1", "image", 0, i64::MAX, None)
+            .await
+            .unwrap();
+        assert_eq!(recovered.points.len(), 1);
+        assert_eq!(store.flush_stats().pending_points, 0);
+        store.shutdown();
+    }
+
     #[test]
     fn test_parse_telemetry_cache_mb() {
         // Absent → default
@@ -2582,6 +3176,28 @@ mod tests {
         assert_eq!(result.points.len(), 6);
     }
 
+    #[tokio::test]
+    async fn test_query_latest_at() {
+        let store = TimeSeriesStore::memory().unwrap();
+
+        for i in 0..5 {
+            let point = DataPoint::new(1000 + i * 100, i as f64);
+            store.write("device1", "valve", point).await.unwrap();
+        }
+        store.flush().unwrap();
+
+        let at = |ts| store.query_latest_at("device1", "valve", ts);
+        assert_eq!(at(1250).await.unwrap().unwrap().timestamp, 1200);
+        assert_eq!(at(1200).await.unwrap().unwrap().timestamp, 1200);
+        assert_eq!(at(5000).await.unwrap().unwrap().as_f64(), Some(4.0));
+        assert!(at(999).await.unwrap().is_none());
+        assert!(store
+            .query_latest_at("device1", "other", 5000)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
     #[tokio::test]
     async fn test_data_point_builder() {
         let point = DataPoint::new(1000, 42.0)
@@ -3306,6 +3922,56 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_retention_device_type_override_and_preview() {
+        let store = TimeSeriesStore::memory().unwrap();
+        let old_ts = Utc::now().timestamp() - 10 * 24 * 3600;
+        store
+            .write("device:cam1", "heartbeat", DataPoint::new(old_ts, 1.0))
+            .await
+            .unwrap();
+        store
+            .write(
+                "device:sensor1",
+                "temperature",
+                DataPoint::new(old_ts, 21.5),
+            )
+            .await
+            .unwrap();
+        store.flush().unwrap();
+
+        store.set_device_types(std::collections::HashMap::from([(
+            "cam1".to_string(),
+            "camera".to_string(),
+        )]));
+
+        // Keep everything a year, but camera data only a week
+        let mut policy = RetentionPolicy::new(Some(24 * 365));
+        policy.set_device_type_retention("camera".to_string(), Some(24 * 7));
+
+        let preview = store.preview_retention(&policy).await.unwrap();
+        assert_eq!(preview.total_points, 1);
+        assert_eq!(preview.entries[0].source_id, "device:cam1");
+        assert_eq!(preview.entries[0].device_type.as_deref(), Some("camera"));
+        assert_eq!(preview.entries[0].retention_hours, 24 * 7);
+
+        // Preview must not delete anything
+        assert!(store
+            .query_latest("device:cam1", "heartbeat")
+            .await
+            .unwrap()
+            .is_some());
+
+        store.set_retention_policy(policy).await;
+        let result = store.apply_retention().await.unwrap();
+        assert_eq!(result.points_removed, 1);
+        assert!(store
+            .query_latest("device:sensor1", "temperature")
+            .await
+            .unwrap()
+            .is_some());
+    }
+
     #[tokio::test]
     async fn test_data_point_with_quality_and_metadata() {
         let point =
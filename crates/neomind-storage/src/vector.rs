@@ -24,6 +24,22 @@ use crate::Error;
 // Vector table: key = document_id, value = VectorDocument (serialized)
 const VECTORS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("vectors");
 
+// HNSW graph table: single key holding the serialized graph (no vectors)
+#[cfg(feature = "hnsw")]
+const HNSW_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("vector_hnsw");
+#[cfg(feature = "hnsw")]
+const HNSW_GRAPH_KEY: &str = "graph";
+
+// HNSW journal: IDs inserted or deleted since the graph was last saved
+#[cfg(feature = "hnsw")]
+const HNSW_JOURNAL_TABLE: TableDefinition<&str, &[u8]> =
+    TableDefinition::new("vector_hnsw_journal");
+
+/// Save the HNSW graph after this many journaled changes, so reloading
+/// never has to replay more than that.
+#[cfg(feature = "hnsw")]
+const HNSW_CHECKPOINT_INTERVAL: usize = 1024;
+
 /// Vector embedding (fixed-size list of floats).
 pub type Embedding = Vec<f32>;
 
@@ -179,6 +195,38 @@ pub enum SimilarityMetric {
     Manhattan,
 }
 
+impl SimilarityMetric {
+    /// Similarity between two vectors (higher = more similar).
+    pub fn score(self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            SimilarityMetric::Cosine => {
+                let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+                let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+                let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+                if norm_a == 0.0 || norm_b == 0.0 {
+                    0.0
+                } else {
+                    dot / (norm_a * norm_b)
+                }
+            }
+            SimilarityMetric::Euclidean => {
+                let dist = a
+                    .iter()
+                    .zip(b.iter())
+                    .map(|(x, y)| (x - y) * (x - y))
+                    .sum::<f32>()
+                    .sqrt();
+                1.0 / (1.0 + dist)
+            }
+            SimilarityMetric::DotProduct => a.iter().zip(b.iter()).map(|(x, y)| x * y).sum(),
+            SimilarityMetric::Manhattan => {
+                let dist: f32 = a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum();
+                1.0 / (1.0 + dist)
+            }
+        }
+    }
+}
+
 /// HNSW-like graph node for approximate nearest neighbor search.
 ///
 /// # Note
@@ -501,47 +549,18 @@ impl VectorStore {
 
     /// Calculate similarity between two embeddings using the configured metric.
     fn similarity(&self, a: &Embedding, b: &Embedding) -> f32 {
-        match self.metric {
-            SimilarityMetric::Cosine => self.cosine_similarity(a, b),
-            SimilarityMetric::Euclidean => 1.0 / (1.0 + self.euclidean_distance(a, b)),
-            SimilarityMetric::DotProduct => self.dot_product(a, b),
-            SimilarityMetric::Manhattan => 1.0 / (1.0 + self.manhattan_distance(a, b)),
-        }
-    }
-
-    /// Cosine similarity between two vectors.
-    fn cosine_similarity(&self, a: &Embedding, b: &Embedding) -> f32 {
-        let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-        let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-        let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm_a == 0.0 || norm_b == 0.0 {
-            0.0
-        } else {
-            dot / (norm_a * norm_b)
-        }
-    }
-
-    /// Dot product of two vectors.
-    fn dot_product(&self, a: &Embedding, b: &Embedding) -> f32 {
-        a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
-    }
-
-    /// Euclidean distance between two vectors.
-    fn euclidean_distance(&self, a: &Embedding, b: &Embedding) -> f32 {
-        a.iter()
-            .zip(b.iter())
-            .map(|(x, y)| (x - y) * (x - y))
-            .sum::<f32>()
-            .sqrt()
-    }
-
-    /// Manhattan distance between two vectors.
-    fn manhattan_distance(&self, a: &Embedding, b: &Embedding) -> f32 {
-        a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum()
+        self.metric.score(a, b)
     }
 }
 
 /// Persistent vector store with redb backend.
+///
+/// With the `hnsw` feature, searches go through an HNSW graph that is
+/// updated on every insert, persisted alongside the documents, and can be
+/// compacted with `optimize()` / `rebuild_index_in_background()`. Each
+/// insert and delete journals its ID in the same transaction as the
+/// document, so loading restores the saved graph and re-applies only the
+/// journaled changes instead of rebuilding.
 pub struct PersistentVectorStore {
     /// redb database.
     db: Arc<Database>,
@@ -549,6 +568,19 @@ pub struct PersistentVectorStore {
     index: VectorStore,
     /// Storage path for singleton
     path: String,
+    /// Approximate nearest-neighbour graph over `index`.
+    #[cfg(feature = "hnsw")]
+    hnsw: parking_lot::RwLock<crate::hnsw::HnswIndex>,
+    /// Set while a background rebuild runs, so only one runs at a time.
+    #[cfg(feature = "hnsw")]
+    rebuilding: std::sync::atomic::AtomicBool,
+    /// Held from a journal write until the graph has the change, and while
+    /// the graph is saved, so a save never clears an unapplied entry.
+    #[cfg(feature = "hnsw")]
+    journal_lock: Mutex<()>,
+    /// Changes journaled since the graph was last saved.
+    #[cfg(feature = "hnsw")]
+    journaled: std::sync::atomic::AtomicUsize,
 }
 
 /// Global vector store singleton (thread-safe).
@@ -581,6 +613,17 @@ impl PersistentVectorStore {
         let index = VectorStore::new();
         let store = Arc::new(PersistentVectorStore {
             db: Arc::new(db),
+            #[cfg(feature = "hnsw")]
+            hnsw: parking_lot::RwLock::new(crate::hnsw::HnswIndex::new(
+                crate::hnsw::HnswConfig::default(),
+                index.metric,
+            )),
+            #[cfg(feature = "hnsw")]
+            rebuilding: std::sync::atomic::AtomicBool::new(false),
+            #[cfg(feature = "hnsw")]
+            journal_lock: Mutex::new(()),
+            #[cfg(feature = "hnsw")]
+            journaled: std::sync::atomic::AtomicUsize::new(0),
             index,
             path: path_str,
         });
@@ -592,7 +635,12 @@ impl PersistentVectorStore {
     /// Load all documents from disk into memory index.
     pub async fn load_index(&self) -> Result<(), Error> {
         let read_txn = self.db.begin_read()?;
-        let table = read_txn.open_table(VECTORS_TABLE)?;
+        let table = match read_txn.open_table(VECTORS_TABLE) {
+            Ok(t) => t,
+            // Nothing stored yet
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
 
         let mut docs = Vec::new();
         for result in table.iter()? {
@@ -602,21 +650,39 @@ impl PersistentVectorStore {
             }
         }
 
+        #[cfg(feature = "hnsw")]
+        self.load_hnsw(&docs);
+
         self.index.insert_batch(docs).await?;
         Ok(())
     }
 
     /// Insert a document and persist to disk.
     pub async fn insert(&self, doc: VectorDocument) -> Result<(), Error> {
-        let write_txn = self.db.begin_write()?;
         {
-            let mut table = write_txn.open_table(VECTORS_TABLE)?;
-            let value = serde_json::to_vec(&doc)?;
-            table.insert(doc.id.as_str(), value.as_slice())?;
-        }
-        write_txn.commit()?;
+            #[cfg(feature = "hnsw")]
+            let _journal = self.journal_lock.lock();
+
+            let write_txn = self.db.begin_write()?;
+            {
+                let mut table = write_txn.open_table(VECTORS_TABLE)?;
+                let value = serde_json::to_vec(&doc)?;
+                table.insert(doc.id.as_str(), value.as_slice())?;
+                #[cfg(feature = "hnsw")]
+                write_txn
+                    .open_table(HNSW_JOURNAL_TABLE)?
+                    .insert(doc.id.as_str(), [].as_slice())?;
+            }
+            write_txn.commit()?;
 
-        // Also update in-memory index
+            // Also update in-memory index
+            #[cfg(feature = "hnsw")]
+            self.hnsw
+                .write()
+                .insert(doc.id.clone(), doc.embedding.clone());
+        }
+        #[cfg(feature = "hnsw")]
+        self.checkpoint_if_due();
         self.index.insert(doc).await?;
 
         Ok(())
@@ -628,17 +694,45 @@ impl PersistentVectorStore {
         query: &Embedding,
         top_k: usize,
     ) -> Result<Vec<SearchResult>, Error> {
-        self.index.search(query, top_k).await
+        #[cfg(feature = "hnsw")]
+        let results = self.search_hnsw(query, top_k);
+        #[cfg(not(feature = "hnsw"))]
+        let results = self.index.search(query, top_k).await;
+        results
+    }
+
+    /// Search with filters. Always scans the in-memory index, since the HNSW
+    /// graph can't apply metadata filters.
+    pub async fn search_with_options(
+        &self,
+        query: &Embedding,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchResult>, Error> {
+        self.index.search_with_options(query, options).await
     }
 
     /// Delete a document.
     pub async fn delete(&self, id: &str) -> Result<bool, Error> {
-        let write_txn = self.db.begin_write()?;
         {
-            let mut table = write_txn.open_table(VECTORS_TABLE)?;
-            table.remove(id)?;
+            #[cfg(feature = "hnsw")]
+            let _journal = self.journal_lock.lock();
+
+            let write_txn = self.db.begin_write()?;
+            {
+                let mut table = write_txn.open_table(VECTORS_TABLE)?;
+                table.remove(id)?;
+                #[cfg(feature = "hnsw")]
+                write_txn
+                    .open_table(HNSW_JOURNAL_TABLE)?
+                    .insert(id, [].as_slice())?;
+            }
+            write_txn.commit()?;
+
+            #[cfg(feature = "hnsw")]
+            self.hnsw.write().remove(id);
         }
-        write_txn.commit()?;
+        #[cfg(feature = "hnsw")]
+        self.checkpoint_if_due();
 
         let _ = self.index.delete(id);
         Ok(true)
@@ -649,6 +743,16 @@ impl PersistentVectorStore {
         Ok(self.index.count())
     }
 
+    /// Get documents by category from the in-memory index.
+    pub fn get_by_category(&self, category: &str) -> Vec<VectorDocument> {
+        self.index.get_by_category(category)
+    }
+
+    /// Get documents by tag from the in-memory index.
+    pub fn get_by_tag(&self, tag: &str) -> Vec<VectorDocument> {
+        self.index.get_by_tag(tag)
+    }
+
     /// Get a document by ID.
     pub async fn get(&self, id: &str) -> Result<Option<VectorDocument>, Error> {
         let read_txn = self.db.begin_read()?;
@@ -663,6 +767,197 @@ impl PersistentVectorStore {
     }
 }
 
+#[cfg(feature = "hnsw")]
+impl PersistentVectorStore {
+    /// Rebuild the HNSW graph without tombstones and persist it.
+    ///
+    /// Runs inline; use `rebuild_index_in_background` to keep serving
+    /// searches from the current graph while the new one is built.
+    pub fn optimize(&self) -> Result<(), Error> {
+        let rebuilt = self.hnsw.read().rebuilt();
+        *self.hnsw.write() = rebuilt;
+        self.persist_index()
+    }
+
+    /// Rebuild the HNSW graph on a blocking thread and swap it in.
+    ///
+    /// Inserts and deletes that land while the rebuild runs are replayed
+    /// onto the new graph before the swap. Returns `false` if a rebuild is
+    /// already in progress.
+    pub fn rebuild_index_in_background(self: &Arc<Self>) -> bool {
+        use std::sync::atomic::Ordering;
+
+        if self
+            .rebuilding
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return false;
+        }
+
+        let store = Arc::clone(self);
+        tokio::task::spawn_blocking(move || {
+            let snapshot = store.hnsw.read().clone();
+            let mut rebuilt = snapshot.rebuilt();
+            {
+                let mut current = store.hnsw.write();
+                Self::catch_up(&mut rebuilt, &current);
+                *current = rebuilt;
+            }
+            if let Err(e) = store.persist_index() {
+                tracing::warn!(error = %e, "Failed to persist rebuilt HNSW index");
+            }
+            store.rebuilding.store(false, Ordering::Release);
+        });
+        true
+    }
+
+    /// Replay onto `rebuilt` the inserts, updates and deletes that reached
+    /// `current` after `rebuilt` was snapshotted from it.
+    fn catch_up(rebuilt: &mut crate::hnsw::HnswIndex, current: &crate::hnsw::HnswIndex) {
+        for id in current.live_ids() {
+            let Some(vector) = current.vector(&id) else {
+                continue;
+            };
+            // Compare vectors too: a re-inserted ID may carry a new one
+            if rebuilt.vector(&id) != Some(vector) {
+                rebuilt.insert(id, vector.clone());
+            }
+        }
+        for id in rebuilt.live_ids() {
+            if !current.contains(&id) {
+                rebuilt.remove(&id);
+            }
+        }
+    }
+
+    /// HNSW search, resolving hits back to their documents.
+    fn search_hnsw(&self, query: &Embedding, top_k: usize) -> Result<Vec<SearchResult>, Error> {
+        if let Some(expected) = self.index.dimension {
+            if query.len() != expected {
+                return Err(Error::InvalidDimension {
+                    expected,
+                    found: query.len(),
+                });
+            }
+        }
+        let hits = self.hnsw.read().search(query, top_k);
+        Ok(hits
+            .into_iter()
+            .filter_map(|(id, score)| {
+                self.index.get(&id).map(|doc| SearchResult {
+                    id,
+                    score,
+                    metadata: doc.metadata,
+                })
+            })
+            .collect())
+    }
+
+    /// Write the current HNSW graph to disk and clear the journal.
+    pub fn persist_index(&self) -> Result<(), Error> {
+        let _journal = self.journal_lock.lock();
+        let bytes = self.hnsw.read().to_bytes()?;
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(HNSW_TABLE)?;
+            table.insert(HNSW_GRAPH_KEY, bytes.as_slice())?;
+            // The saved graph has every journaled change
+            write_txn
+                .open_table(HNSW_JOURNAL_TABLE)?
+                .retain(|_, _| false)?;
+        }
+        write_txn.commit()?;
+        self.journaled
+            .store(0, std::sync::atomic::Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Save the graph once enough changes have been journaled.
+    fn checkpoint_if_due(&self) {
+        let journaled = self
+            .journaled
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            + 1;
+        if journaled >= HNSW_CHECKPOINT_INTERVAL {
+            if let Err(e) = self.persist_index() {
+                tracing::warn!(error = %e, "Failed to checkpoint HNSW index");
+            }
+        }
+    }
+
+    /// Restore the persisted graph and re-apply the journal, or build a
+    /// graph from `docs` if it is missing, unreadable, or out of sync with
+    /// the document table. Saves the result unless it is the saved graph.
+    fn load_hnsw(&self, docs: &[VectorDocument]) {
+        let vectors: std::collections::HashMap<String, Embedding> = docs
+            .iter()
+            .map(|d| (d.id.clone(), d.embedding.clone()))
+            .collect();
+        let journal = self.read_journal();
+
+        let restored = self.read_persisted_graph().and_then(|bytes| {
+            crate::hnsw::HnswIndex::from_bytes(&bytes, self.index.metric, &vectors)
+                .map_err(|e| tracing::warn!(error = %e, "Discarding persisted HNSW index"))
+                .ok()
+        });
+        let restored = restored.map(|mut index| {
+            for id in &journal {
+                match vectors.get(id) {
+                    // Re-inserting also replaces an updated vector's edges
+                    Some(vector) => index.insert(id.clone(), vector.clone()),
+                    None => {
+                        index.remove(id);
+                    }
+                }
+            }
+            index
+        });
+
+        let (index, saved) = match restored {
+            Some(index) if index.len() == vectors.len() => (index, journal.is_empty()),
+            _ => {
+                let config = self.hnsw.read().config();
+                let mut index = crate::hnsw::HnswIndex::new(config, self.index.metric);
+                for doc in docs {
+                    index.insert(doc.id.clone(), doc.embedding.clone());
+                }
+                (index, false)
+            }
+        };
+        *self.hnsw.write() = index;
+
+        if !saved {
+            if let Err(e) = self.persist_index() {
+                tracing::warn!(error = %e, "Failed to persist loaded HNSW index");
+            }
+        }
+    }
+
+    fn read_journal(&self) -> Vec<String> {
+        let Ok(read_txn) = self.db.begin_read() else {
+            return Vec::new();
+        };
+        let Ok(table) = read_txn.open_table(HNSW_JOURNAL_TABLE) else {
+            return Vec::new();
+        };
+        let Ok(entries) = table.iter() else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|(id, _)| id.value().to_string())
+            .collect()
+    }
+
+    fn read_persisted_graph(&self) -> Option<Vec<u8>> {
+        let read_txn = self.db.begin_read().ok()?;
+        let table = read_txn.open_table(HNSW_TABLE).ok()?;
+        let value = table.get(HNSW_GRAPH_KEY).ok()??;
+        Some(value.value().to_vec())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -927,4 +1222,105 @@ mod tests {
         let results = store.search(&query, 2).await.unwrap();
         assert_eq!(results[0].id, "doc1");
     }
+
+    #[cfg(feature = "hnsw")]
+    #[tokio::test]
+    async fn test_persistent_vector_store_hnsw_optimize() {
+        let temp_path =
+            std::env::temp_dir().join(format!("vector_hnsw_{}.redb", uuid::Uuid::new_v4()));
+        let store = PersistentVectorStore::open(&temp_path).unwrap();
+
+        for i in 0..200 {
+            let angle = i as f32 * 0.01;
+            store
+                .insert(VectorDocument::new(
+                    format!("doc{}", i),
+                    vec![angle.cos(), angle.sin()],
+                ))
+                .await
+                .unwrap();
+        }
+        store.delete("doc100").await.unwrap();
+
+        store.optimize().unwrap();
+        assert_eq!(store.hnsw.read().deleted_count(), 0);
+
+        // Reload restores the persisted graph instead of rebuilding
+        store.load_index().await.unwrap();
+        assert_eq!(store.hnsw.read().len(), 199);
+
+        // doc100 (angle 1.0) was deleted; next closest are 1.01, 0.99, 1.02
+        let query = vec![1.003f32.cos(), 1.003f32.sin()];
+        let results = store.search(&query, 3).await.unwrap();
+        let ids: Vec<_> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["doc101", "doc99", "doc102"]);
+    }
+
+    #[cfg(feature = "hnsw")]
+    #[tokio::test]
+    async fn test_persistent_vector_store_hnsw_journal() {
+        let temp_path =
+            std::env::temp_dir().join(format!("vector_hnsw_{}.redb", uuid::Uuid::new_v4()));
+        let store = PersistentVectorStore::open(&temp_path).unwrap();
+
+        for i in 0..50 {
+            let angle = i as f32 * 0.01;
+            store
+                .insert(VectorDocument::new(
+                    format!("doc{}", i),
+                    vec![angle.cos(), angle.sin()],
+                ))
+                .await
+                .unwrap();
+        }
+        store.optimize().unwrap();
+        assert!(store.read_journal().is_empty());
+
+        // Changes after the save are journaled
+        store
+            .insert(VectorDocument::new("doc0", vec![-1.0, 0.0]))
+            .await
+            .unwrap();
+        store
+            .insert(VectorDocument::new("doc50", vec![0.0, -1.0]))
+            .await
+            .unwrap();
+        store.delete("doc1").await.unwrap();
+        assert_eq!(store.read_journal().len(), 3);
+
+        // Reload restores the saved graph and applies the journal on top:
+        // the old doc0 and doc1 nodes remain as tombstones, which a rebuild
+        // from the documents would not have
+        store.load_index().await.unwrap();
+        assert_eq!(store.hnsw.read().len(), 50);
+        assert_eq!(store.hnsw.read().deleted_count(), 2);
+        assert!(store.read_journal().is_empty());
+
+        let results = store.search(&vec![-1.0, 0.0], 1).await.unwrap();
+        assert_eq!(results[0].id, "doc0");
+        let results = store.search(&vec![0.0, -1.0], 1).await.unwrap();
+        assert_eq!(results[0].id, "doc50");
+    }
+
+    #[cfg(feature = "hnsw")]
+    #[test]
+    fn test_hnsw_catch_up_uses_current_vectors() {
+        use crate::hnsw::{HnswConfig, HnswIndex};
+
+        let mut current = HnswIndex::new(HnswConfig::default(), SimilarityMetric::Cosine);
+        current.insert("a", vec![1.0, 0.0]);
+        current.insert("b", vec![0.0, 1.0]);
+        let mut rebuilt = current.rebuilt();
+
+        // Landed while the rebuild ran
+        current.insert("a", vec![-1.0, 0.0]);
+        current.insert("c", vec![0.0, -1.0]);
+        current.remove("b");
+
+        PersistentVectorStore::catch_up(&mut rebuilt, &current);
+        assert_eq!(rebuilt.vector("a"), Some(&vec![-1.0, 0.0]));
+        assert!(rebuilt.contains("c"));
+        assert!(!rebuilt.contains("b"));
+        assert_eq!(rebuilt.len(), 2);
+    }
 }
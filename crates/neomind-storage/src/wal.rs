@@ -0,0 +1,347 @@
+//! Write-ahead journal for buffered time series writes.
+//!
+//! [`TimeSeriesStore`](crate::TimeSeriesStore) buffers single-point writes and
+//! commits them to redb in batches. Every buffered point is first appended to
+//! the active journal next to the database (`<db>.wal`). When the buffer is
+//! drained for a flush, the active file is sealed as `<db>.wal.<n>` and a new
+//! one is started; a sealed file is deleted once its points are committed.
+//! On open, leftover journal files are replayed, so points accepted before a
+//! crash still reach the database.
+//!
+//! Records are JSON lines. Appends reach the OS immediately and are synced to
+//! disk at most every `sync_interval`, which bounds what a power cut can
+//! lose. A line torn by a crash or a failed append is skipped on replay,
+//! and the records after it are still recovered.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::timeseries::DataPoint;
+
+#[derive(Serialize)]
+struct RecordRef<'a> {
+    s: &'a str,
+    m: &'a str,
+    p: &'a DataPoint,
+}
+
+/// A journaled point.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Record {
+    #[serde(rename = "s")]
+    pub source_id: String,
+    #[serde(rename = "m")]
+    pub metric: String,
+    #[serde(rename = "p")]
+    pub point: DataPoint,
+}
+
+/// Path of the active journal for a database.
+fn active_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.as_os_str().to_owned();
+    name.push(".wal");
+    PathBuf::from(name)
+}
+
+/// Path of the sealed journal segment `seq` for a database.
+fn segment_path(db_path: &Path, seq: u64) -> PathBuf {
+    let mut name = active_path(db_path).into_os_string();
+    name.push(format!(".{}", seq));
+    PathBuf::from(name)
+}
+
+/// Sealed journal segments for a database, oldest first.
+fn sealed_segments(db_path: &Path) -> io::Result<Vec<(u64, PathBuf)>> {
+    let active = active_path(db_path);
+    let (Some(dir), Some(active_name)) = (active.parent(), active.file_name()) else {
+        return Ok(Vec::new());
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let prefix = format!("{}.", active_name.to_string_lossy());
+
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let seq = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(|seq| seq.parse::<u64>().ok());
+        if let Some(seq) = seq {
+            segments.push((seq, path));
+        }
+    }
+    segments.sort();
+    Ok(segments)
+}
+
+/// Journal files left for a database: sealed segments in order, then the
+/// active file.
+pub(crate) fn journal_files(db_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = sealed_segments(db_path)?
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect();
+    let active = active_path(db_path);
+    if active.exists() {
+        files.push(active);
+    }
+    Ok(files)
+}
+
+/// Seal an active file left by the previous run, so the fresh journal
+/// started after replay does not truncate points that failed to replay.
+pub(crate) fn seal_leftover(db_path: &Path) -> io::Result<()> {
+    let active = active_path(db_path);
+    if !active.exists() {
+        return Ok(());
+    }
+    let next_seq = sealed_segments(db_path)?
+        .last()
+        .map_or(0, |(seq, _)| seq + 1);
+    fs::rename(active, segment_path(db_path, next_seq))
+}
+
+/// Journal contents left for a database.
+pub(crate) struct Recovered {
+    pub records: Vec<Record>,
+    /// Files the records came from; delete them once the records are
+    /// committed
+    pub files: Vec<PathBuf>,
+    /// Unreadable lines that were skipped
+    pub skipped: usize,
+}
+
+/// Read every journal file left for a database.
+pub(crate) fn recover(db_path: &Path) -> io::Result<Recovered> {
+    let files = journal_files(db_path)?;
+
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for path in &files {
+        let reader = BufReader::new(File::open(path)?);
+        for line in reader.split(b'\n') {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            match serde_json::from_slice::<Record>(&line) {
+                Ok(record) => records.push(record),
+                Err(e) => {
+                    // A failed append may have been followed by good ones,
+                    // so keep reading past a torn line
+                    tracing::warn!(
+                        "Skipping unreadable line in time series journal {}: {}",
+                        path.display(),
+                        e
+                    );
+                    skipped += 1;
+                }
+            }
+        }
+    }
+    Ok(Recovered {
+        records,
+        files,
+        skipped,
+    })
+}
+
+/// The active journal file of a store.
+pub(crate) struct Journal {
+    db_path: PathBuf,
+    file: File,
+    /// Bytes appended to the active file
+    len: u64,
+    /// Whether an append failed partway, leaving an unterminated line
+    torn: bool,
+    next_seq: u64,
+    sync_interval: Duration,
+    last_sync: Instant,
+}
+
+impl Journal {
+    /// Start a fresh active journal. Run [`seal_leftover`] first: an existing
+    /// active file is truncated.
+    pub(crate) fn create(db_path: &Path, sync_interval: Duration) -> io::Result<Self> {
+        let next_seq = sealed_segments(db_path)?
+            .last()
+            .map_or(0, |(seq, _)| seq + 1);
+        Ok(Self {
+            db_path: db_path.to_path_buf(),
+            file: Self::open_active(db_path)?,
+            len: 0,
+            torn: false,
+            next_seq,
+            sync_interval,
+            last_sync: Instant::now(),
+        })
+    }
+
+    fn open_active(db_path: &Path) -> io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(active_path(db_path))
+    }
+
+    /// Append a point.
+    pub(crate) fn append(
+        &mut self,
+        source_id: &str,
+        metric: &str,
+        point: &DataPoint,
+    ) -> io::Result<()> {
+        let mut line = Vec::new();
+        if self.torn {
+            // End the partial line so this record is readable on its own
+            line.push(b'\n');
+        }
+        serde_json::to_writer(
+            &mut line,
+            &RecordRef {
+                s: source_id,
+                m: metric,
+                p: point,
+            },
+        )?;
+        line.push(b'\n');
+        self.torn = true;
+        self.file.write_all(&line)?;
+        self.torn = false;
+        self.len += line.len() as u64;
+        if self.last_sync.elapsed() >= self.sync_interval {
+            self.file.sync_data()?;
+            self.last_sync = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Seal the active file and start a new one. Returns the sealed file, or
+    /// `None` if nothing was appended since the last seal.
+    pub(crate) fn seal(&mut self) -> io::Result<Option<PathBuf>> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.file.sync_data()?;
+        let sealed = segment_path(&self.db_path, self.next_seq);
+        fs::rename(active_path(&self.db_path), &sealed)?;
+        self.next_seq += 1;
+        self.file = Self::open_active(&self.db_path)?;
+        self.len = 0;
+        self.torn = false;
+        self.last_sync = Instant::now();
+        Ok(Some(sealed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(timestamp: i64) -> DataPoint {
+        DataPoint {
+            timestamp,
+            value: serde_json::json!(timestamp * 10),
+            quality: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_seal_and_recover() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("telemetry.redb");
+        let mut journal = Journal::create(&db, Duration::ZERO).unwrap();
+
+        journal.append("device:s1", "temp", &point(1)).unwrap();
+        journal.append("device:s1", "temp", &point(2)).unwrap();
+        let sealed = journal.seal().unwrap().unwrap();
+        assert!(sealed.to_string_lossy().ends_with(".wal.0"));
+        assert!(journal.seal().unwrap().is_none());
+        journal.append("device:s2", "hum", &point(3)).unwrap();
+
+        // Simulate a crash in the middle of an append
+        let mut active = OpenOptions::new()
+            .append(true)
+            .open(active_path(&db))
+            .unwrap();
+        active.write_all(b"{\"s\":\"device:s2\",\"m\":").unwrap();
+        drop(journal);
+
+        let Recovered {
+            records,
+            files,
+            skipped,
+        } = recover(&db).unwrap();
+        assert_eq!(files.len(), 2);
+        let timestamps: Vec<i64> = records.iter().map(|r| r.point.timestamp).collect();
+        assert_eq!(timestamps, vec![1, 2, 3]);
+        assert_eq!(records[2].source_id, "device:s2");
+        assert_eq!(skipped, 1);
+
+        // A new journal continues the segment numbering
+        let mut journal = Journal::create(&db, Duration::ZERO).unwrap();
+        journal.append("device:s1", "temp", &point(4)).unwrap();
+        let sealed = journal.seal().unwrap().unwrap();
+        assert!(sealed.to_string_lossy().ends_with(".wal.1"));
+    }
+
+    #[test]
+    fn test_seal_leftover() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("telemetry.redb");
+        seal_leftover(&db).unwrap();
+
+        let mut journal = Journal::create(&db, Duration::ZERO).unwrap();
+        journal.append("device:s1", "temp", &point(1)).unwrap();
+        journal.seal().unwrap();
+        journal.append("device:s1", "temp", &point(2)).unwrap();
+        drop(journal);
+
+        // The leftover active file becomes the newest segment, so a fresh
+        // journal no longer truncates it
+        seal_leftover(&db).unwrap();
+        assert!(!active_path(&db).exists());
+        drop(Journal::create(&db, Duration::ZERO).unwrap());
+        let recovered = recover(&db).unwrap();
+        assert_eq!(
+            recovered.files,
+            vec![segment_path(&db, 0), segment_path(&db, 1), active_path(&db)]
+        );
+        assert_eq!(recovered.records.len(), 2);
+    }
+
+    #[test]
+    fn test_recover_past_torn_append() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("telemetry.redb");
+        let mut journal = Journal::create(&db, Duration::ZERO).unwrap();
+        journal.append("device:s1", "temp", &point(1)).unwrap();
+
+        // An append that failed partway, followed by successful ones
+        journal.file.write_all(b"{\"s\":\"device:s1\",").unwrap();
+        journal.torn = true;
+        journal.append("device:s1", "temp", &point(2)).unwrap();
+        journal.append("device:s1", "temp", &point(3)).unwrap();
+        drop(journal);
+
+        let recovered = recover(&db).unwrap();
+        let timestamps: Vec<i64> = recovered
+            .records
+            .iter()
+            .map(|r| r.point.timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![1, 2, 3]);
+        assert_eq!(recovered.skipped, 1);
+    }
+}
@@ -13,6 +13,12 @@ use tauri::{image::Image, AppHandle, Emitter, Listener, Manager};
 use tokio::runtime::Runtime;
 use tracing::info;
 
+// Metered so Script transforms in the embedded server get a heap cap
+#[cfg(feature = "script-heap-limit")]
+#[global_allocator]
+static GLOBAL: edge_api::automation::MeteredAlloc<std::alloc::System> =
+    edge_api::automation::MeteredAlloc(std::alloc::System);
+
 // Global state for the Axum server
 struct ServerState {
     runtime: Arc<Mutex<Option<Runtime>>>,
@@ -25,6 +25,7 @@ import { useUpdateCheck } from '@/hooks/useUpdateCheck'
 // Each page is loaded on-demand, reducing Time to Interactive by ~70%
 const LoginPage = lazy(() => import('@/pages/login').then(m => ({ default: m.LoginPage })))
 const SetupPage = lazy(() => import('@/pages/setup').then(m => ({ default: m.SetupPage })))
+const MfaSetupPage = lazy(() => import('@/pages/mfa-setup').then(m => ({ default: m.MfaSetupPage })))
 const ChatPage = lazy(() => import('@/pages/chat').then(m => ({ default: m.ChatPage })))
 const VisualDashboard = lazy(() =>
   import('@/pages/dashboard-components/VisualDashboard').then(m => ({ default: m.VisualDashboard }))
@@ -82,6 +83,7 @@ window.addEventListener('unhandledrejection', (event) => {
 // Checks authentication first, then setup status in background
 function ProtectedRoute({ children }: { children: React.ReactNode }) {
   const [setupRequired, setSetupRequired] = useState<boolean | false>(false)
+  const mfaSetupRequired = useStore((s) => s.mfaSetupRequired)
 
   // Warm the canonical server URL cache so webhook URL displays don't flash
   // localhost before the backend-provided LAN IP arrives (Tauri mode only).
@@ -120,6 +122,11 @@ function ProtectedRoute({ children }: { children: React.ReactNode }) {
     return <Navigate to="/login" replace />
   }
 
+  // Admin without a second factor - the session only reaches MFA setup
+  if (mfaSetupRequired && token) {
+    return <Navigate to="/mfa-setup" replace />
+  }
+
   // Setup required - redirect to setup page
   if (setupRequired) {
     return <Navigate to="/setup" replace />
@@ -245,8 +252,9 @@ function App() {
       delete (window as any).NeoMindStream
     }
   }, [])
-  const { isAuthenticated, checkAuthStatus, setWsConnected, updateDialogOpen } = useStore((s) => ({
+  const { isAuthenticated, mfaSetupRequired, checkAuthStatus, setWsConnected, updateDialogOpen } = useStore((s) => ({
     isAuthenticated: s.isAuthenticated,
+    mfaSetupRequired: s.mfaSetupRequired,
     checkAuthStatus: s.checkAuthStatus,
     setWsConnected: s.setWsConnected,
     updateDialogOpen: s.updateDialogOpen,
@@ -398,7 +406,8 @@ function App() {
   // Unified WebSocket + SSE connection management
   // Connects when authenticated (not on setup), disconnects otherwise
   useEffect(() => {
-    if (isAuthenticated && currentPath !== '/setup') {
+    const setupOnly = currentPath === '/setup' || mfaSetupRequired
+    if (isAuthenticated && !setupOnly) {
       import('@/lib/websocket').then(({ ws }) => {
         // Register connection handler for state tracking + extension re-sync
         const cleanup = ws.onConnection((connected, isReconnect) => {
@@ -418,7 +427,7 @@ function App() {
       })
       // Sync extension components immediately on auth
       extensionSyncRef.current?.()
-    } else if (!isAuthenticated || currentPath === '/setup') {
+    } else {
       import('@/lib/websocket').then(({ ws }) => {
         ws.disconnect()
       })
@@ -426,7 +435,7 @@ function App() {
         closeAllEventsConnections()
       })
     }
-  }, [isAuthenticated, setWsConnected, currentPath])
+  }, [isAuthenticated, mfaSetupRequired, setWsConnected, currentPath])
 
 
   // Show loading screen in Tauri until backend is ready
@@ -465,6 +474,9 @@ function App() {
           {/* Login route */}
           <Route path="/login" element={<LoginPage />} />
 
+          {/* Second-factor enrollment for admins without one */}
+          <Route path="/mfa-setup" element={<MfaSetupPage />} />
+
           {/* Shared dashboard (public, no auth required) */}
           <Route path="/share/:token" element={<SharedDashboardPage />} />
 
@@ -0,0 +1,90 @@
+/**
+ * First second-factor enrollment: authenticator app or passkey, then the
+ * recovery codes. Used by the setup wizard and the forced setup page.
+ */
+
+import { useState } from "react"
+import { useTranslation } from "react-i18next"
+import { Fingerprint, Shield, Smartphone } from "lucide-react"
+import { Button } from "@/components/ui/button"
+import { api } from "@/lib/api"
+import { createPasskey, isPasskeySupported } from "@/lib/auth/webauthn"
+import { TotpEnrollment } from "./TotpEnrollment"
+import { RecoveryCodes } from "./RecoveryCodes"
+import type { MfaProof } from "@/types"
+
+interface MfaEnrollmentProps {
+  onDone: () => void
+}
+
+/** Register a passkey on this device; returns recovery codes for a first factor */
+export async function registerPasskey(name: string, proof: MfaProof = {}): Promise<string[] | null> {
+  const { publicKey } = await api.beginPasskeyRegistration()
+  const credential = await createPasskey(publicKey)
+  const res = await api.finishPasskeyRegistration(credential, name, proof)
+  return res.recovery_codes
+}
+
+export function MfaEnrollment({ onDone }: MfaEnrollmentProps) {
+  const { t } = useTranslation('auth')
+  const [method, setMethod] = useState<'choose' | 'totp'>('choose')
+  const [recoveryCodes, setRecoveryCodes] = useState<string[] | null>(null)
+  const [isLoading, setIsLoading] = useState(false)
+  const [error, setError] = useState("")
+
+  const handleEnrolled = (codes: string[] | null) => {
+    if (codes && codes.length > 0) {
+      setRecoveryCodes(codes)
+    } else {
+      onDone()
+    }
+  }
+
+  const handlePasskey = async () => {
+    setError("")
+    setIsLoading(true)
+    try {
+      handleEnrolled(await registerPasskey(t('mfaDefaultPasskeyName')))
+    } catch (err) {
+      setError(err instanceof Error && err.message ? err.message : t('mfaPasskeyFailed'))
+    } finally {
+      setIsLoading(false)
+    }
+  }
+
+  if (recoveryCodes) {
+    return <RecoveryCodes codes={recoveryCodes} onDone={onDone} />
+  }
+
+  if (method === 'totp') {
+    return <TotpEnrollment onComplete={handleEnrolled} onCancel={() => setMethod('choose')} />
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">{t('mfaSetupDesc')}</p>
+      <Button variant="outline" className="h-auto w-full justify-start gap-3 p-4" onClick={() => setMethod('totp')}>
+        <Smartphone className="h-5 w-5 text-primary flex-shrink-0" />
+        <div className="text-left">
+          <div className="font-medium">{t('mfaAuthenticatorApp')}</div>
+          <div className="text-xs text-muted-foreground font-normal">{t('mfaAuthenticatorAppDesc')}</div>
+        </div>
+      </Button>
+      {isPasskeySupported() && (
+        <Button variant="outline" className="h-auto w-full justify-start gap-3 p-4" disabled={isLoading} onClick={handlePasskey}>
+          <Fingerprint className="h-5 w-5 text-primary flex-shrink-0" />
+          <div className="text-left">
+            <div className="font-medium">{t('mfaPasskey')}</div>
+            <div className="text-xs text-muted-foreground font-normal">{t('mfaPasskeyDesc')}</div>
+          </div>
+        </Button>
+      )}
+      {error && (
+        <div className="flex items-start gap-2 text-sm text-error bg-muted rounded-md p-3">
+          <Shield className="h-4 w-4 mt-0.5 flex-shrink-0" />
+          <span>{error}</span>
+        </div>
+      )}
+    </div>
+  )
+}
@@ -0,0 +1,112 @@
+/**
+ * Second step of login: authenticator code, recovery code or passkey.
+ */
+
+import { useState } from "react"
+import { useTranslation } from "react-i18next"
+import { ArrowLeft, Fingerprint, KeyRound, Shield } from "lucide-react"
+import { Button } from "@/components/ui/button"
+import { Input } from "@/components/ui/input"
+import { getPasskeyAssertion, isPasskeySupported } from "@/lib/auth/webauthn"
+import type { MfaLoginChallenge, MfaProof } from "@/types"
+
+interface MfaLoginFormProps {
+  challenge: MfaLoginChallenge
+  onSubmit: (proof: MfaProof) => Promise<void>
+  onCancel: () => void
+}
+
+export function MfaLoginForm({ challenge, onSubmit, onCancel }: MfaLoginFormProps) {
+  const { t } = useTranslation('auth')
+  const [code, setCode] = useState("")
+  const [useRecoveryCode, setUseRecoveryCode] = useState(!challenge.methods.includes('totp'))
+  const [isLoading, setIsLoading] = useState(false)
+  const [error, setError] = useState("")
+
+  const canUsePasskey = challenge.methods.includes('webauthn') && !!challenge.webauthn && isPasskeySupported()
+
+  const submit = async (proof: MfaProof) => {
+    setError("")
+    setIsLoading(true)
+    try {
+      await onSubmit(proof)
+    } catch (err) {
+      setError(err instanceof Error && err.message ? err.message : t('mfaInvalidCode'))
+    } finally {
+      setIsLoading(false)
+    }
+  }
+
+  const handleSubmit = (e: React.FormEvent) => {
+    e.preventDefault()
+    const value = code.trim()
+    submit(useRecoveryCode ? { recovery_code: value } : { code: value })
+  }
+
+  const handlePasskey = async () => {
+    if (!challenge.webauthn) return
+    try {
+      const webauthn = await getPasskeyAssertion(challenge.webauthn)
+      await submit({ webauthn })
+    } catch (err) {
+      setError(err instanceof Error ? err.message : t('mfaPasskeyFailed'))
+    }
+  }
+
+  return (
+    <form onSubmit={handleSubmit} className="flex flex-col gap-4 sm:gap-5">
+      <p className="text-sm text-muted-foreground text-center">
+        {useRecoveryCode ? t('mfaRecoveryCodeDesc') : t('mfaCodeDesc')}
+      </p>
+      <div className="relative">
+        <KeyRound className="absolute left-3 top-1/2 -translate-y-1/2 h-4 w-4 text-muted-foreground pointer-events-none" />
+        <Input
+          id="mfa-code"
+          type="text"
+          value={code}
+          onChange={(e) => setCode(e.target.value)}
+          placeholder={useRecoveryCode ? t('mfaRecoveryCodePlaceholder') : t('mfaCodePlaceholder')}
+          autoComplete="one-time-code"
+          inputMode={useRecoveryCode ? "text" : "numeric"}
+          autoFocus
+          required
+          className="pl-9 h-11 bg-bg-70 border-border focus:bg-background dark:focus:bg-bg-50 focus:border-primary transition-colors text-base tracking-widest"
+        />
+      </div>
+      {error && (
+        <div className="flex items-start gap-2 text-sm text-error bg-muted rounded-md p-3">
+          <Shield className="h-4 w-4 mt-0.5 flex-shrink-0" />
+          <span>{error}</span>
+        </div>
+      )}
+      <Button type="submit" disabled={isLoading || !code.trim()} className="h-11 w-full">
+        {isLoading ? t('mfaVerifying') : t('mfaVerify')}
+      </Button>
+      {canUsePasskey && (
+        <Button type="button" variant="outline" disabled={isLoading} className="h-11 w-full gap-2" onClick={handlePasskey}>
+          <Fingerprint className="h-4 w-4" />
+          {t('mfaUsePasskey')}
+        </Button>
+      )}
+      <div className="flex items-center justify-between text-sm">
+        <button
+          type="button"
+          onClick={onCancel}
+          className="inline-flex items-center gap-1 text-muted-foreground hover:text-foreground transition-colors"
+        >
+          <ArrowLeft className="h-3.5 w-3.5" />
+          {t('mfaBackToLogin')}
+        </button>
+        {challenge.methods.includes('totp') && challenge.methods.includes('recovery_code') && (
+          <button
+            type="button"
+            onClick={() => { setUseRecoveryCode((v) => !v); setCode(""); setError("") }}
+            className="text-muted-foreground hover:text-foreground transition-colors"
+          >
+            {useRecoveryCode ? t('mfaUseAuthenticator') : t('mfaUseRecoveryCode')}
+          </button>
+        )}
+      </div>
+    </form>
+  )
+}
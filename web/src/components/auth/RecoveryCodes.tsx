@@ -0,0 +1,45 @@
+/**
+ * One-time display of freshly generated recovery codes.
+ */
+
+import { useState } from "react"
+import { useTranslation } from "react-i18next"
+import { Check, Copy } from "lucide-react"
+import { Button } from "@/components/ui/button"
+
+interface RecoveryCodesProps {
+  codes: string[]
+  onDone: () => void
+}
+
+export function RecoveryCodes({ codes, onDone }: RecoveryCodesProps) {
+  const { t } = useTranslation('auth')
+  const [copied, setCopied] = useState(false)
+
+  const handleCopy = async () => {
+    try {
+      await navigator.clipboard.writeText(codes.join('\n'))
+      setCopied(true)
+    } catch { /* ignore */ }
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">{t('mfaRecoveryCodesDesc')}</p>
+      <div className="grid grid-cols-2 gap-2 rounded-lg bg-muted p-4 font-mono text-sm">
+        {codes.map((code) => (
+          <span key={code}>{code}</span>
+        ))}
+      </div>
+      <div className="flex gap-2">
+        <Button variant="outline" className="gap-2" onClick={handleCopy}>
+          {copied ? <Check className="h-4 w-4" /> : <Copy className="h-4 w-4" />}
+          {copied ? t('mfaCopied') : t('mfaCopyCodes')}
+        </Button>
+        <Button className="flex-1" onClick={onDone}>
+          {t('mfaSavedCodes')}
+        </Button>
+      </div>
+    </div>
+  )
+}
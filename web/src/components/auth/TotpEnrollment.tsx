@@ -0,0 +1,101 @@
+/**
+ * Authenticator app enrollment: show the secret, confirm the first code.
+ */
+
+import { useEffect, useState } from "react"
+import { useTranslation } from "react-i18next"
+import { KeyRound, Loader2, Shield } from "lucide-react"
+import { Button } from "@/components/ui/button"
+import { Input } from "@/components/ui/input"
+import { api } from "@/lib/api"
+import type { MfaProof, TotpSetupResponse } from "@/types"
+
+interface TotpEnrollmentProps {
+  /** Existing factor, needed when the user already has one */
+  getProof?: () => Promise<MfaProof>
+  /** Called with recovery codes when this was the user's first factor */
+  onComplete: (recoveryCodes: string[] | null) => void
+  onCancel?: () => void
+}
+
+export function TotpEnrollment({ getProof, onComplete, onCancel }: TotpEnrollmentProps) {
+  const { t } = useTranslation('auth')
+  const [setup, setSetup] = useState<TotpSetupResponse | null>(null)
+  const [code, setCode] = useState("")
+  const [isLoading, setIsLoading] = useState(false)
+  const [error, setError] = useState("")
+
+  useEffect(() => {
+    api.beginTotpSetup()
+      .then(setSetup)
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)))
+  }, [])
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault()
+    setError("")
+    setIsLoading(true)
+    try {
+      const proof = getProof ? await getProof() : {}
+      const res = await api.confirmTotpSetup(code.trim(), proof)
+      onComplete(res.recovery_codes)
+    } catch (err) {
+      setError(err instanceof Error && err.message ? err.message : t('mfaInvalidCode'))
+    } finally {
+      setIsLoading(false)
+    }
+  }
+
+  if (!setup) {
+    return error ? (
+      <p className="text-sm text-error">{error}</p>
+    ) : (
+      <div className="flex justify-center py-6">
+        <Loader2 className="h-5 w-5 animate-spin text-muted-foreground" />
+      </div>
+    )
+  }
+
+  return (
+    <form onSubmit={handleSubmit} className="space-y-4">
+      <p className="text-sm text-muted-foreground">{t('mfaTotpSetupDesc')}</p>
+      <div className="rounded-lg bg-muted p-4 space-y-2">
+        <p className="text-xs text-muted-foreground">{t('mfaSecretKey')}</p>
+        <p className="font-mono text-sm break-all select-all">{setup.secret}</p>
+        <a href={setup.otpauth_uri} className="text-sm text-primary hover:underline">
+          {t('mfaOpenAuthenticator')}
+        </a>
+      </div>
+      <div className="relative">
+        <KeyRound className="absolute left-3 top-1/2 -translate-y-1/2 h-4 w-4 text-muted-foreground pointer-events-none" />
+        <Input
+          id="totp-code"
+          type="text"
+          value={code}
+          onChange={(e) => setCode(e.target.value)}
+          placeholder={t('mfaCodePlaceholder')}
+          autoComplete="one-time-code"
+          inputMode="numeric"
+          required
+          className="pl-9 h-11 tracking-widest"
+        />
+      </div>
+      {error && (
+        <div className="flex items-start gap-2 text-sm text-error bg-muted rounded-md p-3">
+          <Shield className="h-4 w-4 mt-0.5 flex-shrink-0" />
+          <span>{error}</span>
+        </div>
+      )}
+      <div className="flex gap-2">
+        {onCancel && (
+          <Button type="button" variant="outline" onClick={onCancel}>
+            {t('common:cancel')}
+          </Button>
+        )}
+        <Button type="submit" className="flex-1" disabled={isLoading || !code.trim()}>
+          {isLoading ? t('mfaVerifying') : t('mfaEnable')}
+        </Button>
+      </div>
+    </form>
+  )
+}
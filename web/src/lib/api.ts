@@ -12,7 +12,13 @@ if (typeof AbortSignal !== 'undefined' && !AbortSignal.timeout) {
 import type {
   UserInfo,
   LoginResponse,
+  MfaProof,
+  MfaStatus,
+  PasskeyInfo,
+  PublicKeyRequestOptionsJSON,
+  TotpSetupResponse,
   RegisterRequest,
+  UserRole,
   ChangePasswordRequest,
   Device,
   DeviceType,
@@ -110,6 +116,7 @@ import type {
 import type { SkillSummary, SkillDetail } from '@/types/skill'
 import { notifyFromError, notifySuccess } from './notify'
 import { tokenManager as unifiedTokenManager } from './auth'
+import type { PasskeyCredential, PublicKeyCreationOptionsJSON } from './auth/webauthn'
 
 // In Tauri, we need to use the full URL since the backend runs on port 9375
 // In development/web, we can use relative path
@@ -324,28 +331,37 @@ export async function fetchAPI<T>(
     }
   }
 
+  // Keep the parsed error body on the thrown error (e.g. the MFA challenge
+  // that comes with a 401 from /auth/login)
+  const parseErrorBody = (response: Response): Promise<unknown> =>
+    response.clone().json().catch(() => undefined)
+
   // Handle 401 Unauthorized - trigger callbacks and throw error
   if (response.status === 401) {
     if (!skipGlobalError) {
       triggerUnauthorizedCallbacks()
     }
+    const data = await parseErrorBody(response)
     const message = await parseErrorMessage(response)
     if (!skipErrorToast && shouldShowUnauthorizedToast()) {
       notifyFromError(message, 'Unauthorized')
     }
     const err = new Error(message)
     ;(err as any).status = 401
+    ;(err as any).data = data
     throw err
   }
 
   // Handle other errors
   if (!response.ok) {
+    const data = await parseErrorBody(response)
     const message = await parseErrorMessage(response)
     if (!skipErrorToast) {
       notifyFromError(message)
     }
     const err = new Error(message)
     ;(err as any).status = response.status
+    ;(err as any).data = data
     throw err
   }
 
@@ -419,11 +435,25 @@ export const api = {
       body: JSON.stringify({ username, password }),
       skipAuth: true,
       skipGlobalError: true,
+      // The login page shows errors inline, including the second-factor step
+      skipErrorToast: true,
     }).then(res => {
       // Store token
       tokenManager.setToken(res.token, rememberMe)
       return res
     }),
+  /** Finish a login with the MFA token from the 401 challenge and a second factor */
+  loginMfa: (mfaToken: string, proof: MfaProof, rememberMe: boolean = false) =>
+    fetchAPI<LoginResponse>('/auth/login/mfa', {
+      method: 'POST',
+      body: JSON.stringify({ mfa_token: mfaToken, ...proof }),
+      skipAuth: true,
+      skipGlobalError: true,
+      skipErrorToast: true,
+    }).then(res => {
+      tokenManager.setToken(res.token, rememberMe)
+      return res
+    }),
   register: (username: string, password: string) =>
     fetchAPI<LoginResponse>('/auth/register', {
       method: 'POST',
@@ -450,6 +480,53 @@ export const api = {
       method: 'POST',
       body: JSON.stringify(req),
     }),
+
+  // ========== Second Factor API ==========
+  getMfaStatus: () =>
+    fetchAPI<MfaStatus>('/auth/mfa', { skipErrorToast: true }),
+  beginTotpSetup: () =>
+    fetchAPI<TotpSetupResponse>('/auth/mfa/totp', { method: 'POST' }),
+  /** Returns recovery codes when this is the user's first factor */
+  confirmTotpSetup: (code: string, proof: MfaProof = {}) =>
+    fetchAPI<{ enabled: boolean; recovery_codes: string[] | null }>('/auth/mfa/totp/confirm', {
+      method: 'POST',
+      body: JSON.stringify({ code, proof }),
+      skipErrorToast: true,
+    }),
+  disableTotp: (proof: MfaProof) =>
+    fetchAPI<{ message: string }>('/auth/mfa/totp/disable', {
+      method: 'POST',
+      body: JSON.stringify(proof),
+      skipErrorToast: true,
+    }),
+  regenerateRecoveryCodes: (proof: MfaProof) =>
+    fetchAPI<{ recovery_codes: string[] }>('/auth/mfa/recovery-codes', {
+      method: 'POST',
+      body: JSON.stringify(proof),
+      skipErrorToast: true,
+    }),
+  beginPasskeyRegistration: () =>
+    fetchAPI<{ publicKey: PublicKeyCreationOptionsJSON }>('/auth/mfa/webauthn/register', {
+      method: 'POST',
+    }),
+  /** Returns recovery codes when this is the user's first factor */
+  finishPasskeyRegistration: (credential: PasskeyCredential, name: string, proof: MfaProof = {}) =>
+    fetchAPI<{ passkey: PasskeyInfo; recovery_codes: string[] | null }>('/auth/mfa/webauthn/register/finish', {
+      method: 'POST',
+      body: JSON.stringify({ ...credential, name, proof }),
+      skipErrorToast: true,
+    }),
+  removePasskey: (id: string, proof: MfaProof) =>
+    fetchAPI<{ message: string }>(`/auth/mfa/webauthn/${encodeURIComponent(id)}/remove`, {
+      method: 'POST',
+      body: JSON.stringify(proof),
+      skipErrorToast: true,
+    }),
+  /** Passkey options for confirming a sensitive action; null without passkeys */
+  getMfaChallenge: () =>
+    fetchAPI<{ webauthn: { publicKey: PublicKeyRequestOptionsJSON } | null }>('/auth/mfa/challenge', {
+      method: 'POST',
+    }),
   listUsers: () =>
     fetchAPI<{ users: UserInfo[] }>('/users'),
   createUser: (req: RegisterRequest) =>
@@ -461,6 +538,11 @@ export const api = {
     fetchAPI<{ message: string }>(`/users/${username}`, {
       method: 'DELETE',
     }),
+  updateUserRole: (username: string, role: UserRole) =>
+    fetchAPI<{ user: UserInfo }>(`/users/${username}/role`, {
+      method: 'PUT',
+      body: JSON.stringify({ role }),
+    }),
 
   // Devices
   getDevices: () => fetchAPI<{ devices: Device[]; count: number }>('/devices'),
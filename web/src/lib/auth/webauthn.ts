@@ -0,0 +1,109 @@
+/**
+ * Passkey (WebAuthn) helpers
+ *
+ * The server sends and expects binary fields as base64url strings; the
+ * browser API works with ArrayBuffers.
+ */
+
+import type { PasskeyAssertion, PublicKeyRequestOptionsJSON } from '@/types'
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+function fromBase64Url(value: string): ArrayBuffer {
+  const base64 = value.replace(/-/g, '+').replace(/_/g, '/')
+  const padded = base64 + '='.repeat((4 - (base64.length % 4)) % 4)
+  const binary = atob(padded)
+  const bytes = new Uint8Array(binary.length)
+  for (let i = 0; i < binary.length; i++) {
+    bytes[i] = binary.charCodeAt(i)
+  }
+  return bytes.buffer as ArrayBuffer
+}
+
+function toBase64Url(buffer: ArrayBuffer): string {
+  const bytes = new Uint8Array(buffer)
+  let binary = ''
+  for (let i = 0; i < bytes.length; i++) {
+    binary += String.fromCharCode(bytes[i])
+  }
+  return btoa(binary).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '')
+}
+
+// ============================================================================
+// Browser Calls
+// ============================================================================
+
+export function isPasskeySupported(): boolean {
+  return typeof window !== 'undefined' && !!window.PublicKeyCredential
+}
+
+/** Creation options as returned by /auth/mfa/webauthn/register */
+export interface PublicKeyCreationOptionsJSON {
+  challenge: string
+  rp: { id: string; name: string }
+  user: { id: string; name: string; displayName: string }
+  pubKeyCredParams: { type: 'public-key'; alg: number }[]
+  timeout?: number
+  attestation?: AttestationConveyancePreference
+  authenticatorSelection?: AuthenticatorSelectionCriteria
+  excludeCredentials?: { type: 'public-key'; id: string }[]
+}
+
+/** New passkey in the shape /auth/mfa/webauthn/register/finish expects */
+export interface PasskeyCredential {
+  id: string
+  client_data_json: string
+  attestation_object: string
+}
+
+export async function createPasskey(
+  options: PublicKeyCreationOptionsJSON
+): Promise<PasskeyCredential> {
+  const credential = (await navigator.credentials.create({
+    publicKey: {
+      ...options,
+      challenge: fromBase64Url(options.challenge),
+      user: { ...options.user, id: fromBase64Url(options.user.id) },
+      excludeCredentials: options.excludeCredentials?.map((c) => ({
+        type: c.type,
+        id: fromBase64Url(c.id),
+      })),
+    },
+  })) as PublicKeyCredential | null
+  if (!credential) {
+    throw new Error('Passkey registration was cancelled')
+  }
+  const response = credential.response as AuthenticatorAttestationResponse
+  return {
+    id: credential.id,
+    client_data_json: toBase64Url(response.clientDataJSON),
+    attestation_object: toBase64Url(response.attestationObject),
+  }
+}
+
+export async function getPasskeyAssertion(
+  options: PublicKeyRequestOptionsJSON
+): Promise<PasskeyAssertion> {
+  const credential = (await navigator.credentials.get({
+    publicKey: {
+      ...options,
+      challenge: fromBase64Url(options.challenge),
+      allowCredentials: options.allowCredentials?.map((c) => ({
+        type: c.type,
+        id: fromBase64Url(c.id),
+      })),
+    },
+  })) as PublicKeyCredential | null
+  if (!credential) {
+    throw new Error('Passkey sign-in was cancelled')
+  }
+  const response = credential.response as AuthenticatorAssertionResponse
+  return {
+    id: credential.id,
+    client_data_json: toBase64Url(response.clientDataJSON),
+    authenticator_data: toBase64Url(response.authenticatorData),
+    signature: toBase64Url(response.signature),
+  }
+}
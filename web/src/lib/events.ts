@@ -12,6 +12,8 @@ export type EventType =
   | 'DeviceTransportOffline'
   | 'DeviceMetric'
   | 'DeviceCommandResult'
+  | 'DeviceCommandExpired'
+  | 'Anomaly'
   | 'RuleEvaluated'
   | 'RuleTriggered'
   | 'RuleExecuted'
@@ -323,7 +325,9 @@ export class EventsWebSocket {
     if (category !== 'all') {
       params.set('category', category)
     }
-    eventTypes.forEach(type => params.append('event_type', type))
+    if (eventTypes.length > 0) {
+      params.set('event_type', eventTypes.join(','))
+    }
 
     const apiKey = getApiKey()
     const token = tokenManager.getToken()
@@ -455,7 +459,9 @@ export class EventsWebSocket {
     if (category !== 'all') {
       params.set('category', category)
     }
-    eventTypes.forEach(type => params.append('event_type', type))
+    if (eventTypes.length > 0) {
+      params.set('event_type', eventTypes.join(','))
+    }
 
     // Use API key or JWT token for authentication
     const apiKey = getApiKey()
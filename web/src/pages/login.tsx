@@ -20,6 +20,8 @@ import { textNano } from '@/design-system/tokens/typography'
 import { tokenManager, getApiBase, getApiKey, setApiBase, clearApiKey, setApiKey } from "@/lib/api"
 import { INSTANCE_CACHE_KEY, CURRENT_INSTANCE_KEY, PENDING_SWITCH_KEY } from "@/lib/instance-constants"
 import { decryptApiKey } from "@/store/slices/instanceSlice"
+import { MfaLoginForm } from "@/components/auth/MfaLoginForm"
+import type { MfaProof } from "@/types"
 
 const languages = [
   { code: 'en', name: 'English' },
@@ -77,7 +79,7 @@ function translateError(error: string, t: (key: string, params?: Record<string,
 
 export function LoginPage() {
   const { t, i18n } = useTranslation(['common', 'auth', 'instances'])
-  const { login, checkAuthStatus } = useStore()
+  const { login, completeMfaLogin, cancelMfaLogin, mfaChallenge, checkAuthStatus } = useStore()
   const navigate = useNavigate()
   const [username, setUsername] = useState("")
   const [password, setPassword] = useState("")
@@ -253,7 +255,7 @@ export function LoginPage() {
     setIsLoading(true)
 
     try {
-      await login(username, password, rememberMe)
+      const done = await login(username, password, rememberMe)
       if (rememberMe) {
         try { localStorage.setItem(CREDENTIALS_KEY, JSON.stringify({ username, password, rememberMe: true })) } catch { /* ignore */ }
       } else {
@@ -261,7 +263,10 @@ export function LoginPage() {
       }
       setHasLoadedCredentials(true)
       forceViewportReset()
-      navigate('/', { replace: true })
+      // Otherwise the second-factor step is shown
+      if (done) {
+        navigate('/', { replace: true })
+      }
     } catch (err) {
       setError(translateError(err instanceof Error ? err.message : String(t('auth:loginFailed')), t))
       forceViewportReset()
@@ -270,6 +275,12 @@ export function LoginPage() {
     }
   }
 
+  const handleMfaSubmit = async (proof: MfaProof) => {
+    await completeMfaLogin(proof, rememberMe)
+    forceViewportReset()
+    navigate('/', { replace: true })
+  }
+
   const handleBackdropClick = () => {
     forceViewportReset()
     if (document.activeElement instanceof HTMLElement) document.activeElement.blur()
@@ -430,7 +441,12 @@ export function LoginPage() {
               borderColor: 'color-mix(in oklch, var(--border) 55%, transparent)',
             }}
           >
-            <h2 className="text-2xl sm:text-3xl font-semibold mb-4 sm:mb-6 text-center">{t('auth:login')}</h2>
+            <h2 className="text-2xl sm:text-3xl font-semibold mb-4 sm:mb-6 text-center">
+              {mfaChallenge ? t('auth:mfaTitle') : t('auth:login')}
+            </h2>
+            {mfaChallenge ? (
+              <MfaLoginForm challenge={mfaChallenge} onSubmit={handleMfaSubmit} onCancel={cancelMfaLogin} />
+            ) : (
             <form onSubmit={handleSubmit} className="flex flex-col gap-4 sm:gap-5">
               <div className="relative">
                 <User className="absolute left-3 top-1/2 -translate-y-1/2 h-4 w-4 text-muted-foreground pointer-events-none" />
@@ -493,6 +509,7 @@ export function LoginPage() {
                 {isLoading ? t('auth:loggingIn') : t('auth:login')}
               </Button>
             </form>
+            )}
           </div>
         </div>
         <footer className="hidden sm:block absolute left-0 right-0 z-10 text-center bottom-6">
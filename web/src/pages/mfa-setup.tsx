@@ -0,0 +1,70 @@
+/**
+ * Forced second-factor enrollment for admins.
+ *
+ * An admin who signs in without a second factor gets a token that only
+ * reaches the MFA endpoints; it becomes a full session once a factor is
+ * enrolled here.
+ */
+
+import { useEffect } from "react"
+import { useTranslation } from "react-i18next"
+import { Navigate, useNavigate } from "react-router-dom"
+import { ShieldCheck } from "lucide-react"
+import { Button } from "@/components/ui/button"
+import { BrandLogoHorizontal } from "@/components/shared/BrandName"
+import { MfaEnrollment } from "@/components/auth/MfaEnrollment"
+import { useStore } from "@/store"
+import { api, tokenManager } from "@/lib/api"
+
+export function MfaSetupPage() {
+  const { t } = useTranslation('auth')
+  const navigate = useNavigate()
+  const { setMfaSetupRequired, logout } = useStore()
+
+  // Nothing to do if a factor was enrolled meanwhile (e.g. in another tab)
+  useEffect(() => {
+    api.getMfaStatus()
+      .then((status) => {
+        if (status.enabled || !status.required) {
+          setMfaSetupRequired(false)
+          navigate('/', { replace: true })
+        }
+      })
+      .catch(() => {})
+  }, [navigate, setMfaSetupRequired])
+
+  if (!tokenManager.getToken()) {
+    return <Navigate to="/login" replace />
+  }
+
+  const handleDone = () => {
+    setMfaSetupRequired(false)
+    navigate('/', { replace: true })
+  }
+
+  const handleLogout = async () => {
+    await logout()
+    navigate('/login', { replace: true })
+  }
+
+  return (
+    <div className="flex flex-col bg-background viewport-full">
+      <header className="flex items-center justify-between px-4 sm:px-6 h-14 sm:h-16 safe-top">
+        <BrandLogoHorizontal className="h-6 sm:h-7" />
+        <Button variant="ghost" size="sm" onClick={handleLogout}>
+          {t('logout')}
+        </Button>
+      </header>
+      <main className="flex-1 px-4 sm:px-6 safe-bottom flex items-center justify-center min-h-0 overflow-y-auto">
+        <div className="w-full max-w-md rounded-2xl border bg-card p-6 sm:p-8 shadow-md animate-fade-in-up">
+          <div className="flex flex-col items-center text-center mb-6">
+            <ShieldCheck className="h-10 w-10 text-primary mb-3" />
+            <h2 className="text-2xl font-semibold">{t('mfaSetupTitle')}</h2>
+            <p className="text-sm text-muted-foreground mt-2">{t('mfaSetupRequiredDesc')}</p>
+          </div>
+          <MfaEnrollment onDone={handleDone} />
+        </div>
+      </main>
+    </div>
+  )
+}
@@ -7,6 +7,7 @@ import { PageLayout } from "@/components/layout/PageLayout"
 import { PageTabsContent, PageTabsBottomNav } from "@/components/shared"
 import { AboutTab } from "./settings/AboutTab"
 import { PreferencesTab } from "./settings/PreferencesTab"
+import { SecurityTab } from "./settings/SecurityTab"
 import { UnifiedLLMBackendsTab } from "@/components/llm/UnifiedLLMBackendsTab"
 import { UnifiedDeviceConnectionsTab } from "@/components/connections"
 import {
@@ -21,7 +22,7 @@ export function SettingsPage() {
   const [searchParams, setSearchParams] = useSearchParams()
   const sectionFromUrl = searchParams.get("tab") as SettingsSectionType | null
 
-  const validSections: SettingsSectionType[] = ["llm", "connections", "preferences", "about"]
+  const validSections: SettingsSectionType[] = ["llm", "connections", "preferences", "security", "about"]
   const [activeSection, setActiveSection] = useState<SettingsSectionType>(() => {
     return sectionFromUrl && validSections.includes(sectionFromUrl) ? sectionFromUrl : "preferences"
   })
@@ -67,6 +68,7 @@ export function SettingsPage() {
   )
   const connectionsEl = <UnifiedDeviceConnectionsTab />
   const preferencesEl = <PreferencesTab />
+  const securityEl = <SecurityTab />
   const aboutEl = <AboutTab />
 
   return (
@@ -89,6 +91,7 @@ export function SettingsPage() {
             <PageTabsContent value="llm" activeTab={activeSection}>{llmEl}</PageTabsContent>
             <PageTabsContent value="connections" activeTab={activeSection}>{connectionsEl}</PageTabsContent>
             <PageTabsContent value="preferences" activeTab={activeSection}>{preferencesEl}</PageTabsContent>
+            <PageTabsContent value="security" activeTab={activeSection}>{securityEl}</PageTabsContent>
             <PageTabsContent value="about" activeTab={activeSection}>{aboutEl}</PageTabsContent>
           </div>
         ) : (
@@ -103,6 +106,7 @@ export function SettingsPage() {
               {activeSection === "llm" && llmEl}
               {activeSection === "connections" && connectionsEl}
               {activeSection === "preferences" && preferencesEl}
+              {activeSection === "security" && securityEl}
               {activeSection === "about" && aboutEl}
             </div>
           </div>
@@ -0,0 +1,237 @@
+import { useCallback, useEffect, useState } from "react"
+import { useTranslation } from "react-i18next"
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card"
+import { Badge } from "@/components/ui/badge"
+import { Button } from "@/components/ui/button"
+import { Input } from "@/components/ui/input"
+import { Label } from "@/components/ui/label"
+import { Fingerprint, KeyRound, Loader2, ShieldCheck, Smartphone, Trash2 } from "lucide-react"
+import { useToast } from "@/hooks/use-toast"
+import { api } from "@/lib/api"
+import { getPasskeyAssertion, isPasskeySupported } from "@/lib/auth/webauthn"
+import { TotpEnrollment } from "@/components/auth/TotpEnrollment"
+import { RecoveryCodes } from "@/components/auth/RecoveryCodes"
+import { registerPasskey } from "@/components/auth/MfaEnrollment"
+import { formatTimestamp } from "@/lib/utils/format"
+import type { MfaProof, MfaStatus } from "@/types"
+
+export function SecurityTab() {
+  const { t } = useTranslation(["settings", "auth"])
+  const { toast } = useToast()
+  const [status, setStatus] = useState<MfaStatus | null>(null)
+  const [enrollingTotp, setEnrollingTotp] = useState(false)
+  const [recoveryCodes, setRecoveryCodes] = useState<string[] | null>(null)
+  // Authenticator or recovery code confirming a change; empty means passkey
+  const [verifyCode, setVerifyCode] = useState("")
+  const [busy, setBusy] = useState(false)
+
+  const loadStatus = useCallback(async () => {
+    try {
+      setStatus(await api.getMfaStatus())
+    } catch {
+      setStatus(null)
+    }
+  }, [])
+
+  useEffect(() => {
+    loadStatus()
+  }, [loadStatus])
+
+  // Changing factors needs an existing one once any is enrolled
+  const getProof = async (): Promise<MfaProof> => {
+    if (!status?.enabled) return {}
+    const code = verifyCode.trim()
+    if (code) {
+      return /^\d{6}$/.test(code) ? { code } : { recovery_code: code }
+    }
+    const { webauthn } = await api.getMfaChallenge()
+    if (!webauthn) {
+      throw new Error(t("settings:mfaVerifyRequired"))
+    }
+    return { webauthn: await getPasskeyAssertion(webauthn.publicKey) }
+  }
+
+  const run = async (action: () => Promise<void>, success: string) => {
+    setBusy(true)
+    try {
+      await action()
+      setVerifyCode("")
+      toast({ title: success })
+      await loadStatus()
+    } catch (err) {
+      toast({
+        title: t("settings:mfaActionFailed"),
+        description: err instanceof Error ? err.message : String(err),
+        variant: "destructive",
+      })
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const handleTotpEnrolled = (codes: string[] | null) => {
+    setEnrollingTotp(false)
+    setVerifyCode("")
+    if (codes && codes.length > 0) setRecoveryCodes(codes)
+    toast({ title: t("settings:mfaTotpEnabled") })
+    loadStatus()
+  }
+
+  const handleDisableTotp = () =>
+    run(async () => {
+      await api.disableTotp(await getProof())
+    }, t("settings:mfaTotpDisabled"))
+
+  const handleAddPasskey = () =>
+    run(async () => {
+      const codes = await registerPasskey(t("auth:mfaDefaultPasskeyName"), await getProof())
+      if (codes && codes.length > 0) setRecoveryCodes(codes)
+    }, t("settings:mfaPasskeyAdded"))
+
+  const handleRemovePasskey = (id: string) =>
+    run(async () => {
+      await api.removePasskey(id, await getProof())
+    }, t("settings:mfaPasskeyRemoved"))
+
+  const handleRegenerateCodes = () =>
+    run(async () => {
+      const res = await api.regenerateRecoveryCodes(await getProof())
+      setRecoveryCodes(res.recovery_codes)
+    }, t("settings:mfaRecoveryCodesRegenerated"))
+
+  if (!status) {
+    return (
+      <div className="flex justify-center py-12">
+        <Loader2 className="h-5 w-5 animate-spin text-muted-foreground" />
+      </div>
+    )
+  }
+
+  return (
+    <div className="space-y-6">
+      <Card>
+        <CardHeader>
+          <CardTitle className="flex items-center gap-2">
+            <ShieldCheck className="h-5 w-5 text-success" />
+            {t("settings:twoFactorAuth")}
+            <Badge variant={status.enabled ? "default" : "outline"} className="ml-auto">
+              {status.enabled ? t("settings:mfaOn") : t("settings:mfaOff")}
+            </Badge>
+          </CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-6">
+          <p className="text-sm text-muted-foreground">
+            {status.required ? t("settings:mfaRequiredDesc") : t("settings:mfaOptionalDesc")}
+          </p>
+
+          {recoveryCodes && (
+            <RecoveryCodes codes={recoveryCodes} onDone={() => setRecoveryCodes(null)} />
+          )}
+
+          {status.enabled && !recoveryCodes && (
+            <div className="space-y-2">
+              <Label htmlFor="mfa-verify">{t("settings:mfaVerifyLabel")}</Label>
+              <Input
+                id="mfa-verify"
+                value={verifyCode}
+                onChange={(e) => setVerifyCode(e.target.value)}
+                placeholder={t("auth:mfaCodePlaceholder")}
+                autoComplete="one-time-code"
+                className="max-w-xs tracking-widest"
+              />
+              <p className="text-xs text-muted-foreground">{t("settings:mfaVerifyHint")}</p>
+            </div>
+          )}
+
+          {/* Authenticator app */}
+          <div className="space-y-3">
+            <div className="flex items-center justify-between gap-4">
+              <div className="flex items-center gap-3">
+                <Smartphone className="h-5 w-5 text-muted-foreground" />
+                <div>
+                  <div className="text-sm font-medium">{t("auth:mfaAuthenticatorApp")}</div>
+                  <div className="text-xs text-muted-foreground">
+                    {status.totp ? t("settings:mfaConfigured") : t("settings:mfaNotConfigured")}
+                  </div>
+                </div>
+              </div>
+              {status.totp ? (
+                <Button variant="outline" size="sm" disabled={busy} onClick={handleDisableTotp}>
+                  {t("settings:mfaDisable")}
+                </Button>
+              ) : !enrollingTotp && (
+                <Button variant="outline" size="sm" disabled={busy} onClick={() => setEnrollingTotp(true)}>
+                  {t("settings:mfaSetUp")}
+                </Button>
+              )}
+            </div>
+            {enrollingTotp && (
+              <TotpEnrollment
+                getProof={getProof}
+                onComplete={handleTotpEnrolled}
+                onCancel={() => setEnrollingTotp(false)}
+              />
+            )}
+          </div>
+
+          {/* Passkeys */}
+          <div className="space-y-3">
+            <div className="flex items-center justify-between gap-4">
+              <div className="flex items-center gap-3">
+                <Fingerprint className="h-5 w-5 text-muted-foreground" />
+                <div>
+                  <div className="text-sm font-medium">{t("auth:mfaPasskey")}</div>
+                  <div className="text-xs text-muted-foreground">
+                    {t("settings:mfaPasskeyCount", { count: status.passkeys.length })}
+                  </div>
+                </div>
+              </div>
+              {isPasskeySupported() && (
+                <Button variant="outline" size="sm" disabled={busy} onClick={handleAddPasskey}>
+                  {t("settings:mfaAddPasskey")}
+                </Button>
+              )}
+            </div>
+            {status.passkeys.map((passkey) => (
+              <div key={passkey.id} className="flex items-center justify-between gap-4 rounded-lg border p-3 ml-8">
+                <div className="min-w-0">
+                  <div className="text-sm truncate">{passkey.name}</div>
+                  <div className="text-xs text-muted-foreground">
+                    {t("settings:mfaAddedAt", { time: formatTimestamp(passkey.created_at) })}
+                  </div>
+                </div>
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  disabled={busy}
+                  onClick={() => handleRemovePasskey(passkey.id)}
+                  aria-label={t("settings:mfaRemovePasskey")}
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              </div>
+            ))}
+          </div>
+
+          {/* Recovery codes */}
+          {status.enabled && (
+            <div className="flex items-center justify-between gap-4">
+              <div className="flex items-center gap-3">
+                <KeyRound className="h-5 w-5 text-muted-foreground" />
+                <div>
+                  <div className="text-sm font-medium">{t("settings:mfaRecoveryCodes")}</div>
+                  <div className="text-xs text-muted-foreground">
+                    {t("settings:mfaRecoveryCodesRemaining", { count: status.recovery_codes_remaining })}
+                  </div>
+                </div>
+              </div>
+              <Button variant="outline" size="sm" disabled={busy} onClick={handleRegenerateCodes}>
+                {t("settings:mfaRegenerate")}
+              </Button>
+            </div>
+          )}
+        </CardContent>
+      </Card>
+    </div>
+  )
+}
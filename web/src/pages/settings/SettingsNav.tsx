@@ -1,9 +1,9 @@
 import { ReactNode } from "react"
 import { cn } from "@/lib/utils"
-import { Cpu, Plug, Sliders, Info } from "lucide-react"
+import { Cpu, Plug, Sliders, ShieldCheck, Info } from "lucide-react"
 import { useTranslation } from "react-i18next"
 
-export type SettingsSection = "llm" | "connections" | "preferences" | "about"
+export type SettingsSection = "llm" | "connections" | "preferences" | "security" | "about"
 
 export interface SettingsSectionConfig {
   value: SettingsSection
@@ -16,6 +16,7 @@ export function getSettingsSections(t: ReturnType<typeof useTranslation>["t"]):
     { value: "llm", label: t("settings:llmBackends"), icon: <Cpu className="h-4 w-4" /> },
     { value: "connections", label: t("settings:deviceConnections"), icon: <Plug className="h-4 w-4" /> },
     { value: "preferences", label: t("settings:preferences"), icon: <Sliders className="h-4 w-4" /> },
+    { value: "security", label: t("settings:security"), icon: <ShieldCheck className="h-4 w-4" /> },
     { value: "about", label: t("settings:about"), icon: <Info className="h-4 w-4" /> },
   ]
 }
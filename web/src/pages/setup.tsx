@@ -1,27 +1,27 @@
 /**
- * Setup Page - Simplified 2-step initial setup
+ * Setup Page - Simplified 3-step initial setup
  *
  * Step 1: Create admin account + select timezone (auto-detected)
- * Step 2: Complete with quick-start guide
+ * Step 2: Enroll the admin's second factor
+ * Step 3: Complete with quick-start guide
  *
  * LLM configuration is deferred — users configure it when they first use AI features.
  */
 import { useState, useEffect } from "react"
 import { useNavigate } from "react-router-dom"
 import { useTranslation } from "react-i18next"
-import { useStore } from "@/store"
 import { useErrorHandler } from "@/hooks/useErrorHandler"
 import { getApiBase, isTauriEnv } from '@/lib/api'
 import { Loader2 } from "lucide-react"
 import { LoadingState } from "@/components/shared/LoadingState"
 import { AccountStep } from "./setup/AccountStep"
+import { SecurityStep } from "./setup/SecurityStep"
 import { CompleteStep } from "./setup/CompleteStep"
 
-type SetupStep = 'account' | 'complete'
+type SetupStep = 'account' | 'security' | 'complete'
 
 export function SetupPage() {
   const navigate = useNavigate()
-  const { login } = useStore()
   const { withErrorHandling } = useErrorHandler()
   const { t } = useTranslation(['setup'])
 
@@ -29,7 +29,6 @@ export function SetupPage() {
   const [checking, setChecking] = useState(true)
   const [accountInfo, setAccountInfo] = useState<{
     username: string
-    password: string
     token: string
     timezone: string
   } | null>(null)
@@ -73,8 +72,8 @@ export function SetupPage() {
     }
   }
 
-  const handleAccountCreated = async (username: string, password: string, token: string, timezone: string) => {
-    setAccountInfo({ username, password, token, timezone })
+  const handleAccountCreated = async (username: string, _password: string, token: string, timezone: string) => {
+    setAccountInfo({ username, token, timezone })
     // Complete setup (mark as done on server)
     try {
       await fetch(getApiUrl('/setup/complete'), {
@@ -84,16 +83,14 @@ export function SetupPage() {
     } catch {
       // Non-blocking — the account is already created
     }
-    setStep('complete')
+    setStep('security')
   }
 
+  // Enrolling the second factor upgrades the account token to a full
+  // session, so there is no need to sign in again
   const handleComplete = () => {
     if (accountInfo) {
-      login(accountInfo.username, accountInfo.password, true).then(() => {
-        window.location.href = '/'
-      }).catch(() => {
-        navigate('/login')
-      })
+      window.location.href = '/'
     } else {
       navigate('/login')
     }
@@ -117,6 +114,10 @@ export function SetupPage() {
     )
   }
 
+  if (step === 'security') {
+    return <SecurityStep onEnrolled={() => setStep('complete')} />
+  }
+
   if (step === 'complete') {
     return (
       <CompleteStep
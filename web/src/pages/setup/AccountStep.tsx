@@ -119,7 +119,9 @@ export function AccountStep({ getApiUrl, onAccountCreated }: AccountStepProps) {
       const response = await fetch(getApiUrl('/setup/initialize'), {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ username, password, email: email || undefined }),
+        // Timezone goes along: the new token only reaches MFA setup until
+        // a second factor is enrolled
+        body: JSON.stringify({ username, password, email: email || undefined, timezone: selectedTimezone }),
       })
 
       const data = await response.json()
@@ -130,20 +132,6 @@ export function AccountStep({ getApiUrl, onAccountCreated }: AccountStepProps) {
       localStorage.setItem('neomind_token', data.token)
       localStorage.setItem('neomind_user', JSON.stringify(data.user))
 
-      // Save timezone
-      try {
-        await fetch(getApiUrl('/settings/timezone'), {
-          method: 'PUT',
-          headers: {
-            'Content-Type': 'application/json',
-            'Authorization': `Bearer ${data.token}`,
-          },
-          body: JSON.stringify({ timezone: selectedTimezone }),
-        })
-      } catch (tzError) {
-        console.warn('Failed to save timezone, continuing:', tzError)
-      }
-
       // Newsletter subscription (non-blocking)
       if (subscribeToNewsletter && email?.trim()) {
         mcSubscribe(email, username).catch(() => {})
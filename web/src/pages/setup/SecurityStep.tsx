@@ -0,0 +1,43 @@
+/**
+ * SecurityStep - Enroll the admin's second factor
+ *
+ * Step 2 of setup: admins must have a second factor, and the token from
+ * account creation only reaches the MFA endpoints until one is enrolled.
+ */
+import { useTranslation } from 'react-i18next'
+import { MfaEnrollment } from '@/components/auth/MfaEnrollment'
+import { SetupBackground } from './SetupBackground'
+import { SetupHeader } from './SetupHeader'
+
+interface SecurityStepProps {
+  onEnrolled: () => void
+}
+
+export function SecurityStep({ onEnrolled }: SecurityStepProps) {
+  const { t } = useTranslation(['setup', 'auth'])
+
+  return (
+    <div className="viewport-full flex flex-col bg-background relative overflow-hidden">
+      <SetupBackground />
+      <SetupHeader />
+
+      <main className="relative z-10 flex-1 min-h-0 overflow-y-auto safe-bottom">
+        <div className="min-h-full flex items-center justify-center px-4 pt-20 pb-6 sm:px-6 sm:pt-24 sm:pb-10">
+          <div className="w-full max-w-md animate-fade-in-up">
+            <div
+              className="backdrop-blur-xl rounded-2xl p-6 sm:p-8 border shadow-md"
+              style={{
+                backgroundColor: 'color-mix(in oklch, var(--background) 72%, transparent)',
+                borderColor: 'color-mix(in oklch, var(--border) 55%, transparent)',
+              }}
+            >
+              <h2 className="text-xl sm:text-2xl font-semibold mb-1.5 text-center tracking-tight">{t('setup:securityTitle')}</h2>
+              <p className="text-muted-foreground text-center mb-5 text-sm px-2">{t('setup:securityDescription')}</p>
+              <MfaEnrollment onDone={onEnrolled} />
+            </div>
+          </div>
+        </div>
+      </main>
+    </div>
+  )
+}
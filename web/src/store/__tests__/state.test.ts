@@ -52,7 +52,7 @@ describe('Store State Management', () => {
       // Simulate login
       store.setState({
         isAuthenticated: true,
-        user: { id: 'user-1', username: 'testuser', role: 'user' },
+        user: { id: 'user-1', username: 'testuser', role: 'operator' },
         token: 'jwt-token-123',
       })
 
@@ -64,7 +64,7 @@ describe('Store State Management', () => {
     it('should handle logout transition', () => {
       const loggedInState: TestAuthState = {
         isAuthenticated: true,
-        user: { id: 'user-1', username: 'testuser', role: 'user' },
+        user: { id: 'user-1', username: 'testuser', role: 'operator' },
         token: 'jwt-token-123',
       }
 
@@ -85,7 +85,7 @@ describe('Store State Management', () => {
     it('should handle partial state updates', () => {
       const store = create<TestAuthState>(() => ({
         isAuthenticated: true,
-        user: { id: 'user-1', username: 'testuser', role: 'user' },
+        user: { id: 'user-1', username: 'testuser', role: 'operator' },
         token: 'jwt-token-123',
       }))
 
@@ -6,9 +6,30 @@
 
 import type { StateCreator } from 'zustand'
 import type { AuthState } from '../types'
-import type { UserInfo } from '@/types'
+import type { LoginResponse, MfaLoginChallenge, MfaProof, UserInfo } from '@/types'
 import { tokenManager, api, getApiKey } from '@/lib/api'
 
+// Survives reloads so a setup-only session goes straight back to enrollment
+const MFA_SETUP_KEY = 'neomind_mfa_setup_required'
+
+function storeMfaSetupRequired(required: boolean) {
+  try {
+    if (required) {
+      localStorage.setItem(MFA_SETUP_KEY, '1')
+    } else {
+      localStorage.removeItem(MFA_SETUP_KEY)
+    }
+  } catch { /* ignore */ }
+}
+
+function isMfaChallenge(error: unknown): MfaLoginChallenge | null {
+  const err = error as { status?: number; data?: { mfa_required?: boolean; mfa?: MfaLoginChallenge } }
+  if (err?.status === 401 && err.data?.mfa_required && err.data.mfa) {
+    return err.data.mfa
+  }
+  return null
+}
+
 export interface AuthSlice extends AuthState {
   // UI state
   loginError: string | null
@@ -16,7 +37,11 @@ export interface AuthSlice extends AuthState {
   checkAuthStatus: () => void
   setLoginError: (error: string | null) => void
   // User authentication actions
-  login: (username: string, password: string, rememberMe?: boolean) => Promise<void>
+  /** Resolves false when the password was accepted but a second factor is needed */
+  login: (username: string, password: string, rememberMe?: boolean) => Promise<boolean>
+  completeMfaLogin: (proof: MfaProof, rememberMe?: boolean) => Promise<void>
+  cancelMfaLogin: () => void
+  setMfaSetupRequired: (required: boolean) => void
   register: (username: string, password: string) => Promise<void>
   logout: () => Promise<void>
   getCurrentUser: () => Promise<UserInfo | null>
@@ -27,103 +52,153 @@ export const createAuthSlice: StateCreator<
   [],
   [],
   AuthSlice
-> = (set, get) => ({
-  // Initial state
-  apiKey: null,
-  isAuthenticated: false,
-  user: tokenManager.getUser(),  // Restore from cache
-  token: tokenManager.getToken(),
-  loginError: null,
-
-  // Actions
-  checkAuthStatus: () => {
-    // Check for JWT token or API key
-    const token = tokenManager.getToken()
-    const apiKey = getApiKey()
-    const cachedUser = tokenManager.getUser()
-    if (token) {
-      set({ token, isAuthenticated: true, user: cachedUser })
-      // Try to fetch current user info
-      get().getCurrentUser().catch((error) => {
-        // Only clear token and user on 401/403 (auth errors)
-        if (error?.status === 401 || error?.status === 403) {
-          tokenManager.clearToken()
-          tokenManager.clearUser()
-          set({ token: null, user: null, isAuthenticated: false })
-        }
-        // On 500 or other errors, keep cached user info
-      })
-    } else if (apiKey) {
-      // API key auth — no JWT token, but authenticated via service account
-      set({ token: null, isAuthenticated: true, user: null })
-      // Fetch service account info (backend returns virtual user for API key)
-      get().getCurrentUser().catch(() => {})
-    } else {
-      set({ isAuthenticated: false, user: null })
-    }
-  },
-
-  saveApiKey: () => {
-    // No-op - API key authentication removed
-  },
-
-  clearApiKey: () => {
-    // No-op - API key authentication removed
-  },
-
-  // User authentication actions
-  login: async (username: string, password: string, rememberMe = false) => {
-    const response = await api.login(username, password, rememberMe)
-    // Cache user info
+> = (set, get) => {
+  // Cache the session from a completed (password or second-factor) login
+  const finishLogin = (response: LoginResponse, rememberMe: boolean) => {
     tokenManager.setUser(response.user, rememberMe)
+    storeMfaSetupRequired(!!response.mfa_setup_required)
     set({
       user: response.user,
       token: response.token,
       isAuthenticated: true,
+      mfaChallenge: null,
+      mfaSetupRequired: !!response.mfa_setup_required,
     })
-  },
+  }
 
-  register: async (username: string, password: string) => {
-    const response = await api.register(username, password)
-    // Cache user info (session only for register)
-    tokenManager.setUser(response.user, false)
-    set({
-      user: response.user,
-      token: response.token,
-      isAuthenticated: true,
-    })
-  },
-
-  logout: async () => {
-    try {
-      await api.logout()
-    } catch {
-      // Ignore errors during logout
-    } finally {
-      tokenManager.clearToken()
-      tokenManager.clearUser()
+  return {
+    // Initial state
+    apiKey: null,
+    isAuthenticated: false,
+    user: tokenManager.getUser(),  // Restore from cache
+    token: tokenManager.getToken(),
+    mfaChallenge: null,
+    mfaSetupRequired: !!localStorage.getItem(MFA_SETUP_KEY),
+    loginError: null,
+
+    // Actions
+    checkAuthStatus: () => {
+      // Check for JWT token or API key
+      const token = tokenManager.getToken()
+      const apiKey = getApiKey()
+      const cachedUser = tokenManager.getUser()
+      if (token) {
+        set({ token, isAuthenticated: true, user: cachedUser })
+        // Try to fetch current user info
+        get().getCurrentUser().then((user) => {
+          // Admins must enroll a second factor before using the app
+          if (user?.role === 'admin') {
+            api.getMfaStatus()
+              .then((status) => get().setMfaSetupRequired(status.required && !status.enabled))
+              .catch(() => {})
+          }
+        }).catch((error) => {
+          // Only clear token and user on 401/403 (auth errors)
+          if (error?.status === 401 || error?.status === 403) {
+            tokenManager.clearToken()
+            tokenManager.clearUser()
+            set({ token: null, user: null, isAuthenticated: false })
+          }
+          // On 500 or other errors, keep cached user info
+        })
+      } else if (apiKey) {
+        // API key auth — no JWT token, but authenticated via service account
+        set({ token: null, isAuthenticated: true, user: null })
+        // Fetch service account info (backend returns virtual user for API key)
+        get().getCurrentUser().catch(() => {})
+      } else {
+        set({ isAuthenticated: false, user: null })
+      }
+    },
+
+    saveApiKey: () => {
+      // No-op - API key authentication removed
+    },
+
+    clearApiKey: () => {
+      // No-op - API key authentication removed
+    },
+
+    // User authentication actions
+    login: async (username: string, password: string, rememberMe = false) => {
+      let response: LoginResponse
+      try {
+        response = await api.login(username, password, rememberMe)
+      } catch (error) {
+        const challenge = isMfaChallenge(error)
+        if (challenge) {
+          set({ mfaChallenge: challenge })
+          return false
+        }
+        throw error
+      }
+      finishLogin(response, rememberMe)
+      return true
+    },
+
+    completeMfaLogin: async (proof: MfaProof, rememberMe = false) => {
+      const challenge = get().mfaChallenge
+      if (!challenge) {
+        throw new Error('No login is waiting for a second factor')
+      }
+      const response = await api.loginMfa(challenge.mfa_token, proof, rememberMe)
+      finishLogin(response, rememberMe)
+    },
+
+    cancelMfaLogin: () => {
+      set({ mfaChallenge: null })
+    },
+
+    setMfaSetupRequired: (required: boolean) => {
+      storeMfaSetupRequired(required)
+      set({ mfaSetupRequired: required })
+    },
+
+    register: async (username: string, password: string) => {
+      const response = await api.register(username, password)
+      // Cache user info (session only for register)
+      tokenManager.setUser(response.user, false)
       set({
-        user: null,
-        token: null,
-        isAuthenticated: false,
+        user: response.user,
+        token: response.token,
+        isAuthenticated: true,
       })
-    }
-  },
-
-  getCurrentUser: async () => {
-    try {
-      const user = await api.getCurrentUser()
-      // Update cache with latest user info
-      const remember = !!localStorage.getItem('neomind_token')
-      tokenManager.setUser(user, remember)
-      set({ user })
-      return user
-    } catch {
-      return null
-    }
-  },
+    },
+
+    logout: async () => {
+      try {
+        await api.logout()
+      } catch {
+        // Ignore errors during logout
+      } finally {
+        tokenManager.clearToken()
+        tokenManager.clearUser()
+        storeMfaSetupRequired(false)
+        set({
+          user: null,
+          token: null,
+          isAuthenticated: false,
+          mfaChallenge: null,
+          mfaSetupRequired: false,
+        })
+      }
+    },
 
-  setLoginError: (error: string | null) => {
-    set({ loginError: error })
-  },
-})
+    getCurrentUser: async () => {
+      try {
+        const user = await api.getCurrentUser()
+        // Update cache with latest user info
+        const remember = !!localStorage.getItem('neomind_token')
+        tokenManager.setUser(user, remember)
+        set({ user })
+        return user
+      } catch {
+        return null
+      }
+    },
+
+    setLoginError: (error: string | null) => {
+      set({ loginError: error })
+    },
+  }
+}
@@ -15,6 +15,7 @@ import type {
   CommandHistoryResponse,
   ChatSession,
   UserInfo,
+  MfaLoginChallenge,
 } from '@/types'
 
 // ============================================================================
@@ -27,6 +28,10 @@ export interface AuthState {
   // User authentication (JWT)
   user: UserInfo | null
   token: string | null
+  // Password accepted, waiting for the second factor
+  mfaChallenge: MfaLoginChallenge | null
+  // Admin without a second factor: the token only reaches MFA setup
+  mfaSetupRequired: boolean
 }
 
 // ============================================================================
@@ -5,10 +5,10 @@ import { describe, it, expect } from 'vitest'
 describe('Type Definitions', () => {
   describe('UserRole', () => {
     it('should accept valid user roles', () => {
-      const validRoles: Array<'admin' | 'user' | 'viewer'> = ['admin', 'user', 'viewer']
+      const validRoles: Array<'admin' | 'operator' | 'viewer'> = ['admin', 'operator', 'viewer']
       expect(validRoles).toHaveLength(3)
       expect(validRoles).toContain('admin')
-      expect(validRoles).toContain('user')
+      expect(validRoles).toContain('operator')
       expect(validRoles).toContain('viewer')
     })
   })
@@ -1,12 +1,14 @@
 // ========== User Authentication Types ==========
 
-export type UserRole = 'admin' | 'user' | 'viewer'
+export type UserRole = 'admin' | 'operator' | 'viewer'
 
 export interface UserInfo {
   id: string
   username: string
   role: UserRole
   created_at: number
+  /** Tenant the user is bound to; absent for users who see every tenant */
+  tenant_id?: string
 }
 
 export interface LoginRequest {
@@ -17,12 +19,72 @@ export interface LoginRequest {
 export interface LoginResponse {
   token: string
   user: UserInfo
+  /** Admin without a second factor: the token only reaches MFA setup */
+  mfa_setup_required?: boolean
+}
+
+// ========== Second Factor Types ==========
+
+export type MfaMethod = 'totp' | 'webauthn' | 'recovery_code'
+
+/** Returned with a 401 by /auth/login when a second factor is needed */
+export interface MfaLoginChallenge {
+  mfa_token: string
+  methods: MfaMethod[]
+  /** PublicKeyCredentialRequestOptions, binary fields base64url-encoded */
+  webauthn?: PublicKeyRequestOptionsJSON
+  expires_at: number
+}
+
+export interface PublicKeyRequestOptionsJSON {
+  challenge: string
+  timeout?: number
+  userVerification?: UserVerificationRequirement
+  allowCredentials?: { type: 'public-key'; id: string }[]
+}
+
+/** Passkey assertion in the shape the server expects */
+export interface PasskeyAssertion {
+  id: string
+  client_data_json: string
+  authenticator_data: string
+  signature: string
+}
+
+/** A second factor sent with a request; one field is set */
+export interface MfaProof {
+  code?: string
+  recovery_code?: string
+  webauthn?: PasskeyAssertion
+}
+
+export interface PasskeyInfo {
+  id: string
+  name: string
+  created_at: number
+  last_used_at?: number
+}
+
+export interface MfaStatus {
+  enabled: boolean
+  /** Whether the user's role must have a second factor */
+  required: boolean
+  totp: boolean
+  passkeys: PasskeyInfo[]
+  recovery_codes_remaining: number
+}
+
+export interface TotpSetupResponse {
+  secret: string
+  otpauth_uri: string
 }
 
 export interface RegisterRequest {
   username: string
   password: string
   role?: UserRole
+  /** Tenant to bind an admin-created user to */
+  tenant_id?: string
 }
 
 export interface ChangePasswordRequest {
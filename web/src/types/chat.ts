@@ -124,6 +124,8 @@ export type ServerMessage =
   | { type: 'ToolCallStart'; tool: string; arguments: Record<string, unknown>; sessionId: string; round?: number }
   // Tool call completed - result is a string (JSON or plain text)
   | { type: 'ToolCallEnd'; tool: string; result: string; sessionId: string; success?: boolean; round?: number }
+  // Incremental output from a streaming tool while it is still running (before its ToolCallEnd)
+  | { type: 'ToolProgress'; tool: string; data: unknown; sessionId: string; round?: number }
   // Intermediate end for multi-round tool calling
   | { type: 'IntermediateEnd'; sessionId: string }
   // Progress event during long-running operations
@@ -1,6 +1,6 @@
 // ========== Data Push Types ==========
 
-export type PushTargetType = 'webhook' | 'mqtt'
+export type PushTargetType = 'webhook' | 'mqtt' | 'influxdb' | 'telegraf'
 export type DeliveryStatus = 'pending' | 'success' | 'failed' | 'retrying'
 export type PushScheduleType = 'event_driven' | 'interval'
 
@@ -102,7 +102,7 @@ export interface CleanupMessagesRequest {
 // Message Channel Types (formerly AlertChannel for backward compatibility)
 export interface AlertChannel {
   name: string
-  channel_type: 'console' | 'memory' | 'webhook' | 'email' | 'telegram' | 'wecom' | 'dingtalk' | 'slack' | 'feishu'
+  channel_type: 'console' | 'memory' | 'webhook' | 'email' | 'telegram' | 'wecom' | 'dingtalk' | 'slack' | 'feishu' | 'mqtt'
   enabled: boolean
   config?: Record<string, unknown>
 }
@@ -159,7 +159,7 @@ export interface ChannelSchemaResponse {
 
 export interface MessageChannel {
   name: string
-  channel_type: 'console' | 'memory' | 'webhook' | 'email' | 'telegram' | 'wecom' | 'dingtalk' | 'slack' | 'feishu'
+  channel_type: 'console' | 'memory' | 'webhook' | 'email' | 'telegram' | 'wecom' | 'dingtalk' | 'slack' | 'feishu' | 'mqtt'
   enabled: boolean
   config?: Record<string, unknown>
   recipients?: string[]  // For email channels